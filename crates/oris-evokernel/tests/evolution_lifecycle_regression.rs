@@ -154,11 +154,12 @@ fn test_kernel() -> Arc<Kernel<TestState>> {
         events: Box::new(InMemoryEventStore::new()),
         snaps: None,
         reducer: Box::new(StateUpdatedOnlyReducer),
-        exec: Box::new(NoopActionExecutor),
+        exec: Arc::new(NoopActionExecutor),
         step: Box::new(NoopStepFn),
         policy: Box::new(AllowAllPolicy),
         effect_sink: None,
         mode: KernelMode::Normal,
+        snapshot_policy: None,
     })
 }
 
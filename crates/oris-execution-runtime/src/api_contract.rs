@@ -11,16 +11,19 @@ use serde::Serialize;
 use serde_json::Value;
 
 use super::api_models::{
-    ApiEnvelope, ApiMeta, AttemptRetryHistoryResponse, AuditLogListResponse, CancelJobRequest,
-    CancelJobResponse, CheckpointInspectResponse, DeadLetterItem, DeadLetterListResponse,
-    DeadLetterReplayResponse, InterruptDetailResponse, InterruptListResponse, JobDetailResponse,
-    JobHistoryResponse, JobStateResponse, JobTimelineResponse, ListAuditLogsQuery,
-    ListDeadLettersQuery, ListInterruptsQuery, ListJobsQuery, ListJobsResponse,
-    RejectInterruptRequest, ReplayJobRequest, ResumeInterruptRequest, ResumeJobRequest,
-    RunJobRequest, RunJobResponse, TimelineExportResponse, WorkerAckRequest, WorkerAckResponse,
+    ApiEnvelope, ApiMeta, AttemptCancelResponse, AttemptRetryHistoryResponse, AuditLogListResponse,
+    CancelJobRequest, CancelJobResponse, CheckpointInspectResponse, CreateRunRequest,
+    CreateRunResponse, DeadLetterItem, DeadLetterListResponse, DeadLetterReplayResponse,
+    InterruptDetailResponse, InterruptListResponse, JobDetailResponse, JobHistoryResponse,
+    JobStateResponse, JobTimelineResponse, ListAuditLogsQuery, ListDeadLettersQuery,
+    ListInterruptsQuery, ListJobsQuery, ListJobsResponse, PauseJobResponse, RejectInterruptRequest,
+    ReplayJobRequest, ResolveInterruptRequest, ResolveInterruptResponse, ResumeInterruptRequest,
+    ResumeJobRequest, ResumeRunRequest, RunEventsQuery, RunJobRequest, RunJobResponse,
+    RunStatusResponse, TimelineExportResponse, WorkerAckRequest, WorkerAckResponse,
     WorkerExtendLeaseRequest, WorkerHeartbeatRequest, WorkerLeaseResponse, WorkerPollRequest,
     WorkerPollResponse, WorkerReportStepRequest,
 };
+use super::models::RuntimeStats;
 
 pub const RUNTIME_API_CONTRACT_DOC_PATH: &str = "docs/runtime-api-contract.json";
 
@@ -58,6 +61,8 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
 
     add_schema::<ApiMeta>(&mut schemas, "ApiMeta");
     add_schema::<RunJobRequest>(&mut schemas, "RunJobRequest");
+    add_schema::<CreateRunRequest>(&mut schemas, "CreateRunRequest");
+    add_schema::<ResumeRunRequest>(&mut schemas, "ResumeRunRequest");
     add_schema::<ResumeJobRequest>(&mut schemas, "ResumeJobRequest");
     add_schema::<ReplayJobRequest>(&mut schemas, "ReplayJobRequest");
     add_schema::<CancelJobRequest>(&mut schemas, "CancelJobRequest");
@@ -67,14 +72,18 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
     add_schema::<WorkerReportStepRequest>(&mut schemas, "WorkerReportStepRequest");
     add_schema::<WorkerAckRequest>(&mut schemas, "WorkerAckRequest");
     add_schema::<ListJobsQuery>(&mut schemas, "ListJobsQuery");
+    add_schema::<RunEventsQuery>(&mut schemas, "RunEventsQuery");
     add_schema::<ListInterruptsQuery>(&mut schemas, "ListInterruptsQuery");
     add_schema::<ListAuditLogsQuery>(&mut schemas, "ListAuditLogsQuery");
     add_schema::<ListDeadLettersQuery>(&mut schemas, "ListDeadLettersQuery");
     add_schema::<ResumeInterruptRequest>(&mut schemas, "ResumeInterruptRequest");
     add_schema::<RejectInterruptRequest>(&mut schemas, "RejectInterruptRequest");
+    add_schema::<ResolveInterruptRequest>(&mut schemas, "ResolveInterruptRequest");
 
     add_schema::<ApiEnvelope<ListJobsResponse>>(&mut schemas, "ApiEnvelope_ListJobsResponse");
     add_schema::<ApiEnvelope<RunJobResponse>>(&mut schemas, "ApiEnvelope_RunJobResponse");
+    add_schema::<ApiEnvelope<CreateRunResponse>>(&mut schemas, "ApiEnvelope_CreateRunResponse");
+    add_schema::<ApiEnvelope<RunStatusResponse>>(&mut schemas, "ApiEnvelope_RunStatusResponse");
     add_schema::<ApiEnvelope<JobStateResponse>>(&mut schemas, "ApiEnvelope_JobStateResponse");
     add_schema::<ApiEnvelope<JobDetailResponse>>(&mut schemas, "ApiEnvelope_JobDetailResponse");
     add_schema::<ApiEnvelope<TimelineExportResponse>>(
@@ -88,6 +97,7 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
         "ApiEnvelope_CheckpointInspectResponse",
     );
     add_schema::<ApiEnvelope<CancelJobResponse>>(&mut schemas, "ApiEnvelope_CancelJobResponse");
+    add_schema::<ApiEnvelope<PauseJobResponse>>(&mut schemas, "ApiEnvelope_PauseJobResponse");
     add_schema::<ApiEnvelope<WorkerPollResponse>>(&mut schemas, "ApiEnvelope_WorkerPollResponse");
     add_schema::<ApiEnvelope<WorkerLeaseResponse>>(&mut schemas, "ApiEnvelope_WorkerLeaseResponse");
     add_schema::<ApiEnvelope<WorkerAckResponse>>(&mut schemas, "ApiEnvelope_WorkerAckResponse");
@@ -99,6 +109,10 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
         &mut schemas,
         "ApiEnvelope_InterruptDetailResponse",
     );
+    add_schema::<ApiEnvelope<ResolveInterruptResponse>>(
+        &mut schemas,
+        "ApiEnvelope_ResolveInterruptResponse",
+    );
     add_schema::<ApiEnvelope<AuditLogListResponse>>(
         &mut schemas,
         "ApiEnvelope_AuditLogListResponse",
@@ -107,6 +121,10 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
         &mut schemas,
         "ApiEnvelope_AttemptRetryHistoryResponse",
     );
+    add_schema::<ApiEnvelope<AttemptCancelResponse>>(
+        &mut schemas,
+        "ApiEnvelope_AttemptCancelResponse",
+    );
     add_schema::<ApiEnvelope<DeadLetterListResponse>>(
         &mut schemas,
         "ApiEnvelope_DeadLetterListResponse",
@@ -116,6 +134,7 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
         &mut schemas,
         "ApiEnvelope_DeadLetterReplayResponse",
     );
+    add_schema::<ApiEnvelope<RuntimeStats>>(&mut schemas, "ApiEnvelope_RuntimeStats");
 
     RuntimeApiContract {
         api_version: "v1",
@@ -265,6 +284,17 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
                 Some("ApiEnvelope_AttemptRetryHistoryResponse"),
                 vec![path_param("attempt_id")],
             ),
+            endpoint(
+                "POST",
+                "/v1/attempts/:attempt_id/cancel",
+                "api-auth",
+                "Cancel an attempt, or ask its worker to stop if it is running",
+                None,
+                None,
+                "application/json",
+                Some("ApiEnvelope_AttemptCancelResponse"),
+                vec![path_param("attempt_id")],
+            ),
             endpoint(
                 "GET",
                 "/v1/dlq",
@@ -298,6 +328,17 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
                 Some("ApiEnvelope_DeadLetterReplayResponse"),
                 vec![path_param("attempt_id")],
             ),
+            endpoint(
+                "GET",
+                "/v1/runtime/stats",
+                "api-auth",
+                "Attempt counts by status, active leases and queue age",
+                None,
+                None,
+                "application/json",
+                Some("ApiEnvelope_RuntimeStats"),
+                vec![],
+            ),
             endpoint(
                 "GET",
                 "/v1/jobs",
@@ -331,6 +372,50 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
                 Some("ApiEnvelope_RunJobResponse"),
                 vec![],
             ),
+            endpoint(
+                "POST",
+                "/v1/runs",
+                "api-auth",
+                "Create a run of a named graph; retries with its idempotency_key replay it",
+                Some("CreateRunRequest"),
+                None,
+                "application/json",
+                Some("ApiEnvelope_CreateRunResponse"),
+                vec![],
+            ),
+            endpoint(
+                "GET",
+                "/v1/runs/:run_id/events",
+                "api-auth",
+                "Stream a run's kernel events as server-sent events until it ends",
+                None,
+                Some("RunEventsQuery"),
+                "text/event-stream",
+                None,
+                vec![path_param("run_id")],
+            ),
+            endpoint(
+                "POST",
+                "/v1/runs/:run_id/resume",
+                "api-auth",
+                "Resume a run blocked on an interrupt or a pause",
+                Some("ResumeRunRequest"),
+                None,
+                "application/json",
+                Some("ApiEnvelope_RunStatusResponse"),
+                vec![path_param("run_id")],
+            ),
+            endpoint(
+                "POST",
+                "/v1/runs/:run_id/cancel",
+                "api-auth",
+                "Cancel a run and its attempt in flight",
+                Some("CancelJobRequest"),
+                None,
+                "application/json",
+                Some("ApiEnvelope_RunStatusResponse"),
+                vec![path_param("run_id")],
+            ),
             endpoint(
                 "GET",
                 "/v1/jobs/:thread_id",
@@ -419,6 +504,17 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
                 Some("ApiEnvelope_RunJobResponse"),
                 vec![path_param("thread_id")],
             ),
+            endpoint(
+                "POST",
+                "/v1/jobs/:thread_id/pause",
+                "api-auth",
+                "Pause a running job at its next node boundary",
+                None,
+                None,
+                "application/json",
+                Some("ApiEnvelope_PauseJobResponse"),
+                vec![path_param("thread_id")],
+            ),
             endpoint(
                 "POST",
                 "/v1/jobs/:thread_id/cancel",
@@ -529,6 +625,17 @@ pub fn generate_runtime_api_contract() -> RuntimeApiContract {
                 Some("ApiEnvelope_CancelJobResponse"),
                 vec![path_param("interrupt_id")],
             ),
+            endpoint(
+                "POST",
+                "/v1/interrupts/:interrupt_id/resolve",
+                "api-auth",
+                "Approve or reject a pending interrupt",
+                Some("ResolveInterruptRequest"),
+                None,
+                "application/json",
+                Some("ApiEnvelope_ResolveInterruptResponse"),
+                vec![path_param("interrupt_id")],
+            ),
         ],
         schemas,
     }
@@ -619,7 +726,7 @@ mod tests {
     #[test]
     fn generated_runtime_api_contract_covers_current_v1_surface() {
         let contract = generate_runtime_api_contract();
-        assert_eq!(contract.endpoints.len(), 37);
+        assert_eq!(contract.endpoints.len(), 45);
         assert!(contract
             .endpoints
             .iter()
@@ -3,6 +3,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use oris_kernel::event::KernelError;
 use serde::Serialize;
 use serde_json::Value;
 
@@ -20,6 +21,9 @@ pub enum ApiError {
     Forbidden(ErrorState),
     NotFound(ErrorState),
     Conflict(ErrorState),
+    Unprocessable(ErrorState),
+    Unavailable(ErrorState),
+    NotImplemented(ErrorState),
     Internal(ErrorState),
 }
 
@@ -67,6 +71,21 @@ impl ApiError {
         Self::Conflict(ErrorState::new(message))
     }
 
+    /// A well-formed request whose fields are invalid (422); put the offending fields in
+    /// the details.
+    pub fn unprocessable(message: impl Into<String>) -> Self {
+        Self::Unprocessable(ErrorState::new(message))
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::Unavailable(ErrorState::new(message))
+    }
+
+    /// An endpoint this server is not configured to serve (501)
+    pub fn not_implemented(message: impl Into<String>) -> Self {
+        Self::NotImplemented(ErrorState::new(message))
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal(ErrorState::new(message))
     }
@@ -79,6 +98,9 @@ impl ApiError {
             | Self::Forbidden(s)
             | Self::NotFound(s)
             | Self::Conflict(s)
+            | Self::Unprocessable(s)
+            | Self::Unavailable(s)
+            | Self::NotImplemented(s)
             | Self::Internal(s) => s.request_id = request_id,
         }
         self
@@ -91,6 +113,9 @@ impl ApiError {
             | Self::Forbidden(s)
             | Self::NotFound(s)
             | Self::Conflict(s)
+            | Self::Unprocessable(s)
+            | Self::Unavailable(s)
+            | Self::NotImplemented(s)
             | Self::Internal(s) => s.details = Some(details),
         }
         self
@@ -105,6 +130,9 @@ impl IntoResponse for ApiError {
             Self::Forbidden(s) => (StatusCode::FORBIDDEN, "forbidden", s),
             Self::NotFound(s) => (StatusCode::NOT_FOUND, "not_found", s),
             Self::Conflict(s) => (StatusCode::CONFLICT, "conflict", s),
+            Self::Unprocessable(s) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", s),
+            Self::Unavailable(s) => (StatusCode::SERVICE_UNAVAILABLE, "unavailable", s),
+            Self::NotImplemented(s) => (StatusCode::NOT_IMPLEMENTED, "not_implemented", s),
             Self::Internal(s) => (StatusCode::INTERNAL_SERVER_ERROR, "internal", s),
         };
         let request_id = state
@@ -121,3 +149,93 @@ impl IntoResponse for ApiError {
         (status, Json(body)).into_response()
     }
 }
+
+/// Maps a kernel error to the HTTP status its variant stands for. The details carry the
+/// error's stable code and whether retrying the request may succeed.
+impl From<KernelError> for ApiError {
+    fn from(err: KernelError) -> Self {
+        let details = serde_json::json!({
+            "error_code": err.code(),
+            "retryable": err.is_retryable(),
+        });
+        let message = err.to_string();
+        let api_error = match err {
+            KernelError::NotFound(_) | KernelError::RunNotFound(_) => Self::not_found(message),
+            KernelError::LeaseConflict(_)
+            | KernelError::NotDispatchable(_)
+            | KernelError::Conflict(_)
+            | KernelError::RunEnded { .. }
+            | KernelError::AlreadyResumed { .. } => Self::conflict(message),
+            KernelError::Validation(_) | KernelError::InvalidResumeToken { .. } => {
+                Self::bad_request(message)
+            }
+            KernelError::Policy(_) => Self::forbidden(message),
+            KernelError::Timeout(_) => Self::unavailable(message),
+            _ => Self::internal(message),
+        };
+        api_error.with_details(details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(err: KernelError) -> StatusCode {
+        ApiError::from(err).into_response().status()
+    }
+
+    #[test]
+    fn kernel_errors_map_to_http_statuses_by_variant() {
+        let cases = [
+            (KernelError::NotFound("x".into()), StatusCode::NOT_FOUND),
+            (KernelError::RunNotFound("r".into()), StatusCode::NOT_FOUND),
+            (KernelError::LeaseConflict("x".into()), StatusCode::CONFLICT),
+            (
+                KernelError::NotDispatchable("x".into()),
+                StatusCode::CONFLICT,
+            ),
+            (KernelError::Conflict("x".into()), StatusCode::CONFLICT),
+            (KernelError::Validation("x".into()), StatusCode::BAD_REQUEST),
+            (KernelError::Policy("x".into()), StatusCode::FORBIDDEN),
+            (
+                KernelError::Timeout("x".into()),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                KernelError::Storage("x".into()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (
+                KernelError::Driver("x".into()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+        for (err, status) in cases {
+            let code = err.code();
+            assert_eq!(status_of(err), status, "{}", code);
+        }
+    }
+
+    #[test]
+    fn kernel_error_details_carry_code_and_retryability() {
+        let ApiError::Conflict(state) = ApiError::from(KernelError::LeaseConflict(
+            "lease heartbeat version conflict for lease: l1".into(),
+        )) else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(
+            state.message,
+            "lease heartbeat version conflict for lease: l1"
+        );
+        assert_eq!(
+            state.details,
+            Some(serde_json::json!({"error_code": "LEASE_CONFLICT", "retryable": true}))
+        );
+
+        let ApiError::NotFound(state) = ApiError::from(KernelError::NotFound("gone".into())) else {
+            panic!("expected not found");
+        };
+        assert_eq!(state.details.unwrap()["retryable"], false);
+    }
+}
@@ -54,6 +54,10 @@ impl SqliteIdempotencyStore {
             .conn
             .lock()
             .map_err(|_| "idempotency sqlite lock poisoned".to_string())?;
+        Self::query_record(&conn, key)
+    }
+
+    fn query_record(conn: &Connection, key: &str) -> Result<Option<IdempotencyRecord>, String> {
         let mut stmt = conn
             .prepare(
                 "SELECT operation, thread_id, payload_hash, response_json
@@ -94,4 +98,48 @@ impl SqliteIdempotencyStore {
         .map_err(|e| format!("failed to persist idempotency key: {}", e))?;
         Ok(())
     }
+
+    /// Stores `record` unless `key` is already taken, in which case the record already
+    /// stored under it is returned and nothing is written.
+    pub fn put_if_absent(
+        &self,
+        key: &str,
+        record: &IdempotencyRecord,
+    ) -> Result<Option<IdempotencyRecord>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "idempotency sqlite lock poisoned".to_string())?;
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO execution_idempotency
+                 (idempotency_key, operation, thread_id, payload_hash, response_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    key,
+                    record.operation,
+                    record.thread_id,
+                    record.payload_hash,
+                    record.response_json
+                ],
+            )
+            .map_err(|e| format!("failed to reserve idempotency key: {}", e))?;
+        if inserted == 1 {
+            return Ok(None);
+        }
+        Self::query_record(&conn, key)
+    }
+
+    pub fn remove(&self, key: &str) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "idempotency sqlite lock poisoned".to_string())?;
+        conn.execute(
+            "DELETE FROM execution_idempotency WHERE idempotency_key = ?1",
+            params![key],
+        )
+        .map_err(|e| format!("failed to remove idempotency key: {}", e))?;
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 //! API DTOs for Phase 2 execution server.
 
+use crate::models::InterruptDecision;
 use crate::observability::KernelObservability;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -35,6 +36,15 @@ impl ApiMeta {
     }
 }
 
+/// How a job runs: `normal` performs its actions, `dry_run` only simulates them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunMode {
+    #[default]
+    Normal,
+    DryRun,
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct RunJobRequest {
     pub thread_id: String,
@@ -43,12 +53,20 @@ pub struct RunJobRequest {
     pub timeout_policy: Option<TimeoutPolicyRequest>,
     pub priority: Option<i32>,
     pub tenant_id: Option<String>,
+    /// Defaults to `normal`.
+    pub mode: Option<JobRunMode>,
 }
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct ResumeJobRequest {
+    /// Ignored when the thread is paused, which continues at the node it stopped at.
+    #[serde(default)]
     pub value: Value,
     pub checkpoint_id: Option<String>,
+    /// Defaults to `normal`; a thread started as a dry run only resumes in `normal`
+    /// mode with `allow_mode_change`.
+    pub mode: Option<JobRunMode>,
+    pub allow_mode_change: Option<bool>,
 }
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
@@ -69,6 +87,60 @@ pub struct RunJobResponse {
     pub idempotency_key: Option<String>,
     pub idempotent_replay: bool,
     pub trace: Option<TraceContextResponse>,
+    #[serde(default)]
+    pub mode: JobRunMode,
+}
+
+/// Body of `POST /v1/runs`.
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct CreateRunRequest {
+    /// Name of the graph to run, as configured on the server.
+    pub graph: String,
+    /// Generated when absent; a retry with the same `idempotency_key` gets the same one.
+    pub thread_id: Option<String>,
+    /// The run's input message; must be a string.
+    #[serde(default)]
+    pub input: Value,
+    pub idempotency_key: Option<String>,
+    /// `normal` (the default) or `dry_run`.
+    pub mode: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CreateRunResponse {
+    pub run_id: String,
+    pub thread_id: String,
+    /// Checkpoint the run was at when the request returned.
+    pub checkpoint_id: Option<String>,
+    pub status: String,
+    pub interrupts: Vec<Value>,
+    pub mode: JobRunMode,
+    pub idempotency_key: Option<String>,
+    /// Set when the response was replayed for a retried `idempotency_key`.
+    pub idempotent_replay: bool,
+}
+
+/// Body of `POST /v1/runs/:run_id/resume`.
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct ResumeRunRequest {
+    /// Answer to the interrupt the run is blocked on; ignored when the run is paused.
+    #[serde(default)]
+    pub value: Value,
+    /// Resume from this checkpoint instead of the latest.
+    pub checkpoint_id: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+/// Where a run stands after `POST /v1/runs/:run_id/resume` or `/cancel`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunStatusResponse {
+    pub run_id: String,
+    pub thread_id: String,
+    pub status: String,
+    pub checkpoint_id: Option<String>,
+    pub interrupts: Vec<Value>,
+    /// Set when the request repeated one that already took effect.
+    pub idempotent_replay: bool,
 }
 
 #[derive(Clone, Debug, Serialize, JsonSchema)]
@@ -124,6 +196,14 @@ pub struct CancelJobResponse {
     pub reason: Option<String>,
 }
 
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct PauseJobResponse {
+    pub thread_id: String,
+    /// `pausing`: the run stops at its next node boundary and its own response reports
+    /// `paused`.
+    pub status: String,
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct WorkerPollRequest {
     pub worker_id: String,
@@ -194,6 +274,8 @@ pub struct WorkerLeaseResponse {
     pub worker_id: String,
     pub lease_id: String,
     pub lease_expires_at: String,
+    /// `continue`, or `cancel` once the attempt was cancelled: stop and ack it as cancelled.
+    pub directive: String,
     pub trace: Option<TraceContextResponse>,
 }
 
@@ -210,6 +292,11 @@ pub struct WorkerAckResponse {
 pub struct JobListItem {
     pub thread_id: String,
     pub status: String,
+    /// Graph the job's run executes.
+    pub workflow: String,
+    /// Why the run last changed status, if it was given one.
+    pub status_reason: Option<String>,
+    pub created_at: String,
     pub updated_at: String,
 }
 
@@ -218,9 +305,22 @@ pub struct ListJobsResponse {
     pub jobs: Vec<JobListItem>,
 }
 
+/// Query of `GET /v1/runs/:run_id/events`.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema)]
+pub struct RunEventsQuery {
+    /// First event seq to replay (default 1); a `Last-Event-ID` header takes precedence.
+    pub from_seq: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct ListJobsQuery {
     pub status: Option<String>,
+    /// Only jobs whose run executes this graph.
+    pub workflow: Option<String>,
+    /// Only jobs created at or after this time (Unix milliseconds).
+    pub created_from_ms: Option<i64>,
+    /// Only jobs created before this time (Unix milliseconds).
+    pub created_to_ms: Option<i64>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
 }
@@ -303,6 +403,26 @@ pub struct RejectInterruptRequest {
     pub reason: Option<String>,
 }
 
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct ResolveInterruptRequest {
+    pub decision: InterruptDecision,
+    /// Value an approved run resumes with.
+    #[serde(default)]
+    pub value: Value,
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct ResolveInterruptResponse {
+    pub interrupt_id: String,
+    pub run_id: String,
+    pub decision: InterruptDecision,
+    pub status: String,
+    pub resolved_at: Option<String>,
+    /// The resumed run, when approved.
+    pub run: Option<RunJobResponse>,
+}
+
 #[derive(Clone, Debug, Serialize, JsonSchema)]
 pub struct JobDetailResponse {
     pub thread_id: String,
@@ -346,6 +466,16 @@ pub struct AttemptRetryHistoryResponse {
     pub history: Vec<AttemptRetryHistoryItem>,
 }
 
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct AttemptCancelResponse {
+    pub attempt_id: String,
+    /// `cancelled`, `cancel_requested` (its worker stops on the next heartbeat) or
+    /// `already_finished`.
+    pub outcome: String,
+    /// Status the attempt ended with; unset while a requested cancel is pending.
+    pub status: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, JsonSchema)]
 pub struct DeadLetterItem {
     pub attempt_id: String,
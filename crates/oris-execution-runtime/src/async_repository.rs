@@ -0,0 +1,1548 @@
+//! Async form of the runtime repository contract.
+//!
+//! [AsyncRuntimeRepository] has the methods of [RuntimeRepository] as async fns, so
+//! repositories backed by an async driver (Postgres) run on the caller's runtime instead
+//! of blocking a thread on their own. Every cloneable [RuntimeRepository] is also an
+//! [AsyncRuntimeRepository] whose calls run on Tokio's blocking pool, so embedded stores
+//! such as SQLite do not stall the caller's runtime. Sync callers of an async-only repository wrap it in
+//! [BlockingRuntimeRepository] (feature `kernel-postgres`).
+
+#[cfg(feature = "kernel-postgres")]
+use std::future::Future;
+#[cfg(feature = "kernel-postgres")]
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+use oris_kernel::event::KernelError;
+use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::PageRequest;
+
+use super::models::{
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    BountyRecord, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions, DispatchableAttempts,
+    DisputeRecord, EnqueueBatchOutcome, EnqueueOptions, InterruptDecision, InterruptFilter,
+    InterruptRecord, LeaseDirective, LeaseFence, LeaseRecord, OrganismRecord, RecipeRecord,
+    RetryPolicyConfig, RunRecord, RunRecordFilter, RunRuntimeStatus, RuntimeStats,
+    SessionMessageRecord, SessionRecord, SwarmTaskRecord, WorkerRecord,
+};
+use super::repository::RuntimeRepository;
+
+/// [RuntimeRepository] with async methods; each one keeps the contract, and the default,
+/// of its namesake there.
+#[async_trait]
+pub trait AsyncRuntimeRepository: Send + Sync {
+    async fn enqueue_attempts(
+        &self,
+        _batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not enqueue attempts".to_string(),
+        ))
+    }
+
+    async fn list_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AttemptDispatchRecord>, KernelError>;
+
+    async fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        if options.max_concurrent_per_run.is_some() {
+            return Err(KernelError::Driver(
+                "this runtime repository does not limit per-run concurrency".to_string(),
+            ));
+        }
+        Ok(DispatchableAttempts {
+            attempts: self.list_dispatchable_attempts(now, limit).await?,
+            skipped_for_fairness: 0,
+        })
+    }
+
+    async fn next_dispatch_at(
+        &self,
+        _now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KernelError> {
+        Ok(None)
+    }
+
+    async fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let mut claimed = Vec::new();
+        for attempt in self.list_dispatchable_attempts(now, limit).await? {
+            match self
+                .upsert_lease(&attempt.attempt_id, worker_id, now + lease_ttl)
+                .await
+            {
+                Ok(lease) => claimed.push(ClaimedAttempt { attempt, lease }),
+                Err(KernelError::LeaseConflict(_)) | Err(KernelError::NotDispatchable(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn upsert_lease(
+        &self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseRecord, KernelError>;
+
+    async fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError>;
+
+    async fn heartbeat_lease_with_version(
+        &self,
+        _lease_id: &str,
+        _worker_id: &str,
+        _expected_version: u64,
+        _heartbeat_at: DateTime<Utc>,
+        _lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
+
+    async fn expire_leases_and_requeue(
+        &self,
+        stale_before: DateTime<Utc>,
+    ) -> Result<u64, KernelError>;
+
+    async fn transition_timed_out_attempts(&self, _now: DateTime<Utc>) -> Result<u64, KernelError> {
+        Ok(0)
+    }
+
+    async fn finish_attempt(
+        &self,
+        _attempt_id: &str,
+        _status: AttemptExecutionStatus,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository cannot finish attempts".to_string(),
+        ))
+    }
+
+    async fn record_attempt_failure(
+        &self,
+        _attempt_id: &str,
+        _error: &str,
+        _retry_policy: &RetryPolicyConfig,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    async fn complete_attempt(
+        &self,
+        _attempt_id: &str,
+        _lease: &LeaseFence,
+        _status: AttemptExecutionStatus,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
+
+    async fn fail_attempt(
+        &self,
+        _attempt_id: &str,
+        _lease: &LeaseFence,
+        _error: &str,
+        _retry_policy: &RetryPolicyConfig,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
+
+    async fn cancel_attempt(&self, _attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not cancel attempts".to_string(),
+        ))
+    }
+
+    async fn list_dead_letter_attempts(
+        &self,
+        _limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    async fn requeue_dead_letter(&self, _attempt_id: &str) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    async fn runtime_stats(&self, _now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not report stats".to_string(),
+        ))
+    }
+
+    async fn latest_seq_for_run(&self, run_id: &RunId) -> Result<Seq, KernelError>;
+
+    // ============== Run Methods ==============
+
+    async fn create_run(&self, _run: &RunRecord) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    async fn get_run(&self, _run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    async fn update_run_status(
+        &self,
+        _run_id: &RunId,
+        _status: RunRuntimeStatus,
+        _reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    async fn list_runs(
+        &self,
+        _filter: &RunRecordFilter,
+        _page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    // ============== Bounty Methods ==============
+
+    async fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError>;
+
+    async fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError>;
+
+    async fn list_bounties(
+        &self,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BountyRecord>, KernelError>;
+
+    async fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError>;
+
+    async fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError>;
+
+    // ============== Swarm Methods ==============
+
+    async fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError>;
+
+    async fn get_swarm_decomposition(
+        &self,
+        parent_task_id: &str,
+    ) -> Result<Option<SwarmTaskRecord>, KernelError>;
+
+    // ============== Worker Methods ==============
+
+    async fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError>;
+
+    async fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError>;
+
+    async fn list_workers(
+        &self,
+        domain: Option<&str>,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkerRecord>, KernelError>;
+
+    async fn heartbeat_worker(
+        &self,
+        worker_id: &str,
+        heartbeat_at_ms: i64,
+    ) -> Result<(), KernelError>;
+
+    // ============== Recipe Methods ==============
+
+    async fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError>;
+
+    async fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError>;
+
+    async fn fork_recipe(
+        &self,
+        original_id: &str,
+        new_id: &str,
+        new_author: &str,
+    ) -> Result<Option<RecipeRecord>, KernelError>;
+
+    async fn list_recipes(
+        &self,
+        author_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecipeRecord>, KernelError>;
+
+    // ============== Organism Methods ==============
+
+    async fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError>;
+
+    async fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError>;
+
+    async fn update_organism(
+        &self,
+        organism_id: &str,
+        current_step: i32,
+        status: &str,
+    ) -> Result<(), KernelError>;
+
+    // ============== Session Methods ==============
+
+    async fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError>;
+
+    async fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError>;
+
+    async fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError>;
+
+    async fn get_session_history(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionMessageRecord>, KernelError>;
+
+    // ============== Dispute Methods ==============
+
+    async fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError>;
+
+    async fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError>;
+
+    async fn get_disputes_for_bounty(
+        &self,
+        bounty_id: &str,
+    ) -> Result<Vec<DisputeRecord>, KernelError>;
+
+    async fn resolve_dispute(
+        &self,
+        dispute_id: &str,
+        resolution: &str,
+        resolved_by: &str,
+    ) -> Result<(), KernelError>;
+
+    // ============== Interrupt Methods ==============
+
+    async fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        let _ = interrupt;
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+
+    async fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let _ = filter;
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+
+    async fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let _ = (interrupt_id, decision, value);
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+}
+
+/// Runs `call` against a clone of `repo` on Tokio's blocking pool, or inline outside a Tokio
+/// runtime.
+async fn offload<R, T, F>(repo: &R, call: F) -> Result<T, KernelError>
+where
+    R: RuntimeRepository + Clone + 'static,
+    T: Send + 'static,
+    F: FnOnce(&R) -> Result<T, KernelError> + Send + 'static,
+{
+    if tokio::runtime::Handle::try_current().is_err() {
+        return call(repo);
+    }
+    let repo = repo.clone();
+    tokio::task::spawn_blocking(move || call(&repo))
+        .await
+        .map_err(|e| KernelError::Driver(format!("runtime repository call failed: {}", e)))?
+}
+
+#[async_trait]
+impl<R: RuntimeRepository + Clone + 'static> AsyncRuntimeRepository for R {
+    async fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        let batch = batch.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::enqueue_attempts(repo, &batch)
+        })
+        .await
+    }
+
+    async fn list_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::list_dispatchable_attempts(repo, now, limit)
+        })
+        .await
+    }
+
+    async fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        let options = options.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::list_dispatchable_attempts_with_options(repo, now, limit, &options)
+        })
+        .await
+    }
+
+    async fn next_dispatch_at(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::next_dispatch_at(repo, now)
+        })
+        .await
+    }
+
+    async fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let worker_id = worker_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::claim_dispatchable_attempts(repo, now, &worker_id, limit, lease_ttl)
+        })
+        .await
+    }
+
+    async fn upsert_lease(
+        &self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseRecord, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        let worker_id = worker_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::upsert_lease(repo, &attempt_id, &worker_id, lease_expires_at)
+        })
+        .await
+    }
+
+    async fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let lease_id = lease_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::heartbeat_lease(repo, &lease_id, heartbeat_at, lease_expires_at)
+        })
+        .await
+    }
+
+    async fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let lease_id = lease_id.to_owned();
+        let worker_id = worker_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::heartbeat_lease_with_version(
+                repo,
+                &lease_id,
+                &worker_id,
+                expected_version,
+                heartbeat_at,
+                lease_expires_at,
+            )
+        })
+        .await
+    }
+
+    async fn expire_leases_and_requeue(
+        &self,
+        stale_before: DateTime<Utc>,
+    ) -> Result<u64, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::expire_leases_and_requeue(repo, stale_before)
+        })
+        .await
+    }
+
+    async fn transition_timed_out_attempts(&self, now: DateTime<Utc>) -> Result<u64, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::transition_timed_out_attempts(repo, now)
+        })
+        .await
+    }
+
+    async fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::finish_attempt(repo, &attempt_id, status, now)
+        })
+        .await
+    }
+
+    async fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        let error = error.to_owned();
+        let retry_policy = retry_policy.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::record_attempt_failure(repo, &attempt_id, &error, &retry_policy, now)
+        })
+        .await
+    }
+
+    async fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        let lease = lease.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::complete_attempt(repo, &attempt_id, &lease, status, now)
+        })
+        .await
+    }
+
+    async fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        let lease = lease.to_owned();
+        let error = error.to_owned();
+        let retry_policy = retry_policy.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::fail_attempt(repo, &attempt_id, &lease, &error, &retry_policy, now)
+        })
+        .await
+    }
+
+    async fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::cancel_attempt(repo, &attempt_id)
+        })
+        .await
+    }
+
+    async fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::list_dead_letter_attempts(repo, limit)
+        })
+        .await
+    }
+
+    async fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        let attempt_id = attempt_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::requeue_dead_letter(repo, &attempt_id)
+        })
+        .await
+    }
+
+    async fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        offload(self, move |repo| {
+            RuntimeRepository::runtime_stats(repo, now)
+        })
+        .await
+    }
+
+    async fn latest_seq_for_run(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        let run_id = run_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::latest_seq_for_run(repo, &run_id)
+        })
+        .await
+    }
+
+    async fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        let run = run.to_owned();
+        offload(self, move |repo| RuntimeRepository::create_run(repo, &run)).await
+    }
+
+    async fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        let run_id = run_id.to_owned();
+        offload(self, move |repo| RuntimeRepository::get_run(repo, &run_id)).await
+    }
+
+    async fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        let run_id = run_id.to_owned();
+        let reason = reason.map(str::to_owned);
+        offload(self, move |repo| {
+            RuntimeRepository::update_run_status(repo, &run_id, status, reason.as_deref())
+        })
+        .await
+    }
+
+    async fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        let filter = filter.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::list_runs(repo, &filter, page)
+        })
+        .await
+    }
+
+    async fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
+        let bounty = bounty.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::upsert_bounty(repo, &bounty)
+        })
+        .await
+    }
+
+    async fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
+        let bounty_id = bounty_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_bounty(repo, &bounty_id)
+        })
+        .await
+    }
+
+    async fn list_bounties(
+        &self,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BountyRecord>, KernelError> {
+        let status = status.map(str::to_owned);
+        offload(self, move |repo| {
+            RuntimeRepository::list_bounties(repo, status.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
+        let bounty_id = bounty_id.to_owned();
+        let accepted_by = accepted_by.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::accept_bounty(repo, &bounty_id, &accepted_by)
+        })
+        .await
+    }
+
+    async fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
+        let bounty_id = bounty_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::close_bounty(repo, &bounty_id)
+        })
+        .await
+    }
+
+    async fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
+        let task = task.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::upsert_swarm_decomposition(repo, &task)
+        })
+        .await
+    }
+
+    async fn get_swarm_decomposition(
+        &self,
+        parent_task_id: &str,
+    ) -> Result<Option<SwarmTaskRecord>, KernelError> {
+        let parent_task_id = parent_task_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_swarm_decomposition(repo, &parent_task_id)
+        })
+        .await
+    }
+
+    async fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
+        let worker = worker.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::register_worker(repo, &worker)
+        })
+        .await
+    }
+
+    async fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
+        let worker_id = worker_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_worker(repo, &worker_id)
+        })
+        .await
+    }
+
+    async fn list_workers(
+        &self,
+        domain: Option<&str>,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkerRecord>, KernelError> {
+        let domain = domain.map(str::to_owned);
+        let status = status.map(str::to_owned);
+        offload(self, move |repo| {
+            RuntimeRepository::list_workers(repo, domain.as_deref(), status.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn heartbeat_worker(
+        &self,
+        worker_id: &str,
+        heartbeat_at_ms: i64,
+    ) -> Result<(), KernelError> {
+        let worker_id = worker_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::heartbeat_worker(repo, &worker_id, heartbeat_at_ms)
+        })
+        .await
+    }
+
+    async fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
+        let recipe = recipe.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::create_recipe(repo, &recipe)
+        })
+        .await
+    }
+
+    async fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
+        let recipe_id = recipe_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_recipe(repo, &recipe_id)
+        })
+        .await
+    }
+
+    async fn fork_recipe(
+        &self,
+        original_id: &str,
+        new_id: &str,
+        new_author: &str,
+    ) -> Result<Option<RecipeRecord>, KernelError> {
+        let original_id = original_id.to_owned();
+        let new_id = new_id.to_owned();
+        let new_author = new_author.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::fork_recipe(repo, &original_id, &new_id, &new_author)
+        })
+        .await
+    }
+
+    async fn list_recipes(
+        &self,
+        author_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecipeRecord>, KernelError> {
+        let author_id = author_id.map(str::to_owned);
+        offload(self, move |repo| {
+            RuntimeRepository::list_recipes(repo, author_id.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
+        let organism = organism.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::express_organism(repo, &organism)
+        })
+        .await
+    }
+
+    async fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
+        let organism_id = organism_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_organism(repo, &organism_id)
+        })
+        .await
+    }
+
+    async fn update_organism(
+        &self,
+        organism_id: &str,
+        current_step: i32,
+        status: &str,
+    ) -> Result<(), KernelError> {
+        let organism_id = organism_id.to_owned();
+        let status = status.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::update_organism(repo, &organism_id, current_step, &status)
+        })
+        .await
+    }
+
+    async fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
+        let session = session.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::create_session(repo, &session)
+        })
+        .await
+    }
+
+    async fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
+        let session_id = session_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_session(repo, &session_id)
+        })
+        .await
+    }
+
+    async fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
+        let message = message.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::add_session_message(repo, &message)
+        })
+        .await
+    }
+
+    async fn get_session_history(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionMessageRecord>, KernelError> {
+        let session_id = session_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_session_history(repo, &session_id, limit)
+        })
+        .await
+    }
+
+    async fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
+        let dispute = dispute.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::open_dispute(repo, &dispute)
+        })
+        .await
+    }
+
+    async fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
+        let dispute_id = dispute_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_dispute(repo, &dispute_id)
+        })
+        .await
+    }
+
+    async fn get_disputes_for_bounty(
+        &self,
+        bounty_id: &str,
+    ) -> Result<Vec<DisputeRecord>, KernelError> {
+        let bounty_id = bounty_id.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::get_disputes_for_bounty(repo, &bounty_id)
+        })
+        .await
+    }
+
+    async fn resolve_dispute(
+        &self,
+        dispute_id: &str,
+        resolution: &str,
+        resolved_by: &str,
+    ) -> Result<(), KernelError> {
+        let dispute_id = dispute_id.to_owned();
+        let resolution = resolution.to_owned();
+        let resolved_by = resolved_by.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::resolve_dispute(repo, &dispute_id, &resolution, &resolved_by)
+        })
+        .await
+    }
+
+    async fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        let interrupt = interrupt.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::create_interrupt(repo, &interrupt)
+        })
+        .await
+    }
+
+    async fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let filter = filter.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::list_pending_interrupts(repo, &filter)
+        })
+        .await
+    }
+
+    async fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let interrupt_id = interrupt_id.to_owned();
+        let value = value.to_owned();
+        offload(self, move |repo| {
+            RuntimeRepository::resolve_interrupt(repo, &interrupt_id, decision, &value)
+        })
+        .await
+    }
+}
+
+/// Runtime that [BlockingRuntimeRepository::new] drives async repositories on, shared by
+/// every adapter in the process.
+#[cfg(feature = "kernel-postgres")]
+fn shared_blocking_runtime() -> Result<Arc<tokio::runtime::Runtime>, KernelError> {
+    static RUNTIME: OnceLock<Result<Arc<tokio::runtime::Runtime>, String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .worker_threads(1)
+                .thread_name("oris-runtime-blocking")
+                .build()
+                .map(Arc::new)
+                .map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(|e| KernelError::Driver(format!("start blocking repository runtime: {}", e)))
+}
+
+/// [RuntimeRepository] over an [AsyncRuntimeRepository], for sync callers such as
+/// [SkeletonScheduler](crate::SkeletonScheduler): each call blocks the calling thread
+/// until the async call completes on a Tokio runtime.
+///
+/// Calls must not be made from a thread that is driving an async runtime, where
+/// blocking panics; async code should use the wrapped repository directly.
+#[cfg(feature = "kernel-postgres")]
+#[derive(Clone)]
+pub struct BlockingRuntimeRepository<A> {
+    inner: A,
+    handle: tokio::runtime::Handle,
+    // Keeps the shared runtime alive while `handle` points at it
+    _runtime: Option<Arc<tokio::runtime::Runtime>>,
+}
+
+#[cfg(feature = "kernel-postgres")]
+impl<A: AsyncRuntimeRepository> BlockingRuntimeRepository<A> {
+    /// Drive `inner` on a runtime shared by all adapters.
+    pub fn new(inner: A) -> Result<Self, KernelError> {
+        let runtime = shared_blocking_runtime()?;
+        Ok(Self {
+            inner,
+            handle: runtime.handle().clone(),
+            _runtime: Some(runtime),
+        })
+    }
+
+    /// Drive `inner` on the runtime behind `handle`, e.g. the application's own, from a
+    /// thread outside it such as one of its blocking-pool threads.
+    pub fn with_handle(inner: A, handle: tokio::runtime::Handle) -> Self {
+        Self {
+            inner,
+            handle,
+            _runtime: None,
+        }
+    }
+
+    pub fn get_ref(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Run `future` to completion on the adapter's runtime, e.g. to call methods of the
+    /// wrapped repository beyond the [AsyncRuntimeRepository] ones.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.handle.block_on(future)
+    }
+}
+
+#[cfg(feature = "kernel-postgres")]
+impl<A: AsyncRuntimeRepository> RuntimeRepository for BlockingRuntimeRepository<A> {
+    fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        self.block_on(AsyncRuntimeRepository::enqueue_attempts(&self.inner, batch))
+    }
+
+    fn list_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_dispatchable_attempts(
+            &self.inner,
+            now,
+            limit,
+        ))
+    }
+
+    fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        self.block_on(
+            AsyncRuntimeRepository::list_dispatchable_attempts_with_options(
+                &self.inner,
+                now,
+                limit,
+                options,
+            ),
+        )
+    }
+
+    fn next_dispatch_at(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::next_dispatch_at(&self.inner, now))
+    }
+
+    fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::claim_dispatchable_attempts(
+            &self.inner,
+            now,
+            worker_id,
+            limit,
+            lease_ttl,
+        ))
+    }
+
+    fn upsert_lease(
+        &self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseRecord, KernelError> {
+        self.block_on(AsyncRuntimeRepository::upsert_lease(
+            &self.inner,
+            attempt_id,
+            worker_id,
+            lease_expires_at,
+        ))
+    }
+
+    fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        self.block_on(AsyncRuntimeRepository::heartbeat_lease(
+            &self.inner,
+            lease_id,
+            heartbeat_at,
+            lease_expires_at,
+        ))
+    }
+
+    fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        self.block_on(AsyncRuntimeRepository::heartbeat_lease_with_version(
+            &self.inner,
+            lease_id,
+            worker_id,
+            expected_version,
+            heartbeat_at,
+            lease_expires_at,
+        ))
+    }
+
+    fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError> {
+        self.block_on(AsyncRuntimeRepository::expire_leases_and_requeue(
+            &self.inner,
+            stale_before,
+        ))
+    }
+
+    fn transition_timed_out_attempts(&self, now: DateTime<Utc>) -> Result<u64, KernelError> {
+        self.block_on(AsyncRuntimeRepository::transition_timed_out_attempts(
+            &self.inner,
+            now,
+        ))
+    }
+
+    fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.block_on(AsyncRuntimeRepository::finish_attempt(
+            &self.inner,
+            attempt_id,
+            status,
+            now,
+        ))
+    }
+
+    fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.block_on(AsyncRuntimeRepository::record_attempt_failure(
+            &self.inner,
+            attempt_id,
+            error,
+            retry_policy,
+            now,
+        ))
+    }
+
+    fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.block_on(AsyncRuntimeRepository::complete_attempt(
+            &self.inner,
+            attempt_id,
+            lease,
+            status,
+            now,
+        ))
+    }
+
+    fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.block_on(AsyncRuntimeRepository::fail_attempt(
+            &self.inner,
+            attempt_id,
+            lease,
+            error,
+            retry_policy,
+            now,
+        ))
+    }
+
+    fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        self.block_on(AsyncRuntimeRepository::cancel_attempt(
+            &self.inner,
+            attempt_id,
+        ))
+    }
+
+    fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_dead_letter_attempts(
+            &self.inner,
+            limit,
+        ))
+    }
+
+    fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::requeue_dead_letter(
+            &self.inner,
+            attempt_id,
+        ))
+    }
+
+    fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        self.block_on(AsyncRuntimeRepository::runtime_stats(&self.inner, now))
+    }
+
+    fn latest_seq_for_run(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        self.block_on(AsyncRuntimeRepository::latest_seq_for_run(
+            &self.inner,
+            run_id,
+        ))
+    }
+
+    fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::create_run(&self.inner, run))
+    }
+
+    fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_run(&self.inner, run_id))
+    }
+
+    fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::update_run_status(
+            &self.inner,
+            run_id,
+            status,
+            reason,
+        ))
+    }
+
+    fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_runs(&self.inner, filter, page))
+    }
+
+    fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::upsert_bounty(&self.inner, bounty))
+    }
+
+    fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_bounty(&self.inner, bounty_id))
+    }
+
+    fn list_bounties(
+        &self,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BountyRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_bounties(
+            &self.inner,
+            status,
+            limit,
+        ))
+    }
+
+    fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::accept_bounty(
+            &self.inner,
+            bounty_id,
+            accepted_by,
+        ))
+    }
+
+    fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::close_bounty(&self.inner, bounty_id))
+    }
+
+    fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::upsert_swarm_decomposition(
+            &self.inner,
+            task,
+        ))
+    }
+
+    fn get_swarm_decomposition(
+        &self,
+        parent_task_id: &str,
+    ) -> Result<Option<SwarmTaskRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_swarm_decomposition(
+            &self.inner,
+            parent_task_id,
+        ))
+    }
+
+    fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::register_worker(&self.inner, worker))
+    }
+
+    fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_worker(&self.inner, worker_id))
+    }
+
+    fn list_workers(
+        &self,
+        domain: Option<&str>,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkerRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_workers(
+            &self.inner,
+            domain,
+            status,
+            limit,
+        ))
+    }
+
+    fn heartbeat_worker(&self, worker_id: &str, heartbeat_at_ms: i64) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::heartbeat_worker(
+            &self.inner,
+            worker_id,
+            heartbeat_at_ms,
+        ))
+    }
+
+    fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::create_recipe(&self.inner, recipe))
+    }
+
+    fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_recipe(&self.inner, recipe_id))
+    }
+
+    fn fork_recipe(
+        &self,
+        original_id: &str,
+        new_id: &str,
+        new_author: &str,
+    ) -> Result<Option<RecipeRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::fork_recipe(
+            &self.inner,
+            original_id,
+            new_id,
+            new_author,
+        ))
+    }
+
+    fn list_recipes(
+        &self,
+        author_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecipeRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_recipes(
+            &self.inner,
+            author_id,
+            limit,
+        ))
+    }
+
+    fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::express_organism(
+            &self.inner,
+            organism,
+        ))
+    }
+
+    fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_organism(
+            &self.inner,
+            organism_id,
+        ))
+    }
+
+    fn update_organism(
+        &self,
+        organism_id: &str,
+        current_step: i32,
+        status: &str,
+    ) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::update_organism(
+            &self.inner,
+            organism_id,
+            current_step,
+            status,
+        ))
+    }
+
+    fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::create_session(&self.inner, session))
+    }
+
+    fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_session(&self.inner, session_id))
+    }
+
+    fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::add_session_message(
+            &self.inner,
+            message,
+        ))
+    }
+
+    fn get_session_history(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionMessageRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_session_history(
+            &self.inner,
+            session_id,
+            limit,
+        ))
+    }
+
+    fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::open_dispute(&self.inner, dispute))
+    }
+
+    fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_dispute(&self.inner, dispute_id))
+    }
+
+    fn get_disputes_for_bounty(&self, bounty_id: &str) -> Result<Vec<DisputeRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::get_disputes_for_bounty(
+            &self.inner,
+            bounty_id,
+        ))
+    }
+
+    fn resolve_dispute(
+        &self,
+        dispute_id: &str,
+        resolution: &str,
+        resolved_by: &str,
+    ) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::resolve_dispute(
+            &self.inner,
+            dispute_id,
+            resolution,
+            resolved_by,
+        ))
+    }
+
+    fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        self.block_on(AsyncRuntimeRepository::create_interrupt(
+            &self.inner,
+            interrupt,
+        ))
+    }
+
+    fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        self.block_on(AsyncRuntimeRepository::list_pending_interrupts(
+            &self.inner,
+            filter,
+        ))
+    }
+
+    fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        self.block_on(AsyncRuntimeRepository::resolve_interrupt(
+            &self.inner,
+            interrupt_id,
+            decision,
+            value,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread::ThreadId;
+
+    use super::*;
+    use crate::clock::{Clock, SystemClock};
+    use crate::memory_runtime_repository::InMemoryRuntimeRepository;
+
+    /// Records the thread each repository call reads the time on.
+    struct ThreadClock(Mutex<Vec<ThreadId>>);
+
+    impl Clock for ThreadClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+                .lock()
+                .expect("thread clock lock")
+                .push(std::thread::current().id());
+            SystemClock.now()
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_repository_calls_leave_the_runtime_thread() {
+        let clock = Arc::new(ThreadClock(Mutex::new(Vec::new())));
+        let repo = InMemoryRuntimeRepository::new().with_clock(clock.clone());
+
+        let result = AsyncRuntimeRepository::update_run_status(
+            &repo,
+            &"run-missing".to_string(),
+            RunRuntimeStatus::Running,
+            None,
+        )
+        .await;
+
+        assert!(matches!(result, Err(KernelError::NotFound(_))));
+        let threads = clock.0.lock().expect("thread clock lock").clone();
+        assert!(!threads.is_empty());
+        assert!(threads
+            .iter()
+            .all(|thread| *thread != std::thread::current().id()));
+    }
+}
@@ -9,9 +9,9 @@ use chrono::Utc;
 use sqlx::postgres::PgPoolOptions;
 
 #[cfg(feature = "kernel-postgres")]
-use super::PostgresRuntimeRepository;
+use super::AsyncRuntimeRepository;
 #[cfg(feature = "kernel-postgres")]
-use super::RuntimeRepository;
+use super::PostgresRuntimeRepository;
 use super::SqliteRuntimeRepository;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -161,6 +161,7 @@ impl RuntimeStorageConfig {
             let repo = PostgresRuntimeRepository::new(dsn.to_string())
                 .with_schema(self.postgres_schema.clone());
             repo.list_dispatchable_attempts(Utc::now(), 1)
+                .await
                 .map_err(|e| {
                     format!(
                         "runtime backend postgres health check failed for schema '{}': {}",
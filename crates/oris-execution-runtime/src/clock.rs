@@ -0,0 +1,70 @@
+//! Time source for repositories that stamp records with the current time.
+//!
+//! Repositories read "now" for lease heartbeats, enqueue order and run updates.
+//! Production code uses [SystemClock]; tests inject a [ManualClock] to move time
+//! forward deterministically instead of sleeping.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to. Clones share the same time, so a test can
+/// keep a handle and advance the clock it gave to a repository.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_clones_share_time() {
+        let start = Utc::now();
+        let clock = ManualClock::new(start);
+        let handle = clock.clone();
+        handle.advance(Duration::seconds(30));
+        assert_eq!(clock.now(), start + Duration::seconds(30));
+        handle.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -47,6 +47,10 @@ impl std::error::Error for ExecutionGraphBridgeError {}
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutionInvokeView {
     pub interrupts: Vec<Value>,
+    /// Node the run stopped at after [pause](ExecutionGraphBridge::pause); continue it
+    /// with [continue_run](ExecutionGraphBridge::continue_run).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused_at: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -93,4 +97,62 @@ pub trait ExecutionGraphBridge: Send + Sync {
         &self,
         thread_id: &str,
     ) -> Result<Vec<ExecutionCheckpointView>, ExecutionGraphBridgeError>;
+
+    /// Like [run](Self::run), but actions are simulated instead of performed; bridges
+    /// without dry-run support refuse.
+    async fn run_dry(
+        &self,
+        thread_id: &str,
+        input: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let _ = (thread_id, input);
+        Err(ExecutionGraphBridgeError::internal(
+            "this graph bridge does not support dry runs",
+        ))
+    }
+
+    /// Like [resume](Self::resume), but actions are simulated instead of performed.
+    async fn resume_dry(
+        &self,
+        thread_id: &str,
+        checkpoint_id: Option<&str>,
+        value: Value,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let _ = (thread_id, checkpoint_id, value);
+        Err(ExecutionGraphBridgeError::internal(
+            "this graph bridge does not support dry runs",
+        ))
+    }
+
+    /// Asks the run in flight on `thread_id` to stop at its next node boundary, where
+    /// it returns a view with `paused_at` set. `Ok(false)` when nothing is running.
+    async fn pause(&self, thread_id: &str) -> Result<bool, ExecutionGraphBridgeError> {
+        let _ = thread_id;
+        Err(ExecutionGraphBridgeError::internal(
+            "this graph bridge does not support pausing runs",
+        ))
+    }
+
+    /// Continues a paused thread at the node it stopped at.
+    async fn continue_run(
+        &self,
+        thread_id: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let _ = thread_id;
+        Err(ExecutionGraphBridgeError::internal(
+            "this graph bridge does not support pausing runs",
+        ))
+    }
+
+    /// Like [continue_run](Self::continue_run), but actions are simulated instead of
+    /// performed.
+    async fn continue_run_dry(
+        &self,
+        thread_id: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let _ = thread_id;
+        Err(ExecutionGraphBridgeError::internal(
+            "this graph bridge does not support dry runs",
+        ))
+    }
 }
@@ -10,12 +10,13 @@ use std::sync::{Arc, Mutex};
 
 use crate::circuit_breaker::CircuitBreaker;
 
+use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 
 use oris_kernel::event::KernelError;
 
-use super::models::{LeaseRecord, LeaseTerminalState};
-use super::repository::RuntimeRepository;
+use super::async_repository::AsyncRuntimeRepository;
+use super::models::{LeaseRecord, LeaseTerminalState, RuntimeStats};
 
 /// Strict single-owner execution guard for a lease. Verify ownership and expiry before executing.
 #[derive(Clone, Debug)]
@@ -64,7 +65,7 @@ impl WorkerLease {
     /// Enforce single-owner: returns `Ok(())` only if `worker_id` matches the lease owner.
     pub fn verify_owner(&self, worker_id: &str) -> Result<(), KernelError> {
         if self.record.worker_id != worker_id {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::LeaseConflict(format!(
                 "lease {} is owned by {}, not {}",
                 self.record.lease_id, self.record.worker_id, worker_id
             )));
@@ -81,13 +82,13 @@ impl WorkerLease {
         self.verify_owner(worker_id)?;
         // Check terminal state first (K5-a)
         if self.is_terminal() {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::LeaseConflict(format!(
                 "lease {} is in terminal state {:?}",
                 self.record.lease_id, self.record.terminal_state
             )));
         }
         if self.is_expired(now) {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::LeaseConflict(format!(
                 "lease {} expired at {}",
                 self.record.lease_id, self.record.lease_expires_at
             )));
@@ -103,7 +104,7 @@ impl WorkerLease {
             return Ok(());
         }
         // Once terminal, no further transitions allowed
-        Err(KernelError::Driver(format!(
+        Err(KernelError::Validation(format!(
             "invalid state transition: cannot transition from {:?} to {:?}",
             self.record.terminal_state, new_state
         )))
@@ -134,32 +135,53 @@ pub struct LeaseTickResult {
 }
 
 /// Lease manager abstraction.
+#[async_trait]
 pub trait LeaseManager: Send + Sync {
-    fn tick(&self, now: DateTime<Utc>) -> Result<LeaseTickResult, KernelError>;
+    async fn tick(&self, now: DateTime<Utc>) -> Result<LeaseTickResult, KernelError>;
+
+    /// Queue stats for the lease service to publish after a tick; `None` when the
+    /// manager has no repository to read them from.
+    async fn runtime_stats(
+        &self,
+        _now: DateTime<Utc>,
+    ) -> Result<Option<RuntimeStats>, KernelError> {
+        Ok(None)
+    }
 }
 
-/// Skeleton lease manager using `RuntimeRepository`.
-pub struct RepositoryLeaseManager<R: RuntimeRepository> {
+/// Skeleton lease manager over an [AsyncRuntimeRepository], which includes every
+/// `RuntimeRepository`.
+pub struct RepositoryLeaseManager<R> {
     repository: R,
     config: LeaseConfig,
 }
 
-impl<R: RuntimeRepository> RepositoryLeaseManager<R> {
+impl<R> RepositoryLeaseManager<R> {
     pub fn new(repository: R, config: LeaseConfig) -> Self {
         Self { repository, config }
     }
 }
 
-impl<R: RuntimeRepository> LeaseManager for RepositoryLeaseManager<R> {
-    fn tick(&self, now: DateTime<Utc>) -> Result<LeaseTickResult, KernelError> {
+#[async_trait]
+impl<R: AsyncRuntimeRepository> LeaseManager for RepositoryLeaseManager<R> {
+    async fn tick(&self, now: DateTime<Utc>) -> Result<LeaseTickResult, KernelError> {
         let stale_before = now - self.config.heartbeat_grace;
-        let timed_out = self.repository.transition_timed_out_attempts(now)?;
-        let expired = self.repository.expire_leases_and_requeue(stale_before)?;
+        let timed_out = self.repository.transition_timed_out_attempts(now).await?;
+        let expired = self
+            .repository
+            .expire_leases_and_requeue(stale_before)
+            .await?;
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::metrics::LEASES_EXPIRED_TOTAL).increment(expired);
         Ok(LeaseTickResult {
             timed_out,
             expired_requeued: expired,
         })
     }
+
+    async fn runtime_stats(&self, now: DateTime<Utc>) -> Result<Option<RuntimeStats>, KernelError> {
+        self.repository.runtime_stats(now).await.map(Some)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -298,7 +320,8 @@ mod tests {
     use super::*;
     use oris_kernel::identity::{RunId, Seq};
 
-    use super::super::models::{AttemptDispatchRecord, LeaseRecord};
+    use super::super::models::{AttemptDispatchRecord, LeaseDirective, LeaseRecord};
+    use super::super::repository::RuntimeRepository;
 
     #[derive(Clone)]
     struct FakeRepository {
@@ -341,6 +364,7 @@ mod tests {
                 version: 1,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: None,
             })
         }
 
@@ -349,8 +373,8 @@ mod tests {
             _lease_id: &str,
             _heartbeat_at: DateTime<Utc>,
             _lease_expires_at: DateTime<Utc>,
-        ) -> Result<(), KernelError> {
-            Ok(())
+        ) -> Result<LeaseDirective, KernelError> {
+            Ok(LeaseDirective::Continue)
         }
 
         fn expire_leases_and_requeue(
@@ -521,6 +545,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         assert!(lease.verify_owner("W1").is_ok());
@@ -539,6 +564,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         assert!(lease.is_expired(now));
@@ -557,6 +583,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         assert!(lease.check_execution_allowed("W1", now).is_ok());
@@ -566,8 +593,8 @@ mod tests {
             .is_err());
     }
 
-    #[test]
-    fn tick_applies_heartbeat_grace_before_requeueing() {
+    #[tokio::test]
+    async fn tick_applies_heartbeat_grace_before_requeueing() {
         let repo = FakeRepository::new(2, 3);
         let config = LeaseConfig {
             lease_ttl: Duration::seconds(30),
@@ -576,7 +603,7 @@ mod tests {
         let manager = RepositoryLeaseManager::new(repo.clone(), config);
         let now = Utc::now();
 
-        let result = manager.tick(now).expect("tick succeeds");
+        let result = manager.tick(now).await.expect("tick succeeds");
 
         assert_eq!(result.timed_out, 2);
         assert_eq!(result.expired_requeued, 3);
@@ -651,6 +678,7 @@ mod tests {
             version: 1,
             terminal_state: Some(LeaseTerminalState::Completed),
             terminal_at: Some(now),
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         assert!(lease.is_terminal());
@@ -671,6 +699,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let active_lease = WorkerLease::from_record(active_record);
         assert!(active_lease
@@ -696,6 +725,7 @@ mod tests {
             version: 1,
             terminal_state: Some(LeaseTerminalState::Completed),
             terminal_at: Some(now),
+            tenant_id: None,
         };
         let terminal_lease = WorkerLease::from_record(terminal_record);
         assert!(terminal_lease
@@ -726,6 +756,7 @@ mod tests {
                 version: 1,
                 terminal_state: Some(state),
                 terminal_at: Some(now),
+                tenant_id: None,
             };
             let lease = WorkerLease::from_record(record);
             assert!(
@@ -755,6 +786,7 @@ mod tests {
                 version: 1,
                 terminal_state: Some(from_state.clone()),
                 terminal_at: Some(now),
+                tenant_id: None,
             };
             let lease = WorkerLease::from_record(record);
             for to_state in &terminal_states {
@@ -780,6 +812,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         let all_terminal = vec![
@@ -809,6 +842,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let active_lease = WorkerLease::from_record(active_record);
         assert!(active_lease
@@ -824,6 +858,7 @@ mod tests {
             version: 2,
             terminal_state: Some(LeaseTerminalState::Completed),
             terminal_at: Some(now),
+            tenant_id: None,
         };
         let terminal_lease = WorkerLease::from_record(terminal_record);
         assert!(terminal_lease
@@ -847,6 +882,7 @@ mod tests {
             version: 1,
             terminal_state: None,
             terminal_at: None,
+            tenant_id: None,
         };
         let lease = WorkerLease::from_record(record);
         assert!(!lease.is_terminal());
@@ -0,0 +1,354 @@
+//! Keeps one attempt's lease alive while its worker executes it (feature `lease-service`).
+//!
+//! [LeaseKeeper::spawn] heartbeats the lease every `interval` with the lease's fencing
+//! token, extending it by the TTL it was granted with. A heartbeat that fails with a
+//! storage error is retried after a growing delay, up to
+//! [LeaseKeeper::MAX_HEARTBEAT_FAILURES] failures in a row; a rejected heartbeat (the
+//! lease expired and moved on, or was stolen) or one failure too many trips the
+//! [lost](LeaseKeeper::lost) token, which the worker attaches to its run so it stops
+//! instead of writing results it no longer owns. A [LeaseDirective::Cancel] from a
+//! heartbeat trips [cancel_requested](LeaseKeeper::cancel_requested) instead.
+//!
+//! Heartbeating stops when the keeper is dropped, when [LeaseKeeper::stop] is awaited or
+//! when the shutdown token fires. Keep `interval` at a third of the lease TTL or less, so
+//! the retries of a failing heartbeat still land before the lease expires.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use oris_kernel::event::KernelError;
+
+use super::async_repository::AsyncRuntimeRepository;
+use super::clock::{Clock, SystemClock};
+use super::models::{LeaseDirective, LeaseFence, LeaseRecord};
+
+/// Heartbeats one lease in the background; see the [module docs](self).
+pub struct LeaseKeeper {
+    fence: Arc<Mutex<LeaseFence>>,
+    lost: CancellationToken,
+    cancel_requested: CancellationToken,
+    stop: DropGuard,
+    task: JoinHandle<()>,
+}
+
+impl LeaseKeeper {
+    /// Failed heartbeats in a row after which the lease is given up as lost.
+    pub const MAX_HEARTBEAT_FAILURES: u32 = 3;
+
+    /// Heartbeat `lease` every `interval` until the keeper is dropped or stopped, or
+    /// `shutdown` is cancelled. Each heartbeat extends the lease by the TTL it has now
+    /// (`lease_expires_at - heartbeat_at`). Must be called within a Tokio runtime.
+    pub fn spawn<R>(
+        repository: Arc<R>,
+        lease: &LeaseRecord,
+        interval: Duration,
+        shutdown: CancellationToken,
+    ) -> Self
+    where
+        R: AsyncRuntimeRepository + 'static,
+    {
+        Self::spawn_with_clock(repository, lease, interval, shutdown, Arc::new(SystemClock))
+    }
+
+    /// As [Self::spawn], reading heartbeat times from `clock`.
+    pub fn spawn_with_clock<R>(
+        repository: Arc<R>,
+        lease: &LeaseRecord,
+        interval: Duration,
+        shutdown: CancellationToken,
+        clock: Arc<dyn Clock>,
+    ) -> Self
+    where
+        R: AsyncRuntimeRepository + 'static,
+    {
+        let fence = Arc::new(Mutex::new(LeaseFence::from(lease)));
+        let lost = CancellationToken::new();
+        let cancel_requested = CancellationToken::new();
+        let stop = shutdown.child_token();
+        let heartbeat = Heartbeat {
+            repository,
+            fence: Arc::clone(&fence),
+            ttl: lease.lease_expires_at - lease.heartbeat_at,
+            interval,
+            clock,
+            lost: lost.clone(),
+            cancel_requested: cancel_requested.clone(),
+        };
+        let task = tokio::spawn(heartbeat.run(stop.clone()));
+        Self {
+            fence,
+            lost,
+            cancel_requested,
+            stop: stop.drop_guard(),
+            task,
+        }
+    }
+
+    /// Cancelled once the lease is lost; the attempt may belong to another worker by then.
+    pub fn lost(&self) -> CancellationToken {
+        self.lost.clone()
+    }
+
+    /// Cancelled once a heartbeat reports the attempt cancelled.
+    pub fn cancel_requested(&self) -> CancellationToken {
+        self.cancel_requested.clone()
+    }
+
+    /// The lease's fencing token as of the last successful heartbeat.
+    pub fn fence(&self) -> LeaseFence {
+        lock_fence(&self.fence).clone()
+    }
+
+    /// Stop heartbeating, waiting for a heartbeat in flight, and return the final fencing
+    /// token to finish the attempt with.
+    pub async fn stop(self) -> LeaseFence {
+        let LeaseKeeper {
+            fence, stop, task, ..
+        } = self;
+        drop(stop);
+        let _ = task.await;
+        let fence = lock_fence(&fence).clone();
+        fence
+    }
+}
+
+struct Heartbeat<R> {
+    repository: Arc<R>,
+    fence: Arc<Mutex<LeaseFence>>,
+    ttl: chrono::Duration,
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    lost: CancellationToken,
+    cancel_requested: CancellationToken,
+}
+
+impl<R: AsyncRuntimeRepository> Heartbeat<R> {
+    async fn run(self, stop: CancellationToken) {
+        let mut failures = 0u32;
+        let mut delay = self.interval;
+        loop {
+            tokio::select! {
+                _ = stop.cancelled() => return,
+                _ = tokio::time::sleep(delay) => {}
+            }
+            // Not raced against `stop`: a heartbeat that lands must advance the fence
+            let fence = lock_fence(&self.fence).clone();
+            let now = self.clock.now();
+            let beat = self
+                .repository
+                .heartbeat_lease_with_version(
+                    &fence.lease_id,
+                    &fence.worker_id,
+                    fence.version,
+                    now,
+                    now + self.ttl,
+                )
+                .await;
+            match beat {
+                Ok(directive) => {
+                    failures = 0;
+                    delay = self.interval;
+                    lock_fence(&self.fence).version = fence.version + 1;
+                    if directive == LeaseDirective::Cancel {
+                        self.cancel_requested.cancel();
+                    }
+                }
+                Err(KernelError::LeaseConflict(_)) | Err(KernelError::NotFound(_)) => {
+                    tracing::warn!(lease_id = %fence.lease_id, "lease lost: heartbeat rejected");
+                    self.lost.cancel();
+                    return;
+                }
+                Err(e) => {
+                    failures += 1;
+                    if failures >= LeaseKeeper::MAX_HEARTBEAT_FAILURES {
+                        tracing::warn!(
+                            lease_id = %fence.lease_id,
+                            error = %e,
+                            failures,
+                            "lease lost: heartbeat kept failing"
+                        );
+                        self.lost.cancel();
+                        return;
+                    }
+                    tracing::warn!(
+                        lease_id = %fence.lease_id,
+                        error = %e,
+                        failures,
+                        "lease heartbeat failed; retrying"
+                    );
+                    delay = retry_delay(self.interval, failures);
+                }
+            }
+        }
+    }
+}
+
+/// A quarter of `interval` after the first failure, doubling per failure after that, up
+/// to `interval`.
+fn retry_delay(interval: Duration, failures: u32) -> Duration {
+    (interval / 4)
+        .checked_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .map_or(interval, |delay| delay.min(interval))
+}
+
+fn lock_fence(fence: &Mutex<LeaseFence>) -> std::sync::MutexGuard<'_, LeaseFence> {
+    fence.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+    use tokio::time::Instant;
+    use tokio_util::sync::CancellationToken;
+
+    use super::{retry_delay, LeaseKeeper};
+    use crate::clock::Clock;
+    use crate::memory_runtime_repository::InMemoryRuntimeRepository;
+    use crate::models::{AttemptExecutionStatus, LeaseRecord};
+    use crate::repository::RuntimeRepository;
+    use crate::repository_contract::seed_run;
+
+    /// Wall time that follows Tokio's clock, so pausing Tokio time pauses it too.
+    struct TokioClock {
+        start: DateTime<Utc>,
+        origin: Instant,
+    }
+
+    impl TokioClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                start: Utc::now(),
+                origin: Instant::now(),
+            })
+        }
+    }
+
+    impl Clock for TokioClock {
+        fn now(&self) -> DateTime<Utc> {
+            let elapsed = Instant::now().duration_since(self.origin);
+            self.start + chrono::Duration::from_std(elapsed).expect("elapsed in range")
+        }
+    }
+
+    /// Attempt `attempt-1` leased to `worker-1` for `ttl`.
+    fn leased_repository(
+        clock: Arc<TokioClock>,
+        ttl: chrono::Duration,
+    ) -> (Arc<InMemoryRuntimeRepository>, LeaseRecord) {
+        let repo = Arc::new(InMemoryRuntimeRepository::new().with_clock(clock.clone()));
+        seed_run(repo.as_ref(), "run-1");
+        repo.enqueue_attempt("attempt-1", "run-1")
+            .expect("enqueue attempt");
+        let lease = repo
+            .upsert_lease("attempt-1", "worker-1", clock.now() + ttl)
+            .expect("lease attempt");
+        (repo, lease)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_the_lease_alive_across_a_node_running_several_ttls() {
+        let clock = TokioClock::new();
+        let (repo, lease) = leased_repository(clock.clone(), chrono::Duration::seconds(1));
+        let keeper = LeaseKeeper::spawn_with_clock(
+            Arc::clone(&repo),
+            &lease,
+            Duration::from_millis(300),
+            CancellationToken::new(),
+            clock.clone(),
+        );
+
+        // The "node" runs for five TTLs while the lease service reaps stale leases
+        let node_done = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < node_done {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            let expired = repo
+                .expire_leases_and_requeue(clock.now())
+                .expect("expire stale leases");
+            assert_eq!(expired, 0, "lease lapsed at {}", clock.now());
+        }
+
+        assert!(!keeper.lost().is_cancelled());
+        let fence = keeper.stop().await;
+        assert!(fence.version >= lease.version + 15);
+        assert_eq!(
+            repo.attempt_status("attempt-1").expect("read status"),
+            Some(AttemptExecutionStatus::Leased)
+        );
+        repo.heartbeat_lease_with_version(
+            &fence.lease_id,
+            &fence.worker_id,
+            fence.version,
+            clock.now(),
+            clock.now() + chrono::Duration::seconds(1),
+        )
+        .expect("final fence is current");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn trips_lost_when_the_lease_is_taken() {
+        let clock = TokioClock::new();
+        let (repo, lease) = leased_repository(clock.clone(), chrono::Duration::seconds(1));
+        let keeper = LeaseKeeper::spawn_with_clock(
+            Arc::clone(&repo),
+            &lease,
+            Duration::from_millis(300),
+            CancellationToken::new(),
+            clock.clone(),
+        );
+
+        repo.expire_leases_and_requeue(clock.now() + chrono::Duration::seconds(5))
+            .expect("expire lease");
+        repo.upsert_lease(
+            "attempt-1",
+            "worker-2",
+            clock.now() + chrono::Duration::seconds(1),
+        )
+        .expect("another worker leases the attempt");
+
+        tokio::time::timeout(Duration::from_secs(1), keeper.lost().cancelled())
+            .await
+            .expect("keeper notices the lost lease");
+        assert!(!keeper.cancel_requested().is_cancelled());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropping_the_keeper_stops_heartbeats() {
+        let clock = TokioClock::new();
+        let (repo, lease) = leased_repository(clock.clone(), chrono::Duration::seconds(1));
+        let keeper = LeaseKeeper::spawn_with_clock(
+            Arc::clone(&repo),
+            &lease,
+            Duration::from_millis(300),
+            CancellationToken::new(),
+            clock.clone(),
+        );
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let fence = keeper.fence();
+        assert_eq!(fence.version, lease.version + 1);
+        drop(keeper);
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(
+            repo.expire_leases_and_requeue(clock.now())
+                .expect("expire lease"),
+            1
+        );
+        let current = repo.get_lease_for_attempt("attempt-1").expect("read lease");
+        assert!(current.is_none());
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_interval() {
+        let interval = Duration::from_secs(8);
+        assert_eq!(retry_delay(interval, 1), Duration::from_secs(2));
+        assert_eq!(retry_delay(interval, 2), Duration::from_secs(4));
+        assert_eq!(retry_delay(interval, 3), interval);
+        assert_eq!(retry_delay(interval, 40), interval);
+    }
+}
@@ -0,0 +1,434 @@
+//! Background task that drives a [LeaseManager] (feature `lease-service`).
+//!
+//! [LeaseService::spawn] ticks the manager right away and then every `interval`, so
+//! stale leases are expired and their attempts requeued without each host writing its
+//! own loop. A failing tick is retried after an exponentially growing delay, capped at
+//! [LeaseService::MAX_BACKOFF], instead of hammering an unavailable repository. When the
+//! shutdown token fires the service stops; a tick already running is finished first.
+//! With the `metrics` feature each successful tick also publishes the repository's
+//! [RuntimeStats](crate::RuntimeStats) as gauges.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use super::lease::{LeaseManager, LeaseTickResult};
+
+/// Totals over a [LeaseService]'s lifetime.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeaseServiceStats {
+    /// Ticks that completed.
+    pub ticks: u64,
+    /// Ticks that failed with a repository error.
+    pub failed_ticks: u64,
+    /// Attempts moved to their timeout status.
+    pub timed_out: u64,
+    /// Expired leases whose attempts were requeued.
+    pub expired_requeued: u64,
+}
+
+impl LeaseServiceStats {
+    fn record(&mut self, result: &LeaseTickResult) {
+        self.ticks += 1;
+        self.timed_out += result.timed_out;
+        self.expired_requeued += result.expired_requeued;
+    }
+}
+
+/// Spawns the periodic lease tick.
+pub struct LeaseService;
+
+impl LeaseService {
+    /// Longest wait between ticks while the repository keeps failing.
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+    /// Tick `manager` now and every `interval` until `shutdown` is cancelled. Must be
+    /// called within a Tokio runtime; each tick runs as its own task, so a tick that
+    /// panics counts as a failed one.
+    pub fn spawn<M>(
+        manager: M,
+        interval: Duration,
+        shutdown: CancellationToken,
+    ) -> LeaseServiceHandle
+    where
+        M: LeaseManager + 'static,
+    {
+        let manager = Arc::new(manager);
+        let task = tokio::spawn(async move {
+            let mut stats = LeaseServiceStats::default();
+            let mut consecutive_failures = 0u32;
+            loop {
+                let tick_manager = Arc::clone(&manager);
+                let outcome = tokio::spawn(async move {
+                    let now = Utc::now();
+                    let result = tick_manager.tick(now).await;
+                    #[cfg(feature = "metrics")]
+                    if result.is_ok() {
+                        publish_runtime_stats(tick_manager.as_ref(), now).await;
+                    }
+                    result
+                })
+                .await;
+                match outcome {
+                    Ok(Ok(result)) => {
+                        consecutive_failures = 0;
+                        stats.record(&result);
+                        #[cfg(feature = "metrics")]
+                        ::metrics::counter!(crate::metrics::ATTEMPTS_TIMED_OUT_TOTAL)
+                            .increment(result.timed_out);
+                        if result.timed_out > 0 || result.expired_requeued > 0 {
+                            tracing::info!(
+                                timed_out = result.timed_out,
+                                expired_requeued = result.expired_requeued,
+                                "lease tick recovered attempts"
+                            );
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        consecutive_failures = consecutive_failures.saturating_add(1);
+                        stats.failed_ticks += 1;
+                        #[cfg(feature = "metrics")]
+                        ::metrics::counter!(crate::metrics::LEASE_TICK_FAILURES_TOTAL).increment(1);
+                        tracing::warn!(
+                            error = %e,
+                            consecutive_failures,
+                            "lease tick failed"
+                        );
+                    }
+                    Err(e) => {
+                        consecutive_failures = consecutive_failures.saturating_add(1);
+                        stats.failed_ticks += 1;
+                        #[cfg(feature = "metrics")]
+                        ::metrics::counter!(crate::metrics::LEASE_TICK_FAILURES_TOTAL).increment(1);
+                        tracing::error!(error = %e, "lease tick panicked");
+                    }
+                }
+
+                let delay = next_delay(interval, consecutive_failures);
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+            stats
+        });
+        LeaseServiceHandle { task }
+    }
+}
+
+/// Set the queue gauges from the manager's stats. A failed read is logged but does not
+/// fail the tick that already expired leases.
+#[cfg(feature = "metrics")]
+async fn publish_runtime_stats<M: LeaseManager + ?Sized>(manager: &M, now: chrono::DateTime<Utc>) {
+    match manager.runtime_stats(now).await {
+        Ok(Some(stats)) => crate::metrics::record_runtime_stats(&stats),
+        Ok(None) => {}
+        Err(e) => tracing::warn!(error = %e, "reading runtime stats failed"),
+    }
+}
+
+/// `interval` after a successful tick; after `failures` failed ticks in a row, the
+/// interval doubled per failure up to [LeaseService::MAX_BACKOFF] (or the interval
+/// itself, when that is longer).
+fn next_delay(interval: Duration, failures: u32) -> Duration {
+    if failures == 0 {
+        return interval;
+    }
+    let cap = interval.max(LeaseService::MAX_BACKOFF);
+    interval
+        .checked_mul(2u32.saturating_pow(failures))
+        .map_or(cap, |delay| delay.min(cap))
+}
+
+/// Handle to a running [LeaseService].
+pub struct LeaseServiceHandle {
+    task: JoinHandle<LeaseServiceStats>,
+}
+
+impl LeaseServiceHandle {
+    /// Wait for the service to stop after its shutdown token fired, returning its totals.
+    pub async fn await_stopped(self) -> LeaseServiceStats {
+        self.task.await.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+    use oris_kernel::event::KernelError;
+    use oris_kernel::identity::{RunId, Seq};
+    use tokio::time::Instant;
+
+    use super::*;
+    use crate::lease::{LeaseConfig, RepositoryLeaseManager};
+    use crate::models::{
+        AttemptDispatchRecord, BountyRecord, DisputeRecord, LeaseDirective, LeaseRecord,
+        OrganismRecord, RecipeRecord, SessionMessageRecord, SessionRecord, SwarmTaskRecord,
+        WorkerRecord,
+    };
+    use crate::repository::RuntimeRepository;
+
+    /// Requeues one lease per tick, failing the ticks queued in `failures` first, and
+    /// records when each tick reached the repository.
+    #[derive(Clone)]
+    struct FakeRepository {
+        failures: Arc<Mutex<VecDeque<bool>>>,
+        tick_times: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    impl FakeRepository {
+        fn new(failing_ticks: usize) -> Self {
+            Self {
+                failures: Arc::new(Mutex::new(vec![true; failing_ticks].into())),
+                tick_times: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        /// Seconds after `start` at which each tick ran.
+        fn tick_offsets(&self, start: Instant) -> Vec<u64> {
+            self.tick_times
+                .lock()
+                .expect("tick times lock")
+                .iter()
+                .map(|at| at.duration_since(start).as_secs())
+                .collect()
+        }
+    }
+
+    impl RuntimeRepository for FakeRepository {
+        fn list_dispatchable_attempts(
+            &self,
+            _now: DateTime<Utc>,
+            _limit: usize,
+        ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+            Ok(Vec::new())
+        }
+
+        fn upsert_lease(
+            &self,
+            _attempt_id: &str,
+            _worker_id: &str,
+            _lease_expires_at: DateTime<Utc>,
+        ) -> Result<LeaseRecord, KernelError> {
+            Err(KernelError::Driver("not used".to_string()))
+        }
+
+        fn heartbeat_lease(
+            &self,
+            _lease_id: &str,
+            _heartbeat_at: DateTime<Utc>,
+            _lease_expires_at: DateTime<Utc>,
+        ) -> Result<LeaseDirective, KernelError> {
+            Ok(LeaseDirective::Continue)
+        }
+
+        fn expire_leases_and_requeue(
+            &self,
+            _stale_before: DateTime<Utc>,
+        ) -> Result<u64, KernelError> {
+            self.tick_times
+                .lock()
+                .expect("tick times lock")
+                .push(Instant::now());
+            if self
+                .failures
+                .lock()
+                .expect("failures lock")
+                .pop_front()
+                .unwrap_or(false)
+            {
+                return Err(KernelError::Storage("database unavailable".to_string()));
+            }
+            Ok(1)
+        }
+
+        fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
+            Ok(0)
+        }
+
+        fn upsert_bounty(&self, _: &BountyRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_bounty(&self, _: &str) -> Result<Option<BountyRecord>, KernelError> {
+            Ok(None)
+        }
+        fn list_bounties(
+            &self,
+            _: Option<&str>,
+            _: usize,
+        ) -> Result<Vec<BountyRecord>, KernelError> {
+            Ok(vec![])
+        }
+        fn accept_bounty(&self, _: &str, _: &str) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn close_bounty(&self, _: &str) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn upsert_swarm_decomposition(&self, _: &SwarmTaskRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_swarm_decomposition(&self, _: &str) -> Result<Option<SwarmTaskRecord>, KernelError> {
+            Ok(None)
+        }
+        fn register_worker(&self, _: &WorkerRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_worker(&self, _: &str) -> Result<Option<WorkerRecord>, KernelError> {
+            Ok(None)
+        }
+        fn list_workers(
+            &self,
+            _: Option<&str>,
+            _: Option<&str>,
+            _: usize,
+        ) -> Result<Vec<WorkerRecord>, KernelError> {
+            Ok(vec![])
+        }
+        fn heartbeat_worker(&self, _: &str, _: i64) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn create_recipe(&self, _: &RecipeRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_recipe(&self, _: &str) -> Result<Option<RecipeRecord>, KernelError> {
+            Ok(None)
+        }
+        fn fork_recipe(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<Option<RecipeRecord>, KernelError> {
+            Ok(None)
+        }
+        fn list_recipes(
+            &self,
+            _: Option<&str>,
+            _: usize,
+        ) -> Result<Vec<RecipeRecord>, KernelError> {
+            Ok(vec![])
+        }
+        fn express_organism(&self, _: &OrganismRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_organism(&self, _: &str) -> Result<Option<OrganismRecord>, KernelError> {
+            Ok(None)
+        }
+        fn update_organism(&self, _: &str, _: i32, _: &str) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn create_session(&self, _: &SessionRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_session(&self, _: &str) -> Result<Option<SessionRecord>, KernelError> {
+            Ok(None)
+        }
+        fn add_session_message(&self, _: &SessionMessageRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_session_history(
+            &self,
+            _: &str,
+            _: usize,
+        ) -> Result<Vec<SessionMessageRecord>, KernelError> {
+            Ok(vec![])
+        }
+        fn open_dispute(&self, _: &DisputeRecord) -> Result<(), KernelError> {
+            Ok(())
+        }
+        fn get_dispute(&self, _: &str) -> Result<Option<DisputeRecord>, KernelError> {
+            Ok(None)
+        }
+        fn get_disputes_for_bounty(&self, _: &str) -> Result<Vec<DisputeRecord>, KernelError> {
+            Ok(vec![])
+        }
+        fn resolve_dispute(&self, _: &str, _: &str, _: &str) -> Result<(), KernelError> {
+            Ok(())
+        }
+    }
+
+    fn spawn_service(
+        repo: &FakeRepository,
+        interval: Duration,
+    ) -> (LeaseServiceHandle, CancellationToken) {
+        let shutdown = CancellationToken::new();
+        let manager = RepositoryLeaseManager::new(repo.clone(), LeaseConfig::default());
+        let handle = LeaseService::spawn(manager, interval, shutdown.clone());
+        (handle, shutdown)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lease_service_ticks_on_the_interval_until_shutdown() {
+        let repo = FakeRepository::new(0);
+        let start = Instant::now();
+        let (handle, shutdown) = spawn_service(&repo, Duration::from_secs(10));
+
+        tokio::time::sleep(Duration::from_secs(35)).await;
+        shutdown.cancel();
+        let stats = handle.await_stopped().await;
+
+        assert_eq!(repo.tick_offsets(start), vec![0, 10, 20, 30]);
+        assert_eq!(
+            stats,
+            LeaseServiceStats {
+                ticks: 4,
+                failed_ticks: 0,
+                timed_out: 0,
+                expired_requeued: 4,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lease_service_backs_off_exponentially_while_ticks_fail() {
+        let repo = FakeRepository::new(3);
+        let start = Instant::now();
+        let (handle, shutdown) = spawn_service(&repo, Duration::from_secs(10));
+
+        tokio::time::sleep(Duration::from_secs(135)).await;
+        shutdown.cancel();
+        let stats = handle.await_stopped().await;
+
+        // Waits of 20s, 40s, then 80s capped at 60s; the first success resets to 10s
+        assert_eq!(repo.tick_offsets(start), vec![0, 20, 60, 120, 130]);
+        assert_eq!(stats.failed_ticks, 3);
+        assert_eq!(stats.ticks, 2);
+        assert_eq!(stats.expired_requeued, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lease_service_stops_between_ticks_when_shutdown_fires() {
+        let repo = FakeRepository::new(0);
+        let start = Instant::now();
+        let (handle, shutdown) = spawn_service(&repo, Duration::from_secs(10));
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        shutdown.cancel();
+        let stats = handle.await_stopped().await;
+
+        assert_eq!(Instant::now().duration_since(start), Duration::from_secs(5));
+        assert_eq!(repo.tick_offsets(start), vec![0]);
+        assert_eq!(stats.ticks, 1);
+    }
+
+    #[test]
+    fn next_delay_doubles_per_failure_up_to_the_cap() {
+        let interval = Duration::from_secs(1);
+        let delays: Vec<u64> = (0..8)
+            .map(|failures| next_delay(interval, failures).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(
+            next_delay(Duration::from_secs(120), 3),
+            Duration::from_secs(120)
+        );
+        assert_eq!(next_delay(interval, u32::MAX), LeaseService::MAX_BACKOFF);
+    }
+}
@@ -8,18 +8,31 @@ pub mod api_errors;
 pub mod api_idempotency;
 #[cfg(feature = "execution-server")]
 pub mod api_models;
+pub mod async_repository;
 #[cfg(feature = "sqlite-persistence")]
 pub mod backend_config;
 pub mod circuit_breaker;
+pub mod clock;
 #[cfg(feature = "execution-server")]
 pub mod graph_bridge;
 pub mod lease;
+#[cfg(feature = "lease-service")]
+pub mod lease_keeper;
+#[cfg(feature = "lease-service")]
+pub mod lease_service;
+pub mod memory_runtime_repository;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod models;
 pub mod observability;
 #[cfg(feature = "kernel-postgres")]
 pub mod postgres_runtime_repository;
 pub mod recovery;
+#[cfg(feature = "kernel-redis")]
+pub mod redis_runtime_repository;
 pub mod repository;
+#[cfg(any(test, feature = "test-util"))]
+pub mod repository_contract;
 pub mod scheduler;
 #[cfg(feature = "sqlite-persistence")]
 pub mod sqlite_runtime_repository;
@@ -35,20 +48,27 @@ pub use api_errors::ApiError;
 pub use api_idempotency::{IdempotencyRecord, SqliteIdempotencyStore};
 #[cfg(feature = "execution-server")]
 pub use api_models::{
-    ApiEnvelope, ApiMeta, AttemptRetryHistoryItem, AttemptRetryHistoryResponse, AuditLogItem,
-    AuditLogListResponse, CancelJobRequest, CancelJobResponse, CheckpointInspectResponse,
+    ApiEnvelope, ApiMeta, AttemptCancelResponse, AttemptRetryHistoryItem,
+    AttemptRetryHistoryResponse, AuditLogItem, AuditLogListResponse, CancelJobRequest,
+    CancelJobResponse, CheckpointInspectResponse, CreateRunRequest, CreateRunResponse,
     DeadLetterItem, DeadLetterListResponse, DeadLetterReplayResponse, InterruptDetailResponse,
-    InterruptListResponse, JobDetailResponse, JobHistoryItem, JobHistoryResponse, JobStateResponse,
-    JobTimelineItem, JobTimelineResponse, ListAuditLogsQuery, ListDeadLettersQuery,
-    ListInterruptsQuery, ListJobsQuery, ListJobsResponse, RejectInterruptRequest, ReplayJobRequest,
-    ResumeInterruptRequest, ResumeJobRequest, RetryPolicyRequest, RunJobRequest, RunJobResponse,
-    TimelineExportResponse, TimeoutPolicyRequest, TraceContextResponse, WorkerAckRequest,
-    WorkerAckResponse, WorkerExtendLeaseRequest, WorkerHeartbeatRequest, WorkerLeaseResponse,
-    WorkerPollRequest, WorkerPollResponse, WorkerReportStepRequest,
+    InterruptListResponse, JobDetailResponse, JobHistoryItem, JobHistoryResponse, JobRunMode,
+    JobStateResponse, JobTimelineItem, JobTimelineResponse, ListAuditLogsQuery,
+    ListDeadLettersQuery, ListInterruptsQuery, ListJobsQuery, ListJobsResponse, PauseJobResponse,
+    RejectInterruptRequest, ReplayJobRequest, ResolveInterruptRequest, ResolveInterruptResponse,
+    ResumeInterruptRequest, ResumeJobRequest, ResumeRunRequest, RetryPolicyRequest, RunEventsQuery,
+    RunJobRequest, RunJobResponse, RunStatusResponse, TimelineExportResponse, TimeoutPolicyRequest,
+    TraceContextResponse, WorkerAckRequest, WorkerAckResponse, WorkerExtendLeaseRequest,
+    WorkerHeartbeatRequest, WorkerLeaseResponse, WorkerPollRequest, WorkerPollResponse,
+    WorkerReportStepRequest,
 };
+pub use async_repository::AsyncRuntimeRepository;
+#[cfg(feature = "kernel-postgres")]
+pub use async_repository::BlockingRuntimeRepository;
 #[cfg(feature = "sqlite-persistence")]
 pub use backend_config::{RuntimeStorageBackend, RuntimeStorageConfig};
 pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use clock::{Clock, ManualClock, SystemClock};
 #[cfg(feature = "execution-server")]
 pub use graph_bridge::{
     ExecutionCheckpointView, ExecutionGraphBridge, ExecutionGraphBridgeError,
@@ -58,9 +78,18 @@ pub use lease::{
     LeaseConfig, LeaseManager, LeaseTickResult, RepositoryLeaseManager, WorkerHealth,
     WorkerHealthTracker, WorkerLease,
 };
+#[cfg(feature = "lease-service")]
+pub use lease_keeper::LeaseKeeper;
+#[cfg(feature = "lease-service")]
+pub use lease_service::{LeaseService, LeaseServiceHandle, LeaseServiceStats};
+pub use memory_runtime_repository::InMemoryRuntimeRepository;
 pub use models::{
-    AttemptDispatchRecord, AttemptExecutionStatus, InterruptRecord, LeaseRecord,
-    LeaseTerminalState, RunRecord, RunRuntimeStatus,
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    AttemptStatusCounts, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions,
+    DispatchableAttempts, EnqueueBatchOutcome, EnqueueOptions, InterruptDecision, InterruptFilter,
+    InterruptRecord, InterruptStatus, LeaseDirective, LeaseFence, LeaseRecord, LeaseTerminalState,
+    RetryPolicyConfig, RetryStrategy, RunRecord, RunRecordFilter, RunRuntimeStatus, RuntimeStats,
+    TimeoutPolicyConfig,
 };
 pub use observability::{KernelObservability, RejectionReason};
 #[cfg(feature = "kernel-postgres")]
@@ -68,6 +97,8 @@ pub use postgres_runtime_repository::{
     PostgresIdempotencyRecord, PostgresIdempotencyStore, PostgresRuntimeRepository,
 };
 pub use recovery::{CrashRecoveryPipeline, RecoveryContext, RecoveryStep};
+#[cfg(feature = "kernel-redis")]
+pub use redis_runtime_repository::{RedisRepositoryConfig, RedisRuntimeRepository};
 pub use repository::RuntimeRepository;
 pub use scheduler::{
     DispatchContext, FairnessPolicy, ResourceBudget, SchedulerDecision, SchedulerMetrics,
@@ -0,0 +1,1616 @@
+//! In-memory runtime repository for tests and embedded use.
+//!
+//! [InMemoryRuntimeRepository] keeps runs, attempts, leases and the evolution records
+//! in process memory behind one mutex, with the same dispatch, lease and retry
+//! semantics as the SQLite repository. Nothing survives the process; use it where a
+//! database would only get in the way, and inject a [ManualClock](crate::ManualClock)
+//! to drive lease expiry and backoff without sleeping.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+use oris_kernel::event::KernelError;
+use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::PageRequest;
+
+use super::clock::{Clock, SystemClock};
+use super::models::{
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    BountyRecord, BountyStatus, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions,
+    DispatchableAttempts, DisputeRecord, DisputeStatus, EnqueueBatchOutcome, EnqueueOptions,
+    InterruptDecision, InterruptFilter, InterruptRecord, InterruptStatus, LeaseDirective,
+    LeaseFence, LeaseRecord, OrganismRecord, RecipeRecord, RetryPolicyConfig, RunRecord,
+    RunRecordFilter, RunRuntimeStatus, RuntimeStats, SessionMessageRecord, SessionRecord,
+    SwarmTaskRecord, TimeoutPolicyConfig, WorkerRecord,
+};
+use super::repository::{run_tenant_in_scope, RuntimeRepository};
+
+#[derive(Clone, Debug)]
+struct MemoryAttempt {
+    attempt_id: String,
+    run_id: RunId,
+    attempt_no: u32,
+    status: AttemptExecutionStatus,
+    priority: i32,
+    retry_at: Option<DateTime<Utc>>,
+    run_at: Option<DateTime<Utc>>,
+    enqueued_at: DateTime<Utc>,
+    /// Enqueue order, breaking ties between attempts enqueued in the same instant.
+    enqueue_seq: u64,
+    tenant_id: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+    cancel_requested: bool,
+    last_error: Option<String>,
+    dead_lettered_at: Option<DateTime<Utc>>,
+    timeout: Option<TimeoutPolicyConfig>,
+}
+
+impl MemoryAttempt {
+    fn waiting(&self) -> bool {
+        matches!(
+            self.status,
+            AttemptExecutionStatus::Queued | AttemptExecutionStatus::RetryBackoff
+        )
+    }
+
+    fn ready(&self, now: DateTime<Utc>) -> bool {
+        let retry_due = self.status == AttemptExecutionStatus::Queued
+            || (self.status == AttemptExecutionStatus::RetryBackoff
+                && self.retry_at.map_or(true, |at| at <= now));
+        retry_due && self.run_at.map_or(true, |at| at <= now)
+    }
+
+    fn dispatch_record(&self) -> AttemptDispatchRecord {
+        AttemptDispatchRecord {
+            attempt_id: self.attempt_id.clone(),
+            run_id: self.run_id.clone(),
+            attempt_no: self.attempt_no,
+            status: self.status.clone(),
+            retry_at: self.retry_at,
+            priority: self.priority,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MemoryState {
+    runs: HashMap<RunId, RunRecord>,
+    attempts: HashMap<String, MemoryAttempt>,
+    /// Keyed by attempt id: an attempt holds at most one lease.
+    leases: HashMap<String, LeaseRecord>,
+    interrupts: HashMap<String, InterruptRecord>,
+    bounties: HashMap<String, BountyRecord>,
+    swarm_tasks: HashMap<String, SwarmTaskRecord>,
+    workers: HashMap<String, WorkerRecord>,
+    recipes: HashMap<String, RecipeRecord>,
+    organisms: HashMap<String, OrganismRecord>,
+    sessions: HashMap<String, SessionRecord>,
+    session_messages: HashMap<String, SessionMessageRecord>,
+    disputes: HashMap<String, DisputeRecord>,
+    next_enqueue_seq: u64,
+    next_lease_no: u64,
+}
+
+impl MemoryState {
+    fn active_leases_for_run(&self, run_id: &str, now: DateTime<Utc>) -> usize {
+        self.leases
+            .values()
+            .filter(|lease| lease.lease_expires_at >= now)
+            .filter(|lease| {
+                self.attempts
+                    .get(&lease.attempt_id)
+                    .is_some_and(|attempt| attempt.run_id == run_id)
+            })
+            .count()
+    }
+
+    fn has_active_lease(&self, attempt_id: &str, now: DateTime<Utc>) -> bool {
+        self.leases
+            .get(attempt_id)
+            .is_some_and(|lease| lease.lease_expires_at >= now)
+    }
+
+    fn lease_by_id_mut(&mut self, lease_id: &str) -> Option<&mut LeaseRecord> {
+        self.leases
+            .values_mut()
+            .find(|lease| lease.lease_id == lease_id)
+    }
+
+    fn lease_directive(&self, attempt_id: &str) -> LeaseDirective {
+        if self
+            .attempts
+            .get(attempt_id)
+            .is_some_and(|attempt| attempt.cancel_requested)
+        {
+            LeaseDirective::Cancel
+        } else {
+            LeaseDirective::Continue
+        }
+    }
+
+    fn new_lease(
+        &mut self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> LeaseRecord {
+        self.next_lease_no += 1;
+        let attempt = self
+            .attempts
+            .get_mut(attempt_id)
+            .expect("leased attempt exists");
+        attempt.status = AttemptExecutionStatus::Leased;
+        attempt.started_at.get_or_insert(now);
+        let lease = LeaseRecord {
+            lease_id: format!("lease-{}", self.next_lease_no),
+            attempt_id: attempt_id.to_string(),
+            worker_id: worker_id.to_string(),
+            lease_expires_at,
+            heartbeat_at: now,
+            version: 1,
+            terminal_state: None,
+            terminal_at: None,
+            tenant_id: attempt.tenant_id.clone(),
+        };
+        self.leases.insert(attempt_id.to_string(), lease.clone());
+        lease
+    }
+}
+
+/// [RuntimeRepository] held entirely in memory.
+///
+/// Clones share the same state, so a scheduler, a lease manager and a test can all
+/// hold one. Times the trait does not pass in (enqueue order, lease heartbeats on
+/// upsert, run updates, cancellations) come from the repository's [Clock].
+#[derive(Clone)]
+pub struct InMemoryRuntimeRepository {
+    state: Arc<Mutex<MemoryState>>,
+    clock: Arc<dyn Clock>,
+    tenant_id: Option<String>,
+}
+
+impl Default for InMemoryRuntimeRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for InMemoryRuntimeRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryRuntimeRepository")
+            .field("tenant_id", &self.tenant_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InMemoryRuntimeRepository {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemoryState::default())),
+            clock: Arc::new(SystemClock),
+            tenant_id: None,
+        }
+    }
+
+    /// Read the current time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Scope the repository to `tenant_id`, sharing the unscoped repository's state;
+    /// see [RuntimeRepository] for what scoping hides.
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Enqueue one attempt with default options.
+    pub fn enqueue_attempt(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+        self.enqueue_attempts(&[(
+            attempt_id.to_string(),
+            run_id.to_string(),
+            EnqueueOptions::default(),
+        )])
+        .map(|_| ())
+    }
+
+    pub fn get_lease_for_attempt(
+        &self,
+        attempt_id: &str,
+    ) -> Result<Option<LeaseRecord>, KernelError> {
+        let state = self.state()?;
+        Ok(state
+            .leases
+            .get(attempt_id)
+            .filter(|lease| self.in_scope(lease.tenant_id.as_deref()))
+            .cloned())
+    }
+
+    pub fn attempt_status(
+        &self,
+        attempt_id: &str,
+    ) -> Result<Option<AttemptExecutionStatus>, KernelError> {
+        let state = self.state()?;
+        Ok(state
+            .attempts
+            .get(attempt_id)
+            .filter(|attempt| self.in_scope(attempt.tenant_id.as_deref()))
+            .map(|attempt| attempt.status.clone()))
+    }
+
+    /// Bound how long `attempt_id` may stay leased or running; see
+    /// [transition_timed_out_attempts](RuntimeRepository::transition_timed_out_attempts).
+    pub fn set_attempt_timeout_policy(
+        &self,
+        attempt_id: &str,
+        policy: &TimeoutPolicyConfig,
+    ) -> Result<(), KernelError> {
+        if policy.timeout_ms <= 0 {
+            return Err(KernelError::Validation(
+                "timeout policy timeout_ms must be > 0".to_string(),
+            ));
+        }
+        if !matches!(
+            policy.on_timeout_status,
+            AttemptExecutionStatus::Failed | AttemptExecutionStatus::Cancelled
+        ) {
+            return Err(KernelError::Validation(
+                "timeout policy terminal status must be failed or cancelled".to_string(),
+            ));
+        }
+        let mut state = self.state()?;
+        let attempt = self.attempt_mut(&mut state, attempt_id).ok_or_else(|| {
+            KernelError::NotFound(format!(
+                "attempt not found for timeout policy: {}",
+                attempt_id
+            ))
+        })?;
+        attempt.timeout = Some(policy.clone());
+        Ok(())
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, MemoryState>, KernelError> {
+        self.state
+            .lock()
+            .map_err(|_| KernelError::Driver("in-memory runtime repo lock poisoned".to_string()))
+    }
+
+    fn in_scope(&self, tenant_id: Option<&str>) -> bool {
+        self.tenant_id.is_none() || self.tenant_id.as_deref() == tenant_id
+    }
+
+    fn run_in_scope(&self, state: &MemoryState, run_id: &str) -> bool {
+        self.tenant_id.is_none()
+            || state
+                .runs
+                .get(run_id)
+                .is_some_and(|run| self.in_scope(run.tenant_id.as_deref()))
+    }
+
+    fn attempt_mut<'a>(
+        &self,
+        state: &'a mut MemoryState,
+        attempt_id: &str,
+    ) -> Option<&'a mut MemoryAttempt> {
+        state
+            .attempts
+            .get_mut(attempt_id)
+            .filter(|attempt| self.in_scope(attempt.tenant_id.as_deref()))
+    }
+
+    /// Fails with `NotFound` when `attempt_id` exists in another tenant; attempts that
+    /// do not exist are left for the caller to report.
+    fn check_attempt_tenant(
+        &self,
+        state: &MemoryState,
+        attempt_id: &str,
+    ) -> Result<(), KernelError> {
+        match state.attempts.get(attempt_id) {
+            Some(attempt) if !self.in_scope(attempt.tenant_id.as_deref()) => Err(
+                KernelError::NotFound(format!("attempt not found: {}", attempt_id)),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Like [check_attempt_tenant](Self::check_attempt_tenant), for runs.
+    fn check_run_tenant(&self, state: &MemoryState, run_id: &str) -> Result<(), KernelError> {
+        match state.runs.get(run_id) {
+            Some(run) if !self.in_scope(run.tenant_id.as_deref()) => {
+                Err(KernelError::NotFound(format!("run not found: {}", run_id)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn dispatchable(
+        &self,
+        state: &MemoryState,
+        now: DateTime<Utc>,
+        limit: usize,
+        max_per_run: Option<usize>,
+    ) -> DispatchableAttempts {
+        let mut candidates: Vec<&MemoryAttempt> = state
+            .attempts
+            .values()
+            .filter(|attempt| self.in_scope(attempt.tenant_id.as_deref()))
+            .filter(|attempt| {
+                attempt.ready(now) && !state.has_active_lease(&attempt.attempt_id, now)
+            })
+            .collect();
+        let mut skipped_for_fairness = 0;
+        if let Some(max_per_run) = max_per_run {
+            let mut held: HashMap<&str, usize> = HashMap::new();
+            candidates.retain(|attempt| {
+                let active = *held
+                    .entry(attempt.run_id.as_str())
+                    .or_insert_with(|| state.active_leases_for_run(&attempt.run_id, now));
+                let keep = active < max_per_run;
+                if !keep {
+                    skipped_for_fairness += 1;
+                }
+                keep
+            });
+        }
+        candidates.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.enqueued_at.cmp(&b.enqueued_at))
+                .then(a.attempt_no.cmp(&b.attempt_no))
+                .then(a.enqueue_seq.cmp(&b.enqueue_seq))
+        });
+        DispatchableAttempts {
+            attempts: candidates
+                .into_iter()
+                .take(limit)
+                .map(MemoryAttempt::dispatch_record)
+                .collect(),
+            skipped_for_fairness,
+        }
+    }
+
+    fn check_lease_fence(
+        state: &MemoryState,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        now: DateTime<Utc>,
+    ) -> Result<(), KernelError> {
+        let held = state.leases.get(attempt_id).is_some_and(|held| {
+            held.lease_id == lease.lease_id
+                && held.worker_id == lease.worker_id
+                && held.version == lease.version
+                && held.lease_expires_at >= now
+        });
+        if !held {
+            return Err(KernelError::LeaseConflict(format!(
+                "lease {} (worker {}, version {}) no longer holds attempt: {}",
+                lease.lease_id, lease.worker_id, lease.version, attempt_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn ack_attempt_under_lease(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        let mut state = self.state()?;
+        self.check_attempt_tenant(&state, attempt_id)?;
+        if let Some(lease) = lease {
+            Self::check_lease_fence(&state, attempt_id, lease, now)?;
+        }
+        let Some(attempt) = state.attempts.get_mut(attempt_id) else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for ack: {}",
+                attempt_id
+            )));
+        };
+        // A failure of an attempt asked to cancel ends it as cancelled, without a retry
+        let status = if status == AttemptExecutionStatus::Failed && attempt.cancel_requested {
+            AttemptExecutionStatus::Cancelled
+        } else {
+            status
+        };
+        attempt.status = status.clone();
+        attempt.retry_at = None;
+        attempt.started_at = None;
+        state.leases.remove(attempt_id);
+        Ok(status)
+    }
+
+    fn record_attempt_failure_under_lease(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        let mut state = self.state()?;
+        self.check_attempt_tenant(&state, attempt_id)?;
+        if let Some(lease) = lease {
+            Self::check_lease_fence(&state, attempt_id, lease, now)?;
+        }
+        let Some(attempt) = state.attempts.get_mut(attempt_id) else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for failure: {}",
+                attempt_id
+            )));
+        };
+        let attempt_no = attempt.attempt_no.max(1);
+        attempt.retry_at = None;
+        attempt.started_at = None;
+        attempt.last_error = Some(error.to_string());
+        let outcome = if attempt.cancel_requested {
+            attempt.status = AttemptExecutionStatus::Cancelled;
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::Cancelled,
+                next_retry_at: None,
+                next_attempt_no: attempt_no,
+            }
+        } else if attempt_no <= retry_policy.max_retries {
+            let next_attempt_no = attempt_no + 1;
+            let backoff_ms = retry_policy.next_backoff_ms(attempt_no).max(1);
+            let retry_at = now + Duration::milliseconds(backoff_ms);
+            attempt.attempt_no = next_attempt_no;
+            attempt.status = AttemptExecutionStatus::RetryBackoff;
+            attempt.retry_at = Some(retry_at);
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::RetryBackoff,
+                next_retry_at: Some(retry_at),
+                next_attempt_no,
+            }
+        } else {
+            attempt.status = AttemptExecutionStatus::DeadLetter;
+            attempt.dead_lettered_at = Some(now);
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::DeadLetter,
+                next_retry_at: None,
+                next_attempt_no: attempt_no,
+            }
+        };
+        state.leases.remove(attempt_id);
+        Ok(outcome)
+    }
+}
+
+fn attempt_status_to_str(status: &AttemptExecutionStatus) -> &'static str {
+    match status {
+        AttemptExecutionStatus::Queued => "queued",
+        AttemptExecutionStatus::Leased => "leased",
+        AttemptExecutionStatus::Running => "running",
+        AttemptExecutionStatus::RetryBackoff => "retry_backoff",
+        AttemptExecutionStatus::Completed => "completed",
+        AttemptExecutionStatus::Failed => "failed",
+        AttemptExecutionStatus::Cancelled => "cancelled",
+        AttemptExecutionStatus::DeadLetter => "dead_letter",
+    }
+}
+
+impl RuntimeRepository for InMemoryRuntimeRepository {
+    fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        let enqueued_at = self.clock.now();
+        let mut state = self.state()?;
+        // Check the whole batch before inserting, so a bad entry enqueues nothing
+        for (attempt_id, run_id, _) in batch {
+            self.check_run_tenant(&state, run_id)?;
+            if !state.attempts.contains_key(attempt_id) && !state.runs.contains_key(run_id) {
+                return Err(KernelError::NotFound(format!(
+                    "run not found for attempt {}: {}",
+                    attempt_id, run_id
+                )));
+            }
+        }
+        let mut outcome = EnqueueBatchOutcome::default();
+        for (attempt_id, run_id, options) in batch {
+            if state.attempts.contains_key(attempt_id) {
+                outcome.skipped += 1;
+                continue;
+            }
+            state.next_enqueue_seq += 1;
+            let attempt = MemoryAttempt {
+                attempt_id: attempt_id.clone(),
+                run_id: run_id.clone(),
+                attempt_no: 1,
+                status: AttemptExecutionStatus::Queued,
+                priority: options.priority,
+                retry_at: None,
+                run_at: options.run_at,
+                enqueued_at,
+                enqueue_seq: state.next_enqueue_seq,
+                tenant_id: state.runs.get(run_id).and_then(|run| run.tenant_id.clone()),
+                started_at: None,
+                cancel_requested: false,
+                last_error: None,
+                dead_lettered_at: None,
+                timeout: None,
+            };
+            state.attempts.insert(attempt_id.clone(), attempt);
+            outcome.inserted += 1;
+        }
+        Ok(outcome)
+    }
+
+    fn list_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+        let state = self.state()?;
+        Ok(self.dispatchable(&state, now, limit, None).attempts)
+    }
+
+    fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        let state = self.state()?;
+        Ok(self.dispatchable(&state, now, limit, options.max_concurrent_per_run))
+    }
+
+    fn next_dispatch_at(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, KernelError> {
+        let state = self.state()?;
+        Ok(state
+            .attempts
+            .values()
+            .filter(|attempt| attempt.waiting() && self.in_scope(attempt.tenant_id.as_deref()))
+            .filter_map(|attempt| {
+                let retry_at = if attempt.status == AttemptExecutionStatus::RetryBackoff {
+                    attempt.retry_at
+                } else {
+                    None
+                };
+                attempt.run_at.max(retry_at)
+            })
+            .filter(|ready_at| *ready_at > now)
+            .min())
+    }
+
+    fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let mut state = self.state()?;
+        let attempts = self.dispatchable(&state, now, limit, None).attempts;
+        let mut claimed = Vec::with_capacity(attempts.len());
+        for attempt in attempts {
+            let lease = state.new_lease(&attempt.attempt_id, worker_id, now + lease_ttl, now);
+            claimed.push(ClaimedAttempt { attempt, lease });
+        }
+        Ok(claimed)
+    }
+
+    fn upsert_lease(
+        &self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseRecord, KernelError> {
+        let now = self.clock.now();
+        let mut state = self.state()?;
+        self.check_attempt_tenant(&state, attempt_id)?;
+        if state
+            .leases
+            .get(attempt_id)
+            .is_some_and(|lease| lease.lease_expires_at < now)
+        {
+            state.leases.remove(attempt_id);
+        }
+        if state.leases.contains_key(attempt_id) {
+            return Err(KernelError::LeaseConflict(format!(
+                "active lease already exists for attempt: {}",
+                attempt_id
+            )));
+        }
+        if !state
+            .attempts
+            .get(attempt_id)
+            .is_some_and(MemoryAttempt::waiting)
+        {
+            return Err(KernelError::NotDispatchable(format!(
+                "attempt is not dispatchable for lease: {}",
+                attempt_id
+            )));
+        }
+        Ok(state.new_lease(attempt_id, worker_id, lease_expires_at, now))
+    }
+
+    fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let mut state = self.state()?;
+        let tenant_id = self.tenant_id.clone();
+        let Some(lease) = state
+            .lease_by_id_mut(lease_id)
+            .filter(|lease| tenant_id.is_none() || lease.tenant_id == tenant_id)
+        else {
+            return Err(KernelError::NotFound(format!(
+                "lease not found for heartbeat: {}",
+                lease_id
+            )));
+        };
+        lease.heartbeat_at = heartbeat_at;
+        lease.lease_expires_at = lease_expires_at;
+        lease.version += 1;
+        let attempt_id = lease.attempt_id.clone();
+        Ok(state.lease_directive(&attempt_id))
+    }
+
+    fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let mut state = self.state()?;
+        let tenant_id = self.tenant_id.clone();
+        let Some(lease) = state.lease_by_id_mut(lease_id).filter(|lease| {
+            lease.worker_id == worker_id
+                && lease.version == expected_version
+                && (tenant_id.is_none() || lease.tenant_id == tenant_id)
+        }) else {
+            return Err(KernelError::LeaseConflict(format!(
+                "lease heartbeat version conflict for lease: {}",
+                lease_id
+            )));
+        };
+        lease.heartbeat_at = heartbeat_at;
+        lease.lease_expires_at = lease_expires_at;
+        lease.version += 1;
+        let attempt_id = lease.attempt_id.clone();
+        Ok(state.lease_directive(&attempt_id))
+    }
+
+    fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError> {
+        let mut state = self.state()?;
+        let expired: Vec<String> = state
+            .leases
+            .values()
+            .filter(|lease| {
+                lease.lease_expires_at < stale_before && self.in_scope(lease.tenant_id.as_deref())
+            })
+            .map(|lease| lease.attempt_id.clone())
+            .collect();
+        for attempt_id in &expired {
+            state.leases.remove(attempt_id);
+            if let Some(attempt) = state.attempts.get_mut(attempt_id) {
+                if !matches!(
+                    attempt.status,
+                    AttemptExecutionStatus::Completed
+                        | AttemptExecutionStatus::Failed
+                        | AttemptExecutionStatus::Cancelled
+                ) {
+                    // An attempt asked to cancel is not handed to another worker
+                    attempt.status = if attempt.cancel_requested {
+                        AttemptExecutionStatus::Cancelled
+                    } else {
+                        AttemptExecutionStatus::Queued
+                    };
+                }
+            }
+        }
+        Ok(expired.len() as u64)
+    }
+
+    fn transition_timed_out_attempts(&self, now: DateTime<Utc>) -> Result<u64, KernelError> {
+        let mut state = self.state()?;
+        let timed_out: Vec<(String, AttemptExecutionStatus)> = state
+            .attempts
+            .values()
+            .filter(|attempt| self.in_scope(attempt.tenant_id.as_deref()))
+            .filter(|attempt| {
+                matches!(
+                    attempt.status,
+                    AttemptExecutionStatus::Leased | AttemptExecutionStatus::Running
+                )
+            })
+            .filter_map(|attempt| {
+                let policy = attempt.timeout.as_ref()?;
+                let started_at = attempt.started_at?;
+                (started_at + Duration::milliseconds(policy.timeout_ms) <= now)
+                    .then(|| (attempt.attempt_id.clone(), policy.on_timeout_status.clone()))
+            })
+            .collect();
+        for (attempt_id, terminal_status) in &timed_out {
+            state.leases.remove(attempt_id);
+            if let Some(attempt) = state.attempts.get_mut(attempt_id) {
+                attempt.status = terminal_status.clone();
+                attempt.retry_at = None;
+                attempt.started_at = None;
+            }
+        }
+        Ok(timed_out.len() as u64)
+    }
+
+    fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.ack_attempt_under_lease(attempt_id, None, status, now)
+    }
+
+    fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, None, error, retry_policy, now)
+    }
+
+    fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.ack_attempt_under_lease(attempt_id, Some(lease), status, now)
+    }
+
+    fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, Some(lease), error, retry_policy, now)
+    }
+
+    fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        let mut state = self.state()?;
+        let Some(attempt) = self.attempt_mut(&mut state, attempt_id) else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for cancel: {}",
+                attempt_id
+            )));
+        };
+        Ok(match attempt.status.clone() {
+            AttemptExecutionStatus::Queued | AttemptExecutionStatus::RetryBackoff => {
+                attempt.status = AttemptExecutionStatus::Cancelled;
+                attempt.retry_at = None;
+                attempt.started_at = None;
+                attempt.cancel_requested = true;
+                AttemptCancellation::Cancelled
+            }
+            AttemptExecutionStatus::Leased | AttemptExecutionStatus::Running => {
+                attempt.cancel_requested = true;
+                AttemptCancellation::Requested
+            }
+            finished => AttemptCancellation::AlreadyFinished(finished),
+        })
+    }
+
+    fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        let state = self.state()?;
+        let mut dead: Vec<&MemoryAttempt> = state
+            .attempts
+            .values()
+            .filter(|attempt| {
+                attempt.status == AttemptExecutionStatus::DeadLetter
+                    && self.in_scope(attempt.tenant_id.as_deref())
+            })
+            .collect();
+        dead.sort_by(|a, b| {
+            b.dead_lettered_at
+                .cmp(&a.dead_lettered_at)
+                .then(a.attempt_id.cmp(&b.attempt_id))
+        });
+        Ok(dead
+            .into_iter()
+            .take(limit)
+            .map(|attempt| DeadLetterAttemptRecord {
+                attempt_id: attempt.attempt_id.clone(),
+                run_id: attempt.run_id.clone(),
+                attempt_no: attempt.attempt_no,
+                error: attempt.last_error.clone(),
+                dead_lettered_at: attempt.dead_lettered_at.unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        let Some(attempt) = self.attempt_mut(&mut state, attempt_id) else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for requeue: {}",
+                attempt_id
+            )));
+        };
+        if attempt.status != AttemptExecutionStatus::DeadLetter {
+            return Err(KernelError::Conflict(format!(
+                "attempt {} is {}, not dead-lettered",
+                attempt_id,
+                attempt_status_to_str(&attempt.status)
+            )));
+        }
+        attempt.status = AttemptExecutionStatus::Queued;
+        attempt.retry_at = None;
+        attempt.dead_lettered_at = None;
+        Ok(())
+    }
+
+    fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        let state = self.state()?;
+        let mut stats = RuntimeStats::default();
+        let mut oldest_enqueued: Option<DateTime<Utc>> = None;
+        for attempt in state
+            .attempts
+            .values()
+            .filter(|attempt| self.in_scope(attempt.tenant_id.as_deref()))
+        {
+            stats.attempts.add(&attempt.status, 1);
+            match attempt.status {
+                AttemptExecutionStatus::Queued => {
+                    oldest_enqueued = Some(
+                        oldest_enqueued.map_or(attempt.enqueued_at, |t| t.min(attempt.enqueued_at)),
+                    );
+                }
+                AttemptExecutionStatus::RetryBackoff
+                    if attempt.retry_at.map_or(true, |retry_at| retry_at <= now) =>
+                {
+                    stats.retry_backlog += 1;
+                }
+                _ => {}
+            }
+            if state.has_active_lease(&attempt.attempt_id, now) {
+                stats.active_leases += 1;
+            }
+        }
+        stats.oldest_queued_age_ms =
+            oldest_enqueued.map(|enqueued_at| (now - enqueued_at).num_milliseconds().max(0));
+        Ok(stats)
+    }
+
+    fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
+        Ok(0)
+    }
+
+    // ============== Run Methods ==============
+
+    fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        let tenant_id = run_tenant_in_scope(run, self.tenant_id())?;
+        let mut state = self.state()?;
+        if state.runs.contains_key(&run.run_id) {
+            return Err(KernelError::Conflict(format!(
+                "run already exists: {}",
+                run.run_id
+            )));
+        }
+        state.runs.insert(
+            run.run_id.clone(),
+            RunRecord {
+                tenant_id,
+                ..run.clone()
+            },
+        );
+        Ok(())
+    }
+
+    fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        let state = self.state()?;
+        Ok(state
+            .runs
+            .get(run_id)
+            .filter(|run| self.in_scope(run.tenant_id.as_deref()))
+            .cloned())
+    }
+
+    fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        let now = self.clock.now();
+        let mut state = self.state()?;
+        let Some(run) = state
+            .runs
+            .get_mut(run_id)
+            .filter(|run| self.in_scope(run.tenant_id.as_deref()))
+        else {
+            return Err(KernelError::NotFound(format!(
+                "run not found for status update: {}",
+                run_id
+            )));
+        };
+        if run.status.is_final() && run.status != status {
+            return Err(KernelError::Conflict(format!(
+                "run {} is already {}",
+                run_id,
+                run.status.as_str()
+            )));
+        }
+        run.status = status;
+        run.status_reason = reason.map(str::to_string);
+        run.updated_at = now;
+        Ok(())
+    }
+
+    fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        let state = self.state()?;
+        let mut runs: Vec<&RunRecord> = state
+            .runs
+            .values()
+            .filter(|run| self.in_scope(run.tenant_id.as_deref()))
+            .filter(|run| filter.status.as_ref().map_or(true, |s| run.status == *s))
+            .filter(|run| {
+                filter
+                    .workflow_name
+                    .as_ref()
+                    .map_or(true, |name| run.workflow_name == *name)
+            })
+            .filter(|run| filter.created_after.map_or(true, |at| run.created_at >= at))
+            .filter(|run| filter.created_before.map_or(true, |at| run.created_at < at))
+            .collect();
+        runs.sort_by(|a, b| {
+            b.updated_at
+                .cmp(&a.updated_at)
+                .then(a.run_id.cmp(&b.run_id))
+        });
+        Ok(runs
+            .into_iter()
+            .skip(page.offset)
+            .take(page.limit)
+            .cloned()
+            .collect())
+    }
+
+    // ============== Bounty Methods ==============
+
+    fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        let record = match state.bounties.get(&bounty.bounty_id) {
+            Some(existing) => BountyRecord {
+                created_by: existing.created_by.clone(),
+                created_at_ms: existing.created_at_ms,
+                ..bounty.clone()
+            },
+            None => bounty.clone(),
+        };
+        state.bounties.insert(bounty.bounty_id.clone(), record);
+        Ok(())
+    }
+
+    fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
+        Ok(self.state()?.bounties.get(bounty_id).cloned())
+    }
+
+    fn list_bounties(
+        &self,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BountyRecord>, KernelError> {
+        let state = self.state()?;
+        let mut bounties: Vec<&BountyRecord> = state
+            .bounties
+            .values()
+            .filter(|bounty| status.map_or(true, |status| bounty.status.as_str() == status))
+            .collect();
+        bounties.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        Ok(bounties.into_iter().take(limit).cloned().collect())
+    }
+
+    fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
+        let now = self.clock.now().timestamp_millis();
+        let mut state = self.state()?;
+        let Some(bounty) = state
+            .bounties
+            .get_mut(bounty_id)
+            .filter(|bounty| bounty.status == BountyStatus::Open)
+        else {
+            return Err(KernelError::NotFound(format!(
+                "bounty not found or not in open status: {}",
+                bounty_id
+            )));
+        };
+        bounty.status = BountyStatus::Accepted;
+        bounty.accepted_by = Some(accepted_by.to_string());
+        bounty.accepted_at_ms = Some(now);
+        Ok(())
+    }
+
+    fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
+        let now = self.clock.now().timestamp_millis();
+        let mut state = self.state()?;
+        let Some(bounty) = state
+            .bounties
+            .get_mut(bounty_id)
+            .filter(|bounty| bounty.status != BountyStatus::Closed)
+        else {
+            return Err(KernelError::NotFound(format!(
+                "bounty not found or already closed: {}",
+                bounty_id
+            )));
+        };
+        bounty.status = BountyStatus::Closed;
+        bounty.closed_at_ms = Some(now);
+        Ok(())
+    }
+
+    // ============== Swarm Methods ==============
+
+    fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        let record = match state.swarm_tasks.get(&task.parent_task_id) {
+            Some(existing) => SwarmTaskRecord {
+                decomposition_json: task.decomposition_json.clone(),
+                status: task.status.clone(),
+                completed_at_ms: task.completed_at_ms,
+                ..existing.clone()
+            },
+            None => task.clone(),
+        };
+        state
+            .swarm_tasks
+            .insert(task.parent_task_id.clone(), record);
+        Ok(())
+    }
+
+    fn get_swarm_decomposition(
+        &self,
+        parent_task_id: &str,
+    ) -> Result<Option<SwarmTaskRecord>, KernelError> {
+        Ok(self.state()?.swarm_tasks.get(parent_task_id).cloned())
+    }
+
+    // ============== Worker Methods ==============
+
+    fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        let record = match state.workers.get(&worker.worker_id) {
+            Some(existing) => WorkerRecord {
+                registered_at_ms: existing.registered_at_ms,
+                ..worker.clone()
+            },
+            None => worker.clone(),
+        };
+        state.workers.insert(worker.worker_id.clone(), record);
+        Ok(())
+    }
+
+    fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
+        Ok(self.state()?.workers.get(worker_id).cloned())
+    }
+
+    fn list_workers(
+        &self,
+        domain: Option<&str>,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkerRecord>, KernelError> {
+        let state = self.state()?;
+        let mut workers: Vec<&WorkerRecord> = state
+            .workers
+            .values()
+            .filter(|worker| domain.map_or(true, |domain| worker.domains.contains(domain)))
+            .filter(|worker| status.map_or(true, |status| worker.status == status))
+            .collect();
+        workers.sort_by_key(|record| std::cmp::Reverse(record.registered_at_ms));
+        Ok(workers.into_iter().take(limit).cloned().collect())
+    }
+
+    fn heartbeat_worker(&self, worker_id: &str, heartbeat_at_ms: i64) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        let Some(worker) = state.workers.get_mut(worker_id) else {
+            return Err(KernelError::NotFound(format!(
+                "worker not found: {}",
+                worker_id
+            )));
+        };
+        worker.last_heartbeat_ms = Some(heartbeat_at_ms);
+        worker.status = "active".to_string();
+        Ok(())
+    }
+
+    // ============== Recipe Methods ==============
+
+    fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        if state.recipes.contains_key(&recipe.recipe_id) {
+            return Err(KernelError::Conflict(format!(
+                "recipe already exists: {}",
+                recipe.recipe_id
+            )));
+        }
+        state
+            .recipes
+            .insert(recipe.recipe_id.clone(), recipe.clone());
+        Ok(())
+    }
+
+    fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
+        Ok(self.state()?.recipes.get(recipe_id).cloned())
+    }
+
+    fn fork_recipe(
+        &self,
+        original_id: &str,
+        new_id: &str,
+        new_author: &str,
+    ) -> Result<Option<RecipeRecord>, KernelError> {
+        let now = self.clock.now().timestamp_millis();
+        let mut state = self.state()?;
+        let Some(original) = state.recipes.get(original_id).cloned() else {
+            return Ok(None);
+        };
+        if state.recipes.contains_key(new_id) {
+            return Err(KernelError::Conflict(format!(
+                "recipe already exists: {}",
+                new_id
+            )));
+        }
+        let fork = RecipeRecord {
+            recipe_id: new_id.to_string(),
+            name: format!("Fork of {}", original.name),
+            author_id: new_author.to_string(),
+            forked_from: Some(original_id.to_string()),
+            created_at_ms: now,
+            updated_at_ms: now,
+            ..original
+        };
+        state.recipes.insert(new_id.to_string(), fork.clone());
+        Ok(Some(fork))
+    }
+
+    fn list_recipes(
+        &self,
+        author_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecipeRecord>, KernelError> {
+        let state = self.state()?;
+        let mut recipes: Vec<&RecipeRecord> = state
+            .recipes
+            .values()
+            .filter(|recipe| author_id.map_or(true, |author| recipe.author_id == author))
+            .collect();
+        recipes.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        Ok(recipes.into_iter().take(limit).cloned().collect())
+    }
+
+    // ============== Organism Methods ==============
+
+    fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        if state.organisms.contains_key(&organism.organism_id) {
+            return Err(KernelError::Conflict(format!(
+                "organism already exists: {}",
+                organism.organism_id
+            )));
+        }
+        state
+            .organisms
+            .insert(organism.organism_id.clone(), organism.clone());
+        Ok(())
+    }
+
+    fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
+        Ok(self.state()?.organisms.get(organism_id).cloned())
+    }
+
+    fn update_organism(
+        &self,
+        organism_id: &str,
+        current_step: i32,
+        status: &str,
+    ) -> Result<(), KernelError> {
+        let now = self.clock.now().timestamp_millis();
+        let mut state = self.state()?;
+        let Some(organism) = state.organisms.get_mut(organism_id) else {
+            return Err(KernelError::NotFound(format!(
+                "organism not found: {}",
+                organism_id
+            )));
+        };
+        organism.current_step = current_step;
+        organism.status = status.to_string();
+        organism.completed_at_ms = (status == "completed").then_some(now);
+        Ok(())
+    }
+
+    // ============== Session Methods ==============
+
+    fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        if state.sessions.contains_key(&session.session_id) {
+            return Err(KernelError::Conflict(format!(
+                "session already exists: {}",
+                session.session_id
+            )));
+        }
+        state
+            .sessions
+            .insert(session.session_id.clone(), session.clone());
+        Ok(())
+    }
+
+    fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
+        Ok(self.state()?.sessions.get(session_id).cloned())
+    }
+
+    fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        if state.session_messages.contains_key(&message.message_id) {
+            return Err(KernelError::Conflict(format!(
+                "session message already exists: {}",
+                message.message_id
+            )));
+        }
+        state
+            .session_messages
+            .insert(message.message_id.clone(), message.clone());
+        Ok(())
+    }
+
+    fn get_session_history(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionMessageRecord>, KernelError> {
+        let state = self.state()?;
+        let mut messages: Vec<&SessionMessageRecord> = state
+            .session_messages
+            .values()
+            .filter(|message| message.session_id == session_id)
+            .collect();
+        messages.sort_by_key(|record| std::cmp::Reverse(record.sent_at_ms));
+        Ok(messages.into_iter().take(limit).cloned().collect())
+    }
+
+    // ============== Dispute Methods ==============
+
+    fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        if state.disputes.contains_key(&dispute.dispute_id) {
+            return Err(KernelError::Conflict(format!(
+                "dispute already exists: {}",
+                dispute.dispute_id
+            )));
+        }
+        state
+            .disputes
+            .insert(dispute.dispute_id.clone(), dispute.clone());
+        Ok(())
+    }
+
+    fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
+        Ok(self.state()?.disputes.get(dispute_id).cloned())
+    }
+
+    fn get_disputes_for_bounty(&self, bounty_id: &str) -> Result<Vec<DisputeRecord>, KernelError> {
+        let state = self.state()?;
+        let mut disputes: Vec<&DisputeRecord> = state
+            .disputes
+            .values()
+            .filter(|dispute| dispute.bounty_id == bounty_id)
+            .collect();
+        disputes.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        Ok(disputes.into_iter().cloned().collect())
+    }
+
+    fn resolve_dispute(
+        &self,
+        dispute_id: &str,
+        resolution: &str,
+        resolved_by: &str,
+    ) -> Result<(), KernelError> {
+        let now = self.clock.now().timestamp_millis();
+        let mut state = self.state()?;
+        let Some(dispute) = state
+            .disputes
+            .get_mut(dispute_id)
+            .filter(|dispute| dispute.status == DisputeStatus::Open)
+        else {
+            return Err(KernelError::NotFound(format!(
+                "dispute not found or already resolved: {}",
+                dispute_id
+            )));
+        };
+        dispute.status = DisputeStatus::Resolved;
+        dispute.resolution = Some(resolution.to_string());
+        dispute.resolved_by = Some(resolved_by.to_string());
+        dispute.resolved_at_ms = Some(now);
+        Ok(())
+    }
+
+    // ============== Interrupt Methods ==============
+
+    fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        let mut state = self.state()?;
+        self.check_run_tenant(&state, &interrupt.run_id)?;
+        if state.interrupts.contains_key(&interrupt.interrupt_id) {
+            return Err(KernelError::Conflict(format!(
+                "interrupt already exists: {}",
+                interrupt.interrupt_id
+            )));
+        }
+        state
+            .interrupts
+            .insert(interrupt.interrupt_id.clone(), interrupt.clone());
+        Ok(())
+    }
+
+    fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let state = self.state()?;
+        let mut pending: Vec<&InterruptRecord> = state
+            .interrupts
+            .values()
+            .filter(|interrupt| interrupt.status == InterruptStatus::Pending)
+            .filter(|interrupt| {
+                filter
+                    .run_id
+                    .as_ref()
+                    .map_or(true, |run_id| interrupt.run_id == *run_id)
+            })
+            .filter(|interrupt| self.run_in_scope(&state, &interrupt.run_id))
+            .collect();
+        pending.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then(a.interrupt_id.cmp(&b.interrupt_id))
+        });
+        Ok(pending
+            .into_iter()
+            .take(filter.effective_limit())
+            .cloned()
+            .collect())
+    }
+
+    fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let now = self.clock.now();
+        let mut state = self.state()?;
+        let visible = state
+            .interrupts
+            .get(interrupt_id)
+            .is_some_and(|interrupt| self.run_in_scope(&state, &interrupt.run_id));
+        let Some(interrupt) = state.interrupts.get_mut(interrupt_id).filter(|_| visible) else {
+            return Err(KernelError::NotFound(format!(
+                "interrupt not found: {}",
+                interrupt_id
+            )));
+        };
+        if interrupt.status != InterruptStatus::Pending {
+            return Err(KernelError::Conflict(format!(
+                "interrupt {} already {}",
+                interrupt_id,
+                interrupt.status.as_str()
+            )));
+        }
+        interrupt.status = decision.resolved_status();
+        interrupt.decision = Some(decision);
+        interrupt.resolution = Some(value.clone());
+        interrupt.resolved_at = Some(now);
+        Ok(interrupt.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{Duration, Utc};
+    use oris_kernel::KernelError;
+
+    use super::InMemoryRuntimeRepository;
+    use crate::clock::{Clock, ManualClock};
+    use crate::models::{AttemptExecutionStatus, TimeoutPolicyConfig};
+    use crate::repository::RuntimeRepository;
+    use crate::repository_contract::{
+        assert_async_dispatch_lease_requeue_contract, assert_attempt_failure_contract,
+        assert_cancel_attempt_contract, assert_concurrent_claim_contract,
+        assert_dispatch_lease_requeue_contract, assert_enqueue_batch_contract,
+        assert_interrupt_inbox_contract, assert_lease_fencing_contract,
+        assert_max_concurrent_per_run_contract, assert_priority_dispatch_order_contract,
+        assert_run_at_contract, assert_run_lifecycle_contract, assert_run_record_contract,
+        assert_runtime_stats_contract, assert_semantic_roundtrip, assert_tenant_isolation_contract,
+        ContractHarness,
+    };
+
+    impl ContractHarness for InMemoryRuntimeRepository {
+        fn has_lease(&self, attempt_id: &str) -> bool {
+            self.get_lease_for_attempt(attempt_id)
+                .expect("in-memory get lease")
+                .is_some()
+        }
+    }
+
+    #[test]
+    fn runtime_repository_contract_in_memory() {
+        assert_dispatch_lease_requeue_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[tokio::test]
+    async fn runtime_repository_async_contract_in_memory() {
+        let repo = InMemoryRuntimeRepository::new();
+        assert_async_dispatch_lease_requeue_contract(&repo, "memory-async").await;
+    }
+
+    #[test]
+    fn runtime_repository_priority_order_contract_in_memory() {
+        assert_priority_dispatch_order_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_run_at_contract_in_memory() {
+        assert_run_at_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_concurrent_claim_contract_in_memory() {
+        let repo = InMemoryRuntimeRepository::new();
+        let repos = (0..8).map(|_| repo.clone()).collect();
+        assert_concurrent_claim_contract(repos, "memory");
+    }
+
+    #[test]
+    fn runtime_repository_max_concurrent_per_run_contract_in_memory() {
+        assert_max_concurrent_per_run_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_lease_fencing_contract_in_memory() {
+        assert_lease_fencing_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_cancel_attempt_contract_in_memory() {
+        assert_cancel_attempt_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_attempt_failure_contract_in_memory() {
+        assert_attempt_failure_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_runtime_stats_contract_in_memory() {
+        assert_runtime_stats_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_run_record_contract_in_memory() {
+        assert_run_record_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_enqueue_batch_contract_in_memory() {
+        assert_enqueue_batch_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_run_lifecycle_contract_in_memory() {
+        assert_run_lifecycle_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_interrupt_inbox_contract_in_memory() {
+        assert_interrupt_inbox_contract(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_semantic_contract_in_memory() {
+        assert_semantic_roundtrip(&InMemoryRuntimeRepository::new(), "memory");
+    }
+
+    #[test]
+    fn runtime_repository_tenant_isolation_contract_in_memory() {
+        let repo = InMemoryRuntimeRepository::new();
+        assert_tenant_isolation_contract(
+            &repo.clone().with_tenant("a"),
+            &repo.clone().with_tenant("b"),
+            &repo,
+            "memory-tenant",
+        );
+    }
+
+    #[test]
+    fn manual_clock_expires_leases_without_sleeping() {
+        let start = Utc::now();
+        let clock = ManualClock::new(start);
+        let repo = InMemoryRuntimeRepository::new().with_clock(Arc::new(clock.clone()));
+        repo.seed_attempt("attempt-clock", "run-clock");
+
+        let first = repo
+            .upsert_lease("attempt-clock", "worker-a", start + Duration::seconds(30))
+            .expect("first lease");
+        assert_eq!(first.heartbeat_at, start);
+        assert!(matches!(
+            repo.upsert_lease("attempt-clock", "worker-b", start + Duration::seconds(30)),
+            Err(KernelError::LeaseConflict(_))
+        ));
+
+        clock.advance(Duration::seconds(31));
+        assert_eq!(
+            repo.expire_leases_and_requeue(clock.now())
+                .expect("expire leases"),
+            1
+        );
+        let second = repo
+            .upsert_lease(
+                "attempt-clock",
+                "worker-b",
+                clock.now() + Duration::seconds(30),
+            )
+            .expect("lease after expiry");
+        assert_eq!(second.heartbeat_at, start + Duration::seconds(31));
+        assert_ne!(second.lease_id, first.lease_id);
+    }
+
+    #[test]
+    fn timed_out_attempts_end_with_their_policy_status() {
+        let start = Utc::now();
+        let repo = InMemoryRuntimeRepository::new().with_clock(Arc::new(ManualClock::new(start)));
+        repo.seed_attempt("attempt-timeout", "run-timeout");
+        repo.set_attempt_timeout_policy(
+            "attempt-timeout",
+            &TimeoutPolicyConfig {
+                timeout_ms: 1_000,
+                on_timeout_status: AttemptExecutionStatus::Failed,
+            },
+        )
+        .expect("set timeout policy");
+        repo.upsert_lease("attempt-timeout", "worker-a", start + Duration::seconds(30))
+            .expect("lease attempt");
+
+        assert_eq!(
+            repo.transition_timed_out_attempts(start + Duration::milliseconds(999))
+                .expect("transition before timeout"),
+            0
+        );
+        assert_eq!(
+            repo.transition_timed_out_attempts(start + Duration::seconds(1))
+                .expect("transition at timeout"),
+            1
+        );
+        assert_eq!(
+            repo.attempt_status("attempt-timeout").expect("status"),
+            Some(AttemptExecutionStatus::Failed)
+        );
+        assert!(!repo.has_lease("attempt-timeout"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Scheduler and lease metrics (feature `metrics`).
+//!
+//! Recorded through the [`metrics`](https://docs.rs/metrics) facade by the scheduler,
+//! the lease manager and the execution server's worker endpoints. Nothing is collected
+//! until the application installs a recorder. The names are stable so dashboards and
+//! alerts can rely on them.
+
+/// Gauge: dispatchable attempts found by the most recent scheduler scan
+pub const DISPATCHABLE_ATTEMPTS: &str = "oris_scheduler_dispatchable_attempts";
+/// Counter: leases granted to workers
+pub const LEASES_GRANTED_TOTAL: &str = "oris_scheduler_leases_granted_total";
+/// Counter: stale leases expired and their attempts requeued
+pub const LEASES_EXPIRED_TOTAL: &str = "oris_scheduler_leases_expired_total";
+/// Counter: rejected worker heartbeats (unknown lease, wrong owner or version conflict)
+pub const HEARTBEAT_FAILURES_TOTAL: &str = "oris_scheduler_heartbeat_failures_total";
+/// Counter: attempts moved to their timeout status by the lease service
+pub const ATTEMPTS_TIMED_OUT_TOTAL: &str = "oris_scheduler_attempts_timed_out_total";
+/// Counter: lease service ticks that failed with a repository error
+pub const LEASE_TICK_FAILURES_TOTAL: &str = "oris_scheduler_lease_tick_failures_total";
+/// Gauge (label `status`): attempts per status at the last lease service tick
+pub const ATTEMPTS: &str = "oris_scheduler_attempts";
+/// Gauge: unexpired leases at the last lease service tick
+pub const ACTIVE_LEASES: &str = "oris_scheduler_active_leases";
+/// Gauge (seconds): wait of the oldest queued attempt, 0 when none is queued
+pub const OLDEST_QUEUED_AGE_SECONDS: &str = "oris_scheduler_oldest_queued_age_seconds";
+/// Gauge: attempts whose retry backoff has elapsed but that are not yet leased
+pub const RETRY_BACKLOG: &str = "oris_scheduler_retry_backlog";
+
+/// Set the queue gauges from a repository stats snapshot
+pub fn record_runtime_stats(stats: &crate::models::RuntimeStats) {
+    let attempts = &stats.attempts;
+    for (status, count) in [
+        ("queued", attempts.queued),
+        ("leased", attempts.leased),
+        ("running", attempts.running),
+        ("retry_backoff", attempts.retry_backoff),
+        ("completed", attempts.completed),
+        ("failed", attempts.failed),
+        ("cancelled", attempts.cancelled),
+        ("dead_letter", attempts.dead_letter),
+    ] {
+        ::metrics::gauge!(ATTEMPTS, "status" => status).set(count as f64);
+    }
+    ::metrics::gauge!(ACTIVE_LEASES).set(stats.active_leases as f64);
+    ::metrics::gauge!(OLDEST_QUEUED_AGE_SECONDS)
+        .set(stats.oldest_queued_age_ms.unwrap_or(0) as f64 / 1000.0);
+    ::metrics::gauge!(RETRY_BACKLOG).set(stats.retry_backlog as f64);
+}
+
+/// Register the descriptions of the scheduler and lease metrics with the installed recorder
+pub fn describe() {
+    ::metrics::describe_gauge!(
+        DISPATCHABLE_ATTEMPTS,
+        "Dispatchable attempts found by the most recent scheduler scan."
+    );
+    ::metrics::describe_counter!(LEASES_GRANTED_TOTAL, "Leases granted to workers.");
+    ::metrics::describe_counter!(
+        LEASES_EXPIRED_TOTAL,
+        "Stale leases expired with their attempts requeued."
+    );
+    ::metrics::describe_counter!(HEARTBEAT_FAILURES_TOTAL, "Rejected worker heartbeats.");
+    ::metrics::describe_counter!(
+        ATTEMPTS_TIMED_OUT_TOTAL,
+        "Attempts moved to their timeout status by the lease service."
+    );
+    ::metrics::describe_counter!(
+        LEASE_TICK_FAILURES_TOTAL,
+        "Lease service ticks that failed with a repository error."
+    );
+    ::metrics::describe_gauge!(ATTEMPTS, "Attempts per status.");
+    ::metrics::describe_gauge!(ACTIVE_LEASES, "Unexpired attempt leases.");
+    ::metrics::describe_gauge!(
+        OLDEST_QUEUED_AGE_SECONDS,
+        ::metrics::Unit::Seconds,
+        "Wait of the oldest queued attempt."
+    );
+    ::metrics::describe_gauge!(
+        RETRY_BACKLOG,
+        "Attempts past their retry backoff waiting for a worker."
+    );
+}
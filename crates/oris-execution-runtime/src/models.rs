@@ -1,9 +1,11 @@
 //! Runtime domain models for Phase 1 skeleton.
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::identity::RunId;
 
 /// Runtime-level status of a run for control-plane orchestration.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -12,12 +14,53 @@ pub enum RunRuntimeStatus {
     Leased,
     Running,
     BlockedInterrupt,
+    /// Stopped at a step boundary until it is resumed.
+    Paused,
     RetryBackoff,
     Completed,
     Failed,
     Cancelled,
 }
 
+impl RunRuntimeStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RunRuntimeStatus::Queued => "queued",
+            RunRuntimeStatus::Leased => "leased",
+            RunRuntimeStatus::Running => "running",
+            RunRuntimeStatus::BlockedInterrupt => "blocked_interrupt",
+            RunRuntimeStatus::Paused => "paused",
+            RunRuntimeStatus::RetryBackoff => "retry_backoff",
+            RunRuntimeStatus::Completed => "completed",
+            RunRuntimeStatus::Failed => "failed",
+            RunRuntimeStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "leased" => RunRuntimeStatus::Leased,
+            "running" => RunRuntimeStatus::Running,
+            "blocked_interrupt" => RunRuntimeStatus::BlockedInterrupt,
+            "paused" => RunRuntimeStatus::Paused,
+            "retry_backoff" => RunRuntimeStatus::RetryBackoff,
+            "completed" => RunRuntimeStatus::Completed,
+            "failed" => RunRuntimeStatus::Failed,
+            "cancelled" => RunRuntimeStatus::Cancelled,
+            _ => RunRuntimeStatus::Queued,
+        }
+    }
+
+    /// Whether the run is over for good; a failed run is not, as requeueing its
+    /// dead-lettered attempt runs it again.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            RunRuntimeStatus::Completed | RunRuntimeStatus::Cancelled
+        )
+    }
+}
+
 /// Runtime-level status of an execution attempt.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AttemptExecutionStatus {
@@ -28,18 +71,62 @@ pub enum AttemptExecutionStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Failed past its retry policy; only an operator requeue dispatches it again.
+    DeadLetter,
 }
 
 /// Run metadata record for scheduler/control-plane usage.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RunRecord {
     pub run_id: RunId,
+    /// Name of the graph the run executes, as registered with the worker.
     pub workflow_name: String,
     pub status: RunRuntimeStatus,
+    /// Why the run last changed status, e.g. the error that failed it.
+    #[serde(default)]
+    pub status_reason: Option<String>,
+    /// Tenant that owns the run; its attempts and leases inherit it.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Which runs [RuntimeRepository::list_runs](crate::RuntimeRepository::list_runs)
+/// returns; the default matches every run.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunRecordFilter {
+    pub status: Option<RunRuntimeStatus>,
+    /// Only runs of this graph.
+    pub workflow_name: Option<String>,
+    /// Only runs created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only runs created strictly before this time.
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl RunRecordFilter {
+    pub fn with_status(mut self, status: RunRuntimeStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn for_workflow(mut self, workflow_name: impl Into<String>) -> Self {
+        self.workflow_name = Some(workflow_name.into());
+        self
+    }
+
+    pub fn created_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.created_after = after;
+        self.created_before = before;
+        self
+    }
+}
+
 /// Candidate attempt returned by repository for scheduler dispatch.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AttemptDispatchRecord {
@@ -48,6 +135,174 @@ pub struct AttemptDispatchRecord {
     pub attempt_no: u32,
     pub status: AttemptExecutionStatus,
     pub retry_at: Option<DateTime<Utc>>,
+    /// Dispatch priority; higher values are dispatched first.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Per-call limits applied when listing dispatchable attempts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DispatchOptions {
+    /// Skip attempts of runs that already hold this many active leases, so one run that
+    /// fans out cannot take every worker.
+    pub max_concurrent_per_run: Option<usize>,
+}
+
+/// Scheduling of one attempt of an [crate::RuntimeRepository::enqueue_attempts] batch; the
+/// default makes it dispatchable at once, at priority 0.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnqueueOptions {
+    /// Dispatched ahead of every queued attempt with a lower priority.
+    pub priority: i32,
+    /// Not dispatched before this time.
+    pub run_at: Option<DateTime<Utc>>,
+}
+
+/// How many attempts of an enqueued batch were inserted, and how many were skipped because
+/// their attempt id was already taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnqueueBatchOutcome {
+    pub inserted: usize,
+    pub skipped: usize,
+}
+
+/// Dispatchable attempts, plus how many otherwise dispatchable attempts the
+/// [DispatchOptions] limits held back.
+#[derive(Clone, Debug, Default)]
+pub struct DispatchableAttempts {
+    pub attempts: Vec<AttemptDispatchRecord>,
+    pub skipped_for_fairness: usize,
+}
+
+/// Attempt leased by [crate::RuntimeRepository::claim_dispatchable_attempts], as it was
+/// when it was claimed, with its new lease.
+#[derive(Clone, Debug)]
+pub struct ClaimedAttempt {
+    pub attempt: AttemptDispatchRecord,
+    pub lease: LeaseRecord,
+}
+
+/// Attempt that ran out of retries and waits for an operator to requeue it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetterAttemptRecord {
+    pub attempt_id: String,
+    pub run_id: RunId,
+    /// Number of the attempt that failed last.
+    pub attempt_no: u32,
+    /// Error of the last failure.
+    pub error: Option<String>,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// Attempts per [AttemptExecutionStatus].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AttemptStatusCounts {
+    pub queued: u64,
+    pub leased: u64,
+    pub running: u64,
+    pub retry_backoff: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    pub dead_letter: u64,
+}
+
+impl AttemptStatusCounts {
+    pub(crate) fn add(&mut self, status: &AttemptExecutionStatus, count: u64) {
+        let slot = match status {
+            AttemptExecutionStatus::Queued => &mut self.queued,
+            AttemptExecutionStatus::Leased => &mut self.leased,
+            AttemptExecutionStatus::Running => &mut self.running,
+            AttemptExecutionStatus::RetryBackoff => &mut self.retry_backoff,
+            AttemptExecutionStatus::Completed => &mut self.completed,
+            AttemptExecutionStatus::Failed => &mut self.failed,
+            AttemptExecutionStatus::Cancelled => &mut self.cancelled,
+            AttemptExecutionStatus::DeadLetter => &mut self.dead_letter,
+        };
+        *slot += count;
+    }
+}
+
+/// Snapshot of the attempt queue, as returned by
+/// [RuntimeRepository::runtime_stats](crate::RuntimeRepository::runtime_stats).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RuntimeStats {
+    pub attempts: AttemptStatusCounts,
+    /// Leases not yet expired.
+    pub active_leases: u64,
+    /// How long the longest-waiting queued attempt has been enqueued; `None` when no
+    /// attempt is queued.
+    pub oldest_queued_age_ms: Option<i64>,
+    /// Attempts in retry backoff whose retry time has passed, waiting only for a worker.
+    pub retry_backlog: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryStrategy {
+    Fixed,
+    Exponential,
+}
+
+impl RetryStrategy {
+    #[cfg(feature = "sqlite-persistence")]
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Exponential => "exponential",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "fixed" => Some(Self::Fixed),
+            "exponential" => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+}
+
+/// How failed attempts are retried; `max_retries` retries follow the first attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicyConfig {
+    pub strategy: RetryStrategy,
+    pub backoff_ms: i64,
+    pub max_backoff_ms: Option<i64>,
+    pub multiplier: Option<f64>,
+    pub max_retries: u32,
+}
+
+impl RetryPolicyConfig {
+    pub(crate) fn next_backoff_ms(&self, current_attempt_no: u32) -> i64 {
+        let current_attempt_no = current_attempt_no.max(1);
+        let raw_backoff = match self.strategy {
+            RetryStrategy::Fixed => self.backoff_ms,
+            RetryStrategy::Exponential => {
+                let multiplier = self.multiplier.unwrap_or(2.0);
+                let exponent = (current_attempt_no - 1) as i32;
+                ((self.backoff_ms as f64) * multiplier.powi(exponent)).round() as i64
+            }
+        };
+        if let Some(max_backoff_ms) = self.max_backoff_ms {
+            raw_backoff.min(max_backoff_ms)
+        } else {
+            raw_backoff
+        }
+    }
+}
+
+/// How long a leased attempt may run, and the status it ends with once it runs longer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeoutPolicyConfig {
+    pub timeout_ms: i64,
+    pub on_timeout_status: AttemptExecutionStatus,
+}
+
+/// Where an acknowledged or failed attempt ended up.
+#[derive(Clone, Debug)]
+pub struct AttemptAckOutcome {
+    pub status: AttemptExecutionStatus,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    pub next_attempt_no: u32,
 }
 
 /// Lease metadata for worker ownership and failover.
@@ -63,6 +318,52 @@ pub struct LeaseRecord {
     pub terminal_state: Option<LeaseTerminalState>,
     /// Timestamp when terminal state was set (K5-a)
     pub terminal_at: Option<DateTime<Utc>>,
+    /// Tenant of the leased attempt.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+/// Fencing token for writes made under a lease. Every heartbeat bumps the lease
+/// version, so a writer whose lease expired or moved to another worker presents a
+/// token that no longer matches and is rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaseFence {
+    pub lease_id: String,
+    pub worker_id: String,
+    pub version: u64,
+}
+
+impl From<&LeaseRecord> for LeaseFence {
+    fn from(lease: &LeaseRecord) -> Self {
+        Self {
+            lease_id: lease.lease_id.clone(),
+            worker_id: lease.worker_id.clone(),
+            version: lease.version,
+        }
+    }
+}
+
+/// What the holder of a lease should do, as returned by a heartbeat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeaseDirective {
+    /// Keep executing the attempt.
+    #[default]
+    Continue,
+    /// The attempt was cancelled: stop at the next step boundary and finish it as
+    /// cancelled.
+    Cancel,
+}
+
+/// What [RuntimeRepository::cancel_attempt](crate::RuntimeRepository::cancel_attempt)
+/// did to an attempt.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttemptCancellation {
+    /// The attempt was waiting for dispatch and is now cancelled.
+    Cancelled,
+    /// The attempt is leased; its worker is told to stop on its next heartbeat.
+    Requested,
+    /// The attempt had already ended with this status and was left alone.
+    AlreadyFinished(AttemptExecutionStatus),
 }
 
 /// Terminal states for leased execution (K5-a).
@@ -87,14 +388,118 @@ impl Default for LeaseTerminalState {
     }
 }
 
-/// Interrupt metadata record for operator resume flow.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Interrupt a run blocked on, waiting in the operator inbox for a decision.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct InterruptRecord {
     pub interrupt_id: String,
     pub run_id: RunId,
     pub attempt_id: String,
-    pub event_seq: Seq,
-    pub is_pending: bool,
+    /// Node that raised the interrupt, when the runtime knows it.
+    pub step_id: Option<String>,
+    /// Value the run passed to the interrupt, shown to the operator.
+    pub payload: Value,
+    pub status: InterruptStatus,
+    pub created_at: DateTime<Utc>,
+    /// The operator's decision, once resolved.
+    pub decision: Option<InterruptDecision>,
+    /// Value given with the decision: what an approved run resumes with.
+    pub resolution: Option<Value>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Lifecycle of an [InterruptRecord].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptStatus {
+    /// Waiting for an operator.
+    Pending,
+    /// Claimed by a resume in flight.
+    Resuming,
+    /// Approved; the run was resumed with the resolution value.
+    Resumed,
+    /// Rejected; the run was cancelled.
+    Rejected,
+}
+
+impl InterruptStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            InterruptStatus::Pending => "pending",
+            InterruptStatus::Resuming => "resuming",
+            InterruptStatus::Resumed => "resumed",
+            InterruptStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "resuming" => InterruptStatus::Resuming,
+            "resumed" => InterruptStatus::Resumed,
+            "rejected" => InterruptStatus::Rejected,
+            _ => InterruptStatus::Pending,
+        }
+    }
+}
+
+/// An operator's answer to a pending interrupt.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptDecision {
+    /// Resume the run with the given value.
+    Approve,
+    /// Stop the run.
+    Reject,
+}
+
+impl InterruptDecision {
+    pub fn as_str(&self) -> &str {
+        match self {
+            InterruptDecision::Approve => "approve",
+            InterruptDecision::Reject => "reject",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "approve" => Some(InterruptDecision::Approve),
+            "reject" => Some(InterruptDecision::Reject),
+            _ => None,
+        }
+    }
+
+    /// Status of an interrupt resolved with this decision.
+    pub fn resolved_status(&self) -> InterruptStatus {
+        match self {
+            InterruptDecision::Approve => InterruptStatus::Resumed,
+            InterruptDecision::Reject => InterruptStatus::Rejected,
+        }
+    }
+}
+
+/// Which interrupts [RuntimeRepository::list_pending_interrupts](crate::RuntimeRepository::list_pending_interrupts)
+/// returns; the default matches every pending interrupt, up to
+/// [InterruptFilter::DEFAULT_LIMIT].
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InterruptFilter {
+    /// Only interrupts of this run.
+    pub run_id: Option<RunId>,
+    pub limit: Option<usize>,
+}
+
+impl InterruptFilter {
+    /// Page size when `limit` is unset.
+    pub const DEFAULT_LIMIT: usize = 100;
+
+    pub fn for_run(run_id: impl Into<RunId>) -> Self {
+        Self {
+            run_id: Some(run_id.into()),
+            limit: None,
+        }
+    }
+
+    pub fn effective_limit(&self) -> usize {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT)
+    }
 }
 
 /// Bounty status enum
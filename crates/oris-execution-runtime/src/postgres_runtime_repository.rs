@@ -4,20 +4,321 @@
 
 use std::sync::{Arc, OnceLock};
 
-use chrono::{DateTime, TimeZone, Utc};
-use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use tokio::sync::OnceCell;
 
 use oris_kernel::event::KernelError;
 use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::{PageRequest, PostgresEventStore, PostgresRepositoryConfig};
 
+use super::async_repository::AsyncRuntimeRepository;
 use super::models::{
-    AttemptDispatchRecord, AttemptExecutionStatus, BountyRecord, BountyStatus, DisputeRecord,
-    DisputeStatus, LeaseRecord, OrganismRecord, RecipeRecord, SessionMessageRecord, SessionRecord,
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    BountyRecord, BountyStatus, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions,
+    DispatchableAttempts, DisputeRecord, DisputeStatus, EnqueueBatchOutcome, EnqueueOptions,
+    InterruptDecision, InterruptFilter, InterruptRecord, InterruptStatus, LeaseDirective,
+    LeaseFence, LeaseRecord, OrganismRecord, RecipeRecord, RetryPolicyConfig, RunRecord,
+    RunRecordFilter, RunRuntimeStatus, RuntimeStats, SessionMessageRecord, SessionRecord,
     SwarmTaskRecord, WorkerRecord,
 };
-use super::repository::RuntimeRepository;
+use super::repository::run_tenant_in_scope;
 
-const POSTGRES_RUNTIME_SCHEMA_VERSION: i64 = 6;
+/// Rows per multi-row insert of [AsyncRuntimeRepository::enqueue_attempts], keeping each
+/// statement well under the protocol's 65535 bind parameters
+const ENQUEUE_BATCH_CHUNK_ROWS: usize = 1000;
+
+/// Columns [interrupt_record_from_row] reads, in order
+const INTERRUPT_RECORD_COLUMNS: &str = "interrupt_id, run_id, attempt_id, step_id, value_json, status, created_at_ms, decision, resolution_json, resolved_at_ms";
+
+/// One step of the runtime schema, applied once in version order and recorded in
+/// `runtime_schema_migrations`. `{schema}` in a statement stands for the quoted schema.
+struct PostgresRuntimeMigration {
+    version: i64,
+    name: &'static str,
+    statements: &'static [&'static str],
+}
+
+const POSTGRES_RUNTIME_MIGRATIONS: &[PostgresRuntimeMigration] = &[
+    PostgresRuntimeMigration {
+        version: 1,
+        name: "baseline_runtime_tables",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_attempts (
+                attempt_id TEXT PRIMARY KEY,
+                run_id TEXT NOT NULL,
+                attempt_no INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                retry_at_ms BIGINT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_leases (
+                lease_id TEXT PRIMARY KEY,
+                attempt_id TEXT NOT NULL UNIQUE,
+                worker_id TEXT NOT NULL,
+                lease_expires_at_ms BIGINT NOT NULL,
+                heartbeat_at_ms BIGINT NOT NULL,
+                version BIGINT NOT NULL
+            )",
+        ],
+    },
+    PostgresRuntimeMigration {
+        version: 2,
+        name: "runtime_indexes",
+        statements: &[
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_retry
+             ON {schema}.runtime_attempts(status, retry_at_ms)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_leases_expiry
+             ON {schema}.runtime_leases(lease_expires_at_ms)",
+        ],
+    },
+    // EvoMap Bounty, Swarm, Worker registry
+    PostgresRuntimeMigration {
+        version: 3,
+        name: "runtime_bounties_swarm_worker",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_bounties (
+                bounty_id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT,
+                reward BIGINT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                created_by TEXT NOT NULL,
+                created_at_ms BIGINT NOT NULL,
+                closed_at_ms BIGINT NULL,
+                accepted_by TEXT NULL,
+                accepted_at_ms BIGINT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_swarm_tasks (
+                parent_task_id TEXT PRIMARY KEY,
+                decomposition_json TEXT NOT NULL,
+                proposer_id TEXT NOT NULL,
+                proposer_reward_pct INTEGER NOT NULL DEFAULT 5,
+                solver_reward_pct INTEGER NOT NULL DEFAULT 85,
+                aggregator_reward_pct INTEGER NOT NULL DEFAULT 10,
+                status TEXT NOT NULL DEFAULT 'pending',
+                created_at_ms BIGINT NOT NULL,
+                completed_at_ms BIGINT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_workers_registry (
+                worker_id TEXT PRIMARY KEY,
+                domains TEXT NOT NULL,
+                max_load INTEGER NOT NULL DEFAULT 1,
+                metadata_json TEXT,
+                registered_at_ms BIGINT NOT NULL,
+                last_heartbeat_ms BIGINT NULL,
+                status TEXT NOT NULL DEFAULT 'active'
+            )",
+        ],
+    },
+    // EvoMap Recipe, Organism, Session, Dispute
+    PostgresRuntimeMigration {
+        version: 4,
+        name: "runtime_recipes_organisms_sessions_disputes",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_recipes (
+                recipe_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                gene_sequence_json TEXT NOT NULL,
+                author_id TEXT NOT NULL,
+                forked_from TEXT NULL,
+                created_at_ms BIGINT NOT NULL,
+                updated_at_ms BIGINT NOT NULL,
+                is_public INTEGER NOT NULL DEFAULT 0
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_organisms (
+                organism_id TEXT PRIMARY KEY,
+                recipe_id TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                current_step INTEGER NOT NULL DEFAULT 0,
+                total_steps INTEGER NOT NULL,
+                created_at_ms BIGINT NOT NULL,
+                completed_at_ms BIGINT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_collab_sessions (
+                session_id TEXT PRIMARY KEY,
+                session_type TEXT NOT NULL,
+                creator_id TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'active',
+                created_at_ms BIGINT NOT NULL,
+                ended_at_ms BIGINT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_collab_messages (
+                message_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                sender_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                message_type TEXT NOT NULL DEFAULT 'message',
+                sent_at_ms BIGINT NOT NULL
+            )",
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_disputes (
+                dispute_id TEXT PRIMARY KEY,
+                bounty_id TEXT NOT NULL,
+                opened_by TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                evidence_json TEXT,
+                resolution TEXT NULL,
+                resolved_by TEXT NULL,
+                resolved_at_ms BIGINT NULL,
+                created_at_ms BIGINT NOT NULL
+            )",
+        ],
+    },
+    // Priority column on runtime_attempts for ordered dispatch
+    PostgresRuntimeMigration {
+        version: 5,
+        name: "attempt_priority_dispatch_order",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_priority_retry
+             ON {schema}.runtime_attempts(status, priority DESC, retry_at_ms)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_tenant_status_priority
+             ON {schema}.runtime_attempts(status, priority DESC)",
+        ],
+    },
+    // API idempotency store for PostgreSQL backends
+    PostgresRuntimeMigration {
+        version: 6,
+        name: "api_idempotency_store",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.execution_idempotency (
+                idempotency_key TEXT PRIMARY KEY,
+                operation TEXT NOT NULL,
+                thread_id TEXT NOT NULL,
+                payload_hash TEXT NOT NULL,
+                response_json TEXT NOT NULL,
+                created_at_ms BIGINT NOT NULL
+            )",
+            "CREATE INDEX IF NOT EXISTS idx_execution_idempotency_created
+             ON {schema}.execution_idempotency(created_at_ms)",
+        ],
+    },
+    PostgresRuntimeMigration {
+        version: 7,
+        name: "interrupt_inbox",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_interrupts (
+                interrupt_id TEXT PRIMARY KEY,
+                thread_id TEXT NOT NULL,
+                run_id TEXT NOT NULL,
+                attempt_id TEXT NOT NULL,
+                step_id TEXT NULL,
+                value_json TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at_ms BIGINT NOT NULL,
+                decision TEXT NULL,
+                resolution_json TEXT NULL,
+                resolved_at_ms BIGINT NULL
+            )",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_interrupts_status_created
+             ON {schema}.runtime_interrupts(status, created_at_ms)",
+        ],
+    },
+    PostgresRuntimeMigration {
+        version: 8,
+        name: "runtime_runs",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS {schema}.runtime_runs (
+                run_id TEXT PRIMARY KEY,
+                workflow_name TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at_ms BIGINT NOT NULL,
+                updated_at_ms BIGINT NOT NULL
+            )",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_runs_status
+             ON {schema}.runtime_runs(status)",
+        ],
+    },
+    // Enqueue time, so equal priorities dispatch oldest first
+    PostgresRuntimeMigration {
+        version: 9,
+        name: "attempt_enqueue_order",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS enqueued_at_ms BIGINT NULL",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_priority_enqueued
+             ON {schema}.runtime_attempts(status, priority DESC, enqueued_at_ms)",
+        ],
+    },
+    // Final error and time of dead-lettered attempts
+    PostgresRuntimeMigration {
+        version: 10,
+        name: "attempt_dead_letter",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS last_error TEXT NULL",
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS dead_lettered_at_ms BIGINT NULL",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_dead_lettered
+             ON {schema}.runtime_attempts(status, dead_lettered_at_ms DESC)",
+        ],
+    },
+    // Earliest dispatch time of delayed attempts
+    PostgresRuntimeMigration {
+        version: 11,
+        name: "attempt_run_at",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS run_at_ms BIGINT NULL",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_run_at
+             ON {schema}.runtime_attempts(status, run_at_ms)",
+        ],
+    },
+    // Cancellation requested for a leased attempt
+    PostgresRuntimeMigration {
+        version: 12,
+        name: "attempt_cancel_request",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS cancel_requested_at_ms BIGINT NULL",
+        ],
+    },
+    // Run status reasons, and attempts reference their run
+    PostgresRuntimeMigration {
+        version: 13,
+        name: "run_lifecycle",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_runs ADD COLUMN IF NOT EXISTS status_reason TEXT NULL",
+            // Attempts enqueued before runs were required get a run to reference
+            "INSERT INTO {schema}.runtime_runs
+               (run_id, workflow_name, status, created_at_ms, updated_at_ms)
+             SELECT DISTINCT run_id, '', 'queued',
+                    (EXTRACT(EPOCH FROM clock_timestamp()) * 1000)::BIGINT,
+                    (EXTRACT(EPOCH FROM clock_timestamp()) * 1000)::BIGINT
+             FROM {schema}.runtime_attempts
+             ON CONFLICT(run_id) DO NOTHING",
+            "DO $$ BEGIN
+               ALTER TABLE {schema}.runtime_attempts
+                 ADD CONSTRAINT runtime_attempts_run_fk FOREIGN KEY (run_id)
+                 REFERENCES {schema}.runtime_runs(run_id);
+             EXCEPTION WHEN duplicate_object THEN NULL;
+             END $$",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_run
+             ON {schema}.runtime_attempts(run_id)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_runs_workflow
+             ON {schema}.runtime_runs(workflow_name)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_runs_updated
+             ON {schema}.runtime_runs(updated_at_ms)",
+        ],
+    },
+    // Runs, attempts and leases carry the tenant that owns them
+    PostgresRuntimeMigration {
+        version: 14,
+        name: "tenant_isolation",
+        statements: &[
+            "ALTER TABLE {schema}.runtime_runs ADD COLUMN IF NOT EXISTS tenant_id TEXT NULL",
+            "ALTER TABLE {schema}.runtime_attempts ADD COLUMN IF NOT EXISTS tenant_id TEXT NULL",
+            "ALTER TABLE {schema}.runtime_leases ADD COLUMN IF NOT EXISTS tenant_id TEXT NULL",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_runs_tenant
+             ON {schema}.runtime_runs(tenant_id, created_at_ms)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_tenant_status
+             ON {schema}.runtime_attempts(tenant_id, status, priority DESC)",
+            "CREATE INDEX IF NOT EXISTS idx_runtime_leases_tenant
+             ON {schema}.runtime_leases(tenant_id, lease_expires_at_ms)",
+        ],
+    },
+];
+
+/// Latest schema version this build knows how to migrate to
+const POSTGRES_RUNTIME_SCHEMA_VERSION: i64 =
+    POSTGRES_RUNTIME_MIGRATIONS[POSTGRES_RUNTIME_MIGRATIONS.len() - 1].version;
 
 fn is_valid_schema_ident(schema: &str) -> bool {
     !schema.is_empty()
@@ -26,23 +327,177 @@ fn is_valid_schema_ident(schema: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
-fn new_db_runtime() -> Result<Arc<tokio::runtime::Runtime>, String> {
-    static DB_RT: OnceLock<Result<Arc<tokio::runtime::Runtime>, String>> = OnceLock::new();
-    DB_RT
-        .get_or_init(|| {
-            tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .worker_threads(1)
-                .thread_name("oris-runtime-pg")
-                .build()
-                .map(Arc::new)
-                .map_err(|e| e.to_string())
-        })
-        .clone()
+/// Brings `schema` up to [POSTGRES_RUNTIME_SCHEMA_VERSION] in one transaction, holding a
+/// transaction-scoped advisory lock on the schema so concurrent workers migrate it once.
+async fn migrate_postgres_runtime_schema(pool: &PgPool, schema: &str) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("oris_runtime_schema:{}", schema))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let quoted = format!("\"{}\"", schema);
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", quoted))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {}.runtime_schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at_ms BIGINT NOT NULL
+        )",
+        quoted
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let current: i64 = sqlx::query_scalar(&format!(
+        "SELECT COALESCE(MAX(version), 0)::BIGINT FROM {}.runtime_schema_migrations",
+        quoted
+    ))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if current > POSTGRES_RUNTIME_SCHEMA_VERSION {
+        return Err(format!(
+            "postgres runtime schema {} is at version {}, newer than the {} this build supports; upgrade oris-execution-runtime",
+            schema, current, POSTGRES_RUNTIME_SCHEMA_VERSION
+        ));
+    }
+
+    let sql_record = format!(
+        "INSERT INTO {}.runtime_schema_migrations(version, name, applied_at_ms)
+         VALUES ($1, $2, $3)
+         ON CONFLICT(version) DO NOTHING",
+        quoted
+    );
+    for migration in POSTGRES_RUNTIME_MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+    {
+        for statement in migration.statements {
+            sqlx::query(&statement.replace("{schema}", &quoted))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        sqlx::query(&sql_record)
+            .bind(migration.version as i32)
+            .bind(migration.name)
+            .bind(dt_to_ms(Utc::now()))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Fails with `LeaseConflict` unless `lease` still holds `attempt_id`, unexpired, at its
+/// version. The lease row stays locked until `tx` ends, so a takeover cannot slip in
+/// between the check and the write.
+async fn check_lease_fence(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    schema: &str,
+    attempt_id: &str,
+    lease: &LeaseFence,
+    now_ms: i64,
+) -> Result<(), KernelError> {
+    let sql = format!(
+        "SELECT 1 FROM \"{}\".runtime_leases
+         WHERE lease_id = $1 AND attempt_id = $2 AND worker_id = $3 AND version = $4
+           AND lease_expires_at_ms >= $5
+         FOR UPDATE",
+        schema
+    );
+    let held = sqlx::query(&sql)
+        .bind(&lease.lease_id)
+        .bind(attempt_id)
+        .bind(&lease.worker_id)
+        .bind(lease.version as i64)
+        .bind(now_ms)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| map_storage_err("check lease fence", e))?;
+    if held.is_none() {
+        return Err(KernelError::LeaseConflict(format!(
+            "lease {} (worker {}, version {}) no longer holds attempt: {}",
+            lease.lease_id, lease.worker_id, lease.version, attempt_id
+        )));
+    }
+    Ok(())
+}
+
+/// Fails with `NotFound` when `attempt_id` exists but belongs to a tenant other than
+/// `tenant_id`; attempts that do not exist are left for the caller to report.
+async fn check_attempt_tenant(
+    executor: impl sqlx::PgExecutor<'_>,
+    schema: &str,
+    attempt_id: &str,
+    tenant_id: Option<&str>,
+) -> Result<(), KernelError> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(());
+    };
+    let sql = format!(
+        "SELECT tenant_id FROM \"{}\".runtime_attempts WHERE attempt_id = $1",
+        schema
+    );
+    let owner: Option<Option<String>> = sqlx::query_scalar(&sql)
+        .bind(attempt_id)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| map_storage_err("read attempt tenant", e))?;
+    match owner {
+        Some(owner) if owner.as_deref() != Some(tenant_id) => Err(KernelError::NotFound(format!(
+            "attempt not found: {}",
+            attempt_id
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Like [check_attempt_tenant], for runs.
+async fn check_run_tenant(
+    executor: impl sqlx::PgExecutor<'_>,
+    schema: &str,
+    run_id: &str,
+    tenant_id: Option<&str>,
+) -> Result<(), KernelError> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(());
+    };
+    let sql = format!(
+        "SELECT tenant_id FROM \"{}\".runtime_runs WHERE run_id = $1",
+        schema
+    );
+    let owner: Option<Option<String>> = sqlx::query_scalar(&sql)
+        .bind(run_id)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| map_storage_err("read run tenant", e))?;
+    match owner {
+        Some(owner) if owner.as_deref() != Some(tenant_id) => {
+            Err(KernelError::NotFound(format!("run not found: {}", run_id)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The directive for a heartbeated lease whose attempt has (`Some(true)`) or has not a
+/// cancellation request.
+fn lease_directive(cancel_requested: Option<bool>) -> LeaseDirective {
+    if cancel_requested == Some(true) {
+        LeaseDirective::Cancel
+    } else {
+        LeaseDirective::Continue
+    }
 }
 
-fn map_driver_err(prefix: &str, e: impl std::fmt::Display) -> KernelError {
-    KernelError::Driver(format!("{prefix}: {e}"))
+fn map_storage_err(prefix: &str, e: impl std::fmt::Display) -> KernelError {
+    KernelError::Storage(format!("{prefix}: {e}"))
 }
 
 fn is_unique_violation(err: &sqlx::Error) -> bool {
@@ -52,6 +507,25 @@ fn is_unique_violation(err: &sqlx::Error) -> bool {
     }
 }
 
+fn is_foreign_key_violation(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.code().as_deref() == Some("23503"),
+        _ => false,
+    }
+}
+
+fn map_row_to_run_record(row: &sqlx::postgres::PgRow) -> RunRecord {
+    RunRecord {
+        run_id: row.get(0),
+        workflow_name: row.get(1),
+        status: RunRuntimeStatus::from_str(row.get::<String, _>(2).as_str()),
+        status_reason: row.get(3),
+        tenant_id: row.get(6),
+        created_at: ms_to_dt(row.get(4)),
+        updated_at: ms_to_dt(row.get(5)),
+    }
+}
+
 fn dt_to_ms(dt: DateTime<Utc>) -> i64 {
     dt.timestamp_millis()
 }
@@ -62,6 +536,19 @@ fn ms_to_dt(ms: i64) -> DateTime<Utc> {
         .unwrap_or_else(Utc::now)
 }
 
+fn attempt_status_to_str(status: &AttemptExecutionStatus) -> &'static str {
+    match status {
+        AttemptExecutionStatus::Queued => "queued",
+        AttemptExecutionStatus::Leased => "leased",
+        AttemptExecutionStatus::Running => "running",
+        AttemptExecutionStatus::RetryBackoff => "retry_backoff",
+        AttemptExecutionStatus::Completed => "completed",
+        AttemptExecutionStatus::Failed => "failed",
+        AttemptExecutionStatus::Cancelled => "cancelled",
+        AttemptExecutionStatus::DeadLetter => "dead_letter",
+    }
+}
+
 fn parse_attempt_status(value: &str) -> AttemptExecutionStatus {
     match value {
         "leased" => AttemptExecutionStatus::Leased,
@@ -70,17 +557,21 @@ fn parse_attempt_status(value: &str) -> AttemptExecutionStatus {
         "completed" => AttemptExecutionStatus::Completed,
         "failed" => AttemptExecutionStatus::Failed,
         "cancelled" => AttemptExecutionStatus::Cancelled,
+        "dead_letter" => AttemptExecutionStatus::DeadLetter,
         _ => AttemptExecutionStatus::Queued,
     }
 }
 
 #[derive(Clone)]
 pub struct PostgresRuntimeRepository {
-    pool: Option<PgPool>,
+    database_url: String,
+    // Created lazily in the first caller's runtime, and shared by clones
+    pool: Arc<OnceLock<Result<PgPool, String>>>,
+    config: PostgresRepositoryConfig,
     schema: String,
-    init_error: Option<String>,
-    db_runtime: Option<Arc<tokio::runtime::Runtime>>,
-    schema_ready: OnceLock<Result<(), String>>,
+    schema_ready: OnceCell<Result<(), String>>,
+    /// Tenant every query is restricted to; `None` sees all tenants.
+    tenant_id: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -174,40 +665,48 @@ pub struct PostgresA2aSessionRow {
 }
 
 impl PostgresRuntimeRepository {
+    /// Repository over a pool to `database_url`, created on first use. Its connections
+    /// belong to the Tokio runtime of that first call, so a repository is meant to be
+    /// used from one runtime.
     pub fn new(database_url: impl Into<String>) -> Self {
-        let database_url = database_url.into();
-        let db_runtime = new_db_runtime().ok();
-        let pool = db_runtime.as_ref().and_then(|rt| {
-            let _guard = rt.enter();
-            PgPoolOptions::new()
-                .max_connections(5)
-                .connect_lazy(&database_url)
-                .ok()
-        });
-        let init_error = if pool.is_some() {
-            None
-        } else if db_runtime.is_none() {
-            Some("failed to initialize postgres runtime".to_string())
-        } else {
-            Some("failed to initialize lazy postgres runtime pool".to_string())
-        };
-
         Self {
-            pool,
-            schema: "public".to_string(),
-            init_error,
-            db_runtime,
-            schema_ready: OnceLock::new(),
+            database_url: database_url.into(),
+            pool: Arc::new(OnceLock::new()),
+            config: PostgresRepositoryConfig::default(),
+            schema: PostgresRepositoryConfig::DEFAULT_SCHEMA.to_string(),
+            schema_ready: OnceCell::new(),
+            tenant_id: None,
         }
     }
 
+    /// Like [PostgresRuntimeRepository::new], with the pool built from `config`. The
+    /// config and url are checked here, so a bad setting fails with `Validation` before
+    /// any query runs.
+    pub fn with_config(
+        database_url: impl Into<String>,
+        config: PostgresRepositoryConfig,
+    ) -> Result<Self, KernelError> {
+        let database_url = database_url.into();
+        config.validate()?;
+        config.connect_options(&database_url)?;
+        Ok(Self {
+            database_url,
+            pool: Arc::new(OnceLock::new()),
+            schema: config.schema.clone(),
+            config,
+            schema_ready: OnceCell::new(),
+            tenant_id: None,
+        })
+    }
+
     pub fn with_pool(pool: PgPool) -> Self {
         Self {
-            pool: Some(pool),
-            schema: "public".to_string(),
-            init_error: None,
-            db_runtime: new_db_runtime().ok(),
-            schema_ready: OnceLock::new(),
+            database_url: String::new(),
+            pool: Arc::new(OnceLock::from(Ok(pool))),
+            config: PostgresRepositoryConfig::default(),
+            schema: PostgresRepositoryConfig::DEFAULT_SCHEMA.to_string(),
+            schema_ready: OnceCell::new(),
+            tenant_id: None,
         }
     }
 
@@ -216,471 +715,140 @@ impl PostgresRuntimeRepository {
         self
     }
 
-    fn runtime(&self) -> Result<&tokio::runtime::Runtime, KernelError> {
-        if let Some(err) = &self.init_error {
-            return Err(map_driver_err("postgres init error", err));
-        }
-        self.db_runtime
-            .as_deref()
-            .ok_or_else(|| map_driver_err("runtime not available", "no db runtime"))
+    /// A handle sharing this repository's pool that only sees `tenant_id`'s runs,
+    /// attempts, leases and interrupts; rows of other tenants behave as if they did not
+    /// exist. Runs created through it belong to `tenant_id`.
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Tenant this handle is scoped to, if any.
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Kernel event log in this repository's schema, sharing its lazy pool.
+    ///
+    /// The store bootstraps its own `kernel_events` tables on first use.
+    pub async fn event_store(&self) -> Result<PostgresEventStore, KernelError> {
+        Ok(PostgresEventStore::with_pool(self.pool()?.clone()).with_schema(self.schema.clone()))
     }
 
     fn pool(&self) -> Result<&PgPool, KernelError> {
         self.pool
+            .get_or_init(|| {
+                let options = self
+                    .config
+                    .connect_options(&self.database_url)
+                    .map_err(|e| e.to_string())?;
+                Ok(self.config.pool_options().connect_lazy_with(options))
+            })
             .as_ref()
-            .ok_or_else(|| map_driver_err("pool not available", "no postgres pool"))
+            .map_err(|e| map_storage_err("postgres init error", e))
     }
 
-    fn ensure_schema(&self) -> Result<(), KernelError> {
+    /// Applies pending migrations once per repository. Concurrent bootstraps of the
+    /// same schema serialize on an advisory lock, and each runs in one transaction, so
+    /// a failed migration leaves the schema at its previous version.
+    async fn ensure_schema(&self) -> Result<(), KernelError> {
         if !is_valid_schema_ident(&self.schema) {
-            return Err(map_driver_err("invalid schema", &self.schema));
+            return Err(map_storage_err("invalid schema", &self.schema));
         }
 
-        let result = self.schema_ready.get_or_init(|| {
-            let schema = self.schema.clone();
-            let sql_schema = format!("CREATE SCHEMA IF NOT EXISTS \"{}\"", schema);
-            let sql_migration_table = format!(
-                "CREATE TABLE IF NOT EXISTS \"{}\".runtime_schema_migrations (
-                    version INTEGER PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    applied_at_ms BIGINT NOT NULL
-                )",
-                schema
-            );
-            let sql_current_version = format!(
-                "SELECT COALESCE(MAX(version), 0)::BIGINT FROM \"{}\".runtime_schema_migrations",
-                schema
-            );
-
-            let pool = match self.pool() {
-                Ok(p) => p.clone(),
-                Err(e) => return Err(e.to_string()),
-            };
-            let rt = match self.runtime() {
-                Ok(r) => r,
-                Err(e) => return Err(e.to_string()),
-            };
-
-            rt.block_on(async {
-                sqlx::query(&sql_schema)
-                    .execute(&pool)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                sqlx::query(&sql_migration_table)
-                    .execute(&pool)
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                let mut current_version: i64 = sqlx::query_scalar(&sql_current_version)
-                    .fetch_one(&pool)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                if current_version > POSTGRES_RUNTIME_SCHEMA_VERSION {
-                    return Err(format!(
-                        "postgres runtime schema version {} is newer than supported {}",
-                        current_version, POSTGRES_RUNTIME_SCHEMA_VERSION
-                    ));
-                }
-
-                if current_version < 1 {
-                    let sql_attempts = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_attempts (
-                            attempt_id TEXT PRIMARY KEY,
-                            run_id TEXT NOT NULL,
-                            attempt_no INTEGER NOT NULL,
-                            status TEXT NOT NULL,
-                            retry_at_ms BIGINT NULL
-                        )",
-                        schema
-                    );
-                    let sql_leases = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_leases (
-                            lease_id TEXT PRIMARY KEY,
-                            attempt_id TEXT NOT NULL UNIQUE,
-                            worker_id TEXT NOT NULL,
-                            lease_expires_at_ms BIGINT NOT NULL,
-                            heartbeat_at_ms BIGINT NOT NULL,
-                            version BIGINT NOT NULL
-                        )",
-                        schema
-                    );
-                    sqlx::query(&sql_attempts)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_leases)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(1_i32)
-                        .bind("baseline_runtime_tables")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    current_version = 1;
-                }
-
-                if current_version < 2 {
-                    let sql_attempt_idx = format!(
-                        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_retry
-                         ON \"{}\".runtime_attempts(status, retry_at_ms)",
-                        schema
-                    );
-                    let sql_lease_idx = format!(
-                        "CREATE INDEX IF NOT EXISTS idx_runtime_leases_expiry
-                         ON \"{}\".runtime_leases(lease_expires_at_ms)",
-                        schema
-                    );
-                    sqlx::query(&sql_attempt_idx)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_lease_idx)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(2_i32)
-                        .bind("runtime_indexes")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                }
-
-                // Migration v3: EvoMap Bounty, Swarm, Worker registry
-                if current_version < 3 {
-                    let sql_bounties = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_bounties (
-                            bounty_id TEXT PRIMARY KEY,
-                            title TEXT NOT NULL,
-                            description TEXT,
-                            reward BIGINT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'open',
-                            created_by TEXT NOT NULL,
-                            created_at_ms BIGINT NOT NULL,
-                            closed_at_ms BIGINT NULL,
-                            accepted_by TEXT NULL,
-                            accepted_at_ms BIGINT NULL
-                        )",
-                        schema
-                    );
-                    let sql_swarm = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_swarm_tasks (
-                            parent_task_id TEXT PRIMARY KEY,
-                            decomposition_json TEXT NOT NULL,
-                            proposer_id TEXT NOT NULL,
-                            proposer_reward_pct INTEGER NOT NULL DEFAULT 5,
-                            solver_reward_pct INTEGER NOT NULL DEFAULT 85,
-                            aggregator_reward_pct INTEGER NOT NULL DEFAULT 10,
-                            status TEXT NOT NULL DEFAULT 'pending',
-                            created_at_ms BIGINT NOT NULL,
-                            completed_at_ms BIGINT NULL
-                        )",
-                        schema
-                    );
-                    let sql_workers = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_workers_registry (
-                            worker_id TEXT PRIMARY KEY,
-                            domains TEXT NOT NULL,
-                            max_load INTEGER NOT NULL DEFAULT 1,
-                            metadata_json TEXT,
-                            registered_at_ms BIGINT NOT NULL,
-                            last_heartbeat_ms BIGINT NULL,
-                            status TEXT NOT NULL DEFAULT 'active'
-                        )",
-                        schema
-                    );
-
-                    sqlx::query(&sql_bounties)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_swarm)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_workers)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(3_i32)
-                        .bind("runtime_bounties_swarm_worker")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                }
-
-                // Migration v4: EvoMap Recipe, Organism, Session, Dispute
-                if current_version < 4 {
-                    let sql_recipes = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_recipes (
-                            recipe_id TEXT PRIMARY KEY,
-                            name TEXT NOT NULL,
-                            description TEXT,
-                            gene_sequence_json TEXT NOT NULL,
-                            author_id TEXT NOT NULL,
-                            forked_from TEXT NULL,
-                            created_at_ms BIGINT NOT NULL,
-                            updated_at_ms BIGINT NOT NULL,
-                            is_public INTEGER NOT NULL DEFAULT 0
-                        )",
-                        schema
-                    );
-                    let sql_organisms = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_organisms (
-                            organism_id TEXT PRIMARY KEY,
-                            recipe_id TEXT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'pending',
-                            current_step INTEGER NOT NULL DEFAULT 0,
-                            total_steps INTEGER NOT NULL,
-                            created_at_ms BIGINT NOT NULL,
-                            completed_at_ms BIGINT NULL
-                        )",
-                        schema
-                    );
-                    let sql_sessions = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_collab_sessions (
-                            session_id TEXT PRIMARY KEY,
-                            session_type TEXT NOT NULL,
-                            creator_id TEXT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'active',
-                            created_at_ms BIGINT NOT NULL,
-                            ended_at_ms BIGINT NULL
-                        )",
-                        schema
-                    );
-                    let sql_messages = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_collab_messages (
-                            message_id TEXT PRIMARY KEY,
-                            session_id TEXT NOT NULL,
-                            sender_id TEXT NOT NULL,
-                            content TEXT NOT NULL,
-                            message_type TEXT NOT NULL DEFAULT 'message',
-                            sent_at_ms BIGINT NOT NULL
-                        )",
-                        schema
-                    );
-                    let sql_disputes = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".runtime_disputes (
-                            dispute_id TEXT PRIMARY KEY,
-                            bounty_id TEXT NOT NULL,
-                            opened_by TEXT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'open',
-                            evidence_json TEXT,
-                            resolution TEXT NULL,
-                            resolved_by TEXT NULL,
-                            resolved_at_ms BIGINT NULL,
-                            created_at_ms BIGINT NOT NULL
-                        )",
-                        schema
-                    );
-
-                    sqlx::query(&sql_recipes)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_organisms)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_sessions)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_messages)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_disputes)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(4_i32)
-                        .bind("runtime_recipes_organisms_sessions_disputes")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                }
-
-                // Migration v5: priority column on runtime_attempts for ordered dispatch
-                if current_version < 5 {
-                    let sql_add_priority = format!(
-                        "ALTER TABLE \"{}\".runtime_attempts ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0",
-                        schema
-                    );
-                    let sql_idx_priority = format!(
-                        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_priority_retry
-                         ON \"{}\".runtime_attempts(status, priority DESC, retry_at_ms)",
-                        schema
-                    );
-                    let sql_idx_tenant_priority = format!(
-                        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_tenant_status_priority
-                         ON \"{}\".runtime_attempts(status, priority DESC)",
-                        schema
-                    );
-                    sqlx::query(&sql_add_priority)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_idx_priority)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_idx_tenant_priority)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(5_i32)
-                        .bind("attempt_priority_dispatch_order")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                }
-
-                // Migration v6: API idempotency store for PostgreSQL backends
-                if current_version < 6 {
-                    let sql_idempotency = format!(
-                        "CREATE TABLE IF NOT EXISTS \"{}\".execution_idempotency (
-                            idempotency_key TEXT PRIMARY KEY,
-                            operation TEXT NOT NULL,
-                            thread_id TEXT NOT NULL,
-                            payload_hash TEXT NOT NULL,
-                            response_json TEXT NOT NULL,
-                            created_at_ms BIGINT NOT NULL
-                        )",
-                        schema
-                    );
-                    let sql_idx_idempotency = format!(
-                        "CREATE INDEX IF NOT EXISTS idx_execution_idempotency_created
-                         ON \"{}\".execution_idempotency(created_at_ms)",
-                        schema
-                    );
-                    sqlx::query(&sql_idempotency)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    sqlx::query(&sql_idx_idempotency)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    let now = dt_to_ms(Utc::now());
-                    let sql_record = format!(
-                        "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
-                         VALUES ($1, $2, $3)
-                         ON CONFLICT(version) DO NOTHING",
-                        schema
-                    );
-                    sqlx::query(&sql_record)
-                        .bind(6_i32)
-                        .bind("api_idempotency_store")
-                        .bind(now)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| e.to_string())?;
-                }
-
-                Ok(())
+        let result = self
+            .schema_ready
+            .get_or_init(|| async {
+                let pool = self.pool().map_err(|e| e.to_string())?.clone();
+                migrate_postgres_runtime_schema(&pool, &self.schema).await
             })
-        });
+            .await;
 
         result
             .clone()
-            .map_err(|e| map_driver_err("schema bootstrap", e))
+            .map_err(|e| map_storage_err("schema bootstrap", e))
     }
 
-    pub fn enqueue_attempt(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    /// Version of the runtime schema, after applying any pending migrations.
+    pub async fn schema_version(&self) -> Result<i64, KernelError> {
+        self.ensure_schema().await?;
+        let sql = format!(
+            "SELECT COALESCE(MAX(version), 0)::BIGINT FROM \"{}\".runtime_schema_migrations",
+            self.schema
+        );
+        sqlx::query_scalar(&sql)
+            .fetch_one(self.pool()?)
+            .await
+            .map_err(|e| map_storage_err("read schema version", e))
+    }
 
-        let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
-        let schema = self.schema.clone();
-        let attempt_id = attempt_id.to_string();
-        let run_id = run_id.to_string();
-        rt.block_on(async move {
-            let sql = format!(
-                "INSERT INTO \"{}\".runtime_attempts (attempt_id, run_id, attempt_no, status, retry_at_ms)
-                 VALUES ($1, $2, 1, 'queued', NULL)
-                 ON CONFLICT(attempt_id) DO NOTHING",
-                schema
-            );
-            sqlx::query(&sql)
-                .bind(&attempt_id)
-                .bind(&run_id)
-                .execute(&pool)
-                .await
-                .map_err(|e| map_driver_err("enqueue attempt", e))?;
-            Ok(())
-        })
+    /// Enqueue an attempt of `run_id`, which must have been recorded with
+    /// [AsyncRuntimeRepository::create_run]; fails with `NotFound` otherwise.
+    pub async fn enqueue_attempt(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+        self.enqueue_attempt_with_priority(attempt_id, run_id, 0)
+            .await
+    }
+
+    /// Enqueue an attempt that is dispatched ahead of every queued attempt with a lower
+    /// priority; among equal priorities, earlier-enqueued attempts go first.
+    pub async fn enqueue_attempt_with_priority(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        priority: i32,
+    ) -> Result<(), KernelError> {
+        self.insert_queued_attempt(attempt_id, run_id, priority, None)
+            .await
+    }
+
+    /// Enqueue an attempt that is not dispatched before `run_at`; a `run_at` that is
+    /// already in the past makes the attempt dispatchable immediately.
+    pub async fn enqueue_attempt_at(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        run_at: DateTime<Utc>,
+    ) -> Result<(), KernelError> {
+        self.insert_queued_attempt(attempt_id, run_id, 0, Some(run_at))
+            .await
+    }
+
+    async fn insert_queued_attempt(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        priority: i32,
+        run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), KernelError> {
+        let options = EnqueueOptions { priority, run_at };
+        self.enqueue_attempts(&[(attempt_id.to_string(), run_id.to_string(), options)])
+            .await
+            .map(|_| ())
     }
 
-    pub fn get_lease_for_attempt(
+    pub async fn get_lease_for_attempt(
         &self,
         attempt_id: &str,
     ) -> Result<Option<LeaseRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
 
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let attempt_id = attempt_id.to_string();
-        rt.block_on(async move {
+        let tenant_id = self.tenant_id.clone();
+        async move {
             let sql = format!(
-                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version
+                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id
                  FROM \"{}\".runtime_leases
-                 WHERE attempt_id = $1",
+                 WHERE attempt_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)",
                 schema
             );
             let row = sqlx::query(&sql)
                 .bind(&attempt_id)
+                .bind(&tenant_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get lease by attempt", e))?;
+                .map_err(|e| map_storage_err("get lease by attempt", e))?;
 
             Ok(row.map(|row| LeaseRecord {
                 lease_id: row.get::<String, _>(0),
@@ -691,29 +859,34 @@ impl PostgresRuntimeRepository {
                 version: row.get::<i64, _>(5) as u64,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: row.get(6),
             }))
-        })
+        }.await
     }
 
-    pub fn get_lease_by_id(&self, lease_id: &str) -> Result<Option<LeaseRecord>, KernelError> {
-        self.ensure_schema()?;
+    pub async fn get_lease_by_id(
+        &self,
+        lease_id: &str,
+    ) -> Result<Option<LeaseRecord>, KernelError> {
+        self.ensure_schema().await?;
 
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let lease_id = lease_id.to_string();
-        rt.block_on(async move {
+        let tenant_id = self.tenant_id.clone();
+        async move {
             let sql = format!(
-                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version
+                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id
                  FROM \"{}\".runtime_leases
-                 WHERE lease_id = $1",
+                 WHERE lease_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)",
                 schema
             );
             let row = sqlx::query(&sql)
                 .bind(&lease_id)
+                .bind(&tenant_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get lease by id", e))?;
+                .map_err(|e| map_storage_err("get lease by id", e))?;
             Ok(row.map(|row| LeaseRecord {
                 lease_id: row.get::<String, _>(0),
                 attempt_id: row.get::<String, _>(1),
@@ -723,11 +896,12 @@ impl PostgresRuntimeRepository {
                 version: row.get::<i64, _>(5) as u64,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: row.get(6),
             }))
-        })
+        }.await
     }
 
-    pub fn create_bounty(
+    pub async fn create_bounty(
         &self,
         bounty_id: &str,
         title: &str,
@@ -736,9 +910,8 @@ impl PostgresRuntimeRepository {
         created_by: &str,
         created_at: DateTime<Utc>,
     ) -> Result<PostgresBountyRow, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
         let title = title.to_string();
@@ -746,7 +919,7 @@ impl PostgresRuntimeRepository {
         let created_by = created_by.to_string();
         let created_at_ms = dt_to_ms(created_at);
         let bounty_id_for_insert = bounty_id.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_bounties
                  (bounty_id, title, description, reward, status, created_by, created_at_ms, closed_at_ms, accepted_by, accepted_at_ms)
@@ -762,20 +935,23 @@ impl PostgresRuntimeRepository {
                 .bind(created_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create bounty", e))?;
+                .map_err(|e| map_storage_err("create bounty", e))?;
             Ok(())
-        })?;
-        self.get_bounty(&bounty_id)?
-            .ok_or_else(|| map_driver_err("create bounty", "missing row after insert"))
+        }.await?;
+        self.get_bounty(&bounty_id)
+            .await?
+            .ok_or_else(|| map_storage_err("create bounty", "missing row after insert"))
     }
 
-    pub fn get_bounty(&self, bounty_id: &str) -> Result<Option<PostgresBountyRow>, KernelError> {
-        self.ensure_schema()?;
+    pub async fn get_bounty(
+        &self,
+        bounty_id: &str,
+    ) -> Result<Option<PostgresBountyRow>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT bounty_id, title, description, reward, status, created_by, created_at_ms, closed_at_ms, accepted_by, accepted_at_ms
                  FROM \"{}\".runtime_bounties
@@ -786,7 +962,7 @@ impl PostgresRuntimeRepository {
                 .bind(&bounty_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get bounty", e))?;
+                .map_err(|e| map_storage_err("get bounty", e))?;
             Ok(row.map(|r| PostgresBountyRow {
                 bounty_id: r.get::<String, _>(0),
                 title: r.get::<String, _>(1),
@@ -799,23 +975,22 @@ impl PostgresRuntimeRepository {
                 accepted_by: r.get::<Option<String>, _>(8),
                 accepted_at: r.get::<Option<i64>, _>(9).map(ms_to_dt),
             }))
-        })
+        }.await
     }
 
-    pub fn accept_bounty(
+    pub async fn accept_bounty(
         &self,
         bounty_id: &str,
         accepted_by: &str,
         accepted_at: DateTime<Utc>,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
         let accepted_by = accepted_by.to_string();
         let accepted_at_ms = dt_to_ms(accepted_at);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_bounties
                  SET status = 'accepted',
@@ -831,24 +1006,24 @@ impl PostgresRuntimeRepository {
                 .bind(accepted_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("accept bounty", e))?
+                .map_err(|e| map_storage_err("accept bounty", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
-    pub fn close_bounty(
+    pub async fn close_bounty(
         &self,
         bounty_id: &str,
         closed_at: DateTime<Utc>,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
         let closed_at_ms = dt_to_ms(closed_at);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_bounties
                  SET status = 'closed',
@@ -862,14 +1037,15 @@ impl PostgresRuntimeRepository {
                 .bind(closed_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("close bounty", e))?
+                .map_err(|e| map_storage_err("close bounty", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn create_swarm_task(
+    pub async fn create_swarm_task(
         &self,
         parent_task_id: &str,
         decomposition_json: &str,
@@ -880,9 +1056,8 @@ impl PostgresRuntimeRepository {
         status: &str,
         created_at: DateTime<Utc>,
     ) -> Result<PostgresSwarmTaskRow, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let parent_task_id = parent_task_id.to_string();
         let decomposition_json = decomposition_json.to_string();
@@ -890,7 +1065,7 @@ impl PostgresRuntimeRepository {
         let status = status.to_string();
         let created_at_ms = dt_to_ms(created_at);
         let parent_task_id_for_insert = parent_task_id.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_swarm_tasks
                  (parent_task_id, decomposition_json, proposer_id, proposer_reward_pct, solver_reward_pct, aggregator_reward_pct, status, created_at_ms, completed_at_ms)
@@ -908,23 +1083,23 @@ impl PostgresRuntimeRepository {
                 .bind(created_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create swarm task", e))?;
+                .map_err(|e| map_storage_err("create swarm task", e))?;
             Ok(())
-        })?;
-        self.get_swarm_task(&parent_task_id)?
-            .ok_or_else(|| map_driver_err("create swarm task", "missing row after insert"))
+        }.await?;
+        self.get_swarm_task(&parent_task_id)
+            .await?
+            .ok_or_else(|| map_storage_err("create swarm task", "missing row after insert"))
     }
 
-    pub fn get_swarm_task(
+    pub async fn get_swarm_task(
         &self,
         parent_task_id: &str,
     ) -> Result<Option<PostgresSwarmTaskRow>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let parent_task_id = parent_task_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT parent_task_id, decomposition_json, proposer_id, proposer_reward_pct, solver_reward_pct, aggregator_reward_pct, status, created_at_ms, completed_at_ms
                  FROM \"{}\".runtime_swarm_tasks
@@ -935,7 +1110,7 @@ impl PostgresRuntimeRepository {
                 .bind(&parent_task_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get swarm task", e))?;
+                .map_err(|e| map_storage_err("get swarm task", e))?;
             Ok(row.map(|r| PostgresSwarmTaskRow {
                 parent_task_id: r.get::<String, _>(0),
                 decomposition_json: r.get::<String, _>(1),
@@ -947,57 +1122,205 @@ impl PostgresRuntimeRepository {
                 created_at: ms_to_dt(r.get::<i64, _>(7)),
                 completed_at: r.get::<Option<i64>, _>(8).map(ms_to_dt),
             }))
-        })
+        }.await
     }
 
-    pub fn heartbeat_lease_with_version(
+    async fn finish_attempt_under_lease(
         &self,
-        lease_id: &str,
-        worker_id: &str,
-        expected_version: u64,
-        heartbeat_at: DateTime<Utc>,
-        lease_expires_at: DateTime<Utc>,
-    ) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.ensure_schema().await?;
 
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        let lease_id = lease_id.to_string();
-        let worker_id = worker_id.to_string();
-        let expected_version = expected_version as i64;
-        let heartbeat_at_ms = dt_to_ms(heartbeat_at);
-        let lease_expires_at_ms = dt_to_ms(lease_expires_at);
-        rt.block_on(async move {
-            let sql = format!(
-                "UPDATE \"{}\".runtime_leases
-                 SET heartbeat_at_ms = $4, lease_expires_at_ms = $5, version = version + 1
-                 WHERE lease_id = $1 AND worker_id = $2 AND version = $3",
+        let attempt_id = attempt_id.to_string();
+        let lease = lease.cloned();
+        let now_ms = dt_to_ms(now);
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin finish attempt tx", e))?;
+            check_attempt_tenant(&mut *tx, &schema, &attempt_id, tenant_id.as_deref()).await?;
+            if let Some(lease) = &lease {
+                check_lease_fence(&mut tx, &schema, &attempt_id, lease, now_ms).await?;
+            }
+            let delete_sql = format!(
+                "DELETE FROM \"{}\".runtime_leases WHERE attempt_id = $1",
                 schema
             );
-            let updated = sqlx::query(&sql)
-                .bind(&lease_id)
-                .bind(&worker_id)
-                .bind(expected_version)
-                .bind(heartbeat_at_ms)
-                .bind(lease_expires_at_ms)
-                .execute(&pool)
+            sqlx::query(&delete_sql)
+                .bind(&attempt_id)
+                .execute(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("heartbeat lease with version", e))?
-                .rows_affected();
-            if updated == 0 {
-                return Err(KernelError::Driver(format!(
-                    "lease heartbeat version conflict for lease: {}",
-                    lease_id
-                )));
-            }
-            Ok(())
-        })
+                .map_err(|e| map_storage_err("delete attempt lease on finish", e))?;
+            // A failure of an attempt asked to cancel ends it as cancelled
+            let update_sql = format!(
+                "UPDATE \"{}\".runtime_attempts
+                 SET status = CASE
+                       WHEN $2 = 'failed' AND cancel_requested_at_ms IS NOT NULL THEN 'cancelled'
+                       ELSE $2
+                     END,
+                     retry_at_ms = NULL
+                 WHERE attempt_id = $1
+                 RETURNING status",
+                schema
+            );
+            let Some(row) = sqlx::query(&update_sql)
+                .bind(&attempt_id)
+                .bind(attempt_status_to_str(&status))
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("mark finished attempt status", e))?
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found for finish: {}",
+                    attempt_id
+                )));
+            };
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit finish attempt tx", e))?;
+            Ok(parse_attempt_status(&row.get::<String, _>(0)))
+        }
+        .await
+    }
+
+    async fn record_attempt_failure_under_lease(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let attempt_id = attempt_id.to_string();
+        let error = error.to_string();
+        let retry_policy = retry_policy.clone();
+        let lease = lease.cloned();
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin record attempt failure tx", e))?;
+            check_attempt_tenant(&mut *tx, &schema, &attempt_id, tenant_id.as_deref()).await?;
+            if let Some(lease) = &lease {
+                check_lease_fence(&mut tx, &schema, &attempt_id, lease, dt_to_ms(now)).await?;
+            }
+            let select_sql = format!(
+                "SELECT attempt_no, cancel_requested_at_ms
+                 FROM \"{}\".runtime_attempts
+                 WHERE attempt_id = $1
+                 FOR UPDATE",
+                schema
+            );
+            let Some(row) = sqlx::query(&select_sql)
+                .bind(&attempt_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("read attempt for failure", e))?
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found for failure: {}",
+                    attempt_id
+                )));
+            };
+            let attempt_no = row.get::<i32, _>(0).max(1) as u32;
+            let cancel_requested_at_ms: Option<i64> = row.get(1);
+
+            let delete_sql = format!(
+                "DELETE FROM \"{}\".runtime_leases WHERE attempt_id = $1",
+                schema
+            );
+            sqlx::query(&delete_sql)
+                .bind(&attempt_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("delete attempt lease on failure", e))?;
+
+            let outcome = if cancel_requested_at_ms.is_some() {
+                // Cancelled while running: the failure is most likely the cancellation itself
+                let update_sql = format!(
+                    "UPDATE \"{}\".runtime_attempts
+                     SET status = 'cancelled', retry_at_ms = NULL, last_error = $2
+                     WHERE attempt_id = $1",
+                    schema
+                );
+                sqlx::query(&update_sql)
+                    .bind(&attempt_id)
+                    .bind(&error)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_storage_err("cancel failed attempt", e))?;
+                AttemptAckOutcome {
+                    status: AttemptExecutionStatus::Cancelled,
+                    next_retry_at: None,
+                    next_attempt_no: attempt_no,
+                }
+            } else if attempt_no <= retry_policy.max_retries {
+                let next_attempt_no = attempt_no + 1;
+                let backoff_ms = retry_policy.next_backoff_ms(attempt_no).max(1);
+                let retry_at = now + Duration::milliseconds(backoff_ms);
+                let update_sql = format!(
+                    "UPDATE \"{}\".runtime_attempts
+                     SET attempt_no = $2, status = 'retry_backoff', retry_at_ms = $3, last_error = $4
+                     WHERE attempt_id = $1",
+                    schema
+                );
+                sqlx::query(&update_sql)
+                    .bind(&attempt_id)
+                    .bind(next_attempt_no as i32)
+                    .bind(dt_to_ms(retry_at))
+                    .bind(&error)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_storage_err("schedule attempt retry", e))?;
+                AttemptAckOutcome {
+                    status: AttemptExecutionStatus::RetryBackoff,
+                    next_retry_at: Some(retry_at),
+                    next_attempt_no,
+                }
+            } else {
+                let update_sql = format!(
+                    "UPDATE \"{}\".runtime_attempts
+                     SET status = 'dead_letter', retry_at_ms = NULL, last_error = $2, dead_lettered_at_ms = $3
+                     WHERE attempt_id = $1",
+                    schema
+                );
+                sqlx::query(&update_sql)
+                    .bind(&attempt_id)
+                    .bind(&error)
+                    .bind(dt_to_ms(now))
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_storage_err("dead-letter attempt", e))?;
+                AttemptAckOutcome {
+                    status: AttemptExecutionStatus::DeadLetter,
+                    next_retry_at: None,
+                    next_attempt_no: attempt_no,
+                }
+            };
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit record attempt failure tx", e))?;
+            Ok(outcome)
+        }.await
     }
 
     // ========== Worker Registration Methods ==========
 
-    pub fn upsert_worker_registration(
+    pub async fn upsert_worker_registration(
         &self,
         worker_id: &str,
         domains_json: &str,
@@ -1006,16 +1329,15 @@ impl PostgresRuntimeRepository {
         status: &str,
         now: DateTime<Utc>,
     ) -> Result<PostgresWorkerRegistryRow, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let worker_id = worker_id.to_string();
         let domains_json = domains_json.to_string();
         let metadata_json = metadata_json.map(String::from);
         let status = status.to_string();
         let now_ms = dt_to_ms(now);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_workers_registry
                  (worker_id, domains, max_load, metadata_json, registered_at_ms, last_heartbeat_ms, status)
@@ -1038,7 +1360,7 @@ impl PostgresRuntimeRepository {
                 .bind(&status)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("upsert worker registration", e))?;
+                .map_err(|e| map_storage_err("upsert worker registration", e))?;
 
             // Fetch the row
             let sql = format!(
@@ -1051,7 +1373,7 @@ impl PostgresRuntimeRepository {
                 .bind(&worker_id)
                 .fetch_one(&pool)
                 .await
-                .map_err(|e| map_driver_err("get worker registration", e))?;
+                .map_err(|e| map_storage_err("get worker registration", e))?;
             Ok(PostgresWorkerRegistryRow {
                 worker_id: row.get(0),
                 domains_json: row.get(1),
@@ -1061,19 +1383,18 @@ impl PostgresRuntimeRepository {
                 last_heartbeat_at: row.get::<Option<i64>, _>(5).map(|v| ms_to_dt(v)),
                 status: row.get(6),
             })
-        })
+        }.await
     }
 
-    pub fn get_worker_registration(
+    pub async fn get_worker_registration(
         &self,
         worker_id: &str,
     ) -> Result<Option<PostgresWorkerRegistryRow>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let worker_id = worker_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT worker_id, domains, max_load, metadata_json, registered_at_ms, last_heartbeat_ms, status
                  FROM \"{}\".runtime_workers_registry
@@ -1084,7 +1405,7 @@ impl PostgresRuntimeRepository {
                 .bind(&worker_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get worker registration", e))?;
+                .map_err(|e| map_storage_err("get worker registration", e))?;
             Ok(row.map(|r| PostgresWorkerRegistryRow {
                 worker_id: r.get(0),
                 domains_json: r.get(1),
@@ -1094,21 +1415,20 @@ impl PostgresRuntimeRepository {
                 last_heartbeat_at: r.get::<Option<i64>, _>(5).map(ms_to_dt),
                 status: r.get(6),
             }))
-        })
+        }.await
     }
 
-    pub fn count_active_claims_for_worker(
+    pub async fn count_active_claims_for_worker(
         &self,
         worker_id: &str,
         now: DateTime<Utc>,
     ) -> Result<u64, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let worker_id = worker_id.to_string();
         let now_ms = dt_to_ms(now);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT COUNT(*) FROM \"{}\".runtime_a2a_compat_tasks
                  WHERE claimed_by_sender_id = $1
@@ -1121,15 +1441,16 @@ impl PostgresRuntimeRepository {
                 .bind(now_ms)
                 .fetch_one(&pool)
                 .await
-                .map_err(|e| map_driver_err("count active claims for worker", e))?
+                .map_err(|e| map_storage_err("count active claims for worker", e))?
                 .get(0);
             Ok(count.max(0) as u64)
-        })
+        }
+        .await
     }
 
     // ========== Dispute Methods ==========
 
-    pub fn create_dispute(
+    pub async fn create_dispute(
         &self,
         dispute_id: &str,
         bounty_id: &str,
@@ -1137,16 +1458,15 @@ impl PostgresRuntimeRepository {
         description: &str,
         created_at: DateTime<Utc>,
     ) -> Result<PostgresDisputeRow, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let dispute_id = dispute_id.to_string();
         let bounty_id = bounty_id.to_string();
         let opened_by = opened_by.to_string();
         let _description = description.to_string();
         let created_at_ms = dt_to_ms(created_at);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_disputes
                  (dispute_id, bounty_id, opened_by, status, resolution, resolved_by, created_at_ms, resolved_at_ms, evidence_json)
@@ -1160,7 +1480,7 @@ impl PostgresRuntimeRepository {
                 .bind(created_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create dispute", e))?;
+                .map_err(|e| map_storage_err("create dispute", e))?;
             Ok(PostgresDisputeRow {
                 dispute_id,
                 bounty_id,
@@ -1172,16 +1492,18 @@ impl PostgresRuntimeRepository {
                 resolved_at: None,
                 evidence_json: None,
             })
-        })
+        }.await
     }
 
-    pub fn get_dispute(&self, dispute_id: &str) -> Result<Option<PostgresDisputeRow>, KernelError> {
-        self.ensure_schema()?;
+    pub async fn get_dispute(
+        &self,
+        dispute_id: &str,
+    ) -> Result<Option<PostgresDisputeRow>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let dispute_id = dispute_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT dispute_id, bounty_id, opened_by, status, resolution, resolved_by, created_at_ms, resolved_at_ms, evidence_json
                  FROM \"{}\".runtime_disputes
@@ -1192,7 +1514,7 @@ impl PostgresRuntimeRepository {
                 .bind(&dispute_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get dispute", e))?;
+                .map_err(|e| map_storage_err("get dispute", e))?;
             Ok(row.map(|r| PostgresDisputeRow {
                 dispute_id: r.get(0),
                 bounty_id: r.get(1),
@@ -1204,21 +1526,20 @@ impl PostgresRuntimeRepository {
                 resolved_at: r.get::<Option<i64>, _>(7).map(ms_to_dt),
                 evidence_json: r.get(8),
             }))
-        })
+        }.await
     }
 
-    pub fn append_dispute_evidence(
+    pub async fn append_dispute_evidence(
         &self,
         dispute_id: &str,
         evidence_json: &str,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let dispute_id = dispute_id.to_string();
         let evidence_json = evidence_json.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_disputes
                  SET evidence_json = COALESCE(evidence_json || ', ', '') || $2
@@ -1230,28 +1551,28 @@ impl PostgresRuntimeRepository {
                 .bind(&evidence_json)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("append dispute evidence", e))?
+                .map_err(|e| map_storage_err("append dispute evidence", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
-    pub fn resolve_dispute(
+    pub async fn resolve_dispute(
         &self,
         dispute_id: &str,
         resolution: &str,
         resolved_by: &str,
         resolved_at: DateTime<Utc>,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let dispute_id = dispute_id.to_string();
         let resolution = resolution.to_string();
         let resolved_by = resolved_by.to_string();
         let resolved_at_ms = dt_to_ms(resolved_at);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_disputes
                  SET status = 'resolved', resolution = $2, resolved_by = $3, resolved_at_ms = $4
@@ -1265,26 +1586,26 @@ impl PostgresRuntimeRepository {
                 .bind(resolved_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("resolve dispute", e))?
+                .map_err(|e| map_storage_err("resolve dispute", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
-    pub fn settle_bounty_via_dispute(
+    pub async fn settle_bounty_via_dispute(
         &self,
         bounty_id: &str,
         settlement_status: &str,
         closed_at: DateTime<Utc>,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
         let settlement_status = settlement_status.to_string();
         let closed_at_ms = dt_to_ms(closed_at);
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_bounties
                  SET status = $2, closed_at_ms = $3
@@ -1298,20 +1619,20 @@ impl PostgresRuntimeRepository {
                 .bind(closed_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("settle bounty via dispute", e))?
+                .map_err(|e| map_storage_err("settle bounty via dispute", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
     // ========== Recipe Methods ==========
 
-    pub fn create_recipe(&self, recipe: &PostgresRecipeRow) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    pub async fn create_recipe(&self, recipe: &PostgresRecipeRow) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_recipes
                  (recipe_id, name, description, gene_sequence_json, author_id, forked_from, created_at_ms, updated_at_ms, is_public)
@@ -1330,18 +1651,20 @@ impl PostgresRuntimeRepository {
                 .bind(recipe.is_public as i32)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create recipe", e))?;
+                .map_err(|e| map_storage_err("create recipe", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    pub fn get_recipe(&self, recipe_id: &str) -> Result<Option<PostgresRecipeRow>, KernelError> {
-        self.ensure_schema()?;
+    pub async fn get_recipe(
+        &self,
+        recipe_id: &str,
+    ) -> Result<Option<PostgresRecipeRow>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let recipe_id = recipe_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT recipe_id, name, description, gene_sequence_json, author_id, forked_from, created_at_ms, updated_at_ms, is_public
                  FROM \"{}\".runtime_recipes
@@ -1352,7 +1675,7 @@ impl PostgresRuntimeRepository {
                 .bind(&recipe_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get recipe", e))?;
+                .map_err(|e| map_storage_err("get recipe", e))?;
             Ok(row.map(|r| PostgresRecipeRow {
                 recipe_id: r.get(0),
                 name: r.get(1),
@@ -1364,17 +1687,16 @@ impl PostgresRuntimeRepository {
                 updated_at: ms_to_dt(r.get(7)),
                 is_public: r.get::<i32, _>(8) != 0,
             }))
-        })
+        }.await
     }
 
     // ========== Organism Methods ==========
 
-    pub fn create_organism(&self, organism: &PostgresOrganismRow) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    pub async fn create_organism(&self, organism: &PostgresOrganismRow) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_organisms
                  (organism_id, recipe_id, status, current_step, total_steps, created_at_ms, completed_at_ms)
@@ -1391,21 +1713,20 @@ impl PostgresRuntimeRepository {
                 .bind(organism.completed_at.map(dt_to_ms))
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create organism", e))?;
+                .map_err(|e| map_storage_err("create organism", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    pub fn get_organism(
+    pub async fn get_organism(
         &self,
         organism_id: &str,
     ) -> Result<Option<PostgresOrganismRow>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let organism_id = organism_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT organism_id, recipe_id, status, current_step, total_steps, created_at_ms, completed_at_ms
                  FROM \"{}\".runtime_organisms
@@ -1416,7 +1737,7 @@ impl PostgresRuntimeRepository {
                 .bind(&organism_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get organism", e))?;
+                .map_err(|e| map_storage_err("get organism", e))?;
             Ok(row.map(|r| PostgresOrganismRow {
                 organism_id: r.get(0),
                 recipe_id: r.get(1),
@@ -1426,18 +1747,17 @@ impl PostgresRuntimeRepository {
                 created_at: ms_to_dt(r.get(5)),
                 completed_at: r.get::<Option<i64>, _>(6).map(ms_to_dt),
             }))
-        })
+        }.await
     }
 
-    pub fn update_organism_status(
+    pub async fn update_organism_status(
         &self,
         organism_id: &str,
         status: &str,
         current_step: i32,
     ) -> Result<bool, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let organism_id = organism_id.to_string();
         let status = status.to_string();
@@ -1446,7 +1766,7 @@ impl PostgresRuntimeRepository {
         } else {
             None
         };
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_organisms
                  SET status = $2, current_step = $3, completed_at_ms = $4
@@ -1460,20 +1780,23 @@ impl PostgresRuntimeRepository {
                 .bind(completed_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("update organism status", e))?
+                .map_err(|e| map_storage_err("update organism status", e))?
                 .rows_affected();
             Ok(affected > 0)
-        })
+        }
+        .await
     }
 
     // ========== Session Methods ==========
 
-    pub fn upsert_a2a_session(&self, session: &PostgresA2aSessionRow) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    pub async fn upsert_a2a_session(
+        &self,
+        session: &PostgresA2aSessionRow,
+    ) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_a2a_sessions
                  (session_id, sender_id, protocol, protocol_version, enabled_capabilities_json, actor_type, actor_id, actor_role, negotiated_at, expires_at, updated_at)
@@ -1504,22 +1827,21 @@ impl PostgresRuntimeRepository {
                 .bind(dt_to_ms(session.updated_at))
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("upsert a2a session", e))?;
+                .map_err(|e| map_storage_err("upsert a2a session", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    pub fn get_active_a2a_session(
+    pub async fn get_active_a2a_session(
         &self,
         sender_id: &str,
     ) -> Result<Option<PostgresA2aSessionRow>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let sender_id = sender_id.to_string();
         let now_ms = dt_to_ms(Utc::now());
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT session_id, sender_id, protocol, protocol_version, enabled_capabilities_json, actor_type, actor_id, actor_role, negotiated_at, expires_at, updated_at
                  FROM \"{}\".runtime_a2a_sessions
@@ -1531,7 +1853,7 @@ impl PostgresRuntimeRepository {
                 .bind(now_ms)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get active a2a session", e))?;
+                .map_err(|e| map_storage_err("get active a2a session", e))?;
             Ok(row.map(|r| PostgresA2aSessionRow {
                 session_id: r.get(0),
                 sender_id: r.get(1),
@@ -1545,25 +1867,140 @@ impl PostgresRuntimeRepository {
                 expires_at: r.get::<Option<i64>, _>(9).map(ms_to_dt),
                 updated_at: ms_to_dt(r.get(10)),
             }))
-        })
+        }.await
     }
 }
 
-impl RuntimeRepository for PostgresRuntimeRepository {
-    fn list_dispatchable_attempts(
+#[async_trait]
+impl AsyncRuntimeRepository for PostgresRuntimeRepository {
+    async fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        self.ensure_schema().await?;
+        if batch.is_empty() {
+            return Ok(EnqueueBatchOutcome::default());
+        }
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let enqueued_at_ms = dt_to_ms(Utc::now());
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin enqueue attempts tx", e))?;
+            // Report the first attempt whose run is missing, as the foreign key error does
+            // not say which row of a multi-row insert broke it
+            let mut run_ids: Vec<&str> = batch.iter().map(|(_, run_id, _)| run_id.as_str()).collect();
+            run_ids.sort_unstable();
+            run_ids.dedup();
+            let runs_sql = format!(
+                "SELECT run_id FROM \"{}\".runtime_runs
+                 WHERE run_id = ANY($1) AND ($2::TEXT IS NULL OR tenant_id = $2)",
+                schema
+            );
+            let known: std::collections::HashSet<String> = sqlx::query_scalar(&runs_sql)
+                .bind(&run_ids)
+                .bind(&tenant_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("read runs of enqueued attempts", e))?
+                .into_iter()
+                .collect();
+            if let Some((attempt_id, run_id, _)) =
+                batch.iter().find(|(_, run_id, _)| !known.contains(run_id))
+            {
+                return Err(KernelError::NotFound(format!(
+                    "run not found for attempt {}: {}",
+                    attempt_id, run_id
+                )));
+            }
+
+            let mut inserted = 0;
+            for chunk in batch.chunks(ENQUEUE_BATCH_CHUNK_ROWS) {
+                let values = (0..chunk.len())
+                    .map(|i| {
+                        let p = i * 5;
+                        // Attempts belong to their run's tenant
+                        format!(
+                            "(${}, ${}, 1, 'queued', NULL, ${}, ${}, ${}, (SELECT tenant_id FROM \"{}\".runtime_runs WHERE run_id = ${}))",
+                            p + 1,
+                            p + 2,
+                            p + 3,
+                            p + 4,
+                            p + 5,
+                            schema,
+                            p + 2
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let sql = format!(
+                    "INSERT INTO \"{}\".runtime_attempts
+                       (attempt_id, run_id, attempt_no, status, retry_at_ms, priority, enqueued_at_ms, run_at_ms, tenant_id)
+                     VALUES {}
+                     ON CONFLICT(attempt_id) DO NOTHING",
+                    schema, values
+                );
+                let mut query = sqlx::query(&sql);
+                for (attempt_id, run_id, options) in chunk {
+                    query = query
+                        .bind(attempt_id)
+                        .bind(run_id)
+                        .bind(options.priority)
+                        .bind(enqueued_at_ms)
+                        .bind(options.run_at.map(dt_to_ms));
+                }
+                let result = match query.execute(&mut *tx).await {
+                    Ok(result) => result,
+                    Err(e) if is_foreign_key_violation(&e) => {
+                        return Err(KernelError::NotFound(format!(
+                            "run not found for enqueued attempts: {}",
+                            e
+                        )));
+                    }
+                    Err(e) => return Err(map_storage_err("enqueue attempts", e)),
+                };
+                inserted += result.rows_affected() as usize;
+            }
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit enqueue attempts tx", e))?;
+            Ok(EnqueueBatchOutcome {
+                inserted,
+                skipped: batch.len() - inserted,
+            })
+        }.await
+    }
+
+    async fn list_dispatchable_attempts(
         &self,
         now: DateTime<Utc>,
         limit: usize,
     ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.list_dispatchable_attempts_with_options(now, limit, &DispatchOptions::default())
+            .await
+            .map(|listed| listed.attempts)
+    }
+
+    async fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        self.ensure_schema().await?;
 
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let now_ms = dt_to_ms(now);
-        rt.block_on(async move {
+        let max_per_run = options.max_concurrent_per_run.map(|max| max as i64);
+        let tenant_id = self.tenant_id.clone();
+        async move {
             let sql = format!(
-                "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms
+                "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms, a.priority
                  FROM \"{}\".runtime_attempts a
                  LEFT JOIN \"{}\".runtime_leases l
                    ON l.attempt_id = a.attempt_id
@@ -1573,19 +2010,32 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                      a.status = 'queued'
                      OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= $1))
                    )
-                 ORDER BY a.priority DESC, a.attempt_no ASC, a.attempt_id ASC
+                   AND (a.run_at_ms IS NULL OR a.run_at_ms <= $1)
+                   AND (
+                     $3::BIGINT IS NULL
+                     OR (
+                       SELECT COUNT(*)
+                       FROM \"{}\".runtime_leases rl
+                       JOIN \"{}\".runtime_attempts ra ON ra.attempt_id = rl.attempt_id
+                       WHERE ra.run_id = a.run_id AND rl.lease_expires_at_ms >= $1
+                     ) < $3
+                   )
+                   AND ($4::TEXT IS NULL OR a.tenant_id = $4)
+                 ORDER BY a.priority DESC, a.enqueued_at_ms ASC NULLS FIRST, a.attempt_no ASC, a.attempt_id ASC
                  LIMIT $2",
-                schema, schema
+                schema, schema, schema, schema
             );
 
             let rows = sqlx::query(&sql)
                 .bind(now_ms)
                 .bind(limit as i64)
+                .bind(max_per_run)
+                .bind(&tenant_id)
                 .fetch_all(&pool)
                 .await
-                .map_err(|e| map_driver_err("list dispatchable attempts", e))?;
+                .map_err(|e| map_storage_err("list dispatchable attempts", e))?;
 
-            Ok(rows
+            let attempts = rows
                 .into_iter()
                 .map(|row| {
                     let retry_at_ms: Option<i64> = row.get(4);
@@ -1595,19 +2045,237 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                         attempt_no: row.get::<i32, _>(2) as u32,
                         status: parse_attempt_status(row.get::<String, _>(3).as_str()),
                         retry_at: retry_at_ms.map(ms_to_dt),
+                        priority: row.get::<i32, _>(5),
                     }
                 })
-                .collect())
-        })
+                .collect();
+
+            let skipped_for_fairness = match max_per_run {
+                None => 0,
+                Some(max_per_run) => {
+                    let count_sql = format!(
+                        "SELECT COUNT(*)
+                         FROM \"{}\".runtime_attempts a
+                         LEFT JOIN \"{}\".runtime_leases l
+                           ON l.attempt_id = a.attempt_id
+                          AND l.lease_expires_at_ms >= $1
+                         WHERE l.attempt_id IS NULL
+                           AND (
+                             a.status = 'queued'
+                             OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= $1))
+                           )
+                           AND (a.run_at_ms IS NULL OR a.run_at_ms <= $1)
+                           AND (
+                             SELECT COUNT(*)
+                             FROM \"{}\".runtime_leases rl
+                             JOIN \"{}\".runtime_attempts ra ON ra.attempt_id = rl.attempt_id
+                             WHERE ra.run_id = a.run_id AND rl.lease_expires_at_ms >= $1
+                           ) >= $2
+                           AND ($3::TEXT IS NULL OR a.tenant_id = $3)",
+                        schema, schema, schema, schema
+                    );
+                    let skipped: i64 = sqlx::query_scalar(&count_sql)
+                        .bind(now_ms)
+                        .bind(max_per_run)
+                        .bind(&tenant_id)
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| map_storage_err("count attempts held back per run", e))?;
+                    skipped as usize
+                }
+            };
+
+            Ok(DispatchableAttempts {
+                attempts,
+                skipped_for_fairness,
+            })
+        }.await
+    }
+
+    async fn next_dispatch_at(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let now_ms = dt_to_ms(now);
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let sql = format!(
+                "SELECT MIN(ready_at_ms)
+                 FROM (
+                   SELECT GREATEST(
+                            COALESCE(a.run_at_ms, 0),
+                            CASE WHEN a.status = 'retry_backoff' THEN COALESCE(a.retry_at_ms, 0) ELSE 0 END
+                          ) AS ready_at_ms
+                   FROM \"{}\".runtime_attempts a
+                   WHERE a.status IN ('queued', 'retry_backoff')
+                     AND ($2::TEXT IS NULL OR a.tenant_id = $2)
+                 ) pending
+                 WHERE ready_at_ms > $1",
+                schema
+            );
+            let next_ms: Option<i64> = sqlx::query_scalar(&sql)
+                .bind(now_ms)
+                .bind(&tenant_id)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| map_storage_err("next dispatch at", e))?;
+            Ok(next_ms.map(ms_to_dt))
+        }.await
+    }
+
+    async fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let worker_id = worker_id.to_string();
+        let now_ms = dt_to_ms(now);
+        let lease_expires_at = now + lease_ttl;
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin claim attempts tx", e))?;
+            // Rows another worker is claiming stay locked until its transaction ends, and
+            // SKIP LOCKED passes over them instead of waiting to collide on the lease
+            let select_sql = format!(
+                "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms, a.priority, a.tenant_id
+                 FROM \"{}\".runtime_attempts a
+                 LEFT JOIN \"{}\".runtime_leases l
+                   ON l.attempt_id = a.attempt_id
+                  AND l.lease_expires_at_ms >= $1
+                 WHERE l.attempt_id IS NULL
+                   AND (
+                     a.status = 'queued'
+                     OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= $1))
+                   )
+                   AND (a.run_at_ms IS NULL OR a.run_at_ms <= $1)
+                   AND ($3::TEXT IS NULL OR a.tenant_id = $3)
+                 ORDER BY a.priority DESC, a.enqueued_at_ms ASC NULLS FIRST, a.attempt_no ASC, a.attempt_id ASC
+                 LIMIT $2
+                 FOR UPDATE OF a SKIP LOCKED",
+                schema, schema
+            );
+            let rows = sqlx::query(&select_sql)
+                .bind(now_ms)
+                .bind(limit as i64)
+                .bind(&tenant_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("select claimable attempts", e))?;
+            let attempts: Vec<(AttemptDispatchRecord, Option<String>)> = rows
+                .into_iter()
+                .map(|row| {
+                    let retry_at_ms: Option<i64> = row.get(4);
+                    let attempt = AttemptDispatchRecord {
+                        attempt_id: row.get(0),
+                        run_id: row.get(1),
+                        attempt_no: row.get::<i32, _>(2) as u32,
+                        status: parse_attempt_status(row.get::<String, _>(3).as_str()),
+                        retry_at: retry_at_ms.map(ms_to_dt),
+                        priority: row.get::<i32, _>(5),
+                    };
+                    (attempt, row.get(6))
+                })
+                .collect();
+            if attempts.is_empty() {
+                tx.commit()
+                    .await
+                    .map_err(|e| map_storage_err("commit claim attempts tx", e))?;
+                return Ok(Vec::new());
+            }
+
+            let attempt_ids: Vec<&str> = attempts
+                .iter()
+                .map(|(a, _)| a.attempt_id.as_str())
+                .collect();
+            let delete_sql = format!(
+                "DELETE FROM \"{}\".runtime_leases
+                 WHERE attempt_id = ANY($1) AND lease_expires_at_ms < $2",
+                schema
+            );
+            sqlx::query(&delete_sql)
+                .bind(&attempt_ids)
+                .bind(now_ms)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("cleanup expired leases of claimed attempts", e))?;
+            let insert_sql = format!(
+                "INSERT INTO \"{}\".runtime_leases
+                 (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id)
+                 VALUES ($1, $2, $3, $4, $5, 1, $6)",
+                schema
+            );
+            let mut claimed = Vec::with_capacity(attempts.len());
+            for (attempt, attempt_tenant_id) in attempts {
+                let lease_id = format!(
+                    "lease-{}-{}",
+                    attempt.attempt_id,
+                    now.timestamp_nanos_opt().unwrap_or(0)
+                );
+                sqlx::query(&insert_sql)
+                    .bind(&lease_id)
+                    .bind(&attempt.attempt_id)
+                    .bind(&worker_id)
+                    .bind(dt_to_ms(lease_expires_at))
+                    .bind(now_ms)
+                    .bind(&attempt_tenant_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_storage_err("insert claimed lease", e))?;
+                claimed.push(ClaimedAttempt {
+                    lease: LeaseRecord {
+                        lease_id,
+                        attempt_id: attempt.attempt_id.clone(),
+                        worker_id: worker_id.clone(),
+                        lease_expires_at,
+                        heartbeat_at: now,
+                        version: 1,
+                        terminal_state: None,
+                        terminal_at: None,
+                        tenant_id: attempt_tenant_id,
+                    },
+                    attempt,
+                });
+            }
+            let claimed_ids: Vec<&str> = claimed
+                .iter()
+                .map(|c| c.attempt.attempt_id.as_str())
+                .collect();
+            let update_sql = format!(
+                "UPDATE \"{}\".runtime_attempts SET status = 'leased' WHERE attempt_id = ANY($1)",
+                schema
+            );
+            sqlx::query(&update_sql)
+                .bind(&claimed_ids)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("mark claimed attempts leased", e))?;
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit claim attempts tx", e))?;
+            Ok(claimed)
+        }.await
     }
 
-    fn upsert_lease(
+    async fn upsert_lease(
         &self,
         attempt_id: &str,
         worker_id: &str,
         lease_expires_at: DateTime<Utc>,
     ) -> Result<LeaseRecord, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
 
         let now = Utc::now();
         let now_ms = dt_to_ms(now);
@@ -1618,42 +2286,48 @@ impl RuntimeRepository for PostgresRuntimeRepository {
             now.timestamp_nanos_opt().unwrap_or(0)
         );
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let attempt_id = attempt_id.to_string();
         let worker_id = worker_id.to_string();
         let lease_id_out = lease_id.clone();
+        let scope = self.tenant_id.clone();
 
-        rt.block_on(async move {
+        async move {
             let mut tx = pool
                 .begin()
                 .await
-                .map_err(|e| map_driver_err("begin upsert lease tx", e))?;
+                .map_err(|e| map_storage_err("begin upsert lease tx", e))?;
 
             // Serialize lease ownership change for one attempt to avoid split-brain races.
             sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
                 .bind(&attempt_id)
                 .execute(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("advisory lock attempt", e))?;
+                .map_err(|e| map_storage_err("advisory lock attempt", e))?;
 
             let attempt_status_sql = format!(
-                "SELECT status FROM \"{}\".runtime_attempts WHERE attempt_id = $1 FOR UPDATE",
+                "SELECT status, tenant_id FROM \"{}\".runtime_attempts WHERE attempt_id = $1 FOR UPDATE",
                 schema
             );
-            let attempt_status: Option<String> = sqlx::query_scalar(&attempt_status_sql)
+            let attempt_row: Option<(String, Option<String>)> = sqlx::query_as(&attempt_status_sql)
                 .bind(&attempt_id)
                 .fetch_optional(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("read attempt status", e))?;
-            let Some(status) = attempt_status else {
-                return Err(KernelError::Driver(format!(
+                .map_err(|e| map_storage_err("read attempt status", e))?;
+            let Some((status, tenant_id)) = attempt_row else {
+                return Err(KernelError::NotDispatchable(format!(
                     "attempt is not dispatchable for lease: {}",
                     attempt_id
                 )));
             };
+            if scope.is_some() && tenant_id != scope {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found: {}",
+                    attempt_id
+                )));
+            }
             if status != "queued" && status != "retry_backoff" {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::NotDispatchable(format!(
                     "attempt is not dispatchable for lease: {}",
                     attempt_id
                 )));
@@ -1669,12 +2343,12 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(now_ms)
                 .execute(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("cleanup expired lease", e))?;
+                .map_err(|e| map_storage_err("cleanup expired lease", e))?;
 
             let insert_sql = format!(
                 "INSERT INTO \"{}\".runtime_leases
-                 (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version)
-                 VALUES ($1, $2, $3, $4, $5, 1)",
+                 (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id)
+                 VALUES ($1, $2, $3, $4, $5, 1, $6)",
                 schema
             );
             match sqlx::query(&insert_sql)
@@ -1683,17 +2357,18 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(&worker_id)
                 .bind(lease_expires_at_ms)
                 .bind(now_ms)
+                .bind(&tenant_id)
                 .execute(&mut *tx)
                 .await
             {
                 Ok(_) => {}
                 Err(e) if is_unique_violation(&e) => {
-                    return Err(KernelError::Driver(format!(
+                    return Err(KernelError::LeaseConflict(format!(
                         "active lease already exists for attempt: {}",
                         attempt_id
                     )));
                 }
-                Err(e) => return Err(map_driver_err("insert lease", e)),
+                Err(e) => return Err(map_storage_err("insert lease", e)),
             }
 
             let update_sql = format!(
@@ -1706,137 +2381,616 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(&attempt_id)
                 .execute(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("mark leased status", e))?;
+                .map_err(|e| map_storage_err("mark leased status", e))?;
 
             let version_sql = format!(
                 "SELECT version FROM \"{}\".runtime_leases WHERE attempt_id = $1",
                 schema
             );
-            let version: i64 = sqlx::query_scalar(&version_sql)
+            let version: i64 = sqlx::query_scalar(&version_sql)
+                .bind(&attempt_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("read lease version", e))?;
+
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit upsert lease tx", e))?;
+            Ok(LeaseRecord {
+                lease_id: lease_id_out,
+                attempt_id,
+                worker_id,
+                lease_expires_at,
+                heartbeat_at: now,
+                version: version as u64,
+                terminal_state: None,
+                terminal_at: None,
+                tenant_id,
+            })
+        }
+        .await
+    }
+
+    async fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let lease_id = lease_id.to_string();
+        let heartbeat_at_ms = dt_to_ms(heartbeat_at);
+        let lease_expires_at_ms = dt_to_ms(lease_expires_at);
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let sql = format!(
+                "UPDATE \"{}\".runtime_leases l
+                 SET heartbeat_at_ms = $2, lease_expires_at_ms = $3, version = version + 1
+                 WHERE lease_id = $1 AND ($4::TEXT IS NULL OR l.tenant_id = $4)
+                 RETURNING (SELECT a.cancel_requested_at_ms IS NOT NULL
+                            FROM \"{}\".runtime_attempts a
+                            WHERE a.attempt_id = l.attempt_id)",
+                schema, schema
+            );
+            let Some(row) = sqlx::query(&sql)
+                .bind(&lease_id)
+                .bind(heartbeat_at_ms)
+                .bind(lease_expires_at_ms)
+                .bind(&tenant_id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| map_storage_err("heartbeat lease", e))?
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "lease not found for heartbeat: {}",
+                    lease_id
+                )));
+            };
+            Ok(lease_directive(row.get(0)))
+        }
+        .await
+    }
+
+    async fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let lease_id = lease_id.to_string();
+        let worker_id = worker_id.to_string();
+        let expected_version = expected_version as i64;
+        let heartbeat_at_ms = dt_to_ms(heartbeat_at);
+        let lease_expires_at_ms = dt_to_ms(lease_expires_at);
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let sql = format!(
+                "UPDATE \"{}\".runtime_leases l
+                 SET heartbeat_at_ms = $4, lease_expires_at_ms = $5, version = version + 1
+                 WHERE lease_id = $1 AND worker_id = $2 AND version = $3
+                   AND ($6::TEXT IS NULL OR l.tenant_id = $6)
+                 RETURNING (SELECT a.cancel_requested_at_ms IS NOT NULL
+                            FROM \"{}\".runtime_attempts a
+                            WHERE a.attempt_id = l.attempt_id)",
+                schema, schema
+            );
+            let Some(row) = sqlx::query(&sql)
+                .bind(&lease_id)
+                .bind(&worker_id)
+                .bind(expected_version)
+                .bind(heartbeat_at_ms)
+                .bind(lease_expires_at_ms)
+                .bind(&tenant_id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| map_storage_err("heartbeat lease with version", e))?
+            else {
+                return Err(KernelError::LeaseConflict(format!(
+                    "lease heartbeat version conflict for lease: {}",
+                    lease_id
+                )));
+            };
+            Ok(lease_directive(row.get(0)))
+        }
+        .await
+    }
+
+    async fn expire_leases_and_requeue(
+        &self,
+        stale_before: DateTime<Utc>,
+    ) -> Result<u64, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let stale_before_ms = dt_to_ms(stale_before);
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin expire/requeue tx", e))?;
+
+            // Delete first and use RETURNING as the authoritative expired-attempt set.
+            let delete_sql = format!(
+                "DELETE FROM \"{}\".runtime_leases
+                 WHERE lease_expires_at_ms < $1 AND ($2::TEXT IS NULL OR tenant_id = $2)
+                 RETURNING attempt_id",
+                schema
+            );
+            let deleted_rows = sqlx::query(&delete_sql)
+                .bind(stale_before_ms)
+                .bind(&tenant_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("delete expired leases", e))?;
+            let attempt_ids: Vec<String> = deleted_rows.into_iter().map(|r| r.get(0)).collect();
+
+            for attempt_id in &attempt_ids {
+                // An attempt asked to cancel is not handed to another worker
+                let requeue_sql = format!(
+                    "UPDATE \"{}\".runtime_attempts
+                     SET status = CASE
+                       WHEN cancel_requested_at_ms IS NOT NULL THEN 'cancelled'
+                       ELSE 'queued'
+                     END
+                     WHERE attempt_id = $1
+                       AND status NOT IN ('completed', 'failed', 'cancelled')",
+                    schema
+                );
+                sqlx::query(&requeue_sql)
+                    .bind(attempt_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_storage_err("requeue attempt", e))?;
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit expire/requeue tx", e))?;
+            Ok(attempt_ids.len() as u64)
+        }
+        .await
+    }
+
+    async fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.finish_attempt_under_lease(attempt_id, None, status, now)
+            .await
+    }
+
+    async fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.finish_attempt_under_lease(attempt_id, Some(lease), status, now)
+            .await
+    }
+
+    async fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, None, error, retry_policy, now)
+            .await
+    }
+
+    async fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, Some(lease), error, retry_policy, now)
+            .await
+    }
+
+    async fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let attempt_id = attempt_id.to_string();
+        let now_ms = dt_to_ms(Utc::now());
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_storage_err("begin cancel attempt tx", e))?;
+            let select_sql = format!(
+                "SELECT status FROM \"{}\".runtime_attempts
+                 WHERE attempt_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)
+                 FOR UPDATE",
+                schema
+            );
+            let Some(row) = sqlx::query(&select_sql)
+                .bind(&attempt_id)
+                .bind(&tenant_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("read attempt for cancel", e))?
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found for cancel: {}",
+                    attempt_id
+                )));
+            };
+            let outcome = match parse_attempt_status(&row.get::<String, _>(0)) {
+                AttemptExecutionStatus::Queued | AttemptExecutionStatus::RetryBackoff => {
+                    let update_sql = format!(
+                        "UPDATE \"{}\".runtime_attempts
+                         SET status = 'cancelled', retry_at_ms = NULL, cancel_requested_at_ms = $2
+                         WHERE attempt_id = $1",
+                        schema
+                    );
+                    sqlx::query(&update_sql)
+                        .bind(&attempt_id)
+                        .bind(now_ms)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| map_storage_err("cancel queued attempt", e))?;
+                    AttemptCancellation::Cancelled
+                }
+                AttemptExecutionStatus::Leased | AttemptExecutionStatus::Running => {
+                    let update_sql = format!(
+                        "UPDATE \"{}\".runtime_attempts
+                         SET cancel_requested_at_ms = COALESCE(cancel_requested_at_ms, $2)
+                         WHERE attempt_id = $1",
+                        schema
+                    );
+                    sqlx::query(&update_sql)
+                        .bind(&attempt_id)
+                        .bind(now_ms)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| map_storage_err("request attempt cancel", e))?;
+                    AttemptCancellation::Requested
+                }
+                finished => AttemptCancellation::AlreadyFinished(finished),
+            };
+            tx.commit()
+                .await
+                .map_err(|e| map_storage_err("commit cancel attempt tx", e))?;
+            Ok(outcome)
+        }
+        .await
+    }
+
+    async fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let sql = format!(
+                "SELECT attempt_id, run_id, attempt_no, last_error, dead_lettered_at_ms
+                 FROM \"{}\".runtime_attempts
+                 WHERE status = 'dead_letter' AND ($2::TEXT IS NULL OR tenant_id = $2)
+                 ORDER BY dead_lettered_at_ms DESC, attempt_id ASC
+                 LIMIT $1",
+                schema
+            );
+            let rows = sqlx::query(&sql)
+                .bind(limit as i64)
+                .bind(&tenant_id)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| map_storage_err("list dead letter attempts", e))?;
+            Ok(rows
+                .into_iter()
+                .map(|row| DeadLetterAttemptRecord {
+                    attempt_id: row.get(0),
+                    run_id: row.get(1),
+                    attempt_no: row.get::<i32, _>(2) as u32,
+                    error: row.get(3),
+                    dead_lettered_at: ms_to_dt(row.get::<Option<i64>, _>(4).unwrap_or_default()),
+                })
+                .collect())
+        }
+        .await
+    }
+
+    async fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
+
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let attempt_id = attempt_id.to_string();
+        let tenant_id = self.tenant_id.clone();
+
+        async move {
+            let update_sql = format!(
+                "UPDATE \"{}\".runtime_attempts
+                 SET status = 'queued', retry_at_ms = NULL, dead_lettered_at_ms = NULL
+                 WHERE attempt_id = $1 AND status = 'dead_letter'
+                   AND ($2::TEXT IS NULL OR tenant_id = $2)",
+                schema
+            );
+            let updated = sqlx::query(&update_sql)
+                .bind(&attempt_id)
+                .bind(&tenant_id)
+                .execute(&pool)
+                .await
+                .map_err(|e| map_storage_err("requeue dead letter attempt", e))?
+                .rows_affected();
+            if updated > 0 {
+                return Ok(());
+            }
+            let status_sql = format!(
+                "SELECT status FROM \"{}\".runtime_attempts
+                 WHERE attempt_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)",
+                schema
+            );
+            let status = sqlx::query_scalar::<_, String>(&status_sql)
                 .bind(&attempt_id)
-                .fetch_one(&mut *tx)
-                .await
-                .map_err(|e| map_driver_err("read lease version", e))?;
-
-            tx.commit()
+                .bind(&tenant_id)
+                .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("commit upsert lease tx", e))?;
-            Ok(LeaseRecord {
-                lease_id: lease_id_out,
-                attempt_id,
-                worker_id,
-                lease_expires_at,
-                heartbeat_at: now,
-                version: version as u64,
-                terminal_state: None,
-                terminal_at: None,
+                .map_err(|e| map_storage_err("read attempt for requeue", e))?;
+            Err(match status {
+                Some(status) => KernelError::Conflict(format!(
+                    "attempt {} is {}, not dead-lettered",
+                    attempt_id, status
+                )),
+                None => {
+                    KernelError::NotFound(format!("attempt not found for requeue: {}", attempt_id))
+                }
             })
-        })
+        }
+        .await
     }
 
-    fn heartbeat_lease(
-        &self,
-        lease_id: &str,
-        heartbeat_at: DateTime<Utc>,
-        lease_expires_at: DateTime<Utc>,
-    ) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        self.ensure_schema().await?;
 
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        let lease_id = lease_id.to_string();
-        let heartbeat_at_ms = dt_to_ms(heartbeat_at);
-        let lease_expires_at_ms = dt_to_ms(lease_expires_at);
+        let tenant_id = self.tenant_id.clone();
+        let now_ms = dt_to_ms(now);
 
-        rt.block_on(async move {
+        async move {
             let sql = format!(
-                "UPDATE \"{}\".runtime_leases
-                 SET heartbeat_at_ms = $2, lease_expires_at_ms = $3, version = version + 1
-                 WHERE lease_id = $1",
+                "SELECT a.status,
+                        COUNT(*),
+                        MIN(CASE WHEN a.status = 'queued' THEN a.enqueued_at_ms END),
+                        COUNT(*) FILTER (WHERE a.status = 'retry_backoff'
+                                           AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= $1)),
+                        (SELECT COUNT(*) FROM \"{}\".runtime_leases
+                         WHERE lease_expires_at_ms >= $1 AND ($2::TEXT IS NULL OR tenant_id = $2))
+                 FROM \"{}\".runtime_attempts a
+                 WHERE ($2::TEXT IS NULL OR a.tenant_id = $2)
+                 GROUP BY a.status",
+                schema, schema
+            );
+            let rows = sqlx::query(&sql)
+                .bind(now_ms)
+                .bind(&tenant_id)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| map_storage_err("query runtime stats", e))?;
+            let mut stats = RuntimeStats::default();
+            for row in rows {
+                let status: String = row.get(0);
+                stats
+                    .attempts
+                    .add(&parse_attempt_status(&status), row.get::<i64, _>(1) as u64);
+                if let Some(enqueued_ms) = row.get::<Option<i64>, _>(2) {
+                    stats.oldest_queued_age_ms = Some((now_ms - enqueued_ms).max(0));
+                }
+                stats.retry_backlog += row.get::<i64, _>(3) as u64;
+                stats.active_leases = row.get::<i64, _>(4) as u64;
+            }
+            Ok(stats)
+        }
+        .await
+    }
+
+    async fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
+        Ok(0)
+    }
+
+    // ============== Run Methods ==============
+
+    async fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        let tenant_id = run_tenant_in_scope(run, self.tenant_id())?;
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let run = run.clone();
+        async move {
+            let sql = format!(
+                "INSERT INTO \"{}\".runtime_runs
+                 (run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
                 schema
             );
-            let updated = sqlx::query(&sql)
-                .bind(&lease_id)
-                .bind(heartbeat_at_ms)
-                .bind(lease_expires_at_ms)
+            match sqlx::query(&sql)
+                .bind(&run.run_id)
+                .bind(&run.workflow_name)
+                .bind(run.status.as_str())
+                .bind(&run.status_reason)
+                .bind(dt_to_ms(run.created_at))
+                .bind(dt_to_ms(run.updated_at))
+                .bind(&tenant_id)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("heartbeat lease", e))?
-                .rows_affected();
-            if updated == 0 {
-                return Err(KernelError::Driver(format!(
-                    "lease not found for heartbeat: {}",
-                    lease_id
-                )));
+            {
+                Ok(_) => Ok(()),
+                Err(e) if is_unique_violation(&e) => Err(KernelError::Conflict(format!(
+                    "run already exists: {}",
+                    run.run_id
+                ))),
+                Err(e) => Err(map_storage_err("create run", e)),
             }
-            Ok(())
-        })
+        }
+        .await
     }
 
-    fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError> {
-        self.ensure_schema()?;
-
+    async fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
-        let stale_before_ms = dt_to_ms(stale_before);
+        let run_id = run_id.clone();
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let sql = format!(
+                "SELECT run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id
+                 FROM \"{}\".runtime_runs
+                 WHERE run_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)",
+                schema
+            );
+            let row = sqlx::query(&sql)
+                .bind(&run_id)
+                .bind(&tenant_id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| map_storage_err("get run", e))?;
+            Ok(row.map(|r| map_row_to_run_record(&r)))
+        }
+        .await
+    }
 
-        rt.block_on(async move {
+    async fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let run_id = run_id.clone();
+        let reason = reason.map(str::to_string);
+        let tenant_id = self.tenant_id.clone();
+        async move {
             let mut tx = pool
                 .begin()
                 .await
-                .map_err(|e| map_driver_err("begin expire/requeue tx", e))?;
-
-            // Delete first and use RETURNING as the authoritative expired-attempt set.
-            let delete_sql = format!(
-                "DELETE FROM \"{}\".runtime_leases
-                 WHERE lease_expires_at_ms < $1
-                 RETURNING attempt_id",
+                .map_err(|e| map_storage_err("begin update run status", e))?;
+            let sql_current = format!(
+                "SELECT status FROM \"{}\".runtime_runs
+                 WHERE run_id = $1 AND ($2::TEXT IS NULL OR tenant_id = $2)
+                 FOR UPDATE",
                 schema
             );
-            let deleted_rows = sqlx::query(&delete_sql)
-                .bind(stale_before_ms)
-                .fetch_all(&mut *tx)
+            let current: Option<String> = sqlx::query_scalar(&sql_current)
+                .bind(&run_id)
+                .bind(&tenant_id)
+                .fetch_optional(&mut *tx)
                 .await
-                .map_err(|e| map_driver_err("delete expired leases", e))?;
-            let attempt_ids: Vec<String> = deleted_rows.into_iter().map(|r| r.get(0)).collect();
-
-            for attempt_id in &attempt_ids {
-                let requeue_sql = format!(
-                    "UPDATE \"{}\".runtime_attempts
-                     SET status = 'queued'
-                     WHERE attempt_id = $1
-                       AND status NOT IN ('completed', 'failed', 'cancelled')",
-                    schema
-                );
-                sqlx::query(&requeue_sql)
-                    .bind(attempt_id)
-                    .execute(&mut *tx)
-                    .await
-                    .map_err(|e| map_driver_err("requeue attempt", e))?;
+                .map_err(|e| map_storage_err("read run status", e))?;
+            let Some(current) = current.map(|s| RunRuntimeStatus::from_str(&s)) else {
+                return Err(KernelError::NotFound(format!(
+                    "run not found for status update: {}",
+                    run_id
+                )));
+            };
+            if current.is_final() && current != status {
+                return Err(KernelError::Conflict(format!(
+                    "run {} is already {}",
+                    run_id,
+                    current.as_str()
+                )));
             }
-
+            let sql_update = format!(
+                "UPDATE \"{}\".runtime_runs
+                 SET status = $2, status_reason = $3, updated_at_ms = $4
+                 WHERE run_id = $1",
+                schema
+            );
+            sqlx::query(&sql_update)
+                .bind(&run_id)
+                .bind(status.as_str())
+                .bind(&reason)
+                .bind(dt_to_ms(Utc::now()))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| map_storage_err("update run status", e))?;
             tx.commit()
                 .await
-                .map_err(|e| map_driver_err("commit expire/requeue tx", e))?;
-            Ok(attempt_ids.len() as u64)
-        })
+                .map_err(|e| map_storage_err("commit update run status", e))?;
+            Ok(())
+        }
+        .await
     }
 
-    fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
-        Ok(0)
+    async fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let filter = filter.clone();
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let sql = format!(
+                "SELECT run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id
+                 FROM \"{}\".runtime_runs
+                 WHERE ($1::TEXT IS NULL OR status = $1)
+                   AND ($2::TEXT IS NULL OR workflow_name = $2)
+                   AND ($3::BIGINT IS NULL OR created_at_ms >= $3)
+                   AND ($4::BIGINT IS NULL OR created_at_ms < $4)
+                   AND ($7::TEXT IS NULL OR tenant_id = $7)
+                 ORDER BY updated_at_ms DESC, run_id ASC
+                 LIMIT $5 OFFSET $6",
+                schema
+            );
+            let rows = sqlx::query(&sql)
+                .bind(filter.status.as_ref().map(|s| s.as_str().to_string()))
+                .bind(&filter.workflow_name)
+                .bind(filter.created_after.map(dt_to_ms))
+                .bind(filter.created_before.map(dt_to_ms))
+                .bind(page.limit as i64)
+                .bind(page.offset as i64)
+                .bind(&tenant_id)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| map_storage_err("list runs", e))?;
+            Ok(rows.iter().map(map_row_to_run_record).collect())
+        }
+        .await
     }
 
     // ============== Bounty Methods ==============
 
-    fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty = bounty.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_bounties
                  (bounty_id, title, description, reward, status, created_by, created_at_ms, closed_at_ms, accepted_by, accepted_at_ms)
@@ -1865,14 +3019,15 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(bounty.accepted_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("upsert bounty", e))?;
+                .map_err(|e| map_storage_err("upsert bounty", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
-        Ok(
-            PostgresRuntimeRepository::get_bounty(self, bounty_id)?.map(|row| BountyRecord {
+    async fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
+        Ok(PostgresRuntimeRepository::get_bounty(self, bounty_id)
+            .await?
+            .map(|row| BountyRecord {
                 bounty_id: row.bounty_id,
                 title: row.title,
                 description: row.description,
@@ -1883,21 +3038,19 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 closed_at_ms: row.closed_at.map(dt_to_ms),
                 accepted_by: row.accepted_by,
                 accepted_at_ms: row.accepted_at.map(dt_to_ms),
-            }),
-        )
+            }))
     }
 
-    fn list_bounties(
+    async fn list_bounties(
         &self,
         status: Option<&str>,
         limit: usize,
     ) -> Result<Vec<BountyRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let status = status.map(String::from);
-        rt.block_on(async move {
+        async move {
             let (sql, bind_status) = if status.is_some() {
                 (
                     format!(
@@ -1928,13 +3081,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     .bind(limit as i64)
                     .fetch_all(&pool)
                     .await
-                    .map_err(|e| map_driver_err("list bounties", e))?
+                    .map_err(|e| map_storage_err("list bounties", e))?
             } else {
                 sqlx::query(&sql)
                     .bind(limit as i64)
                     .fetch_all(&pool)
                     .await
-                    .map_err(|e| map_driver_err("list bounties", e))?
+                    .map_err(|e| map_storage_err("list bounties", e))?
             };
             Ok(rows
                 .into_iter()
@@ -1951,14 +3104,15 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     accepted_at_ms: r.get(9),
                 })
                 .collect())
-        })
+        }.await
     }
 
-    fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
+    async fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
         let accepted =
-            PostgresRuntimeRepository::accept_bounty(self, bounty_id, accepted_by, Utc::now())?;
+            PostgresRuntimeRepository::accept_bounty(self, bounty_id, accepted_by, Utc::now())
+                .await?;
         if !accepted {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "bounty not found or not in open status: {}",
                 bounty_id
             )));
@@ -1966,14 +3120,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
         Ok(())
     }
 
-    fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
         let now_ms = dt_to_ms(Utc::now());
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_bounties
                  SET status = 'closed', closed_at_ms = $2
@@ -1986,27 +3139,27 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(now_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("close bounty", e))?
+                .map_err(|e| map_storage_err("close bounty", e))?
                 .rows_affected();
             if updated == 0 {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::NotFound(format!(
                     "bounty not found or already closed: {}",
                     bounty_id
                 )));
             }
             Ok(())
-        })
+        }
+        .await
     }
 
     // ============== Swarm Methods ==============
 
-    fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let task = task.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_swarm_tasks
                  (parent_task_id, decomposition_json, proposer_id, proposer_reward_pct, solver_reward_pct, aggregator_reward_pct, status, created_at_ms, completed_at_ms)
@@ -2030,18 +3183,19 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(task.completed_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("upsert swarm decomposition", e))?;
+                .map_err(|e| map_storage_err("upsert swarm decomposition", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    fn get_swarm_decomposition(
+    async fn get_swarm_decomposition(
         &self,
         parent_task_id: &str,
     ) -> Result<Option<SwarmTaskRecord>, KernelError> {
         Ok(
-            PostgresRuntimeRepository::get_swarm_task(self, parent_task_id)?.map(|row| {
-                SwarmTaskRecord {
+            PostgresRuntimeRepository::get_swarm_task(self, parent_task_id)
+                .await?
+                .map(|row| SwarmTaskRecord {
                     parent_task_id: row.parent_task_id,
                     decomposition_json: row.decomposition_json,
                     proposer_id: row.proposer_id,
@@ -2051,14 +3205,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     status: row.status,
                     created_at_ms: dt_to_ms(row.created_at),
                     completed_at_ms: row.completed_at.map(dt_to_ms),
-                }
-            }),
+                }),
         )
     }
 
     // ============== Worker Methods ==============
 
-    fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
+    async fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
         PostgresRuntimeRepository::upsert_worker_registration(
             self,
             &worker.worker_id,
@@ -2067,14 +3220,16 @@ impl RuntimeRepository for PostgresRuntimeRepository {
             worker.metadata_json.as_deref(),
             &worker.status,
             ms_to_dt(worker.registered_at_ms),
-        )?;
+        )
+        .await?;
         Ok(())
     }
 
-    fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
+    async fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
         Ok(
-            PostgresRuntimeRepository::get_worker_registration(self, worker_id)?.map(|row| {
-                WorkerRecord {
+            PostgresRuntimeRepository::get_worker_registration(self, worker_id)
+                .await?
+                .map(|row| WorkerRecord {
                     worker_id: row.worker_id,
                     domains: row.domains_json,
                     max_load: row.max_load,
@@ -2082,24 +3237,22 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     registered_at_ms: dt_to_ms(row.registered_at),
                     last_heartbeat_ms: row.last_heartbeat_at.map(dt_to_ms),
                     status: row.status,
-                }
-            }),
+                }),
         )
     }
 
-    fn list_workers(
+    async fn list_workers(
         &self,
         domain: Option<&str>,
         status: Option<&str>,
         limit: usize,
     ) -> Result<Vec<WorkerRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let domain = domain.map(String::from);
         let status = status.map(String::from);
-        rt.block_on(async move {
+        async move {
             let sql = match (domain.is_some(), status.is_some()) {
                 (true, true) => format!(
                     "SELECT worker_id, domains, max_load, metadata_json, registered_at_ms, last_heartbeat_ms, status
@@ -2142,7 +3295,7 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                         .bind(limit as i64)
                         .fetch_all(&pool)
                         .await
-                        .map_err(|e| map_driver_err("list workers", e))?
+                        .map_err(|e| map_storage_err("list workers", e))?
                 }
                 (Some(d), None) => {
                     let like = format!("%{}%", d);
@@ -2151,7 +3304,7 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                         .bind(limit as i64)
                         .fetch_all(&pool)
                         .await
-                        .map_err(|e| map_driver_err("list workers", e))?
+                        .map_err(|e| map_storage_err("list workers", e))?
                 }
                 (None, Some(s)) => {
                     sqlx::query(&sql)
@@ -2159,14 +3312,14 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                         .bind(limit as i64)
                         .fetch_all(&pool)
                         .await
-                        .map_err(|e| map_driver_err("list workers", e))?
+                        .map_err(|e| map_storage_err("list workers", e))?
                 }
                 (None, None) => {
                     sqlx::query(&sql)
                         .bind(limit as i64)
                         .fetch_all(&pool)
                         .await
-                        .map_err(|e| map_driver_err("list workers", e))?
+                        .map_err(|e| map_storage_err("list workers", e))?
                 }
             };
             Ok(rows
@@ -2181,16 +3334,19 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     status: r.get(6),
                 })
                 .collect())
-        })
+        }.await
     }
 
-    fn heartbeat_worker(&self, worker_id: &str, heartbeat_at_ms: i64) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn heartbeat_worker(
+        &self,
+        worker_id: &str,
+        heartbeat_at_ms: i64,
+    ) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let worker_id = worker_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "UPDATE \"{}\".runtime_workers_registry
                  SET last_heartbeat_ms = $2, status = 'active'
@@ -2202,21 +3358,22 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(heartbeat_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("heartbeat worker", e))?
+                .map_err(|e| map_storage_err("heartbeat worker", e))?
                 .rows_affected();
             if updated == 0 {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::NotFound(format!(
                     "worker not found: {}",
                     worker_id
                 )));
             }
             Ok(())
-        })
+        }
+        .await
     }
 
     // ============== Recipe Methods ==============
 
-    fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
+    async fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
         PostgresRuntimeRepository::create_recipe(
             self,
             &PostgresRecipeRow {
@@ -2231,11 +3388,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 is_public: recipe.is_public,
             },
         )
+        .await
     }
 
-    fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
-        Ok(
-            PostgresRuntimeRepository::get_recipe(self, recipe_id)?.map(|row| RecipeRecord {
+    async fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
+        Ok(PostgresRuntimeRepository::get_recipe(self, recipe_id)
+            .await?
+            .map(|row| RecipeRecord {
                 recipe_id: row.recipe_id,
                 name: row.name,
                 description: row.description,
@@ -2245,17 +3404,16 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 created_at_ms: dt_to_ms(row.created_at),
                 updated_at_ms: dt_to_ms(row.updated_at),
                 is_public: row.is_public,
-            }),
-        )
+            }))
     }
 
-    fn fork_recipe(
+    async fn fork_recipe(
         &self,
         original_id: &str,
         new_id: &str,
         new_author: &str,
     ) -> Result<Option<RecipeRecord>, KernelError> {
-        let Some(original) = RuntimeRepository::get_recipe(self, original_id)? else {
+        let Some(original) = AsyncRuntimeRepository::get_recipe(self, original_id).await? else {
             return Ok(None);
         };
         let now_ms = dt_to_ms(Utc::now());
@@ -2270,21 +3428,20 @@ impl RuntimeRepository for PostgresRuntimeRepository {
             updated_at_ms: now_ms,
             is_public: original.is_public,
         };
-        RuntimeRepository::create_recipe(self, &forked)?;
+        AsyncRuntimeRepository::create_recipe(self, &forked).await?;
         Ok(Some(forked))
     }
 
-    fn list_recipes(
+    async fn list_recipes(
         &self,
         author_id: Option<&str>,
         limit: usize,
     ) -> Result<Vec<RecipeRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let author_id = author_id.map(String::from);
-        rt.block_on(async move {
+        async move {
             let (sql, bind_author) = if author_id.is_some() {
                 (
                     format!(
@@ -2315,13 +3472,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     .bind(limit as i64)
                     .fetch_all(&pool)
                     .await
-                    .map_err(|e| map_driver_err("list recipes", e))?
+                    .map_err(|e| map_storage_err("list recipes", e))?
             } else {
                 sqlx::query(&sql)
                     .bind(limit as i64)
                     .fetch_all(&pool)
                     .await
-                    .map_err(|e| map_driver_err("list recipes", e))?
+                    .map_err(|e| map_storage_err("list recipes", e))?
             };
             Ok(rows
                 .into_iter()
@@ -2337,12 +3494,12 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     is_public: r.get::<i32, _>(8) != 0,
                 })
                 .collect())
-        })
+        }.await
     }
 
     // ============== Organism Methods ==============
 
-    fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
+    async fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
         PostgresRuntimeRepository::create_organism(
             self,
             &PostgresOrganismRow {
@@ -2355,11 +3512,13 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 completed_at: organism.completed_at_ms.map(ms_to_dt),
             },
         )
+        .await
     }
 
-    fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
-        Ok(
-            PostgresRuntimeRepository::get_organism(self, organism_id)?.map(|row| OrganismRecord {
+    async fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
+        Ok(PostgresRuntimeRepository::get_organism(self, organism_id)
+            .await?
+            .map(|row| OrganismRecord {
                 organism_id: row.organism_id,
                 recipe_id: row.recipe_id,
                 status: row.status,
@@ -2367,11 +3526,10 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 total_steps: row.total_steps,
                 created_at_ms: dt_to_ms(row.created_at),
                 completed_at_ms: row.completed_at.map(dt_to_ms),
-            }),
-        )
+            }))
     }
 
-    fn update_organism(
+    async fn update_organism(
         &self,
         organism_id: &str,
         current_step: i32,
@@ -2382,9 +3540,10 @@ impl RuntimeRepository for PostgresRuntimeRepository {
             organism_id,
             status,
             current_step,
-        )?;
+        )
+        .await?;
         if !updated {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "organism not found: {}",
                 organism_id
             )));
@@ -2394,13 +3553,12 @@ impl RuntimeRepository for PostgresRuntimeRepository {
 
     // ============== Session Methods ==============
 
-    fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let session = session.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_collab_sessions
                  (session_id, session_type, creator_id, status, created_at_ms, ended_at_ms)
@@ -2416,18 +3574,18 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(session.ended_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("create session", e))?;
+                .map_err(|e| map_storage_err("create session", e))?;
             Ok(())
-        })
+        }
+        .await
     }
 
-    fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
-        self.ensure_schema()?;
+    async fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let session_id = session_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT session_id, session_type, creator_id, status, created_at_ms, ended_at_ms
                  FROM \"{}\".runtime_collab_sessions
@@ -2438,7 +3596,7 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(&session_id)
                 .fetch_optional(&pool)
                 .await
-                .map_err(|e| map_driver_err("get session", e))?;
+                .map_err(|e| map_storage_err("get session", e))?;
             Ok(row.map(|r| SessionRecord {
                 session_id: r.get(0),
                 session_type: r.get(1),
@@ -2447,16 +3605,16 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 created_at_ms: r.get(4),
                 ended_at_ms: r.get(5),
             }))
-        })
+        }
+        .await
     }
 
-    fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let message = message.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_collab_messages
                  (message_id, session_id, sender_id, content, message_type, sent_at_ms)
@@ -2472,22 +3630,22 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(message.sent_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("add session message", e))?;
+                .map_err(|e| map_storage_err("add session message", e))?;
             Ok(())
-        })
+        }
+        .await
     }
 
-    fn get_session_history(
+    async fn get_session_history(
         &self,
         session_id: &str,
         limit: usize,
     ) -> Result<Vec<SessionMessageRecord>, KernelError> {
-        self.ensure_schema()?;
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let session_id = session_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT message_id, session_id, sender_id, content, message_type, sent_at_ms
                  FROM \"{}\".runtime_collab_messages
@@ -2501,7 +3659,7 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(limit as i64)
                 .fetch_all(&pool)
                 .await
-                .map_err(|e| map_driver_err("get session history", e))?;
+                .map_err(|e| map_storage_err("get session history", e))?;
             Ok(rows
                 .into_iter()
                 .map(|r| SessionMessageRecord {
@@ -2513,18 +3671,18 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     sent_at_ms: r.get(5),
                 })
                 .collect())
-        })
+        }
+        .await
     }
 
     // ============== Dispute Methods ==============
 
-    fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
-        self.ensure_schema()?;
+    async fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let dispute = dispute.clone();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".runtime_disputes
                  (dispute_id, bounty_id, opened_by, status, evidence_json, resolution, resolved_by, resolved_at_ms, created_at_ms)
@@ -2543,14 +3701,15 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(dispute.created_at_ms)
                 .execute(&pool)
                 .await
-                .map_err(|e| map_driver_err("open dispute", e))?;
+                .map_err(|e| map_storage_err("open dispute", e))?;
             Ok(())
-        })
+        }.await
     }
 
-    fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
-        Ok(
-            PostgresRuntimeRepository::get_dispute(self, dispute_id)?.map(|row| DisputeRecord {
+    async fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
+        Ok(PostgresRuntimeRepository::get_dispute(self, dispute_id)
+            .await?
+            .map(|row| DisputeRecord {
                 dispute_id: row.dispute_id,
                 bounty_id: row.bounty_id,
                 opened_by: row.opened_by,
@@ -2560,17 +3719,18 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 resolved_by: row.resolved_by,
                 resolved_at_ms: row.resolved_at.map(dt_to_ms),
                 created_at_ms: dt_to_ms(row.created_at),
-            }),
-        )
+            }))
     }
 
-    fn get_disputes_for_bounty(&self, bounty_id: &str) -> Result<Vec<DisputeRecord>, KernelError> {
-        self.ensure_schema()?;
+    async fn get_disputes_for_bounty(
+        &self,
+        bounty_id: &str,
+    ) -> Result<Vec<DisputeRecord>, KernelError> {
+        self.ensure_schema().await?;
         let pool = self.pool()?.clone();
-        let rt = self.runtime()?;
         let schema = self.schema.clone();
         let bounty_id = bounty_id.to_string();
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT dispute_id, bounty_id, opened_by, status, evidence_json, resolution, resolved_by, resolved_at_ms, created_at_ms
                  FROM \"{}\".runtime_disputes
@@ -2582,7 +3742,7 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                 .bind(&bounty_id)
                 .fetch_all(&pool)
                 .await
-                .map_err(|e| map_driver_err("get disputes for bounty", e))?;
+                .map_err(|e| map_storage_err("get disputes for bounty", e))?;
             Ok(rows
                 .into_iter()
                 .map(|r| DisputeRecord {
@@ -2597,10 +3757,10 @@ impl RuntimeRepository for PostgresRuntimeRepository {
                     created_at_ms: r.get(8),
                 })
                 .collect())
-        })
+        }.await
     }
 
-    fn resolve_dispute(
+    async fn resolve_dispute(
         &self,
         dispute_id: &str,
         resolution: &str,
@@ -2612,15 +3772,157 @@ impl RuntimeRepository for PostgresRuntimeRepository {
             resolution,
             resolved_by,
             Utc::now(),
-        )?;
+        )
+        .await?;
         if !resolved {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "dispute not found or already resolved: {}",
                 dispute_id
             )));
         }
         Ok(())
     }
+
+    // ============== Interrupt Methods ==============
+
+    async fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let interrupt = interrupt.clone();
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            check_run_tenant(&pool, &schema, &interrupt.run_id, tenant_id.as_deref()).await?;
+            let sql = format!(
+                "INSERT INTO \"{}\".runtime_interrupts
+                 (interrupt_id, thread_id, run_id, attempt_id, step_id, value_json, status, created_at_ms, decision, resolution_json, resolved_at_ms)
+                 VALUES ($1, $2, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                schema
+            );
+            sqlx::query(&sql)
+                .bind(&interrupt.interrupt_id)
+                .bind(&interrupt.run_id)
+                .bind(&interrupt.attempt_id)
+                .bind(&interrupt.step_id)
+                .bind(interrupt.payload.to_string())
+                .bind(interrupt.status.as_str())
+                .bind(dt_to_ms(interrupt.created_at))
+                .bind(interrupt.decision.as_ref().map(|d| d.as_str()))
+                .bind(interrupt.resolution.as_ref().map(|v| v.to_string()))
+                .bind(interrupt.resolved_at.map(dt_to_ms))
+                .execute(&pool)
+                .await
+                .map_err(|e| map_storage_err("create interrupt", e))?;
+            Ok(())
+        }.await
+    }
+
+    async fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let run_id = filter.run_id.clone();
+        let limit = filter.effective_limit() as i64;
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let sql = format!(
+                "SELECT {} FROM \"{}\".runtime_interrupts
+                 WHERE status = 'pending' AND ($1::TEXT IS NULL OR run_id = $1)
+                   AND ($3::TEXT IS NULL OR run_id IN (
+                     SELECT run_id FROM \"{}\".runtime_runs WHERE tenant_id = $3
+                   ))
+                 ORDER BY created_at_ms ASC, interrupt_id ASC
+                 LIMIT $2",
+                INTERRUPT_RECORD_COLUMNS, schema, schema
+            );
+            let rows = sqlx::query(&sql)
+                .bind(&run_id)
+                .bind(limit)
+                .bind(&tenant_id)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| map_storage_err("list pending interrupts", e))?;
+            Ok(rows.iter().map(interrupt_record_from_row).collect())
+        }
+        .await
+    }
+
+    async fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        self.ensure_schema().await?;
+        let pool = self.pool()?.clone();
+        let schema = self.schema.clone();
+        let interrupt_id = interrupt_id.to_string();
+        let resolution_json = value.to_string();
+        let tenant_id = self.tenant_id.clone();
+        async move {
+            let in_scope = format!(
+                "($2::TEXT IS NULL OR run_id IN (SELECT run_id FROM \"{}\".runtime_runs WHERE tenant_id = $2))",
+                schema
+            );
+            let sql = format!(
+                "UPDATE \"{}\".runtime_interrupts
+                 SET status = $3, decision = $4, resolution_json = $5, resolved_at_ms = $6
+                 WHERE interrupt_id = $1 AND status = 'pending' AND {}
+                 RETURNING {}",
+                schema, in_scope, INTERRUPT_RECORD_COLUMNS
+            );
+            let row = sqlx::query(&sql)
+                .bind(&interrupt_id)
+                .bind(&tenant_id)
+                .bind(decision.resolved_status().as_str())
+                .bind(decision.as_str())
+                .bind(&resolution_json)
+                .bind(dt_to_ms(Utc::now()))
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| map_storage_err("resolve interrupt", e))?;
+            if let Some(row) = row {
+                return Ok(interrupt_record_from_row(&row));
+            }
+            let sql_status = format!(
+                "SELECT status FROM \"{}\".runtime_interrupts WHERE interrupt_id = $1 AND {}",
+                schema, in_scope
+            );
+            let status: Option<String> = sqlx::query_scalar(&sql_status)
+                .bind(&interrupt_id)
+                .bind(&tenant_id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| map_storage_err("resolve interrupt", e))?;
+            Err(match status {
+                Some(status) => {
+                    KernelError::Conflict(format!("interrupt {} already {}", interrupt_id, status))
+                }
+                None => KernelError::NotFound(format!("interrupt not found: {}", interrupt_id)),
+            })
+        }
+        .await
+    }
+}
+
+fn interrupt_record_from_row(row: &sqlx::postgres::PgRow) -> InterruptRecord {
+    let decision: Option<String> = row.get(7);
+    let resolution_json: Option<String> = row.get(8);
+    InterruptRecord {
+        interrupt_id: row.get(0),
+        run_id: row.get(1),
+        attempt_id: row.get(2),
+        step_id: row.get(3),
+        payload: serde_json::from_str(row.get::<String, _>(4).as_str()).unwrap_or(Value::Null),
+        status: InterruptStatus::from_str(row.get::<String, _>(5).as_str()),
+        created_at: ms_to_dt(row.get(6)),
+        decision: decision.as_deref().and_then(InterruptDecision::from_str),
+        resolution: resolution_json.map(|json| serde_json::from_str(&json).unwrap_or(Value::Null)),
+        resolved_at: row.get::<Option<i64>, _>(9).map(ms_to_dt),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -2648,7 +3950,6 @@ pub struct PostgresIdempotencyRecord {
 pub struct PostgresIdempotencyStore {
     pool: PgPool,
     schema: String,
-    db_runtime: Option<Arc<tokio::runtime::Runtime>>,
 }
 
 impl PostgresIdempotencyStore {
@@ -2662,22 +3963,14 @@ impl PostgresIdempotencyStore {
         Self {
             pool,
             schema: schema.into(),
-            db_runtime: new_db_runtime().ok(),
         }
     }
 
-    fn runtime(&self) -> Result<&tokio::runtime::Runtime, String> {
-        self.db_runtime
-            .as_deref()
-            .ok_or_else(|| "postgres idempotency runtime not available".to_string())
-    }
-
-    pub fn get(&self, key: &str) -> Result<Option<PostgresIdempotencyRecord>, String> {
+    pub async fn get(&self, key: &str) -> Result<Option<PostgresIdempotencyRecord>, String> {
         let pool = self.pool.clone();
         let schema = self.schema.clone();
         let key = key.to_string();
-        let rt = self.runtime()?;
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "SELECT operation, thread_id, payload_hash, response_json
                  FROM \"{}\".execution_idempotency
@@ -2699,10 +3992,11 @@ impl PostgresIdempotencyStore {
                     }
                 }),
             )
-        })
+        }
+        .await
     }
 
-    pub fn put(&self, key: &str, record: &PostgresIdempotencyRecord) -> Result<(), String> {
+    pub async fn put(&self, key: &str, record: &PostgresIdempotencyRecord) -> Result<(), String> {
         let pool = self.pool.clone();
         let schema = self.schema.clone();
         let key = key.to_string();
@@ -2711,8 +4005,7 @@ impl PostgresIdempotencyStore {
         let payload_hash = record.payload_hash.clone();
         let response_json = record.response_json.clone();
         let now = dt_to_ms(Utc::now());
-        let rt = self.runtime()?;
-        rt.block_on(async move {
+        async move {
             let sql = format!(
                 "INSERT INTO \"{}\".execution_idempotency
                  (idempotency_key, operation, thread_id, payload_hash, response_json, created_at_ms)
@@ -2736,7 +4029,57 @@ impl PostgresIdempotencyStore {
                 .await
                 .map_err(|e| format!("postgres idempotency put failed: {}", e))?;
             Ok(())
-        })
+        }
+        .await
+    }
+
+    /// Stores `record` unless `key` is already taken, in which case the record already
+    /// stored under it is returned and nothing is written.
+    pub async fn put_if_absent(
+        &self,
+        key: &str,
+        record: &PostgresIdempotencyRecord,
+    ) -> Result<Option<PostgresIdempotencyRecord>, String> {
+        let sql = format!(
+            "INSERT INTO \"{}\".execution_idempotency
+             (idempotency_key, operation, thread_id, payload_hash, response_json, created_at_ms)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT(idempotency_key) DO NOTHING",
+            self.schema
+        );
+        loop {
+            let inserted = sqlx::query(&sql)
+                .bind(key)
+                .bind(&record.operation)
+                .bind(&record.thread_id)
+                .bind(&record.payload_hash)
+                .bind(&record.response_json)
+                .bind(dt_to_ms(Utc::now()))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| format!("postgres idempotency reserve failed: {}", e))?
+                .rows_affected();
+            if inserted == 1 {
+                return Ok(None);
+            }
+            // The conflicting row may be removed before it is read back; try the insert again.
+            if let Some(existing) = self.get(key).await? {
+                return Ok(Some(existing));
+            }
+        }
+    }
+
+    pub async fn remove(&self, key: &str) -> Result<(), String> {
+        let sql = format!(
+            "DELETE FROM \"{}\".execution_idempotency WHERE idempotency_key = $1",
+            self.schema
+        );
+        sqlx::query(&sql)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("postgres idempotency remove failed: {}", e))?;
+        Ok(())
     }
 }
 
@@ -2746,348 +4089,108 @@ mod tests {
     use std::thread;
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    use chrono::{Duration, Utc};
+    use chrono::{DateTime, Duration, Utc};
+    use oris_kernel::{Event, EventStore, KernelError, PostgresRepositoryConfig};
     use sqlx::postgres::PgPoolOptions;
 
-    use super::{PostgresRuntimeRepository, POSTGRES_RUNTIME_SCHEMA_VERSION};
-    use crate::models::{
-        BountyRecord, BountyStatus, DisputeRecord, DisputeStatus, OrganismRecord, RecipeRecord,
-        SessionMessageRecord, SessionRecord, SwarmTaskRecord, WorkerRecord,
+    use super::{
+        PostgresIdempotencyRecord, PostgresIdempotencyStore, PostgresRuntimeRepository,
+        POSTGRES_RUNTIME_SCHEMA_VERSION,
+    };
+    use crate::repository_contract::{
+        assert_async_dispatch_lease_requeue_contract, assert_attempt_failure_contract,
+        assert_bounty_worker_swarm_contract, assert_cancel_attempt_contract,
+        assert_concurrent_claim_contract, assert_dispatch_lease_requeue_contract,
+        assert_enqueue_batch_contract, assert_interrupt_inbox_contract,
+        assert_lease_fencing_contract, assert_max_concurrent_per_run_contract,
+        assert_priority_dispatch_order_contract, assert_recipe_organism_session_dispute_contract,
+        assert_run_at_contract, assert_run_lifecycle_contract, assert_run_record_contract,
+        assert_runtime_stats_contract, assert_semantic_roundtrip, assert_tenant_isolation_contract,
+        seed_run, ContractHarness,
+    };
+    use crate::{
+        BlockingRuntimeRepository, RuntimeRepository, SchedulerDecision, SkeletonScheduler,
+        SqliteRuntimeRepository,
     };
-    use crate::{RuntimeRepository, SchedulerDecision, SkeletonScheduler, SqliteRuntimeRepository};
-
-    trait ContractHarness: RuntimeRepository {
-        fn seed_attempt(&self, attempt_id: &str, run_id: &str);
-        fn has_lease(&self, attempt_id: &str) -> bool;
-    }
 
     impl ContractHarness for SqliteRuntimeRepository {
         fn seed_attempt(&self, attempt_id: &str, run_id: &str) {
+            seed_run(self, run_id);
             self.enqueue_attempt(attempt_id, run_id)
                 .expect("enqueue sqlite attempt");
         }
 
+        fn seed_attempt_with_priority(&self, attempt_id: &str, run_id: &str, priority: i32) {
+            seed_run(self, run_id);
+            self.enqueue_attempt_with_priority(attempt_id, run_id, priority)
+                .expect("enqueue sqlite attempt with priority");
+        }
+
+        fn seed_attempt_at(&self, attempt_id: &str, run_id: &str, run_at: DateTime<Utc>) {
+            seed_run(self, run_id);
+            self.enqueue_attempt_at(attempt_id, run_id, run_at)
+                .expect("enqueue sqlite attempt at");
+        }
+
         fn has_lease(&self, attempt_id: &str) -> bool {
             self.get_lease_for_attempt(attempt_id)
                 .expect("sqlite get lease")
                 .is_some()
         }
+
+        fn enqueue_attempt_for(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+            self.enqueue_attempt(attempt_id, run_id)
+        }
     }
 
-    impl ContractHarness for PostgresRuntimeRepository {
+    /// The sync contracts run against Postgres through the blocking adapter.
+    type BlockingPostgres = BlockingRuntimeRepository<PostgresRuntimeRepository>;
+
+    impl ContractHarness for BlockingPostgres {
         fn seed_attempt(&self, attempt_id: &str, run_id: &str) {
-            self.enqueue_attempt(attempt_id, run_id)
+            seed_run(self, run_id);
+            self.block_on(self.get_ref().enqueue_attempt(attempt_id, run_id))
                 .expect("enqueue postgres attempt");
         }
 
+        fn seed_attempt_with_priority(&self, attempt_id: &str, run_id: &str, priority: i32) {
+            seed_run(self, run_id);
+            self.block_on(
+                self.get_ref()
+                    .enqueue_attempt_with_priority(attempt_id, run_id, priority),
+            )
+            .expect("enqueue postgres attempt with priority");
+        }
+
+        fn seed_attempt_at(&self, attempt_id: &str, run_id: &str, run_at: DateTime<Utc>) {
+            seed_run(self, run_id);
+            self.block_on(
+                self.get_ref()
+                    .enqueue_attempt_at(attempt_id, run_id, run_at),
+            )
+            .expect("enqueue postgres attempt at");
+        }
+
         fn has_lease(&self, attempt_id: &str) -> bool {
-            self.get_lease_for_attempt(attempt_id)
+            self.block_on(self.get_ref().get_lease_for_attempt(attempt_id))
                 .expect("postgres get lease")
                 .is_some()
         }
-    }
-
-    fn assert_dispatch_lease_requeue_contract<R: ContractHarness>(repo: &R, name: &str) {
-        let run_id = format!("run-{}", name);
-        let attempt_id = format!("attempt-{}", name);
-        let now = Utc::now();
-
-        repo.seed_attempt(&attempt_id, &run_id);
-        let initial = repo
-            .list_dispatchable_attempts(now, 10)
-            .expect("list dispatchable initial");
-        assert!(initial.iter().any(|r| r.attempt_id == attempt_id));
-
-        let lease = repo
-            .upsert_lease(&attempt_id, "worker-a", now + Duration::seconds(1))
-            .expect("upsert lease");
-        assert!(repo.has_lease(&attempt_id));
-
-        let duplicate = repo.upsert_lease(&attempt_id, "worker-b", now + Duration::seconds(2));
-        assert!(duplicate.is_err());
-
-        let hidden = repo
-            .list_dispatchable_attempts(now, 10)
-            .expect("list dispatchable while leased");
-        assert!(!hidden.iter().any(|r| r.attempt_id == attempt_id));
-
-        repo.heartbeat_lease(
-            &lease.lease_id,
-            now + Duration::milliseconds(500),
-            now + Duration::seconds(2),
-        )
-        .expect("heartbeat lease");
-
-        let expired = repo
-            .expire_leases_and_requeue(now + Duration::seconds(10))
-            .expect("expire and requeue");
-        assert_eq!(expired, 1);
-
-        let available = repo
-            .list_dispatchable_attempts(now + Duration::seconds(10), 10)
-            .expect("list dispatchable after requeue");
-        assert!(available.iter().any(|r| r.attempt_id == attempt_id));
-
-        assert_eq!(repo.latest_seq_for_run(&run_id).expect("latest seq"), 0);
-    }
-
-    fn assert_bounty_worker_swarm_contract<R: RuntimeRepository>(repo: &R, prefix: &str) {
-        let now_ms = Utc::now().timestamp_millis();
-        let bounty_id = format!("{prefix}-bounty");
-        let worker_id = format!("{prefix}-worker");
-        let parent_task_id = format!("{prefix}-swarm-task");
-
-        let bounty = BountyRecord {
-            bounty_id: bounty_id.clone(),
-            title: format!("{} title", prefix),
-            description: Some("semantic contract bounty".to_string()),
-            reward: 123,
-            status: BountyStatus::Open,
-            created_by: "alice".to_string(),
-            created_at_ms: now_ms,
-            closed_at_ms: None,
-            accepted_by: None,
-            accepted_at_ms: None,
-        };
-        repo.upsert_bounty(&bounty).expect("upsert bounty");
-
-        let fetched = repo
-            .get_bounty(&bounty_id)
-            .expect("get bounty")
-            .expect("bounty exists");
-        assert_eq!(fetched.status, BountyStatus::Open);
-
-        repo.accept_bounty(&bounty_id, &worker_id)
-            .expect("accept bounty");
-        let accepted = repo
-            .get_bounty(&bounty_id)
-            .expect("get accepted bounty")
-            .expect("accepted bounty exists");
-        assert_eq!(accepted.status, BountyStatus::Accepted);
-        assert_eq!(accepted.accepted_by.as_deref(), Some(worker_id.as_str()));
 
-        repo.close_bounty(&bounty_id).expect("close bounty");
-        let closed = repo
-            .get_bounty(&bounty_id)
-            .expect("get closed bounty")
-            .expect("closed bounty exists");
-        assert_eq!(closed.status, BountyStatus::Closed);
-        assert!(closed.closed_at_ms.is_some());
-
-        let listed = repo
-            .list_bounties(Some("closed"), 8)
-            .expect("list closed bounties");
-        assert!(listed.iter().any(|b| b.bounty_id == bounty_id));
-
-        let worker = WorkerRecord {
-            worker_id: worker_id.clone(),
-            domains: "evomap,semantic".to_string(),
-            max_load: 2,
-            metadata_json: Some(r#"{"role":"solver"}"#.to_string()),
-            registered_at_ms: now_ms,
-            last_heartbeat_ms: Some(now_ms),
-            status: "active".to_string(),
-        };
-        repo.register_worker(&worker).expect("register worker");
-        let worker_fetched = repo
-            .get_worker(&worker_id)
-            .expect("get worker")
-            .expect("worker exists");
-        assert_eq!(worker_fetched.domains, "evomap,semantic");
-        assert_eq!(worker_fetched.status, "active");
-
-        let by_domain = repo
-            .list_workers(Some("evomap"), Some("active"), 10)
-            .expect("list workers by domain+status");
-        assert!(by_domain.iter().any(|w| w.worker_id == worker_id));
-
-        let heartbeat_ms = now_ms + 777;
-        repo.heartbeat_worker(&worker_id, heartbeat_ms)
-            .expect("heartbeat worker");
-        let heartbeat_worker = repo
-            .get_worker(&worker_id)
-            .expect("get worker after heartbeat")
-            .expect("worker exists after heartbeat");
-        assert_eq!(heartbeat_worker.last_heartbeat_ms, Some(heartbeat_ms));
-
-        let swarm = SwarmTaskRecord {
-            parent_task_id: parent_task_id.clone(),
-            decomposition_json: r#"{"children":[{"id":"c1"}]}"#.to_string(),
-            proposer_id: "alice".to_string(),
-            proposer_reward_pct: 5,
-            solver_reward_pct: 85,
-            aggregator_reward_pct: 10,
-            status: "pending".to_string(),
-            created_at_ms: now_ms,
-            completed_at_ms: None,
-        };
-        repo.upsert_swarm_decomposition(&swarm)
-            .expect("upsert swarm task");
-        let swarm_fetched = repo
-            .get_swarm_decomposition(&parent_task_id)
-            .expect("get swarm decomposition")
-            .expect("swarm decomposition exists");
-        assert_eq!(swarm_fetched.proposer_id, "alice");
-        assert_eq!(swarm_fetched.status, "pending");
-    }
-
-    fn assert_recipe_organism_session_dispute_contract<R: RuntimeRepository>(
-        repo: &R,
-        prefix: &str,
-    ) {
-        let now_ms = Utc::now().timestamp_millis();
-        let recipe_id = format!("{prefix}-recipe");
-        let fork_recipe_id = format!("{prefix}-recipe-fork");
-        let organism_id = format!("{prefix}-organism");
-        let session_id = format!("{prefix}-session");
-        let dispute_id = format!("{prefix}-dispute");
-        let bounty_id = format!("{prefix}-bounty-for-dispute");
-
-        repo.upsert_bounty(&BountyRecord {
-            bounty_id: bounty_id.clone(),
-            title: format!("{prefix} bounty"),
-            description: None,
-            reward: 42,
-            status: BountyStatus::Open,
-            created_by: "arbiter".to_string(),
-            created_at_ms: now_ms,
-            closed_at_ms: None,
-            accepted_by: None,
-            accepted_at_ms: None,
-        })
-        .expect("seed bounty for dispute");
-
-        let recipe = RecipeRecord {
-            recipe_id: recipe_id.clone(),
-            name: format!("{prefix} base recipe"),
-            description: Some("base".to_string()),
-            gene_sequence_json: r#"{"steps":["a","b"]}"#.to_string(),
-            author_id: "alice".to_string(),
-            forked_from: None,
-            created_at_ms: now_ms,
-            updated_at_ms: now_ms,
-            is_public: true,
-        };
-        repo.create_recipe(&recipe).expect("create recipe");
-
-        let fetched_recipe = repo
-            .get_recipe(&recipe_id)
-            .expect("get recipe")
-            .expect("recipe exists");
-        assert_eq!(fetched_recipe.author_id, "alice");
-
-        let forked = repo
-            .fork_recipe(&recipe_id, &fork_recipe_id, "bob")
-            .expect("fork recipe call")
-            .expect("forked recipe exists");
-        assert_eq!(forked.forked_from.as_deref(), Some(recipe_id.as_str()));
-        assert_eq!(forked.author_id, "bob");
-
-        let recipes = repo
-            .list_recipes(Some("alice"), 10)
-            .expect("list recipes by author");
-        assert!(recipes.iter().any(|r| r.recipe_id == recipe_id));
-
-        let organism = OrganismRecord {
-            organism_id: organism_id.clone(),
-            recipe_id: recipe_id.clone(),
-            status: "pending".to_string(),
-            current_step: 0,
-            total_steps: 2,
-            created_at_ms: now_ms,
-            completed_at_ms: None,
-        };
-        repo.express_organism(&organism).expect("express organism");
-        repo.update_organism(&organism_id, 1, "running")
-            .expect("update organism running");
-        repo.update_organism(&organism_id, 2, "completed")
-            .expect("update organism completed");
-        let updated_organism = repo
-            .get_organism(&organism_id)
-            .expect("get organism")
-            .expect("organism exists");
-        assert_eq!(updated_organism.status, "completed");
-        assert_eq!(updated_organism.current_step, 2);
-        assert!(updated_organism.completed_at_ms.is_some());
-
-        let session = SessionRecord {
-            session_id: session_id.clone(),
-            session_type: "pair".to_string(),
-            creator_id: "alice".to_string(),
-            status: "active".to_string(),
-            created_at_ms: now_ms,
-            ended_at_ms: None,
-        };
-        repo.create_session(&session).expect("create session");
-
-        repo.add_session_message(&SessionMessageRecord {
-            message_id: format!("{prefix}-msg-1"),
-            session_id: session_id.clone(),
-            sender_id: "alice".to_string(),
-            content: "first".to_string(),
-            message_type: "message".to_string(),
-            sent_at_ms: now_ms + 1,
-        })
-        .expect("add session message 1");
-        repo.add_session_message(&SessionMessageRecord {
-            message_id: format!("{prefix}-msg-2"),
-            session_id: session_id.clone(),
-            sender_id: "bob".to_string(),
-            content: "second".to_string(),
-            message_type: "message".to_string(),
-            sent_at_ms: now_ms + 2,
-        })
-        .expect("add session message 2");
-
-        let session_history = repo
-            .get_session_history(&session_id, 10)
-            .expect("get session history");
-        assert_eq!(session_history.len(), 2);
-        assert_eq!(session_history[0].content, "second");
-
-        let dispute = DisputeRecord {
-            dispute_id: dispute_id.clone(),
-            bounty_id: bounty_id.clone(),
-            opened_by: "alice".to_string(),
-            status: DisputeStatus::Open,
-            evidence_json: Some(r#"{"proof":"hash"}"#.to_string()),
-            resolution: None,
-            resolved_by: None,
-            resolved_at_ms: None,
-            created_at_ms: now_ms,
-        };
-        repo.open_dispute(&dispute).expect("open dispute");
-        let opened = repo
-            .get_dispute(&dispute_id)
-            .expect("get dispute")
-            .expect("dispute exists");
-        assert_eq!(opened.status, DisputeStatus::Open);
-
-        let disputes = repo
-            .get_disputes_for_bounty(&bounty_id)
-            .expect("get disputes for bounty");
-        assert!(disputes.iter().any(|d| d.dispute_id == dispute_id));
-
-        repo.resolve_dispute(&dispute_id, "approved", "arbiter")
-            .expect("resolve dispute");
-        let resolved = repo
-            .get_dispute(&dispute_id)
-            .expect("get resolved dispute")
-            .expect("resolved dispute exists");
-        assert_eq!(resolved.status, DisputeStatus::Resolved);
-        assert_eq!(resolved.resolved_by.as_deref(), Some("arbiter"));
-        assert!(resolved.resolved_at_ms.is_some());
-    }
-
-    fn assert_semantic_roundtrip<R: RuntimeRepository>(repo: &R, prefix: &str) {
-        assert_bounty_worker_swarm_contract(repo, prefix);
-        assert_recipe_organism_session_dispute_contract(repo, prefix);
+        fn enqueue_attempt_for(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+            self.block_on(self.get_ref().enqueue_attempt(attempt_id, run_id))
+        }
     }
 
     fn test_db_url() -> Option<String> {
         std::env::var("ORIS_TEST_POSTGRES_URL").ok()
     }
 
+    fn postgres_repo(db_url: impl Into<String>, schema: String) -> BlockingPostgres {
+        BlockingRuntimeRepository::new(PostgresRuntimeRepository::new(db_url).with_schema(schema))
+            .expect("blocking postgres repo")
+    }
+
     fn test_schema() -> String {
         let ts = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -3139,19 +4242,203 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_dispatch_lease_requeue_contract(&repo, "postgres");
     }
 
+    #[tokio::test]
+    async fn runtime_repository_async_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_async_dispatch_lease_requeue_contract(&repo, "sqlite-async").await;
+    }
+
+    #[tokio::test]
+    async fn runtime_repository_async_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        assert_async_dispatch_lease_requeue_contract(&repo, "postgres-async").await;
+    }
+
+    #[test]
+    fn runtime_repository_attempt_failure_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_attempt_failure_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_attempt_failure_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_attempt_failure_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_runtime_stats_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_runtime_stats_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_runtime_stats_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_runtime_stats_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_run_at_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_run_at_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_run_at_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_run_at_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_concurrent_claim_contract_sqlite() {
+        let path = std::env::temp_dir().join(format!(
+            "oris-runtime-concurrent-claim-{}.db",
+            uuid::Uuid::new_v4()
+        ));
+        let path = path.to_string_lossy().to_string();
+        let repos = (0..8)
+            .map(|_| SqliteRuntimeRepository::new(&path).expect("sqlite repo"))
+            .collect();
+        assert_concurrent_claim_contract(repos, "sqlite");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn runtime_repository_concurrent_claim_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let repos = (0..8)
+            .map(|_| postgres_repo(db_url.clone(), schema.clone()))
+            .collect();
+        assert_concurrent_claim_contract(repos, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_max_concurrent_per_run_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_max_concurrent_per_run_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_max_concurrent_per_run_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_max_concurrent_per_run_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_lease_fencing_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_lease_fencing_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_lease_fencing_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_lease_fencing_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_cancel_attempt_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_cancel_attempt_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_cancel_attempt_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_cancel_attempt_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_priority_order_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_priority_dispatch_order_contract(&repo, "sqlite");
+    }
+
+    #[test]
+    fn runtime_repository_priority_order_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_priority_dispatch_order_contract(&repo, "postgres");
+    }
+
+    #[test]
+    fn runtime_repository_event_store_shares_schema_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let repo = postgres_repo(db_url.clone(), schema.clone());
+        repo.seed_attempt("event-store-attempt", "event-store-run");
+        let events = repo
+            .block_on(repo.get_ref().event_store())
+            .expect("event store");
+        let run_id = "event-store-run".to_string();
+        let seq = events
+            .append(
+                &run_id,
+                &[
+                    Event::StateUpdated {
+                        step_id: Some("n1".to_string()),
+                        payload: serde_json::json!({"v": 1}),
+                        state_hash: None,
+                        merge_report: None,
+                    },
+                    Event::Completed,
+                ],
+            )
+            .expect("append events");
+        assert_eq!(seq, 2);
+        assert_eq!(events.scan(&run_id, 1).expect("scan events").len(), 2);
+
+        let stored = pg_query_i64(
+            &db_url,
+            format!(
+                "SELECT COUNT(*)::BIGINT FROM \"{}\".kernel_events WHERE run_id = 'event-store-run'",
+                schema
+            ),
+        );
+        assert_eq!(stored, 2);
+    }
+
     #[test]
     fn postgres_schema_migration_clean_init_reaches_latest_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
         let schema = test_schema();
-        let repo = PostgresRuntimeRepository::new(db_url.clone()).with_schema(schema.clone());
-        repo.enqueue_attempt("migration-clean-attempt", "migration-clean-run")
-            .expect("enqueue attempt for schema init");
+        let repo = postgres_repo(db_url.clone(), schema.clone());
+        repo.seed_attempt("migration-clean-attempt", "migration-clean-run");
 
         let version = pg_query_i64(
             &db_url,
@@ -3163,6 +4450,36 @@ mod tests {
         assert_eq!(version, POSTGRES_RUNTIME_SCHEMA_VERSION);
     }
 
+    #[tokio::test]
+    async fn postgres_schema_version_and_newer_schema_rejection_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let repo = PostgresRuntimeRepository::new(db_url.clone()).with_schema(schema.clone());
+        assert_eq!(
+            repo.schema_version().await.expect("schema version"),
+            POSTGRES_RUNTIME_SCHEMA_VERSION
+        );
+
+        let pool = repo.pool().expect("pool").clone();
+        sqlx::query(&format!(
+            "INSERT INTO \"{}\".runtime_schema_migrations(version, name, applied_at_ms)
+             VALUES ($1, 'from_the_future', 0)",
+            schema
+        ))
+        .bind(POSTGRES_RUNTIME_SCHEMA_VERSION as i32 + 1)
+        .execute(&pool)
+        .await
+        .expect("record future migration");
+
+        let reopened = PostgresRuntimeRepository::new(db_url).with_schema(schema);
+        match reopened.schema_version().await {
+            Err(KernelError::Storage(msg)) => assert!(msg.contains("upgrade"), "{}", msg),
+            other => panic!("a newer schema should be rejected, got {:?}", other),
+        }
+    }
+
     #[test]
     fn postgres_schema_migration_incremental_upgrade_from_v1_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
@@ -3212,9 +4529,8 @@ mod tests {
             ],
         );
 
-        let repo = PostgresRuntimeRepository::new(db_url.clone()).with_schema(schema.clone());
-        repo.enqueue_attempt("migration-upgrade-attempt", "migration-upgrade-run")
-            .expect("enqueue attempt for upgrade");
+        let repo = postgres_repo(db_url.clone(), schema.clone());
+        repo.seed_attempt("migration-upgrade-attempt", "migration-upgrade-run");
 
         let version = pg_query_i64(
             &db_url,
@@ -3254,11 +4570,10 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = Arc::new(PostgresRuntimeRepository::new(db_url).with_schema(test_schema()));
+        let repo = Arc::new(postgres_repo(db_url, test_schema()));
         let run_id = "run-pg-concurrent";
         let attempt_id = "attempt-pg-concurrent";
-        repo.enqueue_attempt(attempt_id, run_id)
-            .expect("enqueue postgres attempt");
+        repo.seed_attempt(attempt_id, run_id);
 
         let mut handles = Vec::new();
         for idx in 0..8 {
@@ -3285,7 +4600,7 @@ mod tests {
         assert_eq!(failures, 7, "all other concurrent acquisitions should fail");
 
         let active = repo
-            .get_lease_for_attempt(attempt_id)
+            .block_on(repo.get_ref().get_lease_for_attempt(attempt_id))
             .expect("get lease for attempt")
             .expect("active lease exists");
         assert_eq!(active.worker_id, winners[0].worker_id);
@@ -3297,13 +4612,12 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         let run_id = "run-pg-owner";
         let attempt_id = "attempt-pg-owner";
         let now = Utc::now();
 
-        repo.enqueue_attempt(attempt_id, run_id)
-            .expect("enqueue postgres attempt");
+        repo.seed_attempt(attempt_id, run_id);
         let lease = repo
             .upsert_lease(attempt_id, "owner-worker", now + Duration::seconds(20))
             .expect("upsert lease");
@@ -3354,7 +4668,7 @@ mod tests {
         );
 
         let latest = repo
-            .get_lease_by_id(&lease.lease_id)
+            .block_on(repo.get_ref().get_lease_by_id(&lease.lease_id))
             .expect("get lease by id")
             .expect("lease exists");
         assert_eq!(latest.worker_id, "owner-worker");
@@ -3366,9 +4680,8 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
-        repo.enqueue_attempt("attempt-pg-scheduler", "run-pg-scheduler")
-            .expect("enqueue postgres attempt");
+        let repo = postgres_repo(db_url, test_schema());
+        repo.seed_attempt("attempt-pg-scheduler", "run-pg-scheduler");
 
         let scheduler_a = SkeletonScheduler::new(repo.clone());
         let scheduler_b = SkeletonScheduler::new(repo.clone());
@@ -3399,8 +4712,44 @@ mod tests {
         assert_eq!(noops, 1, "one scheduler should observe conflict and noop");
     }
 
-    #[test]
-    fn postgres_bounty_lifecycle_roundtrip_when_env_is_set() {
+    #[tokio::test]
+    async fn postgres_idempotency_reservation_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let repo = PostgresRuntimeRepository::new(db_url).with_schema(schema.clone());
+        repo.ensure_schema().await.expect("ensure schema");
+        let store = PostgresIdempotencyStore::new(repo.pool().expect("pool").clone(), schema);
+        let record = |payload_hash: &str| PostgresIdempotencyRecord {
+            operation: "create_run".to_string(),
+            thread_id: "run-1".to_string(),
+            payload_hash: payload_hash.to_string(),
+            response_json: "{}".to_string(),
+        };
+
+        let first = store
+            .put_if_absent("tenant-a/key-1", &record("hash-1"))
+            .await
+            .expect("reserve key");
+        assert!(first.is_none());
+        let taken = store
+            .put_if_absent("tenant-a/key-1", &record("hash-2"))
+            .await
+            .expect("reserve taken key")
+            .expect("existing record");
+        assert_eq!(taken.payload_hash, "hash-1");
+
+        store.remove("tenant-a/key-1").await.expect("remove key");
+        let again = store
+            .put_if_absent("tenant-a/key-1", &record("hash-2"))
+            .await
+            .expect("reserve released key");
+        assert!(again.is_none());
+    }
+
+    #[tokio::test]
+    async fn postgres_bounty_lifecycle_roundtrip_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -3415,21 +4764,25 @@ mod tests {
                 "alice",
                 now,
             )
+            .await
             .expect("create bounty");
         assert_eq!(created.status, "open");
 
         let accepted = repo
             .accept_bounty("pg-bounty-1", "worker-1", now + Duration::seconds(1))
+            .await
             .expect("accept bounty");
         assert!(accepted);
 
         let closed = repo
             .close_bounty("pg-bounty-1", now + Duration::seconds(2))
+            .await
             .expect("close bounty");
         assert!(closed);
 
         let final_row = repo
             .get_bounty("pg-bounty-1")
+            .await
             .expect("get bounty")
             .expect("bounty exists");
         assert_eq!(final_row.status, "closed");
@@ -3437,8 +4790,8 @@ mod tests {
         assert!(final_row.closed_at.is_some());
     }
 
-    #[test]
-    fn postgres_bounty_invalid_transitions_when_env_is_set() {
+    #[tokio::test]
+    async fn postgres_bounty_invalid_transitions_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -3452,26 +4805,30 @@ mod tests {
             "alice",
             now,
         )
+        .await
         .expect("create bounty");
 
         let close_open = repo
             .close_bounty("pg-bounty-2", now + Duration::seconds(1))
+            .await
             .expect("close open bounty");
         assert!(!close_open);
 
         let accept_once = repo
             .accept_bounty("pg-bounty-2", "worker-2", now + Duration::seconds(2))
+            .await
             .expect("accept open bounty");
         assert!(accept_once);
 
         let accept_again = repo
             .accept_bounty("pg-bounty-2", "worker-3", now + Duration::seconds(3))
+            .await
             .expect("accept accepted bounty");
         assert!(!accept_again);
     }
 
-    #[test]
-    fn postgres_swarm_task_roundtrip_when_env_is_set() {
+    #[tokio::test]
+    async fn postgres_swarm_task_roundtrip_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -3491,6 +4848,7 @@ mod tests {
                 "pending",
                 now,
             )
+            .await
             .expect("create swarm task");
         assert_eq!(created.parent_task_id, "pg-parent-1");
         assert_eq!(created.proposer_reward_pct, 5);
@@ -3499,6 +4857,7 @@ mod tests {
 
         let fetched = repo
             .get_swarm_task("pg-parent-1")
+            .await
             .expect("get swarm task")
             .expect("swarm task exists");
         assert_eq!(fetched.decomposition_json, decomposition_json);
@@ -3511,7 +4870,7 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_bounty_worker_swarm_contract(&repo, "pg-bounty-contract");
     }
 
@@ -3520,7 +4879,7 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_bounty_worker_swarm_contract(&repo, "pg-worker-contract");
     }
 
@@ -3529,7 +4888,7 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_recipe_organism_session_dispute_contract(&repo, "pg-recipe-organism-contract");
     }
 
@@ -3538,10 +4897,103 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_recipe_organism_session_dispute_contract(&repo, "pg-session-dispute-contract");
     }
 
+    #[test]
+    fn runtime_repository_run_record_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_run_record_contract(&repo, "sqlite-run-contract");
+    }
+
+    #[test]
+    fn runtime_repository_run_record_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_run_record_contract(&repo, "pg-run-contract");
+    }
+
+    #[test]
+    fn runtime_repository_enqueue_batch_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_enqueue_batch_contract(&repo, "sqlite-enqueue-batch");
+    }
+
+    #[test]
+    fn runtime_repository_enqueue_batch_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_enqueue_batch_contract(&repo, "pg-enqueue-batch");
+    }
+
+    #[test]
+    fn runtime_repository_run_lifecycle_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_run_lifecycle_contract(&repo, "sqlite-run-lifecycle");
+    }
+
+    #[test]
+    fn runtime_repository_run_lifecycle_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_run_lifecycle_contract(&repo, "pg-run-lifecycle");
+    }
+
+    #[test]
+    fn runtime_repository_tenant_isolation_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_tenant_isolation_contract(
+            &repo.clone().with_tenant("a"),
+            &repo.clone().with_tenant("b"),
+            &repo,
+            "sqlite-tenant",
+        );
+    }
+
+    #[test]
+    fn runtime_repository_tenant_isolation_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let scoped = |tenant: &str| {
+            BlockingRuntimeRepository::new(
+                PostgresRuntimeRepository::new(db_url.clone())
+                    .with_schema(schema.clone())
+                    .with_tenant(tenant),
+            )
+            .expect("blocking postgres repo")
+        };
+        assert_tenant_isolation_contract(
+            &scoped("a"),
+            &scoped("b"),
+            &postgres_repo(db_url.clone(), schema.clone()),
+            "pg-tenant",
+        );
+    }
+
+    #[test]
+    fn runtime_repository_interrupt_inbox_contract_sqlite() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
+        assert_interrupt_inbox_contract(&repo, "sqlite-interrupt-contract");
+    }
+
+    #[test]
+    fn runtime_repository_interrupt_inbox_contract_postgres_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = postgres_repo(db_url, test_schema());
+        assert_interrupt_inbox_contract(&repo, "pg-interrupt-contract");
+    }
+
     #[test]
     fn runtime_repository_semantic_contract_sqlite() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("sqlite repo");
@@ -3553,7 +5005,61 @@ mod tests {
         let Some(db_url) = test_db_url() else {
             return;
         };
-        let repo = PostgresRuntimeRepository::new(db_url).with_schema(test_schema());
+        let repo = postgres_repo(db_url, test_schema());
         assert_semantic_roundtrip(&repo, "pg-semantic-contract");
     }
+
+    #[test]
+    fn with_config_rejects_misconfiguration_before_connecting() {
+        let url = "postgres://oris@localhost:5432/oris";
+        for config in [
+            PostgresRepositoryConfig::new().with_max_connections(0),
+            PostgresRepositoryConfig::new().with_schema("runtime; DROP TABLE"),
+        ] {
+            assert!(matches!(
+                PostgresRuntimeRepository::with_config(url, config),
+                Err(KernelError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            PostgresRuntimeRepository::with_config("not a url", PostgresRepositoryConfig::new()),
+            Err(KernelError::Validation(_))
+        ));
+
+        let repo = PostgresRuntimeRepository::with_config(
+            url,
+            PostgresRepositoryConfig::new()
+                .with_max_connections(2)
+                .with_schema("oris_runtime"),
+        )
+        .expect("valid config");
+        assert_eq!(repo.schema, "oris_runtime");
+    }
+
+    #[tokio::test]
+    async fn with_config_applies_session_settings_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let repo = PostgresRuntimeRepository::with_config(
+            db_url,
+            PostgresRepositoryConfig::new()
+                .with_max_connections(1)
+                .with_statement_timeout(std::time::Duration::from_secs(7))
+                .with_application_name("oris-runtime-test")
+                .with_schema(test_schema()),
+        )
+        .expect("postgres repo");
+        let pool = repo.pool().expect("pool");
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(pool)
+            .await
+            .expect("show statement_timeout");
+        assert_eq!(timeout, "7s");
+        let application_name: String = sqlx::query_scalar("SHOW application_name")
+            .fetch_one(pool)
+            .await
+            .expect("show application_name");
+        assert_eq!(application_name, "oris-runtime-test");
+    }
 }
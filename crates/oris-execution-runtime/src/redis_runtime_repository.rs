@@ -0,0 +1,2290 @@
+//! Redis-backed runtime repository for low-latency dispatch (feature `kernel-redis`).
+//!
+//! [RedisRuntimeRepository] keeps the dispatch queue, attempts and leases in Redis, so
+//! claiming work costs one round trip to an in-memory store instead of a SQL
+//! transaction. Every state change of an attempt or lease (enqueue, claim, lease,
+//! heartbeat, expire and requeue, ack, failure, cancel) runs as one Lua script, which
+//! Redis executes atomically, so concurrent workers never claim the same attempt.
+//!
+//! Keys, all under [RedisRepositoryConfig::key_prefix]:
+//! - `attempt:{id}`: a hash per attempt, indexed by the set `index:attempt`;
+//! - `queue`: a sorted set of the attempts waiting for dispatch, scored by priority
+//!   (negated, so the highest comes first) with equal priorities ordered by enqueue
+//!   time through the member, `{enqueued_at}:{attempt_no}:{seq}:{id}`;
+//! - `wake`: waiting attempts held back by `run_at` or a retry backoff, scored by when
+//!   they become dispatchable;
+//! - `lease:{attempt_id}`: a hash per lease with its worker, expiry and fencing
+//!   `version`, looked up by lease id through `lease_id:{lease_id}`; both keys carry a
+//!   TTL. `leases` scores the leased attempts by lease expiry for the reaper;
+//! - `dead`: dead-lettered attempts by when they were dead-lettered;
+//! - `run:{id}`, `interrupt:{id}` and the evolution records (bounties, workers, ...):
+//!   JSON in a hash per record, indexed by `index:{kind}` sets.
+//!
+//! A lease expires when the reaper ([expire_leases_and_requeue](RuntimeRepository::expire_leases_and_requeue))
+//! finds its recorded expiry older than the cutoff, as with the SQL repositories; the
+//! key TTL, an hour past that expiry, only cleans up leases nobody reaps. Listing runs,
+//! stats and dead letters scan their records, and dispatch skips over delayed attempts
+//! in the queue, so keep those calls off hot paths on large stores. Scripts build the
+//! keys they touch, so the repository needs a standalone Redis (or a Sentinel-managed
+//! primary), not Redis Cluster.
+//!
+//! # Durability
+//!
+//! Redis acknowledges a write before it is on disk. With `appendonly yes` and the
+//! default `appendfsync everysec`, a crash loses up to a second of writes; with RDB
+//! snapshots alone, everything since the last snapshot; a failover loses whatever the
+//! replica had not received yet. For the runtime that means an enqueued attempt can
+//! vanish, a finished attempt can come back leased or queued and run again, and a lease
+//! can roll back to a version a stale worker still holds. `appendfsync always` closes
+//! most of that window at a latency cost; deployments that cannot rerun work must keep a
+//! SQL repository. Because none of this can be checked from the client,
+//! [RedisRuntimeRepository::new] refuses to start until
+//! [RedisRepositoryConfig::acknowledge_persistence] says the server's persistence
+//! settings were reviewed against it.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, Utc};
+use redis::{Commands, ConnectionLike, Script};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use oris_kernel::event::KernelError;
+use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::PageRequest;
+
+use super::models::{
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    BountyRecord, BountyStatus, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions,
+    DispatchableAttempts, DisputeRecord, DisputeStatus, EnqueueBatchOutcome, EnqueueOptions,
+    InterruptDecision, InterruptFilter, InterruptRecord, InterruptStatus, LeaseDirective,
+    LeaseFence, LeaseRecord, OrganismRecord, RecipeRecord, RetryPolicyConfig, RunRecord,
+    RunRecordFilter, RunRuntimeStatus, RuntimeStats, SessionMessageRecord, SessionRecord,
+    SwarmTaskRecord, TimeoutPolicyConfig, WorkerRecord,
+};
+use super::repository::{run_tenant_in_scope, RuntimeRepository};
+
+/// Records fetched per pipeline when scanning an index
+const SCAN_CHUNK: usize = 500;
+
+/// Helpers every script starts with. `ARGV[1]` is the key prefix and `ARGV[2]` the
+/// tenant scope (empty when unscoped); script arguments start at `ARGV[3]`.
+const LUA_PRELUDE: &str = r#"
+local prefix = ARGV[1]
+local scope = ARGV[2]
+-- Lease keys outlive their expiry by this long, so only unreaped leases time out
+local LEASE_RETENTION_MS = 3600000
+
+local function key(...)
+    return prefix .. ':' .. table.concat({...}, ':')
+end
+
+local function hash(k)
+    local flat = redis.call('HGETALL', k)
+    if #flat == 0 then
+        return nil
+    end
+    local fields = {}
+    for i = 1, #flat, 2 do
+        fields[flat[i]] = flat[i + 1]
+    end
+    return fields
+end
+
+local function in_scope(tenant)
+    return scope == '' or tenant == scope
+end
+
+local function ms(value)
+    if value == nil or value == false or value == '' then
+        return nil
+    end
+    return tonumber(value)
+end
+
+local function waiting(a)
+    return a.status == 'queued' or a.status == 'retry_backoff'
+end
+
+local function finished(a)
+    return a.status == 'completed' or a.status == 'failed' or a.status == 'cancelled'
+end
+
+-- When a waiting attempt held back by run_at or a retry backoff becomes dispatchable
+local function wake_at(a)
+    local at = ms(a.run_at)
+    if a.status == 'retry_backoff' then
+        local retry_at = ms(a.retry_at)
+        if retry_at ~= nil and (at == nil or retry_at > at) then
+            at = retry_at
+        end
+    end
+    return at
+end
+
+local function ready(a, now)
+    local at = wake_at(a)
+    return waiting(a) and (at == nil or at <= now)
+end
+
+local function queue(id, a)
+    local member = string.format('%016d:%010d:%020d:%s',
+        tonumber(a.enqueued_at), tonumber(a.attempt_no), tonumber(a.seq), id)
+    a.member = member
+    redis.call('HSET', key('attempt', id), 'member', member)
+    redis.call('ZADD', key('queue'), -tonumber(a.priority), member)
+    local at = wake_at(a)
+    if at ~= nil then
+        redis.call('ZADD', key('wake'), at, id)
+    end
+end
+
+local function unqueue(id, a)
+    if a.member ~= nil and a.member ~= '' then
+        redis.call('ZREM', key('queue'), a.member)
+        redis.call('HSET', key('attempt', id), 'member', '')
+        a.member = ''
+    end
+    redis.call('ZREM', key('wake'), id)
+end
+
+local function keep_lease(id, lease_id, expires_at, now)
+    local ttl = math.max(expires_at - now, 0) + LEASE_RETENTION_MS
+    redis.call('PEXPIRE', key('lease', id), ttl)
+    redis.call('SET', key('lease_id', lease_id), id, 'PX', ttl)
+end
+
+local function grant_lease(id, a, worker, expires_at, now, lease_id)
+    unqueue(id, a)
+    redis.call('HSET', key('attempt', id), 'status', 'leased')
+    if ms(a.started_at) == nil then
+        redis.call('HSET', key('attempt', id), 'started_at', string.format('%d', now))
+    end
+    redis.call('HSET', key('lease', id), 'lease_id', lease_id, 'worker_id', worker,
+        'expires_at', string.format('%d', expires_at), 'heartbeat_at', string.format('%d', now),
+        'version', 1, 'tenant', a.tenant)
+    keep_lease(id, lease_id, expires_at, now)
+    redis.call('ZADD', key('leases'), expires_at, id)
+    redis.call('SADD', key('run_leases', a.run_id), id)
+end
+
+local function release_lease(id, run_id)
+    local lease_id = redis.call('HGET', key('lease', id), 'lease_id')
+    if lease_id then
+        redis.call('DEL', key('lease_id', lease_id))
+    end
+    redis.call('DEL', key('lease', id))
+    redis.call('ZREM', key('leases'), id)
+    if run_id ~= nil then
+        redis.call('SREM', key('run_leases', run_id), id)
+    end
+end
+
+local function active_run_leases(run_id, now)
+    local count = 0
+    for _, id in ipairs(redis.call('SMEMBERS', key('run_leases', run_id))) do
+        local expires_at = redis.call('ZSCORE', key('leases'), id)
+        if expires_at and tonumber(expires_at) >= now then
+            count = count + 1
+        end
+    end
+    return count
+end
+
+-- Whether the fence (lease id, worker, version) still holds attempt id, unexpired
+local function fenced(id, lease_id, worker, version, now)
+    local lease = hash(key('lease', id))
+    return lease ~= nil and lease.lease_id == lease_id and lease.worker_id == worker
+        and lease.version == version and tonumber(lease.expires_at) >= now
+end
+
+local function fence_conflict(id, lease_id, worker, version)
+    return {'lease_conflict', 'lease ' .. lease_id .. ' (worker ' .. worker .. ', version '
+        .. version .. ') no longer holds attempt: ' .. id}
+end
+"#;
+
+/// `ARGV[3]`: enqueue time, then `id, run_id, priority, run_at` per attempt.
+const LUA_ENQUEUE: &str = r#"
+local now = ARGV[3]
+-- Check the whole batch before inserting, so a bad entry enqueues nothing
+for i = 4, #ARGV, 4 do
+    local id, run_id = ARGV[i], ARGV[i + 1]
+    local run_tenant = redis.call('HGET', key('run', run_id), 'tenant')
+    if run_tenant and not in_scope(run_tenant) then
+        return {'not_found', 'run not found: ' .. run_id}
+    end
+    if not run_tenant and redis.call('EXISTS', key('attempt', id)) == 0 then
+        return {'not_found', 'run not found for attempt ' .. id .. ': ' .. run_id}
+    end
+end
+local inserted, skipped = 0, 0
+for i = 4, #ARGV, 4 do
+    local id = ARGV[i]
+    if redis.call('EXISTS', key('attempt', id)) == 1 then
+        skipped = skipped + 1
+    else
+        local a = {
+            run_id = ARGV[i + 1],
+            attempt_no = '1',
+            status = 'queued',
+            priority = ARGV[i + 2],
+            run_at = ARGV[i + 3],
+            retry_at = '',
+            enqueued_at = now,
+            seq = tostring(redis.call('INCR', key('enqueue_seq'))),
+            tenant = redis.call('HGET', key('run', ARGV[i + 1]), 'tenant'),
+        }
+        redis.call('HSET', key('attempt', id), 'run_id', a.run_id, 'attempt_no', a.attempt_no,
+            'status', a.status, 'priority', a.priority, 'run_at', a.run_at, 'retry_at', '',
+            'enqueued_at', now, 'seq', a.seq, 'tenant', a.tenant, 'started_at', '',
+            'cancel_requested', '0', 'last_error', '', 'dead_lettered_at', '')
+        redis.call('SADD', key('index', 'attempt'), id)
+        queue(id, a)
+        inserted = inserted + 1
+    end
+end
+return {'ok', tostring(inserted), tostring(skipped)}
+"#;
+
+/// `ARGV[3..8]`: now, limit, max leases per run (0 for none), worker (empty to only
+/// list), lease expiry, lease id suffix. Replies with the attempts held back by the
+/// per-run limit, then 8 fields per attempt.
+const LUA_DISPATCH: &str = r#"
+local now, limit, max_per_run = tonumber(ARGV[3]), tonumber(ARGV[4]), tonumber(ARGV[5])
+local worker = ARGV[6]
+local picked, skipped, held = {}, 0, {}
+local offset = 0
+-- A per-run limit counts every held-back attempt, so it walks the whole queue
+while #picked < limit or max_per_run > 0 do
+    local members = redis.call('ZRANGE', key('queue'), offset, offset + 99)
+    if #members == 0 then
+        break
+    end
+    offset = offset + #members
+    for _, member in ipairs(members) do
+        local id = string.sub(member, 50)
+        local a = hash(key('attempt', id))
+        if a ~= nil and in_scope(a.tenant) and ready(a, now) then
+            local keep = true
+            if max_per_run > 0 then
+                if held[a.run_id] == nil then
+                    held[a.run_id] = active_run_leases(a.run_id, now)
+                end
+                keep = held[a.run_id] < max_per_run
+                if not keep then
+                    skipped = skipped + 1
+                end
+            end
+            if keep and #picked < limit then
+                a.id = id
+                table.insert(picked, a)
+            end
+        end
+    end
+end
+local reply = {'ok', tostring(skipped)}
+for _, a in ipairs(picked) do
+    local lease_id = ''
+    if worker ~= '' then
+        lease_id = 'lease-' .. a.id .. '-' .. ARGV[8]
+    end
+    for _, field in ipairs({a.id, a.run_id, a.attempt_no, a.status, a.retry_at, a.priority,
+        a.tenant, lease_id}) do
+        table.insert(reply, field)
+    end
+    if worker ~= '' then
+        grant_lease(a.id, a, worker, tonumber(ARGV[7]), now, lease_id)
+    end
+end
+return reply
+"#;
+
+/// `ARGV[3..7]`: attempt id, worker, lease expiry, now, lease id.
+const LUA_UPSERT_LEASE: &str = r#"
+local id, now = ARGV[3], tonumber(ARGV[6])
+local a = hash(key('attempt', id))
+if a ~= nil and not in_scope(a.tenant) then
+    return {'not_found', 'attempt not found: ' .. id}
+end
+local expires_at = redis.call('ZSCORE', key('leases'), id)
+if expires_at and tonumber(expires_at) < now then
+    release_lease(id, a and a.run_id)
+    expires_at = false
+end
+if expires_at then
+    return {'lease_conflict', 'active lease already exists for attempt: ' .. id}
+end
+if a == nil or not waiting(a) then
+    return {'not_dispatchable', 'attempt is not dispatchable for lease: ' .. id}
+end
+grant_lease(id, a, ARGV[4], tonumber(ARGV[5]), now, ARGV[7])
+return {'ok', a.tenant}
+"#;
+
+/// `ARGV[3..7]`: lease id, heartbeat time, new expiry, then worker and expected version
+/// (both empty for an unfenced heartbeat).
+const LUA_HEARTBEAT: &str = r#"
+local lease_id, worker = ARGV[3], ARGV[6]
+local id = redis.call('GET', key('lease_id', lease_id))
+local lease = id and hash(key('lease', id))
+local visible = lease and lease.lease_id == lease_id and in_scope(lease.tenant)
+if worker ~= '' then
+    if not (visible and lease.worker_id == worker and lease.version == ARGV[7]) then
+        return {'lease_conflict', 'lease heartbeat version conflict for lease: ' .. lease_id}
+    end
+elseif not visible then
+    return {'not_found', 'lease not found for heartbeat: ' .. lease_id}
+end
+local heartbeat_at, expires_at = tonumber(ARGV[4]), tonumber(ARGV[5])
+redis.call('HSET', key('lease', id), 'heartbeat_at', ARGV[4], 'expires_at', ARGV[5])
+redis.call('HINCRBY', key('lease', id), 'version', 1)
+redis.call('ZADD', key('leases'), expires_at, id)
+keep_lease(id, lease_id, expires_at, heartbeat_at)
+if redis.call('HGET', key('attempt', id), 'cancel_requested') == '1' then
+    return {'ok', 'cancel'}
+end
+return {'ok', 'continue'}
+"#;
+
+/// `ARGV[3]`: the stale cutoff; leases expiring before it are reaped.
+const LUA_EXPIRE: &str = r#"
+local count = 0
+for _, id in ipairs(redis.call('ZRANGEBYSCORE', key('leases'), '-inf', '(' .. ARGV[3])) do
+    local a = hash(key('attempt', id))
+    local tenant = a and a.tenant or redis.call('HGET', key('lease', id), 'tenant') or ''
+    if in_scope(tenant) then
+        release_lease(id, a and a.run_id)
+        count = count + 1
+        if a ~= nil and not finished(a) then
+            unqueue(id, a)
+            -- An attempt asked to cancel is not handed to another worker
+            if a.cancel_requested == '1' then
+                redis.call('HSET', key('attempt', id), 'status', 'cancelled')
+            else
+                a.status = 'queued'
+                redis.call('HSET', key('attempt', id), 'status', 'queued')
+                queue(id, a)
+            end
+        end
+    end
+end
+return {'ok', tostring(count)}
+"#;
+
+/// `ARGV[3..8]`: attempt id, status, now, then lease id, worker and version (lease id
+/// empty when unfenced).
+const LUA_ACK: &str = r#"
+local id, now = ARGV[3], tonumber(ARGV[5])
+local a = hash(key('attempt', id))
+if a ~= nil and not in_scope(a.tenant) then
+    return {'not_found', 'attempt not found: ' .. id}
+end
+if ARGV[6] ~= '' and not fenced(id, ARGV[6], ARGV[7], ARGV[8], now) then
+    return fence_conflict(id, ARGV[6], ARGV[7], ARGV[8])
+end
+if a == nil then
+    return {'not_found', 'attempt not found for ack: ' .. id}
+end
+local status = ARGV[4]
+-- A failure of an attempt asked to cancel ends it as cancelled, without a retry
+if status == 'failed' and a.cancel_requested == '1' then
+    status = 'cancelled'
+end
+unqueue(id, a)
+release_lease(id, a.run_id)
+a.status, a.retry_at = status, ''
+redis.call('HSET', key('attempt', id), 'status', status, 'retry_at', '', 'started_at', '')
+if waiting(a) then
+    queue(id, a)
+elseif status == 'dead_letter' then
+    redis.call('ZADD', key('dead'), now, id)
+end
+return {'ok', status}
+"#;
+
+/// `ARGV[3..11]`: attempt id, error, now, lease id, worker, version (lease id empty
+/// when unfenced), the attempt_no the backoff was computed for, that backoff, and the
+/// policy's max retries. Replies `stale` with the current attempt_no when it moved on.
+const LUA_FAIL: &str = r#"
+local id, now = ARGV[3], tonumber(ARGV[5])
+local a = hash(key('attempt', id))
+if a ~= nil and not in_scope(a.tenant) then
+    return {'not_found', 'attempt not found: ' .. id}
+end
+if ARGV[6] ~= '' and not fenced(id, ARGV[6], ARGV[7], ARGV[8], now) then
+    return fence_conflict(id, ARGV[6], ARGV[7], ARGV[8])
+end
+if a == nil then
+    return {'not_found', 'attempt not found for failure: ' .. id}
+end
+local attempt_no = math.max(tonumber(a.attempt_no), 1)
+if attempt_no ~= tonumber(ARGV[9]) then
+    return {'ok', 'stale', tostring(attempt_no)}
+end
+unqueue(id, a)
+release_lease(id, a.run_id)
+local attempt_key = key('attempt', id)
+redis.call('HSET', attempt_key, 'retry_at', '', 'started_at', '', 'last_error', ARGV[4])
+if a.cancel_requested == '1' then
+    redis.call('HSET', attempt_key, 'status', 'cancelled')
+    return {'ok', 'cancelled', '', tostring(attempt_no)}
+elseif attempt_no <= tonumber(ARGV[11]) then
+    a.status = 'retry_backoff'
+    a.retry_at = string.format('%d', now + tonumber(ARGV[10]))
+    a.attempt_no = tostring(attempt_no + 1)
+    redis.call('HSET', attempt_key, 'status', a.status, 'retry_at', a.retry_at,
+        'attempt_no', a.attempt_no)
+    queue(id, a)
+    return {'ok', 'retry_backoff', a.retry_at, a.attempt_no}
+end
+redis.call('HSET', attempt_key, 'status', 'dead_letter', 'dead_lettered_at', ARGV[5])
+redis.call('ZADD', key('dead'), now, id)
+return {'ok', 'dead_letter', '', tostring(attempt_no)}
+"#;
+
+/// `ARGV[3]`: attempt id.
+const LUA_CANCEL: &str = r#"
+local id = ARGV[3]
+local a = hash(key('attempt', id))
+if a == nil or not in_scope(a.tenant) then
+    return {'not_found', 'attempt not found for cancel: ' .. id}
+end
+if waiting(a) then
+    unqueue(id, a)
+    redis.call('HSET', key('attempt', id), 'status', 'cancelled', 'retry_at', '',
+        'started_at', '', 'cancel_requested', '1')
+    return {'ok', 'cancelled'}
+elseif a.status == 'leased' or a.status == 'running' then
+    redis.call('HSET', key('attempt', id), 'cancel_requested', '1')
+    return {'ok', 'requested'}
+end
+return {'ok', 'finished', a.status}
+"#;
+
+/// `ARGV[3]`: attempt id.
+const LUA_REQUEUE_DEAD_LETTER: &str = r#"
+local id = ARGV[3]
+local a = hash(key('attempt', id))
+if a == nil or not in_scope(a.tenant) then
+    return {'not_found', 'attempt not found for requeue: ' .. id}
+end
+if a.status ~= 'dead_letter' then
+    return {'conflict', 'attempt ' .. id .. ' is ' .. a.status .. ', not dead-lettered'}
+end
+a.status, a.retry_at = 'queued', ''
+redis.call('HSET', key('attempt', id), 'status', 'queued', 'retry_at', '',
+    'dead_lettered_at', '')
+redis.call('ZREM', key('dead'), id)
+queue(id, a)
+return {'ok'}
+"#;
+
+/// `ARGV[3]`: now.
+const LUA_TIME_OUT: &str = r#"
+local now, count = tonumber(ARGV[3]), 0
+for _, id in ipairs(redis.call('ZRANGE', key('leases'), 0, -1)) do
+    local a = hash(key('attempt', id))
+    if a ~= nil and in_scope(a.tenant) and (a.status == 'leased' or a.status == 'running')
+        and ms(a.timeout_ms) ~= nil and ms(a.started_at) ~= nil
+        and ms(a.started_at) + ms(a.timeout_ms) <= now then
+        release_lease(id, a.run_id)
+        redis.call('HSET', key('attempt', id), 'status', a.on_timeout, 'retry_at', '',
+            'started_at', '')
+        count = count + 1
+    end
+end
+return {'ok', tostring(count)}
+"#;
+
+/// `ARGV[3]`: now. Replies with the earliest wake-up after it, if any.
+const LUA_NEXT_WAKE: &str = r#"
+local offset = 0
+while true do
+    local entries = redis.call('ZRANGEBYSCORE', key('wake'), '(' .. ARGV[3], '+inf',
+        'WITHSCORES', 'LIMIT', offset, 100)
+    if #entries == 0 then
+        return {'ok'}
+    end
+    for i = 1, #entries, 2 do
+        local tenant = redis.call('HGET', key('attempt', entries[i]), 'tenant')
+        if tenant and in_scope(tenant) then
+            return {'ok', entries[i + 1]}
+        end
+    end
+    offset = offset + #entries / 2
+end
+"#;
+
+/// `ARGV[3..5]`: record key, index key (empty for none), id, then field/value pairs.
+/// Replies `1` when inserted and `0` when the key is taken.
+const LUA_INSERT_RECORD: &str = r#"
+if redis.call('EXISTS', ARGV[3]) == 1 then
+    return {'ok', '0'}
+end
+redis.call('HSET', ARGV[3], unpack(ARGV, 6))
+if ARGV[4] ~= '' then
+    redis.call('SADD', ARGV[4], ARGV[5])
+end
+return {'ok', '1'}
+"#;
+
+struct RedisScripts {
+    enqueue: Script,
+    dispatch: Script,
+    upsert_lease: Script,
+    heartbeat: Script,
+    expire: Script,
+    ack: Script,
+    fail: Script,
+    cancel: Script,
+    requeue_dead_letter: Script,
+    time_out: Script,
+    next_wake: Script,
+    insert_record: Script,
+}
+
+impl RedisScripts {
+    fn new() -> Self {
+        let script = |body: &str| Script::new(&format!("{}{}", LUA_PRELUDE, body));
+        Self {
+            enqueue: script(LUA_ENQUEUE),
+            dispatch: script(LUA_DISPATCH),
+            upsert_lease: script(LUA_UPSERT_LEASE),
+            heartbeat: script(LUA_HEARTBEAT),
+            expire: script(LUA_EXPIRE),
+            ack: script(LUA_ACK),
+            fail: script(LUA_FAIL),
+            cancel: script(LUA_CANCEL),
+            requeue_dead_letter: script(LUA_REQUEUE_DEAD_LETTER),
+            time_out: script(LUA_TIME_OUT),
+            next_wake: script(LUA_NEXT_WAKE),
+            insert_record: script(LUA_INSERT_RECORD),
+        }
+    }
+}
+
+/// An attempt the dispatch script picked, with its lease id when it was claimed.
+struct DispatchedAttempt {
+    record: AttemptDispatchRecord,
+    tenant_id: Option<String>,
+    lease_id: String,
+}
+
+/// Settings for [RedisRuntimeRepository::new].
+#[derive(Clone, Debug)]
+pub struct RedisRepositoryConfig {
+    /// Prefix of every key the repository writes, so deployments can share a server.
+    pub key_prefix: String,
+    /// Confirms the server's persistence settings were reviewed against the durability
+    /// trade-offs in the [module docs](self); the repository refuses to start without it.
+    pub acknowledge_persistence: bool,
+}
+
+impl RedisRepositoryConfig {
+    pub const DEFAULT_KEY_PREFIX: &'static str = "oris:runtime";
+
+    /// Default settings with the persistence trade-offs acknowledged.
+    pub fn acknowledged() -> Self {
+        Self {
+            acknowledge_persistence: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+
+    fn validate(&self) -> Result<(), KernelError> {
+        if !self.acknowledge_persistence {
+            return Err(KernelError::Validation(
+                "redis runtime repository requires acknowledge_persistence: Redis can lose \
+                 acknowledged writes depending on its appendonly/appendfsync and snapshot \
+                 settings, so attempts may be lost or run again"
+                    .to_string(),
+            ));
+        }
+        if self.key_prefix.trim().is_empty() {
+            return Err(KernelError::Validation(
+                "redis runtime repository key_prefix must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RedisRepositoryConfig {
+    fn default() -> Self {
+        Self {
+            key_prefix: Self::DEFAULT_KEY_PREFIX.to_string(),
+            acknowledge_persistence: false,
+        }
+    }
+}
+
+/// [RuntimeRepository] on Redis; see the [module docs](self) for the layout and the
+/// durability trade-offs.
+///
+/// Clones share one connection, reopened on the next call after it breaks; open
+/// separate repositories for workers that should not wait on each other.
+#[derive(Clone)]
+pub struct RedisRuntimeRepository {
+    client: redis::Client,
+    connection: Arc<Mutex<Option<redis::Connection>>>,
+    scripts: Arc<RedisScripts>,
+    key_prefix: String,
+    tenant_id: Option<String>,
+}
+
+impl std::fmt::Debug for RedisRuntimeRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisRuntimeRepository")
+            .field("key_prefix", &self.key_prefix)
+            .field("tenant_id", &self.tenant_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisRuntimeRepository {
+    /// Connect to `redis_url` (e.g. `redis://localhost:6379/0`). Fails with `Validation`
+    /// when `config` does not acknowledge Redis persistence or the url is malformed, and
+    /// with `Storage` when the server cannot be reached.
+    pub fn new(redis_url: &str, config: RedisRepositoryConfig) -> Result<Self, KernelError> {
+        config.validate()?;
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| KernelError::Validation(format!("invalid redis url: {}", e)))?;
+        let connection = client
+            .get_connection()
+            .map_err(|e| map_redis_err("connect", e))?;
+        Ok(Self {
+            client,
+            connection: Arc::new(Mutex::new(Some(connection))),
+            scripts: Arc::new(RedisScripts::new()),
+            key_prefix: config.key_prefix,
+            tenant_id: None,
+        })
+    }
+
+    /// A handle sharing this repository's connection that only sees `tenant_id`'s runs,
+    /// attempts, leases and interrupts; records of other tenants behave as if they did
+    /// not exist. Runs created through it belong to `tenant_id`.
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Tenant this handle is scoped to, if any.
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    pub fn get_lease_for_attempt(
+        &self,
+        attempt_id: &str,
+    ) -> Result<Option<LeaseRecord>, KernelError> {
+        let key = self.key(&["lease", attempt_id]);
+        let fields: Vec<Option<String>> = self.with_connection(|con| {
+            redis::cmd("HMGET")
+                .arg(&key)
+                .arg(&[
+                    "lease_id",
+                    "worker_id",
+                    "expires_at",
+                    "heartbeat_at",
+                    "version",
+                    "tenant",
+                ])
+                .query(con)
+                .map_err(|e| map_redis_err("read lease", e))
+        })?;
+        let [Some(lease_id), Some(worker_id), Some(expires_at), Some(heartbeat_at), Some(version), Some(tenant)] =
+            <[Option<String>; 6]>::try_from(fields).unwrap_or_default()
+        else {
+            return Ok(None);
+        };
+        let tenant_id = non_empty(tenant);
+        if !self.in_scope(tenant_id.as_deref()) {
+            return Ok(None);
+        }
+        Ok(Some(LeaseRecord {
+            lease_id,
+            attempt_id: attempt_id.to_string(),
+            worker_id,
+            lease_expires_at: ms_to_dt(parse_i64(&expires_at)?),
+            heartbeat_at: ms_to_dt(parse_i64(&heartbeat_at)?),
+            version: parse_i64(&version)? as u64,
+            terminal_state: None,
+            terminal_at: None,
+            tenant_id,
+        }))
+    }
+
+    /// Bound how long `attempt_id` may stay leased or running; see
+    /// [transition_timed_out_attempts](RuntimeRepository::transition_timed_out_attempts).
+    pub fn set_attempt_timeout_policy(
+        &self,
+        attempt_id: &str,
+        policy: &TimeoutPolicyConfig,
+    ) -> Result<(), KernelError> {
+        if policy.timeout_ms <= 0 {
+            return Err(KernelError::Validation(
+                "timeout policy timeout_ms must be > 0".to_string(),
+            ));
+        }
+        if !matches!(
+            policy.on_timeout_status,
+            AttemptExecutionStatus::Failed | AttemptExecutionStatus::Cancelled
+        ) {
+            return Err(KernelError::Validation(
+                "timeout policy terminal status must be failed or cancelled".to_string(),
+            ));
+        }
+        let key = self.key(&["attempt", attempt_id]);
+        self.with_connection(|con| {
+            let tenant: Option<String> = con
+                .hget(&key, "tenant")
+                .map_err(|e| map_redis_err("read attempt", e))?;
+            if !tenant.is_some_and(|tenant| self.in_scope(non_empty(tenant).as_deref())) {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found for timeout policy: {}",
+                    attempt_id
+                )));
+            }
+            redis::cmd("HSET")
+                .arg(&key)
+                .arg("timeout_ms")
+                .arg(policy.timeout_ms)
+                .arg("on_timeout")
+                .arg(attempt_status_to_str(&policy.on_timeout_status))
+                .query::<()>(con)
+                .map_err(|e| map_redis_err("set attempt timeout policy", e))
+        })
+    }
+
+    fn key(&self, parts: &[&str]) -> String {
+        let mut key = self.key_prefix.clone();
+        for part in parts {
+            key.push(':');
+            key.push_str(part);
+        }
+        key
+    }
+
+    fn in_scope(&self, tenant_id: Option<&str>) -> bool {
+        self.tenant_id.is_none() || self.tenant_id.as_deref() == tenant_id
+    }
+
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&mut redis::Connection) -> Result<T, KernelError>,
+    ) -> Result<T, KernelError> {
+        let mut guard: MutexGuard<'_, Option<redis::Connection>> = self
+            .connection
+            .lock()
+            .map_err(|_| KernelError::Driver("redis runtime repo lock poisoned".to_string()))?;
+        if !guard.as_ref().is_some_and(|con| con.is_open()) {
+            *guard = Some(
+                self.client
+                    .get_connection()
+                    .map_err(|e| map_redis_err("reconnect", e))?,
+            );
+        }
+        f(guard.as_mut().expect("connection opened above"))
+    }
+
+    /// Run `script` with the prefix and tenant scope ahead of `args`, returning the
+    /// reply past its leading status.
+    fn invoke(&self, script: &Script, args: &[String]) -> Result<Vec<String>, KernelError> {
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .arg(&self.key_prefix)
+            .arg(self.tenant_id.as_deref().unwrap_or(""));
+        for arg in args {
+            invocation.arg(arg);
+        }
+        let mut reply: Vec<String> = self.with_connection(|con| {
+            invocation
+                .invoke(con)
+                .map_err(|e| map_redis_err("run script", e))
+        })?;
+        if reply.is_empty() {
+            return Err(KernelError::Storage(
+                "redis runtime repo: empty script reply".to_string(),
+            ));
+        }
+        let rest = reply.split_off(1);
+        let message = || rest.first().cloned().unwrap_or_default();
+        match reply[0].as_str() {
+            "ok" => Ok(rest),
+            "not_found" => Err(KernelError::NotFound(message())),
+            "lease_conflict" => Err(KernelError::LeaseConflict(message())),
+            "not_dispatchable" => Err(KernelError::NotDispatchable(message())),
+            "conflict" => Err(KernelError::Conflict(message())),
+            other => Err(KernelError::Storage(format!(
+                "redis runtime repo: unexpected script reply {}",
+                other
+            ))),
+        }
+    }
+
+    fn dispatch(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        max_per_run: Option<usize>,
+        lease: Option<(&str, DateTime<Utc>)>,
+    ) -> Result<(Vec<DispatchedAttempt>, usize), KernelError> {
+        let (worker_id, lease_expires_at) = lease.unwrap_or(("", now));
+        let reply = self.invoke(
+            &self.scripts.dispatch,
+            &[
+                dt_to_ms(now).to_string(),
+                limit.to_string(),
+                max_per_run.unwrap_or(0).to_string(),
+                worker_id.to_string(),
+                dt_to_ms(lease_expires_at).to_string(),
+                now.timestamp_nanos_opt().unwrap_or(0).to_string(),
+            ],
+        )?;
+        let skipped = parse_i64(&reply[0])? as usize;
+        let attempts = reply[1..]
+            .chunks(8)
+            .map(|fields| {
+                let record = AttemptDispatchRecord {
+                    attempt_id: fields[0].clone(),
+                    run_id: fields[1].clone(),
+                    attempt_no: parse_i64(&fields[2])? as u32,
+                    status: parse_attempt_status(&fields[3]),
+                    retry_at: parse_opt_ms(&fields[4])?,
+                    priority: parse_i64(&fields[5])? as i32,
+                };
+                Ok(DispatchedAttempt {
+                    record,
+                    tenant_id: non_empty(fields[6].clone()),
+                    lease_id: fields[7].clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, KernelError>>()?;
+        Ok((attempts, skipped))
+    }
+
+    fn ack_attempt(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        let (lease_id, worker_id, version) = fence_args(lease);
+        let reply = self.invoke(
+            &self.scripts.ack,
+            &[
+                attempt_id.to_string(),
+                attempt_status_to_str(&status).to_string(),
+                dt_to_ms(now).to_string(),
+                lease_id,
+                worker_id,
+                version,
+            ],
+        )?;
+        Ok(parse_attempt_status(&reply[0]))
+    }
+
+    fn fail_attempt_inner(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        let (lease_id, worker_id, version) = fence_args(lease);
+        let key = self.key(&["attempt", attempt_id]);
+        let mut attempt_no: u32 = self
+            .with_connection(|con| {
+                con.hget::<_, _, Option<u32>>(&key, "attempt_no")
+                    .map_err(|e| map_redis_err("read attempt", e))
+            })?
+            .unwrap_or(1)
+            .max(1);
+        // The backoff depends on attempt_no, so it is computed here and the script
+        // rejects it if another failure moved attempt_no on in between
+        loop {
+            let backoff_ms = retry_policy.next_backoff_ms(attempt_no).max(1);
+            let reply = self.invoke(
+                &self.scripts.fail,
+                &[
+                    attempt_id.to_string(),
+                    error.to_string(),
+                    dt_to_ms(now).to_string(),
+                    lease_id.clone(),
+                    worker_id.clone(),
+                    version.clone(),
+                    attempt_no.to_string(),
+                    backoff_ms.to_string(),
+                    retry_policy.max_retries.to_string(),
+                ],
+            )?;
+            if reply[0] == "stale" {
+                attempt_no = parse_i64(&reply[1])? as u32;
+                continue;
+            }
+            return Ok(AttemptAckOutcome {
+                status: parse_attempt_status(&reply[0]),
+                next_retry_at: parse_opt_ms(&reply[1])?,
+                next_attempt_no: parse_i64(&reply[2])? as u32,
+            });
+        }
+    }
+
+    /// Store a new record of `kind` as JSON plus `fields`; false when `id` is taken.
+    fn insert_record<T: Serialize>(
+        &self,
+        kind: &str,
+        id: &str,
+        record: &T,
+        fields: &[(&str, &str)],
+        index: Option<String>,
+    ) -> Result<bool, KernelError> {
+        let mut args = vec![
+            self.key(&[kind, id]),
+            index.unwrap_or_default(),
+            id.to_string(),
+            "json".to_string(),
+            to_json(record)?,
+        ];
+        for (field, value) in fields {
+            args.push(field.to_string());
+            args.push(value.to_string());
+        }
+        let reply = self.invoke(&self.scripts.insert_record, &args)?;
+        Ok(reply[0] == "1")
+    }
+
+    fn get_record<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        id: &str,
+    ) -> Result<Option<T>, KernelError> {
+        let key = self.key(&[kind, id]);
+        let json: Option<String> = self.with_connection(|con| {
+            con.hget(&key, "json")
+                .map_err(|e| map_redis_err("read record", e))
+        })?;
+        json.as_deref().map(from_json).transpose()
+    }
+
+    /// Every record of `kind` listed in the set `index`.
+    fn list_records<T: DeserializeOwned>(
+        &self,
+        index: &str,
+        kind: &str,
+    ) -> Result<Vec<T>, KernelError> {
+        let ids: Vec<String> = self.with_connection(|con| {
+            con.sscan::<_, String>(index)
+                .map(|ids| ids.collect())
+                .map_err(|e| map_redis_err("scan index", e))
+        })?;
+        let mut records = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(SCAN_CHUNK) {
+            let mut pipe = redis::pipe();
+            for id in chunk {
+                pipe.hget(self.key(&[kind, id]), "json");
+            }
+            let rows: Vec<Option<String>> = self.with_connection(|con| {
+                pipe.query(con)
+                    .map_err(|e| map_redis_err("read records", e))
+            })?;
+            for json in rows.into_iter().flatten() {
+                records.push(from_json(&json)?);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Replace the record of `kind` with what `apply` makes of it (`None` when there is
+    /// none), retrying when it changes in between; an error from `apply` is returned
+    /// with nothing written.
+    fn update_record<T, F>(&self, kind: &str, id: &str, mut apply: F) -> Result<T, KernelError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(Option<T>) -> Result<T, KernelError>,
+    {
+        let key = self.key(&[kind, id]);
+        let index = self.key(&["index", kind]);
+        self.with_connection(|con| {
+            redis::transaction(con, &[&key], |con, pipe| {
+                let current: Option<String> = con.hget(&key, "json")?;
+                let updated = match current
+                    .as_deref()
+                    .map(from_json)
+                    .transpose()
+                    .and_then(&mut apply)
+                {
+                    Ok(updated) => updated,
+                    Err(e) => return Ok(Some(Err(e))),
+                };
+                let json = match to_json(&updated) {
+                    Ok(json) => json,
+                    Err(e) => return Ok(Some(Err(e))),
+                };
+                pipe.hset(&key, "json", json)
+                    .ignore()
+                    .sadd(&index, id)
+                    .ignore()
+                    .query::<Option<()>>(con)
+                    .map(|committed| committed.map(|()| Ok(updated)))
+            })
+            .map_err(|e| map_redis_err("update record", e))?
+        })
+    }
+
+    /// Tenant of a recorded run: `None` for an unknown run, `Some(None)` for one
+    /// without a tenant.
+    fn run_tenant(&self, run_id: &str) -> Result<Option<Option<String>>, KernelError> {
+        let key = self.key(&["run", run_id]);
+        let tenant: Option<String> = self.with_connection(|con| {
+            con.hget(&key, "tenant")
+                .map_err(|e| map_redis_err("read run", e))
+        })?;
+        Ok(tenant.map(non_empty))
+    }
+
+    fn run_in_scope(&self, run_id: &str) -> Result<bool, KernelError> {
+        if self.tenant_id.is_none() {
+            return Ok(true);
+        }
+        Ok(self
+            .run_tenant(run_id)?
+            .is_some_and(|tenant| self.in_scope(tenant.as_deref())))
+    }
+}
+
+fn map_redis_err(context: &str, e: redis::RedisError) -> KernelError {
+    KernelError::Storage(format!("redis runtime repo {}: {}", context, e))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, KernelError> {
+    serde_json::to_string(value)
+        .map_err(|e| KernelError::Storage(format!("redis runtime repo encode record: {}", e)))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, KernelError> {
+    serde_json::from_str(json)
+        .map_err(|e| KernelError::Storage(format!("redis runtime repo decode record: {}", e)))
+}
+
+fn dt_to_ms(value: DateTime<Utc>) -> i64 {
+    value.timestamp_millis()
+}
+
+fn ms_to_dt(value: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(value).unwrap_or_default()
+}
+
+fn parse_i64(value: &str) -> Result<i64, KernelError> {
+    // Sorted-set scores come back as floats
+    value
+        .parse::<i64>()
+        .or_else(|_| value.parse::<f64>().map(|v| v as i64))
+        .map_err(|_| KernelError::Storage(format!("redis runtime repo: not a number: {:?}", value)))
+}
+
+fn parse_opt_ms(value: &str) -> Result<Option<DateTime<Utc>>, KernelError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    parse_i64(value).map(|ms| Some(ms_to_dt(ms)))
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+fn fence_args(lease: Option<&LeaseFence>) -> (String, String, String) {
+    lease.map_or_else(Default::default, |lease| {
+        (
+            lease.lease_id.clone(),
+            lease.worker_id.clone(),
+            lease.version.to_string(),
+        )
+    })
+}
+
+fn attempt_status_to_str(status: &AttemptExecutionStatus) -> &'static str {
+    match status {
+        AttemptExecutionStatus::Queued => "queued",
+        AttemptExecutionStatus::Leased => "leased",
+        AttemptExecutionStatus::Running => "running",
+        AttemptExecutionStatus::RetryBackoff => "retry_backoff",
+        AttemptExecutionStatus::Completed => "completed",
+        AttemptExecutionStatus::Failed => "failed",
+        AttemptExecutionStatus::Cancelled => "cancelled",
+        AttemptExecutionStatus::DeadLetter => "dead_letter",
+    }
+}
+
+fn parse_attempt_status(value: &str) -> AttemptExecutionStatus {
+    match value {
+        "leased" => AttemptExecutionStatus::Leased,
+        "running" => AttemptExecutionStatus::Running,
+        "retry_backoff" => AttemptExecutionStatus::RetryBackoff,
+        "completed" => AttemptExecutionStatus::Completed,
+        "failed" => AttemptExecutionStatus::Failed,
+        "cancelled" => AttemptExecutionStatus::Cancelled,
+        "dead_letter" => AttemptExecutionStatus::DeadLetter,
+        _ => AttemptExecutionStatus::Queued,
+    }
+}
+
+impl RuntimeRepository for RedisRuntimeRepository {
+    fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        if batch.is_empty() {
+            return Ok(EnqueueBatchOutcome::default());
+        }
+        let mut args = vec![dt_to_ms(Utc::now()).to_string()];
+        for (attempt_id, run_id, options) in batch {
+            args.push(attempt_id.clone());
+            args.push(run_id.clone());
+            args.push(options.priority.to_string());
+            args.push(
+                options
+                    .run_at
+                    .map(|at| dt_to_ms(at).to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        let reply = self.invoke(&self.scripts.enqueue, &args)?;
+        Ok(EnqueueBatchOutcome {
+            inserted: parse_i64(&reply[0])? as usize,
+            skipped: parse_i64(&reply[1])? as usize,
+        })
+    }
+
+    fn list_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+        let (attempts, _) = self.dispatch(now, limit, None, None)?;
+        Ok(attempts.into_iter().map(|attempt| attempt.record).collect())
+    }
+
+    fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        let (attempts, skipped_for_fairness) =
+            self.dispatch(now, limit, options.max_concurrent_per_run, None)?;
+        Ok(DispatchableAttempts {
+            attempts: attempts.into_iter().map(|attempt| attempt.record).collect(),
+            skipped_for_fairness,
+        })
+    }
+
+    fn next_dispatch_at(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, KernelError> {
+        let reply = self.invoke(&self.scripts.next_wake, &[dt_to_ms(now).to_string()])?;
+        reply.first().map_or(Ok(None), |at| parse_opt_ms(at))
+    }
+
+    fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let lease_expires_at = now + lease_ttl;
+        let (attempts, _) = self.dispatch(now, limit, None, Some((worker_id, lease_expires_at)))?;
+        Ok(attempts
+            .into_iter()
+            .map(|dispatched| ClaimedAttempt {
+                lease: LeaseRecord {
+                    lease_id: dispatched.lease_id,
+                    attempt_id: dispatched.record.attempt_id.clone(),
+                    worker_id: worker_id.to_string(),
+                    lease_expires_at,
+                    heartbeat_at: now,
+                    version: 1,
+                    terminal_state: None,
+                    terminal_at: None,
+                    tenant_id: dispatched.tenant_id,
+                },
+                attempt: dispatched.record,
+            })
+            .collect())
+    }
+
+    fn upsert_lease(
+        &self,
+        attempt_id: &str,
+        worker_id: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseRecord, KernelError> {
+        let now = Utc::now();
+        let lease_id = format!(
+            "lease-{}-{}",
+            attempt_id,
+            now.timestamp_nanos_opt().unwrap_or(0)
+        );
+        let reply = self.invoke(
+            &self.scripts.upsert_lease,
+            &[
+                attempt_id.to_string(),
+                worker_id.to_string(),
+                dt_to_ms(lease_expires_at).to_string(),
+                dt_to_ms(now).to_string(),
+                lease_id.clone(),
+            ],
+        )?;
+        Ok(LeaseRecord {
+            lease_id,
+            attempt_id: attempt_id.to_string(),
+            worker_id: worker_id.to_string(),
+            lease_expires_at,
+            heartbeat_at: now,
+            version: 1,
+            terminal_state: None,
+            terminal_at: None,
+            tenant_id: non_empty(reply[0].clone()),
+        })
+    }
+
+    fn heartbeat_lease(
+        &self,
+        lease_id: &str,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let reply = self.invoke(
+            &self.scripts.heartbeat,
+            &[
+                lease_id.to_string(),
+                dt_to_ms(heartbeat_at).to_string(),
+                dt_to_ms(lease_expires_at).to_string(),
+                String::new(),
+                String::new(),
+            ],
+        )?;
+        Ok(parse_directive(&reply[0]))
+    }
+
+    fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let reply = self.invoke(
+            &self.scripts.heartbeat,
+            &[
+                lease_id.to_string(),
+                dt_to_ms(heartbeat_at).to_string(),
+                dt_to_ms(lease_expires_at).to_string(),
+                worker_id.to_string(),
+                expected_version.to_string(),
+            ],
+        )?;
+        Ok(parse_directive(&reply[0]))
+    }
+
+    fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError> {
+        let reply = self.invoke(&self.scripts.expire, &[dt_to_ms(stale_before).to_string()])?;
+        Ok(parse_i64(&reply[0])? as u64)
+    }
+
+    fn transition_timed_out_attempts(&self, now: DateTime<Utc>) -> Result<u64, KernelError> {
+        let reply = self.invoke(&self.scripts.time_out, &[dt_to_ms(now).to_string()])?;
+        Ok(parse_i64(&reply[0])? as u64)
+    }
+
+    fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.ack_attempt(attempt_id, None, status, now)
+    }
+
+    fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.fail_attempt_inner(attempt_id, None, error, retry_policy, now)
+    }
+
+    fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        self.ack_attempt(attempt_id, Some(lease), status, now)
+    }
+
+    fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.fail_attempt_inner(attempt_id, Some(lease), error, retry_policy, now)
+    }
+
+    fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        let reply = self.invoke(&self.scripts.cancel, &[attempt_id.to_string()])?;
+        Ok(match reply[0].as_str() {
+            "cancelled" => AttemptCancellation::Cancelled,
+            "requested" => AttemptCancellation::Requested,
+            _ => AttemptCancellation::AlreadyFinished(parse_attempt_status(&reply[1])),
+        })
+    }
+
+    fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        let dead_key = self.key(&["dead"]);
+        let ids: Vec<String> = self.with_connection(|con| {
+            con.zrange(&dead_key, 0, -1)
+                .map_err(|e| map_redis_err("read dead letters", e))
+        })?;
+        let mut dead = Vec::new();
+        for chunk in ids.chunks(SCAN_CHUNK) {
+            let mut pipe = redis::pipe();
+            for id in chunk {
+                pipe.cmd("HMGET").arg(self.key(&["attempt", id])).arg(&[
+                    "status",
+                    "tenant",
+                    "run_id",
+                    "attempt_no",
+                    "last_error",
+                    "dead_lettered_at",
+                ]);
+            }
+            let rows: Vec<[Option<String>; 6]> = self.with_connection(|con| {
+                pipe.query(con)
+                    .map_err(|e| map_redis_err("read dead letters", e))
+            })?;
+            for (id, row) in chunk.iter().zip(rows) {
+                let [Some(status), Some(tenant), Some(run_id), Some(attempt_no), error, Some(at)] =
+                    row
+                else {
+                    continue;
+                };
+                if status != "dead_letter" || !self.in_scope(non_empty(tenant).as_deref()) {
+                    continue;
+                }
+                dead.push(DeadLetterAttemptRecord {
+                    attempt_id: id.clone(),
+                    run_id,
+                    attempt_no: parse_i64(&attempt_no)? as u32,
+                    error: error.and_then(non_empty),
+                    dead_lettered_at: parse_opt_ms(&at)?.unwrap_or_default(),
+                });
+            }
+        }
+        dead.sort_by(|a, b| {
+            b.dead_lettered_at
+                .cmp(&a.dead_lettered_at)
+                .then(a.attempt_id.cmp(&b.attempt_id))
+        });
+        dead.truncate(limit);
+        Ok(dead)
+    }
+
+    fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        self.invoke(&self.scripts.requeue_dead_letter, &[attempt_id.to_string()])
+            .map(|_| ())
+    }
+
+    fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        let now_ms = dt_to_ms(now);
+        let index = self.key(&["index", "attempt"]);
+        let ids: Vec<String> = self.with_connection(|con| {
+            con.sscan::<_, String>(&index)
+                .map(|ids| ids.collect())
+                .map_err(|e| map_redis_err("scan attempts", e))
+        })?;
+        let mut stats = RuntimeStats::default();
+        let mut oldest_enqueued: Option<i64> = None;
+        let mut visible = std::collections::HashSet::new();
+        for chunk in ids.chunks(SCAN_CHUNK) {
+            let mut pipe = redis::pipe();
+            for id in chunk {
+                pipe.cmd("HMGET").arg(self.key(&["attempt", id])).arg(&[
+                    "status",
+                    "tenant",
+                    "enqueued_at",
+                    "retry_at",
+                ]);
+            }
+            let rows: Vec<[Option<String>; 4]> = self.with_connection(|con| {
+                pipe.query(con)
+                    .map_err(|e| map_redis_err("read attempts", e))
+            })?;
+            for (id, row) in chunk.iter().zip(rows) {
+                let [Some(status), Some(tenant), Some(enqueued_at), retry_at] = row else {
+                    continue;
+                };
+                if !self.in_scope(non_empty(tenant).as_deref()) {
+                    continue;
+                }
+                visible.insert(id.clone());
+                let status = parse_attempt_status(&status);
+                stats.attempts.add(&status, 1);
+                match status {
+                    AttemptExecutionStatus::Queued => {
+                        let enqueued_at = parse_i64(&enqueued_at)?;
+                        oldest_enqueued =
+                            Some(oldest_enqueued.map_or(enqueued_at, |t| t.min(enqueued_at)));
+                    }
+                    AttemptExecutionStatus::RetryBackoff => {
+                        let retry_at = parse_opt_ms(retry_at.as_deref().unwrap_or(""))?;
+                        if retry_at.map_or(true, |retry_at| retry_at <= now) {
+                            stats.retry_backlog += 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let leases_key = self.key(&["leases"]);
+        let leased: Vec<String> = self.with_connection(|con| {
+            con.zrangebyscore(&leases_key, now_ms, "+inf")
+                .map_err(|e| map_redis_err("read leases", e))
+        })?;
+        stats.active_leases = leased.iter().filter(|id| visible.contains(*id)).count() as u64;
+        stats.oldest_queued_age_ms =
+            oldest_enqueued.map(|enqueued_at| (now_ms - enqueued_at).max(0));
+        Ok(stats)
+    }
+
+    fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
+        Ok(0)
+    }
+
+    // ============== Run Methods ==============
+
+    fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        let tenant_id = run_tenant_in_scope(run, self.tenant_id())?;
+        let record = RunRecord {
+            tenant_id: tenant_id.clone(),
+            ..run.clone()
+        };
+        let tenant = tenant_id.unwrap_or_default();
+        if !self.insert_record(
+            "run",
+            &run.run_id,
+            &record,
+            &[("tenant", &tenant)],
+            Some(self.key(&["index", "run"])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "run already exists: {}",
+                run.run_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        Ok(self
+            .get_record::<RunRecord>("run", run_id)?
+            .filter(|run| self.in_scope(run.tenant_id.as_deref())))
+    }
+
+    fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        self.update_record("run", run_id, |run: Option<RunRecord>| {
+            let Some(mut run) = run.filter(|run| self.in_scope(run.tenant_id.as_deref())) else {
+                return Err(KernelError::NotFound(format!(
+                    "run not found for status update: {}",
+                    run_id
+                )));
+            };
+            if run.status.is_final() && run.status != status {
+                return Err(KernelError::Conflict(format!(
+                    "run {} is already {}",
+                    run_id,
+                    run.status.as_str()
+                )));
+            }
+            run.status = status.clone();
+            run.status_reason = reason.map(str::to_string);
+            run.updated_at = Utc::now();
+            Ok(run)
+        })
+        .map(|_| ())
+    }
+
+    fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        let mut runs: Vec<RunRecord> = self
+            .list_records::<RunRecord>(&self.key(&["index", "run"]), "run")?
+            .into_iter()
+            .filter(|run| self.in_scope(run.tenant_id.as_deref()))
+            .filter(|run| filter.status.as_ref().map_or(true, |s| run.status == *s))
+            .filter(|run| {
+                filter
+                    .workflow_name
+                    .as_ref()
+                    .map_or(true, |name| run.workflow_name == *name)
+            })
+            .filter(|run| filter.created_after.map_or(true, |at| run.created_at >= at))
+            .filter(|run| filter.created_before.map_or(true, |at| run.created_at < at))
+            .collect();
+        runs.sort_by(|a, b| {
+            b.updated_at
+                .cmp(&a.updated_at)
+                .then(a.run_id.cmp(&b.run_id))
+        });
+        Ok(runs
+            .into_iter()
+            .skip(page.offset)
+            .take(page.limit)
+            .collect())
+    }
+
+    // ============== Bounty Methods ==============
+
+    fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
+        self.update_record(
+            "bounty",
+            &bounty.bounty_id,
+            |existing: Option<BountyRecord>| {
+                Ok(match existing {
+                    Some(existing) => BountyRecord {
+                        created_by: existing.created_by,
+                        created_at_ms: existing.created_at_ms,
+                        ..bounty.clone()
+                    },
+                    None => bounty.clone(),
+                })
+            },
+        )
+        .map(|_| ())
+    }
+
+    fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRecord>, KernelError> {
+        self.get_record("bounty", bounty_id)
+    }
+
+    fn list_bounties(
+        &self,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BountyRecord>, KernelError> {
+        let mut bounties: Vec<BountyRecord> = self
+            .list_records::<BountyRecord>(&self.key(&["index", "bounty"]), "bounty")?
+            .into_iter()
+            .filter(|bounty| status.map_or(true, |status| bounty.status.as_str() == status))
+            .collect();
+        bounties.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        bounties.truncate(limit);
+        Ok(bounties)
+    }
+
+    fn accept_bounty(&self, bounty_id: &str, accepted_by: &str) -> Result<(), KernelError> {
+        let now = Utc::now().timestamp_millis();
+        self.update_record("bounty", bounty_id, |bounty: Option<BountyRecord>| {
+            let Some(mut bounty) = bounty.filter(|bounty| bounty.status == BountyStatus::Open)
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "bounty not found or not in open status: {}",
+                    bounty_id
+                )));
+            };
+            bounty.status = BountyStatus::Accepted;
+            bounty.accepted_by = Some(accepted_by.to_string());
+            bounty.accepted_at_ms = Some(now);
+            Ok(bounty)
+        })
+        .map(|_| ())
+    }
+
+    fn close_bounty(&self, bounty_id: &str) -> Result<(), KernelError> {
+        let now = Utc::now().timestamp_millis();
+        self.update_record("bounty", bounty_id, |bounty: Option<BountyRecord>| {
+            let Some(mut bounty) = bounty.filter(|bounty| bounty.status != BountyStatus::Closed)
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "bounty not found or already closed: {}",
+                    bounty_id
+                )));
+            };
+            bounty.status = BountyStatus::Closed;
+            bounty.closed_at_ms = Some(now);
+            Ok(bounty)
+        })
+        .map(|_| ())
+    }
+
+    // ============== Swarm Methods ==============
+
+    fn upsert_swarm_decomposition(&self, task: &SwarmTaskRecord) -> Result<(), KernelError> {
+        self.update_record(
+            "swarm_task",
+            &task.parent_task_id,
+            |existing: Option<SwarmTaskRecord>| {
+                Ok(match existing {
+                    Some(existing) => SwarmTaskRecord {
+                        decomposition_json: task.decomposition_json.clone(),
+                        status: task.status.clone(),
+                        completed_at_ms: task.completed_at_ms,
+                        ..existing
+                    },
+                    None => task.clone(),
+                })
+            },
+        )
+        .map(|_| ())
+    }
+
+    fn get_swarm_decomposition(
+        &self,
+        parent_task_id: &str,
+    ) -> Result<Option<SwarmTaskRecord>, KernelError> {
+        self.get_record("swarm_task", parent_task_id)
+    }
+
+    // ============== Worker Methods ==============
+
+    fn register_worker(&self, worker: &WorkerRecord) -> Result<(), KernelError> {
+        self.update_record(
+            "worker",
+            &worker.worker_id,
+            |existing: Option<WorkerRecord>| {
+                Ok(match existing {
+                    Some(existing) => WorkerRecord {
+                        registered_at_ms: existing.registered_at_ms,
+                        ..worker.clone()
+                    },
+                    None => worker.clone(),
+                })
+            },
+        )
+        .map(|_| ())
+    }
+
+    fn get_worker(&self, worker_id: &str) -> Result<Option<WorkerRecord>, KernelError> {
+        self.get_record("worker", worker_id)
+    }
+
+    fn list_workers(
+        &self,
+        domain: Option<&str>,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkerRecord>, KernelError> {
+        let mut workers: Vec<WorkerRecord> = self
+            .list_records::<WorkerRecord>(&self.key(&["index", "worker"]), "worker")?
+            .into_iter()
+            .filter(|worker| domain.map_or(true, |domain| worker.domains.contains(domain)))
+            .filter(|worker| status.map_or(true, |status| worker.status == status))
+            .collect();
+        workers.sort_by_key(|record| std::cmp::Reverse(record.registered_at_ms));
+        workers.truncate(limit);
+        Ok(workers)
+    }
+
+    fn heartbeat_worker(&self, worker_id: &str, heartbeat_at_ms: i64) -> Result<(), KernelError> {
+        self.update_record("worker", worker_id, |worker: Option<WorkerRecord>| {
+            let Some(mut worker) = worker else {
+                return Err(KernelError::NotFound(format!(
+                    "worker not found: {}",
+                    worker_id
+                )));
+            };
+            worker.last_heartbeat_ms = Some(heartbeat_at_ms);
+            worker.status = "active".to_string();
+            Ok(worker)
+        })
+        .map(|_| ())
+    }
+
+    // ============== Recipe Methods ==============
+
+    fn create_recipe(&self, recipe: &RecipeRecord) -> Result<(), KernelError> {
+        if !self.insert_record(
+            "recipe",
+            &recipe.recipe_id,
+            recipe,
+            &[],
+            Some(self.key(&["index", "recipe"])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "recipe already exists: {}",
+                recipe.recipe_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_recipe(&self, recipe_id: &str) -> Result<Option<RecipeRecord>, KernelError> {
+        self.get_record("recipe", recipe_id)
+    }
+
+    fn fork_recipe(
+        &self,
+        original_id: &str,
+        new_id: &str,
+        new_author: &str,
+    ) -> Result<Option<RecipeRecord>, KernelError> {
+        let now = Utc::now().timestamp_millis();
+        let Some(original) = self.get_recipe(original_id)? else {
+            return Ok(None);
+        };
+        let fork = RecipeRecord {
+            recipe_id: new_id.to_string(),
+            name: format!("Fork of {}", original.name),
+            author_id: new_author.to_string(),
+            forked_from: Some(original_id.to_string()),
+            created_at_ms: now,
+            updated_at_ms: now,
+            ..original
+        };
+        self.create_recipe(&fork)?;
+        Ok(Some(fork))
+    }
+
+    fn list_recipes(
+        &self,
+        author_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecipeRecord>, KernelError> {
+        let mut recipes: Vec<RecipeRecord> = self
+            .list_records::<RecipeRecord>(&self.key(&["index", "recipe"]), "recipe")?
+            .into_iter()
+            .filter(|recipe| author_id.map_or(true, |author| recipe.author_id == author))
+            .collect();
+        recipes.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        recipes.truncate(limit);
+        Ok(recipes)
+    }
+
+    // ============== Organism Methods ==============
+
+    fn express_organism(&self, organism: &OrganismRecord) -> Result<(), KernelError> {
+        if !self.insert_record(
+            "organism",
+            &organism.organism_id,
+            organism,
+            &[],
+            Some(self.key(&["index", "organism"])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "organism already exists: {}",
+                organism.organism_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_organism(&self, organism_id: &str) -> Result<Option<OrganismRecord>, KernelError> {
+        self.get_record("organism", organism_id)
+    }
+
+    fn update_organism(
+        &self,
+        organism_id: &str,
+        current_step: i32,
+        status: &str,
+    ) -> Result<(), KernelError> {
+        let now = Utc::now().timestamp_millis();
+        self.update_record(
+            "organism",
+            organism_id,
+            |organism: Option<OrganismRecord>| {
+                let Some(mut organism) = organism else {
+                    return Err(KernelError::NotFound(format!(
+                        "organism not found: {}",
+                        organism_id
+                    )));
+                };
+                organism.current_step = current_step;
+                organism.status = status.to_string();
+                organism.completed_at_ms = (status == "completed").then_some(now);
+                Ok(organism)
+            },
+        )
+        .map(|_| ())
+    }
+
+    // ============== Session Methods ==============
+
+    fn create_session(&self, session: &SessionRecord) -> Result<(), KernelError> {
+        if !self.insert_record(
+            "session",
+            &session.session_id,
+            session,
+            &[],
+            Some(self.key(&["index", "session"])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "session already exists: {}",
+                session.session_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_session(&self, session_id: &str) -> Result<Option<SessionRecord>, KernelError> {
+        self.get_record("session", session_id)
+    }
+
+    fn add_session_message(&self, message: &SessionMessageRecord) -> Result<(), KernelError> {
+        // Indexed per session, so a session's history does not scan every message
+        if !self.insert_record(
+            "session_message",
+            &message.message_id,
+            message,
+            &[],
+            Some(self.key(&["index", "session_message", &message.session_id])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "session message already exists: {}",
+                message.message_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_session_history(
+        &self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionMessageRecord>, KernelError> {
+        let mut messages: Vec<SessionMessageRecord> = self.list_records(
+            &self.key(&["index", "session_message", session_id]),
+            "session_message",
+        )?;
+        messages.sort_by_key(|record| std::cmp::Reverse(record.sent_at_ms));
+        messages.truncate(limit);
+        Ok(messages)
+    }
+
+    // ============== Dispute Methods ==============
+
+    fn open_dispute(&self, dispute: &DisputeRecord) -> Result<(), KernelError> {
+        if !self.insert_record(
+            "dispute",
+            &dispute.dispute_id,
+            dispute,
+            &[],
+            Some(self.key(&["index", "dispute"])),
+        )? {
+            return Err(KernelError::Conflict(format!(
+                "dispute already exists: {}",
+                dispute.dispute_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRecord>, KernelError> {
+        self.get_record("dispute", dispute_id)
+    }
+
+    fn get_disputes_for_bounty(&self, bounty_id: &str) -> Result<Vec<DisputeRecord>, KernelError> {
+        let mut disputes: Vec<DisputeRecord> = self
+            .list_records::<DisputeRecord>(&self.key(&["index", "dispute"]), "dispute")?
+            .into_iter()
+            .filter(|dispute| dispute.bounty_id == bounty_id)
+            .collect();
+        disputes.sort_by_key(|record| std::cmp::Reverse(record.created_at_ms));
+        Ok(disputes)
+    }
+
+    fn resolve_dispute(
+        &self,
+        dispute_id: &str,
+        resolution: &str,
+        resolved_by: &str,
+    ) -> Result<(), KernelError> {
+        let now = Utc::now().timestamp_millis();
+        self.update_record("dispute", dispute_id, |dispute: Option<DisputeRecord>| {
+            let Some(mut dispute) = dispute.filter(|dispute| dispute.status == DisputeStatus::Open)
+            else {
+                return Err(KernelError::NotFound(format!(
+                    "dispute not found or already resolved: {}",
+                    dispute_id
+                )));
+            };
+            dispute.status = DisputeStatus::Resolved;
+            dispute.resolution = Some(resolution.to_string());
+            dispute.resolved_by = Some(resolved_by.to_string());
+            dispute.resolved_at_ms = Some(now);
+            Ok(dispute)
+        })
+        .map(|_| ())
+    }
+
+    // ============== Interrupt Methods ==============
+
+    fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        if let Some(tenant) = self.run_tenant(&interrupt.run_id)? {
+            if !self.in_scope(tenant.as_deref()) {
+                return Err(KernelError::NotFound(format!(
+                    "run not found: {}",
+                    interrupt.run_id
+                )));
+            }
+        }
+        // Only pending interrupts are indexed: the inbox is all that lists them
+        let index = (interrupt.status == InterruptStatus::Pending)
+            .then(|| self.key(&["index", "pending_interrupt"]));
+        if !self.insert_record("interrupt", &interrupt.interrupt_id, interrupt, &[], index)? {
+            return Err(KernelError::Conflict(format!(
+                "interrupt already exists: {}",
+                interrupt.interrupt_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let mut pending = Vec::new();
+        for interrupt in self.list_records::<InterruptRecord>(
+            &self.key(&["index", "pending_interrupt"]),
+            "interrupt",
+        )? {
+            if interrupt.status == InterruptStatus::Pending
+                && filter
+                    .run_id
+                    .as_ref()
+                    .map_or(true, |run_id| interrupt.run_id == *run_id)
+                && self.run_in_scope(&interrupt.run_id)?
+            {
+                pending.push(interrupt);
+            }
+        }
+        pending.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then(a.interrupt_id.cmp(&b.interrupt_id))
+        });
+        pending.truncate(filter.effective_limit());
+        Ok(pending)
+    }
+
+    fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let now = Utc::now();
+        let not_found = || KernelError::NotFound(format!("interrupt not found: {}", interrupt_id));
+        let Some(current) = self.get_record::<InterruptRecord>("interrupt", interrupt_id)? else {
+            return Err(not_found());
+        };
+        if !self.run_in_scope(&current.run_id)? {
+            return Err(not_found());
+        }
+        let key = self.key(&["interrupt", interrupt_id]);
+        let pending_index = self.key(&["index", "pending_interrupt"]);
+        self.with_connection(|con| {
+            redis::transaction(con, &[&key], |con, pipe| {
+                let json: Option<String> = con.hget(&key, "json")?;
+                let mut interrupt = match json.as_deref().map(from_json::<InterruptRecord>) {
+                    Some(Ok(interrupt)) => interrupt,
+                    Some(Err(e)) => return Ok(Some(Err(e))),
+                    None => return Ok(Some(Err(not_found()))),
+                };
+                if interrupt.status != InterruptStatus::Pending {
+                    return Ok(Some(Err(KernelError::Conflict(format!(
+                        "interrupt {} already {}",
+                        interrupt_id,
+                        interrupt.status.as_str()
+                    )))));
+                }
+                interrupt.status = decision.resolved_status();
+                interrupt.decision = Some(decision);
+                interrupt.resolution = Some(value.clone());
+                interrupt.resolved_at = Some(now);
+                let json = match to_json(&interrupt) {
+                    Ok(json) => json,
+                    Err(e) => return Ok(Some(Err(e))),
+                };
+                pipe.hset(&key, "json", json)
+                    .ignore()
+                    .srem(&pending_index, interrupt_id)
+                    .ignore()
+                    .query::<Option<()>>(con)
+                    .map(|committed| committed.map(|()| Ok(interrupt)))
+            })
+            .map_err(|e| map_redis_err("resolve interrupt", e))?
+        })
+    }
+}
+
+fn parse_directive(value: &str) -> LeaseDirective {
+    if value == "cancel" {
+        LeaseDirective::Cancel
+    } else {
+        LeaseDirective::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use oris_kernel::KernelError;
+
+    use super::{RedisRepositoryConfig, RedisRuntimeRepository};
+    use crate::repository::RuntimeRepository;
+    use crate::repository_contract::{
+        assert_async_dispatch_lease_requeue_contract, assert_attempt_failure_contract,
+        assert_cancel_attempt_contract, assert_concurrent_claim_contract,
+        assert_dispatch_lease_requeue_contract, assert_enqueue_batch_contract,
+        assert_interrupt_inbox_contract, assert_lease_fencing_contract,
+        assert_max_concurrent_per_run_contract, assert_priority_dispatch_order_contract,
+        assert_run_at_contract, assert_run_lifecycle_contract, assert_run_record_contract,
+        assert_runtime_stats_contract, assert_semantic_roundtrip, assert_tenant_isolation_contract,
+        ContractHarness,
+    };
+
+    impl ContractHarness for RedisRuntimeRepository {
+        fn has_lease(&self, attempt_id: &str) -> bool {
+            self.get_lease_for_attempt(attempt_id)
+                .expect("redis get lease")
+                .is_some()
+        }
+    }
+
+    fn test_redis_url() -> Option<String> {
+        std::env::var("ORIS_TEST_REDIS_URL").ok()
+    }
+
+    /// A key prefix no other test run shares, so each test starts from an empty store.
+    fn test_prefix(name: &str) -> String {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        format!("oris-runtime-test:{}:{}", name, ts)
+    }
+
+    fn redis_repo(url: &str, prefix: &str) -> RedisRuntimeRepository {
+        RedisRuntimeRepository::new(
+            url,
+            RedisRepositoryConfig::acknowledged().with_key_prefix(prefix),
+        )
+        .expect("redis repo")
+    }
+
+    /// Runs `check` against a fresh repository when `ORIS_TEST_REDIS_URL` is set.
+    fn with_redis_repo(name: &str, check: impl FnOnce(&RedisRuntimeRepository)) {
+        let Some(url) = test_redis_url() else {
+            return;
+        };
+        check(&redis_repo(&url, &test_prefix(name)));
+    }
+
+    #[test]
+    fn new_requires_acknowledged_persistence() {
+        let err =
+            RedisRuntimeRepository::new("redis://127.0.0.1:1/", RedisRepositoryConfig::default())
+                .expect_err("unacknowledged config is rejected");
+        assert!(
+            matches!(&err, KernelError::Validation(message) if message.contains("acknowledge_persistence")),
+            "{err:?}"
+        );
+        let err = RedisRuntimeRepository::new(
+            "redis://127.0.0.1:1/",
+            RedisRepositoryConfig::acknowledged().with_key_prefix(" "),
+        )
+        .expect_err("blank key prefix is rejected");
+        assert!(matches!(err, KernelError::Validation(_)), "{err:?}");
+    }
+
+    #[test]
+    fn runtime_repository_contract_redis_when_env_is_set() {
+        with_redis_repo("dispatch", |repo| {
+            assert_dispatch_lease_requeue_contract(repo, "redis")
+        });
+    }
+
+    #[tokio::test]
+    async fn runtime_repository_async_contract_redis_when_env_is_set() {
+        let Some(url) = test_redis_url() else {
+            return;
+        };
+        let repo = redis_repo(&url, &test_prefix("async"));
+        assert_async_dispatch_lease_requeue_contract(&repo, "redis-async").await;
+    }
+
+    #[test]
+    fn runtime_repository_priority_order_contract_redis_when_env_is_set() {
+        with_redis_repo("priority", |repo| {
+            assert_priority_dispatch_order_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_run_at_contract_redis_when_env_is_set() {
+        with_redis_repo("run-at", |repo| assert_run_at_contract(repo, "redis"));
+    }
+
+    #[test]
+    fn runtime_repository_concurrent_claim_contract_redis_when_env_is_set() {
+        let Some(url) = test_redis_url() else {
+            return;
+        };
+        let prefix = test_prefix("concurrent-claim");
+        let repos = (0..4).map(|_| redis_repo(&url, &prefix)).collect();
+        assert_concurrent_claim_contract(repos, "redis");
+    }
+
+    #[test]
+    fn runtime_repository_max_concurrent_per_run_contract_redis_when_env_is_set() {
+        with_redis_repo("fan-out", |repo| {
+            assert_max_concurrent_per_run_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_lease_fencing_contract_redis_when_env_is_set() {
+        with_redis_repo("fencing", |repo| {
+            assert_lease_fencing_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_cancel_attempt_contract_redis_when_env_is_set() {
+        with_redis_repo("cancel", |repo| {
+            assert_cancel_attempt_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_attempt_failure_contract_redis_when_env_is_set() {
+        with_redis_repo("failure", |repo| {
+            assert_attempt_failure_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_runtime_stats_contract_redis_when_env_is_set() {
+        with_redis_repo("stats", |repo| assert_runtime_stats_contract(repo, "redis"));
+    }
+
+    #[test]
+    fn runtime_repository_run_record_contract_redis_when_env_is_set() {
+        with_redis_repo("run-record", |repo| {
+            assert_run_record_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_enqueue_batch_contract_redis_when_env_is_set() {
+        with_redis_repo("enqueue-batch", |repo| {
+            assert_enqueue_batch_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_run_lifecycle_contract_redis_when_env_is_set() {
+        with_redis_repo("run-lifecycle", |repo| {
+            assert_run_lifecycle_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_interrupt_inbox_contract_redis_when_env_is_set() {
+        with_redis_repo("interrupts", |repo| {
+            assert_interrupt_inbox_contract(repo, "redis")
+        });
+    }
+
+    #[test]
+    fn runtime_repository_semantic_contract_redis_when_env_is_set() {
+        with_redis_repo("semantic", |repo| assert_semantic_roundtrip(repo, "redis"));
+    }
+
+    #[test]
+    fn runtime_repository_tenant_isolation_contract_redis_when_env_is_set() {
+        with_redis_repo("tenants", |operator| {
+            let tenant_a = operator.clone().with_tenant("a");
+            let tenant_b = operator.clone().with_tenant("b");
+            assert_tenant_isolation_contract(&tenant_a, &tenant_b, operator, "redis");
+        });
+    }
+
+    #[test]
+    fn timed_out_attempts_end_with_their_policy_status_redis_when_env_is_set() {
+        with_redis_repo("timeouts", |repo| {
+            use crate::models::{AttemptExecutionStatus, TimeoutPolicyConfig};
+            use crate::repository_contract::seed_run;
+            use chrono::{Duration, Utc};
+
+            seed_run(repo, "run-timeout");
+            repo.enqueue_attempts(&[(
+                "attempt-timeout".to_string(),
+                "run-timeout".to_string(),
+                Default::default(),
+            )])
+            .expect("enqueue attempt");
+            repo.set_attempt_timeout_policy(
+                "attempt-timeout",
+                &TimeoutPolicyConfig {
+                    timeout_ms: 1_000,
+                    on_timeout_status: AttemptExecutionStatus::Failed,
+                },
+            )
+            .expect("set timeout policy");
+            repo.upsert_lease(
+                "attempt-timeout",
+                "worker-timeout",
+                Utc::now() + Duration::minutes(5),
+            )
+            .expect("lease attempt");
+            assert_eq!(
+                repo.transition_timed_out_attempts(Utc::now())
+                    .expect("nothing timed out yet"),
+                0
+            );
+            assert_eq!(
+                repo.transition_timed_out_attempts(Utc::now() + Duration::seconds(2))
+                    .expect("time out attempt"),
+                1
+            );
+            assert!(!repo.has_lease("attempt-timeout"));
+            assert!(matches!(
+                repo.cancel_attempt("attempt-timeout"),
+                Ok(crate::models::AttemptCancellation::AlreadyFinished(
+                    AttemptExecutionStatus::Failed
+                ))
+            ));
+        });
+    }
+}
@@ -1,12 +1,18 @@
 //! Storage façade for runtime scheduler/lease operations.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
 
 use oris_kernel::event::KernelError;
 use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::PageRequest;
 
 use super::models::{
-    AttemptDispatchRecord, BountyRecord, DisputeRecord, LeaseRecord, OrganismRecord, RecipeRecord,
+    AttemptAckOutcome, AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus,
+    BountyRecord, ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions, DispatchableAttempts,
+    DisputeRecord, EnqueueBatchOutcome, EnqueueOptions, InterruptDecision, InterruptFilter,
+    InterruptRecord, LeaseDirective, LeaseFence, LeaseRecord, OrganismRecord, RecipeRecord,
+    RetryPolicyConfig, RunRecord, RunRecordFilter, RunRuntimeStatus, RuntimeStats,
     SessionMessageRecord, SessionRecord, SwarmTaskRecord, WorkerRecord,
 };
 
@@ -20,7 +26,24 @@ use super::models::{
 /// - `expire_leases_and_requeue` must reclaim only leases whose expiry is older
 ///   than the supplied stale cutoff, so callers can apply a heartbeat grace
 ///   window before requeueing work.
+/// - a repository scoped to a tenant (see `with_tenant` on the concrete
+///   repositories) must treat other tenants' runs, attempts, leases and
+///   interrupts as nonexistent, so a worker of one tenant can never lease
+///   another tenant's work.
 pub trait RuntimeRepository: Send + Sync {
+    /// Enqueue a batch of attempts in one transaction: on error none of them is queued.
+    /// Attempts whose id is already taken are skipped, so re-enqueuing a batch is
+    /// harmless. Fails with `NotFound` when an attempt's run was not recorded with
+    /// [Self::create_run].
+    fn enqueue_attempts(
+        &self,
+        _batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not enqueue attempts".to_string(),
+        ))
+    }
+
     /// Return attempts eligible for dispatch at the current time.
     fn list_dispatchable_attempts(
         &self,
@@ -28,6 +51,55 @@ pub trait RuntimeRepository: Send + Sync {
         limit: usize,
     ) -> Result<Vec<AttemptDispatchRecord>, KernelError>;
 
+    /// Like [Self::list_dispatchable_attempts], applying the per-run limits in `options`
+    /// and reporting how many attempts they held back.
+    fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
+        if options.max_concurrent_per_run.is_some() {
+            return Err(KernelError::Driver(
+                "this runtime repository does not limit per-run concurrency".to_string(),
+            ));
+        }
+        Ok(DispatchableAttempts {
+            attempts: self.list_dispatchable_attempts(now, limit)?,
+            skipped_for_fairness: 0,
+        })
+    }
+
+    /// Earliest time after `now` at which a queued attempt that is not yet dispatchable
+    /// (delayed `run_at` or retry backoff) becomes dispatchable, so callers can sleep
+    /// until then instead of polling. `None` when nothing is waiting on the clock.
+    fn next_dispatch_at(&self, _now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, KernelError> {
+        Ok(None)
+    }
+
+    /// Lease up to `limit` dispatchable attempts to `worker_id` for `lease_ttl`, in
+    /// dispatch order. Unlike listing and then calling [Self::upsert_lease], workers
+    /// claiming concurrently never pick the same attempt, so they neither collide nor
+    /// see lease conflicts. The default implementation does list and upsert, skipping
+    /// attempts another worker leased in between.
+    fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let mut claimed = Vec::new();
+        for attempt in self.list_dispatchable_attempts(now, limit)? {
+            match self.upsert_lease(&attempt.attempt_id, worker_id, now + lease_ttl) {
+                Ok(lease) => claimed.push(ClaimedAttempt { attempt, lease }),
+                Err(KernelError::LeaseConflict(_)) | Err(KernelError::NotDispatchable(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(claimed)
+    }
+
     /// Create or replace a lease for an attempt.
     fn upsert_lease(
         &self,
@@ -36,13 +108,30 @@ pub trait RuntimeRepository: Send + Sync {
         lease_expires_at: DateTime<Utc>,
     ) -> Result<LeaseRecord, KernelError>;
 
-    /// Refresh heartbeat for an existing lease.
+    /// Refresh heartbeat for an existing lease. Returns [LeaseDirective::Cancel] once
+    /// the lease's attempt has been cancelled with [Self::cancel_attempt].
     fn heartbeat_lease(
         &self,
         lease_id: &str,
         heartbeat_at: DateTime<Utc>,
         lease_expires_at: DateTime<Utc>,
-    ) -> Result<(), KernelError>;
+    ) -> Result<LeaseDirective, KernelError>;
+
+    /// Heartbeat a lease only while `worker_id` holds it at `expected_version`; the
+    /// version then advances by one. A stale owner or version fails with `LeaseConflict`.
+    /// The directive is as for [Self::heartbeat_lease].
+    fn heartbeat_lease_with_version(
+        &self,
+        _lease_id: &str,
+        _worker_id: &str,
+        _expected_version: u64,
+        _heartbeat_at: DateTime<Utc>,
+        _lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
 
     /// Expire stale leases and requeue affected attempts.
     fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError>;
@@ -52,9 +141,150 @@ pub trait RuntimeRepository: Send + Sync {
         Ok(0)
     }
 
+    /// Release an attempt's lease and record how its execution ended.
+    ///
+    /// Returns the status the attempt was left in: a failure comes back as
+    /// `RetryBackoff` when the attempt's retry policy allows another go.
+    fn finish_attempt(
+        &self,
+        _attempt_id: &str,
+        _status: AttemptExecutionStatus,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository cannot finish attempts".to_string(),
+        ))
+    }
+
+    /// Release a failed attempt's lease and either schedule its retry or dead-letter it.
+    ///
+    /// While the attempt's `attempt_no` is within `retry_policy.max_retries`, the attempt
+    /// moves to `RetryBackoff` with the next `attempt_no` and a backoff `retry_at`;
+    /// otherwise it moves to `DeadLetter` with `error` kept as its final error.
+    fn record_attempt_failure(
+        &self,
+        _attempt_id: &str,
+        _error: &str,
+        _retry_policy: &RetryPolicyConfig,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    /// [Self::finish_attempt] fenced by `lease`: in the same transaction the lease must
+    /// still be held, unexpired, by `lease.worker_id` at `lease.version`, otherwise the
+    /// write fails with `LeaseConflict` and the attempt is left untouched.
+    fn complete_attempt(
+        &self,
+        _attempt_id: &str,
+        _lease: &LeaseFence,
+        _status: AttemptExecutionStatus,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
+
+    /// [Self::record_attempt_failure] fenced by `lease`, as in [Self::complete_attempt].
+    fn fail_attempt(
+        &self,
+        _attempt_id: &str,
+        _lease: &LeaseFence,
+        _error: &str,
+        _retry_policy: &RetryPolicyConfig,
+        _now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not fence leases".to_string(),
+        ))
+    }
+
+    /// Cancel an attempt. One waiting for dispatch (queued or backing off) is cancelled
+    /// right away. A leased or running one is flagged: heartbeats of its lease return
+    /// [LeaseDirective::Cancel], a failure it reports is recorded as cancelled instead of
+    /// retried, and it is cancelled rather than requeued if its lease expires. A worker
+    /// that completes it first still wins. Unknown attempts fail with `NotFound`.
+    fn cancel_attempt(&self, _attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not cancel attempts".to_string(),
+        ))
+    }
+
+    /// Dead-lettered attempts, most recently dead-lettered first.
+    fn list_dead_letter_attempts(
+        &self,
+        _limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    /// Queue a dead-lettered attempt again, keeping its `attempt_no`, so it gets one more
+    /// go before the next failure dead-letters it again. Fails with `NotFound` for an
+    /// unknown attempt and `Conflict` for one that is not dead-lettered.
+    fn requeue_dead_letter(&self, _attempt_id: &str) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not retry attempts".to_string(),
+        ))
+    }
+
+    /// Attempt counts by status, active leases, the age of the oldest queued attempt and
+    /// the due retry backlog, as of `now`.
+    fn runtime_stats(&self, _now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not report stats".to_string(),
+        ))
+    }
+
     /// Returns latest persisted sequence for a run (used by replay wiring).
     fn latest_seq_for_run(&self, run_id: &RunId) -> Result<Seq, KernelError>;
 
+    // ============== Run Methods ==============
+
+    /// Record a new run; fails with `Conflict` when the run id is taken. Attempts can
+    /// only be enqueued for a recorded run.
+    fn create_run(&self, _run: &RunRecord) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    /// Get a run recorded with `create_run`.
+    fn get_run(&self, _run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    /// Move a run to `status`, recording `reason` (or clearing it) and bumping
+    /// `updated_at`. Fails with `NotFound` for an unknown run and `Conflict` once the run
+    /// is completed or cancelled, unless `status` is the one it already has.
+    fn update_run_status(
+        &self,
+        _run_id: &RunId,
+        _status: RunRuntimeStatus,
+        _reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
+    /// Runs matching `filter`, most recently updated first, windowed by `page`.
+    fn list_runs(
+        &self,
+        _filter: &RunRecordFilter,
+        _page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        Err(KernelError::Driver(
+            "this runtime repository does not store runs".to_string(),
+        ))
+    }
+
     // ============== Bounty Methods ==============
 
     /// Create or update a bounty
@@ -181,4 +411,59 @@ pub trait RuntimeRepository: Send + Sync {
         resolution: &str,
         resolved_by: &str,
     ) -> Result<(), KernelError>;
+
+    // ============== Interrupt Methods ==============
+
+    /// Persist an interrupt a run blocked on, so it shows up in the operator inbox.
+    fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        let _ = interrupt;
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+
+    /// Pending interrupts matching `filter`, oldest first.
+    fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let _ = filter;
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+
+    /// Record the operator's `decision` on a pending interrupt and return the resolved
+    /// record; `value` is what an approved run resumes with. The caller resumes or
+    /// stops the run.
+    ///
+    /// Fails with `KernelError::NotFound` for an unknown id and `KernelError::Conflict`
+    /// for an interrupt that is no longer pending, so only one decision ever wins.
+    fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let _ = (interrupt_id, decision, value);
+        Err(KernelError::Driver(
+            "this runtime repository has no interrupt inbox".to_string(),
+        ))
+    }
+}
+
+/// Tenant a run created through a repository scoped to `scope` is stored under: a run
+/// without a tenant takes the scope's, and a run of another tenant is rejected.
+pub(crate) fn run_tenant_in_scope(
+    run: &RunRecord,
+    scope: Option<&str>,
+) -> Result<Option<String>, KernelError> {
+    match (scope, run.tenant_id.as_deref()) {
+        (Some(scope), Some(tenant)) if scope != tenant => Err(KernelError::Validation(format!(
+            "run {} belongs to tenant {}, not {}",
+            run.run_id, tenant, scope
+        ))),
+        (Some(scope), _) => Ok(Some(scope.to_string())),
+        (None, tenant) => Ok(tenant.map(str::to_string)),
+    }
 }
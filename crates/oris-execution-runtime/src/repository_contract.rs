@@ -0,0 +1,1564 @@
+//! Behavioural contract shared by every [RuntimeRepository] implementation.
+//!
+//! Each `assert_*` function drives a repository through one area of the contract and
+//! panics on the first deviation, so a backend's tests only need a
+//! [ContractHarness] impl and one test per contract. The SQLite, Postgres and
+//! in-memory repositories all run these; out-of-tree backends get them through the
+//! `test-util` feature.
+
+use std::thread;
+
+use chrono::{DateTime, Duration, Utc};
+use oris_kernel::identity::RunId;
+use oris_kernel::{KernelError, PageRequest};
+
+use crate::models::{
+    AttemptCancellation, AttemptExecutionStatus, AttemptStatusCounts, BountyRecord, BountyStatus,
+    DispatchOptions, DisputeRecord, DisputeStatus, EnqueueBatchOutcome, EnqueueOptions,
+    InterruptDecision, InterruptFilter, InterruptRecord, InterruptStatus, LeaseDirective,
+    LeaseFence, OrganismRecord, RecipeRecord, RetryPolicyConfig, RetryStrategy, RunRecord,
+    RunRecordFilter, RunRuntimeStatus, RuntimeStats, SessionMessageRecord, SessionRecord,
+    SwarmTaskRecord, WorkerRecord,
+};
+use crate::{DispatchContext, RuntimeRepository, SchedulerDecision, SkeletonScheduler};
+
+/// Seeding hooks the contracts need beyond [RuntimeRepository]. Only [has_lease](Self::has_lease)
+/// has no default; the rest enqueue through
+/// [enqueue_attempts](RuntimeRepository::enqueue_attempts).
+pub trait ContractHarness: RuntimeRepository {
+    /// Records `run_id` if needed and enqueues `attempt_id` for it.
+    fn seed_attempt(&self, attempt_id: &str, run_id: &str) {
+        self.seed_attempt_with_options(attempt_id, run_id, EnqueueOptions::default());
+    }
+
+    fn seed_attempt_with_priority(&self, attempt_id: &str, run_id: &str, priority: i32) {
+        let options = EnqueueOptions {
+            priority,
+            run_at: None,
+        };
+        self.seed_attempt_with_options(attempt_id, run_id, options);
+    }
+
+    fn seed_attempt_at(&self, attempt_id: &str, run_id: &str, run_at: DateTime<Utc>) {
+        let options = EnqueueOptions {
+            priority: 0,
+            run_at: Some(run_at),
+        };
+        self.seed_attempt_with_options(attempt_id, run_id, options);
+    }
+
+    fn seed_attempt_with_options(&self, attempt_id: &str, run_id: &str, options: EnqueueOptions) {
+        seed_run(self, run_id);
+        self.enqueue_attempts(&[(attempt_id.to_string(), run_id.to_string(), options)])
+            .expect("enqueue attempt");
+    }
+
+    fn has_lease(&self, attempt_id: &str) -> bool;
+
+    /// Enqueues without recording the run first.
+    fn enqueue_attempt_for(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+        self.enqueue_attempts(&[(
+            attempt_id.to_string(),
+            run_id.to_string(),
+            EnqueueOptions::default(),
+        )])
+        .map(|_| ())
+    }
+}
+
+/// Records `run_id` unless it already is, so attempts can be enqueued for it.
+pub fn seed_run<R: RuntimeRepository + ?Sized>(repo: &R, run_id: &str) {
+    let now = Utc::now();
+    match repo.create_run(&RunRecord {
+        run_id: run_id.to_string(),
+        workflow_name: "contract".to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    }) {
+        Ok(()) | Err(oris_kernel::KernelError::Conflict(_)) => {}
+        Err(e) => panic!("seed run {}: {}", run_id, e),
+    }
+}
+/// Lease, heartbeat, expire and requeue one attempt, then finish it.
+pub fn assert_dispatch_lease_requeue_contract<R: ContractHarness>(repo: &R, name: &str) {
+    let run_id = format!("run-{}", name);
+    let attempt_id = format!("attempt-{}", name);
+    let now = Utc::now();
+
+    repo.seed_attempt(&attempt_id, &run_id);
+    let initial = repo
+        .list_dispatchable_attempts(now, 10)
+        .expect("list dispatchable initial");
+    assert!(initial.iter().any(|r| r.attempt_id == attempt_id));
+
+    let lease = repo
+        .upsert_lease(&attempt_id, "worker-a", now + Duration::seconds(1))
+        .expect("upsert lease");
+    assert!(repo.has_lease(&attempt_id));
+
+    let duplicate = repo.upsert_lease(&attempt_id, "worker-b", now + Duration::seconds(2));
+    assert!(duplicate.is_err());
+
+    let hidden = repo
+        .list_dispatchable_attempts(now, 10)
+        .expect("list dispatchable while leased");
+    assert!(!hidden.iter().any(|r| r.attempt_id == attempt_id));
+
+    repo.heartbeat_lease(
+        &lease.lease_id,
+        now + Duration::milliseconds(500),
+        now + Duration::seconds(2),
+    )
+    .expect("heartbeat lease");
+
+    let expired = repo
+        .expire_leases_and_requeue(now + Duration::seconds(10))
+        .expect("expire and requeue");
+    assert_eq!(expired, 1);
+
+    let available = repo
+        .list_dispatchable_attempts(now + Duration::seconds(10), 10)
+        .expect("list dispatchable after requeue");
+    assert!(available.iter().any(|r| r.attempt_id == attempt_id));
+
+    repo.upsert_lease(&attempt_id, "worker-b", now + Duration::seconds(30))
+        .expect("lease requeued attempt");
+    let finished = repo
+        .finish_attempt(&attempt_id, AttemptExecutionStatus::Completed, now)
+        .expect("finish attempt");
+    assert_eq!(finished, AttemptExecutionStatus::Completed);
+    assert!(!repo.has_lease(&attempt_id));
+    let after_finish = repo
+        .list_dispatchable_attempts(now + Duration::seconds(10), 10)
+        .expect("list dispatchable after finish");
+    assert!(!after_finish.iter().any(|r| r.attempt_id == attempt_id));
+
+    assert_eq!(repo.latest_seq_for_run(&run_id).expect("latest seq"), 0);
+}
+
+/// [assert_dispatch_lease_requeue_contract] through [crate::AsyncRuntimeRepository], as the
+/// worker and lease service call repositories.
+pub async fn assert_async_dispatch_lease_requeue_contract<R: crate::AsyncRuntimeRepository>(
+    repo: &R,
+    name: &str,
+) {
+    let run_id = format!("run-{}", name);
+    let attempt_id = format!("attempt-{}", name);
+    let now = Utc::now();
+
+    repo.create_run(&RunRecord {
+        run_id: run_id.clone(),
+        workflow_name: "contract".to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+    .await
+    .expect("create run");
+    repo.enqueue_attempts(&[(
+        attempt_id.clone(),
+        run_id.clone(),
+        EnqueueOptions::default(),
+    )])
+    .await
+    .expect("enqueue attempt");
+    let initial = repo
+        .list_dispatchable_attempts(now, 10)
+        .await
+        .expect("list dispatchable initial");
+    assert!(initial.iter().any(|r| r.attempt_id == attempt_id));
+
+    let lease = repo
+        .upsert_lease(&attempt_id, "worker-a", now + Duration::seconds(1))
+        .await
+        .expect("upsert lease");
+    let duplicate = repo
+        .upsert_lease(&attempt_id, "worker-b", now + Duration::seconds(2))
+        .await;
+    assert!(duplicate.is_err());
+
+    repo.heartbeat_lease(
+        &lease.lease_id,
+        now + Duration::milliseconds(500),
+        now + Duration::seconds(2),
+    )
+    .await
+    .expect("heartbeat lease");
+    let expired = repo
+        .expire_leases_and_requeue(now + Duration::seconds(10))
+        .await
+        .expect("expire and requeue");
+    assert_eq!(expired, 1);
+
+    let claimed = repo
+        .claim_dispatchable_attempts(
+            now + Duration::seconds(10),
+            "worker-b",
+            10,
+            Duration::seconds(30),
+        )
+        .await
+        .expect("claim requeued attempt");
+    assert!(claimed.iter().any(|c| c.attempt.attempt_id == attempt_id));
+    let finished = repo
+        .finish_attempt(&attempt_id, AttemptExecutionStatus::Completed, now)
+        .await
+        .expect("finish attempt");
+    assert_eq!(finished, AttemptExecutionStatus::Completed);
+    let after_finish = repo
+        .list_dispatchable_attempts(now + Duration::seconds(10), 10)
+        .await
+        .expect("list dispatchable after finish");
+    assert!(!after_finish.iter().any(|r| r.attempt_id == attempt_id));
+}
+
+/// Higher priority dispatches first; equal priorities dispatch in enqueue order.
+pub fn assert_priority_dispatch_order_contract<R: ContractHarness + Clone + 'static>(
+    repo: &R,
+    name: &str,
+) {
+    let run_id = format!("run-{}-priority", name);
+    // Attempt ids sort against enqueue order, so only enqueue time can put `old` first
+    let old = format!("z-{}-old", name);
+    let young = format!("y-{}-young", name);
+    let urgent = format!("x-{}-urgent", name);
+    repo.seed_attempt(&old, &run_id);
+    thread::sleep(std::time::Duration::from_millis(5));
+    repo.seed_attempt(&young, &run_id);
+    thread::sleep(std::time::Duration::from_millis(5));
+    repo.seed_attempt_with_priority(&urgent, &run_id, 10);
+
+    let rows = repo
+        .list_dispatchable_attempts(Utc::now(), 10)
+        .expect("list dispatchable by priority");
+    let order: Vec<(&str, i32)> = rows
+        .iter()
+        .map(|r| (r.attempt_id.as_str(), r.priority))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (urgent.as_str(), 10),
+            (old.as_str(), 0),
+            (young.as_str(), 0)
+        ]
+    );
+
+    let scheduler = SkeletonScheduler::new(repo.clone());
+    for expected in [&urgent, &old, &young] {
+        match scheduler
+            .dispatch_one("worker-priority")
+            .expect("dispatch by priority")
+        {
+            SchedulerDecision::Dispatched { attempt_id, .. } => {
+                assert_eq!(&attempt_id, expected)
+            }
+            other => panic!("expected Dispatched, got {:?}", other),
+        }
+    }
+}
+
+/// Attempts enqueued with a future `run_at` stay hidden until then, and
+/// `next_dispatch_at` reports when the next one becomes ready.
+pub fn assert_run_at_contract<R: ContractHarness + Clone + 'static>(repo: &R, name: &str) {
+    let run_id = format!("run-{}-run-at", name);
+    let delayed = format!("attempt-{}-delayed", name);
+    let overdue = format!("attempt-{}-overdue", name);
+    let now = Utc::now();
+    let run_at = now + Duration::seconds(60);
+    repo.seed_attempt_at(&delayed, &run_id, run_at);
+    // A run_at already behind the clock (e.g. set by a node whose clock lags) is not
+    // held back
+    repo.seed_attempt_at(&overdue, &run_id, now - Duration::hours(1));
+
+    let ids = |at: DateTime<Utc>| -> Vec<String> {
+        repo.list_dispatchable_attempts(at, 10)
+            .expect("list dispatchable with run_at")
+            .into_iter()
+            .map(|r| r.attempt_id)
+            .collect()
+    };
+    assert_eq!(ids(now), vec![overdue.clone()]);
+    assert_eq!(
+        repo.next_dispatch_at(now)
+            .expect("next dispatch before run_at")
+            .map(|at| at.timestamp_millis()),
+        Some(run_at.timestamp_millis())
+    );
+    assert_eq!(
+        ids(run_at - Duration::milliseconds(1)),
+        vec![overdue.clone()]
+    );
+    assert!(ids(run_at).contains(&delayed));
+    assert_eq!(
+        repo.next_dispatch_at(run_at)
+            .expect("next dispatch at run_at"),
+        None
+    );
+
+    let scheduler = SkeletonScheduler::new(repo.clone());
+    match scheduler
+        .dispatch_one("worker-run-at")
+        .expect("dispatch overdue")
+    {
+        SchedulerDecision::Dispatched { attempt_id, .. } => assert_eq!(attempt_id, overdue),
+        other => panic!("expected Dispatched, got {:?}", other),
+    }
+    match scheduler
+        .dispatch_one("worker-run-at")
+        .expect("dispatch delayed")
+    {
+        SchedulerDecision::Idle { next_wakeup } => {
+            assert_eq!(next_wakeup.timestamp_millis(), run_at.timestamp_millis())
+        }
+        other => panic!("expected Idle, got {:?}", other),
+    }
+}
+
+/// `repos` are independent handles on one database, as concurrent workers have.
+pub fn assert_concurrent_claim_contract<R: ContractHarness + Send + 'static>(
+    repos: Vec<R>,
+    prefix: &str,
+) {
+    let run_id = format!("{prefix}-claim-run");
+    for i in 0..20 {
+        repos[0].seed_attempt(&format!("{prefix}-claim-{i:02}"), &run_id);
+    }
+
+    let handles: Vec<_> = repos
+        .into_iter()
+        .enumerate()
+        .map(|(idx, repo)| {
+            let worker_id = format!("{prefix}-worker-{idx}");
+            let prefix = prefix.to_string();
+            thread::spawn(move || {
+                let mut claimed = Vec::new();
+                let mut errors = Vec::new();
+                loop {
+                    match repo.claim_dispatchable_attempts(
+                        Utc::now(),
+                        &worker_id,
+                        2,
+                        Duration::seconds(30),
+                    ) {
+                        Ok(batch) if batch.is_empty() => break,
+                        Ok(batch) => {
+                            for c in batch {
+                                assert_eq!(c.lease.worker_id, worker_id);
+                                assert_eq!(c.lease.attempt_id, c.attempt.attempt_id);
+                                assert!(repo.has_lease(&c.attempt.attempt_id));
+                                if c.attempt.attempt_id.starts_with(&prefix) {
+                                    claimed.push(c.attempt.attempt_id);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            errors.push(e.to_string());
+                            break;
+                        }
+                    }
+                }
+                (claimed, errors)
+            })
+        })
+        .collect();
+
+    let mut claimed = Vec::new();
+    for handle in handles {
+        let (ids, errors) = handle.join().expect("join claiming thread");
+        assert!(errors.is_empty(), "claim errors: {:?}", errors);
+        claimed.extend(ids);
+    }
+    assert_eq!(claimed.len(), 20, "every attempt is claimed exactly once");
+    claimed.sort();
+    claimed.dedup();
+    assert_eq!(claimed.len(), 20, "no attempt is claimed twice");
+}
+
+/// `max_concurrent_per_run` holds back a run's attempts once it holds that many leases.
+pub fn assert_max_concurrent_per_run_contract<R: ContractHarness + Clone + 'static>(
+    repo: &R,
+    name: &str,
+) {
+    let run_id = format!("run-{}-fan-out", name);
+    let attempts: Vec<String> = (0..5)
+        .map(|i| format!("attempt-{}-fan-out-{}", name, i))
+        .collect();
+    for attempt_id in &attempts {
+        repo.seed_attempt(attempt_id, &run_id);
+    }
+    let other = format!("attempt-{}-other-run", name);
+    repo.seed_attempt(&other, &format!("run-{}-other", name));
+
+    let options = DispatchOptions {
+        max_concurrent_per_run: Some(2),
+    };
+    let context = DispatchContext::new().with_max_concurrent_per_run(2);
+    let scheduler = SkeletonScheduler::new(repo.clone());
+    let active = |repo: &R| attempts.iter().filter(|id| repo.has_lease(id)).count();
+
+    for expected in &attempts[..2] {
+        match scheduler
+            .dispatch_one_with_context("worker-fan-out", Some(&context))
+            .expect("dispatch within run limit")
+        {
+            SchedulerDecision::Dispatched {
+                attempt_id,
+                skipped_for_fairness,
+                ..
+            } => {
+                assert_eq!(&attempt_id, expected);
+                assert_eq!(skipped_for_fairness, 0);
+            }
+            other => panic!("expected Dispatched, got {:?}", other),
+        }
+    }
+    let listed = repo
+        .list_dispatchable_attempts_with_options(Utc::now(), 10, &options)
+        .expect("list with per-run limit");
+    let ids: Vec<&str> = listed
+        .attempts
+        .iter()
+        .map(|r| r.attempt_id.as_str())
+        .collect();
+    assert_eq!(ids, vec![other.as_str()]);
+    assert_eq!(listed.skipped_for_fairness, 3);
+    match scheduler
+        .dispatch_one_with_context("worker-other", Some(&context))
+        .expect("dispatch other run")
+    {
+        SchedulerDecision::Dispatched {
+            attempt_id,
+            skipped_for_fairness,
+            ..
+        } => {
+            assert_eq!(attempt_id, other);
+            assert_eq!(skipped_for_fairness, 3);
+        }
+        other => panic!("expected Dispatched, got {:?}", other),
+    }
+
+    // The rest of the run dispatches one by one as its leases complete
+    for next in 2..5 {
+        match scheduler
+            .dispatch_one_with_context("worker-fan-out", Some(&context))
+            .expect("dispatch at run limit")
+        {
+            SchedulerDecision::Backpressure { queue_depth, .. } => {
+                assert_eq!(queue_depth, 5 - next)
+            }
+            other => panic!("expected Backpressure, got {:?}", other),
+        }
+        assert_eq!(active(repo), 2);
+        let done = attempts
+            .iter()
+            .find(|id| repo.has_lease(id))
+            .expect("leased attempt");
+        repo.finish_attempt(done, AttemptExecutionStatus::Completed, Utc::now())
+            .expect("complete leased attempt");
+        match scheduler
+            .dispatch_one_with_context("worker-fan-out", Some(&context))
+            .expect("dispatch after completion")
+        {
+            SchedulerDecision::Dispatched { attempt_id, .. } => {
+                assert_eq!(attempt_id, attempts[next])
+            }
+            other => panic!("expected Dispatched, got {:?}", other),
+        }
+        assert_eq!(active(repo), 2);
+    }
+}
+
+/// Completing or failing under a stale or foreign lease is a `LeaseConflict`.
+pub fn assert_lease_fencing_contract<R: ContractHarness>(repo: &R, name: &str) {
+    let run_id = format!("run-{}-fencing", name);
+    let attempt_id = format!("attempt-{}-fencing", name);
+    let policy = RetryPolicyConfig {
+        strategy: RetryStrategy::Fixed,
+        backoff_ms: 1_000,
+        max_backoff_ms: None,
+        multiplier: None,
+        max_retries: 1,
+    };
+    repo.seed_attempt(&attempt_id, &run_id);
+    let now = Utc::now();
+    let first = repo
+        .upsert_lease(&attempt_id, "worker-first", now + Duration::seconds(30))
+        .expect("first worker leases attempt");
+    repo.heartbeat_lease_with_version(
+        &first.lease_id,
+        "worker-first",
+        first.version,
+        now,
+        now + Duration::seconds(30),
+    )
+    .expect("heartbeat at current version");
+    assert!(matches!(
+        repo.heartbeat_lease_with_version(
+            &first.lease_id,
+            "worker-first",
+            first.version,
+            now,
+            now + Duration::seconds(30),
+        ),
+        Err(oris_kernel::KernelError::LeaseConflict(_))
+    ));
+    let first_fence = LeaseFence {
+        version: first.version + 1,
+        ..LeaseFence::from(&first)
+    };
+
+    // The first worker stalls past its lease and a second worker takes over
+    repo.expire_leases_and_requeue(now + Duration::seconds(60))
+        .expect("expire first lease");
+    let second = repo
+        .upsert_lease(&attempt_id, "worker-second", now + Duration::seconds(30))
+        .expect("second worker leases attempt");
+
+    assert!(matches!(
+        repo.complete_attempt(
+            &attempt_id,
+            &first_fence,
+            AttemptExecutionStatus::Completed,
+            now
+        ),
+        Err(oris_kernel::KernelError::LeaseConflict(_))
+    ));
+    assert!(matches!(
+        repo.fail_attempt(&attempt_id, &first_fence, "stale failure", &policy, now),
+        Err(oris_kernel::KernelError::LeaseConflict(_))
+    ));
+    assert!(matches!(
+        repo.heartbeat_lease_with_version(
+            &first.lease_id,
+            "worker-first",
+            first_fence.version,
+            now,
+            now + Duration::seconds(30),
+        ),
+        Err(oris_kernel::KernelError::LeaseConflict(_))
+    ));
+    assert!(repo.has_lease(&attempt_id));
+
+    let second_fence = LeaseFence::from(&second);
+    assert!(matches!(
+        repo.fail_attempt(
+            &attempt_id,
+            &second_fence,
+            "after expiry",
+            &policy,
+            now + Duration::seconds(60)
+        ),
+        Err(oris_kernel::KernelError::LeaseConflict(_))
+    ));
+    assert_eq!(
+        repo.complete_attempt(
+            &attempt_id,
+            &second_fence,
+            AttemptExecutionStatus::Completed,
+            now
+        )
+        .expect("second worker completes attempt"),
+        AttemptExecutionStatus::Completed
+    );
+    assert!(!repo.has_lease(&attempt_id));
+}
+
+/// Cancelling waiting, leased and finished attempts.
+pub fn assert_cancel_attempt_contract<R: ContractHarness>(repo: &R, name: &str) {
+    let policy = RetryPolicyConfig {
+        strategy: RetryStrategy::Fixed,
+        backoff_ms: 1_000,
+        max_backoff_ms: None,
+        multiplier: None,
+        max_retries: 3,
+    };
+    let now = Utc::now();
+    let lease_attempt = |attempt_id: &str| {
+        repo.seed_attempt(attempt_id, &format!("run-{}", attempt_id));
+        repo.upsert_lease(attempt_id, "worker-cancel", now + Duration::seconds(30))
+            .expect("lease attempt")
+    };
+
+    // Waiting for dispatch: cancelled at once and never dispatched
+    let queued = format!("attempt-{}-cancel-queued", name);
+    repo.seed_attempt(&queued, &format!("run-{}-cancel-queued", name));
+    assert_eq!(
+        repo.cancel_attempt(&queued).expect("cancel queued attempt"),
+        AttemptCancellation::Cancelled
+    );
+    assert!(!repo
+        .list_dispatchable_attempts(now + Duration::hours(1), 100)
+        .expect("list dispatchable")
+        .iter()
+        .any(|attempt| attempt.attempt_id == queued));
+
+    // Leased: the next heartbeat tells the worker to stop, which finishes it cancelled
+    let running = format!("attempt-{}-cancel-running", name);
+    let lease = lease_attempt(&running);
+    let mut fence = LeaseFence::from(&lease);
+    let heartbeat = |fence: &LeaseFence| {
+        repo.heartbeat_lease_with_version(
+            &fence.lease_id,
+            &fence.worker_id,
+            fence.version,
+            now,
+            now + Duration::seconds(30),
+        )
+        .expect("heartbeat lease")
+    };
+    assert_eq!(heartbeat(&fence), LeaseDirective::Continue);
+    fence.version += 1;
+    assert_eq!(
+        repo.cancel_attempt(&running).expect("request cancel"),
+        AttemptCancellation::Requested
+    );
+    assert_eq!(heartbeat(&fence), LeaseDirective::Cancel);
+    fence.version += 1;
+    assert_eq!(
+        repo.complete_attempt(&running, &fence, AttemptExecutionStatus::Cancelled, now)
+            .expect("finish cancelled attempt"),
+        AttemptExecutionStatus::Cancelled
+    );
+
+    // A failure reported after the request ends the attempt cancelled, not retried
+    let failing = format!("attempt-{}-cancel-failing", name);
+    let lease = lease_attempt(&failing);
+    repo.cancel_attempt(&failing).expect("request cancel");
+    let outcome = repo
+        .fail_attempt(&failing, &LeaseFence::from(&lease), "stopped", &policy, now)
+        .expect("record failure");
+    assert_eq!(outcome.status, AttemptExecutionStatus::Cancelled);
+    assert!(outcome.next_retry_at.is_none());
+
+    // Cancel races completion: a worker that completes first keeps its result
+    let racing = format!("attempt-{}-cancel-racing", name);
+    let lease = lease_attempt(&racing);
+    repo.cancel_attempt(&racing).expect("request cancel");
+    assert_eq!(
+        repo.complete_attempt(
+            &racing,
+            &LeaseFence::from(&lease),
+            AttemptExecutionStatus::Completed,
+            now
+        )
+        .expect("complete attempt"),
+        AttemptExecutionStatus::Completed
+    );
+    assert_eq!(
+        repo.cancel_attempt(&racing)
+            .expect("cancel completed attempt"),
+        AttemptCancellation::AlreadyFinished(AttemptExecutionStatus::Completed)
+    );
+
+    // A worker that disappears leaves the attempt cancelled, not requeued
+    let abandoned = format!("attempt-{}-cancel-abandoned", name);
+    lease_attempt(&abandoned);
+    repo.cancel_attempt(&abandoned).expect("request cancel");
+    repo.expire_leases_and_requeue(now + Duration::seconds(60))
+        .expect("expire lease");
+    assert_eq!(
+        repo.cancel_attempt(&abandoned)
+            .expect("cancel expired attempt"),
+        AttemptCancellation::AlreadyFinished(AttemptExecutionStatus::Cancelled)
+    );
+
+    assert!(matches!(
+        repo.cancel_attempt(&format!("attempt-{}-cancel-missing", name)),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+}
+
+/// Failures back off and retry until the retry policy runs out, then dead-letter.
+pub fn assert_attempt_failure_contract<R: ContractHarness>(repo: &R, name: &str) {
+    let run_id = format!("run-{}-failure", name);
+    let attempt_id = format!("attempt-{}-failure", name);
+    let policy = RetryPolicyConfig {
+        strategy: RetryStrategy::Fixed,
+        backoff_ms: 1_000,
+        max_backoff_ms: None,
+        multiplier: None,
+        max_retries: 1,
+    };
+    let now = Utc::now();
+    repo.seed_attempt(&attempt_id, &run_id);
+    repo.upsert_lease(&attempt_id, "worker-failure", now + Duration::seconds(30))
+        .expect("lease attempt");
+
+    let retry = repo
+        .record_attempt_failure(&attempt_id, "first failure", &policy, now)
+        .expect("record first failure");
+    assert_eq!(retry.status, AttemptExecutionStatus::RetryBackoff);
+    assert_eq!(retry.next_attempt_no, 2);
+    assert_eq!(
+        retry.next_retry_at.map(|at| at.timestamp_millis()),
+        Some((now + Duration::milliseconds(1_000)).timestamp_millis())
+    );
+    assert!(!repo.has_lease(&attempt_id));
+    let not_yet = repo
+        .list_dispatchable_attempts(now, 10)
+        .expect("list before retry_at");
+    assert!(!not_yet.iter().any(|r| r.attempt_id == attempt_id));
+    let due = repo
+        .list_dispatchable_attempts(now + Duration::seconds(2), 10)
+        .expect("list after retry_at");
+    let record = due
+        .iter()
+        .find(|r| r.attempt_id == attempt_id)
+        .expect("retry is dispatchable once due");
+    assert_eq!(record.attempt_no, 2);
+
+    let dead = repo
+        .record_attempt_failure(
+            &attempt_id,
+            "second failure",
+            &policy,
+            now + Duration::seconds(2),
+        )
+        .expect("record second failure");
+    assert_eq!(dead.status, AttemptExecutionStatus::DeadLetter);
+    assert_eq!(dead.next_retry_at, None);
+    let dead_letters = repo
+        .list_dead_letter_attempts(10)
+        .expect("list dead letters");
+    let entry = dead_letters
+        .iter()
+        .find(|r| r.attempt_id == attempt_id)
+        .expect("attempt is dead-lettered");
+    assert_eq!(entry.run_id, run_id);
+    assert_eq!(entry.attempt_no, 2);
+    assert_eq!(entry.error.as_deref(), Some("second failure"));
+    let later = repo
+        .list_dispatchable_attempts(now + Duration::days(1), 10)
+        .expect("list after dead letter");
+    assert!(!later.iter().any(|r| r.attempt_id == attempt_id));
+
+    repo.requeue_dead_letter(&attempt_id)
+        .expect("requeue dead letter");
+    assert!(repo
+        .list_dispatchable_attempts(now + Duration::seconds(2), 10)
+        .expect("list after requeue")
+        .iter()
+        .any(|r| r.attempt_id == attempt_id));
+    assert!(!repo
+        .list_dead_letter_attempts(10)
+        .expect("list dead letters after requeue")
+        .iter()
+        .any(|r| r.attempt_id == attempt_id));
+    assert!(matches!(
+        repo.requeue_dead_letter(&attempt_id),
+        Err(oris_kernel::KernelError::Conflict(_))
+    ));
+    assert!(matches!(
+        repo.requeue_dead_letter(&format!("{}-missing", attempt_id)),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+    assert!(matches!(
+        repo.record_attempt_failure(&format!("{}-missing", attempt_id), "boom", &policy, now),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+}
+
+/// Counts attempts by status, active leases, queue age and due retries. Expects a
+/// repository with no attempts yet.
+pub fn assert_runtime_stats_contract<R: ContractHarness>(repo: &R, name: &str) {
+    let run_id = format!("run-{}-stats", name);
+    let now = Utc::now();
+    assert_eq!(
+        repo.runtime_stats(now).expect("stats of empty repository"),
+        RuntimeStats::default()
+    );
+
+    let queued = format!("attempt-{}-stats-queued", name);
+    let queued_later = format!("attempt-{}-stats-queued-later", name);
+    let leased = format!("attempt-{}-stats-leased", name);
+    let retrying = format!("attempt-{}-stats-retrying", name);
+    for attempt_id in [&queued, &queued_later, &leased, &retrying] {
+        repo.seed_attempt(attempt_id, &run_id);
+    }
+    repo.upsert_lease(&leased, "worker-stats", now + Duration::seconds(30))
+        .expect("lease attempt");
+    repo.upsert_lease(&retrying, "worker-stats", now + Duration::seconds(30))
+        .expect("lease retrying attempt");
+    let policy = RetryPolicyConfig {
+        strategy: RetryStrategy::Fixed,
+        backoff_ms: 1_000,
+        max_backoff_ms: None,
+        multiplier: None,
+        max_retries: 3,
+    };
+    repo.record_attempt_failure(&retrying, "boom", &policy, now)
+        .expect("record failure");
+
+    let stats = repo
+        .runtime_stats(now + Duration::seconds(5))
+        .expect("stats with attempts");
+    assert_eq!(
+        stats.attempts,
+        AttemptStatusCounts {
+            queued: 2,
+            leased: 1,
+            retry_backoff: 1,
+            ..AttemptStatusCounts::default()
+        }
+    );
+    assert_eq!(stats.active_leases, 1);
+    assert_eq!(stats.retry_backlog, 1);
+    let age = stats
+        .oldest_queued_age_ms
+        .expect("queued attempts have an age");
+    assert!(
+        (1..=5_000).contains(&age),
+        "oldest queued age {} outside (0, 5s]",
+        age
+    );
+    let later = repo
+        .runtime_stats(now + Duration::seconds(15))
+        .expect("stats ten seconds later");
+    assert_eq!(later.oldest_queued_age_ms, Some(age + 10_000));
+
+    let before_retry = repo.runtime_stats(now).expect("stats before retry_at");
+    assert_eq!(before_retry.retry_backlog, 0);
+
+    repo.finish_attempt(&leased, AttemptExecutionStatus::Completed, now)
+        .expect("finish attempt");
+    let finished = repo
+        .runtime_stats(now + Duration::seconds(5))
+        .expect("stats after finish");
+    assert_eq!(finished.attempts.leased, 0);
+    assert_eq!(finished.attempts.completed, 1);
+    assert_eq!(finished.active_leases, 0);
+    let expired = repo
+        .runtime_stats(now + Duration::minutes(5))
+        .expect("stats once leases would have expired");
+    assert_eq!(expired.active_leases, 0);
+}
+
+/// Round-trips bounties, workers and swarm decompositions.
+pub fn assert_bounty_worker_swarm_contract<R: RuntimeRepository>(repo: &R, prefix: &str) {
+    let now_ms = Utc::now().timestamp_millis();
+    let bounty_id = format!("{prefix}-bounty");
+    let worker_id = format!("{prefix}-worker");
+    let parent_task_id = format!("{prefix}-swarm-task");
+
+    let bounty = BountyRecord {
+        bounty_id: bounty_id.clone(),
+        title: format!("{} title", prefix),
+        description: Some("semantic contract bounty".to_string()),
+        reward: 123,
+        status: BountyStatus::Open,
+        created_by: "alice".to_string(),
+        created_at_ms: now_ms,
+        closed_at_ms: None,
+        accepted_by: None,
+        accepted_at_ms: None,
+    };
+    repo.upsert_bounty(&bounty).expect("upsert bounty");
+
+    let fetched = repo
+        .get_bounty(&bounty_id)
+        .expect("get bounty")
+        .expect("bounty exists");
+    assert_eq!(fetched.status, BountyStatus::Open);
+
+    repo.accept_bounty(&bounty_id, &worker_id)
+        .expect("accept bounty");
+    let accepted = repo
+        .get_bounty(&bounty_id)
+        .expect("get accepted bounty")
+        .expect("accepted bounty exists");
+    assert_eq!(accepted.status, BountyStatus::Accepted);
+    assert_eq!(accepted.accepted_by.as_deref(), Some(worker_id.as_str()));
+
+    repo.close_bounty(&bounty_id).expect("close bounty");
+    let closed = repo
+        .get_bounty(&bounty_id)
+        .expect("get closed bounty")
+        .expect("closed bounty exists");
+    assert_eq!(closed.status, BountyStatus::Closed);
+    assert!(closed.closed_at_ms.is_some());
+
+    let listed = repo
+        .list_bounties(Some("closed"), 8)
+        .expect("list closed bounties");
+    assert!(listed.iter().any(|b| b.bounty_id == bounty_id));
+
+    let worker = WorkerRecord {
+        worker_id: worker_id.clone(),
+        domains: "evomap,semantic".to_string(),
+        max_load: 2,
+        metadata_json: Some(r#"{"role":"solver"}"#.to_string()),
+        registered_at_ms: now_ms,
+        last_heartbeat_ms: Some(now_ms),
+        status: "active".to_string(),
+    };
+    repo.register_worker(&worker).expect("register worker");
+    let worker_fetched = repo
+        .get_worker(&worker_id)
+        .expect("get worker")
+        .expect("worker exists");
+    assert_eq!(worker_fetched.domains, "evomap,semantic");
+    assert_eq!(worker_fetched.status, "active");
+
+    let by_domain = repo
+        .list_workers(Some("evomap"), Some("active"), 10)
+        .expect("list workers by domain+status");
+    assert!(by_domain.iter().any(|w| w.worker_id == worker_id));
+
+    let heartbeat_ms = now_ms + 777;
+    repo.heartbeat_worker(&worker_id, heartbeat_ms)
+        .expect("heartbeat worker");
+    let heartbeat_worker = repo
+        .get_worker(&worker_id)
+        .expect("get worker after heartbeat")
+        .expect("worker exists after heartbeat");
+    assert_eq!(heartbeat_worker.last_heartbeat_ms, Some(heartbeat_ms));
+
+    let swarm = SwarmTaskRecord {
+        parent_task_id: parent_task_id.clone(),
+        decomposition_json: r#"{"children":[{"id":"c1"}]}"#.to_string(),
+        proposer_id: "alice".to_string(),
+        proposer_reward_pct: 5,
+        solver_reward_pct: 85,
+        aggregator_reward_pct: 10,
+        status: "pending".to_string(),
+        created_at_ms: now_ms,
+        completed_at_ms: None,
+    };
+    repo.upsert_swarm_decomposition(&swarm)
+        .expect("upsert swarm task");
+    let swarm_fetched = repo
+        .get_swarm_decomposition(&parent_task_id)
+        .expect("get swarm decomposition")
+        .expect("swarm decomposition exists");
+    assert_eq!(swarm_fetched.proposer_id, "alice");
+    assert_eq!(swarm_fetched.status, "pending");
+}
+
+/// Round-trips recipes, organisms, sessions and disputes.
+pub fn assert_recipe_organism_session_dispute_contract<R: RuntimeRepository>(
+    repo: &R,
+    prefix: &str,
+) {
+    let now_ms = Utc::now().timestamp_millis();
+    let recipe_id = format!("{prefix}-recipe");
+    let fork_recipe_id = format!("{prefix}-recipe-fork");
+    let organism_id = format!("{prefix}-organism");
+    let session_id = format!("{prefix}-session");
+    let dispute_id = format!("{prefix}-dispute");
+    let bounty_id = format!("{prefix}-bounty-for-dispute");
+
+    repo.upsert_bounty(&BountyRecord {
+        bounty_id: bounty_id.clone(),
+        title: format!("{prefix} bounty"),
+        description: None,
+        reward: 42,
+        status: BountyStatus::Open,
+        created_by: "arbiter".to_string(),
+        created_at_ms: now_ms,
+        closed_at_ms: None,
+        accepted_by: None,
+        accepted_at_ms: None,
+    })
+    .expect("seed bounty for dispute");
+
+    let recipe = RecipeRecord {
+        recipe_id: recipe_id.clone(),
+        name: format!("{prefix} base recipe"),
+        description: Some("base".to_string()),
+        gene_sequence_json: r#"{"steps":["a","b"]}"#.to_string(),
+        author_id: "alice".to_string(),
+        forked_from: None,
+        created_at_ms: now_ms,
+        updated_at_ms: now_ms,
+        is_public: true,
+    };
+    repo.create_recipe(&recipe).expect("create recipe");
+
+    let fetched_recipe = repo
+        .get_recipe(&recipe_id)
+        .expect("get recipe")
+        .expect("recipe exists");
+    assert_eq!(fetched_recipe.author_id, "alice");
+
+    let forked = repo
+        .fork_recipe(&recipe_id, &fork_recipe_id, "bob")
+        .expect("fork recipe call")
+        .expect("forked recipe exists");
+    assert_eq!(forked.forked_from.as_deref(), Some(recipe_id.as_str()));
+    assert_eq!(forked.author_id, "bob");
+
+    let recipes = repo
+        .list_recipes(Some("alice"), 10)
+        .expect("list recipes by author");
+    assert!(recipes.iter().any(|r| r.recipe_id == recipe_id));
+
+    let organism = OrganismRecord {
+        organism_id: organism_id.clone(),
+        recipe_id: recipe_id.clone(),
+        status: "pending".to_string(),
+        current_step: 0,
+        total_steps: 2,
+        created_at_ms: now_ms,
+        completed_at_ms: None,
+    };
+    repo.express_organism(&organism).expect("express organism");
+    repo.update_organism(&organism_id, 1, "running")
+        .expect("update organism running");
+    repo.update_organism(&organism_id, 2, "completed")
+        .expect("update organism completed");
+    let updated_organism = repo
+        .get_organism(&organism_id)
+        .expect("get organism")
+        .expect("organism exists");
+    assert_eq!(updated_organism.status, "completed");
+    assert_eq!(updated_organism.current_step, 2);
+    assert!(updated_organism.completed_at_ms.is_some());
+
+    let session = SessionRecord {
+        session_id: session_id.clone(),
+        session_type: "pair".to_string(),
+        creator_id: "alice".to_string(),
+        status: "active".to_string(),
+        created_at_ms: now_ms,
+        ended_at_ms: None,
+    };
+    repo.create_session(&session).expect("create session");
+
+    repo.add_session_message(&SessionMessageRecord {
+        message_id: format!("{prefix}-msg-1"),
+        session_id: session_id.clone(),
+        sender_id: "alice".to_string(),
+        content: "first".to_string(),
+        message_type: "message".to_string(),
+        sent_at_ms: now_ms + 1,
+    })
+    .expect("add session message 1");
+    repo.add_session_message(&SessionMessageRecord {
+        message_id: format!("{prefix}-msg-2"),
+        session_id: session_id.clone(),
+        sender_id: "bob".to_string(),
+        content: "second".to_string(),
+        message_type: "message".to_string(),
+        sent_at_ms: now_ms + 2,
+    })
+    .expect("add session message 2");
+
+    let session_history = repo
+        .get_session_history(&session_id, 10)
+        .expect("get session history");
+    assert_eq!(session_history.len(), 2);
+    assert_eq!(session_history[0].content, "second");
+
+    let dispute = DisputeRecord {
+        dispute_id: dispute_id.clone(),
+        bounty_id: bounty_id.clone(),
+        opened_by: "alice".to_string(),
+        status: DisputeStatus::Open,
+        evidence_json: Some(r#"{"proof":"hash"}"#.to_string()),
+        resolution: None,
+        resolved_by: None,
+        resolved_at_ms: None,
+        created_at_ms: now_ms,
+    };
+    repo.open_dispute(&dispute).expect("open dispute");
+    let opened = repo
+        .get_dispute(&dispute_id)
+        .expect("get dispute")
+        .expect("dispute exists");
+    assert_eq!(opened.status, DisputeStatus::Open);
+
+    let disputes = repo
+        .get_disputes_for_bounty(&bounty_id)
+        .expect("get disputes for bounty");
+    assert!(disputes.iter().any(|d| d.dispute_id == dispute_id));
+
+    repo.resolve_dispute(&dispute_id, "approved", "arbiter")
+        .expect("resolve dispute");
+    let resolved = repo
+        .get_dispute(&dispute_id)
+        .expect("get resolved dispute")
+        .expect("resolved dispute exists");
+    assert_eq!(resolved.status, DisputeStatus::Resolved);
+    assert_eq!(resolved.resolved_by.as_deref(), Some("arbiter"));
+    assert!(resolved.resolved_at_ms.is_some());
+}
+
+/// Records, lists and resolves interrupts.
+pub fn assert_interrupt_inbox_contract<R: RuntimeRepository>(repo: &R, prefix: &str) {
+    let run_id = format!("{prefix}-run");
+    // Stores keep millisecond precision
+    let created_at = chrono::DateTime::from_timestamp_millis(Utc::now().timestamp_millis())
+        .expect("now in range");
+    let pending = |n: i64| InterruptRecord {
+        interrupt_id: format!("{prefix}-int-{n}"),
+        run_id: run_id.clone(),
+        attempt_id: format!("{prefix}-attempt"),
+        step_id: Some("approve".to_string()),
+        payload: serde_json::json!({"question": "ship it?", "n": n}),
+        status: InterruptStatus::Pending,
+        created_at: created_at + Duration::milliseconds(n),
+        decision: None,
+        resolution: None,
+        resolved_at: None,
+    };
+    repo.create_interrupt(&pending(1))
+        .expect("create first interrupt");
+    repo.create_interrupt(&pending(2))
+        .expect("create second interrupt");
+
+    let listed = repo
+        .list_pending_interrupts(&InterruptFilter::for_run(run_id.clone()))
+        .expect("list pending interrupts");
+    let ids: Vec<_> = listed.iter().map(|i| i.interrupt_id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            format!("{prefix}-int-1").as_str(),
+            format!("{prefix}-int-2").as_str()
+        ]
+    );
+    assert_eq!(listed[0], pending(1));
+
+    let approved = repo
+        .resolve_interrupt(
+            &format!("{prefix}-int-1"),
+            InterruptDecision::Approve,
+            &serde_json::json!({"approved": true}),
+        )
+        .expect("approve interrupt");
+    assert_eq!(approved.status, InterruptStatus::Resumed);
+    assert_eq!(approved.decision, Some(InterruptDecision::Approve));
+    assert_eq!(
+        approved.resolution,
+        Some(serde_json::json!({"approved": true}))
+    );
+    assert!(approved.resolved_at.is_some());
+
+    let again = repo.resolve_interrupt(
+        &format!("{prefix}-int-1"),
+        InterruptDecision::Reject,
+        &serde_json::Value::Null,
+    );
+    assert!(matches!(again, Err(oris_kernel::KernelError::Conflict(_))));
+    let missing = repo.resolve_interrupt(
+        &format!("{prefix}-int-missing"),
+        InterruptDecision::Approve,
+        &serde_json::Value::Null,
+    );
+    assert!(matches!(
+        missing,
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+
+    let rejected = repo
+        .resolve_interrupt(
+            &format!("{prefix}-int-2"),
+            InterruptDecision::Reject,
+            &serde_json::Value::Null,
+        )
+        .expect("reject interrupt");
+    assert_eq!(rejected.status, InterruptStatus::Rejected);
+    assert!(repo
+        .list_pending_interrupts(&InterruptFilter::for_run(run_id))
+        .expect("list after resolve")
+        .is_empty());
+}
+
+/// Creates and reads back a run record.
+pub fn assert_run_record_contract<R: RuntimeRepository>(repo: &R, prefix: &str) {
+    let now = chrono::DateTime::from_timestamp_millis(Utc::now().timestamp_millis())
+        .expect("now in range");
+    let run = RunRecord {
+        run_id: format!("{prefix}-run"),
+        workflow_name: "hello".to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    repo.create_run(&run).expect("create run");
+    assert_eq!(
+        repo.get_run(&run.run_id).expect("get run"),
+        Some(run.clone())
+    );
+    assert!(matches!(
+        repo.create_run(&run),
+        Err(oris_kernel::KernelError::Conflict(_))
+    ));
+    assert_eq!(
+        repo.get_run(&format!("{prefix}-missing"))
+            .expect("get missing run"),
+        None
+    );
+}
+
+/// Batch enqueues skip taken ids and enqueue nothing when one run is unknown.
+pub fn assert_enqueue_batch_contract<R: ContractHarness>(repo: &R, prefix: &str) {
+    let run_a = format!("{prefix}-run-a");
+    let run_b = format!("{prefix}-run-b");
+    seed_run(repo, &run_a);
+    seed_run(repo, &run_b);
+    let batch: Vec<(String, RunId, EnqueueOptions)> = (0..6)
+        .map(|i| {
+            let run_id = if i % 2 == 0 { &run_a } else { &run_b };
+            let options = EnqueueOptions {
+                priority: i,
+                run_at: None,
+            };
+            (format!("{prefix}-attempt-{i}"), run_id.clone(), options)
+        })
+        .collect();
+
+    let outcome = repo.enqueue_attempts(&batch).expect("enqueue batch");
+    assert_eq!(
+        outcome,
+        EnqueueBatchOutcome {
+            inserted: 6,
+            skipped: 0
+        }
+    );
+    let dispatchable = repo
+        .list_dispatchable_attempts(Utc::now(), 100)
+        .expect("list dispatchable");
+    let ids: Vec<&str> = dispatchable
+        .iter()
+        .filter(|a| a.attempt_id.starts_with(prefix))
+        .map(|a| a.attempt_id.as_str())
+        .collect();
+    let expected: Vec<String> = (0..6)
+        .rev()
+        .map(|i| format!("{prefix}-attempt-{i}"))
+        .collect();
+    assert_eq!(ids, expected.iter().map(String::as_str).collect::<Vec<_>>());
+
+    // Re-enqueuing the same batch, or a batch overlapping it, inserts only new ids
+    let outcome = repo.enqueue_attempts(&batch).expect("re-enqueue batch");
+    assert_eq!(
+        outcome,
+        EnqueueBatchOutcome {
+            inserted: 0,
+            skipped: 6
+        }
+    );
+    let mut overlapping = batch[4..].to_vec();
+    overlapping.push((
+        format!("{prefix}-attempt-6"),
+        run_a.clone(),
+        EnqueueOptions::default(),
+    ));
+    let outcome = repo
+        .enqueue_attempts(&overlapping)
+        .expect("overlapping batch");
+    assert_eq!(
+        outcome,
+        EnqueueBatchOutcome {
+            inserted: 1,
+            skipped: 2
+        }
+    );
+
+    // An attempt of an unknown run rejects the whole batch
+    let rejected = vec![
+        (
+            format!("{prefix}-attempt-7"),
+            run_a.clone(),
+            EnqueueOptions::default(),
+        ),
+        (
+            format!("{prefix}-attempt-8"),
+            format!("{prefix}-run-none"),
+            EnqueueOptions::default(),
+        ),
+    ];
+    assert!(matches!(
+        repo.enqueue_attempts(&rejected),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+    let dispatchable = repo
+        .list_dispatchable_attempts(Utc::now(), 100)
+        .expect("list dispatchable");
+    assert_eq!(
+        dispatchable
+            .iter()
+            .filter(|a| a.attempt_id.starts_with(prefix))
+            .count(),
+        7
+    );
+    assert_eq!(
+        repo.enqueue_attempts(&[]).expect("empty batch"),
+        EnqueueBatchOutcome::default()
+    );
+}
+
+/// Run status transitions and `list_runs` filtering and paging.
+pub fn assert_run_lifecycle_contract<R: ContractHarness>(repo: &R, prefix: &str) {
+    let now = chrono::DateTime::from_timestamp_millis(Utc::now().timestamp_millis())
+        .expect("now in range");
+    let record = |suffix: &str, workflow: &str, created_at: DateTime<Utc>| RunRecord {
+        run_id: format!("{prefix}-{suffix}"),
+        workflow_name: workflow.to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at,
+        updated_at: created_at,
+    };
+    let older = record("older", "ingest", now - Duration::hours(2));
+    let newer = record("newer", "ingest", now - Duration::hours(1));
+    let other = record("other", "report", now);
+    for run in [&older, &newer, &other] {
+        repo.create_run(run).expect("create run");
+    }
+
+    // Attempts reference their run
+    assert!(matches!(
+        repo.enqueue_attempt_for(
+            &format!("{prefix}-orphan-attempt"),
+            &format!("{prefix}-none")
+        ),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+    repo.enqueue_attempt_for(&format!("{prefix}-attempt"), &older.run_id)
+        .expect("enqueue attempt of a recorded run");
+
+    repo.update_run_status(&older.run_id, RunRuntimeStatus::Running, None)
+        .expect("run starts");
+    repo.update_run_status(&older.run_id, RunRuntimeStatus::Failed, Some("boom"))
+        .expect("run fails");
+    let failed = repo.get_run(&older.run_id).expect("get run").expect("run");
+    assert_eq!(failed.status, RunRuntimeStatus::Failed);
+    assert_eq!(failed.status_reason.as_deref(), Some("boom"));
+    assert!(failed.updated_at > older.updated_at);
+    // A failed run runs again once its attempt is requeued
+    repo.update_run_status(&older.run_id, RunRuntimeStatus::Running, None)
+        .expect("failed run restarts");
+    assert_eq!(
+        repo.get_run(&older.run_id)
+            .expect("get run")
+            .expect("run")
+            .status_reason,
+        None
+    );
+    repo.update_run_status(&older.run_id, RunRuntimeStatus::Completed, None)
+        .expect("run completes");
+    repo.update_run_status(&older.run_id, RunRuntimeStatus::Completed, None)
+        .expect("completing again is a no-op");
+    assert!(matches!(
+        repo.update_run_status(&older.run_id, RunRuntimeStatus::Running, None),
+        Err(oris_kernel::KernelError::Conflict(_))
+    ));
+    assert!(matches!(
+        repo.update_run_status(
+            &format!("{prefix}-missing"),
+            RunRuntimeStatus::Running,
+            None
+        ),
+        Err(oris_kernel::KernelError::NotFound(_))
+    ));
+
+    let ids = |filter: RunRecordFilter, page: PageRequest| -> Vec<String> {
+        repo.list_runs(&filter, page)
+            .expect("list runs")
+            .into_iter()
+            .map(|run| run.run_id)
+            .filter(|id| id.starts_with(prefix))
+            .collect()
+    };
+    // Most recently updated first
+    assert_eq!(
+        ids(RunRecordFilter::default(), PageRequest::default()),
+        [
+            older.run_id.as_str(),
+            other.run_id.as_str(),
+            newer.run_id.as_str()
+        ]
+    );
+    assert_eq!(
+        ids(
+            RunRecordFilter::default().with_status(RunRuntimeStatus::Queued),
+            PageRequest::default()
+        ),
+        [other.run_id.as_str(), newer.run_id.as_str()]
+    );
+    assert_eq!(
+        ids(
+            RunRecordFilter::default().for_workflow("ingest"),
+            PageRequest::default()
+        ),
+        [older.run_id.as_str(), newer.run_id.as_str()]
+    );
+    assert_eq!(
+        ids(
+            RunRecordFilter::default()
+                .for_workflow("ingest")
+                .created_between(Some(newer.created_at), Some(now)),
+            PageRequest::default()
+        ),
+        [newer.run_id.as_str()]
+    );
+    assert_eq!(
+        ids(
+            RunRecordFilter::default().for_workflow("ingest"),
+            PageRequest::new(1, 5)
+        ),
+        [newer.run_id.as_str()]
+    );
+}
+
+/// [assert_bounty_worker_swarm_contract] and
+/// [assert_recipe_organism_session_dispute_contract].
+pub fn assert_semantic_roundtrip<R: RuntimeRepository>(repo: &R, prefix: &str) {
+    assert_bounty_worker_swarm_contract(repo, prefix);
+    assert_recipe_organism_session_dispute_contract(repo, prefix);
+}
+
+/// `tenant_a` and `tenant_b` are handles on one store scoped to tenants "a" and "b";
+/// `operator` is unscoped.
+pub fn assert_tenant_isolation_contract<R: ContractHarness>(
+    tenant_a: &R,
+    tenant_b: &R,
+    operator: &R,
+    prefix: &str,
+) {
+    let now = Utc::now();
+    let run_a = format!("{prefix}-run-a");
+    let run_b = format!("{prefix}-run-b");
+    let attempt_a = format!("{prefix}-attempt-a");
+    let attempt_b = format!("{prefix}-attempt-b");
+    tenant_a.seed_attempt(&attempt_a, &run_a);
+    tenant_b.seed_attempt(&attempt_b, &run_b);
+
+    // Runs take the tenant of the handle that created them
+    let stored_b = operator
+        .get_run(&run_b)
+        .expect("operator get run")
+        .expect("run b exists");
+    assert_eq!(stored_b.tenant_id.as_deref(), Some("b"));
+    assert_eq!(
+        tenant_a.get_run(&run_b).expect("cross-tenant get run"),
+        None
+    );
+    let runs_a: Vec<_> = tenant_a
+        .list_runs(&RunRecordFilter::default(), PageRequest::new(0, 10))
+        .expect("list tenant a runs")
+        .into_iter()
+        .map(|run| run.run_id)
+        .collect();
+    assert_eq!(runs_a, vec![run_a.clone()]);
+    assert!(matches!(
+        tenant_a.update_run_status(&run_b, RunRuntimeStatus::Cancelled, None),
+        Err(KernelError::NotFound(_))
+    ));
+    assert!(matches!(
+        tenant_a.create_run(&RunRecord {
+            run_id: format!("{prefix}-run-foreign"),
+            workflow_name: "contract".to_string(),
+            status: RunRuntimeStatus::Queued,
+            status_reason: None,
+            tenant_id: Some("b".to_string()),
+            created_at: now,
+            updated_at: now,
+        }),
+        Err(KernelError::Validation(_))
+    ));
+    assert!(matches!(
+        tenant_a.enqueue_attempt_for(&format!("{prefix}-attempt-foreign"), &run_b),
+        Err(KernelError::NotFound(_))
+    ));
+
+    // Dispatch and leasing never reach the other tenant's attempts
+    let dispatchable = |repo: &R| -> Vec<String> {
+        repo.list_dispatchable_attempts(now, 10)
+            .expect("list dispatchable")
+            .into_iter()
+            .map(|a| a.attempt_id)
+            .collect()
+    };
+    assert_eq!(dispatchable(tenant_a), vec![attempt_a.clone()]);
+    assert_eq!(dispatchable(tenant_b), vec![attempt_b.clone()]);
+    assert_eq!(dispatchable(operator).len(), 2);
+    assert!(matches!(
+        tenant_a.upsert_lease(&attempt_b, "worker-a", now + Duration::seconds(30)),
+        Err(KernelError::NotFound(_))
+    ));
+    let claimed_b = tenant_b
+        .claim_dispatchable_attempts(now, "worker-b", 10, Duration::seconds(30))
+        .expect("tenant b claim");
+    assert_eq!(claimed_b.len(), 1);
+    assert_eq!(claimed_b[0].attempt.attempt_id, attempt_b);
+    assert_eq!(claimed_b[0].lease.tenant_id.as_deref(), Some("b"));
+    let claimed_a = tenant_a
+        .claim_dispatchable_attempts(now, "worker-a", 10, Duration::seconds(30))
+        .expect("tenant a claim");
+    assert_eq!(claimed_a.len(), 1);
+    assert_eq!(claimed_a[0].attempt.attempt_id, attempt_a);
+    assert!(matches!(
+        tenant_a.heartbeat_lease(
+            &claimed_b[0].lease.lease_id,
+            now,
+            now + Duration::seconds(60)
+        ),
+        Err(KernelError::NotFound(_))
+    ));
+    assert!(matches!(
+        tenant_a.cancel_attempt(&attempt_b),
+        Err(KernelError::NotFound(_))
+    ));
+
+    // Reaping stale leases stays within the tenant
+    assert_eq!(
+        tenant_a
+            .expire_leases_and_requeue(now + Duration::hours(1))
+            .expect("tenant a reap"),
+        1
+    );
+    assert!(!operator.has_lease(&attempt_a));
+    assert!(operator.has_lease(&attempt_b));
+
+    // Interrupts follow their run's tenant
+    let interrupt = InterruptRecord {
+        interrupt_id: format!("{prefix}-int-b"),
+        run_id: run_b.clone(),
+        attempt_id: attempt_b.clone(),
+        step_id: None,
+        payload: serde_json::json!({"question": "ship it?"}),
+        status: InterruptStatus::Pending,
+        created_at: now,
+        decision: None,
+        resolution: None,
+        resolved_at: None,
+    };
+    assert!(matches!(
+        tenant_a.create_interrupt(&interrupt),
+        Err(KernelError::NotFound(_))
+    ));
+    tenant_b
+        .create_interrupt(&interrupt)
+        .expect("tenant b interrupt");
+    assert!(tenant_a
+        .list_pending_interrupts(&InterruptFilter::default())
+        .expect("tenant a interrupts")
+        .is_empty());
+    assert_eq!(
+        tenant_b
+            .list_pending_interrupts(&InterruptFilter::default())
+            .expect("tenant b interrupts")
+            .len(),
+        1
+    );
+    assert!(matches!(
+        tenant_a.resolve_interrupt(
+            &interrupt.interrupt_id,
+            InterruptDecision::Approve,
+            &serde_json::Value::Null
+        ),
+        Err(KernelError::NotFound(_))
+    ));
+}
@@ -6,16 +6,17 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 use oris_kernel::event::KernelError;
 
 use super::circuit_breaker::CircuitBreaker;
-use super::models::AttemptDispatchRecord;
+use super::models::{AttemptDispatchRecord, DispatchOptions, DispatchableAttempts};
 use super::observability::RejectionReason;
 use super::repository::RuntimeRepository;
 
 const DISPATCH_SCAN_LIMIT: usize = 16;
+const DISPATCH_LEASE_TTL_SECS: i64 = 30;
 
 /// Fairness policy for the scheduler (K5-c).
 #[derive(Clone, Debug, Default)]
@@ -97,6 +98,9 @@ pub struct DispatchContext {
     /// `dispatch_one_with_context` returns `SchedulerDecision::Backpressure`
     /// instead of acquiring a new lease.
     pub max_queue_depth: Option<usize>,
+    /// Attempts of a run that already holds this many active leases are skipped, so one
+    /// run that fans out cannot monopolize the workers.
+    pub max_concurrent_per_run: Option<usize>,
     /// Fairness policy for this dispatch (K5-c)
     pub fairness_policy: Option<FairnessPolicy>,
     /// Thread priority for this dispatch (K5-c)
@@ -125,6 +129,11 @@ impl DispatchContext {
         self
     }
 
+    pub fn with_max_concurrent_per_run(mut self, limit: usize) -> Self {
+        self.max_concurrent_per_run = Some(limit);
+        self
+    }
+
     pub fn with_fairness_policy(mut self, policy: FairnessPolicy) -> Self {
         self.fairness_policy = Some(policy);
         self
@@ -147,12 +156,20 @@ pub enum SchedulerDecision {
     Dispatched {
         attempt_id: String,
         worker_id: String,
+        /// Dispatchable attempts passed over because their run was at
+        /// `max_concurrent_per_run`.
+        skipped_for_fairness: usize,
     },
     /// Backpressure applied: the queue depth has exceeded the configured limit.
     Backpressure {
         reason: RejectionReason,
         queue_depth: usize,
     },
+    /// Nothing is dispatchable yet; the earliest delayed or backed-off attempt becomes
+    /// dispatchable at `next_wakeup`, so the caller can sleep until then.
+    Idle {
+        next_wakeup: DateTime<Utc>,
+    },
     Noop,
 }
 
@@ -224,9 +241,9 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
                 // Already in order from repository
             }
             FairnessPolicy::PriorityWeighted { .. } => {
-                // Sort by priority (higher first)
-                // Note: AttemptDispatchRecord doesn't have priority, so we use attempt_no as proxy
-                candidates.sort_by(|a, b| b.attempt_no.cmp(&a.attempt_no));
+                // Higher priority first; the sort is stable, so equal priorities keep the
+                // repository's enqueue order
+                candidates.sort_by(|a, b| b.priority.cmp(&a.priority));
             }
             FairnessPolicy::RoundRobin => {
                 // For round-robin, we could track last dispatched tenant
@@ -235,6 +252,62 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
         }
     }
 
+    /// Whether the dispatch can be left to
+    /// [RuntimeRepository::claim_dispatchable_attempts]: it keeps the repository's order
+    /// and needs neither the queue depth nor per-run limits.
+    fn claims_in_repository(&self, context: Option<&DispatchContext>) -> bool {
+        let policy = context
+            .and_then(|c| c.fairness_policy.as_ref())
+            .unwrap_or(&self.fairness_policy);
+        !matches!(policy, FairnessPolicy::PriorityWeighted { .. })
+            && context.map_or(true, |c| {
+                c.max_queue_depth.is_none() && c.max_concurrent_per_run.is_none()
+            })
+    }
+
+    /// Dispatch by claiming the first dispatchable attempt in one repository call, so
+    /// concurrent schedulers never race for the same lease.
+    fn claim_one(
+        &self,
+        worker_id: &str,
+        context: Option<&DispatchContext>,
+        now: DateTime<Utc>,
+    ) -> Result<SchedulerDecision, KernelError> {
+        let tenant_id = context.and_then(|c| c.tenant_id.as_deref());
+        if let Some((reason, count)) = self
+            .check_tenant_backpressure(tenant_id)
+            .or_else(|| self.check_worker_backpressure(worker_id))
+        {
+            return Ok(SchedulerDecision::Backpressure {
+                reason,
+                queue_depth: count,
+            });
+        }
+
+        let claimed = self.repository.claim_dispatchable_attempts(
+            now,
+            worker_id,
+            1,
+            chrono::Duration::seconds(DISPATCH_LEASE_TTL_SECS),
+        )?;
+        if let Some(claimed) = claimed.into_iter().next() {
+            #[cfg(feature = "metrics")]
+            ::metrics::counter!(crate::metrics::LEASES_GRANTED_TOTAL).increment(1);
+
+            self.increment_tenant_count(tenant_id);
+            self.increment_worker_count(worker_id);
+            return Ok(SchedulerDecision::Dispatched {
+                attempt_id: claimed.attempt.attempt_id,
+                worker_id: worker_id.to_string(),
+                skipped_for_fairness: 0,
+            });
+        }
+        if let Some(next_wakeup) = self.repository.next_dispatch_at(now)? {
+            return Ok(SchedulerDecision::Idle { next_wakeup });
+        }
+        Ok(SchedulerDecision::Noop)
+    }
+
     /// Check per-tenant backpressure (K5-d).
     fn check_tenant_backpressure(
         &self,
@@ -338,6 +411,11 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
     /// If `context.max_queue_depth` is set and the number of dispatchable candidates is
     /// greater than or equal to that limit, returns `SchedulerDecision::Backpressure`
     /// without acquiring any lease.
+    ///
+    /// Without a queue depth or per-run limit, and unless the fairness policy reorders
+    /// candidates, the attempt is claimed with
+    /// [RuntimeRepository::claim_dispatchable_attempts] instead of listing candidates and
+    /// leasing them one by one.
     pub fn dispatch_one_with_context(
         &self,
         worker_id: &str,
@@ -355,9 +433,23 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
             }
         }
 
-        let candidates: Vec<AttemptDispatchRecord> = self
-            .repository
-            .list_dispatchable_attempts(now, DISPATCH_SCAN_LIMIT)?;
+        if self.claims_in_repository(context) {
+            return self.claim_one(worker_id, context, now);
+        }
+
+        let options = DispatchOptions {
+            max_concurrent_per_run: context.and_then(|c| c.max_concurrent_per_run),
+        };
+        let DispatchableAttempts {
+            attempts: candidates,
+            skipped_for_fairness,
+        } = self.repository.list_dispatchable_attempts_with_options(
+            now,
+            DISPATCH_SCAN_LIMIT,
+            &options,
+        )?;
+        #[cfg(feature = "metrics")]
+        ::metrics::gauge!(crate::metrics::DISPATCHABLE_ATTEMPTS).set(candidates.len() as f64);
 
         // Sort candidates based on fairness policy (K5-c)
         let mut sorted_candidates = candidates.clone();
@@ -395,7 +487,7 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
             });
         }
 
-        let lease_expires_at = now + chrono::Duration::seconds(30);
+        let lease_expires_at = now + chrono::Duration::seconds(DISPATCH_LEASE_TTL_SECS);
 
         for candidate in sorted_candidates {
             if let Err(e) =
@@ -409,6 +501,9 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
                 return Err(e);
             }
 
+            #[cfg(feature = "metrics")]
+            ::metrics::counter!(crate::metrics::LEASES_GRANTED_TOTAL).increment(1);
+
             // Update counts after successful dispatch (K5-d)
             self.increment_tenant_count(context.and_then(|c| c.tenant_id.as_deref()));
             self.increment_worker_count(worker_id);
@@ -416,9 +511,28 @@ impl<R: RuntimeRepository> SkeletonScheduler<R> {
             return Ok(SchedulerDecision::Dispatched {
                 attempt_id: candidate.attempt_id,
                 worker_id: worker_id.to_string(),
+                skipped_for_fairness,
             });
         }
 
+        // Nothing was dispatchable at all (as opposed to every candidate being claimed
+        // by another worker): say whether per-run limits held work back, otherwise when
+        // the earliest delayed attempt becomes due.
+        if candidates.is_empty() {
+            if skipped_for_fairness > 0 {
+                return Ok(SchedulerDecision::Backpressure {
+                    reason: RejectionReason::capacity_limit(format!(
+                        "{} attempts held back by max_concurrent_per_run",
+                        skipped_for_fairness
+                    )),
+                    queue_depth: skipped_for_fairness,
+                });
+            }
+            if let Some(next_wakeup) = self.repository.next_dispatch_at(now)? {
+                return Ok(SchedulerDecision::Idle { next_wakeup });
+            }
+        }
+
         Ok(SchedulerDecision::Noop)
     }
 }
@@ -441,7 +555,7 @@ mod tests {
     use super::*;
     use oris_kernel::identity::{RunId, Seq};
 
-    use super::super::models::{AttemptExecutionStatus, LeaseRecord};
+    use super::super::models::{AttemptExecutionStatus, LeaseDirective, LeaseRecord};
 
     #[derive(Clone)]
     struct FakeRepository {
@@ -483,7 +597,7 @@ mod tests {
                 .expect("conflict lock")
                 .contains(attempt_id)
             {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::LeaseConflict(format!(
                     "active lease already exists for attempt: {}",
                     attempt_id
                 )));
@@ -501,6 +615,7 @@ mod tests {
                 version: 1,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: None,
             })
         }
 
@@ -509,8 +624,8 @@ mod tests {
             _lease_id: &str,
             _heartbeat_at: DateTime<Utc>,
             _lease_expires_at: DateTime<Utc>,
-        ) -> Result<(), KernelError> {
-            Ok(())
+        ) -> Result<LeaseDirective, KernelError> {
+            Ok(LeaseDirective::Continue)
         }
 
         fn expire_leases_and_requeue(
@@ -672,6 +787,14 @@ mod tests {
             attempt_no,
             status: AttemptExecutionStatus::Queued,
             retry_at: None,
+            priority: 0,
+        }
+    }
+
+    fn prioritized(id: &str, priority: i32) -> AttemptDispatchRecord {
+        AttemptDispatchRecord {
+            priority,
+            ..attempt(id, 1)
         }
     }
 
@@ -691,11 +814,14 @@ mod tests {
             SchedulerDecision::Dispatched {
                 attempt_id,
                 worker_id,
+                ..
             } => {
                 assert_eq!(attempt_id, "attempt-b");
                 assert_eq!(worker_id, "worker-scheduler");
             }
-            SchedulerDecision::Noop | SchedulerDecision::Backpressure { .. } => {
+            SchedulerDecision::Idle { .. }
+            | SchedulerDecision::Noop
+            | SchedulerDecision::Backpressure { .. } => {
                 panic!("expected a dispatch")
             }
         }
@@ -850,8 +976,8 @@ mod tests {
     fn dispatch_with_priority_weighted_fairness() {
         let repo = FakeRepository::new(
             vec![
-                attempt("attempt-low", 1),   // lower attempt_no = lower priority
-                attempt("attempt-high", 10), // higher attempt_no = higher priority
+                prioritized("attempt-low", 1),
+                prioritized("attempt-high", 10),
             ],
             &[],
         );
@@ -866,7 +992,7 @@ mod tests {
 
         match decision {
             SchedulerDecision::Dispatched { attempt_id, .. } => {
-                // With PriorityWeighted, higher attempt_no should be dispatched first
+                // With PriorityWeighted, higher priority should be dispatched first
                 assert_eq!(attempt_id, "attempt-high");
             }
             other => panic!("expected Dispatched, got {:?}", other),
@@ -971,7 +1097,11 @@ mod tests {
     #[test]
     fn fairness_priority_weighted_dispatches_highest_priority_first() {
         let repo = FakeRepository::new(
-            vec![attempt("low", 1), attempt("mid", 5), attempt("high", 10)],
+            vec![
+                prioritized("low", 1),
+                prioritized("mid", 5),
+                prioritized("high", 10),
+            ],
             &[],
         );
         let scheduler = SkeletonScheduler::new(repo.clone())
@@ -983,14 +1113,18 @@ mod tests {
         let claimed = repo.claimed_attempts.lock().unwrap();
         assert_eq!(
             claimed[0], "high",
-            "PriorityWeighted must pick highest attempt_no first"
+            "PriorityWeighted must pick highest priority first"
         );
     }
 
     #[test]
     fn fairness_priority_weighted_skips_conflict_picks_next_best() {
         let repo = FakeRepository::new(
-            vec![attempt("low", 1), attempt("mid", 5), attempt("high", 10)],
+            vec![
+                prioritized("low", 1),
+                prioritized("mid", 5),
+                prioritized("high", 10),
+            ],
             &["high"], // highest priority is conflicted
         );
         let scheduler = SkeletonScheduler::new(repo.clone())
@@ -1008,7 +1142,7 @@ mod tests {
 
     #[test]
     fn fairness_context_policy_overrides_scheduler_default() {
-        let repo = FakeRepository::new(vec![attempt("low", 1), attempt("high", 10)], &[]);
+        let repo = FakeRepository::new(vec![prioritized("low", 1), prioritized("high", 10)], &[]);
         // Scheduler default is FCFS
         let scheduler = SkeletonScheduler::new(repo.clone());
 
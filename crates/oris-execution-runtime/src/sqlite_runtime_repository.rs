@@ -3,81 +3,158 @@
 use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Duration, TimeZone, Utc};
-use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, TransactionBehavior};
+use serde_json::Value;
 
 use oris_kernel::event::KernelError;
 use oris_kernel::identity::{RunId, Seq};
+use oris_kernel::PageRequest;
 
 use super::models::{
-    AttemptDispatchRecord, AttemptExecutionStatus, BountyRecord, BountyStatus, DisputeRecord,
-    DisputeStatus, LeaseRecord, OrganismRecord, RecipeRecord, SessionMessageRecord, SessionRecord,
-    SwarmTaskRecord, WorkerRecord,
+    AttemptCancellation, AttemptDispatchRecord, AttemptExecutionStatus, BountyRecord, BountyStatus,
+    ClaimedAttempt, DeadLetterAttemptRecord, DispatchOptions, DispatchableAttempts, DisputeRecord,
+    DisputeStatus, EnqueueBatchOutcome, EnqueueOptions, InterruptDecision, InterruptFilter,
+    InterruptRecord, InterruptStatus, LeaseDirective, LeaseFence, LeaseRecord, OrganismRecord,
+    RecipeRecord, RunRecord, RunRecordFilter, RunRuntimeStatus, RuntimeStats, SessionMessageRecord,
+    SessionRecord, SwarmTaskRecord, WorkerRecord,
 };
-use super::repository::RuntimeRepository;
+use super::repository::{run_tenant_in_scope, RuntimeRepository};
 
-const SQLITE_RUNTIME_SCHEMA_VERSION: i64 = 13;
+pub use super::models::{AttemptAckOutcome, RetryPolicyConfig, RetryStrategy, TimeoutPolicyConfig};
 
-#[derive(Clone)]
-pub struct SqliteRuntimeRepository {
-    conn: Arc<Mutex<Connection>>,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum RetryStrategy {
-    Fixed,
-    Exponential,
-}
-
-impl RetryStrategy {
-    fn as_str(&self) -> &'static str {
-        match self {
-            Self::Fixed => "fixed",
-            Self::Exponential => "exponential",
-        }
-    }
+/// How long a write waits for another connection's transaction before failing
+const SQLITE_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
-    pub fn from_str(value: &str) -> Option<Self> {
-        match value.trim().to_ascii_lowercase().as_str() {
-            "fixed" => Some(Self::Fixed),
-            "exponential" => Some(Self::Exponential),
-            _ => None,
-        }
-    }
-}
+/// Columns [map_row_to_interrupt_record] reads, in order
+const INTERRUPT_RECORD_COLUMNS: &str = "interrupt_id, run_id, attempt_id, step_id, value_json, status, created_at_ms, decision, resolution_json, resolved_at_ms";
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct RetryPolicyConfig {
-    pub strategy: RetryStrategy,
-    pub backoff_ms: i64,
-    pub max_backoff_ms: Option<i64>,
-    pub multiplier: Option<f64>,
-    pub max_retries: u32,
+/// One step of the runtime schema, applied once in version order and recorded in
+/// `runtime_schema_migrations`
+struct SqliteRuntimeMigration {
+    version: i64,
+    name: &'static str,
+    apply: fn(&Connection) -> Result<(), KernelError>,
 }
 
-impl RetryPolicyConfig {
-    fn next_backoff_ms(&self, current_attempt_no: u32) -> i64 {
-        let current_attempt_no = current_attempt_no.max(1);
-        let raw_backoff = match self.strategy {
-            RetryStrategy::Fixed => self.backoff_ms,
-            RetryStrategy::Exponential => {
-                let multiplier = self.multiplier.unwrap_or(2.0);
-                let exponent = (current_attempt_no - 1) as i32;
-                ((self.backoff_ms as f64) * multiplier.powi(exponent)).round() as i64
-            }
-        };
-        if let Some(max_backoff_ms) = self.max_backoff_ms {
-            raw_backoff.min(max_backoff_ms)
-        } else {
-            raw_backoff
-        }
-    }
-}
+const SQLITE_RUNTIME_MIGRATIONS: &[SqliteRuntimeMigration] = &[
+    SqliteRuntimeMigration {
+        version: 1,
+        name: "baseline_runtime_tables",
+        apply: apply_sqlite_runtime_migration_v1,
+    },
+    SqliteRuntimeMigration {
+        version: 2,
+        name: "interrupt_resume_and_api_key_role",
+        apply: apply_sqlite_runtime_migration_v2,
+    },
+    SqliteRuntimeMigration {
+        version: 3,
+        name: "attempt_retry_policy_and_history",
+        apply: apply_sqlite_runtime_migration_v3,
+    },
+    SqliteRuntimeMigration {
+        version: 4,
+        name: "attempt_execution_timeout_policy",
+        apply: apply_sqlite_runtime_migration_v4,
+    },
+    SqliteRuntimeMigration {
+        version: 5,
+        name: "runtime_dead_letter_queue",
+        apply: apply_sqlite_runtime_migration_v5,
+    },
+    SqliteRuntimeMigration {
+        version: 6,
+        name: "attempt_priority_dispatch_order",
+        apply: apply_sqlite_runtime_migration_v6,
+    },
+    SqliteRuntimeMigration {
+        version: 7,
+        name: "attempt_tenant_rate_limits",
+        apply: apply_sqlite_runtime_migration_v7,
+    },
+    SqliteRuntimeMigration {
+        version: 8,
+        name: "attempt_trace_context",
+        apply: apply_sqlite_runtime_migration_v8,
+    },
+    SqliteRuntimeMigration {
+        version: 9,
+        name: "replay_effect_guard",
+        apply: apply_sqlite_runtime_migration_v9,
+    },
+    SqliteRuntimeMigration {
+        version: 10,
+        name: "runtime_a2a_sessions",
+        apply: apply_sqlite_runtime_migration_v10,
+    },
+    SqliteRuntimeMigration {
+        version: 11,
+        name: "runtime_a2a_compat_tasks",
+        apply: apply_sqlite_runtime_migration_v11,
+    },
+    // EvoMap Alignment: Bounty, Swarm, Worker registry
+    SqliteRuntimeMigration {
+        version: 12,
+        name: "runtime_bounties_swarm_worker",
+        apply: apply_sqlite_runtime_migration_v12,
+    },
+    // EvoMap Alignment: Recipe, Organism, Session, Dispute
+    SqliteRuntimeMigration {
+        version: 13,
+        name: "runtime_recipes_organisms_sessions_disputes",
+        apply: apply_sqlite_runtime_migration_v13,
+    },
+    SqliteRuntimeMigration {
+        version: 14,
+        name: "interrupt_inbox",
+        apply: apply_sqlite_runtime_migration_v14,
+    },
+    SqliteRuntimeMigration {
+        version: 15,
+        name: "runtime_runs",
+        apply: apply_sqlite_runtime_migration_v15,
+    },
+    SqliteRuntimeMigration {
+        version: 16,
+        name: "attempt_enqueue_order",
+        apply: apply_sqlite_runtime_migration_v16,
+    },
+    SqliteRuntimeMigration {
+        version: 17,
+        name: "attempt_dead_letter",
+        apply: apply_sqlite_runtime_migration_v17,
+    },
+    SqliteRuntimeMigration {
+        version: 18,
+        name: "attempt_run_at",
+        apply: apply_sqlite_runtime_migration_v18,
+    },
+    SqliteRuntimeMigration {
+        version: 19,
+        name: "attempt_cancel_request",
+        apply: apply_sqlite_runtime_migration_v19,
+    },
+    SqliteRuntimeMigration {
+        version: 20,
+        name: "run_lifecycle",
+        apply: apply_sqlite_runtime_migration_v20,
+    },
+    SqliteRuntimeMigration {
+        version: 21,
+        name: "tenant_isolation",
+        apply: apply_sqlite_runtime_migration_v21,
+    },
+];
+
+/// Latest schema version this build knows how to migrate to
+const SQLITE_RUNTIME_SCHEMA_VERSION: i64 =
+    SQLITE_RUNTIME_MIGRATIONS[SQLITE_RUNTIME_MIGRATIONS.len() - 1].version;
 
-#[derive(Clone, Debug)]
-pub struct AttemptAckOutcome {
-    pub status: AttemptExecutionStatus,
-    pub next_retry_at: Option<DateTime<Utc>>,
-    pub next_attempt_no: u32,
+#[derive(Clone)]
+pub struct SqliteRuntimeRepository {
+    conn: Arc<Mutex<Connection>>,
+    /// Tenant every query is restricted to; `None` sees all tenants.
+    tenant_id: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -97,12 +174,6 @@ pub struct AttemptRetryHistorySnapshot {
     pub history: Vec<AttemptRetryHistoryRow>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct TimeoutPolicyConfig {
-    pub timeout_ms: i64,
-    pub on_timeout_status: AttemptExecutionStatus,
-}
-
 #[derive(Clone, Debug)]
 pub struct DeadLetterRow {
     pub attempt_id: String,
@@ -153,96 +224,108 @@ pub struct AttemptTraceContextRow {
 impl SqliteRuntimeRepository {
     pub fn new(db_path: &str) -> Result<Self, KernelError> {
         let conn = Connection::open(db_path)
-            .map_err(|e| KernelError::Driver(format!("open sqlite runtime repo: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("open sqlite runtime repo: {}", e)))?;
+        // Other connections to the same file may hold the write lock while they claim
+        conn.busy_timeout(SQLITE_BUSY_TIMEOUT)
+            .map_err(|e| KernelError::Storage(format!("set sqlite busy timeout: {}", e)))?;
         let repo = Self {
             conn: Arc::new(Mutex::new(conn)),
+            tenant_id: None,
         };
         repo.ensure_schema()?;
         Ok(repo)
     }
 
+    /// A handle on the same database that only sees `tenant_id`'s runs, attempts,
+    /// leases and interrupts; rows of other tenants behave as if they did not exist.
+    /// Runs created through it belong to `tenant_id`.
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Tenant this handle is scoped to, if any.
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Applies pending migrations in one exclusive transaction, so other processes
+    /// opening the same file wait instead of migrating it concurrently, and a failed
+    /// migration leaves the schema at its previous version.
     fn ensure_schema(&self) -> Result<(), KernelError> {
-        let conn = self
+        let mut conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
-        ensure_sqlite_migration_table(&conn)?;
-        let current = sqlite_current_schema_version(&conn)?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Exclusive)
+            .map_err(|e| KernelError::Storage(format!("begin sqlite runtime migration: {}", e)))?;
+        ensure_sqlite_migration_table(&tx)?;
+        let current = sqlite_current_schema_version(&tx)?;
         if current > SQLITE_RUNTIME_SCHEMA_VERSION {
             return Err(KernelError::Driver(format!(
-                "sqlite runtime schema version {} is newer than supported {}",
+                "sqlite runtime schema is at version {}, newer than the {} this build supports; upgrade oris-execution-runtime",
                 current, SQLITE_RUNTIME_SCHEMA_VERSION
             )));
         }
-        if current < 1 {
-            apply_sqlite_runtime_migration_v1(&conn)?;
-            record_sqlite_migration(&conn, 1, "baseline_runtime_tables")?;
-        }
-        if current < 2 {
-            apply_sqlite_runtime_migration_v2(&conn)?;
-            record_sqlite_migration(&conn, 2, "interrupt_resume_and_api_key_role")?;
-        }
-        if current < 3 {
-            apply_sqlite_runtime_migration_v3(&conn)?;
-            record_sqlite_migration(&conn, 3, "attempt_retry_policy_and_history")?;
-        }
-        if current < 4 {
-            apply_sqlite_runtime_migration_v4(&conn)?;
-            record_sqlite_migration(&conn, 4, "attempt_execution_timeout_policy")?;
-        }
-        if current < 5 {
-            apply_sqlite_runtime_migration_v5(&conn)?;
-            record_sqlite_migration(&conn, 5, "runtime_dead_letter_queue")?;
-        }
-        if current < 6 {
-            apply_sqlite_runtime_migration_v6(&conn)?;
-            record_sqlite_migration(&conn, 6, "attempt_priority_dispatch_order")?;
-        }
-        if current < 7 {
-            apply_sqlite_runtime_migration_v7(&conn)?;
-            record_sqlite_migration(&conn, 7, "attempt_tenant_rate_limits")?;
-        }
-        if current < 8 {
-            apply_sqlite_runtime_migration_v8(&conn)?;
-            record_sqlite_migration(&conn, 8, "attempt_trace_context")?;
-        }
-        if current < 9 {
-            apply_sqlite_runtime_migration_v9(&conn)?;
-            record_sqlite_migration(&conn, 9, "replay_effect_guard")?;
-        }
-        if current < 10 {
-            apply_sqlite_runtime_migration_v10(&conn)?;
-            record_sqlite_migration(&conn, 10, "runtime_a2a_sessions")?;
-        }
-        if current < 11 {
-            apply_sqlite_runtime_migration_v11(&conn)?;
-            record_sqlite_migration(&conn, 11, "runtime_a2a_compat_tasks")?;
-        }
-        // EvoMap Alignment: Bounty, Swarm, Worker registry
-        if current < 12 {
-            apply_sqlite_runtime_migration_v12(&conn)?;
-            record_sqlite_migration(&conn, 12, "runtime_bounties_swarm_worker")?;
-        }
-        // EvoMap Alignment: Recipe, Organism, Session, Dispute
-        if current < 13 {
-            apply_sqlite_runtime_migration_v13(&conn)?;
-            record_sqlite_migration(&conn, 13, "runtime_recipes_organisms_sessions_disputes")?;
+        for migration in SQLITE_RUNTIME_MIGRATIONS
+            .iter()
+            .filter(|m| m.version > current)
+        {
+            (migration.apply)(&tx)?;
+            record_sqlite_migration(&tx, migration.version, migration.name)?;
         }
-        Ok(())
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit sqlite runtime migration: {}", e)))
     }
 
-    pub fn enqueue_attempt(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+    /// Version of the runtime schema in this database.
+    pub fn schema_version(&self) -> Result<i64, KernelError> {
         let conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
-        conn.execute(
-            "INSERT OR IGNORE INTO runtime_attempts (attempt_id, run_id, attempt_no, status, retry_at_ms)
-             VALUES (?1, ?2, 1, 'queued', NULL)",
-            params![attempt_id, run_id],
-        )
-        .map_err(|e| KernelError::Driver(format!("enqueue attempt: {}", e)))?;
-        Ok(())
+        sqlite_current_schema_version(&conn)
+    }
+
+    /// Enqueue an attempt of `run_id`, which must have been recorded with
+    /// [RuntimeRepository::create_run]; fails with `NotFound` otherwise.
+    pub fn enqueue_attempt(&self, attempt_id: &str, run_id: &str) -> Result<(), KernelError> {
+        self.enqueue_attempt_with_priority(attempt_id, run_id, 0)
+    }
+
+    /// Enqueue an attempt that is dispatched ahead of every queued attempt with a lower
+    /// priority; among equal priorities, earlier-enqueued attempts go first.
+    pub fn enqueue_attempt_with_priority(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        priority: i32,
+    ) -> Result<(), KernelError> {
+        self.insert_queued_attempt(attempt_id, run_id, priority, None)
+    }
+
+    /// Enqueue an attempt that is not dispatched before `run_at`; a `run_at` that is
+    /// already in the past makes the attempt dispatchable immediately.
+    pub fn enqueue_attempt_at(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        run_at: DateTime<Utc>,
+    ) -> Result<(), KernelError> {
+        self.insert_queued_attempt(attempt_id, run_id, 0, Some(run_at))
+    }
+
+    fn insert_queued_attempt(
+        &self,
+        attempt_id: &str,
+        run_id: &str,
+        priority: i32,
+        run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), KernelError> {
+        let options = EnqueueOptions { priority, run_at };
+        self.enqueue_attempts(&[(attempt_id.to_string(), run_id.to_string(), options)])
+            .map(|_| ())
     }
 
     pub fn set_attempt_timeout_policy(
@@ -251,7 +334,7 @@ impl SqliteRuntimeRepository {
         policy: &TimeoutPolicyConfig,
     ) -> Result<(), KernelError> {
         if policy.timeout_ms <= 0 {
-            return Err(KernelError::Driver(
+            return Err(KernelError::Validation(
                 "timeout policy timeout_ms must be > 0".to_string(),
             ));
         }
@@ -259,7 +342,7 @@ impl SqliteRuntimeRepository {
             policy.on_timeout_status,
             AttemptExecutionStatus::Failed | AttemptExecutionStatus::Cancelled
         ) {
-            return Err(KernelError::Driver(
+            return Err(KernelError::Validation(
                 "timeout policy terminal status must be failed or cancelled".to_string(),
             ));
         }
@@ -267,6 +350,7 @@ impl SqliteRuntimeRepository {
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_attempt_tenant(&conn, attempt_id, self.tenant_id())?;
         let updated = conn
             .execute(
                 "UPDATE runtime_attempts
@@ -279,9 +363,9 @@ impl SqliteRuntimeRepository {
                     attempt_status_to_str(&policy.on_timeout_status)
                 ],
             )
-            .map_err(|e| KernelError::Driver(format!("set attempt timeout policy: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set attempt timeout policy: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for timeout policy: {}",
                 attempt_id
             )));
@@ -294,14 +378,15 @@ impl SqliteRuntimeRepository {
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_attempt_tenant(&conn, attempt_id, self.tenant_id())?;
         let updated = conn
             .execute(
                 "UPDATE runtime_attempts SET priority = ?2 WHERE attempt_id = ?1",
                 params![attempt_id, priority],
             )
-            .map_err(|e| KernelError::Driver(format!("set attempt priority: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set attempt priority: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for priority update: {}",
                 attempt_id
             )));
@@ -318,22 +403,36 @@ impl SqliteRuntimeRepository {
             .map(str::trim)
             .filter(|value| !value.is_empty())
             .map(str::to_string);
+        if let Some(scope) = self.tenant_id() {
+            if normalized.as_deref() != Some(scope) {
+                return Err(KernelError::Validation(format!(
+                    "attempt {} cannot be moved out of tenant {}",
+                    attempt_id, scope
+                )));
+            }
+        }
         let conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_attempt_tenant(&conn, attempt_id, self.tenant_id())?;
         let updated = conn
             .execute(
                 "UPDATE runtime_attempts SET tenant_id = ?2 WHERE attempt_id = ?1",
                 params![attempt_id, normalized],
             )
-            .map_err(|e| KernelError::Driver(format!("set attempt tenant_id: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set attempt tenant_id: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for tenant update: {}",
                 attempt_id
             )));
         }
+        conn.execute(
+            "UPDATE runtime_leases SET tenant_id = ?2 WHERE attempt_id = ?1",
+            params![attempt_id, normalized],
+        )
+        .map_err(|e| KernelError::Storage(format!("set lease tenant_id: {}", e)))?;
         Ok(())
     }
 
@@ -346,8 +445,9 @@ impl SqliteRuntimeRepository {
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         conn.query_row(
-            "SELECT attempt_no, status FROM runtime_attempts WHERE attempt_id = ?1",
-            params![attempt_id],
+            "SELECT attempt_no, status FROM runtime_attempts
+             WHERE attempt_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+            params![attempt_id, self.tenant_id()],
             |row| {
                 Ok((
                     row.get::<_, i64>(0)? as u32,
@@ -356,7 +456,7 @@ impl SqliteRuntimeRepository {
             },
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get attempt status: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get attempt status: {}", e)))
     }
 
     pub fn set_attempt_trace_context(
@@ -371,6 +471,7 @@ impl SqliteRuntimeRepository {
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_attempt_tenant(&conn, attempt_id, self.tenant_id())?;
         let updated = conn
             .execute(
                 "UPDATE runtime_attempts
@@ -381,9 +482,9 @@ impl SqliteRuntimeRepository {
                  WHERE attempt_id = ?1",
                 params![attempt_id, trace_id, parent_span_id, span_id, trace_flags],
             )
-            .map_err(|e| KernelError::Driver(format!("set attempt trace context: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set attempt trace context: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for trace update: {}",
                 attempt_id
             )));
@@ -404,8 +505,9 @@ impl SqliteRuntimeRepository {
              FROM runtime_attempts
              WHERE attempt_id = ?1
                AND trace_id IS NOT NULL
-               AND trace_span_id IS NOT NULL",
-            params![attempt_id],
+               AND trace_span_id IS NOT NULL
+               AND (?2 IS NULL OR tenant_id = ?2)",
+            params![attempt_id, self.tenant_id()],
             |row| {
                 Ok(AttemptTraceContextRow {
                     trace_id: row.get(0)?,
@@ -416,7 +518,7 @@ impl SqliteRuntimeRepository {
             },
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get attempt trace context: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get attempt trace context: {}", e)))
     }
 
     pub fn latest_attempt_trace_for_run(
@@ -433,9 +535,10 @@ impl SqliteRuntimeRepository {
              WHERE run_id = ?1
                AND trace_id IS NOT NULL
                AND trace_span_id IS NOT NULL
+               AND (?2 IS NULL OR tenant_id = ?2)
              ORDER BY attempt_no DESC, attempt_id DESC
              LIMIT 1",
-            params![run_id],
+            params![run_id, self.tenant_id()],
             |row| {
                 Ok(AttemptTraceContextRow {
                     trace_id: row.get(0)?,
@@ -446,7 +549,7 @@ impl SqliteRuntimeRepository {
             },
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("latest attempt trace for run: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("latest attempt trace for run: {}", e)))
     }
 
     pub fn latest_attempt_id_for_run(&self, run_id: &str) -> Result<Option<String>, KernelError> {
@@ -457,14 +560,14 @@ impl SqliteRuntimeRepository {
         conn.query_row(
             "SELECT attempt_id
              FROM runtime_attempts
-             WHERE run_id = ?1
+             WHERE run_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)
              ORDER BY attempt_no DESC, attempt_id DESC
              LIMIT 1",
-            params![run_id],
+            params![run_id, self.tenant_id()],
             |row| row.get(0),
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("latest attempt id for run: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("latest attempt id for run: {}", e)))
     }
 
     pub fn advance_attempt_trace(
@@ -504,9 +607,9 @@ impl SqliteRuntimeRepository {
                 "UPDATE runtime_attempts SET started_at_ms = ?2 WHERE attempt_id = ?1",
                 params![attempt_id, started_at.map(dt_to_ms)],
             )
-            .map_err(|e| KernelError::Driver(format!("set attempt started_at: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set attempt started_at: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for started_at update: {}",
                 attempt_id
             )));
@@ -524,16 +627,16 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let mut stmt = conn
             .prepare(
-                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version
-                 FROM runtime_leases WHERE attempt_id = ?1",
+                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id
+                 FROM runtime_leases WHERE attempt_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get lease by attempt: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get lease by attempt: {}", e)))?;
         let mut rows = stmt
-            .query(params![attempt_id])
-            .map_err(|e| KernelError::Driver(format!("query get lease by attempt: {}", e)))?;
+            .query(params![attempt_id, self.tenant_id()])
+            .map_err(|e| KernelError::Storage(format!("query get lease by attempt: {}", e)))?;
         if let Some(row) = rows
             .next()
-            .map_err(|e| KernelError::Driver(format!("scan get lease by attempt: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("scan get lease by attempt: {}", e)))?
         {
             Ok(Some(LeaseRecord {
                 lease_id: row.get(0).map_err(map_rusqlite_err)?,
@@ -544,6 +647,7 @@ impl SqliteRuntimeRepository {
                 version: row.get::<_, i64>(5).map_err(map_rusqlite_err)? as u64,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: row.get(6).map_err(map_rusqlite_err)?,
             }))
         } else {
             Ok(None)
@@ -557,16 +661,16 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let mut stmt = conn
             .prepare(
-                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version
-                 FROM runtime_leases WHERE lease_id = ?1",
+                "SELECT lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id
+                 FROM runtime_leases WHERE lease_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get lease by id: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get lease by id: {}", e)))?;
         let mut rows = stmt
-            .query(params![lease_id])
-            .map_err(|e| KernelError::Driver(format!("query get lease by id: {}", e)))?;
+            .query(params![lease_id, self.tenant_id()])
+            .map_err(|e| KernelError::Storage(format!("query get lease by id: {}", e)))?;
         if let Some(row) = rows
             .next()
-            .map_err(|e| KernelError::Driver(format!("scan get lease by id: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("scan get lease by id: {}", e)))?
         {
             Ok(Some(LeaseRecord {
                 lease_id: row.get(0).map_err(map_rusqlite_err)?,
@@ -577,6 +681,7 @@ impl SqliteRuntimeRepository {
                 version: row.get::<_, i64>(5).map_err(map_rusqlite_err)? as u64,
                 terminal_state: None,
                 terminal_at: None,
+                tenant_id: row.get(6).map_err(map_rusqlite_err)?,
             }))
         } else {
             Ok(None)
@@ -594,11 +699,13 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let count: i64 = conn
             .query_row(
-                "SELECT COUNT(*) FROM runtime_leases WHERE worker_id = ?1 AND lease_expires_at_ms >= ?2",
-                params![worker_id, dt_to_ms(now)],
+                "SELECT COUNT(*) FROM runtime_leases
+                 WHERE worker_id = ?1 AND lease_expires_at_ms >= ?2
+                   AND (?3 IS NULL OR tenant_id = ?3)",
+                params![worker_id, dt_to_ms(now), self.tenant_id()],
                 |r| r.get(0),
             )
-            .map_err(|e| KernelError::Driver(format!("count active leases: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("count active leases: {}", e)))?;
         Ok(count as usize)
     }
 
@@ -615,13 +722,12 @@ impl SqliteRuntimeRepository {
             .query_row(
                 "SELECT COUNT(*)
                  FROM runtime_leases l
-                 JOIN runtime_attempts a ON a.attempt_id = l.attempt_id
-                 WHERE a.tenant_id = ?1
+                 WHERE l.tenant_id = ?1
                    AND l.lease_expires_at_ms >= ?2",
                 params![tenant_id, dt_to_ms(now)],
                 |r| r.get(0),
             )
-            .map_err(|e| KernelError::Driver(format!("count tenant active leases: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("count tenant active leases: {}", e)))?;
         Ok(count as usize)
     }
 
@@ -639,11 +745,13 @@ impl SqliteRuntimeRepository {
                    AND (
                      a.status = 'queued'
                      OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1))
-                   )",
-                params![dt_to_ms(now)],
+                   )
+                   AND (a.run_at_ms IS NULL OR a.run_at_ms <= ?1)
+                   AND (?2 IS NULL OR a.tenant_id = ?2)",
+                params![dt_to_ms(now), self.tenant_id()],
                 |r| r.get(0),
             )
-            .map_err(|e| KernelError::Driver(format!("queue depth: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("queue depth: {}", e)))?;
         Ok(count as usize)
     }
 
@@ -656,7 +764,7 @@ impl SqliteRuntimeRepository {
             .query_row("SELECT COUNT(*) FROM runtime_a2a_compat_tasks", [], |r| {
                 r.get(0)
             })
-            .map_err(|e| KernelError::Driver(format!("a2a compat queue depth: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("a2a compat queue depth: {}", e)))?;
         Ok(count as usize)
     }
 
@@ -679,12 +787,14 @@ impl SqliteRuntimeRepository {
                      a.status = 'queued'
                      OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1))
                    )
-                 ORDER BY a.priority DESC, a.attempt_no ASC, a.attempt_id ASC
+                   AND (a.run_at_ms IS NULL OR a.run_at_ms <= ?1)
+                   AND (?3 IS NULL OR a.tenant_id = ?3)
+                 ORDER BY a.priority DESC, a.enqueued_at_ms ASC NULLS FIRST, a.attempt_no ASC, a.attempt_id ASC
                  LIMIT ?2",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare dispatchable contexts: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare dispatchable contexts: {}", e)))?;
         let rows = stmt
-            .query_map(params![dt_to_ms(now), limit as i64], |row| {
+            .query_map(params![dt_to_ms(now), limit as i64, self.tenant_id()], |row| {
                 let started_at_ms: Option<i64> = row.get(2)?;
                 Ok(DispatchableAttemptContext {
                     attempt_id: row.get(0)?,
@@ -692,7 +802,7 @@ impl SqliteRuntimeRepository {
                     started_at: started_at_ms.map(ms_to_dt),
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("query dispatchable contexts: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query dispatchable contexts: {}", e)))?;
         let mut out = Vec::new();
         for row in rows {
             out.push(row.map_err(map_rusqlite_err)?);
@@ -700,39 +810,147 @@ impl SqliteRuntimeRepository {
         Ok(out)
     }
 
-    pub fn heartbeat_lease_with_version(
+    fn record_attempt_failure_under_lease(
         &self,
-        lease_id: &str,
-        worker_id: &str,
-        expected_version: u64,
-        heartbeat_at: DateTime<Utc>,
-        lease_expires_at: DateTime<Utc>,
-    ) -> Result<(), KernelError> {
-        let conn = self
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        let mut conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
-        let updated = conn
-            .execute(
-                "UPDATE runtime_leases
-                 SET heartbeat_at_ms = ?4, lease_expires_at_ms = ?5, version = version + 1
-                 WHERE lease_id = ?1 AND worker_id = ?2 AND version = ?3",
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin record failure tx: {}", e)))?;
+        check_attempt_tenant(&tx, attempt_id, self.tenant_id())?;
+        if let Some(lease) = lease {
+            check_lease_fence(&tx, attempt_id, lease, now)?;
+        }
+        let Some((run_id, attempt_no, cancel_requested_at_ms)) = tx
+            .query_row(
+                "SELECT run_id, attempt_no, cancel_requested_at_ms
+                 FROM runtime_attempts
+                 WHERE attempt_id = ?1",
+                params![attempt_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| KernelError::Storage(format!("read attempt for failure: {}", e)))?
+        else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for failure: {}",
+                attempt_id
+            )));
+        };
+        let attempt_no = attempt_no.max(1) as u32;
+
+        tx.execute(
+            "DELETE FROM runtime_leases WHERE attempt_id = ?1",
+            params![attempt_id],
+        )
+        .map_err(|e| KernelError::Storage(format!("delete attempt lease on failure: {}", e)))?;
+
+        let outcome = if cancel_requested_at_ms.is_some() {
+            // Cancelled while running: the failure is most likely the cancellation itself
+            tx.execute(
+                "UPDATE runtime_attempts
+                 SET status = 'cancelled',
+                     retry_at_ms = NULL,
+                     started_at_ms = NULL,
+                     last_error = ?2
+                 WHERE attempt_id = ?1",
+                params![attempt_id, error],
+            )
+            .map_err(|e| KernelError::Storage(format!("cancel failed attempt: {}", e)))?;
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::Cancelled,
+                next_retry_at: None,
+                next_attempt_no: attempt_no,
+            }
+        } else if attempt_no <= retry_policy.max_retries {
+            let next_attempt_no = attempt_no + 1;
+            let backoff_ms = retry_policy.next_backoff_ms(attempt_no).max(1);
+            let retry_at = now + Duration::milliseconds(backoff_ms);
+            tx.execute(
+                "UPDATE runtime_attempts
+                 SET attempt_no = ?2,
+                     status = 'retry_backoff',
+                     retry_at_ms = ?3,
+                     started_at_ms = NULL,
+                     last_error = ?4
+                 WHERE attempt_id = ?1",
                 params![
-                    lease_id,
-                    worker_id,
-                    expected_version as i64,
-                    dt_to_ms(heartbeat_at),
-                    dt_to_ms(lease_expires_at)
+                    attempt_id,
+                    next_attempt_no as i64,
+                    dt_to_ms(retry_at),
+                    error
                 ],
             )
-            .map_err(|e| KernelError::Driver(format!("heartbeat lease with version: {}", e)))?;
-        if updated == 0 {
-            return Err(KernelError::Driver(format!(
-                "lease heartbeat version conflict for lease: {}",
-                lease_id
-            )));
-        }
-        Ok(())
+            .map_err(|e| KernelError::Storage(format!("schedule attempt retry: {}", e)))?;
+            tx.execute(
+                "INSERT INTO runtime_attempt_retry_history
+                 (attempt_id, attempt_no, strategy, backoff_ms, max_retries, scheduled_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    attempt_id,
+                    next_attempt_no as i64,
+                    retry_policy.strategy.as_str(),
+                    backoff_ms,
+                    retry_policy.max_retries as i64,
+                    dt_to_ms(retry_at)
+                ],
+            )
+            .map_err(|e| KernelError::Storage(format!("insert retry history: {}", e)))?;
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::RetryBackoff,
+                next_retry_at: Some(retry_at),
+                next_attempt_no,
+            }
+        } else {
+            tx.execute(
+                "UPDATE runtime_attempts
+                 SET status = 'dead_letter',
+                     retry_at_ms = NULL,
+                     started_at_ms = NULL,
+                     last_error = ?2,
+                     dead_lettered_at_ms = ?3
+                 WHERE attempt_id = ?1",
+                params![attempt_id, error, dt_to_ms(now)],
+            )
+            .map_err(|e| KernelError::Storage(format!("dead-letter attempt: {}", e)))?;
+            // Also listed (and replayable) through the dead letter queue
+            tx.execute(
+                "INSERT INTO runtime_dead_letters
+                 (attempt_id, run_id, attempt_no, terminal_status, reason, dead_at_ms, replay_status, replay_count, last_replayed_at_ms)
+                 VALUES (?1, ?2, ?3, 'dead_letter', ?4, ?5, 'pending', 0, NULL)
+                 ON CONFLICT(attempt_id) DO UPDATE SET
+                   run_id = excluded.run_id,
+                   attempt_no = excluded.attempt_no,
+                   terminal_status = excluded.terminal_status,
+                   reason = excluded.reason,
+                   dead_at_ms = excluded.dead_at_ms,
+                   replay_status = 'pending'",
+                params![attempt_id, run_id, attempt_no as i64, error, dt_to_ms(now)],
+            )
+            .map_err(|e| KernelError::Storage(format!("upsert dead letter from failure: {}", e)))?;
+            AttemptAckOutcome {
+                status: AttemptExecutionStatus::DeadLetter,
+                next_retry_at: None,
+                next_attempt_no: attempt_no,
+            }
+        };
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit record failure: {}", e)))?;
+        Ok(outcome)
     }
 
     pub fn mark_attempt_status(
@@ -745,6 +963,7 @@ impl SqliteRuntimeRepository {
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_attempt_tenant(&conn, attempt_id, self.tenant_id())?;
         conn.execute(
             "UPDATE runtime_attempts
              SET status = ?2,
@@ -759,7 +978,7 @@ impl SqliteRuntimeRepository {
              WHERE attempt_id = ?1",
             params![attempt_id, status_str],
         )
-        .map_err(|e| KernelError::Driver(format!("mark attempt status: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("mark attempt status: {}", e)))?;
         Ok(())
     }
 
@@ -769,6 +988,17 @@ impl SqliteRuntimeRepository {
         status: AttemptExecutionStatus,
         retry_policy: Option<&RetryPolicyConfig>,
         now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.ack_attempt_under_lease(attempt_id, None, status, retry_policy, now)
+    }
+
+    fn ack_attempt_under_lease(
+        &self,
+        attempt_id: &str,
+        lease: Option<&LeaseFence>,
+        status: AttemptExecutionStatus,
+        retry_policy: Option<&RetryPolicyConfig>,
+        now: DateTime<Utc>,
     ) -> Result<AttemptAckOutcome, KernelError> {
         let mut conn = self
             .conn
@@ -776,11 +1006,15 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin ack attempt tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin ack attempt tx: {}", e)))?;
+        check_attempt_tenant(&tx, attempt_id, self.tenant_id())?;
+        if let Some(lease) = lease {
+            check_lease_fence(&tx, attempt_id, lease, now)?;
+        }
 
         let attempt_row = tx
             .query_row(
-                "SELECT run_id, attempt_no, status, retry_strategy, retry_backoff_ms, retry_max_backoff_ms, retry_multiplier, retry_max_retries
+                "SELECT run_id, attempt_no, status, retry_strategy, retry_backoff_ms, retry_max_backoff_ms, retry_multiplier, retry_max_retries, cancel_requested_at_ms
                  FROM runtime_attempts
                  WHERE attempt_id = ?1",
                 params![attempt_id],
@@ -794,11 +1028,12 @@ impl SqliteRuntimeRepository {
                         row.get::<_, Option<i64>>(5)?,
                         row.get::<_, Option<f64>>(6)?,
                         row.get::<_, Option<i64>>(7)?,
+                        row.get::<_, Option<i64>>(8)?,
                     ))
                 },
             )
             .optional()
-            .map_err(|e| KernelError::Driver(format!("read attempt for ack: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("read attempt for ack: {}", e)))?;
 
         let Some((
             run_id,
@@ -809,13 +1044,21 @@ impl SqliteRuntimeRepository {
             stored_max_backoff_ms,
             stored_multiplier,
             stored_max_retries,
+            cancel_requested_at_ms,
         )) = attempt_row
         else {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for ack: {}",
                 attempt_id
             )));
         };
+        // A failure of an attempt asked to cancel ends it as cancelled, without a retry
+        let status = if status == AttemptExecutionStatus::Failed && cancel_requested_at_ms.is_some()
+        {
+            AttemptExecutionStatus::Cancelled
+        } else {
+            status
+        };
 
         if let Some(policy) = retry_policy {
             tx.execute(
@@ -835,14 +1078,14 @@ impl SqliteRuntimeRepository {
                     policy.max_retries as i64
                 ],
             )
-            .map_err(|e| KernelError::Driver(format!("persist retry policy: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("persist retry policy: {}", e)))?;
         }
 
         tx.execute(
             "DELETE FROM runtime_leases WHERE attempt_id = ?1",
             params![attempt_id],
         )
-        .map_err(|e| KernelError::Driver(format!("delete attempt lease on ack: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("delete attempt lease on ack: {}", e)))?;
 
         if status == AttemptExecutionStatus::Failed {
             let effective_policy = retry_policy.cloned().or_else(|| {
@@ -870,7 +1113,7 @@ impl SqliteRuntimeRepository {
                          WHERE attempt_id = ?1",
                         params![attempt_id, next_attempt_no as i64, dt_to_ms(scheduled_at)],
                     )
-                    .map_err(|e| KernelError::Driver(format!("schedule retry backoff: {}", e)))?;
+                    .map_err(|e| KernelError::Storage(format!("schedule retry backoff: {}", e)))?;
                     tx.execute(
                         "INSERT INTO runtime_attempt_retry_history
                          (attempt_id, attempt_no, strategy, backoff_ms, max_retries, scheduled_at_ms)
@@ -884,9 +1127,9 @@ impl SqliteRuntimeRepository {
                             dt_to_ms(scheduled_at)
                         ],
                     )
-                    .map_err(|e| KernelError::Driver(format!("insert retry history: {}", e)))?;
+                    .map_err(|e| KernelError::Storage(format!("insert retry history: {}", e)))?;
                     tx.commit()
-                        .map_err(|e| KernelError::Driver(format!("commit retry ack: {}", e)))?;
+                        .map_err(|e| KernelError::Storage(format!("commit retry ack: {}", e)))?;
                     return Ok(AttemptAckOutcome {
                         status: AttemptExecutionStatus::RetryBackoff,
                         next_retry_at: Some(scheduled_at),
@@ -904,7 +1147,7 @@ impl SqliteRuntimeRepository {
              WHERE attempt_id = ?1",
             params![attempt_id, attempt_status_to_str(&status)],
         )
-        .map_err(|e| KernelError::Driver(format!("mark terminal attempt status: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("mark terminal attempt status: {}", e)))?;
         if status == AttemptExecutionStatus::Failed {
             tx.execute(
                 "INSERT INTO runtime_dead_letters
@@ -925,10 +1168,10 @@ impl SqliteRuntimeRepository {
                     dt_to_ms(now)
                 ],
             )
-            .map_err(|e| KernelError::Driver(format!("upsert dead letter from ack: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("upsert dead letter from ack: {}", e)))?;
         }
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit terminal ack: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit terminal ack: {}", e)))?;
         Ok(AttemptAckOutcome {
             status: status.clone(),
             next_retry_at: None,
@@ -946,12 +1189,13 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let attempt = conn
             .query_row(
-                "SELECT attempt_no, status FROM runtime_attempts WHERE attempt_id = ?1",
-                params![attempt_id],
+                "SELECT attempt_no, status FROM runtime_attempts
+                 WHERE attempt_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+                params![attempt_id, self.tenant_id()],
                 |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
             )
             .optional()
-            .map_err(|e| KernelError::Driver(format!("read retry history attempt: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("read retry history attempt: {}", e)))?;
         let Some((current_attempt_no, status)) = attempt else {
             return Ok(None);
         };
@@ -963,7 +1207,7 @@ impl SqliteRuntimeRepository {
                  WHERE attempt_id = ?1
                  ORDER BY retry_id ASC",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare retry history: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare retry history: {}", e)))?;
         let rows = stmt
             .query_map(params![attempt_id], |row| {
                 Ok(AttemptRetryHistoryRow {
@@ -974,7 +1218,7 @@ impl SqliteRuntimeRepository {
                     scheduled_at: ms_to_dt(row.get::<_, i64>(4)?),
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("query retry history: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query retry history: {}", e)))?;
         let mut history = Vec::new();
         for row in rows {
             history.push(row.map_err(map_rusqlite_err)?);
@@ -1004,13 +1248,17 @@ impl SqliteRuntimeRepository {
                     "SELECT attempt_id, run_id, attempt_no, terminal_status, reason, dead_at_ms, replay_status, replay_count, last_replayed_at_ms
                      FROM runtime_dead_letters
                      WHERE replay_status = ?1
+                       AND (?3 IS NULL OR attempt_id IN (SELECT attempt_id FROM runtime_attempts WHERE tenant_id = ?3))
                      ORDER BY dead_at_ms DESC
                      LIMIT ?2",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list dead letters: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list dead letters: {}", e)))?;
             let rows = stmt
-                .query_map(params![status, limit as i64], map_row_to_dead_letter)
-                .map_err(|e| KernelError::Driver(format!("query list dead letters: {}", e)))?;
+                .query_map(
+                    params![status, limit as i64, self.tenant_id()],
+                    map_row_to_dead_letter,
+                )
+                .map_err(|e| KernelError::Storage(format!("query list dead letters: {}", e)))?;
             for row in rows {
                 out.push(row.map_err(map_rusqlite_err)?);
             }
@@ -1019,13 +1267,14 @@ impl SqliteRuntimeRepository {
                 .prepare(
                     "SELECT attempt_id, run_id, attempt_no, terminal_status, reason, dead_at_ms, replay_status, replay_count, last_replayed_at_ms
                      FROM runtime_dead_letters
+                     WHERE ?2 IS NULL OR attempt_id IN (SELECT attempt_id FROM runtime_attempts WHERE tenant_id = ?2)
                      ORDER BY dead_at_ms DESC
                      LIMIT ?1",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list dead letters: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list dead letters: {}", e)))?;
             let rows = stmt
-                .query_map(params![limit as i64], map_row_to_dead_letter)
-                .map_err(|e| KernelError::Driver(format!("query list dead letters: {}", e)))?;
+                .query_map(params![limit as i64, self.tenant_id()], map_row_to_dead_letter)
+                .map_err(|e| KernelError::Storage(format!("query list dead letters: {}", e)))?;
             for row in rows {
                 out.push(row.map_err(map_rusqlite_err)?);
             }
@@ -1041,12 +1290,13 @@ impl SqliteRuntimeRepository {
         conn.query_row(
             "SELECT attempt_id, run_id, attempt_no, terminal_status, reason, dead_at_ms, replay_status, replay_count, last_replayed_at_ms
              FROM runtime_dead_letters
-             WHERE attempt_id = ?1",
-            params![attempt_id],
+             WHERE attempt_id = ?1
+               AND (?2 IS NULL OR attempt_id IN (SELECT attempt_id FROM runtime_attempts WHERE tenant_id = ?2))",
+            params![attempt_id, self.tenant_id()],
             map_row_to_dead_letter,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get dead letter: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get dead letter: {}", e)))
     }
 
     pub fn replay_dead_letter(
@@ -1060,7 +1310,8 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin replay dead letter tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin replay dead letter tx: {}", e)))?;
+        check_attempt_tenant(&tx, attempt_id, self.tenant_id())?;
         let Some(mut row) = tx
             .query_row(
                 "SELECT attempt_id, run_id, attempt_no, terminal_status, reason, dead_at_ms, replay_status, replay_count, last_replayed_at_ms
@@ -1070,16 +1321,16 @@ impl SqliteRuntimeRepository {
                 map_row_to_dead_letter,
             )
             .optional()
-            .map_err(|e| KernelError::Driver(format!("read dead letter for replay: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("read dead letter for replay: {}", e)))?
         else {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "dead letter not found for attempt: {}",
                 attempt_id
             )));
         };
 
         if row.replay_status != "pending" {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::Conflict(format!(
                 "dead letter already replayed for attempt: {}",
                 attempt_id
             )));
@@ -1089,7 +1340,7 @@ impl SqliteRuntimeRepository {
             "DELETE FROM runtime_leases WHERE attempt_id = ?1",
             params![attempt_id],
         )
-        .map_err(|e| KernelError::Driver(format!("delete lease before dlq replay: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("delete lease before dlq replay: {}", e)))?;
         let updated = tx
             .execute(
                 "UPDATE runtime_attempts
@@ -1099,9 +1350,9 @@ impl SqliteRuntimeRepository {
                  WHERE attempt_id = ?1",
                 params![attempt_id],
             )
-            .map_err(|e| KernelError::Driver(format!("requeue dead letter attempt: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("requeue dead letter attempt: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "attempt not found for dead letter replay: {}",
                 attempt_id
             )));
@@ -1114,9 +1365,9 @@ impl SqliteRuntimeRepository {
              WHERE attempt_id = ?1",
             params![attempt_id, dt_to_ms(now)],
         )
-        .map_err(|e| KernelError::Driver(format!("mark dead letter replayed: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("mark dead letter replayed: {}", e)))?;
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit dead letter replay: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit dead letter replay: {}", e)))?;
 
         row.replay_status = "replayed".to_string();
         row.replay_count += 1;
@@ -1137,7 +1388,7 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin replay effect tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin replay effect tx: {}", e)))?;
 
         let existing = tx
             .query_row(
@@ -1148,12 +1399,12 @@ impl SqliteRuntimeRepository {
                 |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
             )
             .optional()
-            .map_err(|e| KernelError::Driver(format!("read replay effect: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("read replay effect: {}", e)))?;
 
         let claim = match existing {
             Some((status, response_json)) if status == "completed" => {
                 let stored = response_json.ok_or_else(|| {
-                    KernelError::Driver("missing stored replay response".to_string())
+                    KernelError::Storage("missing stored replay response".to_string())
                 })?;
                 ReplayEffectClaim::Completed(stored)
             }
@@ -1166,13 +1417,13 @@ impl SqliteRuntimeRepository {
                      VALUES (?1, ?2, ?3, 'job_replay', 'in_progress', 1, ?4, NULL, NULL)",
                     params![fingerprint, thread_id, replay_target, dt_to_ms(now)],
                 )
-                .map_err(|e| KernelError::Driver(format!("insert replay effect: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("insert replay effect: {}", e)))?;
                 ReplayEffectClaim::Acquired
             }
         };
 
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit replay effect tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit replay effect tx: {}", e)))?;
         Ok(claim)
     }
 
@@ -1196,9 +1447,9 @@ impl SqliteRuntimeRepository {
                    AND status = 'in_progress'",
                 params![fingerprint, dt_to_ms(now), response_json],
             )
-            .map_err(|e| KernelError::Driver(format!("complete replay effect: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("complete replay effect: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::Conflict(format!(
                 "replay effect not claimable for completion: {}",
                 fingerprint
             )));
@@ -1217,7 +1468,7 @@ impl SqliteRuntimeRepository {
                AND status = 'in_progress'",
             params![fingerprint],
         )
-        .map_err(|e| KernelError::Driver(format!("abandon replay effect: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("abandon replay effect: {}", e)))?;
         Ok(())
     }
 
@@ -1236,84 +1487,13 @@ impl SqliteRuntimeRepository {
                  WHERE thread_id = ?1
                  ORDER BY created_at_ms ASC",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare list replay effects: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list replay effects: {}", e)))?;
         let rows = stmt
             .query_map(params![thread_id], map_row_to_replay_effect_log)
-            .map_err(|e| KernelError::Driver(format!("query replay effects: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query replay effects: {}", e)))?;
         let mut out = Vec::new();
         for row in rows {
-            out.push(row.map_err(|e| KernelError::Driver(format!("row replay effects: {}", e)))?);
-        }
-        Ok(out)
-    }
-
-    pub fn upsert_job(&self, thread_id: &str, status: &str) -> Result<(), KernelError> {
-        let now = dt_to_ms(Utc::now());
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
-        conn.execute(
-            "INSERT INTO runtime_jobs (thread_id, status, created_at_ms, updated_at_ms)
-             VALUES (?1, ?2, ?3, ?3)
-             ON CONFLICT(thread_id) DO UPDATE SET status = ?2, updated_at_ms = ?3",
-            params![thread_id, status, now],
-        )
-        .map_err(|e| KernelError::Driver(format!("upsert job: {}", e)))?;
-        Ok(())
-    }
-
-    pub fn list_runs(
-        &self,
-        limit: usize,
-        offset: usize,
-        status_filter: Option<&str>,
-    ) -> Result<Vec<(String, String, DateTime<Utc>)>, KernelError> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
-        let limit_i = limit as i64;
-        let offset_i = offset as i64;
-        let mut out = Vec::new();
-        if let Some(s) = status_filter {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT thread_id, status, updated_at_ms FROM runtime_jobs WHERE status = ?1 ORDER BY updated_at_ms DESC LIMIT ?2 OFFSET ?3",
-                )
-                .map_err(|e| KernelError::Driver(format!("prepare list_runs: {}", e)))?;
-            let rows = stmt
-                .query_map(params![s, limit_i, offset_i], |row| {
-                    let ms: i64 = row.get(2)?;
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, String>(1)?,
-                        ms_to_dt(ms),
-                    ))
-                })
-                .map_err(|e| KernelError::Driver(format!("query list_runs: {}", e)))?;
-            for item in rows {
-                out.push(item.map_err(map_rusqlite_err)?);
-            }
-        } else {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT thread_id, status, updated_at_ms FROM runtime_jobs ORDER BY updated_at_ms DESC LIMIT ?1 OFFSET ?2",
-                )
-                .map_err(|e| KernelError::Driver(format!("prepare list_runs: {}", e)))?;
-            let rows = stmt
-                .query_map(params![limit_i, offset_i], |row| {
-                    let ms: i64 = row.get(2)?;
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, String>(1)?,
-                        ms_to_dt(ms),
-                    ))
-                })
-                .map_err(|e| KernelError::Driver(format!("query list_runs: {}", e)))?;
-            for item in rows {
-                out.push(item.map_err(map_rusqlite_err)?);
-            }
+            out.push(row.map_err(|e| KernelError::Storage(format!("row replay effects: {}", e)))?);
         }
         Ok(out)
     }
@@ -1331,12 +1511,13 @@ impl SqliteRuntimeRepository {
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_run_tenant(&conn, run_id, self.tenant_id())?;
         conn.execute(
             "INSERT INTO runtime_interrupts (interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms)
              VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6)",
             params![interrupt_id, thread_id, run_id, attempt_id, value_json, now],
         )
-        .map_err(|e| KernelError::Driver(format!("insert interrupt: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("insert interrupt: {}", e)))?;
         Ok(())
     }
 
@@ -1351,17 +1532,20 @@ impl SqliteRuntimeRepository {
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let limit_i = limit as i64;
+        let tenant = self.tenant_id();
         let mut out = Vec::new();
         if let (Some(s), Some(r)) = (status_filter, run_id_filter) {
             let mut stmt = conn
                 .prepare(
                     "SELECT interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms, resume_payload_hash, resume_response_json
-                     FROM runtime_interrupts WHERE status = ?1 AND run_id = ?2 ORDER BY created_at_ms DESC LIMIT ?3",
+                     FROM runtime_interrupts WHERE status = ?1 AND run_id = ?2
+                       AND (?4 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?4))
+                     ORDER BY created_at_ms DESC LIMIT ?3",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list_interrupts: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list_interrupts: {}", e)))?;
             let rows = stmt
-                .query_map(params![s, r, limit_i], map_row_to_interrupt)
-                .map_err(|e| KernelError::Driver(format!("query list_interrupts: {}", e)))?;
+                .query_map(params![s, r, limit_i, tenant], map_row_to_interrupt)
+                .map_err(|e| KernelError::Storage(format!("query list_interrupts: {}", e)))?;
             for item in rows {
                 out.push(item.map_err(map_rusqlite_err)?);
             }
@@ -1369,12 +1553,14 @@ impl SqliteRuntimeRepository {
             let mut stmt = conn
                 .prepare(
                     "SELECT interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms, resume_payload_hash, resume_response_json
-                     FROM runtime_interrupts WHERE status = ?1 ORDER BY created_at_ms DESC LIMIT ?2",
+                     FROM runtime_interrupts WHERE status = ?1
+                       AND (?3 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?3))
+                     ORDER BY created_at_ms DESC LIMIT ?2",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list_interrupts: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list_interrupts: {}", e)))?;
             let rows = stmt
-                .query_map(params![s, limit_i], map_row_to_interrupt)
-                .map_err(|e| KernelError::Driver(format!("query list_interrupts: {}", e)))?;
+                .query_map(params![s, limit_i, tenant], map_row_to_interrupt)
+                .map_err(|e| KernelError::Storage(format!("query list_interrupts: {}", e)))?;
             for item in rows {
                 out.push(item.map_err(map_rusqlite_err)?);
             }
@@ -1382,12 +1568,14 @@ impl SqliteRuntimeRepository {
             let mut stmt = conn
                 .prepare(
                     "SELECT interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms, resume_payload_hash, resume_response_json
-                     FROM runtime_interrupts WHERE run_id = ?1 ORDER BY created_at_ms DESC LIMIT ?2",
+                     FROM runtime_interrupts WHERE run_id = ?1
+                       AND (?3 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?3))
+                     ORDER BY created_at_ms DESC LIMIT ?2",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list_interrupts: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list_interrupts: {}", e)))?;
             let rows = stmt
-                .query_map(params![r, limit_i], map_row_to_interrupt)
-                .map_err(|e| KernelError::Driver(format!("query list_interrupts: {}", e)))?;
+                .query_map(params![r, limit_i, tenant], map_row_to_interrupt)
+                .map_err(|e| KernelError::Storage(format!("query list_interrupts: {}", e)))?;
             for item in rows {
                 out.push(item.map_err(map_rusqlite_err)?);
             }
@@ -1395,12 +1583,14 @@ impl SqliteRuntimeRepository {
             let mut stmt = conn
                 .prepare(
                     "SELECT interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms, resume_payload_hash, resume_response_json
-                     FROM runtime_interrupts ORDER BY created_at_ms DESC LIMIT ?1",
+                     FROM runtime_interrupts
+                     WHERE ?2 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?2)
+                     ORDER BY created_at_ms DESC LIMIT ?1",
                 )
-                .map_err(|e| KernelError::Driver(format!("prepare list_interrupts: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("prepare list_interrupts: {}", e)))?;
             let rows = stmt
-                .query_map(params![limit_i], map_row_to_interrupt)
-                .map_err(|e| KernelError::Driver(format!("query list_interrupts: {}", e)))?;
+                .query_map(params![limit_i, tenant], map_row_to_interrupt)
+                .map_err(|e| KernelError::Storage(format!("query list_interrupts: {}", e)))?;
             for item in rows {
                 out.push(item.map_err(map_rusqlite_err)?);
             }
@@ -1416,15 +1606,16 @@ impl SqliteRuntimeRepository {
         let mut stmt = conn
             .prepare(
                 "SELECT interrupt_id, thread_id, run_id, attempt_id, value_json, status, created_at_ms, resume_payload_hash, resume_response_json
-                 FROM runtime_interrupts WHERE interrupt_id = ?1",
+                 FROM runtime_interrupts
+                 WHERE interrupt_id = ?1 AND (?2 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?2))",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get_interrupt: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get_interrupt: {}", e)))?;
         let mut rows = stmt
-            .query(params![interrupt_id])
-            .map_err(|e| KernelError::Driver(format!("query get_interrupt: {}", e)))?;
+            .query(params![interrupt_id, self.tenant_id()])
+            .map_err(|e| KernelError::Storage(format!("query get_interrupt: {}", e)))?;
         if let Some(row) = rows
             .next()
-            .map_err(|e| KernelError::Driver(format!("scan get_interrupt: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("scan get_interrupt: {}", e)))?
         {
             Ok(Some(map_row_to_interrupt(&row).map_err(map_rusqlite_err)?))
         } else {
@@ -1443,12 +1634,13 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let updated = conn
             .execute(
-                "UPDATE runtime_interrupts SET status = ?2 WHERE interrupt_id = ?1",
-                params![interrupt_id, status],
+                "UPDATE runtime_interrupts SET status = ?2
+                 WHERE interrupt_id = ?1 AND (?3 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?3))",
+                params![interrupt_id, status, self.tenant_id()],
             )
-            .map_err(|e| KernelError::Driver(format!("update interrupt status: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("update interrupt status: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "interrupt not found: {}",
                 interrupt_id
             )));
@@ -1456,12 +1648,36 @@ impl SqliteRuntimeRepository {
         Ok(())
     }
 
-    pub fn persist_interrupt_resume_result(
-        &self,
-        interrupt_id: &str,
-        resume_payload_hash: &str,
-        resume_response_json: &str,
-    ) -> Result<(), KernelError> {
+    /// Moves an interrupt back to `pending`, clearing the decision recorded when it was
+    /// resolved, so it can be answered again.
+    pub fn reopen_interrupt(&self, interrupt_id: &str) -> Result<(), KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let updated = conn
+            .execute(
+                "UPDATE runtime_interrupts
+                 SET status = 'pending', decision = NULL, resolution_json = NULL, resolved_at_ms = NULL
+                 WHERE interrupt_id = ?1 AND (?2 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?2))",
+                params![interrupt_id, self.tenant_id()],
+            )
+            .map_err(|e| KernelError::Storage(format!("reopen interrupt: {}", e)))?;
+        if updated == 0 {
+            return Err(KernelError::NotFound(format!(
+                "interrupt not found: {}",
+                interrupt_id
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn persist_interrupt_resume_result(
+        &self,
+        interrupt_id: &str,
+        resume_payload_hash: &str,
+        resume_response_json: &str,
+    ) -> Result<(), KernelError> {
         let now = dt_to_ms(Utc::now());
         let mut conn = self
             .conn
@@ -1469,17 +1685,20 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin resume result tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin resume result tx: {}", e)))?;
         let existing: Option<String> = tx
             .query_row(
-                "SELECT resume_payload_hash FROM runtime_interrupts WHERE interrupt_id = ?1",
-                params![interrupt_id],
+                "SELECT resume_payload_hash FROM runtime_interrupts
+                 WHERE interrupt_id = ?1 AND (?2 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?2))",
+                params![interrupt_id, self.tenant_id()],
                 |r| r.get(0),
             )
-            .map_err(map_rusqlite_err)?;
+            .optional()
+            .map_err(map_rusqlite_err)?
+            .flatten();
         if let Some(hash) = existing {
             if hash != resume_payload_hash {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::Conflict(format!(
                     "interrupt {} already resumed with a different payload",
                     interrupt_id
                 )));
@@ -1492,18 +1711,25 @@ impl SqliteRuntimeRepository {
                      resume_payload_hash = COALESCE(resume_payload_hash, ?2),
                      resume_response_json = COALESCE(resume_response_json, ?3),
                      resumed_at_ms = COALESCE(resumed_at_ms, ?4)
-                 WHERE interrupt_id = ?1",
-                params![interrupt_id, resume_payload_hash, resume_response_json, now],
+                 WHERE interrupt_id = ?1
+                   AND (?5 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?5))",
+                params![
+                    interrupt_id,
+                    resume_payload_hash,
+                    resume_response_json,
+                    now,
+                    self.tenant_id()
+                ],
             )
-            .map_err(|e| KernelError::Driver(format!("persist interrupt resume result: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("persist interrupt resume result: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "interrupt not found: {}",
                 interrupt_id
             )));
         }
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit resume result tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit resume result tx: {}", e)))?;
         Ok(())
     }
 
@@ -1542,13 +1768,13 @@ impl SqliteRuntimeRepository {
                 if existing.0 == action_id && existing.1 == status {
                     Ok(StepReportWriteResult::Duplicate)
                 } else {
-                    Err(KernelError::Driver(format!(
+                    Err(KernelError::Conflict(format!(
                         "dedupe_token '{}' already used with different payload for attempt '{}'",
                         dedupe_token, attempt_id
                     )))
                 }
             }
-            Err(e) => Err(KernelError::Driver(format!("record step report: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("record step report: {}", e))),
         }
     }
 
@@ -1572,7 +1798,7 @@ impl SqliteRuntimeRepository {
              DO UPDATE SET secret_hash = excluded.secret_hash, role = excluded.role, status = excluded.status, updated_at_ms = excluded.updated_at_ms",
             params![key_id, secret_hash, role, status, now],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert api key: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert api key: {}", e)))?;
         Ok(())
     }
 
@@ -1586,13 +1812,13 @@ impl SqliteRuntimeRepository {
                 "SELECT key_id, secret_hash, role, status, created_at_ms, updated_at_ms
                  FROM runtime_api_keys WHERE key_id = ?1",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get_api_key_record: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get_api_key_record: {}", e)))?;
         let mut rows = stmt
             .query(params![key_id])
-            .map_err(|e| KernelError::Driver(format!("query get_api_key_record: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query get_api_key_record: {}", e)))?;
         if let Some(row) = rows
             .next()
-            .map_err(|e| KernelError::Driver(format!("scan get_api_key_record: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("scan get_api_key_record: {}", e)))?
         {
             let status: String = row.get(3).map_err(map_rusqlite_err)?;
             Ok(Some(ApiKeyRow {
@@ -1620,9 +1846,9 @@ impl SqliteRuntimeRepository {
                 "UPDATE runtime_api_keys SET status = ?2, updated_at_ms = ?3 WHERE key_id = ?1",
                 params![key_id, status, now],
             )
-            .map_err(|e| KernelError::Driver(format!("set api key status: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("set api key status: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "api key not found: {}",
                 key_id
             )));
@@ -1637,7 +1863,7 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM runtime_api_keys", [], |r| r.get(0))
-            .map_err(|e| KernelError::Driver(format!("count api keys: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("count api keys: {}", e)))?;
         Ok(count > 0)
     }
 
@@ -1674,7 +1900,7 @@ impl SqliteRuntimeRepository {
                 dt_to_ms(session.updated_at),
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert a2a session: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert a2a session: {}", e)))?;
         Ok(())
     }
 
@@ -1695,13 +1921,13 @@ impl SqliteRuntimeRepository {
                  WHERE sender_id = ?1
                    AND expires_at_ms > ?2",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get active a2a session: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get active a2a session: {}", e)))?;
         let mut rows = stmt
             .query(params![sender_id, dt_to_ms(now)])
-            .map_err(|e| KernelError::Driver(format!("query get active a2a session: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query get active a2a session: {}", e)))?;
         if let Some(row) = rows
             .next()
-            .map_err(|e| KernelError::Driver(format!("scan get active a2a session: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("scan get active a2a session: {}", e)))?
         {
             Ok(Some(A2aSessionRow {
                 sender_id: row.get(0).map_err(map_rusqlite_err)?,
@@ -1730,7 +1956,7 @@ impl SqliteRuntimeRepository {
                 "DELETE FROM runtime_a2a_sessions WHERE expires_at_ms <= ?1",
                 params![dt_to_ms(now)],
             )
-            .map_err(|e| KernelError::Driver(format!("purge expired a2a sessions: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("purge expired a2a sessions: {}", e)))?;
         Ok(deleted as u64)
     }
 
@@ -1760,10 +1986,10 @@ impl SqliteRuntimeRepository {
                 dt_to_ms(created_at)
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create bounty: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create bounty: {}", e)))?;
         drop(conn);
         self.get_bounty(bounty_id)?
-            .ok_or_else(|| KernelError::Driver("created bounty missing after insert".to_string()))
+            .ok_or_else(|| KernelError::Storage("created bounty missing after insert".to_string()))
     }
 
     pub fn get_bounty(&self, bounty_id: &str) -> Result<Option<BountyRow>, KernelError> {
@@ -1779,7 +2005,7 @@ impl SqliteRuntimeRepository {
             map_row_to_bounty,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get bounty: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get bounty: {}", e)))
     }
 
     pub fn accept_bounty(
@@ -1802,7 +2028,7 @@ impl SqliteRuntimeRepository {
                    AND status = 'open'",
                 params![bounty_id, accepted_by, dt_to_ms(accepted_at)],
             )
-            .map_err(|e| KernelError::Driver(format!("accept bounty: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("accept bounty: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -1824,7 +2050,7 @@ impl SqliteRuntimeRepository {
                    AND status = 'accepted'",
                 params![bounty_id, dt_to_ms(closed_at)],
             )
-            .map_err(|e| KernelError::Driver(format!("close bounty: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("close bounty: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -1858,10 +2084,10 @@ impl SqliteRuntimeRepository {
                 dt_to_ms(created_at)
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create swarm task: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create swarm task: {}", e)))?;
         drop(conn);
         self.get_swarm_task(parent_task_id)?.ok_or_else(|| {
-            KernelError::Driver("created swarm task missing after insert".to_string())
+            KernelError::Storage("created swarm task missing after insert".to_string())
         })
     }
 
@@ -1881,7 +2107,7 @@ impl SqliteRuntimeRepository {
             map_row_to_swarm_task,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get swarm task: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get swarm task: {}", e)))
     }
 
     pub fn upsert_worker_registration(
@@ -1911,10 +2137,10 @@ impl SqliteRuntimeRepository {
                status = excluded.status",
             params![worker_id, domains_json, max_load, metadata_json, now_ms, status],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert worker registration: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert worker registration: {}", e)))?;
         drop(conn);
         self.get_worker_registration(worker_id)?
-            .ok_or_else(|| KernelError::Driver("worker row missing after upsert".to_string()))
+            .ok_or_else(|| KernelError::Storage("worker row missing after upsert".to_string()))
     }
 
     pub fn get_worker_registration(
@@ -1933,7 +2159,7 @@ impl SqliteRuntimeRepository {
             map_row_to_worker_registry,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get worker registration: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get worker registration: {}", e)))
     }
 
     pub fn count_active_claims_for_worker(
@@ -1955,7 +2181,7 @@ impl SqliteRuntimeRepository {
                 params![worker_id, dt_to_ms(now)],
                 |row| row.get::<_, i64>(0),
             )
-            .map_err(|e| KernelError::Driver(format!("count active claims for worker: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("count active claims for worker: {}", e)))?;
         Ok(count.max(0) as u64)
     }
 
@@ -1990,10 +2216,10 @@ impl SqliteRuntimeRepository {
                 dt_to_ms(created_at)
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create dispute: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create dispute: {}", e)))?;
         drop(conn);
         self.get_dispute(dispute_id)?
-            .ok_or_else(|| KernelError::Driver("created dispute missing after insert".to_string()))
+            .ok_or_else(|| KernelError::Storage("created dispute missing after insert".to_string()))
     }
 
     pub fn get_dispute(&self, dispute_id: &str) -> Result<Option<DisputeRow>, KernelError> {
@@ -2009,7 +2235,7 @@ impl SqliteRuntimeRepository {
             map_row_to_dispute,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get dispute: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get dispute: {}", e)))
     }
 
     pub fn append_dispute_evidence(
@@ -2040,7 +2266,7 @@ impl SqliteRuntimeRepository {
                    AND status = 'open'",
                 params![dispute_id, payload],
             )
-            .map_err(|e| KernelError::Driver(format!("append dispute evidence: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("append dispute evidence: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -2066,7 +2292,7 @@ impl SqliteRuntimeRepository {
                    AND status = 'open'",
                 params![dispute_id, resolution, resolved_by, dt_to_ms(resolved_at)],
             )
-            .map_err(|e| KernelError::Driver(format!("resolve dispute: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("resolve dispute: {}", e)))?;
         if updated == 0 {
             return Ok(None);
         }
@@ -2093,7 +2319,7 @@ impl SqliteRuntimeRepository {
                    AND status IN ('open', 'accepted')",
                 params![bounty_id, settlement_status, dt_to_ms(closed_at)],
             )
-            .map_err(|e| KernelError::Driver(format!("settle bounty via dispute: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("settle bounty via dispute: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -2121,7 +2347,7 @@ impl SqliteRuntimeRepository {
                 recipe.is_public as i32,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create recipe: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create recipe: {}", e)))?;
         Ok(())
     }
 
@@ -2136,7 +2362,7 @@ impl SqliteRuntimeRepository {
                         forked_from, created_at_ms, updated_at_ms, is_public
                  FROM runtime_recipes WHERE recipe_id = ?1",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get recipe: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get recipe: {}", e)))?;
         let result = stmt
             .query_row(params![recipe_id], |row| {
                 Ok(RecipeRow {
@@ -2152,7 +2378,7 @@ impl SqliteRuntimeRepository {
                 })
             })
             .optional()
-            .map_err(|e| KernelError::Driver(format!("get recipe: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("get recipe: {}", e)))?;
         Ok(result)
     }
 
@@ -2167,7 +2393,7 @@ impl SqliteRuntimeRepository {
                         forked_from, created_at_ms, updated_at_ms, is_public
                  FROM runtime_recipes WHERE author_id = ?1 ORDER BY created_at_ms DESC",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare list recipes: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list recipes: {}", e)))?;
         let rows = stmt
             .query_map(params![author_id], |row| {
                 Ok(RecipeRow {
@@ -2182,10 +2408,10 @@ impl SqliteRuntimeRepository {
                     is_public: row.get::<_, i32>(8)? != 0,
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("list recipes: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("list recipes: {}", e)))?;
         let mut recipes = Vec::new();
         for row in rows {
-            recipes.push(row.map_err(|e| KernelError::Driver(format!("iterate recipes: {}", e)))?);
+            recipes.push(row.map_err(|e| KernelError::Storage(format!("iterate recipes: {}", e)))?);
         }
         Ok(recipes)
     }
@@ -2212,7 +2438,7 @@ impl SqliteRuntimeRepository {
                 organism.completed_at.map(dt_to_ms),
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create organism: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create organism: {}", e)))?;
         Ok(())
     }
 
@@ -2227,7 +2453,7 @@ impl SqliteRuntimeRepository {
                         created_at_ms, completed_at_ms
                  FROM runtime_organisms WHERE organism_id = ?1",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare get organism: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare get organism: {}", e)))?;
         let result = stmt
             .query_row(params![organism_id], |row| {
                 Ok(OrganismRow {
@@ -2241,7 +2467,7 @@ impl SqliteRuntimeRepository {
                 })
             })
             .optional()
-            .map_err(|e| KernelError::Driver(format!("get organism: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("get organism: {}", e)))?;
         Ok(result)
     }
 
@@ -2267,7 +2493,7 @@ impl SqliteRuntimeRepository {
                  WHERE organism_id = ?1",
                 params![organism_id, status, current_step, completed_at_ms],
             )
-            .map_err(|e| KernelError::Driver(format!("update organism status: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("update organism status: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -2301,7 +2527,7 @@ impl SqliteRuntimeRepository {
                 dt_to_ms(task.updated_at),
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert a2a compat task: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert a2a compat task: {}", e)))?;
         Ok(())
     }
 
@@ -2323,17 +2549,19 @@ impl SqliteRuntimeRepository {
                    AND protocol_version = ?2
                  ORDER BY enqueued_at_ms ASC, session_id ASC",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare list a2a compat tasks: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list a2a compat tasks: {}", e)))?;
         let rows = stmt
             .query_map(
                 params![sender_id, protocol_version],
                 map_row_to_a2a_compat_task,
             )
-            .map_err(|e| KernelError::Driver(format!("query list a2a compat tasks: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query list a2a compat tasks: {}", e)))?;
         let mut tasks = Vec::new();
         for row in rows {
             tasks.push(
-                row.map_err(|e| KernelError::Driver(format!("scan list a2a compat tasks: {}", e)))?,
+                row.map_err(|e| {
+                    KernelError::Storage(format!("scan list a2a compat tasks: {}", e))
+                })?,
             );
         }
         Ok(tasks)
@@ -2353,7 +2581,7 @@ impl SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin claim a2a compat task tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin claim a2a compat task tx: {}", e)))?;
         let now_ms = dt_to_ms(now);
         let candidate = tx
             .query_row(
@@ -2374,7 +2602,7 @@ impl SqliteRuntimeRepository {
                 map_row_to_a2a_compat_task,
             )
             .optional()
-            .map_err(|e| KernelError::Driver(format!("query claim a2a compat task: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query claim a2a compat task: {}", e)))?;
 
         if let Some(mut task) = candidate {
             let reclaimed_expired_lease = task.claimed_by_sender_id.is_some()
@@ -2409,10 +2637,12 @@ impl SqliteRuntimeRepository {
                         requested_task_id
                     ],
                 )
-                .map_err(|e| KernelError::Driver(format!("update claim a2a compat task: {}", e)))?;
+                .map_err(|e| {
+                    KernelError::Storage(format!("update claim a2a compat task: {}", e))
+                })?;
             if updated > 0 {
                 tx.commit().map_err(|e| {
-                    KernelError::Driver(format!("commit claim a2a compat task: {}", e))
+                    KernelError::Storage(format!("commit claim a2a compat task: {}", e))
                 })?;
                 task.claimed_by_sender_id = Some(sender_id.to_string());
                 task.lease_expires_at = Some(ms_to_dt(lease_expires_at_ms));
@@ -2437,11 +2667,13 @@ impl SqliteRuntimeRepository {
                 params![sender_id, protocol_version, now_ms, requested_task_id],
                 |row| row.get::<_, Option<i64>>(0),
             )
-            .map_err(|e| KernelError::Driver(format!("query claim retry_after a2a compat: {}", e)))?
+            .map_err(|e| {
+                KernelError::Storage(format!("query claim retry_after a2a compat: {}", e))
+            })?
             .and_then(|ms| if ms > 0 { u64::try_from(ms).ok() } else { None });
 
         tx.commit().map_err(|e| {
-            KernelError::Driver(format!("commit claim miss a2a compat task: {}", e))
+            KernelError::Storage(format!("commit claim miss a2a compat task: {}", e))
         })?;
         Ok(A2aCompatClaimOutcome {
             task: None,
@@ -2467,7 +2699,7 @@ impl SqliteRuntimeRepository {
             map_row_to_a2a_compat_task,
         )
         .optional()
-        .map_err(|e| KernelError::Driver(format!("get a2a compat task: {}", e)))
+        .map_err(|e| KernelError::Storage(format!("get a2a compat task: {}", e)))
     }
 
     pub fn touch_a2a_compat_task_lease(
@@ -2496,7 +2728,7 @@ impl SqliteRuntimeRepository {
                    AND lease_expires_at_ms > ?4",
                 params![session_id, sender_id, lease_expires_at_ms, now_ms],
             )
-            .map_err(|e| KernelError::Driver(format!("touch a2a compat task lease: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("touch a2a compat task lease: {}", e)))?;
         Ok(updated > 0)
     }
 
@@ -2510,7 +2742,7 @@ impl SqliteRuntimeRepository {
                 "DELETE FROM runtime_a2a_compat_tasks WHERE session_id = ?1",
                 params![session_id],
             )
-            .map_err(|e| KernelError::Driver(format!("remove a2a compat task: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("remove a2a compat task: {}", e)))?;
         Ok(removed as u64)
     }
 
@@ -2537,7 +2769,7 @@ impl SqliteRuntimeRepository {
                 now
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("append audit log: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("append audit log: {}", e)))?;
         Ok(())
     }
 
@@ -2568,7 +2800,7 @@ impl SqliteRuntimeRepository {
                  ORDER BY audit_id DESC
                  LIMIT ?5",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare list_audit_logs: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list_audit_logs: {}", e)))?;
         let rows = stmt
             .query_map(
                 params![request_id, action, from_ms, to_ms, limit as i64],
@@ -2588,7 +2820,7 @@ impl SqliteRuntimeRepository {
                     })
                 },
             )
-            .map_err(|e| KernelError::Driver(format!("query list_audit_logs: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query list_audit_logs: {}", e)))?;
         let mut out = Vec::new();
         for row in rows {
             out.push(row.map_err(map_rusqlite_err)?);
@@ -2597,6 +2829,18 @@ impl SqliteRuntimeRepository {
     }
 }
 
+fn map_row_to_run_record(row: &rusqlite::Row) -> rusqlite::Result<RunRecord> {
+    Ok(RunRecord {
+        run_id: row.get(0)?,
+        workflow_name: row.get(1)?,
+        status: RunRuntimeStatus::from_str(&row.get::<_, String>(2)?),
+        status_reason: row.get(3)?,
+        tenant_id: row.get(6)?,
+        created_at: ms_to_dt(row.get(4)?),
+        updated_at: ms_to_dt(row.get(5)?),
+    })
+}
+
 fn map_row_to_interrupt(row: &rusqlite::Row) -> rusqlite::Result<InterruptRow> {
     Ok(InterruptRow {
         interrupt_id: row.get(0)?,
@@ -2611,6 +2855,23 @@ fn map_row_to_interrupt(row: &rusqlite::Row) -> rusqlite::Result<InterruptRow> {
     })
 }
 
+fn map_row_to_interrupt_record(row: &rusqlite::Row) -> rusqlite::Result<InterruptRecord> {
+    let decision: Option<String> = row.get(7)?;
+    let resolution_json: Option<String> = row.get(8)?;
+    Ok(InterruptRecord {
+        interrupt_id: row.get(0)?,
+        run_id: row.get(1)?,
+        attempt_id: row.get(2)?,
+        step_id: row.get(3)?,
+        payload: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or(Value::Null),
+        status: InterruptStatus::from_str(&row.get::<_, String>(5)?),
+        created_at: ms_to_dt(row.get::<_, i64>(6)?),
+        decision: decision.as_deref().and_then(InterruptDecision::from_str),
+        resolution: resolution_json.map(|json| serde_json::from_str(&json).unwrap_or(Value::Null)),
+        resolved_at: row.get::<_, Option<i64>>(9)?.map(ms_to_dt),
+    })
+}
+
 fn map_row_to_dead_letter(row: &rusqlite::Row) -> rusqlite::Result<DeadLetterRow> {
     Ok(DeadLetterRow {
         attempt_id: row.get(0)?,
@@ -2876,18 +3137,79 @@ pub enum StepReportWriteResult {
 }
 
 impl RuntimeRepository for SqliteRuntimeRepository {
+    fn enqueue_attempts(
+        &self,
+        batch: &[(String, RunId, EnqueueOptions)],
+    ) -> Result<EnqueueBatchOutcome, KernelError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin enqueue attempts: {}", e)))?;
+        let enqueued_at_ms = dt_to_ms(Utc::now());
+        let mut outcome = EnqueueBatchOutcome::default();
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT OR IGNORE INTO runtime_attempts
+                       (attempt_id, run_id, attempt_no, status, retry_at_ms, priority, enqueued_at_ms, run_at_ms, tenant_id)
+                     VALUES (?1, ?2, 1, 'queued', NULL, ?3, ?4, ?5,
+                             (SELECT tenant_id FROM runtime_runs WHERE run_id = ?2))",
+                )
+                .map_err(|e| KernelError::Storage(format!("prepare enqueue attempt: {}", e)))?;
+            for (attempt_id, run_id, options) in batch {
+                check_run_tenant(&tx, run_id, self.tenant_id())?;
+                match insert.execute(params![
+                    attempt_id,
+                    run_id,
+                    options.priority,
+                    enqueued_at_ms,
+                    options.run_at.map(dt_to_ms)
+                ]) {
+                    Ok(0) => outcome.skipped += 1,
+                    Ok(_) => outcome.inserted += 1,
+                    Err(rusqlite::Error::SqliteFailure(err, _))
+                        if err.code == ErrorCode::ConstraintViolation =>
+                    {
+                        return Err(KernelError::NotFound(format!(
+                            "run not found for attempt {}: {}",
+                            attempt_id, run_id
+                        )));
+                    }
+                    Err(e) => return Err(KernelError::Storage(format!("enqueue attempt: {}", e))),
+                }
+            }
+        }
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit enqueue attempts: {}", e)))?;
+        Ok(outcome)
+    }
+
     fn list_dispatchable_attempts(
         &self,
         now: DateTime<Utc>,
         limit: usize,
     ) -> Result<Vec<AttemptDispatchRecord>, KernelError> {
+        self.list_dispatchable_attempts_with_options(now, limit, &DispatchOptions::default())
+            .map(|listed| listed.attempts)
+    }
+
+    fn list_dispatchable_attempts_with_options(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        options: &DispatchOptions,
+    ) -> Result<DispatchableAttempts, KernelError> {
         let conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let max_per_run = options.max_concurrent_per_run.map(|max| max as i64);
         let mut stmt = conn
             .prepare(
-                "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms
+                "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms, a.priority
                  FROM runtime_attempts a
                  LEFT JOIN runtime_leases l ON l.attempt_id = a.attempt_id AND l.lease_expires_at_ms >= ?1
                  WHERE l.attempt_id IS NULL
@@ -2895,12 +3217,25 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                      a.status = 'queued'
                      OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1))
                    )
-                 ORDER BY a.priority DESC, a.attempt_no ASC, a.attempt_id ASC
+                   AND (a.run_at_ms IS NULL OR a.run_at_ms <= ?1)
+                   AND (
+                     ?3 IS NULL
+                     OR (
+                       SELECT COUNT(*)
+                       FROM runtime_leases rl
+                       JOIN runtime_attempts ra ON ra.attempt_id = rl.attempt_id
+                       WHERE ra.run_id = a.run_id AND rl.lease_expires_at_ms >= ?1
+                     ) < ?3
+                   )
+                   AND (?4 IS NULL OR a.tenant_id = ?4)
+                 ORDER BY a.priority DESC, a.enqueued_at_ms ASC NULLS FIRST, a.attempt_no ASC, a.attempt_id ASC
                  LIMIT ?2",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare list dispatchable attempts: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list dispatchable attempts: {}", e)))?;
         let rows = stmt
-            .query_map(params![dt_to_ms(now), limit as i64], |row| {
+            .query_map(
+                params![dt_to_ms(now), limit as i64, max_per_run, self.tenant_id()],
+                |row| {
                 let retry_at_ms: Option<i64> = row.get(4)?;
                 Ok(AttemptDispatchRecord {
                     attempt_id: row.get(0)?,
@@ -2908,14 +3243,179 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     attempt_no: row.get::<_, i64>(2)? as u32,
                     status: parse_attempt_status(&row.get::<_, String>(3)?),
                     retry_at: retry_at_ms.map(ms_to_dt),
+                    priority: row.get(5)?,
                 })
-            })
-            .map_err(|e| KernelError::Driver(format!("query dispatchable attempts: {}", e)))?;
-        let mut out = Vec::new();
+                },
+            )
+            .map_err(|e| KernelError::Storage(format!("query dispatchable attempts: {}", e)))?;
+        let mut attempts = Vec::new();
         for item in rows {
-            out.push(item.map_err(map_rusqlite_err)?);
+            attempts.push(item.map_err(map_rusqlite_err)?);
         }
-        Ok(out)
+
+        let skipped_for_fairness = match max_per_run {
+            None => 0,
+            Some(max_per_run) => conn
+                .query_row(
+                    "SELECT COUNT(*)
+                     FROM runtime_attempts a
+                     LEFT JOIN runtime_leases l ON l.attempt_id = a.attempt_id AND l.lease_expires_at_ms >= ?1
+                     WHERE l.attempt_id IS NULL
+                       AND (
+                         a.status = 'queued'
+                         OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1))
+                       )
+                       AND (a.run_at_ms IS NULL OR a.run_at_ms <= ?1)
+                       AND (
+                         SELECT COUNT(*)
+                         FROM runtime_leases rl
+                         JOIN runtime_attempts ra ON ra.attempt_id = rl.attempt_id
+                         WHERE ra.run_id = a.run_id AND rl.lease_expires_at_ms >= ?1
+                       ) >= ?2
+                       AND (?3 IS NULL OR a.tenant_id = ?3)",
+                    params![dt_to_ms(now), max_per_run, self.tenant_id()],
+                    |r| r.get::<_, i64>(0),
+                )
+                .map_err(|e| KernelError::Storage(format!("count attempts held back per run: {}", e)))?
+                as usize,
+        };
+        Ok(DispatchableAttempts {
+            attempts,
+            skipped_for_fairness,
+        })
+    }
+
+    fn next_dispatch_at(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let next_ms: Option<i64> = conn
+            .query_row(
+                "SELECT MIN(ready_at_ms)
+                 FROM (
+                   SELECT MAX(
+                            COALESCE(a.run_at_ms, 0),
+                            CASE WHEN a.status = 'retry_backoff' THEN COALESCE(a.retry_at_ms, 0) ELSE 0 END
+                          ) AS ready_at_ms
+                   FROM runtime_attempts a
+                   WHERE a.status IN ('queued', 'retry_backoff')
+                     AND (?2 IS NULL OR a.tenant_id = ?2)
+                 )
+                 WHERE ready_at_ms > ?1",
+                params![dt_to_ms(now), self.tenant_id()],
+                |r| r.get(0),
+            )
+            .map_err(|e| KernelError::Storage(format!("next dispatch at: {}", e)))?;
+        Ok(next_ms.map(ms_to_dt))
+    }
+
+    fn claim_dispatchable_attempts(
+        &self,
+        now: DateTime<Utc>,
+        worker_id: &str,
+        limit: usize,
+        lease_ttl: Duration,
+    ) -> Result<Vec<ClaimedAttempt>, KernelError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        // Take the write lock up front so that other processes sharing the database file
+        // cannot pick the same attempts between the read and the lease inserts
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| KernelError::Storage(format!("begin claim attempts tx: {}", e)))?;
+        let now_ms = dt_to_ms(now);
+        let attempts = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT a.attempt_id, a.run_id, a.attempt_no, a.status, a.retry_at_ms, a.priority, a.tenant_id
+                     FROM runtime_attempts a
+                     LEFT JOIN runtime_leases l ON l.attempt_id = a.attempt_id AND l.lease_expires_at_ms >= ?1
+                     WHERE l.attempt_id IS NULL
+                       AND (
+                         a.status = 'queued'
+                         OR (a.status = 'retry_backoff' AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1))
+                       )
+                       AND (a.run_at_ms IS NULL OR a.run_at_ms <= ?1)
+                       AND (?3 IS NULL OR a.tenant_id = ?3)
+                     ORDER BY a.priority DESC, a.enqueued_at_ms ASC NULLS FIRST, a.attempt_no ASC, a.attempt_id ASC
+                     LIMIT ?2",
+                )
+                .map_err(|e| KernelError::Storage(format!("prepare claim attempts: {}", e)))?;
+            let rows = stmt
+                .query_map(params![now_ms, limit as i64, self.tenant_id()], |row| {
+                    let retry_at_ms: Option<i64> = row.get(4)?;
+                    Ok((
+                        AttemptDispatchRecord {
+                            attempt_id: row.get(0)?,
+                            run_id: row.get(1)?,
+                            attempt_no: row.get::<_, i64>(2)? as u32,
+                            status: parse_attempt_status(&row.get::<_, String>(3)?),
+                            retry_at: retry_at_ms.map(ms_to_dt),
+                            priority: row.get(5)?,
+                        },
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                })
+                .map_err(|e| KernelError::Storage(format!("query claim attempts: {}", e)))?;
+            let mut attempts = Vec::new();
+            for item in rows {
+                attempts.push(item.map_err(map_rusqlite_err)?);
+            }
+            attempts
+        };
+
+        let lease_expires_at = now + lease_ttl;
+        let mut claimed = Vec::with_capacity(attempts.len());
+        for (attempt, tenant_id) in attempts {
+            let lease_id = format!("lease-{}", uuid::Uuid::new_v4());
+            tx.execute(
+                "DELETE FROM runtime_leases WHERE attempt_id = ?1 AND lease_expires_at_ms < ?2",
+                params![attempt.attempt_id, now_ms],
+            )
+            .map_err(|e| KernelError::Storage(format!("cleanup expired lease: {}", e)))?;
+            tx.execute(
+                "INSERT INTO runtime_leases
+                 (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6)",
+                params![
+                    lease_id,
+                    attempt.attempt_id,
+                    worker_id,
+                    dt_to_ms(lease_expires_at),
+                    now_ms,
+                    tenant_id
+                ],
+            )
+            .map_err(|e| KernelError::Storage(format!("insert claimed lease: {}", e)))?;
+            tx.execute(
+                "UPDATE runtime_attempts
+                 SET status = 'leased',
+                     started_at_ms = COALESCE(started_at_ms, ?2)
+                 WHERE attempt_id = ?1",
+                params![attempt.attempt_id, now_ms],
+            )
+            .map_err(|e| KernelError::Storage(format!("mark claimed status: {}", e)))?;
+            claimed.push(ClaimedAttempt {
+                lease: LeaseRecord {
+                    lease_id,
+                    attempt_id: attempt.attempt_id.clone(),
+                    worker_id: worker_id.to_string(),
+                    lease_expires_at,
+                    heartbeat_at: now,
+                    version: 1,
+                    terminal_state: None,
+                    terminal_at: None,
+                    tenant_id,
+                },
+                attempt,
+            });
+        }
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit claim attempts tx: {}", e)))?;
+        Ok(claimed)
     }
 
     fn upsert_lease(
@@ -2931,17 +3431,19 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin upsert lease tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin upsert lease tx: {}", e)))?;
+        check_attempt_tenant(&tx, attempt_id, self.tenant_id())?;
         let lease_id = format!("lease-{}", uuid::Uuid::new_v4());
         tx.execute(
             "DELETE FROM runtime_leases WHERE attempt_id = ?1 AND lease_expires_at_ms < ?2",
             params![attempt_id, dt_to_ms(now)],
         )
-        .map_err(|e| KernelError::Driver(format!("cleanup expired lease: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("cleanup expired lease: {}", e)))?;
         match tx.execute(
             "INSERT INTO runtime_leases
-             (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version)
-             VALUES (?1, ?2, ?3, ?4, ?5, 1)",
+             (lease_id, attempt_id, worker_id, lease_expires_at_ms, heartbeat_at_ms, version, tenant_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, 1,
+                     (SELECT tenant_id FROM runtime_attempts WHERE attempt_id = ?2))",
             params![
                 lease_id,
                 attempt_id,
@@ -2954,12 +3456,12 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             Err(rusqlite::Error::SqliteFailure(err, _))
                 if err.code == ErrorCode::ConstraintViolation =>
             {
-                return Err(KernelError::Driver(format!(
+                return Err(KernelError::LeaseConflict(format!(
                     "active lease already exists for attempt: {}",
                     attempt_id
                 )));
             }
-            Err(e) => return Err(KernelError::Driver(format!("insert lease: {}", e))),
+            Err(e) => return Err(KernelError::Storage(format!("insert lease: {}", e))),
         };
         let updated_attempt = tx
             .execute(
@@ -2969,22 +3471,22 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                  WHERE attempt_id = ?1 AND status IN ('queued', 'retry_backoff')",
                 params![attempt_id, dt_to_ms(now)],
             )
-            .map_err(|e| KernelError::Driver(format!("mark leased status: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("mark leased status: {}", e)))?;
         if updated_attempt == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotDispatchable(format!(
                 "attempt is not dispatchable for lease: {}",
                 attempt_id
             )));
         }
-        let version: i64 = tx
+        let (version, tenant_id): (i64, Option<String>) = tx
             .query_row(
-                "SELECT version FROM runtime_leases WHERE attempt_id = ?1",
+                "SELECT version, tenant_id FROM runtime_leases WHERE attempt_id = ?1",
                 params![attempt_id],
-                |r| r.get(0),
+                |r| Ok((r.get(0)?, r.get(1)?)),
             )
-            .map_err(|e| KernelError::Driver(format!("read lease version: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("read lease version: {}", e)))?;
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit upsert lease tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit upsert lease tx: {}", e)))?;
         Ok(LeaseRecord {
             lease_id,
             attempt_id: attempt_id.to_string(),
@@ -2994,6 +3496,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             version: version as u64,
             terminal_state: None,
             terminal_at: None,
+            tenant_id,
         })
     }
 
@@ -3002,24 +3505,67 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         lease_id: &str,
         heartbeat_at: DateTime<Utc>,
         lease_expires_at: DateTime<Utc>,
-    ) -> Result<(), KernelError> {
+    ) -> Result<LeaseDirective, KernelError> {
         let conn = self
             .conn
             .lock()
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let updated = conn
             .execute(
-                "UPDATE runtime_leases SET heartbeat_at_ms = ?2, lease_expires_at_ms = ?3, version = version + 1 WHERE lease_id = ?1",
-                params![lease_id, dt_to_ms(heartbeat_at), dt_to_ms(lease_expires_at)],
+                "UPDATE runtime_leases SET heartbeat_at_ms = ?2, lease_expires_at_ms = ?3, version = version + 1
+                 WHERE lease_id = ?1 AND (?4 IS NULL OR tenant_id = ?4)",
+                params![
+                    lease_id,
+                    dt_to_ms(heartbeat_at),
+                    dt_to_ms(lease_expires_at),
+                    self.tenant_id()
+                ],
             )
-            .map_err(|e| KernelError::Driver(format!("heartbeat lease: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("heartbeat lease: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "lease not found for heartbeat: {}",
                 lease_id
             )));
         }
-        Ok(())
+        lease_directive(&conn, lease_id)
+    }
+
+    fn heartbeat_lease_with_version(
+        &self,
+        lease_id: &str,
+        worker_id: &str,
+        expected_version: u64,
+        heartbeat_at: DateTime<Utc>,
+        lease_expires_at: DateTime<Utc>,
+    ) -> Result<LeaseDirective, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let updated = conn
+            .execute(
+                "UPDATE runtime_leases
+                 SET heartbeat_at_ms = ?4, lease_expires_at_ms = ?5, version = version + 1
+                 WHERE lease_id = ?1 AND worker_id = ?2 AND version = ?3
+                   AND (?6 IS NULL OR tenant_id = ?6)",
+                params![
+                    lease_id,
+                    worker_id,
+                    expected_version as i64,
+                    dt_to_ms(heartbeat_at),
+                    dt_to_ms(lease_expires_at),
+                    self.tenant_id()
+                ],
+            )
+            .map_err(|e| KernelError::Storage(format!("heartbeat lease with version: {}", e)))?;
+        if updated == 0 {
+            return Err(KernelError::LeaseConflict(format!(
+                "lease heartbeat version conflict for lease: {}",
+                lease_id
+            )));
+        }
+        lease_directive(&conn, lease_id)
     }
 
     fn expire_leases_and_requeue(&self, stale_before: DateTime<Utc>) -> Result<u64, KernelError> {
@@ -3029,17 +3575,19 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
         let tx = conn
             .transaction()
-            .map_err(|e| KernelError::Driver(format!("begin expire/requeue tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("begin expire/requeue tx: {}", e)))?;
         let mut stmt = tx
             .prepare(
                 "SELECT attempt_id
                  FROM runtime_leases
-                 WHERE lease_expires_at_ms < ?1",
+                 WHERE lease_expires_at_ms < ?1 AND (?2 IS NULL OR tenant_id = ?2)",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare expired lease query: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare expired lease query: {}", e)))?;
         let rows = stmt
-            .query_map(params![dt_to_ms(stale_before)], |r| r.get::<_, String>(0))
-            .map_err(|e| KernelError::Driver(format!("query expired leases: {}", e)))?;
+            .query_map(params![dt_to_ms(stale_before), self.tenant_id()], |r| {
+                r.get::<_, String>(0)
+            })
+            .map_err(|e| KernelError::Storage(format!("query expired leases: {}", e)))?;
         let mut expired_attempts = Vec::new();
         for row in rows {
             expired_attempts.push(row.map_err(map_rusqlite_err)?);
@@ -3050,18 +3598,22 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "DELETE FROM runtime_leases WHERE attempt_id = ?1",
                 params![attempt_id],
             )
-            .map_err(|e| KernelError::Driver(format!("delete expired lease: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("delete expired lease: {}", e)))?;
+            // An attempt asked to cancel is not handed to another worker
             tx.execute(
                 "UPDATE runtime_attempts
-                 SET status = 'queued'
+                 SET status = CASE
+                   WHEN cancel_requested_at_ms IS NOT NULL THEN 'cancelled'
+                   ELSE 'queued'
+                 END
                  WHERE attempt_id = ?1
                    AND status NOT IN ('completed', 'failed', 'cancelled')",
                 params![attempt_id],
             )
-            .map_err(|e| KernelError::Driver(format!("requeue attempt: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("requeue attempt: {}", e)))?;
         }
         tx.commit()
-            .map_err(|e| KernelError::Driver(format!("commit expire/requeue tx: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("commit expire/requeue tx: {}", e)))?;
         Ok(expired_attempts.len() as u64)
     }
 
@@ -3078,11 +3630,14 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                    AND execution_timeout_ms IS NOT NULL
                    AND timeout_terminal_status IS NOT NULL
                    AND status IN ('leased', 'running')
-                   AND (started_at_ms + execution_timeout_ms) <= ?1",
+                   AND (started_at_ms + execution_timeout_ms) <= ?1
+                   AND (?2 IS NULL OR tenant_id = ?2)",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare timed-out attempts query: {}", e)))?;
+            .map_err(|e| {
+                KernelError::Storage(format!("prepare timed-out attempts query: {}", e))
+            })?;
         let rows = stmt
-            .query_map(params![dt_to_ms(now)], |row| {
+            .query_map(params![dt_to_ms(now), self.tenant_id()], |row| {
                 Ok((
                     row.get::<_, String>(0)?,
                     row.get::<_, String>(1)?,
@@ -3090,7 +3645,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     row.get::<_, String>(3)?,
                 ))
             })
-            .map_err(|e| KernelError::Driver(format!("query timed-out attempts: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("query timed-out attempts: {}", e)))?;
         let mut timed_out = Vec::new();
         for row in rows {
             timed_out.push(row.map_err(map_rusqlite_err)?);
@@ -3100,7 +3655,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "DELETE FROM runtime_leases WHERE attempt_id = ?1",
                 params![attempt_id],
             )
-            .map_err(|e| KernelError::Driver(format!("delete timed-out lease: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("delete timed-out lease: {}", e)))?;
             conn.execute(
                 "UPDATE runtime_attempts
                  SET status = ?2,
@@ -3109,7 +3664,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                  WHERE attempt_id = ?1",
                 params![attempt_id, terminal_status],
             )
-            .map_err(|e| KernelError::Driver(format!("mark timed-out attempt status: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("mark timed-out attempt status: {}", e)))?;
             if terminal_status == "failed" {
                 conn.execute(
                     "INSERT INTO runtime_dead_letters
@@ -3124,16 +3679,390 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                        replay_status = 'pending'",
                     params![attempt_id, run_id, attempt_no, "execution_timeout", dt_to_ms(now)],
                 )
-                .map_err(|e| KernelError::Driver(format!("upsert dead letter from timeout: {}", e)))?;
+                .map_err(|e| KernelError::Storage(format!("upsert dead letter from timeout: {}", e)))?;
             }
         }
         Ok(timed_out.len() as u64)
     }
 
+    fn finish_attempt(
+        &self,
+        attempt_id: &str,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Ok(self.ack_attempt(attempt_id, status, None, now)?.status)
+    }
+
+    fn complete_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        status: AttemptExecutionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptExecutionStatus, KernelError> {
+        Ok(self
+            .ack_attempt_under_lease(attempt_id, Some(lease), status, None, now)?
+            .status)
+    }
+
+    fn record_attempt_failure(
+        &self,
+        attempt_id: &str,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, None, error, retry_policy, now)
+    }
+
+    fn fail_attempt(
+        &self,
+        attempt_id: &str,
+        lease: &LeaseFence,
+        error: &str,
+        retry_policy: &RetryPolicyConfig,
+        now: DateTime<Utc>,
+    ) -> Result<AttemptAckOutcome, KernelError> {
+        self.record_attempt_failure_under_lease(attempt_id, Some(lease), error, retry_policy, now)
+    }
+
+    fn cancel_attempt(&self, attempt_id: &str) -> Result<AttemptCancellation, KernelError> {
+        let now_ms = dt_to_ms(Utc::now());
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin cancel attempt tx: {}", e)))?;
+        let Some(status) = tx
+            .query_row(
+                "SELECT status FROM runtime_attempts
+                 WHERE attempt_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+                params![attempt_id, self.tenant_id()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|e| KernelError::Storage(format!("read attempt for cancel: {}", e)))?
+        else {
+            return Err(KernelError::NotFound(format!(
+                "attempt not found for cancel: {}",
+                attempt_id
+            )));
+        };
+        let outcome = match parse_attempt_status(&status) {
+            AttemptExecutionStatus::Queued | AttemptExecutionStatus::RetryBackoff => {
+                tx.execute(
+                    "UPDATE runtime_attempts
+                     SET status = 'cancelled',
+                         retry_at_ms = NULL,
+                         started_at_ms = NULL,
+                         cancel_requested_at_ms = ?2
+                     WHERE attempt_id = ?1",
+                    params![attempt_id, now_ms],
+                )
+                .map_err(|e| KernelError::Storage(format!("cancel queued attempt: {}", e)))?;
+                AttemptCancellation::Cancelled
+            }
+            AttemptExecutionStatus::Leased | AttemptExecutionStatus::Running => {
+                tx.execute(
+                    "UPDATE runtime_attempts
+                     SET cancel_requested_at_ms = COALESCE(cancel_requested_at_ms, ?2)
+                     WHERE attempt_id = ?1",
+                    params![attempt_id, now_ms],
+                )
+                .map_err(|e| KernelError::Storage(format!("request attempt cancel: {}", e)))?;
+                AttemptCancellation::Requested
+            }
+            finished => AttemptCancellation::AlreadyFinished(finished),
+        };
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit cancel attempt tx: {}", e)))?;
+        Ok(outcome)
+    }
+
+    fn list_dead_letter_attempts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetterAttemptRecord>, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT attempt_id, run_id, attempt_no, last_error, dead_lettered_at_ms
+                 FROM runtime_attempts
+                 WHERE status = 'dead_letter' AND (?2 IS NULL OR tenant_id = ?2)
+                 ORDER BY dead_lettered_at_ms DESC, attempt_id ASC
+                 LIMIT ?1",
+            )
+            .map_err(|e| KernelError::Storage(format!("prepare dead letter attempts: {}", e)))?;
+        let rows = stmt
+            .query_map(params![limit as i64, self.tenant_id()], |row| {
+                Ok(DeadLetterAttemptRecord {
+                    attempt_id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    attempt_no: row.get::<_, i64>(2)? as u32,
+                    error: row.get(3)?,
+                    dead_lettered_at: ms_to_dt(row.get::<_, Option<i64>>(4)?.unwrap_or_default()),
+                })
+            })
+            .map_err(|e| KernelError::Storage(format!("query dead letter attempts: {}", e)))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(map_rusqlite_err)?);
+        }
+        Ok(out)
+    }
+
+    fn requeue_dead_letter(&self, attempt_id: &str) -> Result<(), KernelError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin requeue dead letter tx: {}", e)))?;
+        let status = tx
+            .query_row(
+                "SELECT status FROM runtime_attempts
+                 WHERE attempt_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+                params![attempt_id, self.tenant_id()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|e| KernelError::Storage(format!("read attempt for requeue: {}", e)))?;
+        match status.as_deref() {
+            None => {
+                return Err(KernelError::NotFound(format!(
+                    "attempt not found for requeue: {}",
+                    attempt_id
+                )))
+            }
+            Some("dead_letter") => {}
+            Some(status) => {
+                return Err(KernelError::Conflict(format!(
+                    "attempt {} is {}, not dead-lettered",
+                    attempt_id, status
+                )))
+            }
+        }
+        tx.execute(
+            "UPDATE runtime_attempts
+             SET status = 'queued',
+                 retry_at_ms = NULL,
+                 dead_lettered_at_ms = NULL
+             WHERE attempt_id = ?1",
+            params![attempt_id],
+        )
+        .map_err(|e| KernelError::Storage(format!("requeue dead letter attempt: {}", e)))?;
+        tx.execute(
+            "UPDATE runtime_dead_letters
+             SET replay_status = 'replayed',
+                 replay_count = replay_count + 1,
+                 last_replayed_at_ms = ?2
+             WHERE attempt_id = ?1 AND replay_status = 'pending'",
+            params![attempt_id, dt_to_ms(Utc::now())],
+        )
+        .map_err(|e| KernelError::Storage(format!("mark dead letter replayed: {}", e)))?;
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit requeue dead letter: {}", e)))?;
+        Ok(())
+    }
+
+    fn runtime_stats(&self, now: DateTime<Utc>) -> Result<RuntimeStats, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let now_ms = dt_to_ms(now);
+        let mut stmt = conn
+            .prepare(
+                "SELECT a.status,
+                        COUNT(*),
+                        MIN(CASE WHEN a.status = 'queued' THEN a.enqueued_at_ms END),
+                        SUM(CASE WHEN a.status = 'retry_backoff'
+                                  AND (a.retry_at_ms IS NULL OR a.retry_at_ms <= ?1)
+                                 THEN 1 ELSE 0 END),
+                        (SELECT COUNT(*) FROM runtime_leases
+                         WHERE lease_expires_at_ms >= ?1 AND (?2 IS NULL OR tenant_id = ?2))
+                 FROM runtime_attempts a
+                 WHERE (?2 IS NULL OR a.tenant_id = ?2)
+                 GROUP BY a.status",
+            )
+            .map_err(|e| KernelError::Storage(format!("prepare runtime stats: {}", e)))?;
+        let rows = stmt
+            .query_map(params![now_ms, self.tenant_id()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })
+            .map_err(|e| KernelError::Storage(format!("query runtime stats: {}", e)))?;
+        let mut stats = RuntimeStats::default();
+        for row in rows {
+            let (status, count, oldest_enqueued_ms, due_retries, active_leases) =
+                row.map_err(map_rusqlite_err)?;
+            stats
+                .attempts
+                .add(&parse_attempt_status(&status), count as u64);
+            if let Some(enqueued_ms) = oldest_enqueued_ms {
+                stats.oldest_queued_age_ms = Some((now_ms - enqueued_ms).max(0));
+            }
+            stats.retry_backlog += due_retries as u64;
+            stats.active_leases = active_leases as u64;
+        }
+        Ok(stats)
+    }
+
     fn latest_seq_for_run(&self, _run_id: &RunId) -> Result<Seq, KernelError> {
         Ok(0)
     }
 
+    // ============== Run Methods ==============
+
+    fn create_run(&self, run: &RunRecord) -> Result<(), KernelError> {
+        let tenant_id = run_tenant_in_scope(run, self.tenant_id())?;
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        match conn.execute(
+            "INSERT INTO runtime_runs
+               (run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                run.run_id,
+                run.workflow_name,
+                run.status.as_str(),
+                run.status_reason,
+                dt_to_ms(run.created_at),
+                dt_to_ms(run.updated_at),
+                tenant_id
+            ],
+        ) {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == ErrorCode::ConstraintViolation =>
+            {
+                Err(KernelError::Conflict(format!(
+                    "run already exists: {}",
+                    run.run_id
+                )))
+            }
+            Err(e) => Err(KernelError::Storage(format!("create run: {}", e))),
+        }
+    }
+
+    fn get_run(&self, run_id: &RunId) -> Result<Option<RunRecord>, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        conn.query_row(
+            "SELECT run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id
+             FROM runtime_runs WHERE run_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+            params![run_id, self.tenant_id()],
+            map_row_to_run_record,
+        )
+        .optional()
+        .map_err(map_rusqlite_err)
+    }
+
+    fn update_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<&str>,
+    ) -> Result<(), KernelError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin update run status: {}", e)))?;
+        let current: Option<String> = tx
+            .query_row(
+                "SELECT status FROM runtime_runs
+                 WHERE run_id = ?1 AND (?2 IS NULL OR tenant_id = ?2)",
+                params![run_id, self.tenant_id()],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| KernelError::Storage(format!("read run status: {}", e)))?;
+        let Some(current) = current.map(|s| RunRuntimeStatus::from_str(&s)) else {
+            return Err(KernelError::NotFound(format!(
+                "run not found for status update: {}",
+                run_id
+            )));
+        };
+        if current.is_final() && current != status {
+            return Err(KernelError::Conflict(format!(
+                "run {} is already {}",
+                run_id,
+                current.as_str()
+            )));
+        }
+        tx.execute(
+            "UPDATE runtime_runs SET status = ?2, status_reason = ?3, updated_at_ms = ?4
+             WHERE run_id = ?1",
+            params![run_id, status.as_str(), reason, dt_to_ms(Utc::now())],
+        )
+        .map_err(|e| KernelError::Storage(format!("update run status: {}", e)))?;
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit update run status: {}", e)))?;
+        Ok(())
+    }
+
+    fn list_runs(
+        &self,
+        filter: &RunRecordFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunRecord>, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT run_id, workflow_name, status, status_reason, created_at_ms, updated_at_ms, tenant_id
+                 FROM runtime_runs
+                 WHERE (?1 IS NULL OR status = ?1)
+                   AND (?2 IS NULL OR workflow_name = ?2)
+                   AND (?3 IS NULL OR created_at_ms >= ?3)
+                   AND (?4 IS NULL OR created_at_ms < ?4)
+                   AND (?7 IS NULL OR tenant_id = ?7)
+                 ORDER BY updated_at_ms DESC, run_id ASC
+                 LIMIT ?5 OFFSET ?6",
+            )
+            .map_err(|e| KernelError::Storage(format!("prepare list runs: {}", e)))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    filter.status.as_ref().map(|s| s.as_str()),
+                    filter.workflow_name,
+                    filter.created_after.map(dt_to_ms),
+                    filter.created_before.map(dt_to_ms),
+                    page.limit as i64,
+                    page.offset as i64,
+                    self.tenant_id()
+                ],
+                map_row_to_run_record,
+            )
+            .map_err(|e| KernelError::Storage(format!("query list runs: {}", e)))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(map_rusqlite_err)?);
+        }
+        Ok(out)
+    }
+
     // ============== Bounty Methods ==============
 
     fn upsert_bounty(&self, bounty: &BountyRecord) -> Result<(), KernelError> {
@@ -3165,7 +4094,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 bounty.accepted_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert bounty: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert bounty: {}", e)))?;
         Ok(())
     }
 
@@ -3196,7 +4125,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(bounty) => Ok(Some(bounty)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get bounty: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get bounty: {}", e))),
         }
     }
 
@@ -3214,7 +4143,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             Some(s) => {
                 let mut stmt = conn.prepare(
                     "SELECT bounty_id, title, description, reward, status, created_by, created_at_ms, closed_at_ms, accepted_by, accepted_at_ms FROM runtime_bounties WHERE status = ?1 ORDER BY created_at_ms DESC LIMIT ?2"
-                ).map_err(|e| KernelError::Driver(format!("prepare list bounties: {}", e)))?;
+                ).map_err(|e| KernelError::Storage(format!("prepare list bounties: {}", e)))?;
                 let x = stmt
                     .query_map(params![s, limit as i64], |r| {
                         Ok(BountyRecord {
@@ -3230,7 +4159,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                             accepted_at_ms: r.get(9)?,
                         })
                     })
-                    .map_err(|e| KernelError::Driver(format!("query bounties: {}", e)))?
+                    .map_err(|e| KernelError::Storage(format!("query bounties: {}", e)))?
                     .filter_map(|r| r.ok())
                     .collect();
                 x
@@ -3238,7 +4167,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             None => {
                 let mut stmt = conn.prepare(
                     "SELECT bounty_id, title, description, reward, status, created_by, created_at_ms, closed_at_ms, accepted_by, accepted_at_ms FROM runtime_bounties ORDER BY created_at_ms DESC LIMIT ?1"
-                ).map_err(|e| KernelError::Driver(format!("prepare list bounties: {}", e)))?;
+                ).map_err(|e| KernelError::Storage(format!("prepare list bounties: {}", e)))?;
                 let x = stmt
                     .query_map(params![limit as i64], |r| {
                         Ok(BountyRecord {
@@ -3254,7 +4183,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                             accepted_at_ms: r.get(9)?,
                         })
                     })
-                    .map_err(|e| KernelError::Driver(format!("query bounties: {}", e)))?
+                    .map_err(|e| KernelError::Storage(format!("query bounties: {}", e)))?
                     .filter_map(|r| r.ok())
                     .collect();
                 x
@@ -3274,9 +4203,9 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "UPDATE runtime_bounties SET status = 'accepted', accepted_by = ?2, accepted_at_ms = ?3 WHERE bounty_id = ?1 AND status = 'open'",
                 params![bounty_id, accepted_by, now],
             )
-            .map_err(|e| KernelError::Driver(format!("accept bounty: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("accept bounty: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "bounty not found or not in open status: {}",
                 bounty_id
             )));
@@ -3295,9 +4224,9 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "UPDATE runtime_bounties SET status = 'closed', closed_at_ms = ?2 WHERE bounty_id = ?1 AND status IN ('open', 'accepted')",
                 params![bounty_id, now],
             )
-            .map_err(|e| KernelError::Driver(format!("close bounty: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("close bounty: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "bounty not found or already closed: {}",
                 bounty_id
             )));
@@ -3331,7 +4260,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 task.completed_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("upsert swarm: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("upsert swarm: {}", e)))?;
         Ok(())
     }
 
@@ -3364,7 +4293,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(task) => Ok(Some(task)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get swarm: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get swarm: {}", e))),
         }
     }
 
@@ -3394,7 +4323,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 worker.status,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("register worker: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("register worker: {}", e)))?;
         Ok(())
     }
 
@@ -3422,7 +4351,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(worker) => Ok(Some(worker)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get worker: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get worker: {}", e))),
         }
     }
 
@@ -3460,7 +4389,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         };
         let mut stmt = conn
             .prepare(&sql)
-            .map_err(|e| KernelError::Driver(format!("prepare list workers: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare list workers: {}", e)))?;
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         let workers = stmt
             .query_map(params_refs.as_slice(), |r| {
@@ -3474,7 +4403,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     status: r.get(6)?,
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("query workers: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("query workers: {}", e)))?
             .filter_map(|r| r.ok())
             .collect();
         Ok(workers)
@@ -3490,9 +4419,9 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "UPDATE runtime_workers_registry SET last_heartbeat_ms = ?2, status = 'active' WHERE worker_id = ?1",
                 params![worker_id, heartbeat_at_ms],
             )
-            .map_err(|e| KernelError::Driver(format!("heartbeat worker: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("heartbeat worker: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "worker not found: {}",
                 worker_id
             )));
@@ -3522,7 +4451,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 recipe.is_public as i32,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create recipe: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create recipe: {}", e)))?;
         Ok(())
     }
 
@@ -3552,7 +4481,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(recipe) => Ok(Some(recipe)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get recipe: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get recipe: {}", e))),
         }
     }
 
@@ -3583,7 +4512,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     orig.is_public as i32,
                 ],
             )
-            .map_err(|e| KernelError::Driver(format!("fork recipe: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("fork recipe: {}", e)))?;
             Ok(Some(RecipeRecord {
                 recipe_id: new_id.to_string(),
                 name: format!("Fork of {}", orig.name),
@@ -3614,7 +4543,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             Some(aid) => {
                 let mut stmt = conn.prepare(
                     "SELECT recipe_id, name, description, gene_sequence_json, author_id, forked_from, created_at_ms, updated_at_ms, is_public FROM runtime_recipes WHERE author_id = ?1 ORDER BY created_at_ms DESC LIMIT ?2"
-                ).map_err(|e| KernelError::Driver(format!("prepare list recipes: {}", e)))?;
+                ).map_err(|e| KernelError::Storage(format!("prepare list recipes: {}", e)))?;
                 let result: Vec<RecipeRecord> = stmt
                     .query_map(params![aid, limit as i64], |r| {
                         Ok(RecipeRecord {
@@ -3629,7 +4558,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                             is_public: r.get::<_, i32>(8)? != 0,
                         })
                     })
-                    .map_err(|e| KernelError::Driver(format!("query recipes: {}", e)))?
+                    .map_err(|e| KernelError::Storage(format!("query recipes: {}", e)))?
                     .filter_map(|r| r.ok())
                     .collect();
                 result
@@ -3637,7 +4566,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
             None => {
                 let mut stmt = conn.prepare(
                     "SELECT recipe_id, name, description, gene_sequence_json, author_id, forked_from, created_at_ms, updated_at_ms, is_public FROM runtime_recipes ORDER BY created_at_ms DESC LIMIT ?1"
-                ).map_err(|e| KernelError::Driver(format!("prepare list recipes: {}", e)))?;
+                ).map_err(|e| KernelError::Storage(format!("prepare list recipes: {}", e)))?;
                 let result: Vec<RecipeRecord> = stmt
                     .query_map(params![limit as i64], |r| {
                         Ok(RecipeRecord {
@@ -3652,7 +4581,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                             is_public: r.get::<_, i32>(8)? != 0,
                         })
                     })
-                    .map_err(|e| KernelError::Driver(format!("query recipes: {}", e)))?
+                    .map_err(|e| KernelError::Storage(format!("query recipes: {}", e)))?
                     .filter_map(|r| r.ok())
                     .collect();
                 result
@@ -3681,7 +4610,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 organism.completed_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("express organism: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("express organism: {}", e)))?;
         Ok(())
     }
 
@@ -3709,7 +4638,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(organism) => Ok(Some(organism)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get organism: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get organism: {}", e))),
         }
     }
 
@@ -3734,9 +4663,9 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "UPDATE runtime_organisms SET current_step = ?2, status = ?3, completed_at_ms = ?4 WHERE organism_id = ?1",
                 params![organism_id, current_step, status, completed_at_ms],
             )
-            .map_err(|e| KernelError::Driver(format!("update organism: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("update organism: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "organism not found: {}",
                 organism_id
             )));
@@ -3763,7 +4692,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 session.ended_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("create session: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("create session: {}", e)))?;
         Ok(())
     }
 
@@ -3790,7 +4719,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(session) => Ok(Some(session)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get session: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get session: {}", e))),
         }
     }
 
@@ -3811,7 +4740,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 message.sent_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("add session message: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("add session message: {}", e)))?;
         Ok(())
     }
 
@@ -3829,7 +4758,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "SELECT message_id, session_id, sender_id, content, message_type, sent_at_ms
              FROM runtime_collab_messages WHERE session_id = ?1 ORDER BY sent_at_ms DESC LIMIT ?2",
             )
-            .map_err(|e| KernelError::Driver(format!("prepare session history: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("prepare session history: {}", e)))?;
         let messages = stmt
             .query_map(params![session_id, limit as i64], |r| {
                 Ok(SessionMessageRecord {
@@ -3841,7 +4770,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     sent_at_ms: r.get(5)?,
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("query session history: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("query session history: {}", e)))?
             .filter_map(|r| r.ok())
             .collect();
         Ok(messages)
@@ -3869,7 +4798,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 dispute.created_at_ms,
             ],
         )
-        .map_err(|e| KernelError::Driver(format!("open dispute: {}", e)))?;
+        .map_err(|e| KernelError::Storage(format!("open dispute: {}", e)))?;
         Ok(())
     }
 
@@ -3899,7 +4828,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         match result {
             Ok(dispute) => Ok(Some(dispute)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(KernelError::Driver(format!("get dispute: {}", e))),
+            Err(e) => Err(KernelError::Storage(format!("get dispute: {}", e))),
         }
     }
 
@@ -3911,7 +4840,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
         let mut stmt = conn.prepare(
             "SELECT dispute_id, bounty_id, opened_by, status, evidence_json, resolution, resolved_by, resolved_at_ms, created_at_ms
              FROM runtime_disputes WHERE bounty_id = ?1 ORDER BY created_at_ms DESC"
-        ).map_err(|e| KernelError::Driver(format!("prepare disputes: {}", e)))?;
+        ).map_err(|e| KernelError::Storage(format!("prepare disputes: {}", e)))?;
         let disputes = stmt
             .query_map(params![bounty_id], |r| {
                 Ok(DisputeRecord {
@@ -3926,7 +4855,7 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                     created_at_ms: r.get(8)?,
                 })
             })
-            .map_err(|e| KernelError::Driver(format!("query disputes: {}", e)))?
+            .map_err(|e| KernelError::Storage(format!("query disputes: {}", e)))?
             .filter_map(|r| r.ok())
             .collect();
         Ok(disputes)
@@ -3948,15 +4877,155 @@ impl RuntimeRepository for SqliteRuntimeRepository {
                 "UPDATE runtime_disputes SET status = 'resolved', resolution = ?2, resolved_by = ?3, resolved_at_ms = ?4 WHERE dispute_id = ?1 AND status = 'open'",
                 params![dispute_id, resolution, resolved_by, now],
             )
-            .map_err(|e| KernelError::Driver(format!("resolve dispute: {}", e)))?;
+            .map_err(|e| KernelError::Storage(format!("resolve dispute: {}", e)))?;
         if updated == 0 {
-            return Err(KernelError::Driver(format!(
+            return Err(KernelError::NotFound(format!(
                 "dispute not found or already resolved: {}",
                 dispute_id
             )));
         }
         Ok(())
     }
+
+    // ============== Interrupt Methods ==============
+
+    fn create_interrupt(&self, interrupt: &InterruptRecord) -> Result<(), KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        check_run_tenant(&conn, &interrupt.run_id, self.tenant_id())?;
+        let resolution_json = interrupt.resolution.as_ref().map(|v| v.to_string());
+        conn.execute(
+            "INSERT INTO runtime_interrupts
+             (interrupt_id, thread_id, run_id, attempt_id, step_id, value_json, status, created_at_ms, decision, resolution_json, resolved_at_ms)
+             VALUES (?1, ?2, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                interrupt.interrupt_id,
+                interrupt.run_id,
+                interrupt.attempt_id,
+                interrupt.step_id,
+                interrupt.payload.to_string(),
+                interrupt.status.as_str(),
+                dt_to_ms(interrupt.created_at),
+                interrupt.decision.as_ref().map(|d| d.as_str()),
+                resolution_json,
+                interrupt.resolved_at.map(dt_to_ms),
+            ],
+        )
+        .map_err(|e| KernelError::Storage(format!("create interrupt: {}", e)))?;
+        Ok(())
+    }
+
+    fn list_pending_interrupts(
+        &self,
+        filter: &InterruptFilter,
+    ) -> Result<Vec<InterruptRecord>, KernelError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let sql = format!(
+            "SELECT {} FROM runtime_interrupts
+             WHERE status = 'pending' AND (?1 IS NULL OR run_id = ?1)
+               AND (?3 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?3))
+             ORDER BY created_at_ms ASC, interrupt_id ASC
+             LIMIT ?2",
+            INTERRUPT_RECORD_COLUMNS
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| KernelError::Storage(format!("prepare list pending interrupts: {}", e)))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    filter.run_id,
+                    filter.effective_limit() as i64,
+                    self.tenant_id()
+                ],
+                map_row_to_interrupt_record,
+            )
+            .map_err(|e| KernelError::Storage(format!("query list pending interrupts: {}", e)))?;
+        let mut out = Vec::new();
+        for item in rows {
+            out.push(item.map_err(map_rusqlite_err)?);
+        }
+        Ok(out)
+    }
+
+    fn resolve_interrupt(
+        &self,
+        interrupt_id: &str,
+        decision: InterruptDecision,
+        value: &Value,
+    ) -> Result<InterruptRecord, KernelError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| KernelError::Driver("sqlite runtime repo lock poisoned".to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| KernelError::Storage(format!("begin resolve interrupt tx: {}", e)))?;
+        let visible: bool = tx
+            .query_row(
+                "SELECT EXISTS (
+                   SELECT 1 FROM runtime_interrupts
+                   WHERE interrupt_id = ?1 AND (?2 IS NULL OR run_id IN (SELECT run_id FROM runtime_runs WHERE tenant_id = ?2))
+                 )",
+                params![interrupt_id, self.tenant_id()],
+                |r| r.get(0),
+            )
+            .map_err(map_rusqlite_err)?;
+        if !visible {
+            return Err(KernelError::NotFound(format!(
+                "interrupt not found: {}",
+                interrupt_id
+            )));
+        }
+        let updated = tx
+            .execute(
+                "UPDATE runtime_interrupts
+                 SET status = ?2, decision = ?3, resolution_json = ?4, resolved_at_ms = ?5
+                 WHERE interrupt_id = ?1 AND status = 'pending'",
+                params![
+                    interrupt_id,
+                    decision.resolved_status().as_str(),
+                    decision.as_str(),
+                    value.to_string(),
+                    dt_to_ms(Utc::now()),
+                ],
+            )
+            .map_err(|e| KernelError::Storage(format!("resolve interrupt: {}", e)))?;
+        if updated == 0 {
+            let status: Option<String> = tx
+                .query_row(
+                    "SELECT status FROM runtime_interrupts WHERE interrupt_id = ?1",
+                    params![interrupt_id],
+                    |r| r.get(0),
+                )
+                .optional()
+                .map_err(map_rusqlite_err)?;
+            return Err(match status {
+                Some(status) => {
+                    KernelError::Conflict(format!("interrupt {} already {}", interrupt_id, status))
+                }
+                None => KernelError::NotFound(format!("interrupt not found: {}", interrupt_id)),
+            });
+        }
+        let record = tx
+            .query_row(
+                &format!(
+                    "SELECT {} FROM runtime_interrupts WHERE interrupt_id = ?1",
+                    INTERRUPT_RECORD_COLUMNS
+                ),
+                params![interrupt_id],
+                map_row_to_interrupt_record,
+            )
+            .map_err(map_rusqlite_err)?;
+        tx.commit()
+            .map_err(|e| KernelError::Storage(format!("commit resolve interrupt tx: {}", e)))?;
+        Ok(record)
+    }
 }
 
 fn dt_to_ms(dt: DateTime<Utc>) -> i64 {
@@ -3978,6 +5047,7 @@ fn attempt_status_to_str(status: &AttemptExecutionStatus) -> &'static str {
         AttemptExecutionStatus::Completed => "completed",
         AttemptExecutionStatus::Failed => "failed",
         AttemptExecutionStatus::Cancelled => "cancelled",
+        AttemptExecutionStatus::DeadLetter => "dead_letter",
     }
 }
 
@@ -3989,6 +5059,7 @@ fn parse_attempt_status(value: &str) -> AttemptExecutionStatus {
         "completed" => AttemptExecutionStatus::Completed,
         "failed" => AttemptExecutionStatus::Failed,
         "cancelled" => AttemptExecutionStatus::Cancelled,
+        "dead_letter" => AttemptExecutionStatus::DeadLetter,
         _ => AttemptExecutionStatus::Queued,
     }
 }
@@ -4010,7 +5081,7 @@ fn parse_retry_policy_record(
 }
 
 fn map_rusqlite_err(err: rusqlite::Error) -> KernelError {
-    KernelError::Driver(format!("sqlite runtime repo: {}", err))
+    KernelError::Storage(format!("sqlite runtime repo: {}", err))
 }
 
 fn ensure_sqlite_migration_table(conn: &Connection) -> Result<(), KernelError> {
@@ -4023,7 +5094,7 @@ fn ensure_sqlite_migration_table(conn: &Connection) -> Result<(), KernelError> {
         );
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("init sqlite runtime migration table: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("init sqlite runtime migration table: {}", e)))?;
     Ok(())
 }
 
@@ -4033,7 +5104,7 @@ fn sqlite_current_schema_version(conn: &Connection) -> Result<i64, KernelError>
         [],
         |r| r.get(0),
     )
-    .map_err(|e| KernelError::Driver(format!("read sqlite runtime schema version: {}", e)))
+    .map_err(|e| KernelError::Storage(format!("read sqlite runtime schema version: {}", e)))
 }
 
 fn record_sqlite_migration(conn: &Connection, version: i64, name: &str) -> Result<(), KernelError> {
@@ -4043,7 +5114,7 @@ fn record_sqlite_migration(conn: &Connection, version: i64, name: &str) -> Resul
          VALUES (?1, ?2, ?3)",
         params![version, name, now],
     )
-    .map_err(|e| KernelError::Driver(format!("record sqlite runtime migration: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("record sqlite runtime migration: {}", e)))?;
     Ok(())
 }
 
@@ -4122,7 +5193,7 @@ fn apply_sqlite_runtime_migration_v1(conn: &Connection) -> Result<(), KernelErro
         CREATE INDEX IF NOT EXISTS idx_runtime_api_keys_status ON runtime_api_keys(status);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v1: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v1: {}", e)))?;
     Ok(())
 }
 
@@ -4180,7 +5251,7 @@ fn apply_sqlite_runtime_migration_v3(conn: &Connection) -> Result<(), KernelErro
           ON runtime_attempt_retry_history(attempt_id, retry_id ASC);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v3: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v3: {}", e)))?;
     Ok(())
 }
 
@@ -4219,7 +5290,7 @@ fn apply_sqlite_runtime_migration_v5(conn: &Connection) -> Result<(), KernelErro
           ON runtime_dead_letters(replay_status, dead_at_ms DESC);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v5: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v5: {}", e)))?;
     Ok(())
 }
 
@@ -4235,7 +5306,7 @@ fn apply_sqlite_runtime_migration_v6(conn: &Connection) -> Result<(), KernelErro
          ON runtime_attempts(status, priority DESC, retry_at_ms)",
         [],
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v6: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v6: {}", e)))?;
     Ok(())
 }
 
@@ -4246,7 +5317,7 @@ fn apply_sqlite_runtime_migration_v7(conn: &Connection) -> Result<(), KernelErro
          ON runtime_attempts(tenant_id, status, priority DESC)",
         [],
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v7: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v7: {}", e)))?;
     Ok(())
 }
 
@@ -4270,7 +5341,7 @@ fn apply_sqlite_runtime_migration_v8(conn: &Connection) -> Result<(), KernelErro
          ON runtime_attempts(trace_id, attempt_id)",
         [],
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v8: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v8: {}", e)))?;
     Ok(())
 }
 
@@ -4292,7 +5363,7 @@ fn apply_sqlite_runtime_migration_v9(conn: &Connection) -> Result<(), KernelErro
           ON runtime_replay_effects(thread_id, created_at_ms);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v9: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v9: {}", e)))?;
     Ok(())
 }
 
@@ -4315,7 +5386,7 @@ fn apply_sqlite_runtime_migration_v10(conn: &Connection) -> Result<(), KernelErr
           ON runtime_a2a_sessions(expires_at_ms);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v10: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v10: {}", e)))?;
     Ok(())
 }
 
@@ -4340,7 +5411,7 @@ fn apply_sqlite_runtime_migration_v11(conn: &Connection) -> Result<(), KernelErr
           ON runtime_a2a_compat_tasks(lease_expires_at_ms);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v11: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v11: {}", e)))?;
     Ok(())
 }
 
@@ -4393,7 +5464,7 @@ fn apply_sqlite_runtime_migration_v12(conn: &Connection) -> Result<(), KernelErr
         CREATE INDEX IF NOT EXISTS idx_runtime_workers_status ON runtime_workers_registry(status);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v12: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v12: {}", e)))?;
     Ok(())
 }
 
@@ -4466,7 +5537,254 @@ fn apply_sqlite_runtime_migration_v13(conn: &Connection) -> Result<(), KernelErr
         CREATE INDEX IF NOT EXISTS idx_runtime_disputes_status ON runtime_disputes(status);
         "#,
     )
-    .map_err(|e| KernelError::Driver(format!("apply sqlite runtime migration v13: {}", e)))?;
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v13: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v14(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_interrupts", "step_id", "TEXT NULL")?;
+    add_column_if_missing(conn, "runtime_interrupts", "decision", "TEXT NULL")?;
+    add_column_if_missing(conn, "runtime_interrupts", "resolution_json", "TEXT NULL")?;
+    add_column_if_missing(conn, "runtime_interrupts", "resolved_at_ms", "INTEGER NULL")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runtime_interrupts_status_created
+         ON runtime_interrupts(status, created_at_ms)",
+        [],
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v14: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v15(conn: &Connection) -> Result<(), KernelError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS runtime_runs (
+          run_id TEXT PRIMARY KEY,
+          workflow_name TEXT NOT NULL,
+          status TEXT NOT NULL,
+          created_at_ms INTEGER NOT NULL,
+          updated_at_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_runtime_runs_status ON runtime_runs(status);
+        "#,
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v15: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v16(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_attempts", "enqueued_at_ms", "INTEGER NULL")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_priority_enqueued
+         ON runtime_attempts(status, priority DESC, enqueued_at_ms)",
+        [],
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v16: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v17(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_attempts", "last_error", "TEXT NULL")?;
+    add_column_if_missing(
+        conn,
+        "runtime_attempts",
+        "dead_lettered_at_ms",
+        "INTEGER NULL",
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_dead_lettered
+         ON runtime_attempts(status, dead_lettered_at_ms DESC)",
+        [],
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v17: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v18(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_attempts", "run_at_ms", "INTEGER NULL")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runtime_attempts_status_run_at
+         ON runtime_attempts(status, run_at_ms)",
+        [],
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v18: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v19(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(
+        conn,
+        "runtime_attempts",
+        "cancel_requested_at_ms",
+        "INTEGER NULL",
+    )
+}
+
+/// Attempts reference their run. SQLite cannot add a foreign key to an existing table,
+/// so triggers enforce it; runs are recorded first for attempts enqueued without one.
+fn apply_sqlite_runtime_migration_v20(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_runs", "status_reason", "TEXT NULL")?;
+    conn.execute(
+        "INSERT OR IGNORE INTO runtime_runs
+           (run_id, workflow_name, status, created_at_ms, updated_at_ms)
+         SELECT run_id, '', 'queued', ?1, ?1 FROM runtime_attempts GROUP BY run_id",
+        params![dt_to_ms(Utc::now())],
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v20: {}", e)))?;
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_runtime_runs_workflow ON runtime_runs(workflow_name);
+        CREATE INDEX IF NOT EXISTS idx_runtime_runs_updated ON runtime_runs(updated_at_ms);
+        CREATE INDEX IF NOT EXISTS idx_runtime_attempts_run ON runtime_attempts(run_id);
+        CREATE TRIGGER IF NOT EXISTS runtime_attempts_run_fk_insert
+        BEFORE INSERT ON runtime_attempts
+        WHEN NOT EXISTS (SELECT 1 FROM runtime_runs WHERE run_id = NEW.run_id)
+        BEGIN
+          SELECT RAISE(ABORT, 'runtime attempt references unknown run');
+        END;
+        CREATE TRIGGER IF NOT EXISTS runtime_attempts_run_fk_update
+        BEFORE UPDATE OF run_id ON runtime_attempts
+        WHEN NOT EXISTS (SELECT 1 FROM runtime_runs WHERE run_id = NEW.run_id)
+        BEGIN
+          SELECT RAISE(ABORT, 'runtime attempt references unknown run');
+        END;
+        CREATE TRIGGER IF NOT EXISTS runtime_runs_attempt_fk_delete
+        BEFORE DELETE ON runtime_runs
+        WHEN EXISTS (SELECT 1 FROM runtime_attempts WHERE run_id = OLD.run_id)
+        BEGIN
+          SELECT RAISE(ABORT, 'runtime run is referenced by attempts');
+        END;
+        "#,
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v20: {}", e)))?;
+    Ok(())
+}
+
+fn apply_sqlite_runtime_migration_v21(conn: &Connection) -> Result<(), KernelError> {
+    add_column_if_missing(conn, "runtime_runs", "tenant_id", "TEXT NULL")?;
+    add_column_if_missing(conn, "runtime_leases", "tenant_id", "TEXT NULL")?;
+    conn.execute_batch(
+        r#"
+        UPDATE runtime_runs
+        SET tenant_id = (
+          SELECT MAX(a.tenant_id) FROM runtime_attempts a WHERE a.run_id = runtime_runs.run_id
+        )
+        WHERE tenant_id IS NULL;
+        UPDATE runtime_leases
+        SET tenant_id = (
+          SELECT a.tenant_id FROM runtime_attempts a WHERE a.attempt_id = runtime_leases.attempt_id
+        )
+        WHERE tenant_id IS NULL;
+        CREATE INDEX IF NOT EXISTS idx_runtime_runs_tenant ON runtime_runs(tenant_id, created_at_ms);
+        CREATE INDEX IF NOT EXISTS idx_runtime_leases_tenant ON runtime_leases(tenant_id, lease_expires_at_ms);
+        "#,
+    )
+    .map_err(|e| KernelError::Storage(format!("apply sqlite runtime migration v21: {}", e)))?;
+    Ok(())
+}
+
+/// Fails with `NotFound` when `attempt_id` exists but belongs to a tenant other than
+/// `tenant_id`; attempts that do not exist are left for the caller to report.
+fn check_attempt_tenant(
+    conn: &Connection,
+    attempt_id: &str,
+    tenant_id: Option<&str>,
+) -> Result<(), KernelError> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(());
+    };
+    let owner: Option<Option<String>> = conn
+        .query_row(
+            "SELECT tenant_id FROM runtime_attempts WHERE attempt_id = ?1",
+            params![attempt_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| KernelError::Storage(format!("read attempt tenant: {}", e)))?;
+    match owner {
+        Some(owner) if owner.as_deref() != Some(tenant_id) => Err(KernelError::NotFound(
+            format!("attempt not found: {}", attempt_id),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Like [check_attempt_tenant], for runs.
+fn check_run_tenant(
+    conn: &Connection,
+    run_id: &str,
+    tenant_id: Option<&str>,
+) -> Result<(), KernelError> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(());
+    };
+    let owner: Option<Option<String>> = conn
+        .query_row(
+            "SELECT tenant_id FROM runtime_runs WHERE run_id = ?1",
+            params![run_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| KernelError::Storage(format!("read run tenant: {}", e)))?;
+    match owner {
+        Some(owner) if owner.as_deref() != Some(tenant_id) => Err(KernelError::NotFound(format!(
+            "run not found: {}",
+            run_id
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// [LeaseDirective::Cancel] once the attempt held by `lease_id` was asked to cancel.
+fn lease_directive(conn: &Connection, lease_id: &str) -> Result<LeaseDirective, KernelError> {
+    let cancel_requested: Option<bool> = conn
+        .query_row(
+            "SELECT a.cancel_requested_at_ms IS NOT NULL
+             FROM runtime_leases l
+             JOIN runtime_attempts a ON a.attempt_id = l.attempt_id
+             WHERE l.lease_id = ?1",
+            params![lease_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| KernelError::Storage(format!("read lease directive: {}", e)))?;
+    Ok(if cancel_requested == Some(true) {
+        LeaseDirective::Cancel
+    } else {
+        LeaseDirective::Continue
+    })
+}
+
+/// Fails with `LeaseConflict` unless `lease` still holds `attempt_id`, unexpired, at its
+/// version.
+fn check_lease_fence(
+    conn: &Connection,
+    attempt_id: &str,
+    lease: &LeaseFence,
+    now: DateTime<Utc>,
+) -> Result<(), KernelError> {
+    let held: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM runtime_leases
+             WHERE lease_id = ?1 AND attempt_id = ?2 AND worker_id = ?3 AND version = ?4
+               AND lease_expires_at_ms >= ?5",
+            params![
+                lease.lease_id,
+                attempt_id,
+                lease.worker_id,
+                lease.version as i64,
+                dt_to_ms(now)
+            ],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| KernelError::Storage(format!("check lease fence: {}", e)))?;
+    if held.is_none() {
+        return Err(KernelError::LeaseConflict(format!(
+            "lease {} (worker {}, version {}) no longer holds attempt: {}",
+            lease.lease_id, lease.worker_id, lease.version, attempt_id
+        )));
+    }
     Ok(())
 }
 
@@ -4479,10 +5797,10 @@ fn add_column_if_missing(
     let pragma = format!("PRAGMA table_info({})", table);
     let mut stmt = conn
         .prepare(&pragma)
-        .map_err(|e| KernelError::Driver(format!("prepare table_info {}: {}", table, e)))?;
+        .map_err(|e| KernelError::Storage(format!("prepare table_info {}: {}", table, e)))?;
     let cols = stmt
         .query_map([], |row| row.get::<_, String>(1))
-        .map_err(|e| KernelError::Driver(format!("query table_info {}: {}", table, e)))?;
+        .map_err(|e| KernelError::Storage(format!("query table_info {}: {}", table, e)))?;
     for col in cols {
         let name = col.map_err(map_rusqlite_err)?;
         if name == column {
@@ -4490,8 +5808,9 @@ fn add_column_if_missing(
         }
     }
     let alter = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, column_def);
-    conn.execute(&alter, [])
-        .map_err(|e| KernelError::Driver(format!("alter table {} add {}: {}", table, column, e)))?;
+    conn.execute(&alter, []).map_err(|e| {
+        KernelError::Storage(format!("alter table {} add {}: {}", table, column, e))
+    })?;
     Ok(())
 }
 
@@ -4507,9 +5826,14 @@ mod tests {
         apply_sqlite_runtime_migration_v1, ensure_sqlite_migration_table, record_sqlite_migration,
         A2aCompatTaskRow, A2aSessionRow, OrganismRow, RecipeRow, ReplayEffectClaim,
         RetryPolicyConfig, RetryStrategy, SqliteRuntimeRepository, TimeoutPolicyConfig,
-        SQLITE_RUNTIME_SCHEMA_VERSION,
+        SQLITE_RUNTIME_MIGRATIONS, SQLITE_RUNTIME_SCHEMA_VERSION,
+    };
+    use oris_kernel::event::KernelError;
+
+    use crate::models::{
+        AttemptExecutionStatus, InterruptDecision, InterruptFilter, InterruptRecord,
+        InterruptStatus, RunRecord, RunRuntimeStatus,
     };
-    use crate::models::AttemptExecutionStatus;
     use crate::repository::RuntimeRepository;
 
     fn temp_sqlite_path(name: &str) -> PathBuf {
@@ -4539,6 +5863,22 @@ mod tests {
         .unwrap_or(false)
     }
 
+    fn seed_run(repo: &SqliteRuntimeRepository, run_id: &str) {
+        let now = Utc::now();
+        match repo.create_run(&RunRecord {
+            run_id: run_id.to_string(),
+            workflow_name: "test".to_string(),
+            status: RunRuntimeStatus::Queued,
+            status_reason: None,
+            tenant_id: None,
+            created_at: now,
+            updated_at: now,
+        }) {
+            Ok(()) | Err(KernelError::Conflict(_)) => {}
+            Err(e) => panic!("seed run {}: {}", run_id, e),
+        }
+    }
+
     fn migration_version(conn: &Connection) -> i64 {
         conn.query_row(
             "SELECT COALESCE(MAX(version), 0) FROM runtime_schema_migrations",
@@ -4567,6 +5907,27 @@ mod tests {
             "resume_response_json"
         ));
         assert!(column_exists(&conn, "runtime_interrupts", "resumed_at_ms"));
+        assert!(column_exists(&conn, "runtime_interrupts", "step_id"));
+        assert!(table_exists(&conn, "runtime_runs"));
+        assert!(column_exists(&conn, "runtime_runs", "status_reason"));
+        assert!(column_exists(&conn, "runtime_attempts", "enqueued_at_ms"));
+        assert!(column_exists(&conn, "runtime_attempts", "last_error"));
+        assert!(column_exists(&conn, "runtime_attempts", "run_at_ms"));
+        assert!(column_exists(
+            &conn,
+            "runtime_attempts",
+            "cancel_requested_at_ms"
+        ));
+        assert!(column_exists(
+            &conn,
+            "runtime_attempts",
+            "dead_lettered_at_ms"
+        ));
+        assert!(column_exists(
+            &conn,
+            "runtime_interrupts",
+            "resolution_json"
+        ));
         assert!(column_exists(&conn, "runtime_api_keys", "role"));
         assert!(column_exists(&conn, "runtime_attempts", "retry_strategy"));
         assert!(column_exists(&conn, "runtime_attempts", "retry_backoff_ms"));
@@ -4627,6 +5988,12 @@ mod tests {
                 "resume_payload_hash"
             ));
             assert!(!column_exists(&conn, "runtime_api_keys", "role"));
+            conn.execute(
+                "INSERT INTO runtime_attempts (attempt_id, run_id, attempt_no, status)
+                 VALUES ('legacy-attempt', 'legacy-run', 1, 'queued')",
+                [],
+            )
+            .expect("insert attempt without a run");
         }
 
         let path_str = path.to_string_lossy().to_string();
@@ -4645,6 +6012,36 @@ mod tests {
             "resume_response_json"
         ));
         assert!(column_exists(&conn, "runtime_interrupts", "resumed_at_ms"));
+        assert!(column_exists(&conn, "runtime_interrupts", "step_id"));
+        assert!(table_exists(&conn, "runtime_runs"));
+        assert!(column_exists(&conn, "runtime_runs", "status_reason"));
+        let backfilled: Option<String> = conn
+            .query_row(
+                "SELECT status FROM runtime_runs WHERE run_id = 'legacy-run'",
+                [],
+                |r| r.get(0),
+            )
+            .optional()
+            .expect("query backfilled run");
+        assert_eq!(backfilled.as_deref(), Some("queued"));
+        assert!(column_exists(&conn, "runtime_attempts", "enqueued_at_ms"));
+        assert!(column_exists(&conn, "runtime_attempts", "last_error"));
+        assert!(column_exists(&conn, "runtime_attempts", "run_at_ms"));
+        assert!(column_exists(
+            &conn,
+            "runtime_attempts",
+            "cancel_requested_at_ms"
+        ));
+        assert!(column_exists(
+            &conn,
+            "runtime_attempts",
+            "dead_lettered_at_ms"
+        ));
+        assert!(column_exists(
+            &conn,
+            "runtime_interrupts",
+            "resolution_json"
+        ));
         assert!(column_exists(&conn, "runtime_api_keys", "role"));
         assert!(column_exists(&conn, "runtime_attempts", "retry_strategy"));
         assert!(column_exists(&conn, "runtime_attempts", "retry_backoff_ms"));
@@ -4792,6 +6189,67 @@ mod tests {
         let _ = fs::remove_file(path);
     }
 
+    #[test]
+    fn schema_migrations_are_numbered_in_order() {
+        for (index, migration) in SQLITE_RUNTIME_MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i64 + 1, "{}", migration.name);
+        }
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite runtime repo");
+        assert_eq!(
+            repo.schema_version().expect("schema version"),
+            SQLITE_RUNTIME_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn schema_newer_than_supported_is_rejected_with_upgrade_hint() {
+        let path = temp_sqlite_path("schema-too-new");
+        let path_str = path.to_string_lossy().to_string();
+        drop(SqliteRuntimeRepository::new(&path_str).expect("create sqlite runtime repo"));
+        {
+            let conn = Connection::open(&path).expect("open sqlite db");
+            record_sqlite_migration(&conn, SQLITE_RUNTIME_SCHEMA_VERSION + 1, "from_the_future")
+                .expect("record future migration");
+        }
+
+        match SqliteRuntimeRepository::new(&path_str) {
+            Err(KernelError::Driver(msg)) => assert!(msg.contains("upgrade"), "{}", msg),
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("a newer schema should be rejected"),
+        }
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn concurrent_opens_migrate_the_schema_once() {
+        let path = temp_sqlite_path("schema-concurrent");
+        let path_str = path.to_string_lossy().to_string();
+        let openers: Vec<_> = (0..4)
+            .map(|_| {
+                let path_str = path_str.clone();
+                std::thread::spawn(move || SqliteRuntimeRepository::new(&path_str).map(|_| ()))
+            })
+            .collect();
+        for opener in openers {
+            opener
+                .join()
+                .expect("opener thread")
+                .expect("open sqlite runtime repo");
+        }
+
+        let conn = Connection::open(&path).expect("open sqlite db");
+        assert_eq!(migration_version(&conn), SQLITE_RUNTIME_SCHEMA_VERSION);
+        let recorded: i64 = conn
+            .query_row("SELECT COUNT(*) FROM runtime_schema_migrations", [], |r| {
+                r.get(0)
+            })
+            .expect("count migrations");
+        assert_eq!(recorded, SQLITE_RUNTIME_SCHEMA_VERSION);
+
+        let _ = fs::remove_file(path);
+    }
+
     #[test]
     fn a2a_session_upsert_roundtrip_and_expiry_filtering() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite runtime repo");
@@ -5212,6 +6670,7 @@ mod tests {
     #[test]
     fn attempt_trace_context_round_trip_and_advances() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-trace-1");
         repo.enqueue_attempt("attempt-trace-1", "run-trace-1")
             .expect("enqueue trace attempt");
         repo.set_attempt_trace_context(
@@ -5255,6 +6714,7 @@ mod tests {
     #[test]
     fn ack_attempt_exponential_backoff_respects_cap_and_max_retries() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-retry-exp");
         repo.enqueue_attempt("attempt-retry-exp", "run-retry-exp")
             .expect("enqueue retry attempt");
         let policy = RetryPolicyConfig {
@@ -5314,6 +6774,7 @@ mod tests {
     #[test]
     fn transition_timed_out_attempts_applies_configured_terminal_status() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-timeout-1");
         repo.enqueue_attempt("attempt-timeout-1", "run-timeout-1")
             .expect("enqueue timeout attempt");
         repo.set_attempt_timeout_policy(
@@ -5357,6 +6818,7 @@ mod tests {
     #[test]
     fn final_failed_attempts_are_persisted_to_dead_letter_queue_and_replayable() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-dlq-1");
         repo.enqueue_attempt("attempt-dlq-1", "run-dlq-1")
             .expect("enqueue dlq attempt");
 
@@ -5395,6 +6857,7 @@ mod tests {
     #[test]
     fn list_dispatchable_attempts_prefers_higher_priority_first() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-priority");
         repo.enqueue_attempt("attempt-priority-low", "run-priority")
             .expect("enqueue low priority");
         repo.enqueue_attempt("attempt-priority-high", "run-priority")
@@ -5409,12 +6872,14 @@ mod tests {
             .expect("list dispatchable attempts");
         assert_eq!(rows.len(), 2);
         assert_eq!(rows[0].attempt_id, "attempt-priority-high");
+        assert_eq!(rows[0].priority, 90);
         assert_eq!(rows[1].attempt_id, "attempt-priority-low");
     }
 
     #[test]
     fn heartbeat_lease_with_version_rejects_split_brain_owner_or_stale_version() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
+        seed_run(&repo, "run-split-brain");
         repo.enqueue_attempt("attempt-split-brain", "run-split-brain")
             .expect("enqueue split-brain attempt");
         let lease = repo
@@ -5463,6 +6928,7 @@ mod tests {
     fn expire_leases_and_requeue_respects_stale_cutoff() {
         let repo = SqliteRuntimeRepository::new(":memory:").expect("create sqlite repo");
         let now = Utc::now();
+        seed_run(&repo, "run-grace");
         repo.enqueue_attempt("attempt-grace", "run-grace")
             .expect("enqueue attempt");
         repo.upsert_lease("attempt-grace", "worker-grace", now - Duration::seconds(1))
@@ -5847,4 +7313,43 @@ mod tests {
         assert_eq!(retrieved.forked_from, Some("original-recipe".to_string()));
         assert_eq!(retrieved.author_id, "author-forker");
     }
+
+    #[test]
+    fn reopen_interrupt_clears_its_resolution() {
+        let repo = SqliteRuntimeRepository::new(":memory:").expect("create repo");
+        seed_run(&repo, "run-reopen");
+        repo.create_interrupt(&InterruptRecord {
+            interrupt_id: "int-reopen".to_string(),
+            run_id: "run-reopen".to_string(),
+            attempt_id: "attempt-reopen".to_string(),
+            step_id: None,
+            payload: serde_json::json!("approve?"),
+            status: InterruptStatus::Pending,
+            created_at: Utc::now(),
+            decision: None,
+            resolution: None,
+            resolved_at: None,
+        })
+        .expect("create interrupt");
+        repo.resolve_interrupt(
+            "int-reopen",
+            InterruptDecision::Approve,
+            &serde_json::json!(true),
+        )
+        .expect("resolve interrupt");
+
+        repo.reopen_interrupt("int-reopen")
+            .expect("reopen interrupt");
+        let pending = repo
+            .list_pending_interrupts(&InterruptFilter::for_run("run-reopen"))
+            .expect("list pending interrupts");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].decision, None);
+        assert_eq!(pending[0].resolution, None);
+        assert_eq!(pending[0].resolved_at, None);
+        assert!(matches!(
+            repo.reopen_interrupt("int-missing"),
+            Err(KernelError::NotFound(_))
+        ));
+    }
 }
@@ -15,17 +15,20 @@ pub use oris_runtime::execution_runtime::{
     canonical_runtime_api_contract_path, generate_runtime_api_contract,
     runtime_api_contract_pretty_json, write_runtime_api_contract, ApiEnvelope, ApiError, ApiMeta,
     AttemptRetryHistoryItem, AttemptRetryHistoryResponse, AuditLogItem, AuditLogListResponse,
-    CancelJobRequest, CancelJobResponse, CheckpointInspectResponse, DeadLetterItem,
-    DeadLetterListResponse, DeadLetterReplayResponse, ExecutionCheckpointView,
-    ExecutionGraphBridge, ExecutionGraphBridgeError, ExecutionGraphBridgeErrorKind,
-    ExecutionInvokeView, ExecutionStateView, InterruptDetailResponse, InterruptListResponse,
-    JobDetailResponse, JobHistoryItem, JobHistoryResponse, JobStateResponse, JobTimelineItem,
-    JobTimelineResponse, KernelObservability, ListAuditLogsQuery, ListDeadLettersQuery,
-    ListInterruptsQuery, ListJobsQuery, ListJobsResponse, RejectInterruptRequest, ReplayJobRequest,
-    ResumeInterruptRequest, ResumeJobRequest, RetryPolicyRequest, RunJobRequest, RunJobResponse,
-    TimelineExportResponse, TimeoutPolicyRequest, TraceContextResponse, WorkerAckRequest,
-    WorkerAckResponse, WorkerExtendLeaseRequest, WorkerHeartbeatRequest, WorkerLeaseResponse,
-    WorkerPollRequest, WorkerPollResponse, WorkerReportStepRequest, RUNTIME_API_CONTRACT_DOC_PATH,
+    CancelJobRequest, CancelJobResponse, CheckpointInspectResponse, CreateRunRequest,
+    CreateRunResponse, DeadLetterItem, DeadLetterListResponse, DeadLetterReplayResponse,
+    ExecutionCheckpointView, ExecutionGraphBridge, ExecutionGraphBridgeError,
+    ExecutionGraphBridgeErrorKind, ExecutionInvokeView, ExecutionStateView,
+    InterruptDetailResponse, InterruptListResponse, JobDetailResponse, JobHistoryItem,
+    JobHistoryResponse, JobRunMode, JobStateResponse, JobTimelineItem, JobTimelineResponse,
+    KernelObservability, ListAuditLogsQuery, ListDeadLettersQuery, ListInterruptsQuery,
+    ListJobsQuery, ListJobsResponse, PauseJobResponse, RejectInterruptRequest, ReplayJobRequest,
+    ResolveInterruptRequest, ResolveInterruptResponse, ResumeInterruptRequest, ResumeJobRequest,
+    ResumeRunRequest, RetryPolicyRequest, RunEventsQuery, RunJobRequest, RunJobResponse,
+    RunStatusResponse, TimelineExportResponse, TimeoutPolicyRequest, TraceContextResponse,
+    WorkerAckRequest, WorkerAckResponse, WorkerExtendLeaseRequest, WorkerHeartbeatRequest,
+    WorkerLeaseResponse, WorkerPollRequest, WorkerPollResponse, WorkerReportStepRequest,
+    RUNTIME_API_CONTRACT_DOC_PATH,
 };
 
 #[cfg(feature = "sqlite-persistence")]
@@ -47,6 +47,7 @@ mod smoke {
             idempotency_key: None,
             idempotent_replay: false,
             trace: None,
+            mode: Default::default(),
         };
         let json = serde_json::to_string(&resp).expect("RunJobResponse should serialize to JSON");
         assert!(json.contains("t-xyz"));
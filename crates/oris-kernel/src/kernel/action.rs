@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::kernel::identity::RunId;
+use crate::kernel::risk_tier::{RiskTier, RiskTierRegistry};
 use crate::kernel::KernelError;
 
 /// System action: the only way the kernel interacts with the outside world.
@@ -17,6 +18,9 @@ pub enum Action {
         tool: String,
         /// JSON input passed to the tool.
         input: Value,
+        /// Names the call's side effect for deduplication; see [Action::idempotency_key].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idempotency_key: Option<String>,
     },
     /// Invoke an LLM provider with a JSON input.
     CallLLM {
@@ -24,6 +28,9 @@ pub enum Action {
         provider: String,
         /// JSON input (prompt, messages, params).
         input: Value,
+        /// Names the call's side effect for deduplication; see [Action::idempotency_key].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idempotency_key: Option<String>,
     },
     /// Suspend execution for a fixed duration.
     Sleep {
@@ -37,6 +44,73 @@ pub enum Action {
     },
 }
 
+impl Action {
+    /// The routing key of the action, which
+    /// [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry) dispatches on: the
+    /// tool name for `CallTool` (so tools named `http/get`, `http/post` share the `http/`
+    /// namespace), `llm/<provider>` for `CallLLM`, `sleep`, and `signal/<name>` for
+    /// `WaitSignal`.
+    pub fn kind(&self) -> String {
+        match self {
+            Action::CallTool { tool, .. } => tool.clone(),
+            Action::CallLLM { provider, .. } => format!("llm/{}", provider),
+            Action::Sleep { .. } => "sleep".into(),
+            Action::WaitSignal { name } => format!("signal/{}", name),
+        }
+    }
+
+    /// The action's [RiskTier] as `tiers` classifies its [kind](Self::kind). Actions carry
+    /// no tier of their own, so a step cannot downgrade what it asks for.
+    pub fn risk_tier(&self, tiers: &RiskTierRegistry) -> RiskTier {
+        tiers.tier_of(self)
+    }
+
+    /// The key under which [IdempotentActionExecutor](crate::kernel::IdempotentActionExecutor)
+    /// caches the result of a tool or LLM call. A step may set its own; otherwise the driver
+    /// hands the executor the action with [default_idempotency_key] filled in. `Sleep` and
+    /// `WaitSignal` have none.
+    pub fn idempotency_key(&self) -> Option<&str> {
+        match self {
+            Action::CallTool {
+                idempotency_key, ..
+            }
+            | Action::CallLLM {
+                idempotency_key, ..
+            } => idempotency_key.as_deref(),
+            Action::Sleep { .. } | Action::WaitSignal { .. } => None,
+        }
+    }
+
+    /// The action with `key` as its idempotency key; `Sleep` and `WaitSignal` are returned
+    /// unchanged.
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        if let Action::CallTool {
+            idempotency_key, ..
+        }
+        | Action::CallLLM {
+            idempotency_key, ..
+        } = &mut self
+        {
+            *idempotency_key = Some(key.into());
+        }
+        self
+    }
+
+    /// Whether the action can carry an idempotency key.
+    pub(crate) fn takes_idempotency_key(&self) -> bool {
+        matches!(self, Action::CallTool { .. } | Action::CallLLM { .. })
+    }
+}
+
+/// The idempotency key the driver gives a tool or LLM call that has none of its own: the
+/// run, the `step_id` of the run's latest `StateUpdated`, and how many actions completed
+/// since then (plus the action's position in its batch). A step that crashed before its
+/// result was appended requests the action again at the same position, so it gets the
+/// same key.
+pub fn default_idempotency_key(run_id: &RunId, step_id: Option<&str>, index: u32) -> String {
+    format!("{}:{}:{}", run_id, step_id.unwrap_or_default(), index)
+}
+
 /// Result of executing an action (must be turned into events by the driver).
 #[derive(Clone, Debug)]
 pub enum ActionResult {
@@ -46,8 +120,27 @@ pub enum ActionResult {
     Failure(String),
 }
 
+/// Key of the metadata map an executor may put in a successful output object to report
+/// what the action consumed, under the standard entries [METADATA_TOKENS] and
+/// [METADATA_COST_CENTS], e.g. `{"text": "...", "_metadata": {"tokens": 812, "cost_cents": 1.2}}`.
+/// The metadata is logged with the output, and the driver adds it up for
+/// [Policy::check_budget](crate::kernel::Policy::check_budget).
+pub const ACTION_METADATA_KEY: &str = "_metadata";
+
+/// Metadata entry: tokens the action consumed (an integer).
+pub const METADATA_TOKENS: &str = "tokens";
+
+/// Metadata entry: what the action cost, in cents (a number).
+pub const METADATA_COST_CENTS: &str = "cost_cents";
+
+/// The metadata map of an action output, if its executor reported one; see
+/// [ACTION_METADATA_KEY].
+pub fn output_metadata(output: &Value) -> Option<&serde_json::Map<String, Value>> {
+    output.get(ACTION_METADATA_KEY)?.as_object()
+}
+
 /// Classifies executor errors for policy (retry vs fail, backoff, rate-limit).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ActionErrorKind {
     /// Transient (e.g. network blip); policy may retry.
     Transient,
@@ -55,6 +148,24 @@ pub enum ActionErrorKind {
     Permanent,
     /// Rate-limited (e.g. 429); retry after retry_after_ms if set.
     RateLimited,
+    /// No executor handles the action's [kind](Action::kind); do not retry.
+    UnknownKind,
+    /// The executor did not return within [Policy::action_timeout](crate::kernel::Policy::action_timeout);
+    /// policy may retry.
+    Timeout,
+}
+
+impl ActionErrorKind {
+    /// Reason code of the kind, as recorded in `PolicyDecision` events, e.g. `ACTION_TIMEOUT`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Transient => "ACTION_TRANSIENT",
+            Self::Permanent => "ACTION_PERMANENT",
+            Self::RateLimited => "ACTION_RATE_LIMITED",
+            Self::UnknownKind => "ACTION_UNKNOWN_KIND",
+            Self::Timeout => "ACTION_TIMEOUT",
+        }
+    }
 }
 
 /// Structured error from action execution; used by Policy for retry decisions.
@@ -69,7 +180,7 @@ pub struct ActionError {
 }
 
 impl ActionError {
-    /// Creates a transient error (network blip, timeout). The policy may retry.
+    /// Creates a transient error (e.g. a network blip). The policy may retry.
     pub fn transient(message: impl Into<String>) -> Self {
         Self {
             kind: ActionErrorKind::Transient,
@@ -96,6 +207,24 @@ impl ActionError {
         }
     }
 
+    /// Creates the error for an action of `kind` that no executor handles. Never retried.
+    pub fn unknown_kind(kind: &str) -> Self {
+        Self {
+            kind: ActionErrorKind::UnknownKind,
+            message: format!("no executor registered for action kind '{}'", kind),
+            retry_after_ms: None,
+        }
+    }
+
+    /// Creates the error the driver records for an attempt that ran longer than `limit`.
+    pub fn timeout(limit: std::time::Duration) -> Self {
+        Self {
+            kind: ActionErrorKind::Timeout,
+            message: format!("action timed out after {} ms", limit.as_millis()),
+            retry_after_ms: None,
+        }
+    }
+
     /// Convert a generic executor error (KernelError) into an ActionError for policy.
     /// Used by the driver when the executor returns Err(KernelError).
     pub fn from_kernel_error(e: &KernelError) -> Self {
@@ -121,4 +250,62 @@ pub trait ActionExecutor: Send + Sync {
     /// Return `Ok(ActionResult::Failure(_))` for logical failures the policy should not retry.
     /// Return `Err(KernelError::Executor(_))` to let the policy decide on retry.
     fn execute(&self, run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError>;
+
+    /// Names the executor that would run `action`, for [PolicyCtx::executor]; `None` when
+    /// this executor does not dispatch. Routing executors such as
+    /// [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry) override it, and
+    /// wrappers forward it.
+    ///
+    /// [PolicyCtx::executor]: crate::kernel::PolicyCtx::executor
+    fn route(&self, action: &Action) -> Option<String> {
+        let _ = action;
+        None
+    }
+
+    /// Executes the action the driver requested as `action_id`. The driver calls this rather
+    /// than [execute](Self::execute), so executors that record or replay actions know which
+    /// request they serve even when several run at once; the default calls `execute`.
+    fn execute_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        let _ = action_id;
+        self.execute(run_id, action)
+    }
+
+    /// Called instead of [execute_requested](Self::execute_requested) for an action of a
+    /// fail-fast batch that is skipped because a sibling failed. The default fails it
+    /// without executing; replaying executors return what the recorded run got instead.
+    fn skip_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        let _ = (run_id, action_id, action);
+        Ok(ActionResult::Failure(SKIPPED_ACTION_ERROR.into()))
+    }
+
+    /// Called before the driver executes the actions of a batch concurrently, after it
+    /// appended their `ActionRequested` events. Executors that append to the run's log
+    /// should hold those appends until [end_batch](Self::end_batch) and write them in
+    /// request order, so the log does not depend on which action finished first.
+    fn begin_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        let _ = run_id;
+        Ok(())
+    }
+
+    /// Called once every action of the batch has its result, before the driver appends them.
+    fn end_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        let _ = run_id;
+        Ok(())
+    }
 }
+
+/// Error recorded for the actions of a fail-fast batch skipped after a sibling failed.
+pub const SKIPPED_ACTION_ERROR: &str = "skipped: another action of the batch failed";
+
+/// Error recorded for an action aborted because its run was cancelled.
+pub const CANCELLED_ACTION_ERROR: &str = "cancelled: the run was cancelled";
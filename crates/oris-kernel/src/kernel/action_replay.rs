@@ -0,0 +1,710 @@
+//! Recording action results and substituting them on replay.
+//!
+//! Running a step function again over a recorded run must not repeat its side effects.
+//! During a normal run, [RecordingActionExecutor] executes each action with the wrapped
+//! executor and appends an [Event::ActionRecorded] holding the action and what the
+//! executor returned. [ReplayActionExecutor] serves a later run of the same step function
+//! (e.g. a shadow run under another run id) from those events and calls no executor.
+//!
+//! Actions are matched by position: the `step_id` of the run's latest `StateUpdated` and
+//! the action's index among the `ActionRequested` events since then. Every attempt of a
+//! retried action is recorded and replayed in turn. A replay that requests another action,
+//! or one that was never recorded, fails the run with [KernelError::ActionReplayMismatch].
+//!
+//! The actions of a [Next::DoAll](crate::kernel::Next::DoAll) batch are recorded in batch
+//! order once the whole batch is done, whatever order they finished in, and an action
+//! skipped by a fail-fast batch is recorded like any other. Replay returns each action's
+//! recorded outcome, skipped or not, so the replayed log matches the recording.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::kernel::action::{Action, ActionError, ActionErrorKind, ActionExecutor, ActionResult};
+use crate::kernel::event::{
+    Event, EventFilter, EventKind, EventStore, KernelError, SequencedEvent,
+};
+use crate::kernel::identity::{RunId, Seq};
+
+/// What an executor returned for one attempt of an action, as stored in
+/// [Event::ActionRecorded].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedOutcome {
+    /// [ActionResult::Success]
+    Success(Value),
+    /// [ActionResult::Failure]
+    Failure(String),
+    /// The executor returned an error. Errors other than [KernelError::Executor] are
+    /// recorded as permanent, which is how the driver treats them.
+    Error {
+        kind: ActionErrorKind,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retry_after_ms: Option<u64>,
+    },
+}
+
+impl RecordedOutcome {
+    pub fn from_result(result: &Result<ActionResult, KernelError>) -> Self {
+        match result {
+            Ok(ActionResult::Success(output)) => RecordedOutcome::Success(output.clone()),
+            Ok(ActionResult::Failure(error)) => RecordedOutcome::Failure(error.clone()),
+            Err(e) => {
+                let error = ActionError::from_kernel_error(e);
+                RecordedOutcome::Error {
+                    kind: error.kind,
+                    message: error.message,
+                    retry_after_ms: error.retry_after_ms,
+                }
+            }
+        }
+    }
+
+    /// The result to hand back to the driver in place of executing the action.
+    pub fn into_result(self) -> Result<ActionResult, KernelError> {
+        match self {
+            RecordedOutcome::Success(output) => Ok(ActionResult::Success(output)),
+            RecordedOutcome::Failure(error) => Ok(ActionResult::Failure(error)),
+            RecordedOutcome::Error {
+                kind,
+                message,
+                retry_after_ms,
+            } => Err(KernelError::Executor(ActionError {
+                kind,
+                message,
+                retry_after_ms,
+            })),
+        }
+    }
+}
+
+/// Where the action being executed sits in its run's log
+struct ActionPosition {
+    step_id: Option<String>,
+    index: u32,
+    action_id: String,
+}
+
+/// Position of the action requested as `action_id` in `run_id`, or of the one the driver
+/// requested last; executors are called right after the driver appends its `ActionRequested`.
+fn current_position(
+    store: &dyn EventStore,
+    run_id: &RunId,
+    action_id: Option<&str>,
+) -> Result<ActionPosition, KernelError> {
+    let last_update = store
+        .scan_rev(run_id, 1, &EventFilter::only([EventKind::StateUpdated]))?
+        .pop();
+    let (from, step_id) = match last_update {
+        Some(SequencedEvent {
+            seq,
+            event: Event::StateUpdated { step_id, .. },
+            ..
+        }) => (seq + 1, step_id),
+        _ => (1, None),
+    };
+    let requested = store.scan_range(
+        run_id,
+        from,
+        Seq::MAX,
+        &EventFilter::only([EventKind::ActionRequested]),
+    )?;
+    let found = match action_id {
+        Some(wanted) => requested.iter().enumerate().find(|(_, se)| {
+            matches!(&se.event, Event::ActionRequested { action_id, .. } if action_id == wanted)
+        }),
+        None => requested.iter().enumerate().next_back(),
+    };
+    match found {
+        Some((
+            index,
+            SequencedEvent {
+                event: Event::ActionRequested { action_id, .. },
+                ..
+            },
+        )) => Ok(ActionPosition {
+            step_id,
+            index: index as u32,
+            action_id: action_id.clone(),
+        }),
+        _ => Err(KernelError::Driver(format!(
+            "run {} has no requested action to record or replay; these executors must be \
+             called by the kernel driver",
+            run_id
+        ))),
+    }
+}
+
+/// The action as recorded and compared. Idempotency keys are left out: the driver's default
+/// ones name the run, and a replay runs under another run id.
+fn action_value(action: &Action) -> Result<Value, KernelError> {
+    let mut value = serde_json::to_value(action).map_err(|e| KernelError::Driver(e.to_string()))?;
+    if let Some(fields) = value
+        .as_object_mut()
+        .and_then(|variant| variant.values_mut().next())
+        .and_then(Value::as_object_mut)
+    {
+        fields.remove("idempotency_key");
+    }
+    Ok(value)
+}
+
+/// Records held back while a batch executes, per run and with their action index
+type HeldRecords = HashMap<RunId, Vec<(u32, Event)>>;
+
+/// Executes actions with the wrapped executor and records each attempt's outcome in the
+/// run's log as an [Event::ActionRecorded].
+///
+/// `events` must be the log the kernel writes, e.g. an `Arc` of the store shared with
+/// the kernel (`Box::new(arc.clone())`).
+pub struct RecordingActionExecutor<E: ActionExecutor> {
+    inner: E,
+    events: Arc<dyn EventStore>,
+    batches: Mutex<HeldRecords>,
+}
+
+impl<E: ActionExecutor> RecordingActionExecutor<E> {
+    pub fn new(inner: E, events: Arc<dyn EventStore>) -> Self {
+        Self {
+            inner,
+            events,
+            batches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    fn batches(&self) -> Result<MutexGuard<'_, HeldRecords>, KernelError> {
+        self.batches
+            .lock()
+            .map_err(|e| KernelError::Driver(e.to_string()))
+    }
+
+    /// Runs `attempt` for the action at `action_id` (the latest request if `None`) and
+    /// records its outcome, or holds the record back if a batch of the run is executing.
+    fn record(
+        &self,
+        run_id: &RunId,
+        action_id: Option<&str>,
+        action: &Action,
+        attempt: impl FnOnce() -> Result<ActionResult, KernelError>,
+    ) -> Result<ActionResult, KernelError> {
+        let position = current_position(self.events.as_ref(), run_id, action_id)?;
+        let action_json = action_value(action)?;
+        let result = attempt();
+        let outcome = serde_json::to_value(RecordedOutcome::from_result(&result))
+            .map_err(|e| KernelError::Driver(e.to_string()))?;
+        let recorded = Event::ActionRecorded {
+            action_id: position.action_id,
+            step_id: position.step_id,
+            index: position.index,
+            action: action_json,
+            outcome,
+        };
+        if let Some(held) = self.batches()?.get_mut(run_id) {
+            held.push((position.index, recorded));
+            return result;
+        }
+        self.events.append(run_id, &[recorded])?;
+        result
+    }
+}
+
+impl<E: ActionExecutor> ActionExecutor for RecordingActionExecutor<E> {
+    fn execute(&self, run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        self.record(run_id, None, action, || self.inner.execute(run_id, action))
+    }
+
+    fn execute_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.record(run_id, Some(action_id), action, || {
+            self.inner.execute_requested(run_id, action_id, action)
+        })
+    }
+
+    fn skip_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.record(run_id, Some(action_id), action, || {
+            self.inner.skip_requested(run_id, action_id, action)
+        })
+    }
+
+    fn begin_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.batches()?.insert(run_id.clone(), Vec::new());
+        self.inner.begin_batch(run_id)
+    }
+
+    fn end_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        let ended = self.inner.end_batch(run_id);
+        let mut held = self.batches()?.remove(run_id).unwrap_or_default();
+        // Stable, so the attempts of a retried action stay in order
+        held.sort_by_key(|(index, _)| *index);
+        let events: Vec<Event> = held.into_iter().map(|(_, event)| event).collect();
+        if !events.is_empty() {
+            self.events.append(run_id, &events)?;
+        }
+        ended
+    }
+
+    fn route(&self, action: &Action) -> Option<String> {
+        self.inner.route(action)
+    }
+}
+
+/// Attempts recorded for one action position, in order
+type RecordedAttempts = Vec<(Value, RecordedOutcome)>;
+
+/// Returns recorded outcomes instead of executing actions; see the [module docs](self).
+///
+/// One instance can serve several replays of the recording; each replaying run is
+/// matched against it from its start.
+pub struct ReplayActionExecutor {
+    events: Arc<dyn EventStore>,
+    recorded: HashMap<(Option<String>, u32), RecordedAttempts>,
+    /// Attempts served so far, per replaying run and action id
+    served: Mutex<HashMap<(RunId, String), usize>>,
+}
+
+impl ReplayActionExecutor {
+    /// Replays the actions recorded in `recorded_run` of `events`, which is also the log
+    /// the replaying kernel writes.
+    pub fn new(events: Arc<dyn EventStore>, recorded_run: &RunId) -> Result<Self, KernelError> {
+        let recorded = events.scan_range(
+            recorded_run,
+            1,
+            Seq::MAX,
+            &EventFilter::only([EventKind::ActionRecorded]),
+        )?;
+        Self::from_events(events, &recorded)
+    }
+
+    /// Replays the [Event::ActionRecorded] events among `recorded` (e.g. a run read from
+    /// another store); `events` is the log the replaying kernel writes.
+    pub fn from_events(
+        events: Arc<dyn EventStore>,
+        recorded: &[SequencedEvent],
+    ) -> Result<Self, KernelError> {
+        let mut attempts: HashMap<_, RecordedAttempts> = HashMap::new();
+        for se in recorded {
+            if let Event::ActionRecorded {
+                step_id,
+                index,
+                action,
+                outcome,
+                ..
+            } = &se.event
+            {
+                let outcome = serde_json::from_value(outcome.clone()).map_err(|e| {
+                    KernelError::EventStore(format!(
+                        "decode recorded outcome at seq {}: {}",
+                        se.seq, e
+                    ))
+                })?;
+                attempts
+                    .entry((step_id.clone(), *index))
+                    .or_default()
+                    .push((action.clone(), outcome));
+            }
+        }
+        Ok(Self {
+            events,
+            recorded: attempts,
+            served: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+impl ReplayActionExecutor {
+    /// The recorded outcome of the next attempt of the action at `action_id` (the latest
+    /// request if `None`).
+    fn replay(
+        &self,
+        run_id: &RunId,
+        action_id: Option<&str>,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        let position = current_position(self.events.as_ref(), run_id, action_id)?;
+        let actual = action_value(action)?;
+        let attempt = {
+            let mut served = self
+                .served
+                .lock()
+                .map_err(|e| KernelError::Driver(e.to_string()))?;
+            let count = served
+                .entry((run_id.clone(), position.action_id))
+                .or_default();
+            *count += 1;
+            *count - 1
+        };
+        let recorded = self
+            .recorded
+            .get(&(position.step_id.clone(), position.index))
+            .and_then(|attempts| attempts.get(attempt));
+        match recorded {
+            Some((expected, outcome)) if *expected == actual => outcome.clone().into_result(),
+            _ => Err(KernelError::ActionReplayMismatch {
+                run_id: run_id.clone(),
+                step_id: position.step_id,
+                index: position.index,
+                expected: recorded.map(|(expected, _)| expected.clone()),
+                actual,
+            }),
+        }
+    }
+}
+
+impl ActionExecutor for ReplayActionExecutor {
+    fn execute(&self, run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        self.replay(run_id, None, action)
+    }
+
+    fn execute_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.replay(run_id, Some(action_id), action)
+    }
+
+    /// The recorded outcome, whether or not the recorded run skipped the action too
+    fn skip_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.replay(run_id, Some(action_id), action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::driver::{Kernel, RunStatus};
+    use crate::kernel::event_store::InMemoryEventStore;
+    use crate::kernel::kernel_mode::KernelMode;
+    use crate::kernel::policy::RetryWithBackoffPolicy;
+    use crate::kernel::reducer::Reducer;
+    use crate::kernel::state::KernelState;
+    use crate::kernel::step::{ActionBatch, Next, StepFn};
+    use crate::kernel::stubs::AllowAllPolicy;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Lookups {
+        outputs: Vec<Value>,
+        summarized: bool,
+    }
+    impl KernelState for Lookups {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    struct LookupsReducer;
+    impl Reducer<Lookups> for LookupsReducer {
+        fn apply(&self, state: &mut Lookups, event: &SequencedEvent) -> Result<(), KernelError> {
+            match &event.event {
+                Event::StateUpdated { payload, .. } => {
+                    *state = serde_json::from_value(payload.clone())
+                        .map_err(|e| KernelError::Reducer(e.to_string()))?;
+                }
+                Event::ActionSucceeded { output, .. } => state.outputs.push(output.clone()),
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    /// Looks up `first`, records a summary step, then looks up `1`.
+    struct LookupStep {
+        first: u64,
+    }
+    impl StepFn<Lookups> for LookupStep {
+        fn next(&self, state: &Lookups) -> Result<Next, KernelError> {
+            let lookup = |n: u64| {
+                Next::Do(Action::CallTool {
+                    tool: "lookup".into(),
+                    input: json!(n),
+                    idempotency_key: None,
+                })
+            };
+            Ok(match (state.outputs.len(), state.summarized) {
+                (0, _) => lookup(self.first),
+                (1, false) => Next::Emit(vec![Event::StateUpdated {
+                    step_id: Some("summarize".into()),
+                    payload: serde_json::to_value(Lookups {
+                        outputs: state.outputs.clone(),
+                        summarized: true,
+                    })
+                    .unwrap(),
+                    state_hash: None,
+                    merge_report: None,
+                }]),
+                (1, true) => lookup(1),
+                _ => Next::Complete,
+            })
+        }
+    }
+
+    /// Answers with its call count, failing transiently on the first call.
+    struct LiveLookups(Arc<AtomicUsize>);
+    impl ActionExecutor for LiveLookups {
+        fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+            let call = self.0.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                return Err(KernelError::Executor(ActionError::transient("flaky")));
+            }
+            let Action::CallTool { input, .. } = action else {
+                return Ok(ActionResult::Failure("not a lookup".into()));
+            };
+            Ok(ActionResult::Success(
+                json!({ "input": input, "call": call }),
+            ))
+        }
+    }
+
+    fn kernel(
+        events: &Arc<InMemoryEventStore>,
+        exec: Arc<dyn ActionExecutor>,
+        first: u64,
+        mode: KernelMode,
+    ) -> Kernel<Lookups> {
+        Kernel {
+            events: Box::new(events.clone()),
+            snaps: None,
+            reducer: Box::new(LookupsReducer),
+            exec,
+            step: Box::new(LookupStep { first }),
+            policy: Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 2, 0)),
+            effect_sink: None,
+            mode,
+            snapshot_policy: None,
+        }
+    }
+
+    fn final_state(events: &Arc<InMemoryEventStore>, run_id: &RunId) -> Lookups {
+        let mut state = Lookups::default();
+        for se in events.scan(run_id, 1).unwrap() {
+            LookupsReducer.apply(&mut state, &se).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn replay_returns_recorded_outcomes_without_executing() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let recorded_run: RunId = "recorded".into();
+        let recording = RecordingActionExecutor::new(LiveLookups(calls.clone()), events.clone());
+        let status = kernel(&events, Arc::new(recording), 0, KernelMode::Normal)
+            .run_until_blocked(&recorded_run, Lookups::default())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let positions: Vec<_> = events
+            .scan_range(
+                &recorded_run,
+                1,
+                Seq::MAX,
+                &EventFilter::only([EventKind::ActionRecorded]),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|se| match se.event {
+                Event::ActionRecorded { step_id, index, .. } => (step_id, index),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            positions,
+            [(None, 0), (None, 0), (Some("summarize".to_string()), 0)]
+        );
+
+        let replay = Arc::new(ReplayActionExecutor::new(events.clone(), &recorded_run).unwrap());
+        let shadow_run: RunId = "shadow".into();
+        let status = kernel(&events, replay, 0, KernelMode::Replay)
+            .run_until_blocked(&shadow_run, Lookups::default())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let replayed = final_state(&events, &shadow_run);
+        assert_eq!(replayed, final_state(&events, &recorded_run));
+        assert_eq!(replayed.outputs[1], json!({ "input": 1, "call": 2 }));
+    }
+
+    #[test]
+    fn replay_fails_on_actions_that_differ_from_the_recording() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let calls = Arc::new(AtomicUsize::new(1));
+        let recorded_run: RunId = "recorded".into();
+        let recording = RecordingActionExecutor::new(LiveLookups(calls.clone()), events.clone());
+        kernel(&events, Arc::new(recording), 0, KernelMode::Normal)
+            .run_until_blocked(&recorded_run, Lookups::default())
+            .unwrap();
+
+        let replay = ReplayActionExecutor::new(events.clone(), &recorded_run).unwrap();
+        let err = kernel(&events, Arc::new(replay), 7, KernelMode::Replay)
+            .run_until_blocked(&"diverged".into(), Lookups::default())
+            .unwrap_err();
+        match &err {
+            KernelError::ActionReplayMismatch {
+                step_id,
+                index,
+                expected,
+                actual,
+                ..
+            } => {
+                assert_eq!((step_id, *index), (&None, 0));
+                assert_eq!(expected.as_ref().unwrap()["CallTool"]["input"], json!(0));
+                assert_eq!(actual["CallTool"]["input"], json!(7));
+            }
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+        assert!(err.to_string().contains("\"input\":7"), "{}", err);
+
+        let unrecorded = ReplayActionExecutor::from_events(events.clone(), &[]).unwrap();
+        let err = kernel(&events, Arc::new(unrecorded), 0, KernelMode::Replay)
+            .run_until_blocked(&"unrecorded".into(), Lookups::default())
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                KernelError::ActionReplayMismatch { expected: None, .. }
+            ),
+            "{:?}",
+            err
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    /// Looks up 0..4 in one batch, then completes
+    struct BatchLookupStep;
+    impl StepFn<Lookups> for BatchLookupStep {
+        fn next(&self, state: &Lookups) -> Result<Next, KernelError> {
+            if !state.outputs.is_empty() {
+                return Ok(Next::Complete);
+            }
+            Ok(Next::DoAll(ActionBatch::new(
+                (0..4u64)
+                    .map(|n| Action::CallTool {
+                        tool: "lookup".into(),
+                        input: json!(n),
+                        idempotency_key: None,
+                    })
+                    .collect(),
+            )))
+        }
+    }
+
+    /// Answers lookups slower the smaller the input; lookup 2 fails transiently once.
+    struct SlowLookups {
+        calls: Arc<AtomicUsize>,
+        flaked: AtomicBool,
+    }
+    impl ActionExecutor for SlowLookups {
+        fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+            let Action::CallTool { input, .. } = action else {
+                return Ok(ActionResult::Failure("not a lookup".into()));
+            };
+            let n = input.as_u64().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10 * (4 - n)));
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if n == 2 && !self.flaked.swap(true, Ordering::SeqCst) {
+                return Err(KernelError::Executor(ActionError::transient("flaky")));
+            }
+            Ok(ActionResult::Success(json!({ "input": n, "call": call })))
+        }
+    }
+
+    fn recorded(events: &Arc<InMemoryEventStore>, run_id: &RunId) -> Vec<(u32, Value, Value)> {
+        events
+            .scan_range(
+                run_id,
+                1,
+                Seq::MAX,
+                &EventFilter::only([EventKind::ActionRecorded]),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|se| match se.event {
+                Event::ActionRecorded {
+                    index,
+                    action,
+                    outcome,
+                    ..
+                } => (index, action, outcome),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batches_are_recorded_in_batch_order_and_replay_identically() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let batch_kernel = |exec: Arc<dyn ActionExecutor>, mode| Kernel::<Lookups> {
+            events: Box::new(events.clone()),
+            snaps: None,
+            reducer: Box::new(LookupsReducer),
+            exec,
+            step: Box::new(BatchLookupStep),
+            policy: Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 2, 0)),
+            effect_sink: None,
+            mode,
+            snapshot_policy: None,
+        };
+
+        let recorded_run: RunId = "recorded".into();
+        let recording = RecordingActionExecutor::new(
+            SlowLookups {
+                calls: calls.clone(),
+                flaked: AtomicBool::new(false),
+            },
+            events.clone(),
+        );
+        let status = batch_kernel(Arc::new(recording), KernelMode::Normal)
+            .run_until_blocked(&recorded_run, Lookups::default())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        let original = recorded(&events, &recorded_run);
+        let indexes: Vec<_> = original.iter().map(|(index, _, _)| *index).collect();
+        assert_eq!(indexes, [0, 1, 2, 2, 3]);
+
+        // Recording the replay as well shows it reproduces the log, not just the state
+        let replay = RecordingActionExecutor::new(
+            ReplayActionExecutor::new(events.clone(), &recorded_run).unwrap(),
+            events.clone(),
+        );
+        let shadow_run: RunId = "shadow".into();
+        let status = batch_kernel(Arc::new(replay), KernelMode::Replay)
+            .run_until_blocked(&shadow_run, Lookups::default())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert_eq!(recorded(&events, &shadow_run), original);
+        let replayed = final_state(&events, &shadow_run);
+        assert_eq!(replayed, final_state(&events, &recorded_run));
+        let inputs: Vec<_> = replayed
+            .outputs
+            .iter()
+            .map(|o| o["input"].clone())
+            .collect();
+        assert_eq!(inputs, [json!(0), json!(1), json!(2), json!(3)]);
+    }
+}
@@ -0,0 +1,420 @@
+//! Buffered EventStore decorator: batches appends per run before writing them through.
+//!
+//! Appending one event per call to a SQL store costs a transaction per event, which
+//! dominates run latency. [BufferedEventStore] keeps each run's new events in memory and
+//! writes them to the wrapped store as one `append` when the driver flushes, when the
+//! batch reaches [BufferConfig::max_events], or when its oldest event has waited
+//! [BufferConfig::max_delay].
+//!
+//! **Guarantees.** Reads through the decorator see buffered events as if they were
+//! stored, with the seqs they will be written at. Each run's buffer is written with a
+//! single atomic append, so the wrapped log is always a prefix of what was appended and
+//! a crash loses at most the unflushed tail. With the default config that tail is the
+//! current step: the driver flushes after every step and before it reports a status.
+//!
+//! The decorator must be the only writer of the runs it buffers, which holds for runs
+//! driven by one [Kernel](crate::kernel::Kernel). Other readers of the wrapped store only
+//! see events once they are flushed.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::kernel::event::{Event, EventFilter, EventStore, KernelError, SequencedEvent};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunSummary};
+
+/// When a [BufferedEventStore] writes a run's buffered events through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferConfig {
+    /// Write a run's buffer once it holds this many events.
+    pub max_events: usize,
+    /// Write a run's buffer once its oldest event has waited this long; checked on each
+    /// append and step boundary, not by a timer.
+    pub max_delay: Duration,
+    /// Write every buffer at each step boundary. When off, buffers also span steps and
+    /// a crash can lose several steps of a run.
+    pub flush_every_step: bool,
+}
+
+impl BufferConfig {
+    /// Default batch size limit.
+    pub const DEFAULT_MAX_EVENTS: usize = 256;
+    /// Default batch age limit.
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_millis(50);
+
+    /// Batches that span steps, bounded by `max_events` and `max_delay` and written
+    /// through at the latest when the driver reports a status.
+    pub fn batched(max_events: usize, max_delay: Duration) -> Self {
+        Self {
+            max_events,
+            max_delay,
+            flush_every_step: false,
+        }
+    }
+}
+
+impl Default for BufferConfig {
+    /// Flush at every step boundary, the safe default.
+    fn default() -> Self {
+        Self {
+            max_events: Self::DEFAULT_MAX_EVENTS,
+            max_delay: Self::DEFAULT_MAX_DELAY,
+            flush_every_step: true,
+        }
+    }
+}
+
+/// Events of one run not yet written to the wrapped store
+struct PendingRun {
+    /// Head of the wrapped store when the batch started; the batch follows it
+    base: Seq,
+    events: Vec<Event>,
+    since: Instant,
+}
+
+impl PendingRun {
+    fn head(&self) -> Seq {
+        self.base + self.events.len() as Seq
+    }
+
+    fn sequenced(&self) -> impl DoubleEndedIterator<Item = SequencedEvent> + '_ {
+        self.events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| SequencedEvent::new(self.base + 1 + i as Seq, event.clone()))
+    }
+}
+
+/// Event store that buffers appends per run in front of another store.
+pub struct BufferedEventStore<E: EventStore> {
+    inner: E,
+    config: BufferConfig,
+    pending: Mutex<HashMap<RunId, PendingRun>>,
+}
+
+impl<E: EventStore> BufferedEventStore<E> {
+    /// Buffers appends to `inner` with [BufferConfig::default].
+    pub fn new(inner: E) -> Self {
+        Self::with_config(inner, BufferConfig::default())
+    }
+
+    pub fn with_config(inner: E, config: BufferConfig) -> Self {
+        Self {
+            inner,
+            config,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The wrapped store; it holds only flushed events.
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    pub fn config(&self) -> BufferConfig {
+        self.config
+    }
+
+    /// Number of events appended but not yet written through, over all runs.
+    pub fn pending_events(&self) -> usize {
+        self.lock()
+            .map(|pending| pending.values().map(|run| run.events.len()).sum())
+            .unwrap_or(0)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<RunId, PendingRun>>, KernelError> {
+        self.pending
+            .lock()
+            .map_err(|e| KernelError::EventStore(e.to_string()))
+    }
+
+    /// Writes the run's buffer with one append; on failure the buffer is kept for a retry.
+    fn write_through(
+        &self,
+        pending: &mut HashMap<RunId, PendingRun>,
+        run_id: &RunId,
+    ) -> Result<(), KernelError> {
+        let Some(run) = pending.get(run_id) else {
+            return Ok(());
+        };
+        let expected = run.head();
+        let written = self.inner.append(run_id, &run.events)?;
+        pending.remove(run_id);
+        if written != expected {
+            return Err(KernelError::EventStore(format!(
+                "run '{}' was appended to by another writer while its events were buffered \
+                 (expected head {}, got {})",
+                run_id, expected, written
+            )));
+        }
+        Ok(())
+    }
+
+    /// Writes through the buffers selected by `due`, oldest batch first.
+    fn write_through_where(&self, due: impl Fn(&PendingRun) -> bool) -> Result<(), KernelError> {
+        let mut pending = self.lock()?;
+        let mut runs: Vec<(Instant, RunId)> = pending
+            .iter()
+            .filter(|(_, run)| due(run))
+            .map(|(run_id, run)| (run.since, run_id.clone()))
+            .collect();
+        runs.sort();
+        for (_, run_id) in runs {
+            self.write_through(&mut pending, &run_id)?;
+        }
+        Ok(())
+    }
+
+    fn is_due(&self, run: &PendingRun) -> bool {
+        run.events.len() >= self.config.max_events || run.since.elapsed() >= self.config.max_delay
+    }
+}
+
+impl<E: EventStore> EventStore for BufferedEventStore<E> {
+    fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+        let mut pending = self.lock()?;
+        if events.is_empty() {
+            return match pending.get(run_id) {
+                Some(run) => Ok(run.head()),
+                None => self.inner.head(run_id),
+            };
+        }
+        if !pending.contains_key(run_id) {
+            let base = self.inner.head(run_id)?;
+            pending.insert(
+                run_id.clone(),
+                PendingRun {
+                    base,
+                    events: Vec::new(),
+                    since: Instant::now(),
+                },
+            );
+        }
+        let run = pending
+            .get_mut(run_id)
+            .expect("pending run was just inserted");
+        let accepted = run.events.len();
+        run.events.extend_from_slice(events);
+        let head = run.head();
+        if self.is_due(run) {
+            if let Err(e) = self.write_through(&mut pending, run_id) {
+                // A failed append leaves none of its events behind
+                if let Some(run) = pending.get_mut(run_id) {
+                    run.events.truncate(accepted);
+                    if run.events.is_empty() {
+                        pending.remove(run_id);
+                    }
+                }
+                return Err(e);
+            }
+        }
+        Ok(head)
+    }
+
+    fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        let pending = self.lock()?;
+        let mut events = self.inner.scan(run_id, from)?;
+        if let Some(run) = pending.get(run_id) {
+            events.extend(run.sequenced().filter(|e| e.seq >= from));
+        }
+        Ok(events)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        let pending = self.lock()?;
+        match pending.get(run_id) {
+            Some(run) => Ok(run.head()),
+            None => self.inner.head(run_id),
+        }
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let pending = self.lock()?;
+        let Some(run) = pending.get(run_id) else {
+            return self.inner.scan_range(run_id, from, to, filter);
+        };
+        let mut events = self
+            .inner
+            .scan_range(run_id, from, to.min(run.base), filter)?;
+        events.extend(
+            run.sequenced()
+                .filter(|e| e.seq >= from && e.seq <= to && filter.matches(&e.event)),
+        );
+        Ok(events)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let pending = self.lock()?;
+        let Some(run) = pending.get(run_id) else {
+            return self.inner.scan_rev(run_id, limit, filter);
+        };
+        let mut events: Vec<_> = run
+            .sequenced()
+            .rev()
+            .filter(|e| filter.matches(&e.event))
+            .take(limit)
+            .collect();
+        if events.len() < limit {
+            events.extend(self.inner.scan_rev(run_id, limit - events.len(), filter)?);
+        }
+        Ok(events)
+    }
+
+    /// Flushes every buffer first, so the listing reflects all appended events.
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.flush()?;
+        self.inner.list_runs(filter, page)
+    }
+
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        let pending = self.lock()?;
+        if pending.contains_key(run_id) {
+            return Ok(true);
+        }
+        self.inner.run_exists(run_id)
+    }
+
+    /// Flushes every buffer first; compaction acts on the wrapped store.
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        self.flush()?;
+        self.inner.replace_prefix(run_id, up_to_seq, marker)
+    }
+
+    fn flush(&self) -> Result<(), KernelError> {
+        self.write_through_where(|_| true)?;
+        self.inner.flush()
+    }
+
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        if self.config.flush_every_step {
+            return self.flush();
+        }
+        self.write_through_where(|run| self.is_due(run))?;
+        self.inner.flush_step(run_id)
+    }
+}
+
+impl<E: EventStore> Drop for BufferedEventStore<E> {
+    /// Best-effort flush, so dropping the store at shutdown does not lose buffered events.
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event_store::{check_event_store_contract, InMemoryEventStore};
+    use crate::kernel::SharedEventStore;
+
+    fn step(n: u64) -> Event {
+        Event::StateUpdated {
+            step_id: Some(format!("n{}", n)),
+            payload: serde_json::json!({ "n": n }),
+            state_hash: None,
+            merge_report: None,
+        }
+    }
+
+    fn never_due() -> BufferConfig {
+        BufferConfig::batched(usize::MAX, Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn buffered_event_store_meets_the_contract() {
+        check_event_store_contract(&BufferedEventStore::new(InMemoryEventStore::new()));
+        check_event_store_contract(&BufferedEventStore::with_config(
+            InMemoryEventStore::new(),
+            never_due(),
+        ));
+        check_event_store_contract(&BufferedEventStore::with_config(
+            InMemoryEventStore::new(),
+            BufferConfig::batched(2, Duration::from_secs(3600)),
+        ));
+    }
+
+    #[test]
+    fn reads_merge_buffered_events_after_stored_ones() {
+        let store = BufferedEventStore::with_config(InMemoryEventStore::new(), never_due());
+        let run_id = "merge".to_string();
+        store.inner().append(&run_id, &[step(1), step(2)]).unwrap();
+
+        assert_eq!(
+            store.append(&run_id, &[step(3), Event::Completed]).unwrap(),
+            4
+        );
+        assert_eq!(store.inner().head(&run_id).unwrap(), 2);
+        assert_eq!(store.pending_events(), 2);
+        let seqs = |events: Vec<SequencedEvent>| events.iter().map(|e| e.seq).collect::<Vec<_>>();
+        assert_eq!(seqs(store.scan(&run_id, 2).unwrap()), [2, 3, 4]);
+        let all = EventFilter::all();
+        assert_eq!(seqs(store.scan_range(&run_id, 2, 3, &all).unwrap()), [2, 3]);
+        assert_eq!(seqs(store.scan_rev(&run_id, 3, &all).unwrap()), [4, 3, 2]);
+
+        store.flush().unwrap();
+        assert_eq!(store.pending_events(), 0);
+        assert_eq!(seqs(store.inner().scan(&run_id, 1).unwrap()), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn batches_are_written_when_full_or_at_step_boundaries() {
+        let inner = SharedEventStore::new();
+        let log = SharedEventStore(inner.0.clone());
+        let run_id = "batches".to_string();
+
+        let batched = BufferedEventStore::with_config(
+            inner,
+            BufferConfig::batched(3, Duration::from_secs(3600)),
+        );
+        batched.append(&run_id, &[step(1), step(2)]).unwrap();
+        batched.flush_step(&run_id).unwrap();
+        assert_eq!(log.head(&run_id).unwrap(), 0);
+        batched.append(&run_id, &[step(3)]).unwrap();
+        assert_eq!(log.head(&run_id).unwrap(), 3);
+
+        let per_step = BufferedEventStore::new(SharedEventStore(log.0.clone()));
+        per_step.append(&run_id, &[step(4)]).unwrap();
+        assert_eq!(log.head(&run_id).unwrap(), 3);
+        per_step.flush_step(&run_id).unwrap();
+        assert_eq!(log.head(&run_id).unwrap(), 4);
+
+        // Buffers left at drop are written through
+        per_step.append(&run_id, &[Event::Completed]).unwrap();
+        drop(per_step);
+        assert_eq!(log.head(&run_id).unwrap(), 5);
+    }
+
+    #[test]
+    fn flush_reports_a_concurrent_writer() {
+        let log = SharedEventStore::new();
+        let store = BufferedEventStore::with_config(SharedEventStore(log.0.clone()), never_due());
+        let run_id = "contended".to_string();
+        store.append(&run_id, &[step(1)]).unwrap();
+        log.append(&run_id, &[step(99)]).unwrap();
+
+        let err = store.flush().unwrap_err();
+        assert!(err.to_string().contains("another writer"), "{}", err);
+        assert_eq!(store.pending_events(), 0);
+    }
+}
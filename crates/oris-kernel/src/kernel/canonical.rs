@@ -0,0 +1,152 @@
+//! Canonical JSON and state hashes.
+//!
+//! A state hash must not depend on how a state happens to serialize: map iteration order,
+//! serde_json's `preserve_order` feature, or whitespace. [canonical_json] renders a value
+//! with object keys in byte order and no whitespace; [canonical_state_hash] is the SHA-256
+//! of that rendering. The format is fixed, so hashes written by one process (e.g. into
+//! [Event::StateUpdated](crate::kernel::Event::StateUpdated)) can be checked by another,
+//! across restarts and releases.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::kernel::event::KernelError;
+
+/// Renders `value` as canonical JSON.
+///
+/// Object keys are sorted by their UTF-8 bytes and no whitespace is emitted. Integers are
+/// written in decimal and other numbers in their shortest round-trip decimal form, without
+/// exponent (`1.0` is written `1`). Strings escape only `"`, `\` and control characters.
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+/// SHA-256 of the [canonical_json] form of `state`.
+pub fn canonical_state_hash<S: Serialize + ?Sized>(state: &S) -> Result<[u8; 32], KernelError> {
+    let value = serde_json::to_value(state)
+        .map_err(|e| KernelError::Driver(format!("serialize state hash: {}", e)))?;
+    Ok(Sha256::digest(canonical_json(&value).as_bytes()).into())
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                let _ = write!(out, "{}", i);
+            } else if let Some(u) = n.as_u64() {
+                let _ = write!(out, "{}", u);
+            } else if let Some(f) = n.as_f64() {
+                let _ = write!(out, "{}", f);
+            }
+        }
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, item);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn canonical_json_sorts_keys_and_drops_whitespace() {
+        let value: Value = serde_json::from_str(
+            r#"{ "zeta": [1, 2.5, 1.0, -3], "alpha": { "b": null, "a": true },
+                 "Quote": "say \"hi\"\n\u0001é" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"Quote":"say \"hi\"\n\u0001é","alpha":{"a":true,"b":null},"zeta":[1,2.5,1,-3]}"#
+        );
+    }
+
+    #[test]
+    fn state_hash_ignores_field_order_and_is_stable() {
+        #[derive(Serialize)]
+        struct State {
+            count: u64,
+            tags: HashMap<String, String>,
+        }
+
+        let tags: HashMap<String, String> = (0..32)
+            .map(|i| (format!("tag-{}", i), format!("value-{}", i)))
+            .collect();
+        let ordered: BTreeMap<_, _> = tags.clone().into_iter().collect();
+        let state = State { count: 7, tags };
+        let reversed = json!({
+            "tags": ordered
+                .iter()
+                .rev()
+                .map(|(k, v)| (k.clone(), json!(v)))
+                .collect::<serde_json::Map<_, _>>(),
+            "count": 7,
+        });
+
+        let hash = canonical_state_hash(&state).unwrap();
+        assert_eq!(hash, canonical_state_hash(&reversed).unwrap());
+
+        // Pinned: a change here breaks every state hash already stored in event logs.
+        let small = json!({ "step": "draft", "count": 1, "ratio": 0.5 });
+        assert_eq!(
+            canonical_json(&small),
+            r#"{"count":1,"ratio":0.5,"step":"draft"}"#
+        );
+        assert_eq!(
+            hex::encode(canonical_state_hash(&small).unwrap()),
+            "3f5e96d0cb70a9fbf953ad543baf5828b6313891e761634a6cad985bb62f5c1c"
+        );
+    }
+}
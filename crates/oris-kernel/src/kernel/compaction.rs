@@ -0,0 +1,588 @@
+//! Event log compaction: archive the old prefix of a run's log behind a snapshot.
+//!
+//! [compact_run] moves a run's events up to a seq into an [EventArchive] and leaves a
+//! [Event::Compacted] marker at that seq, so the log starts at a snapshot. Replay through
+//! the [Kernel](crate::kernel::Kernel) then starts from the snapshot transparently;
+//! replaying from a seq inside the archived range without it fails with
+//! [KernelError::Compacted]. [scan_with_archive] reads the full history back.
+//!
+//! Archives: [InMemoryEventArchive] for tests, [FileEventArchive] (one JSON-lines file
+//! per run) and [EventStoreArchive] (a secondary [EventStore], e.g. cold Postgres).
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::kernel::event::{Event, EventFilter, EventStore, KernelError, SequencedEvent};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::snapshot::SnapshotStore;
+
+/// Where compaction moves archived events.
+///
+/// Archives receive each run's events in order and without gaps: the first event of each
+/// call continues from [EventArchive::archived_head].
+pub trait EventArchive: Send + Sync {
+    /// Stores `events` of the run; they must be durable when this returns.
+    fn archive(&self, run_id: &RunId, events: &[SequencedEvent]) -> Result<(), KernelError>;
+
+    /// Archived events of the run with `from <= seq <= to`, in ascending seq order.
+    fn fetch(&self, run_id: &RunId, from: Seq, to: Seq)
+        -> Result<Vec<SequencedEvent>, KernelError>;
+
+    /// Highest archived seq of the run (0 if none).
+    fn archived_head(&self, run_id: &RunId) -> Result<Seq, KernelError>;
+}
+
+/// Outcome of [compact_run].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub run_id: RunId,
+    /// The log now starts with the [Event::Compacted] marker at this seq.
+    pub up_to_seq: Seq,
+    /// Snapshot replay starts from.
+    pub snapshot_seq: Seq,
+    /// Events moved to the archive by this call; 0 if the run was already compacted this far.
+    pub archived: usize,
+}
+
+/// Archives the run's events with `seq <= up_to_seq` and replaces them by a
+/// [Event::Compacted] marker at `up_to_seq`.
+///
+/// Refused with [KernelError::Compaction] unless the latest snapshot of the run is at
+/// or after `up_to_seq` and `up_to_seq` is below the head; the last event always stays,
+/// so the run's status can still be derived from its log. Events are archived before the
+/// log is touched, and a retry after a crash does not archive them twice.
+pub fn compact_run<S>(
+    store: &dyn EventStore,
+    snapshots: &dyn SnapshotStore<S>,
+    archive: &dyn EventArchive,
+    run_id: &RunId,
+    up_to_seq: Seq,
+) -> Result<CompactionReport, KernelError> {
+    let head = store.head(run_id)?;
+    if up_to_seq == 0 || up_to_seq >= head {
+        return Err(KernelError::Compaction(format!(
+            "run '{}' can only be compacted through a seq below its head {}, not {}",
+            run_id, head, up_to_seq
+        )));
+    }
+    let snapshot_seq = match snapshots.load_latest(run_id)? {
+        Some(snapshot) if snapshot.at_seq >= up_to_seq => snapshot.at_seq,
+        Some(snapshot) => {
+            return Err(KernelError::Compaction(format!(
+                "run '{}' has its latest snapshot at seq {}, before seq {}",
+                run_id, snapshot.at_seq, up_to_seq
+            )))
+        }
+        None => {
+            return Err(KernelError::Compaction(format!(
+                "run '{}' has no snapshot to compact behind",
+                run_id
+            )))
+        }
+    };
+
+    let mut events = store.scan_range(run_id, 1, up_to_seq, &EventFilter::all())?;
+    if let Some(Event::Compacted {
+        up_to_seq: compacted,
+        ..
+    }) = events.first().map(|se| &se.event)
+    {
+        if *compacted >= up_to_seq {
+            return Ok(CompactionReport {
+                run_id: run_id.clone(),
+                up_to_seq: *compacted,
+                snapshot_seq,
+                archived: 0,
+            });
+        }
+        events.remove(0);
+    }
+    let archived_head = archive.archived_head(run_id)?;
+    events.retain(|se| se.seq > archived_head);
+    if !events.is_empty() {
+        archive.archive(run_id, &events)?;
+    }
+
+    store.replace_prefix(
+        run_id,
+        up_to_seq,
+        &Event::Compacted {
+            up_to_seq,
+            snapshot_seq,
+        },
+    )?;
+    Ok(CompactionReport {
+        run_id: run_id.clone(),
+        up_to_seq,
+        snapshot_seq,
+        archived: events.len(),
+    })
+}
+
+/// Scans the run from `from` like [EventStore::scan], reading compacted events back from
+/// `archive`, so the result is the log as it was before compaction.
+///
+/// Fails with [KernelError::Compacted] if the archive is missing part of the range.
+pub fn scan_with_archive(
+    store: &dyn EventStore,
+    archive: &dyn EventArchive,
+    run_id: &RunId,
+    from: Seq,
+) -> Result<Vec<SequencedEvent>, KernelError> {
+    let mut live = store.scan(run_id, from)?;
+    let Some(Event::Compacted { up_to_seq, .. }) = live.first().map(|se| &se.event) else {
+        return Ok(live);
+    };
+    let up_to_seq = *up_to_seq;
+    let mut events = archive.fetch(run_id, from, up_to_seq)?;
+    let complete = events.first().map(|se| se.seq) == Some(from.max(1))
+        && events.last().map(|se| se.seq) == Some(up_to_seq)
+        && events.len() as Seq == up_to_seq + 1 - from.max(1);
+    if !complete {
+        return Err(KernelError::Compacted {
+            run_id: run_id.clone(),
+            up_to_seq,
+        });
+    }
+    live.remove(0);
+    events.append(&mut live);
+    Ok(events)
+}
+
+/// In-memory archive, for tests and single-process use.
+#[derive(Default)]
+pub struct InMemoryEventArchive {
+    runs: RwLock<HashMap<RunId, Vec<SequencedEvent>>>,
+}
+
+impl InMemoryEventArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EventArchive for InMemoryEventArchive {
+    fn archive(&self, run_id: &RunId, events: &[SequencedEvent]) -> Result<(), KernelError> {
+        let mut runs = self
+            .runs
+            .write()
+            .map_err(|e| KernelError::Compaction(e.to_string()))?;
+        runs.entry(run_id.clone())
+            .or_default()
+            .extend_from_slice(events);
+        Ok(())
+    }
+
+    fn fetch(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let runs = self
+            .runs
+            .read()
+            .map_err(|e| KernelError::Compaction(e.to_string()))?;
+        Ok(runs
+            .get(run_id)
+            .map(|events| {
+                events
+                    .iter()
+                    .filter(|se| se.seq >= from && se.seq <= to)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn archived_head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        let runs = self
+            .runs
+            .read()
+            .map_err(|e| KernelError::Compaction(e.to_string()))?;
+        Ok(runs
+            .get(run_id)
+            .and_then(|events| events.last())
+            .map(|se| se.seq)
+            .unwrap_or(0))
+    }
+}
+
+/// Archive in a directory: one JSON-lines file of [SequencedEvent]s per run, named after
+/// the hex-encoded run id.
+pub struct FileEventArchive {
+    dir: PathBuf,
+}
+
+impl FileEventArchive {
+    /// Archives into `dir`, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, KernelError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| map_archive_err(&dir, e))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, run_id: &RunId) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", hex::encode(run_id)))
+    }
+
+    fn read(&self, run_id: &RunId) -> Result<Vec<SequencedEvent>, KernelError> {
+        let path = self.path(run_id);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(map_archive_err(&path, e)),
+        };
+        BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.map_err(|e| map_archive_err(&path, e))?;
+                serde_json::from_str(&line).map_err(|e| map_archive_err(&path, e))
+            })
+            .collect()
+    }
+}
+
+impl EventArchive for FileEventArchive {
+    fn archive(&self, run_id: &RunId, events: &[SequencedEvent]) -> Result<(), KernelError> {
+        let path = self.path(run_id);
+        let mut lines = String::new();
+        for se in events {
+            lines.push_str(&serde_json::to_string(se).map_err(|e| map_archive_err(&path, e))?);
+            lines.push('\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| map_archive_err(&path, e))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|()| file.sync_all())
+            .map_err(|e| map_archive_err(&path, e))
+    }
+
+    fn fetch(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let mut events = self.read(run_id)?;
+        events.retain(|se| se.seq >= from && se.seq <= to);
+        Ok(events)
+    }
+
+    fn archived_head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        Ok(self.read(run_id)?.last().map(|se| se.seq).unwrap_or(0))
+    }
+}
+
+fn map_archive_err(path: &Path, e: impl std::fmt::Display) -> KernelError {
+    KernelError::Compaction(format!("archive {}: {}", path.display(), e))
+}
+
+/// Archive in a secondary event store; archived events keep their seqs there.
+pub struct EventStoreArchive<E: EventStore>(pub E);
+
+impl<E: EventStore> EventArchive for EventStoreArchive<E> {
+    fn archive(&self, run_id: &RunId, events: &[SequencedEvent]) -> Result<(), KernelError> {
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            return Ok(());
+        };
+        let head = self.0.head(run_id)?;
+        if first.seq != head + 1 {
+            return Err(KernelError::Compaction(format!(
+                "archive of run '{}' ends at seq {}; cannot continue it at {}",
+                run_id, head, first.seq
+            )));
+        }
+        let batch: Vec<Event> = events.iter().map(|se| se.event.clone()).collect();
+        let written = self.0.append(run_id, &batch)?;
+        if written != last.seq {
+            return Err(KernelError::Compaction(format!(
+                "archive of run '{}' stored seqs up to {} instead of {}",
+                run_id, written, last.seq
+            )));
+        }
+        Ok(())
+    }
+
+    fn fetch(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.0.scan_range(run_id, from, to, &EventFilter::all())
+    }
+
+    fn archived_head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        self.0.head(run_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::kernel::driver::{Kernel, RunStatus};
+    use crate::kernel::event_store::{InMemoryEventStore, SharedEventStore};
+    use crate::kernel::kernel_mode::KernelMode;
+    use crate::kernel::snapshot::{InMemorySnapshotStore, Snapshot};
+    use crate::kernel::state::KernelState;
+    use crate::kernel::step::{Next, StepFn};
+    use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+    use crate::kernel::StateUpdatedOnlyReducer;
+
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    struct Counter(u32);
+    impl KernelState for Counter {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    /// Counts to `limit` with one StateUpdated per step, then completes
+    struct CountTo(u32, AtomicUsize);
+    impl StepFn<Counter> for CountTo {
+        fn next(&self, state: &Counter) -> Result<Next, KernelError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            if state.0 >= self.0 {
+                return Ok(Next::Complete);
+            }
+            Ok(Next::Emit(vec![Event::StateUpdated {
+                step_id: Some(format!("count-{}", state.0 + 1)),
+                payload: serde_json::to_value(Counter(state.0 + 1)).unwrap(),
+                state_hash: None,
+                merge_report: None,
+            }]))
+        }
+    }
+
+    struct SharedSnapshots(Arc<InMemorySnapshotStore<Counter>>);
+    impl SnapshotStore<Counter> for SharedSnapshots {
+        fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<Counter>>, KernelError> {
+            self.0.load_latest(run_id)
+        }
+
+        fn save(&self, snapshot: &Snapshot<Counter>) -> Result<(), KernelError> {
+            self.0.save(snapshot)
+        }
+    }
+
+    fn kernel(
+        events: &SharedEventStore,
+        snaps: Option<&Arc<InMemorySnapshotStore<Counter>>>,
+    ) -> Kernel<Counter> {
+        Kernel {
+            events: Box::new(SharedEventStore(events.0.clone())),
+            snaps: snaps.map(|s| Box::new(SharedSnapshots(s.clone())) as Box<_>),
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(CountTo(5, AtomicUsize::new(0))),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        }
+    }
+
+    fn log_json(events: &[SequencedEvent]) -> serde_json::Value {
+        serde_json::to_value(events).unwrap()
+    }
+
+    #[test]
+    fn compacted_run_replays_to_the_same_state() {
+        let events = SharedEventStore::new();
+        let snaps = Arc::new(InMemorySnapshotStore::new());
+        let run_id: RunId = "compact-roundtrip".into();
+        let k = kernel(&events, Some(&snaps));
+        let status = k.run_until_blocked(&run_id, Counter(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let original = events.scan(&run_id, 1).unwrap();
+        let expected = k.replay(&run_id, Counter(0)).unwrap();
+        assert_eq!(expected, Counter(5));
+
+        // Pin the latest snapshot before the end, so replay has events to apply after it
+        snaps
+            .save(&Snapshot {
+                run_id: run_id.clone(),
+                at_seq: 4,
+                state: Counter(4),
+                state_version: 1,
+                event_count: 4,
+                content_hash: None,
+            })
+            .unwrap();
+        let archive = InMemoryEventArchive::new();
+        let report = compact_run(&events, snaps.as_ref(), &archive, &run_id, 4).unwrap();
+        assert_eq!(
+            (report.up_to_seq, report.snapshot_seq, report.archived),
+            (4, 4, 4)
+        );
+        let live = events.scan(&run_id, 1).unwrap();
+        assert_eq!(live.len(), original.len() - 3);
+        assert!(matches!(
+            live[0].event,
+            Event::Compacted { up_to_seq: 4, .. }
+        ));
+
+        assert_eq!(k.replay(&run_id, Counter(0)).unwrap(), expected);
+        assert_eq!(
+            k.replay_from_snapshot(&run_id, Counter(0)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            log_json(&scan_with_archive(&events, &archive, &run_id, 1).unwrap()),
+            log_json(&original)
+        );
+        assert_eq!(
+            log_json(&scan_with_archive(&events, &archive, &run_id, 3).unwrap()),
+            log_json(&original[2..])
+        );
+
+        // Compacting again as far is a no-op; further archives only the new prefix
+        let again = compact_run(&events, snaps.as_ref(), &archive, &run_id, 4).unwrap();
+        assert_eq!(again.archived, 0);
+        snaps
+            .save(&Snapshot {
+                run_id: run_id.clone(),
+                at_seq: 5,
+                state: Counter(5),
+                state_version: 1,
+                event_count: 5,
+                content_hash: None,
+            })
+            .unwrap();
+        let further = compact_run(&events, snaps.as_ref(), &archive, &run_id, 5).unwrap();
+        assert_eq!(further.archived, 1);
+        assert_eq!(archive.archived_head(&run_id).unwrap(), 5);
+        assert_eq!(k.replay(&run_id, Counter(0)).unwrap(), expected);
+        assert_eq!(
+            log_json(&scan_with_archive(&events, &archive, &run_id, 1).unwrap()),
+            log_json(&original)
+        );
+    }
+
+    #[test]
+    fn replay_inside_the_archived_range_needs_a_snapshot() {
+        let events = SharedEventStore::new();
+        let snaps = Arc::new(InMemorySnapshotStore::new());
+        let run_id: RunId = "compact-no-snapshot".into();
+        kernel(&events, Some(&snaps))
+            .run_until_blocked(&run_id, Counter(0))
+            .unwrap();
+        compact_run(
+            &events,
+            snaps.as_ref(),
+            &InMemoryEventArchive::new(),
+            &run_id,
+            3,
+        )
+        .unwrap();
+
+        let without_snapshots = kernel(&events, None);
+        let err = without_snapshots.replay(&run_id, Counter(0)).unwrap_err();
+        assert!(
+            matches!(err, KernelError::Compacted { up_to_seq: 3, .. }),
+            "{}",
+            err
+        );
+        let empty_archive = InMemoryEventArchive::new();
+        assert!(matches!(
+            scan_with_archive(&events, &empty_archive, &run_id, 1),
+            Err(KernelError::Compacted { .. })
+        ));
+    }
+
+    #[test]
+    fn compaction_is_refused_without_a_covering_snapshot() {
+        let events = InMemoryEventStore::new();
+        let snaps = InMemorySnapshotStore::<Counter>::new();
+        let archive = InMemoryEventArchive::new();
+        let run_id: RunId = "compact-refused".into();
+        let step = Event::StateUpdated {
+            step_id: None,
+            payload: serde_json::json!(1),
+            state_hash: None,
+            merge_report: None,
+        };
+        events
+            .append(&run_id, &[step.clone(), step.clone(), step])
+            .unwrap();
+
+        let refused = |up_to| compact_run(&events, &snaps, &archive, &run_id, up_to);
+        assert!(matches!(refused(2), Err(KernelError::Compaction(_))));
+        snaps
+            .save(&Snapshot {
+                run_id: run_id.clone(),
+                at_seq: 1,
+                state: Counter(1),
+                state_version: 1,
+                event_count: 1,
+                content_hash: None,
+            })
+            .unwrap();
+        assert!(matches!(refused(2), Err(KernelError::Compaction(_))));
+        assert!(matches!(refused(0), Err(KernelError::Compaction(_))));
+        assert!(matches!(refused(3), Err(KernelError::Compaction(_))));
+        assert_eq!(archive.archived_head(&run_id).unwrap(), 0);
+        assert_eq!(events.scan(&run_id, 1).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn file_and_store_archives_keep_seqs() {
+        let dir = std::env::temp_dir().join(format!(
+            "oris-kernel-archive-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let run_id: RunId = "runs/with:odd chars".into();
+        let batch = |from: Seq, to: Seq| -> Vec<SequencedEvent> {
+            (from..=to)
+                .map(|seq| {
+                    SequencedEvent::new(
+                        seq,
+                        Event::StateUpdated {
+                            step_id: None,
+                            payload: serde_json::json!(seq),
+                            state_hash: None,
+                            merge_report: None,
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        let file = FileEventArchive::new(&dir).unwrap();
+        let store = EventStoreArchive(InMemoryEventStore::new());
+        for archive in [&file as &dyn EventArchive, &store] {
+            assert_eq!(archive.archived_head(&run_id).unwrap(), 0);
+            archive.archive(&run_id, &batch(1, 3)).unwrap();
+            archive.archive(&run_id, &batch(4, 5)).unwrap();
+            assert_eq!(archive.archived_head(&run_id).unwrap(), 5);
+            let seqs: Vec<_> = archive
+                .fetch(&run_id, 2, 4)
+                .unwrap()
+                .iter()
+                .map(|se| se.seq)
+                .collect();
+            assert_eq!(seqs, [2, 3, 4]);
+        }
+        assert!(store.archive(&run_id, &batch(7, 8)).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -69,10 +69,15 @@ pub fn event_stream_hash(store: &dyn EventStore, run_id: &RunId) -> Result<[u8;
     Ok(compute_event_stream_hash(&events))
 }
 
-/// Computes SHA-256 hash of the canonical serialized event sequence.
+/// Computes SHA-256 hash of the canonical serialized event sequence. When the events were
+/// stored is not part of the sequence, so their `recorded_at_ms` is left out.
 pub fn compute_event_stream_hash(events: &[SequencedEvent]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     for se in events {
+        let se = SequencedEvent {
+            recorded_at_ms: None,
+            ..se.clone()
+        };
         let canonical = serde_json::to_string(&se).unwrap_or_default();
         hasher.update(canonical.as_bytes());
     }
@@ -132,6 +137,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::json!([1]),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -1,16 +1,32 @@
 //! Kernel driver: run_until_blocked, resume, replay.
 
-use std::time::Duration;
-
-use crate::kernel::action::{Action, ActionError, ActionExecutor, ActionResult};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread::{Scope, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::kernel::action::{
+    default_idempotency_key, Action, ActionError, ActionExecutor, ActionResult,
+    CANCELLED_ACTION_ERROR, SKIPPED_ACTION_ERROR,
+};
 use crate::kernel::determinism_guard::DeterminismGuard;
-use crate::kernel::event::{Event, EventStore, SequencedEvent};
+use crate::kernel::event::{
+    Event, EventFilter, EventKind, EventStore, PolicyDecisionKind, SequencedEvent,
+};
+use crate::kernel::execution_log;
 use crate::kernel::identity::{RunId, Seq};
 use crate::kernel::kernel_mode::KernelMode;
-use crate::kernel::policy::{Policy, PolicyCtx, RetryDecision};
+use crate::kernel::policy::{
+    denial_message, BudgetExceeded, BudgetUsage, Policy, PolicyCtx, RetryDecision,
+    POLICY_AUTHORIZED, POLICY_DENIED,
+};
 use crate::kernel::reducer::Reducer;
+use crate::kernel::replay_verifier::{self, VerificationReport};
+use crate::kernel::run_control::RunControl;
 use crate::kernel::runtime_effect::{EffectSink, RuntimeEffect};
-use crate::kernel::snapshot::{Snapshot, SnapshotStore};
+use crate::kernel::snapshot::{Snapshot, SnapshotPolicy, SnapshotStore};
 use crate::kernel::state::KernelState;
 use crate::kernel::step::{InterruptInfo, Next, StepFn};
 use crate::kernel::timeline;
@@ -30,6 +46,8 @@ pub enum RunStatus {
         /// `true` if the failure is transient and the run may be resumed or retried.
         recoverable: bool,
     },
+    /// Run was cancelled on request (see [RunControl]); it cannot be advanced again.
+    Cancelled,
 }
 
 /// State of a run that has paused and is waiting for an external input.
@@ -39,6 +57,29 @@ pub struct BlockedInfo {
     pub interrupt: Option<InterruptInfo>,
     /// Set when the run is waiting for a named external signal.
     pub wait_signal: Option<String>,
+    /// Set when the run was paused on request (see [RunControl]); running it again
+    /// continues it.
+    pub paused: bool,
+    /// Names the interrupt the run is blocked on; pass it to
+    /// [Kernel::resume_with_value] to resume this interrupt and no other. Set with
+    /// `interrupt`.
+    pub resume_token: Option<String>,
+    /// Set when the policy denied the step's action for overrunning the run's budget
+    /// ([Policy::check_budget]); running the run again once the budget allows it continues it.
+    pub budget_exceeded: Option<BudgetExceeded>,
+}
+
+/// What a single step did; see [Kernel::step_once].
+#[derive(Clone, Debug)]
+pub struct StepOutcome<S> {
+    /// The step function's decision.
+    pub next: Next,
+    /// Events the step appended, in order.
+    pub events: Vec<SequencedEvent>,
+    /// State of the run after the step.
+    pub state: S,
+    /// Set when the step completed, blocked or failed the run; `None` when it continues.
+    pub status: Option<RunStatus>,
 }
 
 /// Signal to resume a blocked run (e.g. human approval, external event).
@@ -55,6 +96,271 @@ pub enum Signal {
     },
 }
 
+/// The `ActionRequested` event of `action` as `action_id`
+fn requested(action_id: &str, action: &Action) -> Result<Event, KernelError> {
+    Ok(Event::ActionRequested {
+        action_id: action_id.to_string(),
+        payload: serde_json::to_value(action).map_err(|e| KernelError::Driver(e.to_string()))?,
+    })
+}
+
+/// A `PolicyDecision` event: `policy` decided `decision` about `action`
+fn policy_decision(
+    policy: &dyn Policy,
+    action: &Action,
+    decision: PolicyDecisionKind,
+    code: &str,
+    reason: Option<String>,
+) -> Event {
+    Event::PolicyDecision {
+        action_kind: action.kind(),
+        decision,
+        code: code.to_string(),
+        policy: policy.name(),
+        reason,
+    }
+}
+
+/// The terminal event of one requested action
+struct ActionOutcome {
+    /// `ActionSucceeded` or `ActionFailed`
+    event: Event,
+    failed: bool,
+    /// `PolicyDecision` of each retry consultation, with the `RetryScheduled` of each
+    /// retry, logged before `event`
+    decisions: Vec<Event>,
+    /// `(attempt, error)` of each retry, for the step's span
+    #[cfg(feature = "otel")]
+    retries: Vec<(u32, String)>,
+}
+
+impl ActionOutcome {
+    /// The events to log for the action: its retry decisions, then its terminal event
+    fn into_events(self) -> Vec<Event> {
+        let mut events = self.decisions;
+        events.push(self.event);
+        events
+    }
+}
+
+/// How often an action waiting on its executor checks for a cancel request
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// What the policy learns about an action besides the action itself
+fn policy_ctx(exec: &dyn ActionExecutor, action: &Action) -> PolicyCtx {
+    PolicyCtx {
+        executor: exec.route(action),
+        ..PolicyCtx::default()
+    }
+}
+
+/// Carries out requested actions: the executor (the policy in dry runs) and the policy's
+/// retry loop. Holds only what that needs, so batch workers can share it.
+struct ActionRunner<'a> {
+    exec: &'a Arc<dyn ActionExecutor>,
+    policy: &'a dyn Policy,
+    dry_run: bool,
+    /// A cancel request aborts the action in flight
+    control: Option<&'a RunControl>,
+}
+
+impl ActionRunner<'_> {
+    /// Executes (or, with `skip`, skips) the action requested as `action_id`, retrying
+    /// executor errors as the policy decides. Only a replay mismatch is returned as an error;
+    /// other failures become an `ActionFailed` outcome.
+    fn run(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+        skip: bool,
+    ) -> Result<ActionOutcome, KernelError> {
+        let timeout = match self.dry_run {
+            true => None,
+            false => self
+                .policy
+                .action_timeout(action, &policy_ctx(self.exec.as_ref(), action)),
+        };
+        // In DryRun mode the policy stands in for the executor
+        let attempt_once = || match (self.dry_run, skip) {
+            (false, false) => self.execute(run_id, action_id, action, timeout),
+            (false, true) => self.exec.skip_requested(run_id, action_id, action),
+            (true, false) => Ok(self.policy.simulate(action)),
+            (true, true) => Ok(ActionResult::Failure(SKIPPED_ACTION_ERROR.into())),
+        };
+        #[cfg(feature = "otel")]
+        let mut retries = Vec::new();
+        let mut decisions = Vec::new();
+        let mut attempt = 0u32;
+        let started = Instant::now();
+        let mut result = attempt_once();
+        let (event, failed) = loop {
+            let e = match result {
+                Ok(ActionResult::Success(output)) => {
+                    break (
+                        Event::ActionSucceeded {
+                            action_id: action_id.to_string(),
+                            output,
+                            dry_run: self.dry_run,
+                        },
+                        false,
+                    )
+                }
+                Ok(ActionResult::Failure(error)) => {
+                    break (
+                        Event::ActionFailed {
+                            action_id: action_id.to_string(),
+                            error,
+                            dry_run: self.dry_run,
+                        },
+                        true,
+                    )
+                }
+                // A replay that strayed from its recording is not an action failure
+                Err(e @ KernelError::ActionReplayMismatch { .. }) => return Err(e),
+                Err(e) => e,
+            };
+            let action_err = ActionError::from_kernel_error(&e);
+            let retry =
+                self.policy
+                    .retry_strategy_elapsed(&action_err, action, attempt, started.elapsed());
+            decisions.push(policy_decision(
+                self.policy,
+                action,
+                match retry {
+                    RetryDecision::Fail => PolicyDecisionKind::Fail,
+                    _ => PolicyDecisionKind::Retry,
+                },
+                action_err.kind.code(),
+                Some(e.to_string()),
+            ));
+            let Some(delay) = retry.delay() else {
+                break (
+                    Event::ActionFailed {
+                        action_id: action_id.to_string(),
+                        error: e.to_string(),
+                        dry_run: self.dry_run,
+                    },
+                    true,
+                );
+            };
+            attempt += 1;
+            decisions.push(Event::RetryScheduled {
+                action_id: action_id.to_string(),
+                attempt,
+                delay_ms: delay.as_millis() as u64,
+            });
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+            #[cfg(feature = "otel")]
+            retries.push((attempt, e.to_string()));
+            result = attempt_once();
+        };
+        Ok(ActionOutcome {
+            event,
+            failed,
+            decisions,
+            #[cfg(feature = "otel")]
+            retries,
+        })
+    }
+
+    /// One attempt of the action. With a timeout or a run control the executor runs on its
+    /// own thread, which is abandoned once the attempt times out or the run is cancelled.
+    fn execute(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+        timeout: Option<Duration>,
+    ) -> Result<ActionResult, KernelError> {
+        if self.cancel_requested()? {
+            return Ok(ActionResult::Failure(CANCELLED_ACTION_ERROR.into()));
+        }
+        if timeout.is_none() && self.control.is_none() {
+            return self.exec.execute_requested(run_id, action_id, action);
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let exec = Arc::clone(self.exec);
+        let (thread_run_id, thread_action_id, thread_action) =
+            (run_id.clone(), action_id.to_string(), action.clone());
+        // Executors that block on async work need the caller's runtime on the thread too
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        std::thread::Builder::new()
+            .name(format!("action-{}", action_id))
+            .spawn(move || {
+                let _entered = runtime.as_ref().map(|rt| rt.enter());
+                let _ = tx.send(exec.execute_requested(
+                    &thread_run_id,
+                    &thread_action_id,
+                    &thread_action,
+                ));
+            })
+            .map_err(|e| KernelError::Driver(format!("spawn action thread: {}", e)))?;
+        let deadline = timeout.map(|limit| (Instant::now() + limit, limit));
+        loop {
+            let wait = match (deadline, self.control) {
+                (Some((at, _)), None) => at.saturating_duration_since(Instant::now()),
+                (Some((at, _)), Some(_)) => at
+                    .saturating_duration_since(Instant::now())
+                    .min(CANCEL_POLL_INTERVAL),
+                (None, _) => CANCEL_POLL_INTERVAL,
+            };
+            match rx.recv_timeout(wait) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(KernelError::Driver(format!(
+                        "executor of action {} panicked",
+                        action_id
+                    )))
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+            if self.cancel_requested()? {
+                return Ok(ActionResult::Failure(CANCELLED_ACTION_ERROR.into()));
+            }
+            if let Some((at, limit)) = deadline {
+                if Instant::now() >= at {
+                    return Err(KernelError::Executor(ActionError::timeout(limit)));
+                }
+            }
+        }
+    }
+
+    fn cancel_requested(&self) -> Result<bool, KernelError> {
+        match self.control {
+            Some(control) => control.is_cancel_requested(),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Resume token of the interrupt stored at `seq`
+fn resume_token(run_id: &RunId, seq: Seq) -> String {
+    format!("{}:interrupt:{}", run_id, seq)
+}
+
+/// The seq named by a token from [resume_token] for `run_id`
+fn parse_resume_token(run_id: &RunId, token: &str) -> Option<Seq> {
+    token
+        .strip_prefix(run_id.as_str())?
+        .strip_prefix(":interrupt:")?
+        .parse()
+        .ok()
+}
+
+/// Marks the run as no longer driven when `run_controlled` returns, or unwinds
+struct RunningGuard<'a>(&'a RunControl);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut ctl) = self.0.lock() {
+            ctl.running = false;
+        }
+    }
+}
+
 /// Kernel: event store, optional snapshot store, reducer, executor, step fn, policy, optional effect sink, execution mode.
 pub struct Kernel<S: KernelState> {
     /// Append-only event log; the source of truth for all run state.
@@ -63,8 +369,9 @@ pub struct Kernel<S: KernelState> {
     pub snaps: Option<Box<dyn SnapshotStore<S>>>,
     /// Applies events to the run state.
     pub reducer: Box<dyn Reducer<S>>,
-    /// Executes actions (tool calls, LLM calls, sleeps).
-    pub exec: Box<dyn ActionExecutor>,
+    /// Executes actions (tool calls, LLM calls, sleeps). Shared with the threads that run
+    /// actions under a timeout or a run control.
+    pub exec: Arc<dyn ActionExecutor>,
     /// Computes the next step given the current state.
     pub step: Box<dyn StepFn<S>>,
     /// Governs which actions are allowed and how to retry on failure.
@@ -73,6 +380,34 @@ pub struct Kernel<S: KernelState> {
     pub effect_sink: Option<Box<dyn EffectSink>>,
     /// Execution mode: Normal, Record, Replay, or Verify. Replay/Verify trap clock, randomness, spawn.
     pub mode: KernelMode,
+    /// When set (with `snaps`), runs are snapshotted as the policy says, off the step loop,
+    /// each snapshot recorded as an `Event::SnapshotTaken`; a snapshot that fails to save
+    /// is skipped and the run goes on. When `None`, a snapshot is saved after every event.
+    pub snapshot_policy: Option<SnapshotPolicy>,
+}
+
+/// Snapshots of one run's steps under a [SnapshotPolicy]
+struct AutoSnapshots<'scope, 'k, S> {
+    policy: &'k SnapshotPolicy,
+    store: &'k dyn SnapshotStore<S>,
+    /// Head of the log when events were last counted
+    counted_to: Seq,
+    /// Events appended since the last snapshot, markers excluded
+    since: u64,
+    last_taken: Instant,
+    /// Snapshot being saved: its seq and the thread saving it
+    pending: Option<(Seq, ScopedJoinHandle<'scope, Result<(), KernelError>>)>,
+}
+
+impl<S> AutoSnapshots<'_, '_, S> {
+    fn due(&self) -> bool {
+        self.since > 0
+            && (self.policy.every_n_events.is_some_and(|n| self.since >= n)
+                || self
+                    .policy
+                    .min_interval
+                    .is_some_and(|interval| self.last_taken.elapsed() >= interval))
+    }
 }
 
 impl<S: KernelState> Kernel<S> {
@@ -81,14 +416,54 @@ impl<S: KernelState> Kernel<S> {
         DeterminismGuard::new(self.mode)
     }
 
-    /// Runs until the step returns Complete or Interrupt/WaitSignal. Returns run status.
+    /// Runs until the step returns Complete, Fail or Interrupt/WaitSignal. Returns run status.
     /// State is obtained by replaying the event log (or initial_state if the run has no events yet).
     pub fn run_until_blocked(
         &self,
         run_id: &RunId,
         initial_state: S,
     ) -> Result<RunStatus, KernelError> {
-        self.run_loop(run_id, initial_state)
+        self.ensure_may_advance(run_id)?;
+        self.run_loop(run_id, initial_state, None)
+    }
+
+    /// Like [run_until_blocked](Self::run_until_blocked), honouring the pause and cancel
+    /// requests in `control` at every step boundary: a pause appends `Paused` and returns
+    /// a paused [RunStatus::Blocked], a cancel appends `Cancelled` and returns
+    /// [RunStatus::Cancelled]. A cancel that arrives while the run blocks or fails is
+    /// applied once it stops; one that arrives as it completes is too late.
+    pub fn run_controlled(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        control: &RunControl,
+    ) -> Result<RunStatus, KernelError> {
+        let _running = RunningGuard(control);
+        control.lock()?.running = true;
+        self.ensure_may_advance(run_id)?;
+        let result = self.run_loop(run_id, initial_state, Some(control));
+        let mut ctl = control.lock()?;
+        // Not running any more means a step boundary already stopped the run
+        let was_running = std::mem::replace(&mut ctl.running, false);
+        match (&result, &ctl.cancel) {
+            (Ok(RunStatus::Blocked(_) | RunStatus::Failed { .. }), Some(reason)) if was_running => {
+                self.append_cancelled(run_id, reason.clone())?;
+                Ok(RunStatus::Cancelled)
+            }
+            _ => result,
+        }
+    }
+
+    /// Cancels a run nobody is driving right now (e.g. blocked on an interrupt or paused)
+    /// by appending `Cancelled`; a run in flight is cancelled through its [RunControl].
+    /// Fails with [KernelError::RunNotFound] for a run without events and with
+    /// [KernelError::RunEnded] for one that completed or was cancelled already.
+    pub fn cancel(&self, run_id: &RunId, reason: Option<String>) -> Result<(), KernelError> {
+        if !self.events.run_exists(run_id)? {
+            return Err(KernelError::RunNotFound(run_id.clone()));
+        }
+        self.ensure_not_ended(run_id)?;
+        self.append_cancelled(run_id, reason)
     }
 
     /// Resumes a blocked run with a signal (e.g. resume value or external signal).
@@ -99,201 +474,898 @@ impl<S: KernelState> Kernel<S> {
         initial_state: S,
         signal: Signal,
     ) -> Result<RunStatus, KernelError> {
+        self.ensure_may_advance(run_id)?;
         let value = match &signal {
             Signal::Resume(v) => v.clone(),
             Signal::Signal { value, .. } => value.clone(),
         };
         self.events.append(run_id, &[Event::Resumed { value }])?;
-        self.run_loop(run_id, initial_state)
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_EVENTS_APPENDED_TOTAL).increment(1);
+        self.run_loop(run_id, initial_state, None)
     }
 
-    /// Inner loop: replay to get state, then step until Complete or Blocked.
-    fn run_loop(&self, run_id: &RunId, initial_state: S) -> Result<RunStatus, KernelError> {
+    /// Resumes the interrupt named by `token` (from [BlockedInfo::resume_token]) with
+    /// `value`: appends `Resumed { value }`, which the step function sees as the
+    /// interrupt's result, then runs until blocked or complete.
+    ///
+    /// Fails with [KernelError::AlreadyResumed] if that interrupt was resumed already, and
+    /// with [KernelError::InvalidResumeToken] if the token names no interrupt of the run
+    /// or one the run is no longer blocked on. The check and the append are not atomic,
+    /// so callers resuming one run concurrently must serialize their calls.
+    pub fn resume_with_value(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        token: &str,
+        value: serde_json::Value,
+    ) -> Result<RunStatus, KernelError> {
+        if !self.events.run_exists(run_id)? {
+            return Err(KernelError::RunNotFound(run_id.clone()));
+        }
+        self.ensure_may_advance(run_id)?;
+        self.check_resume_token(run_id, token)?;
+        self.events.append(run_id, &[Event::Resumed { value }])?;
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_EVENTS_APPENDED_TOTAL).increment(1);
+        self.run_loop(run_id, initial_state, None)
+    }
+
+    /// Takes a single step of the run: restores its state from the log, asks the step
+    /// function for the next decision and carries it out exactly as
+    /// [run_until_blocked](Self::run_until_blocked) would. Steps and runs can be
+    /// interleaved freely, since both continue from the log.
+    pub fn step_once(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<StepOutcome<S>, KernelError> {
+        self.ensure_may_advance(run_id)?;
         let mut state = self.restore_state(run_id, initial_state)?;
+        let before = self.events.head(run_id)?;
+        let result = self.take_step(run_id, &mut state, 1, None);
+        let flushed = self.events.flush();
+        let (next, status) = result?;
+        flushed?;
+        Ok(StepOutcome {
+            next,
+            events: self.events.scan(run_id, before + 1)?,
+            state,
+            status,
+        })
+    }
 
-        loop {
-            let next = self.step.next(&state)?;
-            match next {
-                Next::Emit(evs) => {
-                    if let Some(sink) = &self.effect_sink {
-                        for ev in &evs {
-                            if let Event::StateUpdated { step_id, payload } = ev {
-                                sink.record(
-                                    run_id,
-                                    &RuntimeEffect::StateWrite {
-                                        step_id: step_id.clone(),
-                                        payload: payload.clone(),
-                                    },
-                                );
-                            }
+    /// State of the run right after the event at `seq`, rebuilt with the reducer from the
+    /// run's latest snapshot at or before `seq` if there is one, otherwise from
+    /// `initial_state`.
+    /// Nothing is written. Fails with [KernelError::Compacted] when that replay would need
+    /// archived events.
+    pub fn state_at(&self, run_id: &RunId, seq: Seq, initial_state: S) -> Result<S, KernelError> {
+        const FROM_SEQ: Seq = 1;
+        let snapshot = match &self.snaps {
+            Some(store) => self.checked_snapshot(run_id, store.load_at_or_before(run_id, seq))?,
+            None => None,
+        };
+        let (mut state, from_seq) = match snapshot {
+            Some(snapshot) => (snapshot.state, snapshot.at_seq + 1),
+            None => (initial_state, FROM_SEQ),
+        };
+        let sequenced = self.events.scan(run_id, from_seq)?;
+        if let Some(Event::Compacted { up_to_seq, .. }) = sequenced.first().map(|se| &se.event) {
+            return Err(KernelError::Compacted {
+                run_id: run_id.clone(),
+                up_to_seq: *up_to_seq,
+            });
+        }
+        for se in sequenced.iter().take_while(|se| se.seq <= seq) {
+            self.reducer.apply(&mut state, se)?;
+        }
+        Ok(state)
+    }
+
+    /// Replays the run without executing any actions and checks each recomputed state hash
+    /// against the one the driver recorded (see [KernelState::state_hash]); a compacted run
+    /// is replayed from its latest snapshot. Works in every mode; in
+    /// [KernelMode::VerifyReplay] it is the only way to drive the kernel.
+    pub fn verify_replay(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<VerificationReport, KernelError> {
+        let recorded = execution_log::scan_execution_log(self.events.as_ref(), run_id, 1)?;
+        let (state, sequenced) = self.events_to_replay(run_id, initial_state, 1)?;
+        replay_verifier::verify_sequenced(
+            self.reducer.as_ref(),
+            run_id,
+            state,
+            sequenced,
+            &recorded,
+        )
+    }
+
+    /// Refuses to advance in VerifyReplay mode and runs that were cancelled.
+    fn ensure_may_advance(&self, run_id: &RunId) -> Result<(), KernelError> {
+        if self.mode == KernelMode::VerifyReplay {
+            return Err(KernelError::Validation(
+                "kernel is in VerifyReplay mode; use verify_replay instead of running the run"
+                    .into(),
+            ));
+        }
+        if let Some(Event::Cancelled { .. }) = self.latest_status_event(run_id)? {
+            return Err(KernelError::RunEnded {
+                run_id: run_id.clone(),
+                status: EventKind::Cancelled,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that `token` names the interrupt the run is blocked on.
+    fn check_resume_token(&self, run_id: &RunId, token: &str) -> Result<(), KernelError> {
+        let invalid = || KernelError::InvalidResumeToken {
+            run_id: run_id.clone(),
+            token: token.to_string(),
+        };
+        let seq = parse_resume_token(run_id, token).ok_or_else(invalid)?;
+        let interrupts = self.events.scan_range(
+            run_id,
+            seq,
+            Seq::MAX,
+            &EventFilter::only([EventKind::Interrupted, EventKind::Resumed]),
+        )?;
+        match interrupts.as_slice() {
+            [first, rest @ ..]
+                if first.seq == seq && first.event.kind() == EventKind::Interrupted =>
+            {
+                match rest.first().map(|se| se.event.kind()) {
+                    Some(EventKind::Resumed) => Err(KernelError::AlreadyResumed {
+                        run_id: run_id.clone(),
+                        token: token.to_string(),
+                    }),
+                    // Superseded by a later interrupt
+                    Some(_) => Err(invalid()),
+                    // Still the run's latest interrupt, but it may have moved on since
+                    None => {
+                        let latest = self
+                            .events
+                            .scan_rev(run_id, 1, &timeline::status_events())?;
+                        match latest.first() {
+                            Some(se) if se.seq == seq => Ok(()),
+                            _ => Err(invalid()),
                         }
                     }
-                    if !evs.is_empty() {
-                        self.append_and_apply(run_id, &mut state, &evs)?;
-                    }
                 }
-                Next::Do(action) => {
-                    if let Some(sink) = &self.effect_sink {
-                        match &action {
-                            Action::CallLLM { provider, input } => {
-                                sink.record(
-                                    run_id,
-                                    &RuntimeEffect::LLMCall {
-                                        provider: provider.clone(),
-                                        input: input.clone(),
-                                    },
-                                );
-                            }
-                            Action::CallTool { tool, input } => {
-                                sink.record(
-                                    run_id,
-                                    &RuntimeEffect::ToolCall {
-                                        tool: tool.clone(),
-                                        input: input.clone(),
-                                    },
-                                );
-                            }
-                            _ => {}
-                        }
-                    }
-                    self.policy
-                        .authorize(run_id, &action, &PolicyCtx::default())?;
-                    let before = self.events.head(run_id)?;
-                    let action_id = format!("{}-{}", run_id, before + 1);
-                    let payload = serde_json::to_value(&action)
-                        .map_err(|e| KernelError::Driver(e.to_string()))?;
-                    self.append_and_apply(
-                        run_id,
-                        &mut state,
-                        &[Event::ActionRequested {
-                            action_id: action_id.clone(),
-                            payload,
-                        }],
-                    )?;
-                    let result = self.exec.execute(run_id, &action);
-                    match result {
-                        Ok(ActionResult::Success(output)) => {
-                            self.append_and_apply(
-                                run_id,
-                                &mut state,
-                                &[Event::ActionSucceeded {
-                                    action_id: action_id.clone(),
-                                    output,
-                                }],
-                            )?;
-                        }
-                        Ok(ActionResult::Failure(error)) => {
-                            self.append_and_apply(
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Fails with [KernelError::RunEnded] if the run completed or was cancelled.
+    pub(crate) fn ensure_not_ended(&self, run_id: &RunId) -> Result<(), KernelError> {
+        match self.latest_status_event(run_id)? {
+            Some(event @ (Event::Completed | Event::Cancelled { .. })) => {
+                Err(KernelError::RunEnded {
+                    run_id: run_id.clone(),
+                    status: event.kind(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The run's latest event that changes its status, if any
+    pub(crate) fn latest_status_event(&self, run_id: &RunId) -> Result<Option<Event>, KernelError> {
+        Ok(self
+            .events
+            .scan_rev(run_id, 1, &timeline::status_events())?
+            .into_iter()
+            .next()
+            .map(|se| se.event))
+    }
+
+    fn append_cancelled(&self, run_id: &RunId, reason: Option<String>) -> Result<(), KernelError> {
+        self.events.append(run_id, &[Event::Cancelled { reason }])?;
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_EVENTS_APPENDED_TOTAL).increment(1);
+        self.events.flush()
+    }
+
+    /// Inner loop: replay to get state, then step until Complete or Blocked.
+    fn run_loop(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        control: Option<&RunControl>,
+    ) -> Result<RunStatus, KernelError> {
+        #[cfg(feature = "otel")]
+        let run_span = crate::kernel::otel::SpanScope::run(run_id);
+        let result = self.run_steps(run_id, initial_state, control);
+        // A reported status must be backed by the log, even with a buffering store
+        let flushed = self.events.flush();
+        let result = result.and_then(|status| flushed.map(|()| status));
+        #[cfg(feature = "otel")]
+        {
+            match &result {
+                Ok(RunStatus::Failed { .. }) => run_span.record_error("run failed"),
+                Err(e) => run_span.record_error(&e.to_string()),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// Step until Complete or Blocked (each step in its own span with feature `otel`).
+    /// Steps that continue the run end at a step boundary of the event store, where the
+    /// requests in `control` are honoured.
+    fn run_steps(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        control: Option<&RunControl>,
+    ) -> Result<RunStatus, KernelError> {
+        let mut state = self.restore_state(run_id, initial_state)?;
+        let (Some(policy), Some(store)) = (&self.snapshot_policy, self.snaps.as_deref()) else {
+            return self.steps_until_stopped(run_id, &mut state, control, &mut |_| Ok(()));
+        };
+        let head = self.events.head(run_id)?;
+        std::thread::scope(|scope| {
+            let mut snapshots = AutoSnapshots {
+                policy,
+                store,
+                counted_to: head,
+                since: 0,
+                last_taken: Instant::now(),
+                pending: None,
+            };
+            let status = self.steps_until_stopped(run_id, &mut state, control, &mut |state| {
+                self.auto_snapshot(run_id, state, &mut snapshots, scope, false)
+            })?;
+            self.auto_snapshot(run_id, &mut state, &mut snapshots, scope, true)?;
+            Ok(status)
+        })
+    }
+
+    /// The step loop of [run_steps](Self::run_steps); `after_step` runs at each step
+    /// boundary, before the event store's.
+    fn steps_until_stopped(
+        &self,
+        run_id: &RunId,
+        state: &mut S,
+        control: Option<&RunControl>,
+        after_step: &mut dyn FnMut(&mut S) -> Result<(), KernelError>,
+    ) -> Result<RunStatus, KernelError> {
+        let mut step = 0u64;
+        loop {
+            if let Some(control) = control {
+                // Held while appending, so a concurrent resume or cancel sees the event
+                let mut ctl = control.lock()?;
+                if let Some(stop) = ctl.take_stop() {
+                    let status = match stop {
+                        Event::Paused => RunStatus::Blocked(BlockedInfo {
+                            interrupt: None,
+                            wait_signal: None,
+                            paused: true,
+                            resume_token: None,
+                            budget_exceeded: None,
+                        }),
+                        _ => RunStatus::Cancelled,
+                    };
+                    self.append_and_apply(run_id, state, &[stop])?;
+                    return Ok(status);
+                }
+            }
+            step += 1;
+            if let (_, Some(status)) = self.take_step(run_id, state, step, control)? {
+                return Ok(status);
+            }
+            after_step(state)?;
+            self.events.flush_step(run_id)?;
+        }
+    }
+
+    /// Starts saving a snapshot of `state` in the background when the policy says one is
+    /// due, after waiting for the previous one. A saved snapshot is recorded as
+    /// `SnapshotTaken` once its thread finished; when the run `ended`, the last one is
+    /// awaited so the marker is written before the run returns.
+    fn auto_snapshot<'scope, 'k: 'scope>(
+        &'k self,
+        run_id: &RunId,
+        state: &mut S,
+        snapshots: &mut AutoSnapshots<'scope, 'k, S>,
+        scope: &'scope Scope<'scope, '_>,
+        ended: bool,
+    ) -> Result<(), KernelError> {
+        if let Some((_, write)) = &snapshots.pending {
+            if write.is_finished() {
+                self.finish_snapshot(run_id, state, snapshots)?;
+            }
+        }
+        let head = self.events.head(run_id)?;
+        snapshots.since += head - snapshots.counted_to;
+        snapshots.counted_to = head;
+        if snapshots.due() {
+            self.finish_snapshot(run_id, state, snapshots)?;
+            snapshots.since = 0;
+            snapshots.last_taken = Instant::now();
+            let too_large = snapshots
+                .policy
+                .max_state_bytes
+                .zip(state.snapshot_size())
+                .is_some_and(|(max, size)| size > max);
+            if !too_large {
+                let at_seq = snapshots.counted_to;
+                let snapshot = Snapshot {
+                    run_id: run_id.clone(),
+                    at_seq,
+                    state: state.clone(),
+                    state_version: state.version(),
+                    event_count: at_seq,
+                    content_hash: None,
+                };
+                let store = snapshots.store;
+                snapshots.pending = Some((at_seq, scope.spawn(move || store.save(&snapshot))));
+            }
+        }
+        if ended {
+            self.finish_snapshot(run_id, state, snapshots)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the snapshot being saved, if any, and records it as `SnapshotTaken`.
+    /// A failed save only costs replay time, so it is counted and otherwise ignored.
+    fn finish_snapshot(
+        &self,
+        run_id: &RunId,
+        state: &mut S,
+        snapshots: &mut AutoSnapshots<'_, '_, S>,
+    ) -> Result<(), KernelError> {
+        let Some((at_seq, write)) = snapshots.pending.take() else {
+            return Ok(());
+        };
+        if let Ok(Ok(())) = write.join() {
+            self.append_and_apply(run_id, state, &[Event::SnapshotTaken { at_seq }])?;
+            snapshots.counted_to += 1;
+            return Ok(());
+        }
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_SNAPSHOT_FAILURES_TOTAL).increment(1);
+        Ok(())
+    }
+
+    /// Asks the step function for the next decision and carries it out on `state`. Returns
+    /// the decision and, when it ends or blocks the run, the run status. `step` numbers the
+    /// step's span with feature `otel`; a cancel request in `control` aborts its actions.
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    fn take_step(
+        &self,
+        run_id: &RunId,
+        state: &mut S,
+        step: u64,
+        control: Option<&RunControl>,
+    ) -> Result<(Next, Option<RunStatus>), KernelError> {
+        #[cfg(feature = "otel")]
+        let step_span = crate::kernel::otel::SpanScope::step(step);
+        let next = self.step.next(state)?;
+        match next.clone() {
+            Next::Emit(evs) => {
+                #[cfg(feature = "otel")]
+                step_span.record_emit(&evs);
+                if let Some(sink) = &self.effect_sink {
+                    for ev in &evs {
+                        if let Event::StateUpdated {
+                            step_id, payload, ..
+                        } = ev
+                        {
+                            sink.record(
                                 run_id,
-                                &mut state,
-                                &[Event::ActionFailed { action_id, error }],
-                            )?;
-                            return Ok(RunStatus::Failed { recoverable: false });
-                        }
-                        Err(mut e) => {
-                            let mut attempt = 0u32;
-                            loop {
-                                let action_err = ActionError::from_kernel_error(&e);
-                                let decision = self.policy.retry_strategy_attempt(
-                                    &action_err,
-                                    &action,
-                                    attempt,
-                                );
-                                match decision {
-                                    RetryDecision::Fail => {
-                                        self.append_and_apply(
-                                            run_id,
-                                            &mut state,
-                                            &[Event::ActionFailed {
-                                                action_id: action_id.clone(),
-                                                error: e.to_string(),
-                                            }],
-                                        )?;
-                                        return Ok(RunStatus::Failed { recoverable: false });
-                                    }
-                                    RetryDecision::Retry | RetryDecision::RetryAfterMs(0) => {}
-                                    RetryDecision::RetryAfterMs(ms) => {
-                                        std::thread::sleep(Duration::from_millis(ms));
-                                    }
-                                }
-                                attempt += 1;
-                                match self.exec.execute(run_id, &action) {
-                                    Ok(ActionResult::Success(output)) => {
-                                        self.append_and_apply(
-                                            run_id,
-                                            &mut state,
-                                            &[Event::ActionSucceeded {
-                                                action_id: action_id.clone(),
-                                                output,
-                                            }],
-                                        )?;
-                                        break;
-                                    }
-                                    Ok(ActionResult::Failure(error)) => {
-                                        self.append_and_apply(
-                                            run_id,
-                                            &mut state,
-                                            &[Event::ActionFailed {
-                                                action_id: action_id.clone(),
-                                                error,
-                                            }],
-                                        )?;
-                                        return Ok(RunStatus::Failed { recoverable: false });
-                                    }
-                                    Err(e2) => e = e2,
-                                }
-                            }
+                                &RuntimeEffect::StateWrite {
+                                    step_id: step_id.clone(),
+                                    payload: payload.clone(),
+                                },
+                            );
                         }
                     }
                 }
-                Next::Interrupt(info) => {
-                    if let Some(sink) = &self.effect_sink {
-                        sink.record(
-                            run_id,
-                            &RuntimeEffect::InterruptRaise {
-                                value: info.value.clone(),
-                            },
-                        );
-                    }
-                    self.append_and_apply(
+                if !evs.is_empty() {
+                    self.append_and_apply(run_id, state, &evs)?;
+                }
+            }
+            Next::Do(mut action) => {
+                self.record_action_effect(run_id, &action);
+                if let Some(status) = self.admit(run_id, state, std::slice::from_ref(&action))? {
+                    return Ok((next, Some(status)));
+                }
+                let action_id = format!("{}-{}", run_id, self.events.head(run_id)? + 1);
+                self.append_and_apply(run_id, state, &[requested(&action_id, &action)?])?;
+                self.fill_idempotency_keys(run_id, std::slice::from_mut(&mut action))?;
+                let outcome = self
+                    .action_runner(control)
+                    .run(run_id, &action_id, &action, false)?;
+                #[cfg(feature = "otel")]
+                for (attempt, error) in &outcome.retries {
+                    step_span.record_retry(*attempt, error);
+                }
+                let failed = outcome.failed;
+                self.append_and_apply(run_id, state, &outcome.into_events())?;
+                if failed {
+                    return Ok((next, Some(RunStatus::Failed { recoverable: false })));
+                }
+            }
+            Next::DoAll(batch) => {
+                for action in &batch.actions {
+                    self.record_action_effect(run_id, action);
+                }
+                if let Some(status) = self.admit(run_id, state, &batch.actions)? {
+                    return Ok((next, Some(status)));
+                }
+                let before = self.events.head(run_id)?;
+                let action_ids: Vec<String> = (0..batch.actions.len())
+                    .map(|i| format!("{}-{}", run_id, before + 1 + i as Seq))
+                    .collect();
+                let events = action_ids
+                    .iter()
+                    .zip(&batch.actions)
+                    .map(|(action_id, action)| requested(action_id, action))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.append_and_apply(run_id, state, &events)?;
+                let mut actions = batch.actions;
+                self.fill_idempotency_keys(run_id, &mut actions)?;
+                let requests: Vec<(String, Action)> = action_ids.into_iter().zip(actions).collect();
+                let outcomes = self.run_batch(run_id, &requests, batch.fail_fast, control)?;
+                #[cfg(feature = "otel")]
+                for (attempt, error) in outcomes.iter().flat_map(|o| &o.retries) {
+                    step_span.record_retry(*attempt, error);
+                }
+                let failed = outcomes.iter().any(|outcome| outcome.failed);
+                let events: Vec<Event> = outcomes
+                    .into_iter()
+                    .flat_map(ActionOutcome::into_events)
+                    .collect();
+                self.append_and_apply(run_id, state, &events)?;
+                if failed {
+                    return Ok((next, Some(RunStatus::Failed { recoverable: false })));
+                }
+            }
+            Next::Interrupt(info) => {
+                #[cfg(feature = "otel")]
+                step_span.record_interrupt(&info.value);
+                if let Some(sink) = &self.effect_sink {
+                    sink.record(
                         run_id,
-                        &mut state,
-                        &[Event::Interrupted {
+                        &RuntimeEffect::InterruptRaise {
                             value: info.value.clone(),
-                        }],
-                    )?;
-                    return Ok(RunStatus::Blocked(BlockedInfo {
+                        },
+                    );
+                }
+                self.append_and_apply(
+                    run_id,
+                    state,
+                    &[Event::Interrupted {
+                        value: info.value.clone(),
+                    }],
+                )?;
+                let seq = self.events.head(run_id)?;
+                return Ok((
+                    next,
+                    Some(RunStatus::Blocked(BlockedInfo {
                         interrupt: Some(info),
                         wait_signal: None,
-                    }));
+                        paused: false,
+                        resume_token: Some(resume_token(run_id, seq)),
+                        budget_exceeded: None,
+                    })),
+                ));
+            }
+            Next::Fail(reason) => {
+                #[cfg(feature = "otel")]
+                step_span.record_error(&reason);
+                let failed = Event::Failed { reason, code: None };
+                self.append_and_apply(run_id, state, &[failed])?;
+                return Ok((next, Some(RunStatus::Failed { recoverable: true })));
+            }
+            Next::FailWithCode { code, reason } => {
+                #[cfg(feature = "otel")]
+                step_span.record_error(&reason);
+                let failed = Event::Failed {
+                    reason,
+                    code: Some(code),
+                };
+                self.append_and_apply(run_id, state, &[failed])?;
+                return Ok((next, Some(RunStatus::Failed { recoverable: true })));
+            }
+            Next::Complete => {
+                self.append_and_apply(run_id, state, &[Event::Completed])?;
+                return Ok((next, Some(RunStatus::Completed)));
+            }
+        }
+        Ok((next, None))
+    }
+
+    /// Reports an LLM or tool call to the effect sink, if any.
+    fn record_action_effect(&self, run_id: &RunId, action: &Action) {
+        let Some(sink) = &self.effect_sink else {
+            return;
+        };
+        match action {
+            Action::CallLLM {
+                provider, input, ..
+            } => {
+                sink.record(
+                    run_id,
+                    &RuntimeEffect::LLMCall {
+                        provider: provider.clone(),
+                        input: input.clone(),
+                    },
+                );
+            }
+            Action::CallTool { tool, input, .. } => {
+                sink.record(
+                    run_id,
+                    &RuntimeEffect::ToolCall {
+                        tool: tool.clone(),
+                        input: input.clone(),
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Asks the policy whether `actions` may run and logs its decision. The whole batch is
+    /// refused before any of it is requested: an action [Policy::authorize] denies is logged
+    /// as `PolicyDecision` and `ActionDenied`, and its error returned; one over the budget as
+    /// `PolicyDecision` and `BudgetExceeded`, blocking the run. `None` when all may run,
+    /// after logging an allowing `PolicyDecision` for each.
+    fn admit(
+        &self,
+        run_id: &RunId,
+        state: &mut S,
+        actions: &[Action],
+    ) -> Result<Option<RunStatus>, KernelError> {
+        let mut reasons = Vec::with_capacity(actions.len());
+        for action in actions {
+            let e = match self.authorize(run_id, action) {
+                Ok(reason) => {
+                    reasons.push(reason);
+                    continue;
                 }
-                Next::Complete => {
-                    self.append_and_apply(run_id, &mut state, &[Event::Completed])?;
-                    return Ok(RunStatus::Completed);
+                Err(e) => e,
+            };
+            let reason = denial_message(&e);
+            let denied = [
+                policy_decision(
+                    self.policy.as_ref(),
+                    action,
+                    PolicyDecisionKind::Deny,
+                    POLICY_DENIED,
+                    Some(reason.clone()),
+                ),
+                Event::ActionDenied {
+                    payload: serde_json::to_value(action)
+                        .map_err(|e| KernelError::Driver(e.to_string()))?,
+                    reason,
+                },
+            ];
+            self.append_and_apply(run_id, state, &denied)?;
+            return Err(e);
+        }
+        if let Some((action, exceeded)) = self.check_budget(run_id, actions)? {
+            return self
+                .block_on_budget(run_id, state, action, exceeded)
+                .map(Some);
+        }
+        let allowed: Vec<Event> = actions
+            .iter()
+            .zip(reasons)
+            .map(|(action, reason)| {
+                let policy = self.policy.as_ref();
+                policy_decision(
+                    policy,
+                    action,
+                    PolicyDecisionKind::Allow,
+                    POLICY_AUTHORIZED,
+                    reason,
+                )
+            })
+            .collect();
+        if !allowed.is_empty() {
+            self.append_and_apply(run_id, state, &allowed)?;
+        }
+        Ok(None)
+    }
+
+    /// Asks the policy whether `action` may run, telling it which executor would run it.
+    /// Returns why it may, if the policy said.
+    fn authorize(&self, run_id: &RunId, action: &Action) -> Result<Option<String>, KernelError> {
+        self.policy
+            .authorize_with_reason(run_id, action, &policy_ctx(self.exec.as_ref(), action))
+    }
+
+    /// Asks the policy whether the run's budget allows `actions`, each counted as done
+    /// before the next is checked so a batch is refused whole. `None` when it does, else
+    /// the first action it does not allow.
+    fn check_budget<'a>(
+        &self,
+        run_id: &RunId,
+        actions: &'a [Action],
+    ) -> Result<Option<(&'a Action, BudgetExceeded)>, KernelError> {
+        let mut usage = if self.policy.budget().is_unlimited() {
+            BudgetUsage::default()
+        } else {
+            self.budget_usage(run_id)?
+        };
+        for action in actions {
+            let ctx = PolicyCtx {
+                usage: usage.clone(),
+                ..policy_ctx(self.exec.as_ref(), action)
+            };
+            if let Err(exceeded) = self.policy.check_budget(action, &ctx) {
+                return Ok(Some((action, exceeded)));
+            }
+            usage.record(action, None);
+        }
+        Ok(None)
+    }
+
+    /// What the run spent so far: its completed actions and the usage their outputs report
+    fn budget_usage(&self, run_id: &RunId) -> Result<BudgetUsage, KernelError> {
+        let mut usage = BudgetUsage::default();
+        let mut requested = std::collections::HashMap::new();
+        let events = self.events.scan_range(
+            run_id,
+            1,
+            Seq::MAX,
+            &EventFilter::only([
+                EventKind::ActionRequested,
+                EventKind::ActionSucceeded,
+                EventKind::ActionFailed,
+            ]),
+        )?;
+        for se in events {
+            match se.event {
+                Event::ActionRequested { action_id, payload } => {
+                    if let Ok(action) = serde_json::from_value::<Action>(payload) {
+                        requested.insert(action_id, action);
+                    }
+                }
+                Event::ActionSucceeded {
+                    action_id, output, ..
+                } => {
+                    if let Some(action) = requested.get(&action_id) {
+                        usage.record(action, Some(&output));
+                    }
+                }
+                Event::ActionFailed { action_id, .. } => {
+                    if let Some(action) = requested.get(&action_id) {
+                        usage.record(action, None);
+                    }
                 }
+                _ => {}
             }
         }
+        Ok(usage)
+    }
+
+    /// Appends the denial of `action` and `BudgetExceeded`, and reports the run blocked on it.
+    fn block_on_budget(
+        &self,
+        run_id: &RunId,
+        state: &mut S,
+        action: &Action,
+        exceeded: BudgetExceeded,
+    ) -> Result<RunStatus, KernelError> {
+        self.append_and_apply(
+            run_id,
+            state,
+            &[
+                policy_decision(
+                    self.policy.as_ref(),
+                    action,
+                    PolicyDecisionKind::Deny,
+                    &exceeded.code,
+                    Some(exceeded.reason.clone()),
+                ),
+                Event::BudgetExceeded {
+                    code: exceeded.code.clone(),
+                    reason: exceeded.reason.clone(),
+                },
+            ],
+        )?;
+        Ok(RunStatus::Blocked(BlockedInfo {
+            interrupt: None,
+            wait_signal: None,
+            paused: false,
+            resume_token: None,
+            budget_exceeded: Some(exceeded),
+        }))
+    }
+
+    /// Gives the tool and LLM calls among `actions` that have no idempotency key their
+    /// [default_idempotency_key], numbered on from the actions completed since the run's
+    /// latest `StateUpdated`. Only the executor sees these keys; the log keeps the actions
+    /// as the step requested them, so runs under other ids still compare equal.
+    fn fill_idempotency_keys(
+        &self,
+        run_id: &RunId,
+        actions: &mut [Action],
+    ) -> Result<(), KernelError> {
+        let needs_key =
+            |action: &Action| action.takes_idempotency_key() && action.idempotency_key().is_none();
+        if self.mode == KernelMode::DryRun || !actions.iter().any(needs_key) {
+            return Ok(());
+        }
+        let last_update = self
+            .events
+            .scan_rev(run_id, 1, &EventFilter::only([EventKind::StateUpdated]))?
+            .pop();
+        let (from, step_id) = match last_update {
+            Some(SequencedEvent {
+                seq,
+                event: Event::StateUpdated { step_id, .. },
+                ..
+            }) => (seq + 1, step_id),
+            _ => (1, None),
+        };
+        let completed = self
+            .events
+            .scan_range(
+                run_id,
+                from,
+                Seq::MAX,
+                &EventFilter::only([EventKind::ActionSucceeded, EventKind::ActionFailed]),
+            )?
+            .len() as u32;
+        for (i, action) in actions.iter_mut().enumerate() {
+            if needs_key(action) {
+                let key = default_idempotency_key(run_id, step_id.as_deref(), completed + i as u32);
+                *action = action.clone().with_idempotency_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn action_runner<'a>(&'a self, control: Option<&'a RunControl>) -> ActionRunner<'a> {
+        ActionRunner {
+            exec: &self.exec,
+            policy: self.policy.as_ref(),
+            dry_run: self.mode == KernelMode::DryRun,
+            control,
+        }
+    }
+
+    /// Executes the requested actions of a batch on up to
+    /// [Policy::max_parallel_actions] threads and returns their outcomes in request order.
+    /// With `fail_fast`, actions not started when one fails are skipped.
+    fn run_batch(
+        &self,
+        run_id: &RunId,
+        requests: &[(String, Action)],
+        fail_fast: bool,
+        control: Option<&RunControl>,
+    ) -> Result<Vec<ActionOutcome>, KernelError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let runner = self.action_runner(control);
+        let workers = self.policy.max_parallel_actions().clamp(1, requests.len());
+        let next_index = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        // Executors that block on async work need the caller's runtime on the workers too
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.exec.begin_batch(run_id)?;
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                let tx = tx.clone();
+                let (runner, next_index, failed, runtime) =
+                    (&runner, &next_index, &failed, &runtime);
+                scope.spawn(move || {
+                    let _entered = runtime.as_ref().map(|rt| rt.enter());
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::SeqCst);
+                        let Some((action_id, action)) = requests.get(index) else {
+                            break;
+                        };
+                        let skip = fail_fast && failed.load(Ordering::SeqCst);
+                        let result = runner.run(run_id, action_id, action, skip);
+                        if !matches!(result, Ok(ActionOutcome { failed: false, .. })) {
+                            failed.store(true, Ordering::SeqCst);
+                        }
+                        let _ = tx.send((index, result));
+                    }
+                });
+            }
+        });
+        drop(tx);
+        let ended = self.exec.end_batch(run_id);
+        let mut results: Vec<_> = rx.into_iter().collect();
+        results.sort_by_key(|(index, _)| *index);
+        let outcomes = results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect::<Result<Vec<_>, _>>()?;
+        ended?;
+        Ok(outcomes)
     }
 
     fn restore_state(&self, run_id: &RunId, initial_state: S) -> Result<S, KernelError> {
         const FROM_SEQ: Seq = 1;
         let latest_snapshot = self.load_latest_snapshot(run_id)?;
-        let (mut state, from_seq) = match latest_snapshot {
+        let (state, from_seq) = match latest_snapshot {
             Some(snapshot) => (snapshot.state, snapshot.at_seq + 1),
             None => (initial_state, FROM_SEQ),
         };
-        let sequenced = self.events.scan(run_id, from_seq)?;
+        let (mut state, sequenced) = self.events_to_replay(run_id, state, from_seq)?;
         self.apply_events(run_id, &mut state, sequenced)?;
         Ok(state)
     }
 
+    /// The events from `from_seq` on and the state to apply them to.
+    ///
+    /// When compaction archived the log at `from_seq`, replay starts from the latest
+    /// snapshot instead, which compaction guarantees covers the archived events; without
+    /// one this fails with [KernelError::Compacted].
+    fn events_to_replay(
+        &self,
+        run_id: &RunId,
+        state: S,
+        from_seq: Seq,
+    ) -> Result<(S, Vec<SequencedEvent>), KernelError> {
+        let sequenced = self.events.scan(run_id, from_seq)?;
+        let Some(Event::Compacted { up_to_seq, .. }) = sequenced.first().map(|se| &se.event) else {
+            return Ok((state, sequenced));
+        };
+        let up_to_seq = *up_to_seq;
+        match self.load_latest_snapshot(run_id)? {
+            Some(snapshot) if snapshot.at_seq >= up_to_seq => {
+                let sequenced = self.events.scan(run_id, snapshot.at_seq + 1)?;
+                Ok((snapshot.state, sequenced))
+            }
+            _ => Err(KernelError::Compacted {
+                run_id: run_id.clone(),
+                up_to_seq,
+            }),
+        }
+    }
+
     fn load_latest_snapshot(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
         match &self.snaps {
-            Some(store) => store.load_latest(run_id),
+            Some(store) => self.checked_snapshot(run_id, store.load_latest(run_id)),
             None => Ok(None),
         }
     }
 
+    /// `loaded`, except that a snapshot failing its integrity check becomes `None`, so the
+    /// caller replays the full log. The failure goes to the effect sink as a
+    /// `SnapshotIntegrityFailure` and is logged as a warning (feature `tracing`).
+    fn checked_snapshot(
+        &self,
+        run_id: &RunId,
+        loaded: Result<Option<Snapshot<S>>, KernelError>,
+    ) -> Result<Option<Snapshot<S>>, KernelError> {
+        let Err(KernelError::SnapshotIntegrity { at_seq, reason, .. }) = loaded else {
+            return loaded;
+        };
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            run_id = %run_id,
+            at_seq,
+            reason = %reason,
+            "snapshot failed its integrity check; replaying the full event log"
+        );
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_SNAPSHOT_INTEGRITY_FAILURES_TOTAL)
+            .increment(1);
+        if let Some(sink) = &self.effect_sink {
+            sink.record(
+                run_id,
+                &RuntimeEffect::SnapshotIntegrityFailure { at_seq, reason },
+            );
+        }
+        Ok(None)
+    }
+
     fn append_and_apply(
         &self,
         run_id: &RunId,
@@ -304,11 +1376,65 @@ impl<S: KernelState> Kernel<S> {
             return Ok(());
         }
         let before = self.events.head(run_id)?;
-        self.events.append(run_id, events)?;
+        let events = self.annotate_state_updates(before, state, events)?;
+        self.events.append(run_id, &events)?;
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::kernel::metrics::KERNEL_EVENTS_APPENDED_TOTAL)
+            .increment(events.len() as u64);
         let sequenced = self.events.scan(run_id, before + 1)?;
         self.apply_events(run_id, state, sequenced)
     }
 
+    /// Fills in the `state_hash` and `merge_report` of each StateUpdated in `events` with
+    /// the hash of the state after it and the reducer's [MergeReport], applying the batch to
+    /// a copy of `state` at the seqs the events will get, so a payload the reducer rejects
+    /// (e.g. on a [MergeStrategy::FailOnConflict] key) fails the step before anything is
+    /// appended. Returns `events` unchanged when there is nothing to record.
+    ///
+    /// [MergeReport]: crate::kernel::MergeReport
+    /// [MergeStrategy::FailOnConflict]: crate::kernel::MergeStrategy::FailOnConflict
+    fn annotate_state_updates<'a>(
+        &self,
+        before: Seq,
+        state: &S,
+        events: &'a [Event],
+    ) -> Result<Cow<'a, [Event]>, KernelError> {
+        if !events
+            .iter()
+            .any(|e| matches!(e, Event::StateUpdated { .. }))
+        {
+            return Ok(Cow::Borrowed(events));
+        }
+        let mut scratch = state.clone();
+        let mut annotated = Vec::with_capacity(events.len());
+        let mut changed = false;
+        for (seq, event) in (before + 1..).zip(events) {
+            let mut se = SequencedEvent::new(seq, event.clone());
+            let report = self.reducer.apply_with_report(&mut scratch, &se)?;
+            if let Event::StateUpdated {
+                state_hash,
+                merge_report,
+                ..
+            } = &mut se.event
+            {
+                if let Some(hash) = scratch.state_hash()? {
+                    *state_hash = Some(hex::encode(hash));
+                    changed = true;
+                }
+                if let Some(report) = report.filter(|r| !r.is_empty()) {
+                    *merge_report = Some(report);
+                    changed = true;
+                }
+            }
+            annotated.push(se.event);
+        }
+        Ok(if changed {
+            Cow::Owned(annotated)
+        } else {
+            Cow::Borrowed(events)
+        })
+    }
+
     fn apply_events(
         &self,
         run_id: &RunId,
@@ -317,7 +1443,9 @@ impl<S: KernelState> Kernel<S> {
     ) -> Result<(), KernelError> {
         for se in sequenced {
             self.reducer.apply(state, &se)?;
-            self.save_snapshot(run_id, se.seq, state)?;
+            if self.snapshot_policy.is_none() {
+                self.save_snapshot(run_id, se.seq, state)?;
+            }
         }
         Ok(())
     }
@@ -328,6 +1456,9 @@ impl<S: KernelState> Kernel<S> {
                 run_id: run_id.clone(),
                 at_seq,
                 state: state.clone(),
+                state_version: state.version(),
+                event_count: at_seq,
+                content_hash: None,
             })?;
         }
         Ok(())
@@ -335,7 +1466,8 @@ impl<S: KernelState> Kernel<S> {
 
     /// Replays the run from the event log without executing external actions; returns final state.
     ///
-    /// Scans all events for the run (from seq 1), applies each with the Reducer in order.
+    /// Scans all events for the run (from seq 1), applies each with the Reducer in order;
+    /// for a compacted run, from its latest snapshot (see [crate::kernel::compaction]).
     /// Does not call ActionExecutor; any ActionRequested is satisfied by the following
     /// ActionSucceeded/ActionFailed already stored in the log (reducer applies them).
     pub fn replay(&self, run_id: &RunId, initial_state: S) -> Result<S, KernelError> {
@@ -360,11 +1492,11 @@ impl<S: KernelState> Kernel<S> {
         snapshot: Option<&Snapshot<S>>,
     ) -> Result<S, KernelError> {
         const FROM_SEQ: Seq = 1;
-        let (mut state, from_seq) = match snapshot {
+        let (state, from_seq) = match snapshot {
             Some(snap) => (snap.state.clone(), snap.at_seq + 1),
             None => (initial_state, FROM_SEQ),
         };
-        let sequenced = self.events.scan(run_id, from_seq)?;
+        let (mut state, sequenced) = self.events_to_replay(run_id, state, from_seq)?;
         for se in sequenced {
             self.reducer.apply(&mut state, &se)?;
         }
@@ -383,9 +1515,13 @@ mod tests {
     use crate::kernel::action::{Action, ActionError, ActionExecutor, ActionResult};
     use crate::kernel::event::Event;
     use crate::kernel::event_store::{InMemoryEventStore, SharedEventStore};
-    use crate::kernel::policy::RetryWithBackoffPolicy;
+    use crate::kernel::policy::{
+        AllowListPolicy, BudgetRules, RetryWithBackoffPolicy, TimeoutPolicy,
+        BUDGET_ACTIONS_PER_KIND_EXCEEDED, BUDGET_TOKENS_EXCEEDED,
+    };
     use crate::kernel::runtime_effect::RuntimeEffect;
-    use crate::kernel::snapshot::{InMemorySnapshotStore, SnapshotStore};
+    use crate::kernel::snapshot::{InMemorySnapshotStore, SnapshotPolicy, SnapshotStore};
+    use crate::kernel::step::ActionBatch;
     use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor, NoopStepFn};
     use crate::kernel::StateUpdatedOnlyReducer;
     use serde::{Deserialize, Serialize};
@@ -398,6 +1534,10 @@ mod tests {
         fn version(&self) -> u32 {
             1
         }
+
+        fn snapshot_size(&self) -> Option<usize> {
+            Some(self.0 as usize)
+        }
     }
 
     /// Mock executor that counts execute() calls; used to assert replay does not call executor.
@@ -459,6 +1599,8 @@ mod tests {
                 Ok(Next::Emit(vec![Event::StateUpdated {
                     step_id: Some("node1".into()),
                     payload: serde_json::to_value(&TestState(1)).unwrap(),
+                    state_hash: None,
+                    merge_report: None,
                 }]))
             } else {
                 Ok(Next::Complete)
@@ -472,17 +1614,160 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let run_id = "run-complete".to_string();
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
         assert!(matches!(status, RunStatus::Completed));
     }
 
+    #[test]
+    fn merge_reports_are_recorded_and_conflicting_updates_are_not_appended() {
+        use crate::kernel::{MergeRules, MergeStrategy, MergingReducer};
+
+        #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+        struct Ticket {
+            owner: Option<String>,
+            notes: Vec<String>,
+        }
+        impl KernelState for Ticket {
+            fn version(&self) -> u32 {
+                1
+            }
+        }
+        /// Emits partial updates: open, triage, then a reassignment the rules forbid
+        struct TicketStep;
+        impl StepFn<Ticket> for TicketStep {
+            fn next(&self, state: &Ticket) -> Result<Next, KernelError> {
+                let payload = match state.notes.len() {
+                    0 => serde_json::json!({"notes": ["opened"], "owner": "ann"}),
+                    1 => serde_json::json!({"notes": "triaged"}),
+                    _ => serde_json::json!({"owner": "bob"}),
+                };
+                Ok(Next::Emit(vec![Event::StateUpdated {
+                    step_id: None,
+                    payload,
+                    state_hash: None,
+                    merge_report: None,
+                }]))
+            }
+        }
+
+        let rules = MergeRules::new(MergeStrategy::LastWriteWins)
+            .with_key("notes", MergeStrategy::Append)
+            .with_key("owner", MergeStrategy::FailOnConflict);
+        let k = Kernel::<Ticket> {
+            events: Box::new(InMemoryEventStore::new()),
+            snaps: None,
+            reducer: Box::new(MergingReducer::new(rules)),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(TicketStep),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "run-merge".to_string();
+        let err = k.run_until_blocked(&run_id, Ticket::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "merge conflict on key 'owner': current \"ann\" vs update \"bob\""
+        );
+
+        let reports: Vec<_> = k
+            .events
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|se| match se.event {
+                Event::StateUpdated { merge_report, .. } => merge_report.unwrap(),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].merged, ["notes", "owner"]);
+        assert_eq!(reports[1].merged, ["notes"]);
+        assert!(reports.iter().all(|r| r.conflicts.is_empty()));
+        assert_eq!(
+            k.replay(&run_id, Ticket::default()).unwrap(),
+            Ticket {
+                owner: Some("ann".into()),
+                notes: vec!["opened".into(), "triaged".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn run_until_blocked_flushes_a_buffered_store_before_reporting() {
+        use crate::kernel::{BufferConfig, BufferedEventStore, SharedEventStore};
+
+        let log = SharedEventStore::new();
+        let events = BufferedEventStore::with_config(
+            SharedEventStore(log.0.clone()),
+            BufferConfig::batched(usize::MAX, Duration::from_secs(3600)),
+        );
+        let k = Kernel::<TestState> {
+            events: Box::new(events),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(EmitOnceThenCompleteStep(AtomicUsize::new(0))),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "run-buffered".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let stored = log.scan(&run_id, 1).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(matches!(stored[1].event, Event::Completed));
+        assert_eq!(k.replay(&run_id, TestState(0)).unwrap(), TestState(1));
+    }
+
+    /// Step that stops the run with a reason.
+    struct FailingStep;
+    impl StepFn<TestState> for FailingStep {
+        fn next(&self, _state: &TestState) -> Result<Next, KernelError> {
+            Ok(Next::Fail("step limit of 3 exceeded".into()))
+        }
+    }
+
+    #[test]
+    fn run_until_blocked_fail_records_failed_event() {
+        let k = Kernel::<TestState> {
+            events: Box::new(InMemoryEventStore::new()),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(FailingStep),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "run-fail".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Failed { recoverable: true }));
+
+        let events = k.events.scan(&run_id, 1).unwrap();
+        assert!(matches!(
+            &events.last().unwrap().event,
+            Event::Failed { reason, code: None } if reason == "step limit of 3 exceeded"
+        ));
+        let timeline = k.run_timeline(&run_id).unwrap();
+        assert!(matches!(
+            timeline.final_status,
+            timeline::RunStatusSummary::Failed { recoverable: true }
+        ));
+    }
+
     #[test]
     fn run_until_blocked_persists_latest_snapshot_on_completion() {
         let snapshots = Arc::new(InMemorySnapshotStore::new());
@@ -490,11 +1775,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: Some(Box::new(SharedSnapshotStoreHandle(Arc::clone(&snapshots)))),
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(EmitOnceThenCompleteStep(AtomicUsize::new(0))),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let run_id = "run-snapshot-complete".to_string();
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -508,17 +1794,162 @@ mod tests {
         assert_eq!(snapshot.state, TestState(1));
     }
 
+    /// Keeps every saved snapshot; fails every save while `failing` is set
+    #[derive(Clone, Default)]
+    struct SnapshotHistory {
+        saved: Arc<Mutex<Vec<Snapshot<TestState>>>>,
+        failing: bool,
+    }
+    impl SnapshotStore<TestState> for SnapshotHistory {
+        fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<TestState>>, KernelError> {
+            let saved = self.saved.lock().unwrap();
+            Ok(saved.iter().rev().find(|s| &s.run_id == run_id).cloned())
+        }
+
+        fn save(&self, snapshot: &Snapshot<TestState>) -> Result<(), KernelError> {
+            if self.failing {
+                return Err(KernelError::SnapshotStore("disk full".into()));
+            }
+            self.saved.lock().unwrap().push(snapshot.clone());
+            Ok(())
+        }
+    }
+
+    /// Emits one StateUpdated per step, counting up, and completes after `steps` of them
+    struct CountToStep(u32);
+    impl StepFn<TestState> for CountToStep {
+        fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+            if state.0 == self.0 {
+                return Ok(Next::Complete);
+            }
+            Ok(Next::Emit(vec![Event::StateUpdated {
+                step_id: Some(format!("count-{}", state.0 + 1)),
+                payload: serde_json::to_value(TestState(state.0 + 1)).unwrap(),
+                state_hash: None,
+                merge_report: None,
+            }]))
+        }
+    }
+
+    fn counting_kernel(
+        events: &Arc<InMemoryEventStore>,
+        snapshots: Option<SnapshotHistory>,
+        policy: SnapshotPolicy,
+    ) -> Kernel<TestState> {
+        Kernel {
+            events: Box::new(SharedEventStore(Arc::clone(events))),
+            snaps: snapshots.map(|s| Box::new(s) as Box<dyn SnapshotStore<TestState>>),
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(CountToStep(99)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: Some(policy),
+        }
+    }
+
+    #[test]
+    fn snapshot_policy_snapshots_every_n_events_and_records_markers() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let history = SnapshotHistory::default();
+        let k = counting_kernel(
+            &events,
+            Some(history.clone()),
+            SnapshotPolicy::every_n_events(10),
+        );
+        let run_id = "run-snapshot-policy".to_string();
+        // 99 StateUpdated + Completed: 100 events
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let saved = history.saved.lock().unwrap().clone();
+        assert_eq!(saved.len(), 10);
+        assert!(saved.iter().all(|s| s.state_version == 1));
+        let log = events.scan(&run_id, 1).unwrap();
+        let markers: Vec<Seq> = log
+            .iter()
+            .filter_map(|se| match se.event {
+                Event::SnapshotTaken { at_seq } => Some(at_seq),
+                _ => None,
+            })
+            .collect();
+        let saved_seqs: Vec<Seq> = saved.iter().map(|s| s.at_seq).collect();
+        assert_eq!(markers, saved_seqs);
+        assert_eq!(log.len(), 110);
+        // Each snapshot holds the state after the event at its seq
+        for snapshot in &saved {
+            assert_eq!(
+                k.state_at(&run_id, snapshot.at_seq, TestState(0)).unwrap(),
+                snapshot.state
+            );
+        }
+
+        let from_snapshot = k.replay_from_snapshot(&run_id, TestState(0)).unwrap();
+        let plain = counting_kernel(&events, None, SnapshotPolicy::default());
+        let full = plain.replay(&run_id, TestState(0)).unwrap();
+        assert_eq!(from_snapshot, full);
+        assert_eq!(full, TestState(99));
+    }
+
+    #[test]
+    fn failing_snapshots_never_fail_the_run() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let history = SnapshotHistory {
+            failing: true,
+            ..SnapshotHistory::default()
+        };
+        let k = counting_kernel(&events, Some(history), SnapshotPolicy::every_n_events(10));
+        let run_id = "run-snapshot-failing".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let log = events.scan(&run_id, 1).unwrap();
+        assert_eq!(log.len(), 100);
+        assert!(log
+            .iter()
+            .all(|se| !matches!(se.event, Event::SnapshotTaken { .. })));
+    }
+
+    #[test]
+    fn snapshot_policy_interval_and_size_limit() {
+        let history = SnapshotHistory::default();
+        let k = counting_kernel(
+            &Arc::new(InMemoryEventStore::new()),
+            Some(history.clone()),
+            SnapshotPolicy::every(Duration::ZERO),
+        );
+        let run_id = "run-snapshot-interval".to_string();
+        k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        // Every step boundary with new events, markers aside, took one
+        assert_eq!(history.saved.lock().unwrap().len(), 100);
+
+        let history = SnapshotHistory::default();
+        let k = counting_kernel(
+            &Arc::new(InMemoryEventStore::new()),
+            Some(history.clone()),
+            SnapshotPolicy::every_n_events(10).with_max_state_bytes(50),
+        );
+        k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        // TestState(n) is n bytes big in this test's size estimate
+        let saved = history.saved.lock().unwrap();
+        assert_eq!(
+            saved.iter().map(|s| s.state.0).collect::<Vec<_>>(),
+            [10, 20, 30, 40, 50]
+        );
+    }
+
     #[test]
     fn kernel_replay_mode_determinism_guard_traps_clock() {
         let k = Kernel::<TestState> {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Replay,
+            snapshot_policy: None,
         };
         let guard = k.determinism_guard();
         let err = guard.check_clock_access().unwrap_err();
@@ -533,11 +1964,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(EmitOnceThenCompleteStep(AtomicUsize::new(0))),
             policy: Box::new(AllowAllPolicy),
             effect_sink: Some(Box::new(sink)),
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let run_id = "run-effect-capture".to_string();
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -558,6 +1990,103 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    struct HashedState(std::collections::HashMap<String, u32>);
+    impl KernelState for HashedState {
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+            crate::kernel::canonical::canonical_state_hash(self).map(Some)
+        }
+    }
+
+    /// Emits two StateUpdated events in one batch, then completes.
+    struct CountTwiceStep;
+    impl StepFn<HashedState> for CountTwiceStep {
+        fn next(&self, state: &HashedState) -> Result<Next, KernelError> {
+            if !state.0.is_empty() {
+                return Ok(Next::Complete);
+            }
+            let update = |n: u32| Event::StateUpdated {
+                step_id: Some(format!("count-{}", n)),
+                payload: serde_json::json!({ "a": n, "b": n * 10 }),
+                state_hash: None,
+                merge_report: None,
+            };
+            Ok(Next::Emit(vec![update(1), update(2)]))
+        }
+    }
+
+    #[test]
+    fn recorded_state_hashes_match_the_replayed_log() {
+        let inner = Arc::new(InMemoryEventStore::new());
+        let k = Kernel::<HashedState> {
+            events: Box::new(SharedEventStore(inner.clone())),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(CountTwiceStep),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id: RunId = "run-hashed".into();
+        k.run_until_blocked(&run_id, HashedState::default())
+            .unwrap();
+
+        let recorded: Vec<Option<String>> = inner
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|se| match se.event {
+                Event::StateUpdated { state_hash, .. } => Some(state_hash),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(recorded.len(), 2);
+        assert_ne!(recorded[0], recorded[1]);
+
+        let log = crate::kernel::build_execution_log_with_hashes(
+            inner.as_ref(),
+            &StateUpdatedOnlyReducer,
+            &run_id,
+            HashedState::default(),
+        )
+        .unwrap();
+        let replayed: Vec<Option<String>> = log
+            .iter()
+            .filter(|entry| entry.step_id.is_some())
+            .map(|entry| entry.state_hash.map(hex::encode))
+            .collect();
+        assert_eq!(replayed, recorded);
+
+        // States without a hash record none
+        let store = InMemoryEventStore::new();
+        let k = Kernel::<TestState> {
+            events: Box::new(store),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(EmitOnceThenCompleteStep(AtomicUsize::new(0))),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        let events = k.events.scan(&run_id, 1).unwrap();
+        assert!(matches!(
+            &events[0].event,
+            Event::StateUpdated {
+                state_hash: None,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn run_timeline_after_complete_has_events_and_json() {
         let store = InMemoryEventStore::new();
@@ -565,11 +2094,12 @@ mod tests {
             events: Box::new(store),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let run_id = "timeline-run".to_string();
         let _ = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -603,32 +2133,202 @@ mod tests {
         let events = Box::new(SharedEventStore(inner.clone()));
         let run_id = "run-interrupt-resume".to_string();
         let k = Kernel::<TestState> {
-            events,
+            events,
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(InterruptOnceStep(false)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Blocked(_)));
+
+        let k2 = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(inner)),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(InterruptOnceStep(true)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let status2 = k2
+            .resume(&run_id, TestState(0), Signal::Resume(serde_json::json!(1)))
+            .unwrap();
+        assert!(matches!(status2, RunStatus::Completed));
+    }
+
+    /// Interrupts on every step until resumed twice, recording resume values in the state
+    struct InterruptTwiceStep;
+    impl StepFn<TestState> for InterruptTwiceStep {
+        fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+            if state.0 < 2 {
+                Ok(Next::Interrupt(InterruptInfo {
+                    value: serde_json::json!({ "approve": state.0 }),
+                }))
+            } else {
+                Ok(Next::Complete)
+            }
+        }
+    }
+
+    /// Adds each resume value to the state
+    struct ResumeSumReducer;
+    impl Reducer<TestState> for ResumeSumReducer {
+        fn apply(&self, state: &mut TestState, event: &SequencedEvent) -> Result<(), KernelError> {
+            if let Event::Resumed { value } = &event.event {
+                state.0 += value.as_u64().unwrap_or_default() as u32;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn resume_with_value_checks_the_token_and_rejects_a_second_resume() {
+        let inner = Arc::new(InMemoryEventStore::new());
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(inner.clone())),
+            snaps: None,
+            reducer: Box::new(ResumeSumReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(InterruptTwiceStep),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "run-resume-token".to_string();
+        let blocked = |status: RunStatus| match status {
+            RunStatus::Blocked(info) => (info.interrupt.unwrap().value, info.resume_token.unwrap()),
+            other => panic!("expected Blocked, got {:?}", other),
+        };
+        assert!(matches!(
+            k.resume_with_value(&run_id, TestState(0), "x", serde_json::json!(1)),
+            Err(KernelError::RunNotFound(_))
+        ));
+
+        let (payload, first) = blocked(k.run_until_blocked(&run_id, TestState(0)).unwrap());
+        assert_eq!(payload, serde_json::json!({ "approve": 0 }));
+        for wrong in ["", "other-run:interrupt:1", "run-resume-token:interrupt:7"] {
+            assert!(matches!(
+                k.resume_with_value(&run_id, TestState(0), wrong, serde_json::json!(1)),
+                Err(KernelError::InvalidResumeToken { ref token, .. }) if token == wrong
+            ));
+        }
+
+        let (payload, second) = blocked(
+            k.resume_with_value(&run_id, TestState(0), &first, serde_json::json!(1))
+                .unwrap(),
+        );
+        assert_eq!(payload, serde_json::json!({ "approve": 1 }));
+        assert_ne!(first, second);
+        assert!(matches!(
+            k.resume_with_value(&run_id, TestState(0), &first, serde_json::json!(1)),
+            Err(KernelError::AlreadyResumed { ref token, .. }) if token == &first
+        ));
+
+        let status = k
+            .resume_with_value(&run_id, TestState(0), &second, serde_json::json!(1))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert!(matches!(
+            k.resume_with_value(&run_id, TestState(0), &second, serde_json::json!(1)),
+            Err(KernelError::AlreadyResumed { .. })
+        ));
+        let resumed = inner
+            .scan(&run_id, 1)
+            .unwrap()
+            .iter()
+            .filter(|se| matches!(se.event, Event::Resumed { .. }))
+            .count();
+        assert_eq!(resumed, 2);
+    }
+
+    #[test]
+    fn resume_with_value_rejects_an_interrupt_the_run_moved_past() {
+        let k = Kernel::<TestState> {
+            events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(InterruptOnceStep(false)),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
-        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
-        assert!(matches!(status, RunStatus::Blocked(_)));
+        let run_id = "run-resume-stale".to_string();
+        let token = |status: RunStatus| match status {
+            RunStatus::Blocked(info) => info.resume_token.unwrap(),
+            other => panic!("expected Blocked, got {:?}", other),
+        };
+        let stale = token(k.run_until_blocked(&run_id, TestState(0)).unwrap());
+        // Running the run again raises a new interrupt without resuming the first
+        let current = token(k.run_until_blocked(&run_id, TestState(0)).unwrap());
+        assert!(matches!(
+            k.resume_with_value(&run_id, TestState(0), &stale, serde_json::json!(1)),
+            Err(KernelError::InvalidResumeToken { .. })
+        ));
+        k.cancel(&run_id, None).unwrap();
+        assert!(matches!(
+            k.resume_with_value(&run_id, TestState(0), &current, serde_json::json!(1)),
+            Err(KernelError::RunEnded { .. })
+        ));
+    }
 
-        let k2 = Kernel::<TestState> {
-            events: Box::new(SharedEventStore(inner)),
+    #[test]
+    fn cancel_ends_a_blocked_run_and_rejects_unknown_or_ended_runs() {
+        let inner = Arc::new(InMemoryEventStore::new());
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(inner.clone())),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
-            step: Box::new(InterruptOnceStep(true)),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(InterruptOnceStep(false)),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
-        let status2 = k2
-            .resume(&run_id, TestState(0), Signal::Resume(serde_json::json!(1)))
-            .unwrap();
-        assert!(matches!(status2, RunStatus::Completed));
+        let run_id = "run-cancel-blocked".to_string();
+        assert!(matches!(
+            k.cancel(&run_id, None),
+            Err(KernelError::RunNotFound(id)) if id == run_id
+        ));
+
+        k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        k.cancel(&run_id, Some("no longer needed".into())).unwrap();
+        let last = inner.scan(&run_id, 1).unwrap().pop().unwrap();
+        assert!(matches!(
+            last.event,
+            Event::Cancelled { reason: Some(ref r) } if r == "no longer needed"
+        ));
+
+        let ended = |result: Result<RunStatus, KernelError>| {
+            matches!(
+                result,
+                Err(KernelError::RunEnded {
+                    status: EventKind::Cancelled,
+                    ..
+                })
+            )
+        };
+        assert!(matches!(
+            k.cancel(&run_id, None),
+            Err(KernelError::RunEnded { .. })
+        ));
+        assert!(ended(k.run_until_blocked(&run_id, TestState(0))));
+        assert!(ended(k.resume(
+            &run_id,
+            TestState(0),
+            Signal::Resume(serde_json::json!(1))
+        )));
+        assert_eq!(inner.head(&run_id).unwrap(), 2);
     }
 
     #[test]
@@ -638,11 +2338,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: Some(Box::new(SharedSnapshotStoreHandle(Arc::clone(&snapshots)))),
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(InterruptOnceStep(false)),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let run_id = "run-interrupt-checkpoint".to_string();
 
@@ -667,6 +2368,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::json!(42),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -676,11 +2379,12 @@ mod tests {
             events: Box::new(store),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(CountingActionExecutor::new(Arc::clone(&exec_count))),
+            exec: Arc::new(CountingActionExecutor::new(Arc::clone(&exec_count))),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let _ = k.replay(&run_id, TestState(0)).unwrap();
         assert_eq!(
@@ -702,10 +2406,14 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::json!(10),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::json!(20),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -715,11 +2423,12 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::new(store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let initial = TestState(0);
         let s1 = k.replay(&run_id, initial.clone()).unwrap();
@@ -763,11 +2472,12 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::clone(&store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(TransientThenSuccessExecutor::new(2)),
+            exec: Arc::new(TransientThenSuccessExecutor::new(2)),
             step: Box::new(DoOnceThenCompleteStep::new()),
             policy: Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 3, 0)),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
         assert!(matches!(status, RunStatus::Completed));
@@ -793,11 +2503,12 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::clone(&store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(CountingActionExecutor::new(Arc::new(AtomicUsize::new(0)))),
+            exec: Arc::new(CountingActionExecutor::new(Arc::new(AtomicUsize::new(0)))),
             step: Box::new(DoOnceThenCompleteStep::new()),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
         assert!(
@@ -837,6 +2548,7 @@ mod tests {
                 Ok(Next::Do(Action::CallTool {
                     tool: "dummy".into(),
                     input: serde_json::json!(null),
+                    idempotency_key: None,
                 }))
             } else {
                 Ok(Next::Complete)
@@ -862,6 +2574,7 @@ mod tests {
                 Ok(Next::Do(Action::CallTool {
                     tool: "dummy".into(),
                     input: serde_json::json!(null),
+                    idempotency_key: None,
                 }))
             } else {
                 Ok(Next::Complete)
@@ -910,14 +2623,20 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::json!(10),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::json!(20),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("c".into()),
                         payload: serde_json::json!(30),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -929,17 +2648,21 @@ mod tests {
                 run_id: run_id.clone(),
                 at_seq: 2,
                 state: TestState(20),
+                state_version: 1,
+                event_count: 2,
+                content_hash: None,
             })
             .unwrap();
         let k = Kernel::<TestState> {
             events: Box::new(store),
             snaps: Some(Box::new(snaps)),
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
         let state = k.replay_from_snapshot(&run_id, TestState(0)).unwrap();
         assert_eq!(state.0, 30, "only events after at_seq=2 (seq 3) applied");
@@ -956,14 +2679,20 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::json!(1),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::json!(2),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("c".into()),
                         payload: serde_json::json!(3),
+                        state_hash: None,
+                        merge_report: None,
                     },
                 ],
             )
@@ -975,6 +2704,9 @@ mod tests {
                 run_id: run_id.clone(),
                 at_seq: 2,
                 state: TestState(2),
+                state_version: 1,
+                event_count: 2,
+                content_hash: None,
             })
             .unwrap();
         let apply_count = Arc::new(AtomicUsize::new(0));
@@ -982,11 +2714,12 @@ mod tests {
             events: Box::new(store),
             snaps: Some(Box::new(SharedSnapshotStoreHandle(Arc::clone(&snapshots)))),
             reducer: Box::new(CountingStateReducer(Arc::clone(&apply_count))),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
 
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -1010,13 +2743,14 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::clone(&store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(ScriptedActionExecutor::new(vec![Ok(
+            exec: Arc::new(ScriptedActionExecutor::new(vec![Ok(
                 ActionResult::Failure("boom".into()),
             )])),
             step: Box::new(DoThenCompleteStep::new()),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
 
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -1051,6 +2785,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dry_run_records_simulated_results_without_executing() {
+        use crate::kernel::policy::{simulated_output, StubbedPolicy};
+
+        /// Searches, publishes, writes state, then completes.
+        struct SearchThenPublishStep(AtomicUsize);
+        impl StepFn<TestState> for SearchThenPublishStep {
+            fn next(&self, _state: &TestState) -> Result<Next, KernelError> {
+                let call = |tool: &str| {
+                    Next::Do(Action::CallTool {
+                        tool: tool.into(),
+                        input: serde_json::json!(null),
+                        idempotency_key: None,
+                    })
+                };
+                Ok(match self.0.fetch_add(1, Ordering::SeqCst) {
+                    0 => call("search"),
+                    1 => call("publish"),
+                    2 => Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("done".into()),
+                        payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
+                    }]),
+                    _ => Next::Complete,
+                })
+            }
+        }
+
+        let store = Arc::new(InMemoryEventStore::new());
+        let snapshots = Arc::new(InMemorySnapshotStore::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: Some(Box::new(SharedSnapshotStoreHandle(Arc::clone(&snapshots)))),
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(CountingActionExecutor::new(Arc::clone(&calls))),
+            step: Box::new(SearchThenPublishStep(AtomicUsize::new(0))),
+            policy: Box::new(StubbedPolicy::new(AllowAllPolicy).with_tool_stub(
+                "search",
+                ActionResult::Success(serde_json::json!(["stubbed hit"])),
+            )),
+            effect_sink: None,
+            mode: KernelMode::DryRun,
+            snapshot_policy: None,
+        };
+        let run_id = "run-dry".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            0,
+            "no executor call in DryRun"
+        );
+
+        let outputs: Vec<_> = store
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|e| match e.event {
+                Event::ActionSucceeded {
+                    output, dry_run, ..
+                } => Some((output, dry_run)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            outputs,
+            [
+                (serde_json::json!(["stubbed hit"]), true),
+                (simulated_output(), true)
+            ]
+        );
+        assert_eq!(
+            snapshots.load_latest(&run_id).unwrap().unwrap().state,
+            TestState(1)
+        );
+    }
+
     #[test]
     fn retry_then_success_has_single_terminal_success_event() {
         let store = Arc::new(InMemoryEventStore::new());
@@ -1064,11 +2877,12 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::clone(&store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(ArcExecutor(Arc::clone(&exec))),
+            exec: Arc::new(ArcExecutor(Arc::clone(&exec))),
             step: Box::new(DoThenCompleteStep::new()),
             policy: Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 3, 0)),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
 
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -1099,6 +2913,64 @@ mod tests {
         assert_eq!(failed_count, 0, "success path must not emit ActionFailed");
     }
 
+    #[test]
+    fn each_retry_is_scheduled_after_its_decision_with_the_wait() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let run_id = "run-retry-scheduled".to_string();
+        let exec = Arc::new(ScriptedActionExecutor::new(vec![
+            Err(KernelError::Executor(ActionError::transient("transient-1"))),
+            Err(KernelError::Executor(ActionError::transient("transient-2"))),
+            Ok(ActionResult::Success(serde_json::json!("ok"))),
+        ]));
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(ArcExecutor(Arc::clone(&exec))),
+            step: Box::new(DoThenCompleteStep::new()),
+            policy: Box::new(RetryWithBackoffPolicy::with_exponential_backoff(
+                AllowAllPolicy,
+                3,
+                2,
+                None,
+                0.0,
+            )),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let events = store.scan(&run_id, 1).unwrap();
+        let scheduled: Vec<_> = events
+            .windows(2)
+            .filter_map(|pair| match (&pair[0].event, &pair[1].event) {
+                (
+                    Event::PolicyDecision {
+                        decision: PolicyDecisionKind::Retry,
+                        ..
+                    },
+                    Event::RetryScheduled {
+                        action_id,
+                        attempt,
+                        delay_ms,
+                    },
+                ) => Some((action_id.clone(), *attempt, *delay_ms)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            scheduled,
+            [
+                ("run-retry-scheduled-2".to_string(), 1, 2),
+                ("run-retry-scheduled-2".to_string(), 2, 4)
+            ],
+            "{:?}",
+            events
+        );
+    }
+
     #[test]
     fn retry_exhausted_has_single_terminal_failed_event() {
         let store = Arc::new(InMemoryEventStore::new());
@@ -1112,11 +2984,12 @@ mod tests {
             events: Box::new(SharedEventStore(Arc::clone(&store))),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(ArcExecutor(Arc::clone(&exec))),
+            exec: Arc::new(ArcExecutor(Arc::clone(&exec))),
             step: Box::new(DoThenCompleteStep::new()),
             policy: Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 1, 0)),
             effect_sink: None,
             mode: KernelMode::Normal,
+            snapshot_policy: None,
         };
 
         let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
@@ -1156,4 +3029,560 @@ mod tests {
             self.0.execute(run_id, action)
         }
     }
+
+    /// Executes `{"sleep_ms", "fail"}` tool inputs, tracking how many run at once
+    #[derive(Default)]
+    struct BatchExecutor {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+        calls: AtomicUsize,
+    }
+    impl ActionExecutor for BatchExecutor {
+        fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+            let Action::CallTool { tool, input, .. } = action else {
+                return Err(KernelError::Driver("tool calls only".into()));
+            };
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(
+                input["sleep_ms"].as_u64().unwrap_or(0),
+            ));
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if input["fail"].as_bool().unwrap_or(false) {
+                Ok(ActionResult::Failure(format!("{} failed", tool)))
+            } else {
+                Ok(ActionResult::Success(serde_json::json!(tool)))
+            }
+        }
+    }
+
+    /// Requests `batch` once, then completes
+    struct DoAllOnceStep(Mutex<Option<ActionBatch>>);
+    impl StepFn<TestState> for DoAllOnceStep {
+        fn next(&self, _state: &TestState) -> Result<Next, KernelError> {
+            Ok(match self.0.lock().unwrap().take() {
+                Some(batch) => Next::DoAll(batch),
+                None => Next::Complete,
+            })
+        }
+    }
+
+    struct ParallelPolicy(usize);
+    impl Policy for ParallelPolicy {
+        fn authorize(&self, _: &RunId, _: &Action, _: &PolicyCtx) -> Result<(), KernelError> {
+            Ok(())
+        }
+
+        fn max_parallel_actions(&self) -> usize {
+            self.0
+        }
+    }
+
+    fn batch_tool(name: &str, sleep_ms: u64, fail: bool) -> Action {
+        Action::CallTool {
+            tool: name.into(),
+            input: serde_json::json!({ "sleep_ms": sleep_ms, "fail": fail }),
+            idempotency_key: None,
+        }
+    }
+
+    fn run_batch_kernel(
+        exec: Arc<BatchExecutor>,
+        batch: ActionBatch,
+        max_parallel: usize,
+    ) -> (RunStatus, Vec<Event>) {
+        let store = Arc::new(InMemoryEventStore::new());
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec,
+            step: Box::new(DoAllOnceStep(Mutex::new(Some(batch)))),
+            policy: Box::new(ParallelPolicy(max_parallel)),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "run-batch".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        let events = store
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|se| se.event)
+            .collect();
+        (status, events)
+    }
+
+    #[test]
+    fn do_all_runs_up_to_the_limit_at_once_and_appends_in_batch_order() {
+        let exec = Arc::new(BatchExecutor::default());
+        // Later actions finish first
+        let actions = (0..6)
+            .map(|i| batch_tool(&format!("t{}", i), 20 * (6 - i), false))
+            .collect();
+        let (status, events) = run_batch_kernel(exec.clone(), ActionBatch::new(actions), 3);
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(exec.peak.load(Ordering::SeqCst), 3);
+
+        let requested: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::ActionRequested { action_id, .. } => Some(action_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let succeeded: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::ActionSucceeded {
+                    action_id, output, ..
+                } => Some((action_id.clone(), output.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(requested.len(), 6);
+        assert_eq!(
+            succeeded,
+            requested
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (id.clone(), serde_json::json!(format!("t{}", i))))
+                .collect::<Vec<_>>()
+        );
+        // All decisions come before the first request, and all requests before the first result
+        assert!(matches!(events[5], Event::PolicyDecision { .. }));
+        assert!(matches!(events[11], Event::ActionRequested { .. }));
+    }
+
+    #[test]
+    fn fail_fast_batches_skip_the_actions_not_started_after_a_failure() {
+        let actions = || {
+            vec![
+                batch_tool("a", 0, false),
+                batch_tool("b", 0, true),
+                batch_tool("c", 0, false),
+            ]
+        };
+        let errors = |events: &[Event]| -> Vec<String> {
+            events
+                .iter()
+                .filter_map(|e| match e {
+                    Event::ActionFailed { error, .. } => Some(error.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let exec = Arc::new(BatchExecutor::default());
+        let (status, events) =
+            run_batch_kernel(exec.clone(), ActionBatch::new(actions()).fail_fast(), 1);
+        assert!(matches!(status, RunStatus::Failed { recoverable: false }));
+        assert_eq!(exec.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(errors(&events), vec!["b failed", SKIPPED_ACTION_ERROR]);
+
+        let exec = Arc::new(BatchExecutor::default());
+        let (status, events) = run_batch_kernel(exec.clone(), ActionBatch::new(actions()), 1);
+        assert!(matches!(status, RunStatus::Failed { recoverable: false }));
+        assert_eq!(exec.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(errors(&events), vec!["b failed"]);
+    }
+
+    /// Sleeps `sleeps_ms[n]` ms on its n-th call (0 once they run out), then succeeds
+    struct SleepyExecutor {
+        sleeps_ms: Vec<u64>,
+        calls: AtomicUsize,
+    }
+    impl SleepyExecutor {
+        fn new(sleeps_ms: Vec<u64>) -> Self {
+            Self {
+                sleeps_ms,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+    impl ActionExecutor for SleepyExecutor {
+        fn execute(&self, _run_id: &RunId, _action: &Action) -> Result<ActionResult, KernelError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let ms = self.sleeps_ms.get(call).copied().unwrap_or(0);
+            std::thread::sleep(Duration::from_millis(ms));
+            Ok(ActionResult::Success(serde_json::json!(call)))
+        }
+    }
+
+    fn sleepy_kernel(
+        store: &Arc<InMemoryEventStore>,
+        exec: &Arc<SleepyExecutor>,
+        policy: Box<dyn Policy>,
+    ) -> Kernel<TestState> {
+        Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: exec.clone(),
+            step: Box::new(DoThenCompleteStep::new()),
+            policy,
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        }
+    }
+
+    #[test]
+    fn action_timeout_fails_a_hung_attempt_and_lets_the_policy_retry_it() {
+        let timeout = || TimeoutPolicy::new(AllowAllPolicy, Duration::from_millis(50));
+
+        let store = Arc::new(InMemoryEventStore::new());
+        let exec = Arc::new(SleepyExecutor::new(vec![5_000]));
+        let started = Instant::now();
+        let status = sleepy_kernel(&store, &exec, Box::new(timeout()))
+            .run_until_blocked(&"no-retry".to_string(), TestState(0))
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(matches!(status, RunStatus::Failed { recoverable: false }));
+        let failed = store
+            .scan(&"no-retry".to_string(), 1)
+            .unwrap()
+            .into_iter()
+            .find_map(|se| match se.event {
+                Event::ActionFailed { error, .. } => Some(error),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(failed, "Executor: action timed out after 50 ms");
+
+        let store = Arc::new(InMemoryEventStore::new());
+        let exec = Arc::new(SleepyExecutor::new(vec![5_000, 0]));
+        let started = Instant::now();
+        let policy = RetryWithBackoffPolicy::new(timeout(), 1, 0);
+        let status = sleepy_kernel(&store, &exec, Box::new(policy))
+            .run_until_blocked(&"retried".to_string(), TestState(0))
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(exec.calls.load(Ordering::SeqCst), 2);
+        let succeeded: Vec<_> = store
+            .scan(&"retried".to_string(), 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|se| match se.event {
+                Event::ActionSucceeded { output, .. } => Some(output),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(succeeded, vec![serde_json::json!(1)]);
+        let decisions: Vec<_> = store
+            .scan(&"retried".to_string(), 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|se| match se.event {
+                Event::PolicyDecision {
+                    decision,
+                    code,
+                    policy,
+                    ..
+                } => Some((decision, code, policy)),
+                _ => None,
+            })
+            .collect();
+        let policy = "retry_with_backoff(timeout(allow_all))".to_string();
+        assert_eq!(
+            decisions,
+            [
+                (
+                    PolicyDecisionKind::Allow,
+                    POLICY_AUTHORIZED.to_string(),
+                    policy.clone()
+                ),
+                (
+                    PolicyDecisionKind::Retry,
+                    "ACTION_TIMEOUT".to_string(),
+                    policy
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_denied_action_is_logged_as_one_decision_and_denial_and_never_requested() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let exec = Arc::new(ScriptedActionExecutor::new(vec![]));
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::clone(&exec) as Arc<dyn ActionExecutor>,
+            step: Box::new(DoThenCompleteStep::new()),
+            policy: Box::new(AllowListPolicy::tools_only(["search".to_string()])),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "denied".to_string();
+        let result = k.run_until_blocked(&run_id, TestState(0));
+        assert!(matches!(result, Err(KernelError::Policy(_))));
+        assert_eq!(exec.calls(), 0);
+
+        let events: Vec<_> = store
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|se| se.event)
+            .collect();
+        assert!(
+            matches!(
+                &events[..],
+                [
+                    Event::PolicyDecision {
+                        action_kind,
+                        decision: PolicyDecisionKind::Deny,
+                        code,
+                        policy,
+                        ..
+                    },
+                    Event::ActionDenied { reason, .. },
+                ] if action_kind == "dummy"
+                    && code == POLICY_DENIED
+                    && policy == "allow_list"
+                    && reason == "tool not allowed: dummy"
+            ),
+            "{:?}",
+            events
+        );
+
+        let timeline = k.run_timeline(&run_id).unwrap();
+        assert_eq!(
+            timeline.final_status,
+            timeline::RunStatusSummary::Failed { recoverable: true }
+        );
+        assert_eq!(
+            timeline.events[0].detail.as_deref(),
+            Some("deny dummy by allow_list (POLICY_DENIED): tool not allowed: dummy")
+        );
+        let log = execution_log::scan_execution_log(store.as_ref(), &run_id, 1).unwrap();
+        let kinds: Vec<_> = log.iter().map(|entry| entry.event.kind()).collect();
+        assert_eq!(kinds, [EventKind::PolicyDecision, EventKind::ActionDenied]);
+
+        // An allowed action's decision names the pattern that allowed it
+        let store = Arc::new(InMemoryEventStore::new());
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(ScriptedActionExecutor::new(vec![Ok(
+                ActionResult::Success(serde_json::json!(1)),
+            )])),
+            step: Box::new(DoThenCompleteStep::new()),
+            policy: Box::new(AllowListPolicy::kinds(["dum*"]).unwrap()),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let first = store.scan(&run_id, 1).unwrap().remove(0).event;
+        assert!(
+            matches!(
+                &first,
+                Event::PolicyDecision {
+                    decision: PolicyDecisionKind::Allow,
+                    reason: Some(reason),
+                    ..
+                } if reason == "allowed by pattern 'dum*'"
+            ),
+            "{:?}",
+            first
+        );
+    }
+
+    #[test]
+    fn cancelling_a_controlled_run_aborts_its_action_in_flight() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let exec = Arc::new(SleepyExecutor::new(vec![5_000]));
+        let k = sleepy_kernel(&store, &exec, Box::new(AllowAllPolicy));
+        let run_id = "cancel-in-flight".to_string();
+        let control = RunControl::new();
+        let started = Instant::now();
+        let status = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                control.request_cancel(Some("operator".into())).unwrap();
+            });
+            k.run_controlled(&run_id, TestState(0), &control).unwrap()
+        });
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(matches!(status, RunStatus::Cancelled));
+        let events: Vec<_> = store
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|se| se.event)
+            .collect();
+        assert!(matches!(
+            &events[..],
+            [
+                Event::PolicyDecision { .. },
+                Event::ActionRequested { .. },
+                Event::ActionFailed { error, .. },
+                Event::Cancelled { reason: Some(reason) },
+            ] if error == CANCELLED_ACTION_ERROR && reason == "operator"
+        ));
+    }
+
+    /// Counts the actions that succeeded.
+    struct SucceededReducer;
+    impl Reducer<TestState> for SucceededReducer {
+        fn apply(&self, state: &mut TestState, event: &SequencedEvent) -> Result<(), KernelError> {
+            if let Event::ActionSucceeded { .. } = &event.event {
+                state.0 += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// Asks the LLM three times, then completes.
+    struct ThreePromptsStep;
+    impl StepFn<TestState> for ThreePromptsStep {
+        fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+            Ok(if state.0 < 3 {
+                Next::Do(Action::CallLLM {
+                    provider: "openai".into(),
+                    input: serde_json::json!(state.0),
+                    idempotency_key: None,
+                })
+            } else {
+                Next::Complete
+            })
+        }
+    }
+
+    /// LLM that reports 60 tokens per call in its output metadata.
+    struct MeteredLlm(AtomicUsize);
+    impl ActionExecutor for MeteredLlm {
+        fn execute(&self, _run_id: &RunId, _action: &Action) -> Result<ActionResult, KernelError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ActionResult::Success(serde_json::json!({
+                "text": "ok",
+                crate::kernel::ACTION_METADATA_KEY: { "tokens": 60, "cost_cents": 0.5 },
+            })))
+        }
+    }
+
+    /// Budget an operator can change while the run is stopped.
+    struct AdjustableBudget(Arc<Mutex<BudgetRules>>);
+    impl Policy for AdjustableBudget {
+        fn authorize(&self, _: &RunId, _: &Action, _: &PolicyCtx) -> Result<(), KernelError> {
+            Ok(())
+        }
+
+        fn budget(&self) -> BudgetRules {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn exceeding_the_token_budget_blocks_the_run_until_the_budget_is_raised() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let llm = Arc::new(MeteredLlm(AtomicUsize::new(0)));
+        let rules = Arc::new(Mutex::new(BudgetRules {
+            max_tokens: Some(100),
+            ..BudgetRules::default()
+        }));
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(SucceededReducer),
+            exec: llm.clone(),
+            step: Box::new(ThreePromptsStep),
+            policy: Box::new(AdjustableBudget(rules.clone())),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "token-budget".to_string();
+        let budget_denials = || {
+            store
+                .scan(&run_id, 1)
+                .unwrap()
+                .into_iter()
+                .filter(|se| matches!(&se.event, Event::BudgetExceeded { code, .. } if code == BUDGET_TOKENS_EXCEEDED))
+                .count()
+        };
+
+        // The second call crosses the 100 tokens; the third is denied before it runs
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        let RunStatus::Blocked(info) = status else {
+            panic!("expected the run to block, got {:?}", status);
+        };
+        let exceeded = info.budget_exceeded.expect("blocked on the budget");
+        assert_eq!(exceeded.code, BUDGET_TOKENS_EXCEEDED);
+        assert_eq!(exceeded.reason, "the run used 120 of 100 tokens");
+        assert!(info.interrupt.is_none() && !info.paused);
+        assert_eq!(llm.0.load(Ordering::SeqCst), 2);
+        assert_eq!(budget_denials(), 1);
+        assert_eq!(
+            crate::kernel::RunStatusKind::from_last_event(
+                &store.scan(&run_id, 1).unwrap().last().unwrap().event
+            ),
+            crate::kernel::RunStatusKind::Blocked
+        );
+
+        // Still over budget: blocks again without calling the LLM
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(
+            status,
+            RunStatus::Blocked(BlockedInfo {
+                budget_exceeded: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(llm.0.load(Ordering::SeqCst), 2);
+        assert_eq!(budget_denials(), 2);
+
+        rules.lock().unwrap().max_tokens = Some(1_000);
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(llm.0.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn a_batch_over_an_action_count_limit_is_refused_whole() {
+        let store = Arc::new(InMemoryEventStore::new());
+        let exec = Arc::new(BatchExecutor::default());
+        let policy = AdjustableBudget(Arc::new(Mutex::new(BudgetRules {
+            max_actions_per_kind: [("fetch".to_string(), 2)].into_iter().collect(),
+            ..BudgetRules::default()
+        })));
+        let batch = ActionBatch::new((0..3).map(|_| batch_tool("fetch", 0, false)).collect());
+        let k = Kernel::<TestState> {
+            events: Box::new(SharedEventStore(Arc::clone(&store))),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: exec.clone(),
+            step: Box::new(DoAllOnceStep(Mutex::new(Some(batch)))),
+            policy: Box::new(policy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "kind-budget".to_string();
+        let status = k.run_until_blocked(&run_id, TestState(0)).unwrap();
+        assert!(matches!(
+            status,
+            RunStatus::Blocked(BlockedInfo { budget_exceeded: Some(ref e), .. })
+                if e.code == BUDGET_ACTIONS_PER_KIND_EXCEEDED
+        ));
+        let kinds: Vec<_> = store
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|se| se.event.kind())
+            .collect();
+        assert_eq!(
+            kinds,
+            [EventKind::PolicyDecision, EventKind::BudgetExceeded]
+        );
+        assert_eq!(exec.peak.load(Ordering::SeqCst), 0);
+    }
 }
@@ -0,0 +1,780 @@
+//! Durable and in-process [EffectSink] implementations.
+//!
+//! [JsonlFileEffectSink] appends effects to a JSON-lines file, [ChannelEffectSink] hands
+//! them to a Tokio channel, and [FanOutEffectSink] forwards them to several sinks.
+//!
+//! The driver records effects inline, so these sinks never write on its thread: `record`
+//! puts the effect on a bounded queue and a background thread delivers it. When the queue
+//! is full, [OverflowPolicy] decides between dropping the oldest queued effect and making
+//! `record` wait. Delivery failures never fail the run; they are counted in the sink's
+//! [SinkHealth].
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::kernel::event::KernelError;
+use crate::kernel::identity::RunId;
+use crate::kernel::runtime_effect::{EffectSink, RuntimeEffect};
+
+/// One effect as the queued sinks deliver it: a line of a [JsonlFileEffectSink] file or a
+/// message of a [ChannelEffectSink].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EffectRecord {
+    pub run_id: RunId,
+    /// When the driver recorded the effect, in milliseconds since the Unix epoch.
+    pub timestamp_ms: i64,
+    pub effect: RuntimeEffect,
+}
+
+/// What `record` does when a sink's queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued effect to make room; `record` never waits.
+    #[default]
+    DropOldest,
+    /// Wait until the background thread has made room, slowing the run down to the
+    /// sink's pace.
+    Backpressure,
+}
+
+/// Bounded queue between the driver and a sink's background thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SinkQueueConfig {
+    /// Effects that may wait for delivery; at least 1.
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl SinkQueueConfig {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self { capacity, overflow }
+    }
+}
+
+impl Default for SinkQueueConfig {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY, OverflowPolicy::DropOldest)
+    }
+}
+
+/// Delivery counters of a sink, from [EffectSink::health].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SinkHealth {
+    /// Effects passed to `record`.
+    pub recorded: u64,
+    /// Effects the sink delivered.
+    pub delivered: u64,
+    /// Effects dropped because the queue was full.
+    pub dropped: u64,
+    /// Effects whose delivery failed and was given up.
+    pub failed: u64,
+    /// Failed delivery attempts that were retried, for sinks that retry.
+    pub retried: u64,
+    /// Message of the latest delivery failure.
+    pub last_error: Option<String>,
+}
+
+impl SinkHealth {
+    /// No effect was dropped or failed to be delivered.
+    pub fn is_healthy(&self) -> bool {
+        self.dropped == 0 && self.failed == 0
+    }
+
+    /// Counters of two sinks added up, keeping the first one's last error if both have one.
+    pub fn combine(mut self, other: &SinkHealth) -> SinkHealth {
+        self.recorded += other.recorded;
+        self.delivered += other.delivered;
+        self.dropped += other.dropped;
+        self.failed += other.failed;
+        self.retried += other.retried;
+        if self.last_error.is_none() {
+            self.last_error = other.last_error.clone();
+        }
+        self
+    }
+}
+
+/// Destination a queued sink's background thread delivers batches to
+pub(crate) trait Deliver<T>: Send + 'static {
+    fn deliver(&mut self, records: &[T]) -> Result<(), String>;
+
+    /// How long to wait before delivering a failed batch again after `failures` failed
+    /// attempts, or `None` to give it up. `closing` is set once the sink is being dropped.
+    fn retry_delay(&mut self, _failures: u32, _closing: bool) -> Option<Duration> {
+        None
+    }
+}
+
+struct QueueState<T> {
+    records: VecDeque<T>,
+    /// Records taken by the background thread and not yet delivered
+    in_flight: usize,
+    closed: bool,
+    health: SinkHealth,
+}
+
+struct Shared<T> {
+    state: Mutex<QueueState<T>>,
+    /// Signalled when records are queued or the queue closes
+    queued: Condvar,
+    /// Signalled when the background thread took or delivered records
+    drained: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, QueueState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits for records and takes all of them; `None` once closed and empty
+    fn next_batch(&self) -> Option<Vec<T>> {
+        let mut state = self.lock();
+        while state.records.is_empty() && !state.closed {
+            state = self.queued.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        if state.records.is_empty() {
+            return None;
+        }
+        let batch: Vec<_> = state.records.drain(..).collect();
+        state.in_flight = batch.len();
+        self.drained.notify_all();
+        Some(batch)
+    }
+
+    fn finish_batch(&self, len: usize, result: Result<(), String>) {
+        let mut state = self.lock();
+        state.in_flight = 0;
+        match result {
+            Ok(()) => state.health.delivered += len as u64,
+            Err(e) => {
+                state.health.failed += len as u64;
+                state.health.last_error = Some(e);
+            }
+        }
+        self.drained.notify_all();
+    }
+}
+
+/// Bounded queue drained by a background thread
+pub(crate) struct SinkQueue<T> {
+    shared: Arc<Shared<T>>,
+    config: SinkQueueConfig,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> SinkQueue<T> {
+    pub(crate) fn start(
+        name: &str,
+        config: SinkQueueConfig,
+        mut target: impl Deliver<T>,
+    ) -> Result<Self, KernelError> {
+        let config = SinkQueueConfig {
+            capacity: config.capacity.max(1),
+            ..config
+        };
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState {
+                records: VecDeque::with_capacity(config.capacity),
+                in_flight: 0,
+                closed: false,
+                health: SinkHealth::default(),
+            }),
+            queued: Condvar::new(),
+            drained: Condvar::new(),
+        });
+        let worker_shared = shared.clone();
+        let worker = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                while let Some(batch) = worker_shared.next_batch() {
+                    let mut failures = 0;
+                    let result = loop {
+                        let Err(e) = target.deliver(&batch) else {
+                            break Ok(());
+                        };
+                        failures += 1;
+                        let closing = worker_shared.lock().closed;
+                        let Some(delay) = target.retry_delay(failures, closing) else {
+                            break Err(e);
+                        };
+                        {
+                            let mut state = worker_shared.lock();
+                            state.health.retried += 1;
+                            state.health.last_error = Some(e);
+                        }
+                        std::thread::sleep(delay);
+                    };
+                    worker_shared.finish_batch(batch.len(), result);
+                }
+            })
+            .map_err(|e| KernelError::Driver(format!("start effect sink thread: {}", e)))?;
+        Ok(Self {
+            shared,
+            config,
+            worker: Some(worker),
+        })
+    }
+
+    /// Queues a record and returns how many queued records were dropped to make room.
+    pub(crate) fn push(&self, record: T) -> u64 {
+        let mut state = self.shared.lock();
+        state.health.recorded += 1;
+        if state.closed {
+            state.health.dropped += 1;
+            return 1;
+        }
+        let mut dropped = 0;
+        while state.records.len() >= self.config.capacity {
+            match self.config.overflow {
+                OverflowPolicy::DropOldest => {
+                    state.records.pop_front();
+                    dropped += 1;
+                }
+                OverflowPolicy::Backpressure => {
+                    state = self
+                        .shared
+                        .drained
+                        .wait(state)
+                        .unwrap_or_else(|e| e.into_inner());
+                }
+            }
+        }
+        state.health.dropped += dropped;
+        state.records.push_back(record);
+        self.shared.queued.notify_one();
+        dropped
+    }
+
+    /// Counts a record that could not be queued at all, e.g. because it failed to serialize.
+    pub(crate) fn reject(&self, error: String) {
+        let mut state = self.shared.lock();
+        state.health.recorded += 1;
+        state.health.failed += 1;
+        state.health.last_error = Some(error);
+    }
+
+    pub(crate) fn flush(&self) {
+        let mut state = self.shared.lock();
+        while !state.records.is_empty() || state.in_flight > 0 {
+            state = self
+                .shared
+                .drained
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Like [Self::flush], but waits at most `timeout`; `false` if records are still pending.
+    pub(crate) fn flush_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        while !state.records.is_empty() || state.in_flight > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            state = self
+                .shared
+                .drained
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        true
+    }
+
+    pub(crate) fn health(&self) -> SinkHealth {
+        self.shared.lock().health.clone()
+    }
+}
+
+impl<T> Drop for SinkQueue<T> {
+    /// Delivers what is queued, then stops the background thread.
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.queued.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn effect_record(run_id: &RunId, effect: &RuntimeEffect) -> EffectRecord {
+    EffectRecord {
+        run_id: run_id.clone(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        effect: effect.clone(),
+    }
+}
+
+/// When a [JsonlFileEffectSink] syncs its file to disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Leave syncing to the OS.
+    Never,
+    /// After every batch the background thread writes.
+    #[default]
+    EveryBatch,
+    /// After a batch once this long has passed since the last sync.
+    Interval(Duration),
+}
+
+/// Options of a [JsonlFileEffectSink].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JsonlFileConfig {
+    pub fsync: FsyncPolicy,
+    /// Rotate the file before it would grow past this many bytes; `None` never rotates.
+    pub max_bytes: Option<u64>,
+    /// Rotated files to keep (`<path>.1` is the newest); older ones are deleted.
+    pub max_rotated_files: usize,
+    pub queue: SinkQueueConfig,
+}
+
+impl JsonlFileConfig {
+    pub fn with_fsync(mut self, fsync: FsyncPolicy) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Rotates at `max_bytes`, keeping `max_rotated_files` rotated files.
+    pub fn with_rotation(mut self, max_bytes: u64, max_rotated_files: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self.max_rotated_files = max_rotated_files;
+        self
+    }
+
+    pub fn with_queue(mut self, queue: SinkQueueConfig) -> Self {
+        self.queue = queue;
+        self
+    }
+}
+
+/// Writes JSON lines to the current file and rotates it
+struct JsonlWriter {
+    path: PathBuf,
+    config: JsonlFileConfig,
+    file: BufWriter<File>,
+    len: u64,
+    last_sync: Instant,
+}
+
+impl JsonlWriter {
+    fn open(path: PathBuf, config: JsonlFileConfig) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            config,
+            file: BufWriter::new(file),
+            len,
+            last_sync: Instant::now(),
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// Shifts `<path>.n` to `<path>.n+1`, dropping the oldest, and starts a new file
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_all()?;
+        let keep = self.config.max_rotated_files;
+        if keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated_path(keep));
+            for n in (1..keep).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.file = BufWriter::new(file);
+        self.len = 0;
+        Ok(())
+    }
+
+    fn write_batch(&mut self, records: &[EffectRecord]) -> std::io::Result<()> {
+        for record in records {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            let line_len = line.len() as u64;
+            if let Some(max) = self.config.max_bytes {
+                if self.len > 0 && self.len + line_len > max {
+                    self.rotate()?;
+                }
+            }
+            self.file.write_all(&line)?;
+            self.len += line_len;
+        }
+        self.file.flush()?;
+        let sync = match self.config.fsync {
+            FsyncPolicy::Never => false,
+            FsyncPolicy::EveryBatch => true,
+            FsyncPolicy::Interval(every) => self.last_sync.elapsed() >= every,
+        };
+        if sync {
+            self.file.get_ref().sync_data()?;
+            self.last_sync = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl Deliver<EffectRecord> for JsonlWriter {
+    fn deliver(&mut self, records: &[EffectRecord]) -> Result<(), String> {
+        self.write_batch(records)
+            .map_err(|e| format!("write {}: {}", self.path.display(), e))
+    }
+}
+
+/// Effect sink that appends each effect as an [EffectRecord] line to a JSON-lines file.
+///
+/// The file is opened for appending, so a restarted process continues it. With
+/// [JsonlFileConfig::with_rotation] the file is renamed to `<path>.1` (and older rotated
+/// files shifted) before a line would take it past the size limit.
+pub struct JsonlFileEffectSink {
+    path: PathBuf,
+    queue: SinkQueue<EffectRecord>,
+}
+
+impl JsonlFileEffectSink {
+    /// Appends to `path` with [JsonlFileConfig::default]: a sync after every batch and no
+    /// rotation.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, KernelError> {
+        Self::open_with_config(path, JsonlFileConfig::default())
+    }
+
+    pub fn open_with_config(
+        path: impl AsRef<Path>,
+        config: JsonlFileConfig,
+    ) -> Result<Self, KernelError> {
+        let path = path.as_ref().to_path_buf();
+        let queue_config = config.queue;
+        let writer = JsonlWriter::open(path.clone(), config)
+            .map_err(|e| KernelError::Storage(format!("open {}: {}", path.display(), e)))?;
+        let queue = SinkQueue::start("oris-effect-jsonl", queue_config, writer)?;
+        Ok(Self { path, queue })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl EffectSink for JsonlFileEffectSink {
+    fn record(&self, run_id: &RunId, effect: &RuntimeEffect) {
+        self.queue.push(effect_record(run_id, effect));
+    }
+
+    fn health(&self) -> Option<SinkHealth> {
+        Some(self.queue.health())
+    }
+
+    fn flush(&self) {
+        self.queue.flush();
+    }
+}
+
+/// Sends batches on a Tokio channel, waiting for the receiver to make room
+struct ChannelSender(mpsc::Sender<EffectRecord>);
+
+impl Deliver<EffectRecord> for ChannelSender {
+    fn deliver(&mut self, records: &[EffectRecord]) -> Result<(), String> {
+        for record in records {
+            self.0
+                .blocking_send(record.clone())
+                .map_err(|_| "effect channel receiver closed".to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Effect sink that sends each effect as an [EffectRecord] to a Tokio channel, for
+/// consumers in the same process.
+///
+/// A slow receiver fills the channel and then the sink's queue, where
+/// [SinkQueueConfig::overflow] applies; once the receiver is dropped, deliveries fail.
+pub struct ChannelEffectSink {
+    queue: SinkQueue<EffectRecord>,
+}
+
+impl ChannelEffectSink {
+    /// A sink and the receiver of its effects; the channel and the queue in front of it
+    /// each hold `queue.capacity` effects.
+    pub fn new(
+        queue: SinkQueueConfig,
+    ) -> Result<(Self, mpsc::Receiver<EffectRecord>), KernelError> {
+        let (tx, rx) = mpsc::channel(queue.capacity.max(1));
+        let queue = SinkQueue::start("oris-effect-channel", queue, ChannelSender(tx))?;
+        Ok((Self { queue }, rx))
+    }
+}
+
+impl EffectSink for ChannelEffectSink {
+    fn record(&self, run_id: &RunId, effect: &RuntimeEffect) {
+        self.queue.push(effect_record(run_id, effect));
+    }
+
+    fn health(&self) -> Option<SinkHealth> {
+        Some(self.queue.health())
+    }
+
+    fn flush(&self) {
+        self.queue.flush();
+    }
+}
+
+/// Effect sink that records every effect to each of several sinks, in order.
+#[derive(Default)]
+pub struct FanOutEffectSink {
+    sinks: Vec<Box<dyn EffectSink>>,
+}
+
+impl FanOutEffectSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, sink: impl EffectSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn sinks(&self) -> &[Box<dyn EffectSink>] {
+        &self.sinks
+    }
+}
+
+impl EffectSink for FanOutEffectSink {
+    fn record(&self, run_id: &RunId, effect: &RuntimeEffect) {
+        for sink in &self.sinks {
+            sink.record(run_id, effect);
+        }
+    }
+
+    /// Counters of the sinks that report health, added up.
+    fn health(&self) -> Option<SinkHealth> {
+        self.sinks
+            .iter()
+            .filter_map(|sink| sink.health())
+            .reduce(|total, health| total.combine(&health))
+    }
+
+    fn flush(&self) {
+        for sink in &self.sinks {
+            sink.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc as std_mpsc;
+
+    fn tool_call(n: u64) -> RuntimeEffect {
+        RuntimeEffect::ToolCall {
+            tool: "t".into(),
+            input: serde_json::json!(n),
+        }
+    }
+
+    fn read_lines(path: &Path) -> Vec<EffectRecord> {
+        fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    /// Delivers to a test channel, first waiting until the test opens the gate
+    struct Gated {
+        gate: std_mpsc::Receiver<()>,
+        out: std_mpsc::Sender<EffectRecord>,
+    }
+
+    impl Deliver<EffectRecord> for Gated {
+        fn deliver(&mut self, records: &[EffectRecord]) -> Result<(), String> {
+            self.gate.recv().map_err(|e| e.to_string())?;
+            for record in records {
+                self.out.send(record.clone()).map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn jsonl_sink_appends_records_and_rotates_by_size() {
+        let dir = std::env::temp_dir().join(format!("oris-effects-{}", unique_suffix()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("effects.jsonl");
+        let run_id: RunId = "run-jsonl".into();
+
+        let sink = JsonlFileEffectSink::open_with_config(
+            &path,
+            JsonlFileConfig::default().with_rotation(200, 2),
+        )
+        .unwrap();
+        for n in 0..6 {
+            sink.record(&run_id, &tool_call(n));
+        }
+        sink.flush();
+        let health = sink.health().unwrap();
+        assert_eq!((health.recorded, health.delivered), (6, 6));
+        assert!(health.is_healthy(), "{:?}", health);
+        drop(sink);
+
+        // Each line is ~90 bytes, so files hold two lines and only two rotations are kept
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+        let inputs = |records: Vec<EffectRecord>| -> Vec<u64> {
+            records
+                .into_iter()
+                .map(|r| match r.effect {
+                    RuntimeEffect::ToolCall { input, .. } => input.as_u64().unwrap(),
+                    other => panic!("unexpected effect {:?}", other),
+                })
+                .collect()
+        };
+        assert_eq!(inputs(read_lines(&rotated(2))), [0, 1]);
+        assert_eq!(inputs(read_lines(&rotated(1))), [2, 3]);
+        assert_eq!(inputs(read_lines(&path)), [4, 5]);
+        assert!(!rotated(3).exists());
+        assert!(read_lines(&path).iter().all(|r| r.run_id == run_id));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_full_queue_drops_the_oldest_effects_without_waiting() {
+        let (gate_tx, gate) = std_mpsc::channel();
+        let (out, delivered) = std_mpsc::channel();
+        let queue = SinkQueue::start(
+            "test-drop-oldest",
+            SinkQueueConfig::new(2, OverflowPolicy::DropOldest),
+            Gated { gate, out },
+        )
+        .unwrap();
+        let run_id: RunId = "run-drop".into();
+        queue.push(effect_record(&run_id, &tool_call(0)));
+        // Wait until the worker holds effect 0, blocked on the gate
+        while queue.shared.lock().in_flight == 0 {
+            std::thread::yield_now();
+        }
+        for n in 1..=4 {
+            queue.push(effect_record(&run_id, &tool_call(n)));
+        }
+        gate_tx.send(()).unwrap();
+        gate_tx.send(()).unwrap();
+        queue.flush();
+
+        let got: Vec<_> = delivered.try_iter().map(|r| r.effect).collect();
+        let inputs: Vec<_> = got
+            .iter()
+            .map(|e| match e {
+                RuntimeEffect::ToolCall { input, .. } => input.as_u64().unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(inputs, [0, 3, 4]);
+        let health = queue.health();
+        assert_eq!(
+            (health.recorded, health.delivered, health.dropped),
+            (5, 3, 2)
+        );
+        assert!(!health.is_healthy());
+    }
+
+    #[test]
+    fn backpressure_waits_for_room_and_loses_nothing() {
+        let (gate_tx, gate) = std_mpsc::channel();
+        let (out, delivered) = std_mpsc::channel();
+        let queue = Arc::new(
+            SinkQueue::start(
+                "test-backpressure",
+                SinkQueueConfig::new(1, OverflowPolicy::Backpressure),
+                Gated { gate, out },
+            )
+            .unwrap(),
+        );
+        let producer = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                let run_id: RunId = "run-backpressure".into();
+                for n in 0..5 {
+                    queue.push(effect_record(&run_id, &tool_call(n)));
+                }
+            })
+        };
+        for _ in 0..5 {
+            gate_tx.send(()).unwrap();
+        }
+        producer.join().unwrap();
+        queue.flush();
+        assert_eq!(delivered.try_iter().count(), 5);
+        let health = queue.health();
+        assert_eq!((health.delivered, health.dropped), (5, 0));
+    }
+
+    #[test]
+    fn channel_sink_errors_show_in_health_once_the_receiver_is_gone() {
+        let (sink, mut rx) = ChannelEffectSink::new(SinkQueueConfig::default()).unwrap();
+        let run_id: RunId = "run-channel".into();
+        sink.record(&run_id, &tool_call(1));
+        sink.flush();
+        assert_eq!(rx.try_recv().unwrap().effect, tool_call(1));
+
+        drop(rx);
+        sink.record(&run_id, &tool_call(2));
+        sink.flush();
+        let health = sink.health().unwrap();
+        assert_eq!((health.delivered, health.failed), (1, 1));
+        assert_eq!(
+            health.last_error.as_deref(),
+            Some("effect channel receiver closed")
+        );
+    }
+
+    #[test]
+    fn fan_out_records_to_every_sink_and_adds_up_their_health() {
+        let (first, mut first_rx) = ChannelEffectSink::new(SinkQueueConfig::default()).unwrap();
+        let (second, mut second_rx) = ChannelEffectSink::new(SinkQueueConfig::default()).unwrap();
+        let sink = FanOutEffectSink::new()
+            .with(first)
+            .with(second)
+            .with(crate::kernel::NoopEffectSink);
+        let run_id: RunId = "run-fan-out".into();
+        sink.record(&run_id, &tool_call(7));
+        sink.flush();
+        assert_eq!(first_rx.try_recv().unwrap().effect, tool_call(7));
+        assert_eq!(second_rx.try_recv().unwrap().effect, tool_call(7));
+        let health = sink.health().unwrap();
+        assert_eq!((health.recorded, health.delivered), (2, 2));
+    }
+
+    fn unique_suffix() -> String {
+        format!(
+            "{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        )
+    }
+}
@@ -0,0 +1,976 @@
+//! At-rest encryption for event and snapshot stores (feature `encryption`).
+//!
+//! [EncryptedEventStore] and [EncryptedSnapshotStore] wrap any store and encrypt with
+//! AES-256-GCM under keys from a [KeyProvider]. Events keep their kind, ids, step ids, merge reports and
+//! state hashes in plaintext, so filtered scans, run listings and hash checks still work; the payload fields
+//! (state, action input and output, recorded action outcomes, errors, interrupt and resume
+//! values, failure reasons) are replaced by a sealed string naming the key id and nonce. Snapshot state
+//! is stored as a [SealedState]. Ciphertexts are bound to their run id.
+//!
+//! Reads decrypt transparently, so replay, timelines and execution logs work unchanged
+//! on top of the wrappers. Data written before encryption was enabled is read as is.
+//! Rotate keys by making a new key current and keeping the old one in the provider for
+//! reading: each ciphertext records the id of the key it was sealed with.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::kernel::event::{Event, EventFilter, EventStore, KernelError, SequencedEvent};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunSummary};
+use crate::kernel::snapshot::{Snapshot, SnapshotStore};
+
+/// Prefix of sealed event fields: `oris-enc:v1:<key id>:<nonce>:<ciphertext>`
+const SEALED_FIELD_PREFIX: &str = "oris-enc:v1:";
+
+/// Environment variable [EnvKeyProvider::from_env] reads.
+pub const ENCRYPTION_KEYS_ENV: &str = "ORIS_KERNEL_ENCRYPTION_KEYS";
+
+/// A 256-bit AES key.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// A key from 32 base64-encoded bytes.
+    pub fn from_base64(encoded: &str) -> Result<Self, KernelError> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| KernelError::Crypto(format!("key is not valid base64: {}", e)))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            KernelError::Crypto(format!("key must be 32 bytes, got {}", bytes.len()))
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// A fresh random key.
+    pub fn generate() -> Self {
+        Self(Aes256Gcm::generate_key(&mut OsRng).into())
+    }
+
+    /// The key as base64, the format [EncryptionKey::from_base64] reads.
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.0)
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Source of encryption keys, identified by key id.
+pub trait KeyProvider: Send + Sync {
+    /// Id and key new data is sealed with.
+    fn current_key(&self) -> Result<(String, EncryptionKey), KernelError>;
+
+    /// The key with `key_id`, to open data sealed with it; `None` if unknown.
+    fn key(&self, key_id: &str) -> Result<Option<EncryptionKey>, KernelError>;
+}
+
+/// Keys held in memory: one current key and any retired ones kept for reading.
+#[derive(Clone, Debug)]
+pub struct StaticKeyProvider {
+    current: String,
+    keys: HashMap<String, EncryptionKey>,
+}
+
+impl StaticKeyProvider {
+    /// Seals with `key`, recorded as `key_id`.
+    ///
+    /// Key ids may not be empty or contain `:` or `,`.
+    pub fn new(key_id: impl Into<String>, key: EncryptionKey) -> Result<Self, KernelError> {
+        let key_id = checked_key_id(key_id.into())?;
+        Ok(Self {
+            keys: HashMap::from([(key_id.clone(), key)]),
+            current: key_id,
+        })
+    }
+
+    /// Also opens data sealed with `key` under `key_id`, e.g. a key rotated out.
+    pub fn with_retired_key(
+        mut self,
+        key_id: impl Into<String>,
+        key: EncryptionKey,
+    ) -> Result<Self, KernelError> {
+        let key_id = checked_key_id(key_id.into())?;
+        if key_id != self.current {
+            self.keys.insert(key_id, key);
+        }
+        Ok(self)
+    }
+
+    /// Id of the key new data is sealed with.
+    pub fn current_key_id(&self) -> &str {
+        &self.current
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn current_key(&self) -> Result<(String, EncryptionKey), KernelError> {
+        Ok((self.current.clone(), self.keys[&self.current].clone()))
+    }
+
+    fn key(&self, key_id: &str) -> Result<Option<EncryptionKey>, KernelError> {
+        Ok(self.keys.get(key_id).cloned())
+    }
+}
+
+fn checked_key_id(key_id: String) -> Result<String, KernelError> {
+    if key_id.is_empty() || key_id.contains([':', ',']) {
+        return Err(KernelError::Crypto(format!(
+            "invalid key id '{}': must be non-empty without ':' or ','",
+            key_id
+        )));
+    }
+    Ok(key_id)
+}
+
+/// Keys from an environment variable, read once when the provider is created.
+///
+/// The variable holds comma-separated `key-id:base64-key` entries; the first is the
+/// current key and the rest are retired keys kept for reading, e.g.
+/// `ORIS_KERNEL_ENCRYPTION_KEYS="2026-10:q83v...,2026-04:7fGh..."`.
+#[derive(Clone, Debug)]
+pub struct EnvKeyProvider(StaticKeyProvider);
+
+impl EnvKeyProvider {
+    /// Reads [ENCRYPTION_KEYS_ENV].
+    pub fn from_env() -> Result<Self, KernelError> {
+        Self::from_var(ENCRYPTION_KEYS_ENV)
+    }
+
+    /// Reads the variable `name`; fails if it is unset or malformed.
+    pub fn from_var(name: &str) -> Result<Self, KernelError> {
+        let raw = std::env::var(name)
+            .map_err(|e| KernelError::Crypto(format!("cannot read {}: {}", name, e)))?;
+        let mut entries = raw.split(',').map(str::trim).filter(|e| !e.is_empty());
+        let parse = |entry: &str| -> Result<(String, EncryptionKey), KernelError> {
+            let (key_id, key) = entry.split_once(':').ok_or_else(|| {
+                KernelError::Crypto(format!("{}: entries must be key-id:base64-key", name))
+            })?;
+            Ok((key_id.to_string(), EncryptionKey::from_base64(key)?))
+        };
+        let (key_id, key) = parse(
+            entries
+                .next()
+                .ok_or_else(|| KernelError::Crypto(format!("{} holds no keys", name)))?,
+        )?;
+        let mut keys = StaticKeyProvider::new(key_id, key)?;
+        for entry in entries {
+            let (key_id, key) = parse(entry)?;
+            keys = keys.with_retired_key(key_id, key)?;
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl KeyProvider for EnvKeyProvider {
+    fn current_key(&self) -> Result<(String, EncryptionKey), KernelError> {
+        self.0.current_key()
+    }
+
+    fn key(&self, key_id: &str) -> Result<Option<EncryptionKey>, KernelError> {
+        self.0.key(key_id)
+    }
+}
+
+/// Ciphertext with the id of the key and the nonce it was sealed with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedState {
+    pub key_id: String,
+    /// Base64 96-bit nonce.
+    pub nonce: String,
+    /// Base64 AES-GCM ciphertext and tag.
+    pub ciphertext: String,
+}
+
+/// Seals and opens values for one run with a provider's keys
+#[derive(Clone)]
+struct Sealer {
+    keys: Arc<dyn KeyProvider>,
+}
+
+impl Sealer {
+    fn seal<T: Serialize + ?Sized>(
+        &self,
+        run_id: &RunId,
+        value: &T,
+    ) -> Result<SealedState, KernelError> {
+        let plaintext = serde_json::to_vec(value)
+            .map_err(|e| KernelError::Crypto(format!("serialize for sealing: {}", e)))?;
+        let (key_id, key) = self.keys.current_key()?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.0));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: run_id.as_bytes(),
+                },
+            )
+            .map_err(|_| KernelError::Crypto(format!("cannot encrypt with key '{}'", key_id)))?;
+        Ok(SealedState {
+            key_id,
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    fn open<T: DeserializeOwned>(
+        &self,
+        run_id: &RunId,
+        sealed: &SealedState,
+    ) -> Result<T, KernelError> {
+        let key = self.keys.key(&sealed.key_id)?.ok_or_else(|| {
+            KernelError::Crypto(format!(
+                "data of run '{}' is sealed with unknown key '{}'",
+                run_id, sealed.key_id
+            ))
+        })?;
+        let malformed = |what: &str| {
+            KernelError::Crypto(format!("data of run '{}' has a malformed {}", run_id, what))
+        };
+        let nonce = BASE64
+            .decode(&sealed.nonce)
+            .map_err(|_| malformed("nonce"))?;
+        if nonce.len() != 12 {
+            return Err(malformed("nonce"));
+        }
+        let ciphertext = BASE64
+            .decode(&sealed.ciphertext)
+            .map_err(|_| malformed("ciphertext"))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.0));
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: run_id.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                KernelError::Crypto(format!(
+                    "cannot decrypt data of run '{}' with key '{}': wrong key or tampered data",
+                    run_id, sealed.key_id
+                ))
+            })?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| KernelError::Crypto(format!("decrypted data of run '{}': {}", run_id, e)))
+    }
+
+    fn seal_field<T: Serialize + ?Sized>(
+        &self,
+        run_id: &RunId,
+        value: &T,
+    ) -> Result<String, KernelError> {
+        let sealed = self.seal(run_id, value)?;
+        Ok(format!(
+            "{}{}:{}:{}",
+            SEALED_FIELD_PREFIX, sealed.key_id, sealed.nonce, sealed.ciphertext
+        ))
+    }
+
+    /// Opens a field sealed by [Sealer::seal_field]; `None` for plaintext fields
+    fn open_field<T: DeserializeOwned>(
+        &self,
+        run_id: &RunId,
+        field: &str,
+    ) -> Result<Option<T>, KernelError> {
+        let Some(sealed) = field.strip_prefix(SEALED_FIELD_PREFIX) else {
+            return Ok(None);
+        };
+        let mut parts = sealed.splitn(3, ':');
+        let (Some(key_id), Some(nonce), Some(ciphertext)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(KernelError::Crypto(format!(
+                "event of run '{}' has a malformed sealed field",
+                run_id
+            )));
+        };
+        self.open(
+            run_id,
+            &SealedState {
+                key_id: key_id.to_string(),
+                nonce: nonce.to_string(),
+                ciphertext: ciphertext.to_string(),
+            },
+        )
+        .map(Some)
+    }
+
+    fn seal_value(&self, run_id: &RunId, value: &Value) -> Result<Value, KernelError> {
+        self.seal_field(run_id, value).map(Value::String)
+    }
+
+    fn open_value(&self, run_id: &RunId, value: Value) -> Result<Value, KernelError> {
+        match &value {
+            Value::String(field) => Ok(self.open_field(run_id, field)?.unwrap_or(value)),
+            _ => Ok(value),
+        }
+    }
+
+    fn open_string(&self, run_id: &RunId, field: String) -> Result<String, KernelError> {
+        Ok(self.open_field(run_id, &field)?.unwrap_or(field))
+    }
+
+    fn seal_event(&self, run_id: &RunId, event: &Event) -> Result<Event, KernelError> {
+        Ok(match event {
+            Event::StateUpdated {
+                step_id,
+                payload,
+                state_hash,
+                merge_report,
+            } => Event::StateUpdated {
+                step_id: step_id.clone(),
+                payload: self.seal_value(run_id, payload)?,
+                state_hash: state_hash.clone(),
+                merge_report: merge_report.clone(),
+            },
+            Event::ActionRequested { action_id, payload } => Event::ActionRequested {
+                action_id: action_id.clone(),
+                payload: self.seal_value(run_id, payload)?,
+            },
+            Event::ActionSucceeded {
+                action_id,
+                output,
+                dry_run,
+            } => Event::ActionSucceeded {
+                action_id: action_id.clone(),
+                output: self.seal_value(run_id, output)?,
+                dry_run: *dry_run,
+            },
+            Event::ActionFailed {
+                action_id,
+                error,
+                dry_run,
+            } => Event::ActionFailed {
+                action_id: action_id.clone(),
+                error: self.seal_field(run_id, error)?,
+                dry_run: *dry_run,
+            },
+            Event::ActionRecorded {
+                action_id,
+                step_id,
+                index,
+                action,
+                outcome,
+            } => Event::ActionRecorded {
+                action_id: action_id.clone(),
+                step_id: step_id.clone(),
+                index: *index,
+                action: self.seal_value(run_id, action)?,
+                outcome: self.seal_value(run_id, outcome)?,
+            },
+            // Kinds, decisions, codes and policy names stay readable for audits
+            Event::PolicyDecision {
+                action_kind,
+                decision,
+                code,
+                policy,
+                reason,
+            } => Event::PolicyDecision {
+                action_kind: action_kind.clone(),
+                decision: *decision,
+                code: code.clone(),
+                policy: policy.clone(),
+                reason: reason
+                    .as_ref()
+                    .map(|reason| self.seal_field(run_id, reason))
+                    .transpose()?,
+            },
+            Event::ActionDenied { payload, reason } => Event::ActionDenied {
+                payload: self.seal_value(run_id, payload)?,
+                reason: self.seal_field(run_id, reason)?,
+            },
+            Event::Interrupted { value } => Event::Interrupted {
+                value: self.seal_value(run_id, value)?,
+            },
+            Event::Resumed { value } => Event::Resumed {
+                value: self.seal_value(run_id, value)?,
+            },
+            Event::Failed { reason, code } => Event::Failed {
+                reason: self.seal_field(run_id, reason)?,
+                code: code.clone(),
+            },
+            Event::Cancelled { reason } => Event::Cancelled {
+                reason: reason
+                    .as_ref()
+                    .map(|reason| self.seal_field(run_id, reason))
+                    .transpose()?,
+            },
+            // Retry schedules and budget denials hold only the kernel's own codes and figures
+            Event::RetryScheduled { .. }
+            | Event::Completed
+            | Event::Paused
+            | Event::BudgetExceeded { .. }
+            | Event::Compacted { .. }
+            | Event::SnapshotTaken { .. } => event.clone(),
+        })
+    }
+
+    fn open_event(&self, run_id: &RunId, event: Event) -> Result<Event, KernelError> {
+        Ok(match event {
+            Event::StateUpdated {
+                step_id,
+                payload,
+                state_hash,
+                merge_report,
+            } => Event::StateUpdated {
+                step_id,
+                payload: self.open_value(run_id, payload)?,
+                state_hash,
+                merge_report,
+            },
+            Event::ActionRequested { action_id, payload } => Event::ActionRequested {
+                action_id,
+                payload: self.open_value(run_id, payload)?,
+            },
+            Event::ActionSucceeded {
+                action_id,
+                output,
+                dry_run,
+            } => Event::ActionSucceeded {
+                action_id,
+                output: self.open_value(run_id, output)?,
+                dry_run,
+            },
+            Event::ActionFailed {
+                action_id,
+                error,
+                dry_run,
+            } => Event::ActionFailed {
+                action_id,
+                error: self.open_string(run_id, error)?,
+                dry_run,
+            },
+            Event::ActionRecorded {
+                action_id,
+                step_id,
+                index,
+                action,
+                outcome,
+            } => Event::ActionRecorded {
+                action_id,
+                step_id,
+                index,
+                action: self.open_value(run_id, action)?,
+                outcome: self.open_value(run_id, outcome)?,
+            },
+            Event::PolicyDecision {
+                action_kind,
+                decision,
+                code,
+                policy,
+                reason,
+            } => Event::PolicyDecision {
+                action_kind,
+                decision,
+                code,
+                policy,
+                reason: reason
+                    .map(|reason| self.open_string(run_id, reason))
+                    .transpose()?,
+            },
+            Event::ActionDenied { payload, reason } => Event::ActionDenied {
+                payload: self.open_value(run_id, payload)?,
+                reason: self.open_string(run_id, reason)?,
+            },
+            Event::Interrupted { value } => Event::Interrupted {
+                value: self.open_value(run_id, value)?,
+            },
+            Event::Resumed { value } => Event::Resumed {
+                value: self.open_value(run_id, value)?,
+            },
+            Event::Failed { reason, code } => Event::Failed {
+                reason: self.open_string(run_id, reason)?,
+                code,
+            },
+            Event::Cancelled { reason } => Event::Cancelled {
+                reason: reason
+                    .map(|reason| self.open_string(run_id, reason))
+                    .transpose()?,
+            },
+            event @ (Event::RetryScheduled { .. }
+            | Event::Completed
+            | Event::Paused
+            | Event::BudgetExceeded { .. }
+            | Event::Compacted { .. }
+            | Event::SnapshotTaken { .. }) => event,
+        })
+    }
+
+    fn open_events(
+        &self,
+        run_id: &RunId,
+        events: Vec<SequencedEvent>,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        events
+            .into_iter()
+            .map(|se| {
+                Ok(SequencedEvent {
+                    seq: se.seq,
+                    event: self.open_event(run_id, se.event)?,
+                    version: se.version,
+                    recorded_at_ms: se.recorded_at_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Event store that encrypts event payloads before they reach the wrapped store.
+pub struct EncryptedEventStore<E: EventStore> {
+    inner: E,
+    sealer: Sealer,
+}
+
+impl<E: EventStore> EncryptedEventStore<E> {
+    pub fn new(inner: E, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            inner,
+            sealer: Sealer { keys },
+        }
+    }
+
+    /// The wrapped store; its events hold ciphertext.
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: EventStore> EventStore for EncryptedEventStore<E> {
+    fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+        let sealed = events
+            .iter()
+            .map(|event| self.sealer.seal_event(run_id, event))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.inner.append(run_id, &sealed)
+    }
+
+    fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.sealer
+            .open_events(run_id, self.inner.scan(run_id, from)?)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        self.inner.head(run_id)
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.sealer
+            .open_events(run_id, self.inner.scan_range(run_id, from, to, filter)?)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.sealer
+            .open_events(run_id, self.inner.scan_rev(run_id, limit, filter)?)
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.inner.list_runs(filter, page)
+    }
+
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        self.inner.run_exists(run_id)
+    }
+
+    fn flush(&self) -> Result<(), KernelError> {
+        self.inner.flush()
+    }
+
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.inner.flush_step(run_id)
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        let marker = self.sealer.seal_event(run_id, marker)?;
+        self.inner.replace_prefix(run_id, up_to_seq, &marker)
+    }
+}
+
+/// Snapshot store that encrypts snapshot state; the wrapped store holds [SealedState]s.
+pub struct EncryptedSnapshotStore<T> {
+    inner: T,
+    sealer: Sealer,
+}
+
+impl<T: SnapshotStore<SealedState>> EncryptedSnapshotStore<T> {
+    pub fn new(inner: T, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            inner,
+            sealer: Sealer { keys },
+        }
+    }
+
+    /// The wrapped store; its snapshots hold ciphertext.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<S, T> SnapshotStore<S> for EncryptedSnapshotStore<T>
+where
+    S: Serialize + DeserializeOwned,
+    T: SnapshotStore<SealedState>,
+{
+    fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.inner
+            .load_latest(run_id)?
+            .map(|snapshot| self.open_snapshot(run_id, snapshot))
+            .transpose()
+    }
+
+    fn save(&self, snapshot: &Snapshot<S>) -> Result<(), KernelError> {
+        self.inner.save(&Snapshot {
+            run_id: snapshot.run_id.clone(),
+            at_seq: snapshot.at_seq,
+            state: self.sealer.seal(&snapshot.run_id, &snapshot.state)?,
+            state_version: snapshot.state_version,
+            event_count: snapshot.event_count,
+            content_hash: None,
+        })
+    }
+
+    fn load_at_or_before(
+        &self,
+        run_id: &RunId,
+        seq: Seq,
+    ) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.inner
+            .load_at_or_before(run_id, seq)?
+            .map(|snapshot| self.open_snapshot(run_id, snapshot))
+            .transpose()
+    }
+
+    fn prune(&self, run_id: &RunId, keep: usize) -> Result<usize, KernelError> {
+        self.inner.prune(run_id, keep)
+    }
+}
+
+impl<T> EncryptedSnapshotStore<T> {
+    fn open_snapshot<S: DeserializeOwned>(
+        &self,
+        run_id: &RunId,
+        snapshot: Snapshot<SealedState>,
+    ) -> Result<Snapshot<S>, KernelError> {
+        Ok(Snapshot {
+            state: self.sealer.open(run_id, &snapshot.state)?,
+            run_id: snapshot.run_id,
+            at_seq: snapshot.at_seq,
+            state_version: snapshot.state_version,
+            event_count: snapshot.event_count,
+            // The wrapped store's hash covers the ciphertext, and was checked on load
+            content_hash: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::driver::{Kernel, RunStatus};
+    use crate::kernel::event_store::{check_event_store_contract, InMemoryEventStore};
+    use crate::kernel::execution_log::scan_execution_log;
+    use crate::kernel::kernel_mode::KernelMode;
+    use crate::kernel::snapshot::InMemorySnapshotStore;
+    use crate::kernel::state::KernelState;
+    use crate::kernel::step::{Next, StepFn};
+    use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+    use crate::kernel::timeline::run_timeline;
+    use crate::kernel::{SharedEventStore, StateUpdatedOnlyReducer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const SECRET: &str = "patient record 4711";
+
+    fn keys(key_id: &str, key: &EncryptionKey) -> Arc<dyn KeyProvider> {
+        Arc::new(StaticKeyProvider::new(key_id, key.clone()).unwrap())
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    struct Notes(Vec<String>);
+    impl KernelState for Notes {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    /// Writes the secret into the state, then completes
+    struct WriteSecret(AtomicUsize);
+    impl StepFn<Notes> for WriteSecret {
+        fn next(&self, state: &Notes) -> Result<Next, KernelError> {
+            if self.0.fetch_add(1, Ordering::SeqCst) > 0 {
+                return Ok(Next::Complete);
+            }
+            let mut notes = state.clone();
+            notes.0.push(SECRET.to_string());
+            Ok(Next::Emit(vec![Event::StateUpdated {
+                step_id: Some("note".into()),
+                payload: serde_json::to_value(notes).unwrap(),
+                state_hash: None,
+                merge_report: None,
+            }]))
+        }
+    }
+
+    #[test]
+    fn encrypted_event_store_meets_the_contract() {
+        let key = EncryptionKey::generate();
+        check_event_store_contract(&EncryptedEventStore::new(
+            InMemoryEventStore::new(),
+            keys("k1", &key),
+        ));
+    }
+
+    #[test]
+    fn kernel_runs_replay_and_audit_through_the_wrappers() {
+        let key = EncryptionKey::generate();
+        let log = SharedEventStore::new();
+        let snapshots = Arc::new(InMemorySnapshotStore::<SealedState>::new());
+        struct Snapshots(Arc<InMemorySnapshotStore<SealedState>>);
+        impl SnapshotStore<SealedState> for Snapshots {
+            fn load_latest(
+                &self,
+                run_id: &RunId,
+            ) -> Result<Option<Snapshot<SealedState>>, KernelError> {
+                self.0.load_latest(run_id)
+            }
+
+            fn save(&self, snapshot: &Snapshot<SealedState>) -> Result<(), KernelError> {
+                self.0.save(snapshot)
+            }
+        }
+        let k = Kernel::<Notes> {
+            events: Box::new(EncryptedEventStore::new(
+                SharedEventStore(log.0.clone()),
+                keys("k1", &key),
+            )),
+            snaps: Some(Box::new(EncryptedSnapshotStore::new(
+                Snapshots(snapshots.clone()),
+                keys("k1", &key),
+            ))),
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(WriteSecret(AtomicUsize::new(0))),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id: RunId = "encrypted-run".into();
+        let status = k.run_until_blocked(&run_id, Notes::default()).unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        // Nothing the wrapped stores hold reveals the secret
+        let stored = serde_json::to_string(&log.scan(&run_id, 1).unwrap()).unwrap();
+        assert!(!stored.contains(SECRET), "{}", stored);
+        assert!(stored.contains(SEALED_FIELD_PREFIX));
+        let snapshot = snapshots.load_latest(&run_id).unwrap().unwrap();
+        assert!(!serde_json::to_string(&snapshot).unwrap().contains(SECRET));
+
+        let expected = Notes(vec![SECRET.to_string()]);
+        assert_eq!(k.replay(&run_id, Notes::default()).unwrap(), expected);
+        assert_eq!(
+            k.replay_from_snapshot(&run_id, Notes::default()).unwrap(),
+            expected
+        );
+        let timeline =
+            serde_json::to_string(&run_timeline(k.events.as_ref(), &run_id).unwrap()).unwrap();
+        assert!(timeline.contains("Completed"));
+        let execution_log = scan_execution_log(k.events.as_ref(), &run_id, 1).unwrap();
+        assert_eq!(execution_log.len(), 2);
+        assert_eq!(execution_log[0].step_id.as_deref(), Some("note"));
+        let decrypted = serde_json::to_string(&k.events.scan(&run_id, 1).unwrap()).unwrap();
+        assert!(decrypted.contains(SECRET));
+    }
+
+    #[test]
+    fn wrong_and_rotated_keys() {
+        let (old, new) = (EncryptionKey::generate(), EncryptionKey::generate());
+        let log = SharedEventStore::new();
+        let run_id: RunId = "rotated".into();
+        let failed = Event::Failed {
+            reason: SECRET.into(),
+            code: Some("deadline_exceeded".into()),
+        };
+        EncryptedEventStore::new(SharedEventStore(log.0.clone()), keys("old", &old))
+            .append(&run_id, &[failed])
+            .unwrap();
+
+        // Same key id, different key: a crypto error, not a serde one
+        let wrong = EncryptedEventStore::new(SharedEventStore(log.0.clone()), keys("old", &new));
+        let err = wrong.scan(&run_id, 1).unwrap_err();
+        assert!(matches!(err, KernelError::Crypto(_)), "{:?}", err);
+        assert!(err.to_string().contains("wrong key"), "{}", err);
+        let unknown = EncryptedEventStore::new(SharedEventStore(log.0.clone()), keys("new", &new));
+        assert!(matches!(
+            unknown.scan(&run_id, 1),
+            Err(KernelError::Crypto(_))
+        ));
+
+        // After rotation, old events still open and new ones use the new key
+        let rotated = Arc::new(
+            StaticKeyProvider::new("new", new.clone())
+                .unwrap()
+                .with_retired_key("old", old)
+                .unwrap(),
+        );
+        let store = EncryptedEventStore::new(SharedEventStore(log.0.clone()), rotated);
+        store
+            .append(
+                &run_id,
+                &[Event::Resumed {
+                    value: serde_json::json!({ "note": SECRET }),
+                }],
+            )
+            .unwrap();
+        let events = store.scan(&run_id, 1).unwrap();
+        assert!(matches!(
+            &events[0].event,
+            Event::Failed { reason, code: Some(code) } if reason == SECRET && code == "deadline_exceeded"
+        ));
+        assert!(matches!(
+            &events[1].event,
+            Event::Resumed { value } if value["note"] == SECRET
+        ));
+        let raw = serde_json::to_string(&log.scan(&run_id, 2).unwrap()).unwrap();
+        assert!(raw.contains("oris-enc:v1:new:"), "{}", raw);
+
+        // Plaintext written before encryption was enabled reads as is
+        log.append(&run_id, &[Event::Completed]).unwrap();
+        assert_eq!(store.scan(&run_id, 1).unwrap().len(), 3);
+
+        // Ciphertexts are bound to their run
+        let moved = log.scan(&run_id, 1).unwrap().remove(0).event;
+        log.append(&"other-run".to_string(), &[moved]).unwrap();
+        assert!(matches!(
+            store.scan(&"other-run".to_string(), 1),
+            Err(KernelError::Crypto(_))
+        ));
+    }
+
+    #[test]
+    fn snapshots_need_the_right_key() {
+        let key = EncryptionKey::generate();
+        let store = EncryptedSnapshotStore::new(InMemorySnapshotStore::new(), keys("k1", &key));
+        let snapshot = Snapshot {
+            run_id: "snap".to_string(),
+            at_seq: 3,
+            state: Notes(vec![SECRET.to_string()]),
+            state_version: 1,
+            event_count: 3,
+            content_hash: None,
+        };
+        store.save(&snapshot).unwrap();
+        let loaded: Snapshot<Notes> = store.load_latest(&snapshot.run_id).unwrap().unwrap();
+        assert_eq!((loaded.at_seq, loaded.state), (3, snapshot.state));
+
+        let sealed = store
+            .inner()
+            .load_latest(&snapshot.run_id)
+            .unwrap()
+            .unwrap();
+        let wrong = EncryptedSnapshotStore::new(
+            InMemorySnapshotStore::new(),
+            keys("k1", &EncryptionKey::generate()),
+        );
+        wrong.inner().save(&sealed).unwrap();
+        let err = SnapshotStore::<Notes>::load_latest(&wrong, &snapshot.run_id).unwrap_err();
+        assert!(matches!(err, KernelError::Crypto(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn env_provider_reads_current_and_retired_keys() {
+        let (current, retired) = (EncryptionKey::generate(), EncryptionKey::generate());
+        let var = "ORIS_KERNEL_ENCRYPTION_KEYS_TEST";
+        std::env::set_var(
+            var,
+            format!("k2:{}, k1:{}", current.to_base64(), retired.to_base64()),
+        );
+        let provider = EnvKeyProvider::from_var(var).unwrap();
+        assert_eq!(provider.current_key().unwrap().0, "k2");
+        assert!(provider.key("k1").unwrap().is_some());
+        assert!(provider.key("k3").unwrap().is_none());
+
+        std::env::set_var(var, "k1:not-base64");
+        assert!(matches!(
+            EnvKeyProvider::from_var(var),
+            Err(KernelError::Crypto(_))
+        ));
+        std::env::remove_var(var);
+        assert!(EnvKeyProvider::from_var(var).is_err());
+        assert!(StaticKeyProvider::new("a:b", current).is_err());
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[test]
+    fn sqlite_file_holds_no_plaintext() {
+        use crate::kernel::sqlite_store::SqliteEventStore;
+
+        let path = std::env::temp_dir().join(format!(
+            "oris-kernel-encrypted-{}-{}.sqlite",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let key = EncryptionKey::generate();
+        let store =
+            EncryptedEventStore::new(SqliteEventStore::new(&path).unwrap(), keys("k1", &key));
+        let run_id: RunId = "sqlite-encrypted".into();
+        store
+            .append(
+                &run_id,
+                &[Event::Interrupted {
+                    value: serde_json::json!(SECRET),
+                }],
+            )
+            .unwrap();
+        assert!(matches!(
+            &store.scan(&run_id, 1).unwrap()[0].event,
+            Event::Interrupted { value } if value == SECRET
+        ));
+        drop(store);
+        let bytes = std::fs::read(&path).unwrap();
+        let wal = std::fs::read(path.with_extension("sqlite-wal")).unwrap_or_default();
+        let needle = SECRET.as_bytes();
+        for contents in [&bytes, &wal] {
+            assert!(!contents.windows(needle.len()).any(|w| w == needle));
+        }
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("sqlite-wal"));
+        let _ = std::fs::remove_file(path.with_extension("sqlite-shm"));
+    }
+}
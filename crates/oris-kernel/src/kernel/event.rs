@@ -3,14 +3,27 @@
 //! Events are the source of truth. All state is derived by reducing events.
 //! Constraints: append is atomic (all or nothing); every event has a seq; scan returns ordered by seq.
 
+use std::collections::BTreeSet;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::kernel::action::ActionErrorKind;
 use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunSummary};
+use crate::kernel::reducer::MergeReport;
+
+/// Schema version of [Event] as it is written today.
+///
+/// Persistent stores record it with every event and upgrade older events through an
+/// [EventMigrator](crate::kernel::EventMigrator) on read. Version 1 is every event
+/// stored before versions were recorded. Bump it, and register a migration from the
+/// previous version, whenever a change to [Event] would break reading stored events.
+pub const CURRENT_EVENT_VERSION: u32 = 2;
 
 /// A single event in the kernel event log.
 ///
-/// Covers: state updates, action lifecycle, interrupt/resume, completion.
+/// Covers: state updates, action lifecycle, interrupt/resume, failure, completion.
 /// Aligns with existing trace (StepCompleted → StateUpdated + optional Action*; InterruptReached → Interrupted; ResumeReceived → Resumed).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
@@ -20,6 +33,15 @@ pub enum Event {
         step_id: Option<String>,
         /// Serialized state or state delta (schema depends on State type).
         payload: Value,
+        /// Hex [canonical_state_hash](crate::kernel::canonical_state_hash) of the state after
+        /// this event, when the writer's state type supports hashing.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state_hash: Option<String>,
+        /// How the reducer merged the payload into the state (see
+        /// [Reducer::apply_with_report](crate::kernel::Reducer::apply_with_report)), when
+        /// the driver's reducer reports it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        merge_report: Option<MergeReport>,
     },
     /// An external action was requested (tool, LLM, sleep, wait signal).
     ActionRequested {
@@ -34,6 +56,10 @@ pub enum Event {
         action_id: String,
         /// JSON output returned by the executor.
         output: Value,
+        /// The output was simulated by the policy in [KernelMode::DryRun](crate::kernel::KernelMode::DryRun);
+        /// no executor ran.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
     },
     /// The action failed; error is stored for audit and retry policy.
     ActionFailed {
@@ -41,6 +67,63 @@ pub enum Event {
         action_id: String,
         /// Error message from the executor.
         error: String,
+        /// The failure was simulated by the policy in [KernelMode::DryRun](crate::kernel::KernelMode::DryRun);
+        /// no executor ran.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
+    },
+    /// One execution of an action and its outcome, written by
+    /// [RecordingActionExecutor](crate::kernel::RecordingActionExecutor) so a replay can
+    /// return it instead of calling the executor. Bookkeeping only: reducers should ignore it.
+    ActionRecorded {
+        /// The `action_id` of the `ActionRequested` being executed.
+        action_id: String,
+        /// `step_id` of the run's latest `StateUpdated` before the action, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step_id: Option<String>,
+        /// Position of the action among those requested since that `StateUpdated`.
+        index: u32,
+        /// The executed action, as in `ActionRequested::payload`.
+        action: Value,
+        /// What the executor returned: a serialized
+        /// [RecordedOutcome](crate::kernel::RecordedOutcome).
+        outcome: Value,
+    },
+    /// The policy was consulted about an action: whether it may run (written before it is
+    /// requested or denied), or whether to retry it after an executor error (written before
+    /// its outcome). Bookkeeping only, for audits: reducers should ignore it.
+    PolicyDecision {
+        /// [Action::kind](crate::kernel::Action::kind) of the action.
+        action_kind: String,
+        decision: PolicyDecisionKind,
+        /// Machine-readable reason: `POLICY_AUTHORIZED`, `POLICY_DENIED`, a `BUDGET_*` code, or
+        /// for retry and fail decisions the failed attempt's
+        /// [ActionErrorKind::code](crate::kernel::ActionErrorKind::code).
+        code: String,
+        /// [Policy::name](crate::kernel::Policy::name) of the policy that decided.
+        policy: String,
+        /// The denial message or the error being retried, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// The policy denied the step's action, so it was never requested and the run stopped
+    /// with the policy's error. Events before it are intact: running the run again, e.g.
+    /// under a policy that allows the action, asks the step again.
+    ActionDenied {
+        /// The denied action, as `ActionRequested::payload` would have held it.
+        payload: Value,
+        /// Why the policy denied it.
+        reason: String,
+    },
+    /// The policy retries a failed attempt of an action after a wait; written after its
+    /// `retry` decision, before the attempt. Bookkeeping only: reducers should ignore it.
+    RetryScheduled {
+        /// The `action_id` of the `ActionRequested` being retried.
+        action_id: String,
+        /// The attempt about to run: 1 for the first retry.
+        attempt: u32,
+        /// How long the driver waits before the attempt, in milliseconds.
+        delay_ms: u64,
     },
     /// Execution was interrupted (e.g. human-in-the-loop).
     Interrupted {
@@ -52,8 +135,213 @@ pub enum Event {
         /// Resume value provided by the caller (e.g. human approval payload).
         value: Value,
     },
+    /// The run was stopped by the step function (e.g. step limit exceeded).
+    ///
+    /// Events before it are intact, so the run can be continued later.
+    Failed {
+        /// Why the run stopped.
+        reason: String,
+        /// Machine-readable reason code (e.g. `deadline_exceeded`), if the step gave one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+    },
     /// The run completed.
     Completed,
+    /// The run was paused at a step boundary on request (see
+    /// [RunControl](crate::kernel::RunControl)); running it again continues it.
+    Paused,
+    /// The policy denied the step's action(s) for overrunning the run's budget (see
+    /// [Policy::check_budget](crate::kernel::Policy::check_budget)), so the run blocked
+    /// before requesting them. Running it again, e.g. once an operator raised the budget,
+    /// asks the step again.
+    BudgetExceeded {
+        /// One of the `BUDGET_*` reason codes, e.g. `BUDGET_TOKENS_EXCEEDED`.
+        code: String,
+        /// Which limit was reached, for operators.
+        reason: String,
+    },
+    /// The run was cancelled on request. Terminal: the kernel refuses to advance it again.
+    Cancelled {
+        /// Why the run was cancelled, if the requester said.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// The run's earlier events were archived by log compaction.
+    ///
+    /// Stored at the seq of the last archived event, so it starts the remaining log; the
+    /// state those events produced is in the snapshot at `snapshot_seq`.
+    Compacted {
+        /// Highest archived seq; the seq this event is stored at.
+        up_to_seq: Seq,
+        /// Snapshot that replay starts from instead (`>= up_to_seq`).
+        snapshot_seq: Seq,
+    },
+    /// The driver saved a snapshot of the run's state under its
+    /// [SnapshotPolicy](crate::kernel::SnapshotPolicy). Written once the snapshot is
+    /// stored, so it may come after events appended meanwhile, or after the event that
+    /// ended or blocked the run. Bookkeeping only: reducers should ignore it.
+    SnapshotTaken {
+        /// Seq of the last event applied to the snapshotted state (`Snapshot::at_seq`).
+        at_seq: Seq,
+    },
+}
+
+impl Event {
+    /// The variant of the event, without its payload.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::StateUpdated { .. } => EventKind::StateUpdated,
+            Event::ActionRequested { .. } => EventKind::ActionRequested,
+            Event::ActionSucceeded { .. } => EventKind::ActionSucceeded,
+            Event::ActionFailed { .. } => EventKind::ActionFailed,
+            Event::ActionRecorded { .. } => EventKind::ActionRecorded,
+            Event::PolicyDecision { .. } => EventKind::PolicyDecision,
+            Event::ActionDenied { .. } => EventKind::ActionDenied,
+            Event::RetryScheduled { .. } => EventKind::RetryScheduled,
+            Event::Interrupted { .. } => EventKind::Interrupted,
+            Event::Resumed { .. } => EventKind::Resumed,
+            Event::Failed { .. } => EventKind::Failed,
+            Event::Completed => EventKind::Completed,
+            Event::Paused => EventKind::Paused,
+            Event::BudgetExceeded { .. } => EventKind::BudgetExceeded,
+            Event::Cancelled { .. } => EventKind::Cancelled,
+            Event::Compacted { .. } => EventKind::Compacted,
+            Event::SnapshotTaken { .. } => EventKind::SnapshotTaken,
+        }
+    }
+}
+
+/// What the policy decided in a [Event::PolicyDecision].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyDecisionKind {
+    /// The action may run.
+    Allow,
+    /// The action may not run ([Policy::authorize](crate::kernel::Policy::authorize) or
+    /// [Policy::check_budget](crate::kernel::Policy::check_budget) refused it).
+    Deny,
+    /// The failed attempt is tried again.
+    Retry,
+    /// The failed attempt is the action's last; it is recorded as `ActionFailed`.
+    Fail,
+}
+
+impl PolicyDecisionKind {
+    /// The serialized name, e.g. `"deny"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Retry => "retry",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// Discriminant of an [Event]; serializes as the variant name, the tag events serialize under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    StateUpdated,
+    ActionRequested,
+    ActionSucceeded,
+    ActionFailed,
+    ActionRecorded,
+    PolicyDecision,
+    ActionDenied,
+    RetryScheduled,
+    Interrupted,
+    Resumed,
+    Failed,
+    Completed,
+    Paused,
+    BudgetExceeded,
+    Cancelled,
+    Compacted,
+    SnapshotTaken,
+}
+
+impl EventKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [EventKind; 17] = [
+        EventKind::StateUpdated,
+        EventKind::ActionRequested,
+        EventKind::ActionSucceeded,
+        EventKind::ActionFailed,
+        EventKind::ActionRecorded,
+        EventKind::PolicyDecision,
+        EventKind::ActionDenied,
+        EventKind::RetryScheduled,
+        EventKind::Interrupted,
+        EventKind::Resumed,
+        EventKind::Failed,
+        EventKind::Completed,
+        EventKind::Paused,
+        EventKind::BudgetExceeded,
+        EventKind::Cancelled,
+        EventKind::Compacted,
+        EventKind::SnapshotTaken,
+    ];
+
+    /// The variant name, e.g. `"StateUpdated"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::StateUpdated => "StateUpdated",
+            EventKind::ActionRequested => "ActionRequested",
+            EventKind::ActionSucceeded => "ActionSucceeded",
+            EventKind::ActionFailed => "ActionFailed",
+            EventKind::ActionRecorded => "ActionRecorded",
+            EventKind::PolicyDecision => "PolicyDecision",
+            EventKind::ActionDenied => "ActionDenied",
+            EventKind::RetryScheduled => "RetryScheduled",
+            EventKind::Interrupted => "Interrupted",
+            EventKind::Resumed => "Resumed",
+            EventKind::Failed => "Failed",
+            EventKind::Completed => "Completed",
+            EventKind::Paused => "Paused",
+            EventKind::BudgetExceeded => "BudgetExceeded",
+            EventKind::Cancelled => "Cancelled",
+            EventKind::Compacted => "Compacted",
+            EventKind::SnapshotTaken => "SnapshotTaken",
+        }
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which kinds of event a scan returns; the default returns every event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    kinds: Option<BTreeSet<EventKind>>,
+}
+
+impl EventFilter {
+    /// Every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only events of the given kinds (none at all if `kinds` is empty).
+    pub fn only(kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        Self {
+            kinds: Some(kinds.into_iter().collect()),
+        }
+    }
+
+    /// The kinds the filter keeps, or `None` when it keeps every event.
+    pub fn kinds(&self) -> Option<&BTreeSet<EventKind>> {
+        self.kinds.as_ref()
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        match &self.kinds {
+            Some(kinds) => kinds.contains(&event.kind()),
+            None => true,
+        }
+    }
 }
 
 /// An event with its assigned sequence number (store may assign seq on append).
@@ -63,6 +351,42 @@ pub struct SequencedEvent {
     pub seq: Seq,
     /// The event payload.
     pub event: Event,
+    /// Schema version the event was stored at; `event` has the current shape either way.
+    #[serde(
+        default = "current_event_version",
+        skip_serializing_if = "is_current_event_version"
+    )]
+    pub version: u32,
+    /// Wall-clock time the store appended the event, in milliseconds since the Unix
+    /// epoch; `None` when the store does not record it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at_ms: Option<i64>,
+}
+
+impl SequencedEvent {
+    /// An event at `seq`, stored at [CURRENT_EVENT_VERSION], without a recorded time.
+    pub fn new(seq: Seq, event: Event) -> Self {
+        Self {
+            seq,
+            event,
+            version: CURRENT_EVENT_VERSION,
+            recorded_at_ms: None,
+        }
+    }
+
+    /// The event with `recorded_at_ms` set.
+    pub fn recorded_at(mut self, ms: i64) -> Self {
+        self.recorded_at_ms = Some(ms);
+        self
+    }
+}
+
+pub(crate) fn current_event_version() -> u32 {
+    CURRENT_EVENT_VERSION
+}
+
+fn is_current_event_version(version: &u32) -> bool {
+    *version == CURRENT_EVENT_VERSION
 }
 
 /// Event store: append-only log per run, source of truth.
@@ -81,6 +405,165 @@ pub trait EventStore: Send + Sync {
 
     /// Returns the highest seq for the run (0 if no events).
     fn head(&self, run_id: &RunId) -> Result<Seq, KernelError>;
+
+    /// Scans the events with `from <= seq <= to` that match `filter`, in ascending seq order.
+    ///
+    /// Bounds outside the log are not errors: the range is clipped to the events that
+    /// exist, so `from > to`, `from` past the head and unknown runs all give an empty
+    /// result. Pass `Seq::MAX` as `to` to read to the end.
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .scan(run_id, from)?
+            .into_iter()
+            .take_while(|e| e.seq <= to)
+            .filter(|e| filter.matches(&e.event))
+            .collect())
+    }
+
+    /// Returns up to `limit` of the run's most recent events matching `filter`, in
+    /// descending seq order; empty for `limit == 0` and for unknown runs.
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut events = self.scan(run_id, 1)?;
+        events.retain(|e| filter.matches(&e.event));
+        events.reverse();
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    /// Lists the runs matching `filter`, ordered by first event time then run id.
+    ///
+    /// Stores that keep no index of their runs return an error; see also
+    /// [`ops::list_runs`](crate::kernel::ops::list_runs), which adds paging metadata.
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        let _ = (filter, page);
+        Err(KernelError::EventStore(
+            "this event store cannot list runs".to_string(),
+        ))
+    }
+
+    /// Whether the store holds any event for the run.
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        Ok(self.head(run_id)? > 0)
+    }
+
+    /// Writes through every event the store has accepted but not yet persisted.
+    ///
+    /// A no-op for stores that persist on `append`. The driver calls it before it
+    /// reports a run's status, so a returned status is always backed by the log.
+    fn flush(&self) -> Result<(), KernelError> {
+        Ok(())
+    }
+
+    /// Marks the end of one driver step of `run_id`; stores that buffer appends may
+    /// write them through here (see [BufferedEventStore](crate::kernel::BufferedEventStore)).
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        let _ = run_id;
+        Ok(())
+    }
+
+    /// Replaces the run's events with `seq <= up_to_seq` by `marker`, stored at seq
+    /// `up_to_seq`, in one atomic step; later events and the head are untouched.
+    ///
+    /// Fails if the run has no event at `up_to_seq`. This is the storage half of
+    /// [compact_run](crate::kernel::compaction::compact_run); call that instead, which
+    /// archives the events first. Stores that cannot delete events return an error.
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        let _ = (run_id, up_to_seq, marker);
+        Err(KernelError::EventStore(
+            "this event store cannot compact runs".to_string(),
+        ))
+    }
+}
+
+/// A shared store is a store, so several owners can write one log (e.g. a
+/// [Kernel](crate::kernel::Kernel) and a
+/// [RecordingActionExecutor](crate::kernel::RecordingActionExecutor)).
+impl<E: EventStore + ?Sized> EventStore for std::sync::Arc<E> {
+    fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+        (**self).append(run_id, events)
+    }
+
+    fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        (**self).scan(run_id, from)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        (**self).head(run_id)
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        (**self).scan_range(run_id, from, to, filter)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        (**self).scan_rev(run_id, limit, filter)
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        (**self).list_runs(filter, page)
+    }
+
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        (**self).run_exists(run_id)
+    }
+
+    fn flush(&self) -> Result<(), KernelError> {
+        (**self).flush()
+    }
+
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        (**self).flush_step(run_id)
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        (**self).replace_prefix(run_id, up_to_seq, marker)
+    }
 }
 
 /// Kernel-level error type.
@@ -98,10 +581,160 @@ pub enum KernelError {
     /// A policy rejection (unauthorized action, budget exceeded, etc.).
     #[error("Policy error: {0}")]
     Policy(String),
-    /// An error in the kernel driver (replay, step, or run-loop logic).
+    /// A storage backend (e.g. a runtime repository's database) failed to read or write.
+    #[error("Storage error: {0}")]
+    Storage(String),
+    /// A lease could not be taken or used: another one is active for the attempt, or the
+    /// lease changed, expired or belongs to another worker.
+    #[error("{0}")]
+    LeaseConflict(String),
+    /// An attempt was to be leased that is not waiting for dispatch.
+    #[error("{0}")]
+    NotDispatchable(String),
+    /// The addressed record (attempt, lease, interrupt, ...) does not exist.
+    #[error("{0}")]
+    NotFound(String),
+    /// The request conflicts with the record's current state (e.g. a dedupe token reused
+    /// with a different payload, or something already done).
+    #[error("{0}")]
+    Conflict(String),
+    /// An operation did not finish in time (e.g. waiting for a database connection).
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    /// The request or configuration is invalid.
+    #[error("Validation error: {0}")]
+    Validation(String),
+    /// An error in the kernel driver (replay, step, or run-loop logic), and the last resort
+    /// for errors no other variant describes.
     #[error("Driver error: {0}")]
     Driver(String),
     /// Executor returned a structured action error (for policy retry decisions).
     #[error("Executor: {0}")]
     Executor(crate::kernel::action::ActionError),
+    /// Replay needed events that log compaction archived, and no snapshot covers them.
+    #[error("run {run_id} is compacted through seq {up_to_seq}; replay needs a snapshot at or after it, or the archive")]
+    Compacted {
+        run_id: RunId,
+        /// Highest archived seq of the run.
+        up_to_seq: Seq,
+    },
+    /// Log compaction was refused (e.g. no covering snapshot) or failed.
+    #[error("Compaction error: {0}")]
+    Compaction(String),
+    /// A stored event has a schema version no registered migration upgrades from.
+    #[error("event {seq} of run {run_id} has schema version {version}, and no migration to version {current} is registered", current = CURRENT_EVENT_VERSION)]
+    UnsupportedEventVersion {
+        run_id: RunId,
+        seq: Seq,
+        /// Version the event was stored at.
+        version: u32,
+    },
+    /// An [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry) route was
+    /// invalid or overlapped one registered before.
+    #[error("Executor registry error: {0}")]
+    ExecutorRegistry(String),
+    /// Encrypting or decrypting stored data failed (e.g. wrong or unknown key).
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+    /// An [ActionResultCache](crate::kernel::ActionResultCache) failed to read or store a
+    /// result.
+    #[error("Action result cache error: {0}")]
+    ActionResultCache(String),
+    /// A run was addressed (e.g. to cancel it) that has no events.
+    #[error("run {0} not found")]
+    RunNotFound(RunId),
+    /// A run that has completed or been cancelled was asked to advance, pause or cancel.
+    #[error("run {run_id} has already ended ({status})")]
+    RunEnded {
+        run_id: RunId,
+        /// The event that ended it: `Completed` or `Cancelled`.
+        status: EventKind,
+    },
+    /// A resume token that names no interrupt of the run, or one the run is no longer
+    /// blocked on.
+    #[error("resume token {token} does not match the interrupt run {run_id} is blocked on")]
+    InvalidResumeToken { run_id: RunId, token: String },
+    /// The interrupt named by a resume token was resumed already.
+    #[error("interrupt {token} of run {run_id} was already resumed")]
+    AlreadyResumed { run_id: RunId, token: String },
+    /// A replay requested an action that differs from the recorded one at the same
+    /// position, or that was never recorded (`expected` is `None`).
+    #[error("action {index} of step {} in run {run_id} does not match the recording: expected {}, got {actual}", step_id.as_deref().unwrap_or("<start>"), expected.as_ref().map_or("no recorded action".to_string(), |e| e.to_string()))]
+    ActionReplayMismatch {
+        run_id: RunId,
+        /// `step_id` of the latest `StateUpdated` before the action.
+        step_id: Option<String>,
+        /// Position of the action among those requested since that `StateUpdated`.
+        index: u32,
+        /// The recorded action, if one was recorded at this position.
+        expected: Option<Value>,
+        /// The action the replay requested.
+        actual: Value,
+    },
+    /// A stored snapshot does not match the content hash saved with it (or no longer
+    /// decodes), so its state cannot be trusted. The driver then replays the full log.
+    #[error("snapshot at seq {at_seq} of run {run_id} failed its integrity check: {reason}")]
+    SnapshotIntegrity {
+        run_id: RunId,
+        /// `at_seq` of the corrupt snapshot.
+        at_seq: Seq,
+        /// What did not match, for operators.
+        reason: String,
+    },
+}
+
+impl KernelError {
+    /// Stable machine-readable code of the error, e.g. `LEASE_CONFLICT`; unlike the
+    /// message, it does not change between releases.
+    pub fn code(&self) -> &'static str {
+        match self {
+            KernelError::EventStore(_) => "EVENT_STORE",
+            KernelError::SnapshotStore(_) => "SNAPSHOT_STORE",
+            KernelError::Reducer(_) => "REDUCER",
+            KernelError::Policy(_) => "POLICY",
+            KernelError::Storage(_) => "STORAGE",
+            KernelError::LeaseConflict(_) => "LEASE_CONFLICT",
+            KernelError::NotDispatchable(_) => "NOT_DISPATCHABLE",
+            KernelError::NotFound(_) => "NOT_FOUND",
+            KernelError::Conflict(_) => "CONFLICT",
+            KernelError::Timeout(_) => "TIMEOUT",
+            KernelError::Validation(_) => "VALIDATION",
+            KernelError::Driver(_) => "DRIVER",
+            KernelError::Executor(_) => "EXECUTOR",
+            KernelError::Compacted { .. } => "COMPACTED",
+            KernelError::Compaction(_) => "COMPACTION",
+            KernelError::UnsupportedEventVersion { .. } => "UNSUPPORTED_EVENT_VERSION",
+            KernelError::ExecutorRegistry(_) => "EXECUTOR_REGISTRY",
+            KernelError::Crypto(_) => "CRYPTO",
+            KernelError::ActionResultCache(_) => "ACTION_RESULT_CACHE",
+            KernelError::RunNotFound(_) => "RUN_NOT_FOUND",
+            KernelError::RunEnded { .. } => "RUN_ENDED",
+            KernelError::InvalidResumeToken { .. } => "INVALID_RESUME_TOKEN",
+            KernelError::AlreadyResumed { .. } => "ALREADY_RESUMED",
+            KernelError::ActionReplayMismatch { .. } => "ACTION_REPLAY_MISMATCH",
+            KernelError::SnapshotIntegrity { .. } => "SNAPSHOT_INTEGRITY",
+        }
+    }
+
+    /// Whether the same call may succeed if made again later: store and storage failures,
+    /// timeouts, lease conflicts (once the lease is re-read or freed), and executor errors
+    /// of a transient, rate-limited or timeout kind. Everything else fails again until the
+    /// request or the state changes.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            KernelError::EventStore(_)
+            | KernelError::SnapshotStore(_)
+            | KernelError::Storage(_)
+            | KernelError::ActionResultCache(_)
+            | KernelError::LeaseConflict(_)
+            | KernelError::Timeout(_) => true,
+            KernelError::Executor(error) => matches!(
+                error.kind,
+                ActionErrorKind::Transient
+                    | ActionErrorKind::RateLimited
+                    | ActionErrorKind::Timeout
+            ),
+            _ => false,
+        }
+    }
 }
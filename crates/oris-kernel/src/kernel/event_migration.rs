@@ -0,0 +1,252 @@
+//! Upgrading stored events to the current [Event] schema.
+//!
+//! Persistent event stores record [CURRENT_EVENT_VERSION] with every event they write.
+//! On read they pass each event's stored JSON through an [EventMigrator], which applies
+//! the registered migrations one version at a time until the JSON has the current shape.
+//! An event whose version has no path to the current one fails the read with
+//! [KernelError::UnsupportedEventVersion], which names the version.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::kernel::event::{Event, KernelError, SequencedEvent, CURRENT_EVENT_VERSION};
+use crate::kernel::identity::{RunId, Seq};
+
+/// Upgrades the JSON of one event from one version to the next.
+pub type EventMigration = dyn Fn(Value) -> Result<Value, String> + Send + Sync;
+
+/// Registry of event migrations, keyed by the version they upgrade from.
+#[derive(Clone)]
+pub struct EventMigrator {
+    migrations: BTreeMap<u32, Arc<EventMigration>>,
+}
+
+impl EventMigrator {
+    /// The migrations for every version this crate has written.
+    ///
+    /// Version 1 events (stored before versions were recorded) already have the
+    /// version 2 shape; every change up to version 2 only added variants or optional
+    /// fields.
+    pub fn new() -> Self {
+        Self::empty().register(1, Ok)
+    }
+
+    /// A registry without migrations: only current-version events can be read.
+    pub fn empty() -> Self {
+        Self {
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the migration from `from_version` to `from_version + 1`, replacing any
+    /// registered before.
+    pub fn register(
+        mut self,
+        from_version: u32,
+        migration: impl Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.migrations.insert(from_version, Arc::new(migration));
+        self
+    }
+
+    /// Whether events stored at `version` can be read.
+    pub fn supports(&self, version: u32) -> bool {
+        version <= CURRENT_EVENT_VERSION
+            && (version..CURRENT_EVENT_VERSION).all(|v| self.migrations.contains_key(&v))
+    }
+
+    /// Decodes event `seq` of `run_id`, stored as `json` at `version`, into the current
+    /// [Event] shape.
+    pub fn decode(
+        &self,
+        run_id: &RunId,
+        seq: Seq,
+        version: u32,
+        json: Value,
+    ) -> Result<SequencedEvent, KernelError> {
+        if !self.supports(version) {
+            return Err(KernelError::UnsupportedEventVersion {
+                run_id: run_id.clone(),
+                seq,
+                version,
+            });
+        }
+        let mut json = json;
+        for from in version..CURRENT_EVENT_VERSION {
+            json = self.migrations[&from](json).map_err(|e| {
+                KernelError::EventStore(format!(
+                    "migrate event {} of run {} from version {}: {}",
+                    seq, run_id, from, e
+                ))
+            })?;
+        }
+        let event: Event = serde_json::from_value(json).map_err(|e| {
+            KernelError::EventStore(format!(
+                "decode event {} of run {} (stored at version {}): {}",
+                seq, run_id, version, e
+            ))
+        })?;
+        Ok(SequencedEvent {
+            seq,
+            event,
+            version,
+            recorded_at_ms: None,
+        })
+    }
+}
+
+impl Default for EventMigrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for EventMigrator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventMigrator")
+            .field("from_versions", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Events as stored before versions were recorded, as `(seq, event_json)` rows
+#[cfg(test)]
+pub(crate) const V1_EVENT_ROWS: &[(Seq, &str)] = &[
+    (
+        1,
+        r#"{"StateUpdated":{"step_id":"draft","payload":{"count":1}}}"#,
+    ),
+    (
+        2,
+        r#"{"ActionRequested":{"action_id":"a1","payload":{"tool":"search"}}}"#,
+    ),
+    (
+        3,
+        r#"{"ActionSucceeded":{"action_id":"a1","output":"found"}}"#,
+    ),
+    (4, r#"{"Interrupted":{"value":{"question":"publish?"}}}"#),
+    (5, r#"{"Resumed":{"value":true}}"#),
+    (
+        6,
+        r#"{"StateUpdated":{"step_id":"publish","payload":{"count":2}}}"#,
+    ),
+    (7, r#""Completed""#),
+];
+
+/// Checks that `store`, holding [V1_EVENT_ROWS] for `run_id` as version 1 rows, replays
+/// them and reports their version, and that events appended later are current.
+#[cfg(test)]
+pub(crate) fn check_v1_rows_replay(store: Box<dyn crate::kernel::EventStore>, run_id: &RunId) {
+    use crate::kernel::execution_log::scan_execution_log;
+    use crate::kernel::{KernelState, ReplayCursor, StateUpdatedOnlyReducer};
+
+    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Count {
+        count: u32,
+    }
+    impl KernelState for Count {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    let events = store.scan(run_id, 1).unwrap();
+    assert_eq!(events.len(), V1_EVENT_ROWS.len());
+    assert!(events.iter().all(|e| e.version == 1));
+    assert!(matches!(&events[4].event, Event::Resumed { value } if value == true));
+
+    let log = scan_execution_log(store.as_ref(), run_id, 1).unwrap();
+    assert!(log.iter().all(|entry| entry.event_version == 1));
+    assert_eq!(log[5].step_id.as_deref(), Some("publish"));
+
+    store
+        .append(
+            run_id,
+            &[Event::StateUpdated {
+                step_id: Some("revise".into()),
+                payload: serde_json::json!({ "count": 3 }),
+                state_hash: None,
+                merge_report: None,
+            }],
+        )
+        .unwrap();
+    let log = scan_execution_log(store.as_ref(), run_id, 7).unwrap();
+    let versions: Vec<u32> = log.iter().map(|entry| entry.event_version).collect();
+    assert_eq!(versions, [1, CURRENT_EVENT_VERSION]);
+
+    let cursor = ReplayCursor::<Count> {
+        events: store,
+        snaps: None,
+        reducer: Box::new(StateUpdatedOnlyReducer),
+    };
+    assert_eq!(
+        cursor.replay(run_id, Count::default()).unwrap(),
+        Count { count: 3 }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrations_chain_to_the_current_version() {
+        let run_id: RunId = "migrate".into();
+        let current = EventMigrator::new()
+            .decode(&run_id, 1, CURRENT_EVENT_VERSION, json!("Completed"))
+            .unwrap();
+        assert!(matches!(current.event, Event::Completed));
+        assert_eq!(current.version, CURRENT_EVENT_VERSION);
+
+        // A version 0 that named the interrupt payload `payload`
+        let migrator = EventMigrator::new().register(0, |mut json| {
+            if let Some(interrupted) = json.get_mut("Interrupted") {
+                let value = interrupted
+                    .as_object_mut()
+                    .and_then(|o| o.remove("payload"))
+                    .ok_or("Interrupted without payload")?;
+                interrupted["value"] = value;
+            }
+            Ok(json)
+        });
+        let migrated = migrator
+            .decode(&run_id, 2, 0, json!({"Interrupted": {"payload": 7}}))
+            .unwrap();
+        assert!(matches!(migrated.event, Event::Interrupted { value } if value == 7));
+        assert_eq!(migrated.version, 0);
+
+        let err = migrator
+            .decode(&run_id, 3, 0, json!({"Interrupted": {}}))
+            .unwrap_err();
+        assert!(err.to_string().contains("from version 0"), "{}", err);
+    }
+
+    #[test]
+    fn versions_without_a_path_are_rejected_by_number() {
+        let run_id: RunId = "unknown-version".into();
+        for (migrator, version) in [
+            (EventMigrator::new(), 0),
+            (EventMigrator::new(), CURRENT_EVENT_VERSION + 1),
+            (EventMigrator::empty(), 1),
+        ] {
+            assert!(!migrator.supports(version));
+            let err = migrator
+                .decode(&run_id, 4, version, json!("Completed"))
+                .unwrap_err();
+            assert!(
+                matches!(err, KernelError::UnsupportedEventVersion { seq: 4, version: v, .. } if v == version),
+                "{:?}",
+                err
+            );
+            assert!(
+                err.to_string()
+                    .contains(&format!("schema version {}", version)),
+                "{}",
+                err
+            );
+        }
+    }
+}
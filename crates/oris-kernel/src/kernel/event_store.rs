@@ -3,21 +3,45 @@
 //! Append is atomic (all or nothing); scan returns events in ascending seq order.
 
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::kernel::event::{Event, EventStore, KernelError, SequencedEvent};
+use chrono::{DateTime, Utc};
+use futures::stream;
+use tokio::sync::broadcast;
+
+use crate::kernel::event::{Event, EventFilter, EventStore, KernelError, SequencedEvent};
 use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{select_runs, PageRequest, RunFilter, RunStatusKind, RunSummary};
+use crate::kernel::watch::{Cursor, EventSubscription, WatchableEventStore};
+
+/// Events each run's broadcast channel holds; subscribers further behind re-read the log
+const WATCH_CHANNEL_CAPACITY: usize = 1024;
+
+/// One run's log and when it was written to
+struct RunLog {
+    events: Vec<SequencedEvent>,
+    first_event_at: DateTime<Utc>,
+    last_event_at: DateTime<Utc>,
+}
+
+type RunLogs = Arc<RwLock<HashMap<RunId, RunLog>>>;
 
 /// In-memory event store: one log per run, seq assigned on append.
+///
+/// Appends are pushed to [subscribers](WatchableEventStore) through a broadcast channel
+/// per watched run.
 pub struct InMemoryEventStore {
     /// run_id -> ordered events (seq 1, 2, 3, ...)
-    logs: RwLock<HashMap<RunId, Vec<SequencedEvent>>>,
+    logs: RunLogs,
+    /// Channels of the runs with subscribers; locked only while `logs` is
+    watchers: Mutex<HashMap<RunId, broadcast::Sender<SequencedEvent>>>,
 }
 
 impl InMemoryEventStore {
     pub fn new() -> Self {
         Self {
-            logs: RwLock::new(HashMap::new()),
+            logs: Arc::new(RwLock::new(HashMap::new())),
+            watchers: Mutex::new(HashMap::new()),
         }
     }
 
@@ -35,30 +59,41 @@ impl Default for InMemoryEventStore {
 impl EventStore for InMemoryEventStore {
     fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
         if events.is_empty() {
-            let logs = self
-                .logs
-                .read()
-                .map_err(|e| KernelError::EventStore(e.to_string()))?;
-            let last = logs
-                .get(run_id)
-                .and_then(|l| l.last())
-                .map(|e| e.seq)
-                .unwrap_or(0);
-            return Ok(last);
+            return self.head(run_id);
         }
         let mut logs = self
             .logs
             .write()
             .map_err(|e| KernelError::EventStore(e.to_string()))?;
-        let log = logs.entry(run_id.clone()).or_default();
-        let start_seq = Self::next_seq(log);
+        let now = Utc::now();
+        let log = logs.entry(run_id.clone()).or_insert_with(|| RunLog {
+            events: Vec::new(),
+            first_event_at: now,
+            last_event_at: now,
+        });
+        log.last_event_at = now;
+        let start_seq = Self::next_seq(&log.events);
         for (i, event) in events.iter().cloned().enumerate() {
-            log.push(SequencedEvent {
-                seq: start_seq + i as Seq,
-                event,
-            });
+            log.events.push(
+                SequencedEvent::new(start_seq + i as Seq, event)
+                    .recorded_at(now.timestamp_millis()),
+            );
         }
-        Ok(*log.last().map(|e| &e.seq).unwrap())
+        // Sent under the write lock, so subscribers see appends in seq order
+        let mut watchers = self
+            .watchers
+            .lock()
+            .map_err(|e| KernelError::EventStore(e.to_string()))?;
+        if let Some(sender) = watchers.get(run_id) {
+            if sender.receiver_count() == 0 {
+                watchers.remove(run_id);
+            } else {
+                for event in &log.events[log.events.len() - events.len()..] {
+                    let _ = sender.send(event.clone());
+                }
+            }
+        }
+        Ok(*log.events.last().map(|e| &e.seq).unwrap())
     }
 
     fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
@@ -70,7 +105,12 @@ impl EventStore for InMemoryEventStore {
             Some(l) => l,
             None => return Ok(Vec::new()),
         };
-        Ok(log.iter().filter(|e| e.seq >= from).cloned().collect())
+        Ok(log
+            .events
+            .iter()
+            .filter(|e| e.seq >= from)
+            .cloned()
+            .collect())
     }
 
     fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
@@ -80,10 +120,199 @@ impl EventStore for InMemoryEventStore {
             .map_err(|e| KernelError::EventStore(e.to_string()))?;
         Ok(logs
             .get(run_id)
-            .and_then(|l| l.last())
+            .and_then(|l| l.events.last())
             .map(|e| e.seq)
             .unwrap_or(0))
     }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let logs = self
+            .logs
+            .read()
+            .map_err(|e| KernelError::EventStore(e.to_string()))?;
+        let Some(log) = logs.get(run_id) else {
+            return Ok(Vec::new());
+        };
+        let start = log.events.partition_point(|e| e.seq < from);
+        let end = log.events.partition_point(|e| e.seq <= to).max(start);
+        Ok(log.events[start..end]
+            .iter()
+            .filter(|e| filter.matches(&e.event))
+            .cloned()
+            .collect())
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let logs = self
+            .logs
+            .read()
+            .map_err(|e| KernelError::EventStore(e.to_string()))?;
+        let Some(log) = logs.get(run_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(log
+            .events
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(&e.event))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        let logs = self
+            .logs
+            .read()
+            .map_err(|e| KernelError::EventStore(e.to_string()))?;
+        let summaries = logs
+            .iter()
+            .filter_map(|(run_id, log)| {
+                let last = &log.events.last()?.event;
+                Some(RunSummary {
+                    run_id: run_id.clone(),
+                    first_event_at: log.first_event_at,
+                    last_event_at: log.last_event_at,
+                    last_event_kind: last.kind().to_string(),
+                    event_count: log.events.len() as u64,
+                    status: RunStatusKind::from_last_event(last),
+                })
+            })
+            .collect();
+        Ok(select_runs(summaries, &filter, page))
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        let mut logs = self
+            .logs
+            .write()
+            .map_err(|e| KernelError::EventStore(e.to_string()))?;
+        let log = logs
+            .get_mut(run_id)
+            .ok_or_else(|| no_event_to_compact(run_id, up_to_seq))?;
+        let at = log.events.partition_point(|e| e.seq < up_to_seq);
+        if log.events.get(at).map(|e| e.seq) != Some(up_to_seq) {
+            return Err(no_event_to_compact(run_id, up_to_seq));
+        }
+        log.events.drain(..at);
+        log.events[0].event = marker.clone();
+        Ok(())
+    }
+}
+
+impl WatchableEventStore for InMemoryEventStore {
+    fn subscribe(&self, run_id: &RunId, from_seq: Seq) -> EventSubscription {
+        struct Watch {
+            logs: RunLogs,
+            run_id: RunId,
+            receiver: broadcast::Receiver<SequencedEvent>,
+            cursor: Cursor,
+        }
+
+        impl Watch {
+            /// Queues the stored events from the cursor on
+            fn reread(&mut self) -> Result<(), KernelError> {
+                let logs = self
+                    .logs
+                    .read()
+                    .map_err(|e| KernelError::EventStore(e.to_string()))?;
+                if let Some(log) = logs.get(&self.run_id) {
+                    let next = self
+                        .cursor
+                        .pending
+                        .back()
+                        .map_or(self.cursor.next, |e| e.seq + 1);
+                    let start = log.events.partition_point(|e| e.seq < next);
+                    self.cursor.extend(log.events[start..].iter().cloned());
+                }
+                Ok(())
+            }
+        }
+
+        // Subscribing under the read lock puts every event either in the backlog read
+        // here or on the channel, never both or neither
+        let subscribed = (|| {
+            let logs = self
+                .logs
+                .read()
+                .map_err(|e| KernelError::EventStore(e.to_string()))?;
+            let receiver = self
+                .watchers
+                .lock()
+                .map_err(|e| KernelError::EventStore(e.to_string()))?
+                .entry(run_id.clone())
+                .or_insert_with(|| broadcast::channel(WATCH_CHANNEL_CAPACITY).0)
+                .subscribe();
+            let mut cursor = Cursor::new(from_seq);
+            if let Some(log) = logs.get(run_id) {
+                let start = log.events.partition_point(|e| e.seq < cursor.next);
+                cursor.extend(log.events[start..].iter().cloned());
+            }
+            Ok(Watch {
+                logs: self.logs.clone(),
+                run_id: run_id.clone(),
+                receiver,
+                cursor,
+            })
+        })();
+        let watch = match subscribed {
+            Ok(watch) => watch,
+            Err(e) => return EventSubscription::new(stream::once(async move { Err(e) })),
+        };
+
+        EventSubscription::new(stream::unfold(Some(watch), |watch| async move {
+            let mut watch = watch?;
+            loop {
+                if let Some(event) = watch.cursor.pop() {
+                    return Some((Ok(event), Some(watch)));
+                }
+                let received = match watch.receiver.recv().await {
+                    Ok(event) => {
+                        let seq = event.seq;
+                        watch.cursor.extend([event]);
+                        if !watch.cursor.pending.is_empty() || seq < watch.cursor.next {
+                            continue;
+                        }
+                        // A gap: events went by unseen, but the log has them
+                        watch.reread()
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => watch.reread(),
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+                if let Err(e) = received {
+                    return Some((Err(e), None));
+                }
+            }
+        }))
+    }
+}
+
+/// Error for a [EventStore::replace_prefix] at a seq the run does not hold
+pub(crate) fn no_event_to_compact(run_id: &RunId, up_to_seq: Seq) -> KernelError {
+    KernelError::EventStore(format!(
+        "run '{}' has no event at seq {} to compact through",
+        run_id, up_to_seq
+    ))
 }
 
 /// Shared event store: wraps `Arc<InMemoryEventStore>` so graph and Kernel can share the same log.
@@ -101,6 +330,12 @@ impl Default for SharedEventStore {
     }
 }
 
+impl WatchableEventStore for SharedEventStore {
+    fn subscribe(&self, run_id: &RunId, from_seq: Seq) -> EventSubscription {
+        self.0.subscribe(run_id, from_seq)
+    }
+}
+
 impl EventStore for SharedEventStore {
     fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
         self.0.append(run_id, events)
@@ -113,4 +348,254 @@ impl EventStore for SharedEventStore {
     fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
         self.0.head(run_id)
     }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.0.scan_range(run_id, from, to, filter)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.0.scan_rev(run_id, limit, filter)
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.0.list_runs(filter, page)
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        self.0.replace_prefix(run_id, up_to_seq, marker)
+    }
+}
+
+/// Behaviour every [EventStore] shares; each implementation's tests run it
+#[cfg(test)]
+pub(crate) fn check_event_store_contract(store: &dyn EventStore) {
+    use crate::kernel::event::EventKind;
+
+    let step = |n: u64| Event::StateUpdated {
+        step_id: Some(format!("n{}", n)),
+        payload: serde_json::json!({ "n": n }),
+        state_hash: None,
+        merge_report: None,
+    };
+    let (a, b) = ("contract-a".to_string(), "contract-b".to_string());
+
+    // Append returns the seq of its last event; seqs start at 1 and have no gaps
+    assert_eq!(store.head(&a).unwrap(), 0);
+    assert_eq!(store.append(&a, &[]).unwrap(), 0);
+    assert_eq!(store.append(&a, &[step(1)]).unwrap(), 1);
+    assert_eq!(store.append(&a, &[step(2), step(3), step(4)]).unwrap(), 4);
+    assert_eq!(store.append(&a, &[]).unwrap(), 4);
+    assert_eq!(store.head(&a).unwrap(), 4);
+
+    // Scan returns events in order from any seq, including before the first and past the head
+    let seqs = |from| {
+        store
+            .scan(&a, from)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.seq)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(seqs(0), [1, 2, 3, 4]);
+    assert_eq!(seqs(3), [3, 4]);
+    assert!(seqs(5).is_empty());
+    let scanned = store.scan(&a, 2).unwrap();
+    assert!(matches!(
+        &scanned[0].event,
+        Event::StateUpdated { payload, .. } if payload["n"] == 2
+    ));
+
+    // Runs are isolated: each has its own sequence and log
+    assert!(store.scan(&b, 1).unwrap().is_empty());
+    assert_eq!(store.append(&b, &[step(10), Event::Completed]).unwrap(), 2);
+    assert_eq!(store.head(&a).unwrap(), 4);
+    assert_eq!(store.scan(&b, 1).unwrap().len(), 2);
+    assert_eq!(seqs(1), [1, 2, 3, 4]);
+
+    // Ranged, reverse and filtered scans; bounds outside the log give empty results
+    let range = |run: &RunId, from, to, filter: &EventFilter| {
+        store
+            .scan_range(run, from, to, filter)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.seq)
+            .collect::<Vec<_>>()
+    };
+    let rev = |run: &RunId, limit, filter: &EventFilter| {
+        store
+            .scan_rev(run, limit, filter)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.seq)
+            .collect::<Vec<_>>()
+    };
+    let all_events = EventFilter::all();
+    let completed_events = EventFilter::only([EventKind::Completed]);
+    assert_eq!(range(&a, 2, 3, &all_events), [2, 3]);
+    assert_eq!(range(&a, 0, Seq::MAX, &all_events), [1, 2, 3, 4]);
+    assert_eq!(range(&a, 3, 3, &all_events), [3]);
+    assert!(range(&a, 3, 2, &all_events).is_empty());
+    assert!(range(&a, 5, 9, &all_events).is_empty());
+    assert!(range(&a, 1, 4, &completed_events).is_empty());
+    assert!(range(&a, 1, 4, &EventFilter::only([])).is_empty());
+    assert_eq!(range(&b, 1, Seq::MAX, &completed_events), [2]);
+    assert!(range(&"contract-missing".to_string(), 1, 9, &all_events).is_empty());
+    assert_eq!(rev(&a, 2, &all_events), [4, 3]);
+    assert_eq!(rev(&a, 10, &all_events), [4, 3, 2, 1]);
+    assert!(rev(&a, 0, &all_events).is_empty());
+    assert!(rev(&a, 10, &completed_events).is_empty());
+    let state_updates = EventFilter::only([EventKind::StateUpdated, EventKind::Failed]);
+    assert_eq!(rev(&b, 5, &state_updates), [1]);
+    let last = store.scan_rev(&b, 1, &all_events).unwrap();
+    assert!(matches!(last[0].event, Event::Completed));
+    let first = store.scan_range(&a, 1, 1, &all_events).unwrap();
+    assert!(matches!(
+        &first[0].event,
+        Event::StateUpdated { payload, .. } if payload["n"] == 1
+    ));
+
+    // Listing: oldest run first, one summary per run, status from the last event
+    assert!(store.run_exists(&a).unwrap());
+    assert!(!store.run_exists(&"contract-missing".to_string()).unwrap());
+    let all = store
+        .list_runs(RunFilter::default(), PageRequest::default())
+        .unwrap();
+    let ids: Vec<_> = all.iter().map(|r| r.run_id.as_str()).collect();
+    assert_eq!(ids, ["contract-a", "contract-b"]);
+    assert_eq!(
+        (
+            all[0].event_count,
+            all[0].last_event_kind.as_str(),
+            all[0].status
+        ),
+        (4, "StateUpdated", RunStatusKind::Running)
+    );
+    assert_eq!(
+        (
+            all[1].event_count,
+            all[1].last_event_kind.as_str(),
+            all[1].status
+        ),
+        (2, "Completed", RunStatusKind::Completed)
+    );
+    assert!(all.iter().all(|r| r.first_event_at <= r.last_event_at));
+
+    let list = |filter: RunFilter, page: PageRequest| {
+        store
+            .list_runs(filter, page)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.run_id)
+            .collect::<Vec<_>>()
+    };
+    let completed = RunFilter::default().with_status(RunStatusKind::Completed);
+    assert_eq!(list(completed, PageRequest::default()), [b.clone()]);
+    let blocked = RunFilter::default().with_status(RunStatusKind::Blocked);
+    assert!(list(blocked, PageRequest::default()).is_empty());
+    assert_eq!(
+        list(RunFilter::default(), PageRequest::new(1, 5)),
+        [b.clone()]
+    );
+    assert!(list(RunFilter::default(), PageRequest::new(0, 0)).is_empty());
+    let before_a = all[0].first_event_at - chrono::Duration::milliseconds(1);
+    assert_eq!(
+        list(
+            RunFilter::default().created_after(before_a),
+            PageRequest::default()
+        )
+        .len(),
+        2
+    );
+    let after_b = RunFilter::default().created_after(all[1].first_event_at);
+    assert!(list(after_b, PageRequest::default()).is_empty());
+
+    // Compaction replaces a prefix with a marker at its last seq; the head stays put
+    let marker = Event::Compacted {
+        up_to_seq: 2,
+        snapshot_seq: 3,
+    };
+    store.replace_prefix(&a, 2, &marker).unwrap();
+    assert_eq!(seqs(1), [2, 3, 4]);
+    assert_eq!(store.head(&a).unwrap(), 4);
+    assert!(matches!(
+        store.scan(&a, 1).unwrap()[0].event,
+        Event::Compacted { up_to_seq: 2, .. }
+    ));
+    assert_eq!(store.append(&a, &[step(5)]).unwrap(), 5);
+    assert!(store.replace_prefix(&a, 1, &marker).is_err());
+    assert!(store.replace_prefix(&a, 9, &marker).is_err());
+    assert!(store
+        .replace_prefix(&"contract-missing".to_string(), 1, &marker)
+        .is_err());
+    assert_eq!(seqs(0), [2, 3, 4, 5]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Relies on the trait's default ranged and reverse scans
+    struct ScanOnly(InMemoryEventStore);
+
+    impl EventStore for ScanOnly {
+        fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+            self.0.append(run_id, events)
+        }
+
+        fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+            self.0.scan(run_id, from)
+        }
+
+        fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+            self.0.head(run_id)
+        }
+
+        fn list_runs(
+            &self,
+            filter: RunFilter,
+            page: PageRequest,
+        ) -> Result<Vec<RunSummary>, KernelError> {
+            self.0.list_runs(filter, page)
+        }
+
+        fn replace_prefix(
+            &self,
+            run_id: &RunId,
+            up_to_seq: Seq,
+            marker: &Event,
+        ) -> Result<(), KernelError> {
+            self.0.replace_prefix(run_id, up_to_seq, marker)
+        }
+    }
+
+    #[test]
+    fn in_memory_event_store_meets_the_contract() {
+        check_event_store_contract(&InMemoryEventStore::new());
+        check_event_store_contract(&SharedEventStore::new());
+    }
+
+    #[test]
+    fn default_scan_methods_meet_the_contract() {
+        check_event_store_contract(&ScanOnly(InMemoryEventStore::new()));
+    }
 }
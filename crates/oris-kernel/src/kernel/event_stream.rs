@@ -0,0 +1,413 @@
+//! Streaming a run's events to a message broker.
+//!
+//! [StreamingEventStore] wraps an event store and publishes every event it appends,
+//! keyed by run id, through an [EventPublisher] (e.g. the NATS publisher behind the
+//! `sink-nats` feature). Publishing runs on a background thread behind a bounded queue,
+//! like the [effect sinks](crate::kernel::effect_sink), so a slow or unreachable broker
+//! never fails or stalls the run: failed batches are retried with backoff, the publisher
+//! reconnects on its next attempt, and what overflows the queue is counted as dropped in
+//! [StreamingEventStore::health]. A batch counts as delivered once the publisher reports
+//! the broker accepted it (the NATS publisher waits for JetStream to acknowledge every
+//! event); a batch that failed, or whose acknowledgement was lost, is published again, so
+//! consumers should deduplicate on (run id, seq). Delivery is at least once only for what
+//! reaches the queue: events that overflow it, and batches still failing when the store
+//! is dropped and `retries_on_close` runs out, are never published.
+
+use std::time::Duration;
+
+use crate::kernel::effect_sink::{Deliver, SinkHealth, SinkQueue, SinkQueueConfig};
+use crate::kernel::event::{
+    Event, EventFilter, EventKind, EventStore, KernelError, SequencedEvent,
+};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunSummary};
+
+/// One event as published: `payload` is the event's JSON exactly as the SQL event stores
+/// store it, so consumers of the stream and of the stores share one schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamMessage {
+    /// Message key: the run the event belongs to.
+    pub run_id: RunId,
+    pub seq: Seq,
+    /// Event kind, e.g. `StateUpdated`.
+    pub kind: EventKind,
+    /// Schema version of `payload` ([CURRENT_EVENT_VERSION](crate::kernel::CURRENT_EVENT_VERSION)).
+    pub version: u32,
+    pub payload: Vec<u8>,
+}
+
+impl StreamMessage {
+    pub fn from_event(run_id: &RunId, event: &SequencedEvent) -> Result<Self, KernelError> {
+        let payload = serde_json::to_vec(&event.event)
+            .map_err(|e| KernelError::EventStore(format!("serialize event: {}", e)))?;
+        Ok(Self {
+            run_id: run_id.clone(),
+            seq: event.seq,
+            kind: event.event.kind(),
+            version: event.version,
+            payload,
+        })
+    }
+
+    /// Id unique to the event, `<run_id>:<seq>`, for brokers that deduplicate on one.
+    pub fn message_id(&self) -> String {
+        format!("{}:{}", self.run_id, self.seq)
+    }
+}
+
+/// Client of a message broker that [StreamingEventStore] publishes through.
+///
+/// Implement it to stream to a broker without a bundled publisher, e.g. with a Kafka
+/// producer keyed by [StreamMessage::run_id].
+pub trait EventPublisher: Send + 'static {
+    /// Publishes `messages`, in order, to `topic` and returns once the broker accepted
+    /// all of them. On an error the whole batch is published again, so a publisher should
+    /// connect (or reconnect) here when it has no usable connection.
+    fn publish(&mut self, topic: &str, messages: &[StreamMessage]) -> Result<(), String>;
+}
+
+/// Options of a [StreamingEventStore].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventStreamConfig {
+    /// Topic (Kafka) or subject (NATS) the events are published to.
+    pub topic: String,
+    pub queue: SinkQueueConfig,
+    /// Delay before the first retry of a failed batch; it doubles up to `retry_max`.
+    pub retry_initial: Duration,
+    pub retry_max: Duration,
+    /// Retries of a failing batch once the store is being dropped, before it is given up.
+    pub retries_on_close: u32,
+    /// How long an append that ends or blocks the run waits for its events to be
+    /// published, so a finished run's stream is complete when the kernel returns.
+    pub completion_flush_timeout: Duration,
+}
+
+impl EventStreamConfig {
+    pub const DEFAULT_RETRY_INITIAL: Duration = Duration::from_millis(100);
+    pub const DEFAULT_RETRY_MAX: Duration = Duration::from_secs(10);
+    pub const DEFAULT_RETRIES_ON_CLOSE: u32 = 3;
+    pub const DEFAULT_COMPLETION_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            queue: SinkQueueConfig::default(),
+            retry_initial: Self::DEFAULT_RETRY_INITIAL,
+            retry_max: Self::DEFAULT_RETRY_MAX,
+            retries_on_close: Self::DEFAULT_RETRIES_ON_CLOSE,
+            completion_flush_timeout: Self::DEFAULT_COMPLETION_FLUSH_TIMEOUT,
+        }
+    }
+
+    pub fn with_queue(mut self, queue: SinkQueueConfig) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    pub fn with_retry(mut self, initial: Duration, max: Duration) -> Self {
+        self.retry_initial = initial;
+        self.retry_max = max;
+        self
+    }
+
+    pub fn with_completion_flush_timeout(mut self, timeout: Duration) -> Self {
+        self.completion_flush_timeout = timeout;
+        self
+    }
+}
+
+/// Publishes batches, retrying failures with exponential backoff
+struct Publishing<P> {
+    publisher: P,
+    config: EventStreamConfig,
+}
+
+impl<P: EventPublisher> Deliver<StreamMessage> for Publishing<P> {
+    fn deliver(&mut self, records: &[StreamMessage]) -> Result<(), String> {
+        self.publisher.publish(&self.config.topic, records)
+    }
+
+    fn retry_delay(&mut self, failures: u32, closing: bool) -> Option<Duration> {
+        if closing && failures > self.config.retries_on_close {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        Some(
+            self.config
+                .retry_initial
+                .saturating_mul(factor)
+                .min(self.config.retry_max),
+        )
+    }
+}
+
+/// Whether the driver stops after appending `event`, so the run's events so far are all
+/// it will publish for now
+fn stops_run(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Completed
+            | Event::Failed { .. }
+            | Event::Cancelled { .. }
+            | Event::Interrupted { .. }
+            | Event::BudgetExceeded { .. }
+            | Event::Paused
+    )
+}
+
+/// Decorator that publishes every event appended to the wrapped store.
+///
+/// Events are published after the wrapped store accepted them, in seq order per run.
+/// An append that ends or blocks the run (e.g. `Completed` or `Interrupted`) waits up to
+/// [EventStreamConfig::completion_flush_timeout] for the queue to drain; reads and
+/// compaction are passed through. Dropping the store publishes what is still queued,
+/// retrying a failing batch [EventStreamConfig::retries_on_close] times.
+pub struct StreamingEventStore<E: EventStore> {
+    inner: E,
+    queue: SinkQueue<StreamMessage>,
+    completion_flush_timeout: Duration,
+}
+
+impl<E: EventStore> StreamingEventStore<E> {
+    pub fn new(
+        inner: E,
+        publisher: impl EventPublisher,
+        config: EventStreamConfig,
+    ) -> Result<Self, KernelError> {
+        let completion_flush_timeout = config.completion_flush_timeout;
+        let queue = SinkQueue::start(
+            "oris-event-stream",
+            config.queue,
+            Publishing { publisher, config },
+        )?;
+        Ok(Self {
+            inner,
+            queue,
+            completion_flush_timeout,
+        })
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Publishing counters: `dropped` counts events lost to a full queue, `retried` failed
+    /// publish attempts and `failed` events given up when the store was dropped.
+    pub fn health(&self) -> SinkHealth {
+        self.queue.health()
+    }
+
+    /// Waits until every event appended so far has been published.
+    pub fn flush_published(&self) {
+        self.queue.flush();
+    }
+
+    fn publish(&self, run_id: &RunId, events: &[Event], last_seq: Seq) {
+        let first_seq = last_seq + 1 - events.len() as Seq;
+        for (event, seq) in events.iter().zip(first_seq..) {
+            let sequenced = SequencedEvent::new(seq, event.clone());
+            match StreamMessage::from_event(run_id, &sequenced) {
+                Ok(message) => {
+                    let dropped = self.queue.push(message);
+                    #[cfg(feature = "metrics")]
+                    ::metrics::counter!(crate::kernel::metrics::KERNEL_EVENT_STREAM_DROPPED_TOTAL)
+                        .increment(dropped);
+                    #[cfg(not(feature = "metrics"))]
+                    let _ = dropped;
+                }
+                Err(e) => self.queue.reject(e.to_string()),
+            }
+        }
+        if events.iter().any(stops_run) {
+            self.queue.flush_timeout(self.completion_flush_timeout);
+        }
+    }
+}
+
+impl<E: EventStore> EventStore for StreamingEventStore<E> {
+    fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+        let last_seq = self.inner.append(run_id, events)?;
+        if !events.is_empty() {
+            self.publish(run_id, events, last_seq);
+        }
+        Ok(last_seq)
+    }
+
+    fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan(run_id, from)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        self.inner.head(run_id)
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan_range(run_id, from, to, filter)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan_rev(run_id, limit, filter)
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.inner.list_runs(filter, page)
+    }
+
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        self.inner.run_exists(run_id)
+    }
+
+    fn flush(&self) -> Result<(), KernelError> {
+        self.inner.flush()
+    }
+
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.inner.flush_step(run_id)
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        self.inner.replace_prefix(run_id, up_to_seq, marker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event_store::InMemoryEventStore;
+    use crate::kernel::OverflowPolicy;
+    use std::sync::{Arc, Mutex};
+
+    /// Records what it published; fails while `down` is set
+    #[derive(Clone, Default)]
+    struct Broker {
+        published: Arc<Mutex<Vec<(String, StreamMessage)>>>,
+        down: Arc<Mutex<bool>>,
+    }
+
+    impl EventPublisher for Broker {
+        fn publish(&mut self, topic: &str, messages: &[StreamMessage]) -> Result<(), String> {
+            if *self.down.lock().unwrap() {
+                return Err("connection refused".into());
+            }
+            let mut published = self.published.lock().unwrap();
+            published.extend(messages.iter().map(|m| (topic.to_string(), m.clone())));
+            Ok(())
+        }
+    }
+
+    impl Broker {
+        fn seqs(&self) -> Vec<Seq> {
+            let published = self.published.lock().unwrap();
+            published.iter().map(|(_, m)| m.seq).collect()
+        }
+    }
+
+    fn fast_config() -> EventStreamConfig {
+        EventStreamConfig::new("oris.events")
+            .with_retry(Duration::from_millis(1), Duration::from_millis(5))
+    }
+
+    fn state_updated(n: u64) -> Event {
+        Event::StateUpdated {
+            step_id: Some(format!("s{}", n)),
+            payload: serde_json::json!({"n": n}),
+            state_hash: None,
+            merge_report: None,
+        }
+    }
+
+    #[test]
+    fn appended_events_are_published_as_stored_json_before_completion_returns() {
+        let broker = Broker::default();
+        let store =
+            StreamingEventStore::new(InMemoryEventStore::new(), broker.clone(), fast_config())
+                .unwrap();
+        let run_id: RunId = "run-stream".into();
+        store
+            .append(&run_id, &[state_updated(1), state_updated(2)])
+            .unwrap();
+        // Completion waits for the stream, so no flush is needed before checking it
+        store.append(&run_id, &[Event::Completed]).unwrap();
+
+        assert_eq!(broker.seqs(), [1, 2, 3]);
+        let published = broker.published.lock().unwrap();
+        let (topic, first) = &published[0];
+        assert_eq!(topic, "oris.events");
+        assert_eq!(first.run_id, run_id);
+        assert_eq!(first.kind, EventKind::StateUpdated);
+        assert_eq!(first.message_id(), "run-stream:1");
+        let stored = store.scan(&run_id, 1).unwrap();
+        assert_eq!(first.payload, serde_json::to_vec(&stored[0].event).unwrap());
+        assert_eq!(published[2].1.kind, EventKind::Completed);
+        assert!(store.health().is_healthy());
+    }
+
+    #[test]
+    fn an_unreachable_broker_never_fails_appends_and_is_retried_once_back() {
+        let broker = Broker::default();
+        *broker.down.lock().unwrap() = true;
+        let store = StreamingEventStore::new(
+            InMemoryEventStore::new(),
+            broker.clone(),
+            fast_config()
+                .with_queue(SinkQueueConfig::new(2, OverflowPolicy::DropOldest))
+                .with_completion_flush_timeout(Duration::from_millis(20)),
+        )
+        .unwrap();
+        let run_id: RunId = "run-outage".into();
+        for n in 1..=4 {
+            store.append(&run_id, &[state_updated(n)]).unwrap();
+        }
+        store.append(&run_id, &[Event::Completed]).unwrap();
+        assert_eq!(store.head(&run_id).unwrap(), 5);
+
+        *broker.down.lock().unwrap() = false;
+        store.flush_published();
+        let health = store.health();
+        assert!(health.retried > 0);
+        assert_eq!(health.last_error.as_deref(), Some("connection refused"));
+        assert!(health.dropped > 0);
+        assert_eq!(health.recorded, health.delivered + health.dropped);
+        // Whatever was not dropped arrives in order, ending with the completion
+        let seqs = broker.seqs();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]), "{:?}", seqs);
+        assert_eq!(seqs.last(), Some(&5));
+    }
+
+    #[test]
+    fn dropping_the_store_gives_up_on_a_broker_that_stays_down() {
+        let broker = Broker::default();
+        *broker.down.lock().unwrap() = true;
+        let store = StreamingEventStore::new(
+            InMemoryEventStore::new(),
+            broker.clone(),
+            fast_config().with_completion_flush_timeout(Duration::ZERO),
+        )
+        .unwrap();
+        store
+            .append(&"run-down".to_string(), &[Event::Completed])
+            .unwrap();
+        drop(store);
+        assert!(broker.seqs().is_empty());
+    }
+}
@@ -5,9 +5,9 @@
 //! for replay (see [crate::kernel::snapshot]).
 
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 
-use crate::kernel::event::Event;
+use crate::kernel::canonical::canonical_state_hash;
+use crate::kernel::event::{Event, EventFilter};
 use crate::kernel::identity::{RunId, Seq, StepId};
 use crate::kernel::reducer::Reducer;
 use crate::kernel::state::KernelState;
@@ -39,13 +39,18 @@ pub struct ExecutionLog {
     pub event_index: Seq,
     /// The event at this index.
     pub event: Event,
-    /// Optional hash of state after applying this event (for verification/replay).
+    /// Schema version the event was stored at, before any migration on read.
+    #[serde(default = "crate::kernel::event::current_event_version")]
+    pub event_version: u32,
+    /// Optional hash of state after applying this event (for verification/replay); see
+    /// [canonical_state_hash].
     pub state_hash: Option<[u8; 32]>,
 }
 
 impl ExecutionLog {
     /// Builds an execution log entry from a sequenced event and run id.
-    /// `state_hash` is optional (e.g. when reading from store without reducer).
+    /// `state_hash` is optional (e.g. when reading from store without reducer); when `None`,
+    /// the hash the driver recorded in a `StateUpdated` event is used, if any.
     pub fn from_sequenced(
         thread_id: RunId,
         se: &crate::kernel::event::SequencedEvent,
@@ -57,7 +62,8 @@ impl ExecutionLog {
             step_id,
             event_index: se.seq,
             event: se.event.clone(),
-            state_hash,
+            event_version: se.version,
+            state_hash: state_hash.or_else(|| recorded_state_hash(&se.event)),
         }
     }
 
@@ -67,7 +73,7 @@ impl ExecutionLog {
             seq: self.event_index,
             step_id: self.step_id.clone(),
             action_id: action_id_from_event(&self.event),
-            kind: event_kind(&self.event),
+            kind: self.event.kind().to_string(),
             timestamp_ms: None,
         }
     }
@@ -81,24 +87,28 @@ fn step_id_from_event(event: &Event) -> Option<StepId> {
     }
 }
 
-fn action_id_from_event(event: &Event) -> Option<String> {
+/// The state hash stored in a StateUpdated event, ignoring malformed values.
+fn recorded_state_hash(event: &Event) -> Option<[u8; 32]> {
     match event {
-        Event::ActionRequested { action_id, .. }
-        | Event::ActionSucceeded { action_id, .. }
-        | Event::ActionFailed { action_id, .. } => Some(action_id.clone()),
+        Event::StateUpdated {
+            state_hash: Some(hex_hash),
+            ..
+        } => {
+            let mut hash = [0u8; 32];
+            hex::decode_to_slice(hex_hash, &mut hash).ok()?;
+            Some(hash)
+        }
         _ => None,
     }
 }
 
-fn event_kind(event: &Event) -> String {
+fn action_id_from_event(event: &Event) -> Option<String> {
     match event {
-        Event::StateUpdated { .. } => "StateUpdated".into(),
-        Event::ActionRequested { .. } => "ActionRequested".into(),
-        Event::ActionSucceeded { .. } => "ActionSucceeded".into(),
-        Event::ActionFailed { .. } => "ActionFailed".into(),
-        Event::Interrupted { .. } => "Interrupted".into(),
-        Event::Resumed { .. } => "Resumed".into(),
-        Event::Completed => "Completed".into(),
+        Event::ActionRequested { action_id, .. }
+        | Event::ActionSucceeded { action_id, .. }
+        | Event::ActionFailed { action_id, .. }
+        | Event::ActionRecorded { action_id, .. } => Some(action_id.clone()),
+        _ => None,
     }
 }
 
@@ -115,6 +125,40 @@ pub fn scan_execution_log(
         .collect())
 }
 
+/// Like [scan_execution_log], for the events with `from <= seq <= to` that match `filter`
+/// only; the rest of the log is not read. Out-of-range bounds give an empty log.
+pub fn scan_execution_log_range(
+    store: &dyn crate::kernel::event::EventStore,
+    run_id: &RunId,
+    from: Seq,
+    to: Seq,
+    filter: &EventFilter,
+) -> Result<Vec<ExecutionLog>, crate::kernel::KernelError> {
+    let sequenced = store.scan_range(run_id, from, to, filter)?;
+    Ok(sequenced
+        .iter()
+        .map(|se| ExecutionLog::from_sequenced(run_id.clone(), se, None))
+        .collect())
+}
+
+/// Replays the whole run through `reducer` from `initial_state` and returns its execution
+/// log with the [canonical_state_hash] of the state after each event.
+///
+/// For a run written by a driver whose state supports hashing, the hashes of `StateUpdated`
+/// entries equal the ones recorded in the events, so comparing the two detects replay
+/// divergence.
+pub fn build_execution_log_with_hashes<S>(
+    store: &dyn crate::kernel::event::EventStore,
+    reducer: &dyn Reducer<S>,
+    run_id: &RunId,
+    initial_state: S,
+) -> Result<Vec<ExecutionLog>, crate::kernel::KernelError>
+where
+    S: KernelState + Serialize,
+{
+    scan_execution_log_with_state_hashes(store, run_id, 1, initial_state, reducer)
+}
+
 /// Reconstructs the execution log and attaches a deterministic state hash after each event.
 ///
 /// `initial_state` must represent the state immediately before `from`. Callers replaying from
@@ -137,7 +181,7 @@ where
         out.push(ExecutionLog::from_sequenced(
             run_id.clone(),
             &se,
-            Some(state_hash(&state)?),
+            Some(canonical_state_hash(&state)?),
         ));
     }
     Ok(out)
@@ -155,18 +199,10 @@ pub fn scan_execution_trace(
         .collect())
 }
 
-fn state_hash<S: Serialize>(state: &S) -> Result<[u8; 32], crate::kernel::KernelError> {
-    let canonical = serde_json::to_vec(state)
-        .map_err(|e| crate::kernel::KernelError::Driver(format!("serialize state hash: {}", e)))?;
-    let mut hasher = Sha256::new();
-    hasher.update(canonical);
-    Ok(hasher.finalize().into())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kernel::event::{EventStore, SequencedEvent};
+    use crate::kernel::event::{EventStore, SequencedEvent, CURRENT_EVENT_VERSION};
     use crate::kernel::event_store::InMemoryEventStore;
     use crate::kernel::StateUpdatedOnlyReducer;
     use serde::{Deserialize, Serialize};
@@ -191,6 +227,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::json!([1]),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -205,16 +243,49 @@ mod tests {
         assert!(matches!(log[1].event, Event::Completed));
     }
 
+    #[test]
+    fn scan_execution_log_range_reads_only_the_window() {
+        let store = InMemoryEventStore::new();
+        let run_id: RunId = "run-range".into();
+        let step = |n: u32| Event::StateUpdated {
+            step_id: Some(format!("n{}", n)),
+            payload: serde_json::json!([n]),
+            state_hash: None,
+            merge_report: None,
+        };
+        store
+            .append(&run_id, &[step(1), step(2), step(3), Event::Completed])
+            .unwrap();
+
+        let log = scan_execution_log_range(&store, &run_id, 2, 3, &EventFilter::all()).unwrap();
+        let indices: Vec<_> = log.iter().map(|e| e.event_index).collect();
+        assert_eq!(indices, [2, 3]);
+        assert_eq!(log[0].step_id.as_deref(), Some("n2"));
+
+        let completed = EventFilter::only([crate::kernel::EventKind::Completed]);
+        let log = scan_execution_log_range(&store, &run_id, 1, Seq::MAX, &completed).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].event_index, 4);
+
+        assert!(
+            scan_execution_log_range(&store, &run_id, 5, 1, &EventFilter::all())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn from_sequenced_state_updated_has_step_id() {
         let thread_id: RunId = "run-1".into();
-        let se = SequencedEvent {
-            seq: 1,
-            event: Event::StateUpdated {
+        let se = SequencedEvent::new(
+            1,
+            Event::StateUpdated {
                 step_id: Some("node-a".into()),
                 payload: serde_json::json!([1]),
+                state_hash: None,
+                merge_report: None,
             },
-        };
+        );
         let log = ExecutionLog::from_sequenced(thread_id.clone(), &se, None);
         assert_eq!(log.thread_id, thread_id);
         assert_eq!(log.step_id.as_deref(), Some("node-a"));
@@ -225,10 +296,7 @@ mod tests {
     #[test]
     fn from_sequenced_completed_has_no_step_id() {
         let thread_id: RunId = "run-2".into();
-        let se = SequencedEvent {
-            seq: 2,
-            event: Event::Completed,
-        };
+        let se = SequencedEvent::new(2, Event::Completed);
         let log = ExecutionLog::from_sequenced(thread_id.clone(), &se, None);
         assert_eq!(log.step_id, None);
         assert_eq!(log.event_index, 2);
@@ -244,6 +312,7 @@ mod tests {
                 action_id: "a1".into(),
                 payload: serde_json::json!({"tool": "demo"}),
             },
+            event_version: CURRENT_EVENT_VERSION,
             state_hash: None,
         };
 
@@ -267,6 +336,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -48,7 +48,7 @@ impl ExecutionStepInput {
             ExecutionStepInput::Resume(_) => Ok(()),
             ExecutionStepInput::Signal { name, .. } => {
                 if name.is_empty() {
-                    return Err(KernelError::Driver(
+                    return Err(KernelError::Validation(
                         "ExecutionStepInput::Signal name must be non-empty".into(),
                     ));
                 }
@@ -176,6 +176,8 @@ mod tests {
             Ok(Next::Emit(vec![Event::StateUpdated {
                 step_id: None,
                 payload: serde_json::json!({ "n": state.0 }),
+                state_hash: None,
+                merge_report: None,
             }]))
         }
     }
@@ -0,0 +1,464 @@
+//! ActionExecutorRegistry: one [ActionExecutor] that dispatches to several by action kind.
+//!
+//! Routes are keyed by [Action::kind]: an exact kind (`sleep`, `llm/openai`) or a
+//! namespace pattern ending in `/*` (`http/*` matches `http/get` and `http/v2/post`). A
+//! registry refuses a route that overlaps one registered before, so every kind has one
+//! executor, unless overlaps were allowed with
+//! [allow_overlaps](ActionExecutorRegistry::allow_overlaps); then the most specific route
+//! wins (an exact kind, else the longest namespace). Kinds no route matches go to the
+//! fallback, or fail with [ActionErrorKind::UnknownKind](crate::kernel::ActionErrorKind::UnknownKind).
+//!
+//! The registry reports the selected route through [ActionExecutor::route], so the driver
+//! passes it to the policy as [PolicyCtx::executor](crate::kernel::PolicyCtx::executor).
+
+use crate::kernel::action::{
+    Action, ActionError, ActionExecutor, ActionResult, SKIPPED_ACTION_ERROR,
+};
+use crate::kernel::identity::RunId;
+use crate::kernel::KernelError;
+
+/// A parsed route pattern; also matches the rules of
+/// [CompositePolicy::first_match](crate::kernel::CompositePolicy::first_match)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum KindPattern {
+    Exact(String),
+    /// The namespace with its trailing `/`, e.g. `http/` for `http/*`
+    Namespace(String),
+}
+
+impl KindPattern {
+    /// Fails with why `pattern` is invalid.
+    pub(crate) fn parse(pattern: &str) -> Result<Self, String> {
+        let invalid = |why: &str| format!("invalid route '{}': {}", pattern, why);
+        let parsed = match pattern.strip_suffix('*') {
+            Some(namespace) if namespace.ends_with('/') && namespace.len() > 1 => {
+                KindPattern::Namespace(namespace.to_string())
+            }
+            Some(_) => return Err(invalid("a wildcard must be a whole trailing '/*' segment")),
+            None if pattern.is_empty() => return Err(invalid("empty")),
+            None => KindPattern::Exact(pattern.to_string()),
+        };
+        if parsed.text().contains('*') {
+            return Err(invalid("only a trailing '/*' wildcard is supported"));
+        }
+        Ok(parsed)
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            KindPattern::Exact(kind) | KindPattern::Namespace(kind) => kind,
+        }
+    }
+
+    pub(crate) fn matches(&self, kind: &str) -> bool {
+        match self {
+            KindPattern::Exact(exact) => kind == exact,
+            KindPattern::Namespace(prefix) => kind.starts_with(prefix.as_str()),
+        }
+    }
+
+    /// Whether some kind matches both patterns.
+    fn overlaps(&self, other: &KindPattern) -> bool {
+        match (self, other) {
+            (KindPattern::Exact(a), KindPattern::Exact(b)) => a == b,
+            (KindPattern::Exact(kind), namespace @ KindPattern::Namespace(_))
+            | (namespace @ KindPattern::Namespace(_), KindPattern::Exact(kind)) => {
+                namespace.matches(kind)
+            }
+            (KindPattern::Namespace(a), KindPattern::Namespace(b)) => {
+                a.starts_with(b.as_str()) || b.starts_with(a.as_str())
+            }
+        }
+    }
+
+    /// Higher is more specific: exact kinds beat namespaces, longer namespaces beat shorter.
+    fn specificity(&self) -> (bool, usize) {
+        match self {
+            KindPattern::Exact(kind) => (true, kind.len()),
+            KindPattern::Namespace(prefix) => (false, prefix.len()),
+        }
+    }
+}
+
+struct Route {
+    /// The pattern as registered, reported by `route`
+    name: String,
+    pattern: KindPattern,
+    executor: Box<dyn ActionExecutor>,
+}
+
+/// Dispatches actions to registered executors by [Action::kind]; see the
+/// [module docs](self).
+///
+/// ```rust
+/// use oris_kernel::{ActionExecutor, ActionExecutorRegistry, NoopActionExecutor};
+///
+/// let exec = ActionExecutorRegistry::new()
+///     .register("http/*", NoopActionExecutor)?
+///     .register("search/*", NoopActionExecutor)?
+///     .with_fallback(NoopActionExecutor);
+/// assert!(exec.register("http/get", NoopActionExecutor).is_err());
+/// # Ok::<(), oris_kernel::KernelError>(())
+/// ```
+#[derive(Default)]
+pub struct ActionExecutorRegistry {
+    routes: Vec<Route>,
+    fallback: Option<Box<dyn ActionExecutor>>,
+    allow_overlaps: bool,
+}
+
+impl ActionExecutorRegistry {
+    /// The route name [ActionExecutor::route] reports for actions sent to the fallback.
+    pub const FALLBACK: &'static str = "*";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lets later routes overlap earlier ones; the most specific matching route wins.
+    /// Registering the same pattern twice is still an error.
+    pub fn allow_overlaps(mut self) -> Self {
+        self.allow_overlaps = true;
+        self
+    }
+
+    /// Sends actions of the kinds `pattern` matches (`kind` or `namespace/*`) to `executor`.
+    /// Fails with [KernelError::ExecutorRegistry] for an invalid pattern, one registered
+    /// already, or one overlapping an earlier route when overlaps are not allowed.
+    pub fn register(
+        mut self,
+        pattern: &str,
+        executor: impl ActionExecutor + 'static,
+    ) -> Result<Self, KernelError> {
+        let parsed = KindPattern::parse(pattern).map_err(KernelError::ExecutorRegistry)?;
+        for route in &self.routes {
+            if route.pattern == parsed {
+                return Err(KernelError::ExecutorRegistry(format!(
+                    "route '{}' is already registered",
+                    pattern
+                )));
+            }
+            if !self.allow_overlaps && route.pattern.overlaps(&parsed) {
+                return Err(KernelError::ExecutorRegistry(format!(
+                    "route '{}' overlaps route '{}'",
+                    pattern, route.name
+                )));
+            }
+        }
+        self.routes.push(Route {
+            name: pattern.to_string(),
+            pattern: parsed,
+            executor: Box::new(executor),
+        });
+        Ok(self)
+    }
+
+    /// Sends actions no route matches to `executor` instead of failing them.
+    pub fn with_fallback(mut self, executor: impl ActionExecutor + 'static) -> Self {
+        self.fallback = Some(Box::new(executor));
+        self
+    }
+
+    /// The registered patterns, in registration order.
+    pub fn routes(&self) -> impl Iterator<Item = &str> {
+        self.routes.iter().map(|route| route.name.as_str())
+    }
+
+    /// The most specific route matching `kind`
+    fn select(&self, kind: &str) -> Option<&Route> {
+        self.routes
+            .iter()
+            .filter(|route| route.pattern.matches(kind))
+            .max_by_key(|route| route.pattern.specificity())
+    }
+
+    /// The executor of the action's route, or the fallback
+    fn executor_for(&self, action: &Action) -> Result<&dyn ActionExecutor, KernelError> {
+        let kind = action.kind();
+        match (self.select(&kind), &self.fallback) {
+            (Some(route), _) => Ok(route.executor.as_ref()),
+            (None, Some(fallback)) => Ok(fallback.as_ref()),
+            (None, None) => Err(KernelError::Executor(ActionError::unknown_kind(&kind))),
+        }
+    }
+
+    /// Every executor the registry holds, the fallback last
+    fn executors(&self) -> impl Iterator<Item = &dyn ActionExecutor> {
+        self.routes
+            .iter()
+            .map(|route| route.executor.as_ref())
+            .chain(self.fallback.as_deref())
+    }
+}
+
+impl ActionExecutor for ActionExecutorRegistry {
+    /// Runs the action on its route's executor, or on the fallback; fails with an
+    /// [ActionError::unknown_kind] executor error when there is neither.
+    fn execute(&self, run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        self.executor_for(action)?.execute(run_id, action)
+    }
+
+    fn execute_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.executor_for(action)?
+            .execute_requested(run_id, action_id, action)
+    }
+
+    fn skip_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        match self.executor_for(action) {
+            Ok(executor) => executor.skip_requested(run_id, action_id, action),
+            Err(_) => Ok(ActionResult::Failure(SKIPPED_ACTION_ERROR.into())),
+        }
+    }
+
+    fn begin_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.executors()
+            .try_for_each(|executor| executor.begin_batch(run_id))
+    }
+
+    /// Ends the batch on every executor even if one of them fails.
+    fn end_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        let mut ended = Ok(());
+        for executor in self.executors() {
+            let result = executor.end_batch(run_id);
+            if ended.is_ok() {
+                ended = result;
+            }
+        }
+        ended
+    }
+
+    /// The pattern of the selected route, [FALLBACK](Self::FALLBACK) for the fallback.
+    fn route(&self, action: &Action) -> Option<String> {
+        let kind = action.kind();
+        match self.select(&kind) {
+            Some(route) => Some(route.name.clone()),
+            None => self.fallback.as_ref().map(|_| Self::FALLBACK.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::action::ActionErrorKind;
+    use crate::kernel::driver::{Kernel, RunStatus};
+    use crate::kernel::event::{Event, EventStore};
+    use crate::kernel::event_store::{InMemoryEventStore, SharedEventStore};
+    use crate::kernel::kernel_mode::KernelMode;
+    use crate::kernel::policy::AllowListPolicy;
+    use crate::kernel::step::{Next, StepFn};
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    /// Answers every action with its own name
+    struct Named(&'static str);
+
+    impl ActionExecutor for Named {
+        fn execute(&self, _run_id: &RunId, _action: &Action) -> Result<ActionResult, KernelError> {
+            Ok(ActionResult::Success(json!(self.0)))
+        }
+    }
+
+    fn tool(name: &str) -> Action {
+        Action::CallTool {
+            tool: name.into(),
+            input: Value::Null,
+            idempotency_key: None,
+        }
+    }
+
+    fn output(registry: &ActionExecutorRegistry, action: &Action) -> Value {
+        match registry.execute(&"run".to_string(), action).unwrap() {
+            ActionResult::Success(output) => output,
+            ActionResult::Failure(error) => panic!("unexpected failure: {}", error),
+        }
+    }
+
+    #[test]
+    fn dispatches_by_kind_and_falls_back_or_fails_unknown_kinds() {
+        let registry = ActionExecutorRegistry::new()
+            .register("http/*", Named("http"))
+            .unwrap()
+            .register("search/*", Named("search"))
+            .unwrap()
+            .register("llm/openai", Named("openai"))
+            .unwrap();
+        assert_eq!(output(&registry, &tool("http/get")), json!("http"));
+        assert_eq!(
+            output(&registry, &tool("search/vector/knn")),
+            json!("search")
+        );
+        let llm = Action::CallLLM {
+            provider: "openai".into(),
+            input: Value::Null,
+            idempotency_key: None,
+        };
+        assert_eq!(output(&registry, &llm), json!("openai"));
+        assert_eq!(registry.route(&tool("http/get")).as_deref(), Some("http/*"));
+
+        // "http" alone is not in the http/ namespace
+        assert_eq!(registry.route(&tool("http")), None);
+        match registry.execute(&"run".to_string(), &tool("http")) {
+            Err(KernelError::Executor(err)) => {
+                assert_eq!(err.kind, ActionErrorKind::UnknownKind);
+                assert!(err.message.contains("'http'"), "{}", err.message);
+            }
+            other => panic!("expected UnknownKind, got {:?}", other),
+        }
+
+        let registry = registry.with_fallback(Named("shell"));
+        assert_eq!(output(&registry, &tool("http")), json!("shell"));
+        assert_eq!(
+            registry.route(&tool("http")).as_deref(),
+            Some(ActionExecutorRegistry::FALLBACK)
+        );
+    }
+
+    #[test]
+    fn rejects_overlapping_routes_unless_allowed() {
+        let registry = ActionExecutorRegistry::new()
+            .register("http/*", Named("http"))
+            .unwrap();
+        for overlapping in ["http/*", "http/get", "http/internal/*"] {
+            assert!(
+                matches!(
+                    ActionExecutorRegistry::new()
+                        .register("http/*", Named("http"))
+                        .unwrap()
+                        .register(overlapping, Named("other")),
+                    Err(KernelError::ExecutorRegistry(_))
+                ),
+                "{} should overlap http/*",
+                overlapping
+            );
+        }
+        for invalid in ["", "*", "/*", "http*", "http/*/get"] {
+            assert!(
+                matches!(
+                    ActionExecutorRegistry::new().register(invalid, Named("x")),
+                    Err(KernelError::ExecutorRegistry(_))
+                ),
+                "{:?} should be invalid",
+                invalid
+            );
+        }
+
+        let registry = registry
+            .allow_overlaps()
+            .register("http/internal/*", Named("internal"))
+            .unwrap()
+            .register("http/internal/health", Named("health"))
+            .unwrap();
+        assert_eq!(
+            registry.routes().collect::<Vec<_>>(),
+            ["http/*", "http/internal/*", "http/internal/health"]
+        );
+        assert_eq!(output(&registry, &tool("http/get")), json!("http"));
+        assert_eq!(
+            output(&registry, &tool("http/internal/users")),
+            json!("internal")
+        );
+        assert_eq!(
+            output(&registry, &tool("http/internal/health")),
+            json!("health")
+        );
+        assert!(matches!(
+            registry.register("http/*", Named("again")),
+            Err(KernelError::ExecutorRegistry(_))
+        ));
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Calls(u32);
+
+    impl crate::kernel::KernelState for Calls {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    /// Calls the tools in order, then completes
+    struct CallTools(Vec<&'static str>);
+
+    impl StepFn<Calls> for CallTools {
+        fn next(&self, state: &Calls) -> Result<Next, KernelError> {
+            Ok(match self.0.get(state.0 as usize) {
+                Some(name) => Next::Do(tool(name)),
+                None => Next::Complete,
+            })
+        }
+    }
+
+    /// Counts succeeded actions
+    struct CountCalls;
+
+    impl crate::kernel::Reducer<Calls> for CountCalls {
+        fn apply(
+            &self,
+            state: &mut Calls,
+            event: &crate::kernel::SequencedEvent,
+        ) -> Result<(), KernelError> {
+            if let Event::ActionSucceeded { .. } = event.event {
+                state.0 += 1;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn policy_sees_the_selected_executor() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel = |tools: Vec<&'static str>| Kernel::<Calls> {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(CountCalls),
+            exec: Arc::new(
+                ActionExecutorRegistry::new()
+                    .register("search/*", Named("search"))
+                    .unwrap()
+                    .with_fallback(Named("shell")),
+            ),
+            step: Box::new(CallTools(tools)),
+            // Any search tool, but only the "echo" tool of the fallback
+            policy: Box::new(
+                AllowListPolicy::tools_only(["echo".to_string()])
+                    .with_executors(["search/*".to_string()]),
+            ),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+
+        let allowed = "run-executor-allowed".to_string();
+        let status = kernel(vec!["search/docs", "search/code", "echo"])
+            .run_until_blocked(&allowed, Calls(0))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let refused = "run-executor-refused".to_string();
+        let result = kernel(vec!["search/docs", "rm"]).run_until_blocked(&refused, Calls(0));
+        assert!(
+            matches!(result, Err(KernelError::Policy(ref msg)) if msg.contains("rm")),
+            "{:?}",
+            result
+        );
+        let succeeded = events
+            .scan(&refused, 1)
+            .unwrap()
+            .iter()
+            .filter(|se| matches!(se.event, Event::ActionSucceeded { .. }))
+            .count();
+        assert_eq!(succeeded, 1);
+    }
+}
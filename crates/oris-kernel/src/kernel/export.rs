@@ -0,0 +1,249 @@
+//! CloudEvents 1.0 export of kernel events.
+//!
+//! [to_cloudevent] wraps a stored event in a CloudEvents envelope (JSON format) and
+//! [from_cloudevent] turns one back into the event, so runs can be handed to event buses
+//! and ingested from other systems. The envelope shape is pinned by golden files
+//! (`tests/golden/cloudevents_run.json`); a change to it is a breaking change for
+//! consumers.
+//!
+//! | attribute         | value                                                   |
+//! |-------------------|---------------------------------------------------------|
+//! | `specversion`     | `1.0`                                                   |
+//! | `id`              | the event's seq, e.g. `"3"`                             |
+//! | `source`          | `/oris/kernel/runs/<run id, percent-encoded>`           |
+//! | `type`            | `dev.oris.kernel.<kind in snake case>`, e.g. `dev.oris.kernel.state_updated` |
+//! | `subject`         | the run id                                              |
+//! | `datacontenttype` | `application/json`, when there is data                  |
+//! | `data`            | the event's fields as stored; absent for `Completed` and `Paused` |
+//! | `orisversion`     | schema version the event was stored at (extension)      |
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::kernel::event::{
+    Event, EventFilter, EventKind, EventStore, KernelError, SequencedEvent,
+};
+use crate::kernel::identity::{RunId, Seq};
+
+/// CloudEvents version of the envelopes.
+pub const CLOUDEVENTS_SPEC_VERSION: &str = "1.0";
+/// Prefix of every exported event `type`.
+pub const CLOUDEVENT_TYPE_PREFIX: &str = "dev.oris.kernel.";
+/// Extension attribute holding the event's schema version.
+pub const CLOUDEVENT_VERSION_EXTENSION: &str = "orisversion";
+
+const SOURCE_PREFIX: &str = "/oris/kernel/runs/";
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// A CloudEvents 1.0 envelope in the JSON event format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CloudEvent {
+    pub specversion: String,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacontenttype: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Extension attributes, e.g. [CLOUDEVENT_VERSION_EXTENSION].
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// The CloudEvents `type` of an event kind, e.g. `dev.oris.kernel.state_updated`.
+pub fn cloudevent_type(kind: EventKind) -> String {
+    let mut ty = String::from(CLOUDEVENT_TYPE_PREFIX);
+    for (i, c) in kind.as_str().chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                ty.push('_');
+            }
+            ty.push(c.to_ascii_lowercase());
+        } else {
+            ty.push(c);
+        }
+    }
+    ty
+}
+
+/// The event kind a CloudEvents `type` stands for, if it is one of the kernel's.
+pub fn event_kind_of_type(event_type: &str) -> Option<EventKind> {
+    EventKind::ALL
+        .into_iter()
+        .find(|kind| cloudevent_type(*kind) == event_type)
+}
+
+/// `source` of a run's events: the run id, percent-encoded, under `/oris/kernel/runs/`
+fn run_source(run_id: &RunId) -> String {
+    let mut source = String::from(SOURCE_PREFIX);
+    for byte in run_id.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            source.push(byte as char);
+        } else {
+            source.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    source
+}
+
+/// Wraps a run's stored event in a CloudEvents envelope.
+pub fn to_cloudevent(run_id: &RunId, event: &SequencedEvent) -> CloudEvent {
+    // Events are externally tagged: `"Completed"` or `{"StateUpdated": {...}}`
+    let data = match serde_json::to_value(&event.event).expect("events serialize to JSON") {
+        Value::Object(tagged) => tagged.into_iter().next().map(|(_, fields)| fields),
+        _ => None,
+    };
+    CloudEvent {
+        specversion: CLOUDEVENTS_SPEC_VERSION.to_string(),
+        id: event.seq.to_string(),
+        source: run_source(run_id),
+        event_type: cloudevent_type(event.event.kind()),
+        subject: Some(run_id.clone()),
+        datacontenttype: data.as_ref().map(|_| JSON_CONTENT_TYPE.to_string()),
+        time: None,
+        data,
+        extensions: BTreeMap::from([(
+            CLOUDEVENT_VERSION_EXTENSION.to_string(),
+            Value::from(event.version),
+        )]),
+    }
+}
+
+fn invalid(cloudevent: &CloudEvent, reason: impl std::fmt::Display) -> KernelError {
+    KernelError::Validation(format!("cloudevent '{}': {}", cloudevent.id, reason))
+}
+
+/// Rebuilds the run id and event from an envelope made by [to_cloudevent].
+///
+/// Fails with [KernelError::Validation] for another spec version, a `type` that is not a
+/// kernel event, a non-numeric `id`, a missing `subject`, or data that does not fit the
+/// event kind. `time` and unknown extensions are ignored.
+pub fn from_cloudevent(cloudevent: &CloudEvent) -> Result<(RunId, SequencedEvent), KernelError> {
+    if cloudevent.specversion != CLOUDEVENTS_SPEC_VERSION {
+        return Err(invalid(
+            cloudevent,
+            format!("unsupported specversion '{}'", cloudevent.specversion),
+        ));
+    }
+    let kind = event_kind_of_type(&cloudevent.event_type).ok_or_else(|| {
+        invalid(
+            cloudevent,
+            format!("unknown type '{}'", cloudevent.event_type),
+        )
+    })?;
+    let seq: Seq = cloudevent
+        .id
+        .parse()
+        .map_err(|_| invalid(cloudevent, "id is not a seq"))?;
+    let run_id = cloudevent
+        .subject
+        .clone()
+        .ok_or_else(|| invalid(cloudevent, "missing subject (run id)"))?;
+    let tagged = match &cloudevent.data {
+        None | Some(Value::Null) => Value::from(kind.as_str()),
+        Some(fields) => Value::Object(serde_json::Map::from_iter([(
+            kind.as_str().to_string(),
+            fields.clone(),
+        )])),
+    };
+    let event: Event = serde_json::from_value(tagged)
+        .map_err(|e| invalid(cloudevent, format!("data is not a {} event: {}", kind, e)))?;
+    let mut sequenced = SequencedEvent::new(seq, event);
+    if let Some(version) = cloudevent.extensions.get(CLOUDEVENT_VERSION_EXTENSION) {
+        sequenced.version = version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| invalid(cloudevent, "orisversion is not a version number"))?;
+    }
+    Ok((run_id, sequenced))
+}
+
+/// Exports a run's events with `from <= seq <= to` whose kind passes `filter`, in seq
+/// order, as CloudEvents (a JSON array of them is a CloudEvents batch).
+pub fn export_range(
+    store: &dyn EventStore,
+    run_id: &RunId,
+    from: Seq,
+    to: Seq,
+    filter: &EventFilter,
+) -> Result<Vec<CloudEvent>, KernelError> {
+    Ok(store
+        .scan_range(run_id, from, to, filter)?
+        .iter()
+        .map(|event| to_cloudevent(run_id, event))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_event_kind_has_a_distinct_type_that_maps_back() {
+        assert_eq!(
+            cloudevent_type(EventKind::StateUpdated),
+            "dev.oris.kernel.state_updated"
+        );
+        assert_eq!(
+            cloudevent_type(EventKind::Completed),
+            "dev.oris.kernel.completed"
+        );
+        for kind in EventKind::ALL {
+            assert_eq!(event_kind_of_type(&cloudevent_type(kind)), Some(kind));
+        }
+        assert_eq!(event_kind_of_type("dev.oris.kernel.unknown"), None);
+    }
+
+    #[test]
+    fn run_ids_are_percent_encoded_in_the_source() {
+        let event = to_cloudevent(
+            &"tenant a/run#1".to_string(),
+            &SequencedEvent::new(1, Event::Completed),
+        );
+        assert_eq!(event.source, "/oris/kernel/runs/tenant%20a%2Frun%231");
+        assert_eq!(event.subject.as_deref(), Some("tenant a/run#1"));
+    }
+
+    #[test]
+    fn malformed_envelopes_are_rejected_with_validation_errors() {
+        let good = to_cloudevent(
+            &"run-ce".to_string(),
+            &SequencedEvent::new(
+                2,
+                Event::Failed {
+                    reason: "boom".into(),
+                    code: None,
+                },
+            ),
+        );
+        type Corrupt = fn(&mut CloudEvent);
+        let cases: [(Corrupt, &str); 4] = [
+            (|e| e.specversion = "0.3".into(), "unsupported specversion"),
+            (
+                |e| e.event_type = "com.example.other".into(),
+                "unknown type",
+            ),
+            (|e| e.id = "abc".into(), "id is not a seq"),
+            (
+                |e| e.data = Some(serde_json::json!({"code": 1})),
+                "data is not a Failed event",
+            ),
+        ];
+        for (corrupt, expected) in cases {
+            let mut event = good.clone();
+            corrupt(&mut event);
+            match from_cloudevent(&event) {
+                Err(KernelError::Validation(msg)) => assert!(msg.contains(expected), "{}", msg),
+                other => panic!("expected a validation error, got {:?}", other),
+            }
+        }
+    }
+}
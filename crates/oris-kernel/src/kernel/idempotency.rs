@@ -0,0 +1,366 @@
+//! Deduplicating action side effects by idempotency key.
+//!
+//! The driver hands every tool or LLM call to the executor with an
+//! [idempotency key](Action::idempotency_key): the step's own, or the
+//! [default](crate::kernel::default_idempotency_key) derived from the action's position in
+//! the run. [IdempotentActionExecutor] keeps the result of each key in an
+//! [ActionResultCache] and returns it instead of executing again, so a run that crashed
+//! after its action ran but before the result reached the log does not repeat the side
+//! effect when it is run again.
+//!
+//! Only results the executor returned as `Ok` are cached; an `Err` is left for the policy
+//! to retry and the next attempt executes. Cached entries may expire after a TTL, and
+//! [ActionResultCache::purge_expired] drops the expired ones.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::kernel::action::{Action, ActionExecutor, ActionResult};
+use crate::kernel::event::KernelError;
+use crate::kernel::identity::RunId;
+
+/// Stores the result of executed actions by idempotency key.
+pub trait ActionResultCache: Send + Sync {
+    /// The result stored under `key`, unless there is none or it expired.
+    fn get(&self, key: &str) -> Result<Option<ActionResult>, KernelError>;
+
+    /// Stores `result` under `key` unless a live result is stored there already, and returns
+    /// the stored one: the first result for a key wins.
+    fn put(&self, key: &str, result: &ActionResult) -> Result<ActionResult, KernelError>;
+
+    /// Removes the expired results and returns how many were removed.
+    fn purge_expired(&self) -> Result<usize, KernelError>;
+}
+
+/// In-memory [ActionResultCache]; results last as long as the process unless a TTL is set.
+#[derive(Debug, Default)]
+pub struct InMemoryActionResultCache {
+    results: RwLock<HashMap<String, (ActionResult, Instant)>>,
+    ttl: Option<Duration>,
+}
+
+impl InMemoryActionResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Results expire `ttl` after they were stored.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn is_live(&self, stored_at: Instant) -> bool {
+        self.ttl.map_or(true, |ttl| stored_at.elapsed() < ttl)
+    }
+}
+
+fn map_cache_err(err: impl std::fmt::Display) -> KernelError {
+    KernelError::ActionResultCache(format!("cache lock poisoned: {}", err))
+}
+
+impl ActionResultCache for InMemoryActionResultCache {
+    fn get(&self, key: &str) -> Result<Option<ActionResult>, KernelError> {
+        let results = self.results.read().map_err(map_cache_err)?;
+        Ok(results
+            .get(key)
+            .filter(|(_, stored_at)| self.is_live(*stored_at))
+            .map(|(result, _)| result.clone()))
+    }
+
+    fn put(&self, key: &str, result: &ActionResult) -> Result<ActionResult, KernelError> {
+        let mut results = self.results.write().map_err(map_cache_err)?;
+        match results.get(key) {
+            Some((stored, stored_at)) if self.is_live(*stored_at) => Ok(stored.clone()),
+            _ => {
+                results.insert(key.to_string(), (result.clone(), Instant::now()));
+                Ok(result.clone())
+            }
+        }
+    }
+
+    fn purge_expired(&self) -> Result<usize, KernelError> {
+        let mut results = self.results.write().map_err(map_cache_err)?;
+        let before = results.len();
+        results.retain(|_, (_, stored_at)| self.is_live(*stored_at));
+        Ok(before - results.len())
+    }
+}
+
+/// Executes each keyed action at most once per key and answers repeats from `cache`; see
+/// the [module docs](self). Actions without a key (`Sleep`, `WaitSignal`, or calls run
+/// outside the driver) always execute.
+pub struct IdempotentActionExecutor<E: ActionExecutor> {
+    inner: E,
+    cache: Arc<dyn ActionResultCache>,
+}
+
+impl<E: ActionExecutor> IdempotentActionExecutor<E> {
+    pub fn new(inner: E, cache: Arc<dyn ActionResultCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    /// The cached result of `action`, or the result of `execute`, cached if it is `Ok`.
+    fn once(
+        &self,
+        action: &Action,
+        execute: impl FnOnce() -> Result<ActionResult, KernelError>,
+    ) -> Result<ActionResult, KernelError> {
+        let Some(key) = action.idempotency_key() else {
+            return execute();
+        };
+        if let Some(cached) = self.cache.get(key)? {
+            return Ok(cached);
+        }
+        let result = execute()?;
+        self.cache.put(key, &result)
+    }
+}
+
+impl<E: ActionExecutor> ActionExecutor for IdempotentActionExecutor<E> {
+    fn execute(&self, run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        self.once(action, || self.inner.execute(run_id, action))
+    }
+
+    fn route(&self, action: &Action) -> Option<String> {
+        self.inner.route(action)
+    }
+
+    fn execute_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        self.once(action, || {
+            self.inner.execute_requested(run_id, action_id, action)
+        })
+    }
+
+    /// A skipped action that ran before the crash keeps its result.
+    fn skip_requested(
+        &self,
+        run_id: &RunId,
+        action_id: &str,
+        action: &Action,
+    ) -> Result<ActionResult, KernelError> {
+        if let Some(cached) = match action.idempotency_key() {
+            Some(key) => self.cache.get(key)?,
+            None => None,
+        } {
+            return Ok(cached);
+        }
+        self.inner.skip_requested(run_id, action_id, action)
+    }
+
+    fn begin_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.inner.begin_batch(run_id)
+    }
+
+    fn end_batch(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.inner.end_batch(run_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    use crate::kernel::driver::{Kernel, RunStatus};
+    use crate::kernel::event::{Event, EventStore, SequencedEvent};
+    use crate::kernel::event_store::InMemoryEventStore;
+    use crate::kernel::identity::Seq;
+    use crate::kernel::kernel_mode::KernelMode;
+    use crate::kernel::reducer::Reducer;
+    use crate::kernel::state::KernelState;
+    use crate::kernel::step::{Next, StepFn};
+    use crate::kernel::stubs::AllowAllPolicy;
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    struct Charges(Vec<Value>);
+    impl KernelState for Charges {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    struct ChargesReducer;
+    impl Reducer<Charges> for ChargesReducer {
+        fn apply(&self, state: &mut Charges, event: &SequencedEvent) -> Result<(), KernelError> {
+            if let Event::ActionSucceeded { output, .. } = &event.event {
+                state.0.push(output.clone());
+            }
+            Ok(())
+        }
+    }
+
+    /// Charges twice, then completes.
+    struct ChargeTwice;
+    impl StepFn<Charges> for ChargeTwice {
+        fn next(&self, state: &Charges) -> Result<Next, KernelError> {
+            Ok(match state.0.len() {
+                n @ 0..=1 => Next::Do(Action::CallTool {
+                    tool: "charge".into(),
+                    input: json!(n),
+                    idempotency_key: None,
+                }),
+                _ => Next::Complete,
+            })
+        }
+    }
+
+    /// Answers with a receipt numbered by its call count, remembering the keys it saw.
+    #[derive(Default)]
+    struct Payments {
+        calls: AtomicUsize,
+        keys: Mutex<Vec<Option<String>>>,
+    }
+    impl ActionExecutor for Arc<Payments> {
+        fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            self.keys
+                .lock()
+                .unwrap()
+                .push(action.idempotency_key().map(str::to_string));
+            Ok(ActionResult::Success(json!({ "receipt": call })))
+        }
+    }
+
+    /// Loses every append of an action result while `crash` is set, like a process that
+    /// died between executing an action and logging what it returned.
+    struct CrashBeforeResult {
+        inner: InMemoryEventStore,
+        crash: AtomicBool,
+    }
+    impl EventStore for Arc<CrashBeforeResult> {
+        fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+            let is_result = |e: &Event| matches!(e, Event::ActionSucceeded { .. });
+            if self.crash.load(Ordering::SeqCst) && events.iter().any(is_result) {
+                return Err(KernelError::EventStore("crashed".into()));
+            }
+            self.inner.append(run_id, events)
+        }
+
+        fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+            self.inner.scan(run_id, from)
+        }
+
+        fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+            self.inner.head(run_id)
+        }
+    }
+
+    fn kernel(
+        events: &Arc<CrashBeforeResult>,
+        payments: &Arc<Payments>,
+        cache: &Arc<InMemoryActionResultCache>,
+    ) -> Kernel<Charges> {
+        Kernel {
+            events: Box::new(events.clone()),
+            snaps: None,
+            reducer: Box::new(ChargesReducer),
+            exec: Arc::new(IdempotentActionExecutor::new(
+                payments.clone(),
+                cache.clone(),
+            )),
+            step: Box::new(ChargeTwice),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        }
+    }
+
+    #[test]
+    fn a_run_restarted_after_a_crash_reuses_the_executed_action_result() {
+        let events = Arc::new(CrashBeforeResult {
+            inner: InMemoryEventStore::new(),
+            crash: AtomicBool::new(true),
+        });
+        let payments = Arc::new(Payments::default());
+        let cache = Arc::new(InMemoryActionResultCache::new());
+        let run_id = "checkout".to_string();
+
+        let crashed =
+            kernel(&events, &payments, &cache).run_until_blocked(&run_id, Charges::default());
+        assert!(matches!(crashed, Err(KernelError::EventStore(_))));
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 1);
+
+        events.crash.store(false, Ordering::SeqCst);
+        let status = kernel(&events, &payments, &cache)
+            .run_until_blocked(&run_id, Charges::default())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        // The first charge ran once; its restarted request got the cached receipt
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 2);
+        let state = kernel(&events, &payments, &cache)
+            .replay(&run_id, Charges::default())
+            .unwrap();
+        assert_eq!(state.0, vec![json!({"receipt": 0}), json!({"receipt": 1})]);
+        assert_eq!(
+            *payments.keys.lock().unwrap(),
+            vec![
+                Some("checkout::0".to_string()),
+                Some("checkout::1".to_string())
+            ]
+        );
+        // The log keeps the actions as the step requested them
+        let logged = events.inner.scan(&run_id, 1).unwrap();
+        assert!(logged.iter().all(|se| match &se.event {
+            Event::ActionRequested { payload, .. } =>
+                payload["CallTool"].get("idempotency_key").is_none(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn repeated_keys_return_the_first_result_and_unkeyed_actions_always_execute() {
+        let payments = Arc::new(Payments::default());
+        let exec = IdempotentActionExecutor::new(
+            payments.clone(),
+            Arc::new(InMemoryActionResultCache::new()),
+        );
+        let run_id = "r".to_string();
+        let unkeyed = Action::CallTool {
+            tool: "charge".into(),
+            input: json!(1),
+            idempotency_key: None,
+        };
+        let keyed = unkeyed.clone().with_idempotency_key("order-7");
+
+        let first = exec.execute_requested(&run_id, "r-1", &keyed).unwrap();
+        let again = exec.execute(&run_id, &keyed).unwrap();
+        let skipped = exec.skip_requested(&run_id, "r-2", &keyed).unwrap();
+        for result in [again, skipped] {
+            assert!(matches!(
+                (&first, &result),
+                (ActionResult::Success(a), ActionResult::Success(b)) if a == b
+            ));
+        }
+        exec.execute(&run_id, &unkeyed).unwrap();
+        exec.execute(&run_id, &unkeyed).unwrap();
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn expired_results_execute_again_and_are_purged() {
+        let cache = InMemoryActionResultCache::new().with_ttl(Duration::ZERO);
+        cache.put("k", &ActionResult::Success(json!(1))).unwrap();
+        assert!(cache.get("k").unwrap().is_none());
+        let stored = cache.put("k", &ActionResult::Success(json!(2))).unwrap();
+        assert!(matches!(stored, ActionResult::Success(v) if v == json!(2)));
+        assert_eq!(cache.purge_expired().unwrap(), 1);
+
+        let lasting = InMemoryActionResultCache::new().with_ttl(Duration::from_secs(3600));
+        lasting.put("k", &ActionResult::Success(json!(1))).unwrap();
+        let stored = lasting.put("k", &ActionResult::Success(json!(2))).unwrap();
+        assert!(matches!(stored, ActionResult::Success(v) if v == json!(1)));
+        assert_eq!(lasting.purge_expired().unwrap(), 0);
+    }
+}
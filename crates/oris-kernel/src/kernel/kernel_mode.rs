@@ -17,12 +17,24 @@ pub enum KernelMode {
     Replay,
     /// Verifying: same as Replay but also check event stream hash matches expected.
     Verify,
+    /// Verifying an existing run by replaying it and comparing recomputed state hashes with
+    /// the recorded ones (see [Kernel::verify_replay](crate::kernel::Kernel::verify_replay)).
+    /// The run is never advanced: running or resuming it fails.
+    VerifyReplay,
+    /// Rehearsing a run: actions are authorized as usual but never executed. The driver
+    /// records [Policy::simulate](crate::kernel::Policy::simulate)'s result instead, with
+    /// `dry_run: true` on the result event, so the log, snapshots and timeline show what the
+    /// run would do.
+    DryRun,
 }
 
 impl KernelMode {
     /// Returns true if clock access, hardware randomness, and thread spawn must be trapped.
     pub fn traps_nondeterminism(self) -> bool {
-        matches!(self, KernelMode::Replay | KernelMode::Verify)
+        matches!(
+            self,
+            KernelMode::Replay | KernelMode::Verify | KernelMode::VerifyReplay
+        )
     }
 }
 
@@ -72,5 +84,6 @@ mod tests {
     #[test]
     fn verify_traps() {
         assert!(KernelMode::Verify.traps_nondeterminism());
+        assert!(KernelMode::VerifyReplay.traps_nondeterminism());
     }
 }
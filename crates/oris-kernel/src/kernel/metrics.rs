@@ -0,0 +1,41 @@
+//! Kernel metrics (feature `metrics`).
+//!
+//! Recorded through the [`metrics`](https://docs.rs/metrics) facade; nothing is collected
+//! until the application installs a recorder (e.g. a Prometheus exporter). The names are
+//! stable so dashboards and alerts can rely on them.
+
+/// Counter: events appended to a run's event log by the kernel driver
+pub const KERNEL_EVENTS_APPENDED_TOTAL: &str = "oris_kernel_events_appended_total";
+
+/// Counter: events a [StreamingEventStore](crate::kernel::StreamingEventStore) dropped
+/// because its publish queue was full
+pub const KERNEL_EVENT_STREAM_DROPPED_TOTAL: &str = "oris_kernel_event_stream_dropped_total";
+
+/// Counter: snapshots the driver failed to save under its
+/// [SnapshotPolicy](crate::kernel::SnapshotPolicy); the runs went on without them
+pub const KERNEL_SNAPSHOT_FAILURES_TOTAL: &str = "oris_kernel_snapshot_failures_total";
+
+/// Counter: stored snapshots the driver rejected because they failed their integrity
+/// check; the runs were replayed from their full logs instead
+pub const KERNEL_SNAPSHOT_INTEGRITY_FAILURES_TOTAL: &str =
+    "oris_kernel_snapshot_integrity_failures_total";
+
+/// Register the descriptions of the kernel metrics with the installed recorder
+pub fn describe() {
+    ::metrics::describe_counter!(
+        KERNEL_EVENTS_APPENDED_TOTAL,
+        "Events appended to run event logs by the kernel driver."
+    );
+    ::metrics::describe_counter!(
+        KERNEL_EVENT_STREAM_DROPPED_TOTAL,
+        "Events dropped by streaming event stores because their publish queue was full."
+    );
+    ::metrics::describe_counter!(
+        KERNEL_SNAPSHOT_FAILURES_TOTAL,
+        "Automatic snapshots the kernel driver failed to save."
+    );
+    ::metrics::describe_counter!(
+        KERNEL_SNAPSHOT_INTEGRITY_FAILURES_TOTAL,
+        "Stored snapshots the kernel driver rejected as corrupt and replayed around."
+    );
+}
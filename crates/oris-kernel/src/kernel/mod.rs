@@ -5,28 +5,52 @@
 //! Graph and Agent compile down to StepFn; tools implement ActionExecutor.
 
 pub mod action;
+pub mod action_replay;
+pub mod buffered_store;
+pub mod canonical;
+pub mod compaction;
 pub mod determinism_guard;
 pub mod driver;
+pub mod effect_sink;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod event;
+pub mod event_migration;
 pub mod event_store;
+pub mod event_stream;
 pub mod evidence_bundle;
 pub mod execution_log;
 pub mod execution_step;
 pub mod execution_suspension;
+pub mod executor_registry;
+pub mod export;
+pub mod idempotency;
 pub mod identity;
 pub mod interrupt;
 pub mod interrupt_resolver;
 pub mod kernel_interrupt;
 pub mod kernel_mode;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "sink-nats")]
+pub mod nats_publisher;
+pub mod ops;
+#[cfg(feature = "otel")]
+pub mod otel;
 pub mod policy;
 #[cfg(feature = "kernel-postgres")]
+pub mod postgres_config;
+#[cfg(feature = "kernel-postgres")]
 pub mod postgres_store;
 pub mod reducer;
 pub mod replay_cursor;
 pub mod replay_resume;
 pub mod replay_verifier;
+pub mod risk_tier;
+pub mod run_control;
 pub mod runner;
 pub mod runtime_effect;
+pub mod shadow;
 pub mod snapshot;
 #[cfg(feature = "sqlite-persistence")]
 pub mod sqlite_store;
@@ -35,18 +59,52 @@ pub mod step;
 pub mod stubs;
 pub mod timeline;
 pub mod timeline_fork;
+pub mod watch;
 
-pub use action::{Action, ActionError, ActionErrorKind, ActionExecutor, ActionResult};
+pub use action::{
+    default_idempotency_key, output_metadata, Action, ActionError, ActionErrorKind, ActionExecutor,
+    ActionResult, ACTION_METADATA_KEY, CANCELLED_ACTION_ERROR, METADATA_COST_CENTS,
+    METADATA_TOKENS, SKIPPED_ACTION_ERROR,
+};
+pub use action_replay::{RecordedOutcome, RecordingActionExecutor, ReplayActionExecutor};
+pub use buffered_store::{BufferConfig, BufferedEventStore};
+pub use compaction::{
+    compact_run, scan_with_archive, CompactionReport, EventArchive, EventStoreArchive,
+    FileEventArchive, InMemoryEventArchive,
+};
 pub use determinism_guard::{
     compute_event_stream_hash, event_stream_hash, verify_event_stream_hash, DeterminismGuard,
 };
-pub use driver::{BlockedInfo, Kernel, RunStatus, Signal};
-pub use event::{Event, EventStore, KernelError, SequencedEvent};
+pub use driver::{BlockedInfo, Kernel, RunStatus, Signal, StepOutcome};
+pub use effect_sink::{
+    ChannelEffectSink, EffectRecord, FanOutEffectSink, FsyncPolicy, JsonlFileConfig,
+    JsonlFileEffectSink, OverflowPolicy, SinkHealth, SinkQueueConfig,
+};
+#[cfg(feature = "encryption")]
+pub use encryption::{
+    EncryptedEventStore, EncryptedSnapshotStore, EncryptionKey, EnvKeyProvider, KeyProvider,
+    SealedState, StaticKeyProvider,
+};
+pub use event::{
+    Event, EventFilter, EventKind, EventStore, KernelError, PolicyDecisionKind, SequencedEvent,
+    CURRENT_EVENT_VERSION,
+};
+pub use event_migration::{EventMigration, EventMigrator};
 pub use event_store::{InMemoryEventStore, SharedEventStore};
+pub use event_stream::{EventPublisher, EventStreamConfig, StreamMessage, StreamingEventStore};
 pub use evidence_bundle::{EvidenceBundle, EvidenceBundleBuilder, TestOutcome};
-pub use execution_log::{scan_execution_log, scan_execution_trace, ExecutionLog, KernelTraceEvent};
+pub use execution_log::{
+    build_execution_log_with_hashes, scan_execution_log, scan_execution_log_range,
+    scan_execution_trace, ExecutionLog, KernelTraceEvent,
+};
 pub use execution_step::{ExecutionStep, ExecutionStepInput, StepResult};
 pub use execution_suspension::{ExecutionSuspension, ExecutionSuspensionState, SuspensionError};
+pub use executor_registry::ActionExecutorRegistry;
+pub use export::{
+    cloudevent_type, event_kind_of_type, export_range, from_cloudevent, to_cloudevent, CloudEvent,
+    CLOUDEVENTS_SPEC_VERSION, CLOUDEVENT_TYPE_PREFIX, CLOUDEVENT_VERSION_EXTENSION,
+};
+pub use idempotency::{ActionResultCache, IdempotentActionExecutor, InMemoryActionResultCache};
 pub use identity::{RunId, Seq, StepId};
 pub use interrupt::{Interrupt, InterruptError, InterruptId, InterruptKind, InterruptStore};
 pub use interrupt_resolver::{
@@ -57,22 +115,47 @@ pub use kernel_interrupt::{
     KernelInterruptId, KernelInterruptKind, KernelInterruptStatus, KernelInterruptStore,
 };
 pub use kernel_mode::KernelMode;
+pub use ops::{PageRequest, RunFilter, RunPage, RunStatusKind, RunSummary};
 pub use policy::{
-    AllowListPolicy, BudgetRules, Policy, PolicyCtx, RetryDecision, RetryWithBackoffPolicy,
+    simulated_output, AllowListPolicy, BudgetExceeded, BudgetRules, BudgetUsage, CompositePolicy,
+    JitterSource, Policy, PolicyCtx, PolicyVerdict, RandomJitter, RetryDecision,
+    RetryWithBackoffPolicy, StubbedPolicy, TimeoutPolicy, BUDGET_ACTIONS_PER_KIND_EXCEEDED,
+    BUDGET_COST_EXCEEDED, BUDGET_LLM_TOKENS_EXCEEDED, BUDGET_TOKENS_EXCEEDED,
+    BUDGET_TOOL_CALLS_EXCEEDED, DEFAULT_MAX_PARALLEL_ACTIONS, POLICY_AUTHORIZED, POLICY_DENIED,
 };
 #[cfg(feature = "kernel-postgres")]
+pub use postgres_config::PostgresRepositoryConfig;
+#[cfg(feature = "kernel-postgres")]
 pub use postgres_store::{PostgresEventStore, PostgresSnapshotStore};
-pub use reducer::{Reducer, StateUpdatedOnlyReducer};
+pub use reducer::{
+    merge_value, MergeConflict, MergeReport, MergeRules, MergeStrategy, MergingReducer, Reducer,
+    StateUpdatedOnlyReducer,
+};
 pub use replay_cursor::{ReplayCursor, ReplayStepIter};
 pub use replay_resume::{ReplayResume, ResumeDecision, ResumeResult};
-pub use replay_verifier::{ReplayVerifier, VerificationFailure, VerificationResult, VerifyConfig};
-pub use runner::KernelRunner;
+pub use replay_verifier::{
+    verify_replay, ReplayDivergence, ReplayVerifier, VerificationFailure, VerificationReport,
+    VerificationResult, VerifyConfig,
+};
+pub use risk_tier::{RiskTier, RiskTierRegistry, TieredPolicy};
+pub use run_control::RunControl;
+pub use runner::{KernelHandle, KernelRunner, RunManyProgress};
 pub use runtime_effect::{EffectSink, NoopEffectSink, RuntimeEffect};
-pub use snapshot::{InMemorySnapshotStore, Snapshot, SnapshotStore};
+pub use shadow::{
+    compare_runs, json_diff, run_shadow, ActionDifference, JsonChange, ShadowReport, StateChange,
+    StateChangeKind,
+};
+pub use snapshot::{
+    InMemorySnapshotStore, Snapshot, SnapshotMismatch, SnapshotPolicy, SnapshotStore,
+};
 #[cfg(feature = "sqlite-persistence")]
-pub use sqlite_store::{SqliteEventStore, SqliteSnapshotStore};
+pub use sqlite_store::{SqliteActionResultCache, SqliteEventStore, SqliteSnapshotStore};
 pub use state::KernelState;
-pub use step::{InterruptInfo, Next, StepFn};
+pub use step::{ActionBatch, InterruptInfo, Next, StepFn};
 pub use stubs::{AllowAllPolicy, NoopActionExecutor, NoopStepFn};
-pub use timeline::{run_timeline, RunStatusSummary, RunTimeline, TimelineEntry};
+pub use timeline::{
+    run_timeline, run_timeline_range, summarize_runs, BlockedRun, FleetSummary, ReasonCount,
+    RunStatusSummary, RunTimeline, TimelineEntry, TimelineSummary, FLEET_SUMMARY_TOP_N,
+};
 pub use timeline_fork::{ForkResult, TimelineFork, TimelineForker};
+pub use watch::{EventSubscription, PollingEventStore, WatchableEventStore, DEFAULT_POLL_INTERVAL};
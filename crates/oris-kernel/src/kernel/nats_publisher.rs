@@ -0,0 +1,514 @@
+//! NATS JetStream [EventPublisher] (feature `sink-nats`).
+//!
+//! Speaks the NATS client protocol over a plain TCP connection, so it needs no client
+//! library; `tls://` URLs and servers that require TLS are rejected. Each event is
+//! published with `HPUB` to the configured subject, with headers carrying its run id,
+//! seq, kind and schema version, and `Nats-Msg-Id: <run_id>:<seq>` so the JetStream stream
+//! drops the duplicates a retried batch can cause; servers without header support (before
+//! NATS 2.2) are rejected rather than losing that id. Every event names a reply inbox,
+//! and a batch counts as accepted only once the stream capturing the subject has
+//! acknowledged each of its events. A subject no stream captures, or an event the stream
+//! rejects, fails the batch.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::kernel::event_stream::{EventPublisher, StreamMessage};
+
+/// Open connection: reads server lines, writes client commands
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    /// Prefix of the reply subjects the publisher is subscribed to, ending in `.`
+    inbox: String,
+}
+
+/// Message the server delivered to the publisher's inbox
+struct Reply {
+    subject: String,
+    /// Status line of its headers, e.g. `NATS/1.0 503`
+    status: Option<String>,
+    payload: Vec<u8>,
+}
+
+impl Connection {
+    fn read_line(&mut self) -> Result<String, String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err("connection closed by server".into()),
+            Ok(_) => Ok(line.trim_end().to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Reads until `PONG`, answering the server's own pings
+    fn await_pong(&mut self) -> Result<(), String> {
+        loop {
+            let line = self.read_line()?;
+            match line.split_whitespace().next().unwrap_or_default() {
+                "PONG" => return Ok(()),
+                "PING" => self.write(b"PONG\r\n")?,
+                "-ERR" => return Err(format!("server error: {}", line[4..].trim())),
+                // +OK, INFO updates
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads until a `MSG` or `HMSG` arrives, answering the server's own pings
+    fn read_reply(&mut self) -> Result<Reply, String> {
+        loop {
+            let line = self.read_line()?;
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (header_len, total) = match parts.first().copied().unwrap_or_default() {
+                "MSG" if parts.len() >= 4 => (0, parts[parts.len() - 1]),
+                "HMSG" if parts.len() >= 5 => (
+                    parts[parts.len() - 2]
+                        .parse()
+                        .map_err(|_| format!("malformed server message '{}'", line))?,
+                    parts[parts.len() - 1],
+                ),
+                "PING" => {
+                    self.write(b"PONG\r\n")?;
+                    self.writer.flush().map_err(|e| e.to_string())?;
+                    continue;
+                }
+                "-ERR" => return Err(format!("server error: {}", line[4..].trim())),
+                // +OK, PONG, INFO updates
+                _ => continue,
+            };
+            let total: usize = total
+                .parse()
+                .map_err(|_| format!("malformed server message '{}'", line))?;
+            if header_len > total {
+                return Err(format!("malformed server message '{}'", line));
+            }
+            let mut body = vec![0; total + 2];
+            self.reader
+                .read_exact(&mut body)
+                .map_err(|e| e.to_string())?;
+            body.truncate(total);
+            let status = (header_len > 0).then(|| {
+                String::from_utf8_lossy(&body[..header_len])
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            });
+            return Ok(Reply {
+                subject: parts[1].to_string(),
+                status,
+                payload: body.split_off(header_len),
+            });
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.writer.write_all(bytes).map_err(|e| e.to_string())
+    }
+}
+
+impl Reply {
+    /// Checks the reply is a JetStream acknowledgement that stored the event
+    fn pub_ack(&self) -> Result<(), String> {
+        if let Some(status) = &self.status {
+            if status.split_whitespace().nth(1) == Some("503") {
+                return Err("no JetStream stream captures the subject".into());
+            }
+            return Err(format!("unexpected reply '{}'", status));
+        }
+        let ack: serde_json::Value = serde_json::from_slice(&self.payload)
+            .map_err(|e| format!("parse JetStream acknowledgement: {}", e))?;
+        if let Some(error) = ack.get("error") {
+            return Err(format!(
+                "JetStream rejected the event: {}",
+                error["description"].as_str().unwrap_or("unknown error")
+            ));
+        }
+        if ack["stream"].is_string() && ack["seq"].is_u64() {
+            Ok(())
+        } else {
+            Err(format!(
+                "unexpected JetStream acknowledgement '{}'",
+                String::from_utf8_lossy(&self.payload)
+            ))
+        }
+    }
+}
+
+/// Publishes events to a NATS JetStream stream, reconnecting on the first publish after a
+/// failure.
+pub struct NatsPublisher {
+    address: String,
+    name: String,
+    token: Option<String>,
+    timeout: Duration,
+    connection: Option<Connection>,
+}
+
+impl NatsPublisher {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Publisher for the server at `url` (`nats://host:port` or `host:port`); it connects
+    /// on the first publish.
+    pub fn new(url: &str) -> Self {
+        Self {
+            address: url.trim_start_matches("nats://").to_string(),
+            name: "oris-kernel".into(),
+            token: None,
+            timeout: Self::DEFAULT_TIMEOUT,
+            connection: None,
+        }
+    }
+
+    /// Client name the server shows for the connection.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Authenticates with a server token.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Timeout for connecting and for each read and write, including the wait for each
+    /// acknowledgement.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    fn connect(&self) -> Result<Connection, String> {
+        if self.address.starts_with("tls://") {
+            return Err(format!(
+                "{}: TLS is not supported, connect over plain nats://",
+                self.address
+            ));
+        }
+        let addr = self
+            .address
+            .to_socket_addrs()
+            .map_err(|e| format!("resolve {}: {}", self.address, e))?
+            .next()
+            .ok_or_else(|| format!("resolve {}: no address", self.address))?;
+        let stream = TcpStream::connect_timeout(&addr, self.timeout)
+            .map_err(|e| format!("connect {}: {}", self.address, e))?;
+        stream
+            .set_read_timeout(Some(self.timeout))
+            .and_then(|_| stream.set_write_timeout(Some(self.timeout)))
+            .and_then(|_| stream.set_nodelay(true))
+            .map_err(|e| e.to_string())?;
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        // RandomState is seeded per process and per instance, enough for a unique inbox
+        let inbox_id = RandomState::new().build_hasher().finish();
+        let mut connection = Connection {
+            reader: BufReader::new(reader),
+            writer: BufWriter::new(stream),
+            inbox: format!("_INBOX.oris.{:016x}.", inbox_id),
+        };
+
+        let info = connection.read_line()?;
+        let info = info
+            .strip_prefix("INFO ")
+            .ok_or_else(|| format!("expected INFO from server, got '{}'", info))?;
+        let info: serde_json::Value =
+            serde_json::from_str(info).map_err(|e| format!("parse server INFO: {}", e))?;
+        if info["tls_required"].as_bool().unwrap_or(false) {
+            return Err(format!(
+                "server {} requires TLS, which is not supported",
+                self.address
+            ));
+        }
+        if !info["headers"].as_bool().unwrap_or(false) {
+            return Err(format!(
+                "server {} does not support headers (NATS 2.2+), needed for Nats-Msg-Id",
+                self.address
+            ));
+        }
+
+        let mut options = serde_json::json!({
+            "verbose": false,
+            "pedantic": false,
+            "headers": true,
+            "no_responders": true,
+            "name": self.name,
+            "lang": "rust",
+            "version": env!("CARGO_PKG_VERSION"),
+        });
+        if let Some(token) = &self.token {
+            options["auth_token"] = token.clone().into();
+        }
+        let subscribe = format!(
+            "CONNECT {}\r\nSUB {}* 1\r\nPING\r\n",
+            options, connection.inbox
+        );
+        connection.write(subscribe.as_bytes())?;
+        connection.writer.flush().map_err(|e| e.to_string())?;
+        connection.await_pong()?;
+        Ok(connection)
+    }
+
+    fn publish_on(
+        connection: &mut Connection,
+        subject: &str,
+        messages: &[StreamMessage],
+    ) -> Result<(), String> {
+        for (index, message) in messages.iter().enumerate() {
+            let headers = format!(
+                "NATS/1.0\r\nNats-Msg-Id: {}\r\nOris-Run-Id: {}\r\nOris-Seq: {}\r\nOris-Event-Kind: {}\r\nOris-Event-Version: {}\r\n\r\n",
+                message.message_id(),
+                message.run_id,
+                message.seq,
+                message.kind,
+                message.version
+            );
+            connection.write(
+                format!(
+                    "HPUB {} {}{} {} {}\r\n",
+                    subject,
+                    connection.inbox,
+                    index,
+                    headers.len(),
+                    headers.len() + message.payload.len()
+                )
+                .as_bytes(),
+            )?;
+            connection.write(headers.as_bytes())?;
+            connection.write(&message.payload)?;
+            connection.write(b"\r\n")?;
+        }
+        connection.writer.flush().map_err(|e| e.to_string())?;
+
+        let mut acked = vec![false; messages.len()];
+        let mut pending = messages.len();
+        while pending > 0 {
+            let reply = connection.read_reply()?;
+            let Some(index) = reply
+                .subject
+                .strip_prefix(connection.inbox.as_str())
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|index| *index < messages.len() && !acked[*index])
+            else {
+                continue;
+            };
+            reply
+                .pub_ack()
+                .map_err(|e| format!("publish {}: {}", messages[index].message_id(), e))?;
+            acked[index] = true;
+            pending -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl EventPublisher for NatsPublisher {
+    /// Returns once the JetStream stream capturing `topic` acknowledged every message.
+    fn publish(&mut self, topic: &str, messages: &[StreamMessage]) -> Result<(), String> {
+        let mut connection = match self.connection.take() {
+            Some(connection) => connection,
+            None => self.connect()?,
+        };
+        Self::publish_on(&mut connection, topic, messages)?;
+        self.connection = Some(connection);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event::{Event, EventKind, SequencedEvent};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// One published message as the test server parsed it
+    #[derive(Debug)]
+    struct Received {
+        subject: String,
+        headers: String,
+        payload: Vec<u8>,
+    }
+
+    const INFO: &str = "INFO {\"server_id\":\"test\",\"headers\":true}\r\n";
+
+    /// JetStream acknowledgement storing the `count`th message of a connection
+    fn stored(reply: &str, count: usize) -> String {
+        let ack = format!("{{\"stream\":\"ORIS\",\"seq\":{}}}", count);
+        format!("MSG {} 1 {}\r\n{}\r\n", reply, ack.len(), ack)
+    }
+
+    /// Serves connections one after another, greeting with `info` and answering each
+    /// message with `ack`; each reads `per_connection` messages (then closes before
+    /// acknowledging the last, to simulate a dropped connection) or runs until the client
+    /// leaves
+    fn serve(
+        listener: TcpListener,
+        info: &'static str,
+        ack: fn(&str, usize) -> String,
+        per_connection: Vec<Option<usize>>,
+    ) -> mpsc::Receiver<Received> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for limit in per_connection {
+                let (stream, _) = listener.accept().unwrap();
+                let mut writer = stream.try_clone().unwrap();
+                let mut reader = BufReader::new(stream);
+                writer.write_all(info.as_bytes()).unwrap();
+                let mut count = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                        break;
+                    }
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    match parts.first().copied() {
+                        Some("PING") => writer.write_all(b"PONG\r\n").unwrap(),
+                        Some("HPUB") => {
+                            let header_len: usize = parts[3].parse().unwrap();
+                            let total: usize = parts[4].parse().unwrap();
+                            let mut body = vec![0; total + 2];
+                            reader.read_exact(&mut body).unwrap();
+                            let _ = tx.send(Received {
+                                subject: parts[1].to_string(),
+                                headers: String::from_utf8(body[..header_len].to_vec()).unwrap(),
+                                payload: body[header_len..total].to_vec(),
+                            });
+                            count += 1;
+                            if Some(count) == limit {
+                                break;
+                            }
+                            writer.write_all(ack(parts[2], count).as_bytes()).unwrap();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+        rx
+    }
+
+    fn message(seq: u64, event: Event) -> StreamMessage {
+        StreamMessage::from_event(&"run-nats".to_string(), &SequencedEvent::new(seq, event))
+            .unwrap()
+    }
+
+    #[test]
+    fn publishes_events_with_run_headers_and_reconnects_after_a_dropped_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("nats://{}", listener.local_addr().unwrap());
+        // The first connection drops after one message, before acknowledging it
+        let received = serve(listener, INFO, stored, vec![Some(1), None]);
+        let mut publisher = NatsPublisher::new(&url).with_timeout(Duration::from_secs(2));
+
+        let batch = [message(1, Event::Paused), message(2, Event::Completed)];
+        assert!(publisher.publish("oris.events", &batch).is_err());
+        assert!(!publisher.is_connected());
+        // The retry reconnects and publishes the whole batch again
+        publisher.publish("oris.events", &batch).unwrap();
+        assert!(publisher.is_connected());
+
+        let got: Vec<Received> = received.iter().take(3).collect();
+        assert_eq!(got[0].headers, got[1].headers);
+        let last = &got[2];
+        assert_eq!(last.subject, "oris.events");
+        assert!(last.headers.starts_with("NATS/1.0\r\n"));
+        assert!(last.headers.contains("Nats-Msg-Id: run-nats:2\r\n"));
+        assert!(last.headers.contains("Oris-Run-Id: run-nats\r\n"));
+        assert!(last
+            .headers
+            .contains(&format!("Oris-Event-Kind: {}\r\n", EventKind::Completed)));
+        assert_eq!(last.payload, serde_json::to_vec(&Event::Completed).unwrap());
+    }
+
+    #[test]
+    fn an_unreachable_server_is_an_error_not_a_panic() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let mut publisher =
+            NatsPublisher::new(&addr.to_string()).with_timeout(Duration::from_millis(200));
+        let err = publisher
+            .publish("oris.events", &[message(1, Event::Completed)])
+            .unwrap_err();
+        assert!(err.starts_with("connect "), "{}", err);
+    }
+
+    #[test]
+    fn rejects_tls_and_servers_without_headers() {
+        let mut publisher = NatsPublisher::new("tls://127.0.0.1:4222");
+        let err = publisher
+            .publish("oris.events", &[message(1, Event::Completed)])
+            .unwrap_err();
+        assert!(err.contains("TLS is not supported"), "{}", err);
+
+        for (info, expected) in [
+            (
+                "INFO {\"server_id\":\"test\",\"headers\":true,\"tls_required\":true}\r\n",
+                "requires TLS",
+            ),
+            (
+                "INFO {\"server_id\":\"test\"}\r\n",
+                "does not support headers",
+            ),
+        ] {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("nats://{}", listener.local_addr().unwrap());
+            let received = serve(listener, info, stored, vec![None]);
+            let mut publisher = NatsPublisher::new(&url).with_timeout(Duration::from_secs(2));
+            let err = publisher
+                .publish("oris.events", &[message(1, Event::Completed)])
+                .unwrap_err();
+            assert!(err.contains(expected), "{}", err);
+            assert!(!publisher.is_connected());
+            drop(publisher);
+            assert!(received.iter().next().is_none());
+        }
+    }
+
+    #[test]
+    fn fails_a_batch_the_stream_does_not_acknowledge() {
+        fn no_responders(reply: &str, _count: usize) -> String {
+            let headers = "NATS/1.0 503\r\n\r\n";
+            format!(
+                "HMSG {} 1 {} {}\r\n{}\r\n",
+                reply,
+                headers.len(),
+                headers.len(),
+                headers
+            )
+        }
+        fn rejected(reply: &str, _count: usize) -> String {
+            let ack = "{\"error\":{\"code\":400,\"description\":\"maximum messages exceeded\"}}";
+            format!("MSG {} 1 {}\r\n{}\r\n", reply, ack.len(), ack)
+        }
+
+        for (ack, expected) in [
+            (
+                no_responders as fn(&str, usize) -> String,
+                "no JetStream stream",
+            ),
+            (rejected, "maximum messages exceeded"),
+        ] {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("nats://{}", listener.local_addr().unwrap());
+            let _received = serve(listener, INFO, ack, vec![None]);
+            let mut publisher = NatsPublisher::new(&url).with_timeout(Duration::from_secs(2));
+            let err = publisher
+                .publish("oris.events", &[message(1, Event::Completed)])
+                .unwrap_err();
+            assert!(err.starts_with("publish run-nats:1: "), "{}", err);
+            assert!(err.contains(expected), "{}", err);
+            assert!(!publisher.is_connected());
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Operator queries over an event store: which runs exist and where each one stands.
+//!
+//! Stores answer these through [EventStore::list_runs] and [EventStore::run_exists];
+//! [list_runs] wraps the former with paging metadata for servers and CLIs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "sqlite-persistence", feature = "kernel-postgres"))]
+use crate::kernel::event::EventFilter;
+use crate::kernel::event::{Event, EventStore, KernelError};
+use crate::kernel::identity::RunId;
+
+/// Status of a run as derived from its last event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatusKind {
+    /// The last event neither blocks nor ends the run.
+    Running,
+    /// The run stopped at an interrupt (`Interrupted`) or on its budget (`BudgetExceeded`).
+    Blocked,
+    /// The run was paused on request (`Paused`).
+    Paused,
+    /// The run ended with `Failed`, its last action failed (`ActionFailed`), or the policy
+    /// denied its action (`ActionDenied`).
+    Failed,
+    /// The run ended with `Completed`.
+    Completed,
+    /// The run was cancelled on request (`Cancelled`).
+    Cancelled,
+}
+
+impl RunStatusKind {
+    /// Status of a run whose last event has kind `kind`, an [EventKind](crate::kernel::EventKind) name.
+    pub fn from_last_event_kind(kind: &str) -> Self {
+        match kind {
+            "Interrupted" | "BudgetExceeded" => Self::Blocked,
+            "Paused" => Self::Paused,
+            "Failed" | "ActionFailed" | "ActionDenied" => Self::Failed,
+            "Completed" => Self::Completed,
+            "Cancelled" => Self::Cancelled,
+            _ => Self::Running,
+        }
+    }
+
+    /// Status of a run whose last event is `event`.
+    pub fn from_last_event(event: &Event) -> Self {
+        Self::from_last_event_kind(event.kind().as_str())
+    }
+
+    /// The serialized name, e.g. `"blocked"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Blocked => "blocked",
+            Self::Paused => "paused",
+            Self::Failed => "failed",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Which runs [EventStore::list_runs] returns; the default matches every run.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunFilter {
+    /// Only runs with this derived status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<RunStatusKind>,
+    /// Only runs whose first event was stored strictly after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+}
+
+impl RunFilter {
+    pub fn with_status(mut self, status: RunStatusKind) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn created_after(mut self, after: DateTime<Utc>) -> Self {
+        self.created_after = Some(after);
+        self
+    }
+
+    /// Whether a run with `summary` passes the filter.
+    pub fn matches(&self, summary: &RunSummary) -> bool {
+        if let Some(status) = self.status {
+            if summary.status != status {
+                return false;
+            }
+        }
+        match self.created_after {
+            Some(after) => summary.first_event_at > after,
+            None => true,
+        }
+    }
+}
+
+/// A window of a listing: skip `offset` entries, return at most `limit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl PageRequest {
+    /// Page size used by [PageRequest::default].
+    pub const DEFAULT_LIMIT: usize = 100;
+
+    pub fn new(offset: usize, limit: usize) -> Self {
+        Self { offset, limit }
+    }
+
+    /// The first `limit` entries.
+    pub fn first(limit: usize) -> Self {
+        Self::new(0, limit)
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::first(Self::DEFAULT_LIMIT)
+    }
+}
+
+/// One run of an event store, as listed by [EventStore::list_runs].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub run_id: RunId,
+    /// When the store received the run's first event.
+    pub first_event_at: DateTime<Utc>,
+    /// When the store received the run's last event.
+    pub last_event_at: DateTime<Utc>,
+    /// Kind of the last event, e.g. `Interrupted` (see [Event::kind]).
+    pub last_event_kind: String,
+    pub event_count: u64,
+    pub status: RunStatusKind,
+}
+
+/// A page of runs and where the next one starts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunPage {
+    pub runs: Vec<RunSummary>,
+    /// Offset of the next page, or `None` when this is the last one.
+    pub next_offset: Option<usize>,
+}
+
+/// Lists the runs of `store` matching `filter`, oldest first.
+///
+/// Fails on stores that cannot enumerate their runs (see [EventStore::list_runs]).
+pub fn list_runs(
+    store: &dyn EventStore,
+    filter: RunFilter,
+    page: PageRequest,
+) -> Result<RunPage, KernelError> {
+    // One extra entry tells whether another page follows
+    let mut runs = store.list_runs(
+        filter,
+        PageRequest::new(page.offset, page.limit.saturating_add(1)),
+    )?;
+    let next_offset = if runs.len() > page.limit {
+        runs.truncate(page.limit);
+        Some(page.offset + page.limit)
+    } else {
+        None
+    };
+    Ok(RunPage { runs, next_offset })
+}
+
+/// Orders, filters and pages `summaries` the way [EventStore::list_runs] returns them.
+pub(crate) fn select_runs(
+    mut summaries: Vec<RunSummary>,
+    filter: &RunFilter,
+    page: PageRequest,
+) -> Vec<RunSummary> {
+    summaries.retain(|summary| filter.matches(summary));
+    summaries.sort_by(|a, b| (a.first_event_at, &a.run_id).cmp(&(b.first_event_at, &b.run_id)));
+    summaries
+        .into_iter()
+        .skip(page.offset)
+        .take(page.limit)
+        .collect()
+}
+
+/// SQL for the status of a run whose last event's kind is in column `kind`; mirrors
+/// [RunStatusKind::from_last_event_kind]
+#[cfg(any(feature = "sqlite-persistence", feature = "kernel-postgres"))]
+pub(crate) const RUN_STATUS_SQL: &str = "CASE kind
+    WHEN 'Interrupted' THEN 'blocked'
+    WHEN 'BudgetExceeded' THEN 'blocked'
+    WHEN 'Paused' THEN 'paused'
+    WHEN 'Failed' THEN 'failed'
+    WHEN 'ActionFailed' THEN 'failed'
+    WHEN 'ActionDenied' THEN 'failed'
+    WHEN 'Completed' THEN 'completed'
+    WHEN 'Cancelled' THEN 'cancelled'
+    ELSE 'running' END";
+
+/// SQL `AND` clause keeping the rows whose event kind, computed by `kind_sql`, passes
+/// `filter`; empty when the filter keeps everything
+#[cfg(any(feature = "sqlite-persistence", feature = "kernel-postgres"))]
+pub(crate) fn event_filter_sql(kind_sql: &str, filter: &EventFilter) -> String {
+    match filter.kinds() {
+        None => String::new(),
+        Some(kinds) if kinds.is_empty() => " AND 1 = 0".to_string(),
+        // Kind names are fixed identifiers, safe to inline
+        Some(kinds) => format!(
+            " AND ({}) IN ({})",
+            kind_sql,
+            kinds
+                .iter()
+                .map(|kind| format!("'{}'", kind))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// A page bound or seq as an SQL integer, saturating at `i64::MAX`
+#[cfg(any(feature = "sqlite-persistence", feature = "kernel-postgres"))]
+pub(crate) fn sql_bound<N: TryInto<i64>>(n: N) -> i64 {
+    n.try_into().unwrap_or(i64::MAX)
+}
+
+/// Time of a store's `created_at_ms` column
+#[cfg(any(feature = "sqlite-persistence", feature = "kernel-postgres"))]
+pub(crate) fn datetime_from_ms(ms: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(ms).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event_store::InMemoryEventStore;
+
+    #[test]
+    fn list_runs_pages_until_the_last_run() {
+        let store = InMemoryEventStore::new();
+        for i in 0..5 {
+            store
+                .append(&format!("run-{}", i), &[Event::Completed])
+                .unwrap();
+        }
+
+        let first = list_runs(&store, RunFilter::default(), PageRequest::first(2)).unwrap();
+        assert_eq!(first.runs.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+        let last = list_runs(&store, RunFilter::default(), PageRequest::new(4, 2)).unwrap();
+        assert_eq!(last.runs.len(), 1);
+        assert_eq!(last.next_offset, None);
+    }
+
+    #[test]
+    fn status_follows_the_last_event() {
+        let failed = Event::Failed {
+            reason: "boom".into(),
+            code: None,
+        };
+        assert_eq!(
+            RunStatusKind::from_last_event(&failed),
+            RunStatusKind::Failed
+        );
+        let resumed = Event::Resumed {
+            value: serde_json::json!(1),
+        };
+        assert_eq!(
+            RunStatusKind::from_last_event(&resumed),
+            RunStatusKind::Running
+        );
+        assert_eq!(
+            serde_json::to_value(RunStatusKind::Blocked).unwrap(),
+            RunStatusKind::Blocked.as_str()
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! OpenTelemetry instrumentation (feature `otel`).
+//!
+//! With the feature enabled, [`Kernel::run_until_blocked`](crate::kernel::Kernel::run_until_blocked)
+//! and [`Kernel::resume`](crate::kernel::Kernel::resume) run inside a `kernel.run` span and
+//! every step inside a `kernel.step` child span. Spans go to the global tracer provider;
+//! nothing is exported until the application installs one.
+
+use opentelemetry::{
+    global,
+    trace::{Status, TraceContextExt, Tracer},
+    Context, ContextGuard, KeyValue,
+};
+use serde_json::{json, Value};
+
+use crate::kernel::event::Event;
+use crate::kernel::identity::RunId;
+
+/// Metadata/payload key under which the trace and span IDs of a run are stored
+pub const TRACE_CONTEXT_KEY: &str = "trace_context";
+
+const TRACER_NAME: &str = "oris-kernel";
+
+/// The trace and span IDs of `cx`'s span as `{"trace_id": .., "span_id": ..}`, if it has one
+pub fn trace_context(cx: &Context) -> Option<Value> {
+    let span = cx.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| {
+        json!({
+            "trace_id": span_context.trace_id().to_string(),
+            "span_id": span_context.span_id().to_string(),
+        })
+    })
+}
+
+/// A span that is the current context until it is dropped, which ends it
+pub(crate) struct SpanScope {
+    cx: Context,
+    _guard: ContextGuard,
+}
+
+impl SpanScope {
+    /// Start the root span of a kernel run
+    pub(crate) fn run(run_id: &RunId) -> Self {
+        Self::start("kernel.run", vec![KeyValue::new("run_id", run_id.clone())])
+    }
+
+    /// Start the span of the kernel run's `step`-th step
+    pub(crate) fn step(step: u64) -> Self {
+        Self::start("kernel.step", vec![KeyValue::new("step", step as i64)])
+    }
+
+    fn start(name: &'static str, attributes: Vec<KeyValue>) -> Self {
+        let tracer = global::tracer(TRACER_NAME);
+        let parent = Context::current();
+        let span = tracer
+            .span_builder(name)
+            .with_attributes(attributes)
+            .start_with_context(&tracer, &parent);
+        let cx = parent.with_span(span);
+        Self {
+            _guard: cx.clone().attach(),
+            cx,
+        }
+    }
+
+    /// Record the events a step emitted: the step id and the size of its state update
+    pub(crate) fn record_emit(&self, events: &[Event]) {
+        let span = self.cx.span();
+        for event in events {
+            if let Event::StateUpdated {
+                step_id, payload, ..
+            } = event
+            {
+                if let Some(step_id) = step_id {
+                    span.set_attribute(KeyValue::new("step_id", step_id.clone()));
+                }
+                let size = serde_json::to_vec(payload).map_or(0, |bytes| bytes.len());
+                span.set_attribute(KeyValue::new("update_size", size as i64));
+            }
+        }
+    }
+
+    /// Record that the run hit an interrupt
+    pub(crate) fn record_interrupt(&self, value: &Value) {
+        self.cx
+            .span()
+            .add_event("interrupt", vec![KeyValue::new("value", value.to_string())]);
+    }
+
+    /// Record that an action failed and is retried
+    pub(crate) fn record_retry(&self, attempt: u32, error: &str) {
+        self.cx.span().add_event(
+            "retry",
+            vec![
+                KeyValue::new("attempt", i64::from(attempt)),
+                KeyValue::new("error", error.to_string()),
+            ],
+        );
+    }
+
+    /// Mark the span as failed
+    pub(crate) fn record_error(&self, error: &str) {
+        self.cx.span().set_status(Status::error(error.to_string()));
+    }
+}
+
+impl Drop for SpanScope {
+    fn drop(&mut self) {
+        self.cx.span().end();
+    }
+}
@@ -1,4 +1,4 @@
-//! Policy: governance layer (authorize, retry, budget).
+//! Policy: governance layer (authorize, retry, budget, dry-run simulation).
 //!
 //! Must exist even as a minimal implementation so Oris is not a "run any tool" demo.
 //!
@@ -6,12 +6,24 @@
 //! the policy returns `Fail`. Implementations must eventually return `Fail` or the loop would not
 //! terminate; `RetryWithBackoffPolicy` does so after `max_retries` attempts.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::kernel::action::{Action, ActionError, ActionErrorKind};
+use serde_json::Value;
+
+use crate::kernel::action::{
+    output_metadata, Action, ActionError, ActionErrorKind, ActionResult, METADATA_COST_CENTS,
+    METADATA_TOKENS,
+};
+use crate::kernel::executor_registry::KindPattern;
 use crate::kernel::identity::RunId;
+use crate::kernel::risk_tier::RiskTier;
 use crate::kernel::KernelError;
 
+/// Default of [Policy::max_parallel_actions].
+pub const DEFAULT_MAX_PARALLEL_ACTIONS: usize = 4;
+
 /// Context passed to policy (e.g. caller identity, run metadata).
 #[derive(Clone, Debug, Default)]
 pub struct PolicyCtx {
@@ -19,10 +31,20 @@ pub struct PolicyCtx {
     pub user_id: Option<String>,
     /// Arbitrary run-level metadata for policy enforcement.
     pub metadata: std::collections::HashMap<String, String>,
+    /// The executor the kernel's [ActionExecutor::route](crate::kernel::ActionExecutor::route)
+    /// selected for the action (e.g. the `http/*` route of an
+    /// [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry)), if it routes.
+    pub executor: Option<String>,
+    /// What the run has spent so far, for [Policy::check_budget]. The driver fills it in
+    /// from the run's log when [Policy::budget] sets a limit.
+    pub usage: BudgetUsage,
+    /// The action's [RiskTier](crate::kernel::RiskTier), when a
+    /// [TieredPolicy](crate::kernel::TieredPolicy) hands it to the policy of its tier.
+    pub risk_tier: Option<RiskTier>,
 }
 
 /// Decision after an action failure (retry, backoff, or fail).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RetryDecision {
     /// Retry immediately.
     Retry,
@@ -32,13 +54,167 @@ pub enum RetryDecision {
     Fail,
 }
 
+impl RetryDecision {
+    /// How long to wait before retrying, or `None` for `Fail`.
+    pub fn delay(&self) -> Option<Duration> {
+        match self {
+            RetryDecision::Retry => Some(Duration::ZERO),
+            RetryDecision::RetryAfterMs(ms) => Some(Duration::from_millis(*ms)),
+            RetryDecision::Fail => None,
+        }
+    }
+}
+
 /// Optional budget rules (cost, token limits, etc.).
+///
+/// A limit is reached once the run has spent that much; the next action it would pay for
+/// is denied. Tokens and cost are known only after an action ran, so the action that
+/// crosses a limit completes and the one after it is denied.
 #[derive(Clone, Debug, Default)]
 pub struct BudgetRules {
     /// Maximum number of tool-call actions allowed per run.
     pub max_tool_calls: Option<u64>,
     /// Maximum LLM tokens that may be consumed per run.
     pub max_llm_tokens: Option<u64>,
+    /// Maximum tokens the run's actions, of any kind, may report.
+    pub max_tokens: Option<u64>,
+    /// Maximum cost in cents the run's actions may report.
+    pub max_cost_cents: Option<f64>,
+    /// Maximum number of actions per run of each listed [kind](Action::kind).
+    pub max_actions_per_kind: HashMap<String, u64>,
+}
+
+/// Reason code of a `PolicyDecision` event allowing an action.
+pub const POLICY_AUTHORIZED: &str = "POLICY_AUTHORIZED";
+/// Reason code of a `PolicyDecision` event for an action [Policy::authorize] denied.
+pub const POLICY_DENIED: &str = "POLICY_DENIED";
+/// Reason code of a denial for [BudgetRules::max_tool_calls].
+pub const BUDGET_TOOL_CALLS_EXCEEDED: &str = "BUDGET_TOOL_CALLS_EXCEEDED";
+/// Reason code of a denial for [BudgetRules::max_llm_tokens].
+pub const BUDGET_LLM_TOKENS_EXCEEDED: &str = "BUDGET_LLM_TOKENS_EXCEEDED";
+/// Reason code of a denial for [BudgetRules::max_tokens].
+pub const BUDGET_TOKENS_EXCEEDED: &str = "BUDGET_TOKENS_EXCEEDED";
+/// Reason code of a denial for [BudgetRules::max_cost_cents].
+pub const BUDGET_COST_EXCEEDED: &str = "BUDGET_COST_EXCEEDED";
+/// Reason code of a denial for [BudgetRules::max_actions_per_kind].
+pub const BUDGET_ACTIONS_PER_KIND_EXCEEDED: &str = "BUDGET_ACTIONS_PER_KIND_EXCEEDED";
+
+impl BudgetRules {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tool_calls.is_none()
+            && self.max_llm_tokens.is_none()
+            && self.max_tokens.is_none()
+            && self.max_cost_cents.is_none()
+            && self.max_actions_per_kind.is_empty()
+    }
+
+    /// Denies `action` if the run, having spent `usage`, reached a limit that applies to it.
+    pub fn check(&self, action: &Action, usage: &BudgetUsage) -> Result<(), BudgetExceeded> {
+        let reached = |limit: Option<u64>, spent: u64| limit.filter(|max| spent >= *max);
+        let is_tool = matches!(action, Action::CallTool { .. });
+        let is_llm = matches!(action, Action::CallLLM { .. });
+        if let Some(max) = reached(self.max_tool_calls, usage.tool_calls).filter(|_| is_tool) {
+            return Err(BudgetExceeded::new(
+                BUDGET_TOOL_CALLS_EXCEEDED,
+                format!("the run made {} of {} tool calls", usage.tool_calls, max),
+            ));
+        }
+        let kind = action.kind();
+        let of_kind = usage.actions_of_kind(&kind);
+        if let Some(max) = reached(self.max_actions_per_kind.get(&kind).copied(), of_kind) {
+            return Err(BudgetExceeded::new(
+                BUDGET_ACTIONS_PER_KIND_EXCEEDED,
+                format!("the run made {} of {} '{}' actions", of_kind, max, kind),
+            ));
+        }
+        if let Some(max) = reached(self.max_llm_tokens, usage.llm_tokens).filter(|_| is_llm) {
+            return Err(BudgetExceeded::new(
+                BUDGET_LLM_TOKENS_EXCEEDED,
+                format!("the run used {} of {} LLM tokens", usage.llm_tokens, max),
+            ));
+        }
+        if let Some(max) = reached(self.max_tokens, usage.tokens) {
+            return Err(BudgetExceeded::new(
+                BUDGET_TOKENS_EXCEEDED,
+                format!("the run used {} of {} tokens", usage.tokens, max),
+            ));
+        }
+        if let Some(max) = self.max_cost_cents.filter(|max| usage.cost_cents >= *max) {
+            return Err(BudgetExceeded::new(
+                BUDGET_COST_EXCEEDED,
+                format!("the run spent {} of {} cents", usage.cost_cents, max),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What a run has spent against its [BudgetRules]: the actions it completed and the
+/// usage their outputs reported in their [metadata](crate::kernel::ACTION_METADATA_KEY).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub tool_calls: u64,
+    /// Tokens reported by `CallLLM` actions.
+    pub llm_tokens: u64,
+    /// Tokens reported by actions of any kind.
+    pub tokens: u64,
+    pub cost_cents: f64,
+    /// Completed actions by [kind](Action::kind).
+    pub actions_per_kind: HashMap<String, u64>,
+}
+
+impl BudgetUsage {
+    /// Adds a completed `action` and the usage reported in its `output`, if it succeeded.
+    pub fn record(&mut self, action: &Action, output: Option<&Value>) {
+        if matches!(action, Action::CallTool { .. }) {
+            self.tool_calls += 1;
+        }
+        *self.actions_per_kind.entry(action.kind()).or_insert(0) += 1;
+        let Some(metadata) = output.and_then(output_metadata) else {
+            return;
+        };
+        let tokens = metadata
+            .get(METADATA_TOKENS)
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        self.tokens += tokens;
+        if matches!(action, Action::CallLLM { .. }) {
+            self.llm_tokens += tokens;
+        }
+        self.cost_cents += metadata
+            .get(METADATA_COST_CENTS)
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+    }
+
+    /// Completed actions of `kind`.
+    pub fn actions_of_kind(&self, kind: &str) -> u64 {
+        self.actions_per_kind.get(kind).copied().unwrap_or(0)
+    }
+}
+
+/// A budget denial: which limit the action would overrun, as one of the `BUDGET_*` reason
+/// codes, and a message for operators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    pub code: String,
+    pub reason: String,
+}
+
+impl BudgetExceeded {
+    pub fn new(code: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.reason)
+    }
 }
 
 /// Policy: authorize actions, decide retries, optional budget.
@@ -51,6 +227,25 @@ pub trait Policy: Send + Sync {
         ctx: &PolicyCtx,
     ) -> Result<(), KernelError>;
 
+    /// Like [authorize](Self::authorize), also saying why an allowed action is allowed, e.g.
+    /// the allow-list pattern it matched. The driver records the reason in the action's
+    /// `PolicyDecision` event. Defaults to `authorize` with no reason.
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        self.authorize(run_id, action, ctx).map(|()| None)
+    }
+
+    /// Names the policy in the `PolicyDecision` events the driver records, so decisions
+    /// can be traced to it. Policies wrapping others name them too, e.g.
+    /// `retry_with_backoff(allow_list)`.
+    fn name(&self) -> String {
+        "anonymous".into()
+    }
+
     /// Whether to retry after an error (and optionally after a delay).
     fn retry_strategy(&self, err: &dyn std::fmt::Display, _action: &Action) -> RetryDecision {
         let _ = err;
@@ -69,8 +264,10 @@ pub trait Policy: Send + Sync {
     ) -> RetryDecision {
         let _ = (action, attempt);
         match &err.kind {
-            ActionErrorKind::Permanent => RetryDecision::Fail,
-            ActionErrorKind::Transient | ActionErrorKind::RateLimited => {
+            ActionErrorKind::Permanent | ActionErrorKind::UnknownKind => RetryDecision::Fail,
+            ActionErrorKind::Transient
+            | ActionErrorKind::RateLimited
+            | ActionErrorKind::Timeout => {
                 // Default: no retry unless overridden
                 if let ActionErrorKind::RateLimited = &err.kind {
                     if let Some(ms) = err.retry_after_ms {
@@ -82,20 +279,159 @@ pub trait Policy: Send + Sync {
         }
     }
 
+    /// [retry_strategy_attempt](Self::retry_strategy_attempt) knowing how long the action
+    /// has taken: `elapsed` since its first attempt started, earlier waits included. The
+    /// driver calls this one. Default: ignores `elapsed`.
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        let _ = elapsed;
+        self.retry_strategy_attempt(err, action, attempt)
+    }
+
     /// Optional budget; default is no limits.
     fn budget(&self) -> BudgetRules {
         BudgetRules::default()
     }
+
+    /// Whether the run may still spend on `action`, given what it spent so far
+    /// ([PolicyCtx::usage]). The driver asks after [authorize](Self::authorize) and, on a
+    /// denial, appends `BudgetExceeded` and blocks the run instead of failing it; running
+    /// it again once the budget was raised continues it. Default: [BudgetRules::check]
+    /// against [budget](Self::budget).
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        self.budget().check(action, &ctx.usage)
+    }
+
+    /// How many actions of a [Next::DoAll](crate::kernel::Next::DoAll) batch the driver
+    /// executes at once (at least one). Default: [DEFAULT_MAX_PARALLEL_ACTIONS].
+    fn max_parallel_actions(&self) -> usize {
+        DEFAULT_MAX_PARALLEL_ACTIONS
+    }
+
+    /// How long one attempt of `action` may run. An attempt still running after that is
+    /// failed with an [ActionErrorKind::Timeout] executor error, which goes through
+    /// [retry_strategy_attempt](Self::retry_strategy_attempt) like any other. The executor
+    /// call is abandoned, not interrupted: it keeps its thread until it returns and its
+    /// result is discarded. Default: no limit.
+    fn action_timeout(&self, action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        let _ = (action, ctx);
+        None
+    }
+
+    /// The result to record for an authorized action in [KernelMode::DryRun](crate::kernel::KernelMode::DryRun),
+    /// where no executor runs. Default: success with [simulated_output].
+    fn simulate(&self, action: &Action) -> ActionResult {
+        let _ = action;
+        ActionResult::Success(simulated_output())
+    }
 }
 
-/// Policy that allows only actions whose tool/provider is in the given sets.
+/// The canned output of a simulated action: `{"simulated": true}`.
+pub fn simulated_output() -> Value {
+    serde_json::json!({ "simulated": true })
+}
+
+/// Policy that allows only actions whose tool/provider is in the given sets, whose
+/// executor ([PolicyCtx::executor]) is in `allowed_executors`, or whose [kind](Action::kind)
+/// matches an allowed pattern ([with_allowed_kinds](Self::with_allowed_kinds)).
 /// **Empty set = no tools or providers allowed** for that category. Sleep and WaitSignal are
 /// always allowed. To allow all tools/providers use `AllowAllPolicy`, or populate the sets explicitly.
+///
+/// Denied patterns ([with_denied_kinds](Self::with_denied_kinds)) take precedence over
+/// every allow, Sleep and WaitSignal included.
 pub struct AllowListPolicy {
     /// Set of tool names that `CallTool` actions are allowed to reference.
     pub allowed_tools: HashSet<String>,
     /// Set of provider names that `CallLLM` actions are allowed to reference.
     pub allowed_providers: HashSet<String>,
+    /// Executor routes (e.g. `search/*`) whose actions are all allowed.
+    pub allowed_executors: HashSet<String>,
+    allowed_kinds: Vec<KindGlob>,
+    denied_kinds: Vec<KindGlob>,
+}
+
+/// A glob over action kinds, compiled once: `*` matches any run of characters (`/`
+/// included) and `?` any one character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct KindGlob {
+    /// The pattern as given, reported in decisions
+    pattern: String,
+    tokens: Vec<GlobToken>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum GlobToken {
+    Literal(String),
+    AnyChar,
+    AnyRun,
+}
+
+impl KindGlob {
+    /// Fails with [KernelError::Policy] naming `pattern` if it is invalid.
+    pub(crate) fn parse(pattern: &str) -> Result<Self, KernelError> {
+        let invalid =
+            |why: &str| KernelError::Policy(format!("invalid pattern '{}': {}", pattern, why));
+        if pattern.is_empty() {
+            return Err(invalid("empty"));
+        }
+        let mut tokens = Vec::new();
+        for c in pattern.chars() {
+            match c {
+                '*' if tokens.last() == Some(&GlobToken::AnyRun) => {}
+                '*' => tokens.push(GlobToken::AnyRun),
+                '?' => tokens.push(GlobToken::AnyChar),
+                '[' | ']' | '{' | '}' => {
+                    return Err(invalid("only '*' and '?' wildcards are supported"))
+                }
+                c if c.is_whitespace() || c.is_control() => {
+                    return Err(invalid("kinds contain no whitespace"))
+                }
+                c => match tokens.last_mut() {
+                    Some(GlobToken::Literal(text)) => text.push(c),
+                    _ => tokens.push(GlobToken::Literal(c.to_string())),
+                },
+            }
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            tokens,
+        })
+    }
+
+    fn parse_all(
+        patterns: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Vec<Self>, KernelError> {
+        patterns
+            .into_iter()
+            .map(|pattern| Self::parse(pattern.as_ref()))
+            .collect()
+    }
+
+    pub(crate) fn matches(&self, kind: &str) -> bool {
+        fn matches_from(tokens: &[GlobToken], rest: &str) -> bool {
+            match tokens.split_first() {
+                None => rest.is_empty(),
+                Some((GlobToken::Literal(text), tokens)) => rest
+                    .strip_prefix(text.as_str())
+                    .is_some_and(|rest| matches_from(tokens, rest)),
+                Some((GlobToken::AnyChar, tokens)) => {
+                    let mut chars = rest.chars();
+                    chars.next().is_some() && matches_from(tokens, chars.as_str())
+                }
+                Some((GlobToken::AnyRun, tokens)) => rest
+                    .char_indices()
+                    .map(|(i, _)| i)
+                    .chain(std::iter::once(rest.len()))
+                    .any(|i| matches_from(tokens, &rest[i..])),
+            }
+        }
+        matches_from(&self.tokens, kind)
+    }
 }
 
 impl AllowListPolicy {
@@ -104,6 +440,9 @@ impl AllowListPolicy {
         Self {
             allowed_tools,
             allowed_providers,
+            allowed_executors: HashSet::new(),
+            allowed_kinds: Vec::new(),
+            denied_kinds: Vec::new(),
         }
     }
 
@@ -112,6 +451,9 @@ impl AllowListPolicy {
         Self {
             allowed_tools: tools.into_iter().collect(),
             allowed_providers: HashSet::new(),
+            allowed_executors: HashSet::new(),
+            allowed_kinds: Vec::new(),
+            denied_kinds: Vec::new(),
         }
     }
 
@@ -120,42 +462,107 @@ impl AllowListPolicy {
         Self {
             allowed_tools: HashSet::new(),
             allowed_providers: providers.into_iter().collect(),
+            allowed_executors: HashSet::new(),
+            allowed_kinds: Vec::new(),
+            denied_kinds: Vec::new(),
         }
     }
+
+    /// Creates a policy that permits only the actions whose kind matches one of `patterns`;
+    /// see [with_allowed_kinds](Self::with_allowed_kinds).
+    pub fn kinds(patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self, KernelError> {
+        Self::tools_only([]).with_allowed_kinds(patterns)
+    }
+
+    /// Also allows every action routed to one of `executors` (routes of an
+    /// [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry), e.g. `http/*`).
+    pub fn with_executors(mut self, executors: impl IntoIterator<Item = String>) -> Self {
+        self.allowed_executors.extend(executors);
+        self
+    }
+
+    /// Also allows every action whose [kind](Action::kind) matches one of `patterns`, globs
+    /// where `*` matches any run of characters and `?` any one, e.g. `http/*`,
+    /// `search/vector?` or `llm/*`. Fails with [KernelError::Policy] naming the first
+    /// invalid pattern.
+    pub fn with_allowed_kinds(
+        mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Self, KernelError> {
+        self.allowed_kinds.extend(KindGlob::parse_all(patterns)?);
+        Ok(self)
+    }
+
+    /// Denies every action whose kind matches one of `patterns`, whatever else allows it.
+    /// Patterns are as in [with_allowed_kinds](Self::with_allowed_kinds).
+    pub fn with_denied_kinds(
+        mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Self, KernelError> {
+        self.denied_kinds.extend(KindGlob::parse_all(patterns)?);
+        Ok(self)
+    }
 }
 
 impl Policy for AllowListPolicy {
+    fn name(&self) -> String {
+        "allow_list".into()
+    }
+
     fn authorize(
         &self,
-        _run_id: &RunId,
+        run_id: &RunId,
         action: &Action,
-        _ctx: &PolicyCtx,
+        ctx: &PolicyCtx,
     ) -> Result<(), KernelError> {
-        match action {
-            Action::CallTool { tool, .. } => {
-                if self.allowed_tools.contains(tool) {
-                    Ok(())
-                } else {
-                    Err(KernelError::Policy(format!("tool not allowed: {}", tool)))
-                }
+        self.authorize_with_reason(run_id, action, ctx).map(|_| ())
+    }
+
+    fn authorize_with_reason(
+        &self,
+        _run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        let kind = action.kind();
+        if let Some(denied) = self.denied_kinds.iter().find(|glob| glob.matches(&kind)) {
+            return Err(KernelError::Policy(format!(
+                "action kind '{}' denied by pattern '{}'",
+                kind, denied.pattern
+            )));
+        }
+        if let Some(executor) = ctx
+            .executor
+            .as_ref()
+            .filter(|executor| self.allowed_executors.contains(*executor))
+        {
+            return Ok(Some(format!("allowed executor '{}'", executor)));
+        }
+        let listed = match action {
+            Action::CallTool { tool, .. } if self.allowed_tools.contains(tool) => {
+                return Ok(Some(format!("allowed tool '{}'", tool)));
             }
-            Action::CallLLM { provider, .. } => {
-                if self.allowed_providers.contains(provider) {
-                    Ok(())
-                } else {
-                    Err(KernelError::Policy(format!(
-                        "provider not allowed: {}",
-                        provider
-                    )))
-                }
+            Action::CallLLM { provider, .. } if self.allowed_providers.contains(provider) => {
+                return Ok(Some(format!("allowed provider '{}'", provider)));
             }
-            Action::Sleep { .. } | Action::WaitSignal { .. } => Ok(()),
+            Action::Sleep { .. } | Action::WaitSignal { .. } => return Ok(None),
+            Action::CallTool { tool, .. } => format!("tool not allowed: {}", tool),
+            Action::CallLLM { provider, .. } => format!("provider not allowed: {}", provider),
+        };
+        match self.allowed_kinds.iter().find(|glob| glob.matches(&kind)) {
+            Some(allowed) => Ok(Some(format!("allowed by pattern '{}'", allowed.pattern))),
+            None => Err(KernelError::Policy(listed)),
         }
     }
 }
 
 /// Policy that returns RetryAfterMs with exponential backoff (and optional jitter) for the first
 /// max_retries attempts, then Fail. For RateLimited errors with retry_after_ms, uses that value when set.
+///
+/// `Permanent` and `UnknownKind` errors are not retried unless
+/// [with_kind_retries](Self::with_kind_retries) says otherwise, and
+/// [with_max_elapsed](Self::with_max_elapsed) gives up on an action that would otherwise
+/// keep retrying past a deadline.
 pub struct RetryWithBackoffPolicy<P> {
     /// Inner policy used for `authorize` and `budget` delegation.
     pub inner: P,
@@ -167,18 +574,23 @@ pub struct RetryWithBackoffPolicy<P> {
     pub backoff_cap_ms: Option<u64>,
     /// Jitter ratio in [0.0, 1.0]; added randomness to avoid thundering herd.
     pub jitter_ratio: f64,
+    /// Full jitter: each delay is drawn uniformly from zero to the capped backoff, which
+    /// replaces `jitter_ratio`.
+    pub full_jitter: bool,
+    /// Where full jitter draws its random fractions.
+    pub jitter_source: Arc<dyn JitterSource>,
+    /// Fail instead of retrying once the action's attempts and waits, the next wait
+    /// included, would take longer than this.
+    pub max_elapsed: Option<Duration>,
+    /// Retries allowed per error kind, overriding `max_retries` (and the default of none
+    /// for `Permanent` and `UnknownKind`); 0 never retries the kind.
+    pub max_retries_by_kind: HashMap<ActionErrorKind, u32>,
 }
 
 impl<P: Policy> RetryWithBackoffPolicy<P> {
     /// New with fixed backoff (no exponent, no jitter). Preserves legacy behavior when backoff_base_ms is the only delay.
     pub fn new(inner: P, max_retries: u32, backoff_ms: u64) -> Self {
-        Self {
-            inner,
-            max_retries,
-            backoff_base_ms: backoff_ms,
-            backoff_cap_ms: None,
-            jitter_ratio: 0.0,
-        }
+        Self::with_exponential_backoff(inner, max_retries, backoff_ms, None, 0.0)
     }
 
     /// Exponential backoff: base * 2^attempt, capped, plus jitter.
@@ -195,6 +607,43 @@ impl<P: Policy> RetryWithBackoffPolicy<P> {
             backoff_base_ms,
             backoff_cap_ms,
             jitter_ratio,
+            full_jitter: false,
+            jitter_source: Arc::new(RandomJitter::default()),
+            max_elapsed: None,
+            max_retries_by_kind: HashMap::new(),
+        }
+    }
+
+    /// Draws each delay uniformly from zero to the capped backoff ("full jitter").
+    pub fn with_full_jitter(mut self) -> Self {
+        self.full_jitter = true;
+        self
+    }
+
+    /// Draws full jitter from `source` instead of [RandomJitter], e.g. a fixed sequence in tests.
+    pub fn with_jitter_source(mut self, source: impl JitterSource + 'static) -> Self {
+        self.jitter_source = Arc::new(source);
+        self
+    }
+
+    /// Gives up on an action whose attempts and waits would take longer than `limit`.
+    pub fn with_max_elapsed(mut self, limit: Duration) -> Self {
+        self.max_elapsed = Some(limit);
+        self
+    }
+
+    /// Retries errors of `kind` at most `max_retries` times; 0 never retries them.
+    pub fn with_kind_retries(mut self, kind: ActionErrorKind, max_retries: u32) -> Self {
+        self.max_retries_by_kind.insert(kind, max_retries);
+        self
+    }
+
+    /// How many times errors of `kind` are retried
+    fn max_retries_for(&self, kind: &ActionErrorKind) -> u32 {
+        match (self.max_retries_by_kind.get(kind), kind) {
+            (Some(max), _) => *max,
+            (None, ActionErrorKind::Permanent | ActionErrorKind::UnknownKind) => 0,
+            (None, _) => self.max_retries,
         }
     }
 
@@ -209,6 +658,9 @@ impl<P: Policy> RetryWithBackoffPolicy<P> {
             Some(cap) => std::cmp::min(exp, cap),
             None => exp,
         };
+        if self.full_jitter {
+            return (capped as f64 * self.jitter_source.fraction().clamp(0.0, 1.0)) as u64;
+        }
         if self.jitter_ratio <= 0.0 {
             return capped;
         }
@@ -219,7 +671,35 @@ impl<P: Policy> RetryWithBackoffPolicy<P> {
     }
 }
 
+/// Random fractions for full jitter ([RetryWithBackoffPolicy::with_full_jitter]).
+pub trait JitterSource: Send + Sync {
+    /// A fraction in `[0, 1)`.
+    fn fraction(&self) -> f64;
+}
+
+/// [JitterSource] drawing from std's randomly keyed hasher, so the kernel needs no RNG crate.
+#[derive(Default)]
+pub struct RandomJitter {
+    keys: std::collections::hash_map::RandomState,
+    draws: std::sync::atomic::AtomicU64,
+}
+
+impl JitterSource for RandomJitter {
+    fn fraction(&self) -> f64 {
+        use std::hash::BuildHasher;
+        let draw = self
+            .draws
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // The top 53 bits fill an f64 mantissa exactly
+        (self.keys.hash_one(draw) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
 impl<P: Policy> Policy for RetryWithBackoffPolicy<P> {
+    fn name(&self) -> String {
+        format!("retry_with_backoff({})", self.inner.name())
+    }
+
     fn authorize(
         &self,
         run_id: &RunId,
@@ -229,32 +709,715 @@ impl<P: Policy> Policy for RetryWithBackoffPolicy<P> {
         self.inner.authorize(run_id, action, ctx)
     }
 
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        self.inner.authorize_with_reason(run_id, action, ctx)
+    }
+
+    /// As [retry_strategy_elapsed](Policy::retry_strategy_elapsed) with no time elapsed.
     fn retry_strategy_attempt(
         &self,
         err: &ActionError,
         action: &Action,
         attempt: u32,
     ) -> RetryDecision {
-        if matches!(err.kind, ActionErrorKind::Permanent) {
+        self.retry_strategy_elapsed(err, action, attempt, Duration::ZERO)
+    }
+
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        _action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        if attempt >= self.max_retries_for(&err.kind) {
             return RetryDecision::Fail;
         }
-        if attempt < self.max_retries {
-            RetryDecision::RetryAfterMs(self.delay_ms(err, attempt))
-        } else {
-            let _ = action;
-            RetryDecision::Fail
+        let delay_ms = self.delay_ms(err, attempt);
+        match self.max_elapsed {
+            Some(limit) if elapsed + Duration::from_millis(delay_ms) > limit => RetryDecision::Fail,
+            _ => RetryDecision::RetryAfterMs(delay_ms),
         }
     }
 
     fn budget(&self) -> BudgetRules {
         self.inner.budget()
     }
+
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        self.inner.check_budget(action, ctx)
+    }
+
+    fn max_parallel_actions(&self) -> usize {
+        self.inner.max_parallel_actions()
+    }
+
+    fn action_timeout(&self, action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        self.inner.action_timeout(action, ctx)
+    }
+
+    fn simulate(&self, action: &Action) -> ActionResult {
+        self.inner.simulate(action)
+    }
+}
+
+/// Policy that limits how long each action attempt may run ([Policy::action_timeout]):
+/// `timeout` by default, or the limit set for the action's executor route
+/// ([PolicyCtx::executor]). Everything else is the inner policy's.
+pub struct TimeoutPolicy<P> {
+    /// Policy used for everything but timeouts.
+    pub inner: P,
+    /// Limit of actions whose route has none of its own; `None` leaves them unlimited.
+    pub timeout: Option<Duration>,
+    /// Limits by executor route (e.g. `http/*`).
+    pub executors: HashMap<String, Duration>,
+}
+
+impl<P: Policy> TimeoutPolicy<P> {
+    /// Limits every action attempt to `timeout`.
+    pub fn new(inner: P, timeout: Duration) -> Self {
+        Self {
+            inner,
+            timeout: Some(timeout),
+            executors: HashMap::new(),
+        }
+    }
+
+    /// Limits only the actions of routes given [with_executor_timeout](Self::with_executor_timeout).
+    pub fn per_executor(inner: P) -> Self {
+        Self {
+            inner,
+            timeout: None,
+            executors: HashMap::new(),
+        }
+    }
+
+    /// Limits the actions routed to `executor` to `timeout` instead.
+    pub fn with_executor_timeout(mut self, executor: impl Into<String>, timeout: Duration) -> Self {
+        self.executors.insert(executor.into(), timeout);
+        self
+    }
+}
+
+impl<P: Policy> Policy for TimeoutPolicy<P> {
+    fn name(&self) -> String {
+        format!("timeout({})", self.inner.name())
+    }
+
+    fn authorize(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<(), KernelError> {
+        self.inner.authorize(run_id, action, ctx)
+    }
+
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        self.inner.authorize_with_reason(run_id, action, ctx)
+    }
+
+    fn retry_strategy(&self, err: &dyn std::fmt::Display, action: &Action) -> RetryDecision {
+        self.inner.retry_strategy(err, action)
+    }
+
+    fn retry_strategy_attempt(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+    ) -> RetryDecision {
+        self.inner.retry_strategy_attempt(err, action, attempt)
+    }
+
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        self.inner
+            .retry_strategy_elapsed(err, action, attempt, elapsed)
+    }
+
+    fn budget(&self) -> BudgetRules {
+        self.inner.budget()
+    }
+
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        self.inner.check_budget(action, ctx)
+    }
+
+    fn max_parallel_actions(&self) -> usize {
+        self.inner.max_parallel_actions()
+    }
+
+    fn action_timeout(&self, _action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        ctx.executor
+            .as_ref()
+            .and_then(|executor| self.executors.get(executor))
+            .copied()
+            .or(self.timeout)
+    }
+
+    fn simulate(&self, action: &Action) -> ActionResult {
+        self.inner.simulate(action)
+    }
+}
+
+/// Policy that simulates actions with declared stub results in
+/// [KernelMode::DryRun](crate::kernel::KernelMode::DryRun), keyed by tool or LLM provider
+/// name; other actions fall back to the inner policy. Everything else is the inner policy's.
+pub struct StubbedPolicy<P> {
+    /// Policy used for everything but the declared stubs.
+    pub inner: P,
+    /// Stub results of `CallTool` actions, by tool name.
+    pub tools: HashMap<String, ActionResult>,
+    /// Stub results of `CallLLM` actions, by provider name.
+    pub providers: HashMap<String, ActionResult>,
+}
+
+impl<P: Policy> StubbedPolicy<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            tools: HashMap::new(),
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Simulates calls of `tool` with `result`.
+    pub fn with_tool_stub(mut self, tool: impl Into<String>, result: ActionResult) -> Self {
+        self.tools.insert(tool.into(), result);
+        self
+    }
+
+    /// Simulates calls of LLM `provider` with `result`.
+    pub fn with_provider_stub(mut self, provider: impl Into<String>, result: ActionResult) -> Self {
+        self.providers.insert(provider.into(), result);
+        self
+    }
+}
+
+impl<P: Policy> Policy for StubbedPolicy<P> {
+    fn name(&self) -> String {
+        format!("stubbed({})", self.inner.name())
+    }
+
+    fn authorize(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<(), KernelError> {
+        self.inner.authorize(run_id, action, ctx)
+    }
+
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        self.inner.authorize_with_reason(run_id, action, ctx)
+    }
+
+    fn retry_strategy(&self, err: &dyn std::fmt::Display, action: &Action) -> RetryDecision {
+        self.inner.retry_strategy(err, action)
+    }
+
+    fn retry_strategy_attempt(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+    ) -> RetryDecision {
+        self.inner.retry_strategy_attempt(err, action, attempt)
+    }
+
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        self.inner
+            .retry_strategy_elapsed(err, action, attempt, elapsed)
+    }
+
+    fn budget(&self) -> BudgetRules {
+        self.inner.budget()
+    }
+
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        self.inner.check_budget(action, ctx)
+    }
+
+    fn max_parallel_actions(&self) -> usize {
+        self.inner.max_parallel_actions()
+    }
+
+    fn action_timeout(&self, action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        self.inner.action_timeout(action, ctx)
+    }
+
+    fn simulate(&self, action: &Action) -> ActionResult {
+        let stub = match action {
+            Action::CallTool { tool, .. } => self.tools.get(tool),
+            Action::CallLLM { provider, .. } => self.providers.get(provider),
+            _ => None,
+        };
+        match stub {
+            Some(result) => result.clone(),
+            None => self.inner.simulate(action),
+        }
+    }
+}
+
+/// How a [CompositePolicy] combines its members
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Combinator {
+    AllOf,
+    AnyOf,
+    FirstMatch,
+}
+
+struct Member {
+    /// Names the member in denials and [PolicyVerdict]s
+    label: String,
+    /// The kinds a `first_match` rule governs; `None` matches every kind
+    rule: Option<KindPattern>,
+    policy: Box<dyn Policy>,
+}
+
+/// What one member of a [CompositePolicy] said about an action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyVerdict {
+    /// The member's label: as set with [CompositePolicy::with_labels], else `policy <n>`
+    /// (1-based) or, for `first_match`, the rule's pattern.
+    pub policy: String,
+    /// Why the member denied the action; `None` when it allowed it.
+    pub denial: Option<String>,
+    /// Why the member allowed the action, if it said
+    /// ([authorize_with_reason](Policy::authorize_with_reason)).
+    pub reason: Option<String>,
+}
+
+impl PolicyVerdict {
+    pub fn is_allowed(&self) -> bool {
+        self.denial.is_none()
+    }
+}
+
+/// Several policies acting as one, so an allowlist, a budget and a rate limit can be
+/// layered under the kernel's single policy. Members are ordinary policies, composites
+/// included.
+///
+/// - [all_of](Self::all_of): an action is allowed only if every member allows it. Retry
+///   decisions, timeouts, parallelism and budgets take the most restrictive member's
+///   (`Fail` over any retry, a longer backoff over a shorter one, the lowest limits).
+/// - [any_of](Self::any_of): an action is allowed if some member allows it, and the
+///   least restrictive member's decisions apply.
+/// - [first_match](Self::first_match): the first rule whose pattern matches the action's
+///   [kind](Action::kind) governs it entirely; actions no rule matches are denied.
+///
+/// For `all_of` and `any_of` the order of the members changes neither decision; it only
+/// orders the denials a combined error lists, and picks whose [simulate](Policy::simulate)
+/// runs (the first member's). A combined denial is a [KernelError::Policy] naming each
+/// member that denied and why; [verdicts](Self::verdicts) returns them one by one.
+pub struct CompositePolicy {
+    combinator: Combinator,
+    members: Vec<Member>,
+}
+
+impl CompositePolicy {
+    /// Allows what every one of `policies` allows; none allows everything.
+    pub fn all_of(policies: Vec<Box<dyn Policy>>) -> Self {
+        Self::positional(Combinator::AllOf, policies)
+    }
+
+    /// Allows what at least one of `policies` allows; none denies everything.
+    pub fn any_of(policies: Vec<Box<dyn Policy>>) -> Self {
+        Self::positional(Combinator::AnyOf, policies)
+    }
+
+    /// Hands each action to the first of `rules` whose pattern matches its kind: an exact
+    /// kind, a `namespace/*` pattern as in
+    /// [ActionExecutorRegistry](crate::kernel::ActionExecutorRegistry), or `*` for every
+    /// kind. Fails with [KernelError::Policy] for an invalid pattern.
+    pub fn first_match(rules: Vec<(&str, Box<dyn Policy>)>) -> Result<Self, KernelError> {
+        let members = rules
+            .into_iter()
+            .map(|(pattern, policy)| {
+                let rule = match pattern {
+                    "*" => None,
+                    _ => Some(KindPattern::parse(pattern).map_err(KernelError::Policy)?),
+                };
+                Ok(Member {
+                    label: pattern.to_string(),
+                    rule,
+                    policy,
+                })
+            })
+            .collect::<Result<_, KernelError>>()?;
+        Ok(Self {
+            combinator: Combinator::FirstMatch,
+            members,
+        })
+    }
+
+    fn positional(combinator: Combinator, policies: Vec<Box<dyn Policy>>) -> Self {
+        let members = policies
+            .into_iter()
+            .enumerate()
+            .map(|(i, policy)| Member {
+                label: format!("policy {}", i + 1),
+                rule: None,
+                policy,
+            })
+            .collect();
+        Self {
+            combinator,
+            members,
+        }
+    }
+
+    /// Names the members, in order, for denials and verdicts.
+    pub fn with_labels(mut self, labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        for (member, label) in self.members.iter_mut().zip(labels) {
+            member.label = label.into();
+        }
+        self
+    }
+
+    /// Each deciding member's verdict on `action`: every member for `all_of` and
+    /// `any_of`, the matching rule (if any) for `first_match`.
+    pub fn verdicts(&self, run_id: &RunId, action: &Action, ctx: &PolicyCtx) -> Vec<PolicyVerdict> {
+        self.deciding(action)
+            .map(
+                |member| match member.policy.authorize_with_reason(run_id, action, ctx) {
+                    Ok(reason) => PolicyVerdict {
+                        policy: member.label.clone(),
+                        denial: None,
+                        reason,
+                    },
+                    Err(e) => PolicyVerdict {
+                        policy: member.label.clone(),
+                        denial: Some(denial_message(&e)),
+                        reason: None,
+                    },
+                },
+            )
+            .collect()
+    }
+
+    /// The members whose decisions about `action` count
+    fn deciding<'a>(&'a self, action: &Action) -> impl Iterator<Item = &'a Member> + 'a {
+        let matched = match self.combinator {
+            Combinator::FirstMatch => {
+                let kind = action.kind();
+                self.members
+                    .iter()
+                    .position(|member| {
+                        member
+                            .rule
+                            .as_ref()
+                            .map_or(true, |rule| rule.matches(&kind))
+                    })
+                    .map_or(0..0, |i| i..i + 1)
+            }
+            Combinator::AllOf | Combinator::AnyOf => 0..self.members.len(),
+        };
+        self.members[matched].iter()
+    }
+
+    /// Combines the members' retry decisions: the most restrictive, or the least for `any_of`
+    fn combine_retries(&self, decisions: impl Iterator<Item = RetryDecision>) -> RetryDecision {
+        let decision = match self.combinator {
+            Combinator::AnyOf => decisions.min_by_key(restrictiveness),
+            Combinator::AllOf | Combinator::FirstMatch => decisions.max_by_key(restrictiveness),
+        };
+        decision.unwrap_or(RetryDecision::Fail)
+    }
+}
+
+/// Orders retry decisions from least to most restrictive
+fn restrictiveness(decision: &RetryDecision) -> (u8, u64) {
+    match decision {
+        RetryDecision::Retry => (0, 0),
+        RetryDecision::RetryAfterMs(ms) => (1, *ms),
+        RetryDecision::Fail => (2, 0),
+    }
+}
+
+/// The reason a policy gave for a denial, without the error's own prefix
+pub(crate) fn denial_message(error: &KernelError) -> String {
+    match error {
+        KernelError::Policy(reason) => reason.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `label: reason` of each denial, joined
+fn list_denials<'a>(denials: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    denials
+        .map(|(label, reason)| format!("{}: {}", label, reason))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The lower limit; `None` (no limit) only when both have none
+fn stricter<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The higher limit; `None` (no limit) when either has none
+fn looser<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b > a { b } else { a }),
+        _ => None,
+    }
+}
+
+impl BudgetRules {
+    /// The budget both `self` and `other` allow: each limit at its lower value.
+    pub(crate) fn strictest(mut self, other: BudgetRules) -> Self {
+        self.max_tool_calls = stricter(self.max_tool_calls, other.max_tool_calls);
+        self.max_llm_tokens = stricter(self.max_llm_tokens, other.max_llm_tokens);
+        self.max_tokens = stricter(self.max_tokens, other.max_tokens);
+        self.max_cost_cents = stricter(self.max_cost_cents, other.max_cost_cents);
+        for (kind, max) in other.max_actions_per_kind {
+            let limit = self.max_actions_per_kind.entry(kind).or_insert(max);
+            *limit = (*limit).min(max);
+        }
+        self
+    }
+
+    /// The budget either `self` or `other` allows: each limit at its higher value, and
+    /// none where either has none.
+    fn loosest(mut self, other: BudgetRules) -> Self {
+        self.max_tool_calls = looser(self.max_tool_calls, other.max_tool_calls);
+        self.max_llm_tokens = looser(self.max_llm_tokens, other.max_llm_tokens);
+        self.max_tokens = looser(self.max_tokens, other.max_tokens);
+        self.max_cost_cents = looser(self.max_cost_cents, other.max_cost_cents);
+        self.max_actions_per_kind = self
+            .max_actions_per_kind
+            .into_iter()
+            .filter_map(|(kind, max)| {
+                let other = other.max_actions_per_kind.get(&kind)?;
+                Some((kind, max.max(*other)))
+            })
+            .collect();
+        self
+    }
+}
+
+impl Policy for CompositePolicy {
+    /// `all_of(...)`, `any_of(...)` or `first_match(...)` of the members' names.
+    fn name(&self) -> String {
+        let combinator = match self.combinator {
+            Combinator::AllOf => "all_of",
+            Combinator::AnyOf => "any_of",
+            Combinator::FirstMatch => "first_match",
+        };
+        let members: Vec<String> = self.members.iter().map(|m| m.policy.name()).collect();
+        format!("{}({})", combinator, members.join(", "))
+    }
+
+    fn authorize(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<(), KernelError> {
+        self.authorize_with_reason(run_id, action, ctx).map(|_| ())
+    }
+
+    /// The allowing members' reasons, each after its label.
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        if self.combinator == Combinator::FirstMatch {
+            let Some(member) = self.deciding(action).next() else {
+                return Err(KernelError::Policy(format!(
+                    "no policy rule matches action kind '{}'",
+                    action.kind()
+                )));
+            };
+            return match member.policy.authorize_with_reason(run_id, action, ctx) {
+                Ok(reason) => Ok(Some(match reason {
+                    Some(reason) => format!("{}: {}", member.label, reason),
+                    None => member.label.clone(),
+                })),
+                Err(e) => Err(KernelError::Policy(format!(
+                    "{}: {}",
+                    member.label,
+                    denial_message(&e)
+                ))),
+            };
+        }
+        let verdicts = self.verdicts(run_id, action, ctx);
+        let denials: Vec<(&str, &str)> = verdicts
+            .iter()
+            .filter_map(|v| Some((v.policy.as_str(), v.denial.as_deref()?)))
+            .collect();
+        let denied = match self.combinator {
+            Combinator::AnyOf => denials.len() == verdicts.len(),
+            _ => !denials.is_empty(),
+        };
+        if !denied {
+            let reasons: Vec<String> = verdicts
+                .iter()
+                .filter_map(|v| Some(format!("{}: {}", v.policy, v.reason.as_deref()?)))
+                .collect();
+            return Ok(Some(reasons.join("; ")).filter(|reasons| !reasons.is_empty()));
+        }
+        if verdicts.is_empty() {
+            return Err(KernelError::Policy("no policy allows the action".into()));
+        }
+        Err(KernelError::Policy(format!(
+            "denied by {} of {} policies: {}",
+            denials.len(),
+            verdicts.len(),
+            list_denials(denials.into_iter())
+        )))
+    }
+
+    fn retry_strategy(&self, err: &dyn std::fmt::Display, action: &Action) -> RetryDecision {
+        self.combine_retries(
+            self.deciding(action)
+                .map(|member| member.policy.retry_strategy(err, action)),
+        )
+    }
+
+    fn retry_strategy_attempt(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+    ) -> RetryDecision {
+        self.combine_retries(
+            self.deciding(action)
+                .map(|member| member.policy.retry_strategy_attempt(err, action, attempt)),
+        )
+    }
+
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        self.combine_retries(self.deciding(action).map(|member| {
+            member
+                .policy
+                .retry_strategy_elapsed(err, action, attempt, elapsed)
+        }))
+    }
+
+    /// The strictest of the members' budgets, or the loosest for `any_of`. For
+    /// `first_match` it is only an upper bound: [check_budget](Policy::check_budget)
+    /// applies the matching rule's own.
+    fn budget(&self) -> BudgetRules {
+        let mut budgets = self.members.iter().map(|member| member.policy.budget());
+        match self.combinator {
+            Combinator::AnyOf => budgets
+                .next()
+                .map(|first| budgets.fold(first, BudgetRules::loosest))
+                .unwrap_or_default(),
+            Combinator::AllOf | Combinator::FirstMatch => {
+                budgets.fold(BudgetRules::default(), BudgetRules::strictest)
+            }
+        }
+    }
+
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        let checked: Vec<(&str, Result<(), BudgetExceeded>)> = self
+            .deciding(action)
+            .map(|member| {
+                (
+                    member.label.as_str(),
+                    member.policy.check_budget(action, ctx),
+                )
+            })
+            .collect();
+        let denials: Vec<(&str, &BudgetExceeded)> = checked
+            .iter()
+            .filter_map(|(label, result)| Some((*label, result.as_ref().err()?)))
+            .collect();
+        let denied = match self.combinator {
+            Combinator::AnyOf => !checked.is_empty() && denials.len() == checked.len(),
+            _ => !denials.is_empty(),
+        };
+        match denials.first() {
+            Some((_, first)) if denied => Err(BudgetExceeded::new(
+                first.code.clone(),
+                list_denials(
+                    denials
+                        .iter()
+                        .map(|(label, exceeded)| (*label, exceeded.reason.as_str())),
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn max_parallel_actions(&self) -> usize {
+        let limits = self
+            .members
+            .iter()
+            .map(|member| member.policy.max_parallel_actions());
+        match self.combinator {
+            Combinator::AnyOf => limits.max(),
+            Combinator::AllOf | Combinator::FirstMatch => limits.min(),
+        }
+        .unwrap_or(DEFAULT_MAX_PARALLEL_ACTIONS)
+    }
+
+    fn action_timeout(&self, action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        let mut timeouts = self
+            .deciding(action)
+            .map(|member| member.policy.action_timeout(action, ctx));
+        match self.combinator {
+            Combinator::AnyOf => timeouts
+                .next()
+                .and_then(|first| timeouts.fold(first, looser)),
+            Combinator::AllOf | Combinator::FirstMatch => timeouts.fold(None, stricter),
+        }
+    }
+
+    fn simulate(&self, action: &Action) -> ActionResult {
+        match self.deciding(action).next() {
+            Some(member) => member.policy.simulate(action),
+            None => ActionResult::Success(simulated_output()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::kernel::action::ActionError;
+    use crate::kernel::stubs::AllowAllPolicy;
 
     #[test]
     fn permanent_error_returns_fail() {
@@ -263,6 +1426,7 @@ mod tests {
         let action = Action::CallTool {
             tool: "t1".into(),
             input: serde_json::json!(null),
+            idempotency_key: None,
         };
         let d = policy.retry_strategy_attempt(&err, &action, 0);
         assert!(matches!(d, RetryDecision::Fail));
@@ -276,6 +1440,7 @@ mod tests {
         let action = Action::CallTool {
             tool: "t1".into(),
             input: serde_json::json!(null),
+            idempotency_key: None,
         };
         assert!(matches!(
             policy.retry_strategy_attempt(&err, &action, 0),
@@ -299,6 +1464,7 @@ mod tests {
         let action = Action::CallLLM {
             provider: "p1".into(),
             input: serde_json::json!(null),
+            idempotency_key: None,
         };
         let d = policy.retry_strategy_attempt(&err, &action, 0);
         assert!(matches!(d, RetryDecision::RetryAfterMs(2500)));
@@ -312,6 +1478,7 @@ mod tests {
         let action = Action::CallTool {
             tool: "t1".into(),
             input: serde_json::json!(null),
+            idempotency_key: None,
         };
         let d0 = policy.retry_strategy_attempt(&err, &action, 0);
         let d1 = policy.retry_strategy_attempt(&err, &action, 1);
@@ -332,4 +1499,582 @@ mod tests {
         assert!(ms1 == 100);
         assert!(ms2 == 200);
     }
+
+    /// Stands in for the driver's clock: each attempt takes `attempt_ms`, and each retry
+    /// waits the delay the policy decided. Returns the decisions until the first `Fail`.
+    fn decisions_over_time(
+        policy: &dyn Policy,
+        err: &ActionError,
+        attempt_ms: u64,
+    ) -> Vec<RetryDecision> {
+        let action = tool("t1");
+        let mut now = Duration::ZERO;
+        let mut decisions = Vec::new();
+        for attempt in 0.. {
+            now += Duration::from_millis(attempt_ms);
+            let decision = policy.retry_strategy_elapsed(err, &action, attempt, now);
+            decisions.push(decision.clone());
+            match decision.delay() {
+                Some(delay) => now += delay,
+                None => break,
+            }
+        }
+        decisions
+    }
+
+    /// Hands out the given fractions in order
+    struct FixedJitter(std::sync::Mutex<Vec<f64>>);
+    impl JitterSource for FixedJitter {
+        fn fraction(&self) -> f64 {
+            self.0.lock().unwrap().remove(0)
+        }
+    }
+
+    #[test]
+    fn full_jitter_draws_each_delay_up_to_the_capped_backoff() {
+        let policy = RetryWithBackoffPolicy::with_exponential_backoff(
+            AllowAllPolicy,
+            3,
+            100,
+            Some(300),
+            0.0,
+        )
+        .with_full_jitter()
+        .with_jitter_source(FixedJitter(vec![0.5, 0.25, 0.999].into()));
+        assert_eq!(
+            decisions_over_time(&policy, &ActionError::transient("blip"), 10),
+            [
+                RetryDecision::RetryAfterMs(50),
+                RetryDecision::RetryAfterMs(50),
+                RetryDecision::RetryAfterMs(299),
+                RetryDecision::Fail,
+            ]
+        );
+
+        let random = RandomJitter::default();
+        let fractions: Vec<f64> = (0..16).map(|_| random.fraction()).collect();
+        assert!(fractions.iter().all(|f| (0.0..1.0).contains(f)));
+        assert!(fractions.iter().any(|f| *f != fractions[0]));
+    }
+
+    #[test]
+    fn max_elapsed_fails_the_retry_whose_wait_would_overrun_it() {
+        let policy =
+            RetryWithBackoffPolicy::with_exponential_backoff(AllowAllPolicy, 10, 100, None, 0.0)
+                .with_max_elapsed(Duration::from_secs(1));
+        // Attempts end at 50, 200, 450 and 900ms; the fourth wait (800ms) would end at 1.7s
+        assert_eq!(
+            decisions_over_time(&policy, &ActionError::transient("blip"), 50),
+            [
+                RetryDecision::RetryAfterMs(100),
+                RetryDecision::RetryAfterMs(200),
+                RetryDecision::RetryAfterMs(400),
+                RetryDecision::Fail,
+            ]
+        );
+        // Without time passing only the wait itself counts
+        let blip = ActionError::transient("blip");
+        assert_eq!(
+            policy.retry_strategy_attempt(&blip, &tool("t1"), 3),
+            RetryDecision::RetryAfterMs(800)
+        );
+        assert_eq!(
+            policy.retry_strategy_attempt(&blip, &tool("t1"), 4),
+            RetryDecision::Fail
+        );
+    }
+
+    #[test]
+    fn kind_rules_override_how_often_each_error_kind_is_retried() {
+        let policy = RetryWithBackoffPolicy::new(AllowAllPolicy, 1, 10)
+            .with_kind_retries(ActionErrorKind::Transient, 0)
+            .with_kind_retries(ActionErrorKind::Timeout, 5);
+        let retries = |err: ActionError| decisions_over_time(&policy, &err, 0).len() - 1;
+        assert_eq!(retries(ActionError::transient("blip")), 0);
+        assert_eq!(retries(ActionError::timeout(Duration::from_secs(1))), 5);
+        assert_eq!(retries(ActionError::permanent("bad input")), 0);
+        assert_eq!(
+            decisions_over_time(&policy, &ActionError::rate_limited("429", 2500), 0),
+            [RetryDecision::RetryAfterMs(2500), RetryDecision::Fail]
+        );
+
+        let lenient = RetryWithBackoffPolicy::new(AllowAllPolicy, 1, 10)
+            .with_kind_retries(ActionErrorKind::Permanent, 2);
+        assert_eq!(
+            decisions_over_time(&lenient, &ActionError::permanent("flaky"), 0).len() - 1,
+            2
+        );
+    }
+
+    #[test]
+    fn timeout_policy_prefers_the_route_limit_and_timeouts_are_retried() {
+        let policy = TimeoutPolicy::new(AllowAllPolicy, Duration::from_secs(30))
+            .with_executor_timeout("http/*", Duration::from_secs(5));
+        let action = Action::CallTool {
+            tool: "http/get".into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        };
+        let routed = PolicyCtx {
+            executor: Some("http/*".into()),
+            ..PolicyCtx::default()
+        };
+        assert_eq!(
+            policy.action_timeout(&action, &routed),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            policy.action_timeout(&action, &PolicyCtx::default()),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            TimeoutPolicy::per_executor(AllowAllPolicy)
+                .action_timeout(&action, &PolicyCtx::default()),
+            None
+        );
+
+        let err = ActionError::timeout(Duration::from_secs(5));
+        assert_eq!(err.kind, ActionErrorKind::Timeout);
+        assert!(matches!(
+            policy.retry_strategy_attempt(&err, &action, 0),
+            RetryDecision::Fail
+        ));
+        let retrying = RetryWithBackoffPolicy::new(policy, 1, 10);
+        assert!(matches!(
+            retrying.retry_strategy_attempt(&err, &action, 0),
+            RetryDecision::RetryAfterMs(10)
+        ));
+    }
+
+    #[test]
+    fn budget_rules_deny_the_actions_their_limits_apply_to() {
+        let tool = |name: &str| Action::CallTool {
+            tool: name.into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        };
+        let llm = Action::CallLLM {
+            provider: "openai".into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        };
+        let mut usage = BudgetUsage::default();
+        usage.record(&tool("search"), Some(&serde_json::json!({"hits": 3})));
+        usage.record(
+            &llm,
+            Some(&serde_json::json!({"_metadata": {"tokens": 50, "cost_cents": 2.5}})),
+        );
+        assert_eq!(
+            (usage.tool_calls, usage.tokens, usage.llm_tokens),
+            (1, 50, 50)
+        );
+        assert_eq!(usage.actions_of_kind("llm/openai"), 1);
+
+        let code = |rules: BudgetRules, action: &Action| {
+            let policy = RetryWithBackoffPolicy::new(BudgetOnly(rules), 0, 0);
+            let ctx = PolicyCtx {
+                usage: usage.clone(),
+                ..PolicyCtx::default()
+            };
+            policy.check_budget(action, &ctx).err().map(|e| e.code)
+        };
+        let llm_tokens = BudgetRules {
+            max_llm_tokens: Some(50),
+            ..BudgetRules::default()
+        };
+        assert_eq!(code(llm_tokens.clone(), &tool("search")), None);
+        assert_eq!(
+            code(llm_tokens, &llm).as_deref(),
+            Some(BUDGET_LLM_TOKENS_EXCEEDED)
+        );
+        let tool_calls = BudgetRules {
+            max_tool_calls: Some(1),
+            ..BudgetRules::default()
+        };
+        assert_eq!(code(tool_calls.clone(), &llm), None);
+        assert_eq!(
+            code(tool_calls, &tool("search")).as_deref(),
+            Some(BUDGET_TOOL_CALLS_EXCEEDED)
+        );
+        let per_kind = BudgetRules {
+            max_actions_per_kind: [("search".to_string(), 2)].into_iter().collect(),
+            ..BudgetRules::default()
+        };
+        assert_eq!(code(per_kind, &tool("search")), None);
+        let cost = BudgetRules {
+            max_cost_cents: Some(2.5),
+            ..BudgetRules::default()
+        };
+        assert_eq!(
+            code(cost, &tool("search")).as_deref(),
+            Some(BUDGET_COST_EXCEEDED)
+        );
+        assert_eq!(code(BudgetRules::default(), &llm), None);
+        assert!(BudgetRules::default().is_unlimited());
+    }
+
+    /// Every ordering of `0..n`
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+        let mut all = Vec::new();
+        for shorter in permutations(n - 1) {
+            for at in 0..=shorter.len() {
+                let mut order = shorter.clone();
+                order.insert(at, n - 1);
+                all.push(order);
+            }
+        }
+        all
+    }
+
+    fn tool(name: &str) -> Action {
+        Action::CallTool {
+            tool: name.into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        }
+    }
+
+    fn llm(provider: &str) -> Action {
+        Action::CallLLM {
+            provider: provider.into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        }
+    }
+
+    #[test]
+    fn all_of_and_any_of_decide_the_same_whatever_the_order_of_their_members() {
+        let members: Vec<fn() -> Box<dyn Policy>> = vec![
+            || Box::new(AllowAllPolicy),
+            || Box::new(AllowListPolicy::tools_only(["search".to_string()])),
+            || Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 3, 100)),
+            || {
+                let tools = ["search".to_string(), "fetch".to_string()];
+                Box::new(RetryWithBackoffPolicy::new(
+                    AllowListPolicy::tools_only(tools),
+                    1,
+                    500,
+                ))
+            },
+            || Box::new(TimeoutPolicy::new(AllowAllPolicy, Duration::from_secs(2))),
+            || Box::new(Denies("llm", RetryDecision::Retry)),
+        ];
+        let actions = [tool("search"), tool("fetch"), tool("shell"), llm("openai")];
+        let failures = [
+            (ActionError::transient("timeout"), 0),
+            (ActionError::transient("timeout"), 1),
+            (ActionError::transient("timeout"), 3),
+            (ActionError::rate_limited("429", 2500), 0),
+            (ActionError::permanent("bad request"), 0),
+        ];
+        let run_id = "run".to_string();
+        let ctx = PolicyCtx::default();
+        let decide = |policy: &CompositePolicy| {
+            actions
+                .iter()
+                .map(|action| {
+                    let retries: Vec<RetryDecision> = failures
+                        .iter()
+                        .map(|(err, attempt)| policy.retry_strategy_attempt(err, action, *attempt))
+                        .collect();
+                    (
+                        policy.authorize(&run_id, action, &ctx).is_ok(),
+                        retries,
+                        policy.action_timeout(action, &ctx),
+                        policy.max_parallel_actions(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let combine = |combinator: fn(Vec<Box<dyn Policy>>) -> CompositePolicy, order: &[usize]| {
+            combinator(order.iter().map(|&i| members[i]()).collect())
+        };
+
+        let in_order: Vec<usize> = (0..members.len()).collect();
+        let all_of = decide(&combine(CompositePolicy::all_of, &in_order));
+        let any_of = decide(&combine(CompositePolicy::any_of, &in_order));
+        for order in permutations(members.len()) {
+            assert_eq!(decide(&combine(CompositePolicy::all_of, &order)), all_of);
+            assert_eq!(decide(&combine(CompositePolicy::any_of, &order)), any_of);
+        }
+
+        // The most restrictive member wins for all_of, the least for any_of
+        let search = &all_of[0];
+        assert!(search.0);
+        assert_eq!(search.1[0], RetryDecision::Fail);
+        assert_eq!(search.2, Some(Duration::from_secs(2)));
+        assert!(!all_of[1].0 && !all_of[3].0);
+        assert!(any_of.iter().all(|(allowed, ..)| *allowed));
+        assert_eq!(any_of[0].1[0], RetryDecision::Retry);
+        assert_eq!(any_of[0].1[4], RetryDecision::Retry);
+        assert_eq!(any_of[0].2, None);
+    }
+
+    #[test]
+    fn a_combined_denial_names_every_policy_that_denied() {
+        let policy = CompositePolicy::all_of(vec![
+            Box::new(AllowListPolicy::tools_only(["search".to_string()])),
+            Box::new(AllowAllPolicy),
+            Box::new(Denies("shell", RetryDecision::Fail)),
+        ])
+        .with_labels(["allowlist", "open", "no-shell"]);
+        let run_id = "run".to_string();
+        let ctx = PolicyCtx::default();
+
+        let verdicts = policy.verdicts(&run_id, &tool("shell"), &ctx);
+        let allowed: Vec<_> = verdicts.iter().map(PolicyVerdict::is_allowed).collect();
+        assert_eq!(allowed, [false, true, false]);
+        let err = policy
+            .authorize(&run_id, &tool("shell"), &ctx)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("denied by 2 of 3 policies"), "{}", err);
+        assert!(
+            err.contains("allowlist: tool not allowed: shell"),
+            "{}",
+            err
+        );
+        assert!(err.contains("no-shell: denies shell"), "{}", err);
+        assert!(!err.contains("open:"), "{}", err);
+
+        let any_of = CompositePolicy::any_of(vec![
+            Box::new(Denies("shell", RetryDecision::Fail)),
+            Box::new(Denies("shell", RetryDecision::Fail)),
+        ]);
+        assert!(any_of.authorize(&run_id, &tool("search"), &ctx).is_ok());
+        let err = any_of
+            .authorize(&run_id, &tool("shell"), &ctx)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("policy 1: denies shell; policy 2: denies shell"),
+            "{}",
+            err
+        );
+        assert!(CompositePolicy::any_of(Vec::new())
+            .authorize(&run_id, &tool("search"), &ctx)
+            .is_err());
+        assert!(CompositePolicy::all_of(Vec::new())
+            .authorize(&run_id, &tool("search"), &ctx)
+            .is_ok());
+    }
+
+    #[test]
+    fn first_match_hands_each_kind_to_the_first_rule_that_matches_it() {
+        let policy = CompositePolicy::first_match(vec![
+            (
+                "llm/*",
+                Box::new(RetryWithBackoffPolicy::new(AllowAllPolicy, 2, 50)),
+            ),
+            ("search", Box::new(Denies("search", RetryDecision::Retry))),
+            (
+                "*",
+                Box::new(AllowListPolicy::tools_only(["search".to_string()])),
+            ),
+        ])
+        .unwrap();
+        let run_id = "run".to_string();
+        let ctx = PolicyCtx::default();
+        let timeout = ActionError::transient("timeout");
+
+        assert!(policy.authorize(&run_id, &llm("openai"), &ctx).is_ok());
+        assert_eq!(
+            policy.retry_strategy_attempt(&timeout, &llm("openai"), 0),
+            RetryDecision::RetryAfterMs(50)
+        );
+        // "search" is denied by its own rule even though the catch-all would allow it
+        let err = policy
+            .authorize(&run_id, &tool("search"), &ctx)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("search: denies search"), "{}", err);
+        assert_eq!(
+            policy.verdicts(&run_id, &tool("search"), &ctx)[0].policy,
+            "search"
+        );
+        assert!(policy.authorize(&run_id, &tool("fetch"), &ctx).is_err());
+        assert_eq!(
+            policy.retry_strategy_attempt(&timeout, &tool("fetch"), 0),
+            RetryDecision::Fail
+        );
+
+        let no_catch_all = CompositePolicy::first_match(vec![(
+            "llm/*",
+            Box::new(AllowAllPolicy) as Box<dyn Policy>,
+        )])
+        .unwrap();
+        let err = no_catch_all
+            .authorize(&run_id, &tool("search"), &ctx)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("no policy rule matches action kind 'search'"),
+            "{}",
+            err
+        );
+        assert!(CompositePolicy::first_match(vec![(
+            "llm/*/x",
+            Box::new(AllowAllPolicy) as Box<dyn Policy>,
+        )])
+        .is_err());
+    }
+
+    #[test]
+    fn composite_budgets_merge_the_member_limits() {
+        let tight = BudgetRules {
+            max_tool_calls: Some(1),
+            max_tokens: Some(500),
+            max_actions_per_kind: [("search".to_string(), 3)].into_iter().collect(),
+            ..BudgetRules::default()
+        };
+        let loose = BudgetRules {
+            max_tool_calls: Some(10),
+            max_cost_cents: Some(5.0),
+            max_actions_per_kind: [("search".to_string(), 5), ("fetch".to_string(), 1)]
+                .into_iter()
+                .collect(),
+            ..BudgetRules::default()
+        };
+        let members = || -> Vec<Box<dyn Policy>> {
+            vec![
+                Box::new(BudgetOnly(tight.clone())),
+                Box::new(BudgetOnly(loose.clone())),
+            ]
+        };
+
+        let strictest = CompositePolicy::all_of(members()).budget();
+        assert_eq!(strictest.max_tool_calls, Some(1));
+        assert_eq!(strictest.max_tokens, Some(500));
+        assert_eq!(strictest.max_cost_cents, Some(5.0));
+        assert_eq!(strictest.max_actions_per_kind.get("search"), Some(&3));
+        assert_eq!(strictest.max_actions_per_kind.get("fetch"), Some(&1));
+        let loosest = CompositePolicy::any_of(members()).budget();
+        assert_eq!(loosest.max_tool_calls, Some(10));
+        assert_eq!((loosest.max_tokens, loosest.max_cost_cents), (None, None));
+        assert_eq!(loosest.max_actions_per_kind.get("search"), Some(&5));
+        assert_eq!(loosest.max_actions_per_kind.get("fetch"), None);
+
+        let mut usage = BudgetUsage::default();
+        usage.record(&tool("search"), None);
+        let ctx = PolicyCtx {
+            usage,
+            ..PolicyCtx::default()
+        };
+        let exceeded = CompositePolicy::all_of(members())
+            .check_budget(&tool("search"), &ctx)
+            .unwrap_err();
+        assert_eq!(exceeded.code, BUDGET_TOOL_CALLS_EXCEEDED);
+        assert!(exceeded.reason.starts_with("policy 1: "), "{}", exceeded);
+        assert!(CompositePolicy::any_of(members())
+            .check_budget(&tool("search"), &ctx)
+            .is_ok());
+    }
+
+    #[test]
+    fn allow_list_patterns_report_the_match_and_denials_take_precedence() {
+        let run_id = "run".to_string();
+        let ctx = PolicyCtx::default();
+        let policy = AllowListPolicy::kinds(["http/*", "search/vector?"])
+            .unwrap()
+            .with_denied_kinds(["http/delete*"])
+            .unwrap();
+        let decide = |policy: &AllowListPolicy, action: &Action| {
+            policy
+                .authorize_with_reason(&run_id, action, &ctx)
+                .map_err(|e| denial_message(&e))
+        };
+
+        assert_eq!(
+            decide(&policy, &tool("http/get")),
+            Ok(Some("allowed by pattern 'http/*'".to_string()))
+        );
+        assert!(decide(&policy, &tool("search/vector2")).is_ok());
+        assert_eq!(
+            decide(&policy, &tool("search/vector12")),
+            Err("tool not allowed: search/vector12".to_string())
+        );
+        // The deny pattern overlaps the allowed namespace and wins
+        assert_eq!(
+            decide(&policy, &tool("http/delete_all")),
+            Err("action kind 'http/delete_all' denied by pattern 'http/delete*'".to_string())
+        );
+        assert!(policy
+            .authorize(&run_id, &tool("http/delete"), &ctx)
+            .is_err());
+
+        // Denials also beat listed tools, allowed executor routes, and sleeps
+        let listed = AllowListPolicy::tools_only(["shell".to_string()])
+            .with_executors(["*".to_string()])
+            .with_denied_kinds(["sh?ll", "sleep"])
+            .unwrap();
+        let routed = PolicyCtx {
+            executor: Some("*".into()),
+            ..PolicyCtx::default()
+        };
+        assert!(listed.authorize(&run_id, &tool("shell"), &routed).is_err());
+        assert_eq!(
+            listed
+                .authorize_with_reason(&run_id, &tool("search"), &routed)
+                .unwrap(),
+            Some("allowed executor '*'".to_string())
+        );
+        assert!(listed
+            .authorize(&run_id, &Action::Sleep { millis: 10 }, &ctx)
+            .is_err());
+
+        let all_but_posts = AllowListPolicy::kinds(["*"])
+            .unwrap()
+            .with_denied_kinds(["*/post"])
+            .unwrap();
+        assert!(all_but_posts
+            .authorize(&run_id, &llm("openai"), &ctx)
+            .is_ok());
+        assert!(all_but_posts
+            .authorize(&run_id, &tool("http/post"), &ctx)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_allow_list_patterns_fail_construction_naming_the_pattern() {
+        let error = |result: Result<AllowListPolicy, KernelError>| match result {
+            Err(KernelError::Policy(message)) => message,
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("pattern accepted"),
+        };
+        let message = error(AllowListPolicy::kinds(["http/*", "http/[gp]et"]));
+        assert!(message.contains("'http/[gp]et'"), "{}", message);
+        assert!(error(AllowListPolicy::kinds([""])).contains("empty"));
+        let message =
+            error(AllowListPolicy::kinds(["search"]).and_then(|p| p.with_denied_kinds(["a b"])));
+        assert!(message.contains("'a b'"), "{}", message);
+    }
+
+    /// Denies actions whose kind contains the given text and always answers `retry`
+    struct Denies(&'static str, RetryDecision);
+    impl Policy for Denies {
+        fn authorize(&self, _: &RunId, action: &Action, _: &PolicyCtx) -> Result<(), KernelError> {
+            if action.kind().contains(self.0) {
+                return Err(KernelError::Policy(format!("denies {}", self.0)));
+            }
+            Ok(())
+        }
+
+        fn retry_strategy_attempt(&self, _: &ActionError, _: &Action, _: u32) -> RetryDecision {
+            self.1.clone()
+        }
+    }
+
+    struct BudgetOnly(BudgetRules);
+    impl Policy for BudgetOnly {
+        fn authorize(&self, _: &RunId, _: &Action, _: &PolicyCtx) -> Result<(), KernelError> {
+            Ok(())
+        }
+
+        fn budget(&self) -> BudgetRules {
+            self.0.clone()
+        }
+    }
 }
@@ -0,0 +1,226 @@
+//! Pool and connection settings for the Postgres-backed stores.
+//!
+//! This module is feature-gated behind `kernel-postgres`.
+
+use std::time::Duration;
+
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::Executor;
+
+use crate::kernel::event::KernelError;
+
+/// How a Postgres-backed store connects: pool bounds, timeouts, how its sessions are
+/// labelled, and the schema its tables live in. TLS is chosen in the database url
+/// (`sslmode=require`, ...).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostgresRepositoryConfig {
+    /// Most connections the pool opens; at least 1.
+    pub max_connections: u32,
+    /// Connections the pool keeps open even when idle; at most `max_connections`.
+    pub min_connections: u32,
+    /// Longest a query waits for a free connection before failing.
+    pub acquire_timeout: Duration,
+    /// Server-side limit on each statement, set on every new connection. `None` keeps
+    /// the server's setting.
+    pub statement_timeout: Option<Duration>,
+    /// Reported to the server as `application_name`, e.g. to find the store's sessions
+    /// in `pg_stat_activity`.
+    pub application_name: Option<String>,
+    /// Schema holding the store's tables; letters, digits and underscores only.
+    pub schema: String,
+}
+
+impl PostgresRepositoryConfig {
+    pub const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+    pub const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(30);
+    pub const DEFAULT_SCHEMA: &'static str = "public";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    pub fn with_min_connections(mut self, min_connections: u32) -> Self {
+        self.min_connections = min_connections;
+        self
+    }
+
+    pub fn with_acquire_timeout(mut self, acquire_timeout: Duration) -> Self {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    pub fn with_statement_timeout(mut self, statement_timeout: Duration) -> Self {
+        self.statement_timeout = Some(statement_timeout);
+        self
+    }
+
+    pub fn with_application_name(mut self, application_name: impl Into<String>) -> Self {
+        self.application_name = Some(application_name.into());
+        self
+    }
+
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = schema.into();
+        self
+    }
+
+    /// Fails with `Validation` naming the first setting no pool could work with.
+    pub fn validate(&self) -> Result<(), KernelError> {
+        if self.max_connections == 0 {
+            return Err(KernelError::Validation(
+                "postgres max_connections must be at least 1".to_string(),
+            ));
+        }
+        if self.min_connections > self.max_connections {
+            return Err(KernelError::Validation(format!(
+                "postgres min_connections ({}) exceeds max_connections ({})",
+                self.min_connections, self.max_connections
+            )));
+        }
+        if self.acquire_timeout.is_zero() {
+            return Err(KernelError::Validation(
+                "postgres acquire_timeout must be positive".to_string(),
+            ));
+        }
+        if self.statement_timeout.is_some_and(|t| t.as_millis() == 0) {
+            return Err(KernelError::Validation(
+                "postgres statement_timeout must be at least 1ms; leave it unset for no limit"
+                    .to_string(),
+            ));
+        }
+        if !is_valid_schema_ident(&self.schema) {
+            return Err(KernelError::Validation(format!(
+                "postgres schema {:?} must be non-empty and use only letters, digits and '_'",
+                self.schema
+            )));
+        }
+        Ok(())
+    }
+
+    /// Connect options for `database_url` carrying `application_name`; fails with
+    /// `Validation` when the url does not parse.
+    pub fn connect_options(&self, database_url: &str) -> Result<PgConnectOptions, KernelError> {
+        let options: PgConnectOptions = database_url
+            .parse()
+            .map_err(|e| KernelError::Validation(format!("invalid postgres url: {}", e)))?;
+        Ok(match &self.application_name {
+            Some(name) => options.application_name(name),
+            None => options,
+        })
+    }
+
+    /// Pool options with the configured bounds and timeouts; `statement_timeout` is set
+    /// on each connection as it is opened.
+    pub fn pool_options(&self) -> PgPoolOptions {
+        let options = PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout);
+        match self.statement_timeout {
+            Some(timeout) => {
+                let set = format!("SET statement_timeout = {}", timeout.as_millis());
+                options.after_connect(move |conn, _meta| {
+                    let set = set.clone();
+                    Box::pin(async move {
+                        conn.execute(set.as_str()).await?;
+                        Ok(())
+                    })
+                })
+            }
+            None => options,
+        }
+    }
+}
+
+impl Default for PostgresRepositoryConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: Self::DEFAULT_MAX_CONNECTIONS,
+            min_connections: 0,
+            acquire_timeout: Self::DEFAULT_ACQUIRE_TIMEOUT,
+            statement_timeout: None,
+            application_name: None,
+            schema: Self::DEFAULT_SCHEMA.to_string(),
+        }
+    }
+}
+
+fn is_valid_schema_ident(schema: &str) -> bool {
+    !schema.is_empty()
+        && schema
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        let config = PostgresRepositoryConfig::default();
+        config.validate().expect("default config");
+        assert_eq!(config.max_connections, 5);
+        assert_eq!(config.schema, "public");
+    }
+
+    #[test]
+    fn validate_names_the_bad_setting() {
+        let cases = [
+            (
+                PostgresRepositoryConfig::new().with_max_connections(0),
+                "max_connections",
+            ),
+            (
+                PostgresRepositoryConfig::new()
+                    .with_max_connections(2)
+                    .with_min_connections(3),
+                "min_connections",
+            ),
+            (
+                PostgresRepositoryConfig::new().with_acquire_timeout(Duration::ZERO),
+                "acquire_timeout",
+            ),
+            (
+                PostgresRepositoryConfig::new().with_statement_timeout(Duration::from_micros(10)),
+                "statement_timeout",
+            ),
+            (
+                PostgresRepositoryConfig::new().with_schema("bad-schema\""),
+                "schema",
+            ),
+            (PostgresRepositoryConfig::new().with_schema(""), "schema"),
+        ];
+        for (config, setting) in cases {
+            match config.validate() {
+                Err(KernelError::Validation(msg)) => {
+                    assert!(
+                        msg.contains(setting),
+                        "{:?} should mention {}",
+                        msg,
+                        setting
+                    )
+                }
+                other => panic!("{:?} should be invalid, got {:?}", config, other),
+            }
+        }
+    }
+
+    #[test]
+    fn connect_options_reject_malformed_urls() {
+        let config = PostgresRepositoryConfig::new().with_application_name("oris-test");
+        assert!(matches!(
+            config.connect_options("not a url"),
+            Err(KernelError::Validation(_))
+        ));
+        let options = config
+            .connect_options("postgres://user@localhost:5432/db")
+            .expect("valid url");
+        assert_eq!(options.get_application_name(), Some("oris-test"));
+    }
+}
@@ -13,10 +13,21 @@ use serde::{de::DeserializeOwned, Serialize};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
 #[cfg(feature = "kernel-postgres")]
-use crate::kernel::event::{Event, EventStore, KernelError, SequencedEvent};
+use crate::kernel::event::{
+    Event, EventFilter, EventStore, KernelError, SequencedEvent, CURRENT_EVENT_VERSION,
+};
+#[cfg(feature = "kernel-postgres")]
+use crate::kernel::event_migration::EventMigrator;
+#[cfg(feature = "kernel-postgres")]
+use crate::kernel::event_store::no_event_to_compact;
 #[cfg(feature = "kernel-postgres")]
 use crate::kernel::identity::{RunId, Seq};
 #[cfg(feature = "kernel-postgres")]
+use crate::kernel::ops::{
+    datetime_from_ms, event_filter_sql, sql_bound, PageRequest, RunFilter, RunStatusKind,
+    RunSummary, RUN_STATUS_SQL,
+};
+#[cfg(feature = "kernel-postgres")]
 use crate::kernel::snapshot::{Snapshot, SnapshotStore};
 
 #[cfg(feature = "kernel-postgres")]
@@ -43,6 +54,18 @@ fn new_db_runtime() -> Result<Arc<tokio::runtime::Runtime>, String> {
         .clone()
 }
 
+/// Kind of the event in column `event_json`: unit variants serialize as a string, the
+/// others as an object keyed by the variant name
+#[cfg(feature = "kernel-postgres")]
+const EVENT_KIND_SQL: &str = "CASE WHEN jsonb_typeof(event_json) = 'string'
+    THEN event_json #>> '{}'
+    ELSE (SELECT k FROM jsonb_object_keys(event_json) AS k LIMIT 1) END";
+
+/// Columns [PostgresEventStore::query_events] expects, in order
+#[cfg(feature = "kernel-postgres")]
+const EVENT_COLUMNS: &str =
+    "seq, event_version, event_json, (EXTRACT(EPOCH FROM created_at) * 1000)::BIGINT";
+
 #[cfg(feature = "kernel-postgres")]
 fn map_event_err(prefix: &str, e: impl std::fmt::Display) -> KernelError {
     KernelError::EventStore(format!("{prefix}: {e}"))
@@ -54,6 +77,10 @@ fn map_snapshot_err(prefix: &str, e: impl std::fmt::Display) -> KernelError {
 }
 
 /// Postgres-backed event log store.
+///
+/// Each row records the [CURRENT_EVENT_VERSION] it was written at (rows from before
+/// versions were recorded count as version 1); reads upgrade older rows through the
+/// store's [EventMigrator].
 #[cfg(feature = "kernel-postgres")]
 pub struct PostgresEventStore {
     pool: Option<PgPool>,
@@ -61,6 +88,7 @@ pub struct PostgresEventStore {
     init_error: Option<String>,
     db_runtime: Option<Arc<tokio::runtime::Runtime>>,
     schema_ready: OnceLock<Result<(), String>>,
+    migrator: Arc<EventMigrator>,
 }
 
 #[cfg(feature = "kernel-postgres")]
@@ -89,6 +117,7 @@ impl PostgresEventStore {
             init_error,
             db_runtime,
             schema_ready: OnceLock::new(),
+            migrator: Arc::new(EventMigrator::new()),
         }
     }
 
@@ -99,6 +128,7 @@ impl PostgresEventStore {
             init_error: None,
             db_runtime: new_db_runtime().ok(),
             schema_ready: OnceLock::new(),
+            migrator: Arc::new(EventMigrator::new()),
         }
     }
 
@@ -107,6 +137,12 @@ impl PostgresEventStore {
         self
     }
 
+    /// Upgrades older events with `migrator` instead of the built-in migrations.
+    pub fn with_migrator(mut self, migrator: EventMigrator) -> Self {
+        self.migrator = Arc::new(migrator);
+        self
+    }
+
     fn runtime(&self) -> Result<&tokio::runtime::Runtime, KernelError> {
         if let Some(err) = &self.init_error {
             return Err(map_event_err("postgres init error", err));
@@ -136,15 +172,30 @@ impl PostgresEventStore {
                     seq BIGINT NOT NULL,
                     event_json JSONB NOT NULL,
                     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    event_version INTEGER NOT NULL DEFAULT 1,
                     PRIMARY KEY (run_id, seq)
                 )",
                 schema
             );
+            // Logs created before versions were recorded lack the column; their rows are v1
+            let sql_version = format!(
+                "ALTER TABLE \"{}\".kernel_events
+                 ADD COLUMN IF NOT EXISTS event_version INTEGER NOT NULL DEFAULT 1",
+                schema
+            );
             let sql_idx = format!(
                 "CREATE INDEX IF NOT EXISTS idx_kernel_events_run_created
                  ON \"{}\".kernel_events (run_id, created_at)",
                 schema
             );
+            // Last allocated seq per run; appends lock the run's row to allocate theirs.
+            let sql_heads = format!(
+                "CREATE TABLE IF NOT EXISTS \"{}\".kernel_event_heads (
+                    run_id TEXT PRIMARY KEY,
+                    head BIGINT NOT NULL
+                )",
+                schema
+            );
 
             let pool = match self.pool() {
                 Ok(p) => p.clone(),
@@ -156,10 +207,18 @@ impl PostgresEventStore {
             };
 
             rt.block_on(async {
-                sqlx::query(&sql_schema).execute(&pool).await?;
-                sqlx::query(&sql_events).execute(&pool).await?;
-                sqlx::query(&sql_idx).execute(&pool).await?;
-                Ok::<(), sqlx::Error>(())
+                // Workers starting together would race on the IF NOT EXISTS checks
+                let mut tx = pool.begin().await?;
+                sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                    .bind(format!("oris_kernel_events:{}", schema))
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(&sql_schema).execute(&mut *tx).await?;
+                sqlx::query(&sql_events).execute(&mut *tx).await?;
+                sqlx::query(&sql_version).execute(&mut *tx).await?;
+                sqlx::query(&sql_idx).execute(&mut *tx).await?;
+                sqlx::query(&sql_heads).execute(&mut *tx).await?;
+                tx.commit().await
             })
             .map_err(|e| e.to_string())
         });
@@ -168,6 +227,45 @@ impl PostgresEventStore {
             .clone()
             .map_err(|e| map_event_err("schema bootstrap", e))
     }
+
+    /// Runs `sql`, which selects [EVENT_COLUMNS] and takes the run id and
+    /// one or two integer parameters, and decodes the events
+    fn query_events(
+        &self,
+        sql: String,
+        run_id: &RunId,
+        first: i64,
+        second: Option<i64>,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.ensure_schema()?;
+
+        let pool = self.pool()?.clone();
+        let rt = self.runtime()?;
+        let owned_run_id = run_id.clone();
+
+        let rows = rt.block_on(async move {
+            let mut query =
+                sqlx::query_as::<_, (i64, i32, sqlx::types::Json<serde_json::Value>, i64)>(&sql)
+                    .bind(&owned_run_id)
+                    .bind(first);
+            if let Some(second) = second {
+                query = query.bind(second);
+            }
+            query
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| map_event_err("scan events", e))
+        })?;
+
+        rows.into_iter()
+            .map(|(seq, version, json, created_at_ms)| {
+                Ok(self
+                    .migrator
+                    .decode(run_id, seq as Seq, version as u32, json.0)?
+                    .recorded_at(created_at_ms))
+            })
+            .collect()
+    }
 }
 
 #[cfg(feature = "kernel-postgres")]
@@ -191,49 +289,94 @@ impl EventStore for PostgresEventStore {
                 .await
                 .map_err(|e| map_event_err("begin tx", e))?;
 
-            // Serialize append per run_id to preserve contiguous seq assignment.
-            sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
-                .bind(&run_id)
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| map_event_err("advisory lock", e))?;
-
-            let head_sql = format!(
-                "SELECT COALESCE(MAX(seq), 0) FROM \"{}\".kernel_events WHERE run_id = $1",
+            // Allocate the batch's seqs by bumping the run's head row, which stays locked
+            // until commit: concurrent appenders to the run queue here, and a rollback
+            // releases the seqs with the events. Runs written before the heads table
+            // existed start from their highest stored seq.
+            let alloc_sql = format!(
+                "INSERT INTO \"{0}\".kernel_event_heads AS h (run_id, head)
+                 VALUES ($1, (SELECT COALESCE(MAX(seq), 0) FROM \"{0}\".kernel_events
+                              WHERE run_id = $1) + $2)
+                 ON CONFLICT (run_id) DO UPDATE SET head = h.head + $2
+                 RETURNING head",
                 schema
             );
-            let current_head: i64 = sqlx::query_scalar(&head_sql)
+            let batch = events_to_write.len() as i64;
+            let last_seq: i64 = sqlx::query_scalar(&alloc_sql)
                 .bind(&run_id)
+                .bind(batch)
                 .fetch_one(&mut *tx)
                 .await
-                .map_err(|e| map_event_err("read head", e))?;
+                .map_err(|e| map_event_err("allocate seq", e))?;
 
             let insert_sql = format!(
-                "INSERT INTO \"{}\".kernel_events (run_id, seq, event_json) VALUES ($1, $2, $3)",
+                "INSERT INTO \"{}\".kernel_events (run_id, seq, event_json, event_version)
+                 VALUES ($1, $2, $3, $4)",
                 schema
             );
 
-            let mut last_seq = current_head as Seq;
+            let first_seq = last_seq - batch + 1;
             for (i, event) in events_to_write.iter().enumerate() {
-                let seq = current_head + i as i64 + 1;
                 sqlx::query(&insert_sql)
                     .bind(&run_id)
-                    .bind(seq)
+                    .bind(first_seq + i as i64)
                     .bind(sqlx::types::Json(event))
+                    .bind(CURRENT_EVENT_VERSION as i32)
                     .execute(&mut *tx)
                     .await
                     .map_err(|e| map_event_err("insert event", e))?;
-                last_seq = seq as Seq;
             }
 
             tx.commit()
                 .await
                 .map_err(|e| map_event_err("commit tx", e))?;
-            Ok(last_seq)
+            Ok(last_seq as Seq)
         })
     }
 
     fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.scan_range(run_id, from, Seq::MAX, &EventFilter::all())
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let sql = format!(
+            "SELECT {}
+             FROM \"{}\".kernel_events
+             WHERE run_id = $1 AND seq >= $2 AND seq <= $3{}
+             ORDER BY seq ASC",
+            EVENT_COLUMNS,
+            self.schema,
+            event_filter_sql(EVENT_KIND_SQL, filter)
+        );
+        self.query_events(sql, run_id, sql_bound(from), Some(sql_bound(to)))
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let sql = format!(
+            "SELECT {}
+             FROM \"{}\".kernel_events
+             WHERE run_id = $1{}
+             ORDER BY seq DESC
+             LIMIT $2",
+            EVENT_COLUMNS,
+            self.schema,
+            event_filter_sql(EVENT_KIND_SQL, filter)
+        );
+        self.query_events(sql, run_id, sql_bound(limit), None)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
         self.ensure_schema()?;
 
         let pool = self.pool()?.clone();
@@ -243,54 +386,154 @@ impl EventStore for PostgresEventStore {
 
         rt.block_on(async move {
             let sql = format!(
-                "SELECT seq, event_json
-                 FROM \"{}\".kernel_events
-                 WHERE run_id = $1 AND seq >= $2
-                 ORDER BY seq ASC",
+                "SELECT COALESCE(MAX(seq), 0) FROM \"{}\".kernel_events WHERE run_id = $1",
                 schema
             );
-
-            let rows: Vec<(i64, sqlx::types::Json<Event>)> = sqlx::query_as(&sql)
+            let head: i64 = sqlx::query_scalar(&sql)
                 .bind(&run_id)
-                .bind(from as i64)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| map_event_err("head query", e))?;
+            Ok(head as Seq)
+        })
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.ensure_schema()?;
+
+        let pool = self.pool()?.clone();
+        let rt = self.runtime()?;
+        let schema = self.schema.clone();
+
+        rt.block_on(async move {
+            let sql = format!(
+                "WITH runs AS (
+                    SELECT run_id,
+                           (EXTRACT(EPOCH FROM MIN(created_at)) * 1000)::BIGINT AS first_ms,
+                           (EXTRACT(EPOCH FROM MAX(created_at)) * 1000)::BIGINT AS last_ms,
+                           COUNT(*) AS event_count, MAX(seq) AS head
+                    FROM \"{0}\".kernel_events GROUP BY run_id
+                ), tails AS (
+                    SELECT r.run_id, r.first_ms, r.last_ms, r.event_count, {2} AS kind
+                    FROM runs r
+                    JOIN \"{0}\".kernel_events e ON e.run_id = r.run_id AND e.seq = r.head
+                )
+                SELECT run_id, first_ms, last_ms, event_count, kind
+                FROM (SELECT *, {1} AS status FROM tails) listed
+                WHERE ($1::TEXT IS NULL OR status = $1) AND ($2::BIGINT IS NULL OR first_ms > $2)
+                ORDER BY first_ms, run_id
+                LIMIT $3 OFFSET $4",
+                schema, RUN_STATUS_SQL, EVENT_KIND_SQL
+            );
+
+            let rows: Vec<(String, i64, i64, i64, String)> = sqlx::query_as(&sql)
+                .bind(filter.status.map(|s| s.as_str()))
+                .bind(filter.created_after.map(|t| t.timestamp_millis()))
+                .bind(sql_bound(page.limit))
+                .bind(sql_bound(page.offset))
                 .fetch_all(&pool)
                 .await
-                .map_err(|e| map_event_err("scan events", e))?;
+                .map_err(|e| map_event_err("list runs", e))?;
 
             Ok(rows
                 .into_iter()
-                .map(|(seq, evt)| SequencedEvent {
-                    seq: seq as Seq,
-                    event: evt.0,
-                })
+                .map(
+                    |(run_id, first_ms, last_ms, event_count, kind)| RunSummary {
+                        run_id,
+                        first_event_at: datetime_from_ms(first_ms),
+                        last_event_at: datetime_from_ms(last_ms),
+                        event_count: event_count as u64,
+                        status: RunStatusKind::from_last_event_kind(&kind),
+                        last_event_kind: kind,
+                    },
+                )
                 .collect())
         })
     }
 
-    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
         self.ensure_schema()?;
 
         let pool = self.pool()?.clone();
         let rt = self.runtime()?;
         let schema = self.schema.clone();
         let run_id = run_id.clone();
+        let marker = marker.clone();
+        let up_to = sql_bound(up_to_seq);
 
         rt.block_on(async move {
-            let sql = format!(
-                "SELECT COALESCE(MAX(seq), 0) FROM \"{}\".kernel_events WHERE run_id = $1",
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| map_event_err("begin tx", e))?;
+
+            // The marker keeps the earliest write time, so listings still date the run
+            let replace_sql = format!(
+                "UPDATE \"{0}\".kernel_events
+                 SET event_json = $3,
+                     event_version = $4,
+                     created_at = (SELECT MIN(created_at) FROM \"{0}\".kernel_events
+                                   WHERE run_id = $1 AND seq <= $2)
+                 WHERE run_id = $1 AND seq = $2",
                 schema
             );
-            let head: i64 = sqlx::query_scalar(&sql)
+            let replaced = sqlx::query(&replace_sql)
                 .bind(&run_id)
-                .fetch_one(&pool)
+                .bind(up_to)
+                .bind(sqlx::types::Json(&marker))
+                .bind(CURRENT_EVENT_VERSION as i32)
+                .execute(&mut *tx)
                 .await
-                .map_err(|e| map_event_err("head query", e))?;
-            Ok(head as Seq)
+                .map_err(|e| map_event_err("replace event", e))?
+                .rows_affected();
+            if replaced == 0 {
+                return Err(no_event_to_compact(&run_id, up_to_seq));
+            }
+
+            let delete_sql = format!(
+                "DELETE FROM \"{}\".kernel_events WHERE run_id = $1 AND seq < $2",
+                schema
+            );
+            sqlx::query(&delete_sql)
+                .bind(&run_id)
+                .bind(up_to)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| map_event_err("delete events", e))?;
+
+            tx.commit().await.map_err(|e| map_event_err("commit tx", e))
         })
     }
 }
 
+/// A `kernel_snapshots` row: run, seq, state, state version, event count and content hash
+#[cfg(feature = "kernel-postgres")]
+type SnapshotRow = (
+    String,
+    i64,
+    sqlx::types::Json<serde_json::Value>,
+    i32,
+    i64,
+    Option<String>,
+);
+
 /// Postgres-backed snapshot store.
+///
+/// Snapshots live in a `kernel_snapshots` table keyed by `(run_id, at_seq)`, with the
+/// state as JSONB, its [Snapshot::state_version] and event count, and the
+/// [content hash](Snapshot::compute_content_hash) checked when it is loaded. Every saved
+/// snapshot is kept, so [SnapshotStore::load_at_or_before] can start a replay from an
+/// earlier one, unless [with_retention](Self::with_retention) prunes superseded snapshots
+/// on save.
 #[cfg(feature = "kernel-postgres")]
 pub struct PostgresSnapshotStore<S> {
     pool: Option<PgPool>,
@@ -298,6 +541,7 @@ pub struct PostgresSnapshotStore<S> {
     init_error: Option<String>,
     db_runtime: Option<Arc<tokio::runtime::Runtime>>,
     schema_ready: OnceLock<Result<(), String>>,
+    retention: Option<usize>,
     _state: PhantomData<S>,
 }
 
@@ -327,6 +571,7 @@ impl<S> PostgresSnapshotStore<S> {
             init_error,
             db_runtime,
             schema_ready: OnceLock::new(),
+            retention: None,
             _state: PhantomData,
         }
     }
@@ -338,6 +583,7 @@ impl<S> PostgresSnapshotStore<S> {
             init_error: None,
             db_runtime: new_db_runtime().ok(),
             schema_ready: OnceLock::new(),
+            retention: None,
             _state: PhantomData,
         }
     }
@@ -347,6 +593,13 @@ impl<S> PostgresSnapshotStore<S> {
         self
     }
 
+    /// Keeps only each run's `keep` latest snapshots (at least one), pruning older ones
+    /// whenever a snapshot is saved.
+    pub fn with_retention(mut self, keep: usize) -> Self {
+        self.retention = Some(keep);
+        self
+    }
+
     fn runtime(&self) -> Result<&tokio::runtime::Runtime, KernelError> {
         if let Some(err) = &self.init_error {
             return Err(map_snapshot_err("postgres init error", err));
@@ -376,10 +629,22 @@ impl<S> PostgresSnapshotStore<S> {
                     at_seq BIGINT NOT NULL,
                     state_json JSONB NOT NULL,
                     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    state_version INTEGER NOT NULL DEFAULT 0,
+                    event_count BIGINT NOT NULL DEFAULT 0,
+                    content_hash TEXT,
                     PRIMARY KEY (run_id, at_seq)
                 )",
                 schema
             );
+            // Tables created before versions and hashes were recorded lack the columns;
+            // their rows get version and event count 0 and no hash, so they load unchecked
+            let sql_version = format!(
+                "ALTER TABLE \"{}\".kernel_snapshots
+                 ADD COLUMN IF NOT EXISTS state_version INTEGER NOT NULL DEFAULT 0,
+                 ADD COLUMN IF NOT EXISTS event_count BIGINT NOT NULL DEFAULT 0,
+                 ADD COLUMN IF NOT EXISTS content_hash TEXT",
+                schema
+            );
             let sql_idx = format!(
                 "CREATE INDEX IF NOT EXISTS idx_kernel_snapshots_run_created
                  ON \"{}\".kernel_snapshots (run_id, created_at DESC)",
@@ -398,6 +663,7 @@ impl<S> PostgresSnapshotStore<S> {
             rt.block_on(async {
                 sqlx::query(&sql_schema).execute(&pool).await?;
                 sqlx::query(&sql_snapshots).execute(&pool).await?;
+                sqlx::query(&sql_version).execute(&pool).await?;
                 sqlx::query(&sql_idx).execute(&pool).await?;
                 Ok::<(), sqlx::Error>(())
             })
@@ -411,40 +677,107 @@ impl<S> PostgresSnapshotStore<S> {
 }
 
 #[cfg(feature = "kernel-postgres")]
-impl<S> SnapshotStore<S> for PostgresSnapshotStore<S>
+impl<S> PostgresSnapshotStore<S>
 where
-    S: Clone + Send + Sync + Serialize + DeserializeOwned + Unpin + 'static,
+    S: Serialize + DeserializeOwned + Send + Unpin + 'static,
 {
-    fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
+    /// Latest snapshot of the run with `at_seq <= up_to`, checked against its content hash
+    fn load_up_to(&self, run_id: &RunId, up_to: Seq) -> Result<Option<Snapshot<S>>, KernelError> {
         self.ensure_schema()?;
 
         let pool = self.pool()?.clone();
         let rt = self.runtime()?;
         let schema = self.schema.clone();
         let run_id = run_id.clone();
+        let up_to = up_to.min(i64::MAX as Seq) as i64;
 
-        rt.block_on(async move {
+        let row = rt.block_on(async move {
             let sql = format!(
-                "SELECT run_id, at_seq, state_json
+                "SELECT run_id, at_seq, state_json, state_version, event_count, content_hash
                  FROM \"{}\".kernel_snapshots
-                 WHERE run_id = $1
+                 WHERE run_id = $1 AND at_seq <= $2
                  ORDER BY at_seq DESC
                  LIMIT 1",
                 schema
             );
-            let row: Option<(String, i64, sqlx::types::Json<S>)> = sqlx::query_as(&sql)
+            let row: Option<SnapshotRow> = sqlx::query_as(&sql)
                 .bind(&run_id)
+                .bind(up_to)
                 .fetch_optional(&pool)
                 .await
                 .map_err(|e| map_snapshot_err("load latest snapshot", e))?;
+            Ok::<_, KernelError>(row)
+        })?;
+
+        let Some((run_id, at_seq, state_json, state_version, event_count, content_hash)) = row
+        else {
+            return Ok(None);
+        };
+        let at_seq = at_seq as Seq;
+        let state = match serde_json::from_value(state_json.0) {
+            Ok(state) => state,
+            Err(e) if content_hash.is_some() => {
+                return Err(KernelError::SnapshotIntegrity {
+                    run_id,
+                    at_seq,
+                    reason: format!("state does not decode: {e}"),
+                })
+            }
+            Err(e) => return Err(map_snapshot_err("decode state", e)),
+        };
+        let snapshot = Snapshot {
+            run_id,
+            at_seq,
+            state,
+            state_version: state_version as u32,
+            event_count: event_count as u64,
+            content_hash,
+        };
+        snapshot.verify()?;
+        Ok(Some(snapshot))
+    }
+
+    fn delete_superseded(&self, run_id: &RunId, keep: usize) -> Result<usize, KernelError> {
+        self.ensure_schema()?;
 
-            Ok(row.map(|(run_id, at_seq, state_json)| Snapshot {
-                run_id,
-                at_seq: at_seq as Seq,
-                state: state_json.0,
-            }))
+        let pool = self.pool()?.clone();
+        let rt = self.runtime()?;
+        let schema = self.schema.clone();
+        let run_id = run_id.clone();
+        let keep = keep.max(1) as i64;
+
+        rt.block_on(async move {
+            let sql = format!(
+                "DELETE FROM \"{0}\".kernel_snapshots
+                 WHERE run_id = $1 AND at_seq < (
+                     SELECT MIN(at_seq) FROM (
+                         SELECT at_seq FROM \"{0}\".kernel_snapshots
+                         WHERE run_id = $1
+                         ORDER BY at_seq DESC
+                         LIMIT $2
+                     ) AS kept
+                 )",
+                schema
+            );
+            let result = sqlx::query(&sql)
+                .bind(&run_id)
+                .bind(keep)
+                .execute(&pool)
+                .await
+                .map_err(|e| map_snapshot_err("prune snapshots", e))?;
+            Ok(result.rows_affected() as usize)
         })
     }
+}
+
+#[cfg(feature = "kernel-postgres")]
+impl<S> SnapshotStore<S> for PostgresSnapshotStore<S>
+where
+    S: Clone + Send + Sync + Serialize + DeserializeOwned + Unpin + 'static,
+{
+    fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.load_up_to(run_id, Seq::MAX)
+    }
 
     fn save(&self, snapshot: &Snapshot<S>) -> Result<(), KernelError> {
         self.ensure_schema()?;
@@ -455,24 +788,51 @@ where
         let run_id = snapshot.run_id.clone();
         let at_seq = snapshot.at_seq as i64;
         let state = snapshot.state.clone();
+        let state_version = snapshot.state_version as i32;
+        let event_count = snapshot.event_count as i64;
+        let content_hash = snapshot.compute_content_hash()?;
 
         rt.block_on(async move {
             let sql = format!(
-                "INSERT INTO \"{}\".kernel_snapshots (run_id, at_seq, state_json)
-                 VALUES ($1, $2, $3)
+                "INSERT INTO \"{}\".kernel_snapshots
+                     (run_id, at_seq, state_json, state_version, event_count, content_hash)
+                 VALUES ($1, $2, $3, $4, $5, $6)
                  ON CONFLICT (run_id, at_seq)
-                 DO UPDATE SET state_json = EXCLUDED.state_json, created_at = NOW()",
+                 DO UPDATE SET state_json = EXCLUDED.state_json,
+                               state_version = EXCLUDED.state_version,
+                               event_count = EXCLUDED.event_count,
+                               content_hash = EXCLUDED.content_hash,
+                               created_at = NOW()",
                 schema
             );
             sqlx::query(&sql)
                 .bind(run_id)
                 .bind(at_seq)
                 .bind(sqlx::types::Json(state))
+                .bind(state_version)
+                .bind(event_count)
+                .bind(content_hash)
                 .execute(&pool)
                 .await
                 .map_err(|e| map_snapshot_err("save snapshot", e))?;
-            Ok(())
-        })
+            Ok::<(), KernelError>(())
+        })?;
+        if let Some(keep) = self.retention {
+            self.delete_superseded(&snapshot.run_id, keep)?;
+        }
+        Ok(())
+    }
+
+    fn load_at_or_before(
+        &self,
+        run_id: &RunId,
+        seq: Seq,
+    ) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.load_up_to(run_id, seq)
+    }
+
+    fn prune(&self, run_id: &RunId, keep: usize) -> Result<usize, KernelError> {
+        self.delete_superseded(run_id, keep)
     }
 }
 
@@ -481,7 +841,8 @@ mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     use super::{PostgresEventStore, PostgresSnapshotStore};
-    use crate::kernel::{Event, EventStore, Snapshot, SnapshotStore};
+    use crate::kernel::event_migration::{check_v1_rows_replay, V1_EVENT_ROWS};
+    use crate::kernel::{Event, EventStore, KernelError, Snapshot, SnapshotStore};
 
     fn test_schema() -> String {
         let ts = SystemTime::now()
@@ -495,15 +856,15 @@ mod tests {
         std::env::var("ORIS_TEST_POSTGRES_URL").ok()
     }
 
-    #[tokio::test]
-    async fn postgres_store_construction_is_compile_safe() {
+    #[test]
+    fn postgres_store_construction_is_compile_safe() {
         let _events = PostgresEventStore::new("postgres://localhost/oris").with_schema("oris");
         let _snaps: PostgresSnapshotStore<serde_json::Value> =
             PostgresSnapshotStore::new("postgres://localhost/oris").with_schema("oris");
     }
 
-    #[tokio::test]
-    async fn postgres_event_store_roundtrip_when_env_is_set() {
+    #[test]
+    fn postgres_event_store_roundtrip_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -519,6 +880,8 @@ mod tests {
                 &[Event::StateUpdated {
                     step_id: Some("n1".to_string()),
                     payload: serde_json::json!({"v": 1}),
+                    state_hash: None,
+                    merge_report: None,
                 }],
             )
             .unwrap();
@@ -542,8 +905,141 @@ mod tests {
         assert_eq!(store.head(&run_id).unwrap(), 3);
     }
 
-    #[tokio::test]
-    async fn postgres_snapshot_store_roundtrip_when_env_is_set() {
+    #[test]
+    fn postgres_event_store_contract_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let store = PostgresEventStore::new(db_url).with_schema(test_schema());
+        crate::kernel::event_store::check_event_store_contract(&store);
+    }
+
+    #[test]
+    fn postgres_event_store_upgrades_unversioned_logs_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = format!("{}_v1", test_schema());
+        let run_id = "run-v1".to_string();
+        let store = PostgresEventStore::new(db_url.clone()).with_schema(schema.clone());
+        let pool = store.pool().unwrap().clone();
+        let rt = store.runtime().unwrap();
+
+        // The schema and rows as written before versions were recorded
+        rt.block_on(async {
+            sqlx::query(&format!("CREATE SCHEMA \"{}\"", schema))
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(&format!(
+                "CREATE TABLE \"{}\".kernel_events (
+                    run_id TEXT NOT NULL,
+                    seq BIGINT NOT NULL,
+                    event_json JSONB NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    PRIMARY KEY (run_id, seq)
+                )",
+                schema
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+            for (seq, json) in V1_EVENT_ROWS {
+                sqlx::query(&format!(
+                    "INSERT INTO \"{}\".kernel_events (run_id, seq, event_json)
+                     VALUES ($1, $2, $3::jsonb)",
+                    schema
+                ))
+                .bind(&run_id)
+                .bind(*seq as i64)
+                .bind(*json)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        });
+        check_v1_rows_replay(
+            Box::new(PostgresEventStore::new(db_url).with_schema(schema.clone())),
+            &run_id,
+        );
+
+        // A version this build has no migration from is rejected by number
+        rt.block_on(async {
+            sqlx::query(&format!(
+                "UPDATE \"{}\".kernel_events SET event_version = 9
+                 WHERE run_id = $1 AND seq = 3",
+                schema
+            ))
+            .bind(&run_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        });
+        let err = store.scan(&run_id, 1).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                KernelError::UnsupportedEventVersion {
+                    seq: 3,
+                    version: 9,
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+        assert_eq!(store.scan(&run_id, 4).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn postgres_concurrent_appenders_get_contiguous_seqs_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let schema = test_schema();
+        let run_id = "run-concurrent-append".to_string();
+
+        // Each writer has its own pool, as separate worker processes would
+        let writers: Vec<_> = (0..4)
+            .map(|w| {
+                let store = PostgresEventStore::new(&db_url).with_schema(&schema);
+                let run_id = run_id.clone();
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        let step = |n: u64| Event::StateUpdated {
+                            step_id: Some(format!("w{}-{}", w, n)),
+                            payload: serde_json::json!({}),
+                            state_hash: None,
+                            merge_report: None,
+                        };
+                        store
+                            .append(&run_id, &[step(i * 2), step(i * 2 + 1)])
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let store = PostgresEventStore::new(&db_url).with_schema(&schema);
+        let events = store.scan(&run_id, 1).unwrap();
+        let seqs: Vec<_> = events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, (1..=80).collect::<Vec<_>>());
+        assert_eq!(store.head(&run_id).unwrap(), 80);
+        // Batches are not interleaved
+        let writer = |e: &crate::kernel::SequencedEvent| match &e.event {
+            Event::StateUpdated { step_id, .. } => step_id.clone().unwrap()[..2].to_string(),
+            other => panic!("unexpected event {:?}", other),
+        };
+        for batch in events.chunks(2) {
+            assert_eq!(writer(&batch[0]), writer(&batch[1]));
+        }
+    }
+
+    #[test]
+    fn postgres_snapshot_store_roundtrip_when_env_is_set() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -556,6 +1052,9 @@ mod tests {
                 run_id: run_id.clone(),
                 at_seq: 7,
                 state: serde_json::json!({"k": "v"}),
+                state_version: 1,
+                event_count: 7,
+                content_hash: None,
             })
             .unwrap();
 
@@ -564,12 +1063,43 @@ mod tests {
         assert_eq!(latest.state["k"], "v");
     }
 
+    #[test]
+    fn postgres_snapshot_store_loads_at_or_before_and_prunes_when_env_is_set() {
+        let Some(db_url) = test_db_url() else {
+            return;
+        };
+        let store: PostgresSnapshotStore<serde_json::Value> = PostgresSnapshotStore::new(db_url)
+            .with_schema(test_schema())
+            .with_retention(3);
+        let run_id = "run-snapshot-at-or-before".to_string();
+        for at_seq in [2, 4, 6, 8] {
+            store
+                .save(&Snapshot {
+                    run_id: run_id.clone(),
+                    at_seq,
+                    state: serde_json::json!({ "at": at_seq }),
+                    state_version: 2,
+                    event_count: at_seq,
+                    content_hash: None,
+                })
+                .unwrap();
+        }
+
+        // Retention dropped the snapshot at 2
+        assert!(store.load_at_or_before(&run_id, 3).unwrap().is_none());
+        let at_5 = store.load_at_or_before(&run_id, 5).unwrap().unwrap();
+        assert_eq!((at_5.at_seq, at_5.state_version), (4, 2));
+        assert_eq!(store.prune(&run_id, 1).unwrap(), 2);
+        assert_eq!(store.load_latest(&run_id).unwrap().unwrap().at_seq, 8);
+        assert!(store.load_at_or_before(&run_id, 7).unwrap().is_none());
+    }
+
     // -----------------------------------------------------------------------
     // Issue #374: Postgres parity tests (mirrors SQLite crash-recovery suite)
     // -----------------------------------------------------------------------
 
-    #[tokio::test]
-    async fn postgres_parity_events_survive_reconnect() {
+    #[test]
+    fn postgres_parity_events_survive_reconnect() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -586,14 +1116,20 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n1".into()),
                             payload: serde_json::json!({"v": 1}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n2".into()),
                             payload: serde_json::json!({"v": 2}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n3".into()),
                             payload: serde_json::json!({"v": 3}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                     ],
                 )
@@ -609,8 +1145,8 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn postgres_parity_snapshots_survive_reconnect() {
+    #[test]
+    fn postgres_parity_snapshots_survive_reconnect() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -625,6 +1161,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 5,
                     state: serde_json::json!({"counter": 42}),
+                    state_version: 1,
+                    event_count: 5,
+                    content_hash: None,
                 })
                 .unwrap();
         }
@@ -638,8 +1177,8 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn postgres_parity_replay_from_snapshot_after_reconnect() {
+    #[test]
+    fn postgres_parity_replay_from_snapshot_after_reconnect() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -658,10 +1197,14 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n1".into()),
                             payload: serde_json::json!({"v": 1}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n2".into()),
                             payload: serde_json::json!({"v": 2}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                     ],
                 )
@@ -672,6 +1215,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 2,
                     state: serde_json::json!({"v": 2}),
+                    state_version: 1,
+                    event_count: 2,
+                    content_hash: None,
                 })
                 .unwrap();
 
@@ -682,6 +1228,8 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n3".into()),
                             payload: serde_json::json!({"v": 3}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::Completed,
                     ],
@@ -703,8 +1251,8 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn postgres_parity_multiple_snapshots_latest_wins() {
+    #[test]
+    fn postgres_parity_multiple_snapshots_latest_wins() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -719,6 +1267,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 3,
                     state: serde_json::json!({"v": 3}),
+                    state_version: 1,
+                    event_count: 3,
+                    content_hash: None,
                 })
                 .unwrap();
             store
@@ -726,6 +1277,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 7,
                     state: serde_json::json!({"v": 7}),
+                    state_version: 1,
+                    event_count: 7,
+                    content_hash: None,
                 })
                 .unwrap();
             store
@@ -733,6 +1287,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 5,
                     state: serde_json::json!({"v": 5}),
+                    state_version: 1,
+                    event_count: 5,
+                    content_hash: None,
                 })
                 .unwrap();
         }
@@ -746,8 +1303,8 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn postgres_parity_append_after_reconnect_continues_sequence() {
+    #[test]
+    fn postgres_parity_append_after_reconnect_continues_sequence() {
         let Some(db_url) = test_db_url() else {
             return;
         };
@@ -762,6 +1319,8 @@ mod tests {
                     &[Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::json!({"v": 1}),
+                        state_hash: None,
+                        merge_report: None,
                     }],
                 )
                 .unwrap();
@@ -775,6 +1334,8 @@ mod tests {
                     &[Event::StateUpdated {
                         step_id: Some("n2".into()),
                         payload: serde_json::json!({"v": 2}),
+                        state_hash: None,
+                        merge_report: None,
                     }],
                 )
                 .unwrap();
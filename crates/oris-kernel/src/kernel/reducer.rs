@@ -1,8 +1,16 @@
 //! Reducer: projects events onto state (pure functional semantics).
 //!
 //! Axiom: state is the projection of the event log. Reducer must be deterministic for replay.
+//!
+//! A `StateUpdated` payload is combined with the state key by key through a
+//! [MergeStrategy]; reducers report what they merged as a [MergeReport], which the driver
+//! records on the event.
+
+use std::collections::BTreeMap;
 
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::kernel::event::{Event, SequencedEvent};
 use crate::kernel::state::KernelState;
@@ -12,15 +20,237 @@ use crate::kernel::KernelError;
 pub trait Reducer<S: KernelState>: Send + Sync {
     /// Applies a single sequenced event to the state (in place).
     fn apply(&self, state: &mut S, event: &SequencedEvent) -> Result<(), KernelError>;
+
+    /// Applies the event like [apply](Reducer::apply) and reports how a `StateUpdated`
+    /// payload was merged into the state. The default reports nothing.
+    fn apply_with_report(
+        &self,
+        state: &mut S,
+        event: &SequencedEvent,
+    ) -> Result<Option<MergeReport>, KernelError> {
+        self.apply(state, event).map(|()| None)
+    }
+}
+
+/// How an updated key combines with the value the state holds for it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// The update's value replaces the current one.
+    #[default]
+    LastWriteWins,
+    /// The update's items (or the update itself, if it is not an array) are appended to
+    /// the current array; a current value that is not an array becomes its first item.
+    Append,
+    /// Objects are merged key by key, recursively, with the update winning on other
+    /// values; anything but two objects is replaced.
+    DeepMerge,
+    /// The update may set the key only while it is unset (`null`) or already equal;
+    /// any other value fails the merge with a [KernelError::Conflict].
+    FailOnConflict,
+}
+
+/// A key whose current and updated values had incompatible shapes, and the strategy that
+/// resolved it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// Updated key; keys nested by [MergeStrategy::DeepMerge] are joined with `.`.
+    pub key: String,
+    pub strategy: MergeStrategy,
+    /// JSON type of the current value, e.g. `array`.
+    pub current: String,
+    /// JSON type of the updated value.
+    pub update: String,
+}
+
+/// Keys a `StateUpdated` payload merged into the state, and the conflicts resolved on the
+/// way. Recorded by the driver on the event as `merge_report`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Top-level keys of the update, in the order they were merged.
+    pub merged: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    pub fn is_empty(&self) -> bool {
+        self.merged.is_empty() && self.conflicts.is_empty()
+    }
+}
+
+/// Merge strategy of each state key: a default and per-key overrides.
+///
+/// ```
+/// use oris_kernel::kernel::{MergeRules, MergeStrategy};
+///
+/// let rules = MergeRules::new(MergeStrategy::LastWriteWins)
+///     .with_key("log", MergeStrategy::Append)
+///     .with_key("owner", MergeStrategy::FailOnConflict);
+/// let mut state = serde_json::json!({"log": ["a"], "owner": null, "step": 1});
+/// let report = rules
+///     .merge(&mut state, &serde_json::json!({"log": ["b"], "owner": "ann", "step": 2}))
+///     .unwrap();
+/// assert_eq!(state, serde_json::json!({"log": ["a", "b"], "owner": "ann", "step": 2}));
+/// assert_eq!(report.merged, ["log", "owner", "step"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeRules {
+    pub default: MergeStrategy,
+    pub keys: BTreeMap<String, MergeStrategy>,
+}
+
+impl MergeRules {
+    pub fn new(default: MergeStrategy) -> Self {
+        Self {
+            default,
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Merges `key` with `strategy` instead of the default.
+    pub fn with_key(mut self, key: impl Into<String>, strategy: MergeStrategy) -> Self {
+        self.keys.insert(key.into(), strategy);
+        self
+    }
+
+    pub fn strategy_for(&self, key: &str) -> MergeStrategy {
+        self.keys.get(key).copied().unwrap_or(self.default)
+    }
+
+    /// Merges each key of `update` into `state` with its strategy. A `state` or `update`
+    /// that is not an object is replaced whole, as one conflict under the key `$` when
+    /// their shapes differ.
+    pub fn merge(&self, state: &mut Value, update: &Value) -> Result<MergeReport, KernelError> {
+        let mut report = MergeReport::default();
+        let (Value::Object(fields), Value::Object(updates)) = (&mut *state, update) else {
+            record_shape_conflict(
+                "$",
+                MergeStrategy::LastWriteWins,
+                &*state,
+                update,
+                &mut report,
+            );
+            *state = update.clone();
+            return Ok(report);
+        };
+        for (key, value) in updates {
+            let current = fields.get(key).unwrap_or(&Value::Null);
+            let merged = merge_value(self.strategy_for(key), key, current, value, &mut report)?;
+            fields.insert(key.clone(), merged);
+            report.merged.push(key.clone());
+        }
+        Ok(report)
+    }
+}
+
+/// Combines the value of `key` with its update under `strategy`, recording any conflict
+/// it resolves in `report`.
+pub fn merge_value(
+    strategy: MergeStrategy,
+    key: &str,
+    current: &Value,
+    update: &Value,
+    report: &mut MergeReport,
+) -> Result<Value, KernelError> {
+    match strategy {
+        MergeStrategy::LastWriteWins => {
+            record_shape_conflict(key, strategy, current, update, report);
+            Ok(update.clone())
+        }
+        MergeStrategy::Append => {
+            let mut items = match current {
+                Value::Array(items) => items.clone(),
+                Value::Null => Vec::new(),
+                other => {
+                    record_conflict(key, strategy, other, update, report);
+                    vec![other.clone()]
+                }
+            };
+            match update {
+                Value::Array(new_items) => items.extend(new_items.iter().cloned()),
+                other => items.push(other.clone()),
+            }
+            Ok(Value::Array(items))
+        }
+        MergeStrategy::DeepMerge => match (current, update) {
+            (Value::Object(fields), Value::Object(updates)) => {
+                let mut merged = fields.clone();
+                for (child, value) in updates {
+                    let path = format!("{}.{}", key, child);
+                    let current = fields.get(child).unwrap_or(&Value::Null);
+                    let value = merge_value(strategy, &path, current, value, report)?;
+                    merged.insert(child.clone(), value);
+                }
+                Ok(Value::Object(merged))
+            }
+            _ => {
+                record_shape_conflict(key, strategy, current, update, report);
+                Ok(update.clone())
+            }
+        },
+        MergeStrategy::FailOnConflict => {
+            if current.is_null() || current == update {
+                Ok(update.clone())
+            } else {
+                Err(KernelError::Conflict(format!(
+                    "merge conflict on key '{}': current {} vs update {}",
+                    key, current, update
+                )))
+            }
+        }
+    }
+}
+
+/// Records a conflict when both values are set and of different JSON types.
+fn record_shape_conflict(
+    key: &str,
+    strategy: MergeStrategy,
+    current: &Value,
+    update: &Value,
+    report: &mut MergeReport,
+) {
+    if !current.is_null() && !update.is_null() && json_type(current) != json_type(update) {
+        record_conflict(key, strategy, current, update, report);
+    }
+}
+
+fn record_conflict(
+    key: &str,
+    strategy: MergeStrategy,
+    current: &Value,
+    update: &Value,
+    report: &mut MergeReport,
+) {
+    report.conflicts.push(MergeConflict {
+        key: key.to_string(),
+        strategy,
+        current: json_type(current).to_string(),
+        update: json_type(update).to_string(),
+    });
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
 }
 
 /// Reducer that applies only StateUpdated (deserialize payload → replace state); other events no-op.
 /// Use for replay when the event log was produced by the graph (StateUpdated, Interrupted, Resumed, Completed).
+///
+/// The state is replaced whole; its report lists the payload's keys, with a conflict for
+/// each key whose value changed JSON type.
 pub struct StateUpdatedOnlyReducer;
 
 impl<S> Reducer<S> for StateUpdatedOnlyReducer
 where
-    S: KernelState + DeserializeOwned,
+    S: KernelState + Serialize + DeserializeOwned,
 {
     fn apply(&self, state: &mut S, event: &SequencedEvent) -> Result<(), KernelError> {
         if let Event::StateUpdated { payload, .. } = &event.event {
@@ -30,4 +260,135 @@ where
         }
         Ok(())
     }
+
+    fn apply_with_report(
+        &self,
+        state: &mut S,
+        event: &SequencedEvent,
+    ) -> Result<Option<MergeReport>, KernelError> {
+        let Event::StateUpdated { payload, .. } = &event.event else {
+            return Ok(None);
+        };
+        let mut current =
+            serde_json::to_value(&*state).map_err(|e| KernelError::Reducer(e.to_string()))?;
+        let report = MergeRules::default().merge(&mut current, payload)?;
+        self.apply(state, event)?;
+        Ok(Some(report))
+    }
+}
+
+/// Reducer that merges each `StateUpdated` payload into the state key by key with
+/// [MergeRules], so a payload may carry only the keys it updates; other events no-op.
+pub struct MergingReducer {
+    rules: MergeRules,
+}
+
+impl MergingReducer {
+    pub fn new(rules: MergeRules) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &MergeRules {
+        &self.rules
+    }
+}
+
+impl<S> Reducer<S> for MergingReducer
+where
+    S: KernelState + Serialize + DeserializeOwned,
+{
+    fn apply(&self, state: &mut S, event: &SequencedEvent) -> Result<(), KernelError> {
+        self.apply_with_report(state, event).map(|_| ())
+    }
+
+    fn apply_with_report(
+        &self,
+        state: &mut S,
+        event: &SequencedEvent,
+    ) -> Result<Option<MergeReport>, KernelError> {
+        let Event::StateUpdated { payload, .. } = &event.event else {
+            return Ok(None);
+        };
+        let mut current =
+            serde_json::to_value(&*state).map_err(|e| KernelError::Reducer(e.to_string()))?;
+        let report = self.rules.merge(&mut current, payload)?;
+        *state =
+            serde_json::from_value(current).map_err(|e| KernelError::EventStore(e.to_string()))?;
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strategies_combine_values_and_report_shape_conflicts() {
+        let rules = MergeRules::new(MergeStrategy::LastWriteWins)
+            .with_key("tags", MergeStrategy::Append)
+            .with_key("profile", MergeStrategy::DeepMerge);
+        let mut state = json!({
+            "tags": "first",
+            "profile": {"name": "ann", "langs": ["en"], "age": 30},
+            "count": [1],
+        });
+        let report = rules
+            .merge(
+                &mut state,
+                &json!({
+                    "tags": ["second"],
+                    "profile": {"langs": {"primary": "fr"}, "age": 31},
+                    "count": 2,
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            state,
+            json!({
+                "tags": ["first", "second"],
+                "profile": {"name": "ann", "langs": {"primary": "fr"}, "age": 31},
+                "count": 2,
+            })
+        );
+        assert_eq!(report.merged, ["count", "profile", "tags"]);
+        let conflicts: Vec<_> = report
+            .conflicts
+            .iter()
+            .map(|c| {
+                (
+                    c.key.as_str(),
+                    c.strategy,
+                    c.current.as_str(),
+                    c.update.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                ("count", MergeStrategy::LastWriteWins, "array", "number"),
+                ("profile.langs", MergeStrategy::DeepMerge, "array", "object"),
+                ("tags", MergeStrategy::Append, "string", "array"),
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_on_conflict_names_the_key_and_both_values() {
+        let rules = MergeRules::new(MergeStrategy::FailOnConflict);
+        let mut state = json!({"owner": null, "region": "eu"});
+        rules
+            .merge(&mut state, &json!({"owner": "ann", "region": "eu"}))
+            .unwrap();
+        let err = rules
+            .merge(&mut state, &json!({"owner": "bob"}))
+            .unwrap_err();
+        assert!(matches!(err, KernelError::Conflict(_)), "{:?}", err);
+        assert_eq!(
+            err.to_string(),
+            "merge conflict on key 'owner': current \"ann\" vs update \"bob\""
+        );
+        assert_eq!(state, json!({"owner": "ann", "region": "eu"}));
+    }
 }
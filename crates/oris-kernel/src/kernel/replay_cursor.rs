@@ -142,10 +142,14 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::to_value(&TestState(2)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -171,10 +175,14 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(10)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::to_value(&TestState(20)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -186,6 +194,9 @@ mod tests {
                 run_id: run_id.clone(),
                 at_seq: 1,
                 state: TestState(10),
+                state_version: 1,
+                event_count: 1,
+                content_hash: None,
             })
             .unwrap();
         let cursor = ReplayCursor::<TestState> {
@@ -210,10 +221,14 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::to_value(&TestState(2)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                 ],
             )
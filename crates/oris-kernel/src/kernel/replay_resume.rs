@@ -91,12 +91,12 @@ impl<S: KernelState + PartialEq> ReplayResume<S> {
         // 4. If not already resumed, inject the decision
         if !already_resumed {
             let resume_seq = (replay_events.len() + 1) as Seq;
-            let resume_event = SequencedEvent {
-                seq: resume_seq,
-                event: Event::Resumed {
+            let resume_event = SequencedEvent::new(
+                resume_seq,
+                Event::Resumed {
                     value: decision.value,
                 },
-            };
+            );
             self.reducer.apply(&mut state, &resume_event)?;
             events_replayed += 1;
         }
@@ -155,6 +155,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Interrupted {
                         value: serde_json::json!({"reason": "ask"}),
@@ -194,6 +196,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Interrupted {
                         value: serde_json::json!({}),
@@ -233,6 +237,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(5)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Interrupted {
                         value: serde_json::json!({}),
@@ -4,13 +4,22 @@
 //! - **State hash equality**: verifies event stream hash matches expected.
 //! - **Tool checksum**: hashes all tool calls in the run.
 //! - **Interrupt consistency**: every Interrupt must have a matching Resumed.
+//!
+//! [verify_replay] checks determinism end to end: it re-runs the reducer over a run's log
+//! and compares the state hash after each event with the hash recorded for it (see
+//! [KernelState::state_hash]), returning a [VerificationReport].
+
+use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::kernel::determinism_guard::verify_event_stream_hash;
-use crate::kernel::event::Event;
-use crate::kernel::identity::RunId;
+use crate::kernel::event::{Event, SequencedEvent};
+use crate::kernel::execution_log::ExecutionLog;
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::reducer::Reducer;
+use crate::kernel::state::KernelState;
 use crate::kernel::EventStore;
 use crate::kernel::KernelError;
 
@@ -132,6 +141,121 @@ impl ReplayVerifier {
     }
 }
 
+/// Outcome of [verify_replay], serializable for machine consumption.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub run_id: RunId,
+    /// Events the reducer re-applied.
+    pub replayed: usize,
+    /// Recorded entries with a state hash that were compared.
+    pub checked: usize,
+    /// Compared entries whose recomputed hash matched.
+    pub matched: usize,
+    /// The first entry whose hash differs; comparison stops there.
+    pub first_divergence: Option<ReplayDivergence>,
+}
+
+impl VerificationReport {
+    /// Whether every compared hash matched.
+    pub fn is_deterministic(&self) -> bool {
+        self.first_divergence.is_none()
+    }
+}
+
+/// First entry where replay disagrees with the recorded log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayDivergence {
+    pub seq: Seq,
+    /// Hex state hash recorded for `seq`.
+    pub expected_hash: String,
+    /// Hex hash recomputed by replay; `None` when the log has no event at `seq` or the
+    /// state type computes no hash.
+    pub actual_hash: Option<String>,
+    /// The event at `seq` in the log, if any.
+    pub event: Option<Event>,
+}
+
+/// Replays `run_id` from `initial_state` through `reducer` without executing any actions
+/// and compares each state hash with the one `recorded` holds for the same seq.
+///
+/// `recorded` is typically [scan_execution_log](crate::kernel::scan_execution_log) of the
+/// run, which carries the hashes the driver wrote; entries without a hash are replayed but
+/// not compared. A divergence is reported in the result, not as an error; errors mean the
+/// log could not be read or replayed. A compacted run fails with [KernelError::Compacted]:
+/// use [Kernel::verify_replay](crate::kernel::Kernel::verify_replay), which starts from a
+/// snapshot.
+pub fn verify_replay<S: KernelState>(
+    store: &dyn EventStore,
+    reducer: &dyn Reducer<S>,
+    run_id: &RunId,
+    initial_state: S,
+    recorded: &[ExecutionLog],
+) -> Result<VerificationReport, KernelError> {
+    let sequenced = store.scan(run_id, 1)?;
+    if let Some(Event::Compacted { up_to_seq, .. }) = sequenced.first().map(|se| &se.event) {
+        return Err(KernelError::Compacted {
+            run_id: run_id.clone(),
+            up_to_seq: *up_to_seq,
+        });
+    }
+    verify_sequenced(reducer, run_id, initial_state, sequenced, recorded)
+}
+
+/// Replays `sequenced` onto `state` and compares hashes with `recorded`; entries recorded
+/// before the first replayed event are not compared.
+pub(crate) fn verify_sequenced<S: KernelState>(
+    reducer: &dyn Reducer<S>,
+    run_id: &RunId,
+    mut state: S,
+    sequenced: Vec<SequencedEvent>,
+    recorded: &[ExecutionLog],
+) -> Result<VerificationReport, KernelError> {
+    let start = sequenced.first().map_or(Seq::MAX, |se| se.seq);
+    let mut expected: HashMap<Seq, [u8; 32]> = recorded
+        .iter()
+        .filter(|entry| entry.event_index >= start)
+        .filter_map(|entry| Some((entry.event_index, entry.state_hash?)))
+        .collect();
+    let mut report = VerificationReport {
+        run_id: run_id.clone(),
+        replayed: 0,
+        checked: 0,
+        matched: 0,
+        first_divergence: None,
+    };
+    for se in sequenced {
+        reducer.apply(&mut state, &se)?;
+        report.replayed += 1;
+        let Some(expected_hash) = expected.remove(&se.seq) else {
+            continue;
+        };
+        report.checked += 1;
+        let actual_hash = state.state_hash()?;
+        if actual_hash == Some(expected_hash) {
+            report.matched += 1;
+        } else {
+            report.first_divergence = Some(ReplayDivergence {
+                seq: se.seq,
+                expected_hash: hex::encode(expected_hash),
+                actual_hash: actual_hash.map(hex::encode),
+                event: Some(se.event),
+            });
+            return Ok(report);
+        }
+    }
+    // Hashes recorded for events the log no longer holds
+    if let Some((&seq, hash)) = expected.iter().min_by_key(|(seq, _)| **seq) {
+        report.checked += 1;
+        report.first_divergence = Some(ReplayDivergence {
+            seq,
+            expected_hash: hex::encode(hash),
+            actual_hash: None,
+            event: None,
+        });
+    }
+    Ok(report)
+}
+
 /// Computes SHA-256 of all tool calls in the run.
 fn compute_tool_checksum(store: &dyn EventStore, run_id: &RunId) -> Result<String, KernelError> {
     let events = store.scan(run_id, 1)?;
@@ -289,6 +413,97 @@ mod tests {
         assert!(result.is_ok(), "expected Ok, got {:?}", result);
     }
 
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    struct Count(u64);
+    impl KernelState for Count {
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+            crate::kernel::canonical::canonical_state_hash(self).map(Some)
+        }
+    }
+
+    /// Replays like StateUpdatedOnlyReducer, but drifts from seq `from` on.
+    struct DriftingReducer {
+        from: Seq,
+    }
+    impl Reducer<Count> for DriftingReducer {
+        fn apply(&self, state: &mut Count, se: &SequencedEvent) -> Result<(), KernelError> {
+            crate::kernel::StateUpdatedOnlyReducer.apply(state, se)?;
+            if se.seq >= self.from {
+                state.0 += 1;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn verify_replay_reports_the_first_divergence() {
+        let store = InMemoryEventStore::new();
+        let run_id: RunId = "verify-replay".into();
+        let update = |n: u64| Event::StateUpdated {
+            step_id: Some(format!("n{}", n)),
+            payload: serde_json::json!(n),
+            state_hash: None,
+            merge_report: None,
+        };
+        store
+            .append(
+                &run_id,
+                &[update(1), update(2), update(3), Event::Completed],
+            )
+            .unwrap();
+        let recorded = crate::kernel::build_execution_log_with_hashes(
+            &store,
+            &crate::kernel::StateUpdatedOnlyReducer,
+            &run_id,
+            Count(0),
+        )
+        .unwrap();
+
+        let faithful = DriftingReducer { from: Seq::MAX };
+        let report = verify_replay(&store, &faithful, &run_id, Count(0), &recorded).unwrap();
+        assert!(report.is_deterministic());
+        assert_eq!((report.replayed, report.checked, report.matched), (4, 4, 4));
+
+        let report = verify_replay(
+            &store,
+            &DriftingReducer { from: 2 },
+            &run_id,
+            Count(0),
+            &recorded,
+        )
+        .unwrap();
+        assert!(!report.is_deterministic());
+        assert_eq!((report.replayed, report.checked, report.matched), (2, 2, 1));
+        let divergence = report.first_divergence.as_ref().unwrap();
+        assert_eq!(divergence.seq, 2);
+        assert_eq!(
+            divergence.expected_hash,
+            hex::encode(recorded[1].state_hash.unwrap())
+        );
+        assert_ne!(
+            divergence.actual_hash.as_deref(),
+            Some(divergence.expected_hash.as_str())
+        );
+        assert!(matches!(
+            divergence.event,
+            Some(Event::StateUpdated { ref step_id, .. }) if step_id.as_deref() == Some("n2")
+        ));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["first_divergence"]["seq"], 2);
+
+        // A log missing recorded events diverges at the first missing seq
+        let truncated = InMemoryEventStore::new();
+        truncated.append(&run_id, &[update(1)]).unwrap();
+        let report = verify_replay(&truncated, &faithful, &run_id, Count(0), &recorded).unwrap();
+        let divergence = report.first_divergence.unwrap();
+        assert_eq!((divergence.seq, report.matched), (2, 1));
+        assert!(divergence.actual_hash.is_none() && divergence.event.is_none());
+    }
+
     #[test]
     fn tool_checksum_returns_same_for_same_calls() {
         let store = InMemoryEventStore::new();
@@ -0,0 +1,439 @@
+//! Risk tiers: actions classified by how hard their effects are to undo, and governed per tier.
+//!
+//! A [RiskTierRegistry] maps action kind patterns to a [RiskTier]. A [TieredPolicy] hands
+//! each action to the policy of its tier, which sees the tier in [PolicyCtx::risk_tier], so
+//! read-only actions can be allowed freely while writes get a budget and irreversible
+//! actions are denied unless a policy explicitly allows them.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::kernel::action::{Action, ActionError, ActionResult};
+use crate::kernel::identity::RunId;
+use crate::kernel::policy::{
+    denial_message, simulated_output, BudgetExceeded, BudgetRules, KindGlob, Policy, PolicyCtx,
+    RetryDecision, DEFAULT_MAX_PARALLEL_ACTIONS,
+};
+use crate::kernel::stubs::AllowAllPolicy;
+use crate::kernel::KernelError;
+
+/// How hard an action's effects are to undo, from least to most risky.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskTier {
+    /// Reads only (searches, fetches); safe to run and repeat.
+    ReadOnly,
+    /// Writes that can be undone (drafts, files under version control).
+    ReversibleWrite,
+    /// Effects that cannot be taken back (payments, sent messages, deletions).
+    Irreversible,
+}
+
+impl RiskTier {
+    /// Every tier, from least to most risky.
+    pub const ALL: [RiskTier; 3] = [
+        RiskTier::ReadOnly,
+        RiskTier::ReversibleWrite,
+        RiskTier::Irreversible,
+    ];
+
+    /// The serialized name, e.g. `"read_only"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskTier::ReadOnly => "read_only",
+            RiskTier::ReversibleWrite => "reversible_write",
+            RiskTier::Irreversible => "irreversible",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Maps [action kinds](Action::kind) to risk tiers. Rules are kind globs as in
+/// [AllowListPolicy::with_allowed_kinds](crate::kernel::AllowListPolicy::with_allowed_kinds);
+/// the first matching rule decides, so list specific patterns first. Kinds no rule matches
+/// get the fallback tier, `Irreversible` unless set. `Sleep` and `WaitSignal` have no
+/// external effect and are always `ReadOnly`.
+///
+/// ```rust
+/// use oris_kernel::{RiskTier, RiskTierRegistry};
+///
+/// let tiers = RiskTierRegistry::new()
+///     .classify("http/get", RiskTier::ReadOnly)?
+///     .classify("http/*", RiskTier::ReversibleWrite)?;
+/// assert_eq!(tiers.tier_of_kind("http/get"), RiskTier::ReadOnly);
+/// assert_eq!(tiers.tier_of_kind("http/post"), RiskTier::ReversibleWrite);
+/// assert_eq!(tiers.tier_of_kind("payments/send"), RiskTier::Irreversible);
+/// # Ok::<(), oris_kernel::KernelError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct RiskTierRegistry {
+    rules: Vec<(KindGlob, RiskTier)>,
+    fallback: RiskTier,
+}
+
+impl Default for RiskTierRegistry {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback: RiskTier::Irreversible,
+        }
+    }
+}
+
+impl RiskTierRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts the kinds `pattern` matches, and no earlier rule does, in `tier`. Fails with
+    /// [KernelError::Policy] naming an invalid pattern.
+    pub fn classify(mut self, pattern: &str, tier: RiskTier) -> Result<Self, KernelError> {
+        self.rules.push((KindGlob::parse(pattern)?, tier));
+        Ok(self)
+    }
+
+    /// The tier of kinds no rule matches.
+    pub fn with_fallback(mut self, tier: RiskTier) -> Self {
+        self.fallback = tier;
+        self
+    }
+
+    /// The tier of `action`.
+    pub fn tier_of(&self, action: &Action) -> RiskTier {
+        match action {
+            Action::Sleep { .. } | Action::WaitSignal { .. } => RiskTier::ReadOnly,
+            Action::CallTool { .. } | Action::CallLLM { .. } => self.tier_of_kind(&action.kind()),
+        }
+    }
+
+    /// The tier of actions of `kind`.
+    pub fn tier_of_kind(&self, kind: &str) -> RiskTier {
+        self.rules
+            .iter()
+            .find(|(glob, _)| glob.matches(kind))
+            .map_or(self.fallback, |(_, tier)| *tier)
+    }
+}
+
+/// Governs each action with the policy of its [RiskTier], as classified by a
+/// [RiskTierRegistry]. The tier's policy sees the tier in [PolicyCtx::risk_tier] and decides
+/// everything about the action: authorization, retries, its budget check and timeout.
+///
+/// By default `ReadOnly` and `ReversibleWrite` actions are allowed ([AllowAllPolicy]) and
+/// `Irreversible` ones are denied; [with_policy](Self::with_policy) sets a tier's policy.
+/// Denials name the tier, e.g. `irreversible tier: no policy allows 'payments/send'`.
+pub struct TieredPolicy {
+    registry: RiskTierRegistry,
+    policies: BTreeMap<RiskTier, Box<dyn Policy>>,
+}
+
+impl TieredPolicy {
+    pub fn new(registry: RiskTierRegistry) -> Self {
+        let mut policies: BTreeMap<RiskTier, Box<dyn Policy>> = BTreeMap::new();
+        policies.insert(RiskTier::ReadOnly, Box::new(AllowAllPolicy));
+        policies.insert(RiskTier::ReversibleWrite, Box::new(AllowAllPolicy));
+        Self { registry, policies }
+    }
+
+    /// Governs the actions of `tier` with `policy`.
+    pub fn with_policy(mut self, tier: RiskTier, policy: impl Policy + 'static) -> Self {
+        self.policies.insert(tier, Box::new(policy));
+        self
+    }
+
+    /// Denies every action of `tier`.
+    pub fn deny(mut self, tier: RiskTier) -> Self {
+        self.policies.remove(&tier);
+        self
+    }
+
+    pub fn registry(&self) -> &RiskTierRegistry {
+        &self.registry
+    }
+
+    /// The tier of `action` and its policy, if the tier has one
+    fn governing(&self, action: &Action) -> (RiskTier, Option<&dyn Policy>) {
+        let tier = self.registry.tier_of(action);
+        (tier, self.policies.get(&tier).map(|policy| policy.as_ref()))
+    }
+}
+
+/// `ctx` as the policy of `tier` sees it
+fn tier_ctx(ctx: &PolicyCtx, tier: RiskTier) -> PolicyCtx {
+    PolicyCtx {
+        risk_tier: Some(tier),
+        ..ctx.clone()
+    }
+}
+
+impl Policy for TieredPolicy {
+    /// `tiered(...)` of each tier's policy name, e.g. `tiered(read_only=allow_all, ...)`.
+    fn name(&self) -> String {
+        let tiers: Vec<String> = self
+            .policies
+            .iter()
+            .map(|(tier, policy)| format!("{}={}", tier, policy.name()))
+            .collect();
+        format!("tiered({})", tiers.join(", "))
+    }
+
+    fn authorize(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<(), KernelError> {
+        self.authorize_with_reason(run_id, action, ctx).map(|_| ())
+    }
+
+    /// Names the tier, then the tier policy's reason.
+    fn authorize_with_reason(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        let (tier, policy) = self.governing(action);
+        let Some(policy) = policy else {
+            return Err(KernelError::Policy(format!(
+                "{} tier: no policy allows '{}'",
+                tier,
+                action.kind()
+            )));
+        };
+        match policy.authorize_with_reason(run_id, action, &tier_ctx(ctx, tier)) {
+            Ok(Some(reason)) => Ok(Some(format!("{} tier: {}", tier, reason))),
+            Ok(None) => Ok(Some(format!("{} tier", tier))),
+            Err(e) => Err(KernelError::Policy(format!(
+                "{} tier: {}",
+                tier,
+                denial_message(&e)
+            ))),
+        }
+    }
+
+    fn retry_strategy(&self, err: &dyn std::fmt::Display, action: &Action) -> RetryDecision {
+        match self.governing(action) {
+            (_, Some(policy)) => policy.retry_strategy(err, action),
+            (_, None) => RetryDecision::Fail,
+        }
+    }
+
+    fn retry_strategy_attempt(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+    ) -> RetryDecision {
+        match self.governing(action) {
+            (_, Some(policy)) => policy.retry_strategy_attempt(err, action, attempt),
+            (_, None) => RetryDecision::Fail,
+        }
+    }
+
+    fn retry_strategy_elapsed(
+        &self,
+        err: &ActionError,
+        action: &Action,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision {
+        match self.governing(action) {
+            (_, Some(policy)) => policy.retry_strategy_elapsed(err, action, attempt, elapsed),
+            (_, None) => RetryDecision::Fail,
+        }
+    }
+
+    /// The strictest of the tier policies' budgets; only an upper bound, as
+    /// [check_budget](Policy::check_budget) applies each tier's own.
+    fn budget(&self) -> BudgetRules {
+        self.policies
+            .values()
+            .map(|policy| policy.budget())
+            .fold(BudgetRules::default(), BudgetRules::strictest)
+    }
+
+    /// The tier policy's check against the run's whole usage; a denial names the tier.
+    fn check_budget(&self, action: &Action, ctx: &PolicyCtx) -> Result<(), BudgetExceeded> {
+        let (tier, Some(policy)) = self.governing(action) else {
+            return Ok(());
+        };
+        policy
+            .check_budget(action, &tier_ctx(ctx, tier))
+            .map_err(|exceeded| {
+                BudgetExceeded::new(exceeded.code, format!("{} tier: {}", tier, exceeded.reason))
+            })
+    }
+
+    fn max_parallel_actions(&self) -> usize {
+        self.policies
+            .values()
+            .map(|policy| policy.max_parallel_actions())
+            .min()
+            .unwrap_or(DEFAULT_MAX_PARALLEL_ACTIONS)
+    }
+
+    fn action_timeout(&self, action: &Action, ctx: &PolicyCtx) -> Option<Duration> {
+        let (tier, policy) = self.governing(action);
+        policy?.action_timeout(action, &tier_ctx(ctx, tier))
+    }
+
+    fn simulate(&self, action: &Action) -> ActionResult {
+        match self.governing(action) {
+            (_, Some(policy)) => policy.simulate(action),
+            (_, None) => ActionResult::Success(simulated_output()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::policy::{AllowListPolicy, BudgetUsage, BUDGET_TOOL_CALLS_EXCEEDED};
+
+    fn tool(name: &str) -> Action {
+        Action::CallTool {
+            tool: name.into(),
+            input: serde_json::json!(null),
+            idempotency_key: None,
+        }
+    }
+
+    fn registry() -> RiskTierRegistry {
+        RiskTierRegistry::new()
+            .classify("search/*", RiskTier::ReadOnly)
+            .unwrap()
+            .classify("files/delete", RiskTier::Irreversible)
+            .unwrap()
+            .classify("files/*", RiskTier::ReversibleWrite)
+            .unwrap()
+    }
+
+    #[test]
+    fn the_first_matching_rule_classifies_and_the_rest_fall_back() {
+        let tiers = registry();
+        assert_eq!(tiers.tier_of(&tool("search/web")), RiskTier::ReadOnly);
+        assert_eq!(tiers.tier_of(&tool("files/delete")), RiskTier::Irreversible);
+        assert_eq!(
+            tiers.tier_of(&tool("files/write")),
+            RiskTier::ReversibleWrite
+        );
+        assert_eq!(
+            tiers.tier_of(&tool("payments/send")),
+            RiskTier::Irreversible
+        );
+        assert_eq!(
+            tiers.tier_of(&Action::Sleep { millis: 5 }),
+            RiskTier::ReadOnly
+        );
+        let lenient = registry().with_fallback(RiskTier::ReversibleWrite);
+        assert_eq!(
+            lenient.tier_of(&tool("payments/send")),
+            RiskTier::ReversibleWrite
+        );
+        assert!(RiskTierRegistry::new()
+            .classify("files/[a-z]", RiskTier::ReadOnly)
+            .is_err());
+    }
+
+    #[test]
+    fn irreversible_actions_are_denied_unless_a_policy_allows_them_and_denials_name_the_tier() {
+        let run_id = "run".to_string();
+        let ctx = PolicyCtx::default();
+        let policy = TieredPolicy::new(registry());
+        assert!(policy.authorize(&run_id, &tool("search/web"), &ctx).is_ok());
+        assert!(policy
+            .authorize(&run_id, &tool("files/write"), &ctx)
+            .is_ok());
+        let err = policy
+            .authorize(&run_id, &tool("files/delete"), &ctx)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("irreversible tier: no policy allows 'files/delete'"),
+            "{}",
+            err
+        );
+
+        let policy = TieredPolicy::new(registry())
+            .with_policy(
+                RiskTier::Irreversible,
+                AllowListPolicy::kinds(["files/delete"]).unwrap(),
+            )
+            .deny(RiskTier::ReversibleWrite);
+        assert_eq!(
+            policy
+                .authorize_with_reason(&run_id, &tool("files/delete"), &ctx)
+                .unwrap(),
+            Some("irreversible tier: allowed by pattern 'files/delete'".to_string())
+        );
+        assert!(policy
+            .authorize(&run_id, &tool("payments/send"), &ctx)
+            .unwrap_err()
+            .to_string()
+            .contains("irreversible tier: tool not allowed: payments/send"));
+        assert!(policy
+            .authorize(&run_id, &tool("files/write"), &ctx)
+            .unwrap_err()
+            .to_string()
+            .contains("reversible_write tier"));
+    }
+
+    #[test]
+    fn each_tier_applies_its_own_budget_and_sees_its_tier() {
+        let policy = TieredPolicy::new(registry()).with_policy(
+            RiskTier::ReversibleWrite,
+            TierBudget(BudgetRules {
+                max_tool_calls: Some(2),
+                ..BudgetRules::default()
+            }),
+        );
+        assert_eq!(policy.budget().max_tool_calls, Some(2));
+
+        let mut usage = BudgetUsage::default();
+        usage.record(&tool("search/web"), None);
+        usage.record(&tool("files/write"), None);
+        let ctx = PolicyCtx {
+            usage,
+            ..PolicyCtx::default()
+        };
+        assert!(policy.check_budget(&tool("search/web"), &ctx).is_ok());
+        let exceeded = policy.check_budget(&tool("files/write"), &ctx).unwrap_err();
+        assert_eq!(exceeded.code, BUDGET_TOOL_CALLS_EXCEEDED);
+        assert!(exceeded.reason.starts_with("reversible_write tier: "));
+
+        let run_id = "run".to_string();
+        let sees_tier = TieredPolicy::new(registry())
+            .with_policy(RiskTier::Irreversible, TierBudget(BudgetRules::default()));
+        assert!(sees_tier
+            .authorize(&run_id, &tool("files/write"), &PolicyCtx::default())
+            .is_ok());
+        assert!(sees_tier
+            .authorize(&run_id, &tool("files/delete"), &PolicyCtx::default())
+            .unwrap_err()
+            .to_string()
+            .contains("irreversible tier: unexpected tier Some(Irreversible)"));
+    }
+
+    /// Allows only actions of the read-only or reversible-write tiers, with a budget
+    struct TierBudget(BudgetRules);
+    impl Policy for TierBudget {
+        fn authorize(&self, _: &RunId, _: &Action, ctx: &PolicyCtx) -> Result<(), KernelError> {
+            match ctx.risk_tier {
+                Some(RiskTier::ReadOnly | RiskTier::ReversibleWrite) => Ok(()),
+                other => Err(KernelError::Policy(format!("unexpected tier {:?}", other))),
+            }
+        }
+
+        fn budget(&self) -> BudgetRules {
+            self.0.clone()
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! External pause and cancel requests for a run in flight.
+//!
+//! A [RunControl] is shared between whoever drives a run with
+//! [Kernel::run_controlled](crate::kernel::Kernel::run_controlled) and whoever wants to stop
+//! it (usually through a [KernelHandle](crate::kernel::KernelHandle)). The driver looks at
+//! it at every step boundary: a cancel request appends `Cancelled` and ends the run for
+//! good, a pause request appends `Paused` and returns a paused
+//! [RunStatus::Blocked](crate::kernel::RunStatus::Blocked).
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::kernel::event::{Event, KernelError};
+
+/// Pending requests and whether a driver is currently running the run.
+#[derive(Debug, Default)]
+pub(crate) struct ControlState {
+    pub(crate) pause: bool,
+    /// `Some(reason)` once a cancel was requested
+    pub(crate) cancel: Option<Option<String>>,
+    /// A driver is between the start and the end of `run_controlled`
+    pub(crate) running: bool,
+}
+
+impl ControlState {
+    /// The event that stops the run at this boundary, if any; cancel wins over pause.
+    /// Marks the run as no longer running, since the driver stops right after.
+    pub(crate) fn take_stop(&mut self) -> Option<Event> {
+        let event = match (&self.cancel, self.pause) {
+            (Some(reason), _) => Event::Cancelled {
+                reason: reason.clone(),
+            },
+            (None, true) => Event::Paused,
+            (None, false) => return None,
+        };
+        self.running = false;
+        Some(event)
+    }
+}
+
+/// Pause/cancel requests for one run; see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct RunControl {
+    state: Mutex<ControlState>,
+}
+
+impl RunControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the run to pause at its next step boundary.
+    pub fn request_pause(&self) -> Result<(), KernelError> {
+        self.lock()?.pause = true;
+        Ok(())
+    }
+
+    /// Withdraws a pause request that has not taken effect yet.
+    pub fn clear_pause(&self) -> Result<(), KernelError> {
+        self.lock()?.pause = false;
+        Ok(())
+    }
+
+    /// Asks the run to stop for good at its next step boundary.
+    pub fn request_cancel(&self, reason: Option<String>) -> Result<(), KernelError> {
+        self.lock()?.cancel = Some(reason);
+        Ok(())
+    }
+
+    pub fn is_pause_requested(&self) -> Result<bool, KernelError> {
+        Ok(self.lock()?.pause)
+    }
+
+    pub fn is_cancel_requested(&self) -> Result<bool, KernelError> {
+        Ok(self.lock()?.cancel.is_some())
+    }
+
+    /// Whether a driver is running the run right now.
+    pub fn is_running(&self) -> Result<bool, KernelError> {
+        Ok(self.lock()?.running)
+    }
+
+    pub(crate) fn lock(&self) -> Result<MutexGuard<'_, ControlState>, KernelError> {
+        self.state
+            .lock()
+            .map_err(|e| KernelError::Driver(format!("run control poisoned: {}", e)))
+    }
+}
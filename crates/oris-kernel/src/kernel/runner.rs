@@ -5,13 +5,56 @@
 //! Use this instead of calling `kernel.run_until_blocked` directly when
 //! using GraphStepFnAdapter or other step functions that require a runtime.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use crate::kernel::driver::{Kernel, RunStatus, Signal};
-use crate::kernel::identity::RunId;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use crate::kernel::driver::{Kernel, RunStatus, Signal, StepOutcome};
+use crate::kernel::event::Event;
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::replay_verifier::VerificationReport;
+use crate::kernel::run_control::RunControl;
 use crate::kernel::state::KernelState;
 use crate::kernel::KernelError;
 
+/// Builds the runtime step functions block on.
+///
+/// A single-worker multi-thread runtime is used because `Handle::block_on` on a
+/// current-thread runtime cannot drive the timer and IO drivers, which would hang
+/// any step that awaits a timer (e.g. a node timeout or retry backoff).
+fn step_runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+}
+
+/// Progress of [KernelRunner::run_many_async_with_progress], reported as each run finishes.
+#[derive(Debug)]
+pub struct RunManyProgress<'a> {
+    pub run_id: &'a RunId,
+    pub result: &'a Result<RunStatus, KernelError>,
+    /// Runs finished so far, this one included.
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Turns the panic of a run's blocking task into the run's error
+fn run_join_error(run_id: &RunId, error: tokio::task::JoinError) -> KernelError {
+    if !error.is_panic() {
+        return KernelError::Driver(format!("run {} was cancelled", run_id));
+    }
+    let panic = error.into_panic();
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".into());
+    KernelError::Driver(format!("run {} panicked: {}", run_id, message))
+}
+
 /// Runner that executes the kernel with correct runtime handling.
 ///
 /// - **Sync**: Runs the kernel on a dedicated thread with its own Tokio runtime,
@@ -41,10 +84,7 @@ impl<S: KernelState> KernelRunner<S> {
         let run_id = run_id.clone();
         let (tx, rx) = std::sync::mpsc::channel();
         std::thread::spawn(move || {
-            let rt = match tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-            {
+            let rt = match step_runtime() {
                 Ok(rt) => rt,
                 Err(e) => {
                     let _ = tx.send(Err(KernelError::Driver(e.to_string())));
@@ -70,10 +110,7 @@ impl<S: KernelState> KernelRunner<S> {
         let kernel = Arc::clone(&self.kernel);
         let run_id = run_id.clone();
         tokio::task::spawn_blocking(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .map_err(|e| KernelError::Driver(e.to_string()))?;
+            let rt = step_runtime().map_err(|e| KernelError::Driver(e.to_string()))?;
             let _guard = rt.enter();
             kernel.run_until_blocked(&run_id, initial_state)
         })
@@ -81,6 +118,92 @@ impl<S: KernelState> KernelRunner<S> {
         .map_err(|e| KernelError::Driver(e.to_string()))?
     }
 
+    /// Starts the run in the background, inside `spawn_blocking` as in
+    /// run_until_blocked_async, and returns a handle to pause, resume or cancel it. Must
+    /// be called within a Tokio runtime.
+    pub fn spawn(&self, run_id: &RunId, initial_state: S) -> KernelHandle<S> {
+        let handle = KernelHandle {
+            kernel: Arc::clone(&self.kernel),
+            run_id: run_id.clone(),
+            initial_state,
+            control: Arc::new(RunControl::new()),
+            task: Mutex::new(None),
+        };
+        handle.start();
+        handle
+    }
+
+    /// Async batch entry: runs each `(run_id, initial_state)` until blocked/completed, at
+    /// most `max_concurrent` at a time (at least one), each inside `spawn_blocking` as in
+    /// run_until_blocked_async. A panicking run becomes a [KernelError::Driver] for that
+    /// run alone. Results come back in the order of `runs`.
+    ///
+    /// Runs only share the kernel's stores; run ids must be distinct, since two runs with
+    /// the same id would write one log.
+    pub async fn run_many_async(
+        &self,
+        runs: Vec<(RunId, S)>,
+        max_concurrent: usize,
+    ) -> Vec<(RunId, Result<RunStatus, KernelError>)> {
+        self.run_many_async_with_progress(runs, max_concurrent, |_| {})
+            .await
+    }
+
+    /// Same as run_many_async, calling `on_progress` as each run finishes.
+    pub async fn run_many_async_with_progress<F>(
+        &self,
+        runs: Vec<(RunId, S)>,
+        max_concurrent: usize,
+        mut on_progress: F,
+    ) -> Vec<(RunId, Result<RunStatus, KernelError>)>
+    where
+        F: FnMut(RunManyProgress<'_>),
+    {
+        let total = runs.len();
+        let permits = Arc::new(Semaphore::new(max_concurrent.max(1)));
+        let mut pending: FuturesUnordered<_> = runs
+            .into_iter()
+            .enumerate()
+            .map(|(index, (run_id, initial_state))| {
+                let kernel = Arc::clone(&self.kernel);
+                let permits = Arc::clone(&permits);
+                async move {
+                    let result = match permits.acquire_owned().await {
+                        Ok(permit) => {
+                            let blocking_run_id = run_id.clone();
+                            tokio::task::spawn_blocking(move || {
+                                let _permit = permit;
+                                let rt = step_runtime()
+                                    .map_err(|e| KernelError::Driver(e.to_string()))?;
+                                let _guard = rt.enter();
+                                kernel.run_until_blocked(&blocking_run_id, initial_state)
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(run_join_error(&run_id, e)))
+                        }
+                        Err(e) => Err(KernelError::Driver(e.to_string())),
+                    };
+                    (index, run_id, result)
+                }
+            })
+            .collect();
+
+        let mut finished: Vec<Option<(RunId, Result<RunStatus, KernelError>)>> =
+            (0..total).map(|_| None).collect();
+        let mut completed = 0;
+        while let Some((index, run_id, result)) = pending.next().await {
+            completed += 1;
+            on_progress(RunManyProgress {
+                run_id: &run_id,
+                result: &result,
+                completed,
+                total,
+            });
+            finished[index] = Some((run_id, result));
+        }
+        finished.into_iter().flatten().collect()
+    }
+
     /// Sync resume: same as run_until_blocked_sync but after appending a resume event.
     pub fn resume_sync(
         &self,
@@ -92,10 +215,7 @@ impl<S: KernelState> KernelRunner<S> {
         let run_id = run_id.clone();
         let (tx, rx) = std::sync::mpsc::channel();
         std::thread::spawn(move || {
-            let rt = match tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-            {
+            let rt = match step_runtime() {
                 Ok(rt) => rt,
                 Err(e) => {
                     let _ = tx.send(Err(KernelError::Driver(e.to_string())));
@@ -120,16 +240,240 @@ impl<S: KernelState> KernelRunner<S> {
         let kernel = Arc::clone(&self.kernel);
         let run_id = run_id.clone();
         tokio::task::spawn_blocking(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .map_err(|e| KernelError::Driver(e.to_string()))?;
+            let rt = step_runtime().map_err(|e| KernelError::Driver(e.to_string()))?;
             let _guard = rt.enter();
             kernel.resume(&run_id, initial_state, signal)
         })
         .await
         .map_err(|e| KernelError::Driver(e.to_string()))?
     }
+
+    /// Sync resume of one interrupt: resumes the interrupt named by `token` with `value`
+    /// (see [Kernel::resume_with_value]) on a dedicated thread with an internal runtime.
+    pub fn resume_with_value(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        token: &str,
+        value: serde_json::Value,
+    ) -> Result<RunStatus, KernelError> {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = run_id.clone();
+        let token = token.to_string();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = match step_runtime() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = tx.send(Err(KernelError::Driver(e.to_string())));
+                    return;
+                }
+            };
+            let _guard = rt.enter();
+            let result = kernel.resume_with_value(&run_id, initial_state, &token, value);
+            let _ = tx.send(result);
+        });
+        rx.recv()
+            .map_err(|_| KernelError::Driver("runner thread panicked or dropped".into()))?
+    }
+
+    /// Async resume of one interrupt: same as resume_with_value, inside `spawn_blocking`.
+    pub async fn resume_with_value_async(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+        token: &str,
+        value: serde_json::Value,
+    ) -> Result<RunStatus, KernelError> {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = run_id.clone();
+        let token = token.to_string();
+        tokio::task::spawn_blocking(move || {
+            let rt = step_runtime().map_err(|e| KernelError::Driver(e.to_string()))?;
+            let _guard = rt.enter();
+            kernel.resume_with_value(&run_id, initial_state, &token, value)
+        })
+        .await
+        .map_err(|e| KernelError::Driver(e.to_string()))?
+    }
+
+    /// Sync single step, for debugging: takes one step of the run (see
+    /// [Kernel::step_once]) on a dedicated thread with an internal runtime. The run can be
+    /// continued with any of the run or resume methods afterwards.
+    pub fn step_once(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<StepOutcome<S>, KernelError> {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = run_id.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = match step_runtime() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = tx.send(Err(KernelError::Driver(e.to_string())));
+                    return;
+                }
+            };
+            let _guard = rt.enter();
+            let result = kernel.step_once(&run_id, initial_state);
+            let _ = tx.send(result);
+        });
+        rx.recv()
+            .map_err(|_| KernelError::Driver("runner thread panicked or dropped".into()))?
+    }
+
+    /// Async single step: same as step_once, inside `spawn_blocking`.
+    pub async fn step_once_async(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<StepOutcome<S>, KernelError> {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = run_id.clone();
+        tokio::task::spawn_blocking(move || {
+            let rt = step_runtime().map_err(|e| KernelError::Driver(e.to_string()))?;
+            let _guard = rt.enter();
+            kernel.step_once(&run_id, initial_state)
+        })
+        .await
+        .map_err(|e| KernelError::Driver(e.to_string()))?
+    }
+
+    /// State of the run right after the event at `seq` (see [Kernel::state_at]). Only the
+    /// reducer runs, so no runtime is needed.
+    pub fn state_at(&self, run_id: &RunId, seq: Seq, initial_state: S) -> Result<S, KernelError> {
+        self.kernel.state_at(run_id, seq, initial_state)
+    }
+
+    /// Sync verification: replays an existing run and compares recomputed state hashes with
+    /// the recorded ones (see [Kernel::verify_replay]). No step or action is executed, so
+    /// no runtime is needed.
+    pub fn verify_replay_sync(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<VerificationReport, KernelError> {
+        self.kernel.verify_replay(run_id, initial_state)
+    }
+
+    /// Async verification: same as verify_replay_sync, inside `spawn_blocking` since the
+    /// event store may block.
+    pub async fn verify_replay_async(
+        &self,
+        run_id: &RunId,
+        initial_state: S,
+    ) -> Result<VerificationReport, KernelError> {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = run_id.clone();
+        tokio::task::spawn_blocking(move || kernel.verify_replay(&run_id, initial_state))
+            .await
+            .map_err(|e| KernelError::Driver(e.to_string()))?
+    }
+}
+
+/// Control surface of a run started with [KernelRunner::spawn].
+///
+/// Requests take effect at the next step boundary (see [RunControl]). Once the run
+/// completed or was cancelled, they fail with [KernelError::RunEnded].
+pub struct KernelHandle<S: KernelState> {
+    kernel: Arc<Kernel<S>>,
+    run_id: RunId,
+    initial_state: S,
+    control: Arc<RunControl>,
+    task: Mutex<Option<JoinHandle<Result<RunStatus, KernelError>>>>,
+}
+
+impl<S: KernelState> KernelHandle<S> {
+    pub fn run_id(&self) -> &RunId {
+        &self.run_id
+    }
+
+    /// Asks the run to pause: at its next step boundary it appends `Paused` and stops
+    /// with a paused [RunStatus::Blocked].
+    pub fn pause(&self) -> Result<(), KernelError> {
+        self.kernel.ensure_not_ended(&self.run_id)?;
+        self.control.request_pause()
+    }
+
+    /// Continues a paused run: withdraws a pause that has not taken effect yet, or runs
+    /// the run again in the background if it stopped at `Paused`, or at `BudgetExceeded`
+    /// (once the kernel's policy allows more).
+    pub fn resume(&self) -> Result<(), KernelError> {
+        self.kernel.ensure_not_ended(&self.run_id)?;
+        let mut ctl = self.control.lock()?;
+        ctl.pause = false;
+        if ctl.running {
+            return Ok(());
+        }
+        match self.kernel.latest_status_event(&self.run_id)? {
+            Some(Event::Paused | Event::BudgetExceeded { .. }) => {
+                // Marked before the task starts so a second resume does not start another
+                ctl.running = true;
+                drop(ctl);
+                self.start();
+                Ok(())
+            }
+            _ => Err(KernelError::Conflict(format!(
+                "run {} is not paused",
+                self.run_id
+            ))),
+        }
+    }
+
+    /// Cancels the run: a run in flight appends `Cancelled` at its next step boundary,
+    /// or once it blocks; a stopped run gets it appended right away. A run that completes
+    /// before its next boundary stays completed.
+    pub fn cancel(&self, reason: Option<String>) -> Result<(), KernelError> {
+        self.kernel.ensure_not_ended(&self.run_id)?;
+        let mut ctl = self.control.lock()?;
+        if ctl.running {
+            ctl.cancel = Some(reason);
+            return Ok(());
+        }
+        // Under the lock, so a concurrent resume cannot restart the run meanwhile
+        self.kernel.cancel(&self.run_id, reason)
+    }
+
+    /// Waits for the background task and returns how the run stopped. After a resume,
+    /// waits for the task that resume started.
+    pub async fn wait(&self) -> Result<RunStatus, KernelError> {
+        let task = self
+            .task
+            .lock()
+            .map_err(|e| KernelError::Driver(format!("run handle poisoned: {}", e)))?
+            .take();
+        match task {
+            Some(task) => task
+                .await
+                .unwrap_or_else(|e| Err(run_join_error(&self.run_id, e))),
+            None => Err(KernelError::Driver(format!(
+                "run {} has no task to wait for",
+                self.run_id
+            ))),
+        }
+    }
+
+    fn start(&self) {
+        let kernel = Arc::clone(&self.kernel);
+        let run_id = self.run_id.clone();
+        let initial_state = self.initial_state.clone();
+        let control = Arc::clone(&self.control);
+        // Counts as running from now on, so a cancel sent before the task gets going is
+        // left to the driver rather than appended ahead of the run's first event
+        if let Ok(mut ctl) = control.lock() {
+            ctl.running = true;
+        }
+        let task = tokio::task::spawn_blocking(move || {
+            let rt = step_runtime().map_err(|e| KernelError::Driver(e.to_string()))?;
+            let _guard = rt.enter();
+            kernel.run_controlled(&run_id, initial_state, &control)
+        });
+        if let Ok(mut slot) = self.task.lock() {
+            *slot = Some(task);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -156,11 +500,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let runner = KernelRunner::new(kernel);
         let run_id = "runner-sync-test".to_string();
@@ -176,11 +521,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let runner = KernelRunner::new(kernel);
         let run_id = "runner-async-test".to_string();
@@ -197,11 +543,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let runner = KernelRunner::new(kernel);
         let status1 = runner
@@ -216,6 +563,363 @@ mod tests {
         assert!(matches!(status2, RunStatus::Completed));
     }
 
+    #[tokio::test]
+    async fn verify_replay_mode_checks_an_existing_run_without_advancing_it() {
+        use crate::kernel::{Event, EventStore, KernelMode, SharedEventStore, StepFn};
+
+        #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+        struct Hashed(u32);
+        impl KernelState for Hashed {
+            fn version(&self) -> u32 {
+                1
+            }
+
+            fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+                crate::kernel::canonical::canonical_state_hash(self).map(Some)
+            }
+        }
+
+        struct CountToTwo;
+        impl StepFn<Hashed> for CountToTwo {
+            fn next(&self, state: &Hashed) -> Result<crate::kernel::Next, KernelError> {
+                Ok(if state.0 < 2 {
+                    crate::kernel::Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("count".into()),
+                        payload: serde_json::json!(state.0 + 1),
+                        state_hash: None,
+                        merge_report: None,
+                    }])
+                } else {
+                    crate::kernel::Next::Complete
+                })
+            }
+        }
+
+        let store = std::sync::Arc::new(InMemoryEventStore::new());
+        let kernel = |mode| Kernel::<Hashed> {
+            events: Box::new(SharedEventStore(store.clone())),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(CountToTwo),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode,
+            snapshot_policy: None,
+        };
+        let run_id = "runner-verify".to_string();
+        KernelRunner::new(kernel(KernelMode::Normal))
+            .run_until_blocked_async(&run_id, Hashed(0))
+            .await
+            .unwrap();
+        let head = store.head(&run_id).unwrap();
+
+        let verifier = KernelRunner::new(kernel(KernelMode::VerifyReplay));
+        let report = verifier
+            .verify_replay_async(&run_id, Hashed(0))
+            .await
+            .unwrap();
+        assert!(report.is_deterministic(), "{:?}", report);
+        assert_eq!((report.replayed, report.checked, report.matched), (3, 2, 2));
+
+        assert!(verifier
+            .run_until_blocked_async(&run_id, Hashed(0))
+            .await
+            .is_err());
+        assert_eq!(store.head(&run_id).unwrap(), head);
+    }
+
+    #[test]
+    fn stepping_interleaves_with_runs_and_state_at_rebuilds_each_step() {
+        use crate::kernel::{Event, EventStore, Next, SharedEventStore, StepFn};
+
+        struct CountToThree;
+        impl StepFn<TestState> for CountToThree {
+            fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+                Ok(if state.0 < 3 {
+                    Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("count".into()),
+                        payload: serde_json::json!(state.0 + 1),
+                        state_hash: None,
+                        merge_report: None,
+                    }])
+                } else {
+                    Next::Complete
+                })
+            }
+        }
+
+        let store = Arc::new(InMemoryEventStore::new());
+        let runner = KernelRunner::new(Kernel::<TestState> {
+            events: Box::new(SharedEventStore(store.clone())),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(CountToThree),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        });
+        let run_id = "runner-step".to_string();
+
+        for expected in 1..=2 {
+            let outcome = runner.step_once(&run_id, TestState(0)).unwrap();
+            assert!(matches!(outcome.next, Next::Emit(_)));
+            assert_eq!(outcome.events.len(), 1);
+            assert_eq!(outcome.events[0].seq, expected as u64);
+            assert_eq!(outcome.state.0, expected);
+            assert!(outcome.status.is_none());
+        }
+
+        let status = runner
+            .run_until_blocked_sync(&run_id, TestState(0))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let states: Vec<_> = (0..=4)
+            .map(|seq| runner.state_at(&run_id, seq, TestState(0)).unwrap().0)
+            .collect();
+        assert_eq!(states, [0, 1, 2, 3, 3]);
+        assert_eq!(store.head(&run_id).unwrap(), 4);
+
+        let outcome = runner
+            .step_once(&"runner-done".to_string(), TestState(3))
+            .unwrap();
+        assert!(matches!(outcome.next, Next::Complete));
+        assert!(matches!(outcome.status, Some(RunStatus::Completed)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn run_many_async_bounds_concurrency_and_isolates_failures() {
+        use crate::kernel::action::{Action, ActionExecutor, ActionResult};
+        use crate::kernel::{Event, EventStore, Next, SharedEventStore, StepFn};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        const RUNS: usize = 200;
+        const MAX_CONCURRENT: usize = 8;
+
+        /// Counts to 3 with a tool call first; starting at 100 fails, at 200 panics.
+        struct Counter;
+        impl StepFn<TestState> for Counter {
+            fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+                Ok(match state.0 {
+                    0 => Next::Do(Action::CallTool {
+                        tool: "tick".into(),
+                        input: serde_json::json!(null),
+                        idempotency_key: None,
+                    }),
+                    n @ 1..=2 => Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("count".into()),
+                        payload: serde_json::json!(n + 1),
+                        state_hash: None,
+                        merge_report: None,
+                    }]),
+                    100 => Next::Fail("unlucky".into()),
+                    200 => panic!("step blew up"),
+                    _ => Next::Complete,
+                })
+            }
+        }
+
+        /// Tracks how many runs are inside an action at once.
+        struct Tick {
+            active: AtomicUsize,
+            peak: AtomicUsize,
+        }
+        impl ActionExecutor for Arc<Tick> {
+            fn execute(&self, _: &RunId, _: &Action) -> Result<ActionResult, KernelError> {
+                let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+                self.peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(2));
+                self.active.fetch_sub(1, Ordering::SeqCst);
+                Ok(ActionResult::Success(serde_json::json!(1)))
+            }
+        }
+
+        /// Takes the tool output as the new count.
+        struct TickReducer;
+        impl crate::kernel::Reducer<TestState> for TickReducer {
+            fn apply(
+                &self,
+                state: &mut TestState,
+                event: &crate::kernel::SequencedEvent,
+            ) -> Result<(), KernelError> {
+                match &event.event {
+                    Event::StateUpdated { payload, .. }
+                    | Event::ActionSucceeded {
+                        output: payload, ..
+                    } => state.0 = payload.as_u64().unwrap_or_default() as u32,
+                    _ => {}
+                }
+                Ok(())
+            }
+        }
+
+        let store = Arc::new(InMemoryEventStore::new());
+        let tick = Arc::new(Tick {
+            active: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let runner = KernelRunner::new(Kernel::<TestState> {
+            events: Box::new(SharedEventStore(store.clone())),
+            snaps: None,
+            reducer: Box::new(TickReducer),
+            exec: Arc::new(tick.clone()),
+            step: Box::new(Counter),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        });
+        let runs: Vec<_> = (0..RUNS)
+            .map(|i| {
+                let start = match i % 50 {
+                    7 => 100,
+                    13 => 200,
+                    _ => 0,
+                };
+                (format!("many-{}", i), TestState(start))
+            })
+            .collect();
+
+        let progress = Mutex::new(Vec::new());
+        let results = runner
+            .run_many_async_with_progress(runs, MAX_CONCURRENT, |p| {
+                assert_eq!(p.total, RUNS);
+                progress.lock().unwrap().push(p.completed);
+            })
+            .await;
+
+        assert_eq!(*progress.lock().unwrap(), (1..=RUNS).collect::<Vec<_>>());
+        let ids: Vec<_> = results.iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(
+            ids,
+            (0..RUNS).map(|i| format!("many-{}", i)).collect::<Vec<_>>()
+        );
+        let (mut completed, mut failed, mut panicked) = (0, 0, 0);
+        for (run_id, result) in &results {
+            match result {
+                Ok(RunStatus::Completed) => {
+                    completed += 1;
+                    let seqs: Vec<_> = store
+                        .scan(run_id, 1)
+                        .unwrap()
+                        .iter()
+                        .map(|se| se.seq)
+                        .collect();
+                    assert_eq!(seqs, [1, 2, 3, 4, 5, 6], "{}", run_id);
+                }
+                Ok(RunStatus::Failed { recoverable: true }) => failed += 1,
+                Err(KernelError::Driver(message)) => {
+                    assert!(message.contains("step blew up"), "{}", message);
+                    panicked += 1;
+                }
+                other => panic!("unexpected result for {}: {:?}", run_id, other),
+            }
+        }
+        assert_eq!((completed, failed, panicked), (192, 4, 4));
+        let peak = tick.peak.load(Ordering::SeqCst);
+        assert!((2..=MAX_CONCURRENT).contains(&peak), "peak {}", peak);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn spawned_run_pauses_resumes_and_cancels_at_step_boundaries() {
+        use crate::kernel::{Event, EventKind, EventStore, Next, SharedEventStore, StepFn};
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        /// Counts to 3, reporting each step it enters and waiting for a go before finishing it.
+        struct Gated {
+            entered: Mutex<mpsc::Sender<u32>>,
+            go: Mutex<mpsc::Receiver<()>>,
+        }
+        impl StepFn<TestState> for Gated {
+            fn next(&self, state: &TestState) -> Result<Next, KernelError> {
+                self.entered.lock().unwrap().send(state.0).unwrap();
+                self.go
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_secs(5))
+                    .unwrap();
+                Ok(if state.0 < 3 {
+                    Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("count".into()),
+                        payload: serde_json::json!(state.0 + 1),
+                        state_hash: None,
+                        merge_report: None,
+                    }])
+                } else {
+                    Next::Complete
+                })
+            }
+        }
+
+        let (entered_tx, entered) = mpsc::channel();
+        let (go, go_rx) = mpsc::channel();
+        let store = Arc::new(InMemoryEventStore::new());
+        let runner = KernelRunner::new(Kernel::<TestState> {
+            events: Box::new(SharedEventStore(store.clone())),
+            snaps: None,
+            reducer: Box::new(StateUpdatedOnlyReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(Gated {
+                entered: Mutex::new(entered_tx),
+                go: Mutex::new(go_rx),
+            }),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        });
+        let run_id = "runner-handle".to_string();
+        let next_step = || entered.recv_timeout(Duration::from_secs(5)).unwrap();
+        let kinds = || -> Vec<EventKind> {
+            store
+                .scan(&run_id, 1)
+                .unwrap()
+                .iter()
+                .map(|se| se.event.kind())
+                .collect()
+        };
+
+        let handle = runner.spawn(&run_id, TestState(0));
+        assert_eq!(next_step(), 0);
+        handle.pause().unwrap();
+        go.send(()).unwrap();
+        let status = handle.wait().await.unwrap();
+        assert!(matches!(status, RunStatus::Blocked(ref info) if info.paused));
+        assert_eq!(kinds(), [EventKind::StateUpdated, EventKind::Paused]);
+
+        handle.resume().unwrap();
+        assert_eq!(next_step(), 1);
+        go.send(()).unwrap();
+        assert_eq!(next_step(), 2);
+        handle.cancel(Some("enough".into())).unwrap();
+        go.send(()).unwrap();
+        assert!(matches!(handle.wait().await, Ok(RunStatus::Cancelled)));
+        assert_eq!(
+            kinds(),
+            [
+                EventKind::StateUpdated,
+                EventKind::Paused,
+                EventKind::StateUpdated,
+                EventKind::StateUpdated,
+                EventKind::Cancelled,
+            ]
+        );
+
+        for result in [handle.pause(), handle.resume(), handle.cancel(None)] {
+            assert!(
+                matches!(result, Err(KernelError::RunEnded { ref run_id, status: EventKind::Cancelled }) if run_id == "runner-handle"),
+                "{:?}",
+                result
+            );
+        }
+        assert!(handle.wait().await.is_err(), "no task left to wait for");
+        assert_eq!(store.head(&run_id).unwrap(), 5);
+    }
+
     /// CI-style: from async context, runner must complete within a timeout (no reactor blocking).
     #[tokio::test]
     async fn run_until_blocked_async_completes_within_timeout() {
@@ -223,11 +927,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let runner = KernelRunner::new(kernel);
         let result = tokio::time::timeout(
@@ -5,16 +5,19 @@
 //! side effects leak into the execution state (replay and verification can rely on
 //! a complete effect stream).
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::kernel::effect_sink::SinkHealth;
 use crate::kernel::identity::{RunId, Seq};
 
 /// A single runtime side effect that the kernel (or adapters) must capture.
 ///
 /// Every LLM call, tool call, state write, and interrupt raise is recorded as one
 /// of these variants so that execution is fully auditable and replay-safe.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum RuntimeEffect {
     /// An LLM was invoked (provider + input).
     LLMCall {
@@ -42,6 +45,14 @@ pub enum RuntimeEffect {
         /// Interrupt payload forwarded to the interrupt resolver.
         value: Value,
     },
+    /// A stored snapshot failed its integrity check, so the run's state was rebuilt from
+    /// the full event log instead.
+    SnapshotIntegrityFailure {
+        /// `at_seq` of the rejected snapshot.
+        at_seq: Seq,
+        /// What did not match.
+        reason: String,
+    },
 }
 
 /// A log entry capturing runtime effects for audit and replay.
@@ -82,9 +93,35 @@ impl EffectLog {
 /// The kernel driver (and any code that produces side effects) should log every
 /// [RuntimeEffect] through this trait so that nothing is uncaptured. Implementations
 /// may append to a thread-local buffer, a run-scoped log, or a no-op for tests.
+/// `record` is called on the driver's thread, so it should return quickly; the sinks in
+/// [crate::kernel::effect_sink] queue effects and deliver them in the background.
 pub trait EffectSink: Send + Sync {
     /// Records one runtime effect for the given run.
     fn record(&self, run_id: &RunId, effect: &RuntimeEffect);
+
+    /// Delivery counters, for sinks that deliver asynchronously; `None` by default.
+    fn health(&self) -> Option<SinkHealth> {
+        None
+    }
+
+    /// Waits until every effect recorded so far has been delivered. No-op by default.
+    fn flush(&self) {}
+}
+
+/// Shared sink, so callers can keep a handle (e.g. for [EffectSink::health]) to the sink
+/// they give the kernel.
+impl<S: EffectSink + ?Sized> EffectSink for Arc<S> {
+    fn record(&self, run_id: &RunId, effect: &RuntimeEffect) {
+        (**self).record(run_id, effect)
+    }
+
+    fn health(&self) -> Option<SinkHealth> {
+        (**self).health()
+    }
+
+    fn flush(&self) {
+        (**self).flush()
+    }
 }
 
 /// Effect sink that discards all effects (e.g. when capture is not needed).
@@ -0,0 +1,526 @@
+//! Shadow runs: running changed code over a recorded run and comparing the two logs.
+//!
+//! [run_shadow] runs a step function under a new run id from the recorded run's initial
+//! state, serving external calls from the recording with a [ReplayActionExecutor] and
+//! feeding back the recorded resume values. [compare_runs] then aligns both logs step by
+//! step and reports what the shadow run did differently. [ShadowReport] serializes to JSON
+//! so it can be attached to review artifacts.
+//!
+//! Alignment is by position: the i-th `StateUpdated` (and the i-th `ActionRequested`) of
+//! one run is compared with the i-th of the other. `ActionRecorded` events are bookkeeping
+//! and are not compared.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::kernel::action_replay::ReplayActionExecutor;
+use crate::kernel::driver::{Kernel, RunStatus, Signal};
+use crate::kernel::event::{Event, EventStore, KernelError, SequencedEvent};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::kernel_mode::KernelMode;
+use crate::kernel::reducer::Reducer;
+use crate::kernel::state::KernelState;
+use crate::kernel::step::StepFn;
+use crate::kernel::stubs::AllowAllPolicy;
+use crate::kernel::timeline::{status_after, RunStatusSummary};
+
+/// Differences between a base run and its shadow run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShadowReport {
+    pub base_run: RunId,
+    pub shadow_run: RunId,
+    pub state_changes: Vec<StateChange>,
+    pub action_differences: Vec<ActionDifference>,
+    /// Status after the base run's last status-changing event; `None` if it has none.
+    pub base_status: Option<RunStatusSummary>,
+    pub shadow_status: Option<RunStatusSummary>,
+}
+
+impl ShadowReport {
+    /// True when the shadow run wrote the same states, requested the same actions and
+    /// ended in the same status as the base run.
+    pub fn is_equivalent(&self) -> bool {
+        self.state_changes.is_empty()
+            && self.action_differences.is_empty()
+            && !self.status_differs()
+    }
+
+    pub fn status_differs(&self) -> bool {
+        self.base_status != self.shadow_status
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateChangeKind {
+    /// Only the shadow run wrote this state.
+    Added,
+    /// Only the base run wrote this state.
+    Removed,
+    /// Both runs wrote a state here, with a different step id or payload.
+    Changed,
+}
+
+/// A `StateUpdated` that differs between the runs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateChange {
+    /// Position among the runs' `StateUpdated` events.
+    pub index: usize,
+    pub kind: StateChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_seq: Option<Seq>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_seq: Option<Seq>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_step_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_step_id: Option<String>,
+    /// Payload differences; an added or removed state is one change at the root.
+    pub diff: Vec<JsonChange>,
+}
+
+/// An `ActionRequested` payload that differs between the runs, or that only one run
+/// requested (the other side is `None`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionDifference {
+    /// Position among the runs' `ActionRequested` events.
+    pub index: usize,
+    pub base: Option<Value>,
+    pub shadow: Option<Value>,
+}
+
+/// One differing value between two JSON documents.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonChange {
+    /// JSON Pointer (RFC 6901) to the value; `""` is the root.
+    pub path: String,
+    /// Value in the base document; `None` if the path is absent there.
+    pub base: Option<Value>,
+    /// Value in the shadow document; `None` if the path is absent there.
+    pub shadow: Option<Value>,
+}
+
+/// Differences between two JSON documents. Objects are compared key by key and arrays
+/// index by index; any other differing values are reported whole.
+pub fn json_diff(base: &Value, shadow: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_into(String::new(), base, shadow, &mut changes);
+    changes
+}
+
+fn diff_into(path: String, base: &Value, shadow: &Value, changes: &mut Vec<JsonChange>) {
+    match (base, shadow) {
+        (Value::Object(b), Value::Object(s)) => {
+            let keys: BTreeSet<&String> = b.keys().chain(s.keys()).collect();
+            for key in keys {
+                let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                diff_child(child, b.get(key), s.get(key), changes);
+            }
+        }
+        (Value::Array(b), Value::Array(s)) => {
+            for i in 0..b.len().max(s.len()) {
+                diff_child(format!("{}/{}", path, i), b.get(i), s.get(i), changes);
+            }
+        }
+        _ if base != shadow => changes.push(JsonChange {
+            path,
+            base: Some(base.clone()),
+            shadow: Some(shadow.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_child(
+    path: String,
+    base: Option<&Value>,
+    shadow: Option<&Value>,
+    changes: &mut Vec<JsonChange>,
+) {
+    match (base, shadow) {
+        (Some(b), Some(s)) => diff_into(path, b, s, changes),
+        (None, None) => {}
+        _ => changes.push(JsonChange {
+            path,
+            base: base.cloned(),
+            shadow: shadow.cloned(),
+        }),
+    }
+}
+
+/// What one run's log contributes to the comparison
+#[derive(Default)]
+struct RunOutline {
+    states: Vec<(Seq, Option<String>, Value)>,
+    actions: Vec<Value>,
+    status: Option<RunStatusSummary>,
+}
+
+fn outline(store: &dyn EventStore, run_id: &RunId) -> Result<RunOutline, KernelError> {
+    let sequenced: Vec<SequencedEvent> = store.scan(run_id, 1)?;
+    if let Some(Event::Compacted { up_to_seq, .. }) = sequenced.first().map(|se| &se.event) {
+        return Err(KernelError::Compacted {
+            run_id: run_id.clone(),
+            up_to_seq: *up_to_seq,
+        });
+    }
+    let mut outline = RunOutline::default();
+    for se in sequenced {
+        if let Some(status) = status_after(&se.event) {
+            outline.status = Some(status);
+        }
+        match se.event {
+            Event::StateUpdated {
+                step_id, payload, ..
+            } => outline.states.push((se.seq, step_id, payload)),
+            Event::ActionRequested { payload, .. } => outline.actions.push(payload),
+            _ => {}
+        }
+    }
+    Ok(outline)
+}
+
+/// Compares `shadow_run` with `base_run`, both read from `store`; see the
+/// [module docs](self). A compacted run fails with [KernelError::Compacted].
+pub fn compare_runs(
+    store: &dyn EventStore,
+    base_run: &RunId,
+    shadow_run: &RunId,
+) -> Result<ShadowReport, KernelError> {
+    let base = outline(store, base_run)?;
+    let shadow = outline(store, shadow_run)?;
+
+    let mut state_changes = Vec::new();
+    for index in 0..base.states.len().max(shadow.states.len()) {
+        let (b, s) = (base.states.get(index), shadow.states.get(index));
+        let (kind, diff) = match (b, s) {
+            (Some((_, b_step, b_payload)), Some((_, s_step, s_payload))) => {
+                let diff = json_diff(b_payload, s_payload);
+                if diff.is_empty() && b_step == s_step {
+                    continue;
+                }
+                (StateChangeKind::Changed, diff)
+            }
+            _ => {
+                let kind = if b.is_some() {
+                    StateChangeKind::Removed
+                } else {
+                    StateChangeKind::Added
+                };
+                let diff = vec![JsonChange {
+                    path: String::new(),
+                    base: b.map(|(_, _, payload)| payload.clone()),
+                    shadow: s.map(|(_, _, payload)| payload.clone()),
+                }];
+                (kind, diff)
+            }
+        };
+        state_changes.push(StateChange {
+            index,
+            kind,
+            base_seq: b.map(|(seq, _, _)| *seq),
+            shadow_seq: s.map(|(seq, _, _)| *seq),
+            base_step_id: b.and_then(|(_, step_id, _)| step_id.clone()),
+            shadow_step_id: s.and_then(|(_, step_id, _)| step_id.clone()),
+            diff,
+        });
+    }
+
+    let action_differences = (0..base.actions.len().max(shadow.actions.len()))
+        .filter_map(|index| {
+            let (b, s) = (base.actions.get(index), shadow.actions.get(index));
+            (b != s).then(|| ActionDifference {
+                index,
+                base: b.cloned(),
+                shadow: s.cloned(),
+            })
+        })
+        .collect();
+
+    Ok(ShadowReport {
+        base_run: base_run.clone(),
+        shadow_run: shadow_run.clone(),
+        state_changes,
+        action_differences,
+        base_status: base.status,
+        shadow_status: shadow.status,
+    })
+}
+
+/// Runs `step` as a shadow of `base_run` under `shadow_run` and compares the two.
+///
+/// `initial_state` must be the state the base run started from. Actions are answered
+/// from the base run's `ActionRecorded` events (it must have been run with a
+/// [RecordingActionExecutor](crate::kernel::RecordingActionExecutor)), and each time the
+/// shadow run blocks it is resumed with the base run's next `Resumed` value. An action
+/// the recording does not hold stops the shadow run there; the report shows the
+/// differing request. `shadow_run` must not exist yet.
+pub fn run_shadow<S: KernelState>(
+    events: Arc<dyn EventStore>,
+    reducer: Box<dyn Reducer<S>>,
+    step: Box<dyn StepFn<S>>,
+    base_run: &RunId,
+    shadow_run: &RunId,
+    initial_state: S,
+) -> Result<ShadowReport, KernelError> {
+    if events.run_exists(shadow_run)? {
+        return Err(KernelError::Conflict(format!(
+            "shadow run {} already exists",
+            shadow_run
+        )));
+    }
+    let resumes: Vec<Value> = events
+        .scan(base_run, 1)?
+        .into_iter()
+        .filter_map(|se| match se.event {
+            Event::Resumed { value } => Some(value),
+            _ => None,
+        })
+        .collect();
+    let kernel = Kernel {
+        events: Box::new(events.clone()),
+        snaps: None,
+        reducer,
+        exec: Arc::new(ReplayActionExecutor::new(events.clone(), base_run)?),
+        step,
+        policy: Box::new(AllowAllPolicy),
+        effect_sink: None,
+        mode: KernelMode::Replay,
+        snapshot_policy: None,
+    };
+
+    let mut resumes = resumes.into_iter();
+    let mut status = kernel.run_until_blocked(shadow_run, initial_state.clone());
+    while let Ok(RunStatus::Blocked(_)) = status {
+        let Some(value) = resumes.next() else {
+            break;
+        };
+        status = kernel.resume(shadow_run, initial_state.clone(), Signal::Resume(value));
+    }
+    match status {
+        Ok(_) | Err(KernelError::ActionReplayMismatch { .. }) => {
+            compare_runs(events.as_ref(), base_run, shadow_run)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::action::{Action, ActionExecutor, ActionResult};
+    use crate::kernel::action_replay::RecordingActionExecutor;
+    use crate::kernel::event_store::InMemoryEventStore;
+    use crate::kernel::step::{InterruptInfo, Next};
+    use serde_json::json;
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    struct Quote {
+        price: Option<Value>,
+        approved: Option<Value>,
+        total: Option<u64>,
+    }
+    impl KernelState for Quote {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    struct QuoteReducer;
+    impl Reducer<Quote> for QuoteReducer {
+        fn apply(&self, state: &mut Quote, event: &SequencedEvent) -> Result<(), KernelError> {
+            match &event.event {
+                Event::StateUpdated { payload, .. } => {
+                    *state = serde_json::from_value(payload.clone())
+                        .map_err(|e| KernelError::Reducer(e.to_string()))?;
+                }
+                Event::ActionSucceeded { output, .. } => state.price = Some(output.clone()),
+                Event::Resumed { value } => state.approved = Some(value.clone()),
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    /// Prices `sku`, waits for approval, then writes `price * quantity`.
+    struct QuoteStep {
+        sku: &'static str,
+        quantity: u64,
+    }
+    impl StepFn<Quote> for QuoteStep {
+        fn next(&self, state: &Quote) -> Result<Next, KernelError> {
+            Ok(match (&state.price, &state.approved, state.total) {
+                (None, _, _) => Next::Do(Action::CallTool {
+                    tool: "price".into(),
+                    input: json!(self.sku),
+                    idempotency_key: None,
+                }),
+                (Some(_), None, _) => Next::Interrupt(InterruptInfo {
+                    value: json!("approve?"),
+                }),
+                (Some(price), Some(_), None) => {
+                    let mut next = state.clone();
+                    next.total = Some(price.as_u64().unwrap_or(0) * self.quantity);
+                    Next::Emit(vec![Event::StateUpdated {
+                        step_id: Some("total".into()),
+                        payload: serde_json::to_value(next).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
+                    }])
+                }
+                _ => Next::Complete,
+            })
+        }
+    }
+
+    struct Prices;
+    impl ActionExecutor for Prices {
+        fn execute(&self, _run_id: &RunId, _action: &Action) -> Result<ActionResult, KernelError> {
+            Ok(ActionResult::Success(json!(5)))
+        }
+    }
+
+    fn record_base(events: &Arc<InMemoryEventStore>, base: &RunId) {
+        let kernel = Kernel {
+            events: Box::new(events.clone()),
+            snaps: None,
+            reducer: Box::new(QuoteReducer),
+            exec: Arc::new(RecordingActionExecutor::new(Prices, events.clone())),
+            step: Box::new(QuoteStep {
+                sku: "apple",
+                quantity: 2,
+            }),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let status = kernel.run_until_blocked(base, Quote::default()).unwrap();
+        assert!(matches!(status, RunStatus::Blocked(_)));
+        let status = kernel
+            .resume(base, Quote::default(), Signal::Resume(json!(true)))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+    }
+
+    fn shadow(
+        events: &Arc<InMemoryEventStore>,
+        base: &RunId,
+        run_id: &str,
+        step: QuoteStep,
+    ) -> ShadowReport {
+        run_shadow(
+            events.clone(),
+            Box::new(QuoteReducer),
+            Box::new(step),
+            base,
+            &run_id.into(),
+            Quote::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn shadow_run_reports_changed_states_and_actions() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let base: RunId = "base".into();
+        record_base(&events, &base);
+
+        let same = shadow(
+            &events,
+            &base,
+            "same",
+            QuoteStep {
+                sku: "apple",
+                quantity: 2,
+            },
+        );
+        assert!(same.is_equivalent(), "{:?}", same);
+        assert_eq!(same.shadow_status, Some(RunStatusSummary::Completed));
+
+        let doubled = shadow(
+            &events,
+            &base,
+            "doubled",
+            QuoteStep {
+                sku: "apple",
+                quantity: 4,
+            },
+        );
+        assert!(doubled.action_differences.is_empty());
+        assert!(!doubled.status_differs());
+        assert_eq!(doubled.state_changes.len(), 1);
+        let change = &doubled.state_changes[0];
+        assert_eq!(change.kind, StateChangeKind::Changed);
+        assert_eq!(change.shadow_step_id.as_deref(), Some("total"));
+        assert_eq!(
+            change.diff,
+            [JsonChange {
+                path: "/total".into(),
+                base: Some(json!(10)),
+                shadow: Some(json!(20)),
+            }]
+        );
+
+        let diverged = shadow(
+            &events,
+            &base,
+            "diverged",
+            QuoteStep {
+                sku: "pear",
+                quantity: 2,
+            },
+        );
+        assert_eq!(diverged.action_differences.len(), 1);
+        assert_eq!(
+            diverged.action_differences[0].shadow.as_ref().unwrap()["CallTool"]["input"],
+            json!("pear")
+        );
+        assert_eq!(diverged.state_changes[0].kind, StateChangeKind::Removed);
+        assert_eq!(diverged.shadow_status, None);
+        assert!(diverged.status_differs());
+
+        let json = serde_json::to_value(&diverged).unwrap();
+        assert_eq!(json["state_changes"][0]["kind"], "removed");
+        let back: ShadowReport = serde_json::from_value(json).unwrap();
+        assert_eq!(back, diverged);
+
+        let err = run_shadow(
+            events.clone(),
+            Box::new(QuoteReducer),
+            Box::new(QuoteStep {
+                sku: "apple",
+                quantity: 2,
+            }),
+            &base,
+            &"same".into(),
+            Quote::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, KernelError::Conflict(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn json_diff_reports_paths_of_differing_values() {
+        let base = json!({"a": 1, "b": [1, 2], "c": {"x/y": true}, "gone": null});
+        let shadow = json!({"a": 1, "b": [1, 3, 4], "c": {"x/y": false}, "new": "v"});
+        let paths: Vec<_> = json_diff(&base, &shadow)
+            .into_iter()
+            .map(|c| (c.path, c.base, c.shadow))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("/b/1".to_string(), Some(json!(2)), Some(json!(3))),
+                ("/b/2".to_string(), None, Some(json!(4))),
+                ("/c/x~1y".to_string(), Some(json!(true)), Some(json!(false))),
+                ("/gone".to_string(), Some(Value::Null), None),
+                ("/new".to_string(), None, Some(json!("v"))),
+            ]
+        );
+        assert!(json_diff(&base, &base).is_empty());
+    }
+}
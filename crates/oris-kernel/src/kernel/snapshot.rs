@@ -3,12 +3,26 @@
 //! **Checkpointing/snapshots are strictly an optimization layer.** The source of truth
 //! is the event-sourced execution log (see [crate::kernel::execution_log::ExecutionLog] and
 //! [crate::kernel::event::EventStore]). Snapshots only speed up replay by providing
-//! initial state at a given seq; they do not replace the log.
+//! initial state at a given seq; they do not replace the log. The exception is a run
+//! whose log was compacted ([crate::kernel::compaction]): its replay starts from a snapshot.
 //! Every snapshot must carry `at_seq` (the seq up to which state has been projected).
+//!
+//! Stores that persist snapshots save a [content hash](Snapshot::compute_content_hash)
+//! with each one and check it on load, failing with [KernelError::SnapshotIntegrity] when
+//! the stored snapshot changed; the driver then replays the full log instead. [verify_all]
+//! audits every stored snapshot of a run against a replay of its log.
+
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
 
+use crate::kernel::canonical::{canonical_json, canonical_state_hash};
+use crate::kernel::event::{Event, EventStore};
 use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::reducer::Reducer;
+use crate::kernel::state::KernelState;
 use crate::kernel::KernelError;
 
 /// A snapshot of state at a given sequence number.
@@ -23,18 +37,143 @@ pub struct Snapshot<S> {
     pub at_seq: Seq,
     /// The state at this point.
     pub state: S,
+    /// [KernelState::version](crate::kernel::KernelState::version) of `state` when it was
+    /// saved, so a snapshot of an older state schema can be recognized (and migrated or
+    /// skipped); 0 when unknown, e.g. for snapshots stored before versions were recorded.
+    #[serde(default)]
+    pub state_version: u32,
+    /// Number of events applied to produce `state`, archived ones included. Seqs are dense
+    /// from 1, so this is `at_seq` for the driver's snapshots; 0 when unknown.
+    #[serde(default)]
+    pub event_count: u64,
+    /// [Content hash](Self::compute_content_hash) the store saved with the snapshot, set on
+    /// the snapshots it loads; `None` for snapshots saved without one, which are not checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+impl<S: Serialize> Snapshot<S> {
+    /// Hex SHA-256 over the run, `at_seq`, `event_count`, `state_version` and the
+    /// [canonical JSON](crate::kernel::canonical::canonical_json) of `state`. Ignores
+    /// `content_hash` itself.
+    pub fn compute_content_hash(&self) -> Result<String, KernelError> {
+        let state = serde_json::to_value(&self.state)
+            .map_err(|e| KernelError::SnapshotStore(format!("serialize snapshot state: {}", e)))?;
+        let content = json!({
+            "run_id": self.run_id,
+            "at_seq": self.at_seq,
+            "event_count": self.event_count,
+            "state_version": self.state_version,
+            "state": state,
+        });
+        Ok(hex::encode(Sha256::digest(
+            canonical_json(&content).as_bytes(),
+        )))
+    }
+
+    /// Checks the snapshot against its `content_hash`, failing with
+    /// [KernelError::SnapshotIntegrity] on a mismatch. A snapshot without a hash passes.
+    pub fn verify(&self) -> Result<(), KernelError> {
+        let Some(expected) = &self.content_hash else {
+            return Ok(());
+        };
+        let actual = self.compute_content_hash()?;
+        if actual == *expected {
+            return Ok(());
+        }
+        Err(KernelError::SnapshotIntegrity {
+            run_id: self.run_id.clone(),
+            at_seq: self.at_seq,
+            reason: format!(
+                "content hash {} does not match the stored {}",
+                actual, expected
+            ),
+        })
+    }
+}
+
+/// When the driver snapshots a run on its own (see `Kernel::snapshot_policy`).
+///
+/// It takes a snapshot once `every_n_events` events were appended since the last one, or
+/// once `min_interval` has passed since the last one and events were appended since,
+/// whichever comes first; a trigger left `None` never fires. States whose
+/// [KernelState::snapshot_size](crate::kernel::KernelState::snapshot_size) exceeds
+/// `max_state_bytes` are not snapshotted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotPolicy {
+    /// Events (other than `SnapshotTaken` markers) appended between snapshots.
+    pub every_n_events: Option<u64>,
+    /// Time after which the next step boundary takes a snapshot.
+    pub min_interval: Option<Duration>,
+    /// Largest state worth a snapshot, in bytes.
+    pub max_state_bytes: Option<usize>,
+}
+
+impl SnapshotPolicy {
+    /// Snapshots every `n` events.
+    pub fn every_n_events(n: u64) -> Self {
+        Self {
+            every_n_events: Some(n),
+            ..Self::default()
+        }
+    }
+
+    /// Snapshots at most every `interval`, at the first step boundary after it passed.
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            min_interval: Some(interval),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = Some(interval);
+        self
+    }
+
+    pub fn with_max_state_bytes(mut self, max: usize) -> Self {
+        self.max_state_bytes = Some(max);
+        self
+    }
 }
 
 /// Snapshot store: load latest snapshot or save a new one (optimization layer).
+///
+/// Stores that persist snapshots outside the process should save each one's
+/// [content hash](Snapshot::compute_content_hash) and [verify](Snapshot::verify) it on
+/// load, so a corrupt snapshot fails with [KernelError::SnapshotIntegrity] instead of
+/// seeding a replay.
 pub trait SnapshotStore<S>: Send + Sync {
     /// Loads the latest snapshot for the run, if any.
     fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError>;
 
     /// Saves a snapshot. Overwrites or appends according to implementation.
     fn save(&self, snapshot: &Snapshot<S>) -> Result<(), KernelError>;
+
+    /// Loads the run's latest snapshot with `at_seq <= seq`, if any.
+    ///
+    /// Stores that keep older snapshots should override this; the default only finds the
+    /// latest one.
+    fn load_at_or_before(
+        &self,
+        run_id: &RunId,
+        seq: Seq,
+    ) -> Result<Option<Snapshot<S>>, KernelError> {
+        Ok(self
+            .load_latest(run_id)?
+            .filter(|snapshot| snapshot.at_seq <= seq))
+    }
+
+    /// Deletes the run's snapshots except the `keep` latest ones (at least one is always
+    /// kept) and returns how many were deleted. Stores that keep only the latest snapshot
+    /// delete nothing.
+    fn prune(&self, _run_id: &RunId, _keep: usize) -> Result<usize, KernelError> {
+        Ok(0)
+    }
 }
 
-/// In-memory snapshot store: one snapshot per run (latest overwrites).
+/// In-memory snapshot store: one snapshot per run (latest overwrites). Snapshots never
+/// leave the process, so they are not hashed.
 pub struct InMemorySnapshotStore<S> {
     latest: std::sync::RwLock<std::collections::HashMap<RunId, Snapshot<S>>>,
 }
@@ -71,3 +210,212 @@ impl<S: Clone + Send + Sync> SnapshotStore<S> for InMemorySnapshotStore<S> {
         Ok(())
     }
 }
+
+/// A stored snapshot that [verify_all] found wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotMismatch {
+    /// `at_seq` of the snapshot.
+    pub at_seq: Seq,
+    /// What did not match, for operators.
+    pub reason: String,
+}
+
+/// Audits every stored snapshot of `run_id`: each must pass its store's integrity check
+/// and hold the state, and event count, that replaying `events` with `reducer` from
+/// `initial_state` produces at its `at_seq`. Returns the snapshots that do not, oldest
+/// first; an empty list means all of them can seed a replay.
+///
+/// Snapshots are found by walking [SnapshotStore::load_at_or_before] back from the latest
+/// one, so stores that keep only the latest snapshot have just that one audited. A
+/// compacted run is replayed from the snapshot its `Compacted` marker names, and snapshots
+/// before it are not re-derived; without that snapshot this fails with
+/// [KernelError::Compacted].
+pub fn verify_all<S>(
+    store: &dyn SnapshotStore<S>,
+    events: &dyn EventStore,
+    reducer: &dyn Reducer<S>,
+    run_id: &RunId,
+    initial_state: S,
+) -> Result<Vec<SnapshotMismatch>, KernelError>
+where
+    S: KernelState + Serialize,
+{
+    let mut mismatches = Vec::new();
+    let mut snapshots = Vec::new();
+    let mut cursor = Seq::MAX;
+    loop {
+        let at_seq = match store.load_at_or_before(run_id, cursor) {
+            Ok(Some(snapshot)) => {
+                let at_seq = snapshot.at_seq;
+                snapshots.push(snapshot);
+                at_seq
+            }
+            Ok(None) => break,
+            Err(KernelError::SnapshotIntegrity { at_seq, reason, .. }) => {
+                mismatches.push(SnapshotMismatch { at_seq, reason });
+                at_seq
+            }
+            Err(e) => return Err(e),
+        };
+        match at_seq.checked_sub(1) {
+            Some(seq) => cursor = seq,
+            None => break,
+        }
+    }
+    snapshots.reverse();
+
+    let mut state = initial_state;
+    let mut count = 0;
+    let mut sequenced = events.scan(run_id, 1)?;
+    if let Some(&Event::Compacted {
+        up_to_seq,
+        snapshot_seq,
+    }) = sequenced.first().map(|se| &se.event)
+    {
+        let position = snapshots
+            .iter()
+            .position(|snapshot| snapshot.at_seq == snapshot_seq)
+            .ok_or_else(|| KernelError::Compacted {
+                run_id: run_id.clone(),
+                up_to_seq,
+            })?;
+        let base = snapshots.remove(position);
+        snapshots.drain(..position);
+        count = match base.event_count {
+            0 => base.at_seq,
+            n => n,
+        };
+        state = base.state;
+        sequenced = events.scan(run_id, snapshot_seq + 1)?;
+    }
+
+    let mut pending = snapshots.into_iter().peekable();
+    for se in sequenced {
+        while let Some(snapshot) = pending.next_if(|snapshot| snapshot.at_seq < se.seq) {
+            mismatches.push(SnapshotMismatch {
+                at_seq: snapshot.at_seq,
+                reason: "no event in the log has its seq".into(),
+            });
+        }
+        reducer.apply(&mut state, &se)?;
+        count += 1;
+        while let Some(snapshot) = pending.next_if(|snapshot| snapshot.at_seq == se.seq) {
+            if let Some(reason) = snapshot_mismatch(&snapshot, &state, count)? {
+                mismatches.push(SnapshotMismatch {
+                    at_seq: snapshot.at_seq,
+                    reason,
+                });
+            }
+        }
+    }
+    mismatches.extend(pending.map(|snapshot| SnapshotMismatch {
+        at_seq: snapshot.at_seq,
+        reason: "its seq is past the end of the log".into(),
+    }));
+    mismatches.sort_by_key(|mismatch| mismatch.at_seq);
+    Ok(mismatches)
+}
+
+/// Why `snapshot` differs from the replayed `state` after `count` events, if it does
+fn snapshot_mismatch<S: Serialize>(
+    snapshot: &Snapshot<S>,
+    state: &S,
+    count: u64,
+) -> Result<Option<String>, KernelError> {
+    if snapshot.event_count != 0 && snapshot.event_count != count {
+        return Ok(Some(format!(
+            "covers {} events, but the log has {} up to its seq",
+            snapshot.event_count, count
+        )));
+    }
+    if canonical_state_hash(&snapshot.state)? != canonical_state_hash(state)? {
+        return Ok(Some("state differs from a replay of the log".into()));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event_store::InMemoryEventStore;
+    use crate::kernel::StateUpdatedOnlyReducer;
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Counter(u32);
+
+    impl KernelState for Counter {
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+
+    fn snapshot(at_seq: Seq, event_count: u64, state: u32) -> Snapshot<Counter> {
+        Snapshot {
+            run_id: "run-snapshot".into(),
+            at_seq,
+            state: Counter(state),
+            state_version: 1,
+            event_count,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn content_hash_detects_changed_state_and_position() {
+        let mut sealed = snapshot(2, 2, 7);
+        assert!(sealed.verify().is_ok(), "snapshots without a hash pass");
+        sealed.content_hash = Some(sealed.compute_content_hash().unwrap());
+        assert!(sealed.verify().is_ok());
+
+        let mut changed = sealed.clone();
+        changed.state = Counter(8);
+        assert!(matches!(
+            changed.verify(),
+            Err(KernelError::SnapshotIntegrity { at_seq: 2, .. })
+        ));
+        let mut moved = sealed.clone();
+        moved.event_count = 3;
+        assert!(moved.verify().is_err());
+    }
+
+    #[test]
+    fn verify_all_checks_event_counts_and_seqs() {
+        let events = InMemoryEventStore::new();
+        let run_id: RunId = "run-snapshot".into();
+        let updates: Vec<Event> = (1..=2)
+            .map(|n| Event::StateUpdated {
+                step_id: None,
+                payload: serde_json::json!(n),
+                state_hash: None,
+                merge_report: None,
+            })
+            .collect();
+        events.append(&run_id, &updates).unwrap();
+        let audit = |store: &InMemorySnapshotStore<Counter>| {
+            verify_all(
+                store,
+                &events,
+                &StateUpdatedOnlyReducer,
+                &run_id,
+                Counter::default(),
+            )
+            .unwrap()
+        };
+
+        let store = InMemorySnapshotStore::new();
+        store.save(&snapshot(2, 2, 2)).unwrap();
+        assert!(audit(&store).is_empty());
+
+        store.save(&snapshot(2, 3, 2)).unwrap();
+        assert!(audit(&store)[0].reason.contains("covers 3 events"));
+
+        store.save(&snapshot(5, 5, 2)).unwrap();
+        assert_eq!(
+            audit(&store),
+            vec![SnapshotMismatch {
+                at_seq: 5,
+                reason: "its seq is past the end of the log".into(),
+            }]
+        );
+    }
+}
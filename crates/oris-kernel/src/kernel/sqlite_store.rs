@@ -1,4 +1,4 @@
-//! SQLite-backed kernel stores for event log and snapshots.
+//! SQLite-backed kernel stores for event log, snapshots and action results.
 //!
 //! This module is feature-gated behind `sqlite-persistence`.
 
@@ -7,20 +7,37 @@ use std::marker::PhantomData;
 #[cfg(feature = "sqlite-persistence")]
 use std::path::{Path, PathBuf};
 #[cfg(feature = "sqlite-persistence")]
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 #[cfg(feature = "sqlite-persistence")]
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[cfg(feature = "sqlite-persistence")]
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
 #[cfg(feature = "sqlite-persistence")]
 use serde::{de::DeserializeOwned, Serialize};
 
 #[cfg(feature = "sqlite-persistence")]
-use crate::kernel::event::{Event, EventStore, KernelError, SequencedEvent};
+use crate::kernel::action::ActionResult;
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::action_replay::RecordedOutcome;
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::event::{
+    Event, EventFilter, EventStore, KernelError, SequencedEvent, CURRENT_EVENT_VERSION,
+};
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::event_migration::EventMigrator;
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::event_store::no_event_to_compact;
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::idempotency::ActionResultCache;
 #[cfg(feature = "sqlite-persistence")]
 use crate::kernel::identity::{RunId, Seq};
 #[cfg(feature = "sqlite-persistence")]
+use crate::kernel::ops::{
+    datetime_from_ms, event_filter_sql, sql_bound, PageRequest, RunFilter, RunStatusKind,
+    RunSummary, RUN_STATUS_SQL,
+};
+#[cfg(feature = "sqlite-persistence")]
 use crate::kernel::snapshot::{Snapshot, SnapshotStore};
 
 #[cfg(feature = "sqlite-persistence")]
@@ -42,36 +59,38 @@ fn now_ms() -> i64 {
 }
 
 /// SQLite-backed event log store.
+///
+/// Events live in a `kernel_events` table keyed by `(run_id, seq)`. [`append`] allocates
+/// sequence numbers inside an immediate transaction, so they stay gapless and monotonic
+/// per run even with several processes writing to the same file.
+///
+/// Each row records the [CURRENT_EVENT_VERSION] it was written at (rows from before
+/// versions were recorded count as version 1); reads upgrade older rows through the
+/// store's [EventMigrator].
+///
+/// [`append`]: EventStore::append
 #[cfg(feature = "sqlite-persistence")]
 pub struct SqliteEventStore {
-    db_path: PathBuf,
-    lock: Mutex<()>,
+    conn: Mutex<Connection>,
+    migrator: Arc<EventMigrator>,
 }
 
 #[cfg(feature = "sqlite-persistence")]
 impl SqliteEventStore {
-    pub fn new(path: impl Into<PathBuf>) -> Self {
-        Self {
-            db_path: path.into(),
-            lock: Mutex::new(()),
-        }
-    }
-
-    fn open_connection(&self) -> Result<Connection, KernelError> {
-        if let Some(parent) = Path::new(&self.db_path).parent() {
+    /// Open (or create) the event log at `path`, creating parent directories and the
+    /// schema as needed.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, KernelError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
             std::fs::create_dir_all(parent).map_err(|e| map_event_err("create parent dir", e))?;
         }
-        let conn =
-            Connection::open(&self.db_path).map_err(|e| map_event_err("open sqlite db", e))?;
+        let conn = Connection::open(path).map_err(|e| map_event_err("open sqlite db", e))?;
         conn.pragma_update(None, "journal_mode", "WAL")
             .map_err(|e| map_event_err("set journal_mode", e))?;
         conn.pragma_update(None, "synchronous", "NORMAL")
             .map_err(|e| map_event_err("set synchronous", e))?;
-        self.ensure_schema(&conn)?;
-        Ok(conn)
-    }
-
-    fn ensure_schema(&self, conn: &Connection) -> Result<(), KernelError> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| map_event_err("set busy_timeout", e))?;
         conn.execute_batch(
             "
             CREATE TABLE IF NOT EXISTS kernel_events (
@@ -79,6 +98,7 @@ impl SqliteEventStore {
                 seq INTEGER NOT NULL,
                 event_json TEXT NOT NULL,
                 created_at_ms INTEGER NOT NULL,
+                event_version INTEGER NOT NULL DEFAULT 1,
                 PRIMARY KEY (run_id, seq)
             );
             CREATE INDEX IF NOT EXISTS idx_kernel_events_run_seq
@@ -86,52 +106,130 @@ impl SqliteEventStore {
             ",
         )
         .map_err(|e| map_event_err("ensure schema", e))?;
-        Ok(())
+        // Logs created before versions were recorded lack the column; their rows are v1
+        let versioned: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('kernel_events')
+                 WHERE name = 'event_version'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| map_event_err("inspect schema", e))?;
+        if !versioned {
+            conn.execute(
+                "ALTER TABLE kernel_events ADD COLUMN event_version INTEGER NOT NULL DEFAULT 1",
+                [],
+            )
+            .map_err(|e| map_event_err("add event_version column", e))?;
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+            migrator: Arc::new(EventMigrator::new()),
+        })
+    }
+
+    /// Upgrades older events with `migrator` instead of the built-in migrations.
+    pub fn with_migrator(mut self, migrator: EventMigrator) -> Self {
+        self.migrator = Arc::new(migrator);
+        self
+    }
+
+    fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>, KernelError> {
+        self.conn.lock().map_err(|_| map_event_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))
+    }
+}
+
+#[cfg(feature = "sqlite-persistence")]
+fn read_head(conn: &Connection, run_id: &RunId) -> Result<Seq, KernelError> {
+    let head: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(seq), 0) FROM kernel_events WHERE run_id = ?1",
+            params![run_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| map_event_err("read head", e))?;
+    Ok(head as Seq)
+}
+
+/// Kind of the event in column `event_json`: unit variants serialize as a string, the
+/// others as an object keyed by the variant name
+#[cfg(feature = "sqlite-persistence")]
+const EVENT_KIND_SQL: &str = "CASE WHEN json_type(event_json) = 'text'
+    THEN json_extract(event_json, '$')
+    ELSE (SELECT key FROM json_each(event_json) LIMIT 1) END";
+
+/// Columns [SqliteEventStore::query_events] expects, in order
+#[cfg(feature = "sqlite-persistence")]
+const EVENT_COLUMNS: &str = "seq, event_version, event_json, created_at_ms";
+
+#[cfg(feature = "sqlite-persistence")]
+impl SqliteEventStore {
+    /// Runs `sql`, which selects [EVENT_COLUMNS] of `run_id`, and decodes the events
+    fn query_events(
+        &self,
+        run_id: &RunId,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| map_event_err("prepare scan", e))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })
+            .map_err(|e| map_event_err("query scan", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| map_event_err("row decode", e))?;
+        rows.into_iter()
+            .map(|(seq, version, json, created_at_ms)| {
+                let json =
+                    serde_json::from_str(&json).map_err(|e| map_event_err("row decode", e))?;
+                Ok(self
+                    .migrator
+                    .decode(run_id, seq as Seq, version, json)?
+                    .recorded_at(created_at_ms))
+            })
+            .collect()
     }
 }
 
 #[cfg(feature = "sqlite-persistence")]
 impl EventStore for SqliteEventStore {
     fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
-        let _guard = self
-            .lock
-            .lock()
-            .map_err(|_| map_event_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))?;
-        let mut conn = self.open_connection()?;
-
+        let mut conn = self.conn()?;
         if events.is_empty() {
-            let head: i64 = conn
-                .query_row(
-                    "SELECT COALESCE(MAX(seq), 0) FROM kernel_events WHERE run_id = ?1",
-                    params![run_id],
-                    |row| row.get(0),
-                )
-                .map_err(|e| map_event_err("read head", e))?;
-            return Ok(head as Seq);
+            return read_head(&conn, run_id);
         }
 
+        // IMMEDIATE takes the write lock before reading the head, so no other writer can
+        // allocate the same seqs in between
         let tx = conn
-            .transaction()
+            .transaction_with_behavior(TransactionBehavior::Immediate)
             .map_err(|e| map_event_err("begin tx", e))?;
-        let current_head: i64 = tx
-            .query_row(
-                "SELECT COALESCE(MAX(seq), 0) FROM kernel_events WHERE run_id = ?1",
-                params![run_id],
-                |row| row.get(0),
-            )
-            .map_err(|e| map_event_err("read head", e))?;
-
-        let mut last_seq = current_head as Seq;
-        for (idx, event) in events.iter().enumerate() {
-            let seq = current_head + idx as i64 + 1;
+        let mut last_seq = read_head(&tx, run_id)?;
+        for event in events {
             let json =
                 serde_json::to_string(event).map_err(|e| map_event_err("serialize event", e))?;
+            last_seq += 1;
             tx.execute(
-                "INSERT INTO kernel_events (run_id, seq, event_json, created_at_ms) VALUES (?1, ?2, ?3, ?4)",
-                params![run_id, seq, json, now_ms()],
+                "INSERT INTO kernel_events (run_id, seq, event_json, created_at_ms, event_version)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run_id,
+                    last_seq as i64,
+                    json,
+                    now_ms(),
+                    CURRENT_EVENT_VERSION
+                ],
             )
             .map_err(|e| map_event_err("insert event", e))?;
-            last_seq = seq as Seq;
         }
 
         tx.commit().map_err(|e| map_event_err("commit tx", e))?;
@@ -139,65 +237,159 @@ impl EventStore for SqliteEventStore {
     }
 
     fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
-        let _guard = self
-            .lock
-            .lock()
-            .map_err(|_| map_event_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))?;
-        let conn = self.open_connection()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT seq, event_json FROM kernel_events
+        self.query_events(
+            run_id,
+            &format!(
+                "SELECT {} FROM kernel_events
                  WHERE run_id = ?1 AND seq >= ?2
                  ORDER BY seq ASC",
+                EVENT_COLUMNS
+            ),
+            params![run_id, sql_bound(from)],
+        )
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        read_head(&*self.conn()?, run_id)
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let sql = format!(
+            "SELECT {} FROM kernel_events
+             WHERE run_id = ?1 AND seq >= ?2 AND seq <= ?3{}
+             ORDER BY seq ASC",
+            EVENT_COLUMNS,
+            event_filter_sql(EVENT_KIND_SQL, filter)
+        );
+        self.query_events(
+            run_id,
+            &sql,
+            params![run_id, sql_bound(from), sql_bound(to)],
+        )
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        let sql = format!(
+            "SELECT {} FROM kernel_events
+             WHERE run_id = ?1{}
+             ORDER BY seq DESC
+             LIMIT ?2",
+            EVENT_COLUMNS,
+            event_filter_sql(EVENT_KIND_SQL, filter)
+        );
+        self.query_events(run_id, &sql, params![run_id, sql_bound(limit)])
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "WITH runs AS (
+                SELECT run_id, MIN(created_at_ms) AS first_ms, MAX(created_at_ms) AS last_ms,
+                       COUNT(*) AS event_count, MAX(seq) AS head
+                FROM kernel_events GROUP BY run_id
+            ), tails AS (
+                SELECT r.run_id, r.first_ms, r.last_ms, r.event_count, {} AS kind
+                FROM runs r JOIN kernel_events e ON e.run_id = r.run_id AND e.seq = r.head
             )
-            .map_err(|e| map_event_err("prepare scan", e))?;
+            SELECT run_id, first_ms, last_ms, event_count, kind
+            FROM (SELECT *, {} AS status FROM tails)
+            WHERE (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR first_ms > ?2)
+            ORDER BY first_ms, run_id
+            LIMIT ?3 OFFSET ?4",
+            EVENT_KIND_SQL, RUN_STATUS_SQL
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| map_event_err("prepare list runs", e))?;
         let rows = stmt
-            .query_map(params![run_id, from as i64], |row| {
-                let seq: i64 = row.get(0)?;
-                let json: String = row.get(1)?;
-                let event: Event = serde_json::from_str(&json).map_err(|err| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        json.len(),
-                        rusqlite::types::Type::Text,
-                        Box::new(err),
-                    )
-                })?;
-                Ok(SequencedEvent {
-                    seq: seq as Seq,
-                    event,
-                })
-            })
-            .map_err(|e| map_event_err("query scan", e))?;
-
-        let mut out = Vec::new();
-        for row in rows {
-            out.push(row.map_err(|e| map_event_err("row decode", e))?);
-        }
-        Ok(out)
+            .query_map(
+                params![
+                    filter.status.map(|s| s.as_str()),
+                    filter.created_after.map(|t| t.timestamp_millis()),
+                    sql_bound(page.limit),
+                    sql_bound(page.offset),
+                ],
+                |row| {
+                    let kind: String = row.get(4)?;
+                    Ok(RunSummary {
+                        run_id: row.get(0)?,
+                        first_event_at: datetime_from_ms(row.get(1)?),
+                        last_event_at: datetime_from_ms(row.get(2)?),
+                        event_count: row.get::<_, i64>(3)? as u64,
+                        status: RunStatusKind::from_last_event_kind(&kind),
+                        last_event_kind: kind,
+                    })
+                },
+            )
+            .map_err(|e| map_event_err("query list runs", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| map_event_err("row decode", e))
     }
 
-    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
-        let _guard = self
-            .lock
-            .lock()
-            .map_err(|_| map_event_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))?;
-        let conn = self.open_connection()?;
-        let head: i64 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(seq), 0) FROM kernel_events WHERE run_id = ?1",
-                params![run_id],
-                |row| row.get(0),
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        let json =
+            serde_json::to_string(marker).map_err(|e| map_event_err("serialize event", e))?;
+        let mut conn = self.conn()?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| map_event_err("begin tx", e))?;
+        // The marker keeps the earliest write time, so listings still date the run
+        let replaced = tx
+            .execute(
+                "UPDATE kernel_events
+                 SET event_json = ?3,
+                     event_version = ?4,
+                     created_at_ms = (SELECT MIN(created_at_ms) FROM kernel_events
+                                      WHERE run_id = ?1 AND seq <= ?2)
+                 WHERE run_id = ?1 AND seq = ?2",
+                params![run_id, sql_bound(up_to_seq), json, CURRENT_EVENT_VERSION],
             )
-            .map_err(|e| map_event_err("read head", e))?;
-        Ok(head as Seq)
+            .map_err(|e| map_event_err("replace event", e))?;
+        if replaced == 0 {
+            return Err(no_event_to_compact(run_id, up_to_seq));
+        }
+        tx.execute(
+            "DELETE FROM kernel_events WHERE run_id = ?1 AND seq < ?2",
+            params![run_id, sql_bound(up_to_seq)],
+        )
+        .map_err(|e| map_event_err("delete events", e))?;
+        tx.commit().map_err(|e| map_event_err("commit tx", e))
     }
 }
 
 /// SQLite-backed snapshot store.
+///
+/// Snapshots live in a `kernel_snapshots` table keyed by `(run_id, at_seq)`, with the
+/// state as JSON, its [Snapshot::state_version] and event count, and the
+/// [content hash](Snapshot::compute_content_hash) checked when it is loaded. Every saved
+/// snapshot is kept, so [SnapshotStore::load_at_or_before] can start a replay from an
+/// earlier one, unless [with_retention](Self::with_retention) prunes superseded snapshots
+/// on save.
 #[cfg(feature = "sqlite-persistence")]
 pub struct SqliteSnapshotStore<S> {
     db_path: PathBuf,
     lock: Mutex<()>,
+    retention: Option<usize>,
     _state: PhantomData<S>,
 }
 
@@ -207,10 +399,24 @@ impl<S> SqliteSnapshotStore<S> {
         Self {
             db_path: path.into(),
             lock: Mutex::new(()),
+            retention: None,
             _state: PhantomData,
         }
     }
 
+    /// Keeps only each run's `keep` latest snapshots (at least one), pruning older ones
+    /// whenever a snapshot is saved.
+    pub fn with_retention(mut self, keep: usize) -> Self {
+        self.retention = Some(keep);
+        self
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, ()>, KernelError> {
+        self.lock
+            .lock()
+            .map_err(|_| map_snapshot_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))
+    }
+
     fn open_connection(&self) -> Result<Connection, KernelError> {
         if let Some(parent) = Path::new(&self.db_path).parent() {
             std::fs::create_dir_all(parent)
@@ -234,6 +440,9 @@ impl<S> SqliteSnapshotStore<S> {
                 at_seq INTEGER NOT NULL,
                 state_json TEXT NOT NULL,
                 created_at_ms INTEGER NOT NULL,
+                state_version INTEGER NOT NULL DEFAULT 0,
+                event_count INTEGER NOT NULL DEFAULT 0,
+                content_hash TEXT,
                 PRIMARY KEY (run_id, at_seq)
             );
             CREATE INDEX IF NOT EXISTS idx_kernel_snapshots_run_seq
@@ -241,78 +450,314 @@ impl<S> SqliteSnapshotStore<S> {
             ",
         )
         .map_err(|e| map_snapshot_err("ensure schema", e))?;
+        // Tables created before versions and hashes were recorded lack the columns; their
+        // rows get version and event count 0 and no hash, so they are loaded unchecked
+        for (column, definition) in [
+            ("state_version", "INTEGER NOT NULL DEFAULT 0"),
+            ("event_count", "INTEGER NOT NULL DEFAULT 0"),
+            ("content_hash", "TEXT"),
+        ] {
+            let present: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM pragma_table_info('kernel_snapshots')
+                     WHERE name = ?1",
+                    params![column],
+                    |row| row.get(0),
+                )
+                .map_err(|e| map_snapshot_err("inspect schema", e))?;
+            if !present {
+                conn.execute(
+                    &format!("ALTER TABLE kernel_snapshots ADD COLUMN {column} {definition}"),
+                    [],
+                )
+                .map_err(|e| map_snapshot_err("add snapshot column", e))?;
+            }
+        }
         Ok(())
     }
+
+    fn prune_with(conn: &Connection, run_id: &RunId, keep: usize) -> Result<usize, KernelError> {
+        conn.execute(
+            "DELETE FROM kernel_snapshots
+             WHERE run_id = ?1 AND at_seq < (
+                 SELECT MIN(at_seq) FROM (
+                     SELECT at_seq FROM kernel_snapshots
+                     WHERE run_id = ?1
+                     ORDER BY at_seq DESC
+                     LIMIT ?2
+                 )
+             )",
+            params![run_id, keep.max(1) as i64],
+        )
+        .map_err(|e| map_snapshot_err("prune snapshots", e))
+    }
 }
 
 #[cfg(feature = "sqlite-persistence")]
-impl<S> SnapshotStore<S> for SqliteSnapshotStore<S>
+impl<S> SqliteSnapshotStore<S>
 where
-    S: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    S: Serialize + DeserializeOwned,
 {
-    fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
-        let _guard = self
-            .lock
-            .lock()
-            .map_err(|_| map_snapshot_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))?;
+    /// Latest snapshot of the run with `at_seq <= up_to`, checked against its content hash
+    fn load_up_to(&self, run_id: &RunId, up_to: Seq) -> Result<Option<Snapshot<S>>, KernelError> {
+        let _guard = self.lock()?;
         let conn = self.open_connection()?;
         let row = conn
             .query_row(
-                "SELECT at_seq, state_json
+                "SELECT at_seq, state_json, state_version, event_count, content_hash
                  FROM kernel_snapshots
-                 WHERE run_id = ?1
+                 WHERE run_id = ?1 AND at_seq <= ?2
                  ORDER BY at_seq DESC
                  LIMIT 1",
-                params![run_id],
+                params![run_id, up_to.min(i64::MAX as Seq) as i64],
                 |row| {
                     let at_seq: i64 = row.get(0)?;
                     let state_json: String = row.get(1)?;
-                    Ok((at_seq, state_json))
+                    let state_version: i64 = row.get(2)?;
+                    let event_count: i64 = row.get(3)?;
+                    let content_hash: Option<String> = row.get(4)?;
+                    Ok((at_seq, state_json, state_version, event_count, content_hash))
                 },
             )
             .optional()
             .map_err(|e| map_snapshot_err("load latest snapshot", e))?;
 
-        match row {
-            Some((at_seq, state_json)) => {
-                let state = serde_json::from_str(&state_json)
-                    .map_err(|e| map_snapshot_err("decode state", e))?;
-                Ok(Some(Snapshot {
+        let Some((at_seq, state_json, state_version, event_count, content_hash)) = row else {
+            return Ok(None);
+        };
+        let at_seq = at_seq as Seq;
+        let state = match serde_json::from_str(&state_json) {
+            Ok(state) => state,
+            Err(e) if content_hash.is_some() => {
+                return Err(KernelError::SnapshotIntegrity {
                     run_id: run_id.clone(),
-                    at_seq: at_seq as Seq,
-                    state,
-                }))
+                    at_seq,
+                    reason: format!("state does not decode: {e}"),
+                })
             }
-            None => Ok(None),
-        }
+            Err(e) => return Err(map_snapshot_err("decode state", e)),
+        };
+        let snapshot = Snapshot {
+            run_id: run_id.clone(),
+            at_seq,
+            state,
+            state_version: state_version as u32,
+            event_count: event_count as u64,
+            content_hash,
+        };
+        snapshot.verify()?;
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(feature = "sqlite-persistence")]
+impl<S> SnapshotStore<S> for SqliteSnapshotStore<S>
+where
+    S: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    fn load_latest(&self, run_id: &RunId) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.load_up_to(run_id, Seq::MAX)
     }
 
     fn save(&self, snapshot: &Snapshot<S>) -> Result<(), KernelError> {
-        let _guard = self
-            .lock
-            .lock()
-            .map_err(|_| map_snapshot_err("lock poisoned", "FATAL: mutex poisoned - previous holder panicked while holding lock; state may be corrupted"))?;
+        let _guard = self.lock()?;
         let conn = self.open_connection()?;
         let json = serde_json::to_string(&snapshot.state)
             .map_err(|e| map_snapshot_err("encode state", e))?;
+        let content_hash = snapshot.compute_content_hash()?;
         conn.execute(
-            "INSERT INTO kernel_snapshots (run_id, at_seq, state_json, created_at_ms)
-             VALUES (?1, ?2, ?3, ?4)
+            "INSERT INTO kernel_snapshots
+                 (run_id, at_seq, state_json, created_at_ms, state_version, event_count, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
              ON CONFLICT (run_id, at_seq)
-             DO UPDATE SET state_json = excluded.state_json, created_at_ms = excluded.created_at_ms",
-            params![snapshot.run_id, snapshot.at_seq as i64, json, now_ms()],
+             DO UPDATE SET state_json = excluded.state_json,
+                           created_at_ms = excluded.created_at_ms,
+                           state_version = excluded.state_version,
+                           event_count = excluded.event_count,
+                           content_hash = excluded.content_hash",
+            params![
+                snapshot.run_id,
+                snapshot.at_seq as i64,
+                json,
+                now_ms(),
+                snapshot.state_version as i64,
+                snapshot.event_count as i64,
+                content_hash
+            ],
         )
         .map_err(|e| map_snapshot_err("save snapshot", e))?;
+        if let Some(keep) = self.retention {
+            Self::prune_with(&conn, &snapshot.run_id, keep)?;
+        }
         Ok(())
     }
+
+    fn load_at_or_before(
+        &self,
+        run_id: &RunId,
+        seq: Seq,
+    ) -> Result<Option<Snapshot<S>>, KernelError> {
+        self.load_up_to(run_id, seq)
+    }
+
+    fn prune(&self, run_id: &RunId, keep: usize) -> Result<usize, KernelError> {
+        let _guard = self.lock()?;
+        let conn = self.open_connection()?;
+        Self::prune_with(&conn, run_id, keep)
+    }
+}
+
+#[cfg(feature = "sqlite-persistence")]
+fn map_cache_err(prefix: &str, err: impl std::fmt::Display) -> KernelError {
+    KernelError::ActionResultCache(format!("{prefix}: {err}"))
+}
+
+/// SQLite-backed [ActionResultCache].
+///
+/// Results live in a `kernel_action_results` table keyed by idempotency key, as the JSON of
+/// their [RecordedOutcome], so a cached output comes back exactly as the executor returned
+/// it. Keep the file next to (or inside) the event log's, so a restarted process finds
+/// the results of the actions its crashed predecessor ran.
+#[cfg(feature = "sqlite-persistence")]
+pub struct SqliteActionResultCache {
+    conn: Mutex<Connection>,
+    ttl: Option<Duration>,
+}
+
+#[cfg(feature = "sqlite-persistence")]
+impl SqliteActionResultCache {
+    /// Open (or create) the cache at `path`, creating parent directories and the schema
+    /// as needed.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, KernelError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| map_cache_err("create parent dir", e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| map_cache_err("open sqlite db", e))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| map_cache_err("set journal_mode", e))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(|e| map_cache_err("set synchronous", e))?;
+        conn.busy_timeout(Duration::from_secs(5))
+            .map_err(|e| map_cache_err("set busy_timeout", e))?;
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS kernel_action_results (
+                idempotency_key TEXT PRIMARY KEY,
+                outcome_json TEXT NOT NULL,
+                stored_at_ms INTEGER NOT NULL
+            );
+            ",
+        )
+        .map_err(|e| map_cache_err("ensure schema", e))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            ttl: None,
+        })
+    }
+
+    /// Results expire `ttl` after they were stored.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, KernelError> {
+        self.conn
+            .lock()
+            .map_err(|e| map_cache_err("lock poisoned", e))
+    }
+
+    /// Results stored at or before this time have expired
+    fn expired_at_ms(&self) -> i64 {
+        self.ttl
+            .map_or(i64::MIN, |ttl| now_ms() - ttl.as_millis() as i64)
+    }
+}
+
+#[cfg(feature = "sqlite-persistence")]
+fn read_cached(
+    conn: &Connection,
+    key: &str,
+    expired_at_ms: i64,
+) -> Result<Option<ActionResult>, KernelError> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT outcome_json FROM kernel_action_results
+             WHERE idempotency_key = ?1 AND stored_at_ms > ?2",
+            params![key, expired_at_ms],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| map_cache_err("read result", e))?;
+    json.map(|json| {
+        let outcome: RecordedOutcome =
+            serde_json::from_str(&json).map_err(|e| map_cache_err("decode result", e))?;
+        outcome.into_result()
+    })
+    .transpose()
+}
+
+#[cfg(feature = "sqlite-persistence")]
+impl ActionResultCache for SqliteActionResultCache {
+    fn get(&self, key: &str) -> Result<Option<ActionResult>, KernelError> {
+        let conn = self.lock()?;
+        read_cached(&conn, key, self.expired_at_ms())
+    }
+
+    fn put(&self, key: &str, result: &ActionResult) -> Result<ActionResult, KernelError> {
+        let json = serde_json::to_string(&RecordedOutcome::from_result(&Ok(result.clone())))
+            .map_err(|e| map_cache_err("encode result", e))?;
+        let expired_at_ms = self.expired_at_ms();
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| map_cache_err("begin transaction", e))?;
+        tx.execute(
+            "DELETE FROM kernel_action_results
+             WHERE idempotency_key = ?1 AND stored_at_ms <= ?2",
+            params![key, expired_at_ms],
+        )
+        .map_err(|e| map_cache_err("drop expired result", e))?;
+        tx.execute(
+            "INSERT OR IGNORE INTO kernel_action_results (idempotency_key, outcome_json, stored_at_ms)
+             VALUES (?1, ?2, ?3)",
+            params![key, json, now_ms()],
+        )
+        .map_err(|e| map_cache_err("store result", e))?;
+        // An expired result under the key was dropped above, so the row is the live one
+        let stored = read_cached(&tx, key, i64::MIN)?;
+        tx.commit()
+            .map_err(|e| map_cache_err("commit transaction", e))?;
+        stored.ok_or_else(|| map_cache_err("store result", "stored result not found"))
+    }
+
+    fn purge_expired(&self) -> Result<usize, KernelError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "DELETE FROM kernel_action_results WHERE stored_at_ms <= ?1",
+            params![self.expired_at_ms()],
+        )
+        .map_err(|e| map_cache_err("purge expired results", e))
+    }
 }
 
 #[cfg(all(test, feature = "sqlite-persistence"))]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    use super::{SqliteEventStore, SqliteSnapshotStore};
-    use crate::kernel::{Event, EventStore, Snapshot, SnapshotStore};
+    use super::{SqliteActionResultCache, SqliteEventStore, SqliteSnapshotStore};
+    use crate::kernel::driver::{Kernel, RunStatus, Signal};
+    use crate::kernel::event_migration::{check_v1_rows_replay, V1_EVENT_ROWS};
+    use crate::kernel::snapshot::verify_all;
+    use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+    use crate::kernel::{
+        ActionResult, ActionResultCache, EffectSink, Event, EventStore, InterruptInfo, KernelError,
+        KernelMode, KernelState, Next, Reducer, RunId, RuntimeEffect, SequencedEvent, Snapshot,
+        SnapshotStore, StepFn,
+    };
 
     fn test_db_path(name: &str) -> std::path::PathBuf {
         let ts = SystemTime::now()
@@ -325,7 +770,7 @@ mod tests {
     #[test]
     fn sqlite_event_store_roundtrip() {
         let path = test_db_path("events");
-        let store = SqliteEventStore::new(&path);
+        let store = SqliteEventStore::new(&path).unwrap();
         let run_id = "run-sqlite-events".to_string();
 
         assert_eq!(store.head(&run_id).unwrap(), 0);
@@ -335,6 +780,8 @@ mod tests {
                 &[Event::StateUpdated {
                     step_id: Some("n1".into()),
                     payload: serde_json::json!({"v": 1}),
+                    state_hash: None,
+                    merge_report: None,
                 }],
             )
             .unwrap();
@@ -358,6 +805,114 @@ mod tests {
         assert_eq!(store.head(&run_id).unwrap(), 3);
     }
 
+    #[test]
+    fn sqlite_action_result_cache_keeps_the_first_result_across_reopen() {
+        let path = test_db_path("action-results");
+        let output = serde_json::json!({"receipt": "r-1", "amount": 12.5, "items": [1, 2]});
+        {
+            let cache = SqliteActionResultCache::new(&path).unwrap();
+            assert!(cache.get("order-7").unwrap().is_none());
+            cache
+                .put("order-7", &ActionResult::Success(output.clone()))
+                .unwrap();
+            let stored = cache
+                .put("order-7", &ActionResult::Failure("declined".into()))
+                .unwrap();
+            assert!(matches!(stored, ActionResult::Success(v) if v == output));
+            cache
+                .put("order-8", &ActionResult::Failure("declined".into()))
+                .unwrap();
+        }
+        let cache = SqliteActionResultCache::new(&path).unwrap();
+        assert!(
+            matches!(cache.get("order-7").unwrap(), Some(ActionResult::Success(v)) if v == output)
+        );
+        assert!(
+            matches!(cache.get("order-8").unwrap(), Some(ActionResult::Failure(e)) if e == "declined")
+        );
+        assert_eq!(cache.purge_expired().unwrap(), 0);
+
+        let expiring = SqliteActionResultCache::new(&path)
+            .unwrap()
+            .with_ttl(std::time::Duration::ZERO);
+        assert!(expiring.get("order-7").unwrap().is_none());
+        let stored = expiring
+            .put("order-7", &ActionResult::Success(serde_json::json!(2)))
+            .unwrap();
+        assert!(matches!(stored, ActionResult::Success(v) if v == serde_json::json!(2)));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert_eq!(expiring.purge_expired().unwrap(), 2);
+    }
+
+    #[test]
+    fn sqlite_event_store_meets_the_contract() {
+        let path = test_db_path("contract");
+        crate::kernel::event_store::check_event_store_contract(
+            &SqliteEventStore::new(&path).unwrap(),
+        );
+    }
+
+    #[test]
+    fn sqlite_event_store_upgrades_unversioned_logs() {
+        let path = test_db_path("v1-log");
+        let run_id = "run-v1".to_string();
+        {
+            // The schema and rows as written before versions were recorded
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE kernel_events (
+                    run_id TEXT NOT NULL,
+                    seq INTEGER NOT NULL,
+                    event_json TEXT NOT NULL,
+                    created_at_ms INTEGER NOT NULL,
+                    PRIMARY KEY (run_id, seq)
+                )",
+            )
+            .unwrap();
+            for (seq, json) in V1_EVENT_ROWS {
+                conn.execute(
+                    "INSERT INTO kernel_events VALUES (?1, ?2, ?3, 0)",
+                    rusqlite::params![run_id, *seq as i64, json],
+                )
+                .unwrap();
+            }
+        }
+        check_v1_rows_replay(Box::new(SqliteEventStore::new(&path).unwrap()), &run_id);
+
+        // A version this build has no migration from is rejected by number
+        let store = SqliteEventStore::new(&path).unwrap();
+        store
+            .conn()
+            .unwrap()
+            .execute(
+                "UPDATE kernel_events SET event_version = 9 WHERE run_id = ?1 AND seq = 3",
+                rusqlite::params![run_id],
+            )
+            .unwrap();
+        let err = store.scan(&run_id, 1).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                KernelError::UnsupportedEventVersion {
+                    seq: 3,
+                    version: 9,
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+        assert_eq!(store.scan(&run_id, 4).unwrap().len(), 5);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn sqlite_event_store_rejects_unopenable_paths() {
+        let dir = test_db_path("not-a-file");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(SqliteEventStore::new(&dir).is_err());
+    }
+
     #[test]
     fn sqlite_snapshot_store_roundtrip() {
         let path = test_db_path("snapshots");
@@ -369,6 +924,9 @@ mod tests {
                 run_id: run_id.clone(),
                 at_seq: 9,
                 state: serde_json::json!({"k": "v"}),
+                state_version: 1,
+                event_count: 9,
+                content_hash: None,
             })
             .unwrap();
 
@@ -377,6 +935,338 @@ mod tests {
         assert_eq!(latest.state["k"], "v");
     }
 
+    fn value_snapshot(run_id: &str, at_seq: u64) -> Snapshot<serde_json::Value> {
+        Snapshot {
+            run_id: run_id.to_string(),
+            at_seq,
+            state: serde_json::json!({ "at": at_seq }),
+            state_version: 2,
+            event_count: at_seq,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn sqlite_snapshot_store_loads_at_or_before_and_prunes() {
+        let path = test_db_path("snapshots-at-or-before");
+        let store: SqliteSnapshotStore<serde_json::Value> = SqliteSnapshotStore::new(&path);
+        let run_id = "run-sqlite-at-or-before";
+        for at_seq in [2, 4, 6, 8] {
+            store.save(&value_snapshot(run_id, at_seq)).unwrap();
+        }
+        store.save(&value_snapshot("other-run", 1)).unwrap();
+
+        let at_5 = store.load_at_or_before(&run_id.into(), 5).unwrap().unwrap();
+        assert_eq!((at_5.at_seq, at_5.state_version), (4, 2));
+        assert_eq!(
+            store
+                .load_at_or_before(&run_id.into(), 6)
+                .unwrap()
+                .unwrap()
+                .at_seq,
+            6
+        );
+        assert!(store
+            .load_at_or_before(&run_id.into(), 1)
+            .unwrap()
+            .is_none());
+
+        assert_eq!(store.prune(&run_id.into(), 2).unwrap(), 2);
+        assert_eq!(store.prune(&run_id.into(), 2).unwrap(), 0);
+        assert!(store
+            .load_at_or_before(&run_id.into(), 5)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store.load_latest(&run_id.into()).unwrap().unwrap().at_seq,
+            8
+        );
+        assert!(store.load_latest(&"other-run".into()).unwrap().is_some());
+    }
+
+    #[test]
+    fn sqlite_snapshot_store_retention_prunes_on_save() {
+        let path = test_db_path("snapshots-retention");
+        let store: SqliteSnapshotStore<serde_json::Value> =
+            SqliteSnapshotStore::new(&path).with_retention(1);
+        let run_id = "run-sqlite-retention";
+        for at_seq in 1..=3 {
+            store.save(&value_snapshot(run_id, at_seq)).unwrap();
+        }
+        assert_eq!(
+            store.load_latest(&run_id.into()).unwrap().unwrap().at_seq,
+            3
+        );
+        assert!(store
+            .load_at_or_before(&run_id.into(), 2)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn sqlite_snapshot_store_adds_the_version_column_to_old_tables() {
+        let path = test_db_path("snapshots-unversioned");
+        {
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE kernel_snapshots (
+                    run_id TEXT NOT NULL,
+                    at_seq INTEGER NOT NULL,
+                    state_json TEXT NOT NULL,
+                    created_at_ms INTEGER NOT NULL,
+                    PRIMARY KEY (run_id, at_seq)
+                );
+                INSERT INTO kernel_snapshots VALUES ('run-old', 3, '{\"n\":3}', 0);",
+            )
+            .unwrap();
+        }
+        let store: SqliteSnapshotStore<serde_json::Value> = SqliteSnapshotStore::new(&path);
+        let old = store.load_latest(&"run-old".into()).unwrap().unwrap();
+        assert_eq!((old.at_seq, old.state_version), (3, 0));
+        assert_eq!(old.state["n"], 3);
+
+        store.save(&value_snapshot("run-old", 4)).unwrap();
+        assert_eq!(
+            store
+                .load_latest(&"run-old".into())
+                .unwrap()
+                .unwrap()
+                .state_version,
+            2
+        );
+    }
+
+    /// Steps taken, and whether the run was resumed, rebuilt from the run's events
+    #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+    struct Steps {
+        taken: u32,
+        resumed: bool,
+    }
+
+    impl KernelState for Steps {
+        fn version(&self) -> u32 {
+            3
+        }
+    }
+
+    /// Counts the events it applies
+    struct CountingReducer(Arc<AtomicUsize>);
+
+    impl Reducer<Steps> for CountingReducer {
+        fn apply(&self, state: &mut Steps, event: &SequencedEvent) -> Result<(), KernelError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            match &event.event {
+                Event::StateUpdated { .. } => state.taken += 1,
+                Event::Resumed { .. } => state.resumed = true,
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    /// Takes three steps, waits for a resume, then completes
+    struct ThreeStepsThenApproval;
+
+    impl StepFn<Steps> for ThreeStepsThenApproval {
+        fn next(&self, state: &Steps) -> Result<Next, KernelError> {
+            Ok(if state.taken < 3 {
+                Next::Emit(vec![Event::StateUpdated {
+                    step_id: Some(format!("step-{}", state.taken + 1)),
+                    payload: serde_json::json!({ "taken": state.taken + 1 }),
+                    state_hash: None,
+                    merge_report: None,
+                }])
+            } else if !state.resumed {
+                Next::Interrupt(InterruptInfo {
+                    value: serde_json::json!("approve?"),
+                })
+            } else {
+                Next::Complete
+            })
+        }
+    }
+
+    fn sqlite_kernel(path: &std::path::Path, applied: Arc<AtomicUsize>) -> Kernel<Steps> {
+        Kernel {
+            events: Box::new(SqliteEventStore::new(path).unwrap()),
+            snaps: Some(Box::new(SqliteSnapshotStore::new(path).with_retention(2))),
+            reducer: Box::new(CountingReducer(applied)),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(ThreeStepsThenApproval),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        }
+    }
+
+    #[test]
+    fn restarted_kernel_resumes_from_the_persisted_snapshot() {
+        let path = test_db_path("restart-resume");
+        let run_id = "run-restart-resume".to_string();
+
+        // Run until the interrupt (seq 4), then drop the kernel as a restart would
+        {
+            let kernel = sqlite_kernel(&path, Arc::new(AtomicUsize::new(0)));
+            let status = kernel.run_until_blocked(&run_id, Steps::default()).unwrap();
+            assert!(matches!(status, RunStatus::Blocked(_)));
+        }
+
+        // A new process resumes: only Resumed (5) and Completed (6) are applied, on top
+        // of the snapshot at seq 4, instead of replaying from seq 1
+        let applied = Arc::new(AtomicUsize::new(0));
+        let kernel = sqlite_kernel(&path, applied.clone());
+        let status = kernel
+            .resume(
+                &run_id,
+                Steps::default(),
+                Signal::Resume(serde_json::json!(true)),
+            )
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        assert_eq!(applied.load(Ordering::SeqCst), 2);
+
+        let snaps: SqliteSnapshotStore<Steps> = SqliteSnapshotStore::new(&path);
+        let latest = snaps.load_latest(&run_id).unwrap().unwrap();
+        assert_eq!((latest.at_seq, latest.state_version), (6, 3));
+        assert_eq!(latest.state.taken, 3);
+        assert!(latest.state.resumed);
+        // Retention kept the two latest snapshots
+        assert!(snaps.load_at_or_before(&run_id, 4).unwrap().is_none());
+    }
+
+    /// Rewrites the state of the run's latest stored snapshot with `corrupt`
+    fn corrupt_latest_snapshot(path: &std::path::Path, run_id: &str, corrupt: fn(&str) -> String) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        let (at_seq, json): (i64, String) = conn
+            .query_row(
+                "SELECT at_seq, state_json FROM kernel_snapshots
+                 WHERE run_id = ?1 ORDER BY at_seq DESC LIMIT 1",
+                [run_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        conn.execute(
+            "UPDATE kernel_snapshots SET state_json = ?3 WHERE run_id = ?1 AND at_seq = ?2",
+            rusqlite::params![run_id, at_seq, corrupt(&json)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn sqlite_snapshot_store_rejects_corrupted_snapshots() {
+        let path = test_db_path("snapshots-corrupt");
+        let store: SqliteSnapshotStore<serde_json::Value> = SqliteSnapshotStore::new(&path);
+        store.save(&value_snapshot("run-flipped", 5)).unwrap();
+        let saved = store.load_latest(&"run-flipped".into()).unwrap().unwrap();
+        assert_eq!(
+            saved.content_hash,
+            Some(saved.compute_content_hash().unwrap())
+        );
+
+        // One byte of the state changed: still valid JSON, but not what was saved
+        corrupt_latest_snapshot(&path, "run-flipped", |json| json.replacen('5', "6", 1));
+        let err = store.load_latest(&"run-flipped".into()).unwrap_err();
+        assert!(matches!(
+            err,
+            KernelError::SnapshotIntegrity { at_seq: 5, .. }
+        ));
+        assert_eq!(err.code(), "SNAPSHOT_INTEGRITY");
+
+        store.save(&value_snapshot("run-truncated", 2)).unwrap();
+        corrupt_latest_snapshot(&path, "run-truncated", |json| json[..json.len() - 1].into());
+        assert!(matches!(
+            store.load_at_or_before(&"run-truncated".into(), 2),
+            Err(KernelError::SnapshotIntegrity { at_seq: 2, .. })
+        ));
+    }
+
+    /// Keeps the effects the kernel records
+    #[derive(Clone, Default)]
+    struct CollectedEffects(Arc<std::sync::Mutex<Vec<RuntimeEffect>>>);
+
+    impl EffectSink for CollectedEffects {
+        fn record(&self, _run_id: &RunId, effect: &RuntimeEffect) {
+            self.0.lock().unwrap().push(effect.clone());
+        }
+    }
+
+    #[test]
+    fn corrupted_snapshot_falls_back_to_full_replay() {
+        let path = test_db_path("corrupt-resume");
+        let run_id = "run-corrupt-resume".to_string();
+        {
+            let kernel = sqlite_kernel(&path, Arc::new(AtomicUsize::new(0)));
+            let status = kernel.run_until_blocked(&run_id, Steps::default()).unwrap();
+            assert!(matches!(status, RunStatus::Blocked(_)));
+        }
+        // The snapshot at seq 4 now claims 7 steps were taken
+        corrupt_latest_snapshot(&path, &run_id, |json| json.replacen('3', "7", 1));
+
+        let applied = Arc::new(AtomicUsize::new(0));
+        let effects = CollectedEffects::default();
+        let mut kernel = sqlite_kernel(&path, applied.clone());
+        kernel.effect_sink = Some(Box::new(effects.clone()));
+        let status = kernel
+            .resume(
+                &run_id,
+                Steps::default(),
+                Signal::Resume(serde_json::json!(true)),
+            )
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        // All six events were replayed instead of the two after the snapshot
+        assert_eq!(applied.load(Ordering::SeqCst), 6);
+        assert!(matches!(
+            effects.0.lock().unwrap().as_slice(),
+            [RuntimeEffect::SnapshotIntegrityFailure { at_seq: 4, .. }]
+        ));
+
+        let snaps: SqliteSnapshotStore<Steps> = SqliteSnapshotStore::new(&path);
+        let latest = snaps.load_latest(&run_id).unwrap().unwrap();
+        assert_eq!((latest.at_seq, latest.event_count), (6, 6));
+        assert_eq!(latest.state.taken, 3);
+        assert!(latest.state.resumed);
+    }
+
+    #[test]
+    fn verify_all_reports_snapshots_that_differ_from_the_log() {
+        let path = test_db_path("verify-all");
+        let run_id = "run-verify-all".to_string();
+        let events = SqliteEventStore::new(&path).unwrap();
+        let snaps: SqliteSnapshotStore<Steps> = SqliteSnapshotStore::new(&path);
+        let reducer = CountingReducer(Arc::new(AtomicUsize::new(0)));
+        {
+            let kernel = sqlite_kernel(&path, Arc::new(AtomicUsize::new(0)));
+            kernel.run_until_blocked(&run_id, Steps::default()).unwrap();
+        }
+        let audit = || verify_all(&snaps, &events, &reducer, &run_id, Steps::default()).unwrap();
+        assert_eq!(audit(), vec![]);
+
+        // Stored with a valid hash, but not the state the log produces at seq 2
+        snaps
+            .save(&Snapshot {
+                run_id: run_id.clone(),
+                at_seq: 2,
+                state: Steps {
+                    taken: 5,
+                    resumed: false,
+                },
+                state_version: 3,
+                event_count: 2,
+                content_hash: None,
+            })
+            .unwrap();
+        corrupt_latest_snapshot(&path, &run_id, |json| json.replacen('3', "1", 1));
+        let mismatches = audit();
+        assert_eq!(
+            mismatches.iter().map(|m| m.at_seq).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert!(mismatches[0].reason.contains("replay"));
+        assert!(mismatches[1].reason.contains("content hash"));
+    }
+
     // -----------------------------------------------------------------------
     // Issue #373: SQLite crash-recovery tests
     // -----------------------------------------------------------------------
@@ -388,7 +1278,7 @@ mod tests {
 
         // Phase 1: Write events then "crash" (drop store)
         {
-            let store = SqliteEventStore::new(&path);
+            let store = SqliteEventStore::new(&path).unwrap();
             store
                 .append(
                     &run_id,
@@ -396,14 +1286,20 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n1".into()),
                             payload: serde_json::json!({"v": 1}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n2".into()),
                             payload: serde_json::json!({"v": 2}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n3".into()),
                             payload: serde_json::json!({"v": 3}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                     ],
                 )
@@ -412,7 +1308,7 @@ mod tests {
 
         // Phase 2: Reopen and verify all events survived
         {
-            let store = SqliteEventStore::new(&path);
+            let store = SqliteEventStore::new(&path).unwrap();
             assert_eq!(store.head(&run_id).unwrap(), 3);
 
             let events = store.scan(&run_id, 1).unwrap();
@@ -435,6 +1331,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 5,
                     state: serde_json::json!({"counter": 42}),
+                    state_version: 1,
+                    event_count: 5,
+                    content_hash: None,
                 })
                 .unwrap();
         } // store dropped
@@ -455,7 +1354,7 @@ mod tests {
 
         // Phase 1: Write events and snapshot, then "crash"
         {
-            let event_store = SqliteEventStore::new(&path);
+            let event_store = SqliteEventStore::new(&path).unwrap();
             let snap_store: SqliteSnapshotStore<serde_json::Value> =
                 SqliteSnapshotStore::new(&path);
 
@@ -467,10 +1366,14 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n1".into()),
                             payload: serde_json::json!({"v": 1}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n2".into()),
                             payload: serde_json::json!({"v": 2}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                     ],
                 )
@@ -482,6 +1385,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 2,
                     state: serde_json::json!({"v": 2}),
+                    state_version: 1,
+                    event_count: 2,
+                    content_hash: None,
                 })
                 .unwrap();
 
@@ -493,10 +1399,14 @@ mod tests {
                         Event::StateUpdated {
                             step_id: Some("n3".into()),
                             payload: serde_json::json!({"v": 3}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::StateUpdated {
                             step_id: Some("n4".into()),
                             payload: serde_json::json!({"v": 4}),
+                            state_hash: None,
+                            merge_report: None,
                         },
                         Event::Completed,
                     ],
@@ -506,7 +1416,7 @@ mod tests {
 
         // Phase 2: Reopen and verify recovery path
         {
-            let event_store = SqliteEventStore::new(&path);
+            let event_store = SqliteEventStore::new(&path).unwrap();
             let snap_store: SqliteSnapshotStore<serde_json::Value> =
                 SqliteSnapshotStore::new(&path);
 
@@ -538,6 +1448,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 3,
                     state: serde_json::json!({"v": 3}),
+                    state_version: 1,
+                    event_count: 3,
+                    content_hash: None,
                 })
                 .unwrap();
             store
@@ -545,6 +1458,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 7,
                     state: serde_json::json!({"v": 7}),
+                    state_version: 1,
+                    event_count: 7,
+                    content_hash: None,
                 })
                 .unwrap();
             store
@@ -552,6 +1468,9 @@ mod tests {
                     run_id: run_id.clone(),
                     at_seq: 5,
                     state: serde_json::json!({"v": 5}),
+                    state_version: 1,
+                    event_count: 5,
+                    content_hash: None,
                 })
                 .unwrap();
         } // crash
@@ -572,13 +1491,15 @@ mod tests {
 
         // Phase 1: Append some events
         {
-            let store = SqliteEventStore::new(&path);
+            let store = SqliteEventStore::new(&path).unwrap();
             store
                 .append(
                     &run_id,
                     &[Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::json!({"v": 1}),
+                        state_hash: None,
+                        merge_report: None,
                     }],
                 )
                 .unwrap();
@@ -586,13 +1507,15 @@ mod tests {
 
         // Phase 2: Reopen and append more — sequence must continue
         {
-            let store = SqliteEventStore::new(&path);
+            let store = SqliteEventStore::new(&path).unwrap();
             let seq = store
                 .append(
                     &run_id,
                     &[Event::StateUpdated {
                         step_id: Some("n2".into()),
                         payload: serde_json::json!({"v": 2}),
+                        state_hash: None,
+                        merge_report: None,
                     }],
                 )
                 .unwrap();
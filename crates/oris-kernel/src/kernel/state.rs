@@ -2,10 +2,30 @@
 //!
 //! State must be serializable and versioned for schema evolution (2.0 migration).
 
+use crate::kernel::event::KernelError;
+
 /// Kernel state: cloneable, send, sync, and with a schema version for migrations.
 ///
 /// Existing graph::State can implement this by adding `fn version(&self) -> u32` (e.g. returning 1).
 pub trait KernelState: Clone + Send + Sync + 'static {
     /// Schema version for state migration (e.g. 1, 2, ...).
     fn version(&self) -> u32;
+
+    /// Hash of this state that the driver records in each `StateUpdated` event it writes;
+    /// `None` (the default) records no hash.
+    ///
+    /// Serializable states should return
+    /// [canonical_state_hash](crate::kernel::canonical::canonical_state_hash)`(self)`, so the
+    /// recorded hashes match those of
+    /// [build_execution_log_with_hashes](crate::kernel::build_execution_log_with_hashes).
+    fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+        Ok(None)
+    }
+
+    /// Approximate size of this state in a snapshot, in bytes, checked against
+    /// [SnapshotPolicy::max_state_bytes](crate::kernel::SnapshotPolicy::max_state_bytes);
+    /// `None` (the default) means unknown, and the limit does not apply.
+    fn snapshot_size(&self) -> Option<usize> {
+        None
+    }
 }
@@ -1,4 +1,4 @@
-//! Step function: given state, decide next (emit events, do action, interrupt, or complete).
+//! Step function: given state, decide next (emit events, do action, interrupt, fail, or complete).
 //!
 //! Graph/Agent compile down to a StepFn.
 
@@ -23,12 +23,45 @@ pub enum Next {
     Emit(Vec<Event>),
     /// Request one external action (policy + executor; result becomes events).
     Do(Action),
+    /// Request independent actions the driver may execute concurrently, at most
+    /// [Policy::max_parallel_actions](crate::kernel::Policy::max_parallel_actions) at a time.
+    /// Their events are appended in batch order whatever order they finish in.
+    DoAll(ActionBatch),
     /// Pause for interrupt (e.g. human approval).
     Interrupt(InterruptInfo),
+    /// Stop the run with a reason (e.g. step limit exceeded); recorded as `Event::Failed`.
+    Fail(String),
+    /// Like [`Next::Fail`], also recording a machine-readable reason code on the event.
+    FailWithCode { code: String, reason: String },
     /// Run is complete.
     Complete,
 }
 
+/// Independent actions requested by one step; see [Next::DoAll].
+#[derive(Clone, Debug)]
+pub struct ActionBatch {
+    pub actions: Vec<Action>,
+    /// Once an action fails, actions of the batch that have not started are skipped and
+    /// recorded as failed. Actions already running finish either way.
+    pub fail_fast: bool,
+}
+
+impl ActionBatch {
+    /// A batch in which a failure does not stop the other actions.
+    pub fn new(actions: Vec<Action>) -> Self {
+        Self {
+            actions,
+            fail_fast: false,
+        }
+    }
+
+    /// Skips the actions that have not started once one fails.
+    pub fn fail_fast(mut self) -> Self {
+        self.fail_fast = true;
+        self
+    }
+}
+
 /// Step function: given current state, returns the next action (emit / do / interrupt / complete).
 /// Graph and Agent are compiled to this interface.
 pub trait StepFn<S: KernelState>: Send + Sync {
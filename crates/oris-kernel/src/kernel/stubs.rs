@@ -32,6 +32,10 @@ impl<S: KernelState> StepFn<S> for NoopStepFn {
 pub struct AllowAllPolicy;
 
 impl Policy for AllowAllPolicy {
+    fn name(&self) -> String {
+        "allow_all".into()
+    }
+
     fn authorize(
         &self,
         _run_id: &RunId,
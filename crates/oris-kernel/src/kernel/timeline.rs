@@ -1,23 +1,71 @@
 //! Run timeline: observable sequence of events for a run (audit, debugging).
 //!
-//! Built from EventStore; can be serialized to JSON for UI/CLI.
+//! Built from EventStore; can be exported as JSON ([RunTimeline::to_json]) for a UI, or as
+//! a compact table ([RunTimeline::to_text]) for a terminal. [summarize_runs] gives the
+//! matching view over all runs of a store.
 
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::kernel::event::{Event, EventStore};
+use crate::kernel::action::Action;
+use crate::kernel::event::{Event, EventFilter, EventKind, EventStore, SequencedEvent};
 use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunStatusKind};
 use crate::kernel::KernelError;
 
+/// Longest JSON value a `detail` quotes before truncating it.
+const DETAIL_VALUE_MAX_CHARS: usize = 80;
+
 /// One entry in a run timeline (summary of an event at a given seq).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TimelineEntry {
     pub seq: Seq,
     /// Event kind: StateUpdated, ActionRequested, ActionSucceeded, ActionFailed, Interrupted, Resumed, Completed.
     pub kind: String,
+    /// When the event was appended (Unix milliseconds), if the store recorded it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at_ms: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub step_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub action_id: Option<String>,
+    /// [Action::kind] of the action the event is about, e.g. `search` or `llm/openai`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_kind: Option<String>,
+    /// Wall-clock time the event closes: for a `StateUpdated`, the step since the previous
+    /// one (or the resume or first event before it); for an action's result, the action
+    /// since its `ActionRequested`. `None` when either end is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// One-line summary: a policy decision or denial, e.g.
+    /// `deny search by allow_list (POLICY_DENIED): tool not allowed: search`, an action's
+    /// output or error, an interrupt or resume value, or why the run stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Totals over a timeline's entries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineSummary {
+    /// `StateUpdated` events.
+    pub steps: u64,
+    /// `ActionRequested` events by [Action::kind]; `unknown` for payloads that are not an
+    /// [Action].
+    pub actions: BTreeMap<String, u64>,
+    /// `RetryScheduled` events.
+    pub retries: u64,
+    /// `Interrupted` events.
+    pub interrupts: u64,
+    /// Why the run has its `final_status`: the failure, denial, cancellation or budget
+    /// reason, or the pending interrupt's value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Wall-clock time from the first entry to the last, when both were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
 }
 
 /// Full timeline for a run: ordered events and final status.
@@ -26,15 +74,127 @@ pub struct RunTimeline {
     pub run_id: String,
     pub events: Vec<TimelineEntry>,
     pub final_status: RunStatusSummary,
+    #[serde(default)]
+    pub summary: TimelineSummary,
+}
+
+impl RunTimeline {
+    /// The timeline as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, KernelError> {
+        serde_json::to_string_pretty(self).map_err(|e| KernelError::Driver(e.to_string()))
+    }
+
+    /// The timeline as a compact table for terminals: a header line with the final status
+    /// and totals, then one row per entry.
+    pub fn to_text(&self) -> String {
+        let s = &self.summary;
+        let mut totals = vec![format!("{} steps", s.steps)];
+        if !s.actions.is_empty() {
+            let kinds: Vec<_> = s
+                .actions
+                .iter()
+                .map(|(kind, n)| format!("{}={}", kind, n))
+                .collect();
+            totals.push(format!("actions {}", kinds.join(" ")));
+        }
+        if s.retries > 0 {
+            totals.push(format!("{} retries", s.retries));
+        }
+        if s.interrupts > 0 {
+            totals.push(format!("{} interrupts", s.interrupts));
+        }
+        if let Some(ms) = s.duration_ms {
+            totals.push(format!("{}ms", ms));
+        }
+        let mut out = format!(
+            "run {}: {} ({})\n",
+            self.run_id,
+            self.final_status.label(),
+            totals.join(", ")
+        );
+        if let Some(reason) = &s.reason {
+            out.push_str(&format!("reason: {}\n", reason));
+        }
+
+        let header = [
+            "SEQ",
+            "TIME",
+            "EVENT",
+            "STEP/ACTION",
+            "KIND",
+            "MS",
+            "DETAIL",
+        ];
+        let rows: Vec<[String; 7]> = self
+            .events
+            .iter()
+            .map(|e| {
+                [
+                    e.seq.to_string(),
+                    e.recorded_at_ms.map(format_time).unwrap_or_default(),
+                    e.kind.clone(),
+                    e.step_id
+                        .clone()
+                        .or_else(|| e.action_id.clone())
+                        .unwrap_or_default(),
+                    e.action_kind.clone().unwrap_or_default(),
+                    e.duration_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+                    e.detail.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut push_row = |cells: [&str; 7]| {
+            let line: Vec<_> = cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            out.push_str(line.join("  ").trim_end());
+            out.push('\n');
+        };
+        push_row(header);
+        for row in &rows {
+            push_row(row.each_ref().map(String::as_str));
+        }
+        out
+    }
 }
 
 /// Summary of run outcome (for JSON/timeline; mirrors RunStatus).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "status")]
 pub enum RunStatusSummary {
     Completed,
-    Blocked { interrupt: bool },
-    Failed { recoverable: bool },
+    Blocked {
+        interrupt: bool,
+    },
+    Failed {
+        recoverable: bool,
+    },
+    /// Paused on request; running the run again continues it.
+    Paused,
+    Cancelled,
+}
+
+impl RunStatusSummary {
+    /// Short human-readable form, e.g. `blocked (interrupt)`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            RunStatusSummary::Completed => "completed",
+            RunStatusSummary::Blocked { interrupt: true } => "blocked (interrupt)",
+            RunStatusSummary::Blocked { interrupt: false } => "blocked",
+            RunStatusSummary::Failed { recoverable: true } => "failed (recoverable)",
+            RunStatusSummary::Failed { recoverable: false } => "failed",
+            RunStatusSummary::Paused => "paused",
+            RunStatusSummary::Cancelled => "cancelled",
+        }
+    }
 }
 
 /// Build a RunTimeline from an event store by scanning all events for the run
@@ -42,53 +202,381 @@ pub enum RunStatusSummary {
 pub fn run_timeline(events: &dyn EventStore, run_id: &RunId) -> Result<RunTimeline, KernelError> {
     const FROM_SEQ: Seq = 1;
     let sequenced = events.scan(run_id, FROM_SEQ)?;
-    let mut entries = Vec::new();
-    let mut final_status = RunStatusSummary::Completed;
+    Ok(timeline_of(run_id, &sequenced))
+}
 
+/// The timeline of a run whose whole log is `sequenced`
+fn timeline_of(run_id: &RunId, sequenced: &[SequencedEvent]) -> RunTimeline {
+    let mut builder = TimelineBuilder::default();
+    let mut final_status = RunStatusSummary::Completed;
+    let mut reason = None;
     for se in sequenced {
-        let (kind, step_id, action_id) = match &se.event {
+        let entry = builder.push(se, true);
+        if let Some(status) = status_after(&se.event) {
+            final_status = status;
+            reason = entry.detail.clone();
+        }
+    }
+    builder.finish(run_id, final_status, reason)
+}
+
+/// Build a RunTimeline holding only the events with `from <= seq <= to` that match
+/// `filter`, without reading the rest of the log.
+///
+/// `final_status` and `summary.reason` still describe the whole run; they are read from
+/// the last status-changing event alone. The other totals, and durations, only count
+/// entries in the window. Out-of-range bounds give an empty `events`.
+pub fn run_timeline_range(
+    events: &dyn EventStore,
+    run_id: &RunId,
+    from: Seq,
+    to: Seq,
+    filter: &EventFilter,
+) -> Result<RunTimeline, KernelError> {
+    let mut builder = TimelineBuilder::default();
+    for se in &events.scan_range(run_id, from, to, filter)? {
+        builder.push(se, false);
+    }
+    let last_status = events.scan_rev(run_id, 1, &status_events())?;
+    let (final_status, reason) = match last_status.first() {
+        Some(se) => (
+            status_after(&se.event).unwrap_or(RunStatusSummary::Completed),
+            detail(&se.event),
+        ),
+        None => (RunStatusSummary::Completed, None),
+    };
+
+    Ok(builder.finish(run_id, final_status, reason))
+}
+
+/// How many oldest blocked runs and failure reasons a [FleetSummary] lists.
+pub const FLEET_SUMMARY_TOP_N: usize = 10;
+
+/// Where the runs of an event store stand, for dashboards; see [summarize_runs].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FleetSummary {
+    /// Runs matching the filter.
+    pub total: u64,
+    /// Runs by derived status; statuses no run has are left out.
+    pub by_status: BTreeMap<RunStatusKind, u64>,
+    /// The runs blocked the longest, oldest first; at most [FLEET_SUMMARY_TOP_N].
+    pub oldest_blocked: Vec<BlockedRun>,
+    /// Failed runs by reason code, most frequent first (ties by code); at most
+    /// [FLEET_SUMMARY_TOP_N].
+    pub failure_reasons: Vec<ReasonCount>,
+    /// When the summary was taken; blocked ages are measured up to it.
+    pub generated_at: DateTime<Utc>,
+}
+
+impl FleetSummary {
+    /// Runs with `status`.
+    pub fn count(&self, status: RunStatusKind) -> u64 {
+        self.by_status.get(&status).copied().unwrap_or(0)
+    }
+
+    /// How long the oldest blocked run has been blocked, if any run is.
+    pub fn oldest_blocked_age_ms(&self) -> Option<u64> {
+        self.oldest_blocked.first().map(|run| run.age_ms)
+    }
+}
+
+/// A blocked run in a [FleetSummary].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockedRun {
+    pub run_id: RunId,
+    /// `Interrupted` or `BudgetExceeded`.
+    pub last_event_kind: String,
+    /// When the event that blocked it was stored.
+    pub blocked_since: DateTime<Utc>,
+    pub age_ms: u64,
+}
+
+/// How many failed runs of a [FleetSummary] share a reason code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasonCount {
+    /// The `Failed` event's code, or the kind of the event that failed the run when it
+    /// has none (`Failed`, `ActionFailed`, `ActionDenied`).
+    pub code: String,
+    pub runs: u64,
+}
+
+/// Counts the runs of `store` matching `filter` by status, with the longest-blocked runs
+/// and the most common failure reasons.
+///
+/// Pages through [EventStore::list_runs] and reads only the last status event of each
+/// failed run, so it works on any store that lists runs without loading their logs.
+pub fn summarize_runs(
+    store: &dyn EventStore,
+    filter: RunFilter,
+) -> Result<FleetSummary, KernelError> {
+    summarize_runs_at(store, filter, Utc::now())
+}
+
+fn summarize_runs_at(
+    store: &dyn EventStore,
+    filter: RunFilter,
+    now: DateTime<Utc>,
+) -> Result<FleetSummary, KernelError> {
+    let mut total = 0;
+    let mut by_status = BTreeMap::new();
+    let mut oldest_blocked: Vec<BlockedRun> = Vec::new();
+    let mut reasons: HashMap<String, u64> = HashMap::new();
+    let mut offset = 0;
+    loop {
+        let page = PageRequest::new(offset, PageRequest::DEFAULT_LIMIT);
+        let runs = store.list_runs(filter.clone(), page)?;
+        for run in &runs {
+            total += 1;
+            *by_status.entry(run.status).or_default() += 1;
+            match run.status {
+                RunStatusKind::Blocked => oldest_blocked.push(BlockedRun {
+                    run_id: run.run_id.clone(),
+                    last_event_kind: run.last_event_kind.clone(),
+                    blocked_since: run.last_event_at,
+                    age_ms: (now - run.last_event_at).num_milliseconds().max(0) as u64,
+                }),
+                RunStatusKind::Failed => {
+                    let last = store.scan_rev(&run.run_id, 1, &status_events())?;
+                    let code = match last.first().map(|se| &se.event) {
+                        Some(Event::Failed {
+                            code: Some(code), ..
+                        }) => code.clone(),
+                        _ => run.last_event_kind.clone(),
+                    };
+                    *reasons.entry(code).or_default() += 1;
+                }
+                _ => {}
+            }
+        }
+        // Keep only the oldest, so memory stays bounded however many runs are blocked
+        oldest_blocked
+            .sort_by(|a, b| (a.blocked_since, &a.run_id).cmp(&(b.blocked_since, &b.run_id)));
+        oldest_blocked.truncate(FLEET_SUMMARY_TOP_N);
+        if runs.len() < page.limit {
+            break;
+        }
+        offset += runs.len();
+    }
+
+    let mut failure_reasons: Vec<_> = reasons
+        .into_iter()
+        .map(|(code, runs)| ReasonCount { code, runs })
+        .collect();
+    failure_reasons.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.code.cmp(&b.code)));
+    failure_reasons.truncate(FLEET_SUMMARY_TOP_N);
+
+    Ok(FleetSummary {
+        total,
+        by_status,
+        oldest_blocked,
+        failure_reasons,
+        generated_at: now,
+    })
+}
+
+/// The kinds of event [status_after] gives a status for
+pub(crate) fn status_events() -> EventFilter {
+    EventFilter::only([
+        EventKind::ActionFailed,
+        EventKind::ActionDenied,
+        EventKind::Interrupted,
+        EventKind::Failed,
+        EventKind::Completed,
+        EventKind::Paused,
+        EventKind::BudgetExceeded,
+        EventKind::Cancelled,
+    ])
+}
+
+/// The run status once `event` is applied, if the event changes it
+pub(crate) fn status_after(event: &Event) -> Option<RunStatusSummary> {
+    match event {
+        Event::ActionFailed { .. } => Some(RunStatusSummary::Failed { recoverable: false }),
+        Event::ActionDenied { .. } => Some(RunStatusSummary::Failed { recoverable: true }),
+        Event::Interrupted { .. } => Some(RunStatusSummary::Blocked { interrupt: true }),
+        Event::Failed { .. } => Some(RunStatusSummary::Failed { recoverable: true }),
+        Event::Completed => Some(RunStatusSummary::Completed),
+        Event::Paused => Some(RunStatusSummary::Paused),
+        Event::BudgetExceeded { .. } => Some(RunStatusSummary::Blocked { interrupt: false }),
+        Event::Cancelled { .. } => Some(RunStatusSummary::Cancelled),
+        _ => None,
+    }
+}
+
+/// Turns events into entries, carrying what later entries need from earlier ones.
+#[derive(Default)]
+struct TimelineBuilder {
+    entries: Vec<TimelineEntry>,
+    summary: TimelineSummary,
+    /// Kind and request time of the actions requested so far, by action id
+    requested: HashMap<String, (Option<String>, Option<i64>)>,
+    /// When the current step started
+    step_started_at: Option<i64>,
+}
+
+impl TimelineBuilder {
+    /// Adds the entry for `se`. With `from_start`, the first event starts the first step;
+    /// otherwise (a window of the log) steps are only timed from a `StateUpdated` or
+    /// `Resumed` seen here.
+    fn push(&mut self, se: &SequencedEvent, from_start: bool) -> &TimelineEntry {
+        let at = se.recorded_at_ms;
+        if from_start && self.entries.is_empty() {
+            self.step_started_at = at;
+        }
+        let mut entry = TimelineEntry {
+            seq: se.seq,
+            kind: se.event.kind().to_string(),
+            recorded_at_ms: at,
+            step_id: None,
+            action_id: None,
+            action_kind: None,
+            duration_ms: None,
+            detail: detail(&se.event),
+        };
+        match &se.event {
             Event::StateUpdated { step_id, .. } => {
-                ("StateUpdated".to_string(), step_id.clone(), None)
+                self.summary.steps += 1;
+                entry.step_id = step_id.clone();
+                entry.duration_ms = elapsed(self.step_started_at, at);
+                self.step_started_at = at;
             }
-            Event::ActionRequested { action_id, .. } => {
-                ("ActionRequested".to_string(), None, Some(action_id.clone()))
+            Event::ActionRequested { action_id, payload } => {
+                let kind = action_kind(payload);
+                *self
+                    .summary
+                    .actions
+                    .entry(kind.clone().unwrap_or_else(|| "unknown".into()))
+                    .or_default() += 1;
+                self.requested.insert(action_id.clone(), (kind.clone(), at));
+                entry.action_id = Some(action_id.clone());
+                entry.action_kind = kind;
             }
-            Event::ActionSucceeded { action_id, .. } => {
-                ("ActionSucceeded".to_string(), None, Some(action_id.clone()))
+            Event::ActionSucceeded { action_id, .. } | Event::ActionFailed { action_id, .. } => {
+                if let Some((kind, requested_at)) = self.requested.get(action_id) {
+                    entry.action_kind = kind.clone();
+                    entry.duration_ms = elapsed(*requested_at, at);
+                }
+                entry.action_id = Some(action_id.clone());
             }
-            Event::ActionFailed { action_id, .. } => {
-                final_status = RunStatusSummary::Failed { recoverable: false };
-                ("ActionFailed".to_string(), None, Some(action_id.clone()))
+            Event::RetryScheduled { action_id, .. } => {
+                self.summary.retries += 1;
+                entry.action_kind = self
+                    .requested
+                    .get(action_id)
+                    .and_then(|(kind, _)| kind.clone());
+                entry.action_id = Some(action_id.clone());
             }
-            Event::Interrupted { .. } => {
-                final_status = RunStatusSummary::Blocked { interrupt: true };
-                ("Interrupted".to_string(), None, None)
+            Event::ActionRecorded {
+                action_id, action, ..
+            } => {
+                entry.action_kind = action_kind(action);
+                entry.action_id = Some(action_id.clone());
             }
-            Event::Resumed { .. } => ("Resumed".to_string(), None, None),
-            Event::Completed => {
-                final_status = RunStatusSummary::Completed;
-                ("Completed".to_string(), None, None)
+            Event::ActionDenied { payload, .. } => entry.action_kind = action_kind(payload),
+            Event::PolicyDecision { action_kind, .. } => {
+                entry.action_kind = Some(action_kind.clone())
             }
-        };
-        entries.push(TimelineEntry {
-            seq: se.seq,
-            kind,
-            step_id,
-            action_id,
-        });
+            Event::Interrupted { .. } => self.summary.interrupts += 1,
+            // Waiting for the resume is not part of the step
+            Event::Resumed { .. } => self.step_started_at = at,
+            _ => {}
+        }
+        self.entries.push(entry);
+        self.entries.last().expect("just pushed")
     }
 
-    Ok(RunTimeline {
-        run_id: run_id.clone(),
-        events: entries,
-        final_status,
-    })
+    fn finish(
+        mut self,
+        run_id: &RunId,
+        final_status: RunStatusSummary,
+        reason: Option<String>,
+    ) -> RunTimeline {
+        self.summary.reason = reason;
+        self.summary.duration_ms = elapsed(
+            self.entries.first().and_then(|e| e.recorded_at_ms),
+            self.entries.last().and_then(|e| e.recorded_at_ms),
+        );
+        RunTimeline {
+            run_id: run_id.clone(),
+            events: self.entries,
+            final_status,
+            summary: self.summary,
+        }
+    }
+}
+
+/// Milliseconds from `start` to `end`, if both are known (0 if the clock went backwards)
+fn elapsed(start: Option<i64>, end: Option<i64>) -> Option<u64> {
+    Some(end?.saturating_sub(start?).max(0) as u64)
+}
+
+/// [Action::kind] of a serialized action, if `payload` is one
+fn action_kind(payload: &Value) -> Option<String> {
+    serde_json::from_value::<Action>(payload.clone())
+        .ok()
+        .map(|action| action.kind())
+}
+
+/// `value` as compact JSON (strings unquoted), cut to [DETAIL_VALUE_MAX_CHARS]
+fn brief(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() <= DETAIL_VALUE_MAX_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(DETAIL_VALUE_MAX_CHARS).collect();
+    format!("{}…", cut)
+}
+
+fn format_time(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+        .unwrap_or_else(|| ms.to_string())
+}
+
+fn detail(event: &Event) -> Option<String> {
+    match event {
+        Event::PolicyDecision {
+            action_kind,
+            decision,
+            code,
+            policy,
+            reason,
+        } => {
+            let summary = format!(
+                "{} {} by {} ({})",
+                decision.as_str(),
+                action_kind,
+                policy,
+                code
+            );
+            Some(match reason {
+                Some(reason) => format!("{}: {}", summary, reason),
+                None => summary,
+            })
+        }
+        Event::ActionDenied { reason, .. } => Some(reason.clone()),
+        Event::RetryScheduled {
+            attempt, delay_ms, ..
+        } => Some(format!("attempt {} in {}ms", attempt, delay_ms)),
+        Event::ActionSucceeded { output, .. } => Some(brief(output)),
+        Event::ActionFailed { error, .. } => Some(error.clone()),
+        Event::Interrupted { value } | Event::Resumed { value } => Some(brief(value)),
+        Event::Failed { reason, code } => Some(match code {
+            Some(code) => format!("{} ({})", reason, code),
+            None => reason.clone(),
+        }),
+        Event::BudgetExceeded { code, reason } => Some(format!("{} ({})", reason, code)),
+        Event::Cancelled { reason } => reason.clone(),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kernel::event::Event;
     use crate::kernel::event_store::InMemoryEventStore;
 
     #[test]
@@ -102,6 +590,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("n1".into()),
                         payload: serde_json::json!({"x": 1}),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
@@ -117,6 +607,77 @@ mod tests {
         assert!(matches!(tl.final_status, RunStatusSummary::Completed));
     }
 
+    #[test]
+    fn ranged_timeline_matches_the_full_one() {
+        let store = InMemoryEventStore::new();
+        let run_id = "range-test".to_string();
+        let step = |n: u32| Event::StateUpdated {
+            step_id: Some(format!("n{}", n)),
+            payload: serde_json::json!({ "n": n }),
+            state_hash: None,
+            merge_report: None,
+        };
+        store
+            .append(
+                &run_id,
+                &[
+                    step(1),
+                    Event::ActionRequested {
+                        action_id: "a1".into(),
+                        payload: serde_json::json!({}),
+                    },
+                    Event::ActionFailed {
+                        action_id: "a1".into(),
+                        error: "boom".into(),
+                        dry_run: false,
+                    },
+                    step(2),
+                    Event::Interrupted {
+                        value: serde_json::json!("approve?"),
+                    },
+                    Event::Resumed {
+                        value: serde_json::json!(true),
+                    },
+                    step(3),
+                ],
+            )
+            .unwrap();
+        let full = serde_json::to_value(run_timeline(&store, &run_id).unwrap()).unwrap();
+        let ranged = |from, to, filter: &EventFilter| {
+            serde_json::to_value(run_timeline_range(&store, &run_id, from, to, filter).unwrap())
+                .unwrap()
+        };
+
+        // Durations may start before the window, so only the full timeline has them all
+        let without_durations = |events: &Value| {
+            let mut events = events.clone();
+            for e in events.as_array_mut().unwrap() {
+                e.as_object_mut().unwrap().remove("duration_ms");
+            }
+            events
+        };
+        let window = ranged(2, 4, &EventFilter::all());
+        assert_eq!(
+            without_durations(&window["events"]),
+            without_durations(&serde_json::json!(full["events"].as_array().unwrap()[1..4]))
+        );
+        assert_eq!(window["final_status"], full["final_status"]);
+        assert_eq!(full["final_status"]["status"], "Blocked");
+
+        let steps = ranged(1, Seq::MAX, &EventFilter::only([EventKind::StateUpdated]));
+        let seqs: Vec<_> = steps["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["seq"].as_u64().unwrap())
+            .collect();
+        assert_eq!(seqs, [1, 4, 7]);
+
+        let beyond = ranged(100, 200, &EventFilter::all());
+        assert_eq!(beyond["events"], serde_json::json!([]));
+        assert_eq!(beyond["final_status"], full["final_status"]);
+    }
+
     #[test]
     fn timeline_json_roundtrip() {
         let store = InMemoryEventStore::new();
@@ -132,6 +693,7 @@ mod tests {
                     Event::ActionSucceeded {
                         action_id: "a1".into(),
                         output: serde_json::json!("ok"),
+                        dry_run: false,
                     },
                     Event::Completed,
                 ],
@@ -140,5 +702,264 @@ mod tests {
         let tl = run_timeline(&store, &run_id).unwrap();
         let json = serde_json::to_string(&tl).unwrap();
         let _: RunTimeline = serde_json::from_str(&json).unwrap();
+        let back: RunTimeline = serde_json::from_str(&tl.to_json().unwrap()).unwrap();
+        assert_eq!(back.summary, tl.summary);
+    }
+
+    fn at(seq: Seq, ms: i64, event: Event) -> SequencedEvent {
+        SequencedEvent::new(seq, event).recorded_at(ms)
+    }
+
+    fn search(action_id: &str) -> Event {
+        Event::ActionRequested {
+            action_id: action_id.into(),
+            payload: serde_json::to_value(Action::CallTool {
+                tool: "search".into(),
+                input: serde_json::json!({"q": "oris"}),
+                idempotency_key: None,
+            })
+            .unwrap(),
+        }
+    }
+
+    fn step(n: u32) -> Event {
+        Event::StateUpdated {
+            step_id: Some(format!("n{}", n)),
+            payload: serde_json::json!({ "n": n }),
+            state_hash: None,
+            merge_report: None,
+        }
+    }
+
+    #[test]
+    fn entries_carry_times_durations_and_action_kinds() {
+        let run_id = "durations".to_string();
+        let tl = timeline_of(
+            &run_id,
+            &[
+                at(1, 1_000, step(1)),
+                at(2, 1_010, search("a1")),
+                at(
+                    3,
+                    1_050,
+                    Event::ActionFailed {
+                        action_id: "a1".into(),
+                        error: "timeout".into(),
+                        dry_run: false,
+                    },
+                ),
+                at(
+                    4,
+                    1_060,
+                    Event::RetryScheduled {
+                        action_id: "a1".into(),
+                        attempt: 1,
+                        delay_ms: 5,
+                    },
+                ),
+                at(
+                    5,
+                    1_100,
+                    Event::ActionSucceeded {
+                        action_id: "a1".into(),
+                        output: serde_json::json!({"hits": 3}),
+                        dry_run: false,
+                    },
+                ),
+                at(6, 1_200, step(2)),
+                at(
+                    7,
+                    1_210,
+                    Event::Interrupted {
+                        value: serde_json::json!("approve?"),
+                    },
+                ),
+                at(
+                    8,
+                    9_000,
+                    Event::Resumed {
+                        value: serde_json::json!(true),
+                    },
+                ),
+                at(9, 9_030, step(3)),
+                at(
+                    10,
+                    9_040,
+                    Event::Failed {
+                        reason: "step limit".into(),
+                        code: Some("max_steps".into()),
+                    },
+                ),
+            ],
+        );
+
+        let e = &tl.events;
+        assert_eq!(e[0].recorded_at_ms, Some(1_000));
+        assert_eq!(e[0].duration_ms, Some(0));
+        assert_eq!(e[1].action_kind.as_deref(), Some("search"));
+        assert_eq!(e[2].duration_ms, Some(40));
+        assert_eq!(e[2].detail.as_deref(), Some("timeout"));
+        assert_eq!(e[3].action_kind.as_deref(), Some("search"));
+        assert_eq!(e[4].action_kind.as_deref(), Some("search"));
+        assert_eq!(e[4].duration_ms, Some(90));
+        assert_eq!(e[4].detail.as_deref(), Some(r#"{"hits":3}"#));
+        assert_eq!(e[5].duration_ms, Some(200));
+        assert_eq!(e[6].detail.as_deref(), Some("approve?"));
+        // The step after a resume is timed from the resume, not the interrupt
+        assert_eq!(e[8].duration_ms, Some(30));
+
+        assert_eq!(
+            tl.final_status,
+            RunStatusSummary::Failed { recoverable: true }
+        );
+        assert_eq!(
+            tl.summary,
+            TimelineSummary {
+                steps: 3,
+                actions: BTreeMap::from([("search".to_string(), 1)]),
+                retries: 1,
+                interrupts: 1,
+                reason: Some("step limit (max_steps)".into()),
+                duration_ms: Some(8_040),
+            }
+        );
+    }
+
+    #[test]
+    fn events_without_times_have_no_durations() {
+        let run_id = "untimed".to_string();
+        let tl = timeline_of(
+            &run_id,
+            &[
+                SequencedEvent::new(1, step(1)),
+                SequencedEvent::new(2, search("a1")),
+                SequencedEvent::new(
+                    3,
+                    Event::ActionSucceeded {
+                        action_id: "a1".into(),
+                        output: Value::String("x".repeat(200)),
+                        dry_run: false,
+                    },
+                ),
+                SequencedEvent::new(4, Event::Completed),
+            ],
+        );
+        assert!(tl.events.iter().all(|e| e.duration_ms.is_none()));
+        assert_eq!(tl.summary.duration_ms, None);
+        assert_eq!(tl.summary.reason, None);
+        let output = tl.events[2].detail.as_deref().unwrap();
+        assert_eq!(output.chars().count(), DETAIL_VALUE_MAX_CHARS + 1);
+        assert!(output.ends_with('…'));
+    }
+
+    #[test]
+    fn text_table_has_a_header_and_a_row_per_entry() {
+        let run_id = "text".to_string();
+        let tl = timeline_of(
+            &run_id,
+            &[
+                at(1, 0, step(1)),
+                at(2, 5, search("a1")),
+                at(
+                    3,
+                    25,
+                    Event::Cancelled {
+                        reason: Some("operator".into()),
+                    },
+                ),
+            ],
+        );
+        let text = tl.to_text();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "run text: cancelled (1 steps, actions search=1, 25ms)"
+        );
+        assert_eq!(lines[1], "reason: operator");
+        assert!(lines[2].starts_with("SEQ  TIME"));
+        assert_eq!(lines.len(), 6);
+        assert!(lines[4].contains("1970-01-01T00:00:00.005Z"));
+        assert!(lines[4].contains("ActionRequested"));
+        assert!(lines[4].contains("a1"));
+        assert!(lines[4].contains("search"));
+        assert!(lines[5].ends_with("operator"));
+        let event_column = lines[2].find("EVENT").unwrap();
+        assert_eq!(lines[3].find("StateUpdated"), Some(event_column));
+    }
+
+    #[test]
+    fn fleet_summary_counts_statuses_blocked_ages_and_failure_reasons() {
+        let store = InMemoryEventStore::new();
+        let failed = |code: Option<&str>| Event::Failed {
+            reason: "stopped".into(),
+            code: code.map(str::to_string),
+        };
+        let interrupted = Event::Interrupted {
+            value: serde_json::json!("approve?"),
+        };
+        let runs: Vec<(&str, Vec<Event>)> = vec![
+            ("blocked-a", vec![step(1), interrupted.clone()]),
+            ("blocked-b", vec![interrupted]),
+            (
+                "deadline-1",
+                vec![step(1), failed(Some("deadline_exceeded"))],
+            ),
+            ("deadline-2", vec![failed(Some("deadline_exceeded"))]),
+            ("uncoded", vec![failed(None)]),
+            (
+                "denied",
+                vec![Event::ActionDenied {
+                    payload: serde_json::json!({}),
+                    reason: "no".into(),
+                }],
+            ),
+            ("running", vec![step(1)]),
+        ];
+        for (run_id, events) in runs {
+            store.append(&run_id.to_string(), &events).unwrap();
+        }
+        // More runs than a page, so the summary reads several
+        for i in 0..PageRequest::DEFAULT_LIMIT {
+            store
+                .append(&format!("done-{}", i), &[Event::Completed])
+                .unwrap();
+        }
+
+        let now = Utc::now() + chrono::Duration::hours(1);
+        let fleet = summarize_runs_at(&store, RunFilter::default(), now).unwrap();
+        assert_eq!(fleet.total, 7 + PageRequest::DEFAULT_LIMIT as u64);
+        assert_eq!(
+            fleet.count(RunStatusKind::Completed),
+            PageRequest::DEFAULT_LIMIT as u64
+        );
+        assert_eq!(fleet.count(RunStatusKind::Blocked), 2);
+        assert_eq!(fleet.count(RunStatusKind::Failed), 4);
+        assert_eq!(fleet.count(RunStatusKind::Running), 1);
+        assert_eq!(fleet.count(RunStatusKind::Paused), 0);
+
+        let blocked: Vec<_> = fleet.oldest_blocked.iter().map(|r| &r.run_id).collect();
+        assert_eq!(blocked, ["blocked-a", "blocked-b"]);
+        assert!(fleet.oldest_blocked_age_ms().unwrap() >= 3_600_000);
+        assert_eq!(fleet.oldest_blocked[0].last_event_kind, "Interrupted");
+
+        let reasons: Vec<_> = fleet
+            .failure_reasons
+            .iter()
+            .map(|r| (r.code.as_str(), r.runs))
+            .collect();
+        assert_eq!(
+            reasons,
+            [("deadline_exceeded", 2), ("ActionDenied", 1), ("Failed", 1)]
+        );
+
+        let only_failed = summarize_runs(
+            &store,
+            RunFilter::default().with_status(RunStatusKind::Failed),
+        )
+        .unwrap();
+        assert_eq!(only_failed.total, 4);
+        assert!(only_failed.oldest_blocked.is_empty());
+        let json = serde_json::to_value(&only_failed).unwrap();
+        assert_eq!(json["by_status"], serde_json::json!({ "failed": 4 }));
     }
 }
@@ -64,10 +64,7 @@ impl<S: KernelState> TimelineForker<S> {
 
         // 2. Inject alternate event at fork point
         let alt_seq = fork_at_seq + 1;
-        let alt_se = SequencedEvent {
-            seq: alt_seq,
-            event: alternate_event.clone(),
-        };
+        let alt_se = SequencedEvent::new(alt_seq, alternate_event.clone());
         self.reducer.apply(&mut state, &alt_se)?;
 
         // 3. Continue replaying remaining events under branch_id
@@ -77,6 +74,8 @@ impl<S: KernelState> TimelineForker<S> {
             let branched_se = SequencedEvent {
                 seq: se.seq,
                 event: se.event.clone(),
+                version: se.version,
+                recorded_at_ms: se.recorded_at_ms,
             };
             self.reducer.apply(&mut state, &branched_se)?;
         }
@@ -170,14 +169,20 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("a".into()),
                         payload: serde_json::to_value(&TestState(1)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("b".into()),
                         payload: serde_json::to_value(&TestState(2)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::StateUpdated {
                         step_id: Some("c".into()),
                         payload: serde_json::to_value(&TestState(3)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                 ],
             )
@@ -197,6 +202,8 @@ mod tests {
                 Event::StateUpdated {
                     step_id: Some("alt-b".into()),
                     payload: serde_json::to_value(&TestState(99)).unwrap(),
+                    state_hash: None,
+                    merge_report: None,
                 },
                 TestState(0),
             )
@@ -222,6 +229,8 @@ mod tests {
                     Event::StateUpdated {
                         step_id: Some("x".into()),
                         payload: serde_json::to_value(&TestState(10)).unwrap(),
+                        state_hash: None,
+                        merge_report: None,
                     },
                     Event::Completed,
                 ],
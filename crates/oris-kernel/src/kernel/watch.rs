@@ -0,0 +1,366 @@
+//! Live subscriptions to a run's events.
+//!
+//! [WatchableEventStore::subscribe] returns an [EventSubscription]: a stream that first
+//! yields the run's stored events from a given seq, then each event as it is appended.
+//! Seqs arrive in ascending order without gaps or repeats, however appends race with the
+//! subscription. [InMemoryEventStore](crate::kernel::InMemoryEventStore) pushes new events
+//! through a broadcast channel; [PollingEventStore] adds subscriptions to any other store
+//! (e.g. the SQL stores) by polling it.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::stream::{self, Stream};
+
+use crate::kernel::event::{Event, EventFilter, EventStore, KernelError, SequencedEvent};
+use crate::kernel::identity::{RunId, Seq};
+use crate::kernel::ops::{PageRequest, RunFilter, RunSummary};
+
+/// How often [PollingEventStore] checks for new events by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Event stores that can stream a run's events to live observers.
+pub trait WatchableEventStore: EventStore {
+    /// The run's events with `seq >= from_seq`: the stored backlog first, then new events
+    /// as they are appended, in ascending seq order without gaps.
+    ///
+    /// The stream never ends on its own while the store is alive; drop it to unsubscribe.
+    /// After a read error it yields the error and ends.
+    fn subscribe(&self, run_id: &RunId, from_seq: Seq) -> EventSubscription;
+}
+
+/// Stream of one run's events, returned by [WatchableEventStore::subscribe].
+pub struct EventSubscription {
+    inner: Pin<Box<dyn Stream<Item = Result<SequencedEvent, KernelError>> + Send>>,
+}
+
+impl EventSubscription {
+    /// Wraps a stream that keeps the [WatchableEventStore::subscribe] guarantees.
+    pub fn new(
+        stream: impl Stream<Item = Result<SequencedEvent, KernelError>> + Send + 'static,
+    ) -> Self {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+}
+
+impl Stream for EventSubscription {
+    type Item = Result<SequencedEvent, KernelError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for EventSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EventSubscription(..)")
+    }
+}
+
+/// Position of a subscription in its run's log
+pub(crate) struct Cursor {
+    /// Seq of the next event to yield
+    pub(crate) next: Seq,
+    /// Events read but not yet yielded, contiguous from `next`
+    pub(crate) pending: VecDeque<SequencedEvent>,
+}
+
+impl Cursor {
+    pub(crate) fn new(from_seq: Seq) -> Self {
+        Self {
+            next: from_seq.max(1),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Queues the events of `events` that continue the log from `next`, and stops at the
+    /// first gap. Earlier seqs are dropped; a compaction marker may open the log past
+    /// `next`, since no stored event precedes it.
+    pub(crate) fn extend(&mut self, events: impl IntoIterator<Item = SequencedEvent>) {
+        for event in events {
+            let expected = self.pending.back().map_or(self.next, |e| e.seq + 1);
+            let opens_log = self.pending.is_empty()
+                && matches!(event.event, Event::Compacted { .. })
+                && event.seq > expected;
+            if event.seq == expected || opens_log {
+                self.pending.push_back(event);
+            } else if event.seq > expected {
+                break;
+            }
+        }
+    }
+
+    /// The next queued event, advancing past it
+    pub(crate) fn pop(&mut self) -> Option<SequencedEvent> {
+        let event = self.pending.pop_front()?;
+        self.next = event.seq + 1;
+        Some(event)
+    }
+}
+
+/// Decorator that adds polling subscriptions to any event store.
+///
+/// Every subscription reads the store on a blocking thread each `interval` until it finds
+/// new events, so it suits stores without change notification, such as
+/// [SqliteEventStore](crate::kernel::SqliteEventStore). Subscriptions need a Tokio
+/// runtime; everything else is passed through to the wrapped store.
+pub struct PollingEventStore<E: EventStore> {
+    inner: Arc<E>,
+    interval: Duration,
+}
+
+impl<E: EventStore + 'static> PollingEventStore<E> {
+    /// Polls every [DEFAULT_POLL_INTERVAL].
+    pub fn new(inner: E) -> Self {
+        Self::from_arc(Arc::new(inner))
+    }
+
+    /// Wraps a store shared with other owners.
+    pub fn from_arc(inner: Arc<E>) -> Self {
+        Self {
+            inner,
+            interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: EventStore + 'static> EventStore for PollingEventStore<E> {
+    fn append(&self, run_id: &RunId, events: &[Event]) -> Result<Seq, KernelError> {
+        self.inner.append(run_id, events)
+    }
+
+    fn scan(&self, run_id: &RunId, from: Seq) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan(run_id, from)
+    }
+
+    fn head(&self, run_id: &RunId) -> Result<Seq, KernelError> {
+        self.inner.head(run_id)
+    }
+
+    fn scan_range(
+        &self,
+        run_id: &RunId,
+        from: Seq,
+        to: Seq,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan_range(run_id, from, to, filter)
+    }
+
+    fn scan_rev(
+        &self,
+        run_id: &RunId,
+        limit: usize,
+        filter: &EventFilter,
+    ) -> Result<Vec<SequencedEvent>, KernelError> {
+        self.inner.scan_rev(run_id, limit, filter)
+    }
+
+    fn list_runs(
+        &self,
+        filter: RunFilter,
+        page: PageRequest,
+    ) -> Result<Vec<RunSummary>, KernelError> {
+        self.inner.list_runs(filter, page)
+    }
+
+    fn run_exists(&self, run_id: &RunId) -> Result<bool, KernelError> {
+        self.inner.run_exists(run_id)
+    }
+
+    fn flush(&self) -> Result<(), KernelError> {
+        self.inner.flush()
+    }
+
+    fn flush_step(&self, run_id: &RunId) -> Result<(), KernelError> {
+        self.inner.flush_step(run_id)
+    }
+
+    fn replace_prefix(
+        &self,
+        run_id: &RunId,
+        up_to_seq: Seq,
+        marker: &Event,
+    ) -> Result<(), KernelError> {
+        self.inner.replace_prefix(run_id, up_to_seq, marker)
+    }
+}
+
+impl<E: EventStore + 'static> WatchableEventStore for PollingEventStore<E> {
+    fn subscribe(&self, run_id: &RunId, from_seq: Seq) -> EventSubscription {
+        struct Poller<E> {
+            store: Arc<E>,
+            run_id: RunId,
+            interval: Duration,
+            cursor: Cursor,
+            failed: bool,
+        }
+
+        let poller = Poller {
+            store: self.inner.clone(),
+            run_id: run_id.clone(),
+            interval: self.interval,
+            cursor: Cursor::new(from_seq),
+            failed: false,
+        };
+        EventSubscription::new(stream::unfold(poller, |mut poller| async move {
+            if poller.failed {
+                return None;
+            }
+            let mut first_poll = true;
+            loop {
+                if let Some(event) = poller.cursor.pop() {
+                    return Some((Ok(event), poller));
+                }
+                if !first_poll {
+                    tokio::time::sleep(poller.interval).await;
+                }
+                first_poll = false;
+                let (store, run_id, from) = (
+                    poller.store.clone(),
+                    poller.run_id.clone(),
+                    poller.cursor.next,
+                );
+                let scanned = tokio::task::spawn_blocking(move || {
+                    store.scan_range(&run_id, from, Seq::MAX, &EventFilter::all())
+                })
+                .await
+                .unwrap_or_else(|e| {
+                    Err(KernelError::EventStore(format!(
+                        "subscription poll failed: {}",
+                        e
+                    )))
+                });
+                match scanned {
+                    Ok(events) => poller.cursor.extend(events),
+                    Err(e) => {
+                        poller.failed = true;
+                        return Some((Err(e), poller));
+                    }
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::event_store::InMemoryEventStore;
+    use futures::StreamExt;
+
+    fn step(n: u64) -> Event {
+        Event::StateUpdated {
+            step_id: None,
+            payload: serde_json::json!(n),
+            state_hash: None,
+            merge_report: None,
+        }
+    }
+
+    /// Appends `total` events in small batches on another thread while subscribing from
+    /// several seqs, and checks every subscription sees each seq once, in order.
+    async fn check_racing_subscribers<W: WatchableEventStore + 'static>(store: Arc<W>) {
+        let run_id: RunId = "watched".into();
+        let total: Seq = 300;
+        store.append(&run_id, &[step(1), step(2)]).unwrap();
+        let writer = {
+            let (store, run_id) = (store.clone(), run_id.clone());
+            std::thread::spawn(move || {
+                let mut head = 2;
+                while head < total {
+                    let batch: Vec<_> = (head + 1..=(head + 3).min(total)).map(step).collect();
+                    head = store.append(&run_id, &batch).unwrap();
+                    std::thread::yield_now();
+                }
+            })
+        };
+        let mut subscriptions = Vec::new();
+        for from in [0, 1, 2, 50, 120, 200] {
+            subscriptions.push((from, store.subscribe(&run_id, from)));
+            tokio::task::yield_now().await;
+        }
+        for (from, subscription) in subscriptions {
+            let seqs: Vec<Seq> = subscription
+                .take((total + 1 - from.max(1)) as usize)
+                .map(|e| e.unwrap().seq)
+                .collect()
+                .await;
+            assert_eq!(seqs, (from.max(1)..=total).collect::<Vec<_>>());
+        }
+        writer.join().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn in_memory_subscribers_see_every_event_once_in_order() {
+        check_racing_subscribers(Arc::new(InMemoryEventStore::new())).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn polling_subscribers_see_every_event_once_in_order() {
+        check_racing_subscribers(Arc::new(
+            PollingEventStore::new(InMemoryEventStore::new())
+                .with_interval(Duration::from_millis(1)),
+        ))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn subscriptions_survive_lagging_and_start_at_compaction_markers() {
+        let store = InMemoryEventStore::new();
+        let run_id: RunId = "lagging".into();
+        let mut subscription = store.subscribe(&run_id, 1);
+        // More events than the broadcast channel holds arrive before the first read
+        for n in 1..=3000 {
+            store.append(&run_id, &[step(n)]).unwrap();
+        }
+        let mut expected = 1;
+        while expected <= 3000 {
+            assert_eq!(subscription.next().await.unwrap().unwrap().seq, expected);
+            expected += 1;
+        }
+
+        store
+            .replace_prefix(
+                &run_id,
+                2000,
+                &Event::Compacted {
+                    up_to_seq: 2000,
+                    snapshot_seq: 2000,
+                },
+            )
+            .unwrap();
+        let polling = PollingEventStore::new(store).with_interval(Duration::from_millis(1));
+        let seqs: Vec<Seq> = polling
+            .subscribe(&run_id, 5)
+            .take(3)
+            .map(|e| e.unwrap().seq)
+            .collect()
+            .await;
+        assert_eq!(seqs, [2000, 2001, 2002]);
+    }
+
+    #[test]
+    fn cursor_stops_at_gaps_and_skips_seen_events() {
+        let at = |seq| SequencedEvent::new(seq, step(seq));
+        let mut cursor = Cursor::new(3);
+        cursor.extend([at(1), at(3), at(4), at(6), at(7)]);
+        let seqs: Vec<Seq> = std::iter::from_fn(|| cursor.pop()).map(|e| e.seq).collect();
+        assert_eq!(seqs, [3, 4]);
+        assert_eq!(cursor.next, 5);
+    }
+}
@@ -0,0 +1,123 @@
+//! Pins the CloudEvents envelopes of kernel events against a golden file, so downstream
+//! consumers can rely on their shape. Regenerate after a deliberate change with
+//! `ORIS_UPDATE_GOLDEN=1 cargo test -p oris-kernel --test cloudevents_golden`.
+
+use std::path::PathBuf;
+
+use oris_kernel::{
+    export_range, from_cloudevent, CloudEvent, Event, EventFilter, EventKind, EventStore,
+    InMemoryEventStore, RunId,
+};
+
+const GOLDEN: &str = "tests/golden/cloudevents_run.json";
+
+/// A run touching most event kinds, with fixed ids and values
+fn golden_run(store: &InMemoryEventStore, run_id: &RunId) {
+    let events = vec![
+        Event::StateUpdated {
+            step_id: Some("plan".into()),
+            payload: serde_json::json!({"todo": ["search"]}),
+            state_hash: Some("9f2c".into()),
+            merge_report: None,
+        },
+        Event::ActionRequested {
+            action_id: "a1".into(),
+            payload: serde_json::json!({"CallTool": {"tool": "search", "input": {"q": "oris"}}}),
+        },
+        Event::RetryScheduled {
+            action_id: "a1".into(),
+            attempt: 1,
+            delay_ms: 250,
+        },
+        Event::ActionFailed {
+            action_id: "a1".into(),
+            error: "timeout".into(),
+            dry_run: false,
+        },
+        Event::ActionSucceeded {
+            action_id: "a1".into(),
+            output: serde_json::json!({"hits": 3}),
+            dry_run: false,
+        },
+        Event::Interrupted {
+            value: serde_json::json!({"approve": "publish"}),
+        },
+        Event::Resumed {
+            value: serde_json::json!({"approved_by": "alice"}),
+        },
+        Event::Paused,
+        Event::Completed,
+    ];
+    store.append(run_id, &events).unwrap();
+}
+
+fn golden_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GOLDEN)
+}
+
+#[test]
+fn cloudevent_envelopes_match_the_golden_file() {
+    let store = InMemoryEventStore::new();
+    let run_id: RunId = "golden-run".into();
+    golden_run(&store, &run_id);
+    let exported = export_range(&store, &run_id, 1, u64::MAX, &EventFilter::all()).unwrap();
+    let actual = serde_json::to_string_pretty(&exported).unwrap() + "\n";
+
+    if std::env::var_os("ORIS_UPDATE_GOLDEN").is_some() {
+        std::fs::write(golden_path(), &actual).unwrap();
+    }
+    // Compared as JSON values, so the key order of a serde_json build does not matter
+    let expected: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(golden_path()).unwrap()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&exported).unwrap(),
+        expected,
+        "CloudEvents envelopes changed; if deliberate, regenerate {}",
+        GOLDEN
+    );
+}
+
+#[test]
+fn golden_envelopes_parse_back_into_the_stored_events() {
+    let store = InMemoryEventStore::new();
+    let run_id: RunId = "golden-run".into();
+    golden_run(&store, &run_id);
+    let golden: Vec<CloudEvent> =
+        serde_json::from_str(&std::fs::read_to_string(golden_path()).unwrap()).unwrap();
+
+    let stored = store.scan(&run_id, 1).unwrap();
+    assert_eq!(golden.len(), stored.len());
+    for (envelope, event) in golden.iter().zip(&stored) {
+        let (parsed_run, parsed) = from_cloudevent(envelope).unwrap();
+        assert_eq!(parsed_run, run_id);
+        assert_eq!(parsed.seq, event.seq);
+        assert_eq!(parsed.version, event.version);
+        assert_eq!(
+            serde_json::to_value(&parsed.event).unwrap(),
+            serde_json::to_value(&event.event).unwrap()
+        );
+    }
+}
+
+#[test]
+fn ranged_exports_keep_only_the_requested_seqs_and_kinds() {
+    let store = InMemoryEventStore::new();
+    let run_id: RunId = "golden-run".into();
+    golden_run(&store, &run_id);
+    let exported = export_range(
+        &store,
+        &run_id,
+        2,
+        8,
+        &EventFilter::only([
+            EventKind::ActionRequested,
+            EventKind::Paused,
+            EventKind::Completed,
+        ]),
+    )
+    .unwrap();
+    let ids: Vec<_> = exported.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["2", "8"]);
+    assert_eq!(exported[1].event_type, "dev.oris.kernel.paused");
+    assert_eq!(exported[1].data, None);
+}
@@ -43,10 +43,14 @@ fn repeated_replay_preserves_state_and_event_hash() {
                         label: "captured".into(),
                     })
                     .unwrap(),
+                    state_hash: None,
+                    merge_report: None,
                 },
                 Event::StateUpdated {
                     step_id: Some("replay".into()),
                     payload: serde_json::to_value(expected_state.clone()).unwrap(),
+                    state_hash: None,
+                    merge_report: None,
                 },
                 Event::Completed,
             ],
@@ -174,11 +174,12 @@ fn build_demo_evo(
         events: Box::new(InMemoryEventStore::new()),
         snaps: None,
         reducer: Box::new(StateUpdatedOnlyReducer),
-        exec: Box::new(NoopActionExecutor),
+        exec: Arc::new(NoopActionExecutor),
         step: Box::new(NoopStepFn),
         policy: Box::new(AllowAllPolicy),
         effect_sink: None,
         mode: KernelMode::Normal,
+        snapshot_policy: None,
     });
 
     let policy = demo_sandbox_policy();
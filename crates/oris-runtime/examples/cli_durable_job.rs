@@ -1,4 +1,5 @@
-//! Minimal CLI for durable job: run, list, inspect, resume, replay, cancel.
+//! Minimal CLI for durable job: run, list, inspect, resume, replay, pause, cancel, verify,
+//! timeline, summary.
 //!
 //! Demonstrates Phase 2 operator API with local SQLite persistence.
 //!
@@ -8,32 +9,83 @@
 //!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- inspect --thread-id my-job
 //!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- resume --thread-id my-job
 //!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- replay --thread-id my-job
+//!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- replay --thread-id my-job --checkpoint-id <id> --fork-to my-job-fork
+//!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- pause --thread-id my-job
 //!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- cancel --thread-id my-job
 //!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- resume --thread-id my-job --checkpoint-id <id>
+//!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- verify --thread-id my-job
+//!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- timeline --thread-id my-job
+//!   cargo run -p oris-runtime --example cli_durable_job --features sqlite-persistence -- summary
+//!
+//! Runs also append kernel events (with state hashes) to the event log at `ORIS_KERNEL_DB`
+//! (default: the checkpoint database). `verify` replays that log with a kernel in
+//! `VerifyReplay` mode, executing no nodes, and prints the verification report as JSON.
+//! `timeline` prints the log as a table: when each event was appended, how long each step
+//! and action took, and a summary of steps, actions by kind and retries. `summary` needs
+//! no thread: it prints, as JSON, how many runs of the log are in each status, the ones
+//! blocked the longest and the most common failure reasons (as `GET /v1/runs/summary`).
+//!
+//! `pause` and `cancel` persist a request that a `run` or `resume` of the same thread in
+//! another process polls for; set `ORIS_CLI_NODE_DELAY_MS` to slow the nodes down
+//! enough to try them. A paused run records `Paused` in the event log and continues with
+//! `resume`. A cancelled run records `Cancelled` and can no longer be run, resumed or
+//! replayed; a thread with no run in flight (paused, interrupted or failed) is cancelled
+//! at once.
 
 #[cfg(feature = "sqlite-persistence")]
 use oris_runtime::graph::{
-    function_node, MessagesState, RunnableConfig, SqliteSaver, StateGraph, END, START,
+    function_node, CancellationToken, GraphError, MessagesState, RunnableConfig, SqliteSaver,
+    StateGraph, END, START,
+};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::{
+    summarize_runs, AllowAllPolicy, EventFilter, EventKind, EventStore, Kernel, KernelError,
+    KernelMode, KernelRunner, NoopActionExecutor, NoopStepFn, RunFilter, SqliteEventStore,
+    StateUpdatedOnlyReducer,
 };
 #[cfg(feature = "sqlite-persistence")]
 use oris_runtime::schemas::messages::Message;
 #[cfg(feature = "sqlite-persistence")]
 use std::collections::HashMap;
+#[cfg(feature = "sqlite-persistence")]
+use std::sync::Arc;
+#[cfg(feature = "sqlite-persistence")]
+use std::time::Duration;
+
+/// How often a running job checks for a persisted pause or cancel request
+#[cfg(feature = "sqlite-persistence")]
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
+/// Event kinds after which no worker is running the thread
 #[cfg(feature = "sqlite-persistence")]
-fn parse_args(args: &[String]) -> Option<(String, String, Option<String>)> {
-    // subcommand --thread-id <id> [--checkpoint-id <id>]
+const STOPPED_KINDS: [EventKind; 6] = [
+    EventKind::ActionFailed,
+    EventKind::Interrupted,
+    EventKind::Failed,
+    EventKind::Completed,
+    EventKind::Paused,
+    EventKind::Cancelled,
+];
+
+#[cfg(feature = "sqlite-persistence")]
+fn parse_args(args: &[String]) -> Option<(String, String, Option<String>, Option<String>)> {
+    // subcommand --thread-id <id> [--checkpoint-id <id>] [--fork-to <id>]
     let mut i = 0;
     let mut cmd = None;
     let mut thread_id = None;
     let mut checkpoint_id = None;
+    let mut fork_to = None;
     while i < args.len() {
         if args[i] == "run"
             || args[i] == "list"
             || args[i] == "inspect"
             || args[i] == "resume"
             || args[i] == "replay"
+            || args[i] == "pause"
             || args[i] == "cancel"
+            || args[i] == "verify"
+            || args[i] == "timeline"
+            || args[i] == "summary"
         {
             cmd = Some(args[i].clone());
             i += 1;
@@ -49,16 +101,168 @@ fn parse_args(args: &[String]) -> Option<(String, String, Option<String>)> {
             i += 2;
             continue;
         }
+        if args[i] == "--fork-to" && i + 1 < args.len() {
+            fork_to = Some(args[i + 1].clone());
+            i += 2;
+            continue;
+        }
         i += 1;
     }
     let cmd = cmd?;
-    let thread_id = thread_id?;
-    Some((cmd, thread_id, checkpoint_id))
+    // summary covers every run of the event log
+    let thread_id = match thread_id {
+        Some(thread_id) => thread_id,
+        None if cmd == "summary" => String::new(),
+        None => return None,
+    };
+    Some((cmd, thread_id, checkpoint_id, fork_to))
+}
+
+/// Whether the thread's event log shows a run in flight: it has events and the latest
+/// one does not stop the run
+#[cfg(feature = "sqlite-persistence")]
+fn is_in_flight(events: &Arc<SqliteEventStore>, thread_id: &str) -> Result<bool, KernelError> {
+    let last = events.scan_rev(&thread_id.to_string(), 1, &EventFilter::all())?;
+    Ok(last
+        .first()
+        .is_some_and(|se| !STOPPED_KINDS.contains(&se.event.kind())))
+}
+
+#[cfg(feature = "sqlite-persistence")]
+fn ensure_not_cancelled(events: &Arc<SqliteEventStore>, thread_id: &str) -> Result<(), String> {
+    let cancelled = events
+        .scan_rev(
+            &thread_id.to_string(),
+            1,
+            &EventFilter::only([EventKind::Cancelled]),
+        )
+        .map_err(|e| e.to_string())?;
+    if cancelled.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Thread '{}' is cancelled", thread_id))
+    }
+}
+
+/// Kernel over the thread's event log that runs no nodes: enough to verify or cancel it
+#[cfg(feature = "sqlite-persistence")]
+fn log_kernel(events: &Arc<SqliteEventStore>, mode: KernelMode) -> Kernel<MessagesState> {
+    Kernel {
+        events: Box::new(events.clone()),
+        snaps: None,
+        reducer: Box::new(StateUpdatedOnlyReducer),
+        exec: Arc::new(NoopActionExecutor),
+        step: Box::new(NoopStepFn),
+        policy: Box::new(AllowAllPolicy),
+        effect_sink: None,
+        mode,
+        snapshot_policy: None,
+    }
+}
+
+/// Invoke the graph, stopping it when a `pause` or `cancel` for the thread is persisted
+#[cfg(feature = "sqlite-persistence")]
+async fn invoke_stoppable(
+    compiled: &oris_runtime::graph::CompiledGraph<MessagesState>,
+    checkpointer: &Arc<SqliteSaver<MessagesState>>,
+    events: &Arc<SqliteEventStore>,
+    thread_id: &str,
+    config: &RunnableConfig,
+    input: Option<MessagesState>,
+) -> Result<MessagesState, GraphError> {
+    let error = |e: String| GraphError::ExecutionError(e);
+    ensure_not_cancelled(events, thread_id).map_err(error)?;
+    // A cancel whose worker stopped before applying it still ends the thread
+    if checkpointer
+        .is_cancel_requested(thread_id)
+        .await
+        .map_err(|e| error(e.to_string()))?
+    {
+        cancel_thread(checkpointer, events, thread_id)
+            .await
+            .map_err(|e| error(e.to_string()))?;
+        return Err(error(format!("Thread '{}' is cancelled", thread_id)));
+    }
+    // A pause left over from an earlier run must not pause this one
+    checkpointer
+        .clear_pause_request(thread_id)
+        .await
+        .map_err(|e| error(e.to_string()))?;
+
+    let token = CancellationToken::new();
+    let poller = {
+        let checkpointer = checkpointer.clone();
+        let token = token.clone();
+        let thread_id = thread_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(STOP_POLL_INTERVAL).await;
+                let pause = checkpointer.is_pause_requested(&thread_id).await;
+                let cancel = checkpointer.is_cancel_requested(&thread_id).await;
+                if pause.unwrap_or(false) || cancel.unwrap_or(false) {
+                    token.cancel();
+                    break;
+                }
+            }
+        })
+    };
+
+    let result = compiled
+        .invoke_with_config(input, &config.clone().with_cancellation(token))
+        .await;
+    poller.abort();
+    result
+}
+
+/// Append `Cancelled` to the thread's event log and drop its pending cancel request
+#[cfg(feature = "sqlite-persistence")]
+async fn cancel_thread(
+    checkpointer: &Arc<SqliteSaver<MessagesState>>,
+    events: &Arc<SqliteEventStore>,
+    thread_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    checkpointer.clear_cancel_request(thread_id).await?;
+    log_kernel(events, KernelMode::Normal).cancel(&thread_id.to_string(), None)?;
+    Ok(())
+}
+
+/// Report a run stopped at `node` by a persisted request; a cancel also ends the thread
+#[cfg(feature = "sqlite-persistence")]
+async fn stopped_output(
+    checkpointer: &Arc<SqliteSaver<MessagesState>>,
+    events: &Arc<SqliteEventStore>,
+    thread_id: &str,
+    node: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if checkpointer.is_cancel_requested(thread_id).await? {
+        cancel_thread(checkpointer, events, thread_id).await?;
+        return Ok(format!("Run cancelled at node '{}'.", node));
+    }
+    checkpointer.clear_pause_request(thread_id).await?;
+    Ok(format!(
+        "Run paused at node '{}'. Resume with: resume --thread-id {}",
+        node, thread_id
+    ))
+}
+
+/// Replay the thread's kernel event log and report whether every recorded state hash is
+/// reproduced, without running any node
+#[cfg(feature = "sqlite-persistence")]
+async fn verify_thread(
+    events: &Arc<SqliteEventStore>,
+    thread_id: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let report = KernelRunner::new(log_kernel(events, KernelMode::VerifyReplay))
+        .verify_replay_async(&thread_id.to_string(), MessagesState::new())
+        .await?;
+    Ok(serde_json::to_string_pretty(&report)?)
 }
 
 #[cfg(feature = "sqlite-persistence")]
 async fn execute_command(
     compiled: &oris_runtime::graph::CompiledGraph<MessagesState>,
+    checkpointer: &Arc<SqliteSaver<MessagesState>>,
+    events: &Arc<SqliteEventStore>,
     cmd: &str,
     thread_id: &str,
     config: &RunnableConfig,
@@ -66,8 +270,22 @@ async fn execute_command(
     match cmd {
         "run" => {
             let initial = MessagesState::with_messages(vec![Message::new_human_message("CLI run")]);
-            let state = compiled.invoke_with_config(Some(initial), config).await?;
-            Ok(format!("Run completed. Messages: {}", state.messages.len()))
+            match invoke_stoppable(
+                compiled,
+                checkpointer,
+                events,
+                thread_id,
+                config,
+                Some(initial),
+            )
+            .await
+            {
+                Ok(state) => Ok(format!("Run completed. Messages: {}", state.messages.len())),
+                Err(GraphError::Cancelled { node }) => {
+                    stopped_output(checkpointer, events, thread_id, &node).await
+                }
+                Err(e) => Err(e.into()),
+            }
         }
         "list" => {
             let history = compiled.get_state_history(config).await?;
@@ -96,13 +314,19 @@ async fn execute_command(
             ))
         }
         "resume" => {
-            let state = compiled.invoke_with_config(None, config).await?;
-            Ok(format!(
-                "Resume completed. Messages: {}",
-                state.messages.len()
-            ))
+            match invoke_stoppable(compiled, checkpointer, events, thread_id, config, None).await {
+                Ok(state) => Ok(format!(
+                    "Resume completed. Messages: {}",
+                    state.messages.len()
+                )),
+                Err(GraphError::Cancelled { node }) => {
+                    stopped_output(checkpointer, events, thread_id, &node).await
+                }
+                Err(e) => Err(e.into()),
+            }
         }
         "replay" => {
+            ensure_not_cancelled(events, thread_id)?;
             let state = compiled.invoke_with_config(None, config).await?;
             Ok(format!(
                 "Replay completed for thread '{}'. Messages: {}",
@@ -110,10 +334,39 @@ async fn execute_command(
                 state.messages.len()
             ))
         }
-        "cancel" => Ok(format!(
-            "Cancel accepted for thread '{}'. (local CLI stub: no active worker to signal)",
-            thread_id
-        )),
+        "pause" => {
+            ensure_not_cancelled(events, thread_id)?;
+            if !is_in_flight(events, thread_id)? {
+                return Err(format!("Thread '{}' has no run in flight", thread_id).into());
+            }
+            checkpointer.request_pause(thread_id).await?;
+            Ok(format!(
+                "Pause accepted for thread '{}'. The running worker stops within {}ms.",
+                thread_id,
+                STOP_POLL_INTERVAL.as_millis()
+            ))
+        }
+        "cancel" => {
+            if is_in_flight(events, thread_id)? {
+                checkpointer.request_cancel(thread_id).await?;
+                return Ok(format!(
+                    "Cancel accepted for thread '{}'. The running worker stops within {}ms.",
+                    thread_id,
+                    STOP_POLL_INTERVAL.as_millis()
+                ));
+            }
+            // Fails cleanly for a thread without events or one that completed or was cancelled
+            log_kernel(events, KernelMode::Normal).cancel(&thread_id.to_string(), None)?;
+            Ok(format!("Thread '{}' cancelled.", thread_id))
+        }
+        "verify" => verify_thread(events, thread_id).await,
+        "timeline" => Ok(log_kernel(events, KernelMode::Normal)
+            .run_timeline(&thread_id.to_string())?
+            .to_text()),
+        "summary" => Ok(serde_json::to_string_pretty(&summarize_runs(
+            events.as_ref(),
+            RunFilter::default(),
+        )?)?),
         _ => Err(format!("Unknown command: {}", cmd).into()),
     }
 }
@@ -121,14 +374,18 @@ async fn execute_command(
 #[cfg(feature = "sqlite-persistence")]
 fn build_graph_and_compiled(
     db_path: &str,
+    events_db: &str,
+    delay: Duration,
 ) -> Result<
     (
         oris_runtime::graph::CompiledGraph<MessagesState>,
-        std::sync::Arc<SqliteSaver<MessagesState>>,
+        Arc<SqliteSaver<MessagesState>>,
+        Arc<SqliteEventStore>,
     ),
     Box<dyn std::error::Error>,
 > {
-    let research_node = function_node("research", |_state: &MessagesState| async move {
+    let research_node = function_node("research", move |_state: &MessagesState| async move {
+        tokio::time::sleep(delay).await;
         let mut update = HashMap::new();
         update.insert(
             "messages".to_string(),
@@ -139,7 +396,8 @@ fn build_graph_and_compiled(
         Ok(update)
     });
 
-    let approval_node = function_node("approval", |_state: &MessagesState| async move {
+    let approval_node = function_node("approval", move |_state: &MessagesState| async move {
+        tokio::time::sleep(delay).await;
         let mut update = HashMap::new();
         update.insert(
             "messages".to_string(),
@@ -157,9 +415,12 @@ fn build_graph_and_compiled(
     graph.add_edge("research", "approval");
     graph.add_edge("approval", END);
 
-    let checkpointer = std::sync::Arc::new(SqliteSaver::new(db_path)?);
-    let compiled = graph.compile_with_persistence(Some(checkpointer.clone()), None)?;
-    Ok((compiled, checkpointer))
+    let checkpointer = Arc::new(SqliteSaver::new(db_path)?);
+    let events = Arc::new(SqliteEventStore::new(events_db)?);
+    let compiled = graph
+        .compile_with_persistence(Some(checkpointer.clone()), None)?
+        .with_event_store(events.clone());
+    Ok((compiled, checkpointer, events))
 }
 
 #[cfg(feature = "sqlite-persistence")]
@@ -169,7 +430,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db_path =
         std::env::var("ORIS_SQLITE_DB").unwrap_or_else(|_| "oris_cli_checkpoints.db".into());
 
-    let (cmd, thread_id, checkpoint_id) = match parse_args(&args) {
+    let (cmd, thread_id, checkpoint_id, fork_to) = match parse_args(&args) {
         Some(t) => t,
         None => {
             eprintln!("Usage:");
@@ -177,20 +438,47 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("  list  --thread-id <id>     List checkpoints");
             eprintln!("  inspect --thread-id <id>   Inspect latest checkpoint");
             eprintln!("  resume --thread-id <id> [--checkpoint-id <id>]  Resume from latest or checkpoint");
-            eprintln!("  replay --thread-id <id> [--checkpoint-id <id>]  Replay from latest or checkpoint");
-            eprintln!("  cancel --thread-id <id>    Mark local cancel request (stub)");
+            eprintln!("  replay --thread-id <id> [--checkpoint-id <id>] [--fork-to <id>]  Replay from latest or checkpoint, optionally on a forked thread");
+            eprintln!("  pause --thread-id <id>     Pause a running run/resume of the thread");
+            eprintln!("  cancel --thread-id <id>    Cancel the thread for good");
+            eprintln!(
+                "  verify --thread-id <id>    Replay the event log and check recorded state hashes"
+            );
+            eprintln!(
+                "  timeline --thread-id <id>  Print the event log with timings and a summary"
+            );
+            eprintln!("  summary                    Count the event log's runs by status");
             std::process::exit(1);
         }
     };
 
-    let (compiled, _checkpointer) = build_graph_and_compiled(&db_path)?;
+    let events_db = std::env::var("ORIS_KERNEL_DB").unwrap_or_else(|_| db_path.clone());
+    let delay = Duration::from_millis(
+        std::env::var("ORIS_CLI_NODE_DELAY_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .unwrap_or(0),
+    );
+    let (compiled, checkpointer, events) = build_graph_and_compiled(&db_path, &events_db, delay)?;
     let config = if let Some(cp) = checkpoint_id {
         RunnableConfig::with_checkpoint(&thread_id, &cp)
     } else {
         RunnableConfig::with_thread_id(&thread_id)
     };
 
-    let output = execute_command(&compiled, &cmd, &thread_id, &config).await?;
+    // replay --fork-to copies the checkpoints into a new thread and replays there
+    let (thread_id, config) = match fork_to {
+        Some(fork_to) if cmd == "replay" => {
+            let fork_config = compiled.fork_thread(&config, &fork_to, None).await?;
+            println!("Forked thread '{}' to '{}'", thread_id, fork_to);
+            (fork_to, fork_config)
+        }
+        Some(_) => return Err("--fork-to is only supported by replay".into()),
+        None => (thread_id, config),
+    };
+
+    let output =
+        execute_command(&compiled, &checkpointer, &events, &cmd, &thread_id, &config).await?;
     println!("{}", output);
 
     Ok(())
@@ -227,31 +515,224 @@ mod tests {
         let parsed = parse_args(&args).expect("replay should parse");
         assert_eq!(parsed.0, "replay");
         assert_eq!(parsed.2.as_deref(), Some("cp-1"));
+        assert_eq!(parsed.3, None);
 
         let args = vec![
-            "cancel".to_string(),
+            "replay".to_string(),
             "--thread-id".to_string(),
             "job-a".to_string(),
+            "--fork-to".to_string(),
+            "job-b".to_string(),
         ];
-        let parsed = parse_args(&args).expect("cancel should parse");
-        assert_eq!(parsed.0, "cancel");
+        let parsed = parse_args(&args).expect("replay --fork-to should parse");
+        assert_eq!(parsed.3.as_deref(), Some("job-b"));
+
+        for cmd in ["pause", "cancel"] {
+            let args = vec![
+                cmd.to_string(),
+                "--thread-id".to_string(),
+                "job-a".to_string(),
+            ];
+            let parsed = parse_args(&args).expect("pause and cancel should parse");
+            assert_eq!(parsed.0, cmd);
+        }
+
+        let args = vec![
+            "verify".to_string(),
+            "--thread-id".to_string(),
+            "job-a".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("verify should parse");
+        assert_eq!(parsed.0, "verify");
+
+        let args = vec![
+            "timeline".to_string(),
+            "--thread-id".to_string(),
+            "job-a".to_string(),
+        ];
+        let parsed = parse_args(&args).expect("timeline should parse");
+        assert_eq!(parsed.0, "timeline");
+
+        let parsed = parse_args(&["summary".to_string()]).expect("summary needs no thread");
+        assert_eq!(parsed.0, "summary");
+        assert!(parse_args(&["inspect".to_string()]).is_none());
     }
 
     #[test]
     fn execute_command_handles_phase2_dispatch_paths() {
-        let (compiled, _checkpointer) = build_graph_and_compiled(":memory:").expect("build graph");
+        let (compiled, checkpointer, events) =
+            build_graph_and_compiled(":memory:", ":memory:", Duration::ZERO).expect("build graph");
         let config = RunnableConfig::with_thread_id("dispatch-test");
         let rt = tokio::runtime::Runtime::new().expect("runtime");
         rt.block_on(async {
-            let cancel_output = execute_command(&compiled, "cancel", "dispatch-test", &config)
-                .await
-                .expect("cancel output");
-            assert!(cancel_output.contains("Cancel accepted"));
+            let exec = |cmd: &'static str| {
+                execute_command(
+                    &compiled,
+                    &checkpointer,
+                    &events,
+                    cmd,
+                    "dispatch-test",
+                    &config,
+                )
+            };
 
-            let err = execute_command(&compiled, "unknown", "dispatch-test", &config)
-                .await
-                .expect_err("unknown should fail");
+            // Signals for a thread that never ran fail cleanly
+            let err = exec("pause").await.expect_err("nothing to pause");
+            assert!(err.to_string().contains("no run in flight"), "{}", err);
+            let err = exec("cancel").await.expect_err("nothing to cancel");
+            assert!(err.to_string().contains("not found"), "{}", err);
+
+            let run_output = exec("run").await.expect("run output");
+            assert!(run_output.contains("Run completed"));
+            let err = exec("cancel").await.expect_err("completed run");
+            assert!(err.to_string().contains("already ended"), "{}", err);
+
+            let err = exec("unknown").await.expect_err("unknown should fail");
             assert!(err.to_string().contains("Unknown command"));
         });
     }
+
+    #[test]
+    fn pause_and_cancel_stop_a_running_worker() {
+        let (compiled, checkpointer, events) =
+            build_graph_and_compiled(":memory:", ":memory:", Duration::from_millis(400))
+                .expect("build graph");
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        rt.block_on(async {
+            let exec = |cmd: &'static str, thread_id: &'static str| {
+                let config = RunnableConfig::with_thread_id(thread_id);
+                let (compiled, checkpointer, events) = (&compiled, &checkpointer, &events);
+                async move {
+                    execute_command(compiled, checkpointer, events, cmd, thread_id, &config).await
+                }
+            };
+            // Sends `cmd` while the first node of the thread's run is still sleeping
+            let signal_later = |cmd: &'static str, thread_id: &'static str| async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                exec(cmd, thread_id).await
+            };
+            let last_kind = |thread_id: &str| {
+                events
+                    .scan_rev(&thread_id.to_string(), 1, &EventFilter::all())
+                    .unwrap()[0]
+                    .event
+                    .kind()
+            };
+
+            let (run, pause) =
+                tokio::join!(exec("run", "pause-job"), signal_later("pause", "pause-job"));
+            assert!(pause.expect("pause output").contains("Pause accepted"));
+            assert!(run
+                .expect("run output")
+                .contains("Run paused at node 'research'"));
+            assert_eq!(last_kind("pause-job"), EventKind::Paused);
+            let err = exec("pause", "pause-job")
+                .await
+                .expect_err("already paused");
+            assert!(err.to_string().contains("no run in flight"), "{}", err);
+            let cancelled = exec("cancel", "pause-job")
+                .await
+                .expect("cancel paused thread");
+            assert!(cancelled.contains("cancelled"));
+            assert_eq!(last_kind("pause-job"), EventKind::Cancelled);
+
+            let (run, cancel) = tokio::join!(
+                exec("run", "cancel-job"),
+                signal_later("cancel", "cancel-job")
+            );
+            assert!(cancel.expect("cancel output").contains("Cancel accepted"));
+            assert!(run
+                .expect("run output")
+                .contains("Run cancelled at node 'research'"));
+            assert_eq!(last_kind("cancel-job"), EventKind::Cancelled);
+
+            for thread_id in ["pause-job", "cancel-job"] {
+                for cmd in ["run", "resume", "replay", "pause"] {
+                    let err = exec(cmd, thread_id).await.expect_err("thread is cancelled");
+                    assert!(err.to_string().contains("is cancelled"), "{} {}", cmd, err);
+                }
+                let err = exec("cancel", thread_id)
+                    .await
+                    .expect_err("cancelled twice");
+                assert!(err.to_string().contains("already ended"), "{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn verify_reports_a_run_as_deterministic() {
+        let db = std::env::temp_dir().join(format!("oris_cli_verify_{}.db", std::process::id()));
+        let db = db.to_str().expect("utf-8 temp path").to_string();
+        let _ = std::fs::remove_file(&db);
+        let (compiled, checkpointer, events) =
+            build_graph_and_compiled(&db, &db, Duration::ZERO).expect("build graph");
+        let config = RunnableConfig::with_thread_id("verify-test");
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let report = rt.block_on(async {
+            execute_command(
+                &compiled,
+                &checkpointer,
+                &events,
+                "run",
+                "verify-test",
+                &config,
+            )
+            .await
+            .expect("run output");
+            execute_command(
+                &compiled,
+                &checkpointer,
+                &events,
+                "verify",
+                "verify-test",
+                &config,
+            )
+            .await
+            .expect("verify output")
+        });
+        let _ = std::fs::remove_file(&db);
+
+        let report: serde_json::Value = serde_json::from_str(&report).expect("json report");
+        assert_eq!(report["run_id"], "verify-test");
+        assert_eq!(report["checked"], 2, "{}", report);
+        assert_eq!(report["matched"], 2, "{}", report);
+        assert!(report["first_divergence"].is_null(), "{}", report);
+    }
+
+    #[test]
+    fn timeline_prints_the_run_with_a_summary() {
+        let (compiled, checkpointer, events) =
+            build_graph_and_compiled(":memory:", ":memory:", Duration::ZERO).expect("build graph");
+        let config = RunnableConfig::with_thread_id("timeline-test");
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let exec = |cmd: &'static str| {
+            rt.block_on(execute_command(
+                &compiled,
+                &checkpointer,
+                &events,
+                cmd,
+                "timeline-test",
+                &config,
+            ))
+            .expect("command output")
+        };
+        exec("run");
+        let text = exec("timeline");
+        let fleet: serde_json::Value =
+            serde_json::from_str(&exec("summary")).expect("summary json");
+        assert_eq!(fleet["total"], 1, "{}", fleet);
+
+        let header = text.lines().next().expect("summary line");
+        assert!(
+            header.starts_with("run timeline-test: completed (2 steps"),
+            "{}",
+            text
+        );
+        assert_eq!(
+            text.lines().filter(|l| l.contains("StateUpdated")).count(),
+            2,
+            "{}",
+            text
+        );
+    }
 }
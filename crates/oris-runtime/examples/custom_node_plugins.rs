@@ -16,7 +16,7 @@ struct PrefixConfig {
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut registry = NodePluginRegistry::<MessagesState>::new();
     registry.register_plugin(typed_node_plugin(
-        "prefix_message",
+        "example/prefix_message",
         |name, config: PrefixConfig| {
             let prefix = config.prefix;
             Ok(Arc::new(function_node(
@@ -36,7 +36,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut graph = StateGraph::<MessagesState>::new();
     graph.add_plugin_node(
         "plugin-step",
-        "prefix_message",
+        "example/prefix_message",
         serde_json::json!({ "prefix": "hello" }),
         &registry,
     )?;
@@ -2,21 +2,45 @@
 //!
 //! Run with:
 //!   cargo run -p oris-runtime --example execution_server --features "sqlite-persistence,execution-server"
+//!
+//! Add the `metrics` feature to also serve the graph, kernel and scheduler metrics
+//! (`oris_runtime::metrics`) on `/metrics` through a Prometheus recorder.
+//!
+//! `GET /v1/runs/:run_id/events` streams a run's kernel events as server-sent events: the
+//! stored events from `?from_seq=` (default 1), then new ones as they are appended, until a
+//! final `done` event once the run ends. Each SSE id is the event seq, so a reconnecting
+//! `EventSource` resumes after its `Last-Event-ID`. Events are read from the kernel event
+//! log at `ORIS_KERNEL_DB` (default: the server database).
+//! `GET /v1/runs/summary` counts the runs of that log by status, with the longest-blocked
+//! runs and the most common failure reasons.
+//!
+//! A background lease service expires stale worker leases and requeues their attempts
+//! every `ORIS_LEASE_TICK_INTERVAL_SECS` seconds (default 5). On Ctrl-C the server stops
+//! accepting requests and the service finishes any in-flight tick before exiting.
 
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use std::collections::HashMap;
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use std::sync::Arc;
+#[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
+use std::time::Duration;
 
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
-use oris_runtime::execution_runtime::{RuntimeStorageBackend, RuntimeStorageConfig};
+use oris_runtime::execution_runtime::{
+    LeaseConfig, LeaseService, RepositoryLeaseManager, RuntimeStorageBackend, RuntimeStorageConfig,
+    SqliteRuntimeRepository,
+};
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use oris_runtime::execution_server::{build_router, ExecutionApiState};
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use oris_runtime::graph::{function_node, MessagesState, SqliteSaver, StateGraph, END, START};
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
+use oris_runtime::kernel::{PollingEventStore, SqliteEventStore};
+#[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use oris_runtime::schemas::messages::Message;
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
+use tokio_util::sync::CancellationToken;
+#[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
 use tracing_subscriber::EnvFilter;
 
 #[cfg(all(feature = "sqlite-persistence", feature = "execution-server"))]
@@ -84,7 +108,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let (Some(key_id), Some(secret)) = (api_key_id.clone(), api_key.clone()) {
         state = state.with_persisted_api_key_record(key_id, secret, true);
     }
-    let app = build_router(state);
+    #[cfg(feature = "metrics")]
+    {
+        let handle = metrics_exporter_prometheus::PrometheusBuilder::new()
+            .install_recorder()
+            .map_err(|e| std::io::Error::other(format!("install metrics recorder: {}", e)))?;
+        oris_runtime::metrics::describe();
+        state = state.with_metrics_renderer(move || handle.render());
+    }
+    let kernel_db = std::env::var("ORIS_KERNEL_DB").unwrap_or_else(|_| db_path.clone());
+    let kernel_events = Arc::new(
+        PollingEventStore::new(SqliteEventStore::new(&kernel_db)?)
+            .with_interval(Duration::from_millis(200)),
+    );
+    let app = build_router(state.with_watchable_kernel_event_store(kernel_events));
 
     tracing::info!("execution server listening on http://{}", addr);
     tracing::info!(
@@ -102,7 +139,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if bearer_token.is_some() || api_key.is_some() || api_key_id.is_some() {
         tracing::info!("execution API auth enabled");
     }
-    axum::serve(listener, app).await?;
+
+    let lease_interval = std::env::var("ORIS_LEASE_TICK_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .map_or(Duration::from_secs(5), Duration::from_secs);
+    let shutdown = CancellationToken::new();
+    let lease_service = LeaseService::spawn(
+        RepositoryLeaseManager::new(
+            SqliteRuntimeRepository::new(&db_path)?,
+            LeaseConfig::default(),
+        ),
+        lease_interval,
+        shutdown.clone(),
+    );
+
+    let served = axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await;
+    shutdown.cancel();
+    let lease_stats = lease_service.await_stopped().await;
+    tracing::info!(
+        ticks = lease_stats.ticks,
+        failed_ticks = lease_stats.failed_ticks,
+        expired_requeued = lease_stats.expired_requeued,
+        "lease service stopped"
+    );
+    served?;
     Ok(())
 }
 
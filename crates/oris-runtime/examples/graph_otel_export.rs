@@ -0,0 +1,101 @@
+//! Export graph run spans to an OTLP collector.
+//!
+//! Each `invoke_with_config` call becomes a `graph.run` trace with a `graph.node` span
+//! per node. The graph pauses before `review`, so the checkpoint written there carries
+//! the run's trace and span IDs in its metadata; the second call resumes the thread.
+//!
+//! The collector endpoint is read from `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! (default `http://localhost:4317`), e.g. a local Jaeger started with
+//! `docker run -p 16686:16686 -p 4317:4317 jaegertracing/all-in-one`.
+//!
+//! Run with:
+//!   cargo run -p oris-runtime --example graph_otel_export --features otel
+
+#[cfg(feature = "otel")]
+use oris_runtime::graph::{
+    function_node, otel::TRACE_CONTEXT_KEY, InMemorySaver, MessagesState, RunnableConfig,
+    StateGraph, END, START,
+};
+#[cfg(feature = "otel")]
+use oris_runtime::schemas::messages::Message;
+#[cfg(feature = "otel")]
+use std::collections::HashMap;
+#[cfg(feature = "otel")]
+use std::sync::Arc;
+
+#[cfg(feature = "otel")]
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| "http://localhost:4317".into());
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint.clone())
+        .build()?;
+    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    let draft = function_node("draft", |_state: &MessagesState| async move {
+        let mut update = HashMap::new();
+        update.insert(
+            "messages".to_string(),
+            serde_json::to_value(vec![Message::new_ai_message("Draft: ship it on Friday.")])?,
+        );
+        Ok(update)
+    });
+    let review = function_node("review", |_state: &MessagesState| async move {
+        let mut update = HashMap::new();
+        update.insert(
+            "messages".to_string(),
+            serde_json::to_value(vec![Message::new_ai_message("Review: approved.")])?,
+        );
+        Ok(update)
+    });
+
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph.add_node("draft", draft)?;
+    graph.add_node("review", review)?;
+    graph.add_edge(START, "draft");
+    graph.add_edge("draft", "review");
+    graph.add_edge("review", END);
+    let compiled =
+        graph.compile_with_interrupts(Arc::new(InMemorySaver::new()), &["review"], &[])?;
+
+    let config = RunnableConfig::with_thread_id("otel-demo");
+    compiled
+        .invoke_with_config(
+            Some(MessagesState::with_messages(vec![
+                Message::new_human_message("Plan the release"),
+            ])),
+            &config,
+        )
+        .await?;
+    let paused = compiled.get_state(&config).await?;
+    println!("Paused before {:?}", paused.next);
+    if let Some(trace) = paused.metadata.get(TRACE_CONTEXT_KEY) {
+        println!("Checkpoint trace context: {}", trace);
+    }
+
+    let final_state = compiled.invoke_with_config(None, &config).await?;
+    for message in &final_state.messages {
+        println!(
+            "  {}: {}",
+            message.message_type.to_string(),
+            message.content
+        );
+    }
+
+    provider.shutdown()?;
+    println!("Spans exported to {}", endpoint);
+    Ok(())
+}
+
+#[cfg(not(feature = "otel"))]
+fn main() {
+    eprintln!("This example requires the 'otel' feature.");
+    eprintln!("Run: cargo run -p oris-runtime --example graph_otel_export --features otel");
+}
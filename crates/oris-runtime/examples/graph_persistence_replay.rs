@@ -108,13 +108,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             serde_json::to_value(vec![Message::new_ai_message("Forked message")])?,
         );
 
+        let forked_checkpoint_id = compiled
+            .update_state(&fork_config, &update, Some("node1"))
+            .await?;
+        println!("Forked checkpoint ID: {}", forked_checkpoint_id);
         let forked_snapshot = compiled
-            .update_state(&fork_config, &update, Some("fork_node"))
+            .get_state(&RunnableConfig::with_checkpoint(
+                "thread-replay-1",
+                forked_checkpoint_id,
+            ))
             .await?;
-        println!(
-            "Forked checkpoint ID: {:?}",
-            forked_snapshot.checkpoint_id()
-        );
         println!(
             "Forked state messages count: {}",
             forked_snapshot.values.messages.len()
@@ -115,21 +115,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         serde_json::to_value(vec![Message::new_ai_message("Topic: chickens")])?,
     );
 
-    let updated_snapshot = compiled
+    let updated_checkpoint_id = compiled
         .update_state(&selected_checkpoint.to_config(), &state_updates, None)
         .await?;
 
-    println!(
-        "  Updated checkpoint_id: {:?}",
-        updated_snapshot.checkpoint_id()
-    );
+    println!("  Updated checkpoint_id: {}", updated_checkpoint_id);
 
     // Step 5: Resume execution from the updated checkpoint
     println!("\n=== Step 5: Resume from checkpoint ===");
     let resumed_state = compiled
         .invoke_with_config(
             None, // None means resume from checkpoint
-            &updated_snapshot.to_config(),
+            &RunnableConfig::with_checkpoint("time-travel-demo", updated_checkpoint_id),
         )
         .await?;
 
@@ -62,11 +62,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "messages".to_string(),
         serde_json::to_value(vec![Message::new_human_message("topic1")])?,
     );
-    let snapshot1 = compiled
+    let checkpoint1 = compiled
         .update_state(&fork_point.to_config(), &updates1, None)
         .await?;
     let result1 = compiled
-        .invoke_with_config(None, &snapshot1.to_config())
+        .invoke_with_config(
+            None,
+            &RunnableConfig::with_checkpoint("fork-demo", checkpoint1),
+        )
         .await?;
     println!("Result: {:?}", result1.messages.last().map(|m| &m.content));
 
@@ -77,11 +80,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "messages".to_string(),
         serde_json::to_value(vec![Message::new_human_message("topic2")])?,
     );
-    let snapshot2 = compiled
+    let checkpoint2 = compiled
         .update_state(&fork_point.to_config(), &updates2, None)
         .await?;
     let result2 = compiled
-        .invoke_with_config(None, &snapshot2.to_config())
+        .invoke_with_config(
+            None,
+            &RunnableConfig::with_checkpoint("fork-demo", checkpoint2),
+        )
         .await?;
     println!("Result: {:?}", result2.messages.last().map(|m| &m.content));
 
@@ -0,0 +1,144 @@
+//! Tail a run's effects from a JSON-lines file while the run executes.
+//!
+//! The kernel records its effects to a JsonlFileEffectSink, which writes them from a
+//! background thread so the run never waits on the disk. The run executes on its own
+//! thread while the main thread follows the file and prints each effect as it lands.
+//!
+//! Run with: cargo run -p oris-runtime --example kernel_effect_tail
+
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+use std::time::Duration;
+
+use oris_runtime::kernel::driver::{Kernel, RunStatus};
+use oris_runtime::kernel::event_store::InMemoryEventStore;
+use oris_runtime::kernel::stubs::AllowAllPolicy;
+use oris_runtime::kernel::{
+    Action, ActionExecutor, ActionResult, EffectRecord, EffectSink, Event, FsyncPolicy,
+    JsonlFileConfig, JsonlFileEffectSink, KernelError, KernelMode, KernelState, Next, Reducer,
+    RunId, RuntimeEffect, SequencedEvent, StepFn,
+};
+
+/// Pages fetched so far, rebuilt from the run's events
+#[derive(Clone, Debug, Default)]
+struct Crawl {
+    fetched: usize,
+}
+
+impl KernelState for Crawl {
+    fn version(&self) -> u32 {
+        1
+    }
+}
+
+struct CrawlReducer;
+
+impl Reducer<Crawl> for CrawlReducer {
+    fn apply(&self, state: &mut Crawl, event: &SequencedEvent) -> Result<(), KernelError> {
+        if let Event::ActionSucceeded { .. } = &event.event {
+            state.fetched += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Fetches five pages, one tool call per step
+struct Crawler;
+
+impl StepFn<Crawl> for Crawler {
+    fn next(&self, state: &Crawl) -> Result<Next, KernelError> {
+        if state.fetched == 5 {
+            return Ok(Next::Complete);
+        }
+        Ok(Next::Do(Action::CallTool {
+            tool: "http/get".into(),
+            input: serde_json::json!({"page": state.fetched + 1}),
+            idempotency_key: None,
+        }))
+    }
+}
+
+/// Takes a while per page, so the tail shows the effects arriving one by one
+struct SlowFetcher;
+
+impl ActionExecutor for SlowFetcher {
+    fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        std::thread::sleep(Duration::from_millis(150));
+        Ok(ActionResult::Success(
+            serde_json::json!({"fetched": action.kind()}),
+        ))
+    }
+}
+
+fn describe(record: &EffectRecord) -> String {
+    match &record.effect {
+        RuntimeEffect::ToolCall { tool, input } => format!("tool call {} {}", tool, input),
+        RuntimeEffect::LLMCall { provider, .. } => format!("llm call to {}", provider),
+        RuntimeEffect::StateWrite { payload, .. } => format!("state write {}", payload),
+        RuntimeEffect::InterruptRaise { value } => format!("interrupt {}", value),
+        RuntimeEffect::SnapshotIntegrityFailure { at_seq, reason } => {
+            format!("corrupt snapshot at seq {}: {}", at_seq, reason)
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("oris-effects-{}.jsonl", std::process::id()));
+    let sink = Arc::new(JsonlFileEffectSink::open_with_config(
+        &path,
+        JsonlFileConfig::default()
+            .with_fsync(FsyncPolicy::Interval(Duration::from_millis(500)))
+            .with_rotation(1024 * 1024, 3),
+    )?);
+    println!("recording effects to {}", path.display());
+
+    let kernel = Kernel {
+        events: Box::new(InMemoryEventStore::new()),
+        snaps: None,
+        reducer: Box::new(CrawlReducer),
+        exec: Arc::new(SlowFetcher),
+        step: Box::new(Crawler),
+        policy: Box::new(AllowAllPolicy),
+        effect_sink: Some(Box::new(sink.clone())),
+        mode: KernelMode::Normal,
+        snapshot_policy: None,
+    };
+    let run = std::thread::spawn(move || {
+        kernel.run_until_blocked(&"effect-tail".to_string(), Crawl::default())
+    });
+
+    // Follow the file: read what is there, then poll for more until the run has ended
+    let mut reader = BufReader::new(std::fs::File::open(&path)?);
+    let mut line = String::new();
+    let mut run = Some(run);
+    loop {
+        // A line still being written stays in `line` until the rest of it arrives
+        if reader.read_line(&mut line)? > 0 && line.ends_with('\n') {
+            let record: EffectRecord = serde_json::from_str(&line)?;
+            println!("[{}] {}", record.run_id, describe(&record));
+            line.clear();
+            continue;
+        }
+        match run.take() {
+            Some(handle) if handle.is_finished() => {
+                let status = handle.join().expect("run thread panicked")?;
+                assert!(matches!(status, RunStatus::Completed));
+                // Everything the run recorded is in the file once the sink is flushed
+                sink.flush();
+            }
+            Some(handle) => {
+                run = Some(handle);
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            None => break,
+        }
+    }
+
+    let health = sink.health().unwrap_or_default();
+    println!(
+        "run completed; {} effects recorded, {} written, {} dropped, {} failed",
+        health.recorded, health.delivered, health.dropped, health.failed
+    );
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,201 @@
+//! Govern a run's actions by risk tier: read-only actions run freely, reversible writes
+//! run within a budget, and irreversible ones wait for a human to approve them.
+//!
+//! A RiskTierRegistry classifies action kinds into tiers and a TieredPolicy hands each
+//! action to the policy of its tier. The step interrupts before its payment; resuming with
+//! an approval lets the irreversible tier's policy allow it.
+//!
+//! Run with: cargo run -p oris-runtime --example kernel_risk_tiers
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use oris_runtime::kernel::driver::{Kernel, RunStatus, Signal};
+use oris_runtime::kernel::event_store::InMemoryEventStore;
+use oris_runtime::kernel::{
+    run_timeline, Action, ActionExecutor, ActionResult, BudgetRules, Event, InterruptInfo,
+    KernelError, KernelMode, KernelState, Next, Policy, PolicyCtx, Reducer, RiskTier,
+    RiskTierRegistry, RunId, SequencedEvent, StepFn, TieredPolicy,
+};
+
+/// How far the agent got, rebuilt from the run's events
+#[derive(Clone, Debug, Default)]
+struct Agent {
+    completed: usize,
+    approved_by: Option<String>,
+}
+
+impl KernelState for Agent {
+    fn version(&self) -> u32 {
+        1
+    }
+}
+
+struct AgentReducer;
+
+impl Reducer<Agent> for AgentReducer {
+    fn apply(&self, state: &mut Agent, event: &SequencedEvent) -> Result<(), KernelError> {
+        match &event.event {
+            Event::ActionSucceeded { .. } => state.completed += 1,
+            Event::Resumed { value } => {
+                state.approved_by = value["approved_by"].as_str().map(String::from);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn call(tool: &str, input: serde_json::Value) -> Next {
+    Next::Do(Action::CallTool {
+        tool: tool.into(),
+        input,
+        idempotency_key: None,
+    })
+}
+
+/// Looks up the docs, saves a draft, then asks for approval before paying the invoice
+struct InvoiceAgent;
+
+impl StepFn<Agent> for InvoiceAgent {
+    fn next(&self, state: &Agent) -> Result<Next, KernelError> {
+        Ok(match (state.completed, &state.approved_by) {
+            (0, _) => call("search/docs", serde_json::json!({"q": "invoice 42"})),
+            (1, _) => call("files/write", serde_json::json!({"path": "draft.txt"})),
+            (2, None) => Next::Interrupt(InterruptInfo {
+                value: serde_json::json!({"approve": "payments/send", "amount_cents": 1200}),
+            }),
+            (2, Some(approver)) => call(
+                "payments/send",
+                serde_json::json!({"amount_cents": 1200, "approved_by": approver}),
+            ),
+            _ => Next::Complete,
+        })
+    }
+}
+
+struct EchoExecutor;
+
+impl ActionExecutor for EchoExecutor {
+    fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        Ok(ActionResult::Success(
+            serde_json::json!({"done": action.kind()}),
+        ))
+    }
+}
+
+/// Allows writes, at most three per kind over the run
+struct WriteBudget;
+
+impl Policy for WriteBudget {
+    fn name(&self) -> String {
+        "write_budget".into()
+    }
+
+    fn authorize(&self, _: &RunId, _: &Action, _: &PolicyCtx) -> Result<(), KernelError> {
+        Ok(())
+    }
+
+    fn budget(&self) -> BudgetRules {
+        BudgetRules {
+            max_actions_per_kind: HashMap::from([("files/write".to_string(), 3)]),
+            ..BudgetRules::default()
+        }
+    }
+}
+
+/// Allows only tool calls a human approved, i.e. whose input names the approver
+struct ApprovedOnly;
+
+impl Policy for ApprovedOnly {
+    fn name(&self) -> String {
+        "approved_only".into()
+    }
+
+    fn authorize(
+        &self,
+        run_id: &RunId,
+        action: &Action,
+        ctx: &PolicyCtx,
+    ) -> Result<(), KernelError> {
+        self.authorize_with_reason(run_id, action, ctx).map(|_| ())
+    }
+
+    fn authorize_with_reason(
+        &self,
+        _: &RunId,
+        action: &Action,
+        _: &PolicyCtx,
+    ) -> Result<Option<String>, KernelError> {
+        match action {
+            Action::CallTool { input, .. } => match input["approved_by"].as_str() {
+                Some(approver) => Ok(Some(format!("approved by {}", approver))),
+                None => Err(KernelError::Policy(format!(
+                    "'{}' needs human approval",
+                    action.kind()
+                ))),
+            },
+            _ => Err(KernelError::Policy(
+                "only tool calls can be approved".into(),
+            )),
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let tiers = RiskTierRegistry::new()
+        .classify("search/*", RiskTier::ReadOnly)?
+        .classify("files/*", RiskTier::ReversibleWrite)?
+        .classify("payments/*", RiskTier::Irreversible)?;
+
+    let run_id = "risk-tiers".to_string();
+    let payment = Action::CallTool {
+        tool: "payments/send".into(),
+        input: serde_json::json!({"amount_cents": 1200}),
+        idempotency_key: None,
+    };
+    println!("payments/send is {}", payment.risk_tier(&tiers));
+    // Irreversible actions are denied until a policy allows them
+    let by_default = TieredPolicy::new(tiers.clone());
+    if let Err(e) = by_default.authorize(&run_id, &payment, &PolicyCtx::default()) {
+        println!("default: {}", e);
+    }
+
+    let policy = TieredPolicy::new(tiers)
+        .with_policy(RiskTier::ReversibleWrite, WriteBudget)
+        .with_policy(RiskTier::Irreversible, ApprovedOnly);
+    if let Err(e) = policy.authorize(&run_id, &payment, &PolicyCtx::default()) {
+        println!("unapproved: {}", e);
+    }
+    println!("policy: {}", policy.name());
+
+    let kernel = Kernel {
+        events: Box::new(InMemoryEventStore::new()),
+        snaps: None,
+        reducer: Box::new(AgentReducer),
+        exec: Arc::new(EchoExecutor),
+        step: Box::new(InvoiceAgent),
+        policy: Box::new(policy),
+        effect_sink: None,
+        mode: KernelMode::Normal,
+        snapshot_policy: None,
+    };
+
+    let status = kernel.run_until_blocked(&run_id, Agent::default())?;
+    assert!(matches!(status, RunStatus::Blocked(_)));
+    println!("blocked for approval; resuming as alice");
+    let status = kernel.resume(
+        &run_id,
+        Agent::default(),
+        Signal::Resume(serde_json::json!({"approved_by": "alice"})),
+    )?;
+    assert!(matches!(status, RunStatus::Completed));
+
+    for entry in run_timeline(kernel.events.as_ref(), &run_id)?.events {
+        match entry.detail {
+            Some(detail) => println!("{:>3} {}: {}", entry.seq, entry.kind, detail),
+            None => println!("{:>3} {}", entry.seq, entry.kind),
+        }
+    }
+    Ok(())
+}
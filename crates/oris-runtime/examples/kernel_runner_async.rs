@@ -34,11 +34,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         events: Box::new(InMemoryEventStore::new()),
         snaps: None,
         reducer: Box::new(GraphStepReducer),
-        exec: Box::new(NoopActionExecutor),
+        exec: Arc::new(NoopActionExecutor),
         step: Box::new(adapter),
         policy: Box::new(AllowAllPolicy),
         effect_sink: None,
         mode: oris_runtime::kernel::KernelMode::Normal,
+        snapshot_policy: None,
     };
 
     let runner = KernelRunner::new(kernel);
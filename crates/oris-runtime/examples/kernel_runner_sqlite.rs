@@ -0,0 +1,89 @@
+//! Run the kernel with its event log in SQLite, then reopen the log and read it back.
+//!
+//! Same graph as `kernel_runner_sync`, but events go to a `SqliteEventStore`, so the
+//! run's history survives the process.
+//!
+//! Run with:
+//!   cargo run -p oris-runtime --example kernel_runner_sqlite --features sqlite-persistence
+
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::graph::{
+    function_node, CompiledGraph, GraphStepFnAdapter, GraphStepReducer, GraphStepState,
+    MessagesState, StateGraph, END, START,
+};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::driver::{Kernel, RunStatus};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::runner::KernelRunner;
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::{EventStore, SqliteEventStore};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::schemas::messages::Message;
+#[cfg(feature = "sqlite-persistence")]
+use std::sync::Arc;
+
+#[cfg(feature = "sqlite-persistence")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let db_path =
+        std::env::var("ORIS_KERNEL_DB").unwrap_or_else(|_| "oris_kernel_events.db".into());
+
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph
+        .add_node(
+            "node1",
+            function_node("node1", |_s: &MessagesState| async move {
+                Ok(std::collections::HashMap::new())
+            }),
+        )
+        .unwrap();
+    graph.add_edge(START, "node1");
+    graph.add_edge("node1", END);
+
+    let compiled: Arc<CompiledGraph<MessagesState>> = Arc::new(graph.compile().unwrap());
+    let adapter = GraphStepFnAdapter::new(compiled);
+
+    let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+        events: Box::new(SqliteEventStore::new(&db_path)?),
+        snaps: None,
+        reducer: Box::new(GraphStepReducer),
+        exec: Arc::new(NoopActionExecutor),
+        step: Box::new(adapter),
+        policy: Box::new(AllowAllPolicy),
+        effect_sink: None,
+        mode: oris_runtime::kernel::KernelMode::Normal,
+        snapshot_policy: None,
+    };
+
+    let runner = KernelRunner::new(kernel);
+    let run_id = format!(
+        "sqlite-run-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis()
+    );
+    let initial = GraphStepState::new(MessagesState::with_messages(vec![
+        Message::new_human_message("Hello from SQLite"),
+    ]));
+
+    let status = runner.run_until_blocked_sync(&run_id, initial)?;
+    assert!(matches!(status, RunStatus::Completed));
+    drop(runner);
+
+    // A fresh store on the same file sees the whole run
+    let events = SqliteEventStore::new(&db_path)?;
+    println!("Run {} completed; events in {}:", run_id, db_path);
+    for event in events.scan(&run_id, 1)? {
+        println!("  {:>3}: {:?}", event.seq, event.event);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite-persistence"))]
+fn main() {
+    eprintln!("This example requires the 'sqlite-persistence' feature.");
+    eprintln!(
+        "Run: cargo run -p oris-runtime --example kernel_runner_sqlite --features sqlite-persistence"
+    );
+}
@@ -0,0 +1,103 @@
+//! Step a graph through the kernel one node at a time and print what each step changed.
+//!
+//! Uses KernelRunner::step_once to advance the run, json_diff to compare the state
+//! before and after each step, and KernelRunner::state_at to look back at an earlier seq.
+//!
+//! Run with: cargo run -p oris-runtime --example kernel_step_debugger
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use oris_runtime::graph::{
+    function_node, CompiledGraph, FunctionNode, GraphStepFnAdapter, GraphStepReducer,
+    GraphStepState, MessagesState, StateGraph, END, START,
+};
+use oris_runtime::kernel::driver::Kernel;
+use oris_runtime::kernel::event_store::InMemoryEventStore;
+use oris_runtime::kernel::runner::KernelRunner;
+use oris_runtime::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+use oris_runtime::kernel::{json_diff, KernelMode, Next};
+use oris_runtime::schemas::messages::Message;
+
+fn reply(node: &'static str, text: &'static str) -> FunctionNode<MessagesState> {
+    function_node(node, move |_s: &MessagesState| async move {
+        let mut update = HashMap::new();
+        update.insert(
+            "messages".to_string(),
+            serde_json::to_value(vec![Message::new_ai_message(text)])?,
+        );
+        Ok(update)
+    })
+}
+
+/// One-line summary of a step decision
+fn describe(next: &Next) -> String {
+    match next {
+        Next::Emit(events) => format!("emit {} event(s)", events.len()),
+        Next::Do(action) => format!("do {:?}", action),
+        Next::DoAll(batch) => format!("do {} action(s)", batch.actions.len()),
+        Next::Interrupt(info) => format!("interrupt {}", info.value),
+        Next::Fail(reason) | Next::FailWithCode { reason, .. } => format!("fail: {}", reason),
+        Next::Complete => "complete".into(),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph.add_node("draft", reply("draft", "Here is a first draft."))?;
+    graph.add_node("review", reply("review", "Reviewed: looks good."))?;
+    graph.add_edge(START, "draft");
+    graph.add_edge("draft", "review");
+    graph.add_edge("review", END);
+    let compiled: Arc<CompiledGraph<MessagesState>> = Arc::new(graph.compile()?);
+
+    let runner = KernelRunner::new(Kernel {
+        events: Box::new(InMemoryEventStore::new()),
+        snaps: None,
+        reducer: Box::new(GraphStepReducer),
+        exec: Arc::new(NoopActionExecutor),
+        step: Box::new(GraphStepFnAdapter::new(compiled)),
+        policy: Box::new(AllowAllPolicy),
+        effect_sink: None,
+        mode: KernelMode::Normal,
+        snapshot_policy: None,
+    });
+    let run_id = "step-debugger".to_string();
+    let initial = GraphStepState::new(MessagesState::with_messages(vec![
+        Message::new_human_message("Write me a haiku"),
+    ]));
+
+    let mut before = serde_json::to_value(&initial)?;
+    for step in 1.. {
+        let outcome = runner.step_once(&run_id, initial.clone())?;
+        let seqs: Vec<_> = outcome.events.iter().map(|se| se.seq).collect();
+        println!(
+            "step {}: {}, appended seqs {:?}",
+            step,
+            describe(&outcome.next),
+            seqs
+        );
+        let after = serde_json::to_value(&outcome.state)?;
+        for change in json_diff(&before, &after) {
+            println!(
+                "  {}: {} -> {}",
+                change.path,
+                change.base.map_or("<absent>".into(), |v| v.to_string()),
+                change.shadow.map_or("<absent>".into(), |v| v.to_string()),
+            );
+        }
+        before = after;
+        if let Some(status) = outcome.status {
+            println!("run ended: {:?}", status);
+            break;
+        }
+    }
+
+    let first = runner.state_at(&run_id, 1, initial)?;
+    println!(
+        "after seq 1 the run was at node {:?} with {} messages",
+        first.current_node,
+        first.graph_state.messages.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Run a RuntimeWorker against a SQLite runtime repository.
+//!
+//! Registers a one-node `hello` graph, enqueues a run of it, and then polls for
+//! dispatchable attempts until Ctrl-C. Attempts enqueued by other processes on the same
+//! database are picked up too, as long as their run names a registered graph.
+//!
+//! Run with:
+//!   cargo run -p oris-runtime --example worker --features sqlite-persistence
+
+#[cfg(feature = "sqlite-persistence")]
+use chrono::Utc;
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::execution_runtime::{
+    EventStoreFactory, GraphRegistry, RunRecord, RunRuntimeStatus, RuntimeRepository,
+    RuntimeWorker, RuntimeWorkerConfig, SqliteRuntimeRepository,
+};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::graph::{function_node, MessagesState, StateGraph, END, START};
+#[cfg(feature = "sqlite-persistence")]
+use oris_runtime::kernel::{EventStore, SqliteEventStore};
+#[cfg(feature = "sqlite-persistence")]
+use std::sync::Arc;
+#[cfg(feature = "sqlite-persistence")]
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "sqlite-persistence")]
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let runtime_db =
+        std::env::var("ORIS_RUNTIME_DB").unwrap_or_else(|_| "oris_runtime_worker.db".into());
+    let events_db =
+        std::env::var("ORIS_KERNEL_DB").unwrap_or_else(|_| "oris_worker_events.db".into());
+
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph.add_node(
+        "hello",
+        function_node("hello", |_s: &MessagesState| async move {
+            println!("  hello from the worker");
+            Ok(std::collections::HashMap::new())
+        }),
+    )?;
+    graph.add_edge(START, "hello");
+    graph.add_edge("hello", END);
+    let mut graphs = GraphRegistry::new();
+    graphs.register("hello", Arc::new(graph.compile()?), MessagesState::new());
+
+    let repo = Arc::new(SqliteRuntimeRepository::new(&runtime_db)?);
+    let now = Utc::now();
+    let run_id = format!("worker-run-{}", now.timestamp_millis());
+    repo.create_run(&RunRecord {
+        run_id: run_id.clone(),
+        workflow_name: "hello".into(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    })?;
+    repo.enqueue_attempt(&format!("{}-attempt-1", run_id), &run_id)?;
+    println!("Enqueued run {} in {}", run_id, runtime_db);
+
+    let event_store: EventStoreFactory =
+        Arc::new(move || Ok(Box::new(SqliteEventStore::new(&events_db)?) as Box<dyn EventStore>));
+    let worker = RuntimeWorker::new(
+        repo,
+        Arc::new(graphs),
+        event_store,
+        RuntimeWorkerConfig::default(),
+    );
+
+    let shutdown = CancellationToken::new();
+    let on_ctrl_c = shutdown.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        on_ctrl_c.cancel();
+    });
+    println!(
+        "Worker {} polling; Ctrl-C to stop",
+        worker.config().worker_id
+    );
+    worker.run(shutdown).await;
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite-persistence"))]
+fn main() {
+    eprintln!("This example requires the 'sqlite-persistence' feature.");
+    eprintln!("Run: cargo run -p oris-runtime --example worker --features sqlite-persistence");
+}
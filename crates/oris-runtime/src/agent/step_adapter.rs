@@ -8,6 +8,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::kernel::canonical::canonical_state_hash;
 use crate::kernel::event::Event;
 use crate::kernel::state::KernelState;
 use crate::kernel::step::{Next, StepFn};
@@ -41,6 +42,10 @@ impl KernelState for AgentStepState {
     fn version(&self) -> u32 {
         1
     }
+
+    fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+        canonical_state_hash(self).map(Some)
+    }
 }
 
 /// Sync StepFn that runs the agent (one full invoke per step) via block_on.
@@ -78,6 +83,8 @@ impl StepFn<AgentStepState> for AgentStepFnAdapter {
                 Ok(Next::Emit(vec![Event::StateUpdated {
                     step_id: Some("agent".to_string()),
                     payload,
+                    state_hash: None,
+                    merge_report: None,
                 }]))
             }
             Ok(super::AgentInvokeResult::Interrupt { interrupt_value }) => {
@@ -4,7 +4,13 @@
 //! the legacy compatibility re-exports for that graph-aware surface are
 //! deprecated here.
 
+pub mod worker;
+
 pub use oris_execution_runtime::*;
+pub use worker::{
+    AttemptOutcome, EventStoreFactory, ExecutedAttempt, GraphRegistry, RuntimeWorker,
+    RuntimeWorkerConfig,
+};
 
 #[cfg(feature = "execution-server")]
 #[deprecated(
@@ -0,0 +1,483 @@
+//! Worker that takes dispatchable attempts from an [AsyncRuntimeRepository] and runs their
+//! graphs.
+//!
+//! Each pass claims dispatchable attempts one by one with
+//! [AsyncRuntimeRepository::claim_dispatchable_attempts], looks up the attempt's run to
+//! find the graph it executes in a [GraphRegistry], and drives that graph through a
+//! [KernelRunner] while a [LeaseKeeper] heartbeats the lease. When the run stops the attempt is
+//! finished as completed or cancelled with [AsyncRuntimeRepository::complete_attempt]; a
+//! failed run is recorded with [AsyncRuntimeRepository::fail_attempt], which schedules a
+//! retry or dead-letters the attempt under the worker's retry policy. Heartbeats and both
+//! writes carry the lease's fencing token, so they are rejected once the lease has moved
+//! on. If a heartbeat finds the lease gone, the run is paused at its next step boundary
+//! and the attempt is left to whichever worker holds it now. If a heartbeat reports the
+//! attempt cancelled ([LeaseDirective::Cancel]), the run is cancelled at its next step
+//! boundary and the attempt finished as cancelled.
+//!
+//! The run record follows its attempts: it is `Running` while an attempt drives it, then
+//! completed, blocked, paused, cancelled, backing off or failed as the attempt ended.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio_util::sync::CancellationToken;
+
+use oris_execution_runtime::async_repository::AsyncRuntimeRepository;
+use oris_execution_runtime::lease_keeper::LeaseKeeper;
+use oris_execution_runtime::models::{
+    AttemptDispatchRecord, AttemptExecutionStatus, ClaimedAttempt, LeaseFence, LeaseRecord,
+    RetryPolicyConfig, RetryStrategy, RunRuntimeStatus,
+};
+
+use crate::graph::{CompiledGraph, GraphStepFnAdapter, GraphStepReducer, GraphStepState, State};
+use crate::kernel::driver::{Kernel, RunStatus};
+use crate::kernel::runner::KernelRunner;
+use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+use crate::kernel::{EventStore, KernelError, KernelMode, KernelState, RunId};
+
+/// Builds the event store a run's kernel appends to; called once per executed attempt.
+pub type EventStoreFactory =
+    Arc<dyn Fn() -> Result<Box<dyn EventStore>, KernelError> + Send + Sync>;
+
+struct RegisteredGraph<S: State> {
+    graph: Arc<CompiledGraph<S>>,
+    initial_state: S,
+}
+
+/// Compiled graphs a worker can run, keyed by the name stored in
+/// [RunRecord::workflow_name](oris_execution_runtime::models::RunRecord::workflow_name).
+pub struct GraphRegistry<S: State> {
+    graphs: HashMap<String, RegisteredGraph<S>>,
+}
+
+impl<S: State> GraphRegistry<S> {
+    pub fn new() -> Self {
+        Self {
+            graphs: HashMap::new(),
+        }
+    }
+
+    /// Registers `graph` under `name`; runs of it start from `initial_state`. Replaces a
+    /// graph registered under the same name before.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        graph: Arc<CompiledGraph<S>>,
+        initial_state: S,
+    ) -> &mut Self {
+        self.graphs.insert(
+            name.into(),
+            RegisteredGraph {
+                graph,
+                initial_state,
+            },
+        );
+        self
+    }
+
+    /// The graph registered under `name` and the state its runs start from.
+    pub fn get(&self, name: &str) -> Option<(Arc<CompiledGraph<S>>, S)> {
+        self.graphs
+            .get(name)
+            .map(|entry| (Arc::clone(&entry.graph), entry.initial_state.clone()))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.graphs.contains_key(name)
+    }
+}
+
+impl<S: State> Default for GraphRegistry<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Settings for a [RuntimeWorker].
+#[derive(Clone, Debug)]
+pub struct RuntimeWorkerConfig {
+    /// Owner recorded on the leases this worker takes.
+    pub worker_id: String,
+    /// Longest [RuntimeWorker::run] sleeps after a pass that found nothing to do; it
+    /// wakes earlier when a delayed or backed-off attempt falls due before then.
+    pub poll_interval: Duration,
+    /// How far past each heartbeat a lease is extended.
+    pub lease_ttl: Duration,
+    /// How often the lease of a running attempt is heartbeated; keep it well below
+    /// `lease_ttl`.
+    pub heartbeat_interval: Duration,
+    /// Most attempts claimed per pass.
+    pub batch_size: usize,
+    /// Retries of attempts whose run failed, before they are dead-lettered.
+    pub retry_policy: RetryPolicyConfig,
+}
+
+impl Default for RuntimeWorkerConfig {
+    fn default() -> Self {
+        Self {
+            worker_id: format!("worker-{}", std::process::id()),
+            poll_interval: Duration::from_millis(500),
+            lease_ttl: Duration::from_secs(30),
+            heartbeat_interval: Duration::from_secs(10),
+            batch_size: 16,
+            retry_policy: RetryPolicyConfig {
+                strategy: RetryStrategy::Exponential,
+                backoff_ms: 1_000,
+                max_backoff_ms: Some(60_000),
+                multiplier: Some(2.0),
+                max_retries: 3,
+            },
+        }
+    }
+}
+
+/// How an attempt the worker leased ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// The attempt was finished with this status, as stored by the repository.
+    Finished(AttemptExecutionStatus),
+    /// The lease was lost while the run was in flight; the run was paused and the attempt
+    /// left untouched.
+    LeaseLost,
+}
+
+/// How a leased attempt's run stopped.
+enum RunEnd {
+    /// Stopped without failing; the attempt is finished with this status and the run
+    /// record moved to the second.
+    Stopped(AttemptExecutionStatus, RunRuntimeStatus),
+    /// Failed, or could not start, for this reason.
+    Failed(String),
+    LeaseLost,
+}
+
+/// An attempt executed by [RuntimeWorker::run_once].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutedAttempt {
+    pub attempt_id: String,
+    pub run_id: RunId,
+    pub outcome: AttemptOutcome,
+}
+
+/// Polls an [AsyncRuntimeRepository] for dispatchable attempts and runs their graphs.
+pub struct RuntimeWorker<R, S>
+where
+    R: AsyncRuntimeRepository + 'static,
+    S: State + KernelState + 'static,
+{
+    repo: Arc<R>,
+    graphs: Arc<GraphRegistry<S>>,
+    event_store: EventStoreFactory,
+    config: RuntimeWorkerConfig,
+}
+
+impl<R, S> RuntimeWorker<R, S>
+where
+    R: AsyncRuntimeRepository + 'static,
+    S: State + KernelState + 'static,
+{
+    pub fn new(
+        repo: Arc<R>,
+        graphs: Arc<GraphRegistry<S>>,
+        event_store: EventStoreFactory,
+        config: RuntimeWorkerConfig,
+    ) -> Self {
+        Self {
+            repo,
+            graphs,
+            event_store,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &RuntimeWorkerConfig {
+        &self.config
+    }
+
+    /// Polls until `shutdown` is cancelled. A failed pass is logged and retried after
+    /// `poll_interval`; an attempt in flight is finished before the worker stops.
+    pub async fn run(&self, shutdown: CancellationToken) {
+        while !shutdown.is_cancelled() {
+            let idle = match self.run_once().await {
+                Ok(executed) => executed.is_empty(),
+                Err(e) => {
+                    log::warn!("worker {}: pass failed: {}", self.config.worker_id, e);
+                    true
+                }
+            };
+            if idle {
+                let delay = self.idle_delay().await;
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+        }
+    }
+
+    /// One pass: claims dispatchable attempts one at a time, up to `batch_size`, and runs
+    /// each to the end before claiming the next, so no lease waits unheartbeated behind
+    /// another attempt.
+    pub async fn run_once(&self) -> Result<Vec<ExecutedAttempt>, KernelError> {
+        let mut executed = Vec::new();
+        for _ in 0..self.config.batch_size {
+            let claimed = self
+                .repo
+                .claim_dispatchable_attempts(
+                    Utc::now(),
+                    &self.config.worker_id,
+                    1,
+                    self.lease_ttl()?,
+                )
+                .await?;
+            let Some(ClaimedAttempt { attempt, lease }) = claimed.into_iter().next() else {
+                break;
+            };
+            executed.push(self.execute(attempt, lease).await?);
+        }
+        Ok(executed)
+    }
+
+    async fn execute(
+        &self,
+        candidate: AttemptDispatchRecord,
+        lease: LeaseRecord,
+    ) -> Result<ExecutedAttempt, KernelError> {
+        let mut fence = LeaseFence::from(&lease);
+        let end = match self.resolve_graph(&candidate).await? {
+            Ok((graph, initial_state)) => {
+                self.record_run_status(&candidate.run_id, RunRuntimeStatus::Running, None)
+                    .await;
+                self.drive(&candidate.run_id, &lease, &mut fence, graph, initial_state)
+                    .await?
+            }
+            Err(reason) => RunEnd::Failed(reason),
+        };
+
+        let attempt_id = &candidate.attempt_id;
+        let (stored, run_status) = match end {
+            RunEnd::Stopped(status, run_status) => {
+                let stored = self
+                    .repo
+                    .complete_attempt(attempt_id, &fence, status, Utc::now())
+                    .await;
+                (stored, Some((run_status, None)))
+            }
+            RunEnd::Failed(error) => {
+                log::warn!(
+                    "worker {}: attempt {} failed: {}",
+                    self.config.worker_id,
+                    candidate.attempt_id,
+                    error
+                );
+                let stored = self
+                    .repo
+                    .fail_attempt(
+                        attempt_id,
+                        &fence,
+                        &error,
+                        &self.config.retry_policy,
+                        Utc::now(),
+                    )
+                    .await
+                    .map(|outcome| outcome.status);
+                let run_status = match stored {
+                    Ok(AttemptExecutionStatus::RetryBackoff) => RunRuntimeStatus::RetryBackoff,
+                    _ => RunRuntimeStatus::Failed,
+                };
+                (stored, Some((run_status, Some(error))))
+            }
+            RunEnd::LeaseLost => (Err(KernelError::LeaseConflict(fence.lease_id)), None),
+        };
+        let outcome = match stored {
+            Ok(status) => {
+                if let Some((run_status, reason)) = run_status {
+                    // The repository has the last word on how the attempt ended
+                    let run_status = match status {
+                        AttemptExecutionStatus::Cancelled => RunRuntimeStatus::Cancelled,
+                        _ => run_status,
+                    };
+                    self.record_run_status(&candidate.run_id, run_status, reason)
+                        .await;
+                }
+                AttemptOutcome::Finished(status)
+            }
+            // The lease was lost, possibly only just before the write; the attempt may be
+            // another worker's now and its outcome is theirs to record
+            Err(KernelError::LeaseConflict(_)) => AttemptOutcome::LeaseLost,
+            Err(e) => return Err(e),
+        };
+        Ok(ExecutedAttempt {
+            attempt_id: candidate.attempt_id,
+            run_id: candidate.run_id,
+            outcome,
+        })
+    }
+
+    /// The graph the attempt's run executes, or why there is none: the run is unknown or
+    /// names a graph this worker does not have.
+    async fn resolve_graph(
+        &self,
+        candidate: &AttemptDispatchRecord,
+    ) -> Result<Result<(Arc<CompiledGraph<S>>, S), String>, KernelError> {
+        let Some(run) = self.repo.get_run(&candidate.run_id).await? else {
+            return Ok(Err(format!("run {} not found", candidate.run_id)));
+        };
+        Ok(self.graphs.get(&run.workflow_name).ok_or_else(|| {
+            format!(
+                "run {} uses graph {:?}, which is not registered",
+                run.run_id, run.workflow_name
+            )
+        }))
+    }
+
+    /// Runs the graph until it stops while a [LeaseKeeper] heartbeats `lease`, then
+    /// advances `fence` to the lease's last version.
+    async fn drive(
+        &self,
+        run_id: &RunId,
+        lease: &LeaseRecord,
+        fence: &mut LeaseFence,
+        graph: Arc<CompiledGraph<S>>,
+        initial_state: S,
+    ) -> Result<RunEnd, KernelError> {
+        let kernel: Kernel<GraphStepState<S>> = Kernel {
+            events: (self.event_store)()?,
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(graph)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let handle = KernelRunner::new(kernel).spawn(run_id, GraphStepState::new(initial_state));
+
+        let keeper = LeaseKeeper::spawn(
+            Arc::clone(&self.repo),
+            lease,
+            self.config.heartbeat_interval,
+            CancellationToken::new(),
+        );
+        let lost = keeper.lost();
+        let cancel_requested = keeper.cancel_requested();
+        let wait = handle.wait();
+        tokio::pin!(wait);
+        let mut lease_lost = false;
+        let mut cancelled = false;
+        let result = loop {
+            tokio::select! {
+                result = &mut wait => break result,
+                _ = lost.cancelled(), if !lease_lost => {
+                    // Another worker may own the attempt by now: stop at the next step
+                    // boundary and leave the run resumable for it
+                    lease_lost = true;
+                    if let Err(e) = handle.pause() {
+                        log::warn!(
+                            "worker {}: could not pause run {}: {}",
+                            self.config.worker_id,
+                            run_id,
+                            e
+                        );
+                    }
+                }
+                _ = cancel_requested.cancelled(), if !cancelled && !lease_lost => {
+                    cancelled = true;
+                    if let Err(e) = handle.cancel(Some("attempt cancelled".to_string())) {
+                        log::warn!(
+                            "worker {}: could not cancel run {}: {}",
+                            self.config.worker_id,
+                            run_id,
+                            e
+                        );
+                    }
+                }
+            }
+        };
+        *fence = keeper.stop().await;
+        if lease_lost {
+            return Ok(RunEnd::LeaseLost);
+        }
+
+        Ok(match result {
+            Ok(RunStatus::Completed) => RunEnd::Stopped(
+                AttemptExecutionStatus::Completed,
+                RunRuntimeStatus::Completed,
+            ),
+            // A blocked or paused run waits for outside input; the attempt's own work is done
+            Ok(RunStatus::Blocked(_)) => RunEnd::Stopped(
+                AttemptExecutionStatus::Completed,
+                RunRuntimeStatus::BlockedInterrupt,
+            ),
+            Ok(RunStatus::Running) => {
+                RunEnd::Stopped(AttemptExecutionStatus::Completed, RunRuntimeStatus::Paused)
+            }
+            Ok(RunStatus::Cancelled) => RunEnd::Stopped(
+                AttemptExecutionStatus::Cancelled,
+                RunRuntimeStatus::Cancelled,
+            ),
+            // Whatever stopped the run, the attempt was cancelled and is not to be retried
+            Ok(RunStatus::Failed { .. }) | Err(_) if cancelled => RunEnd::Stopped(
+                AttemptExecutionStatus::Cancelled,
+                RunRuntimeStatus::Cancelled,
+            ),
+            Ok(RunStatus::Failed { recoverable }) => RunEnd::Failed(format!(
+                "run {} failed (recoverable: {})",
+                run_id, recoverable
+            )),
+            Err(e) => RunEnd::Failed(format!("run {} failed: {}", run_id, e)),
+        })
+    }
+
+    /// Moves the run record to `status`. The run record is bookkeeping, so a failed
+    /// write is logged rather than failing the attempt.
+    async fn record_run_status(
+        &self,
+        run_id: &RunId,
+        status: RunRuntimeStatus,
+        reason: Option<String>,
+    ) {
+        let result = self
+            .repo
+            .update_run_status(run_id, status.clone(), reason.as_deref())
+            .await;
+        if let Err(e) = result {
+            log::warn!(
+                "worker {}: could not mark run {} {}: {}",
+                self.config.worker_id,
+                run_id,
+                status.as_str(),
+                e
+            );
+        }
+    }
+
+    /// Time until the earliest delayed attempt falls due, capped at `poll_interval` so
+    /// newly enqueued work is still picked up.
+    async fn idle_delay(&self) -> Duration {
+        let now = Utc::now();
+        match self.repo.next_dispatch_at(now).await {
+            Ok(Some(next)) => (next - now)
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+                .min(self.config.poll_interval),
+            Ok(None) => self.config.poll_interval,
+            Err(e) => {
+                log::warn!(
+                    "worker {}: next dispatch time unavailable: {}",
+                    self.config.worker_id,
+                    e
+                );
+                self.config.poll_interval
+            }
+        }
+    }
+
+    fn lease_ttl(&self) -> Result<chrono::Duration, KernelError> {
+        chrono::Duration::from_std(self.config.lease_ttl)
+            .map_err(|e| KernelError::Validation(format!("lease_ttl out of range: {}", e)))
+    }
+}
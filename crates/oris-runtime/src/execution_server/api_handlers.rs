@@ -1,25 +1,28 @@
 //! Axum handlers for Phase 2 execution server.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
 use std::sync::{Arc, Mutex, RwLock as StdRwLock};
 use std::time::Instant;
 
 use axum::extract::{OriginalUri, Path, Query, State};
 use axum::http::{
     header::{AUTHORIZATION, CONTENT_TYPE},
-    HeaderMap, StatusCode,
+    HeaderMap, HeaderValue, StatusCode,
 };
 use axum::middleware::{from_fn, from_fn_with_state, Next};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use chrono::{Duration, Utc};
+use futures::{Stream, StreamExt};
 #[cfg(feature = "sqlite-persistence")]
 use oris_execution_runtime::models::{BountyRecord, BountyStatus, SessionMessageRecord};
 use oris_execution_runtime::models::{RecipeRecord, WorkerRecord};
 use oris_execution_runtime::{
     ExecutionCheckpointView, ExecutionGraphBridge, ExecutionGraphBridgeErrorKind,
-    KernelObservability,
+    ExecutionInvokeView, KernelObservability,
 };
 use serde::de::DeserializeOwned;
 use serde_json::Value;
@@ -67,21 +70,29 @@ use crate::execution_runtime::api_errors::ApiError;
 #[cfg(feature = "sqlite-persistence")]
 use crate::execution_runtime::api_idempotency::{IdempotencyRecord, SqliteIdempotencyStore};
 use crate::execution_runtime::api_models::{
-    ApiEnvelope, ApiMeta, AttemptRetryHistoryItem, AttemptRetryHistoryResponse, AuditLogItem,
-    AuditLogListResponse, CancelJobRequest, CancelJobResponse, CheckpointInspectResponse,
+    ApiEnvelope, ApiMeta, AttemptCancelResponse, AttemptRetryHistoryItem,
+    AttemptRetryHistoryResponse, AuditLogItem, AuditLogListResponse, CancelJobRequest,
+    CancelJobResponse, CheckpointInspectResponse, CreateRunRequest, CreateRunResponse,
     DeadLetterItem, DeadLetterListResponse, DeadLetterReplayResponse, InterruptDetailResponse,
     InterruptListItem, InterruptListResponse, JobDetailResponse, JobHistoryItem,
-    JobHistoryResponse, JobListItem, JobStateResponse, JobTimelineItem, JobTimelineResponse,
-    ListAuditLogsQuery, ListDeadLettersQuery, ListInterruptsQuery, ListJobsQuery, ListJobsResponse,
-    RejectInterruptRequest, ReplayJobRequest, ResumeInterruptRequest, ResumeJobRequest,
-    RetryPolicyRequest, RunJobRequest, RunJobResponse, TimelineExportResponse,
-    TimeoutPolicyRequest, TraceContextResponse, WorkerAckRequest, WorkerAckResponse,
-    WorkerExtendLeaseRequest, WorkerHeartbeatRequest, WorkerLeaseResponse, WorkerPollRequest,
-    WorkerPollResponse, WorkerReportStepRequest,
+    JobHistoryResponse, JobListItem, JobRunMode, JobStateResponse, JobTimelineItem,
+    JobTimelineResponse, ListAuditLogsQuery, ListDeadLettersQuery, ListInterruptsQuery,
+    ListJobsQuery, ListJobsResponse, PauseJobResponse, RejectInterruptRequest, ReplayJobRequest,
+    ResolveInterruptRequest, ResolveInterruptResponse, ResumeInterruptRequest, ResumeJobRequest,
+    ResumeRunRequest, RetryPolicyRequest, RunEventsQuery, RunJobRequest, RunJobResponse,
+    RunStatusResponse, TimelineExportResponse, TimeoutPolicyRequest, TraceContextResponse,
+    WorkerAckRequest, WorkerAckResponse, WorkerExtendLeaseRequest, WorkerHeartbeatRequest,
+    WorkerLeaseResponse, WorkerPollRequest, WorkerPollResponse, WorkerReportStepRequest,
 };
+#[cfg(feature = "sqlite-persistence")]
+use crate::execution_runtime::async_repository::AsyncRuntimeRepository;
 use crate::execution_runtime::lease::{LeaseConfig, LeaseManager, RepositoryLeaseManager};
-use crate::execution_runtime::models::AttemptExecutionStatus;
-use crate::execution_runtime::repository::RuntimeRepository;
+use crate::execution_runtime::models::{AttemptExecutionStatus, RuntimeStats};
+#[cfg(feature = "sqlite-persistence")]
+use crate::execution_runtime::models::{
+    AttemptCancellation, InterruptDecision, InterruptRecord, InterruptStatus, LeaseDirective,
+    RunRecord, RunRecordFilter, RunRuntimeStatus,
+};
 #[cfg(all(
     feature = "sqlite-persistence",
     any(feature = "agent-contract", feature = "agent-contract-experimental"),
@@ -97,6 +108,9 @@ use crate::execution_runtime::sqlite_runtime_repository::{
     RetryStrategy, SqliteRuntimeRepository, StepReportWriteResult, TimeoutPolicyConfig,
 };
 use crate::graph::{CompiledGraph, MessagesState};
+use crate::kernel::{summarize_runs, FleetSummary, RunFilter};
+#[cfg(feature = "sqlite-persistence")]
+use crate::kernel::{KernelError, PageRequest};
 use tracing::{info_span, Instrument};
 
 use super::graph_bridge::CompiledGraphExecutionBridge;
@@ -3000,11 +3014,20 @@ impl ExecutionApiAuthConfig {
     }
 }
 
+/// Graph name `POST /v1/runs` accepts unless [`ExecutionApiState::with_graph_name`] sets one
+pub const DEFAULT_GRAPH_NAME: &str = "default";
+
 #[derive(Clone)]
 pub struct ExecutionApiState {
     pub compiled: Arc<CompiledGraph<MessagesState>>,
+    /// Name `POST /v1/runs` accepts for `compiled`; see [`Self::with_graph_name`]
+    pub graph_name: String,
     pub graph_bridge: Arc<dyn ExecutionGraphBridge>,
     pub cancelled_threads: Arc<RwLock<HashSet<String>>>,
+    /// Threads last run or resumed as a dry run (`"mode": "dry_run"`)
+    pub dry_run_threads: Arc<RwLock<HashSet<String>>>,
+    /// Threads whose last run stopped at a pause; resuming them continues the run
+    pub paused_threads: Arc<RwLock<HashSet<String>>>,
     #[cfg(any(
         feature = "evolution-network",
         feature = "evolution-network-experimental"
@@ -3187,10 +3210,23 @@ pub struct ExecutionApiState {
     pub runtime_repo: Option<SqliteRuntimeRepository>,
     #[cfg(feature = "kernel-postgres")]
     pub pg_idempotency_store: Option<crate::execution_runtime::PostgresIdempotencyStore>,
+    /// Kernel event log behind `/v1/runs/summary` and `/v1/runs/:run_id/events`; defaults
+    /// to the event store the graph was compiled with (`CompiledGraph::with_event_store`).
+    /// Without one both endpoints answer 501. See [`Self::with_kernel_event_store`]
+    pub kernel_events: Option<Arc<dyn crate::kernel::EventStore>>,
+    /// Pushes new kernel events to `/v1/runs/:run_id/events`; without it the stream polls
+    /// [`Self::kernel_events`]. See [`Self::with_watchable_kernel_event_store`]
+    pub kernel_event_watch: Option<Arc<dyn crate::kernel::WatchableEventStore>>,
     pub runtime_metrics: RuntimeMetrics,
+    /// Renders `metrics`-facade metrics for `/metrics`; see [`Self::with_metrics_renderer`]
+    #[cfg(feature = "metrics")]
+    pub metrics_renderer: Option<Arc<dyn Fn() -> String + Send + Sync>>,
     pub worker_poll_limit: usize,
     pub max_active_leases_per_worker: usize,
     pub max_active_leases_per_tenant: usize,
+    /// Reject runtime requests without an `x-oris-tenant-id` header; see
+    /// [`Self::with_multi_tenancy`]
+    pub multi_tenancy: bool,
 }
 
 impl ExecutionApiState {
@@ -3216,10 +3252,15 @@ impl ExecutionApiState {
         let evolution_store: Arc<dyn EvoEvolutionStore> =
             Arc::new(JsonlEvolutionStore::new(default_store_root()));
 
+        let kernel_events = compiled.event_store().cloned();
+
         Self {
             graph_bridge: Arc::new(CompiledGraphExecutionBridge::new(compiled.clone())),
             compiled,
+            graph_name: DEFAULT_GRAPH_NAME.to_string(),
             cancelled_threads: Arc::new(RwLock::new(HashSet::new())),
+            dry_run_threads: Arc::new(RwLock::new(HashSet::new())),
+            paused_threads: Arc::new(RwLock::new(HashSet::new())),
             #[cfg(any(
                 feature = "evolution-network",
                 feature = "evolution-network-experimental"
@@ -3401,10 +3442,15 @@ impl ExecutionApiState {
             runtime_repo: None,
             #[cfg(feature = "kernel-postgres")]
             pg_idempotency_store: None,
+            kernel_events,
+            kernel_event_watch: None,
             runtime_metrics: RuntimeMetrics::default(),
+            #[cfg(feature = "metrics")]
+            metrics_renderer: None,
             worker_poll_limit: 1,
             max_active_leases_per_worker: 8,
             max_active_leases_per_tenant: 8,
+            multi_tenancy: false,
         }
     }
 
@@ -3428,6 +3474,60 @@ impl ExecutionApiState {
         self
     }
 
+    /// Serve `compiled` as the graph named `name` at `POST /v1/runs` (default `"default"`)
+    pub fn with_graph_name(mut self, name: impl Into<String>) -> Self {
+        self.graph_name = name.into();
+        self
+    }
+
+    /// Simulate the actions of dry-run jobs with `policy`'s
+    /// [`simulate`](crate::kernel::Policy::simulate), e.g. a
+    /// [`StubbedPolicy`](crate::kernel::StubbedPolicy); replaces the graph bridge with one
+    /// for `compiled`
+    pub fn with_dry_run_policy(mut self, policy: Arc<dyn crate::kernel::Policy>) -> Self {
+        self.graph_bridge = Arc::new(
+            CompiledGraphExecutionBridge::new(self.compiled.clone()).with_dry_run_policy(policy),
+        );
+        self
+    }
+
+    /// Require every runtime request to name its tenant in the `x-oris-tenant-id` header;
+    /// runs, attempts, leases and interrupts of other tenants are then invisible to it
+    pub fn with_multi_tenancy(mut self, enabled: bool) -> Self {
+        self.multi_tenancy = enabled;
+        self
+    }
+
+    /// Summarize the runs of `events` at `/v1/runs/summary`
+    pub fn with_kernel_event_store(mut self, events: Arc<dyn crate::kernel::EventStore>) -> Self {
+        self.kernel_events = Some(events);
+        self
+    }
+
+    /// Like [`Self::with_kernel_event_store`], and `/v1/runs/:run_id/events` subscribes to
+    /// `events` instead of polling it
+    pub fn with_watchable_kernel_event_store<E>(mut self, events: Arc<E>) -> Self
+    where
+        E: crate::kernel::WatchableEventStore + 'static,
+    {
+        self.kernel_events = Some(events.clone());
+        self.kernel_event_watch = Some(events);
+        self
+    }
+
+    /// Append the Prometheus text returned by `render` to `/metrics`
+    ///
+    /// Use it to expose the metrics listed in [`crate::metrics`], e.g. with the `render`
+    /// method of the handle returned when installing a Prometheus recorder.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_renderer(
+        mut self,
+        render: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.metrics_renderer = Some(Arc::new(render));
+        self
+    }
+
     #[cfg(any(
         feature = "evolution-network",
         feature = "evolution-network-experimental"
@@ -3583,11 +3683,18 @@ pub fn build_router(state: ExecutionApiState) -> Router {
                 "/v1/attempts/:attempt_id/retries",
                 get(list_attempt_retries),
             )
+            .route("/v1/attempts/:attempt_id/cancel", post(cancel_attempt))
             .route("/v1/dlq", get(list_dead_letters))
             .route("/v1/dlq/:attempt_id", get(get_dead_letter))
             .route("/v1/dlq/:attempt_id/replay", post(replay_dead_letter))
             .route("/v1/jobs", get(list_jobs).post(run_job))
+            .route("/v1/runs/summary", get(runs_summary))
+            .route("/v1/runs/:run_id/events", get(stream_run_events))
+            .route("/v1/runs/:run_id/resume", post(resume_run))
+            .route("/v1/runs/:run_id/cancel", post(cancel_run))
+            .route("/v1/runtime/stats", get(runtime_stats))
             .route("/v1/jobs/run", post(run_job))
+            .route("/v1/runs", post(create_run))
             .route("/v1/jobs/:thread_id", get(inspect_job))
             .route("/v1/jobs/:thread_id/detail", get(job_detail))
             .route("/v1/jobs/:thread_id/timeline/export", get(export_timeline))
@@ -3599,6 +3706,7 @@ pub fn build_router(state: ExecutionApiState) -> Router {
             )
             .route("/v1/jobs/:thread_id/resume", post(resume_job))
             .route("/v1/jobs/:thread_id/replay", post(replay_job))
+            .route("/v1/jobs/:thread_id/pause", post(pause_job))
             .route("/v1/jobs/:thread_id/cancel", post(cancel_job))
             .route("/v1/workers/poll", post(worker_poll))
             .route("/v1/workers/:worker_id/heartbeat", post(worker_heartbeat))
@@ -3620,6 +3728,10 @@ pub fn build_router(state: ExecutionApiState) -> Router {
             .route(
                 "/v1/interrupts/:interrupt_id/reject",
                 post(reject_interrupt),
+            )
+            .route(
+                "/v1/interrupts/:interrupt_id/resolve",
+                post(resolve_interrupt),
             ),
     )))
     .layer(from_fn_with_state(state.clone(), auth_middleware))
@@ -4050,6 +4162,18 @@ async fn metrics_endpoint(
         }
         body.push_str(&evolution_metrics);
     }
+    #[cfg(feature = "metrics")]
+    let body = match &state.metrics_renderer {
+        Some(render) => {
+            let mut body = body;
+            if !body.ends_with('\n') {
+                body.push('\n');
+            }
+            body.push_str(&render());
+            body
+        }
+        None => body,
+    };
 
     Ok((
         [(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
@@ -4209,6 +4333,7 @@ fn payload_hash(
     timeout_policy: Option<&TimeoutPolicyRequest>,
     priority: i32,
     tenant_id: Option<&str>,
+    mode: JobRunMode,
 ) -> String {
     let mut hasher = Sha256::new();
     hasher.update(thread_id.as_bytes());
@@ -4224,6 +4349,10 @@ fn payload_hash(
     hasher.update(priority.to_string().as_bytes());
     hasher.update(b"|");
     hasher.update(tenant_id.unwrap_or("").as_bytes());
+    // Normal runs keep the hashes recorded before modes existed
+    if mode == JobRunMode::DryRun {
+        hasher.update(b"|dry_run");
+    }
     format!("{:x}", hasher.finalize())
 }
 
@@ -4951,6 +5080,11 @@ fn parse_audit_target(method: &axum::http::Method, path: &str) -> Option<AuditTa
             resource_type: "thread",
             resource_id: Some((*thread_id).to_string()),
         }),
+        ("jobs", ["v1", "jobs", thread_id, "pause"]) => Some(AuditTarget {
+            action: "job.pause",
+            resource_type: "thread",
+            resource_id: Some((*thread_id).to_string()),
+        }),
         ("jobs", ["v1", "jobs", thread_id, "cancel"]) => Some(AuditTarget {
             action: "job.cancel",
             resource_type: "thread",
@@ -4966,6 +5100,16 @@ fn parse_audit_target(method: &axum::http::Method, path: &str) -> Option<AuditTa
             resource_type: "interrupt",
             resource_id: Some((*interrupt_id).to_string()),
         }),
+        ("interrupts", ["v1", "interrupts", interrupt_id, "resolve"]) => Some(AuditTarget {
+            action: "interrupt.resolve",
+            resource_type: "interrupt",
+            resource_id: Some((*interrupt_id).to_string()),
+        }),
+        ("attempts", ["v1", "attempts", attempt_id, "cancel"]) => Some(AuditTarget {
+            action: "attempt.cancel",
+            resource_type: "attempt",
+            resource_id: Some((*attempt_id).to_string()),
+        }),
         ("dlq", ["v1", "dlq", attempt_id, "replay"]) => Some(AuditTarget {
             action: "dlq.replay",
             resource_type: "attempt",
@@ -5106,6 +5250,8 @@ fn role_can_access(role: &ApiRole, method: &axum::http::Method, path: &str) -> b
     let is_audit = path.starts_with("/v1/audit");
     let is_attempts = path.starts_with("/v1/attempts");
     let is_dlq = path.starts_with("/v1/dlq");
+    let is_runs = path.starts_with("/v1/runs");
+    let is_runtime = path.starts_with("/v1/runtime");
     let is_a2a_compat = is_a2a_compat_path(path);
 
     // EvoMap semantic endpoint path checks
@@ -5130,7 +5276,9 @@ fn role_can_access(role: &ApiRole, method: &axum::http::Method, path: &str) -> b
         ApiRole::Operator => {
             is_jobs_or_interrupts
                 || (is_audit && *method == axum::http::Method::GET)
-                || (is_attempts && *method == axum::http::Method::GET)
+                || is_attempts
+                || (is_runs && *method == axum::http::Method::GET)
+                || (is_runtime && *method == axum::http::Method::GET)
                 || is_dlq
                 || is_a2a_compat
                 || is_evomap_semantic
@@ -5316,6 +5464,32 @@ fn resolve_compat_protocol_version(
     Ok(protocol_version)
 }
 
+/// Job status once a run or resume returned `result`, tracking paused threads. A run
+/// stopped because its thread was cancelled reports `cancelled`.
+async fn invoke_status(
+    state: &ExecutionApiState,
+    thread_id: &str,
+    result: &ExecutionInvokeView,
+) -> &'static str {
+    if result.paused_at.is_none() {
+        state.paused_threads.write().await.remove(thread_id);
+        return if result.interrupts.is_empty() {
+            "completed"
+        } else {
+            "interrupted"
+        };
+    }
+    if state.cancelled_threads.read().await.contains(thread_id) {
+        return "cancelled";
+    }
+    state
+        .paused_threads
+        .write()
+        .await
+        .insert(thread_id.to_string());
+    "paused"
+}
+
 async fn ensure_not_cancelled(state: &ExecutionApiState, thread_id: &str) -> Result<(), ApiError> {
     if state.cancelled_threads.read().await.contains(thread_id) {
         return Err(ApiError::conflict(format!(
@@ -9067,16 +9241,99 @@ pub async fn evolution_a2a_session_snapshot(
     }))
 }
 
+/// Tenant named by the `x-oris-tenant-id` header, required when multi-tenancy is enabled.
+fn request_tenant(
+    state: &ExecutionApiState,
+    headers: &HeaderMap,
+    rid: &str,
+) -> Result<Option<String>, ApiError> {
+    let header = headers
+        .get("x-oris-tenant-id")
+        .map(|value| {
+            value.to_str().map_err(|_| {
+                ApiError::bad_request("x-oris-tenant-id must be valid ASCII")
+                    .with_request_id(rid.to_string())
+            })
+        })
+        .transpose()?;
+    let tenant_id = parse_tenant_id(header, rid)?;
+    if tenant_id.is_none() && state.multi_tenancy {
+        return Err(ApiError::bad_request("x-oris-tenant-id header is required")
+            .with_request_id(rid.to_string()));
+    }
+    Ok(tenant_id)
+}
+
+/// The runtime repository scoped to `tenant_id`, or unscoped when it is `None`.
+#[cfg(feature = "sqlite-persistence")]
+fn scoped_runtime_repo(
+    state: &ExecutionApiState,
+    tenant_id: Option<&str>,
+) -> Option<SqliteRuntimeRepository> {
+    let repo = state.runtime_repo.clone()?;
+    Some(match tenant_id {
+        Some(tenant_id) => repo.with_tenant(tenant_id),
+        None => repo,
+    })
+}
+
 #[cfg(feature = "sqlite-persistence")]
-fn runtime_repo<'a>(
-    state: &'a ExecutionApiState,
+fn runtime_repo(
+    state: &ExecutionApiState,
+    headers: &HeaderMap,
     rid: &str,
-) -> Result<&'a SqliteRuntimeRepository, ApiError> {
-    state.runtime_repo.as_ref().ok_or_else(|| {
+) -> Result<SqliteRuntimeRepository, ApiError> {
+    let tenant_id = request_tenant(state, headers, rid)?;
+    scoped_runtime_repo(state, tenant_id.as_deref()).ok_or_else(|| {
         ApiError::internal("runtime repository is not configured").with_request_id(rid.to_string())
     })
 }
 
+/// Tenant of a request against job `thread_id`, rejecting jobs owned by another tenant.
+async fn job_request_tenant(
+    state: &ExecutionApiState,
+    headers: &HeaderMap,
+    thread_id: &str,
+    rid: &str,
+) -> Result<Option<String>, ApiError> {
+    let tenant_id = request_tenant(state, headers, rid)?;
+    #[cfg(feature = "sqlite-persistence")]
+    ensure_job_in_tenant(state, tenant_id.as_deref(), thread_id, false, rid).await?;
+    #[cfg(not(feature = "sqlite-persistence"))]
+    let _ = thread_id;
+    Ok(tenant_id)
+}
+
+/// Rejects a job whose run is not owned by `tenant_id`, as if it did not exist.
+///
+/// Like the tenant-scoped repositories, a tenant sees neither jobs of other tenants nor
+/// jobs whose run has no tenant. With `allow_new`, a job that has no run yet passes, so
+/// the tenant can start it.
+#[cfg(feature = "sqlite-persistence")]
+async fn ensure_job_in_tenant(
+    state: &ExecutionApiState,
+    tenant_id: Option<&str>,
+    thread_id: &str,
+    allow_new: bool,
+    rid: &str,
+) -> Result<(), ApiError> {
+    let (Some(repo), Some(tenant_id)) = (state.runtime_repo.as_ref(), tenant_id) else {
+        return Ok(());
+    };
+    let run = AsyncRuntimeRepository::get_run(repo, &thread_id.to_string())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.to_string()))?;
+    let visible = match run {
+        Some(run) => run.tenant_id.as_deref() == Some(tenant_id),
+        None => allow_new,
+    };
+    if visible {
+        Ok(())
+    } else {
+        Err(ApiError::not_found("job not found").with_request_id(rid.to_string()))
+    }
+}
+
 #[cfg(feature = "sqlite-persistence")]
 fn parse_retry_policy(
     request: Option<&RetryPolicyRequest>,
@@ -9209,13 +9466,29 @@ pub async fn run_job(
 
     let input = req.input.unwrap_or_else(|| "API run".to_string());
     let priority = parse_priority(req.priority, &rid)?;
-    let tenant_id = parse_tenant_id(req.tenant_id.as_deref(), &rid)?;
+    let header_tenant_id = request_tenant(&state, &headers, &rid)?;
+    let tenant_id = match (
+        header_tenant_id,
+        parse_tenant_id(req.tenant_id.as_deref(), &rid)?,
+    ) {
+        (Some(header), Some(body)) if header != body => {
+            return Err(ApiError::bad_request(
+                "tenant_id does not match the x-oris-tenant-id header",
+            )
+            .with_request_id(rid));
+        }
+        (header, body) => header.or(body),
+    };
+    #[cfg(feature = "sqlite-persistence")]
+    ensure_job_in_tenant(&state, tenant_id.as_deref(), &req.thread_id, true, &rid).await?;
+    let mode = req.mode.unwrap_or_default();
     let request_payload_hash = payload_hash(
         &req.thread_id,
         &input,
         req.timeout_policy.as_ref(),
         priority,
         tenant_id.as_deref(),
+        mode,
     );
     log::info!(
         "execution_run request_id={} thread_id={} checkpoint_id=none",
@@ -9274,6 +9547,7 @@ pub async fn run_job(
         }
         if let Some(existing) = store
             .get(&key)
+            .await
             .map_err(|e| ApiError::internal(e).with_request_id(rid.clone()))?
         {
             if existing.operation == "run"
@@ -9321,12 +9595,13 @@ pub async fn run_job(
         Some(&run_trace),
     );
 
-    let result = match state
-        .graph_bridge
-        .run(&req.thread_id, &input)
-        .instrument(run_span)
-        .await
-    {
+    let run = async {
+        match mode {
+            JobRunMode::Normal => state.graph_bridge.run(&req.thread_id, &input).await,
+            JobRunMode::DryRun => state.graph_bridge.run_dry(&req.thread_id, &input).await,
+        }
+    };
+    let result = match run.instrument(run_span).await {
         Ok(result) => result,
         Err(e) => {
             let error_message = e.to_string();
@@ -9342,22 +9617,34 @@ pub async fn run_job(
         }
     };
 
-    let interrupts = result.interrupts;
-    let status = if interrupts.is_empty() {
-        "completed".to_string()
-    } else {
-        "interrupted".to_string()
-    };
-    if interrupts.is_empty() {
-        record_task_succeeded(&state, &req.thread_id, "task completed successfully").await;
-    } else {
-        record_task_running(
-            &state,
-            &req.thread_id,
-            "task interrupted and waiting for resume",
-        )
-        .await;
+    if mode == JobRunMode::DryRun {
+        state
+            .dry_run_threads
+            .write()
+            .await
+            .insert(req.thread_id.clone());
+    }
+
+    let status = invoke_status(&state, &req.thread_id, &result).await;
+    match status {
+        "completed" => {
+            record_task_succeeded(&state, &req.thread_id, "task completed successfully").await
+        }
+        "interrupted" => {
+            record_task_running(
+                &state,
+                &req.thread_id,
+                "task interrupted and waiting for resume",
+            )
+            .await
+        }
+        "paused" => {
+            record_task_running(&state, &req.thread_id, "task paused and waiting for resume").await
+        }
+        _ => {}
     }
+    let status = status.to_string();
+    let interrupts = result.interrupts;
 
     let response = RunJobResponse {
         thread_id: req.thread_id.clone(),
@@ -9366,6 +9653,7 @@ pub async fn run_job(
         idempotency_key: req.idempotency_key.clone(),
         idempotent_replay: false,
         trace: Some(run_trace.to_response()),
+        mode,
     };
 
     #[cfg(feature = "sqlite-persistence")]
@@ -9384,11 +9672,10 @@ pub async fn run_job(
     }
 
     #[cfg(feature = "sqlite-persistence")]
-    if let Some(repo) = state.runtime_repo.as_ref() {
+    if let Some(repo) = scoped_runtime_repo(&state, tenant_id.as_deref()).as_ref() {
         let attempt_id = format!("attempt-{}-{}", req.thread_id, uuid::Uuid::new_v4());
-        let _ = repo.upsert_job(&req.thread_id, &status);
-        let _ = repo.enqueue_attempt(&attempt_id, &req.thread_id);
-        let _ = repo.set_attempt_priority(&attempt_id, priority);
+        let _ = record_job_run(repo, &req.thread_id, &status).await;
+        let _ = repo.enqueue_attempt_with_priority(&attempt_id, &req.thread_id, priority);
         let _ = repo.set_attempt_tenant_id(&attempt_id, tenant_id.as_deref());
         let _ = repo.set_attempt_trace_context(
             &attempt_id,
@@ -9400,20 +9687,7 @@ pub async fn run_job(
         if let Some(policy) = timeout_policy.as_ref() {
             let _ = repo.set_attempt_timeout_policy(&attempt_id, policy);
         }
-        if !interrupts.is_empty() {
-            let interrupt_attempt_id = format!("attempt-{}-main", req.thread_id);
-            for (i, iv) in interrupts.iter().enumerate() {
-                let interrupt_id = format!("int-{}-{}", req.thread_id, i);
-                let value_json = serde_json::to_string(iv).unwrap_or_default();
-                let _ = repo.insert_interrupt(
-                    &interrupt_id,
-                    &req.thread_id,
-                    &req.thread_id,
-                    &interrupt_attempt_id,
-                    &value_json,
-                );
-            }
-        }
+        record_pending_interrupts(repo, &req.thread_id, &interrupts, &rid).await;
     }
 
     #[cfg(feature = "sqlite-persistence")]
@@ -9451,6 +9725,7 @@ pub async fn run_job(
         };
         store
             .put(&key, &record)
+            .await
             .map_err(|e| ApiError::internal(e).with_request_id(rid.clone()))?;
     }
 
@@ -9461,68 +9736,494 @@ pub async fn run_job(
     }))
 }
 
-pub async fn inspect_job(
-    State(state): State<ExecutionApiState>,
-    Path(thread_id): Path<String>,
-    headers: HeaderMap,
-) -> Result<Json<ApiEnvelope<JobStateResponse>>, ApiError> {
-    let rid = request_id(&headers);
-    validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
-    log::info!(
-        "execution_inspect request_id={} thread_id={} checkpoint_id=none",
-        rid,
-        thread_id
-    );
-    let snapshot = state
-        .graph_bridge
-        .snapshot(&thread_id, None)
-        .await
-        .map_err(|e| {
-            if e.kind == ExecutionGraphBridgeErrorKind::NotFound {
-                ApiError::not_found(e.message).with_request_id(rid.clone())
-            } else {
-                ApiError::internal(e.message).with_request_id(rid.clone())
-            }
-        })?;
+fn create_run_payload_hash(req: &CreateRunRequest, tenant_id: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.graph.as_bytes());
+    hasher.update(b"|");
+    hasher.update(req.thread_id.as_deref().unwrap_or("").as_bytes());
+    hasher.update(b"|");
+    if let Ok(bytes) = serde_json::to_vec(&req.input) {
+        hasher.update(bytes);
+    }
+    hasher.update(b"|");
+    hasher.update(req.mode.as_deref().unwrap_or("").as_bytes());
+    hasher.update(b"|");
+    hasher.update(tenant_id.unwrap_or("").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
 
-    let checkpoint_id = snapshot.checkpoint_id.clone();
-    let created_at = snapshot.created_at.to_rfc3339();
-    let values = snapshot.values;
+fn validate_create_run(
+    state: &ExecutionApiState,
+    req: &CreateRunRequest,
+) -> Result<(String, JobRunMode), ApiError> {
+    let mut fields = Vec::new();
+    if req.graph != state.graph_name {
+        fields.push(serde_json::json!({
+            "field": "graph",
+            "message": format!("unknown graph '{}'", req.graph),
+        }));
+    }
+    if matches!(req.thread_id.as_deref(), Some(thread_id) if thread_id.trim().is_empty()) {
+        fields.push(serde_json::json!({
+            "field": "thread_id",
+            "message": "thread_id must not be empty",
+        }));
+    }
+    let input = match &req.input {
+        Value::String(input) => Some(input.clone()),
+        _ => {
+            fields.push(serde_json::json!({
+                "field": "input",
+                "message": "input must be a string",
+            }));
+            None
+        }
+    };
+    let mode = match req.mode.as_deref() {
+        None | Some("normal") => Some(JobRunMode::Normal),
+        Some("dry_run") => Some(JobRunMode::DryRun),
+        Some(other) => {
+            fields.push(serde_json::json!({
+                "field": "mode",
+                "message": format!("unknown mode '{}'; expected normal or dry_run", other),
+            }));
+            None
+        }
+    };
+    match (input, mode) {
+        (Some(input), Some(mode)) if fields.is_empty() => Ok((input, mode)),
+        _ => Err(ApiError::unprocessable("invalid run request")
+            .with_details(serde_json::json!({ "fields": fields }))),
+    }
+}
 
-    Ok(Json(ApiEnvelope {
-        meta: ApiMeta::ok(),
-        request_id: rid,
-        data: JobStateResponse {
-            thread_id,
-            checkpoint_id,
-            created_at,
-            values,
-        },
-    }))
+/// The operation, payload hash and response stored under an idempotency key, if any
+async fn stored_idempotency_record(
+    state: &ExecutionApiState,
+    key: Option<&str>,
+    rid: &str,
+) -> Result<Option<(String, String, String)>, ApiError> {
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    #[cfg(feature = "sqlite-persistence")]
+    if let Some(store) = state.idempotency_store.as_ref() {
+        if let Some(record) = store
+            .get(key)
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?
+        {
+            return Ok(Some((
+                record.operation,
+                record.payload_hash,
+                record.response_json,
+            )));
+        }
+    }
+    #[cfg(feature = "kernel-postgres")]
+    if let Some(store) = state.pg_idempotency_store.as_ref() {
+        if let Some(record) = store
+            .get(key)
+            .await
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?
+        {
+            return Ok(Some((
+                record.operation,
+                record.payload_hash,
+                record.response_json,
+            )));
+        }
+    }
+    #[cfg(not(any(feature = "sqlite-persistence", feature = "kernel-postgres")))]
+    let _ = (state, key, rid);
+    Ok(None)
 }
 
-pub async fn job_history(
-    State(state): State<ExecutionApiState>,
-    Path(thread_id): Path<String>,
-    headers: HeaderMap,
-) -> Result<Json<ApiEnvelope<JobHistoryResponse>>, ApiError> {
-    let rid = request_id(&headers);
-    validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
-    log::info!(
-        "execution_history request_id={} thread_id={} checkpoint_id=none",
+/// Stores the response of `operation` under an idempotency key in the configured stores
+async fn put_idempotency_record(
+    state: &ExecutionApiState,
+    key: &str,
+    operation: &str,
+    thread_id: &str,
+    payload_hash: &str,
+    response_json: &str,
+    rid: &str,
+) -> Result<(), ApiError> {
+    #[cfg(feature = "sqlite-persistence")]
+    if let Some(store) = state.idempotency_store.as_ref() {
+        let record = IdempotencyRecord {
+            operation: operation.to_string(),
+            thread_id: thread_id.to_string(),
+            payload_hash: payload_hash.to_string(),
+            response_json: response_json.to_string(),
+        };
+        store
+            .put(key, &record)
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?;
+    }
+    #[cfg(feature = "kernel-postgres")]
+    if let Some(store) = state.pg_idempotency_store.as_ref() {
+        let record = crate::execution_runtime::PostgresIdempotencyRecord {
+            operation: operation.to_string(),
+            thread_id: thread_id.to_string(),
+            payload_hash: payload_hash.to_string(),
+            response_json: response_json.to_string(),
+        };
+        store
+            .put(key, &record)
+            .await
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?;
+    }
+    #[cfg(not(any(feature = "sqlite-persistence", feature = "kernel-postgres")))]
+    let _ = (
+        state,
+        key,
+        operation,
+        thread_id,
+        payload_hash,
+        response_json,
         rid,
-        thread_id
     );
-    let history = state.graph_bridge.history(&thread_id).await.map_err(|e| {
-        ApiError::internal(format!("history failed: {}", e.message)).with_request_id(rid.clone())
-    })?;
+    Ok(())
+}
 
-    let items = history
-        .iter()
-        .map(|s| JobHistoryItem {
-            checkpoint_id: s.checkpoint_id.clone(),
-            created_at: s.created_at.to_rfc3339(),
-        })
+/// Reserves an idempotency key for `operation` with its provisional response, unless the
+/// key is taken; then returns the operation, payload hash and response stored under it.
+async fn reserve_idempotency_record(
+    state: &ExecutionApiState,
+    key: &str,
+    operation: &str,
+    thread_id: &str,
+    payload_hash: &str,
+    response_json: &str,
+    rid: &str,
+) -> Result<Option<(String, String, String)>, ApiError> {
+    #[cfg(feature = "sqlite-persistence")]
+    if let Some(store) = state.idempotency_store.as_ref() {
+        let record = IdempotencyRecord {
+            operation: operation.to_string(),
+            thread_id: thread_id.to_string(),
+            payload_hash: payload_hash.to_string(),
+            response_json: response_json.to_string(),
+        };
+        if let Some(existing) = store
+            .put_if_absent(key, &record)
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?
+        {
+            return Ok(Some((
+                existing.operation,
+                existing.payload_hash,
+                existing.response_json,
+            )));
+        }
+    }
+    #[cfg(feature = "kernel-postgres")]
+    if let Some(store) = state.pg_idempotency_store.as_ref() {
+        let record = crate::execution_runtime::PostgresIdempotencyRecord {
+            operation: operation.to_string(),
+            thread_id: thread_id.to_string(),
+            payload_hash: payload_hash.to_string(),
+            response_json: response_json.to_string(),
+        };
+        if let Some(existing) = store
+            .put_if_absent(key, &record)
+            .await
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?
+        {
+            return Ok(Some((
+                existing.operation,
+                existing.payload_hash,
+                existing.response_json,
+            )));
+        }
+    }
+    #[cfg(not(any(feature = "sqlite-persistence", feature = "kernel-postgres")))]
+    let _ = (
+        state,
+        operation,
+        key,
+        thread_id,
+        payload_hash,
+        response_json,
+        rid,
+    );
+    Ok(None)
+}
+
+/// Drops an idempotency key reserved by a request that failed, so a retry runs it again
+async fn release_idempotency_record(
+    state: &ExecutionApiState,
+    key: &str,
+    rid: &str,
+) -> Result<(), ApiError> {
+    #[cfg(feature = "sqlite-persistence")]
+    if let Some(store) = state.idempotency_store.as_ref() {
+        store
+            .remove(key)
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?;
+    }
+    #[cfg(feature = "kernel-postgres")]
+    if let Some(store) = state.pg_idempotency_store.as_ref() {
+        store
+            .remove(key)
+            .await
+            .map_err(|e| ApiError::internal(e).with_request_id(rid.to_string()))?;
+    }
+    #[cfg(not(any(feature = "sqlite-persistence", feature = "kernel-postgres")))]
+    let _ = (state, key, rid);
+    Ok(())
+}
+
+/// Idempotency keys of the run APIs are scoped to the caller's tenant. Tenant ids
+/// cannot contain `/`, so the prefix never collides with another tenant's keys.
+fn tenant_idempotency_key(tenant_id: Option<&str>, key: &str) -> String {
+    format!("{}/{}", tenant_id.unwrap_or(""), key)
+}
+
+/// JSON body extractor whose rejections use the API error envelope: a body that does
+/// not deserialize into `T` is a 422 naming the offending field, anything else a 400.
+pub struct ApiJson<T>(pub T);
+
+#[axum::async_trait]
+impl<S, T> axum::extract::FromRequest<S> for ApiJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {
+        let rid = request_id(req.headers());
+        let Json(value) = Json::<Value>::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()).with_request_id(rid.clone()))?;
+        serde_path_to_error::deserialize(value)
+            .map(ApiJson)
+            .map_err(|e| {
+                let message = e.inner().to_string();
+                let field = match e.path().to_string() {
+                    path if path != "." => path,
+                    _ => message
+                        .strip_prefix("missing field `")
+                        .and_then(|rest| rest.split('`').next())
+                        .unwrap_or("body")
+                        .to_string(),
+                };
+                ApiError::unprocessable("invalid run request")
+                    .with_request_id(rid)
+                    .with_details(serde_json::json!({
+                        "fields": [{ "field": field, "message": message }]
+                    }))
+            })
+    }
+}
+
+/// `POST /v1/runs`: start a run of a named graph
+///
+/// A retry carrying an `idempotency_key` seen before with the same payload gets the original
+/// run's response back with a 200 instead of starting another run; a new run answers 201.
+/// The key is reserved before the run starts, so a retry racing the first request gets the
+/// run's provisional `running` response rather than starting a second run. Keys are scoped
+/// to the caller's tenant.
+pub async fn create_run(
+    State(state): State<ExecutionApiState>,
+    mut headers: HeaderMap,
+    ApiJson(req): ApiJson<CreateRunRequest>,
+) -> Result<(StatusCode, Json<ApiEnvelope<CreateRunResponse>>), ApiError> {
+    let rid = request_id(&headers);
+    let (input, mode) =
+        validate_create_run(&state, &req).map_err(|e| e.with_request_id(rid.clone()))?;
+    let tenant_id = request_tenant(&state, &headers, &rid)?;
+    let request_payload_hash = create_run_payload_hash(&req, tenant_id.as_deref());
+    if matches!(req.idempotency_key.as_deref(), Some(key) if key.trim().is_empty()) {
+        return Err(ApiError::bad_request("idempotency_key must not be empty").with_request_id(rid));
+    }
+    let idempotency_key = req
+        .idempotency_key
+        .as_deref()
+        .map(|key| tenant_idempotency_key(tenant_id.as_deref(), key));
+
+    let thread_id = req
+        .thread_id
+        .clone()
+        .unwrap_or_else(|| format!("run-{}", uuid::Uuid::new_v4()));
+    let mut response = CreateRunResponse {
+        run_id: thread_id.clone(),
+        thread_id: thread_id.clone(),
+        checkpoint_id: None,
+        status: "running".to_string(),
+        interrupts: Vec::new(),
+        mode,
+        idempotency_key: req.idempotency_key.clone(),
+        idempotent_replay: false,
+    };
+    let encode_response = |response: &CreateRunResponse| {
+        serde_json::to_string(response).map_err(|e| {
+            ApiError::internal(format!("encode idempotent response failed: {}", e))
+                .with_request_id(rid.clone())
+        })
+    };
+
+    if let Some(key) = idempotency_key.as_deref() {
+        if let Some((operation, stored_hash, response_json)) = reserve_idempotency_record(
+            &state,
+            key,
+            "create_run",
+            &thread_id,
+            &request_payload_hash,
+            &encode_response(&response)?,
+            &rid,
+        )
+        .await?
+        {
+            if operation == "create_run" && stored_hash == request_payload_hash {
+                let mut response: CreateRunResponse = serde_json::from_str(&response_json)
+                    .map_err(|e| {
+                        ApiError::internal(format!("decode idempotent response failed: {}", e))
+                            .with_request_id(rid.clone())
+                    })?;
+                response.idempotent_replay = true;
+                return Ok((
+                    StatusCode::OK,
+                    Json(ApiEnvelope {
+                        meta: ApiMeta::ok(),
+                        request_id: rid,
+                        data: response,
+                    }),
+                ));
+            }
+            return Err(ApiError::conflict(
+                "idempotency_key already exists with different request payload",
+            )
+            .with_request_id(rid.clone())
+            .with_details(serde_json::json!({
+                "idempotency_key": req.idempotency_key,
+                "operation": operation
+            })));
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&rid) {
+        headers.insert("x-request-id", value);
+    }
+    let run = match run_job(
+        State(state.clone()),
+        headers,
+        Json(RunJobRequest {
+            thread_id: thread_id.clone(),
+            input: Some(input),
+            idempotency_key: None,
+            timeout_policy: None,
+            priority: None,
+            tenant_id: None,
+            mode: Some(mode),
+        }),
+    )
+    .await
+    {
+        Ok(Json(run)) => run,
+        Err(e) => {
+            if let Some(key) = idempotency_key.as_deref() {
+                release_idempotency_record(&state, key, &rid).await?;
+            }
+            return Err(e);
+        }
+    };
+    response.checkpoint_id = state
+        .graph_bridge
+        .snapshot(&thread_id, None)
+        .await
+        .ok()
+        .and_then(|snapshot| snapshot.checkpoint_id);
+    response.status = run.data.status;
+    response.interrupts = run.data.interrupts;
+
+    if let Some(key) = idempotency_key.as_deref() {
+        put_idempotency_record(
+            &state,
+            key,
+            "create_run",
+            &thread_id,
+            &request_payload_hash,
+            &encode_response(&response)?,
+            &rid,
+        )
+        .await?;
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: response,
+        }),
+    ))
+}
+
+pub async fn inspect_job(
+    State(state): State<ExecutionApiState>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiEnvelope<JobStateResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
+    log::info!(
+        "execution_inspect request_id={} thread_id={} checkpoint_id=none",
+        rid,
+        thread_id
+    );
+    let snapshot = state
+        .graph_bridge
+        .snapshot(&thread_id, None)
+        .await
+        .map_err(|e| {
+            if e.kind == ExecutionGraphBridgeErrorKind::NotFound {
+                ApiError::not_found(e.message).with_request_id(rid.clone())
+            } else {
+                ApiError::internal(e.message).with_request_id(rid.clone())
+            }
+        })?;
+
+    let checkpoint_id = snapshot.checkpoint_id.clone();
+    let created_at = snapshot.created_at.to_rfc3339();
+    let values = snapshot.values;
+
+    Ok(Json(ApiEnvelope {
+        meta: ApiMeta::ok(),
+        request_id: rid,
+        data: JobStateResponse {
+            thread_id,
+            checkpoint_id,
+            created_at,
+            values,
+        },
+    }))
+}
+
+pub async fn job_history(
+    State(state): State<ExecutionApiState>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiEnvelope<JobHistoryResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
+    log::info!(
+        "execution_history request_id={} thread_id={} checkpoint_id=none",
+        rid,
+        thread_id
+    );
+    let history = state.graph_bridge.history(&thread_id).await.map_err(|e| {
+        ApiError::internal(format!("history failed: {}", e.message)).with_request_id(rid.clone())
+    })?;
+
+    let items = history
+        .iter()
+        .map(|s| JobHistoryItem {
+            checkpoint_id: s.checkpoint_id.clone(),
+            created_at: s.created_at.to_rfc3339(),
+        })
         .collect();
 
     Ok(Json(ApiEnvelope {
@@ -9542,6 +10243,7 @@ pub async fn job_timeline(
 ) -> Result<Json<ApiEnvelope<JobTimelineResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     log::info!(
         "execution_timeline request_id={} thread_id={} checkpoint_id=none",
         rid,
@@ -9587,6 +10289,7 @@ pub async fn inspect_checkpoint(
 ) -> Result<Json<ApiEnvelope<CheckpointInspectResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     if checkpoint_id.trim().is_empty() {
         return Err(
             ApiError::bad_request("checkpoint_id must not be empty").with_request_id(rid.clone())
@@ -9632,6 +10335,7 @@ pub async fn resume_job(
 ) -> Result<Json<ApiEnvelope<RunJobResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    let tenant_id = job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     ensure_not_cancelled(&state, &thread_id)
         .await
         .map_err(|e| e.with_request_id(rid.clone()))?;
@@ -9645,13 +10349,40 @@ pub async fn resume_job(
             .unwrap_or_else(|| "none".to_string())
     );
 
+    let mode = req.mode.unwrap_or_default();
+    if mode == JobRunMode::Normal
+        && !req.allow_mode_change.unwrap_or(false)
+        && state.dry_run_threads.read().await.contains(&thread_id)
+    {
+        return Err(ApiError::conflict(format!(
+            "thread '{}' is a dry run; resume it with mode 'dry_run', or set \
+             allow_mode_change to perform its actions",
+            thread_id
+        ))
+        .with_request_id(rid.clone()));
+    }
+
     record_task_running(&state, &thread_id, "task resume execution started").await;
 
-    let result = match state
-        .graph_bridge
-        .resume(&thread_id, req.checkpoint_id.as_deref(), req.value)
-        .await
-    {
+    let checkpoint_id = req.checkpoint_id.as_deref();
+    let paused = state.paused_threads.read().await.contains(&thread_id);
+    let resumed = match (mode, paused) {
+        (JobRunMode::Normal, false) => {
+            state
+                .graph_bridge
+                .resume(&thread_id, checkpoint_id, req.value)
+                .await
+        }
+        (JobRunMode::DryRun, false) => {
+            state
+                .graph_bridge
+                .resume_dry(&thread_id, checkpoint_id, req.value)
+                .await
+        }
+        (JobRunMode::Normal, true) => state.graph_bridge.continue_run(&thread_id).await,
+        (JobRunMode::DryRun, true) => state.graph_bridge.continue_run_dry(&thread_id).await,
+    };
+    let result = match resumed {
         Ok(result) => result,
         Err(e) => {
             let error_message = e.to_string();
@@ -9669,46 +10400,45 @@ pub async fn resume_job(
         }
     };
 
-    let interrupts: Vec<Value> = result.interrupts;
-    let status = if interrupts.is_empty() {
-        "completed".to_string()
-    } else {
-        "interrupted".to_string()
-    };
-    if interrupts.is_empty() {
-        record_task_succeeded(&state, &thread_id, "task resume completed successfully").await;
-    } else {
-        record_task_running(
-            &state,
-            &thread_id,
-            "task interrupted again and waiting for resume",
-        )
-        .await;
+    {
+        let mut dry_run_threads = state.dry_run_threads.write().await;
+        match mode {
+            JobRunMode::Normal => dry_run_threads.remove(&thread_id),
+            JobRunMode::DryRun => dry_run_threads.insert(thread_id.clone()),
+        };
+    }
+
+    let status = invoke_status(&state, &thread_id, &result).await;
+    match status {
+        "completed" => {
+            record_task_succeeded(&state, &thread_id, "task resume completed successfully").await
+        }
+        "interrupted" => {
+            record_task_running(
+                &state,
+                &thread_id,
+                "task interrupted again and waiting for resume",
+            )
+            .await
+        }
+        "paused" => {
+            record_task_running(&state, &thread_id, "task paused and waiting for resume").await
+        }
+        _ => {}
     }
+    let status = status.to_string();
+    let interrupts: Vec<Value> = result.interrupts;
 
     #[cfg(feature = "sqlite-persistence")]
-    if let Some(repo) = state.runtime_repo.as_ref() {
-        let _ = repo.upsert_job(&thread_id, &status);
+    if let Some(repo) = scoped_runtime_repo(&state, tenant_id.as_deref()).as_ref() {
+        let _ = record_job_run(repo, &thread_id, &status).await;
         let pending = repo
             .list_interrupts(Some("pending"), Some(&thread_id), 100)
             .unwrap_or_default();
         for row in pending {
             let _ = repo.update_interrupt_status(&row.interrupt_id, "resumed");
         }
-        if !interrupts.is_empty() {
-            let attempt_id = format!("attempt-{}-main", thread_id);
-            for (i, iv) in interrupts.iter().enumerate() {
-                let interrupt_id = format!("int-{}-{}", thread_id, i);
-                let value_json = serde_json::to_string(iv).unwrap_or_default();
-                let _ = repo.insert_interrupt(
-                    &interrupt_id,
-                    &thread_id,
-                    &thread_id,
-                    &attempt_id,
-                    &value_json,
-                );
-            }
-        }
+        record_pending_interrupts(repo, &thread_id, &interrupts, &rid).await;
     }
 
     Ok(Json(ApiEnvelope {
@@ -9721,6 +10451,7 @@ pub async fn resume_job(
             idempotency_key: None,
             idempotent_replay: false,
             trace: None,
+            mode,
         },
     }))
 }
@@ -9733,6 +10464,7 @@ pub async fn replay_job(
 ) -> Result<Json<ApiEnvelope<RunJobResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     ensure_not_cancelled(&state, &thread_id)
         .await
         .map_err(|e| e.with_request_id(rid.clone()))?;
@@ -9831,6 +10563,7 @@ pub async fn replay_job(
         idempotency_key: None,
         idempotent_replay: false,
         trace: None,
+        mode: JobRunMode::Normal,
     };
 
     #[cfg(feature = "sqlite-persistence")]
@@ -9854,40 +10587,398 @@ pub async fn replay_job(
     }))
 }
 
-pub async fn cancel_job(
+pub async fn pause_job(
     State(state): State<ExecutionApiState>,
     Path(thread_id): Path<String>,
     headers: HeaderMap,
-    Json(req): Json<CancelJobRequest>,
-) -> Result<Json<ApiEnvelope<CancelJobResponse>>, ApiError> {
+) -> Result<Json<ApiEnvelope<PauseJobResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
+    ensure_not_cancelled(&state, &thread_id)
+        .await
+        .map_err(|e| e.with_request_id(rid.clone()))?;
     log::info!(
-        "execution_cancel request_id={} thread_id={} checkpoint_id=none",
+        "execution_pause request_id={} thread_id={} checkpoint_id=none",
         rid,
         thread_id
     );
-    state
-        .cancelled_threads
-        .write()
-        .await
-        .insert(thread_id.clone());
-    let reason = req.reason.clone();
-    let cancel_summary = reason
-        .clone()
-        .unwrap_or_else(|| "task cancelled via API request".to_string());
-    record_task_cancelled(&state, &thread_id, cancel_summary.as_str()).await;
+    let pausing = state.graph_bridge.pause(&thread_id).await.map_err(|e| {
+        ApiError::internal(format!("pause failed: {}", e)).with_request_id(rid.clone())
+    })?;
+    if !pausing {
+        let message = if state.paused_threads.read().await.contains(&thread_id) {
+            format!("thread '{}' is already paused", thread_id)
+        } else {
+            format!("thread '{}' has no run in flight", thread_id)
+        };
+        return Err(ApiError::conflict(message).with_request_id(rid));
+    }
     Ok(Json(ApiEnvelope {
         meta: ApiMeta::ok(),
         request_id: rid,
-        data: CancelJobResponse {
+        data: PauseJobResponse {
             thread_id,
-            status: "cancelled".to_string(),
+            status: "pausing".to_string(),
+        },
+    }))
+}
+
+pub async fn cancel_job(
+    State(state): State<ExecutionApiState>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<CancelJobRequest>,
+) -> Result<Json<ApiEnvelope<CancelJobResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
+    log::info!(
+        "execution_cancel request_id={} thread_id={} checkpoint_id=none",
+        rid,
+        thread_id
+    );
+    if !state
+        .cancelled_threads
+        .write()
+        .await
+        .insert(thread_id.clone())
+    {
+        return Err(
+            ApiError::conflict(format!("thread '{}' is already cancelled", thread_id))
+                .with_request_id(rid),
+        );
+    }
+    state.paused_threads.write().await.remove(&thread_id);
+    // Marked cancelled first, so a run stopped here reports `cancelled` rather than `paused`
+    if let Err(e) = state.graph_bridge.pause(&thread_id).await {
+        log::warn!(
+            "execution_cancel request_id={} thread_id={} could not stop the run in flight: {}",
+            rid,
+            thread_id,
+            e
+        );
+    }
+    let reason = req.reason.clone();
+    let cancel_summary = reason
+        .clone()
+        .unwrap_or_else(|| "task cancelled via API request".to_string());
+    record_task_cancelled(&state, &thread_id, cancel_summary.as_str()).await;
+    Ok(Json(ApiEnvelope {
+        meta: ApiMeta::ok(),
+        request_id: rid,
+        data: CancelJobResponse {
+            thread_id,
+            status: "cancelled".to_string(),
             reason,
         },
     }))
 }
 
+/// The run record of `run_id` and its job status, which the in-memory cancellations override
+#[cfg(feature = "sqlite-persistence")]
+async fn current_run_status(
+    state: &ExecutionApiState,
+    repo: &SqliteRuntimeRepository,
+    run_id: &str,
+    rid: &str,
+) -> Result<RunRuntimeStatus, ApiError> {
+    let run = AsyncRuntimeRepository::get_run(repo, &run_id.to_string())
+        .await
+        .map_err(|e| ApiError::from(e).with_request_id(rid.to_string()))?
+        .ok_or_else(|| ApiError::not_found("run not found").with_request_id(rid.to_string()))?;
+    if state.cancelled_threads.read().await.contains(run_id) {
+        return Ok(RunRuntimeStatus::Cancelled);
+    }
+    Ok(run.status)
+}
+
+/// Summary of run `run_id` at `status`, with the checkpoint its thread is at
+#[cfg(feature = "sqlite-persistence")]
+async fn run_status_response(
+    state: &ExecutionApiState,
+    run_id: String,
+    status: String,
+    interrupts: Vec<Value>,
+) -> RunStatusResponse {
+    let checkpoint_id = state
+        .graph_bridge
+        .snapshot(&run_id, None)
+        .await
+        .ok()
+        .and_then(|snapshot| snapshot.checkpoint_id);
+    RunStatusResponse {
+        thread_id: run_id.clone(),
+        run_id,
+        status,
+        checkpoint_id,
+        interrupts,
+        idempotent_replay: false,
+    }
+}
+
+/// `POST /v1/runs/:run_id/resume`: resume a run blocked on an interrupt or a pause
+///
+/// Answers 409 with the run's status when it is not blocked. A retry carrying the
+/// `idempotency_key` of a resume that already took effect gets that resume's summary back.
+/// The key is reserved before the run resumes, so a retry racing the first request gets
+/// the provisional `running` summary rather than resuming the run twice. Keys are scoped
+/// to the caller's tenant.
+pub async fn resume_run(
+    State(state): State<ExecutionApiState>,
+    Path(run_id): Path<String>,
+    mut headers: HeaderMap,
+    Json(req): Json<ResumeRunRequest>,
+) -> Result<Json<ApiEnvelope<RunStatusResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    validate_thread_id(&run_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    let tenant_id = job_request_tenant(&state, &headers, &run_id, &rid).await?;
+    #[cfg(feature = "sqlite-persistence")]
+    {
+        let repo = scoped_runtime_repo(&state, tenant_id.as_deref()).ok_or_else(|| {
+            ApiError::internal("runtime repository is not configured").with_request_id(rid.clone())
+        })?;
+        if matches!(req.idempotency_key.as_deref(), Some(key) if key.trim().is_empty()) {
+            return Err(
+                ApiError::bad_request("idempotency_key must not be empty").with_request_id(rid)
+            );
+        }
+        let request_payload_hash = json_hash(&serde_json::json!({
+            "run_id": run_id,
+            "checkpoint_id": req.checkpoint_id,
+            "value": req.value,
+        }))
+        .map_err(|e| e.with_request_id(rid.clone()))?;
+        let idempotency_key = req
+            .idempotency_key
+            .as_deref()
+            .map(|key| tenant_idempotency_key(tenant_id.as_deref(), key));
+        let encode_response = |response: &RunStatusResponse| {
+            serde_json::to_string(response).map_err(|e| {
+                ApiError::internal(format!("encode idempotent response failed: {}", e))
+                    .with_request_id(rid.clone())
+            })
+        };
+
+        if let Some(key) = idempotency_key.as_deref() {
+            let provisional =
+                run_status_response(&state, run_id.clone(), "running".to_string(), Vec::new())
+                    .await;
+            if let Some((operation, stored_hash, response_json)) = reserve_idempotency_record(
+                &state,
+                key,
+                "resume_run",
+                &run_id,
+                &request_payload_hash,
+                &encode_response(&provisional)?,
+                &rid,
+            )
+            .await?
+            {
+                if operation == "resume_run" && stored_hash == request_payload_hash {
+                    let mut response: RunStatusResponse = serde_json::from_str(&response_json)
+                        .map_err(|e| {
+                            ApiError::internal(format!("decode idempotent response failed: {}", e))
+                                .with_request_id(rid.clone())
+                        })?;
+                    response.idempotent_replay = true;
+                    return Ok(Json(ApiEnvelope {
+                        meta: ApiMeta::ok(),
+                        request_id: rid,
+                        data: response,
+                    }));
+                }
+                return Err(ApiError::conflict(
+                    "idempotency_key already exists with different request payload",
+                )
+                .with_request_id(rid.clone())
+                .with_details(serde_json::json!({
+                    "idempotency_key": req.idempotency_key,
+                    "operation": operation
+                })));
+            }
+        }
+
+        if let Ok(value) = HeaderValue::from_str(&rid) {
+            headers.insert("x-request-id", value);
+        }
+        let resumed = resume_blocked_run(
+            &state,
+            &repo,
+            &run_id,
+            headers,
+            ResumeJobRequest {
+                value: req.value,
+                checkpoint_id: req.checkpoint_id,
+                mode: None,
+                allow_mode_change: None,
+            },
+            &rid,
+        )
+        .await;
+        let response = match resumed {
+            Ok(response) => response,
+            Err(e) => {
+                if let Some(key) = idempotency_key.as_deref() {
+                    release_idempotency_record(&state, key, &rid).await?;
+                }
+                return Err(e);
+            }
+        };
+        if let Some(key) = idempotency_key.as_deref() {
+            put_idempotency_record(
+                &state,
+                key,
+                "resume_run",
+                &run_id,
+                &request_payload_hash,
+                &encode_response(&response)?,
+                &rid,
+            )
+            .await?;
+        }
+        return Ok(Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: response,
+        }));
+    }
+    #[cfg(not(feature = "sqlite-persistence"))]
+    {
+        let _ = (tenant_id, &mut headers, req);
+        Err(ApiError::internal("run APIs require sqlite-persistence").with_request_id(rid))
+    }
+}
+
+/// Resumes a run blocked on an interrupt or a pause in the mode it was started in,
+/// answering 409 with the run's status when it is not blocked.
+#[cfg(feature = "sqlite-persistence")]
+async fn resume_blocked_run(
+    state: &ExecutionApiState,
+    repo: &SqliteRuntimeRepository,
+    run_id: &str,
+    headers: HeaderMap,
+    mut req: ResumeJobRequest,
+    rid: &str,
+) -> Result<RunStatusResponse, ApiError> {
+    let status = current_run_status(state, repo, run_id, rid).await?;
+    if !matches!(
+        status,
+        RunRuntimeStatus::BlockedInterrupt | RunRuntimeStatus::Paused
+    ) {
+        let status = run_status_to_job_status(&status);
+        return Err(
+            ApiError::conflict(format!("run '{}' is {} and not blocked", run_id, status))
+                .with_request_id(rid.to_string())
+                .with_details(serde_json::json!({ "run_id": run_id, "status": status })),
+        );
+    }
+
+    // Resumed in the mode it was started in
+    req.mode = Some(if state.dry_run_threads.read().await.contains(run_id) {
+        JobRunMode::DryRun
+    } else {
+        JobRunMode::Normal
+    });
+    let Json(resumed) = resume_job(
+        State(state.clone()),
+        Path(run_id.to_string()),
+        headers,
+        Json(req),
+    )
+    .await?;
+    Ok(run_status_response(
+        state,
+        run_id.to_string(),
+        resumed.data.status,
+        resumed.data.interrupts,
+    )
+    .await)
+}
+
+/// `POST /v1/runs/:run_id/cancel`: cancel a run, stopping the step in flight and its attempt
+///
+/// Cancelling a cancelled run again returns its summary; a completed run answers 409.
+pub async fn cancel_run(
+    State(state): State<ExecutionApiState>,
+    Path(run_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<CancelJobRequest>,
+) -> Result<Json<ApiEnvelope<RunStatusResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    validate_thread_id(&run_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    let tenant_id = job_request_tenant(&state, &headers, &run_id, &rid).await?;
+    #[cfg(feature = "sqlite-persistence")]
+    {
+        let repo = scoped_runtime_repo(&state, tenant_id.as_deref()).ok_or_else(|| {
+            ApiError::internal("runtime repository is not configured").with_request_id(rid.clone())
+        })?;
+        let status = current_run_status(&state, &repo, &run_id, &rid).await?;
+        if status == RunRuntimeStatus::Cancelled {
+            let mut response =
+                run_status_response(&state, run_id, "cancelled".to_string(), Vec::new()).await;
+            response.idempotent_replay = true;
+            return Ok(Json(ApiEnvelope {
+                meta: ApiMeta::ok(),
+                request_id: rid,
+                data: response,
+            }));
+        }
+        if status.is_final() {
+            let status = run_status_to_job_status(&status);
+            return Err(
+                ApiError::conflict(format!("run '{}' is already {}", run_id, status))
+                    .with_request_id(rid)
+                    .with_details(serde_json::json!({ "run_id": run_id, "status": status })),
+            );
+        }
+
+        let _ = cancel_job(
+            State(state.clone()),
+            Path(run_id.clone()),
+            headers,
+            Json(req),
+        )
+        .await
+        .map_err(|e| e.with_request_id(rid.clone()))?;
+        match repo.latest_attempt_id_for_run(&run_id) {
+            Ok(Some(attempt_id)) => {
+                if let Err(e) = AsyncRuntimeRepository::cancel_attempt(&repo, &attempt_id).await {
+                    log::warn!(
+                        "execution_cancel request_id={} run_id={} could not cancel attempt {}: {}",
+                        rid,
+                        run_id,
+                        attempt_id,
+                        e
+                    );
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!(
+                "execution_cancel request_id={} run_id={} could not look up its attempt: {}",
+                rid,
+                run_id,
+                e
+            ),
+        }
+        record_job_run(&repo, &run_id, "cancelled")
+            .await
+            .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?;
+        let response =
+            run_status_response(&state, run_id, "cancelled".to_string(), Vec::new()).await;
+        return Ok(Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: response,
+        }));
+    }
+    #[cfg(not(feature = "sqlite-persistence"))]
+    {
+        let _ = (tenant_id, req);
+        Err(ApiError::internal("run APIs require sqlite-persistence").with_request_id(rid))
+    }
+}
+
 pub async fn list_jobs(
     State(state): State<ExecutionApiState>,
     headers: HeaderMap,
@@ -9896,19 +10987,42 @@ pub async fn list_jobs(
     let rid = request_id(&headers);
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
-        let limit = q.limit.unwrap_or(50).min(200);
-        let offset = q.offset.unwrap_or(0);
-        let status_filter = q.status.as_deref();
-        let runs = repo
-            .list_runs(limit, offset, status_filter)
+        let repo = runtime_repo(&state, &headers, &rid)?;
+        let page = PageRequest::new(q.offset.unwrap_or(0), q.limit.unwrap_or(50).min(200));
+        let status = match q.status.as_deref() {
+            Some(status) => Some(job_status_to_run_status(status).ok_or_else(|| {
+                ApiError::bad_request(format!("unknown job status '{}'", status))
+                    .with_request_id(rid.clone())
+            })?),
+            None => None,
+        };
+        let created_at = |ms: Option<i64>, field: &str| {
+            ms.map(|ms| {
+                chrono::DateTime::from_timestamp_millis(ms).ok_or_else(|| {
+                    ApiError::bad_request(format!("{} is out of range", field))
+                        .with_request_id(rid.clone())
+                })
+            })
+            .transpose()
+        };
+        let filter = RunRecordFilter {
+            status,
+            workflow_name: q.workflow.clone(),
+            created_after: created_at(q.created_from_ms, "created_from_ms")?,
+            created_before: created_at(q.created_to_ms, "created_to_ms")?,
+        };
+        let runs = AsyncRuntimeRepository::list_runs(&repo, &filter, page)
+            .await
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
         let jobs = runs
             .into_iter()
-            .map(|(tid, st, updated)| JobListItem {
-                thread_id: tid,
-                status: st,
-                updated_at: updated.to_rfc3339(),
+            .map(|run| JobListItem {
+                thread_id: run.run_id,
+                status: run_status_to_job_status(&run.status).to_string(),
+                workflow: run.workflow_name,
+                status_reason: run.status_reason,
+                created_at: run.created_at.to_rfc3339(),
+                updated_at: run.updated_at.to_rfc3339(),
             })
             .collect();
         return Ok(Json(ApiEnvelope {
@@ -9928,6 +11042,151 @@ pub async fn list_jobs(
     }
 }
 
+/// The run event endpoints need a kernel event log: the graph's own (attached with
+/// `CompiledGraph::with_event_store`) or one set with `with_kernel_event_store`
+fn kernel_events_not_configured(rid: &str) -> ApiError {
+    ApiError::not_implemented(
+        "kernel event store is not configured; compile the graph with_event_store or set \
+         ExecutionApiState::with_kernel_event_store",
+    )
+    .with_request_id(rid.to_string())
+}
+
+/// Counts the runs of the kernel event log by status, with the longest-blocked runs and
+/// the most common failure reasons; `?status=` and `?created_after=` narrow the runs
+pub async fn runs_summary(
+    State(state): State<ExecutionApiState>,
+    headers: HeaderMap,
+    Query(filter): Query<RunFilter>,
+) -> Result<Json<ApiEnvelope<FleetSummary>>, ApiError> {
+    let rid = request_id(&headers);
+    let events = state
+        .kernel_events
+        .as_ref()
+        .ok_or_else(|| kernel_events_not_configured(&rid))?;
+    let summary = summarize_runs(events.as_ref(), filter)
+        .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
+    Ok(Json(ApiEnvelope {
+        meta: ApiMeta::ok(),
+        request_id: rid,
+        data: summary,
+    }))
+}
+
+/// How often `/v1/runs/:run_id/events` sends a heartbeat comment, so proxies keep an idle
+/// stream open
+pub const RUN_EVENTS_HEARTBEAT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Whether `event` ends its run, which closes the run's event stream
+fn ends_run(event: &crate::kernel::Event) -> bool {
+    use crate::kernel::EventKind;
+    matches!(
+        event.kind(),
+        EventKind::Completed | EventKind::Failed | EventKind::Cancelled
+    )
+}
+
+/// The `done` event closing the stream of a run that ended with `event`
+fn run_done_event(run_id: &str, event: &crate::kernel::Event) -> SseEvent {
+    let status = crate::kernel::RunStatusKind::from_last_event(event);
+    SseEvent::default().event("done").data(
+        serde_json::json!({
+            "run_id": run_id,
+            "status": status.as_str(),
+        })
+        .to_string(),
+    )
+}
+
+/// Streams the run's kernel events as server-sent events
+///
+/// Replays the stored events from `?from_seq=` (default 1), then sends new ones as they are
+/// appended, each with its seq as the SSE id so a reconnecting client resumes after its
+/// `Last-Event-ID`. A final `done` event carries the run's status once it completes, fails
+/// or is cancelled; heartbeat comments keep the stream open while the run is quiet.
+pub async fn stream_run_events(
+    State(state): State<ExecutionApiState>,
+    Path(run_id): Path<String>,
+    headers: HeaderMap,
+    Query(q): Query<RunEventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, ApiError> {
+    let rid = request_id(&headers);
+    job_request_tenant(&state, &headers, &run_id, &rid).await?;
+    let events = state
+        .kernel_events
+        .clone()
+        .ok_or_else(|| kernel_events_not_configured(&rid))?;
+    let last_event_id = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|id| id.trim().parse::<crate::kernel::Seq>().ok())
+                .ok_or_else(|| {
+                    ApiError::bad_request("Last-Event-ID must be an event seq")
+                        .with_request_id(rid.clone())
+                })?,
+        ),
+        None => None,
+    };
+    let from_seq = last_event_id.map_or(q.from_seq.unwrap_or(1), |seq| seq + 1);
+
+    if !events
+        .run_exists(&run_id)
+        .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?
+    {
+        return Err(ApiError::not_found("run not found").with_request_id(rid));
+    }
+    // A run that ended before `from_seq` has nothing left to send but `done`
+    let ended = events
+        .scan_rev(&run_id, 1, &crate::kernel::EventFilter::all())
+        .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?
+        .into_iter()
+        .find(|last| last.seq < from_seq && ends_run(&last.event));
+
+    let subscription = match state.kernel_event_watch.as_ref() {
+        Some(watch) => watch.subscribe(&run_id, from_seq),
+        None => {
+            use crate::kernel::WatchableEventStore;
+            crate::kernel::PollingEventStore::from_arc(Arc::new(events))
+                .subscribe(&run_id, from_seq)
+        }
+    };
+    let stream = async_stream::stream! {
+        if let Some(last) = ended {
+            yield Ok(run_done_event(&run_id, &last.event));
+            return;
+        }
+        let mut subscription = subscription;
+        while let Some(next) = subscription.next().await {
+            match next {
+                Ok(event) => {
+                    yield Ok(SseEvent::default()
+                        .id(event.seq.to_string())
+                        .event(event.event.kind().as_str())
+                        .json_data(&event)
+                        .unwrap_or_else(|e| {
+                            SseEvent::default().event("error").data(e.to_string())
+                        }));
+                    if ends_run(&event.event) {
+                        yield Ok(run_done_event(&run_id, &event.event));
+                        return;
+                    }
+                }
+                Err(e) => {
+                    yield Ok(SseEvent::default().event("error").data(e.to_string()));
+                    return;
+                }
+            }
+        }
+    };
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(RUN_EVENTS_HEARTBEAT)
+            .text("heartbeat"),
+    ))
+}
+
 pub async fn list_interrupts(
     State(state): State<ExecutionApiState>,
     headers: HeaderMap,
@@ -9936,7 +11195,7 @@ pub async fn list_interrupts(
     let rid = request_id(&headers);
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let limit = q.limit.unwrap_or(50).min(200);
         let rows = repo
             .list_interrupts(q.status.as_deref(), q.run_id.as_deref(), limit)
@@ -9988,7 +11247,7 @@ pub async fn list_audit_logs(
     }
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let limit = q.limit.unwrap_or(100).clamp(1, 500);
         let request_id_filter = q
             .request_id
@@ -10043,7 +11302,7 @@ pub async fn list_attempt_retries(
     }
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let snapshot = repo
             .get_attempt_retry_history(&attempt_id)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
@@ -10075,6 +11334,7 @@ pub async fn list_attempt_retries(
                     AttemptExecutionStatus::Completed => "completed".to_string(),
                     AttemptExecutionStatus::Failed => "failed".to_string(),
                     AttemptExecutionStatus::Cancelled => "cancelled".to_string(),
+                    AttemptExecutionStatus::DeadLetter => "dead_letter".to_string(),
                 },
                 history,
             },
@@ -10090,16 +11350,65 @@ pub async fn list_attempt_retries(
     }
 }
 
-pub async fn list_dead_letters(
+pub async fn cancel_attempt(
     State(state): State<ExecutionApiState>,
+    Path(attempt_id): Path<String>,
     headers: HeaderMap,
-    Query(q): Query<ListDeadLettersQuery>,
-) -> Result<Json<ApiEnvelope<DeadLetterListResponse>>, ApiError> {
+) -> Result<Json<ApiEnvelope<AttemptCancelResponse>>, ApiError> {
     let rid = request_id(&headers);
+    if attempt_id.trim().is_empty() {
+        return Err(ApiError::bad_request("attempt_id must not be empty").with_request_id(rid));
+    }
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
-        let limit = q.limit.unwrap_or(100).clamp(1, 500);
+        let repo = runtime_repo(&state, &headers, &rid)?;
+        let cancellation = AsyncRuntimeRepository::cancel_attempt(&repo, &attempt_id)
+            .await
+            .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?;
+        let (outcome, status) = match cancellation {
+            AttemptCancellation::Cancelled => ("cancelled", Some("cancelled")),
+            AttemptCancellation::Requested => ("cancel_requested", None),
+            AttemptCancellation::AlreadyFinished(status) => (
+                "already_finished",
+                Some(match status {
+                    AttemptExecutionStatus::Completed => "completed",
+                    AttemptExecutionStatus::Failed => "failed",
+                    AttemptExecutionStatus::Cancelled => "cancelled",
+                    AttemptExecutionStatus::DeadLetter => "dead_letter",
+                    AttemptExecutionStatus::Queued => "queued",
+                    AttemptExecutionStatus::Leased => "leased",
+                    AttemptExecutionStatus::Running => "running",
+                    AttemptExecutionStatus::RetryBackoff => "retry_backoff",
+                }),
+            ),
+        };
+        return Ok(Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: AttemptCancelResponse {
+                attempt_id,
+                outcome: outcome.to_string(),
+                status: status.map(str::to_string),
+            },
+        }));
+    }
+    #[cfg(not(feature = "sqlite-persistence"))]
+    {
+        let _ = attempt_id;
+        Err(ApiError::internal("attempt APIs require sqlite-persistence").with_request_id(rid))
+    }
+}
+
+pub async fn list_dead_letters(
+    State(state): State<ExecutionApiState>,
+    headers: HeaderMap,
+    Query(q): Query<ListDeadLettersQuery>,
+) -> Result<Json<ApiEnvelope<DeadLetterListResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    #[cfg(feature = "sqlite-persistence")]
+    {
+        let repo = runtime_repo(&state, &headers, &rid)?;
+        let limit = q.limit.unwrap_or(100).clamp(1, 500);
         let status_filter = q.status.as_deref().map(str::trim).filter(|v| !v.is_empty());
         let rows = repo
             .list_dead_letters(status_filter, limit)
@@ -10129,7 +11438,7 @@ pub async fn get_dead_letter(
     }
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let row = repo
             .get_dead_letter(&attempt_id)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
@@ -10160,19 +11469,10 @@ pub async fn replay_dead_letter(
     }
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let row = repo
             .replay_dead_letter(&attempt_id, Utc::now())
-            .map_err(|e| {
-                let msg = e.to_string();
-                if msg.contains("not found") {
-                    ApiError::not_found(msg).with_request_id(rid.clone())
-                } else if msg.contains("already replayed") {
-                    ApiError::conflict(msg).with_request_id(rid.clone())
-                } else {
-                    ApiError::internal(msg).with_request_id(rid.clone())
-                }
-            })?;
+            .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?;
         return Ok(Json(ApiEnvelope {
             meta: ApiMeta::ok(),
             request_id: rid,
@@ -10190,6 +11490,29 @@ pub async fn replay_dead_letter(
     }
 }
 
+pub async fn runtime_stats(
+    State(state): State<ExecutionApiState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiEnvelope<RuntimeStats>>, ApiError> {
+    let rid = request_id(&headers);
+    #[cfg(feature = "sqlite-persistence")]
+    {
+        let repo = runtime_repo(&state, &headers, &rid)?;
+        let stats = AsyncRuntimeRepository::runtime_stats(&repo, Utc::now())
+            .await
+            .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
+        return Ok(Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: stats,
+        }));
+    }
+    #[cfg(not(feature = "sqlite-persistence"))]
+    {
+        Err(ApiError::internal("runtime stats require sqlite-persistence").with_request_id(rid))
+    }
+}
+
 pub async fn get_interrupt(
     State(state): State<ExecutionApiState>,
     Path(interrupt_id): Path<String>,
@@ -10198,7 +11521,7 @@ pub async fn get_interrupt(
     let rid = request_id(&headers);
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let row = repo
             .get_interrupt(&interrupt_id)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
@@ -10236,7 +11559,7 @@ pub async fn resume_interrupt(
     let rid = request_id(&headers);
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?.clone();
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let resume_hash = json_hash(&req.value).map_err(|e| e.with_request_id(rid.clone()))?;
         let row = repo
             .get_interrupt(&interrupt_id)
@@ -10287,9 +11610,13 @@ pub async fn resume_interrupt(
         repo.update_interrupt_status(&interrupt_id, "resuming")
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
 
+        // Interrupts of a dry run are answered within the dry run
+        let dry_run = state.dry_run_threads.read().await.contains(&row.thread_id);
         let resume_req = ResumeJobRequest {
             value: req.value,
             checkpoint_id: None,
+            mode: dry_run.then_some(JobRunMode::DryRun),
+            allow_mode_change: None,
         };
         let envelope = match resume_job(
             State(state),
@@ -10310,14 +11637,7 @@ pub async fn resume_interrupt(
                 .with_request_id(rid.clone())
         })?;
         repo.persist_interrupt_resume_result(&interrupt_id, &resume_hash, &response_json)
-            .map_err(|e| {
-                let msg = e.to_string();
-                if msg.contains("different payload") {
-                    ApiError::conflict(msg).with_request_id(rid.clone())
-                } else {
-                    ApiError::internal(msg).with_request_id(rid.clone())
-                }
-            })?;
+            .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?;
         return Ok(Json(envelope));
     }
     #[cfg(not(feature = "sqlite-persistence"))]
@@ -10336,7 +11656,7 @@ pub async fn reject_interrupt(
     let rid = request_id(&headers);
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?;
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let row = repo
             .get_interrupt(&interrupt_id)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
@@ -10356,7 +11676,8 @@ pub async fn reject_interrupt(
             .write()
             .await
             .insert(row.thread_id.clone());
-        repo.upsert_job(&row.thread_id, "cancelled")
+        record_job_run(&repo, &row.thread_id, "cancelled")
+            .await
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
         return Ok(Json(ApiEnvelope {
             meta: ApiMeta::ok(),
@@ -10375,6 +11696,206 @@ pub async fn reject_interrupt(
     }
 }
 
+pub async fn resolve_interrupt(
+    State(state): State<ExecutionApiState>,
+    Path(interrupt_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<ResolveInterruptRequest>,
+) -> Result<Json<ApiEnvelope<ResolveInterruptResponse>>, ApiError> {
+    let rid = request_id(&headers);
+    #[cfg(feature = "sqlite-persistence")]
+    {
+        let repo = runtime_repo(&state, &headers, &rid)?;
+        if req.decision == InterruptDecision::Approve {
+            let row = repo
+                .get_interrupt(&interrupt_id)
+                .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
+                .ok_or_else(|| {
+                    ApiError::not_found("interrupt not found").with_request_id(rid.clone())
+                })?;
+            if row.status == "pending"
+                && state
+                    .cancelled_threads
+                    .read()
+                    .await
+                    .contains(&row.thread_id)
+            {
+                return Err(
+                    ApiError::conflict(format!("thread '{}' is cancelled", row.thread_id))
+                        .with_request_id(rid.clone()),
+                );
+            }
+        }
+
+        // Only one resolution wins; a resolved interrupt is a conflict
+        let record = AsyncRuntimeRepository::resolve_interrupt(
+            &repo,
+            &interrupt_id,
+            req.decision,
+            &req.value,
+        )
+        .await
+        .map_err(|e| ApiError::from(e).with_request_id(rid.clone()))?;
+
+        let run = match req.decision {
+            InterruptDecision::Approve => {
+                let dry_run = state.dry_run_threads.read().await.contains(&record.run_id);
+                let resume_req = ResumeJobRequest {
+                    value: req.value,
+                    checkpoint_id: None,
+                    mode: dry_run.then_some(JobRunMode::DryRun),
+                    allow_mode_change: None,
+                };
+                match resume_job(
+                    State(state),
+                    Path(record.run_id.clone()),
+                    headers,
+                    Json(resume_req),
+                )
+                .await
+                {
+                    Ok(response) => Some(response.0.data),
+                    Err(err) => {
+                        if let Err(e) = repo.reopen_interrupt(&interrupt_id) {
+                            log::warn!(
+                                "execution_interrupt request_id={} interrupt_id={} could not be reopened after a failed resume: {}",
+                                rid,
+                                interrupt_id,
+                                e
+                            );
+                        }
+                        return Err(err);
+                    }
+                }
+            }
+            InterruptDecision::Reject => {
+                state
+                    .cancelled_threads
+                    .write()
+                    .await
+                    .insert(record.run_id.clone());
+                record_job_run(&repo, &record.run_id, "cancelled")
+                    .await
+                    .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
+                None
+            }
+        };
+        return Ok(Json(ApiEnvelope {
+            meta: ApiMeta::ok(),
+            request_id: rid,
+            data: ResolveInterruptResponse {
+                interrupt_id: record.interrupt_id,
+                run_id: record.run_id,
+                decision: req.decision,
+                status: record.status.as_str().to_string(),
+                resolved_at: record.resolved_at.map(|t| t.to_rfc3339()),
+                run,
+            },
+        }));
+    }
+    #[cfg(not(feature = "sqlite-persistence"))]
+    {
+        let _ = (interrupt_id, req);
+        Err(ApiError::internal("interrupt API requires sqlite-persistence").with_request_id(rid))
+    }
+}
+
+/// Graph name recorded for the runs of jobs started through this server.
+#[cfg(feature = "sqlite-persistence")]
+const JOB_WORKFLOW_NAME: &str = "execution_server";
+
+/// The run status recorded for a job status; `interrupted` jobs are runs blocked on an
+/// interrupt, every other job status names its run status.
+#[cfg(feature = "sqlite-persistence")]
+fn job_status_to_run_status(status: &str) -> Option<RunRuntimeStatus> {
+    if status == "interrupted" {
+        return Some(RunRuntimeStatus::BlockedInterrupt);
+    }
+    let parsed = RunRuntimeStatus::from_str(status);
+    (parsed.as_str() == status).then_some(parsed)
+}
+
+#[cfg(feature = "sqlite-persistence")]
+fn run_status_to_job_status(status: &RunRuntimeStatus) -> &str {
+    match status {
+        RunRuntimeStatus::BlockedInterrupt => "interrupted",
+        other => other.as_str(),
+    }
+}
+
+/// Moves the job's run to `status`, recording the run the first time the job is seen.
+#[cfg(feature = "sqlite-persistence")]
+async fn record_job_run(
+    repo: &SqliteRuntimeRepository,
+    thread_id: &str,
+    status: &str,
+) -> Result<(), KernelError> {
+    let status = job_status_to_run_status(status).unwrap_or(RunRuntimeStatus::Running);
+    let run_id = thread_id.to_string();
+    match AsyncRuntimeRepository::update_run_status(repo, &run_id, status.clone(), None).await {
+        Err(KernelError::NotFound(_)) => {
+            let now = Utc::now();
+            AsyncRuntimeRepository::create_run(
+                repo,
+                &RunRecord {
+                    run_id,
+                    workflow_name: JOB_WORKFLOW_NAME.to_string(),
+                    status,
+                    status_reason: None,
+                    tenant_id: None,
+                    created_at: now,
+                    updated_at: now,
+                },
+            )
+            .await
+        }
+        other => other,
+    }
+}
+
+/// Records the interrupts a run or resume stopped at in the interrupt inbox.
+///
+/// Every interrupt gets a fresh id, so one raised after an earlier interrupt of the run
+/// was answered does not collide with it.
+#[cfg(feature = "sqlite-persistence")]
+async fn record_pending_interrupts(
+    repo: &SqliteRuntimeRepository,
+    thread_id: &str,
+    interrupts: &[Value],
+    rid: &str,
+) {
+    let attempt_id = format!("attempt-{}-main", thread_id);
+    let created_at = Utc::now();
+    for iv in interrupts {
+        let interrupt_id = format!("int-{}-{}", thread_id, uuid::Uuid::new_v4().simple());
+        let created = AsyncRuntimeRepository::create_interrupt(
+            repo,
+            &InterruptRecord {
+                interrupt_id: interrupt_id.clone(),
+                run_id: thread_id.to_string(),
+                attempt_id: attempt_id.clone(),
+                step_id: None,
+                payload: iv.clone(),
+                status: InterruptStatus::Pending,
+                created_at,
+                decision: None,
+                resolution: None,
+                resolved_at: None,
+            },
+        )
+        .await;
+        if let Err(e) = created {
+            log::warn!(
+                "execution_interrupt request_id={} thread_id={} could not record interrupt {}: {}",
+                rid,
+                thread_id,
+                interrupt_id,
+                e
+            );
+        }
+    }
+}
+
 pub async fn job_detail(
     State(state): State<ExecutionApiState>,
     Path(thread_id): Path<String>,
@@ -10382,6 +11903,7 @@ pub async fn job_detail(
 ) -> Result<Json<ApiEnvelope<JobDetailResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     let snapshot = state
         .graph_bridge
         .snapshot(&thread_id, None)
@@ -10419,6 +11941,8 @@ pub async fn job_detail(
     let values = snapshot.values;
     let status = if state.cancelled_threads.read().await.contains(&thread_id) {
         "cancelled".to_string()
+    } else if state.paused_threads.read().await.contains(&thread_id) {
+        "paused".to_string()
     } else {
         "running".to_string()
     };
@@ -10481,6 +12005,7 @@ pub async fn export_timeline(
 ) -> Result<Json<ApiEnvelope<TimelineExportResponse>>, ApiError> {
     let rid = request_id(&headers);
     validate_thread_id(&thread_id).map_err(|e| e.with_request_id(rid.clone()))?;
+    job_request_tenant(&state, &headers, &thread_id, &rid).await?;
     let history = state.graph_bridge.history(&thread_id).await.map_err(|e| {
         ApiError::internal(format!("timeline export failed: {}", e.message))
             .with_request_id(rid.clone())
@@ -10532,7 +12057,7 @@ pub async fn worker_poll(
 
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?;
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let poll_limit = req.limit.unwrap_or(state.worker_poll_limit).max(1);
         let max_active = req
             .max_active_leases
@@ -10548,6 +12073,7 @@ pub async fn worker_poll(
         let lease_manager = RepositoryLeaseManager::new(repo.clone(), LeaseConfig::default());
         lease_manager
             .tick(now)
+            .await
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
 
         let active = repo
@@ -10579,6 +12105,8 @@ pub async fn worker_poll(
         let candidates = repo
             .list_dispatchable_attempt_contexts(now, scan_limit)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?;
+        #[cfg(feature = "metrics")]
+        ::metrics::gauge!(crate::metrics::DISPATCHABLE_ATTEMPTS).set(candidates.len() as f64);
 
         for candidate in candidates {
             if let Some(tenant_id) = candidate.tenant_id.as_deref() {
@@ -10593,8 +12121,17 @@ pub async fn worker_poll(
 
             let lease_expires_at = now + chrono::Duration::seconds(30);
             state.runtime_metrics.record_lease_operation();
-            match repo.upsert_lease(&candidate.attempt_id, &req.worker_id, lease_expires_at) {
+            match AsyncRuntimeRepository::upsert_lease(
+                &repo,
+                &candidate.attempt_id,
+                &req.worker_id,
+                lease_expires_at,
+            )
+            .await
+            {
                 Ok(lease) => {
+                    #[cfg(feature = "metrics")]
+                    ::metrics::counter!(crate::metrics::LEASES_GRANTED_TOTAL).increment(1);
                     let dispatch_latency_ms = poll_started.elapsed().as_secs_f64() * 1000.0;
                     state
                         .runtime_metrics
@@ -10637,16 +12174,11 @@ pub async fn worker_poll(
                         },
                     }));
                 }
-                Err(err) => {
-                    let msg = err.to_string();
-                    if msg.contains("active lease already exists")
-                        || msg.contains("not dispatchable")
-                    {
-                        state.runtime_metrics.record_lease_conflict();
-                        continue;
-                    }
-                    return Err(ApiError::internal(msg).with_request_id(rid.clone()));
+                Err(KernelError::LeaseConflict(_) | KernelError::NotDispatchable(_)) => {
+                    state.runtime_metrics.record_lease_conflict();
+                    continue;
                 }
+                Err(err) => return Err(ApiError::from(err).with_request_id(rid.clone())),
             }
         }
 
@@ -10707,14 +12239,20 @@ pub async fn worker_heartbeat(
 
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?;
+        let repo = runtime_repo(&state, &headers, &rid)?;
         state.runtime_metrics.record_lease_operation();
         let lease = repo
             .get_lease_by_id(&req.lease_id)
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
-            .ok_or_else(|| ApiError::not_found("lease not found").with_request_id(rid.clone()))?;
+            .ok_or_else(|| {
+                #[cfg(feature = "metrics")]
+                ::metrics::counter!(crate::metrics::HEARTBEAT_FAILURES_TOTAL).increment(1);
+                ApiError::not_found("lease not found").with_request_id(rid.clone())
+            })?;
         if lease.worker_id != worker_id {
             state.runtime_metrics.record_lease_conflict();
+            #[cfg(feature = "metrics")]
+            ::metrics::counter!(crate::metrics::HEARTBEAT_FAILURES_TOTAL).increment(1);
             return Err(ApiError::conflict("lease ownership mismatch")
                 .with_request_id(rid.clone())
                 .with_details(serde_json::json!({
@@ -10725,18 +12263,26 @@ pub async fn worker_heartbeat(
         let ttl = req.lease_ttl_seconds.unwrap_or(30).max(1);
         let now = Utc::now();
         let expires = now + Duration::seconds(ttl);
-        if let Err(err) = repo.heartbeat_lease_with_version(
+        let directive = match AsyncRuntimeRepository::heartbeat_lease_with_version(
+            &repo,
             &req.lease_id,
             &worker_id,
             lease.version,
             now,
             expires,
-        ) {
-            if err.to_string().contains("lease heartbeat version conflict") {
-                state.runtime_metrics.record_lease_conflict();
+        )
+        .await
+        {
+            Ok(directive) => directive,
+            Err(err) => {
+                if matches!(err, KernelError::LeaseConflict(_)) {
+                    state.runtime_metrics.record_lease_conflict();
+                }
+                #[cfg(feature = "metrics")]
+                ::metrics::counter!(crate::metrics::HEARTBEAT_FAILURES_TOTAL).increment(1);
+                return Err(ApiError::from(err).with_request_id(rid.clone()));
             }
-            return Err(ApiError::internal(err.to_string()).with_request_id(rid.clone()));
-        }
+        };
         let trace = repo
             .advance_attempt_trace(&lease.attempt_id, &generate_span_id())
             .map_err(|e| ApiError::internal(e.to_string()).with_request_id(rid.clone()))?
@@ -10757,6 +12303,10 @@ pub async fn worker_heartbeat(
                 worker_id,
                 lease_id: req.lease_id,
                 lease_expires_at: expires.to_rfc3339(),
+                directive: match directive {
+                    LeaseDirective::Continue => "continue".to_string(),
+                    LeaseDirective::Cancel => "cancel".to_string(),
+                },
                 trace: trace.map(|ctx| ctx.to_response()),
             },
         }));
@@ -10806,7 +12356,7 @@ pub async fn worker_report_step(
 
     #[cfg(feature = "sqlite-persistence")]
     let (report_status, report_trace) = {
-        let repo = runtime_repo(&state, &rid)?;
+        let repo = runtime_repo(&state, &headers, &rid)?;
         match repo.record_step_report(
             &worker_id,
             &req.attempt_id,
@@ -10825,13 +12375,7 @@ pub async fn worker_report_step(
                 };
                 (status, trace)
             }
-            Err(e) => {
-                let msg = e.to_string();
-                if msg.contains("dedupe_token") {
-                    return Err(ApiError::conflict(msg).with_request_id(rid));
-                }
-                return Err(ApiError::internal(msg).with_request_id(rid));
-            }
+            Err(e) => return Err(ApiError::from(e).with_request_id(rid)),
         }
     };
 
@@ -10883,7 +12427,7 @@ pub async fn worker_ack(
 
     #[cfg(feature = "sqlite-persistence")]
     {
-        let repo = runtime_repo(&state, &rid)?;
+        let repo = runtime_repo(&state, &headers, &rid)?;
         let retry_policy = parse_retry_policy(req.retry_policy.as_ref(), &rid)?;
         let status = match req.terminal_status.as_str() {
             "completed" => AttemptExecutionStatus::Completed,
@@ -10912,13 +12456,14 @@ pub async fn worker_ack(
             AttemptExecutionStatus::Queued => "queued".to_string(),
             AttemptExecutionStatus::Leased => "leased".to_string(),
             AttemptExecutionStatus::Running => "running".to_string(),
+            AttemptExecutionStatus::DeadLetter => "dead_letter".to_string(),
         };
         let lifecycle_summary = format!("worker ack outcome: {}", response_status);
         match outcome.status {
             AttemptExecutionStatus::Completed => {
                 record_task_succeeded(&state, &req.attempt_id, lifecycle_summary.as_str()).await;
             }
-            AttemptExecutionStatus::Failed => {
+            AttemptExecutionStatus::Failed | AttemptExecutionStatus::DeadLetter => {
                 record_task_failed(
                     &state,
                     &req.attempt_id,
@@ -10997,6 +12542,25 @@ mod tests {
 
     use super::{build_router, ApiRole, ExecutionApiState};
 
+    /// Records `run_id`, which attempts must reference before they can be enqueued.
+    #[cfg(feature = "sqlite-persistence")]
+    fn seed_run(
+        repo: &crate::execution_runtime::sqlite_runtime_repository::SqliteRuntimeRepository,
+        run_id: &str,
+    ) {
+        let now = Utc::now();
+        repo.create_run(&crate::execution_runtime::models::RunRecord {
+            run_id: run_id.to_string(),
+            workflow_name: "test".to_string(),
+            status: crate::execution_runtime::models::RunRuntimeStatus::Queued,
+            status_reason: None,
+            tenant_id: None,
+            created_at: now,
+            updated_at: now,
+        })
+        .expect("seed run");
+    }
+
     async fn build_test_graph() -> Arc<crate::graph::CompiledGraph<MessagesState>> {
         let node = function_node("research", |_state: &MessagesState| async move {
             let mut update = HashMap::new();
@@ -16175,6 +17739,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-a2a-supervised-1");
         repo.enqueue_attempt("attempt-a2a-supervised-1", "run-a2a-supervised-1")
             .expect("enqueue supervised attempt");
         let router = build_router(state);
@@ -18145,6 +19710,252 @@ mod tests {
         assert_eq!(run_resp.status(), StatusCode::CONFLICT);
     }
 
+    #[tokio::test]
+    async fn dry_run_simulates_actions_and_guards_normal_resume() {
+        use crate::graph::request_action;
+        use crate::kernel::{Action, ActionResult, AllowAllPolicy, StubbedPolicy};
+
+        let published = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&published);
+        let publish = function_node("publish", move |_state: &MessagesState| {
+            let counter = Arc::clone(&counter);
+            async move {
+                let action = Action::CallTool {
+                    tool: "publish".into(),
+                    input: serde_json::json!("draft"),
+                    idempotency_key: None,
+                };
+                let receipt = match request_action(action)? {
+                    Some(receipt) => receipt,
+                    None => {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        serde_json::json!("published")
+                    }
+                };
+                let mut update = HashMap::new();
+                update.insert(
+                    "messages".to_string(),
+                    serde_json::to_value(vec![Message::new_ai_message(receipt.to_string())])
+                        .unwrap(),
+                );
+                Ok(update)
+            }
+        });
+        let confirm = function_node("confirm", |_state: &MessagesState| async move {
+            interrupt("confirm?")
+                .await
+                .map_err(GraphError::InterruptError)?;
+            Ok(HashMap::new())
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("publish", publish).unwrap();
+        graph.add_node("confirm", confirm).unwrap();
+        graph.add_edge(START, "publish");
+        graph.add_edge("publish", "confirm");
+        graph.add_edge("confirm", END);
+        let compiled = Arc::new(
+            graph
+                .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+                .unwrap(),
+        );
+        let policy = StubbedPolicy::new(AllowAllPolicy).with_tool_stub(
+            "publish",
+            ActionResult::Success(serde_json::json!("simulated receipt")),
+        );
+        let router =
+            build_router(ExecutionApiState::new(compiled).with_dry_run_policy(Arc::new(policy)));
+        let post = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let run_resp = router
+            .clone()
+            .oneshot(post(
+                "/v1/jobs/run",
+                serde_json::json!({"thread_id": "dry-1", "input": "go", "mode": "dry_run"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(run_resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["mode"], "dry_run");
+        assert_eq!(json["data"]["status"], "interrupted");
+        assert_eq!(published.load(Ordering::SeqCst), 0);
+
+        let state_resp = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/v1/jobs/dry-1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(state_resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("simulated receipt"));
+
+        let refused = router
+            .clone()
+            .oneshot(post(
+                "/v1/jobs/dry-1/resume",
+                serde_json::json!({"value": true}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(refused.status(), StatusCode::CONFLICT);
+
+        let resumed = router
+            .clone()
+            .oneshot(post(
+                "/v1/jobs/dry-1/resume",
+                serde_json::json!({"value": true, "allow_mode_change": true}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resumed.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resumed.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["mode"], "normal");
+        assert_eq!(json["data"]["status"], "completed");
+    }
+
+    #[tokio::test]
+    async fn pause_stops_a_run_in_flight_and_resume_continues_it() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        // The first and third runs of the node hang until they are stopped
+        let work = function_node("work", move |_state: &MessagesState| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if call % 2 == 0 {
+                    std::future::pending::<()>().await;
+                }
+                let mut update = HashMap::new();
+                update.insert(
+                    "messages".to_string(),
+                    serde_json::to_value(vec![Message::new_ai_message("done")]).unwrap(),
+                );
+                Ok(update)
+            }
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("work", work).unwrap();
+        graph.add_edge(START, "work");
+        graph.add_edge("work", END);
+        let compiled = Arc::new(
+            graph
+                .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+                .unwrap(),
+        );
+        let router = build_router(ExecutionApiState::new(compiled));
+        let post = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let data = |resp: axum::response::Response| async move {
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["data"].clone()
+        };
+        // Pauses the thread once its run is in flight
+        let pause_when_running = |uri: String| {
+            let router = router.clone();
+            async move {
+                for _ in 0..200 {
+                    let resp = router
+                        .clone()
+                        .oneshot(post(&uri, serde_json::json!({})))
+                        .await
+                        .unwrap();
+                    if resp.status() == StatusCode::OK {
+                        return;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+                panic!("{} never found the run in flight", uri);
+            }
+        };
+
+        let idle = router
+            .clone()
+            .oneshot(post("/v1/jobs/pause-1/pause", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(idle.status(), StatusCode::CONFLICT);
+
+        let run = tokio::spawn(router.clone().oneshot(post(
+            "/v1/jobs/run",
+            serde_json::json!({"thread_id": "pause-1", "input": "go"}),
+        )));
+        pause_when_running("/v1/jobs/pause-1/pause".into()).await;
+        let paused = run.await.unwrap().unwrap();
+        assert_eq!(paused.status(), StatusCode::OK);
+        assert_eq!(data(paused).await["status"], "paused");
+
+        let again = router
+            .clone()
+            .oneshot(post("/v1/jobs/pause-1/pause", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+        let resumed = router
+            .clone()
+            .oneshot(post("/v1/jobs/pause-1/resume", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(resumed.status(), StatusCode::OK);
+        assert_eq!(data(resumed).await["status"], "completed");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let run = tokio::spawn(router.clone().oneshot(post(
+            "/v1/jobs/run",
+            serde_json::json!({"thread_id": "cancel-1", "input": "go"}),
+        )));
+        // The node starts after the run registered as in flight
+        while calls.load(Ordering::SeqCst) < 3 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let cancelled = router
+            .clone()
+            .oneshot(post("/v1/jobs/cancel-1/cancel", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(cancelled.status(), StatusCode::OK);
+        let stopped = run.await.unwrap().unwrap();
+        assert_eq!(data(stopped).await["status"], "cancelled");
+        for uri in [
+            "/v1/jobs/cancel-1/cancel",
+            "/v1/jobs/cancel-1/pause",
+            "/v1/jobs/cancel-1/resume",
+        ] {
+            let resp = router
+                .clone()
+                .oneshot(post(uri, serde_json::json!({})))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::CONFLICT, "{}", uri);
+        }
+    }
+
     #[tokio::test]
     async fn timeline_and_checkpoint_inspect_work() {
         let router = build_router(ExecutionApiState::new(build_interrupt_graph().await));
@@ -18271,60 +20082,491 @@ mod tests {
         let resp1 = router.clone().oneshot(req1).await.unwrap();
         assert_eq!(resp1.status(), StatusCode::OK);
 
-        let req2 = Request::builder()
-            .method(Method::POST)
-            .uri("/v1/jobs/run")
-            .header("content-type", "application/json")
-            .body(Body::from(body))
-            .unwrap();
-        let resp2 = router.oneshot(req2).await.unwrap();
-        assert_eq!(resp2.status(), StatusCode::OK);
-        let body2 = axum::body::to_bytes(resp2.into_body(), usize::MAX)
-            .await
-            .expect("idempotent body");
-        let json2: serde_json::Value = serde_json::from_slice(&body2).expect("idempotent json");
-        assert_eq!(json2["data"]["idempotent_replay"], true);
+        let req2 = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let resp2 = router.oneshot(req2).await.unwrap();
+        assert_eq!(resp2.status(), StatusCode::OK);
+        let body2 = axum::body::to_bytes(resp2.into_body(), usize::MAX)
+            .await
+            .expect("idempotent body");
+        let json2: serde_json::Value = serde_json::from_slice(&body2).expect("idempotent json");
+        assert_eq!(json2["data"]["idempotent_replay"], true);
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn idempotent_run_payload_mismatch_conflicts() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+
+        let req1 = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "thread_id": "idem-run-2",
+                    "input": "hello-a",
+                    "idempotency_key": "idem-key-2"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let resp1 = router.clone().oneshot(req1).await.unwrap();
+        assert_eq!(resp1.status(), StatusCode::OK);
+
+        let req2 = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "thread_id": "idem-run-2",
+                    "input": "hello-b",
+                    "idempotency_key": "idem-key-2"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let resp2 = router.oneshot(req2).await.unwrap();
+        assert_eq!(resp2.status(), StatusCode::CONFLICT);
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn create_run_same_key_replays_original_run() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+        let body = serde_json::json!({
+            "graph": "default",
+            "input": "hello",
+            "idempotency_key": "create-run-key-1"
+        })
+        .to_string();
+        let create = |body: String| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/v1/runs")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let resp1 = router.clone().oneshot(create(body.clone())).await.unwrap();
+        assert_eq!(resp1.status(), StatusCode::CREATED);
+        let body1 = axum::body::to_bytes(resp1.into_body(), usize::MAX)
+            .await
+            .expect("create body");
+        let json1: serde_json::Value = serde_json::from_slice(&body1).expect("create json");
+        let run = &json1["data"];
+        assert!(run["run_id"].as_str().is_some_and(|id| !id.is_empty()));
+        assert_eq!(run["thread_id"], run["run_id"]);
+        assert!(run["checkpoint_id"].is_string());
+        assert_eq!(run["idempotent_replay"], false);
+
+        let resp2 = router.clone().oneshot(create(body)).await.unwrap();
+        assert_eq!(resp2.status(), StatusCode::OK);
+        let body2 = axum::body::to_bytes(resp2.into_body(), usize::MAX)
+            .await
+            .expect("replay body");
+        let json2: serde_json::Value = serde_json::from_slice(&body2).expect("replay json");
+        let replay = &json2["data"];
+        assert_eq!(replay["run_id"], run["run_id"]);
+        assert_eq!(replay["thread_id"], run["thread_id"]);
+        assert_eq!(replay["checkpoint_id"], run["checkpoint_id"]);
+        assert_eq!(replay["idempotent_replay"], true);
+
+        let mismatch = serde_json::json!({
+            "graph": "default",
+            "input": "hello-again",
+            "idempotency_key": "create-run-key-1"
+        })
+        .to_string();
+        let resp3 = router.oneshot(create(mismatch)).await.unwrap();
+        assert_eq!(resp3.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn create_run_invalid_fields_are_unprocessable() {
+        let router = build_router(ExecutionApiState::new(build_interrupt_graph().await));
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/runs")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "graph": "missing-graph",
+                    "input": {"text": "hello"},
+                    "mode": "turbo"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("error body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("error json");
+        assert_eq!(json["error"]["code"], "validation_failed");
+        let fields: Vec<&str> = json["error"]["details"]["fields"]
+            .as_array()
+            .expect("field errors")
+            .iter()
+            .filter_map(|field| field["field"].as_str())
+            .collect();
+        assert_eq!(fields, vec!["graph", "input", "mode"]);
+    }
+
+    #[tokio::test]
+    async fn create_run_malformed_body_is_unprocessable() {
+        let router = build_router(ExecutionApiState::new(build_interrupt_graph().await));
+        for (body, field) in [
+            (serde_json::json!({ "input": "hello" }), "graph"),
+            (
+                serde_json::json!({ "graph": "default", "input": "hello", "mode": 7 }),
+                "mode",
+            ),
+        ] {
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri("/v1/runs")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let resp = router.clone().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("error body");
+            let json: serde_json::Value = serde_json::from_slice(&body).expect("error json");
+            assert_eq!(json["error"]["code"], "validation_failed");
+            assert_eq!(json["error"]["details"]["fields"][0]["field"], field);
+        }
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/runs")
+            .header("content-type", "application/json")
+            .body(Body::from("{\"graph\":"))
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("error body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("error json");
+        assert_eq!(json["error"]["code"], "invalid_argument");
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn create_run_concurrent_retries_start_one_run() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let node = function_node("slow", move |_state: &MessagesState| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                Ok(HashMap::new())
+            }
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("slow", node).unwrap();
+        graph.add_edge(START, "slow");
+        graph.add_edge("slow", END);
+        let saver = Arc::new(InMemorySaver::new());
+        let compiled = Arc::new(graph.compile_with_persistence(Some(saver), None).unwrap());
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            compiled, ":memory:",
+        ));
+        let body = serde_json::json!({
+            "graph": "default",
+            "input": "hello",
+            "idempotency_key": "create-run-race-1"
+        });
+
+        let ((status_a, first_a), (status_b, first_b)) = tokio::join!(
+            post_run_json(&router, "/v1/runs", body.clone()),
+            post_run_json(&router, "/v1/runs", body.clone()),
+        );
+        let mut statuses = vec![status_a, status_b];
+        statuses.sort();
+        assert_eq!(statuses, vec![StatusCode::OK, StatusCode::CREATED]);
+        assert_eq!(first_a["data"]["run_id"], first_b["data"]["run_id"]);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        let (status, replay) = post_run_json(&router, "/v1/runs", body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replay["data"]["run_id"], first_a["data"]["run_id"]);
+        assert_eq!(replay["data"]["status"], "completed");
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn create_run_idempotency_keys_are_tenant_scoped() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+        let mut run_ids = Vec::new();
+        for tenant_id in ["tenant-a", "tenant-b"] {
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri("/v1/runs")
+                .header("content-type", "application/json")
+                .header("x-oris-tenant-id", tenant_id)
+                .body(Body::from(
+                    serde_json::json!({
+                        "graph": "default",
+                        "input": "hello",
+                        "idempotency_key": "shared-key"
+                    })
+                    .to_string(),
+                ))
+                .unwrap();
+            let resp = router.clone().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("create body");
+            let json: serde_json::Value = serde_json::from_slice(&body).expect("create json");
+            assert_eq!(json["data"]["idempotent_replay"], false);
+            run_ids.push(json["data"]["run_id"].clone());
+        }
+        assert_ne!(run_ids[0], run_ids[1]);
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    async fn post_run_json(
+        router: &axum::Router,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("run body");
+        (status, serde_json::from_slice(&body).expect("run json"))
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resume_run_answers_blocked_runs_once() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+        let (status, _) = post_run_json(
+            &router,
+            "/v1/jobs/run",
+            serde_json::json!({ "thread_id": "resume-run-1", "input": "hello" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let resume = serde_json::json!({ "value": true, "idempotency_key": "resume-key-1" });
+        let (status, first) =
+            post_run_json(&router, "/v1/runs/resume-run-1/resume", resume.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["data"]["run_id"], "resume-run-1");
+        assert_eq!(first["data"]["status"], "completed");
+        assert!(first["data"]["checkpoint_id"].is_string());
+        assert_eq!(first["data"]["idempotent_replay"], false);
+
+        let (status, retry) = post_run_json(&router, "/v1/runs/resume-run-1/resume", resume).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(retry["data"]["status"], "completed");
+        assert_eq!(
+            retry["data"]["checkpoint_id"],
+            first["data"]["checkpoint_id"]
+        );
+        assert_eq!(retry["data"]["idempotent_replay"], true);
+
+        let (status, not_blocked) = post_run_json(
+            &router,
+            "/v1/runs/resume-run-1/resume",
+            serde_json::json!({ "value": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(not_blocked["error"]["details"]["status"], "completed");
+
+        let (status, terminal) = post_run_json(
+            &router,
+            "/v1/runs/resume-run-1/cancel",
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(terminal["error"]["details"]["status"], "completed");
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resume_run_concurrent_retries_resume_once() {
+        let resumes = Arc::new(AtomicUsize::new(0));
+        let counter = resumes.clone();
+        let node = function_node("approval", move |_state: &MessagesState| {
+            let counter = counter.clone();
+            async move {
+                interrupt("approve?")
+                    .await
+                    .map_err(GraphError::InterruptError)?;
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                Ok(HashMap::new())
+            }
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("approval", node).unwrap();
+        graph.add_edge(START, "approval");
+        graph.add_edge("approval", END);
+        let saver = Arc::new(InMemorySaver::new());
+        let compiled = Arc::new(graph.compile_with_persistence(Some(saver), None).unwrap());
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            compiled, ":memory:",
+        ));
+        let (status, _) = post_run_json(
+            &router,
+            "/v1/jobs/run",
+            serde_json::json!({ "thread_id": "resume-race-1", "input": "hello" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let resume = serde_json::json!({ "value": true, "idempotency_key": "resume-race-key" });
+        let ((status_a, first_a), (status_b, first_b)) = tokio::join!(
+            post_run_json(&router, "/v1/runs/resume-race-1/resume", resume.clone()),
+            post_run_json(&router, "/v1/runs/resume-race-1/resume", resume.clone()),
+        );
+        assert_eq!(status_a, StatusCode::OK);
+        assert_eq!(status_b, StatusCode::OK);
+        let mut replays = vec![
+            first_a["data"]["idempotent_replay"].as_bool().unwrap(),
+            first_b["data"]["idempotent_replay"].as_bool().unwrap(),
+        ];
+        replays.sort();
+        assert_eq!(replays, vec![false, true]);
+        assert_eq!(resumes.load(Ordering::SeqCst), 1);
+
+        let (status, replay) =
+            post_run_json(&router, "/v1/runs/resume-race-1/resume", resume).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replay["data"]["status"], "completed");
+        assert_eq!(replay["data"]["idempotent_replay"], true);
+        assert_eq!(resumes.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resume_run_idempotency_keys_are_tenant_scoped() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+        let tenant_request = |uri: &str, tenant_id: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("x-oris-tenant-id", tenant_id)
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        for tenant_id in ["tenant-a", "tenant-b"] {
+            let thread_id = format!("resume-{}", tenant_id);
+            let resp = router
+                .clone()
+                .oneshot(tenant_request(
+                    "/v1/jobs/run",
+                    tenant_id,
+                    serde_json::json!({ "thread_id": thread_id, "input": "hello" }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let resp = router
+                .clone()
+                .oneshot(tenant_request(
+                    &format!("/v1/runs/{}/resume", thread_id),
+                    tenant_id,
+                    serde_json::json!({ "value": true, "idempotency_key": "shared-key" }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("resume body");
+            let json: serde_json::Value = serde_json::from_slice(&body).expect("resume json");
+            assert_eq!(json["data"]["run_id"], thread_id);
+            assert_eq!(json["data"]["status"], "completed");
+            assert_eq!(json["data"]["idempotent_replay"], false);
+        }
     }
 
     #[cfg(feature = "sqlite-persistence")]
     #[tokio::test]
-    async fn idempotent_run_payload_mismatch_conflicts() {
+    async fn cancel_run_is_idempotent_and_blocks_resume() {
         let router = build_router(ExecutionApiState::with_sqlite_idempotency(
             build_interrupt_graph().await,
             ":memory:",
         ));
+        let (status, _) = post_run_json(
+            &router,
+            "/v1/jobs/run",
+            serde_json::json!({ "thread_id": "cancel-run-1", "input": "hello" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
 
-        let req1 = Request::builder()
-            .method(Method::POST)
-            .uri("/v1/jobs/run")
-            .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::json!({
-                    "thread_id": "idem-run-2",
-                    "input": "hello-a",
-                    "idempotency_key": "idem-key-2"
-                })
-                .to_string(),
-            ))
-            .unwrap();
-        let resp1 = router.clone().oneshot(req1).await.unwrap();
-        assert_eq!(resp1.status(), StatusCode::OK);
+        let cancel = serde_json::json!({ "reason": "runaway" });
+        let (status, first) =
+            post_run_json(&router, "/v1/runs/cancel-run-1/cancel", cancel.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["data"]["status"], "cancelled");
+        assert_eq!(first["data"]["idempotent_replay"], false);
 
-        let req2 = Request::builder()
-            .method(Method::POST)
-            .uri("/v1/jobs/run")
-            .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::json!({
-                    "thread_id": "idem-run-2",
-                    "input": "hello-b",
-                    "idempotency_key": "idem-key-2"
-                })
-                .to_string(),
-            ))
-            .unwrap();
-        let resp2 = router.oneshot(req2).await.unwrap();
-        assert_eq!(resp2.status(), StatusCode::CONFLICT);
+        let (status, retry) = post_run_json(&router, "/v1/runs/cancel-run-1/cancel", cancel).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(retry["data"]["status"], "cancelled");
+        assert_eq!(retry["data"]["idempotent_replay"], true);
+
+        let (status, resumed) = post_run_json(
+            &router,
+            "/v1/runs/cancel-run-1/resume",
+            serde_json::json!({ "value": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(resumed["error"]["details"]["status"], "cancelled");
+
+        let (status, _) = post_run_json(
+            &router,
+            "/v1/runs/missing-run/cancel",
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
@@ -18367,7 +20609,7 @@ mod tests {
             .unwrap();
         let run_resp = router.clone().oneshot(run_req).await.unwrap();
         assert_eq!(run_resp.status(), StatusCode::OK);
-        let interrupt_id = "int-resume-idem-1-0";
+        let interrupt_id = pending_interrupt_id(&router, "resume-idem-1").await;
 
         let first_req = Request::builder()
             .method(Method::POST)
@@ -18420,7 +20662,7 @@ mod tests {
             .unwrap();
         let run_resp = router.clone().oneshot(run_req).await.unwrap();
         assert_eq!(run_resp.status(), StatusCode::OK);
-        let interrupt_id = "int-resume-idem-2-0";
+        let interrupt_id = pending_interrupt_id(&router, "resume-idem-2").await;
 
         let first_req = Request::builder()
             .method(Method::POST)
@@ -18513,6 +20755,72 @@ mod tests {
         assert_eq!(replay_effects[0].execution_count, 1);
     }
 
+    #[tokio::test]
+    async fn replay_job_applies_appended_messages_once() {
+        let draft = function_node("draft", |_state: &MessagesState| async move {
+            let mut update = HashMap::new();
+            update.insert(
+                "messages".to_string(),
+                serde_json::to_value(vec![Message::new_ai_message("draft")]).unwrap(),
+            );
+            Ok(update)
+        });
+        let review = function_node("review", |_state: &MessagesState| async move {
+            let _ = interrupt("review draft")
+                .await
+                .map_err(GraphError::InterruptError)?;
+            Ok(HashMap::new())
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("draft", draft).unwrap();
+        graph.add_node("review", review).unwrap();
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "review");
+        graph.add_edge("review", END);
+        let saver = Arc::new(InMemorySaver::new());
+        let compiled = Arc::new(graph.compile_with_persistence(Some(saver), None).unwrap());
+        let router = build_router(ExecutionApiState::new(compiled.clone()));
+
+        let run_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "thread_id": "replay-append-1",
+                    "input": "seed"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let run_resp = router.clone().oneshot(run_req).await.unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+
+        let replay_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/replay-append-1/replay")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::json!({}).to_string()))
+            .unwrap();
+        let replay_resp = router.oneshot(replay_req).await.unwrap();
+        assert_eq!(replay_resp.status(), StatusCode::OK);
+
+        let snapshot = compiled
+            .get_state(&crate::graph::RunnableConfig::with_thread_id(
+                "replay-append-1",
+            ))
+            .await
+            .expect("state after replay");
+        let contents: Vec<&str> = snapshot
+            .values
+            .messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["seed", "draft"]);
+        assert_eq!(snapshot.next, vec!["review".to_string()]);
+    }
+
     #[cfg(feature = "sqlite-persistence")]
     #[tokio::test]
     async fn worker_report_step_dedupe_is_enforced() {
@@ -18588,6 +20896,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-worker-1");
         repo.enqueue_attempt("attempt-worker-1", "run-worker-1")
             .expect("enqueue");
         let router = build_router(state);
@@ -18867,6 +21176,7 @@ mod tests {
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:")
                 .with_static_api_key("metrics-key");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-metrics-1");
         repo.enqueue_attempt("attempt-metrics-a", "run-metrics-1")
             .expect("enqueue a");
         repo.enqueue_attempt("attempt-metrics-b", "run-metrics-1")
@@ -19581,6 +21891,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-worker-retry-1");
         repo.enqueue_attempt("attempt-worker-retry-1", "run-worker-retry-1")
             .expect("enqueue retry attempt");
         let router = build_router(state);
@@ -19666,6 +21977,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-worker-timeout-1");
         repo.enqueue_attempt("attempt-worker-timeout-1", "run-worker-timeout-1")
             .expect("enqueue timeout attempt");
         repo.set_attempt_timeout_policy(
@@ -19735,6 +22047,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-dlq-api-1");
         repo.enqueue_attempt("attempt-dlq-api-1", "run-dlq-api-1")
             .expect("enqueue dlq api attempt");
         let router = build_router(state);
@@ -19857,12 +22170,139 @@ mod tests {
         assert_eq!(dlq_row.replay_count, 1);
     }
 
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn attempt_cancel_api_cancels_queued_and_signals_leased_attempts() {
+        let state =
+            ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
+        let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-cancel-api");
+        repo.enqueue_attempt("attempt-cancel-api-leased", "run-cancel-api")
+            .expect("enqueue leased attempt");
+        let router = build_router(state);
+        let post_json = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let cancel = |attempt_id: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/v1/attempts/{}/cancel", attempt_id))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let poll_resp = router
+            .clone()
+            .oneshot(post_json(
+                "/v1/workers/poll",
+                serde_json::json!({ "worker_id": "worker-cancel-api" }),
+            ))
+            .await
+            .unwrap();
+        let poll_body = axum::body::to_bytes(poll_resp.into_body(), usize::MAX)
+            .await
+            .expect("poll body");
+        let poll_json: serde_json::Value = serde_json::from_slice(&poll_body).expect("poll json");
+        let lease_id = poll_json["data"]["lease_id"]
+            .as_str()
+            .expect("lease id")
+            .to_string();
+
+        let cancel_resp = router
+            .clone()
+            .oneshot(cancel("attempt-cancel-api-leased"))
+            .await
+            .unwrap();
+        assert_eq!(cancel_resp.status(), StatusCode::OK);
+        let cancel_body = axum::body::to_bytes(cancel_resp.into_body(), usize::MAX)
+            .await
+            .expect("cancel body");
+        let cancel_json: serde_json::Value =
+            serde_json::from_slice(&cancel_body).expect("cancel json");
+        assert_eq!(cancel_json["data"]["outcome"], "cancel_requested");
+        assert!(cancel_json["data"]["status"].is_null());
+
+        // The worker learns of the cancel on its next heartbeat and acks the attempt
+        let heartbeat_resp = router
+            .clone()
+            .oneshot(post_json(
+                "/v1/workers/worker-cancel-api/heartbeat",
+                serde_json::json!({ "lease_id": lease_id }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(heartbeat_resp.status(), StatusCode::OK);
+        let heartbeat_body = axum::body::to_bytes(heartbeat_resp.into_body(), usize::MAX)
+            .await
+            .expect("heartbeat body");
+        let heartbeat_json: serde_json::Value =
+            serde_json::from_slice(&heartbeat_body).expect("heartbeat json");
+        assert_eq!(heartbeat_json["data"]["directive"], "cancel");
+        let ack_resp = router
+            .clone()
+            .oneshot(post_json(
+                "/v1/workers/worker-cancel-api/ack",
+                serde_json::json!({
+                    "attempt_id": "attempt-cancel-api-leased",
+                    "terminal_status": "cancelled"
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(ack_resp.status(), StatusCode::OK);
+
+        let again_resp = router
+            .clone()
+            .oneshot(cancel("attempt-cancel-api-leased"))
+            .await
+            .unwrap();
+        let again_body = axum::body::to_bytes(again_resp.into_body(), usize::MAX)
+            .await
+            .expect("second cancel body");
+        let again_json: serde_json::Value =
+            serde_json::from_slice(&again_body).expect("second cancel json");
+        assert_eq!(again_json["data"]["outcome"], "already_finished");
+        assert_eq!(again_json["data"]["status"], "cancelled");
+
+        repo.enqueue_attempt("attempt-cancel-api-queued", "run-cancel-api")
+            .expect("enqueue queued attempt");
+        let queued_resp = router
+            .clone()
+            .oneshot(cancel("attempt-cancel-api-queued"))
+            .await
+            .unwrap();
+        let queued_body = axum::body::to_bytes(queued_resp.into_body(), usize::MAX)
+            .await
+            .expect("queued cancel body");
+        let queued_json: serde_json::Value =
+            serde_json::from_slice(&queued_body).expect("queued cancel json");
+        assert_eq!(queued_json["data"]["outcome"], "cancelled");
+        let (_, status) = repo
+            .get_attempt_status("attempt-cancel-api-queued")
+            .expect("read attempt status")
+            .expect("attempt exists");
+        assert_eq!(status, AttemptExecutionStatus::Cancelled);
+
+        let missing_resp = router
+            .clone()
+            .oneshot(cancel("attempt-cancel-api-missing"))
+            .await
+            .unwrap();
+        assert_eq!(missing_resp.status(), StatusCode::NOT_FOUND);
+    }
+
     #[cfg(feature = "sqlite-persistence")]
     #[tokio::test]
     async fn worker_poll_prefers_higher_priority_attempts() {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-priority-api");
         repo.enqueue_attempt("attempt-priority-low", "run-priority-api")
             .expect("enqueue low priority");
         repo.enqueue_attempt("attempt-priority-high", "run-priority-api")
@@ -19920,6 +22360,43 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn runtime_stats_reports_queue_depth_and_leases() {
+        let state =
+            ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
+        let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-stats-api");
+        for attempt_id in ["attempt-stats-api-1", "attempt-stats-api-2"] {
+            repo.enqueue_attempt(attempt_id, "run-stats-api")
+                .expect("enqueue stats attempt");
+        }
+        repo.upsert_lease(
+            "attempt-stats-api-1",
+            "worker-stats-api",
+            Utc::now() + Duration::seconds(30),
+        )
+        .expect("lease stats attempt");
+        let router = build_router(state);
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/runtime/stats")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("runtime stats body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("runtime stats json");
+        assert_eq!(json["data"]["attempts"]["queued"], 1);
+        assert_eq!(json["data"]["attempts"]["leased"], 1);
+        assert_eq!(json["data"]["active_leases"], 1);
+        assert_eq!(json["data"]["retry_backlog"], 0);
+        assert!(json["data"]["oldest_queued_age_ms"].is_i64());
+    }
+
     #[cfg(feature = "sqlite-persistence")]
     #[tokio::test]
     async fn auth_worker_role_cannot_access_dlq_endpoints() {
@@ -19932,6 +22409,7 @@ mod tests {
                     ApiRole::Worker,
                 );
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-dlq-rbac");
         repo.enqueue_attempt("attempt-dlq-rbac", "run-dlq-rbac")
             .expect("enqueue dlq rbac attempt");
         repo.ack_attempt(
@@ -19976,6 +22454,7 @@ mod tests {
                     ApiRole::Worker,
                 );
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-worker-retry-rbac");
         repo.enqueue_attempt("attempt-worker-retry-rbac", "run-worker-retry-rbac")
             .expect("enqueue retry rbac attempt");
         let router = build_router(state);
@@ -19997,6 +22476,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-worker-2");
         repo.enqueue_attempt("attempt-worker-2a", "run-worker-2")
             .expect("enqueue");
         repo.enqueue_attempt("attempt-worker-2b", "run-worker-2")
@@ -20101,6 +22581,7 @@ mod tests {
         let state =
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
+        seed_run(&repo, "run-tenant-1");
         repo.enqueue_attempt("attempt-tenant-1a", "run-tenant-1")
             .expect("enqueue tenant attempt a");
         repo.enqueue_attempt("attempt-tenant-1b", "run-tenant-1")
@@ -20159,6 +22640,158 @@ mod tests {
         assert_eq!(second_poll_json["data"]["tenant_limit"], 1);
     }
 
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn multi_tenancy_requires_a_tenant_and_hides_other_tenants() {
+        let state =
+            ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:")
+                .with_multi_tenancy(true);
+        let repo = state
+            .runtime_repo
+            .clone()
+            .expect("runtime repo")
+            .with_tenant("tenant-a");
+        seed_run(&repo, "run-tenant-a");
+        repo.enqueue_attempt("attempt-tenant-a", "run-tenant-a")
+            .expect("enqueue tenant a attempt");
+        let router = build_router(state);
+
+        let poll = |tenant_id: Option<&str>| {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri("/v1/workers/poll")
+                .header("content-type", "application/json");
+            if let Some(tenant_id) = tenant_id {
+                builder = builder.header("x-oris-tenant-id", tenant_id);
+            }
+            builder
+                .body(Body::from(
+                    serde_json::json!({ "worker_id": "worker-tenant" }).to_string(),
+                ))
+                .unwrap()
+        };
+        let missing_resp = router.clone().oneshot(poll(None)).await.unwrap();
+        assert_eq!(missing_resp.status(), StatusCode::BAD_REQUEST);
+
+        let list_jobs = |tenant_id: &str| {
+            Request::builder()
+                .method(Method::GET)
+                .uri("/v1/jobs")
+                .header("x-oris-tenant-id", tenant_id)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let other_jobs_resp = router.clone().oneshot(list_jobs("tenant-b")).await.unwrap();
+        assert_eq!(other_jobs_resp.status(), StatusCode::OK);
+        let other_jobs_body = axum::body::to_bytes(other_jobs_resp.into_body(), usize::MAX)
+            .await
+            .expect("other tenant jobs body");
+        let other_jobs_json: serde_json::Value =
+            serde_json::from_slice(&other_jobs_body).expect("other tenant jobs json");
+        assert_eq!(other_jobs_json["data"]["jobs"], serde_json::json!([]));
+
+        let own_jobs_resp = router.clone().oneshot(list_jobs("tenant-a")).await.unwrap();
+        let own_jobs_body = axum::body::to_bytes(own_jobs_resp.into_body(), usize::MAX)
+            .await
+            .expect("own tenant jobs body");
+        let own_jobs_json: serde_json::Value =
+            serde_json::from_slice(&own_jobs_body).expect("own tenant jobs json");
+        assert_eq!(own_jobs_json["data"]["jobs"][0]["thread_id"], "run-tenant-a");
+
+        let inspect_req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/jobs/run-tenant-a")
+            .header("x-oris-tenant-id", "tenant-b")
+            .body(Body::empty())
+            .unwrap();
+        let inspect_resp = router.clone().oneshot(inspect_req).await.unwrap();
+        assert_eq!(inspect_resp.status(), StatusCode::NOT_FOUND);
+
+        let other_poll_resp = router
+            .clone()
+            .oneshot(poll(Some("tenant-b")))
+            .await
+            .unwrap();
+        let other_poll_body = axum::body::to_bytes(other_poll_resp.into_body(), usize::MAX)
+            .await
+            .expect("other tenant poll body");
+        let other_poll_json: serde_json::Value =
+            serde_json::from_slice(&other_poll_body).expect("other tenant poll json");
+        assert_eq!(other_poll_json["data"]["decision"], "noop");
+
+        let own_poll_resp = router.oneshot(poll(Some("tenant-a"))).await.unwrap();
+        let own_poll_body = axum::body::to_bytes(own_poll_resp.into_body(), usize::MAX)
+            .await
+            .expect("own tenant poll body");
+        let own_poll_json: serde_json::Value =
+            serde_json::from_slice(&own_poll_body).expect("own tenant poll json");
+        assert_eq!(own_poll_json["data"]["decision"], "dispatched");
+        assert_eq!(own_poll_json["data"]["attempt_id"], "attempt-tenant-a");
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn multi_tenancy_hides_jobs_without_a_tenant() {
+        // Jobs paused at an interrupt before the server tracked runs or their tenants
+        let graph = build_interrupt_graph().await;
+        for thread_id in ["legacy-job", "unrecorded-job"] {
+            let config = crate::graph::RunnableConfig::with_thread_id(thread_id);
+            graph
+                .invoke_with_config_interrupt(
+                    crate::graph::StateOrCommand::State(MessagesState::with_messages(vec![
+                        Message::new_human_message("hello"),
+                    ])),
+                    &config,
+                )
+                .await
+                .expect("run job outside the api");
+            graph.get_state(&config).await.expect("job state");
+        }
+        let state =
+            ExecutionApiState::with_sqlite_idempotency(graph, ":memory:").with_multi_tenancy(true);
+        seed_run(
+            state.runtime_repo.as_ref().expect("runtime repo"),
+            "legacy-job",
+        );
+        let router = build_router(state);
+
+        let get = |uri: String| {
+            Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .header("x-oris-tenant-id", "tenant-a")
+                .body(Body::empty())
+                .unwrap()
+        };
+        for thread_id in ["legacy-job", "unrecorded-job"] {
+            for uri in [
+                format!("/v1/jobs/{}", thread_id),
+                format!("/v1/jobs/{}/history", thread_id),
+            ] {
+                let resp = router.clone().oneshot(get(uri.clone())).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", uri);
+            }
+        }
+
+        // A tenant can still start a new job and then sees it
+        let run_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .header("x-oris-tenant-id", "tenant-a")
+            .body(Body::from(
+                serde_json::json!({ "thread_id": "tenant-job", "input": "hello" }).to_string(),
+            ))
+            .unwrap();
+        let run_resp = router.clone().oneshot(run_req).await.unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+        let inspect_resp = router
+            .oneshot(get("/v1/jobs/tenant-job".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(inspect_resp.status(), StatusCode::OK);
+    }
+
     #[cfg(feature = "sqlite-persistence")]
     #[derive(Debug, Default)]
     struct SchedulerStressBaseline {
@@ -20285,6 +22918,9 @@ mod tests {
             ExecutionApiState::with_sqlite_idempotency(build_test_graph().await, ":memory:");
         let repo = state.runtime_repo.clone().expect("runtime repo");
         for i in 0..iterations {
+            if i % 2 == 0 {
+                seed_run(&repo, &format!("run-stress-{}", i / 2));
+            }
             repo.enqueue_attempt(
                 &format!("attempt-stress-{i}"),
                 &format!("run-stress-{}", i / 2),
@@ -20448,7 +23084,7 @@ mod tests {
             .uri("/v1/jobs?limit=10&offset=0")
             .body(Body::empty())
             .unwrap();
-        let list_resp = router.oneshot(list_req).await.unwrap();
+        let list_resp = router.clone().oneshot(list_req).await.unwrap();
         assert_eq!(list_resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(list_resp.into_body(), usize::MAX)
             .await
@@ -20457,6 +23093,301 @@ mod tests {
         let jobs = json["data"]["jobs"].as_array().unwrap();
         assert!(!jobs.is_empty());
         assert_eq!(jobs[0]["thread_id"], "list-job-1");
+        assert_eq!(jobs[0]["status"], "interrupted");
+        assert_eq!(jobs[0]["workflow"], "execution_server");
+
+        let filtered_req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/jobs?status=completed&workflow=execution_server")
+            .body(Body::empty())
+            .unwrap();
+        let filtered_resp = router.clone().oneshot(filtered_req).await.unwrap();
+        assert_eq!(filtered_resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(filtered_resp.into_body(), usize::MAX)
+            .await
+            .expect("filtered jobs body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("filtered jobs json");
+        assert!(json["data"]["jobs"].as_array().unwrap().is_empty());
+
+        let bad_req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/jobs?status=bogus")
+            .body(Body::empty())
+            .unwrap();
+        let bad_resp = router.oneshot(bad_req).await.unwrap();
+        assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn runs_summary_counts_kernel_runs_by_status() {
+        use crate::kernel::{Event, EventStore, InMemoryEventStore};
+
+        let events = Arc::new(InMemoryEventStore::new());
+        events
+            .append(
+                &"blocked-run".to_string(),
+                &[Event::Interrupted {
+                    value: serde_json::json!("approve?"),
+                }],
+            )
+            .unwrap();
+        for run_id in ["failed-1", "failed-2"] {
+            events
+                .append(
+                    &run_id.to_string(),
+                    &[Event::Failed {
+                        reason: "too slow".into(),
+                        code: Some("deadline_exceeded".into()),
+                    }],
+                )
+                .unwrap();
+        }
+        events
+            .append(&"done".to_string(), &[Event::Completed])
+            .unwrap();
+        let router = build_router(
+            ExecutionApiState::new(build_test_graph().await).with_kernel_event_store(events),
+        );
+
+        let get_json = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap();
+                let resp = router.oneshot(req).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .expect("runs summary body");
+                serde_json::from_slice::<serde_json::Value>(&body).expect("runs summary json")
+            }
+        };
+
+        let all = get_json("/v1/runs/summary").await;
+        assert_eq!(all["data"]["total"], 4);
+        assert_eq!(
+            all["data"]["by_status"],
+            serde_json::json!({ "blocked": 1, "failed": 2, "completed": 1 })
+        );
+        assert_eq!(all["data"]["oldest_blocked"][0]["run_id"], "blocked-run");
+        assert_eq!(
+            all["data"]["failure_reasons"],
+            serde_json::json!([{ "code": "deadline_exceeded", "runs": 2 }])
+        );
+
+        let failed = get_json("/v1/runs/summary?status=failed").await;
+        assert_eq!(failed["data"]["total"], 2);
+    }
+
+    fn sse_step(n: u64) -> crate::kernel::Event {
+        crate::kernel::Event::StateUpdated {
+            step_id: Some(format!("step-{}", n)),
+            payload: serde_json::json!(n),
+            state_hash: None,
+            merge_report: None,
+        }
+    }
+
+    fn sse_ids(body: &str) -> Vec<&str> {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("id: "))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn run_events_stream_replays_then_tails_until_done() {
+        use crate::kernel::{Event, EventStore, InMemoryEventStore};
+
+        let events = Arc::new(InMemoryEventStore::new());
+        let run_id = "streamed-run".to_string();
+        events.append(&run_id, &[sse_step(1), sse_step(2)]).unwrap();
+        let router = build_router(
+            ExecutionApiState::new(build_test_graph().await)
+                .with_watchable_kernel_event_store(events.clone()),
+        );
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            events
+                .append(&run_id, &[sse_step(3), Event::Completed])
+                .unwrap();
+        });
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/runs/streamed-run/events?from_seq=2")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/event-stream");
+        let body = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            axum::body::to_bytes(resp.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stream ends after the run completes")
+        .expect("stream body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8 stream");
+        writer.await.unwrap();
+
+        assert_eq!(sse_ids(&body), vec!["2", "3", "4"]);
+        assert!(body.contains("event: StateUpdated"));
+        assert!(body.contains("event: Completed"));
+        assert!(body.trim_end().ends_with(
+            "event: done\ndata: {\"run_id\":\"streamed-run\",\"status\":\"completed\"}"
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_events_stream_resumes_after_last_event_id() {
+        use crate::kernel::{Event, EventStore, InMemoryEventStore};
+
+        let events = Arc::new(InMemoryEventStore::new());
+        events
+            .append(
+                &"polled-run".to_string(),
+                &[
+                    sse_step(1),
+                    sse_step(2),
+                    Event::Cancelled {
+                        reason: Some("operator".into()),
+                    },
+                ],
+            )
+            .unwrap();
+        // Not subscribed to: the stream polls the store
+        let router = build_router(
+            ExecutionApiState::new(build_test_graph().await).with_kernel_event_store(events),
+        );
+        let stream = |last_event_id: &'static str| {
+            let router = router.clone();
+            async move {
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri("/v1/runs/polled-run/events?from_seq=1")
+                    .header("last-event-id", last_event_id)
+                    .body(Body::empty())
+                    .unwrap();
+                let resp = router.oneshot(req).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .expect("stream body");
+                String::from_utf8(body.to_vec()).expect("utf8 stream")
+            }
+        };
+
+        let resumed = stream("1").await;
+        assert_eq!(sse_ids(&resumed), vec!["2", "3"]);
+        assert!(resumed.contains("\"status\":\"cancelled\""));
+
+        let caught_up = stream("3").await;
+        assert!(sse_ids(&caught_up).is_empty());
+        assert!(caught_up.starts_with("event: done\n"));
+    }
+
+    #[tokio::test]
+    async fn run_events_stream_rejects_unknown_runs() {
+        let events = Arc::new(crate::kernel::InMemoryEventStore::new());
+        let router = build_router(
+            ExecutionApiState::new(build_test_graph().await)
+                .with_watchable_kernel_event_store(events),
+        );
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/runs/missing-run/events")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn run_events_stream_defaults_to_the_graph_event_store() {
+        let node = function_node("answer", |_state: &MessagesState| async move {
+            Ok(crate::graph::messages_state_update(vec![
+                Message::new_ai_message("done"),
+            ]))
+        });
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("answer", node).unwrap();
+        graph.add_edge(START, "answer");
+        graph.add_edge("answer", END);
+        let saver = Arc::new(InMemorySaver::new());
+        let compiled = graph
+            .compile_with_persistence(Some(saver), None)
+            .unwrap()
+            .with_event_store(Arc::new(crate::kernel::InMemoryEventStore::new()));
+        let router = build_router(ExecutionApiState::new(Arc::new(compiled)));
+
+        let run_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "thread_id": "graph-events-1", "input": "hi" }).to_string(),
+            ))
+            .unwrap();
+        let run_resp = router.clone().oneshot(run_req).await.unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/runs/graph-events-1/events")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            axum::body::to_bytes(resp.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stream ends for a completed run")
+        .expect("stream body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8 stream");
+        assert!(body.contains("event: Completed"));
+        assert!(body.contains("\"status\":\"completed\""));
+    }
+
+    #[tokio::test]
+    async fn run_events_stream_without_event_store_is_not_implemented() {
+        let router = build_router(ExecutionApiState::new(build_test_graph().await));
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/runs/any-run/events")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("error body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("error json");
+        assert_eq!(json["error"]["code"], "not_implemented");
+    }
+
+    /// The id of the latest pending interrupt of a run, as listed by the interrupt inbox.
+    #[cfg(feature = "sqlite-persistence")]
+    async fn pending_interrupt_id(router: &axum::Router, run_id: &str) -> String {
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/interrupts?status=pending&run_id={}", run_id))
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("list interrupts body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("list interrupts json");
+        json["data"]["interrupts"][0]["interrupt_id"]
+            .as_str()
+            .expect("pending interrupt")
+            .to_string()
     }
 
     #[cfg(feature = "sqlite-persistence")]
@@ -20523,7 +23454,7 @@ mod tests {
         let run_json: serde_json::Value = serde_json::from_slice(&run_body).expect("run json");
         let interrupts = run_json["data"]["interrupts"].as_array().unwrap();
         assert!(!interrupts.is_empty());
-        let interrupt_id = "int-resume-int-1-0";
+        let interrupt_id = pending_interrupt_id(&router, "resume-int-1").await;
 
         let resume_req = Request::builder()
             .method(Method::POST)
@@ -20562,7 +23493,7 @@ mod tests {
         let run_json: serde_json::Value = serde_json::from_slice(&run_body).expect("run json");
         let interrupts = run_json["data"]["interrupts"].as_array().unwrap();
         assert!(!interrupts.is_empty());
-        let interrupt_id = "int-reject-int-1-0";
+        let interrupt_id = pending_interrupt_id(&router, "reject-int-1").await;
 
         let reject_req = Request::builder()
             .method(Method::POST)
@@ -20574,6 +23505,174 @@ mod tests {
         assert_eq!(reject_resp.status(), StatusCode::OK);
     }
 
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resolve_interrupt_resumes_once_then_conflicts() {
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            build_interrupt_graph().await,
+            ":memory:",
+        ));
+        let run_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "thread_id": "resolve-int-1",
+                    "input": "trigger interrupt"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let run_resp = router.clone().oneshot(run_req).await.unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+        let interrupt_id = pending_interrupt_id(&router, "resolve-int-1").await;
+
+        let resolve = |decision: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/v1/interrupts/{}/resolve", interrupt_id))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "decision": decision, "value": true }).to_string(),
+                ))
+                .unwrap()
+        };
+        let resolve_resp = router.clone().oneshot(resolve("approve")).await.unwrap();
+        assert_eq!(resolve_resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resolve_resp.into_body(), usize::MAX)
+            .await
+            .expect("resolve body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("resolve json");
+        assert_eq!(json["data"]["status"], "resumed");
+        assert_eq!(json["data"]["decision"], "approve");
+        assert_eq!(json["data"]["run"]["thread_id"], "resolve-int-1");
+
+        let again = router.clone().oneshot(resolve("reject")).await.unwrap();
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+
+        let list_req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/interrupts?status=pending&run_id=resolve-int-1")
+            .body(Body::empty())
+            .unwrap();
+        let list_resp = router.oneshot(list_req).await.unwrap();
+        let body = axum::body::to_bytes(list_resp.into_body(), usize::MAX)
+            .await
+            .expect("list interrupts body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("list interrupts json");
+        assert!(json["data"]["interrupts"].as_array().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resolve_interrupt_reject_cancels_run() {
+        let state =
+            ExecutionApiState::with_sqlite_idempotency(build_interrupt_graph().await, ":memory:");
+        let router = build_router(state.clone());
+        let run_req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "thread_id": "resolve-int-2",
+                    "input": "trigger interrupt"
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let run_resp = router.clone().oneshot(run_req).await.unwrap();
+        assert_eq!(run_resp.status(), StatusCode::OK);
+        let interrupt_id = pending_interrupt_id(&router, "resolve-int-2").await;
+
+        let resolve_req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/v1/interrupts/{}/resolve", interrupt_id))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "decision": "reject" }).to_string(),
+            ))
+            .unwrap();
+        let resolve_resp = router.oneshot(resolve_req).await.unwrap();
+        assert_eq!(resolve_resp.status(), StatusCode::OK);
+        assert!(state
+            .cancelled_threads
+            .read()
+            .await
+            .contains("resolve-int-2"));
+        let repo = state.runtime_repo.as_ref().expect("runtime repo");
+        let record = repo
+            .get_interrupt(&interrupt_id)
+            .expect("get interrupt")
+            .expect("interrupt exists");
+        assert_eq!(record.status, "rejected");
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resolve_interrupt_records_later_interrupts_of_the_run() {
+        let ask = |question: &'static str| {
+            function_node(question, move |_state: &MessagesState| async move {
+                let answer = interrupt(question)
+                    .await
+                    .map_err(GraphError::InterruptError)?;
+                let mut update = HashMap::new();
+                update.insert(
+                    "messages".to_string(),
+                    serde_json::to_value(vec![Message::new_ai_message(format!(
+                        "{}={}",
+                        question, answer
+                    ))])
+                    .unwrap(),
+                );
+                Ok(update)
+            })
+        };
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node("first", ask("first")).unwrap();
+        graph.add_node("second", ask("second")).unwrap();
+        graph.add_edge(START, "first");
+        graph.add_edge("first", "second");
+        graph.add_edge("second", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let router = build_router(ExecutionApiState::with_sqlite_idempotency(
+            Arc::new(compiled),
+            ":memory:",
+        ));
+        let (status, _) = post_run_json(
+            &router,
+            "/v1/jobs/run",
+            serde_json::json!({ "thread_id": "resolve-int-3", "input": "hello" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let first = pending_interrupt_id(&router, "resolve-int-3").await;
+        let (status, json) = post_run_json(
+            &router,
+            &format!("/v1/interrupts/{}/resolve", first),
+            serde_json::json!({ "decision": "approve", "value": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["run"]["status"], "interrupted");
+
+        // The second interrupt reaches the inbox under its own id and can be answered
+        let second = pending_interrupt_id(&router, "resolve-int-3").await;
+        assert_ne!(second, first);
+        let (status, json) = post_run_json(
+            &router,
+            &format!("/v1/interrupts/{}/resolve", second),
+            serde_json::json!({ "decision": "approve", "value": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["run"]["status"], "completed");
+    }
+
     #[cfg(feature = "sqlite-persistence")]
     #[tokio::test]
     async fn job_detail_works() {
@@ -28365,7 +31464,8 @@ pub async fn evomap_bounty_create(
                 accepted_by: None,
                 accepted_at_ms: None,
             };
-            repo.upsert_bounty(&bounty)
+            AsyncRuntimeRepository::upsert_bounty(repo, &bounty)
+                .await
                 .map_err(|e| ApiError::internal(format!("db error: {}", e)))?;
         }
     }
@@ -28982,8 +32082,8 @@ pub async fn evomap_session_submit(
         let submission_id = format!("sub-{}", uuid::Uuid::new_v4());
 
         if let Some(repo) = state.runtime_repo.as_ref() {
-            let session = repo
-                .get_session(&req.session_id)
+            let session = AsyncRuntimeRepository::get_session(repo, &req.session_id)
+                .await
                 .map_err(|e| ApiError::internal(format!("db error: {}", e)))?;
 
             if session.is_none() {
@@ -29001,7 +32101,8 @@ pub async fn evomap_session_submit(
                 message_type: "submission".to_string(),
                 sent_at_ms: now,
             };
-            repo.add_session_message(&message)
+            AsyncRuntimeRepository::add_session_message(repo, &message)
+                .await
                 .map_err(|e| ApiError::internal(format!("db error: {}", e)))?;
 
             return Ok(Json(ApiEnvelope {
@@ -31310,7 +34411,8 @@ pub async fn evomap_directory(
     let limit = q.limit.unwrap_or(50).max(1);
     #[cfg(feature = "sqlite-persistence")]
     let workers: Vec<WorkerRecord> = if let Some(repo) = state.runtime_repo.as_ref() {
-        repo.list_workers(q.domain.as_deref(), q.status.as_deref(), limit)
+        AsyncRuntimeRepository::list_workers(repo, q.domain.as_deref(), q.status.as_deref(), limit)
+            .await
             .map_err(|e| {
                 ApiError::internal(format!("list workers failed: {e}")).with_request_id(rid.clone())
             })?
@@ -31357,7 +34459,8 @@ pub async fn evomap_recipe_list(
     let limit = q.limit.unwrap_or(50).max(1);
     #[cfg(feature = "sqlite-persistence")]
     let recipes: Vec<RecipeRecord> = if let Some(repo) = state.runtime_repo.as_ref() {
-        repo.list_recipes(q.author_id.as_deref(), limit)
+        AsyncRuntimeRepository::list_recipes(repo, q.author_id.as_deref(), limit)
+            .await
             .map_err(|e| {
                 ApiError::internal(format!("list recipes failed: {e}")).with_request_id(rid.clone())
             })?
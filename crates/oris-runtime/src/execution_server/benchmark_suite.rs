@@ -21,16 +21,25 @@ use crate::graph::{
 use crate::schemas::messages::Message;
 
 use crate::execution_runtime::api_models::{ListJobsQuery, ReplayJobRequest, RunJobRequest};
-use crate::execution_runtime::models::AttemptExecutionStatus;
+use crate::execution_runtime::models::{
+    AttemptExecutionStatus, EnqueueOptions, RunRecord, RunRuntimeStatus,
+};
 use crate::execution_runtime::repository::RuntimeRepository;
 use crate::execution_runtime::scheduler::{SchedulerDecision, SkeletonScheduler};
 use crate::execution_runtime::sqlite_runtime_repository::SqliteRuntimeRepository;
 use crate::execution_server::api_handlers::{
     inspect_job, list_jobs, replay_job, run_job, ExecutionApiState,
 };
+use crate::kernel::{BufferConfig, BufferedEventStore, Event, EventStore, RunId, SqliteEventStore};
 
 pub const RUNTIME_BENCHMARK_BASELINE_DOC_PATH: &str = "docs/runtime-benchmark-baseline.json";
 
+/// Attempts each enqueue iteration fans out for one run
+const ENQUEUE_FAN_OUT_ATTEMPTS: usize = 500;
+
+/// Events each kernel append iteration writes, one `append` call per event
+const KERNEL_APPEND_EVENTS_PER_RUN: u64 = 50;
+
 #[derive(Clone, Debug, Serialize)]
 pub struct RuntimeBenchmarkSuiteReport {
     pub generated_at: String,
@@ -71,6 +80,20 @@ pub async fn run_runtime_benchmark_suite(
 
     let dispatch_metric = bench_dispatch_path(&repo, &scheduler, sample_size)?;
     let heartbeat_metric = bench_lease_heartbeat_path(&repo, sample_size)?;
+    let enqueue_single_metric = bench_enqueue_fan_out(&repo, false, sample_size)?;
+    let enqueue_batch_metric = bench_enqueue_fan_out(&repo, true, sample_size)?;
+    let events = SqliteEventStore::new(path_to_str(&db_path)?)?;
+    let append_direct_metric =
+        bench_kernel_event_append(&events, "kernel_event_append_direct", sample_size)?;
+    let buffered = BufferedEventStore::with_config(
+        events,
+        BufferConfig::batched(
+            BufferConfig::DEFAULT_MAX_EVENTS,
+            BufferConfig::DEFAULT_MAX_DELAY,
+        ),
+    );
+    let append_buffered_metric =
+        bench_kernel_event_append(&buffered, "kernel_event_append_buffered", sample_size)?;
 
     let simple_state = ExecutionApiState::with_sqlite_idempotency(
         build_benchmark_graph().await,
@@ -96,6 +119,10 @@ pub async fn run_runtime_benchmark_suite(
         benchmarks: vec![
             dispatch_metric,
             heartbeat_metric,
+            enqueue_single_metric,
+            enqueue_batch_metric,
+            append_direct_metric,
+            append_buffered_metric,
             run_job_metric,
             inspect_job_metric,
             list_jobs_metric,
@@ -168,6 +195,23 @@ async fn build_interrupt_benchmark_graph() -> Arc<crate::graph::CompiledGraph<Me
     Arc::new(graph.compile_with_persistence(Some(saver), None).unwrap())
 }
 
+fn create_bench_run(
+    repo: &SqliteRuntimeRepository,
+    run_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let now = Utc::now();
+    repo.create_run(&RunRecord {
+        run_id: run_id.to_string(),
+        workflow_name: "bench".to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    })?;
+    Ok(())
+}
+
 fn bench_dispatch_path(
     repo: &SqliteRuntimeRepository,
     scheduler: &SkeletonScheduler<SqliteRuntimeRepository>,
@@ -177,6 +221,7 @@ fn bench_dispatch_path(
     for idx in 0..sample_size {
         let attempt_id = format!("bench-dispatch-{}", idx);
         let run_id = format!("bench-run-dispatch-{}", idx);
+        create_bench_run(repo, &run_id)?;
         repo.enqueue_attempt(&attempt_id, &run_id)?;
 
         let started = Instant::now();
@@ -189,7 +234,7 @@ fn bench_dispatch_path(
             } => {
                 assert_eq!(leased, attempt_id);
             }
-            SchedulerDecision::Noop => {
+            SchedulerDecision::Idle { .. } | SchedulerDecision::Noop => {
                 return Err("dispatch benchmark unexpectedly produced noop".into());
             }
             SchedulerDecision::Backpressure { queue_depth, .. } => {
@@ -224,6 +269,7 @@ fn bench_lease_heartbeat_path(
     sample_size: u32,
 ) -> Result<RuntimeBenchmarkMetric, Box<dyn std::error::Error>> {
     let attempt_id = "bench-heartbeat-attempt";
+    create_bench_run(repo, "bench-heartbeat-run")?;
     repo.enqueue_attempt(attempt_id, "bench-heartbeat-run")?;
     let lease = repo.upsert_lease(
         attempt_id,
@@ -256,6 +302,77 @@ fn bench_lease_heartbeat_path(
     ))
 }
 
+/// One iteration enqueues [ENQUEUE_FAN_OUT_ATTEMPTS] attempts of a fresh run, either one
+/// `enqueue_attempt` call each or a single `enqueue_attempts` batch
+fn bench_enqueue_fan_out(
+    repo: &SqliteRuntimeRepository,
+    batched: bool,
+    sample_size: u32,
+) -> Result<RuntimeBenchmarkMetric, Box<dyn std::error::Error>> {
+    let id = if batched {
+        "attempt_enqueue_batch"
+    } else {
+        "attempt_enqueue_single"
+    };
+    let mut total = StdDuration::ZERO;
+    for idx in 0..sample_size {
+        let run_id = format!("bench-run-{}-{}", id, idx);
+        create_bench_run(repo, &run_id)?;
+        let batch: Vec<(String, RunId, EnqueueOptions)> = (0..ENQUEUE_FAN_OUT_ATTEMPTS)
+            .map(|n| {
+                (
+                    format!("{}-{}", run_id, n),
+                    run_id.clone(),
+                    EnqueueOptions::default(),
+                )
+            })
+            .collect();
+
+        let started = Instant::now();
+        if batched {
+            repo.enqueue_attempts(&batch)?;
+        } else {
+            for (attempt_id, run_id, _) in &batch {
+                repo.enqueue_attempt(attempt_id, run_id)?;
+            }
+        }
+        total += started.elapsed();
+    }
+    Ok(metric_from_duration(id, "scheduler", total, sample_size))
+}
+
+/// One iteration appends [KERNEL_APPEND_EVENTS_PER_RUN] events to a fresh run one at a
+/// time, the way the kernel driver does, then flushes
+fn bench_kernel_event_append(
+    events: &dyn EventStore,
+    id: &'static str,
+    sample_size: u32,
+) -> Result<RuntimeBenchmarkMetric, Box<dyn std::error::Error>> {
+    let mut total = StdDuration::ZERO;
+    for idx in 0..sample_size {
+        let run_id: RunId = format!("bench-{}-{}", id, idx);
+        let started = Instant::now();
+        for n in 0..KERNEL_APPEND_EVENTS_PER_RUN {
+            events.append(
+                &run_id,
+                &[Event::StateUpdated {
+                    step_id: Some(format!("step-{}", n)),
+                    payload: serde_json::json!({ "n": n }),
+                    state_hash: None,
+                    merge_report: None,
+                }],
+            )?;
+        }
+        events.flush()?;
+        total += started.elapsed();
+        if events.head(&run_id)? != KERNEL_APPEND_EVENTS_PER_RUN {
+            return Err(format!("{} benchmark lost events", id).into());
+        }
+    }
+
+    Ok(metric_from_duration(id, "kernel", total, sample_size))
+}
+
 async fn bench_run_job_api(
     state: ExecutionApiState,
     sample_size: u32,
@@ -275,6 +392,7 @@ async fn bench_run_job_api(
                 timeout_policy: None,
                 priority: None,
                 tenant_id: None,
+                mode: None,
             }),
         )
         .await
@@ -309,6 +427,7 @@ async fn bench_inspect_job_api(
             timeout_policy: None,
             priority: None,
             tenant_id: None,
+            mode: None,
         }),
     )
     .await
@@ -354,6 +473,7 @@ async fn bench_list_jobs_api(
                 timeout_policy: None,
                 priority: None,
                 tenant_id: None,
+                mode: None,
             }),
         )
         .await
@@ -368,6 +488,9 @@ async fn bench_list_jobs_api(
             headers.clone(),
             Query(ListJobsQuery {
                 status: None,
+                workflow: None,
+                created_from_ms: None,
+                created_to_ms: None,
                 limit: Some(50),
                 offset: Some(0),
             }),
@@ -407,6 +530,7 @@ async fn bench_replay_api(
                 timeout_policy: None,
                 priority: None,
                 tenant_id: None,
+                mode: None,
             }),
         )
         .await
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use oris_execution_runtime::{
@@ -7,16 +8,77 @@ use oris_execution_runtime::{
 };
 use serde_json::Value;
 
-use crate::graph::{Command, CompiledGraph, MessagesState, RunnableConfig, StateOrCommand};
+use crate::graph::{
+    with_simulated_actions, CancellationToken, Command, CompiledGraph, GraphError, MessagesState,
+    RunnableConfig, StateOrCommand,
+};
+use crate::kernel::{AllowAllPolicy, Policy};
 use crate::schemas::messages::Message;
 
 pub(crate) struct CompiledGraphExecutionBridge {
     compiled: Arc<CompiledGraph<MessagesState>>,
+    /// Simulates the actions of dry runs
+    dry_run_policy: Arc<dyn Policy>,
+    /// Cancellation tokens of the runs in flight, by thread; cancelling one pauses the run
+    in_flight: Mutex<HashMap<String, CancellationToken>>,
 }
 
 impl CompiledGraphExecutionBridge {
     pub(crate) fn new(compiled: Arc<CompiledGraph<MessagesState>>) -> Self {
-        Self { compiled }
+        Self {
+            compiled,
+            dry_run_policy: Arc::new(AllowAllPolicy),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn with_dry_run_policy(mut self, policy: Arc<dyn Policy>) -> Self {
+        self.dry_run_policy = policy;
+        self
+    }
+
+    /// Invoke the graph as a pausable run of `thread_id`
+    async fn invoke(
+        &self,
+        thread_id: &str,
+        input: StateOrCommand<MessagesState>,
+        config: RunnableConfig,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let token = CancellationToken::new();
+        self.in_flight()?
+            .insert(thread_id.to_string(), token.clone());
+        let result = self
+            .compiled
+            .invoke_with_config_interrupt(input, &config.with_cancellation(token))
+            .await;
+        self.in_flight()?.remove(thread_id);
+        match result {
+            Ok(result) => Ok(ExecutionInvokeView {
+                interrupts: result
+                    .interrupt
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|interrupt| interrupt.value)
+                    .collect(),
+                paused_at: None,
+            }),
+            Err(GraphError::Cancelled { node }) => Ok(ExecutionInvokeView {
+                interrupts: Vec::new(),
+                paused_at: Some(node),
+            }),
+            Err(e) => Err(ExecutionGraphBridgeError::internal(e.to_string())),
+        }
+    }
+
+    fn in_flight(
+        &self,
+    ) -> Result<
+        std::sync::MutexGuard<'_, HashMap<String, CancellationToken>>,
+        ExecutionGraphBridgeError,
+    > {
+        self.in_flight.lock().map_err(|e| {
+            ExecutionGraphBridgeError::internal(format!("run registry poisoned: {}", e))
+        })
     }
 }
 
@@ -28,20 +90,12 @@ impl ExecutionGraphBridge for CompiledGraphExecutionBridge {
         input: &str,
     ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
         let initial = MessagesState::with_messages(vec![Message::new_human_message(input)]);
-        let config = RunnableConfig::with_thread_id(thread_id);
-        let result = self
-            .compiled
-            .invoke_with_config_interrupt(StateOrCommand::State(initial), &config)
-            .await
-            .map_err(|e| ExecutionGraphBridgeError::internal(e.to_string()))?;
-        Ok(ExecutionInvokeView {
-            interrupts: result
-                .interrupt
-                .unwrap_or_default()
-                .into_iter()
-                .map(|interrupt| interrupt.value)
-                .collect(),
-        })
+        self.invoke(
+            thread_id,
+            StateOrCommand::State(initial),
+            RunnableConfig::with_thread_id(thread_id),
+        )
+        .await
     }
 
     async fn resume(
@@ -50,20 +104,12 @@ impl ExecutionGraphBridge for CompiledGraphExecutionBridge {
         checkpoint_id: Option<&str>,
         value: Value,
     ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
-        let config = checkpoint_config(thread_id, checkpoint_id);
-        let result = self
-            .compiled
-            .invoke_with_config_interrupt(StateOrCommand::Command(Command::resume(value)), &config)
-            .await
-            .map_err(|e| ExecutionGraphBridgeError::internal(e.to_string()))?;
-        Ok(ExecutionInvokeView {
-            interrupts: result
-                .interrupt
-                .unwrap_or_default()
-                .into_iter()
-                .map(|interrupt| interrupt.value)
-                .collect(),
-        })
+        self.invoke(
+            thread_id,
+            StateOrCommand::Command(Command::resume(value)),
+            checkpoint_config(thread_id, checkpoint_id),
+        )
+        .await
     }
 
     async fn replay(
@@ -72,10 +118,23 @@ impl ExecutionGraphBridge for CompiledGraphExecutionBridge {
         checkpoint_id: Option<&str>,
     ) -> Result<(), ExecutionGraphBridgeError> {
         let config = checkpoint_config(thread_id, checkpoint_id);
-        self.compiled
-            .invoke_with_config(None, &config)
+        let snapshot = self
+            .compiled
+            .get_state(&config)
             .await
-            .map_err(|e| ExecutionGraphBridgeError::internal(e.to_string()))?;
+            .map_err(map_snapshot_error)?;
+        // Replay re-executes the run from START on the state it started from; the
+        // checkpoint's own values already hold every node's update. Checkpoints that do
+        // not record their run's input continue at their pending node instead.
+        let result = match snapshot.input() {
+            Some(input) => {
+                self.compiled
+                    .invoke_with_config(Some(input), &RunnableConfig::with_thread_id(thread_id))
+                    .await
+            }
+            None => self.compiled.invoke_with_config(None, &config).await,
+        };
+        result.map_err(|e| ExecutionGraphBridgeError::internal(e.to_string()))?;
         Ok(())
     }
 
@@ -118,6 +177,69 @@ impl ExecutionGraphBridge for CompiledGraphExecutionBridge {
             })
             .collect())
     }
+
+    async fn run_dry(
+        &self,
+        thread_id: &str,
+        input: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        with_simulated_actions(self.dry_run_policy.clone(), self.run(thread_id, input)).await
+    }
+
+    async fn resume_dry(
+        &self,
+        thread_id: &str,
+        checkpoint_id: Option<&str>,
+        value: Value,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        with_simulated_actions(
+            self.dry_run_policy.clone(),
+            self.resume(thread_id, checkpoint_id, value),
+        )
+        .await
+    }
+
+    async fn pause(&self, thread_id: &str) -> Result<bool, ExecutionGraphBridgeError> {
+        Ok(match self.in_flight()?.get(thread_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        })
+    }
+
+    async fn continue_run(
+        &self,
+        thread_id: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        let config = RunnableConfig::with_thread_id(thread_id);
+        let snapshot = self
+            .compiled
+            .get_state(&config)
+            .await
+            .map_err(map_snapshot_error)?;
+        let node = snapshot.next.first().cloned().ok_or_else(|| {
+            ExecutionGraphBridgeError::not_found(format!(
+                "thread '{}' has no node to continue at",
+                thread_id
+            ))
+        })?;
+        // A goto carries no resume value, so the node runs again as if never stopped
+        self.invoke(
+            thread_id,
+            StateOrCommand::Command(Command::goto(node)),
+            config,
+        )
+        .await
+    }
+
+    async fn continue_run_dry(
+        &self,
+        thread_id: &str,
+    ) -> Result<ExecutionInvokeView, ExecutionGraphBridgeError> {
+        with_simulated_actions(self.dry_run_policy.clone(), self.continue_run(thread_id)).await
+    }
 }
 
 fn checkpoint_config(thread_id: &str, checkpoint_id: Option<&str>) -> RunnableConfig {
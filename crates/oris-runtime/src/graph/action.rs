@@ -0,0 +1,145 @@
+//! Kernel actions requested from inside graph nodes.
+//!
+//! When a graph runs under the kernel through [`GraphStepFnAdapter`](super::GraphStepFnAdapter),
+//! [request_action] suspends the node and hands the action to the kernel, which
+//! authorizes it with its policy, performs it with its executor and records the output
+//! in the event log. The node then runs again from the start and [request_action]
+//! returns the recorded output, so nodes must request their actions in the same order
+//! on every run. Outside the kernel it returns `None` and the node does the work itself,
+//! unless the graph runs under [with_simulated_actions] (a dry run), where it returns the
+//! policy's simulated result instead.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::task_local;
+
+use crate::kernel::action::{Action, ActionResult};
+use crate::kernel::Policy;
+
+use super::error::GraphError;
+
+task_local! {
+    static ACTION_CONTEXT: RefCell<ActionContext>;
+    static SIMULATION_POLICY: Arc<dyn Policy>;
+}
+
+/// Outputs of the actions the current node has already had performed
+struct ActionContext {
+    outputs: Vec<Value>,
+    next: usize,
+}
+
+/// Run `f` under the kernel, with the outputs of the node's previous actions
+pub(crate) async fn with_action_outputs<F: Future>(outputs: Vec<Value>, f: F) -> F::Output {
+    ACTION_CONTEXT
+        .scope(RefCell::new(ActionContext { outputs, next: 0 }), f)
+        .await
+}
+
+/// Run `f` (e.g. a graph invocation) as a dry run: [request_action] answers each action
+/// outside the kernel with `policy`'s [simulate](Policy::simulate) result.
+///
+/// Only actions requested through [request_action] are simulated; work a node does
+/// directly still happens.
+pub async fn with_simulated_actions<F: Future>(policy: Arc<dyn Policy>, f: F) -> F::Output {
+    SIMULATION_POLICY.scope(policy, f).await
+}
+
+/// Have the kernel perform `action` on behalf of the current node
+///
+/// Returns the action's output once the kernel has performed it, or `None` when the
+/// graph is not running under the kernel. The first call for an action returns
+/// `GraphError::ActionRequested`, which the node must propagate with `?`. In a dry run
+/// ([with_simulated_actions]) it returns the simulated output, or an error for a
+/// simulated failure.
+///
+/// ```rust,ignore
+/// let action = Action::CallTool {
+///     tool: "search".into(),
+///     input: json!({"q": query}),
+///     idempotency_key: None,
+/// };
+/// let output = match request_action(action)? {
+///     Some(output) => output,
+///     None => search_locally(&query).await?,
+/// };
+/// ```
+pub fn request_action(action: Action) -> Result<Option<Value>, GraphError> {
+    let recorded = ACTION_CONTEXT.try_with(|ctx| {
+        let mut ctx = ctx.borrow_mut();
+        let output = ctx.outputs.get(ctx.next).cloned();
+        if output.is_some() {
+            ctx.next += 1;
+        }
+        output
+    });
+    match recorded {
+        Ok(Some(output)) => Ok(Some(output)),
+        Ok(None) => Err(GraphError::ActionRequested(action)),
+        Err(_) => simulate(&action),
+    }
+}
+
+/// The simulated output of `action` in a dry run, or `None` outside one
+fn simulate(action: &Action) -> Result<Option<Value>, GraphError> {
+    match SIMULATION_POLICY.try_with(|policy| policy.simulate(action)) {
+        Ok(ActionResult::Success(output)) => Ok(Some(output)),
+        Ok(ActionResult::Failure(error)) => Err(GraphError::ExecutionError(format!(
+            "simulated action failed: {}",
+            error
+        ))),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sleep() -> Action {
+        Action::Sleep { millis: 1 }
+    }
+
+    #[tokio::test]
+    async fn request_action_replays_recorded_outputs_in_order() {
+        assert!(matches!(request_action(sleep()), Ok(None)));
+
+        with_action_outputs(vec![serde_json::json!(1)], async {
+            assert_eq!(request_action(sleep()).unwrap(), Some(serde_json::json!(1)));
+            assert!(matches!(
+                request_action(sleep()),
+                Err(GraphError::ActionRequested(Action::Sleep { millis: 1 }))
+            ));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn dry_runs_answer_with_the_policy_simulation() {
+        use crate::kernel::{simulated_output, AllowAllPolicy, StubbedPolicy};
+
+        let policy = StubbedPolicy::new(AllowAllPolicy)
+            .with_tool_stub("search", ActionResult::Failure("offline".into()));
+        with_simulated_actions(Arc::new(policy), async {
+            assert_eq!(request_action(sleep()).unwrap(), Some(simulated_output()));
+            let search = Action::CallTool {
+                tool: "search".into(),
+                input: serde_json::json!("q"),
+                idempotency_key: None,
+            };
+            assert!(matches!(
+                request_action(search),
+                Err(GraphError::ExecutionError(message)) if message.contains("offline")
+            ));
+            // Under the kernel, recorded outputs take precedence
+            with_action_outputs(vec![serde_json::json!(1)], async {
+                assert_eq!(request_action(sleep()).unwrap(), Some(serde_json::json!(1)));
+            })
+            .await;
+        })
+        .await;
+    }
+}
@@ -0,0 +1,366 @@
+//! Lifecycle callbacks for observing graph runs.
+//!
+//! Attach a [`GraphCallbacks`] implementation with [`RunnableConfig::with_callbacks`] for
+//! one run, or with [`CompiledGraph::with_callbacks`](super::CompiledGraph::with_callbacks)
+//! for every run of a graph. Callbacks only receive shared references, so they cannot
+//! change the run's state; a panicking callback is logged and the run continues.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{error::GraphError, persistence::config::RunnableConfig, state::StateUpdate};
+
+/// The run a callback is invoked for
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RunContext {
+    /// Thread of the run, if it has one
+    pub thread_id: Option<String>,
+    /// Checkpoint namespace; set when the graph runs as a subgraph
+    pub checkpoint_ns: Option<String>,
+}
+
+impl RunContext {
+    /// The context of a run with `config`
+    pub fn from_config(config: Option<&RunnableConfig>) -> Self {
+        Self {
+            thread_id: config.and_then(RunnableConfig::get_thread_id),
+            checkpoint_ns: config.and_then(RunnableConfig::get_checkpoint_ns),
+        }
+    }
+}
+
+/// Hooks called as a graph runs
+///
+/// Every method has an empty default, so implementations override only what they need.
+/// Hooks run inline on the executing task and should return quickly.
+pub trait GraphCallbacks: Send + Sync {
+    /// A node is about to run
+    fn on_node_start(&self, _run_ctx: &RunContext, _node: &str) {}
+
+    /// A node finished and returned `update`
+    fn on_node_end(
+        &self,
+        _run_ctx: &RunContext,
+        _node: &str,
+        _update: &StateUpdate,
+        _duration: Duration,
+    ) {
+    }
+
+    /// A checkpoint was written
+    fn on_checkpoint(&self, _checkpoint_id: &str) {}
+
+    /// A node failed (interrupts and parent gotos are not failures)
+    fn on_error(&self, _node: &str, _err: &GraphError) {}
+}
+
+impl fmt::Debug for dyn GraphCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GraphCallbacks")
+    }
+}
+
+/// The callbacks of one run: the graph's own followed by those of the run's config
+pub(crate) struct RunCallbacks<'a> {
+    graph: &'a [Arc<dyn GraphCallbacks>],
+    config: Option<&'a RunnableConfig>,
+    run_ctx: RunContext,
+}
+
+impl<'a> RunCallbacks<'a> {
+    pub(crate) fn new(
+        graph: &'a [Arc<dyn GraphCallbacks>],
+        config: Option<&'a RunnableConfig>,
+    ) -> Self {
+        Self {
+            graph,
+            config,
+            run_ctx: RunContext::from_config(config),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.graph.is_empty() && self.config.map_or(true, |c| c.callbacks().is_empty())
+    }
+
+    /// Call `hook` on every callback, logging (not propagating) panics
+    fn each(&self, hook: &str, call: impl Fn(&dyn GraphCallbacks)) {
+        let config = self.config.map(RunnableConfig::callbacks).unwrap_or(&[]);
+        for callbacks in self.graph.iter().chain(config) {
+            if catch_unwind(AssertUnwindSafe(|| call(callbacks.as_ref()))).is_err() {
+                log::error!("Graph callback {} panicked; continuing the run", hook);
+            }
+        }
+    }
+
+    /// Report that `node` started, returning when it did
+    pub(crate) fn node_started(&self, node: &str) -> Instant {
+        if !self.is_empty() {
+            self.each("on_node_start", |c| c.on_node_start(&self.run_ctx, node));
+        }
+        Instant::now()
+    }
+
+    /// Report how `node`, started at `started`, finished
+    pub(crate) fn node_finished(
+        &self,
+        node: &str,
+        result: &Result<StateUpdate, GraphError>,
+        started: Instant,
+    ) {
+        #[cfg(feature = "metrics")]
+        record_node_metrics(node, result, started);
+        if self.is_empty() {
+            return;
+        }
+        match result {
+            Ok(update) => {
+                let duration = started.elapsed();
+                self.each("on_node_end", |c| {
+                    c.on_node_end(&self.run_ctx, node, update, duration)
+                });
+            }
+            Err(
+                GraphError::InterruptError(_)
+                | GraphError::ParentGoto { .. }
+                | GraphError::ActionRequested(_),
+            ) => {}
+            Err(err) => self.each("on_error", |c| c.on_error(node, err)),
+        }
+    }
+
+    /// Report that a checkpoint was written
+    pub(crate) fn checkpoint(&self, checkpoint_id: &str) {
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!(crate::metrics::GRAPH_CHECKPOINTS_WRITTEN_TOTAL).increment(1);
+        if !self.is_empty() {
+            self.each("on_checkpoint", |c| c.on_checkpoint(checkpoint_id));
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_node_metrics(node: &str, result: &Result<StateUpdate, GraphError>, started: Instant) {
+    use crate::metrics::{GRAPH_NODE_DURATION_SECONDS, GRAPH_NODE_ERRORS_TOTAL};
+
+    match result {
+        Ok(_) => ::metrics::histogram!(GRAPH_NODE_DURATION_SECONDS, "node" => node.to_string())
+            .record(started.elapsed()),
+        Err(
+            GraphError::InterruptError(_)
+            | GraphError::ParentGoto { .. }
+            | GraphError::ActionRequested(_),
+        ) => {}
+        Err(_) => {
+            ::metrics::counter!(GRAPH_NODE_ERRORS_TOTAL, "node" => node.to_string()).increment(1)
+        }
+    }
+}
+
+/// Callbacks that emit a `tracing` span per node run
+///
+/// Each node runs inside an `info`-level `graph_node` span carrying the node name, thread
+/// and checkpoint namespace; the span closes with the node's duration recorded, or an
+/// `error` event if it failed. Checkpoints are logged as `debug` events.
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+pub struct TracingCallbacks {
+    spans: std::sync::Mutex<std::collections::HashMap<(RunContext, String), tracing::Span>>,
+}
+
+#[cfg(feature = "tracing")]
+impl TracingCallbacks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn take_span(&self, run_ctx: Option<&RunContext>, node: &str) -> Option<tracing::Span> {
+        let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+        match run_ctx {
+            Some(run_ctx) => spans.remove(&(run_ctx.clone(), node.to_string())),
+            None => {
+                let key = spans.keys().find(|(_, n)| n == node).cloned()?;
+                spans.remove(&key)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl GraphCallbacks for TracingCallbacks {
+    fn on_node_start(&self, run_ctx: &RunContext, node: &str) {
+        let span = tracing::info_span!(
+            "graph_node",
+            node = node,
+            thread_id = run_ctx.thread_id.as_deref().unwrap_or_default(),
+            checkpoint_ns = run_ctx.checkpoint_ns.as_deref().unwrap_or_default(),
+            duration_ms = tracing::field::Empty,
+        );
+        self.spans
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((run_ctx.clone(), node.to_string()), span);
+    }
+
+    fn on_node_end(
+        &self,
+        run_ctx: &RunContext,
+        node: &str,
+        update: &StateUpdate,
+        duration: Duration,
+    ) {
+        if let Some(span) = self.take_span(Some(run_ctx), node) {
+            span.record("duration_ms", duration.as_millis() as u64);
+            span.in_scope(
+                || tracing::info!(keys = ?update.keys().collect::<Vec<_>>(), "node finished"),
+            );
+        }
+    }
+
+    fn on_checkpoint(&self, checkpoint_id: &str) {
+        tracing::debug!(checkpoint_id, "checkpoint written");
+    }
+
+    fn on_error(&self, node: &str, err: &GraphError) {
+        match self.take_span(None, node) {
+            Some(span) => span.in_scope(|| tracing::error!(error = %err, "node failed")),
+            None => tracing::error!(node, error = %err, "node failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::graph::{function_node, InMemorySaver, MessagesState, StateGraph, END, START};
+    use crate::schemas::messages::Message;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn push(&self, event: String) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn events(&self) -> Vec<String> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl GraphCallbacks for Recorder {
+        fn on_node_start(&self, run_ctx: &RunContext, node: &str) {
+            self.push(format!(
+                "start {} {}",
+                node,
+                run_ctx.thread_id.as_deref().unwrap_or("-")
+            ));
+        }
+
+        fn on_node_end(&self, _: &RunContext, node: &str, update: &StateUpdate, _: Duration) {
+            self.push(format!("end {} {}", node, update.len()));
+        }
+
+        fn on_checkpoint(&self, _checkpoint_id: &str) {
+            self.push("checkpoint".to_string());
+        }
+
+        fn on_error(&self, node: &str, _err: &GraphError) {
+            self.push(format!("error {}", node));
+        }
+    }
+
+    struct Panicking;
+
+    impl GraphCallbacks for Panicking {
+        fn on_node_start(&self, _: &RunContext, _: &str) {
+            panic!("callback bug");
+        }
+    }
+
+    fn graph(fail: bool) -> StateGraph<MessagesState> {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "greet",
+                function_node("greet", |_s: &MessagesState| async move {
+                    let mut update = HashMap::new();
+                    update.insert(
+                        "messages".to_string(),
+                        serde_json::to_value(vec![Message::new_ai_message("hi")])?,
+                    );
+                    Ok(update)
+                }),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "check",
+                function_node("check", move |_s: &MessagesState| async move {
+                    if fail {
+                        return Err(GraphError::ExecutionError("boom".to_string()));
+                    }
+                    Ok(HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "greet");
+        graph.add_edge("greet", "check");
+        graph.add_edge("check", END);
+        graph
+    }
+
+    #[tokio::test]
+    async fn reports_node_lifecycle_and_survives_panics() {
+        let compiled_cb = Arc::new(Recorder::default());
+        let config_cb = Arc::new(Recorder::default());
+        let compiled = graph(false)
+            .compile()
+            .unwrap()
+            .with_callbacks(compiled_cb.clone());
+
+        compiled.invoke(MessagesState::new()).await.unwrap();
+        assert_eq!(
+            compiled_cb.events(),
+            vec![
+                "start greet -",
+                "end greet 1",
+                "start check -",
+                "end check 0"
+            ]
+        );
+
+        let config = RunnableConfig::with_thread_id("cb-1")
+            .with_callbacks(Arc::new(Panicking))
+            .with_callbacks(config_cb.clone());
+        let compiled = graph(false)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["check"], &[])
+            .unwrap();
+        compiled
+            .invoke_with_config(Some(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            config_cb.events(),
+            vec!["start greet cb-1", "end greet 1", "checkpoint"]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_node_errors() {
+        let recorder = Arc::new(Recorder::default());
+        let compiled = graph(true)
+            .compile()
+            .unwrap()
+            .with_callbacks(recorder.clone());
+        assert!(compiled.invoke(MessagesState::new()).await.is_err());
+        assert_eq!(recorder.events().last().unwrap(), "error check");
+    }
+}
@@ -5,28 +5,43 @@ use std::sync::Arc;
 use async_stream::stream;
 use futures::Stream;
 
+use crate::kernel::canonical::canonical_state_hash;
 use crate::kernel::{Event, EventStore};
 
 use super::{
-    edge::{Edge, END, START},
+    callbacks::{GraphCallbacks, RunCallbacks},
+    deadline::{with_cancellation, with_deadline},
+    edge::{
+        BranchLog, Edge, FanOutProgress, BRANCHES_METADATA_KEY, END, FAN_OUT_METADATA_KEY, START,
+    },
     error::GraphError,
     execution::{
         durability::DurabilityMode, scheduler::NodeScheduler, superstep::SuperStepExecutor,
     },
     interrupts::{
-        set_interrupt_context, Interrupt, InterruptContext, InvokeResult, StateOrCommand,
+        has_resume_values, set_interrupt_context, Breakpoint, BreakpointWhen, Command, Interrupt,
+        InterruptContext, InterruptError, InvokeResult, StateOrCommand, BREAKPOINT_METADATA_KEY,
+        INTERRUPTS_METADATA_KEY,
     },
-    node::Node,
+    node::{Goto, Node, NodeCommand},
+    node_cache::{InMemoryNodeCache, NodeCacheBox, NodeCachePolicy},
+    node_options::{invoke_with_options, NodeOptions, OptionsNode},
     persistence::{
         checkpointer::CheckpointerBox,
-        config::{CheckpointConfig, RunnableConfig},
-        snapshot::StateSnapshot,
+        config::{CheckpointConfig, RunnableConfig, DEFAULT_MAX_CONCURRENCY, DEFAULT_STEP_LIMIT},
+        snapshot::{
+            StateSnapshot, CANCELLED_STATUS, DEADLINE_EXCEEDED_STATUS, INPUT_METADATA_KEY,
+            STATUS_METADATA_KEY,
+        },
         store::StoreBox,
     },
-    state::{State, StateUpdate},
+    reducers::StateReducers,
+    retry::{node_attempts_value, NodeAttempts, NODE_ATTEMPTS_METADATA_KEY},
+    state::{full_state_update, State, StateUpdate},
     step_result::GraphStepOnceResult,
     streaming::{
         chunk::StreamChunk,
+        event::{GraphStreamEvent, NodePayload, StreamPayload},
         metadata::{MessageChunk, MessageMetadata},
         mode::StreamMode,
     },
@@ -47,6 +62,74 @@ pub struct CompiledGraph<S: State> {
     /// When true (default), step_once is allowed. When false, step_once returns an error unless
     /// config has allow_non_pure_step_once set—used to guard deterministic replay (nodes must not do I/O).
     pure_graph: bool,
+    /// Per-node execution options (retry, timeout), keyed by node name.
+    node_options: HashMap<String, NodeOptions>,
+    /// Per-node result caching policies, keyed by node name.
+    node_cache_policies: HashMap<String, NodeCachePolicy<S>>,
+    /// Storage for cached node updates.
+    node_cache: NodeCacheBox,
+    /// Per-key reducers used to merge node updates.
+    reducers: StateReducers,
+    /// Nodes to pause before (static breakpoints).
+    interrupt_before: HashSet<String>,
+    /// Nodes to pause after (static breakpoints).
+    interrupt_after: HashSet<String>,
+    /// Lifecycle callbacks called on every run.
+    callbacks: Vec<Arc<dyn GraphCallbacks>>,
+}
+
+/// What a paused interruptible run records in its checkpoint
+#[derive(Clone, Copy)]
+struct PauseContext<'a> {
+    checkpoint_config: &'a CheckpointConfig,
+    parent_config: Option<&'a CheckpointConfig>,
+    branches: &'a BranchLog,
+    node_attempts: &'a HashMap<String, NodeAttempts>,
+    /// Fan-out whose branches are still running, if any
+    fan_out: Option<&'a FanOutProgress>,
+    event_store: Option<&'a Arc<dyn EventStore>>,
+    run_id: &'a String,
+    /// Config of the run, whose callbacks are told about the checkpoint
+    config: Option<&'a RunnableConfig>,
+    /// State the run started from at START, which replays of the checkpoint start from
+    input: Option<&'a serde_json::Value>,
+}
+
+/// Record in the run's event log that a cancelled run stopped where it can be resumed
+fn record_paused(pause: &PauseContext<'_>) -> Result<(), GraphError> {
+    if let Some(es) = pause.event_store {
+        es.append(pause.run_id, &[Event::Paused])
+            .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Where an interruptible run starts when resuming from a checkpoint
+struct ResumeFrom {
+    /// The pending node recorded in the checkpoint
+    node: String,
+    /// Whether the run stopped at an `interrupt_before` breakpoint on `node`
+    /// that must be passed exactly once
+    past_breakpoint: bool,
+    /// Fan-out that was running, whose unfinished branches run before anything else
+    fan_out: Option<FanOutProgress>,
+    /// State the interrupted run started from, carried into the checkpoints it writes next
+    input: Option<serde_json::Value>,
+}
+
+impl ResumeFrom {
+    /// The pending node of a paused checkpoint, if any
+    fn pending<S: State>(snapshot: &StateSnapshot<S>) -> Option<Self> {
+        let node = snapshot.next.first()?.clone();
+        let past_breakpoint = Breakpoint::from_metadata(&snapshot.metadata)
+            .is_some_and(|b| b.when == BreakpointWhen::Before && b.node == node);
+        Some(Self {
+            node,
+            past_breakpoint,
+            fan_out: FanOutProgress::from_metadata(&snapshot.metadata),
+            input: snapshot.metadata.get(INPUT_METADATA_KEY).cloned(),
+        })
+    }
 }
 
 impl<S: State + 'static> CompiledGraph<S> {
@@ -69,10 +152,44 @@ impl<S: State + 'static> CompiledGraph<S> {
     pub(crate) fn store(&self) -> &Option<StoreBox> {
         &self.store
     }
+
+    /// Get the kernel event store runs of this graph append to, if any
+    pub(crate) fn event_store(&self) -> Option<&Arc<dyn EventStore>> {
+        self.event_store.as_ref()
+    }
+
+    /// Get the static breakpoints as (interrupt_before, interrupt_after) (for rendering)
+    pub(crate) fn breakpoints(&self) -> (&HashSet<String>, &HashSet<String>) {
+        (&self.interrupt_before, &self.interrupt_after)
+    }
 }
 
 impl<S: State + 'static> CompiledGraph<S> {
-    // Methods moved above after struct definition
+    /// Rebuild this graph with the given nodes and a parent's persistence
+    /// (for subgraph persistence propagation). The subgraph keeps its own store if
+    /// the parent has none.
+    pub(crate) fn inherit_persistence(
+        &self,
+        nodes: HashMap<String, Arc<dyn Node<S>>>,
+        checkpointer: Option<CheckpointerBox<S>>,
+        store: Option<StoreBox>,
+    ) -> Self {
+        Self {
+            nodes,
+            adjacency: self.adjacency.clone(),
+            checkpointer,
+            store: store.or_else(|| self.store.clone()),
+            event_store: self.event_store.clone(),
+            pure_graph: self.pure_graph,
+            node_options: self.node_options.clone(),
+            node_cache_policies: self.node_cache_policies.clone(),
+            node_cache: self.node_cache.clone(),
+            reducers: self.reducers.clone(),
+            interrupt_before: self.interrupt_before.clone(),
+            interrupt_after: self.interrupt_after.clone(),
+            callbacks: self.callbacks.clone(),
+        }
+    }
 }
 
 impl<S: State + 'static> CompiledGraph<S> {
@@ -90,6 +207,13 @@ impl<S: State + 'static> CompiledGraph<S> {
             store: None,
             event_store: None,
             pure_graph: true,
+            node_options: HashMap::new(),
+            node_cache_policies: HashMap::new(),
+            node_cache: Arc::new(InMemoryNodeCache::new()),
+            reducers: StateReducers::new(),
+            interrupt_before: HashSet::new(),
+            interrupt_after: HashSet::new(),
+            callbacks: Vec::new(),
         })
     }
 
@@ -107,9 +231,187 @@ impl<S: State + 'static> CompiledGraph<S> {
             store,
             event_store: None,
             pure_graph: true,
+            node_options: HashMap::new(),
+            node_cache_policies: HashMap::new(),
+            node_cache: Arc::new(InMemoryNodeCache::new()),
+            reducers: StateReducers::new(),
+            interrupt_before: HashSet::new(),
+            interrupt_after: HashSet::new(),
+            callbacks: Vec::new(),
         })
     }
 
+    /// Attach per-node execution options (set via `StateGraph::add_node_with_options`)
+    pub(crate) fn with_node_options(self, node_options: HashMap<String, NodeOptions>) -> Self {
+        Self {
+            node_options,
+            ..self
+        }
+    }
+
+    /// Attach per-node cache policies (set via `StateGraph::add_node_with_cache`)
+    pub(crate) fn with_node_cache_policies(
+        self,
+        node_cache_policies: HashMap<String, NodeCachePolicy<S>>,
+    ) -> Self {
+        Self {
+            node_cache_policies,
+            ..self
+        }
+    }
+
+    /// Attach per-key reducers (set via `StateGraph::set_reducer`)
+    pub(crate) fn with_reducers(self, reducers: StateReducers) -> Self {
+        Self { reducers, ..self }
+    }
+
+    /// Store cached node updates in `cache` instead of in memory
+    ///
+    /// Use e.g. a `SqliteNodeCache` to keep cached updates across restarts.
+    pub fn with_node_cache(self, node_cache: NodeCacheBox) -> Self {
+        Self { node_cache, ..self }
+    }
+
+    /// Observe every run of this graph with `callbacks`
+    ///
+    /// Graph callbacks are called before those added to a run's `RunnableConfig`.
+    pub fn with_callbacks(mut self, callbacks: Arc<dyn GraphCallbacks>) -> Self {
+        self.callbacks.push(callbacks);
+        self
+    }
+
+    /// Drop every cached update of `node`, so its next execution runs the node
+    pub async fn invalidate_node_cache(&self, node: &str) -> Result<(), GraphError> {
+        self.node_cache
+            .invalidate(node)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Node cache error: {}", e)))
+    }
+
+    /// Attach static breakpoints (set via `StateGraph::compile_with_interrupts`)
+    pub(crate) fn with_breakpoints(
+        self,
+        interrupt_before: HashSet<String>,
+        interrupt_after: HashSet<String>,
+    ) -> Self {
+        Self {
+            interrupt_before,
+            interrupt_after,
+            ..self
+        }
+    }
+
+    /// Invoke a node, applying its cache policy, timeout and retry policy if configured
+    ///
+    /// On a cache hit the cached update is returned without polling the node. The node's
+    /// future is dropped if the config's cancellation token fires first. The run's
+    /// callbacks are told when the node starts and how it finished.
+    async fn invoke_node(
+        &self,
+        name: &str,
+        node: &Arc<dyn Node<S>>,
+        state: &S,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+    ) -> (Result<StateUpdate, GraphError>, NodeAttempts) {
+        let callbacks = RunCallbacks::new(&self.callbacks, config);
+        let started = callbacks.node_started(name);
+        let (result, attempts) = self
+            .invoke_cached_node(name, node, state, config, store)
+            .await;
+        callbacks.node_finished(name, &result, started);
+        (result, attempts)
+    }
+
+    /// Invoke a node through its cache policy, if it has one
+    async fn invoke_cached_node(
+        &self,
+        name: &str,
+        node: &Arc<dyn Node<S>>,
+        state: &S,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+    ) -> (Result<StateUpdate, GraphError>, NodeAttempts) {
+        let Some(policy) = self.node_cache_policies.get(name) else {
+            return self.run_node(name, node, state, config, store).await;
+        };
+        let key = match policy.key(name, state) {
+            Ok(key) => key,
+            Err(e) => return (Err(e), NodeAttempts::default()),
+        };
+        match self.node_cache.get(name, &key).await {
+            Ok(Some(update)) => {
+                log::debug!("Node '{}' served from cache", name);
+                let attempts = NodeAttempts {
+                    cache_hit: true,
+                    ..NodeAttempts::default()
+                };
+                return (Ok(update), attempts);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Node cache lookup for '{}' failed: {}", name, e),
+        }
+        let (result, attempts) = self.run_node(name, node, state, config, store).await;
+        if let Ok(update) = &result {
+            if let Err(e) = self.node_cache.put(name, &key, update, policy.ttl).await {
+                log::warn!("Caching the update of node '{}' failed: {}", name, e);
+            }
+        }
+        (result, attempts)
+    }
+
+    /// Run a node, applying its timeout and retry policy and honouring cancellation
+    ///
+    /// The run's deadline and cancellation token are visible to the node as
+    /// [`current_deadline`](super::current_deadline) and
+    /// [`current_cancellation`](super::current_cancellation). Cancellation wins over a
+    /// node that returns once the token fires, so the run always stops at this node.
+    async fn run_node(
+        &self,
+        name: &str,
+        node: &Arc<dyn Node<S>>,
+        state: &S,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+    ) -> (Result<StateUpdate, GraphError>, NodeAttempts) {
+        let token = config.and_then(RunnableConfig::cancellation);
+        let invoke = with_cancellation(
+            token.cloned(),
+            with_deadline(
+                config.and_then(RunnableConfig::deadline),
+                invoke_with_options(
+                    name,
+                    node,
+                    self.node_options.get(name),
+                    state,
+                    config,
+                    store,
+                ),
+            ),
+        );
+        match token {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => (
+                    Err(GraphError::Cancelled {
+                        node: name.to_string(),
+                    }),
+                    NodeAttempts::default(),
+                ),
+                result = invoke => result,
+            },
+            None => invoke.await,
+        }
+    }
+
+    /// The fallback node for `node` if `error` is a timeout it should recover from
+    fn timeout_fallback(&self, node: &str, error: &GraphError) -> Option<String> {
+        self.node_options
+            .get(node)
+            .and_then(|options| options.timeout_fallback(error))
+            .map(str::to_string)
+    }
+
     /// Mark this graph as non-pure (nodes may perform I/O). step_once will then error unless
     /// RunnableConfig has allow_non_pure_step_once set. Use for graphs that call LLM/tools inside nodes
     /// until they are refactored to emit Actions; keeps deterministic replay safe by default.
@@ -149,6 +451,7 @@ impl<S: State + 'static> CompiledGraph<S> {
     pub async fn invoke(&self, initial_state: S) -> Result<S, GraphError> {
         let mut current_state = initial_state;
         let mut current_node = START.to_string();
+        let mut branches = BranchLog::new();
         let mut visited = HashSet::new();
         let max_iterations = 1000; // Prevent infinite loops
         let mut iterations = 0;
@@ -196,6 +499,13 @@ impl<S: State + 'static> CompiledGraph<S> {
 
                 // Get the first edge (for START, there should typically be one)
                 let edge = &edges[0];
+                if let Some(fan_out) = FanOutProgress::dispatch(edge, &current_state)? {
+                    current_node = fan_out.join.clone();
+                    current_state = self
+                        .run_fan_out(fan_out, current_state, None, None, None, &mut Vec::new())
+                        .await?;
+                    continue;
+                }
                 let next_node = edge.get_target(&current_state).await?;
                 current_node = next_node;
                 continue;
@@ -207,12 +517,33 @@ impl<S: State + 'static> CompiledGraph<S> {
                 .get(&current_node)
                 .ok_or_else(|| GraphError::NodeNotFound(current_node.clone()))?;
 
-            // Use invoke for basic invoke method (no config/store available)
-            let update = node.invoke(&current_state).await?;
+            // No config/store available in the basic invoke method
+            let (update, _) = self
+                .invoke_node(&current_node, node, &current_state, None, None)
+                .await;
+            let update = match update {
+                Ok(update) => update,
+                Err(e) => match self.timeout_fallback(&current_node, &e) {
+                    Some(fallback) => {
+                        log::warn!("{}; routing to fallback '{}'", e, fallback);
+                        current_node = fallback;
+                        continue;
+                    }
+                    None => return Err(e),
+                },
+            };
 
             // Merge the update into the current state
+            let NodeCommand { update, goto } = NodeCommand::from_update(update)?;
             current_state = self.merge_state_update(&current_state, &update)?;
 
+            // A goto returned by the node takes precedence over its edges
+            if let Some(goto) = goto {
+                current_node =
+                    self.resolve_goto(&current_node, goto, &current_state, &mut branches)?;
+                continue;
+            }
+
             // Determine next node based on edges
             if edges.is_empty() {
                 return Err(GraphError::ExecutionError(format!(
@@ -223,8 +554,18 @@ impl<S: State + 'static> CompiledGraph<S> {
 
             // For regular edges, take the first one
             // For conditional edges, evaluate the condition
+            // For fan-out edges, run and reduce the branches, then continue at the join
             let edge = &edges[0];
-            let next_node = edge.get_target(&current_state).await?;
+            let next_node = match FanOutProgress::dispatch(edge, &current_state)? {
+                Some(fan_out) => {
+                    let join = fan_out.join.clone();
+                    current_state = self
+                        .run_fan_out(fan_out, current_state, None, None, None, &mut Vec::new())
+                        .await?;
+                    join
+                }
+                None => edge.get_target(&current_state).await?,
+            };
 
             // If next node is END, we're done
             if next_node == END {
@@ -237,69 +578,39 @@ impl<S: State + 'static> CompiledGraph<S> {
 
     /// Merge a state update into the current state
     ///
-    /// This handles the merging logic for different state types.
-    /// For MessagesState, we use specialized logic.
-    /// For other state types, we use the State trait's merge method.
+    /// Keys with a reducer registered through `StateGraph::set_reducer` are combined
+    /// with it; the rest go through [`State::apply_update`].
     fn merge_state_update(&self, state: &S, update: &StateUpdate) -> Result<S, GraphError> {
-        // Try to handle MessagesState specially
-        // For other state types, we'll need to serialize/deserialize
-        // This is a limitation of the current design - in a full implementation,
-        // we'd have a StateUpdate trait or similar
-
-        // Convert state to JSON to check if it's MessagesState-like
-        let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
-
-        // If state has "messages" field, treat it as MessagesState
-        if state_json.get("messages").is_some() {
-            // This is a workaround - we need to handle MessagesState specially
-            // In practice, we'd use specialization or a different approach
-            return self.merge_messages_state_update(state, update);
-        }
-
-        // For other state types, create a new state from the update and merge
-        // This requires the state to be serializable/deserializable
-        let update_json = serde_json::to_value(update).map_err(GraphError::SerializationError)?;
-
-        // Try to deserialize update as state and merge
-        // This is a simplified approach
-        let update_state: S = serde_json::from_value(update_json.clone()).map_err(|_| {
-            GraphError::StateMergeError("Cannot deserialize update as state".to_string())
-        })?;
-
-        Ok(state.merge(&update_state))
+        self.reducers.apply(state, update)
     }
 
-    /// Merge update for MessagesState (specialized handling)
-    fn merge_messages_state_update(
+    /// The node to run after `node` returned `goto`, recorded in `branches`
+    ///
+    /// A `Goto::Parent` ends this graph with `GraphError::ParentGoto`, which the
+    /// subgraph node running it turns into a jump of the parent graph.
+    fn resolve_goto(
         &self,
+        node: &str,
+        goto: Goto,
         state: &S,
-        update: &StateUpdate,
-    ) -> Result<S, GraphError> {
-        use super::state::{apply_update_to_messages_state, MessagesState};
-
-        // Try to convert state to MessagesState
-        let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
-
-        let messages_state: MessagesState = if let Some(messages_value) = state_json.get("messages")
-        {
-            if let Ok(messages) = serde_json::from_value::<Vec<crate::schemas::messages::Message>>(
-                messages_value.clone(),
-            ) {
-                MessagesState::with_messages(messages)
-            } else {
-                MessagesState::new()
+        branches: &mut BranchLog,
+    ) -> Result<String, GraphError> {
+        let target = match goto {
+            Goto::End => END.to_string(),
+            Goto::Node(target) => target,
+            Goto::Parent(target) => {
+                return Err(GraphError::ParentGoto {
+                    node: node.to_string(),
+                    target,
+                    update: full_state_update(state)?,
+                })
             }
-        } else {
-            MessagesState::new()
         };
-
-        // Apply update
-        let updated_state = apply_update_to_messages_state(&messages_state, update);
-
-        // Convert back to S
-        let updated_json =
-            serde_json::to_value(&updated_state).map_err(GraphError::SerializationError)?;
-        serde_json::from_value(updated_json).map_err(GraphError::SerializationError)
+        let target = branches.goto(node, target);
+        if target != END && !self.nodes.contains_key(&target) {
+            return Err(GraphError::NodeNotFound(target));
+        }
+        Ok(target)
     }
 
     /// Execute a single graph step from (current_state, current_node).
@@ -307,7 +618,8 @@ impl<S: State + 'static> CompiledGraph<S> {
     ///
     /// Currently requires exactly one outgoing edge from START and from each node;
     /// multi-exit is not supported and will return an error. The single allowed edge
-    /// may be conditional; routing is determined by `edge.get_target(state)`.
+    /// may be conditional; routing is determined by `edge.get_target(state)`, unless
+    /// the node returns a goto.
     /// Intended for graphs whose nodes do not perform external I/O; otherwise replay
     /// may be non-deterministic or have side effects (see kernel-api §4.2).
     pub async fn step_once(
@@ -348,6 +660,7 @@ impl<S: State + 'static> CompiledGraph<S> {
             edge.get_target(current_state).await?
         } else if current_node == END {
             return Ok(GraphStepOnceResult::Complete {
+                executed_node: None,
                 state: current_state.clone(),
             });
         } else {
@@ -356,6 +669,7 @@ impl<S: State + 'static> CompiledGraph<S> {
 
         if node_to_run == END {
             return Ok(GraphStepOnceResult::Complete {
+                executed_node: None,
                 state: current_state.clone(),
             });
         }
@@ -369,23 +683,38 @@ impl<S: State + 'static> CompiledGraph<S> {
             .get(&node_to_run)
             .ok_or_else(|| GraphError::NodeNotFound(node_to_run.clone()))?;
 
-        let update_result = node.invoke_with_context(current_state, config, store).await;
+        let (update_result, _) = self
+            .invoke_node(&node_to_run, node, current_state, config, store)
+            .await;
 
         match update_result {
             Ok(update) => {
+                let NodeCommand { update, goto } = NodeCommand::from_update(update)?;
                 let new_state = self.merge_state_update(current_state, &update)?;
-                if edges.is_empty() {
-                    return Ok(GraphStepOnceResult::Complete { state: new_state });
-                }
-                if edges.len() != 1 {
-                    return Err(GraphError::ExecutionError(
-                        "step_once requires exactly one outgoing edge per node".to_string(),
-                    ));
-                }
-                let edge = &edges[0];
-                let next_node = edge.get_target(&new_state).await?;
+                let next_node = match goto {
+                    Some(goto) => {
+                        self.resolve_goto(&node_to_run, goto, &new_state, &mut BranchLog::new())?
+                    }
+                    None => {
+                        if edges.is_empty() {
+                            return Ok(GraphStepOnceResult::Complete {
+                                executed_node: Some(node_to_run),
+                                state: new_state,
+                            });
+                        }
+                        if edges.len() != 1 {
+                            return Err(GraphError::ExecutionError(
+                                "step_once requires exactly one outgoing edge per node".to_string(),
+                            ));
+                        }
+                        edges[0].get_target(&new_state).await?
+                    }
+                };
                 if next_node == END {
-                    Ok(GraphStepOnceResult::Complete { state: new_state })
+                    Ok(GraphStepOnceResult::Complete {
+                        executed_node: Some(node_to_run),
+                        state: new_state,
+                    })
                 } else {
                     Ok(GraphStepOnceResult::Emit {
                         executed_node: node_to_run.clone(),
@@ -401,7 +730,18 @@ impl<S: State + 'static> CompiledGraph<S> {
                     value,
                 })
             }
-            Err(e) => Err(e),
+            Err(GraphError::ActionRequested(action)) => Ok(GraphStepOnceResult::Action {
+                state: current_state.clone(),
+                action,
+            }),
+            Err(e) => match self.timeout_fallback(&node_to_run, &e) {
+                Some(fallback) => Ok(GraphStepOnceResult::Emit {
+                    executed_node: node_to_run.clone(),
+                    new_state: current_state.clone(),
+                    next_node: fallback,
+                }),
+                None => Err(e),
+            },
         }
     }
 
@@ -446,6 +786,7 @@ impl<S: State + 'static> CompiledGraph<S> {
         Box::pin(stream! {
             let mut current_state = initial_state;
             let mut current_node = START.to_string();
+            let mut branches = BranchLog::new();
             let mut visited = HashSet::new();
             let max_iterations = 1000;
             let mut iterations = 0;
@@ -556,6 +897,7 @@ impl<S: State + 'static> CompiledGraph<S> {
                     use futures::StreamExt;
                     let mut subgraph_stream = subgraph.stream_with_options(current_state.clone(), subgraph_options);
                     let mut final_state = current_state.clone();
+                    let mut parent_goto = None;
 
                     while let Some(sub_event) = subgraph_stream.next().await {
                         match sub_event {
@@ -617,30 +959,31 @@ impl<S: State + 'static> CompiledGraph<S> {
                                 final_state = sub_final_state;
                             }
                             StreamEvent::Error { error } => {
+                                // A goto to this graph ends the subgraph with its final state
+                                if let GraphError::ParentGoto { target, update, .. } = &*error {
+                                    parent_goto = Some((target.clone(), update.clone()));
+                                    break;
+                                }
                                 yield StreamEvent::Error { error };
                                 return;
                             }
                         }
                     }
 
-                    // Convert final state to update
-                    let state_json = match serde_json::to_value(&final_state) {
-                        Ok(json) => json,
-                        Err(e) => {
-                            yield StreamEvent::Error {
-                                error: std::sync::Arc::new(GraphError::SerializationError(e)),
-                            };
-                            return;
-                        }
-                    };
-
-                    let mut update = HashMap::new();
-                    if let serde_json::Value::Object(map) = state_json {
-                        for (key, value) in map {
-                            update.insert(key, value);
+                    if let Some((target, update)) = parent_goto {
+                        NodeCommand::new(update).with_goto(Goto::node(target)).into_update()
+                    } else {
+                        // Convert final state to update
+                        match full_state_update(&final_state) {
+                            Ok(update) => update,
+                            Err(e) => {
+                                yield StreamEvent::Error {
+                                    error: std::sync::Arc::new(e),
+                                };
+                                return;
+                            }
                         }
                     }
-                    update
                 } else if needs_message_streaming {
                     // Try to get LLM from node for streaming
                     if let Some(llm) = node.get_llm() {
@@ -724,9 +1067,13 @@ impl<S: State + 'static> CompiledGraph<S> {
                     } else {
                         // Not an LLM node, use invoke_with_context
                         // Note: stream_internal doesn't have config/store, so pass None
-                        match node.invoke_with_context(&current_state, None, None).await {
+                        match self.invoke_node(&current_node, &node, &current_state, None, None).await.0 {
                             Ok(update) => update,
                             Err(e) => {
+                                if let Some(fallback) = self.timeout_fallback(&current_node, &e) {
+                                    current_node = fallback;
+                                    continue;
+                                }
                                 yield StreamEvent::Error {
                                     error: std::sync::Arc::new(e),
                                 };
@@ -737,9 +1084,13 @@ impl<S: State + 'static> CompiledGraph<S> {
                 } else {
                     // No message streaming needed, use invoke_with_context
                     // Note: stream_internal doesn't have config/store, so pass None
-                    match node.invoke_with_context(&current_state, None, None).await {
+                    match self.invoke_node(&current_node, &node, &current_state, None, None).await.0 {
                         Ok(update) => update,
                         Err(e) => {
+                            if let Some(fallback) = self.timeout_fallback(&current_node, &e) {
+                                current_node = fallback;
+                                continue;
+                            }
                             yield StreamEvent::Error {
                                 error: std::sync::Arc::new(e),
                             };
@@ -749,6 +1100,15 @@ impl<S: State + 'static> CompiledGraph<S> {
                 };
 
                 // Merge the update into the current state
+                let NodeCommand { update, goto } = match NodeCommand::from_update(update) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        yield StreamEvent::Error {
+                            error: std::sync::Arc::new(e),
+                        };
+                        return;
+                    }
+                };
                 current_state = match self.merge_state_update(&current_state, &update) {
                     Ok(new_state) => new_state,
                     Err(e) => {
@@ -767,6 +1127,22 @@ impl<S: State + 'static> CompiledGraph<S> {
                     path: Vec::new(), // Empty path for top-level nodes
                 };
 
+                // A goto returned by the node takes precedence over its edges
+                if let Some(goto) = goto {
+                    match self.resolve_goto(&current_node, goto, &current_state, &mut branches) {
+                        Ok(next_node) => {
+                            current_node = next_node;
+                            continue;
+                        }
+                        Err(e) => {
+                            yield StreamEvent::Error {
+                                error: std::sync::Arc::new(e),
+                            };
+                            return;
+                        }
+                    }
+                }
+
                 // Determine next node based on edges
                 if edges.is_empty() {
                     yield StreamEvent::Error {
@@ -998,14 +1374,44 @@ impl<S: State + 'static> CompiledGraph<S> {
     /// Pass `None` as `initial_state` along with a `checkpoint_id` in config to
     /// resume execution from a historical checkpoint. This creates a new fork in
     /// the execution history.
+    ///
+    /// Pass `None` with a config built by [`RunnableConfig::with_resume`] to resume an
+    /// interrupted thread: the interrupted node re-runs and its `interrupt()` call
+    /// returns the resume value.
+    ///
+    /// With the `otel` feature, the run is recorded as OpenTelemetry spans (see
+    /// [`otel`](super::otel)).
     pub async fn invoke_with_config(
         &self,
         initial_state: Option<S>,
         config: &RunnableConfig,
     ) -> Result<S, GraphError> {
-        let state_or_command = match initial_state {
-            Some(state) => StateOrCommand::State(state),
-            None => {
+        #[cfg(not(feature = "otel"))]
+        return self.invoke_with_config_inner(initial_state, config).await;
+        #[cfg(feature = "otel")]
+        {
+            use opentelemetry::trace::FutureExt;
+
+            let cx = super::otel::start_run(config);
+            let config = config.clone().with_callbacks(super::otel::node_callbacks());
+            let result = self
+                .invoke_with_config_inner(initial_state, &config)
+                .with_context(cx.clone())
+                .await;
+            super::otel::finish_run(&cx, &result);
+            result
+        }
+    }
+
+    async fn invoke_with_config_inner(
+        &self,
+        initial_state: Option<S>,
+        config: &RunnableConfig,
+    ) -> Result<S, GraphError> {
+        let state_or_command = match (initial_state, config.get_resume()) {
+            (Some(state), _) => StateOrCommand::State(state),
+            (None, Some(value)) => StateOrCommand::Command(Command::resume(value)),
+            (None, None) => {
                 // None input - load from checkpoint (by checkpoint_id or latest for crash recovery)
                 let checkpoint_config = CheckpointConfig::from_config(config)?;
                 let checkpointer = self.checkpointer.as_ref().ok_or_else(|| {
@@ -1028,6 +1434,20 @@ impl<S: State + 'static> CompiledGraph<S> {
                         checkpoint_config.thread_id
                     ))
                 })?;
+                // Continue at the pending node (e.g. past a breakpoint) rather than from START
+                if let Some(resume_from) = ResumeFrom::pending(&snapshot) {
+                    let result = self
+                        .run_interruptible(
+                            snapshot.values,
+                            config,
+                            Some(snapshot.config.clone()),
+                            Vec::new(),
+                            BranchLog::continuing(&snapshot.metadata),
+                            Some(resume_from),
+                        )
+                        .await?;
+                    return Ok(result.state);
+                }
                 StateOrCommand::State(snapshot.values)
             }
         };
@@ -1064,60 +1484,107 @@ impl<S: State + 'static> CompiledGraph<S> {
         })?;
 
         // Handle Command input or regular state
-        let (current_state, resume_values, parent_config) = match initial_state {
-            StateOrCommand::State(state) => {
-                // Regular state input
-                // Check if we should load from checkpoint (time-travel)
-                let (state, parent) = if let Some(checkpoint_id) = &checkpoint_config.checkpoint_id
-                {
-                    let snapshot = checkpointer
-                        .get(thread_id, Some(checkpoint_id))
-                        .await
-                        .map_err(|e| {
-                            GraphError::ExecutionError(format!("Failed to load checkpoint: {}", e))
-                        })?;
+        let (current_state, resume_values, parent_config, branches, resume_from) =
+            match initial_state {
+                StateOrCommand::State(state) => {
+                    // Regular state input
+                    // Check if we should load from checkpoint (time-travel)
+                    let (state, parent, branches) =
+                        if let Some(checkpoint_id) = &checkpoint_config.checkpoint_id {
+                            let snapshot = checkpointer
+                                .get(thread_id, Some(checkpoint_id))
+                                .await
+                                .map_err(|e| {
+                                GraphError::ExecutionError(format!(
+                                    "Failed to load checkpoint: {}",
+                                    e
+                                ))
+                            })?;
+
+                            let snapshot = snapshot.ok_or_else(|| {
+                                GraphError::ExecutionError(format!(
+                                    "Checkpoint not found: {}",
+                                    checkpoint_id
+                                ))
+                            })?;
+
+                            // Record parent config for fork tracking
+                            let parent = Some(snapshot.config.clone());
+                            let branches = BranchLog::from_metadata(&snapshot.metadata);
+                            (snapshot.values, parent, branches)
+                        } else {
+                            (state, None, BranchLog::new())
+                        };
+                    (state, Vec::new(), parent, branches, None)
+                }
+                StateOrCommand::Command(cmd) => {
+                    // Command input - resume from checkpoint
+                    // Get the latest checkpoint
+                    let snapshot = checkpointer.get(thread_id, None).await.map_err(|e| {
+                        GraphError::ExecutionError(format!("Failed to load checkpoint: {}", e))
+                    })?;
 
                     let snapshot = snapshot.ok_or_else(|| {
                         GraphError::ExecutionError(format!(
-                            "Checkpoint not found: {}",
-                            checkpoint_id
+                            "No checkpoint found for thread: {}",
+                            thread_id
                         ))
                     })?;
 
+                    // Extract resume values from command
+                    let resume_values = if let Some(resume_value) = cmd.resume_value() {
+                        vec![resume_value.clone()]
+                    } else {
+                        Vec::new()
+                    };
+
                     // Record parent config for fork tracking
                     let parent = Some(snapshot.config.clone());
-                    (snapshot.values, parent)
-                } else {
-                    (state, None)
-                };
-                (state, Vec::new(), parent)
-            }
-            StateOrCommand::Command(cmd) => {
-                // Command input - resume from checkpoint
-                // Get the latest checkpoint
-                let snapshot = checkpointer.get(thread_id, None).await.map_err(|e| {
-                    GraphError::ExecutionError(format!("Failed to load checkpoint: {}", e))
-                })?;
-
-                let snapshot = snapshot.ok_or_else(|| {
-                    GraphError::ExecutionError(format!(
-                        "No checkpoint found for thread: {}",
-                        thread_id
-                    ))
-                })?;
+                    // Re-run the interrupted node; without a pending node, start over and
+                    // replay recorded branches so the run follows the original path
+                    let resume_from = ResumeFrom::pending(&snapshot);
+                    let branches = if resume_from.is_some() {
+                        BranchLog::continuing(&snapshot.metadata)
+                    } else {
+                        BranchLog::from_metadata(&snapshot.metadata)
+                    };
+                    (
+                        snapshot.values,
+                        resume_values,
+                        parent,
+                        branches,
+                        resume_from,
+                    )
+                }
+            };
 
-                // Extract resume values from command
-                let resume_values = if let Some(resume_value) = cmd.resume_value() {
-                    vec![resume_value.clone()]
-                } else {
-                    Vec::new()
-                };
+        self.run_interruptible(
+            current_state,
+            config,
+            parent_config,
+            resume_values,
+            branches,
+            resume_from,
+        )
+        .await
+    }
 
-                // Record parent config for fork tracking
-                let parent = Some(snapshot.config.clone());
-                (snapshot.values, resume_values, parent)
-            }
-        };
+    /// Run with interrupt support from START, or from a checkpoint's pending node
+    ///
+    /// New checkpoints never reuse the checkpoint_id from `config`: a run loaded from
+    /// a checkpoint forks from it, and `parent_config` records where it came from.
+    async fn run_interruptible(
+        &self,
+        current_state: S,
+        config: &RunnableConfig,
+        parent_config: Option<CheckpointConfig>,
+        resume_values: Vec<serde_json::Value>,
+        branches: BranchLog,
+        resume_from: Option<ResumeFrom>,
+    ) -> Result<InvokeResult<S>, GraphError> {
+        let mut checkpoint_config = CheckpointConfig::from_config(config)?;
+        checkpoint_config.checkpoint_id = None;
+        let thread_id = &checkpoint_config.thread_id;
 
         // Build trace: push ResumeReceived when resuming with values (before moving resume_values)
         let mut trace = Vec::new();
@@ -1140,20 +1607,24 @@ impl<S: State + 'static> CompiledGraph<S> {
             InterruptContext::with_resume_values(resume_values)
         };
 
-        // Update checkpoint_config with parent if we're resuming from a checkpoint
-        let mut checkpoint_config = checkpoint_config.clone();
-        if let Some(ref _parent) = parent_config {
-            checkpoint_config.checkpoint_id = None; // Clear checkpoint_id to create new fork
-                                                    // Store parent in the checkpoint when saving
-        }
-
         // Create RunnableConfig from checkpoint_config for nodes
         let mut runnable_config =
-            RunnableConfig::with_thread_id(checkpoint_config.thread_id.clone());
-        if let Some(checkpoint_id) = &checkpoint_config.checkpoint_id {
+            RunnableConfig::with_thread_id(checkpoint_config.thread_id.clone())
+                .with_step_limit(config.get_step_limit())
+                .with_max_concurrency(config.get_max_concurrency());
+        if let Some(token) = config.cancellation() {
+            runnable_config = runnable_config.with_cancellation(token.clone());
+        }
+        if let Some(deadline) = config.deadline() {
+            runnable_config = runnable_config.with_deadline(deadline);
+        }
+        for callbacks in config.callbacks() {
+            runnable_config = runnable_config.with_callbacks(callbacks.clone());
+        }
+        if let Some(checkpoint_ns) = &checkpoint_config.checkpoint_ns {
             runnable_config.configurable.insert(
-                "checkpoint_id".to_string(),
-                serde_json::json!(checkpoint_id),
+                "checkpoint_ns".to_string(),
+                serde_json::json!(checkpoint_ns),
             );
         }
 
@@ -1166,6 +1637,8 @@ impl<S: State + 'static> CompiledGraph<S> {
                 Some(&runnable_config),
                 self.store.clone(),
                 &mut trace,
+                branches,
+                resume_from,
                 self.event_store.as_ref(),
                 &checkpoint_config.thread_id,
             )
@@ -1176,34 +1649,149 @@ impl<S: State + 'static> CompiledGraph<S> {
         result
     }
 
-    /// Execute graph with interrupt support
+    /// Execute this graph as a subgraph node of a running parent graph
     ///
-    /// Internal method that executes the graph and handles interrupts.
-    /// Fills `trace` with StepCompleted, InterruptReached events.
-    async fn execute_with_interrupt_support(
+    /// Unlike `invoke_with_config_interrupt`, this keeps the parent's interrupt context so
+    /// resume values reach nodes inside the subgraph, and it reports an interrupt as
+    /// `GraphError::InterruptError` so it bubbles up to the parent's invoke caller.
+    /// Checkpoints are written under the `checkpoint_ns` carried by `config`; when
+    /// resuming, the subgraph continues at the pending node of the latest checkpoint in
+    /// that namespace.
+    pub(crate) async fn invoke_as_subgraph(
         &self,
-        initial_state: S,
-        checkpoint_config: &CheckpointConfig,
-        parent_config: Option<&CheckpointConfig>,
-        config: Option<&RunnableConfig>,
-        store: Option<StoreBox>,
-        trace: &mut Vec<TraceEvent>,
-        event_store: Option<&Arc<dyn EventStore>>,
-        run_id: &String,
-    ) -> Result<InvokeResult<S>, GraphError> {
-        let mut current_state = initial_state;
-        let mut current_node = START.to_string();
-        let mut visited = HashSet::new();
-        let max_iterations = 1000;
-        let mut iterations = 0;
-
-        loop {
-            if iterations >= max_iterations {
-                return Err(GraphError::ExecutionError(
-                    "Maximum iterations reached. Possible infinite loop.".to_string(),
-                ));
+        state: S,
+        config: &RunnableConfig,
+    ) -> Result<S, GraphError> {
+        let mut checkpoint_config = CheckpointConfig::from_config(config)?;
+        checkpoint_config.checkpoint_id = None;
+
+        // When resuming, continue from the pending node of this namespace's latest checkpoint
+        // instead of re-running the nodes before it
+        let resumed = match &self.checkpointer {
+            Some(checkpointer) if has_resume_values() => checkpointer
+                .list_namespace(
+                    &checkpoint_config.thread_id,
+                    checkpoint_config.checkpoint_ns.as_deref(),
+                    Some(1),
+                )
+                .await
+                .map_err(|e| {
+                    GraphError::ExecutionError(format!("Failed to load checkpoint: {}", e))
+                })?
+                .pop(),
+            _ => None,
+        };
+        let (state, parent_config, branches, resume_from) = match resumed {
+            Some(snapshot) => match ResumeFrom::pending(&snapshot) {
+                Some(resume) => (
+                    snapshot.values,
+                    Some(snapshot.config),
+                    BranchLog::continuing(&snapshot.metadata),
+                    Some(resume),
+                ),
+                None => (
+                    state,
+                    None,
+                    BranchLog::from_metadata(&snapshot.metadata),
+                    None,
+                ),
+            },
+            None => (state, None, BranchLog::new(), None),
+        };
+
+        let mut trace = Vec::new();
+        let result = self
+            .execute_with_interrupt_support(
+                state,
+                &checkpoint_config,
+                parent_config.as_ref(),
+                Some(config),
+                self.store.clone(),
+                &mut trace,
+                branches,
+                resume_from,
+                None,
+                &checkpoint_config.thread_id,
+            )
+            .await?;
+
+        match result
+            .interrupt
+            .and_then(|interrupts| interrupts.into_iter().next())
+        {
+            Some(interrupt) => Err(InterruptError::new(interrupt.value).into_graph_error()),
+            None => Ok(result.state),
+        }
+    }
+
+    /// Execute graph with interrupt support
+    ///
+    /// Internal method that executes the graph and handles interrupts.
+    /// Fills `trace` with StepCompleted, InterruptReached events.
+    /// Conditional edges are routed through `branches`, which replays decisions
+    /// recorded in a resumed checkpoint before evaluating routers again.
+    async fn execute_with_interrupt_support(
+        &self,
+        initial_state: S,
+        checkpoint_config: &CheckpointConfig,
+        parent_config: Option<&CheckpointConfig>,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+        trace: &mut Vec<TraceEvent>,
+        mut branches: BranchLog,
+        resume_from: Option<ResumeFrom>,
+        event_store: Option<&Arc<dyn EventStore>>,
+        run_id: &String,
+    ) -> Result<InvokeResult<S>, GraphError> {
+        let (mut current_node, mut past_breakpoint, mut fan_out, input) = match resume_from {
+            Some(resume) => (
+                resume.node,
+                resume.past_breakpoint,
+                resume.fan_out,
+                resume.input,
+            ),
+            None => {
+                let input = match self.checkpointer {
+                    Some(_) => Some(serde_json::to_value(&initial_state)?),
+                    None => None,
+                };
+                (START.to_string(), false, None, input)
+            }
+        };
+        let mut current_state = initial_state;
+        let mut visited = HashSet::new();
+        let mut node_attempts: HashMap<String, NodeAttempts> = HashMap::new();
+        let step_limit = config.map_or(DEFAULT_STEP_LIMIT, |c| c.get_step_limit());
+        let mut steps = 0;
+        let mut last_node = START.to_string();
+
+        loop {
+            // Run the branches of a dispatched (or resumed) fan-out, then continue at its join
+            if let Some(pending) = fan_out.take() {
+                let pause = PauseContext {
+                    checkpoint_config,
+                    parent_config,
+                    branches: &branches,
+                    node_attempts: &node_attempts,
+                    fan_out: None,
+                    event_store,
+                    run_id,
+                    config,
+                    input: input.as_ref(),
+                };
+                current_node = pending.join.clone();
+                current_state = self
+                    .run_fan_out(
+                        pending,
+                        current_state,
+                        config,
+                        store.clone(),
+                        Some(pause),
+                        trace,
+                    )
+                    .await?;
+                continue;
             }
-            iterations += 1;
 
             if current_node == END {
                 if let Some(es) = event_store {
@@ -1238,8 +1826,10 @@ impl<S: State + 'static> CompiledGraph<S> {
                     ));
                 }
                 let edge = &edges[0];
-                let next_node = edge.get_target(&current_state).await?;
-                current_node = next_node;
+                fan_out = FanOutProgress::dispatch(edge, &current_state)?;
+                if fan_out.is_none() {
+                    current_node = edge.route(&current_state, &mut branches).await?;
+                }
                 continue;
             }
 
@@ -1249,6 +1839,79 @@ impl<S: State + 'static> CompiledGraph<S> {
                 .get(&current_node)
                 .ok_or_else(|| GraphError::NodeNotFound(current_node.clone()))?;
 
+            // Cancellation is honoured between nodes
+            if config.is_some_and(RunnableConfig::is_cancelled) {
+                let pause = PauseContext {
+                    checkpoint_config,
+                    parent_config,
+                    branches: &branches,
+                    node_attempts: &node_attempts,
+                    fan_out: None,
+                    event_store,
+                    run_id,
+                    config,
+                    input: input.as_ref(),
+                };
+                return Err(self.cancel_at(&pause, &current_state, &current_node).await);
+            }
+
+            // Static breakpoint before the node (passed once when resuming at it)
+            if !std::mem::take(&mut past_breakpoint)
+                && self.interrupt_before.contains(&current_node)
+            {
+                let pause = PauseContext {
+                    checkpoint_config,
+                    parent_config,
+                    branches: &branches,
+                    node_attempts: &node_attempts,
+                    fan_out: None,
+                    event_store,
+                    run_id,
+                    config,
+                    input: input.as_ref(),
+                };
+                return self
+                    .pause_at_breakpoint(
+                        &pause,
+                        current_state,
+                        Breakpoint::before(current_node.clone()),
+                        vec![current_node.clone()],
+                        trace,
+                    )
+                    .await;
+            }
+
+            let limit_error = if steps >= step_limit {
+                Some(GraphError::StepLimitExceeded {
+                    limit: step_limit,
+                    last_node: last_node.clone(),
+                })
+            } else if config.is_some_and(RunnableConfig::is_past_deadline) {
+                Some(GraphError::DeadlineExceeded {
+                    node: current_node.clone(),
+                })
+            } else {
+                None
+            };
+            if let Some(error) = limit_error {
+                let pause = PauseContext {
+                    checkpoint_config,
+                    parent_config,
+                    branches: &branches,
+                    node_attempts: &node_attempts,
+                    fan_out: None,
+                    event_store,
+                    run_id,
+                    config,
+                    input: input.as_ref(),
+                };
+                return Err(self
+                    .fail_at_limit(&pause, &current_state, &current_node, error)
+                    .await);
+            }
+            steps += 1;
+            last_node = current_node.clone();
+
             // Event-first (2.0): append ActionRequested before node execution (node step as action)
             let action_id = if event_store.is_some() {
                 Some(format!("{}-{}", run_id, current_node))
@@ -1268,13 +1931,15 @@ impl<S: State + 'static> CompiledGraph<S> {
                 .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
             }
 
-            // Execute node and handle interrupts
-            // Use invoke_with_context to support config and store
-            let update_result = node
-                .invoke_with_context(&current_state, config, store.clone())
+            // Execute node (retrying per its policy) and handle interrupts
+            let (update_result, attempts) = self
+                .invoke_node(&current_node, node, &current_state, config, store.clone())
                 .await;
+            if attempts.is_notable() {
+                node_attempts.insert(current_node.clone(), attempts);
+            }
 
-            match update_result {
+            let goto = match update_result {
                 Ok(update) => {
                     // Event-first (2.0): append ActionSucceeded after node success
                     if let (Some(es), Some(ref aid)) = (event_store, &action_id) {
@@ -1285,6 +1950,7 @@ impl<S: State + 'static> CompiledGraph<S> {
                             &[Event::ActionSucceeded {
                                 action_id: aid.clone(),
                                 output,
+                                dry_run: false,
                             }],
                         )
                         .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
@@ -1293,24 +1959,33 @@ impl<S: State + 'static> CompiledGraph<S> {
                     trace.push(TraceEvent::StepCompleted {
                         node: current_node.clone(),
                     });
+                    let NodeCommand { update, goto } = NodeCommand::from_update(update)?;
                     current_state = self.merge_state_update(&current_state, &update)?;
                     // Event-first (2.0): append StateUpdated after each node
+                    // with the hash that replaying the payloads reproduces
                     if let Some(es) = event_store {
                         let payload = serde_json::to_value(&current_state)
                             .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
+                        let state_hash = canonical_state_hash(&payload)
+                            .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
                         es.append(
                             run_id,
                             &[Event::StateUpdated {
                                 step_id: Some(current_node.clone()),
                                 payload,
+                                state_hash: Some(hex::encode(state_hash)),
+                                merge_report: None,
                             }],
                         )
                         .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
                     }
+                    goto
                 }
                 Err(GraphError::InterruptError(interrupt_err)) => {
                     // Interrupt occurred - save checkpoint and return
                     let interrupt_value = interrupt_err.value().clone();
+                    #[cfg(feature = "otel")]
+                    super::otel::record_interrupt(&current_node, &interrupt_value);
                     trace.push(TraceEvent::InterruptReached {
                         value: interrupt_value.clone(),
                     });
@@ -1322,6 +1997,7 @@ impl<S: State + 'static> CompiledGraph<S> {
                                 Event::ActionFailed {
                                     action_id: aid.clone(),
                                     error: "Interrupt".to_string(),
+                                    dry_run: false,
                                 },
                                 Event::Interrupted {
                                     value: interrupt_value.clone(),
@@ -1343,38 +2019,26 @@ impl<S: State + 'static> CompiledGraph<S> {
                     // Save checkpoint at interrupt point
                     // Note: checkpointer should always be available when using interrupt support
                     // (checked in invoke_with_config_interrupt)
-                    let mut snapshot = if let Some(parent) = parent_config {
-                        // Create snapshot with parent config for fork tracking
-                        StateSnapshot::with_parent(
-                            current_state.clone(),
-                            vec![current_node.clone()],
-                            checkpoint_config.clone(),
-                            parent.clone(),
-                        )
-                    } else {
-                        StateSnapshot::new(
-                            current_state.clone(),
-                            vec![current_node.clone()],
-                            checkpoint_config.clone(),
-                        )
+                    let pause = PauseContext {
+                        checkpoint_config,
+                        parent_config,
+                        branches: &branches,
+                        node_attempts: &node_attempts,
+                        fan_out: None,
+                        event_store,
+                        run_id,
+                        config,
+                        input: input.as_ref(),
                     };
-                    if let Some(es) = event_store {
-                        let seq = es
-                            .head(run_id)
-                            .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
-                        snapshot = snapshot.with_at_seq(seq);
-                    }
-                    if let Some(checkpointer) = &self.checkpointer {
-                        checkpointer
-                            .put(checkpoint_config.thread_id.as_str(), &snapshot)
-                            .await
-                            .map_err(|e| {
-                                GraphError::ExecutionError(format!(
-                                    "Failed to save checkpoint: {}",
-                                    e
-                                ))
-                            })?;
-                    }
+                    self.put_pause_checkpoint(
+                        &pause,
+                        &current_state,
+                        vec![current_node.clone()],
+                        Some(&interrupt),
+                        None,
+                        None,
+                    )
+                    .await?;
 
                     return Ok(InvokeResult::with_interrupt_and_trace(
                         current_state,
@@ -1390,23 +2054,76 @@ impl<S: State + 'static> CompiledGraph<S> {
                             &[Event::ActionFailed {
                                 action_id: aid.clone(),
                                 error: e.to_string(),
+                                dry_run: false,
                             }],
                         );
                     }
+                    if matches!(e, GraphError::Cancelled { .. }) {
+                        let pause = PauseContext {
+                            checkpoint_config,
+                            parent_config,
+                            branches: &branches,
+                            node_attempts: &node_attempts,
+                            fan_out: None,
+                            event_store,
+                            run_id,
+                            config,
+                            input: input.as_ref(),
+                        };
+                        return Err(self.cancel_at(&pause, &current_state, &current_node).await);
+                    }
+                    if let Some(fallback) = self.timeout_fallback(&current_node, &e) {
+                        log::warn!("{}; routing to fallback '{}'", e, fallback);
+                        current_node = fallback;
+                        continue;
+                    }
                     return Err(e);
                 }
-            }
-
-            // Determine next node
-            if edges.is_empty() {
-                return Err(GraphError::ExecutionError(format!(
-                    "No edges from node: {}",
-                    current_node
-                )));
-            }
+            };
 
-            let edge = &edges[0];
-            let next_node = edge.get_target(&current_state).await?;
+            // Determine next node; a goto returned by the node takes precedence over its edges
+            let next_node = match goto {
+                Some(goto) => {
+                    self.resolve_goto(&current_node, goto, &current_state, &mut branches)?
+                }
+                None => {
+                    if edges.is_empty() {
+                        return Err(GraphError::ExecutionError(format!(
+                            "No edges from node: {}",
+                            current_node
+                        )));
+                    }
+                    let edge = &edges[0];
+                    if let Some(dispatched) = FanOutProgress::dispatch(edge, &current_state)? {
+                        // A breakpoint after the node pauses before any branch runs
+                        if self.interrupt_after.contains(&current_node) {
+                            let pause = PauseContext {
+                                checkpoint_config,
+                                parent_config,
+                                branches: &branches,
+                                node_attempts: &node_attempts,
+                                fan_out: Some(&dispatched),
+                                event_store,
+                                run_id,
+                                config,
+                                input: input.as_ref(),
+                            };
+                            return self
+                                .pause_at_breakpoint(
+                                    &pause,
+                                    current_state,
+                                    Breakpoint::after(current_node.clone()),
+                                    dispatched.next_nodes(),
+                                    trace,
+                                )
+                                .await;
+                        }
+                        fan_out = Some(dispatched);
+                        continue;
+                    }
+                    edge.route(&current_state, &mut branches).await?
+                }
+            };
 
             if next_node == END {
                 if let Some(es) = event_store {
@@ -1419,10 +2136,332 @@ impl<S: State + 'static> CompiledGraph<S> {
                 ));
             }
 
+            // Static breakpoint after the node; resuming continues at `next_node`
+            if self.interrupt_after.contains(&current_node) {
+                let pause = PauseContext {
+                    checkpoint_config,
+                    parent_config,
+                    branches: &branches,
+                    node_attempts: &node_attempts,
+                    fan_out: None,
+                    event_store,
+                    run_id,
+                    config,
+                    input: input.as_ref(),
+                };
+                return self
+                    .pause_at_breakpoint(
+                        &pause,
+                        current_state,
+                        Breakpoint::after(current_node.clone()),
+                        vec![next_node],
+                        trace,
+                    )
+                    .await;
+            }
+
             current_node = next_node;
         }
     }
 
+    /// Run the unfinished branches of a fan-out and reduce every branch update into `state`
+    ///
+    /// Each branch runs its worker on `state` merged with the branch payload, with at
+    /// most `max_concurrency` branches in flight. Updates are merged in dispatch order,
+    /// so the result does not depend on which branch finishes first. With `pause`, the
+    /// progress is checkpointed when the fan-out starts and after every finished branch;
+    /// a failed or cancelled branch stops the fan-out and leaves the finished ones recorded.
+    async fn run_fan_out(
+        &self,
+        mut fan_out: FanOutProgress,
+        state: S,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+        pause: Option<PauseContext<'_>>,
+        trace: &mut Vec<TraceEvent>,
+    ) -> Result<S, GraphError> {
+        use futures::StreamExt;
+
+        self.put_fan_out_checkpoint(pause, &state, &fan_out, None)
+            .await?;
+
+        let limit = config.map_or(DEFAULT_MAX_CONCURRENCY, RunnableConfig::get_max_concurrency);
+        let (state_ref, store_ref) = (&state, &store);
+        let mut running = futures::stream::iter(fan_out.pending())
+            .map(|(index, send)| async move {
+                let result = match self.nodes.get(&send.node) {
+                    Some(node) => match self.merge_state_update(state_ref, &send.payload) {
+                        Ok(branch_state) => {
+                            self.invoke_node(
+                                &send.node,
+                                node,
+                                &branch_state,
+                                config,
+                                store_ref.clone(),
+                            )
+                            .await
+                            .0
+                        }
+                        Err(e) => Err(e),
+                    },
+                    None => Err(GraphError::NodeNotFound(send.node.clone())),
+                };
+                (index, result)
+            })
+            .buffer_unordered(limit);
+
+        while let Some((index, result)) = running.next().await {
+            let node = fan_out.sends[index].node.clone();
+            match result {
+                Ok(update) => {
+                    trace.push(TraceEvent::StepCompleted { node });
+                    fan_out.completed.insert(index, update);
+                    self.put_fan_out_checkpoint(pause, &state, &fan_out, None)
+                        .await?;
+                }
+                Err(GraphError::Cancelled { node }) => {
+                    log::info!("Fan-out from '{}' cancelled at '{}'", fan_out.from, node);
+                    if let Some(pause) = &pause {
+                        record_paused(pause)?;
+                    }
+                    self.put_fan_out_checkpoint(pause, &state, &fan_out, Some(CANCELLED_STATUS))
+                        .await?;
+                    return Err(GraphError::Cancelled { node });
+                }
+                Err(GraphError::InterruptError(_)) => {
+                    return Err(GraphError::ExecutionError(format!(
+                        "Fan-out branch '{}' interrupted; interrupts are not supported in fan-out branches",
+                        node
+                    )));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        drop(running);
+
+        let updates: Vec<(String, StateUpdate)> = fan_out
+            .completed
+            .into_iter()
+            .map(|(index, update)| (fan_out.sends[index].node.clone(), update))
+            .collect();
+        self.reducers.apply_all(&state, &updates)
+    }
+
+    /// Persist the progress of a running fan-out, pending at its unfinished workers
+    async fn put_fan_out_checkpoint(
+        &self,
+        pause: Option<PauseContext<'_>>,
+        state: &S,
+        fan_out: &FanOutProgress,
+        status: Option<&str>,
+    ) -> Result<(), GraphError> {
+        let Some(pause) = pause else {
+            return Ok(());
+        };
+        let pause = PauseContext {
+            fan_out: Some(fan_out),
+            ..pause
+        };
+        self.put_pause_checkpoint(&pause, state, fan_out.next_nodes(), None, None, status)
+            .await?;
+        Ok(())
+    }
+
+    /// Stop at a static breakpoint: record it, write a checkpoint whose `next` is the
+    /// pending node, and return an interrupt carrying the breakpoint.
+    async fn pause_at_breakpoint(
+        &self,
+        pause: &PauseContext<'_>,
+        state: S,
+        breakpoint: Breakpoint,
+        next: Vec<String>,
+        trace: &mut Vec<TraceEvent>,
+    ) -> Result<InvokeResult<S>, GraphError> {
+        trace.push(TraceEvent::InterruptReached {
+            value: breakpoint.to_value(),
+        });
+        let (interrupt, _) = self
+            .record_breakpoint(pause, &state, &breakpoint, next)
+            .await?;
+        Ok(InvokeResult::with_interrupt_and_trace(
+            state,
+            vec![interrupt],
+            std::mem::take(trace),
+        ))
+    }
+
+    /// Record a static breakpoint in the event log and persist the checkpoint pending
+    /// at `next`, returning the breakpoint's interrupt and the checkpoint id.
+    async fn record_breakpoint(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        breakpoint: &Breakpoint,
+        next: Vec<String>,
+    ) -> Result<(Interrupt, Option<String>), GraphError> {
+        let value = breakpoint.to_value();
+        if let Some(es) = pause.event_store {
+            es.append(
+                pause.run_id,
+                &[Event::Interrupted {
+                    value: value.clone(),
+                }],
+            )
+            .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
+        }
+        let interrupt = Interrupt::new(value);
+        let checkpoint_id = self
+            .put_pause_checkpoint(pause, state, next, Some(&interrupt), Some(breakpoint), None)
+            .await?;
+        Ok((interrupt, checkpoint_id))
+    }
+
+    /// Stop a run that exceeded its step limit or deadline: record the failure and persist
+    /// a checkpoint pending at `next`, so the thread can be resumed with a higher limit or
+    /// a fresh deadline.
+    ///
+    /// Returns `error`, or the error that prevented persisting the checkpoint.
+    async fn fail_at_limit(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        next: &str,
+        error: GraphError,
+    ) -> GraphError {
+        if let Some(es) = pause.event_store {
+            if let Err(e) = es.append(
+                pause.run_id,
+                &[Event::Failed {
+                    reason: error.to_string(),
+                    code: error.code().map(str::to_string),
+                }],
+            ) {
+                return GraphError::ExecutionError(e.to_string());
+            }
+        }
+        let status = matches!(error, GraphError::DeadlineExceeded { .. })
+            .then_some(DEADLINE_EXCEEDED_STATUS);
+        match self
+            .put_pause_checkpoint(pause, state, vec![next.to_string()], None, None, status)
+            .await
+        {
+            Ok(_) => error,
+            Err(e) => e,
+        }
+    }
+
+    /// Stop a cancelled run: record `Paused` and persist a checkpoint pending at `node`
+    /// with status `cancelled`, so invoking the thread again resumes at that node.
+    ///
+    /// Returns `GraphError::Cancelled`, or the error that prevented persisting the checkpoint.
+    async fn cancel_at(&self, pause: &PauseContext<'_>, state: &S, node: &str) -> GraphError {
+        match self.record_cancelled(pause, state, node).await {
+            Ok(_) => GraphError::Cancelled {
+                node: node.to_string(),
+            },
+            Err(e) => e,
+        }
+    }
+
+    /// Record `Paused` for a run cancelled at `node` and persist its `cancelled`
+    /// checkpoint, returning the checkpoint id.
+    async fn record_cancelled(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        node: &str,
+    ) -> Result<Option<String>, GraphError> {
+        log::info!("Run '{}' cancelled at node '{}'", pause.run_id, node);
+        record_paused(pause)?;
+        self.put_pause_checkpoint(
+            pause,
+            state,
+            vec![node.to_string()],
+            None,
+            None,
+            Some(CANCELLED_STATUS),
+        )
+        .await
+    }
+
+    /// Persist the checkpoint written when an interruptible run pauses or stops
+    ///
+    /// Returns the checkpoint id, or `None` when no checkpointer is configured.
+    async fn put_pause_checkpoint(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        next: Vec<String>,
+        interrupt: Option<&Interrupt>,
+        breakpoint: Option<&Breakpoint>,
+        status: Option<&str>,
+    ) -> Result<Option<String>, GraphError> {
+        let checkpoint_config = pause.checkpoint_config;
+        let mut snapshot = if let Some(parent) = pause.parent_config {
+            // Create snapshot with parent config for fork tracking
+            StateSnapshot::with_parent(
+                state.clone(),
+                next,
+                checkpoint_config.clone(),
+                parent.clone(),
+            )
+        } else {
+            StateSnapshot::new(state.clone(), next, checkpoint_config.clone())
+        };
+        snapshot
+            .metadata
+            .insert(BRANCHES_METADATA_KEY.to_string(), pause.branches.to_value());
+        if !pause.node_attempts.is_empty() {
+            snapshot.metadata.insert(
+                NODE_ATTEMPTS_METADATA_KEY.to_string(),
+                node_attempts_value(pause.node_attempts),
+            );
+        }
+        if let Some(interrupt) = interrupt {
+            snapshot.metadata.insert(
+                INTERRUPTS_METADATA_KEY.to_string(),
+                serde_json::to_value(std::slice::from_ref(interrupt))?,
+            );
+        }
+        if let Some(breakpoint) = breakpoint {
+            snapshot
+                .metadata
+                .insert(BREAKPOINT_METADATA_KEY.to_string(), breakpoint.to_value());
+        }
+        if let Some(status) = status {
+            snapshot
+                .metadata
+                .insert(STATUS_METADATA_KEY.to_string(), serde_json::json!(status));
+        }
+        if let Some(fan_out) = pause.fan_out {
+            snapshot
+                .metadata
+                .insert(FAN_OUT_METADATA_KEY.to_string(), fan_out.to_value());
+        }
+        if let Some(input) = pause.input {
+            snapshot
+                .metadata
+                .insert(INPUT_METADATA_KEY.to_string(), input.clone());
+        }
+        #[cfg(feature = "otel")]
+        super::otel::annotate_metadata(&mut snapshot.metadata);
+        if let Some(es) = pause.event_store {
+            let seq = es
+                .head(pause.run_id)
+                .map_err(|e| GraphError::ExecutionError(e.to_string()))?;
+            snapshot = snapshot.with_at_seq(seq);
+        }
+        let Some(checkpointer) = &self.checkpointer else {
+            return Ok(None);
+        };
+        let checkpoint_id = checkpointer
+            .put(checkpoint_config.thread_id.as_str(), &snapshot)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to save checkpoint: {}", e)))?;
+        RunCallbacks::new(&self.callbacks, pause.config).checkpoint(&checkpoint_id);
+        Ok(Some(checkpoint_id))
+    }
+
     /// Invoke the graph with initial state, config, and durability mode
     ///
     /// This method supports checkpointing, resuming from checkpoints, and
@@ -1523,12 +2562,25 @@ impl<S: State + 'static> CompiledGraph<S> {
 
         // Use super-step executor for parallel execution
         let scheduler = NodeScheduler::new(self.adjacency.clone());
-        let executor = SuperStepExecutor::new(
-            self.nodes.clone(),
-            scheduler,
-            self.checkpointer.clone(),
-            durability_mode,
-        );
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(name, node)| {
+                let node = match self.node_options.get(name) {
+                    Some(options) => Arc::new(OptionsNode::new(
+                        name.clone(),
+                        node.clone(),
+                        options.clone(),
+                    )) as Arc<dyn Node<S>>,
+                    None => node.clone(),
+                };
+                (name.clone(), node)
+            })
+            .collect();
+        let executor =
+            SuperStepExecutor::new(nodes, scheduler, self.checkpointer.clone(), durability_mode)
+                .with_reducers(self.reducers.clone())
+                .with_callbacks(self.callbacks.clone());
 
         // Create new checkpoint config without checkpoint_id for new fork
         let mut new_checkpoint_config = checkpoint_config.clone();
@@ -1612,77 +2664,574 @@ impl<S: State + 'static> CompiledGraph<S> {
         })
     }
 
-    /// Get the current state for a thread
-    ///
-    /// Returns the latest checkpoint for the given thread_id.
-    pub async fn get_state(&self, config: &RunnableConfig) -> Result<StateSnapshot<S>, GraphError> {
-        let checkpoint_config = CheckpointConfig::from_config(config)?;
-        let thread_id = &checkpoint_config.thread_id;
-
-        let checkpointer = self
-            .checkpointer
-            .as_ref()
-            .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
-
-        let snapshot = checkpointer
-            .get(thread_id, checkpoint_config.checkpoint_id.as_deref())
-            .await
-            .map_err(|e| GraphError::ExecutionError(format!("Failed to get state: {}", e)))?;
-
-        snapshot.ok_or_else(|| {
-            GraphError::ExecutionError(format!("No state found for thread: {}", thread_id))
-        })
-    }
-
-    /// Get the state history for a thread
+    /// Stream a checkpointed run, yielding per-node updates
     ///
-    /// Returns all checkpoints for the given thread_id in chronological order.
-    pub async fn get_state_history(
-        &self,
+    /// Equivalent to [Self::stream_with_config_and_payload] with [StreamPayload::Updates].
+    pub fn stream_with_config<'a>(
+        &'a self,
+        initial_state: S,
         config: &RunnableConfig,
-    ) -> Result<Vec<StateSnapshot<S>>, GraphError> {
-        let checkpoint_config = CheckpointConfig::from_config(config)?;
-        let thread_id = &checkpoint_config.thread_id;
-
-        let checkpointer = self
-            .checkpointer
-            .as_ref()
-            .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
-
-        checkpointer
-            .list(thread_id, None)
-            .await
-            .map_err(|e| GraphError::ExecutionError(format!("Failed to get state history: {}", e)))
+    ) -> Pin<Box<dyn Stream<Item = GraphStreamEvent<S>> + Send + 'a>> {
+        self.stream_with_config_and_payload(initial_state, config, StreamPayload::Updates)
     }
 
-    /// Update the state for a thread
+    /// Stream a checkpointed run, yielding node deltas or full state values
     ///
-    /// This creates a new checkpoint with updated state values.
-    /// The new checkpoint will be associated with the same thread, but will have
-    /// a new checkpoint_id and will record the original checkpoint as its parent
-    /// (for fork tracking in time-travel scenarios).
+    /// When a checkpointer is configured, a checkpoint is written after every node
+    /// (and at an interrupt) and reported as [GraphStreamEvent::CheckpointWritten].
+    /// Checkpoints are persisted before the corresponding event is yielded, so
+    /// dropping the stream mid-run leaves the last checkpoint intact and the run can
+    /// be resumed with `invoke_with_config(None, config)`.
     ///
-    /// # Arguments
+    /// Static breakpoints pause the run as in `invoke_with_config_interrupt`: the
+    /// checkpoint pending at the breakpoint is reported, then [GraphStreamEvent::Interrupted].
+    /// A run cancelled through the config's token ends with `GraphError::Cancelled` after
+    /// its `cancelled` checkpoint is reported.
     ///
-    /// * `config` - The runnable configuration pointing to the checkpoint to update
-    /// * `values` - State updates to apply
-    /// * `as_node` - Optional node name to record in metadata
+    /// # Arguments
     ///
-    /// # Returns
+    /// * `initial_state` - The initial state to start execution with
+    /// * `config` - The runnable configuration (thread_id, checkpoint_ns, etc.)
+    /// * `payload` - Whether `NodeFinished` carries the node delta or the full state
+    pub fn stream_with_config_and_payload<'a>(
+        &'a self,
+        initial_state: S,
+        config: &RunnableConfig,
+        payload: StreamPayload,
+    ) -> Pin<Box<dyn Stream<Item = GraphStreamEvent<S>> + Send + 'a>> {
+        let config = config.clone();
+
+        Box::pin(stream! {
+            let mut checkpoint_config = match CheckpointConfig::from_config(&config) {
+                Ok(cfg) => cfg,
+                Err(e) => {
+                    yield GraphStreamEvent::Error { error: Arc::new(e) };
+                    return;
+                }
+            };
+            checkpoint_config.checkpoint_id = None;
+
+            let mut branches = BranchLog::new();
+            let mut node_attempts: HashMap<String, NodeAttempts> = HashMap::new();
+            let mut current_state = initial_state;
+            let mut current_node = START.to_string();
+            let step_limit = config.get_step_limit();
+            let mut steps = 0;
+            let mut last_node = START.to_string();
+            let input = match &self.checkpointer {
+                Some(_) => match serde_json::to_value(&current_state) {
+                    Ok(input) => Some(input),
+                    Err(e) => {
+                        yield GraphStreamEvent::Error { error: Arc::new(e.into()) };
+                        return;
+                    }
+                },
+                None => None,
+            };
+
+            loop {
+                if current_node == END {
+                    yield GraphStreamEvent::RunCompleted { final_state: current_state };
+                    return;
+                }
+
+                let edges = match self.adjacency.get(&current_node) {
+                    Some(edges) if !edges.is_empty() || current_node != START => edges.clone(),
+                    _ => {
+                        yield GraphStreamEvent::Error {
+                            error: Arc::new(GraphError::ExecutionError(format!(
+                                "No edges from node: {}",
+                                current_node
+                            ))),
+                        };
+                        return;
+                    }
+                };
+
+                let mut goto = None;
+                if current_node != START {
+                    let node = match self.nodes.get(&current_node) {
+                        Some(node) => node.clone(),
+                        None => {
+                            yield GraphStreamEvent::Error {
+                                error: Arc::new(GraphError::NodeNotFound(current_node.clone())),
+                            };
+                            return;
+                        }
+                    };
+
+                    // Cancellation is honoured between nodes
+                    if config.is_cancelled() {
+                        let pause = PauseContext {
+                            checkpoint_config: &checkpoint_config,
+                            parent_config: None,
+                            branches: &branches,
+                            node_attempts: &node_attempts,
+                            fan_out: None,
+                            event_store: self.event_store.as_ref(),
+                            run_id: &checkpoint_config.thread_id,
+                            config: Some(&config),
+                            input: input.as_ref(),
+                        };
+                        let events = self
+                            .stream_cancelled(&pause, &current_state, &current_node)
+                            .await;
+                        for event in events {
+                            yield event;
+                        }
+                        return;
+                    }
+
+                    // Static breakpoint before the node
+                    if self.interrupt_before.contains(&current_node) {
+                        let pause = PauseContext {
+                            checkpoint_config: &checkpoint_config,
+                            parent_config: None,
+                            branches: &branches,
+                            node_attempts: &node_attempts,
+                            fan_out: None,
+                            event_store: self.event_store.as_ref(),
+                            run_id: &checkpoint_config.thread_id,
+                            config: Some(&config),
+                            input: input.as_ref(),
+                        };
+                        let breakpoint = Breakpoint::before(current_node.clone());
+                        let next = vec![current_node.clone()];
+                        let events = self
+                            .stream_breakpoint(&pause, &current_state, breakpoint, next)
+                            .await;
+                        for event in events {
+                            yield event;
+                        }
+                        return;
+                    }
+
+                    // The checkpoint written after the previous node is already pending here
+                    if steps >= step_limit {
+                        yield GraphStreamEvent::Error {
+                            error: Arc::new(GraphError::StepLimitExceeded {
+                                limit: step_limit,
+                                last_node,
+                            }),
+                        };
+                        return;
+                    }
+                    if config.is_past_deadline() {
+                        yield GraphStreamEvent::Error {
+                            error: Arc::new(GraphError::DeadlineExceeded {
+                                node: current_node.clone(),
+                            }),
+                        };
+                        return;
+                    }
+                    steps += 1;
+                    last_node = current_node.clone();
+
+                    yield GraphStreamEvent::NodeStarted { node: current_node.clone() };
+
+                    let (update_result, attempts) = set_interrupt_context(
+                        InterruptContext::new(),
+                        self.invoke_node(
+                            &current_node,
+                            &node,
+                            &current_state,
+                            Some(&config),
+                            self.store.clone(),
+                        ),
+                    )
+                    .await;
+                    if attempts.is_notable() {
+                        node_attempts.insert(current_node.clone(), attempts);
+                    }
+
+                    let update = match update_result {
+                        Ok(update) => update,
+                        Err(GraphError::InterruptError(interrupt_err)) => {
+                            match self
+                                .put_stream_checkpoint(
+                                    &current_state,
+                                    vec![current_node.clone()],
+                                    &checkpoint_config,
+                                    &branches,
+                                    &node_attempts,
+                                    Some(&Interrupt::new(interrupt_err.value().clone())),
+                                )
+                                .await
+                            {
+                                Ok(Some(checkpoint_id)) => {
+                                    RunCallbacks::new(&self.callbacks, Some(&config))
+                                        .checkpoint(&checkpoint_id);
+                                    yield GraphStreamEvent::CheckpointWritten { checkpoint_id };
+                                }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    yield GraphStreamEvent::Error { error: Arc::new(e) };
+                                    return;
+                                }
+                            }
+                            yield GraphStreamEvent::Interrupted {
+                                node: current_node.clone(),
+                                value: interrupt_err.value().clone(),
+                            };
+                            return;
+                        }
+                        Err(GraphError::Cancelled { .. }) => {
+                            let pause = PauseContext {
+                                checkpoint_config: &checkpoint_config,
+                                parent_config: None,
+                                branches: &branches,
+                                node_attempts: &node_attempts,
+                                fan_out: None,
+                                event_store: self.event_store.as_ref(),
+                                run_id: &checkpoint_config.thread_id,
+                                config: Some(&config),
+                                input: input.as_ref(),
+                            };
+                            let events = self
+                                .stream_cancelled(&pause, &current_state, &current_node)
+                                .await;
+                            for event in events {
+                                yield event;
+                            }
+                            return;
+                        }
+                        Err(e) => {
+                            if let Some(fallback) = self.timeout_fallback(&current_node, &e) {
+                                current_node = fallback;
+                                continue;
+                            }
+                            yield GraphStreamEvent::Error { error: Arc::new(e) };
+                            return;
+                        }
+                    };
+
+                    let command = match NodeCommand::from_update(update) {
+                        Ok(command) => command,
+                        Err(e) => {
+                            yield GraphStreamEvent::Error { error: Arc::new(e) };
+                            return;
+                        }
+                    };
+                    let update = command.update;
+                    goto = command.goto;
+                    current_state = match self.merge_state_update(&current_state, &update) {
+                        Ok(state) => state,
+                        Err(e) => {
+                            yield GraphStreamEvent::Error { error: Arc::new(e) };
+                            return;
+                        }
+                    };
+
+                    let node_payload = match payload {
+                        StreamPayload::Updates => NodePayload::Update(update),
+                        StreamPayload::Values => NodePayload::Values(current_state.clone()),
+                    };
+                    yield GraphStreamEvent::NodeFinished {
+                        node: current_node.clone(),
+                        update: node_payload,
+                    };
+                }
+
+                // A goto returned by the node takes precedence over its edges
+                let next_node = match (goto, edges.first()) {
+                    (Some(goto), _) => {
+                        self.resolve_goto(&current_node, goto, &current_state, &mut branches)
+                    }
+                    (None, Some(edge)) => edge.route(&current_state, &mut branches).await,
+                    (None, None) => Err(GraphError::ExecutionError(format!(
+                        "No edges from node: {}",
+                        current_node
+                    ))),
+                };
+                let next_node = match next_node {
+                    Ok(next_node) => next_node,
+                    Err(e) => {
+                        yield GraphStreamEvent::Error { error: Arc::new(e) };
+                        return;
+                    }
+                };
+
+                // A breakpoint after the node pauses at its checkpoint; resuming continues at
+                // `next_node`
+                if next_node != END && self.interrupt_after.contains(&current_node) {
+                    let pause = PauseContext {
+                        checkpoint_config: &checkpoint_config,
+                        parent_config: None,
+                        branches: &branches,
+                        node_attempts: &node_attempts,
+                        fan_out: None,
+                        event_store: self.event_store.as_ref(),
+                        run_id: &checkpoint_config.thread_id,
+                        config: Some(&config),
+                        input: input.as_ref(),
+                    };
+                    let breakpoint = Breakpoint::after(current_node.clone());
+                    let next = vec![next_node];
+                    let events = self
+                        .stream_breakpoint(&pause, &current_state, breakpoint, next)
+                        .await;
+                    for event in events {
+                        yield event;
+                    }
+                    return;
+                }
+
+                if current_node != START {
+                    let next = if next_node == END { vec![] } else { vec![next_node.clone()] };
+                    match self
+                        .put_stream_checkpoint(
+                            &current_state,
+                            next,
+                            &checkpoint_config,
+                            &branches,
+                            &node_attempts,
+                            None,
+                        )
+                        .await
+                    {
+                        Ok(Some(checkpoint_id)) => {
+                            RunCallbacks::new(&self.callbacks, Some(&config))
+                                .checkpoint(&checkpoint_id);
+                            yield GraphStreamEvent::CheckpointWritten { checkpoint_id };
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            yield GraphStreamEvent::Error { error: Arc::new(e) };
+                            return;
+                        }
+                    }
+                }
+
+                current_node = next_node;
+            }
+        })
+    }
+
+    /// Events ending a stream at a static breakpoint: the checkpoint pending at `next`,
+    /// then the interrupt carrying the breakpoint
+    async fn stream_breakpoint(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        breakpoint: Breakpoint,
+        next: Vec<String>,
+    ) -> Vec<GraphStreamEvent<S>> {
+        match self
+            .record_breakpoint(pause, state, &breakpoint, next)
+            .await
+        {
+            Ok((interrupt, checkpoint_id)) => checkpoint_id
+                .map(|checkpoint_id| GraphStreamEvent::CheckpointWritten { checkpoint_id })
+                .into_iter()
+                .chain([GraphStreamEvent::Interrupted {
+                    node: breakpoint.node,
+                    value: interrupt.value,
+                }])
+                .collect(),
+            Err(e) => vec![GraphStreamEvent::Error { error: Arc::new(e) }],
+        }
+    }
+
+    /// Events ending a stream cancelled at `node`: the `cancelled` checkpoint pending at
+    /// `node`, then `GraphError::Cancelled`
+    async fn stream_cancelled(
+        &self,
+        pause: &PauseContext<'_>,
+        state: &S,
+        node: &str,
+    ) -> Vec<GraphStreamEvent<S>> {
+        match self.record_cancelled(pause, state, node).await {
+            Ok(checkpoint_id) => checkpoint_id
+                .map(|checkpoint_id| GraphStreamEvent::CheckpointWritten { checkpoint_id })
+                .into_iter()
+                .chain([GraphStreamEvent::Error {
+                    error: Arc::new(GraphError::Cancelled {
+                        node: node.to_string(),
+                    }),
+                }])
+                .collect(),
+            Err(e) => vec![GraphStreamEvent::Error { error: Arc::new(e) }],
+        }
+    }
+
+    /// Persist a checkpoint for `stream_with_config_and_payload`.
+    ///
+    /// Returns `None` when no checkpointer is configured.
+    async fn put_stream_checkpoint(
+        &self,
+        state: &S,
+        next: Vec<String>,
+        checkpoint_config: &CheckpointConfig,
+        branches: &BranchLog,
+        node_attempts: &HashMap<String, NodeAttempts>,
+        interrupt: Option<&Interrupt>,
+    ) -> Result<Option<String>, GraphError> {
+        let Some(checkpointer) = &self.checkpointer else {
+            return Ok(None);
+        };
+        let mut snapshot = StateSnapshot::new(state.clone(), next, checkpoint_config.clone());
+        snapshot
+            .metadata
+            .insert(BRANCHES_METADATA_KEY.to_string(), branches.to_value());
+        if !node_attempts.is_empty() {
+            snapshot.metadata.insert(
+                NODE_ATTEMPTS_METADATA_KEY.to_string(),
+                node_attempts_value(node_attempts),
+            );
+        }
+        if let Some(interrupt) = interrupt {
+            snapshot.metadata.insert(
+                INTERRUPTS_METADATA_KEY.to_string(),
+                serde_json::to_value(std::slice::from_ref(interrupt))?,
+            );
+        }
+        let checkpoint_id = checkpointer
+            .put(checkpoint_config.thread_id.as_str(), &snapshot)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to save checkpoint: {}", e)))?;
+        Ok(Some(checkpoint_id))
+    }
+
+    /// Get the current state for a thread
+    ///
+    /// Returns the checkpoint selected by the config's checkpoint_id, or else the latest
+    /// checkpoint of the graph selected by its checkpoint_ns (the root graph by default).
+    pub async fn get_state(&self, config: &RunnableConfig) -> Result<StateSnapshot<S>, GraphError> {
+        let checkpoint_config = CheckpointConfig::from_config(config)?;
+        let thread_id = &checkpoint_config.thread_id;
+
+        let checkpointer = self
+            .checkpointer
+            .as_ref()
+            .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
+
+        let snapshot = match (
+            &checkpoint_config.checkpoint_id,
+            &checkpoint_config.checkpoint_ns,
+        ) {
+            (None, Some(checkpoint_ns)) => checkpointer
+                .list_namespace(thread_id, Some(checkpoint_ns), Some(1))
+                .await
+                .map(|mut snapshots| snapshots.pop()),
+            (checkpoint_id, _) => checkpointer.get(thread_id, checkpoint_id.as_deref()).await,
+        }
+        .map_err(|e| GraphError::ExecutionError(format!("Failed to get state: {}", e)))?;
+
+        snapshot.ok_or_else(|| {
+            GraphError::ExecutionError(format!("No state found for thread: {}", thread_id))
+        })
+    }
+
+    /// Get the state history for a thread
+    ///
+    /// Returns the checkpoints of the graph selected by the config's checkpoint_ns (the
+    /// root graph by default) in chronological order. Subgraph checkpoints are read with
+    /// `config.for_subgraph(node)`.
+    pub async fn get_state_history(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<StateSnapshot<S>>, GraphError> {
+        let checkpoint_config = CheckpointConfig::from_config(config)?;
+        let thread_id = &checkpoint_config.thread_id;
+
+        let checkpointer = self
+            .checkpointer
+            .as_ref()
+            .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
+
+        checkpointer
+            .list_namespace(thread_id, checkpoint_config.checkpoint_ns.as_deref(), None)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to get state history: {}", e)))
+    }
+
+    /// Update the state for a thread
+    ///
+    /// Loads the checkpoint selected by `config` (the latest one when no checkpoint_id
+    /// is set), applies `values` through the same reducer used during execution and
+    /// writes the result as a new checkpoint whose parent is the loaded one. Use this to
+    /// correct a paused thread before resuming it with `invoke_with_config(None, ..)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The runnable configuration pointing to the checkpoint to update
+    /// * `values` - State updates to apply; every key must be a field of the state
+    /// * `as_node` - Node the update is attributed to. When set, the new checkpoint
+    ///   continues at that node's successor (evaluating conditional edges against the
+    ///   updated state), as if the node had produced the update. When `None`, the
+    ///   pending node of the loaded checkpoint is kept.
+    ///
+    /// # Returns
     ///
-    /// A new `StateSnapshot` with the updated state and new checkpoint_id
+    /// The checkpoint_id of the new checkpoint
     pub async fn update_state(
         &self,
         config: &RunnableConfig,
         values: &StateUpdate,
         as_node: Option<&str>,
-    ) -> Result<StateSnapshot<S>, GraphError> {
+    ) -> Result<String, GraphError> {
+        let checkpointer = self
+            .checkpointer
+            .as_ref()
+            .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
+
         // Get current state
         let original_snapshot = self.get_state(config).await?;
 
+        // Reject keys that are not part of the state schema
+        let state_json = serde_json::to_value(&original_snapshot.values)?;
+        if let Some(fields) = state_json.as_object() {
+            let mut unknown: Vec<&str> = values
+                .keys()
+                .filter(|key| !fields.contains_key(key.as_str()))
+                .map(String::as_str)
+                .collect();
+            if !unknown.is_empty() {
+                unknown.sort_unstable();
+                return Err(GraphError::InvalidStateUpdate(format!(
+                    "Unknown state keys: {}",
+                    unknown.join(", ")
+                )));
+            }
+        }
+
         // Apply updates to state
         let updated_values = self.merge_state_update(&original_snapshot.values, values)?;
 
+        let mut branches = BranchLog::continuing(&original_snapshot.metadata);
+        let mut metadata = HashMap::new();
+        let next = match as_node {
+            Some(node) => {
+                if !self.nodes.contains_key(node) {
+                    return Err(GraphError::NodeNotFound(node.to_string()));
+                }
+                let edge = self
+                    .adjacency
+                    .get(node)
+                    .and_then(|edges| edges.first())
+                    .ok_or_else(|| {
+                        GraphError::ExecutionError(format!("No edges from node: {}", node))
+                    })?;
+                let target = edge.route(&updated_values, &mut branches).await?;
+                metadata.insert("as_node".to_string(), serde_json::json!(node));
+                if target == END {
+                    Vec::new()
+                } else {
+                    vec![target]
+                }
+            }
+            None => {
+                // Still paused at the same place: keep what the resume path needs
+                for key in [BREAKPOINT_METADATA_KEY, INTERRUPTS_METADATA_KEY] {
+                    if let Some(value) = original_snapshot.metadata.get(key) {
+                        metadata.insert(key.to_string(), value.clone());
+                    }
+                }
+                original_snapshot.next.clone()
+            }
+        };
+        metadata.insert(BRANCHES_METADATA_KEY.to_string(), branches.to_value());
+
         // Create new checkpoint config (new checkpoint_id will be generated)
         let mut new_config = CheckpointConfig::new(original_snapshot.thread_id());
         new_config.checkpoint_ns = original_snapshot.config.checkpoint_ns.clone();
@@ -1690,33 +3239,102 @@ impl<S: State + 'static> CompiledGraph<S> {
         // Create new snapshot with parent config for fork tracking
         let mut new_snapshot = StateSnapshot::with_parent(
             updated_values,
-            original_snapshot.next.clone(),
+            next,
             new_config,
             original_snapshot.config.clone(), // Parent config
         );
+        new_snapshot.metadata = metadata;
 
-        // Update metadata
-        if let Some(node) = as_node {
-            new_snapshot
-                .metadata
-                .insert("as_node".to_string(), serde_json::json!(node));
-        }
+        checkpointer
+            .put(new_snapshot.thread_id(), &new_snapshot)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to update state: {}", e)))
+    }
 
-        // Save updated checkpoint
+    /// Fork a thread: copy its checkpoint lineage to a new thread_id
+    ///
+    /// Copies the checkpoints of the source thread in chronological order, up to and
+    /// including `up_to_checkpoint` (or the checkpoint_id in `source_config`; all
+    /// checkpoints when neither is set). Each copy gets a new checkpoint_id under
+    /// `new_thread_id` and its parent link is rewritten to the copied parent, so the
+    /// source thread is left untouched.
+    ///
+    /// # Returns
+    ///
+    /// The config for the fork; `invoke_with_config(None, ..)` with it continues from
+    /// the last copied checkpoint.
+    pub async fn fork_thread(
+        &self,
+        source_config: &RunnableConfig,
+        new_thread_id: &str,
+        up_to_checkpoint: Option<&str>,
+    ) -> Result<RunnableConfig, GraphError> {
+        let source = CheckpointConfig::from_config(source_config)?;
         let checkpointer = self
             .checkpointer
             .as_ref()
             .ok_or_else(|| GraphError::ExecutionError("Checkpointer not configured".to_string()))?;
 
-        let checkpoint_id = checkpointer
-            .put(new_snapshot.thread_id(), &new_snapshot)
+        if source.thread_id == new_thread_id {
+            return Err(GraphError::ExecutionError(
+                "Fork target must differ from the source thread".to_string(),
+            ));
+        }
+        let existing = checkpointer
+            .get(new_thread_id, None)
             .await
-            .map_err(|e| GraphError::ExecutionError(format!("Failed to update state: {}", e)))?;
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to fork thread: {}", e)))?;
+        if existing.is_some() {
+            return Err(GraphError::ExecutionError(format!(
+                "Thread already has checkpoints: {}",
+                new_thread_id
+            )));
+        }
+
+        let mut history = checkpointer
+            .list(&source.thread_id, None)
+            .await
+            .map_err(|e| GraphError::ExecutionError(format!("Failed to fork thread: {}", e)))?;
+        if let Some(up_to) = up_to_checkpoint.or(source.checkpoint_id.as_deref()) {
+            let position = history
+                .iter()
+                .position(|snapshot| snapshot.checkpoint_id().map(String::as_str) == Some(up_to))
+                .ok_or_else(|| {
+                    GraphError::ExecutionError(format!(
+                        "Checkpoint {} not found in thread {}",
+                        up_to, source.thread_id
+                    ))
+                })?;
+            history.truncate(position + 1);
+        }
+        if history.is_empty() {
+            return Err(GraphError::ExecutionError(format!(
+                "No checkpoint found for thread_id: {}",
+                source.thread_id
+            )));
+        }
 
-        // Update snapshot with new checkpoint_id
-        new_snapshot.config.checkpoint_id = Some(checkpoint_id);
+        // Old checkpoint_id -> copied checkpoint_id, for rewriting parent links
+        let mut copied: HashMap<String, String> = HashMap::new();
+        for mut snapshot in history {
+            let source_id = snapshot.checkpoint_id().cloned();
+            snapshot.config.thread_id = new_thread_id.to_string();
+            snapshot.config.checkpoint_id = None;
+            snapshot.parent_config = snapshot.parent_config.and_then(|mut parent| {
+                parent.checkpoint_id = Some(copied.get(parent.checkpoint_id.as_ref()?)?.clone());
+                parent.thread_id = new_thread_id.to_string();
+                Some(parent)
+            });
+            let checkpoint_id = checkpointer
+                .put(new_thread_id, &snapshot)
+                .await
+                .map_err(|e| GraphError::ExecutionError(format!("Failed to fork thread: {}", e)))?;
+            if let Some(source_id) = source_id {
+                copied.insert(source_id, checkpoint_id);
+            }
+        }
 
-        Ok(new_snapshot)
+        Ok(RunnableConfig::with_thread_id(new_thread_id))
     }
 }
 
@@ -1790,7 +3408,7 @@ pub enum StreamEvent<S: State> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::{function_node, state::MessagesState, StateGraph, END, START};
+    use crate::graph::{function_node, state::MessagesState, RetryPolicy, StateGraph, END, START};
     use futures::StreamExt;
 
     #[tokio::test]
@@ -1860,33 +3478,1146 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn step_once_pure_graph_default_succeeds() {
-        let mut graph = StateGraph::<MessagesState>::new();
-        graph
-            .add_node(
-                "node1",
-                function_node("node1", |_s: &MessagesState| async move {
-                    Ok(std::collections::HashMap::new())
-                }),
-            )
-            .unwrap();
-        graph.add_edge(START, "node1");
-        graph.add_edge("node1", END);
-        let compiled = graph.compile().unwrap();
-        let state = MessagesState::new();
-        let r = compiled.step_once(&state, START, None).await.unwrap();
-        assert!(matches!(r, GraphStepOnceResult::Complete { .. }));
-    }
+    async fn resume_replays_recorded_conditional_branch() {
+        use crate::graph::{interrupt, Command, InMemorySaver};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let prefer_b = Arc::new(AtomicBool::new(false));
+        let router_flag = prefer_b.clone();
 
-    #[tokio::test]
-    async fn step_once_non_pure_without_allow_errors() {
         let mut graph = StateGraph::<MessagesState>::new();
         graph
             .add_node(
-                "node1",
-                function_node("node1", |_s: &MessagesState| async move {
-                    Ok(std::collections::HashMap::new())
-                }),
+                "decide",
+                function_node(
+                    "decide",
+                    |_s: &MessagesState| async move { Ok(HashMap::new()) },
+                ),
+            )
+            .unwrap();
+        for name in ["a", "b"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        let approved = interrupt("approve?").await?;
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message(format!(
+                                "{}:{}",
+                                name, approved
+                            )),
+                        ]))
+                    }),
+                )
+                .unwrap();
+            graph.add_edge(name, END);
+        }
+        graph.add_edge(START, "decide");
+        let mut mapping = HashMap::new();
+        mapping.insert("a".to_string(), "a".to_string());
+        mapping.insert("b".to_string(), "b".to_string());
+        graph.add_conditional_edges_sync(
+            "decide",
+            move |_s: &MessagesState| {
+                if router_flag.load(Ordering::SeqCst) {
+                    "b".to_string()
+                } else {
+                    "a".to_string()
+                }
+            },
+            mapping,
+        );
+
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("branch-thread");
+
+        let first = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert!(first.has_interrupt());
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        let recorded = BranchLog::from_metadata(&snapshot.metadata);
+        assert!(recorded.is_replaying());
+
+        // The router would now pick "b", but resume must follow the recorded branch.
+        prefer_b.store(true, Ordering::SeqCst);
+        let resumed = compiled
+            .invoke_with_config_interrupt(StateOrCommand::Command(Command::resume(true)), &config)
+            .await
+            .unwrap();
+        assert!(!resumed.has_interrupt());
+        assert_eq!(resumed.state.messages.last().unwrap().content, "a:true");
+    }
+
+    #[tokio::test]
+    async fn stream_with_config_reports_nodes_and_checkpoints() {
+        use crate::graph::{InMemorySaver, NodePayload, StreamPayload};
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["first", "second"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message(name),
+                        ]))
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "first");
+        graph.add_edge("first", "second");
+        graph.add_edge("second", END);
+        let checkpointer = Arc::new(InMemorySaver::new());
+        let compiled = graph
+            .compile_with_persistence(Some(checkpointer), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("stream-thread");
+
+        let events: Vec<_> = compiled
+            .stream_with_config_and_payload(MessagesState::new(), &config, StreamPayload::Values)
+            .collect()
+            .await;
+
+        let finished: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                GraphStreamEvent::NodeFinished {
+                    update: NodePayload::Values(state),
+                    ..
+                } => Some(state.messages.len()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(finished, vec![1, 2]);
+        let checkpoints = events
+            .iter()
+            .filter(|event| matches!(event, GraphStreamEvent::CheckpointWritten { .. }))
+            .count();
+        assert_eq!(checkpoints, 2);
+        assert!(matches!(
+            events.last(),
+            Some(GraphStreamEvent::RunCompleted { final_state }) if final_state.messages.len() == 2
+        ));
+        assert_eq!(compiled.get_state_history(&config).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn dropping_stream_keeps_last_checkpoint() {
+        use crate::graph::InMemorySaver;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["first", "second"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message(name),
+                        ]))
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "first");
+        graph.add_edge("first", "second");
+        graph.add_edge("second", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("dropped-stream");
+
+        {
+            let mut stream = compiled.stream_with_config(MessagesState::new(), &config);
+            while let Some(event) = stream.next().await {
+                if matches!(event, GraphStreamEvent::CheckpointWritten { .. }) {
+                    break;
+                }
+            }
+        }
+
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.values.messages.len(), 1);
+        assert_eq!(snapshot.next, vec!["second".to_string()]);
+    }
+
+    fn flaky_graph(failures: u32, policy: RetryPolicy) -> StateGraph<MessagesState> {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node_with_retry(
+                "fetch",
+                function_node("fetch", move |_s: &MessagesState| {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        if call < failures {
+                            return Err(GraphError::ExecutionError(format!("flaky {}", call)));
+                        }
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message("fetched"),
+                        ]))
+                    }
+                }),
+                policy,
+            )
+            .unwrap();
+        graph.add_edge(START, "fetch");
+        graph.add_edge("fetch", END);
+        graph
+    }
+
+    #[tokio::test]
+    async fn retried_node_appends_single_state_update() {
+        use crate::graph::InMemorySaver;
+        use crate::kernel::{InMemoryEventStore, SharedEventStore};
+
+        let events = Arc::new(InMemoryEventStore::new());
+        let compiled = flaky_graph(2, RetryPolicy::new(3))
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap()
+            .with_event_store(Arc::new(SharedEventStore(events.clone())));
+        let config = RunnableConfig::with_thread_id("retry-thread");
+
+        let result = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert_eq!(result.state.messages.len(), 1);
+
+        let state_updates = events
+            .scan(&"retry-thread".to_string(), 1)
+            .unwrap()
+            .into_iter()
+            .filter(|record| matches!(record.event, Event::StateUpdated { .. }))
+            .count();
+        assert_eq!(state_updates, 1);
+    }
+
+    #[tokio::test]
+    async fn retry_attempts_are_recorded_in_checkpoint_metadata() {
+        use crate::graph::InMemorySaver;
+
+        let compiled = flaky_graph(2, RetryPolicy::new(3))
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("retry-metadata");
+
+        let events: Vec<_> = compiled
+            .stream_with_config(MessagesState::new(), &config)
+            .collect()
+            .await;
+        assert!(matches!(
+            events.last(),
+            Some(GraphStreamEvent::RunCompleted { .. })
+        ));
+
+        let history = compiled.get_state_history(&config).await.unwrap();
+        assert_eq!(history.len(), 1);
+        let attempts: HashMap<String, NodeAttempts> =
+            serde_json::from_value(history[0].metadata[NODE_ATTEMPTS_METADATA_KEY].clone())
+                .unwrap();
+        assert_eq!(attempts["fetch"].attempts, 3);
+        assert_eq!(
+            attempts["fetch"].last_error.as_deref(),
+            Some("Execution error: flaky 1")
+        );
+    }
+
+    #[tokio::test]
+    async fn non_retryable_error_fails_immediately() {
+        let policy = RetryPolicy::new(5).with_retry_on(|_| false);
+        let compiled = flaky_graph(1, policy).compile().unwrap();
+
+        let err = compiled.invoke(MessagesState::new()).await.unwrap_err();
+        assert!(matches!(err, GraphError::ExecutionError(msg) if msg == "flaky 0"));
+    }
+
+    #[tokio::test]
+    async fn node_timeout_fails_or_routes_to_fallback() {
+        use crate::graph::{NodeOptions, OnTimeout};
+        use std::time::Duration;
+
+        let build = |on_timeout: OnTimeout| {
+            let with_fallback = matches!(on_timeout, OnTimeout::Fallback(_));
+            let mut graph = StateGraph::<MessagesState>::new();
+            graph
+                .add_node_with_options(
+                    "slow",
+                    function_node("slow", |_s: &MessagesState| async move {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        Ok(HashMap::new())
+                    }),
+                    NodeOptions::new()
+                        .with_timeout(Duration::from_millis(10))
+                        .with_on_timeout(on_timeout),
+                )
+                .unwrap();
+            // Only reachable (and so only valid) when routed to on timeout
+            if with_fallback {
+                graph
+                    .add_node(
+                        "fallback",
+                        function_node("fallback", |_s: &MessagesState| async move {
+                            Ok(crate::graph::messages_state_update(vec![
+                                crate::schemas::messages::Message::new_ai_message("fallback"),
+                            ]))
+                        }),
+                    )
+                    .unwrap();
+                graph.add_edge("fallback", END);
+            }
+            graph.add_edge(START, "slow");
+            graph.add_edge("slow", END);
+            graph.compile().unwrap()
+        };
+
+        let err = build(OnTimeout::Fail)
+            .invoke(MessagesState::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GraphError::NodeTimeout { node, .. } if node == "slow"));
+
+        let state = build(OnTimeout::Fallback("fallback".to_string()))
+            .invoke(MessagesState::new())
+            .await
+            .unwrap();
+        assert_eq!(state.messages.last().unwrap().content, "fallback");
+    }
+
+    fn counting_graph(calls: &Arc<std::sync::Mutex<Vec<String>>>) -> StateGraph<MessagesState> {
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["prepare", "approval", "finish"] {
+            let calls = calls.clone();
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| {
+                        calls.lock().unwrap().push(name.to_string());
+                        async move {
+                            Ok(crate::graph::messages_state_update(vec![
+                                crate::schemas::messages::Message::new_ai_message(name),
+                            ]))
+                        }
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "prepare");
+        graph.add_edge("prepare", "approval");
+        graph.add_edge("approval", "finish");
+        graph.add_edge("finish", END);
+        graph
+    }
+
+    #[tokio::test]
+    async fn interrupt_before_pauses_and_resumes_once() {
+        use crate::graph::InMemorySaver;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compiled = counting_graph(&calls)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["approval"], &[])
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("breakpoint-before");
+
+        let paused = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            paused.interrupt.unwrap()[0].value,
+            serde_json::json!({"node": "approval", "when": "before"})
+        );
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.next, vec!["approval".to_string()]);
+        assert_eq!(
+            Breakpoint::from_metadata(&snapshot.metadata),
+            Some(Breakpoint::before("approval"))
+        );
+        assert_eq!(*calls.lock().unwrap(), vec!["prepare"]);
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 3);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["prepare", "approval", "finish"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_pauses_at_breakpoints_and_resumes_once() {
+        use crate::graph::InMemorySaver;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compiled = counting_graph(&calls)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["approval"], &[])
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("stream-breakpoint-before");
+
+        let events: Vec<_> = compiled
+            .stream_with_config(MessagesState::new(), &config)
+            .collect()
+            .await;
+        assert!(matches!(
+            &events[events.len() - 2..],
+            [
+                GraphStreamEvent::CheckpointWritten { .. },
+                GraphStreamEvent::Interrupted { node, value },
+            ] if node == "approval" && *value == serde_json::json!({"node": "approval", "when": "before"})
+        ));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.next, vec!["approval".to_string()]);
+        assert_eq!(
+            Breakpoint::from_metadata(&snapshot.metadata),
+            Some(Breakpoint::before("approval"))
+        );
+        assert_eq!(*calls.lock().unwrap(), vec!["prepare"]);
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 3);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["prepare", "approval", "finish"]
+        );
+
+        // A breakpoint after a node pauses at the checkpoint pending at the next one
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compiled = counting_graph(&calls)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &[], &["prepare"])
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("stream-breakpoint-after");
+
+        let events: Vec<_> = compiled
+            .stream_with_config(MessagesState::new(), &config)
+            .collect()
+            .await;
+        assert!(matches!(
+            events.last(),
+            Some(GraphStreamEvent::Interrupted { node, .. }) if node == "prepare"
+        ));
+        assert_eq!(compiled.get_state_history(&config).await.unwrap().len(), 1);
+        assert_eq!(
+            compiled.get_state(&config).await.unwrap().next,
+            vec!["approval".to_string()]
+        );
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 3);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["prepare", "approval", "finish"]
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupt_after_resumes_at_next_node() {
+        use crate::graph::InMemorySaver;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compiled = counting_graph(&calls)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &[], &["prepare"])
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("breakpoint-after");
+
+        let paused = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert!(paused.has_interrupt());
+        assert_eq!(paused.state.messages.len(), 1);
+        assert_eq!(
+            compiled.get_state(&config).await.unwrap().next,
+            vec!["approval".to_string()]
+        );
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 3);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["prepare", "approval", "finish"]
+        );
+    }
+
+    #[test]
+    fn compile_with_interrupts_rejects_unknown_nodes() {
+        use crate::graph::InMemorySaver;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let result = counting_graph(&calls).compile_with_interrupts(
+            Arc::new(InMemorySaver::new()),
+            &["missing"],
+            &[],
+        );
+        assert!(matches!(result, Err(GraphError::CompilationError(_))));
+    }
+
+    #[tokio::test]
+    async fn resume_with_config_reruns_only_interrupted_node() {
+        use crate::graph::{interrupt, InMemorySaver};
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut graph = StateGraph::<MessagesState>::new();
+        let prepare_calls = calls.clone();
+        graph
+            .add_node(
+                "prepare",
+                function_node("prepare", move |_s: &MessagesState| {
+                    prepare_calls.lock().unwrap().push("prepare");
+                    async move { Ok(HashMap::new()) }
+                }),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "approve",
+                function_node("approve", |_s: &MessagesState| async move {
+                    let answer = interrupt(serde_json::json!({"question": "ship it?"})).await?;
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message(
+                            answer.as_str().unwrap_or_default(),
+                        ),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "prepare");
+        graph.add_edge("prepare", "approve");
+        graph.add_edge("approve", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("dynamic-interrupt");
+
+        compiled
+            .invoke_with_config(Some(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(
+            snapshot.interrupts(),
+            vec![Interrupt::new(serde_json::json!({"question": "ship it?"}))]
+        );
+
+        let state = compiled
+            .invoke_with_config(None, &config.clone().with_resume("yes"))
+            .await
+            .unwrap();
+        assert_eq!(state.messages.last().unwrap().content, "yes");
+        assert_eq!(*calls.lock().unwrap(), vec!["prepare"]);
+    }
+
+    #[tokio::test]
+    async fn update_state_edits_paused_thread_before_resume() {
+        use crate::graph::InMemorySaver;
+        use crate::schemas::messages::Message;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compiled = counting_graph(&calls)
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["approval"], &[])
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("update-state");
+        compiled
+            .invoke_with_config(Some(MessagesState::new()), &config)
+            .await
+            .unwrap();
+
+        let mut unknown = HashMap::new();
+        unknown.insert("not_a_field".to_string(), serde_json::json!(1));
+        let result = compiled.update_state(&config, &unknown, None).await;
+        assert!(matches!(result, Err(GraphError::InvalidStateUpdate(_))));
+
+        // Attributed to "approval": the edit stands in for its output and routing moves on
+        let edit = crate::graph::messages_state_update(vec![Message::new_human_message("edited")]);
+        let checkpoint_id = compiled
+            .update_state(&config, &edit, Some("approval"))
+            .await
+            .unwrap();
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.checkpoint_id(), Some(&checkpoint_id));
+        assert_eq!(snapshot.next, vec!["finish".to_string()]);
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        let contents: Vec<_> = state.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["prepare", "edited", "finish"]);
+        assert_eq!(*calls.lock().unwrap(), vec!["prepare", "finish"]);
+    }
+
+    /// A single "work" node that loops on itself until the state holds `rounds` messages.
+    fn looping_graph(rounds: usize) -> StateGraph<MessagesState> {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "work",
+                function_node("work", |_s: &MessagesState| async move {
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message("round"),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "work");
+        let mut mapping = HashMap::new();
+        mapping.insert("again".to_string(), "work".to_string());
+        mapping.insert("done".to_string(), END.to_string());
+        graph.add_conditional_edges_sync(
+            "work",
+            move |s: &MessagesState| {
+                if s.messages.len() < rounds {
+                    "again".to_string()
+                } else {
+                    "done".to_string()
+                }
+            },
+            mapping,
+        );
+        graph
+    }
+
+    #[tokio::test]
+    async fn step_limit_fails_with_checkpoint_and_resumes_with_higher_limit() {
+        use crate::graph::InMemorySaver;
+
+        let compiled = looping_graph(5)
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("step-limit");
+
+        let result = compiled
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &config.clone().with_step_limit(3),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(GraphError::StepLimitExceeded { limit: 3, ref last_node }) if last_node == "work"
+        ));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.values.messages.len(), 3);
+        assert_eq!(snapshot.next, vec!["work".to_string()]);
+
+        let state = compiled
+            .invoke_with_config(None, &config.with_step_limit(10))
+            .await
+            .unwrap();
+        assert_eq!(state.messages.len(), 5);
+
+        // The default limit still stops an unbounded loop
+        let result = looping_graph(usize::MAX)
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap()
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &RunnableConfig::with_thread_id("step-limit-default"),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(GraphError::StepLimitExceeded {
+                limit: DEFAULT_STEP_LIMIT,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn deadline_fails_with_checkpoint_and_resumes_with_new_deadline() {
+        use crate::graph::{current_deadline, InMemorySaver};
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::time::{Duration, Instant};
+
+        let saw_deadline = Arc::new(AtomicBool::new(false));
+        let seen = saw_deadline.clone();
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "draft",
+                function_node("draft", |_s: &MessagesState| async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message("draft"),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "publish",
+                function_node("publish", move |_s: &MessagesState| {
+                    seen.store(current_deadline().is_some(), Ordering::SeqCst);
+                    async move {
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message("publish"),
+                        ]))
+                    }
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "publish");
+        graph.add_edge("publish", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("deadline");
+
+        // "draft" outlives the deadline, so the run stops before "publish"
+        let result = compiled
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &config.clone().with_max_duration(Duration::from_millis(10)),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(GraphError::DeadlineExceeded { ref node }) if node == "publish")
+        );
+        assert_eq!(
+            result.unwrap_err().code(),
+            Some(crate::graph::DEADLINE_EXCEEDED_STATUS)
+        );
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert!(snapshot.is_deadline_exceeded());
+        assert_eq!(snapshot.next, vec!["publish".to_string()]);
+        assert_eq!(snapshot.values.messages.len(), 1);
+
+        let state = compiled
+            .invoke_with_config(
+                None,
+                &config.with_deadline(Instant::now() + Duration::from_secs(60)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(state.messages.len(), 2);
+        assert!(saw_deadline.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn cancellation_aborts_node_and_resumes_from_cancelled_checkpoint() {
+        use crate::graph::{CancellationToken, InMemorySaver};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let slow_once = Arc::new(AtomicBool::new(true));
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "draft",
+                function_node("draft", |_s: &MessagesState| async move {
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message("draft"),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "publish",
+                function_node("publish", move |_s: &MessagesState| {
+                    let slow = slow_once.swap(false, Ordering::SeqCst);
+                    async move {
+                        if slow {
+                            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                        }
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message("publish"),
+                        ]))
+                    }
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "publish");
+        graph.add_edge("publish", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("cancel");
+
+        // Cancelling while "publish" awaits aborts it and checkpoints before it
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+        let result = compiled
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &config.clone().with_cancellation(token),
+            )
+            .await;
+        assert!(matches!(result, Err(GraphError::Cancelled { ref node }) if node == "publish"));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert!(snapshot.is_cancelled());
+        assert_eq!(snapshot.next, vec!["publish".to_string()]);
+        assert_eq!(snapshot.values.messages.len(), 1);
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 2);
+
+        // A token cancelled up front stops the run before its first node
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let result = compiled
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &RunnableConfig::with_thread_id("cancel-early").with_cancellation(cancelled),
+            )
+            .await;
+        assert!(matches!(result, Err(GraphError::Cancelled { ref node }) if node == "draft"));
+    }
+
+    #[tokio::test]
+    async fn stream_cancellation_checkpoints_and_records_pause() {
+        use crate::graph::{CancellationToken, InMemorySaver};
+        use crate::kernel::{InMemoryEventStore, SharedEventStore};
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let events = Arc::new(InMemoryEventStore::new());
+        let compiled = counting_graph(&calls)
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap()
+            .with_event_store(Arc::new(SharedEventStore(events.clone())));
+
+        // Cancelling between nodes stops before the next one and checkpoints there
+        let token = CancellationToken::new();
+        let config = RunnableConfig::with_thread_id("stream-cancel");
+        let mut stream = compiled.stream_with_config(
+            MessagesState::new(),
+            &config.clone().with_cancellation(token.clone()),
+        );
+        let mut last = None;
+        while let Some(event) = stream.next().await {
+            if matches!(&event, GraphStreamEvent::NodeFinished { node, .. } if node == "prepare") {
+                token.cancel();
+            }
+            last = Some(event);
+        }
+        drop(stream);
+        assert!(matches!(
+            last,
+            Some(GraphStreamEvent::Error { ref error })
+                if matches!(error.as_ref(), GraphError::Cancelled { node } if node == "approval")
+        ));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert!(snapshot.is_cancelled());
+        assert_eq!(snapshot.next, vec!["approval".to_string()]);
+        assert!(events
+            .scan(&"stream-cancel".to_string(), 1)
+            .unwrap()
+            .iter()
+            .any(|record| matches!(record.event, Event::Paused)));
+
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(state.messages.len(), 3);
+
+        // Cancelling while a node awaits aborts it and checkpoints before it
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "slow",
+                function_node("slow", |_s: &MessagesState| async move {
+                    tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                    Ok(HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "slow");
+        graph.add_edge("slow", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+        let config = RunnableConfig::with_thread_id("stream-cancel-node");
+        let events: Vec<_> = compiled
+            .stream_with_config(
+                MessagesState::new(),
+                &config.clone().with_cancellation(token),
+            )
+            .collect()
+            .await;
+        assert!(matches!(
+            &events[events.len() - 2..],
+            [
+                GraphStreamEvent::CheckpointWritten { .. },
+                GraphStreamEvent::Error { error },
+            ] if matches!(error.as_ref(), GraphError::Cancelled { node } if node == "slow")
+        ));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert!(snapshot.is_cancelled());
+        assert_eq!(snapshot.next, vec!["slow".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn cached_node_is_not_run_again_until_invalidated() {
+        use crate::graph::{InMemorySaver, NodeCachePolicy};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node_with_cache(
+                "embed",
+                function_node("embed", move |_s: &MessagesState| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        Ok(crate::graph::messages_state_update(vec![
+                            crate::schemas::messages::Message::new_ai_message("embedded"),
+                        ]))
+                    }
+                }),
+                NodeCachePolicy::new(),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "store",
+                function_node(
+                    "store",
+                    |_s: &MessagesState| async move { Ok(HashMap::new()) },
+                ),
+            )
+            .unwrap();
+        graph.add_edge(START, "embed");
+        graph.add_edge("embed", "store");
+        graph.add_edge("store", END);
+        let compiled = graph
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &[], &["embed"])
+            .unwrap();
+        let input = || {
+            MessagesState::with_messages(vec![
+                crate::schemas::messages::Message::new_human_message("text"),
+            ])
+        };
+
+        let first = RunnableConfig::with_thread_id("cache-1");
+        compiled
+            .invoke_with_config(Some(input()), &first)
+            .await
+            .unwrap();
+        let second = RunnableConfig::with_thread_id("cache-2");
+        let state = compiled
+            .invoke_with_config(Some(input()), &second)
+            .await
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(state.messages.len(), 2);
+
+        let snapshot = compiled.get_state(&second).await.unwrap();
+        let attempts: HashMap<String, NodeAttempts> =
+            serde_json::from_value(snapshot.metadata[NODE_ATTEMPTS_METADATA_KEY].clone()).unwrap();
+        assert!(attempts["embed"].cache_hit);
+        let first_snapshot = compiled.get_state(&first).await.unwrap();
+        assert!(!first_snapshot
+            .metadata
+            .contains_key(NODE_ATTEMPTS_METADATA_KEY));
+
+        compiled.invalidate_node_cache("embed").await.unwrap();
+        compiled
+            .invoke_with_config(Some(input()), &RunnableConfig::with_thread_id("cache-3"))
+            .await
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    /// START -> split -(fan-out per message)-> work -> combine -> END; the worker sleeps
+    /// longer for earlier items, so branches finish in reverse dispatch order
+    fn map_reduce_graph(
+        calls: &Arc<std::sync::Mutex<Vec<String>>>,
+        in_flight: &Arc<std::sync::atomic::AtomicUsize>,
+        peak: &Arc<std::sync::atomic::AtomicUsize>,
+        fail_once: &Arc<std::sync::Mutex<Option<String>>>,
+    ) -> StateGraph<MessagesState> {
+        use crate::graph::{messages_state_update, SendTo};
+        use crate::schemas::messages::Message;
+        use std::sync::atomic::Ordering;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["split", "combine"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, |_s: &MessagesState| async move { Ok(HashMap::new()) }),
+                )
+                .unwrap();
+        }
+        let (calls, in_flight, peak, fail_once) = (
+            calls.clone(),
+            in_flight.clone(),
+            peak.clone(),
+            fail_once.clone(),
+        );
+        graph
+            .add_node(
+                "work",
+                function_node("work", move |state: &MessagesState| {
+                    let item = state.messages.last().unwrap().content.clone();
+                    calls.lock().unwrap().push(item.clone());
+                    let fail = fail_once.lock().unwrap().take_if(|f| *f == item).is_some();
+                    let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                    async move {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        let delay = 50 - 10 * item.parse::<u64>().unwrap();
+                        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        if fail {
+                            return Err(GraphError::ExecutionError(format!(
+                                "item {} failed",
+                                item
+                            )));
+                        }
+                        Ok(messages_state_update(vec![Message::new_ai_message(
+                            format!("done {}", item),
+                        )]))
+                    }
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "split");
+        graph.add_fan_out_edges(
+            "split",
+            &["work"],
+            |state: &MessagesState| {
+                state
+                    .messages
+                    .iter()
+                    .map(|m| SendTo::new("work", messages_state_update(vec![m.clone()])))
+                    .collect()
+            },
+            "combine",
+        );
+        graph.add_edge("combine", END);
+        graph
+    }
+
+    fn numbered_messages(count: usize) -> MessagesState {
+        MessagesState::with_messages(
+            (0..count)
+                .map(|i| crate::schemas::messages::Message::new_human_message(i.to_string()))
+                .collect(),
+        )
+    }
+
+    fn done_messages(state: &MessagesState) -> Vec<String> {
+        state
+            .messages
+            .iter()
+            .filter(|m| m.content.starts_with("done"))
+            .map(|m| m.content.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fan_out_reduces_branches_in_dispatch_order_within_concurrency_limit() {
+        use crate::graph::InMemorySaver;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let fail_once = Arc::new(std::sync::Mutex::new(None));
+        let graph = map_reduce_graph(&calls, &in_flight, &peak, &fail_once);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let expected: Vec<String> = (0..5).map(|i| format!("done {}", i)).collect();
+
+        let state = compiled.invoke(numbered_messages(5)).await.unwrap();
+        assert_eq!(done_messages(&state), expected);
+        assert_eq!(peak.load(Ordering::SeqCst), 5);
+
+        peak.store(0, Ordering::SeqCst);
+        let config = RunnableConfig::with_thread_id("map").with_max_concurrency(2);
+        let state = compiled
+            .invoke_with_config(Some(numbered_messages(5)), &config)
+            .await
+            .unwrap();
+        assert_eq!(done_messages(&state), expected);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(calls.lock().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn fan_out_resume_reruns_only_unfinished_branches() {
+        use crate::graph::{FanOutProgress, InMemorySaver};
+        use std::sync::atomic::AtomicUsize;
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let fail_once = Arc::new(std::sync::Mutex::new(Some("3".to_string())));
+        let graph = map_reduce_graph(
+            &calls,
+            &Arc::new(AtomicUsize::new(0)),
+            &Arc::new(AtomicUsize::new(0)),
+            &fail_once,
+        );
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        // One branch at a time: items 0-2 finish before item 3 fails
+        let config = RunnableConfig::with_thread_id("map-crash").with_max_concurrency(1);
+
+        let result = compiled
+            .invoke_with_config(Some(numbered_messages(5)), &config)
+            .await;
+        assert!(matches!(result, Err(GraphError::ExecutionError(_))));
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        let progress = FanOutProgress::from_metadata(&snapshot.metadata).unwrap();
+        assert_eq!(progress.from, "split");
+        assert_eq!(progress.sends.len(), 5);
+        assert_eq!(
+            progress.completed.keys().copied().collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert_eq!(snapshot.next, vec!["work".to_string(), "work".to_string()]);
+
+        calls.lock().unwrap().clear();
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(*calls.lock().unwrap(), ["3", "4"]);
+        assert_eq!(
+            done_messages(&state),
+            (0..5).map(|i| format!("done {}", i)).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn step_once_pure_graph_default_succeeds() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "node1",
+                function_node("node1", |_s: &MessagesState| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "node1");
+        graph.add_edge("node1", END);
+        let compiled = graph.compile().unwrap();
+        let state = MessagesState::new();
+        let r = compiled.step_once(&state, START, None).await.unwrap();
+        assert!(matches!(r, GraphStepOnceResult::Complete { .. }));
+    }
+
+    #[tokio::test]
+    async fn step_once_non_pure_without_allow_errors() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "node1",
+                function_node("node1", |_s: &MessagesState| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
             )
             .unwrap();
         graph.add_edge(START, "node1");
@@ -1920,4 +4651,139 @@ mod tests {
             .unwrap();
         assert!(matches!(r, GraphStepOnceResult::Complete { .. }));
     }
+
+    /// A graph whose "decide" command node jumps to "a" or "b", both of which
+    /// interrupt before appending "<node>:<resume value>"
+    fn goto_graph(prefer_b: Arc<std::sync::atomic::AtomicBool>) -> StateGraph<MessagesState> {
+        use crate::graph::{command_node, interrupt, messages_state_update, Goto, NodeCommand};
+        use crate::schemas::messages::Message;
+        use std::sync::atomic::Ordering;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "decide",
+                command_node("decide", &["a", "b"], move |_s: &MessagesState| {
+                    let target = if prefer_b.load(Ordering::SeqCst) {
+                        "b"
+                    } else {
+                        "a"
+                    };
+                    async move {
+                        Ok(
+                            NodeCommand::new(messages_state_update(vec![Message::new_ai_message(
+                                "decided",
+                            )]))
+                            .with_goto(Goto::node(target)),
+                        )
+                    }
+                }),
+            )
+            .unwrap();
+        for name in ["a", "b"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        let approved = interrupt("approve?").await?;
+                        Ok(messages_state_update(vec![Message::new_ai_message(
+                            format!("{}:{}", name, approved),
+                        )]))
+                    }),
+                )
+                .unwrap();
+            graph.add_edge(name, END);
+        }
+        graph.add_edge(START, "decide");
+        graph
+    }
+
+    #[tokio::test]
+    async fn goto_applies_update_then_jumps_and_replays_on_resume() {
+        use crate::graph::{Command, InMemorySaver, GOTO_BRANCH_KEY};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let prefer_b = Arc::new(AtomicBool::new(false));
+        let compiled = goto_graph(prefer_b.clone())
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("goto-thread");
+
+        let first = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert!(first.has_interrupt());
+        assert_eq!(first.state.messages[0].content, "decided");
+        let snapshot = compiled.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.next, vec!["a".to_string()]);
+        let recorded = BranchLog::continuing(&snapshot.metadata);
+        assert_eq!(recorded.decisions()[0].key, GOTO_BRANCH_KEY);
+        assert_eq!(recorded.decisions()[0].to, "a");
+
+        // The node would now jump to "b", but resume must follow the recorded jump.
+        prefer_b.store(true, Ordering::SeqCst);
+        let resumed = compiled
+            .invoke_with_config_interrupt(StateOrCommand::Command(Command::resume(true)), &config)
+            .await
+            .unwrap();
+        assert!(!resumed.has_interrupt());
+        let contents: Vec<_> = resumed
+            .state
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["decided", "a:true"]);
+    }
+
+    #[tokio::test]
+    async fn parent_goto_leaves_subgraph_and_jumps_in_parent() {
+        use crate::graph::{command_node, messages_state_update, Goto, NodeCommand};
+        use crate::schemas::messages::Message;
+
+        let child =
+            || {
+                let mut child = StateGraph::<MessagesState>::new();
+                child
+                    .add_node(
+                        "escalate",
+                        command_node("escalate", &[], |_s: &MessagesState| async move {
+                            Ok(NodeCommand::new(messages_state_update(vec![
+                                Message::new_ai_message("escalated"),
+                            ]))
+                            .with_goto(Goto::parent("human")))
+                        }),
+                    )
+                    .unwrap();
+                child.add_edge(START, "escalate");
+                child.add_edge("escalate", END);
+                child.compile().unwrap()
+            };
+
+        let mut parent = StateGraph::<MessagesState>::new();
+        parent.add_subgraph("support", child()).unwrap();
+        for name in ["bot", "human"] {
+            parent
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        Ok(messages_state_update(vec![Message::new_ai_message(name)]))
+                    }),
+                )
+                .unwrap();
+        }
+        parent.add_edge(START, "support");
+        parent.add_edge("support", "bot");
+        parent.add_edge("bot", "human");
+        parent.add_edge("human", END);
+        let compiled = parent.compile().unwrap();
+
+        let state = compiled.invoke(MessagesState::new()).await.unwrap();
+        let contents: Vec<_> = state.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["escalated", "human"]);
+
+        let err = child().invoke(MessagesState::new()).await.unwrap_err();
+        assert!(matches!(err, GraphError::ParentGoto { ref target, .. } if target == "human"));
+    }
 }
@@ -0,0 +1,50 @@
+//! The run deadline and cancellation token as seen from inside a node.
+
+use std::future::Future;
+use std::time::Instant;
+
+use tokio::task_local;
+
+use super::CancellationToken;
+
+task_local! {
+    static DEADLINE: Instant;
+    static CANCELLATION: CancellationToken;
+}
+
+/// The deadline of the run executing the current node, if it has one
+///
+/// Set with `RunnableConfig::with_deadline` or `with_max_duration`. Long-running nodes
+/// can check it to return early instead of overrunning; outside a node this is `None`.
+pub fn current_deadline() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// The cancellation token of the run executing the current node, if it has one
+///
+/// Set with `RunnableConfig::with_cancellation`. The graph stops the run at the node
+/// when the token fires whatever the node does; nodes that wait can also select on
+/// [`cancelled`](CancellationToken::cancelled) to wind down promptly. Outside a node this
+/// is `None`.
+pub fn current_cancellation() -> Option<CancellationToken> {
+    CANCELLATION.try_with(CancellationToken::clone).ok()
+}
+
+/// Run `future` (a node invocation) with `deadline` as its [`current_deadline`]
+pub(crate) async fn with_deadline<F: Future>(deadline: Option<Instant>, future: F) -> F::Output {
+    match deadline {
+        Some(deadline) => DEADLINE.scope(deadline, future).await,
+        None => future.await,
+    }
+}
+
+/// Run `future` (a node invocation) with `token` as its [`current_cancellation`]
+pub(crate) async fn with_cancellation<F: Future>(
+    token: Option<CancellationToken>,
+    future: F,
+) -> F::Output {
+    match token {
+        Some(token) => CANCELLATION.scope(token, future).await,
+        None => future.await,
+    }
+}
@@ -0,0 +1,269 @@
+//! Loading node plugins from dynamic libraries (feature `dynamic-plugins`).
+//!
+//! A plugin library is a `cdylib` crate that exports its registration function with
+//! [`export_node_plugins!`](crate::export_node_plugins). The host loads it with
+//! [`NodePluginRegistry::load_dylib`], or every library in a directory with
+//! [`NodePluginRegistry::load_dylib_dir`]. Plugins cross the library boundary as Rust
+//! trait objects, so the library must be built with the same Rust toolchain (with
+//! `panic = "unwind"`) and `oris-runtime` version as the host; the entry point records
+//! the plugin API version, runtime version and state type so mismatches are rejected
+//! instead of misbehaving. Loaded libraries stay loaded for the life of the process,
+//! since nodes built from their plugins may outlive the registry.
+//!
+//! A library links its own copies of the standard library, `oris-runtime` and `tokio`.
+//! Its panics cannot unwind into the host, so the entry point contains registration
+//! panics itself, and its nodes must not rely on runtime context such as
+//! `tokio::time::sleep`, `tokio::spawn`, [`current_deadline`](super::current_deadline) or
+//! [`current_cancellation`](super::current_cancellation), which belong to the host's
+//! copies.
+
+use std::any::{type_name, Any};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use super::{
+    error::GraphError,
+    plugin::{NodePluginRegistry, PLUGIN_API_VERSION},
+    state::State,
+};
+
+/// Symbol of the entry point exported by plugin libraries for [PLUGIN_API_VERSION]
+pub const PLUGIN_ENTRY_SYMBOL: &str = "oris_plugin_entry_v1";
+
+/// Version of `oris-runtime` that plugin libraries must be built against
+pub const RUNTIME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What the [PLUGIN_ENTRY_SYMBOL] entry point of a plugin library returns
+///
+/// Built by [`export_node_plugins!`](crate::export_node_plugins); not meant to be
+/// constructed by hand.
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// [PLUGIN_API_VERSION] the library was built against
+    pub api_version: u32,
+    /// [RUNTIME_VERSION] the library was built against
+    pub runtime_version: &'static str,
+    /// `std::any::type_name` of the state type the plugins are for
+    pub state_type: &'static str,
+    /// `fn(&mut NodePluginRegistry<S>) -> Result<(), GraphError>` for that state type,
+    /// returning an error instead of panicking
+    pub register: *const (),
+}
+
+/// Signature of the [PLUGIN_ENTRY_SYMBOL] entry point
+#[allow(improper_ctypes_definitions)]
+pub type PluginEntry = unsafe extern "C" fn() -> PluginDeclaration;
+
+/// Outcome of loading one library in [`NodePluginRegistry::load_dylib_dir`]
+#[derive(Debug)]
+pub struct DylibLoadReport {
+    pub path: PathBuf,
+    /// Plugin types registered from the library, or why nothing was registered
+    pub result: Result<Vec<String>, GraphError>,
+}
+
+impl<S: State> NodePluginRegistry<S> {
+    /// Load a plugin library and register the node plugins it exports
+    ///
+    /// Returns the registered plugin types. Nothing is registered, and
+    /// `GraphError::PluginLoadError` names the file, if the library cannot be opened,
+    /// lacks [PLUGIN_ENTRY_SYMBOL], was built for another plugin API version, runtime
+    /// version or state type, or its registration fails, panics, repeats a registered
+    /// plugin type or uses a namespace reserved for other registrations. Provenances of
+    /// the library's registrations name the library.
+    pub fn load_dylib(&mut self, path: impl AsRef<Path>) -> Result<Vec<String>, GraphError> {
+        let path = path.as_ref();
+        let load_error = |message: String| GraphError::PluginLoadError {
+            path: path.display().to_string(),
+            message,
+        };
+
+        // SAFETY: opening the library runs its initializers; plugin libraries are
+        // trusted code, like statically linked plugins.
+        let library =
+            unsafe { libloading::Library::new(path) }.map_err(|e| load_error(e.to_string()))?;
+        // SAFETY: the symbol is declared with the `PluginEntry` signature by
+        // `export_node_plugins!`.
+        let declaration = unsafe {
+            let entry = library
+                .get::<PluginEntry>(PLUGIN_ENTRY_SYMBOL.as_bytes())
+                .map_err(|e| {
+                    load_error(format!(
+                        "missing entry point {}: {}",
+                        PLUGIN_ENTRY_SYMBOL, e
+                    ))
+                })?;
+            entry()
+        };
+        if declaration.api_version != PLUGIN_API_VERSION {
+            return Err(load_error(format!(
+                "built for plugin API version {} but this runtime implements version {}",
+                declaration.api_version, PLUGIN_API_VERSION
+            )));
+        }
+        if declaration.runtime_version != RUNTIME_VERSION {
+            return Err(load_error(format!(
+                "built against oris-runtime {} but the host uses {}",
+                declaration.runtime_version, RUNTIME_VERSION
+            )));
+        }
+        if declaration.state_type != type_name::<S>() {
+            return Err(load_error(format!(
+                "exports plugins for state type {} but the registry is for {}",
+                declaration.state_type,
+                type_name::<S>()
+            )));
+        }
+
+        // SAFETY: the declaration was built by `export_node_plugins!` from a function with
+        // this signature for state type `S`, checked above.
+        let register: fn(&mut NodePluginRegistry<S>) -> Result<(), GraphError> =
+            unsafe { std::mem::transmute(declaration.register) };
+        let mut loaded = self.staging();
+        match register(&mut loaded) {
+            Ok(()) => {}
+            Err(GraphError::PluginLoadError { message, .. }) => return Err(load_error(message)),
+            Err(e) => return Err(load_error(format!("registration failed: {}", e))),
+        }
+        let plugin_types = self
+            .merge(loaded, path)
+            .map_err(|e| load_error(e.to_string()))?;
+
+        // Nodes built from the library's plugins may outlive this registry
+        std::mem::forget(library);
+        Ok(plugin_types)
+    }
+
+    /// Load every plugin library in `dir` (files with the platform's library extension)
+    ///
+    /// A library that fails to load does not stop the others; each gets a
+    /// [DylibLoadReport], in file name order. Fails only if `dir` cannot be read.
+    pub fn load_dylib_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<DylibLoadReport>, GraphError> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).map_err(|e| GraphError::PluginLoadError {
+            path: dir.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                let result = self.load_dylib(&path);
+                DylibLoadReport { path, result }
+            })
+            .collect())
+    }
+}
+
+/// Run a plugin library's registration function, turning a panic into an error
+///
+/// Called inside the library by [`export_node_plugins!`](crate::export_node_plugins),
+/// since the host cannot catch the library's panics.
+#[doc(hidden)]
+pub fn __register_contained<S: State>(
+    registry: &mut NodePluginRegistry<S>,
+    register: fn(&mut NodePluginRegistry<S>) -> Result<(), GraphError>,
+) -> Result<(), GraphError> {
+    catch_unwind(AssertUnwindSafe(|| register(registry))).unwrap_or_else(|panic| {
+        Err(GraphError::PluginLoadError {
+            path: String::new(),
+            message: format!("registration panicked: {}", panic_message(panic.as_ref())),
+        })
+    })
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Export a plugin library's registration function as its [PLUGIN_ENTRY_SYMBOL] entry point
+///
+/// `$register` is a `fn(&mut NodePluginRegistry<$state>) -> Result<(), GraphError>`; the
+/// crate must be built as a `cdylib` with the `dynamic-plugins` feature of `oris-runtime`.
+///
+/// ```rust,ignore
+/// fn register(registry: &mut NodePluginRegistry<MessagesState>) -> Result<(), GraphError> {
+///     registry.register_plugin(my_plugin())?;
+///     Ok(())
+/// }
+///
+/// oris_runtime::export_node_plugins!(MessagesState, register);
+/// ```
+#[macro_export]
+macro_rules! export_node_plugins {
+    ($state:ty, $register:path) => {
+        #[no_mangle]
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn oris_plugin_entry_v1() -> $crate::graph::PluginDeclaration {
+            fn register(
+                registry: &mut $crate::graph::NodePluginRegistry<$state>,
+            ) -> ::std::result::Result<(), $crate::graph::GraphError> {
+                $crate::graph::__register_contained(registry, $register)
+            }
+            let register: fn(
+                &mut $crate::graph::NodePluginRegistry<$state>,
+            ) -> ::std::result::Result<(), $crate::graph::GraphError> = register;
+            $crate::graph::PluginDeclaration {
+                api_version: $crate::graph::PLUGIN_API_VERSION,
+                runtime_version: $crate::graph::RUNTIME_VERSION,
+                state_type: ::std::any::type_name::<$state>(),
+                register: register as *const (),
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::MessagesState;
+
+    #[test]
+    fn load_dylib_dir_reports_each_failing_file() {
+        let dir = std::env::temp_dir().join(format!("oris-dylib-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let bogus = dir.join(format!("bogus.{}", std::env::consts::DLL_EXTENSION));
+        std::fs::write(&bogus, b"not a library").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"skipped").unwrap();
+
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        let reports = registry.load_dylib_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].path, bogus);
+        assert!(matches!(
+            reports[0].result,
+            Err(GraphError::PluginLoadError { ref path, .. }) if path == &bogus.display().to_string()
+        ));
+        assert!(registry.plugin_types().is_empty());
+    }
+
+    #[test]
+    fn registration_panics_become_errors() {
+        fn register(_registry: &mut NodePluginRegistry<MessagesState>) -> Result<(), GraphError> {
+            panic!("boom")
+        }
+
+        let mut registry = NodePluginRegistry::new();
+        let err = __register_contained(&mut registry, register).unwrap_err();
+        assert!(matches!(
+            err,
+            GraphError::PluginLoadError { ref message, .. } if message == "registration panicked: boom"
+        ));
+    }
+}
@@ -1,14 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 use std::sync::Arc;
 
-use super::{error::GraphError, state::State};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    error::GraphError,
+    state::{State, StateUpdate},
+};
 
 /// Special node names for graph entry and exit
 pub const START: &str = "__start__";
 pub const END: &str = "__end__";
 
-/// Edge type - either a regular edge or a conditional edge
+/// Checkpoint metadata key under which the conditional branches taken so far are recorded.
+pub const BRANCHES_METADATA_KEY: &str = "branches";
+
+/// Router key recorded in the [BranchLog] for a jump taken by a node's goto.
+pub const GOTO_BRANCH_KEY: &str = "goto";
+
+/// Checkpoint metadata key under which an unfinished fan-out is recorded.
+pub const FAN_OUT_METADATA_KEY: &str = "fan_out";
+
+/// Router of a fan-out edge: one [`SendTo`] per branch to run
+pub type FanOutRouter<S> = Arc<dyn Fn(&S) -> Vec<SendTo> + Send + Sync>;
+
+/// Edge type - a regular, conditional or fan-out edge
 #[derive(Clone)]
 pub enum EdgeType<S: State> {
     /// Regular edge - fixed routing to a single node
@@ -25,6 +43,13 @@ pub enum EdgeType<S: State> {
         >,
         mapping: HashMap<String, String>, // Maps condition result to node name
     },
+    /// Fan-out edge - the router dispatches any number of branches to `workers`,
+    /// whose updates are reduced into the state before execution continues at `join`
+    FanOut {
+        router: FanOutRouter<S>,
+        workers: Vec<String>,
+        join: String,
+    },
 }
 
 impl<S: State> std::fmt::Debug for EdgeType<S> {
@@ -36,6 +61,12 @@ impl<S: State> std::fmt::Debug for EdgeType<S> {
                 .field("condition", &"<fn>")
                 .field("mapping", mapping)
                 .finish(),
+            EdgeType::FanOut { workers, join, .. } => f
+                .debug_struct("FanOut")
+                .field("router", &"<fn>")
+                .field("workers", workers)
+                .field("join", join)
+                .finish(),
         }
     }
 }
@@ -75,13 +106,62 @@ impl<S: State> Edge<S> {
         }
     }
 
+    /// Create a new conditional edge with a synchronous router
+    ///
+    /// The router returns a key that is translated to a node name through `mapping`.
+    pub fn conditional_sync<F>(
+        from: impl Into<String>,
+        router: F,
+        mapping: HashMap<String, String>,
+    ) -> Self
+    where
+        F: Fn(&S) -> String + Send + Sync + 'static,
+    {
+        Self::conditional(
+            from,
+            move |state: &S| {
+                let key = router(state);
+                async move { Ok(key) }
+            },
+            mapping,
+        )
+    }
+
+    /// Create a new fan-out edge
+    ///
+    /// `router` returns one [`SendTo`] per branch; each must target one of `workers`.
+    pub fn fan_out<F>(
+        from: impl Into<String>,
+        workers: Vec<String>,
+        router: F,
+        join: impl Into<String>,
+    ) -> Self
+    where
+        F: Fn(&S) -> Vec<SendTo> + Send + Sync + 'static,
+    {
+        Self {
+            from: from.into(),
+            edge_type: EdgeType::FanOut {
+                router: Arc::new(router),
+                workers,
+                join: join.into(),
+            },
+        }
+    }
+
     /// Get the target node name for a given state
     ///
     /// For regular edges, this always returns the same node.
     /// For conditional edges, this evaluates the condition function.
+    /// Fan-out edges have no single target and return an error; executors that
+    /// support them expand the edge with [`FanOutProgress::dispatch`] instead.
     pub async fn get_target(&self, state: &S) -> Result<String, GraphError> {
         match &self.edge_type {
             EdgeType::Regular { to } => Ok(to.clone()),
+            EdgeType::FanOut { .. } => Err(GraphError::ExecutionError(format!(
+                "Fan-out edge from '{}' is only supported by invoke and invoke_with_config",
+                self.from
+            ))),
             EdgeType::Conditional { condition, mapping } => {
                 let condition_result = (condition)(state).await?;
                 mapping.get(&condition_result).cloned().ok_or_else(|| {
@@ -94,6 +174,45 @@ impl<S: State> Edge<S> {
         }
     }
 
+    /// Resolve the target node and record the decision in `branches`
+    ///
+    /// Conditional edges first consume a pending replayed decision from `branches`
+    /// (when resuming from a checkpoint) so execution follows the same branch as the
+    /// original run; otherwise the router is evaluated and the outcome is recorded.
+    pub async fn route(&self, state: &S, branches: &mut BranchLog) -> Result<String, GraphError> {
+        let mapping = match &self.edge_type {
+            EdgeType::Regular { to } => return Ok(to.clone()),
+            EdgeType::Conditional { mapping, .. } => mapping,
+            EdgeType::FanOut { .. } => return self.get_target(state).await,
+        };
+
+        if let Some(decision) = branches.take_replayed(&self.from) {
+            if mapping.values().any(|target| *target == decision.to) {
+                branches.record(decision.clone());
+                return Ok(decision.to);
+            }
+            log::warn!(
+                "Recorded branch '{}' -> '{}' is no longer a valid target; re-evaluating router",
+                decision.from,
+                decision.to
+            );
+            branches.stop_replay();
+        }
+
+        let target = self.get_target(state).await?;
+        let key = mapping
+            .iter()
+            .find(|(_, to)| **to == target)
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default();
+        branches.record(BranchDecision {
+            from: self.from.clone(),
+            key,
+            to: target.clone(),
+        });
+        Ok(target)
+    }
+
     /// Check if this is a regular edge
     pub fn is_regular(&self) -> bool {
         matches!(self.edge_type, EdgeType::Regular { .. })
@@ -103,6 +222,226 @@ impl<S: State> Edge<S> {
     pub fn is_conditional(&self) -> bool {
         matches!(self.edge_type, EdgeType::Conditional { .. })
     }
+
+    /// Check if this is a fan-out edge
+    pub fn is_fan_out(&self) -> bool {
+        matches!(self.edge_type, EdgeType::FanOut { .. })
+    }
+}
+
+/// One branch of a fan-out: run `node` on the parent state merged with `payload`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SendTo {
+    /// Worker node that runs the branch
+    pub node: String,
+    /// Update merged into the branch's copy of the state before `node` runs
+    pub payload: StateUpdate,
+}
+
+impl SendTo {
+    /// Create a branch for `node` with the given payload
+    pub fn new(node: impl Into<String>, payload: StateUpdate) -> Self {
+        Self {
+            node: node.into(),
+            payload,
+        }
+    }
+}
+
+/// The branches of a running fan-out and the updates of those that finished
+///
+/// While branches run, this is persisted in checkpoint metadata under
+/// [FAN_OUT_METADATA_KEY] so a resumed run only re-runs unfinished branches.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FanOutProgress {
+    /// Source node of the fan-out edge
+    pub from: String,
+    /// Node execution continues at once every branch is reduced
+    pub join: String,
+    /// Branches in dispatch order
+    pub sends: Vec<SendTo>,
+    /// Updates of finished branches, keyed by their index in `sends`
+    #[serde(default)]
+    pub completed: BTreeMap<usize, StateUpdate>,
+}
+
+impl FanOutProgress {
+    /// Evaluate a fan-out edge's router, or return `None` for other edges
+    ///
+    /// Fails if the router sends to a node the edge does not declare as a worker.
+    pub fn dispatch<S: State>(edge: &Edge<S>, state: &S) -> Result<Option<Self>, GraphError> {
+        let EdgeType::FanOut {
+            router,
+            workers,
+            join,
+        } = &edge.edge_type
+        else {
+            return Ok(None);
+        };
+        let sends = router(state);
+        if let Some(send) = sends.iter().find(|send| !workers.contains(&send.node)) {
+            return Err(GraphError::ExecutionError(format!(
+                "Fan-out from '{}' sent to '{}', which is not one of its workers",
+                edge.from, send.node
+            )));
+        }
+        Ok(Some(Self {
+            from: edge.from.clone(),
+            join: join.clone(),
+            sends,
+            completed: BTreeMap::new(),
+        }))
+    }
+
+    /// Read an unfinished fan-out from checkpoint metadata
+    pub fn from_metadata(metadata: &HashMap<String, Value>) -> Option<Self> {
+        metadata
+            .get(FAN_OUT_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Branches that have not finished, with their index in `sends`
+    pub fn pending(&self) -> Vec<(usize, SendTo)> {
+        self.sends
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.completed.contains_key(index))
+            .map(|(index, send)| (index, send.clone()))
+            .collect()
+    }
+
+    /// Nodes still to run: the unfinished workers, or `join` once all branches finished
+    pub fn next_nodes(&self) -> Vec<String> {
+        let pending: Vec<String> = self
+            .pending()
+            .into_iter()
+            .map(|(_, send)| send.node)
+            .collect();
+        if pending.is_empty() {
+            vec![self.join.clone()]
+        } else {
+            pending
+        }
+    }
+
+    /// Serialize for storage in checkpoint metadata
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// A routing decision taken by a conditional edge or a node's goto
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchDecision {
+    /// Source node of the conditional edge or goto
+    pub from: String,
+    /// Router output that selected the branch ([GOTO_BRANCH_KEY] for a goto)
+    pub key: String,
+    /// Target node the router output was mapped to
+    pub to: String,
+}
+
+/// Ordered log of conditional branch decisions for one run
+///
+/// The log is persisted in checkpoint metadata under [BRANCHES_METADATA_KEY].
+/// When resuming, the recorded decisions are replayed in order before any router
+/// is evaluated again, which keeps resumed runs on the original path.
+#[derive(Clone, Debug, Default)]
+pub struct BranchLog {
+    decisions: Vec<BranchDecision>,
+    replay: VecDeque<BranchDecision>,
+}
+
+impl BranchLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a log that replays previously recorded decisions
+    pub fn replaying(decisions: Vec<BranchDecision>) -> Self {
+        Self {
+            decisions: Vec::new(),
+            replay: decisions.into(),
+        }
+    }
+
+    /// Create a replaying log from checkpoint metadata
+    ///
+    /// Missing or malformed metadata yields an empty log.
+    pub fn from_metadata(metadata: &HashMap<String, Value>) -> Self {
+        let decisions = metadata
+            .get(BRANCHES_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default();
+        Self::replaying(decisions)
+    }
+
+    /// Create a log that continues from checkpoint metadata without replaying
+    ///
+    /// Used when a run resumes at the checkpoint's pending node rather than from START:
+    /// earlier decisions are kept for the next checkpoint but never re-applied.
+    pub fn continuing(metadata: &HashMap<String, Value>) -> Self {
+        let mut log = Self::from_metadata(metadata);
+        log.decisions = std::mem::take(&mut log.replay).into();
+        log
+    }
+
+    /// Decisions taken (or replayed) so far in this run
+    pub fn decisions(&self) -> &[BranchDecision] {
+        &self.decisions
+    }
+
+    /// Whether recorded decisions are still pending replay
+    pub fn is_replaying(&self) -> bool {
+        !self.replay.is_empty()
+    }
+
+    /// Serialize the decisions for storage in checkpoint metadata
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(&self.decisions).unwrap_or(Value::Array(vec![]))
+    }
+
+    /// Record the jump `from` takes with a goto, or replay the recorded one
+    ///
+    /// When resuming, the jump recorded for `from` wins over `to`, so a node whose
+    /// goto depends on anything but the state still follows the original path.
+    pub fn goto(&mut self, from: &str, to: String) -> String {
+        if let Some(decision) = self.take_replayed(from) {
+            self.record(decision.clone());
+            return decision.to;
+        }
+        self.record(BranchDecision {
+            from: from.to_string(),
+            key: GOTO_BRANCH_KEY.to_string(),
+            to: to.clone(),
+        });
+        to
+    }
+
+    fn record(&mut self, decision: BranchDecision) {
+        self.decisions.push(decision);
+    }
+
+    fn take_replayed(&mut self, from: &str) -> Option<BranchDecision> {
+        match self.replay.front() {
+            Some(decision) if decision.from == from => self.replay.pop_front(),
+            Some(decision) => {
+                log::warn!(
+                    "Recorded branch from '{}' does not match current node '{}'; stopping replay",
+                    decision.from,
+                    from
+                );
+                self.stop_replay();
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn stop_replay(&mut self) {
+        self.replay.clear();
+    }
 }
 
 /// Helper function to create a regular edge
@@ -154,4 +493,65 @@ mod tests {
         assert_eq!(target, "node_yes");
         assert!(edge.is_conditional());
     }
+
+    #[tokio::test]
+    async fn test_route_replays_recorded_branch() {
+        let mut mapping = HashMap::new();
+        mapping.insert("yes".to_string(), "node_yes".to_string());
+        mapping.insert("no".to_string(), "node_no".to_string());
+
+        let edge =
+            Edge::conditional_sync("node1", |_state: &MessagesState| "yes".to_string(), mapping);
+        let state = MessagesState::new();
+
+        let mut live = BranchLog::new();
+        assert_eq!(edge.route(&state, &mut live).await.unwrap(), "node_yes");
+        assert_eq!(live.decisions()[0].key, "yes");
+
+        let mut replay = BranchLog::replaying(vec![BranchDecision {
+            from: "node1".to_string(),
+            key: "no".to_string(),
+            to: "node_no".to_string(),
+        }]);
+        assert_eq!(edge.route(&state, &mut replay).await.unwrap(), "node_no");
+        assert!(!replay.is_replaying());
+        assert_eq!(edge.route(&state, &mut replay).await.unwrap(), "node_yes");
+        assert_eq!(replay.decisions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_dispatch_checks_workers() {
+        let edge = Edge::fan_out(
+            "split",
+            vec!["work".to_string()],
+            |_state: &MessagesState| {
+                vec![
+                    SendTo::new("work", StateUpdate::new()),
+                    SendTo::new("other", StateUpdate::new()),
+                ]
+            },
+            "join",
+        );
+        let state = MessagesState::new();
+        assert!(edge.is_fan_out());
+        assert!(edge.get_target(&state).await.is_err());
+        assert!(FanOutProgress::dispatch(&edge, &state).is_err());
+        assert!(FanOutProgress::dispatch(&Edge::new("a", "b"), &state)
+            .unwrap()
+            .is_none());
+
+        let mut progress = FanOutProgress {
+            from: "split".to_string(),
+            join: "join".to_string(),
+            sends: vec![
+                SendTo::new("work", StateUpdate::new()),
+                SendTo::new("work", StateUpdate::new()),
+            ],
+            completed: BTreeMap::new(),
+        };
+        progress.completed.insert(0, StateUpdate::new());
+        assert_eq!(progress.next_nodes(), vec!["work".to_string()]);
+        let metadata = HashMap::from([(FAN_OUT_METADATA_KEY.to_string(), progress.to_value())]);
+        assert_eq!(FanOutProgress::from_metadata(&metadata), Some(progress));
+    }
 }
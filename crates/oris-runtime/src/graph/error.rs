@@ -27,6 +27,12 @@ pub enum GraphError {
     #[error("No path from START to END")]
     NoPathToEnd,
 
+    #[error(
+        "Graph validation failed: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    ValidationFailed(Vec<super::validation::ValidationIssue>),
+
     #[error("Invalid state update: {0}")]
     InvalidStateUpdate(String),
 
@@ -45,10 +51,125 @@ pub enum GraphError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    #[error("Node '{node}' timed out after {elapsed:?}")]
+    NodeTimeout {
+        node: String,
+        elapsed: std::time::Duration,
+    },
+
+    #[error("Step limit of {limit} exceeded after node '{last_node}'")]
+    StepLimitExceeded { limit: usize, last_node: String },
+
+    #[error("Run cancelled at node '{node}'")]
+    Cancelled { node: String },
+
+    #[error("Run deadline exceeded before node '{node}'")]
+    DeadlineExceeded { node: String },
+
+    #[error(
+        "Conflicting writes to state key '{key}' from nodes '{first}' and '{second}'; \
+         register a reducer for it with StateGraph::set_reducer"
+    )]
+    ConflictingUpdate {
+        key: String,
+        first: String,
+        second: String,
+    },
+
+    /// A `Goto::Parent` leaving a subgraph; `update` is the subgraph's final state
+    #[error(
+        "Node '{node}' jumped to parent node '{target}' but the graph is not running as a subgraph"
+    )]
+    ParentGoto {
+        node: String,
+        target: String,
+        update: super::state::StateUpdate,
+    },
+
+    #[error(
+        "Plugin '{plugin_type}' targets plugin API version {got} but this runtime implements \
+         version {expected}; rebuild it against this oris-runtime"
+    )]
+    PluginIncompatible {
+        plugin_type: String,
+        expected: u32,
+        got: u32,
+    },
+
+    #[error("Failed to load plugin library '{path}': {message}")]
+    PluginLoadError { path: String, message: String },
+
+    /// A plugin or router type registered twice; names both registration sites
+    #[error(
+        "Plugin type '{plugin_type}' is already registered at {existing}; \
+         conflicting registration at {conflicting}"
+    )]
+    PluginConflict {
+        plugin_type: String,
+        existing: String,
+        conflicting: String,
+    },
+
+    /// A registration in a namespace reserved with
+    /// [`NodePluginRegistry::register_namespace`](super::NodePluginRegistry::register_namespace)
+    /// that the namespace's guard rejects
+    #[error(
+        "Plugin namespace '{namespace}' is reserved at {reserved_at}; \
+         '{plugin_type}' registered at {rejected} is not allowed in it"
+    )]
+    PluginNamespaceReserved {
+        namespace: String,
+        plugin_type: String,
+        reserved_at: String,
+        rejected: String,
+    },
+
+    #[error(
+        "Invalid config for plugin '{plugin_type}': {}",
+        .errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidConfig {
+        plugin_type: String,
+        errors: Vec<super::plugin::ConfigValidationError>,
+    },
+
+    /// A `${env:VAR}` or `${secret:NAME}` placeholder in a plugin config that cannot be
+    /// expanded; never carries resolved values
+    #[error("Cannot resolve config placeholder '{placeholder}': {reason}")]
+    UnresolvedPlaceholder { placeholder: String, reason: String },
+
+    /// An entry of a [`GraphSpec`](super::GraphSpec) that cannot be built; `path` is its
+    /// JSON path, e.g. `$.nodes[2].config`
+    #[error("Invalid graph spec at {path}: {message}")]
+    InvalidSpec { path: String, message: String },
+
+    /// A node asked the kernel to perform an action with
+    /// [`request_action`](super::request_action); the node runs again once it is done
+    #[error("Node requested kernel action {0:?}")]
+    ActionRequested(crate::kernel::action::Action),
+
+    /// A retriever failed; `RetryPolicy` only retries it if it is
+    /// [retryable](crate::retrievers::RetrieverError::is_retryable)
+    #[error("Retriever error: {0}")]
+    RetrieverError(#[from] crate::retrievers::RetrieverError),
+
     #[error("Interrupt error: {0}")]
     InterruptError(#[from] super::interrupts::error::InterruptError),
 }
 
+impl GraphError {
+    /// Machine-readable code of the errors that stop a run resumably, as recorded on
+    /// the kernel's `Failed` event
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            GraphError::StepLimitExceeded { .. } => Some("step_limit_exceeded"),
+            GraphError::DeadlineExceeded { .. } => Some("deadline_exceeded"),
+            GraphError::Cancelled { .. } => Some("cancelled"),
+            _ => None,
+        }
+    }
+}
+
 impl From<crate::language_models::LLMError> for GraphError {
     fn from(e: crate::language_models::LLMError) -> Self {
         GraphError::LLMError(e.to_string())
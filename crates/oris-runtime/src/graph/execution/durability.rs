@@ -56,11 +56,23 @@ pub async fn save_checkpoint<S: State + 'static>(
     snapshot: &StateSnapshot<S>,
     mode: DurabilityMode,
 ) -> Result<(), GraphError> {
+    save_checkpoint_with_id(checkpointer, snapshot, mode)
+        .await
+        .map(|_| ())
+}
+
+/// Save a checkpoint according to the durability mode, returning its id if it was
+/// written before returning (`Sync` mode)
+pub(crate) async fn save_checkpoint_with_id<S: State + 'static>(
+    checkpointer: Option<&CheckpointerBox<S>>,
+    snapshot: &StateSnapshot<S>,
+    mode: DurabilityMode,
+) -> Result<Option<String>, GraphError> {
     if let Some(checkpointer) = checkpointer {
         match mode {
             DurabilityMode::Exit => {
                 // Don't save here, will be saved on exit
-                Ok(())
+                Ok(None)
             }
             DurabilityMode::Async => {
                 // Spawn async task to save checkpoint
@@ -74,21 +86,21 @@ pub async fn save_checkpoint<S: State + 'static>(
                     }
                 });
 
-                Ok(())
+                Ok(None)
             }
             DurabilityMode::Sync => {
                 // Save synchronously
-                checkpointer
+                let checkpoint_id = checkpointer
                     .put(snapshot.thread_id(), snapshot)
                     .await
                     .map_err(|e| {
                         GraphError::ExecutionError(format!("Failed to save checkpoint: {}", e))
                     })?;
-                Ok(())
+                Ok(Some(checkpoint_id))
             }
         }
     } else {
-        Ok(())
+        Ok(None)
     }
 }
 
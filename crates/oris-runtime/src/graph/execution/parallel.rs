@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
 use crate::graph::{
+    callbacks::{GraphCallbacks, RunCallbacks},
+    deadline::{with_cancellation, with_deadline},
     error::GraphError,
     node::Node,
     persistence::{config::RunnableConfig, store::StoreBox},
+    reducers::StateReducers,
     state::{State, StateUpdate},
 };
 
@@ -22,6 +25,20 @@ pub async fn execute_nodes_parallel<S: State>(
     config: Option<&RunnableConfig>,
     store: Option<StoreBox>,
 ) -> Result<Vec<(String, StateUpdate)>, GraphError> {
+    execute_nodes_parallel_observed(nodes, node_names, state, config, store, &[]).await
+}
+
+/// Execute multiple nodes in parallel, reporting each to the graph's `callbacks`
+/// and those of `config`
+pub(crate) async fn execute_nodes_parallel_observed<S: State>(
+    nodes: &HashMap<String, std::sync::Arc<dyn Node<S>>>,
+    node_names: &[String],
+    state: &S,
+    config: Option<&RunnableConfig>,
+    store: Option<StoreBox>,
+    callbacks: &[std::sync::Arc<dyn GraphCallbacks>],
+) -> Result<Vec<(String, StateUpdate)>, GraphError> {
+    let callbacks = &RunCallbacks::new(callbacks, config);
     // Create futures for all nodes
     let futures: Vec<_> = node_names
         .iter()
@@ -33,8 +50,17 @@ pub async fn execute_nodes_parallel<S: State>(
 
             async move {
                 let node = node_opt.ok_or_else(|| GraphError::NodeNotFound(node_name.clone()))?;
-                let update = node.invoke_with_context(&state, config, store).await?;
-                Ok::<(String, StateUpdate), GraphError>((node_name, update))
+                let started = callbacks.node_started(&node_name);
+                let result = with_cancellation(
+                    config.and_then(RunnableConfig::cancellation).cloned(),
+                    with_deadline(
+                        config.and_then(RunnableConfig::deadline),
+                        node.invoke_with_context(&state, config, store),
+                    ),
+                )
+                .await;
+                callbacks.node_finished(&node_name, &result, started);
+                Ok::<(String, StateUpdate), GraphError>((node_name, result?))
             }
         })
         .collect();
@@ -61,64 +87,13 @@ pub async fn execute_nodes_parallel<S: State>(
 /// Merge multiple state updates into a single state
 ///
 /// When multiple nodes execute in parallel, their updates need to be merged.
+/// Uses only the state type's reducers; two updates writing a key that has none
+/// fail with `GraphError::ConflictingUpdate`.
 pub fn merge_state_updates<S: State>(
     state: &S,
     updates: &[(String, StateUpdate)],
 ) -> Result<S, GraphError> {
-    let mut current_state = state.clone();
-
-    // Merge all updates sequentially
-    // Note: The order of merging may matter for some state types
-    for (node_name, update) in updates {
-        log::debug!("Merging update from node: {}", node_name);
-        current_state = merge_single_update(&current_state, update)?;
-    }
-
-    Ok(current_state)
-}
-
-/// Merge a single state update
-fn merge_single_update<S: State>(state: &S, update: &StateUpdate) -> Result<S, GraphError> {
-    // Try to handle MessagesState specially
-    let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
-
-    if state_json.get("messages").is_some() {
-        return merge_messages_state_update(state, update);
-    }
-
-    // For other state types, create a new state from the update and merge
-    let update_json = serde_json::to_value(update).map_err(GraphError::SerializationError)?;
-
-    let update_state: S = serde_json::from_value(update_json.clone()).map_err(|_| {
-        GraphError::ExecutionError("Cannot deserialize update as state".to_string())
-    })?;
-
-    Ok(state.merge(&update_state))
-}
-
-/// Merge update for MessagesState (specialized handling)
-fn merge_messages_state_update<S: State>(state: &S, update: &StateUpdate) -> Result<S, GraphError> {
-    use crate::graph::state::{apply_update_to_messages_state, MessagesState};
-
-    let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
-
-    let messages_state: MessagesState = if let Some(messages_value) = state_json.get("messages") {
-        if let Ok(messages) =
-            serde_json::from_value::<Vec<crate::schemas::messages::Message>>(messages_value.clone())
-        {
-            MessagesState::with_messages(messages)
-        } else {
-            MessagesState::new()
-        }
-    } else {
-        MessagesState::new()
-    };
-
-    let updated_state = apply_update_to_messages_state(&messages_state, update);
-
-    let updated_json =
-        serde_json::to_value(&updated_state).map_err(GraphError::SerializationError)?;
-    serde_json::from_value(updated_json).map_err(GraphError::SerializationError)
+    StateReducers::new().apply_all(state, updates)
 }
 
 #[cfg(test)]
@@ -38,6 +38,14 @@ impl<S: State> NodeScheduler<S> {
                                 .push(from.clone());
                         }
                     }
+                    EdgeType::FanOut { workers, join, .. } => {
+                        for target in workers.iter().chain(std::iter::once(join)) {
+                            reverse_adjacency
+                                .entry(target.clone())
+                                .or_default()
+                                .push(from.clone());
+                        }
+                    }
                 }
             }
         }
@@ -2,21 +2,23 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::graph::{
+    callbacks::{GraphCallbacks, RunCallbacks},
     edge::START,
     error::GraphError,
     node::Node,
     persistence::{
         checkpointer::CheckpointerBox,
-        config::{CheckpointConfig, RunnableConfig},
-        snapshot::StateSnapshot,
+        config::{CheckpointConfig, RunnableConfig, DEFAULT_STEP_LIMIT},
+        snapshot::{StateSnapshot, DEADLINE_EXCEEDED_STATUS, STATUS_METADATA_KEY},
         store::StoreBox,
     },
+    reducers::StateReducers,
     state::State,
 };
 
 use super::{
-    durability::{save_checkpoint, DurabilityMode},
-    parallel::{execute_nodes_parallel, merge_state_updates},
+    durability::{save_checkpoint_with_id, DurabilityMode},
+    parallel::execute_nodes_parallel_observed,
     scheduler::NodeScheduler,
 };
 
@@ -29,6 +31,8 @@ pub struct SuperStepExecutor<S: State + 'static> {
     scheduler: NodeScheduler<S>,
     checkpointer: Option<CheckpointerBox<S>>,
     durability_mode: DurabilityMode,
+    reducers: StateReducers,
+    callbacks: Vec<Arc<dyn GraphCallbacks>>,
 }
 
 impl<S: State + 'static> SuperStepExecutor<S> {
@@ -44,9 +48,21 @@ impl<S: State + 'static> SuperStepExecutor<S> {
             scheduler,
             checkpointer,
             durability_mode,
+            reducers: StateReducers::new(),
+            callbacks: Vec::new(),
         }
     }
 
+    /// Merge each super-step's updates with `reducers` (set via `StateGraph::set_reducer`)
+    pub fn with_reducers(self, reducers: StateReducers) -> Self {
+        Self { reducers, ..self }
+    }
+
+    /// Report node runs and checkpoints to `callbacks` (set via `CompiledGraph::with_callbacks`)
+    pub(crate) fn with_callbacks(self, callbacks: Vec<Arc<dyn GraphCallbacks>>) -> Self {
+        Self { callbacks, ..self }
+    }
+
     /// Execute the graph using super-step model
     ///
     /// Returns the final state after all super-steps complete.
@@ -69,7 +85,9 @@ impl<S: State + 'static> SuperStepExecutor<S> {
         let mut current_state = initial_state;
         let mut executed_nodes = HashSet::new();
         let mut step = 0;
-        let max_steps = 1000; // Prevent infinite loops
+        let step_limit = config.map_or(DEFAULT_STEP_LIMIT, |c| c.get_step_limit());
+        let mut last_nodes = vec![START.to_string()];
+        let callbacks = RunCallbacks::new(&self.callbacks, config);
 
         // Save initial checkpoint (only for Sync mode, others will be saved later)
         if self.durability_mode == DurabilityMode::Sync {
@@ -89,19 +107,19 @@ impl<S: State + 'static> SuperStepExecutor<S> {
                         checkpoint_config.clone(),
                     )
                 };
-                save_checkpoint(Some(&checkpointer), &initial_snapshot, self.durability_mode)
-                    .await?;
+                if let Some(checkpoint_id) = save_checkpoint_with_id(
+                    Some(&checkpointer),
+                    &initial_snapshot,
+                    self.durability_mode,
+                )
+                .await?
+                {
+                    callbacks.checkpoint(&checkpoint_id);
+                }
             }
         }
 
         loop {
-            if step >= max_steps {
-                return Err(GraphError::ExecutionError(
-                    "Maximum super-steps reached. Possible infinite loop.".to_string(),
-                ));
-            }
-            step += 1;
-
             // Get ready nodes for this super-step
             let ready_nodes = self
                 .scheduler
@@ -125,15 +143,62 @@ impl<S: State + 'static> SuperStepExecutor<S> {
                 ));
             }
 
+            let limit_error = if step >= step_limit {
+                Some(GraphError::StepLimitExceeded {
+                    limit: step_limit,
+                    last_node: last_nodes.join(", "),
+                })
+            } else if config.is_some_and(RunnableConfig::is_past_deadline) {
+                Some(GraphError::DeadlineExceeded {
+                    node: ready_nodes.join(", "),
+                })
+            } else {
+                None
+            };
+            if let Some(error) = limit_error {
+                // Persist where the run stopped so it can be resumed with a higher limit
+                // or a fresh deadline
+                if let Some(checkpointer) = &self.checkpointer {
+                    let mut snapshot = match parent_config {
+                        Some(parent) => StateSnapshot::with_parent(
+                            current_state.clone(),
+                            ready_nodes.clone(),
+                            checkpoint_config.clone(),
+                            parent.clone(),
+                        ),
+                        None => StateSnapshot::new(
+                            current_state.clone(),
+                            ready_nodes.clone(),
+                            checkpoint_config.clone(),
+                        ),
+                    };
+                    if matches!(error, GraphError::DeadlineExceeded { .. }) {
+                        snapshot.metadata.insert(
+                            STATUS_METADATA_KEY.to_string(),
+                            serde_json::json!(DEADLINE_EXCEEDED_STATUS),
+                        );
+                    }
+                    checkpointer
+                        .put(checkpoint_config.thread_id.as_str(), &snapshot)
+                        .await
+                        .map_err(|e| {
+                            GraphError::ExecutionError(format!("Failed to save checkpoint: {}", e))
+                        })?;
+                }
+                return Err(error);
+            }
+            step += 1;
+
             log::debug!("Super-step {}: Executing nodes: {:?}", step, ready_nodes);
 
             // Execute all ready nodes in parallel
-            let updates = execute_nodes_parallel(
+            let updates = execute_nodes_parallel_observed(
                 &self.nodes,
                 &ready_nodes,
                 &current_state,
                 config,        // Pass config to nodes
                 store.clone(), // Pass store to nodes (clone for each call)
+                &self.callbacks,
             )
             .await?;
 
@@ -141,9 +206,10 @@ impl<S: State + 'static> SuperStepExecutor<S> {
             for node_name in &ready_nodes {
                 executed_nodes.insert(node_name.clone());
             }
+            last_nodes.clone_from(&ready_nodes);
 
             // Merge all state updates
-            current_state = merge_state_updates(&current_state, &updates)?;
+            current_state = self.reducers.apply_all(&current_state, &updates)?;
 
             // Save checkpoint after super-step
             if let Some(checkpointer) = &self.checkpointer {
@@ -176,7 +242,12 @@ impl<S: State + 'static> SuperStepExecutor<S> {
                     )
                 };
 
-                save_checkpoint(Some(&checkpointer), &snapshot, self.durability_mode).await?;
+                if let Some(checkpoint_id) =
+                    save_checkpoint_with_id(Some(&checkpointer), &snapshot, self.durability_mode)
+                        .await?
+                {
+                    callbacks.checkpoint(&checkpoint_id);
+                }
             }
 
             // Check if we've reached END using scheduler's is_complete method
@@ -203,7 +274,7 @@ impl<S: State + 'static> SuperStepExecutor<S> {
                 } else {
                     StateSnapshot::new(current_state.clone(), vec![], checkpoint_config.clone())
                 };
-                checkpointer
+                let checkpoint_id = checkpointer
                     .put(checkpoint_config.thread_id.as_str(), &final_snapshot)
                     .await
                     .map_err(|e| {
@@ -212,6 +283,7 @@ impl<S: State + 'static> SuperStepExecutor<S> {
                             e
                         ))
                     })?;
+                callbacks.checkpoint(&checkpoint_id);
             }
         }
 
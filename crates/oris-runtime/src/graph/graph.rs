@@ -3,12 +3,18 @@ use std::sync::Arc;
 
 use super::{
     compiled::CompiledGraph,
-    edge::{Edge, EdgeType, END, START},
+    edge::{Edge, EdgeType, SendTo, END, START},
     error::GraphError,
     node::{Node, SubgraphNode, SubgraphNodeWithTransform},
+    node_cache::NodeCachePolicy,
+    node_options::NodeOptions,
     persistence::{checkpointer::CheckpointerBox, store::StoreBox},
     plugin::NodePluginRegistry,
+    reducers::StateReducers,
+    retry::RetryPolicy,
+    spec::{ConditionalEdgeSpec, EdgeSpec, GraphSpec, NodeSpec},
     state::{State, StateUpdate},
+    validation::validate_graph,
 };
 
 /// StateGraph - a builder for creating stateful graphs
@@ -33,6 +39,16 @@ use super::{
 pub struct StateGraph<S: State> {
     nodes: HashMap<String, Arc<dyn Node<S>>>,
     edges: Vec<Edge<S>>,
+    node_options: HashMap<String, NodeOptions>,
+    node_cache_policies: HashMap<String, NodeCachePolicy<S>>,
+    reducers: StateReducers,
+    interrupt_before: Vec<String>,
+    interrupt_after: Vec<String>,
+    /// Plugin payloads of the nodes added with `add_plugin_node`, in insertion order
+    plugin_nodes: Vec<NodeSpec>,
+    /// Router payloads of conditional edges added with `add_plugin_conditional_edges`,
+    /// keyed by index in `edges`
+    plugin_routers: HashMap<usize, ConditionalEdgeSpec>,
 }
 
 impl<S: State + 'static> StateGraph<S> {
@@ -41,6 +57,13 @@ impl<S: State + 'static> StateGraph<S> {
         Self {
             nodes: HashMap::new(),
             edges: Vec::new(),
+            node_options: HashMap::new(),
+            node_cache_policies: HashMap::new(),
+            reducers: StateReducers::new(),
+            interrupt_before: Vec::new(),
+            interrupt_after: Vec::new(),
+            plugin_nodes: Vec::new(),
+            plugin_routers: HashMap::new(),
         }
     }
 
@@ -62,6 +85,90 @@ impl<S: State + 'static> StateGraph<S> {
         self.add_shared_node(name, Arc::new(node))
     }
 
+    /// Add a node that is retried according to `policy` when it fails
+    ///
+    /// Retries happen inside the node boundary, so failed attempts never write
+    /// checkpoints or state events; the attempt count and last error are recorded
+    /// in checkpoint metadata under
+    /// [`NODE_ATTEMPTS_METADATA_KEY`](super::NODE_ATTEMPTS_METADATA_KEY).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use oris_runtime::graph::{function_node, Backoff, MessagesState, RetryPolicy, StateGraph};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// graph
+    ///     .add_node_with_retry(
+    ///         "fetch",
+    ///         function_node("fetch", |_state| async move {
+    ///             Ok(std::collections::HashMap::new())
+    ///         }),
+    ///         RetryPolicy::new(3).with_backoff(Backoff::Fixed(Duration::from_millis(100))),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn add_node_with_retry<N: Node<S> + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        node: N,
+        policy: RetryPolicy,
+    ) -> Result<&mut Self, GraphError> {
+        self.add_node_with_options(name, node, NodeOptions::from(policy))
+    }
+
+    /// Add a node with execution options (retry policy, timeout and timeout behavior)
+    ///
+    /// A timed-out attempt fails the run with `GraphError::NodeTimeout`, is retried, or
+    /// routes to a fallback node, depending on [`NodeOptions::on_timeout`]. Fallback
+    /// targets are validated when the graph is compiled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use oris_runtime::graph::{function_node, MessagesState, NodeOptions, StateGraph};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// graph
+    ///     .add_node_with_options(
+    ///         "fetch",
+    ///         function_node("fetch", |_state| async move {
+    ///             Ok(std::collections::HashMap::new())
+    ///         }),
+    ///         NodeOptions::new().with_timeout(Duration::from_secs(10)),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn add_node_with_options<N: Node<S> + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        node: N,
+        options: NodeOptions,
+    ) -> Result<&mut Self, GraphError> {
+        let name = name.into();
+        check_node_options(&name, &options)?;
+        self.add_shared_node(name.clone(), Arc::new(node))?;
+        self.node_options.insert(name, options);
+        Ok(self)
+    }
+
+    /// Set the [NodeOptions] of a node already in the graph, such as one added with
+    /// [`add_plugin_node`](Self::add_plugin_node)
+    pub fn set_node_options(
+        &mut self,
+        name: &str,
+        options: NodeOptions,
+    ) -> Result<&mut Self, GraphError> {
+        if !self.nodes.contains_key(name) {
+            return Err(GraphError::NodeNotFound(name.to_string()));
+        }
+        check_node_options(name, &options)?;
+        self.node_options.insert(name.to_string(), options);
+        Ok(self)
+    }
+
     /// Add a pre-built shared node instance to the graph.
     ///
     /// This is mainly used by runtime plugin registries that construct nodes
@@ -91,7 +198,13 @@ impl<S: State + 'static> StateGraph<S> {
         let name = name.into();
         let config = config.into();
         let node = registry.create_node(&name, plugin_type, &config)?;
-        self.add_shared_node(name, node)
+        self.add_shared_node(name.clone(), node)?;
+        self.plugin_nodes.push(NodeSpec {
+            name,
+            plugin_type: plugin_type.to_string(),
+            config,
+        });
+        Ok(self)
     }
 
     /// Add a subgraph as a node (shared state type)
@@ -161,6 +274,74 @@ impl<S: State + 'static> StateGraph<S> {
         self.add_node(node.name().to_string(), node)
     }
 
+    /// Add a node whose updates are cached under `policy`
+    ///
+    /// When the cache holds an update for the node's cache key, that update is applied
+    /// without running the node, and the hit is recorded in checkpoint metadata under
+    /// [`NODE_ATTEMPTS_METADATA_KEY`](super::NODE_ATTEMPTS_METADATA_KEY). Updates are kept
+    /// in memory unless the compiled graph is given another cache with
+    /// `CompiledGraph::with_node_cache`. The super-step executor
+    /// (`invoke_with_config_and_mode`) always runs the node.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use oris_runtime::graph::{function_node, MessagesState, NodeCachePolicy, StateGraph};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// graph
+    ///     .add_node_with_cache(
+    ///         "embed",
+    ///         function_node("embed", |_state| async move {
+    ///             Ok(std::collections::HashMap::new())
+    ///         }),
+    ///         NodeCachePolicy::new().with_ttl(Duration::from_secs(3600)),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn add_node_with_cache<N: Node<S> + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        node: N,
+        policy: NodeCachePolicy<S>,
+    ) -> Result<&mut Self, GraphError> {
+        let name = name.into();
+        self.add_shared_node(name.clone(), Arc::new(node))?;
+        self.node_cache_policies.insert(name, policy);
+        Ok(self)
+    }
+
+    /// Register how writes to a state key are combined
+    ///
+    /// The reducer receives the key's current value (`Null` when unset) and the value
+    /// a node writes, and returns the new value. It takes precedence over the state
+    /// type's own [`State::reducer`](super::State::reducer) for that key. Nodes that run
+    /// in the same step (in the super-step executor or as fan-out branches) may only
+    /// both write a key that has a reducer; otherwise the run fails with
+    /// `GraphError::ConflictingUpdate`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use oris_runtime::graph::{MessagesState, Reducers, StateGraph};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// graph
+    ///     .set_reducer("messages", Reducers::append)
+    ///     .set_reducer("score", Reducers::max)
+    ///     .set_reducer("tags", |current: &serde_json::Value, update: &serde_json::Value| {
+    ///         if update.is_null() { current.clone() } else { update.clone() }
+    ///     });
+    /// ```
+    pub fn set_reducer<F>(&mut self, key: impl Into<String>, reducer: F) -> &mut Self
+    where
+        F: Fn(&serde_json::Value, &serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.reducers.insert(key, Arc::new(reducer));
+        self
+    }
+
     /// Add a regular edge between two nodes
     ///
     /// # Arguments
@@ -211,6 +392,200 @@ impl<S: State + 'static> StateGraph<S> {
         self
     }
 
+    /// Add a conditional edge from a node using a synchronous router
+    ///
+    /// The router inspects the state and returns a key; `mapping` translates the key
+    /// into the next node name (which may be END). Every mapping target is validated
+    /// at `compile()` time.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::collections::HashMap;
+    /// use oris_runtime::graph::{StateGraph, MessagesState, END};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// let mut mapping = HashMap::new();
+    /// mapping.insert("continue".to_string(), "node2".to_string());
+    /// mapping.insert("done".to_string(), END.to_string());
+    ///
+    /// graph.add_conditional_edges_sync("node1", |state: &MessagesState| {
+    ///     if state.messages.len() > 3 { "done".to_string() } else { "continue".to_string() }
+    /// }, mapping);
+    /// ```
+    pub fn add_conditional_edges_sync<F>(
+        &mut self,
+        from: impl Into<String>,
+        router: F,
+        mapping: HashMap<String, String>,
+    ) -> &mut Self
+    where
+        F: Fn(&S) -> String + Send + Sync + 'static,
+    {
+        let edge = Edge::conditional_sync(from, router, mapping);
+        self.edges.push(edge);
+        self
+    }
+
+    /// Add a conditional edge whose router is built by a registered router plugin
+    ///
+    /// Like [`add_conditional_edges_sync`](Self::add_conditional_edges_sync), with the
+    /// router resolved from `registry` by `router_type` and `config`. The payload is
+    /// kept so the edge can be exported with [`GraphSpec::from_state_graph`]. Fails if
+    /// the plugin publishes its [route keys](super::RouterPlugin::route_keys) and
+    /// `mapping` misses one of them.
+    pub fn add_plugin_conditional_edges(
+        &mut self,
+        from: impl Into<String>,
+        router_type: &str,
+        config: impl Into<serde_json::Value>,
+        mapping: HashMap<String, String>,
+        registry: &NodePluginRegistry<S>,
+    ) -> Result<&mut Self, GraphError> {
+        let from = from.into();
+        let config = config.into();
+        let router = registry.create_router(router_type, &config)?;
+        if let Some(keys) = registry.router_keys(router_type, &config) {
+            if let Some(key) = keys.iter().find(|key| !mapping.contains_key(*key)) {
+                return Err(GraphError::CompilationError(format!(
+                    "Router '{}' on '{}' can return '{}', which has no entry in the edge mapping",
+                    router_type, from, key
+                )));
+            }
+        }
+        let spec = ConditionalEdgeSpec {
+            from: from.clone(),
+            router: router_type.to_string(),
+            config,
+            mapping: mapping.clone().into_iter().collect(),
+        };
+        self.plugin_routers.insert(self.edges.len(), spec);
+        self.edges.push(Edge::conditional_sync(
+            from,
+            move |state: &S| router(state),
+            mapping,
+        ));
+        Ok(self)
+    }
+
+    /// Add a fan-out edge for map-reduce over a collection
+    ///
+    /// After `from` runs, `router` returns one [`SendTo`] per branch. Each branch runs its
+    /// worker node on a copy of the state merged with the branch payload, at most
+    /// `RunnableConfig::get_max_concurrency()` at a time. The branch updates are then
+    /// merged into the state in dispatch order and execution continues at `join`.
+    /// Every branch must target one of `workers`, and workers need no outgoing edges.
+    ///
+    /// Fan-out edges run under `invoke` and `invoke_with_config`; with a checkpointer,
+    /// finished branches are checkpointed so a resumed run only re-runs the rest.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use oris_runtime::graph::{messages_state_update, MessagesState, SendTo, StateGraph};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// // One "summarize" branch per message, each seeing that message last
+    /// graph.add_fan_out_edges(
+    ///     "split",
+    ///     &["summarize"],
+    ///     |state: &MessagesState| {
+    ///         state
+    ///             .messages
+    ///             .iter()
+    ///             .map(|m| SendTo::new("summarize", messages_state_update(vec![m.clone()])))
+    ///             .collect()
+    ///     },
+    ///     "combine",
+    /// );
+    /// ```
+    pub fn add_fan_out_edges<F>(
+        &mut self,
+        from: impl Into<String>,
+        workers: &[&str],
+        router: F,
+        join: impl Into<String>,
+    ) -> &mut Self
+    where
+        F: Fn(&S) -> Vec<SendTo> + Send + Sync + 'static,
+    {
+        let workers = workers.iter().map(|worker| worker.to_string()).collect();
+        let edge = Edge::fan_out(from, workers, router, join);
+        self.edges.push(edge);
+        self
+    }
+
+    /// Pause before each of `nodes` whenever the compiled graph runs
+    ///
+    /// The breakpoints behave like the `interrupt_before` argument of
+    /// [`compile_with_interrupts`](Self::compile_with_interrupts), which adds to them.
+    /// Compiling fails if one names a node that does not exist.
+    pub fn add_interrupt_before(&mut self, nodes: &[&str]) -> &mut Self {
+        self.interrupt_before
+            .extend(nodes.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Pause after each of `nodes` whenever the compiled graph runs
+    ///
+    /// The counterpart of [`add_interrupt_before`](Self::add_interrupt_before).
+    pub fn add_interrupt_after(&mut self, nodes: &[&str]) -> &mut Self {
+        self.interrupt_after
+            .extend(nodes.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Describe the graph as a [`GraphSpec`]
+    ///
+    /// Fails if a node was not added with `add_plugin_node`, or an edge is a
+    /// conditional edge without a router plugin or a fan-out edge.
+    pub(super) fn to_spec(&self) -> Result<GraphSpec, GraphError> {
+        let exported: HashSet<&str> = self.plugin_nodes.iter().map(|n| n.name.as_str()).collect();
+        let mut missing: Vec<&String> = self
+            .nodes
+            .keys()
+            .filter(|name| !exported.contains(name.as_str()))
+            .collect();
+        missing.sort();
+        if let Some(name) = missing.first() {
+            return Err(GraphError::CompilationError(format!(
+                "Node '{}' was not built from a plugin and cannot be exported",
+                name
+            )));
+        }
+
+        let mut spec = GraphSpec {
+            nodes: self.plugin_nodes.clone(),
+            interrupt_before: self.interrupt_before.clone(),
+            interrupt_after: self.interrupt_after.clone(),
+            ..GraphSpec::default()
+        };
+        for (index, edge) in self.edges.iter().enumerate() {
+            match &edge.edge_type {
+                EdgeType::Regular { to } => spec.edges.push(EdgeSpec {
+                    from: edge.from.clone(),
+                    to: to.clone(),
+                }),
+                EdgeType::Conditional { .. } => {
+                    let router = self.plugin_routers.get(&index).ok_or_else(|| {
+                        GraphError::CompilationError(format!(
+                            "Conditional edge from '{}' has no router plugin and cannot be exported",
+                            edge.from
+                        ))
+                    })?;
+                    spec.conditional_edges.push(router.clone());
+                }
+                EdgeType::FanOut { .. } => {
+                    return Err(GraphError::CompilationError(format!(
+                        "Fan-out edge from '{}' cannot be exported",
+                        edge.from
+                    )))
+                }
+            }
+        }
+        Ok(spec)
+    }
+
     /// Compile the graph into an executable CompiledGraph
     ///
     /// This validates the graph structure and creates an optimized
@@ -218,14 +593,23 @@ impl<S: State + 'static> StateGraph<S> {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - No path exists from START to END
-    /// - Nodes are referenced in edges but not defined
-    /// - Other graph validation errors
+    /// Returns `GraphError::ValidationFailed` with every [`ValidationIssue`](super::ValidationIssue)
+    /// found:
+    /// - Edges or conditional-edge mappings that reference unknown nodes
+    /// - Nodes unreachable from START, or with no path to END
+    /// - No path from START to END
     pub fn compile(self) -> Result<CompiledGraph<S>, GraphError> {
         self.compile_with_persistence(None, None)
     }
 
+    /// Compile the graph without the validation pass of [`compile`](Self::compile)
+    ///
+    /// For graphs built with targets that are only known at run time (e.g. nodes
+    /// reached through `Command::goto`). Invalid edges then fail when they are taken.
+    pub fn compile_unchecked(self) -> Result<CompiledGraph<S>, GraphError> {
+        self.build(None, None)
+    }
+
     /// Compile the graph with checkpointer and store
     ///
     /// This allows the graph to persist state and support features like
@@ -243,18 +627,84 @@ impl<S: State + 'static> StateGraph<S> {
         checkpointer: Option<CheckpointerBox<S>>,
         store: Option<StoreBox>,
     ) -> Result<CompiledGraph<S>, GraphError> {
-        // Validate graph structure
         self.validate()?;
+        self.build(checkpointer, store)
+    }
+
+    /// Build the executable graph from the builder's nodes and edges
+    fn build(
+        self,
+        checkpointer: Option<CheckpointerBox<S>>,
+        store: Option<StoreBox>,
+    ) -> Result<CompiledGraph<S>, GraphError> {
+        for node in self.interrupt_before.iter().chain(&self.interrupt_after) {
+            if !self.nodes.contains_key(node) {
+                return Err(GraphError::CompilationError(format!(
+                    "Breakpoint node '{}' not found",
+                    node
+                )));
+            }
+        }
 
         // Build adjacency list for efficient traversal
         let adjacency = self.build_adjacency()?;
+        let interrupt_before: HashSet<String> = self.interrupt_before.into_iter().collect();
+        let interrupt_after: HashSet<String> = self.interrupt_after.into_iter().collect();
 
         // Take ownership of nodes so we don't borrow self while moving
         let nodes = self.nodes;
         let nodes =
             Self::propagate_persistence_to_subgraphs(nodes, checkpointer.as_ref(), store.as_ref())?;
 
-        CompiledGraph::with_persistence(nodes, adjacency, checkpointer, store)
+        Ok(
+            CompiledGraph::with_persistence(nodes, adjacency, checkpointer, store)?
+                .with_node_options(self.node_options)
+                .with_node_cache_policies(self.node_cache_policies)
+                .with_reducers(self.reducers)
+                .with_breakpoints(interrupt_before, interrupt_after),
+        )
+    }
+
+    /// Compile the graph with a checkpointer and static breakpoints
+    ///
+    /// Execution through `invoke_with_config` / `invoke_with_config_interrupt` pauses
+    /// before every node in `interrupt_before` and after every node in `interrupt_after`,
+    /// writes a checkpoint whose `next` is the pending node (with the breakpoint in its
+    /// metadata under [`BREAKPOINT_METADATA_KEY`](super::BREAKPOINT_METADATA_KEY)), and
+    /// returns an interrupt describing the breakpoint. Calling
+    /// `invoke_with_config(None, config)` on the same thread passes the breakpoint once
+    /// and continues from the pending node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a breakpoint names a node that does not exist, or for the
+    /// same reasons as [`compile`](Self::compile).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::sync::Arc;
+    /// use oris_runtime::graph::{function_node, InMemorySaver, MessagesState, StateGraph, END, START};
+    ///
+    /// let mut graph = StateGraph::<MessagesState>::new();
+    /// graph.add_node("approval", function_node("approval", |_state| async move {
+    ///     Ok(std::collections::HashMap::new())
+    /// })).unwrap();
+    /// graph.add_edge(START, "approval");
+    /// graph.add_edge("approval", END);
+    /// let compiled = graph
+    ///     .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["approval"], &[])
+    ///     .unwrap();
+    /// ```
+    pub fn compile_with_interrupts(
+        mut self,
+        checkpointer: CheckpointerBox<S>,
+        interrupt_before: &[&str],
+        interrupt_after: &[&str],
+    ) -> Result<CompiledGraph<S>, GraphError> {
+        self.add_interrupt_before(interrupt_before)
+            .add_interrupt_after(interrupt_after);
+        self.compile_with_persistence(Some(checkpointer), None)
     }
 
     /// Propagate checkpointer and store to subgraphs
@@ -262,18 +712,39 @@ impl<S: State + 'static> StateGraph<S> {
     /// This ensures that subgraphs inherit the parent's checkpointer and store
     /// if they don't have their own, as per Python LangGraph behavior.
     ///
-    /// Note: Currently, persistence is handled at execution time via config.
-    /// Subgraphs will automatically use the parent's checkpointer when invoked
-    /// with config that includes checkpointer information.
+    /// Shared-state subgraphs without their own checkpointer are rebuilt with the
+    /// parent's checkpointer (recursively, for nested subgraphs), so checkpoints written
+    /// inside them land in the parent's thread under a namespaced `checkpoint_ns`.
     fn propagate_persistence_to_subgraphs(
         nodes: HashMap<String, Arc<dyn Node<S>>>,
-        _checkpointer: Option<&CheckpointerBox<S>>,
-        _store: Option<&StoreBox>,
+        checkpointer: Option<&CheckpointerBox<S>>,
+        store: Option<&StoreBox>,
     ) -> Result<HashMap<String, Arc<dyn Node<S>>>, GraphError> {
-        // Note: Persistence propagation happens at execution time.
-        // When a subgraph is invoked with config, it will use the parent's
-        // checkpointer if available. This matches Python LangGraph behavior.
-        Ok(nodes)
+        if checkpointer.is_none() && store.is_none() {
+            return Ok(nodes);
+        }
+
+        let mut propagated = HashMap::with_capacity(nodes.len());
+        for (name, node) in nodes {
+            let node = match node.get_subgraph() {
+                Some(subgraph) if subgraph.checkpointer().is_none() => {
+                    let sub_nodes = Self::propagate_persistence_to_subgraphs(
+                        subgraph.nodes().clone(),
+                        checkpointer,
+                        store,
+                    )?;
+                    let inherited = subgraph.inherit_persistence(
+                        sub_nodes,
+                        checkpointer.cloned(),
+                        store.cloned(),
+                    );
+                    Arc::new(SubgraphNode::new(name.clone(), inherited)) as Arc<dyn Node<S>>
+                }
+                _ => node,
+            };
+            propagated.insert(name, node);
+        }
+        Ok(propagated)
     }
 
     fn validate_new_node_name(&self, name: &str) -> Result<(), GraphError> {
@@ -294,47 +765,14 @@ impl<S: State + 'static> StateGraph<S> {
         Ok(())
     }
 
-    /// Validate the graph structure
+    /// Validate the graph structure, reporting every issue at once
     fn validate(&self) -> Result<(), GraphError> {
-        // Check that all edges reference valid nodes
-        for edge in &self.edges {
-            // Check source node (unless it's START)
-            if edge.from != START && !self.nodes.contains_key(&edge.from) {
-                return Err(GraphError::InvalidEdge(
-                    edge.from.clone(),
-                    "source node not found".to_string(),
-                ));
-            }
-
-            // Check target nodes
-            match &edge.edge_type {
-                EdgeType::Regular { to } => {
-                    if *to != END && !self.nodes.contains_key(to) {
-                        return Err(GraphError::InvalidEdge(
-                            edge.from.clone(),
-                            format!("target node '{}' not found", to),
-                        ));
-                    }
-                }
-                EdgeType::Conditional { mapping, .. } => {
-                    for target in mapping.values() {
-                        if *target != END && !self.nodes.contains_key(target) {
-                            return Err(GraphError::InvalidEdge(
-                                edge.from.clone(),
-                                format!("conditional target node '{}' not found", target),
-                            ));
-                        }
-                    }
-                }
-            }
-        }
-
-        // Check that there's a path from START to END
-        if !self.has_path_to_end() {
-            return Err(GraphError::NoPathToEnd);
+        let issues = validate_graph(&self.nodes, &self.edges, &self.node_options);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(GraphError::ValidationFailed(issues))
         }
-
-        Ok(())
     }
 
     /// Build adjacency list for graph traversal
@@ -347,59 +785,12 @@ impl<S: State + 'static> StateGraph<S> {
                 .or_insert_with(Vec::new)
                 .push(edge.clone());
         }
-
-        Ok(adjacency)
-    }
-
-    /// Check if there's a path from START to END using DFS
-    fn has_path_to_end(&self) -> bool {
-        let adjacency = match self.build_adjacency() {
-            Ok(adj) => adj,
-            Err(_) => return false,
-        };
-
-        let mut visited = HashSet::new();
-        self.dfs(START, &adjacency, &mut visited)
-    }
-
-    /// Depth-first search to find path to END
-    fn dfs(
-        &self,
-        node: &str,
-        adjacency: &HashMap<String, Vec<Edge<S>>>,
-        visited: &mut HashSet<String>,
-    ) -> bool {
-        if node == END {
-            return true;
-        }
-
-        if visited.contains(node) {
-            return false;
-        }
-
-        visited.insert(node.to_string());
-
-        if let Some(edges) = adjacency.get(node) {
-            for edge in edges {
-                match &edge.edge_type {
-                    EdgeType::Regular { to } => {
-                        if self.dfs(to, adjacency, visited) {
-                            return true;
-                        }
-                    }
-                    EdgeType::Conditional { mapping, .. } => {
-                        // For conditional edges, check all possible targets
-                        for target in mapping.values() {
-                            if self.dfs(target, adjacency, visited) {
-                                return true;
-                            }
-                        }
-                    }
-                }
-            }
+        // Command nodes may have no edges of their own
+        for name in self.nodes.keys() {
+            adjacency.entry(name.clone()).or_default();
         }
 
-        false
+        Ok(adjacency)
     }
 }
 
@@ -409,10 +800,20 @@ impl<S: State + 'static> Default for StateGraph<S> {
     }
 }
 
+fn check_node_options(name: &str, options: &NodeOptions) -> Result<(), GraphError> {
+    if options.retry.as_ref().is_some_and(|p| p.max_attempts == 0) {
+        return Err(GraphError::CompilationError(format!(
+            "Retry policy for node '{}' must allow at least one attempt",
+            name
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::{function_node, state::MessagesState};
+    use crate::graph::{function_node, state::MessagesState, ValidationIssue};
 
     #[test]
     fn test_add_node() {
@@ -450,6 +851,36 @@ mod tests {
         assert!(graph.compile().is_ok());
     }
 
+    #[test]
+    fn test_conditional_edges_validate_targets() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "node1",
+                function_node("node1", |_state| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "node1");
+        let mut mapping = HashMap::new();
+        mapping.insert("done".to_string(), END.to_string());
+        mapping.insert("retry".to_string(), "missing".to_string());
+        graph.add_conditional_edges_sync("node1", |_s: &MessagesState| "done".to_string(), mapping);
+
+        match graph.compile() {
+            Err(GraphError::ValidationFailed(issues)) => assert_eq!(
+                issues,
+                vec![ValidationIssue::UnknownConditionalTarget {
+                    from: "node1".to_string(),
+                    key: "retry".to_string(),
+                    to: "missing".to_string(),
+                }]
+            ),
+            _ => panic!("expected ValidationFailed"),
+        }
+    }
+
     #[test]
     fn test_validate() {
         let mut graph = StateGraph::<MessagesState>::new();
@@ -457,4 +888,97 @@ mod tests {
 
         assert!(graph.compile().is_err());
     }
+
+    #[test]
+    fn test_validation_reports_all_issues() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["draft", "review", "orphan", "sink"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, |_state| async move {
+                        Ok(std::collections::HashMap::new())
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "reviw");
+        graph.add_edge("draft", "review");
+        graph.add_edge("review", END);
+        graph.add_edge("review", "sink");
+        graph.add_edge("orphan", END);
+
+        let issues = match graph.compile() {
+            Err(GraphError::ValidationFailed(issues)) => issues,
+            _ => panic!("expected ValidationFailed"),
+        };
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::UnknownEdgeTarget {
+                    from: "draft".to_string(),
+                    to: "reviw".to_string(),
+                },
+                ValidationIssue::UnreachableNode {
+                    node: "orphan".to_string(),
+                },
+                ValidationIssue::NoOutgoingEdges {
+                    node: "sink".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_nodes_need_no_edges() {
+        use crate::graph::{command_node, Goto, NodeCommand};
+
+        let build = |destinations: &'static [&'static str]| {
+            let mut graph = StateGraph::<MessagesState>::new();
+            graph
+                .add_node(
+                    "route",
+                    command_node("route", destinations, |_state| async move {
+                        Ok(NodeCommand::goto(Goto::End))
+                    }),
+                )
+                .unwrap();
+            graph.add_edge(START, "route");
+            graph
+        };
+
+        assert!(build(&[END]).compile().is_ok());
+        let issues = match build(&["missing"]).compile() {
+            Err(GraphError::ValidationFailed(issues)) => issues,
+            _ => panic!("expected ValidationFailed"),
+        };
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::UnknownEdgeTarget {
+                    from: "route".to_string(),
+                    to: "missing".to_string(),
+                },
+                ValidationIssue::NoPathToEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_unchecked_skips_validation() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "node1",
+                function_node("node1", |_state| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "node1");
+        graph.add_edge("node1", "dynamic_target");
+
+        assert!(graph.compile_unchecked().is_ok());
+    }
 }
@@ -0,0 +1,168 @@
+//! `${env:VAR}` and `${secret:NAME}` placeholders in plugin configs.
+//!
+//! [NodePluginRegistry](super::NodePluginRegistry) expands placeholders in every string
+//! of a node or router config before validating it and handing it to the plugin, so
+//! specs can reference credentials without containing them. Secrets come from the
+//! registry's [SecretResolver]. Errors name the placeholder, never its resolved value.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::error::GraphError;
+
+/// Source of the values of `${secret:NAME}` placeholders
+pub trait SecretResolver: Send + Sync {
+    /// Value of the secret `name`, or `None` if it is not defined.
+    fn resolve(&self, name: &str) -> Option<String>;
+}
+
+/// [SecretResolver] reading secrets from environment variables
+///
+/// `${secret:NAME}` resolves to the variable `NAME`, or `PREFIXNAME` with
+/// [`with_prefix`](Self::with_prefix).
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretResolver {
+    prefix: String,
+}
+
+impl EnvSecretResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read secret `NAME` from the variable `{prefix}NAME`, e.g. `ORIS_SECRET_NAME`.
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl SecretResolver for EnvSecretResolver {
+    fn resolve(&self, name: &str) -> Option<String> {
+        std::env::var(format!("{}{}", self.prefix, name)).ok()
+    }
+}
+
+/// [SecretResolver] over a fixed map, mainly for tests
+impl SecretResolver for HashMap<String, String> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+/// Expand the placeholders in every string of `config`
+///
+/// Object keys and non-string values are left as they are. Fails with
+/// `GraphError::UnresolvedPlaceholder` on an unset variable, an undefined secret, an
+/// unknown placeholder kind or a missing closing `}`.
+pub fn interpolate_config(
+    config: &Value,
+    secrets: &dyn SecretResolver,
+) -> Result<Value, GraphError> {
+    Ok(match config {
+        Value::String(text) => Value::String(interpolate_str(text, secrets)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| interpolate_config(item, secrets))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), interpolate_config(value, secrets)?)))
+                .collect::<Result<_, GraphError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn interpolate_str(text: &str, secrets: &dyn SecretResolver) -> Result<String, GraphError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let unresolved = |placeholder: &str, reason: &str| GraphError::UnresolvedPlaceholder {
+            placeholder: placeholder.to_string(),
+            reason: reason.to_string(),
+        };
+        let Some(end) = after.find('}') else {
+            return Err(unresolved(&rest[start..], "missing closing '}'"));
+        };
+        let placeholder = &rest[start..start + end + 3];
+        let value = match after[..end].split_once(':') {
+            Some(("env", var)) => std::env::var(var)
+                .map_err(|_| unresolved(placeholder, "environment variable is not set"))?,
+            Some(("secret", name)) => secrets
+                .resolve(name)
+                .ok_or_else(|| unresolved(placeholder, "secret is not defined"))?,
+            _ => {
+                return Err(unresolved(
+                    placeholder,
+                    "expected ${env:VAR} or ${secret:NAME}",
+                ))
+            }
+        };
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn secrets() -> HashMap<String, String> {
+        HashMap::from([("API_KEY".to_string(), "sk-123".to_string())])
+    }
+
+    #[test]
+    fn interpolates_nested_strings() {
+        std::env::set_var("ORIS_INTERPOLATION_TEST_HOST", "example.com");
+        let config = json!({
+            "url": "https://${env:ORIS_INTERPOLATION_TEST_HOST}/v1",
+            "headers": ["Bearer ${secret:API_KEY}"],
+            "retries": 3
+        });
+
+        assert_eq!(
+            interpolate_config(&config, &secrets()).unwrap(),
+            json!({
+                "url": "https://example.com/v1",
+                "headers": ["Bearer sk-123"],
+                "retries": 3
+            })
+        );
+    }
+
+    #[test]
+    fn unresolved_placeholders_are_named() {
+        let err = interpolate_config(&json!({"key": "${secret:MISSING}"}), &secrets()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot resolve config placeholder '${secret:MISSING}': secret is not defined"
+        );
+
+        let err = interpolate_config(&json!("${env:ORIS_INTERPOLATION_TEST_UNSET}"), &secrets())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            GraphError::UnresolvedPlaceholder { ref placeholder, .. }
+                if placeholder == "${env:ORIS_INTERPOLATION_TEST_UNSET}"
+        ));
+
+        for text in ["${vault:KEY}", "${secret:API_KEY"] {
+            assert!(
+                interpolate_config(&json!(text), &secrets()).is_err(),
+                "{}",
+                text
+            );
+        }
+    }
+}
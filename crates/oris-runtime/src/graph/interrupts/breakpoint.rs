@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Checkpoint metadata key under which a static breakpoint is recorded.
+pub const BREAKPOINT_METADATA_KEY: &str = "breakpoint";
+
+/// Which side of a node a static breakpoint fires on
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakpointWhen {
+    /// Before the node runs (`interrupt_before`)
+    Before,
+    /// After the node ran (`interrupt_after`)
+    After,
+}
+
+/// A static breakpoint hit configured with `compile_with_interrupts`
+///
+/// Stored in checkpoint metadata so a resumed run (possibly in another process)
+/// knows why execution stopped and passes the breakpoint exactly once.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Breakpoint {
+    /// The node the breakpoint is attached to
+    pub node: String,
+    /// Whether execution stopped before or after the node
+    pub when: BreakpointWhen,
+}
+
+impl Breakpoint {
+    /// Breakpoint that fires before `node` runs
+    pub fn before(node: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            when: BreakpointWhen::Before,
+        }
+    }
+
+    /// Breakpoint that fires after `node` ran
+    pub fn after(node: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            when: BreakpointWhen::After,
+        }
+    }
+
+    /// Read the breakpoint recorded in checkpoint metadata, if any
+    pub fn from_metadata(metadata: &HashMap<String, Value>) -> Option<Self> {
+        metadata
+            .get(BREAKPOINT_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Serialize for checkpoint metadata and interrupt payloads
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakpoint_metadata_roundtrip() {
+        let mut metadata = HashMap::new();
+        assert_eq!(Breakpoint::from_metadata(&metadata), None);
+
+        let breakpoint = Breakpoint::before("approval");
+        metadata.insert(BREAKPOINT_METADATA_KEY.to_string(), breakpoint.to_value());
+        assert_eq!(Breakpoint::from_metadata(&metadata), Some(breakpoint));
+        assert_eq!(
+            metadata[BREAKPOINT_METADATA_KEY],
+            serde_json::json!({"node": "approval", "when": "before"})
+        );
+    }
+}
@@ -109,6 +109,19 @@ pub fn has_interrupt() -> bool {
         .unwrap_or(false)
 }
 
+/// Check if the current context carries resume values (i.e. execution is resuming)
+pub fn has_resume_values() -> bool {
+    INTERRUPT_CONTEXT
+        .try_with(|ctx| {
+            ctx.borrow()
+                .as_ref()
+                .map(|c| !c.resume_values.is_empty())
+                .unwrap_or(false)
+        })
+        .ok()
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
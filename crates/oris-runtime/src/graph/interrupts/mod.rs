@@ -1,3 +1,4 @@
+pub mod breakpoint;
 pub mod command;
 pub mod context;
 pub mod error;
@@ -8,6 +9,7 @@ pub mod types;
 #[cfg(test)]
 mod tests;
 
+pub use breakpoint::*;
 pub use command::*;
 pub use context::*;
 pub use error::*;
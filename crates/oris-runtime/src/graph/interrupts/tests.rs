@@ -148,11 +148,12 @@ mod tests {
             events: Box::new(SharedEventStore(inner)),
             snaps: None,
             reducer: Box::new(StateUpdatedOnlyReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(NoopStepFn),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let replayed = kernel
             .replay(&run_id, initial_state)
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Checkpoint metadata key under which pending interrupts are recorded.
+pub const INTERRUPTS_METADATA_KEY: &str = "interrupts";
+
 /// Interrupt information
 ///
 /// Represents an interrupt that occurred during graph execution.
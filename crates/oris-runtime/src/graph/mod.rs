@@ -1,25 +1,57 @@
+mod action;
+mod callbacks;
 mod compiled;
+mod deadline;
+#[cfg(feature = "dynamic-plugins")]
+mod dynamic_plugin;
 mod edge;
 pub mod error;
 mod execution;
 mod graph;
+mod interpolation;
 mod interrupts;
 mod node;
+mod node_cache;
+mod node_options;
+#[cfg(feature = "otel")]
+pub mod otel;
 mod persistence;
 mod plugin;
+mod prebuilt;
+mod reducers;
+mod retry;
+mod spec;
 mod state;
 mod step_adapter;
 mod step_result;
 mod streaming;
 pub mod task;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod trace;
+mod validation;
+mod visualize;
+#[cfg(feature = "wasm-plugins")]
+mod wasm_plugin;
 
+pub use action::{request_action, with_simulated_actions};
+pub use callbacks::*;
 pub use compiled::*;
+pub use deadline::{current_cancellation, current_deadline};
+#[cfg(feature = "dynamic-plugins")]
+pub use dynamic_plugin::*;
 pub use edge::*;
 pub use error::*;
 pub use graph::*;
+pub use interpolation::*;
 pub use node::*;
+pub use node_cache::*;
+pub use node_options::*;
 pub use plugin::*;
+pub use prebuilt::*;
+pub use reducers::*;
+pub use retry::*;
+pub use spec::*;
 pub use state::*;
 // StreamEvent and StreamOptions are re-exported from compiled module
 pub use compiled::{StreamEvent, StreamOptions};
@@ -31,3 +63,6 @@ pub use step_result::GraphStepOnceResult;
 pub use streaming::*;
 pub use task::*;
 pub use trace::*;
+pub use validation::ValidationIssue;
+#[cfg(feature = "wasm-plugins")]
+pub use wasm_plugin::*;
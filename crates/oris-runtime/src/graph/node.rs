@@ -16,8 +16,18 @@ use super::{
     StateUpdate,
 };
 
+mod command;
+mod retrieval;
 mod subgraph;
+mod tool;
+pub use command::{command_node, CommandNode, Goto, NodeCommand, GOTO_UPDATE_KEY};
+pub use retrieval::{
+    format_documents, retrieval_node, retrieval_node_config_schema, retrieval_node_plugin,
+    DocumentFormatter, RetrievalNode, RetrievalNodeConfig, RetrievalOptions, RetrievalRole,
+    RetrieverRegistry, RETRIEVAL_NODE_PLUGIN_TYPE, RETRIEVAL_TOOL_CALL_ID,
+};
 pub use subgraph::{SubgraphNode, SubgraphNodeWithTransform};
+pub use tool::{tool_node, ToolNode, ToolNodeOptions};
 
 /// Trait for nodes in a LangGraph
 ///
@@ -71,6 +81,14 @@ pub trait Node<S: State>: Send + Sync {
     fn get_subgraph(&self) -> Option<Arc<CompiledGraph<S>>> {
         None
     }
+
+    /// Get the nodes this node may jump to with a [`NodeCommand`] goto
+    ///
+    /// Returns None if this node never returns a goto, in which case it needs
+    /// outgoing edges. This is used for graph validation.
+    fn goto_destinations(&self) -> Option<Vec<String>> {
+        None
+    }
 }
 
 /// Function node - wraps an async function
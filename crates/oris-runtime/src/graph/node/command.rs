@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::Node;
+use crate::graph::{edge::END, error::GraphError, state::State, StateUpdate};
+
+/// Key under which a [`NodeCommand`]'s goto travels inside a node's state update
+///
+/// Executors strip it before the update is merged, so it never reaches the state.
+pub const GOTO_UPDATE_KEY: &str = "__goto__";
+
+/// Where a [`NodeCommand`] sends execution next
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "node", rename_all = "snake_case")]
+pub enum Goto {
+    /// A node of the same graph
+    Node(String),
+    /// Finish the graph
+    End,
+    /// A node of the parent graph; the subgraph finishes and the parent jumps there
+    Parent(String),
+}
+
+impl Goto {
+    /// Jump to a node of the same graph (`END` finishes the graph)
+    pub fn node(name: impl Into<String>) -> Self {
+        let name = name.into();
+        if name == END {
+            Self::End
+        } else {
+            Self::Node(name)
+        }
+    }
+
+    /// Jump to a node of the parent graph
+    pub fn parent(name: impl Into<String>) -> Self {
+        Self::Parent(name.into())
+    }
+}
+
+/// What a command node returns: a state update and, optionally, the node to run next
+///
+/// The update is applied first; a goto then takes precedence over the node's edges.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeCommand {
+    pub update: StateUpdate,
+    pub goto: Option<Goto>,
+}
+
+impl NodeCommand {
+    /// A command that applies `update` and follows the node's edges
+    pub fn new(update: StateUpdate) -> Self {
+        Self { update, goto: None }
+    }
+
+    /// A command that only jumps
+    pub fn goto(goto: Goto) -> Self {
+        Self {
+            update: HashMap::new(),
+            goto: Some(goto),
+        }
+    }
+
+    /// Set the node to jump to after applying the update
+    pub fn with_goto(mut self, goto: Goto) -> Self {
+        self.goto = Some(goto);
+        self
+    }
+
+    /// Encode the command as a plain state update, with the goto under [`GOTO_UPDATE_KEY`]
+    pub fn into_update(self) -> StateUpdate {
+        let mut update = self.update;
+        if let Some(goto) = self.goto {
+            update.insert(
+                GOTO_UPDATE_KEY.to_string(),
+                serde_json::to_value(goto).unwrap_or_default(),
+            );
+        }
+        update
+    }
+
+    /// Split a node's state update back into the update and its goto, if any
+    pub fn from_update(mut update: StateUpdate) -> Result<Self, GraphError> {
+        let goto = match update.remove(GOTO_UPDATE_KEY) {
+            Some(value) => Some(serde_json::from_value(value).map_err(|e| {
+                GraphError::ExecutionError(format!("Invalid goto in node update: {}", e))
+            })?),
+            None => None,
+        };
+        Ok(Self { update, goto })
+    }
+}
+
+impl From<StateUpdate> for NodeCommand {
+    fn from(update: StateUpdate) -> Self {
+        Self::new(update)
+    }
+}
+
+type CommandFn<S> = Arc<
+    dyn Fn(
+            &S,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<NodeCommand, GraphError>> + Send>,
+        > + Send
+        + Sync,
+>;
+
+/// Command node - wraps an async function that returns a [`NodeCommand`]
+///
+/// The node decides where execution goes next, so it needs no outgoing edges. Any
+/// edges it has are followed when a command carries no goto.
+pub struct CommandNode<S: State> {
+    name: String,
+    destinations: Vec<String>,
+    func: CommandFn<S>,
+}
+
+impl<S: State> CommandNode<S> {
+    /// Create a command node that may jump to `destinations`
+    pub fn new<F, Fut>(name: String, destinations: Vec<String>, func: F) -> Self
+    where
+        F: Fn(&S) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<NodeCommand, GraphError>> + Send + 'static,
+    {
+        Self {
+            name,
+            destinations,
+            func: Arc::new(move |state| Box::pin(func(state))),
+        }
+    }
+
+    /// Get the name of the node
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[async_trait]
+impl<S: State> Node<S> for CommandNode<S> {
+    async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        Ok((self.func)(state).await?.into_update())
+    }
+
+    fn goto_destinations(&self) -> Option<Vec<String>> {
+        Some(self.destinations.clone())
+    }
+}
+
+/// Helper function to create a command node from a closure
+///
+/// `destinations` lists the nodes (or `END`) the node may jump to within its graph;
+/// validation treats them like outgoing edges.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use oris_runtime::graph::{command_node, Goto, MessagesState, NodeCommand, END};
+///
+/// let _node = command_node("triage", &["escalate", END], |s: &MessagesState| {
+///     let urgent = s.messages.len() > 3;
+///     async move {
+///         Ok(NodeCommand::goto(if urgent { Goto::node("escalate") } else { Goto::End }))
+///     }
+/// });
+/// ```
+pub fn command_node<S: State, F, Fut>(
+    name: impl Into<String>,
+    destinations: &[&str],
+    func: F,
+) -> CommandNode<S>
+where
+    F: Fn(&S) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<NodeCommand, GraphError>> + Send + 'static,
+{
+    CommandNode::new(
+        name.into(),
+        destinations.iter().map(|d| d.to_string()).collect(),
+        func,
+    )
+}
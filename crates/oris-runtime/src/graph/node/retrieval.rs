@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::Node;
+use crate::{
+    graph::{
+        error::GraphError,
+        plugin::{typed_node_plugin, NodePlugin},
+        state::State,
+        StateUpdate,
+    },
+    schemas::{messages::Message, Document, Retriever},
+};
+
+/// Plugin type of [`retrieval_node_plugin`].
+pub const RETRIEVAL_NODE_PLUGIN_TYPE: &str = "oris/retrieval";
+
+/// Id of the tool messages a [`RetrievalNode`] appends with [`RetrievalRole::Tool`].
+pub const RETRIEVAL_TOOL_CALL_ID: &str = "retrieval";
+
+/// Turns the query and the retrieved documents into the content of the appended message
+pub type DocumentFormatter = Arc<dyn Fn(&str, &[Document]) -> String + Send + Sync>;
+
+/// Role of the message a [`RetrievalNode`] appends
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalRole {
+    #[default]
+    System,
+    /// A tool message with the id [`RETRIEVAL_TOOL_CALL_ID`]
+    Tool,
+}
+
+/// Options for [`RetrievalNode`]
+#[derive(Clone)]
+pub struct RetrievalOptions {
+    /// Number of documents to keep (default: 4)
+    pub k: usize,
+    /// Minimum score a document needs to be kept
+    pub score_threshold: Option<f64>,
+    /// Role of the appended message (default: system)
+    pub role: RetrievalRole,
+    /// Formats the kept documents (default: [`format_documents`])
+    pub formatter: DocumentFormatter,
+}
+
+impl Default for RetrievalOptions {
+    fn default() -> Self {
+        Self {
+            k: 4,
+            score_threshold: None,
+            role: RetrievalRole::default(),
+            formatter: Arc::new(format_documents),
+        }
+    }
+}
+
+impl RetrievalOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_k(mut self, k: usize) -> Self {
+        self.k = k;
+        self
+    }
+
+    pub fn with_score_threshold(mut self, score_threshold: f64) -> Self {
+        self.score_threshold = Some(score_threshold);
+        self
+    }
+
+    pub fn with_role(mut self, role: RetrievalRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn with_formatter(
+        mut self,
+        formatter: impl Fn(&str, &[Document]) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.formatter = Arc::new(formatter);
+        self
+    }
+}
+
+impl fmt::Debug for RetrievalOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetrievalOptions")
+            .field("k", &self.k)
+            .field("score_threshold", &self.score_threshold)
+            .field("role", &self.role)
+            .field("formatter", &"<fn>")
+            .finish()
+    }
+}
+
+/// Retrieval node - looks up documents for the last human message
+///
+/// The retriever's documents are filtered by `score_threshold`, cut to the first `k` in
+/// the retriever's order, formatted into one system (or tool) message and appended to
+/// `messages`. The message metadata keeps the query and each document's score and
+/// metadata (e.g. its `source`). Retriever failures are `GraphError::RetrieverError`:
+/// the node's retry policy applies to transient ones, while fatal ones (see
+/// `RetrieverError::is_retryable`) are never retried.
+pub struct RetrievalNode {
+    retriever: Arc<dyn Retriever>,
+    options: RetrievalOptions,
+}
+
+impl RetrievalNode {
+    pub fn new(retriever: Arc<dyn Retriever>, options: RetrievalOptions) -> Self {
+        Self { retriever, options }
+    }
+}
+
+#[async_trait]
+impl<S: State> Node<S> for RetrievalNode {
+    async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
+        let query = state_json
+            .get("messages")
+            .and_then(|messages| messages.as_array())
+            .and_then(|messages| {
+                messages
+                    .iter()
+                    .rev()
+                    .find(|message| message["message_type"] == "human")
+            })
+            .and_then(|message| message["content"].as_str())
+            .ok_or_else(|| {
+                GraphError::ExecutionError(
+                    "Retrieval node needs a human message to use as the query".to_string(),
+                )
+            })?;
+
+        let documents: Vec<Document> = self
+            .retriever
+            .get_relevant_documents(query)
+            .await?
+            .into_iter()
+            .filter(|doc| match self.options.score_threshold {
+                Some(threshold) => doc.score >= threshold,
+                None => true,
+            })
+            .take(self.options.k)
+            .collect();
+
+        let content = (self.options.formatter)(query, &documents);
+        let message = match self.options.role {
+            RetrievalRole::System => Message::new_system_message(content),
+            RetrievalRole::Tool => Message::new_tool_message(content, RETRIEVAL_TOOL_CALL_ID),
+        }
+        .with_metadata(json!({
+            "query": query,
+            "documents": documents
+                .iter()
+                .map(|doc| json!({ "score": doc.score, "metadata": doc.metadata }))
+                .collect::<Vec<_>>(),
+        }));
+
+        let mut update = HashMap::new();
+        update.insert("messages".to_string(), serde_json::to_value(vec![message])?);
+        Ok(update)
+    }
+}
+
+/// Helper function to create a retrieval node
+pub fn retrieval_node(retriever: Arc<dyn Retriever>, options: RetrievalOptions) -> RetrievalNode {
+    RetrievalNode::new(retriever, options)
+}
+
+/// Default [`DocumentFormatter`]: numbered documents with their source and score
+pub fn format_documents(query: &str, documents: &[Document]) -> String {
+    if documents.is_empty() {
+        return format!("No relevant documents found for query: {}", query);
+    }
+    let documents: Vec<String> = documents
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| {
+            format!(
+                "[Document {}]\nSource: {}\nScore: {:.3}\nContent: {}\n",
+                i + 1,
+                doc.metadata
+                    .get("source")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown"),
+                doc.score,
+                doc.page_content
+            )
+        })
+        .collect();
+    format!(
+        "Retrieved {} document(s) for query '{}':\n\n{}",
+        documents.len(),
+        query,
+        documents.join("\n---\n\n")
+    )
+}
+
+/// Retrievers that [`retrieval_node_plugin`] nodes can name in their config
+#[derive(Clone, Default)]
+pub struct RetrieverRegistry {
+    retrievers: HashMap<String, Arc<dyn Retriever>>,
+}
+
+impl RetrieverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `retriever` as `name`, replacing any retriever of that name
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        retriever: Arc<dyn Retriever>,
+    ) -> &mut Self {
+        self.retrievers.insert(name.into(), retriever);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Retriever>> {
+        self.retrievers.get(name).cloned()
+    }
+}
+
+/// Config of [`retrieval_node_plugin`] nodes
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrievalNodeConfig {
+    /// Name of the retriever in the plugin's [`RetrieverRegistry`]
+    pub retriever: String,
+    #[serde(default = "default_k")]
+    pub k: usize,
+    #[serde(default)]
+    pub score_threshold: Option<f64>,
+    #[serde(default)]
+    pub role: RetrievalRole,
+}
+
+fn default_k() -> usize {
+    RetrievalOptions::default().k
+}
+
+/// JSON Schema of [`RetrievalNodeConfig`]
+pub fn retrieval_node_config_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "retriever": { "type": "string", "description": "Name of a registered retriever." },
+            "k": { "type": "integer", "minimum": 1, "default": 4 },
+            "score_threshold": { "type": "number" },
+            "role": { "enum": ["system", "tool"], "default": "system" }
+        },
+        "required": ["retriever"],
+        "additionalProperties": false
+    })
+}
+
+/// Node plugin building [`RetrievalNode`]s over the retrievers in `retrievers`
+///
+/// Nodes use the default formatter; a config naming an unregistered retriever fails
+/// when the node is built.
+pub fn retrieval_node_plugin<S: State + 'static>(
+    retrievers: RetrieverRegistry,
+) -> impl NodePlugin<S> + 'static {
+    typed_node_plugin(
+        RETRIEVAL_NODE_PLUGIN_TYPE,
+        move |name, config: RetrievalNodeConfig| {
+            let retriever = retrievers.get(&config.retriever).ok_or_else(|| {
+                GraphError::CompilationError(format!(
+                    "Retrieval node '{}': retriever '{}' is not registered",
+                    name, config.retriever
+                ))
+            })?;
+            let mut options = RetrievalOptions::new()
+                .with_k(config.k)
+                .with_role(config.role);
+            options.score_threshold = config.score_threshold;
+            Ok(Arc::new(RetrievalNode::new(retriever, options)) as Arc<dyn Node<S>>)
+        },
+    )
+    .with_display_name("Retrieval")
+    .with_description(
+        "Retrieves documents for the last human message and appends them as a system or tool message.",
+    )
+    .with_config_schema(retrieval_node_config_schema())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::graph::{MessagesState, NodePluginRegistry, RetryPolicy, StateGraph, END, START};
+    use crate::retrievers::RetrieverError;
+    use crate::schemas::messages::MessageType;
+
+    /// Returns three documents, failing the first `failures` calls with `error`
+    struct StubRetriever {
+        failures: usize,
+        error: fn() -> RetrieverError,
+        calls: AtomicUsize,
+    }
+
+    impl StubRetriever {
+        fn new(failures: usize, error: fn() -> RetrieverError) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                error,
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Retriever for StubRetriever {
+        async fn get_relevant_documents(
+            &self,
+            query: &str,
+        ) -> Result<Vec<Document>, RetrieverError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)());
+            }
+            Ok([0.9, 0.7, 0.2]
+                .into_iter()
+                .enumerate()
+                .map(|(i, score)| {
+                    Document::new(format!("{} #{}", query, i))
+                        .with_metadata(HashMap::from([(
+                            "source".to_string(),
+                            json!(format!("doc{}.md", i)),
+                        )]))
+                        .with_score(score)
+                })
+                .collect())
+        }
+    }
+
+    fn question() -> MessagesState {
+        MessagesState::with_messages(vec![
+            Message::new_human_message("rust graphs"),
+            Message::new_ai_message("let me look"),
+        ])
+    }
+
+    fn appended(update: &StateUpdate) -> Message {
+        let mut messages: Vec<Message> =
+            serde_json::from_value(update["messages"].clone()).unwrap();
+        assert_eq!(messages.len(), 1);
+        messages.remove(0)
+    }
+
+    #[tokio::test]
+    async fn appends_top_documents_above_threshold() {
+        let node = retrieval_node(
+            StubRetriever::new(0, || RetrieverError::Unknown(String::new())),
+            RetrievalOptions::new()
+                .with_k(5)
+                .with_score_threshold(0.5)
+                .with_role(RetrievalRole::Tool)
+                .with_formatter(|query, docs| format!("{}: {}", query, docs.len())),
+        );
+        let message = appended(
+            &Node::<MessagesState>::invoke(&node, &question())
+                .await
+                .unwrap(),
+        );
+
+        assert!(matches!(message.message_type, MessageType::ToolMessage));
+        assert_eq!(message.id.as_deref(), Some(RETRIEVAL_TOOL_CALL_ID));
+        assert_eq!(message.content, "rust graphs: 2");
+        let metadata = message.metadata().unwrap();
+        assert_eq!(metadata["query"], "rust graphs");
+        assert_eq!(metadata["documents"][1]["score"], 0.7);
+        assert_eq!(metadata["documents"][1]["metadata"]["source"], "doc1.md");
+    }
+
+    #[tokio::test]
+    async fn default_formatter_lists_k_documents() {
+        let node = retrieval_node(
+            StubRetriever::new(0, || RetrieverError::Unknown(String::new())),
+            RetrievalOptions::new().with_k(1),
+        );
+        let message = appended(
+            &Node::<MessagesState>::invoke(&node, &question())
+                .await
+                .unwrap(),
+        );
+        assert!(matches!(message.message_type, MessageType::SystemMessage));
+        assert_eq!(
+            message.content,
+            "Retrieved 1 document(s) for query 'rust graphs':\n\n\
+             [Document 1]\nSource: doc0.md\nScore: 0.900\nContent: rust graphs #0\n"
+        );
+    }
+
+    async fn run_with_retries(retriever: Arc<StubRetriever>) -> Result<MessagesState, GraphError> {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph.add_node_with_retry(
+            "retrieve",
+            retrieval_node(retriever, RetrievalOptions::new()),
+            RetryPolicy::new(3),
+        )?;
+        graph.add_edge(START, "retrieve");
+        graph.add_edge("retrieve", END);
+        graph.compile()?.invoke(question()).await
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_and_fatal_ones_are_not() {
+        let transient = StubRetriever::new(2, || RetrieverError::TavilyError("503".into()));
+        let state = run_with_retries(transient.clone()).await.unwrap();
+        assert_eq!(transient.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(state.messages.len(), 3);
+
+        let fatal = StubRetriever::new(1, || RetrieverError::ConfigurationError("no index".into()));
+        let err = run_with_retries(fatal.clone()).await.unwrap_err();
+        assert_eq!(fatal.calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            err,
+            GraphError::RetrieverError(RetrieverError::ConfigurationError(_))
+        ));
+    }
+
+    #[test]
+    fn plugin_resolves_retrievers_by_name() {
+        let mut retrievers = RetrieverRegistry::new();
+        retrievers.register(
+            "docs",
+            StubRetriever::new(0, || RetrieverError::Unknown(String::new())),
+        );
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        registry
+            .register_plugin(retrieval_node_plugin(retrievers))
+            .unwrap();
+
+        assert!(registry
+            .create_node(
+                "retrieve",
+                RETRIEVAL_NODE_PLUGIN_TYPE,
+                &json!({"retriever": "docs", "k": 2})
+            )
+            .is_ok());
+        assert!(matches!(
+            registry.create_node(
+                "retrieve",
+                RETRIEVAL_NODE_PLUGIN_TYPE,
+                &json!({"retriever": "missing"})
+            ),
+            Err(GraphError::CompilationError(_))
+        ));
+    }
+}
@@ -1,14 +1,13 @@
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use super::Node;
+use super::{Goto, Node, NodeCommand};
 use crate::graph::{
     compiled::CompiledGraph,
     error::GraphError,
     persistence::{config::RunnableConfig, store::StoreBox},
-    state::State,
+    state::{full_state_update, State},
     StateUpdate,
 };
 
@@ -55,21 +54,8 @@ impl<S: State + 'static> SubgraphNode<S> {
 impl<S: State + 'static> Node<S> for SubgraphNode<S> {
     async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
         // Execute the subgraph with the current state
-        let final_state = self.subgraph.invoke(state.clone()).await?;
-
-        // Convert final state to state update
         // For shared state, we merge the final state back
-        let state_json =
-            serde_json::to_value(final_state).map_err(GraphError::SerializationError)?;
-
-        let mut update = HashMap::new();
-        if let serde_json::Value::Object(map) = state_json {
-            for (key, value) in map {
-                update.insert(key, value);
-            }
-        }
-
-        Ok(update)
+        parent_command(self.subgraph.invoke(state.clone()).await)
     }
 
     async fn invoke_with_context(
@@ -78,28 +64,17 @@ impl<S: State + 'static> Node<S> for SubgraphNode<S> {
         config: Option<&RunnableConfig>,
         _store: Option<StoreBox>,
     ) -> Result<StateUpdate, GraphError> {
-        // Execute the subgraph with config and store
-        // Note: subgraph will inherit checkpointer from parent if provided
-        let final_state = if let Some(config) = config {
+        // Execute the subgraph with a namespaced config so its checkpoints are distinguishable
+        // and interrupts raised inside it bubble up to the parent's caller.
+        // Note: subgraph inherits the checkpointer from the parent at compile time
+        let result = if let Some(config) = config {
             self.subgraph
-                .invoke_with_config(Some(state.clone()), config)
-                .await?
+                .invoke_as_subgraph(state.clone(), &config.for_subgraph(&self.name))
+                .await
         } else {
-            self.subgraph.invoke(state.clone()).await?
+            self.subgraph.invoke(state.clone()).await
         };
-
-        // Convert final state to state update
-        let state_json =
-            serde_json::to_value(final_state).map_err(GraphError::SerializationError)?;
-
-        let mut update = HashMap::new();
-        if let serde_json::Value::Object(map) = state_json {
-            for (key, value) in map {
-                update.insert(key, value);
-            }
-        }
-
-        Ok(update)
+        parent_command(result)
     }
 
     fn get_subgraph(&self) -> Option<Arc<CompiledGraph<S>>> {
@@ -107,6 +82,19 @@ impl<S: State + 'static> Node<S> for SubgraphNode<S> {
     }
 }
 
+/// Turn the result of a subgraph run into the subgraph node's update
+///
+/// A `Goto::Parent` inside the subgraph becomes a goto of the subgraph node.
+fn parent_command<S: State>(result: Result<S, GraphError>) -> Result<StateUpdate, GraphError> {
+    match result {
+        Ok(final_state) => full_state_update(&final_state),
+        Err(GraphError::ParentGoto { target, update, .. }) => Ok(NodeCommand::new(update)
+            .with_goto(Goto::node(target))
+            .into_update()),
+        Err(e) => Err(e),
+    }
+}
+
 /// Subgraph node with state transformation
 ///
 /// This allows a subgraph with a different state type to be used
@@ -150,6 +138,25 @@ impl<ParentState: State + 'static, SubState: State + 'static>
     pub fn subgraph(&self) -> &CompiledGraph<SubState> {
         &self.subgraph
     }
+
+    /// Transform the result of a subgraph run into the parent state update
+    fn transform_result(
+        &self,
+        result: Result<SubState, GraphError>,
+    ) -> Result<StateUpdate, GraphError> {
+        match result {
+            Ok(final_sub_state) => (self.transform_out)(&final_sub_state),
+            Err(GraphError::ParentGoto { target, update, .. }) => {
+                let final_sub_state: SubState = serde_json::from_value(serde_json::Value::Object(
+                    update.into_iter().collect(),
+                ))?;
+                Ok(NodeCommand::new((self.transform_out)(&final_sub_state)?)
+                    .with_goto(Goto::node(target))
+                    .into_update())
+            }
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[async_trait]
@@ -160,11 +167,8 @@ impl<ParentState: State + 'static, SubState: State + 'static> Node<ParentState>
         // Transform parent state to subgraph state
         let sub_state = (self.transform_in)(state)?;
 
-        // Execute the subgraph
-        let final_sub_state = self.subgraph.invoke(sub_state).await?;
-
-        // Transform subgraph state back to parent state update
-        (self.transform_out)(&final_sub_state)
+        // Execute the subgraph and transform its state back to a parent state update
+        self.transform_result(self.subgraph.invoke(sub_state).await)
     }
 
     async fn invoke_with_context(
@@ -176,17 +180,17 @@ impl<ParentState: State + 'static, SubState: State + 'static> Node<ParentState>
         // Transform parent state to subgraph state
         let sub_state = (self.transform_in)(state)?;
 
-        // Execute the subgraph with config
-        let final_sub_state = if let Some(config) = config {
+        // Execute the subgraph with a namespaced config
+        let result = if let Some(config) = config {
             self.subgraph
-                .invoke_with_config(Some(sub_state), config)
-                .await?
+                .invoke_as_subgraph(sub_state, &config.for_subgraph(&self.name))
+                .await
         } else {
-            self.subgraph.invoke(sub_state).await?
+            self.subgraph.invoke(sub_state).await
         };
 
         // Transform subgraph state back to parent state update
-        (self.transform_out)(&final_sub_state)
+        self.transform_result(result)
     }
 
     // Note: get_subgraph is not implemented for SubgraphNodeWithTransform
@@ -57,4 +57,164 @@ mod subgraph_tests {
         let result = compiled.invoke(state).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_subgraph_interrupt_bubbles_and_resumes_with_namespaced_checkpoints() {
+        use crate::graph::{
+            interrupt, messages_state_update, Command, InMemorySaver, RunnableConfig,
+            StateOrCommand,
+        };
+        use crate::schemas::messages::Message;
+        use std::sync::Arc;
+
+        let mut subgraph = StateGraph::<MessagesState>::new();
+        subgraph
+            .add_node(
+                "approve",
+                function_node("approve", |_state: &MessagesState| async move {
+                    let approved = interrupt("approve draft?").await?;
+                    Ok(messages_state_update(vec![Message::new_ai_message(
+                        format!("approved:{}", approved),
+                    )]))
+                }),
+            )
+            .unwrap();
+        subgraph.add_edge(START, "approve");
+        subgraph.add_edge("approve", END);
+
+        let mut parent = StateGraph::<MessagesState>::new();
+        parent
+            .add_subgraph("review", subgraph.compile().unwrap())
+            .unwrap();
+        parent.add_edge(START, "review");
+        parent.add_edge("review", END);
+        let compiled = parent
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+
+        let config = RunnableConfig::with_thread_id("subgraph-thread");
+        let first = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert!(first.has_interrupt());
+
+        let history = compiled.get_state_history(&config).await.unwrap();
+        assert!(!history.is_empty());
+        assert!(history
+            .iter()
+            .all(|snapshot| snapshot.config.checkpoint_ns.is_none()));
+        let sub_history = compiled
+            .get_state_history(&config.for_subgraph("review"))
+            .await
+            .unwrap();
+        assert!(!sub_history.is_empty());
+        assert!(sub_history
+            .iter()
+            .all(|snapshot| snapshot.config.checkpoint_ns.as_deref() == Some("review")));
+
+        let resumed = compiled
+            .invoke_with_config_interrupt(StateOrCommand::Command(Command::resume(true)), &config)
+            .await
+            .unwrap();
+        assert!(!resumed.has_interrupt());
+        assert_eq!(
+            resumed.state.messages.last().unwrap().content,
+            "approved:true"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subgraph_resume_continues_at_interrupted_inner_node() {
+        use crate::graph::{
+            interrupt, messages_state_update, Command, InMemorySaver, RunnableConfig,
+            StateOrCommand,
+        };
+        use crate::schemas::messages::Message;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let drafts = Arc::new(AtomicUsize::new(0));
+        let counter = drafts.clone();
+        let mut subgraph = StateGraph::<MessagesState>::new();
+        subgraph
+            .add_node(
+                "draft",
+                function_node("draft", move |_state: &MessagesState| {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Ok(messages_state_update(vec![Message::new_ai_message(
+                            "draft",
+                        )]))
+                    }
+                }),
+            )
+            .unwrap();
+        subgraph
+            .add_node(
+                "approve",
+                function_node("approve", |_state: &MessagesState| async move {
+                    let approved = interrupt("approve draft?").await?;
+                    Ok(messages_state_update(vec![Message::new_ai_message(
+                        format!("approved:{}", approved),
+                    )]))
+                }),
+            )
+            .unwrap();
+        subgraph
+            .add_node(
+                "publish",
+                function_node("publish", |_state: &MessagesState| async move {
+                    Ok(messages_state_update(vec![Message::new_ai_message(
+                        "published",
+                    )]))
+                }),
+            )
+            .unwrap();
+        subgraph.add_edge(START, "draft");
+        subgraph.add_edge("draft", "approve");
+        subgraph.add_edge("approve", "publish");
+        subgraph.add_edge("publish", END);
+
+        let mut parent = StateGraph::<MessagesState>::new();
+        parent
+            .add_subgraph("review", subgraph.compile().unwrap())
+            .unwrap();
+        parent.add_edge(START, "review");
+        parent.add_edge("review", END);
+        let compiled = parent
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+
+        let config = RunnableConfig::with_thread_id("subgraph-inner-resume");
+        let first = compiled
+            .invoke_with_config_interrupt(StateOrCommand::State(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        assert!(first.has_interrupt());
+
+        let parent_state = compiled.get_state(&config).await.unwrap();
+        assert_eq!(parent_state.config.checkpoint_ns, None);
+        assert_eq!(parent_state.next, vec!["review".to_string()]);
+        let inner_state = compiled
+            .get_state(&config.for_subgraph("review"))
+            .await
+            .unwrap();
+        assert_eq!(inner_state.next, vec!["approve".to_string()]);
+
+        let resumed = compiled
+            .invoke_with_config_interrupt(StateOrCommand::Command(Command::resume(true)), &config)
+            .await
+            .unwrap();
+        assert!(!resumed.has_interrupt());
+        let contents: Vec<&str> = resumed
+            .state
+            .messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["draft", "approved:true", "published"]);
+        assert_eq!(drafts.load(Ordering::SeqCst), 1);
+    }
 }
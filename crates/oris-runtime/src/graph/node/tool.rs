@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+
+use super::Node;
+use crate::{
+    error::ToolError,
+    graph::{error::GraphError, state::State, StateUpdate},
+    schemas::{
+        messages::{Message, MessageType},
+        FunctionCallResponse,
+    },
+    tools::Tool,
+};
+
+/// Options for [`ToolNode`]
+#[derive(Clone, Debug)]
+pub struct ToolNodeOptions {
+    /// Report unknown tools, malformed arguments and tool failures to the model as
+    /// error tool messages instead of failing the run (default: true)
+    pub handle_tool_errors: bool,
+}
+
+impl Default for ToolNodeOptions {
+    fn default() -> Self {
+        Self {
+            handle_tool_errors: true,
+        }
+    }
+}
+
+/// Tool node - executes the tool calls of the last AI message
+///
+/// Calls run concurrently, and their results are appended to `messages` as tool
+/// messages in call order. If the last message is not an AI message with tool
+/// calls, the node returns an empty update.
+pub struct ToolNode {
+    tools: HashMap<String, Arc<dyn Tool>>,
+    options: ToolNodeOptions,
+}
+
+impl ToolNode {
+    /// Create a tool node that can dispatch to `tools`
+    pub fn new(tools: Vec<Arc<dyn Tool>>) -> Self {
+        Self {
+            tools: tools.into_iter().map(|tool| (tool.name(), tool)).collect(),
+            options: ToolNodeOptions::default(),
+        }
+    }
+
+    /// Set the node's options
+    pub fn with_options(mut self, options: ToolNodeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Run one tool call, returning the content of its tool message
+    async fn call(&self, call: &FunctionCallResponse) -> Result<String, ToolError> {
+        let tool = self
+            .tools
+            .get(&call.function.name)
+            .ok_or_else(|| ToolError::ToolNotFound(call.function.name.clone()))?;
+        serde_json::from_str::<serde_json::Value>(&call.function.arguments).map_err(|e| {
+            ToolError::ParsingError(format!(
+                "arguments for tool '{}' are not valid JSON: {}",
+                call.function.name, e
+            ))
+        })?;
+        tool.call(&call.function.arguments).await
+    }
+}
+
+#[async_trait]
+impl<S: State> Node<S> for ToolNode {
+    async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        let state_json = serde_json::to_value(state).map_err(GraphError::SerializationError)?;
+        let last: Option<Message> = match state_json
+            .get("messages")
+            .and_then(|messages| messages.as_array())
+            .and_then(|messages| messages.last())
+        {
+            Some(message) => Some(serde_json::from_value(message.clone())?),
+            None => None,
+        };
+        let Some(tool_calls) = last
+            .filter(|message| matches!(message.message_type, MessageType::AIMessage))
+            .and_then(|message| message.tool_calls)
+        else {
+            return Ok(HashMap::new());
+        };
+
+        let calls: Vec<FunctionCallResponse> = serde_json::from_value(tool_calls).map_err(|e| {
+            GraphError::ExecutionError(format!("Invalid tool calls in AI message: {}", e))
+        })?;
+        let results = join_all(calls.iter().map(|call| self.call(call))).await;
+
+        let mut messages = Vec::with_capacity(calls.len());
+        for (call, result) in calls.iter().zip(results) {
+            let content = match result {
+                Ok(content) => content,
+                Err(e) if self.options.handle_tool_errors => format!("Error: {}", e),
+                Err(e) => {
+                    return Err(GraphError::ExecutionError(format!(
+                        "Tool call '{}' to '{}' failed: {}",
+                        call.id, call.function.name, e
+                    )))
+                }
+            };
+            messages.push(Message::new_tool_message(content, call.id.clone()));
+        }
+
+        let mut update = HashMap::new();
+        update.insert("messages".to_string(), serde_json::to_value(messages)?);
+        Ok(update)
+    }
+}
+
+/// Helper function to create a tool node with the default options
+pub fn tool_node(tools: Vec<Arc<dyn Tool>>) -> ToolNode {
+    ToolNode::new(tools)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::graph::MessagesState;
+
+    struct EchoTool {
+        name: &'static str,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn description(&self) -> String {
+            "Echoes its input".to_string()
+        }
+
+        async fn run(&self, input: Value) -> Result<String, ToolError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(format!(
+                "{}:{}",
+                self.name,
+                input.as_str().unwrap_or_default()
+            ))
+        }
+    }
+
+    fn tools() -> Vec<Arc<dyn Tool>> {
+        vec![
+            Arc::new(EchoTool {
+                name: "slow",
+                delay: Duration::from_millis(50),
+            }),
+            Arc::new(EchoTool {
+                name: "fast",
+                delay: Duration::ZERO,
+            }),
+        ]
+    }
+
+    fn calling(calls: Value) -> MessagesState {
+        MessagesState::with_messages(vec![
+            Message::new_human_message("go"),
+            Message::new_ai_message("").with_tool_calls(calls),
+        ])
+    }
+
+    fn call(id: &str, name: &str, arguments: &str) -> Value {
+        json!({
+            "id": id,
+            "type": "function",
+            "function": {"name": name, "arguments": arguments},
+        })
+    }
+
+    fn tool_messages(update: &StateUpdate) -> Vec<(String, String)> {
+        let messages: Vec<Message> = serde_json::from_value(update["messages"].clone()).unwrap();
+        messages
+            .into_iter()
+            .map(|m| {
+                assert!(matches!(m.message_type, MessageType::ToolMessage));
+                (m.id.unwrap_or_default(), m.content)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn runs_tool_calls_concurrently_in_call_order() {
+        let state = calling(json!([
+            call("c1", "slow", r#"{"input": "a"}"#),
+            call("c2", "fast", r#"{"input": "b"}"#),
+        ]));
+        let update = Node::<MessagesState>::invoke(&tool_node(tools()), &state)
+            .await
+            .unwrap();
+        assert_eq!(
+            tool_messages(&update),
+            vec![
+                ("c1".to_string(), "slow:a".to_string()),
+                ("c2".to_string(), "fast:b".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_errors_become_tool_messages_unless_disabled() {
+        let state = calling(json!([
+            call("c1", "fast", r#"{"input": "#),
+            call("c2", "missing", "{}"),
+        ]));
+        let update = Node::<MessagesState>::invoke(&tool_node(tools()), &state)
+            .await
+            .unwrap();
+        let messages = tool_messages(&update);
+        assert!(messages[0]
+            .1
+            .starts_with("Error: Input parsing failed: arguments for tool 'fast'"));
+        assert_eq!(
+            messages[1],
+            (
+                "c2".to_string(),
+                "Error: Tool not found: missing".to_string()
+            )
+        );
+
+        let strict = tool_node(tools()).with_options(ToolNodeOptions {
+            handle_tool_errors: false,
+        });
+        assert!(Node::<MessagesState>::invoke(&strict, &state)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn ignores_messages_without_tool_calls() {
+        let state = MessagesState::with_messages(vec![Message::new_ai_message("done")]);
+        let update = Node::<MessagesState>::invoke(&tool_node(tools()), &state)
+            .await
+            .unwrap();
+        assert!(update.is_empty());
+    }
+}
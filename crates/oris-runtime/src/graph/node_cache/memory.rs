@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::graph::{persistence::error::PersistenceError, state::StateUpdate};
+
+use super::NodeCache;
+
+struct Entry {
+    update: StateUpdate,
+    expires_at: Option<Instant>,
+}
+
+/// In-memory node cache
+///
+/// The default cache of compiled graphs; entries are lost when the process exits.
+#[derive(Default)]
+pub struct InMemoryNodeCache {
+    entries: RwLock<HashMap<String, HashMap<String, Entry>>>,
+}
+
+impl InMemoryNodeCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NodeCache for InMemoryNodeCache {
+    async fn get(&self, node: &str, key: &str) -> Result<Option<StateUpdate>, PersistenceError> {
+        let mut entries = self.entries.write().await;
+        let Some(node_entries) = entries.get_mut(node) else {
+            return Ok(None);
+        };
+        match node_entries.get(key) {
+            Some(entry) if entry.expires_at.is_some_and(|at| at <= Instant::now()) => {
+                node_entries.remove(key);
+                Ok(None)
+            }
+            Some(entry) => Ok(Some(entry.update.clone())),
+            None => Ok(None),
+        }
+    }
+
+    async fn put(
+        &self,
+        node: &str,
+        key: &str,
+        update: &StateUpdate,
+        ttl: Option<Duration>,
+    ) -> Result<(), PersistenceError> {
+        self.entries
+            .write()
+            .await
+            .entry(node.to_string())
+            .or_default()
+            .insert(
+                key.to_string(),
+                Entry {
+                    update: update.clone(),
+                    expires_at: ttl.map(|ttl| Instant::now() + ttl),
+                },
+            );
+        Ok(())
+    }
+
+    async fn invalidate(&self, node: &str) -> Result<(), PersistenceError> {
+        self.entries.write().await.remove(node);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_node_cache_expires_and_invalidates() {
+        let cache = InMemoryNodeCache::new();
+        let update = StateUpdate::from([("count".to_string(), serde_json::json!(1))]);
+
+        cache.put("embed", "a", &update, None).await.unwrap();
+        cache
+            .put("embed", "b", &update, Some(Duration::ZERO))
+            .await
+            .unwrap();
+        assert_eq!(cache.get("embed", "a").await.unwrap(), Some(update.clone()));
+        assert_eq!(cache.get("embed", "b").await.unwrap(), None);
+        assert_eq!(cache.get("other", "a").await.unwrap(), None);
+
+        cache.invalidate("embed").await.unwrap();
+        assert_eq!(cache.get("embed", "a").await.unwrap(), None);
+    }
+}
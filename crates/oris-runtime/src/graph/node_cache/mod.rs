@@ -0,0 +1,42 @@
+mod memory;
+mod policy;
+
+#[cfg(feature = "sqlite-persistence")]
+mod sqlite;
+
+pub use memory::*;
+pub use policy::*;
+
+#[cfg(feature = "sqlite-persistence")]
+pub use sqlite::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::{persistence::error::PersistenceError, state::StateUpdate};
+
+/// Storage for node results cached under a [`NodeCachePolicy`]
+///
+/// Entries are partitioned by node name so a node's cache can be invalidated as a whole.
+#[async_trait]
+pub trait NodeCache: Send + Sync {
+    /// Get the cached update for `key`, unless it is missing or expired
+    async fn get(&self, node: &str, key: &str) -> Result<Option<StateUpdate>, PersistenceError>;
+
+    /// Cache `update` under `key`, expiring after `ttl` if set
+    async fn put(
+        &self,
+        node: &str,
+        key: &str,
+        update: &StateUpdate,
+        ttl: Option<Duration>,
+    ) -> Result<(), PersistenceError>;
+
+    /// Remove every cached update of `node`
+    async fn invalidate(&self, node: &str) -> Result<(), PersistenceError>;
+}
+
+/// Type alias for a shared node cache
+pub type NodeCacheBox = Arc<dyn NodeCache>;
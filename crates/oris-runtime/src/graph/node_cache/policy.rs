@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::graph::{error::GraphError, state::State};
+
+/// Caching policy for a node whose update depends only on its input state
+///
+/// On a cache hit the cached update is applied and the node is not run.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use oris_runtime::graph::{MessagesState, NodeCachePolicy};
+///
+/// let policy = NodeCachePolicy::<MessagesState>::new()
+///     .with_key_fn(|state| state.messages.len().to_string())
+///     .with_ttl(Duration::from_secs(3600));
+/// ```
+pub struct NodeCachePolicy<S> {
+    /// Derives the cache key from the state; defaults to a hash of the serialized state
+    pub key_fn: Option<fn(&S) -> String>,
+    /// How long a cached update stays valid; entries never expire when unset
+    pub ttl: Option<Duration>,
+}
+
+impl<S: State> NodeCachePolicy<S> {
+    /// Cache by state hash, without expiry
+    pub fn new() -> Self {
+        Self {
+            key_fn: None,
+            ttl: None,
+        }
+    }
+
+    /// Set the function deriving the cache key from the state
+    pub fn with_key_fn(mut self, key_fn: fn(&S) -> String) -> Self {
+        self.key_fn = Some(key_fn);
+        self
+    }
+
+    /// Set how long cached updates stay valid
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Cache key of `state` for `node`
+    pub fn key(&self, node: &str, state: &S) -> Result<String, GraphError> {
+        if let Some(key_fn) = self.key_fn {
+            return Ok(key_fn(state));
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(node.as_bytes());
+        hasher.update(b"|");
+        hasher.update(serde_json::to_vec(state)?);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+}
+
+impl<S: State> Default for NodeCachePolicy<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for NodeCachePolicy<S> {
+    fn clone(&self) -> Self {
+        Self {
+            key_fn: self.key_fn,
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for NodeCachePolicy<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeCachePolicy")
+            .field("key_fn", &self.key_fn.map(|_| "<fn>"))
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
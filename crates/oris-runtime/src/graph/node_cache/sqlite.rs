@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use tokio::sync::Mutex;
+
+use crate::graph::{persistence::error::PersistenceError, state::StateUpdate};
+
+use super::NodeCache;
+
+/// SQLite-backed node cache
+///
+/// Cached updates survive restarts and can be shared by processes using the same file.
+pub struct SqliteNodeCache {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteNodeCache {
+    /// Open (or create) a node cache in a database file
+    pub fn new(path: &str) -> Result<Self, PersistenceError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Create a node cache in an in-memory database
+    pub fn new_in_memory() -> Result<Self, PersistenceError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, PersistenceError> {
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS node_cache (
+                node TEXT NOT NULL,
+                cache_key TEXT NOT NULL,
+                update_json TEXT NOT NULL,
+                expires_at TEXT,
+                PRIMARY KEY (node, cache_key)
+            )",
+            [],
+        )?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+}
+
+#[async_trait]
+impl NodeCache for SqliteNodeCache {
+    async fn get(&self, node: &str, key: &str) -> Result<Option<StateUpdate>, PersistenceError> {
+        let conn = self.connection.lock().await;
+        let row: Option<(String, Option<String>)> = conn
+            .query_row(
+                "SELECT update_json, expires_at FROM node_cache WHERE node = ?1 AND cache_key = ?2",
+                params![node, key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((update_json, expires_at)) = row else {
+            return Ok(None);
+        };
+        if let Some(expires_at) = expires_at {
+            let expires_at = DateTime::parse_from_rfc3339(&expires_at)
+                .map_err(|e| PersistenceError::DatabaseError(e.to_string()))?;
+            if expires_at <= Utc::now() {
+                conn.execute(
+                    "DELETE FROM node_cache WHERE node = ?1 AND cache_key = ?2",
+                    params![node, key],
+                )?;
+                return Ok(None);
+            }
+        }
+        Ok(Some(serde_json::from_str(&update_json)?))
+    }
+
+    async fn put(
+        &self,
+        node: &str,
+        key: &str,
+        update: &StateUpdate,
+        ttl: Option<Duration>,
+    ) -> Result<(), PersistenceError> {
+        let update_json = serde_json::to_string(update)?;
+        let expires_at = ttl
+            .map(|ttl| {
+                chrono::Duration::from_std(ttl)
+                    .map(|ttl| (Utc::now() + ttl).to_rfc3339())
+                    .map_err(|e| PersistenceError::InvalidConfig(e.to_string()))
+            })
+            .transpose()?;
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO node_cache (node, cache_key, update_json, expires_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![node, key, update_json, expires_at],
+        )?;
+        Ok(())
+    }
+
+    async fn invalidate(&self, node: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute("DELETE FROM node_cache WHERE node = ?1", params![node])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_node_cache_survives_reopen() {
+        let db_path =
+            std::env::temp_dir().join(format!("oris-node-cache-{}.db", std::process::id()));
+        let db_path = db_path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&db_path);
+        let update = StateUpdate::from([("count".to_string(), serde_json::json!(1))]);
+
+        let cache = SqliteNodeCache::new(&db_path).unwrap();
+        cache.put("embed", "a", &update, None).await.unwrap();
+        cache
+            .put("embed", "b", &update, Some(Duration::ZERO))
+            .await
+            .unwrap();
+        drop(cache);
+
+        let cache = SqliteNodeCache::new(&db_path).unwrap();
+        assert_eq!(cache.get("embed", "a").await.unwrap(), Some(update));
+        assert_eq!(cache.get("embed", "b").await.unwrap(), None);
+        cache.invalidate("embed").await.unwrap();
+        assert_eq!(cache.get("embed", "a").await.unwrap(), None);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
@@ -0,0 +1,275 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::language_models::llm::LLM;
+
+use super::{
+    compiled::CompiledGraph,
+    error::GraphError,
+    node::Node,
+    persistence::{config::RunnableConfig, store::StoreBox},
+    retry::{NodeAttempts, RetryPolicy},
+    state::{State, StateUpdate},
+};
+
+/// What to do when a node exceeds its timeout
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum OnTimeout {
+    /// Fail the run with `GraphError::NodeTimeout`
+    #[default]
+    Fail,
+    /// Retry the node according to its retry policy (fails if none is set or attempts run out)
+    Retry,
+    /// Skip the node's update and continue execution at the named fallback node
+    ///
+    /// The super-step executor (`invoke_with_config_and_mode`) cannot reroute, so
+    /// there the timeout fails the run as with `Fail`.
+    Fallback(String),
+}
+
+/// Per-node execution options: retry policy and timeout
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use oris_runtime::graph::{NodeOptions, OnTimeout};
+///
+/// let options = NodeOptions::new()
+///     .with_timeout(Duration::from_secs(30))
+///     .with_on_timeout(OnTimeout::Fallback("cached_answer".to_string()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NodeOptions {
+    /// Retry policy applied to failed attempts
+    pub retry: Option<RetryPolicy>,
+    /// Maximum duration of a single attempt
+    pub timeout: Option<Duration>,
+    /// Behavior when an attempt exceeds `timeout`
+    pub on_timeout: OnTimeout,
+}
+
+impl NodeOptions {
+    /// Create options with no retry policy and no timeout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the retry policy
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Set the per-attempt timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the behavior on timeout
+    pub fn with_on_timeout(mut self, on_timeout: OnTimeout) -> Self {
+        self.on_timeout = on_timeout;
+        self
+    }
+
+    /// The fallback node to route to if `error` is a timeout and a fallback is configured
+    pub fn timeout_fallback(&self, error: &GraphError) -> Option<&str> {
+        match (&self.on_timeout, error) {
+            (OnTimeout::Fallback(target), GraphError::NodeTimeout { .. }) => Some(target),
+            _ => None,
+        }
+    }
+
+    fn should_retry(&self, error: &GraphError, attempt: u32) -> bool {
+        let Some(policy) = &self.retry else {
+            return false;
+        };
+        match error {
+            GraphError::NodeTimeout { .. } => {
+                self.on_timeout == OnTimeout::Retry && attempt < policy.max_attempts
+            }
+            _ => policy.should_retry(error, attempt),
+        }
+    }
+}
+
+impl From<RetryPolicy> for NodeOptions {
+    fn from(policy: RetryPolicy) -> Self {
+        Self::new().with_retry(policy)
+    }
+}
+
+/// Invoke a node, applying its timeout and retry policy
+///
+/// Returns the final result together with the attempt statistics.
+pub(crate) async fn invoke_with_options<S: State>(
+    name: &str,
+    node: &Arc<dyn Node<S>>,
+    options: Option<&NodeOptions>,
+    state: &S,
+    config: Option<&RunnableConfig>,
+    store: Option<StoreBox>,
+) -> (Result<StateUpdate, GraphError>, NodeAttempts) {
+    let mut stats = NodeAttempts::default();
+    loop {
+        stats.attempts += 1;
+        let started = Instant::now();
+        let future = node.invoke_with_context(state, config, store.clone());
+        let result = match options.and_then(|o| o.timeout) {
+            Some(timeout) => tokio::time::timeout(timeout, future)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(GraphError::NodeTimeout {
+                        node: name.to_string(),
+                        elapsed: started.elapsed(),
+                    })
+                }),
+            None => future.await,
+        };
+        let error = match result {
+            Ok(update) => return (Ok(update), stats),
+            Err(error) => error,
+        };
+        if !matches!(
+            error,
+            GraphError::InterruptError(_) | GraphError::ActionRequested(_)
+        ) {
+            stats.last_error = Some(error.to_string());
+        }
+        match options {
+            Some(options) if options.should_retry(&error, stats.attempts) => {
+                let policy = options.retry.as_ref().expect("retry implies a policy");
+                log::warn!(
+                    "Node '{}' attempt {}/{} failed: {}; retrying",
+                    name,
+                    stats.attempts,
+                    policy.max_attempts,
+                    error
+                );
+                #[cfg(feature = "otel")]
+                super::otel::record_retry(name, stats.attempts, &error);
+                let delay = policy.backoff.delay(stats.attempts);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            _ => return (Err(error), stats),
+        }
+    }
+}
+
+/// Node wrapper that applies node options, for executors that invoke nodes directly
+pub(crate) struct OptionsNode<S: State> {
+    name: String,
+    inner: Arc<dyn Node<S>>,
+    options: NodeOptions,
+}
+
+impl<S: State> OptionsNode<S> {
+    pub(crate) fn new(name: String, inner: Arc<dyn Node<S>>, options: NodeOptions) -> Self {
+        Self {
+            name,
+            inner,
+            options,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: State> Node<S> for OptionsNode<S> {
+    async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        self.invoke_with_context(state, None, None).await
+    }
+
+    async fn invoke_with_context(
+        &self,
+        state: &S,
+        config: Option<&RunnableConfig>,
+        store: Option<StoreBox>,
+    ) -> Result<StateUpdate, GraphError> {
+        invoke_with_options(
+            &self.name,
+            &self.inner,
+            Some(&self.options),
+            state,
+            config,
+            store,
+        )
+        .await
+        .0
+    }
+
+    fn get_llm(&self) -> Option<Arc<dyn LLM>> {
+        self.inner.get_llm()
+    }
+
+    fn get_subgraph(&self) -> Option<Arc<CompiledGraph<S>>> {
+        self.inner.get_subgraph()
+    }
+
+    fn goto_destinations(&self) -> Option<Vec<String>> {
+        self.inner.goto_destinations()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{function_node, MessagesState};
+
+    fn sleepy_node() -> Arc<dyn Node<MessagesState>> {
+        Arc::new(function_node("sleepy", |_s: &MessagesState| async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(std::collections::HashMap::new())
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_timeout_fails_with_node_timeout() {
+        let options = NodeOptions::new().with_timeout(Duration::from_millis(10));
+        let (result, stats) = invoke_with_options(
+            "sleepy",
+            &sleepy_node(),
+            Some(&options),
+            &MessagesState::new(),
+            None,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(GraphError::NodeTimeout { node, .. }) if node == "sleepy"));
+        assert_eq!(stats.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_retried_only_when_selected() {
+        let options = NodeOptions::new()
+            .with_retry(RetryPolicy::new(3))
+            .with_timeout(Duration::from_millis(10));
+        let (_, stats) = invoke_with_options(
+            "sleepy",
+            &sleepy_node(),
+            Some(&options),
+            &MessagesState::new(),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(stats.attempts, 1);
+
+        let options = options.with_on_timeout(OnTimeout::Retry);
+        let (result, stats) = invoke_with_options(
+            "sleepy",
+            &sleepy_node(),
+            Some(&options),
+            &MessagesState::new(),
+            None,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(GraphError::NodeTimeout { .. })));
+        assert_eq!(stats.attempts, 3);
+    }
+}
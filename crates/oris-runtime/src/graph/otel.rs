@@ -0,0 +1,199 @@
+//! OpenTelemetry instrumentation for graph runs (feature `otel`).
+//!
+//! `CompiledGraph::invoke_with_config` runs inside a `graph.run` span (attribute
+//! `thread_id`) with a `graph.node` child span per node run, carrying the node's
+//! `duration_ms`, `update_size` and the `checkpoint_id` written after it. Interrupts
+//! and retries are recorded as span events, and checkpoints store the trace and span
+//! IDs under [`TRACE_CONTEXT_KEY`] in their metadata.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::{
+    global,
+    trace::{SpanId, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use serde_json::Value;
+
+pub use crate::kernel::otel::{trace_context, TRACE_CONTEXT_KEY};
+
+use super::{
+    callbacks::{GraphCallbacks, RunContext},
+    error::GraphError,
+    persistence::config::RunnableConfig,
+    state::StateUpdate,
+};
+
+const TRACER_NAME: &str = "oris-runtime";
+
+/// Start the root span of a graph run, under the current context if it has a span
+pub(crate) fn start_run(config: &RunnableConfig) -> Context {
+    let tracer = global::tracer(TRACER_NAME);
+    let mut attributes = Vec::new();
+    if let Some(thread_id) = config.get_thread_id() {
+        attributes.push(KeyValue::new("thread_id", thread_id));
+    }
+    let parent = Context::current();
+    let span = tracer
+        .span_builder("graph.run")
+        .with_attributes(attributes)
+        .start_with_context(&tracer, &parent);
+    parent.with_span(span)
+}
+
+/// End the root span of a graph run, ending any node span still waiting for a checkpoint
+pub(crate) fn finish_run<S>(cx: &Context, result: &Result<S, GraphError>) {
+    node_callbacks().end_finished(run_key(cx), None);
+    let span = cx.span();
+    match result {
+        Err(GraphError::InterruptError(_) | GraphError::ActionRequested(_)) | Ok(_) => {}
+        Err(e) => span.set_status(Status::error(e.to_string())),
+    }
+    span.end();
+}
+
+/// Record on the current span that `node` hit an interrupt
+pub(crate) fn record_interrupt(node: &str, value: &Value) {
+    Context::current().span().add_event(
+        "interrupt",
+        vec![
+            KeyValue::new("node", node.to_string()),
+            KeyValue::new("value", value.to_string()),
+        ],
+    );
+}
+
+/// Record on the current span that attempt `attempt` of `node` failed and is retried
+pub(crate) fn record_retry(node: &str, attempt: u32, error: &GraphError) {
+    Context::current().span().add_event(
+        "retry",
+        vec![
+            KeyValue::new("node", node.to_string()),
+            KeyValue::new("attempt", i64::from(attempt)),
+            KeyValue::new("error", error.to_string()),
+        ],
+    );
+}
+
+/// Store the current trace and span IDs in checkpoint `metadata`
+pub(crate) fn annotate_metadata(metadata: &mut HashMap<String, Value>) {
+    if let Some(trace) = trace_context(&Context::current()) {
+        metadata.insert(TRACE_CONTEXT_KEY.to_string(), trace);
+    }
+}
+
+/// The callbacks recording node spans, shared by every instrumented run
+pub(crate) fn node_callbacks() -> Arc<OtelCallbacks> {
+    static CALLBACKS: OnceLock<Arc<OtelCallbacks>> = OnceLock::new();
+    CALLBACKS.get_or_init(Default::default).clone()
+}
+
+/// Identifies a run by its root span, which is the current context while it runs
+fn run_key(cx: &Context) -> SpanId {
+    cx.span().span_context().span_id()
+}
+
+/// Records a `graph.node` span per node run as a child of the run's span
+///
+/// A finished node's span stays open until the checkpoint written after it (so it can
+/// carry the `checkpoint_id`), the next node starts, or the run ends; it is ended with
+/// the time the node finished.
+#[derive(Default)]
+pub(crate) struct OtelCallbacks {
+    running: Mutex<HashMap<(SpanId, RunContext, String), Context>>,
+    finished: Mutex<HashMap<SpanId, Vec<(Context, SystemTime)>>>,
+}
+
+impl OtelCallbacks {
+    /// End the finished node spans of run `run`, tagging them with `checkpoint_id`
+    fn end_finished(&self, run: SpanId, checkpoint_id: Option<&str>) {
+        let finished = self
+            .finished
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&run)
+            .unwrap_or_default();
+        for (cx, ended_at) in finished {
+            let span = cx.span();
+            if let Some(checkpoint_id) = checkpoint_id {
+                span.set_attribute(KeyValue::new("checkpoint_id", checkpoint_id.to_string()));
+            }
+            span.end_with_timestamp(ended_at);
+        }
+    }
+
+    fn take_running(&self, run: SpanId, run_ctx: &RunContext, node: &str) -> Option<Context> {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(run, run_ctx.clone(), node.to_string()))
+    }
+}
+
+impl GraphCallbacks for OtelCallbacks {
+    fn on_node_start(&self, run_ctx: &RunContext, node: &str) {
+        let parent = Context::current();
+        let run = run_key(&parent);
+        self.end_finished(run, None);
+
+        let tracer = global::tracer(TRACER_NAME);
+        let mut attributes = vec![KeyValue::new("node", node.to_string())];
+        if let Some(ns) = &run_ctx.checkpoint_ns {
+            attributes.push(KeyValue::new("checkpoint_ns", ns.clone()));
+        }
+        let span = tracer
+            .span_builder("graph.node")
+            .with_attributes(attributes)
+            .start_with_context(&tracer, &parent);
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                (run, run_ctx.clone(), node.to_string()),
+                parent.with_span(span),
+            );
+    }
+
+    fn on_node_end(
+        &self,
+        run_ctx: &RunContext,
+        node: &str,
+        update: &StateUpdate,
+        duration: Duration,
+    ) {
+        let run = run_key(&Context::current());
+        let Some(cx) = self.take_running(run, run_ctx, node) else {
+            return;
+        };
+        let span = cx.span();
+        span.set_attribute(KeyValue::new("duration_ms", duration.as_millis() as i64));
+        let size = serde_json::to_vec(update).map_or(0, |bytes| bytes.len());
+        span.set_attribute(KeyValue::new("update_size", size as i64));
+        self.finished
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(run)
+            .or_default()
+            .push((cx, SystemTime::now()));
+    }
+
+    fn on_checkpoint(&self, checkpoint_id: &str) {
+        self.end_finished(run_key(&Context::current()), Some(checkpoint_id));
+    }
+
+    fn on_error(&self, node: &str, err: &GraphError) {
+        let run = run_key(&Context::current());
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        let key = running
+            .keys()
+            .find(|(r, _, n)| *r == run && n == node)
+            .cloned();
+        if let Some(cx) = key.and_then(|key| running.remove(&key)) {
+            let span = cx.span();
+            span.set_status(Status::error(err.to_string()));
+            span.end();
+        }
+    }
+}
@@ -23,7 +23,8 @@ pub trait Checkpointer<S: State>: Send + Sync {
 
     /// Get a checkpoint
     ///
-    /// If checkpoint_id is None, returns the latest checkpoint for the thread.
+    /// If checkpoint_id is None, returns the latest checkpoint of the thread's root graph;
+    /// checkpoints written by subgraphs under a `checkpoint_ns` are skipped.
     async fn get(
         &self,
         thread_id: &str,
@@ -39,6 +40,34 @@ pub trait Checkpointer<S: State>: Send + Sync {
         thread_id: &str,
         limit: Option<usize>,
     ) -> Result<Vec<StateSnapshot<S>>, PersistenceError>;
+
+    /// List the checkpoints a thread wrote under `checkpoint_ns`
+    ///
+    /// `None` or an empty namespace selects the root graph's checkpoints; subgraphs write
+    /// theirs under their node path (e.g. `review/lint`). Returns checkpoints in
+    /// chronological order, keeping only the most recent `limit` if set.
+    async fn list_namespace(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateSnapshot<S>>, PersistenceError> {
+        let mut result = self.list(thread_id, None).await?;
+        result.retain(|cp| in_namespace(cp.config.checkpoint_ns.as_deref(), checkpoint_ns));
+        if let Some(limit) = limit {
+            let len = result.len();
+            if len > limit {
+                result.drain(0..(len - limit));
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Whether a checkpoint written under `checkpoint_ns` belongs to `namespace`, treating
+/// `None` and the empty namespace as the root graph
+pub(crate) fn in_namespace(checkpoint_ns: Option<&str>, namespace: Option<&str>) -> bool {
+    checkpoint_ns.unwrap_or_default() == namespace.unwrap_or_default()
 }
 
 /// Type alias for a boxed checkpointer
@@ -1,7 +1,20 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+pub use tokio_util::sync::CancellationToken;
+
+use crate::graph::callbacks::GraphCallbacks;
+
+/// Number of steps a run may take when no limit is set with
+/// [`RunnableConfig::with_step_limit`]
+pub const DEFAULT_STEP_LIMIT: usize = 25;
+
+/// Number of fan-out branches run at once when no limit is set with
+/// [`RunnableConfig::with_max_concurrency`]
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
 
 /// Configuration for graph execution with persistence
 ///
@@ -11,6 +24,15 @@ use serde_json::Value;
 pub struct RunnableConfig {
     /// Configurable parameters (thread_id, checkpoint_id, etc.)
     pub configurable: HashMap<String, Value>,
+    /// Token that cancels the run (not serialized)
+    #[serde(skip)]
+    cancellation: Option<CancellationToken>,
+    /// Lifecycle callbacks of the run (not serialized)
+    #[serde(skip)]
+    callbacks: Vec<Arc<dyn GraphCallbacks>>,
+    /// When the run must stop (not serialized)
+    #[serde(skip)]
+    deadline: Option<Instant>,
 }
 
 impl RunnableConfig {
@@ -70,6 +92,139 @@ impl RunnableConfig {
             .map(|s| s.to_string())
     }
 
+    /// Derive the config used to run a subgraph node.
+    ///
+    /// The checkpoint namespace is extended with the node name (e.g. `parent_node/child_node`)
+    /// so checkpoints written inside the subgraph can be told apart in the thread history.
+    /// Any `checkpoint_id` is dropped because the subgraph starts a fresh checkpoint lineage.
+    pub fn for_subgraph(&self, node: &str) -> Self {
+        let namespace = match self.get_checkpoint_ns() {
+            Some(ns) if !ns.is_empty() => format!("{}/{}", ns, node),
+            _ => node.to_string(),
+        };
+        let mut config = self.clone();
+        config.configurable.remove("checkpoint_id");
+        config
+            .configurable
+            .insert("checkpoint_ns".to_string(), Value::String(namespace));
+        config
+    }
+
+    /// Get the resume value set by [`with_resume`](Self::with_resume)
+    pub fn get_resume(&self) -> Option<Value> {
+        self.configurable.get("resume").cloned()
+    }
+
+    /// Resume an interrupted thread with a value
+    ///
+    /// `invoke_with_config(None, &config)` then continues at the interrupted node, and
+    /// the node's `interrupt()` call returns `value` instead of interrupting again.
+    pub fn with_resume(mut self, value: impl Into<Value>) -> Self {
+        self.configurable.insert("resume".to_string(), value.into());
+        self
+    }
+
+    /// Get the step limit set by [`with_step_limit`](Self::with_step_limit),
+    /// or [`DEFAULT_STEP_LIMIT`]
+    pub fn get_step_limit(&self) -> usize {
+        self.configurable
+            .get("step_limit")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_STEP_LIMIT, |limit| limit as usize)
+    }
+
+    /// Limit the number of steps (node executions, or super-steps for the super-step
+    /// executor) a single invocation may take
+    ///
+    /// Exceeding it fails the run with `GraphError::StepLimitExceeded` after its last
+    /// checkpoint is persisted, so the thread can be resumed with a higher limit.
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.configurable
+            .insert("step_limit".to_string(), Value::from(limit));
+        self
+    }
+
+    /// Get the fan-out concurrency limit set by
+    /// [`with_max_concurrency`](Self::with_max_concurrency), or [`DEFAULT_MAX_CONCURRENCY`]
+    pub fn get_max_concurrency(&self) -> usize {
+        self.configurable
+            .get("max_concurrency")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_MAX_CONCURRENCY, |limit| (limit as usize).max(1))
+    }
+
+    /// Limit how many branches of a fan-out edge run concurrently
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.configurable
+            .insert("max_concurrency".to_string(), Value::from(limit));
+        self
+    }
+
+    /// Get the cancellation token set by [`with_cancellation`](Self::with_cancellation)
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    /// Whether the run's cancellation token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Cancel the run when `token` is cancelled
+    ///
+    /// `invoke_with_config` checks the token between nodes and aborts the running node's
+    /// future, then writes a checkpoint pending at that node with status `cancelled` and
+    /// fails with `GraphError::Cancelled`. Nodes receive the token through their config,
+    /// so they can also watch it themselves. Invoking again with `None` resumes the thread.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Get the deadline set by [`with_deadline`](Self::with_deadline) or
+    /// [`with_max_duration`](Self::with_max_duration)
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Whether the run's deadline has passed
+    pub fn is_past_deadline(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Stop the run at `deadline`
+    ///
+    /// `invoke_with_config` checks the deadline before each node; once it has passed, it
+    /// writes a checkpoint pending at that node with status `deadline_exceeded` and fails
+    /// with `GraphError::DeadlineExceeded`, so the thread can be resumed with a fresh
+    /// deadline. Running nodes are not aborted, but they can read the deadline from their
+    /// config or [`current_deadline`](crate::graph::current_deadline) and bail early.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop the run once `duration` has passed from now; see [`with_deadline`](Self::with_deadline)
+    pub fn with_max_duration(self, duration: Duration) -> Self {
+        self.with_deadline(Instant::now() + duration)
+    }
+
+    /// Get the callbacks added with [`with_callbacks`](Self::with_callbacks)
+    pub fn callbacks(&self) -> &[Arc<dyn GraphCallbacks>] {
+        &self.callbacks
+    }
+
+    /// Observe the run with `callbacks`, after any attached to the graph itself
+    ///
+    /// Can be called repeatedly; subgraph runs inherit the callbacks.
+    pub fn with_callbacks(mut self, callbacks: Arc<dyn GraphCallbacks>) -> Self {
+        self.callbacks.push(callbacks);
+        self
+    }
+
     /// When true, allows step_once to run on a graph marked non-pure (with_pure_guard(false)).
     /// Default is false; set to true only for compatibility when nodes perform I/O until refactored to Actions.
     pub fn allow_non_pure_step_once(&self) -> bool {
@@ -139,6 +294,51 @@ mod tests {
         assert_eq!(config.get_checkpoint_id(), Some("checkpoint-1".to_string()));
     }
 
+    #[test]
+    fn test_step_limit_defaults() {
+        let config = RunnableConfig::with_thread_id("thread-1");
+        assert_eq!(config.get_step_limit(), DEFAULT_STEP_LIMIT);
+        assert_eq!(config.with_step_limit(3).get_step_limit(), 3);
+    }
+
+    #[test]
+    fn test_max_concurrency_defaults() {
+        let config = RunnableConfig::new();
+        assert_eq!(config.get_max_concurrency(), DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(
+            config.clone().with_max_concurrency(2).get_max_concurrency(),
+            2
+        );
+        assert_eq!(config.with_max_concurrency(0).get_max_concurrency(), 1);
+    }
+
+    #[test]
+    fn test_cancellation_token_is_shared() {
+        let token = CancellationToken::new();
+        let config = RunnableConfig::with_thread_id("thread-1").with_cancellation(token.clone());
+        let child = config.for_subgraph("review");
+        assert!(!child.is_cancelled());
+        token.cancel();
+        assert!(config.is_cancelled());
+        assert!(child.is_cancelled());
+        assert!(!RunnableConfig::with_thread_id("thread-1").is_cancelled());
+    }
+
+    #[test]
+    fn test_for_subgraph_nests_namespace() {
+        let config = RunnableConfig::with_checkpoint("thread-1", "checkpoint-1");
+        let child = config.for_subgraph("review");
+        assert_eq!(child.get_checkpoint_ns(), Some("review".to_string()));
+        assert_eq!(child.get_checkpoint_id(), None);
+
+        let grandchild = child.for_subgraph("lint");
+        assert_eq!(
+            grandchild.get_checkpoint_ns(),
+            Some("review/lint".to_string())
+        );
+        assert_eq!(grandchild.get_thread_id(), Some("thread-1".to_string()));
+    }
+
     #[test]
     fn test_checkpoint_config() {
         let runnable_config = RunnableConfig::with_checkpoint("thread-1", "checkpoint-1");
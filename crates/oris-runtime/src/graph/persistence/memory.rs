@@ -9,7 +9,11 @@ use uuid::Uuid;
 
 use crate::graph::state::State;
 
-use super::{checkpointer::Checkpointer, error::PersistenceError, snapshot::StateSnapshot};
+use super::{
+    checkpointer::{in_namespace, Checkpointer},
+    error::PersistenceError,
+    snapshot::StateSnapshot,
+};
 
 /// In-memory checkpointer implementation
 ///
@@ -99,8 +103,12 @@ impl<S: State> Checkpointer<S> for InMemorySaver<S> {
                 })
                 .cloned()
         } else {
-            // Return latest checkpoint
-            thread_checkpoints.last().cloned()
+            // Return the latest checkpoint of the root graph
+            thread_checkpoints
+                .iter()
+                .rev()
+                .find(|cp| in_namespace(cp.config.checkpoint_ns.as_deref(), None))
+                .cloned()
         };
 
         Ok(result)
@@ -174,7 +174,7 @@ where
                 r#"SELECT checkpoint_id, checkpoint_ns, parent_checkpoint_id,
                           state_values, next_nodes, metadata, created_at, at_seq
                    FROM "{}".graph_checkpoints
-                   WHERE thread_id = $1
+                   WHERE thread_id = $1 AND COALESCE(checkpoint_ns, '') = ''
                    ORDER BY created_at DESC
                    LIMIT 1"#,
                 self.schema
@@ -242,6 +242,40 @@ where
 
         Ok(snapshots)
     }
+
+    async fn list_namespace(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateSnapshot<S>>, PersistenceError> {
+        let limit_clause = limit
+            .map(|limit| format!("LIMIT {}", limit))
+            .unwrap_or_default();
+        let sql = format!(
+            r#"SELECT checkpoint_id, checkpoint_ns, parent_checkpoint_id,
+                      state_values, next_nodes, metadata, created_at, at_seq
+               FROM "{}".graph_checkpoints
+               WHERE thread_id = $1 AND COALESCE(checkpoint_ns, '') = $2
+               ORDER BY created_at DESC
+               {}"#,
+            self.schema, limit_clause
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(thread_id)
+            .bind(checkpoint_ns.unwrap_or_default())
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| PersistenceError::DatabaseError(e.to_string()))?;
+
+        let mut snapshots = Vec::with_capacity(rows.len());
+        for row in rows.iter().rev() {
+            snapshots.push(self.row_to_snapshot(thread_id, row)?);
+        }
+
+        Ok(snapshots)
+    }
 }
 
 #[cfg(feature = "postgres")]
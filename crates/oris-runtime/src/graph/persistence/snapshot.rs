@@ -4,10 +4,25 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::graph::state::State;
+use crate::graph::{
+    interrupts::{Interrupt, INTERRUPTS_METADATA_KEY},
+    state::State,
+};
 
 use super::config::CheckpointConfig;
 
+/// Checkpoint metadata key recording how the run that wrote the checkpoint stopped
+pub const STATUS_METADATA_KEY: &str = "status";
+
+/// Checkpoint metadata key holding the state the run that wrote the checkpoint started from
+pub const INPUT_METADATA_KEY: &str = "input";
+
+/// [`STATUS_METADATA_KEY`] value of the checkpoint written when a run is cancelled
+pub const CANCELLED_STATUS: &str = "cancelled";
+
+/// [`STATUS_METADATA_KEY`] value of the checkpoint written when a run passes its deadline
+pub const DEADLINE_EXCEEDED_STATUS: &str = "deadline_exceeded";
+
 /// State snapshot - a checkpoint of graph state at a particular point in time
 ///
 /// Similar to Python's StateSnapshot, this contains the state values,
@@ -90,6 +105,41 @@ impl<S: State> StateSnapshot<S> {
         self
     }
 
+    /// Interrupts pending at this checkpoint (empty unless the run paused)
+    ///
+    /// Read from checkpoint metadata, so they are available from every checkpointer.
+    pub fn interrupts(&self) -> Vec<Interrupt> {
+        self.metadata
+            .get(INTERRUPTS_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// State the run that wrote this checkpoint started from at START, if it was recorded
+    ///
+    /// Replaying the run from this state re-applies every node's update exactly once.
+    pub fn input(&self) -> Option<S> {
+        self.metadata
+            .get(INPUT_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Whether this checkpoint was written by a cancelled run
+    pub fn is_cancelled(&self) -> bool {
+        self.metadata
+            .get(STATUS_METADATA_KEY)
+            .and_then(Value::as_str)
+            == Some(CANCELLED_STATUS)
+    }
+
+    /// Whether this checkpoint was written by a run that passed its deadline
+    pub fn is_deadline_exceeded(&self) -> bool {
+        self.metadata
+            .get(STATUS_METADATA_KEY)
+            .and_then(Value::as_str)
+            == Some(DEADLINE_EXCEEDED_STATUS)
+    }
+
     /// Get the checkpoint ID
     pub fn checkpoint_id(&self) -> Option<&String> {
         self.config.checkpoint_id.as_ref()
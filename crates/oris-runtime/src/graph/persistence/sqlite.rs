@@ -42,28 +42,27 @@ where
     /// Create a new SqliteSaver with a database file path
     pub fn new(path: &str) -> Result<Self, PersistenceError> {
         let connection = Connection::open(path)?;
-        let saver = Self {
+        Self::setup(&connection)?;
+        Ok(Self {
             connection: Arc::new(Mutex::new(connection)),
             state: PhantomData,
-        };
-        saver.setup()?;
-        Ok(saver)
+        })
     }
 
     /// Create a new SqliteSaver with an in-memory database
     pub fn new_in_memory() -> Result<Self, PersistenceError> {
         let connection = Connection::open_in_memory()?;
-        let saver = Self {
+        Self::setup(&connection)?;
+        Ok(Self {
             connection: Arc::new(Mutex::new(connection)),
             state: PhantomData,
-        };
-        saver.setup()?;
-        Ok(saver)
+        })
     }
 
     /// Setup the database schema
-    fn setup(&self) -> Result<(), PersistenceError> {
-        let conn = self.connection.blocking_lock();
+    ///
+    /// Runs before the connection is shared, so the saver can be created inside an async runtime.
+    fn setup(conn: &Connection) -> Result<(), PersistenceError> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS checkpoints (
                 thread_id TEXT NOT NULL,
@@ -101,8 +100,176 @@ where
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cancel_requests (
+                thread_id TEXT PRIMARY KEY,
+                requested_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pause_requests (
+                thread_id TEXT PRIMARY KEY,
+                requested_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         Ok(())
     }
+
+    /// Persist a request to cancel the run on `thread_id` for good
+    ///
+    /// A process running the thread polls [`is_cancel_requested`](Self::is_cancel_requested)
+    /// and cancels the token passed to `RunnableConfig::with_cancellation`; unlike a
+    /// [pause](Self::request_pause), the thread is not meant to be resumed.
+    pub async fn request_cancel(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO cancel_requests (thread_id, requested_at) VALUES (?1, ?2)",
+            params![thread_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a cancel request is pending for `thread_id`
+    pub async fn is_cancel_requested(&self, thread_id: &str) -> Result<bool, PersistenceError> {
+        let conn = self.connection.lock().await;
+        let requested = conn
+            .query_row(
+                "SELECT 1 FROM cancel_requests WHERE thread_id = ?1",
+                params![thread_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+            .is_some();
+        Ok(requested)
+    }
+
+    /// Clear the cancel request for `thread_id` (e.g. before starting a new run)
+    pub async fn clear_cancel_request(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "DELETE FROM cancel_requests WHERE thread_id = ?1",
+            params![thread_id],
+        )?;
+        Ok(())
+    }
+
+    /// Persist a request to pause the run on `thread_id`
+    ///
+    /// A process running the thread polls [`is_pause_requested`](Self::is_pause_requested)
+    /// and cancels the run's token; the run stops at a checkpoint it resumes from.
+    pub async fn request_pause(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO pause_requests (thread_id, requested_at) VALUES (?1, ?2)",
+            params![thread_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a pause request is pending for `thread_id`
+    pub async fn is_pause_requested(&self, thread_id: &str) -> Result<bool, PersistenceError> {
+        let conn = self.connection.lock().await;
+        let requested = conn
+            .query_row(
+                "SELECT 1 FROM pause_requests WHERE thread_id = ?1",
+                params![thread_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+            .is_some();
+        Ok(requested)
+    }
+
+    /// Clear the pause request for `thread_id` (e.g. before starting a new run)
+    pub async fn clear_pause_request(&self, thread_id: &str) -> Result<(), PersistenceError> {
+        let conn = self.connection.lock().await;
+        conn.execute(
+            "DELETE FROM pause_requests WHERE thread_id = ?1",
+            params![thread_id],
+        )?;
+        Ok(())
+    }
+
+    /// Build a snapshot from a `checkpoints` row selecting `checkpoint_id, checkpoint_ns,
+    /// parent_checkpoint_id, state_values, next_nodes, metadata, created_at, at_seq`
+    fn snapshot_from_row(
+        thread_id: &str,
+        row: &rusqlite::Row<'_>,
+    ) -> rusqlite::Result<StateSnapshot<S>> {
+        let checkpoint_id: String = row.get(0)?;
+        let checkpoint_ns: Option<String> = row.get(1)?;
+        let parent_checkpoint_id: Option<String> = row.get(2)?;
+        let state_bytes: Vec<u8> = row.get(3)?;
+        let next_nodes_json: String = row.get(4)?;
+        let metadata_json: String = row.get(5)?;
+        let created_at_str: String = row.get(6)?;
+        let at_seq: Option<i64> = row.get(7)?;
+
+        // Deserialize state using serde_json (map to rusqlite::Error for the row mapper)
+        let values: S = serde_json::from_slice(&state_bytes).map_err(|_e| {
+            rusqlite::Error::InvalidColumnType(
+                3,
+                "state_values".to_string(),
+                rusqlite::types::Type::Blob,
+            )
+        })?;
+
+        // Deserialize next nodes and metadata
+        let next: Vec<String> = serde_json::from_str(&next_nodes_json).map_err(|_e| {
+            rusqlite::Error::InvalidColumnType(
+                4,
+                "next_nodes".to_string(),
+                rusqlite::types::Type::Text,
+            )
+        })?;
+        let metadata: std::collections::HashMap<String, Value> =
+            serde_json::from_str(&metadata_json).map_err(|_e| {
+                rusqlite::Error::InvalidColumnType(
+                    5,
+                    "metadata".to_string(),
+                    rusqlite::types::Type::Text,
+                )
+            })?;
+
+        // Parse created_at
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|_e| {
+                rusqlite::Error::InvalidColumnType(
+                    7,
+                    "created_at".to_string(),
+                    rusqlite::types::Type::Text,
+                )
+            })?
+            .with_timezone(&Utc);
+
+        // Build config
+        let config = CheckpointConfig {
+            thread_id: thread_id.to_string(),
+            checkpoint_id: Some(checkpoint_id.clone()),
+            checkpoint_ns,
+        };
+
+        // Build parent config if exists
+        let parent_config = parent_checkpoint_id.map(|parent_id| CheckpointConfig {
+            thread_id: thread_id.to_string(),
+            checkpoint_id: Some(parent_id),
+            checkpoint_ns: None,
+        });
+
+        Ok(StateSnapshot {
+            values,
+            next,
+            config,
+            metadata,
+            created_at,
+            parent_config,
+            at_seq: at_seq.map(|seq| seq as u64),
+        })
+    }
 }
 
 #[cfg(feature = "sqlite-persistence")]
@@ -188,7 +355,7 @@ where
             "SELECT checkpoint_id, checkpoint_ns, parent_checkpoint_id, state_values, 
                     next_nodes, metadata, created_at, at_seq
              FROM checkpoints 
-             WHERE thread_id = ?1
+             WHERE thread_id = ?1 AND COALESCE(checkpoint_ns, '') = ''
              ORDER BY created_at DESC LIMIT 1"
         };
 
@@ -199,77 +366,7 @@ where
             params![thread_id]
         };
 
-        let result = stmt.query_row(params, |row| {
-            let checkpoint_id: String = row.get(0)?;
-            let checkpoint_ns: Option<String> = row.get(1)?;
-            let parent_checkpoint_id: Option<String> = row.get(2)?;
-            let state_bytes: Vec<u8> = row.get(3)?;
-            let next_nodes_json: String = row.get(4)?;
-            let metadata_json: String = row.get(5)?;
-            let created_at_str: String = row.get(6)?;
-            let at_seq: Option<i64> = row.get(7)?;
-
-            // Deserialize state using serde_json (map to rusqlite::Error for closure return type)
-            let values: S = serde_json::from_slice(&state_bytes).map_err(|_e| {
-                rusqlite::Error::InvalidColumnType(
-                    3,
-                    "state_values".to_string(),
-                    rusqlite::types::Type::Blob,
-                )
-            })?;
-
-            // Deserialize next nodes and metadata
-            let next: Vec<String> = serde_json::from_str(&next_nodes_json).map_err(|_e| {
-                rusqlite::Error::InvalidColumnType(
-                    4,
-                    "next_nodes".to_string(),
-                    rusqlite::types::Type::Text,
-                )
-            })?;
-            let metadata: std::collections::HashMap<String, Value> =
-                serde_json::from_str(&metadata_json).map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        5,
-                        "metadata".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?;
-
-            // Parse created_at
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        7,
-                        "created_at".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            // Build config
-            let config = CheckpointConfig {
-                thread_id: thread_id.to_string(),
-                checkpoint_id: Some(checkpoint_id.clone()),
-                checkpoint_ns,
-            };
-
-            // Build parent config if exists
-            let parent_config = parent_checkpoint_id.map(|parent_id| CheckpointConfig {
-                thread_id: thread_id.to_string(),
-                checkpoint_id: Some(parent_id),
-                checkpoint_ns: None,
-            });
-
-            Ok(StateSnapshot {
-                values,
-                next,
-                config,
-                metadata,
-                created_at,
-                parent_config,
-                at_seq: at_seq.map(|seq| seq as u64),
-            })
-        });
+        let result = stmt.query_row(params, |row| Self::snapshot_from_row(thread_id, row));
 
         match result {
             Ok(snapshot) => Ok(Some(snapshot)),
@@ -297,75 +394,7 @@ where
 
         let mut stmt = conn.prepare(&query)?;
         let rows = stmt.query_map(params![thread_id], |row| {
-            let checkpoint_id: String = row.get(0)?;
-            let checkpoint_ns: Option<String> = row.get(1)?;
-            let parent_checkpoint_id: Option<String> = row.get(2)?;
-            let state_bytes: Vec<u8> = row.get(3)?;
-            let next_nodes_json: String = row.get(4)?;
-            let metadata_json: String = row.get(5)?;
-            let created_at_str: String = row.get(6)?;
-            let at_seq: Option<i64> = row.get(7)?;
-
-            // Deserialize state using serde_json (map to rusqlite::Error for closure return type)
-            let values: S = serde_json::from_slice(&state_bytes).map_err(|_e| {
-                rusqlite::Error::InvalidColumnType(
-                    3,
-                    "state_values".to_string(),
-                    rusqlite::types::Type::Blob,
-                )
-            })?;
-
-            // Deserialize next nodes and metadata
-            let next: Vec<String> = serde_json::from_str(&next_nodes_json).map_err(|_e| {
-                rusqlite::Error::InvalidColumnType(
-                    4,
-                    "next_nodes".to_string(),
-                    rusqlite::types::Type::Text,
-                )
-            })?;
-            let metadata: std::collections::HashMap<String, Value> =
-                serde_json::from_str(&metadata_json).map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        5,
-                        "metadata".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?;
-
-            // Parse created_at
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        7,
-                        "created_at".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            // Build config
-            let config = CheckpointConfig {
-                thread_id: thread_id.to_string(),
-                checkpoint_id: Some(checkpoint_id.clone()),
-                checkpoint_ns,
-            };
-
-            // Build parent config if exists
-            let parent_config = parent_checkpoint_id.map(|parent_id| CheckpointConfig {
-                thread_id: thread_id.to_string(),
-                checkpoint_id: Some(parent_id),
-                checkpoint_ns: None,
-            });
-
-            Ok(StateSnapshot {
-                values,
-                next,
-                config,
-                metadata,
-                created_at,
-                parent_config,
-                at_seq: at_seq.map(|seq| seq as u64),
-            })
+            Self::snapshot_from_row(thread_id, row)
         })?;
 
         let mut snapshots = Vec::new();
@@ -375,6 +404,39 @@ where
 
         Ok(snapshots)
     }
+
+    async fn list_namespace(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<StateSnapshot<S>>, PersistenceError> {
+        let conn = self.connection.lock().await;
+
+        let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
+        let query = format!(
+            "SELECT checkpoint_id, checkpoint_ns, parent_checkpoint_id, state_values,
+                    next_nodes, metadata, created_at, at_seq
+             FROM checkpoints
+             WHERE thread_id = ?1 AND COALESCE(checkpoint_ns, '') = ?2
+             ORDER BY created_at DESC {}",
+            limit_clause
+        );
+
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(
+            params![thread_id, checkpoint_ns.unwrap_or_default()],
+            |row| Self::snapshot_from_row(thread_id, row),
+        )?;
+
+        let mut snapshots = Vec::new();
+        for row in rows {
+            snapshots.push(row.map_err(|e| PersistenceError::DatabaseError(e.to_string()))?);
+        }
+        snapshots.reverse();
+
+        Ok(snapshots)
+    }
 }
 
 #[cfg(all(test, feature = "sqlite-persistence"))]
@@ -406,6 +468,220 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_sqlite_saver_separates_subgraph_namespaces() {
+        let saver = SqliteSaver::<MessagesState>::new_in_memory().unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let root = CheckpointConfig::new("thread-ns");
+            let mut inner = CheckpointConfig::new("thread-ns");
+            inner.checkpoint_ns = Some("review".to_string());
+            let root_id = saver
+                .put(
+                    "thread-ns",
+                    &StateSnapshot::new(MessagesState::new(), vec!["review".to_string()], root),
+                )
+                .await
+                .unwrap();
+            for next in ["approve", "publish"] {
+                saver
+                    .put(
+                        "thread-ns",
+                        &StateSnapshot::new(
+                            MessagesState::new(),
+                            vec![next.to_string()],
+                            inner.clone(),
+                        ),
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            let latest = saver.get("thread-ns", None).await.unwrap().unwrap();
+            assert_eq!(latest.checkpoint_id(), Some(&root_id));
+
+            let root_history = saver.list_namespace("thread-ns", None, None).await.unwrap();
+            assert_eq!(root_history.len(), 1);
+            let inner_latest = saver
+                .list_namespace("thread-ns", Some("review"), Some(1))
+                .await
+                .unwrap();
+            assert_eq!(inner_latest.len(), 1);
+            assert_eq!(inner_latest[0].next, vec!["publish".to_string()]);
+            assert_eq!(saver.list("thread-ns", None).await.unwrap().len(), 3);
+        });
+    }
+
+    /// A breakpoint's pending node survives reopening the database (process restart).
+    #[test]
+    fn test_breakpoint_survives_restart() {
+        use crate::graph::{function_node, RunnableConfig, StateGraph, END, START};
+        use std::sync::Arc;
+
+        fn build(path: &str) -> crate::graph::CompiledGraph<MessagesState> {
+            let mut graph = StateGraph::<MessagesState>::new();
+            for name in ["draft", "approval"] {
+                graph
+                    .add_node(
+                        name,
+                        function_node(name, move |_s: &MessagesState| async move {
+                            Ok(crate::graph::messages_state_update(vec![
+                                Message::new_ai_message(name),
+                            ]))
+                        }),
+                    )
+                    .unwrap();
+            }
+            graph.add_edge(START, "draft");
+            graph.add_edge("draft", "approval");
+            graph.add_edge("approval", END);
+            let saver = SqliteSaver::<MessagesState>::new(path).unwrap();
+            graph
+                .compile_with_interrupts(Arc::new(saver), &["approval"], &[])
+                .unwrap()
+        }
+
+        let db_path =
+            std::env::temp_dir().join(format!("oris-breakpoint-{}.db", std::process::id()));
+        let db_path = db_path.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&db_path);
+        let config = RunnableConfig::with_thread_id("breakpoint-restart");
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let first = build(&db_path);
+        let paused = rt
+            .block_on(first.invoke_with_config(Some(MessagesState::new()), &config))
+            .unwrap();
+        assert_eq!(paused.messages.len(), 1);
+        drop(first);
+
+        let second = build(&db_path);
+        let snapshot = rt.block_on(second.get_state(&config)).unwrap();
+        assert_eq!(snapshot.next, vec!["approval".to_string()]);
+        let finished = rt
+            .block_on(second.invoke_with_config(None, &config))
+            .unwrap();
+        assert_eq!(
+            finished.messages.last().map(|m| m.content.as_str()),
+            Some("approval")
+        );
+        assert_eq!(finished.messages.len(), 2);
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// A cancel or pause request written by one connection is seen by another (another
+    /// process).
+    #[test]
+    fn test_cancel_request_survives_reopen() {
+        let db_path = std::env::temp_dir().join(format!("oris-cancel-{}.db", std::process::id()));
+        let db_path = db_path.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&db_path);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let worker = SqliteSaver::<MessagesState>::new(&db_path).unwrap();
+        let operator = SqliteSaver::<MessagesState>::new(&db_path).unwrap();
+        rt.block_on(async {
+            assert!(!worker.is_cancel_requested("job").await.unwrap());
+            operator.request_cancel("job").await.unwrap();
+            assert!(worker.is_cancel_requested("job").await.unwrap());
+            assert!(!worker.is_cancel_requested("other").await.unwrap());
+            worker.clear_cancel_request("job").await.unwrap();
+            assert!(!operator.is_cancel_requested("job").await.unwrap());
+
+            operator.request_pause("job").await.unwrap();
+            assert!(worker.is_pause_requested("job").await.unwrap());
+            assert!(!worker.is_cancel_requested("job").await.unwrap());
+            worker.clear_pause_request("job").await.unwrap();
+            assert!(!operator.is_pause_requested("job").await.unwrap());
+        });
+
+        let _ = fs::remove_file(&db_path);
+    }
+
+    /// A fork copies the lineage with rewritten links and runs without touching the source.
+    #[test]
+    fn test_fork_thread_copies_lineage() {
+        use crate::graph::{function_node, RunnableConfig, StateGraph, END, START};
+        use std::sync::Arc;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["draft", "approval"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        Ok(crate::graph::messages_state_update(vec![
+                            Message::new_ai_message(name),
+                        ]))
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "approval");
+        graph.add_edge("approval", END);
+        let saver = SqliteSaver::<MessagesState>::new_in_memory().unwrap();
+        let compiled = graph
+            .compile_with_interrupts(Arc::new(saver), &["approval"], &[])
+            .unwrap();
+        let source = RunnableConfig::with_thread_id("fork-source");
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        rt.block_on(async {
+            compiled
+                .invoke_with_config(Some(MessagesState::new()), &source)
+                .await
+                .unwrap();
+            let edit =
+                crate::graph::messages_state_update(vec![Message::new_human_message("note")]);
+            compiled.update_state(&source, &edit, None).await.unwrap();
+            let source_history = compiled.get_state_history(&source).await.unwrap();
+            assert_eq!(source_history.len(), 2);
+
+            let fork = compiled
+                .fork_thread(&source, "fork-target", None)
+                .await
+                .unwrap();
+            let fork_history = compiled.get_state_history(&fork).await.unwrap();
+            assert_eq!(fork_history.len(), 2);
+            assert!(fork_history.iter().all(|s| s.thread_id() == "fork-target"));
+            assert_ne!(
+                fork_history[1].checkpoint_id(),
+                source_history[1].checkpoint_id()
+            );
+            assert_eq!(
+                fork_history[1]
+                    .parent_config
+                    .as_ref()
+                    .and_then(|p| p.checkpoint_id.as_ref()),
+                fork_history[0].checkpoint_id()
+            );
+            assert!(compiled
+                .fork_thread(&source, "fork-target", None)
+                .await
+                .is_err());
+
+            let finished = compiled.invoke_with_config(None, &fork).await.unwrap();
+            assert_eq!(finished.messages.len(), 3);
+            let untouched = compiled.get_state(&source).await.unwrap();
+            assert_eq!(untouched.next, vec!["approval".to_string()]);
+            assert_eq!(compiled.get_state_history(&source).await.unwrap().len(), 2);
+
+            // Forking up to the first checkpoint drops the later edit
+            let early = compiled
+                .fork_thread(
+                    &source,
+                    "fork-early",
+                    source_history[0].checkpoint_id().map(String::as_str),
+                )
+                .await
+                .unwrap();
+            let state = compiled.get_state(&early).await.unwrap();
+            assert_eq!(state.values.messages.len(), 1);
+        });
+    }
+
     #[tokio::test]
     #[ignore = "SqliteSaver::new blocks; cannot run inside tokio runtime"]
     async fn test_sqlite_saver_file() {
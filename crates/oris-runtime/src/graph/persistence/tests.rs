@@ -144,10 +144,14 @@ mod time_travel_tests {
             )])?,
         );
 
-        let updated = compiled
+        let checkpoint_id = compiled
             .update_state(&checkpoint.to_config(), &updates, None)
             .await
             .unwrap();
+        let updated = compiled
+            .get_state(&RunnableConfig::with_checkpoint("fork-test", checkpoint_id))
+            .await
+            .unwrap();
 
         // Check that new checkpoint has parent
         assert!(updated.parent_config.is_some());
@@ -1,11 +1,94 @@
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{
+    borrow::Cow, collections::HashMap, fmt, marker::PhantomData, panic::Location, path::PathBuf,
+    sync::Arc,
+};
 
-use serde::de::DeserializeOwned;
+use jsonschema::{error::ValidationErrorKind, JSONSchema};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::plugins::PluginMetadata;
 
-use super::{error::GraphError, node::Node, state::State};
+use super::{
+    error::GraphError,
+    interpolation::{interpolate_config, EnvSecretResolver, SecretResolver},
+    node::Node,
+    state::State,
+};
+
+/// Version of the node plugin API implemented by this runtime.
+///
+/// Bumped on breaking changes to [NodePlugin] or [NodePluginRegistry]; the registry only
+/// accepts plugins reporting this version from [`NodePlugin::api_version`].
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Namespace that [`NodePluginRegistry::allow_legacy_plugin_types`] files bare plugin
+/// types under.
+pub const LEGACY_PLUGIN_NAMESPACE: &str = "legacy";
+
+/// Split a plugin type into its namespace and name, e.g. `("plugin_reference", "delay")`.
+///
+/// Both parts must be non-empty and made of ASCII letters, digits, `_`, `-` and `.`.
+pub fn parse_plugin_type(plugin_type: &str) -> Result<(&str, &str), String> {
+    let Some((namespace, name)) = plugin_type.split_once('/') else {
+        return Err("expected 'namespace/name'".to_string());
+    };
+    for (part, value) in [("namespace", namespace), ("name", name)] {
+        if !is_plugin_type_segment(value) {
+            return Err(format!(
+                "{} '{}' must be non-empty ASCII letters, digits, '_', '-' or '.'",
+                part, value
+            ));
+        }
+    }
+    Ok((namespace, name))
+}
+
+fn is_plugin_type_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Where a plugin, router or namespace reservation was registered from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginProvenance {
+    /// Source location of the registering call, e.g. `src/main.rs:12:5`.
+    pub location: String,
+    /// Plugin library the registration came from, for plugins loaded with `load_dylib`.
+    pub library: Option<PathBuf>,
+}
+
+impl PluginProvenance {
+    /// Provenance of the caller's location
+    #[track_caller]
+    fn caller() -> Self {
+        let location = Location::caller();
+        Self {
+            location: format!(
+                "{}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            ),
+            library: None,
+        }
+    }
+}
+
+impl fmt::Display for PluginProvenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.library {
+            Some(library) => write!(f, "{} in {}", self.location, library.display()),
+            None => f.write_str(&self.location),
+        }
+    }
+}
+
+/// Decides which registrations may use a namespace reserved with
+/// [`NodePluginRegistry::register_namespace`].
+pub type NamespaceGuard = Arc<dyn Fn(&PluginProvenance) -> bool + Send + Sync>;
 
 /// Runtime plugin interface for constructing custom graph node types from configuration.
 ///
@@ -14,24 +97,156 @@ use super::{error::GraphError, node::Node, state::State};
 /// in the kernel plugin system (see [`crate::plugins::PluginCategory`]). Must declare
 /// [PluginMetadata] for kernel enforcement (replay, sandbox).
 pub trait NodePlugin<S: State>: Send + Sync {
-    /// Stable plugin type identifier used for registration and lookup.
+    /// Stable plugin type identifier used for registration and lookup, in
+    /// `namespace/name` form (see [parse_plugin_type]).
     fn plugin_type(&self) -> &str;
 
+    /// The [PLUGIN_API_VERSION] the plugin was built against.
+    ///
+    /// Implementations return the constant itself, so recompiling against a newer runtime
+    /// updates it; the registry rejects plugins built for another version.
+    fn api_version(&self) -> u32;
+
     /// Declared behavioral boundaries for kernel enforcement. Default is conservative.
     fn plugin_metadata(&self) -> PluginMetadata {
         PluginMetadata::conservative()
     }
 
+    /// Human-readable name shown to users. Defaults to the plugin type.
+    fn display_name(&self) -> &str {
+        self.plugin_type()
+    }
+
+    /// Short description of what the node does.
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Semver version of the crate providing the plugin, e.g. `env!("CARGO_PKG_VERSION")`.
+    fn version(&self) -> Option<&str> {
+        None
+    }
+
+    /// JSON Schema of the configuration payload, if the plugin publishes one.
+    ///
+    /// When present, [NodePluginRegistry] validates configs against it before calling
+    /// [`create_node`](Self::create_node).
+    fn config_schema(&self) -> Option<Value> {
+        None
+    }
+
     /// Create a node instance for the provided graph node name and configuration payload.
     fn create_node(&self, name: &str, config: &Value) -> Result<Arc<dyn Node<S>>, GraphError>;
 }
 
-/// Registry for runtime-resolved node plugins.
+/// A config value that does not satisfy a plugin's config schema.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigValidationError {
+    /// JSON pointer of the offending value within the config (`""` for the config itself).
+    pub path: String,
+    /// The schema constraint that failed, e.g. `type "integer"` or `minimum 0`.
+    pub expected: String,
+    /// The offending value (`null` for a missing required property).
+    pub got: Value,
+}
+
+impl fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: expected {}, got {}", path, self.expected, self.got)
+    }
+}
+
+/// Description of a registered node plugin, for hosts that list available node types
+/// and render their config forms.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PluginDescriptor {
+    pub plugin_type: String,
+    /// Human-readable name (the plugin type unless the plugin provides one).
+    pub name: String,
+    pub description: Option<String>,
+    /// Semver version of the crate providing the plugin.
+    pub version: Option<String>,
+    /// The [PLUGIN_API_VERSION] the plugin was built against.
+    pub api_version: u32,
+    pub metadata: PluginMetadata,
+    /// JSON Schema of the configuration payload, if the plugin publishes one.
+    pub config_schema: Option<Value>,
+}
+
+impl PluginDescriptor {
+    fn of<S: State>(plugin_type: &str, plugin: &dyn NodePlugin<S>) -> Self {
+        Self {
+            plugin_type: plugin_type.to_string(),
+            name: plugin.display_name().to_string(),
+            description: plugin.description().map(str::to_string),
+            version: plugin.version().map(str::to_string),
+            api_version: plugin.api_version(),
+            metadata: plugin.plugin_metadata(),
+            config_schema: plugin.config_schema(),
+        }
+    }
+}
+
+/// Router built by a [RouterPlugin]: returns the mapping key of the next node.
+pub type PluginRouter<S> = Arc<dyn Fn(&S) -> String + Send + Sync>;
+
+/// Runtime plugin interface for constructing conditional-edge routers from configuration.
+///
+/// Routers are registered next to node plugins in [NodePluginRegistry] and referenced by
+/// the conditional edges of a [`GraphSpec`](super::GraphSpec).
+pub trait RouterPlugin<S: State>: Send + Sync {
+    /// Stable router type identifier used for registration and lookup, in
+    /// `namespace/name` form like node plugin types.
+    fn plugin_type(&self) -> &str;
+
+    /// Create a router for the provided configuration payload.
+    fn create_router(&self, config: &Value) -> Result<PluginRouter<S>, GraphError>;
+
+    /// Every key a router built from `config` can return, if the plugin knows them.
+    ///
+    /// When present, `StateGraph::add_plugin_conditional_edges` rejects mappings that
+    /// miss one of them, so a spec cannot route to a label it has no edge for.
+    fn route_keys(&self, _config: &Value) -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// Registry for runtime-resolved node and router plugins.
 ///
 /// This allows applications to register custom node factories up front, then
-/// construct graph nodes later from a runtime payload (`plugin_type` + config).
+/// construct graph nodes later from a runtime payload (`plugin_type` + config),
+/// or a whole graph from a [`GraphSpec`](super::GraphSpec) with
+/// [`build_graph`](Self::build_graph). `${env:VAR}` and `${secret:NAME}` placeholders in
+/// configs are expanded first, with secrets from an [EnvSecretResolver] unless
+/// [`set_secret_resolver`](Self::set_secret_resolver) replaces it.
+///
+/// Plugin and router types are `namespace/name` strings. Each registration records the
+/// [PluginProvenance] of its call site, so a second registration of a type fails with
+/// `GraphError::PluginConflict` naming both, and
+/// [`register_namespace`](Self::register_namespace) reserves a namespace for the
+/// registrations its guard accepts.
 pub struct NodePluginRegistry<S: State> {
-    plugins: HashMap<String, Arc<dyn NodePlugin<S>>>,
+    plugins: HashMap<String, Registered<dyn NodePlugin<S>>>,
+    routers: HashMap<String, Registered<dyn RouterPlugin<S>>>,
+    namespaces: HashMap<String, ReservedNamespace>,
+    legacy_plugin_types: bool,
+    secrets: Arc<dyn SecretResolver>,
+}
+
+/// A registered plugin or router and where it was registered from
+struct Registered<P: ?Sized> {
+    plugin: Arc<P>,
+    provenance: PluginProvenance,
+}
+
+struct ReservedNamespace {
+    guard: NamespaceGuard,
+    provenance: PluginProvenance,
 }
 
 impl<S: State> NodePluginRegistry<S> {
@@ -39,12 +254,84 @@ impl<S: State> NodePluginRegistry<S> {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
+            routers: HashMap::new(),
+            namespaces: HashMap::new(),
+            legacy_plugin_types: false,
+            secrets: Arc::new(EnvSecretResolver::new()),
         }
     }
 
+    /// Resolve `${secret:NAME}` placeholders in configs with `resolver`.
+    pub fn set_secret_resolver(&mut self, resolver: impl SecretResolver + 'static) -> &mut Self {
+        self.secrets = Arc::new(resolver);
+        self
+    }
+
+    /// Accept plugin and router types without a namespace, as a migration aid.
+    ///
+    /// A bare type such as `echo` is registered as `legacy/echo`, and looking up `echo`
+    /// finds it there; both log a deprecation warning. Without this, registering a bare
+    /// type fails.
+    pub fn allow_legacy_plugin_types(&mut self) -> &mut Self {
+        self.legacy_plugin_types = true;
+        self
+    }
+
+    /// Reserve `namespace` for the registrations `guard` accepts.
+    ///
+    /// Registering a plugin or router in the namespace from a [PluginProvenance] the guard
+    /// rejects then fails with `GraphError::PluginNamespaceReserved`, as does reserving a
+    /// namespace that already holds such a registration.
+    ///
+    /// ```rust,ignore
+    /// // Only statically linked plugins may use `acme/...`
+    /// registry.register_namespace("acme", |source| source.library.is_none())?;
+    /// ```
+    #[track_caller]
+    pub fn register_namespace(
+        &mut self,
+        namespace: &str,
+        guard: impl Fn(&PluginProvenance) -> bool + Send + Sync + 'static,
+    ) -> Result<&mut Self, GraphError> {
+        let provenance = PluginProvenance::caller();
+        if !is_plugin_type_segment(namespace) {
+            return Err(GraphError::CompilationError(format!(
+                "Invalid plugin namespace '{}': must be non-empty ASCII letters, digits, '_', '-' or '.'",
+                namespace
+            )));
+        }
+        if let Some(reserved) = self.namespaces.get(namespace) {
+            return Err(GraphError::CompilationError(format!(
+                "Plugin namespace '{}' is already reserved at {}; conflicting reservation at {}",
+                namespace, reserved.provenance, provenance
+            )));
+        }
+        let reserved = ReservedNamespace {
+            guard: Arc::new(guard),
+            provenance,
+        };
+        let registrations = self
+            .plugins
+            .iter()
+            .map(|(plugin_type, registered)| (plugin_type, &registered.provenance))
+            .chain(
+                self.routers
+                    .iter()
+                    .map(|(plugin_type, registered)| (plugin_type, &registered.provenance)),
+            );
+        for (plugin_type, provenance) in registrations {
+            if plugin_type.split_once('/').map(|(ns, _)| ns) == Some(namespace) {
+                check_guard(namespace, &reserved, plugin_type, provenance)?;
+            }
+        }
+        self.namespaces.insert(namespace.to_string(), reserved);
+        Ok(self)
+    }
+
     /// Register a plugin by value.
     ///
     /// Returns an error if the same `plugin_type` is already registered.
+    #[track_caller]
     pub fn register_plugin<P>(&mut self, plugin: P) -> Result<&mut Self, GraphError>
     where
         P: NodePlugin<S> + 'static,
@@ -54,30 +341,61 @@ impl<S: State> NodePluginRegistry<S> {
 
     /// Register a shared plugin implementation.
     ///
-    /// Returns an error if the same `plugin_type` is already registered.
+    /// Returns `GraphError::PluginConflict` if the same `plugin_type` is already
+    /// registered, `GraphError::PluginIncompatible` if the plugin targets another
+    /// [PLUGIN_API_VERSION], and an error if its type has no namespace or is in a
+    /// namespace reserved for other registrations.
+    #[track_caller]
     pub fn register_plugin_arc(
         &mut self,
         plugin: Arc<dyn NodePlugin<S>>,
     ) -> Result<&mut Self, GraphError> {
-        let plugin_type = plugin.plugin_type().to_string();
-        if self.plugins.contains_key(&plugin_type) {
-            return Err(GraphError::CompilationError(format!(
-                "Plugin type '{}' is already registered",
-                plugin_type
-            )));
+        let provenance = PluginProvenance::caller();
+        check_api_version(plugin.as_ref())?;
+        let plugin_type = self.registration_type(plugin.plugin_type(), &provenance)?;
+        if let Some(existing) = self.plugins.get(&plugin_type) {
+            return Err(GraphError::PluginConflict {
+                plugin_type,
+                existing: existing.provenance.to_string(),
+                conflicting: provenance.to_string(),
+            });
         }
-        self.plugins.insert(plugin_type, plugin);
+        self.plugins
+            .insert(plugin_type, Registered { plugin, provenance });
         Ok(self)
     }
 
+    /// Register a plugin, replacing any plugin already registered for its `plugin_type`.
+    ///
+    /// Returns the replaced plugin, if there was one. Incompatible plugins are rejected
+    /// as by [`register_plugin_arc`](Self::register_plugin_arc).
+    #[track_caller]
+    pub fn register_plugin_override<P>(
+        &mut self,
+        plugin: P,
+    ) -> Result<Option<Arc<dyn NodePlugin<S>>>, GraphError>
+    where
+        P: NodePlugin<S> + 'static,
+    {
+        let provenance = PluginProvenance::caller();
+        check_api_version(&plugin)?;
+        let plugin_type = self.registration_type(plugin.plugin_type(), &provenance)?;
+        let plugin = Arc::new(plugin);
+        Ok(self
+            .plugins
+            .insert(plugin_type, Registered { plugin, provenance })
+            .map(|replaced| replaced.plugin))
+    }
+
     /// Return true when a plugin type is registered.
     pub fn contains(&self, plugin_type: &str) -> bool {
-        self.plugins.contains_key(plugin_type)
+        self.plugins.contains_key(&*self.lookup_type(plugin_type))
     }
 
     /// Unregister a plugin by type (dynamic unloading). Returns true if the plugin was removed.
     pub fn unregister_plugin(&mut self, plugin_type: &str) -> bool {
-        self.plugins.remove(plugin_type).is_some()
+        let plugin_type = self.lookup_type(plugin_type).into_owned();
+        self.plugins.remove(&plugin_type).is_some()
     }
 
     /// Return registered plugin types in stable order.
@@ -87,18 +405,369 @@ impl<S: State> NodePluginRegistry<S> {
         plugin_types
     }
 
+    /// Where the plugin registered for `plugin_type` was registered from.
+    pub fn provenance(&self, plugin_type: &str) -> Option<&PluginProvenance> {
+        self.plugins
+            .get(&*self.lookup_type(plugin_type))
+            .map(|registered| &registered.provenance)
+    }
+
     /// Build a node from a plugin registration and runtime configuration.
+    ///
+    /// Placeholders in the config are expanded, then it is validated against the
+    /// plugin's config schema, if it has one.
     pub fn create_node(
         &self,
         name: &str,
         plugin_type: &str,
         config: &Value,
     ) -> Result<Arc<dyn Node<S>>, GraphError> {
-        let plugin = self.plugins.get(plugin_type).ok_or_else(|| {
-            GraphError::CompilationError(format!("Plugin type '{}' is not registered", plugin_type))
-        })?;
-        plugin.create_node(name, config)
+        let plugin = self.plugin(plugin_type)?;
+        let resolved = self.resolve_config(plugin.as_ref(), config)?;
+        plugin.create_node(name, &resolved)
+    }
+
+    /// Validate a config payload against the plugin's config schema.
+    ///
+    /// Placeholders are expanded first, as in [`create_node`](Self::create_node). Returns
+    /// `GraphError::InvalidConfig` listing every violation, showing the values of
+    /// placeholders as written; plugins without a schema accept any config here and
+    /// validate it when the node is created.
+    pub fn validate_config(&self, plugin_type: &str, config: &Value) -> Result<(), GraphError> {
+        self.resolve_config(self.plugin(plugin_type)?.as_ref(), config)
+            .map(drop)
+    }
+
+    /// Expand placeholders in `config` and validate the result, keeping resolved values
+    /// out of validation errors
+    fn resolve_config(
+        &self,
+        plugin: &dyn NodePlugin<S>,
+        config: &Value,
+    ) -> Result<Value, GraphError> {
+        let resolved = interpolate_config(config, self.secrets.as_ref())?;
+        match validate_against_schema(plugin, &resolved) {
+            Ok(()) => Ok(resolved),
+            Err(GraphError::InvalidConfig {
+                plugin_type,
+                mut errors,
+            }) => {
+                for error in &mut errors {
+                    if resolved.pointer(&error.path) != config.pointer(&error.path) {
+                        error.got = config.pointer(&error.path).cloned().unwrap_or_default();
+                    }
+                }
+                Err(GraphError::InvalidConfig {
+                    plugin_type,
+                    errors,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Describe a registered plugin: its type, name, description, version, metadata and
+    /// config schema.
+    pub fn describe(&self, plugin_type: &str) -> Option<PluginDescriptor> {
+        let plugin_type = self.lookup_type(plugin_type);
+        self.plugins
+            .get(&*plugin_type)
+            .map(|registered| PluginDescriptor::of(&plugin_type, registered.plugin.as_ref()))
+    }
+
+    /// Describe every registered plugin, ordered by plugin type.
+    pub fn list(&self) -> Vec<PluginDescriptor> {
+        let mut descriptors: Vec<_> = self
+            .plugins
+            .iter()
+            .map(|(plugin_type, registered)| {
+                PluginDescriptor::of(plugin_type, registered.plugin.as_ref())
+            })
+            .collect();
+        descriptors.sort_by(|a, b| a.plugin_type.cmp(&b.plugin_type));
+        descriptors
+    }
+
+    /// An empty registry with this registry's legacy setting, to register a library's
+    /// plugins into before they are [merged](Self::merge)
+    #[cfg(feature = "dynamic-plugins")]
+    pub(super) fn staging(&self) -> Self {
+        let mut staging = Self::new();
+        staging.legacy_plugin_types = self.legacy_plugin_types;
+        staging
+    }
+
+    /// Move the plugins and routers of `other`, loaded from `library`, into this
+    /// registry, returning the added plugin types; nothing is moved if one of their types
+    /// is already registered or in a namespace reserved for other registrations.
+    #[cfg(feature = "dynamic-plugins")]
+    pub(super) fn merge(
+        &mut self,
+        mut other: Self,
+        library: &std::path::Path,
+    ) -> Result<Vec<String>, GraphError> {
+        for registered in other.plugins.values_mut() {
+            registered.provenance.library = Some(library.to_path_buf());
+        }
+        for registered in other.routers.values_mut() {
+            registered.provenance.library = Some(library.to_path_buf());
+        }
+        for (plugin_type, registered) in &other.plugins {
+            self.check_namespace(plugin_type, &registered.provenance)?;
+            if let Some(existing) = self.plugins.get(plugin_type) {
+                return Err(GraphError::PluginConflict {
+                    plugin_type: plugin_type.clone(),
+                    existing: existing.provenance.to_string(),
+                    conflicting: registered.provenance.to_string(),
+                });
+            }
+        }
+        for (router_type, registered) in &other.routers {
+            self.check_namespace(router_type, &registered.provenance)?;
+            if let Some(existing) = self.routers.get(router_type) {
+                return Err(GraphError::PluginConflict {
+                    plugin_type: router_type.clone(),
+                    existing: existing.provenance.to_string(),
+                    conflicting: registered.provenance.to_string(),
+                });
+            }
+        }
+        let plugin_types = other.plugin_types();
+        self.plugins.extend(other.plugins);
+        self.routers.extend(other.routers);
+        Ok(plugin_types)
+    }
+
+    fn plugin(&self, plugin_type: &str) -> Result<&Arc<dyn NodePlugin<S>>, GraphError> {
+        self.plugins
+            .get(&*self.lookup_type(plugin_type))
+            .map(|registered| &registered.plugin)
+            .ok_or_else(|| {
+                GraphError::CompilationError(format!(
+                    "Plugin type '{}' is not registered",
+                    plugin_type
+                ))
+            })
+    }
+
+    /// The type to register a plugin or router of type `plugin_type` under, checked
+    /// against the reserved namespaces
+    fn registration_type(
+        &self,
+        plugin_type: &str,
+        provenance: &PluginProvenance,
+    ) -> Result<String, GraphError> {
+        let plugin_type = match parse_plugin_type(plugin_type) {
+            Ok(_) => plugin_type.to_string(),
+            Err(_) if self.legacy_plugin_types && is_plugin_type_segment(plugin_type) => {
+                log::warn!(
+                    "Plugin type '{}' registered at {} has no namespace; filing it as '{}/{}'. \
+                     Bare plugin types are deprecated, use 'namespace/name'",
+                    plugin_type,
+                    provenance,
+                    LEGACY_PLUGIN_NAMESPACE,
+                    plugin_type
+                );
+                format!("{}/{}", LEGACY_PLUGIN_NAMESPACE, plugin_type)
+            }
+            Err(reason) => {
+                return Err(GraphError::CompilationError(format!(
+                    "Invalid plugin type '{}' registered at {}: {}",
+                    plugin_type, provenance, reason
+                )))
+            }
+        };
+        self.check_namespace(&plugin_type, provenance)?;
+        Ok(plugin_type)
     }
+
+    /// Reject a registration of `plugin_type` from `provenance` if its namespace is
+    /// reserved and the guard refuses it
+    fn check_namespace(
+        &self,
+        plugin_type: &str,
+        provenance: &PluginProvenance,
+    ) -> Result<(), GraphError> {
+        let Some((namespace, _)) = plugin_type.split_once('/') else {
+            return Ok(());
+        };
+        match self.namespaces.get(namespace) {
+            Some(reserved) => check_guard(namespace, reserved, plugin_type, provenance),
+            None => Ok(()),
+        }
+    }
+
+    /// The registered type a lookup of `plugin_type` refers to: `legacy/NAME` for a bare
+    /// `NAME` when legacy types are allowed
+    fn lookup_type<'a>(&self, plugin_type: &'a str) -> Cow<'a, str> {
+        if !self.legacy_plugin_types || plugin_type.contains('/') {
+            return Cow::Borrowed(plugin_type);
+        }
+        log::warn!(
+            "Plugin type '{}' has no namespace; resolving it as '{}/{}'. \
+             Bare plugin types are deprecated, use 'namespace/name'",
+            plugin_type,
+            LEGACY_PLUGIN_NAMESPACE,
+            plugin_type
+        );
+        Cow::Owned(format!("{}/{}", LEGACY_PLUGIN_NAMESPACE, plugin_type))
+    }
+
+    /// Register a router plugin by value.
+    ///
+    /// Returns an error if the same router `plugin_type` is already registered.
+    #[track_caller]
+    pub fn register_router<P>(&mut self, router: P) -> Result<&mut Self, GraphError>
+    where
+        P: RouterPlugin<S> + 'static,
+    {
+        self.register_router_arc(Arc::new(router))
+    }
+
+    /// Register a shared router plugin implementation.
+    ///
+    /// Returns `GraphError::PluginConflict` if the same router `plugin_type` is already
+    /// registered, and an error if its type has no namespace or is in a namespace
+    /// reserved for other registrations.
+    #[track_caller]
+    pub fn register_router_arc(
+        &mut self,
+        router: Arc<dyn RouterPlugin<S>>,
+    ) -> Result<&mut Self, GraphError> {
+        let provenance = PluginProvenance::caller();
+        let plugin_type = self.registration_type(router.plugin_type(), &provenance)?;
+        if let Some(existing) = self.routers.get(&plugin_type) {
+            return Err(GraphError::PluginConflict {
+                plugin_type,
+                existing: existing.provenance.to_string(),
+                conflicting: provenance.to_string(),
+            });
+        }
+        self.routers.insert(
+            plugin_type,
+            Registered {
+                plugin: router,
+                provenance,
+            },
+        );
+        Ok(self)
+    }
+
+    /// Return true when a router type is registered.
+    pub fn contains_router(&self, plugin_type: &str) -> bool {
+        self.routers.contains_key(&*self.lookup_type(plugin_type))
+    }
+
+    /// Build a router from a router registration and runtime configuration.
+    ///
+    /// Placeholders in the config are expanded first.
+    pub fn create_router(
+        &self,
+        plugin_type: &str,
+        config: &Value,
+    ) -> Result<PluginRouter<S>, GraphError> {
+        let router = self
+            .routers
+            .get(&*self.lookup_type(plugin_type))
+            .ok_or_else(|| {
+                GraphError::CompilationError(format!(
+                    "Router type '{}' is not registered",
+                    plugin_type
+                ))
+            })?;
+        router
+            .plugin
+            .create_router(&interpolate_config(config, self.secrets.as_ref())?)
+    }
+
+    /// Keys the router built from `config` can return, if its plugin publishes them
+    pub(crate) fn router_keys(&self, plugin_type: &str, config: &Value) -> Option<Vec<String>> {
+        let config = interpolate_config(config, self.secrets.as_ref()).ok()?;
+        self.routers
+            .get(&*self.lookup_type(plugin_type))?
+            .plugin
+            .route_keys(&config)
+    }
+}
+
+/// Reject a registration in a reserved namespace that the namespace's guard refuses
+fn check_guard(
+    namespace: &str,
+    reserved: &ReservedNamespace,
+    plugin_type: &str,
+    provenance: &PluginProvenance,
+) -> Result<(), GraphError> {
+    if (reserved.guard)(provenance) {
+        return Ok(());
+    }
+    Err(GraphError::PluginNamespaceReserved {
+        namespace: namespace.to_string(),
+        plugin_type: plugin_type.to_string(),
+        reserved_at: reserved.provenance.to_string(),
+        rejected: provenance.to_string(),
+    })
+}
+
+/// Reject plugins built against another plugin API version
+fn check_api_version<S: State>(plugin: &dyn NodePlugin<S>) -> Result<(), GraphError> {
+    if plugin.api_version() == PLUGIN_API_VERSION {
+        return Ok(());
+    }
+    Err(GraphError::PluginIncompatible {
+        plugin_type: plugin.plugin_type().to_string(),
+        expected: PLUGIN_API_VERSION,
+        got: plugin.api_version(),
+    })
+}
+
+/// Check `config` against the plugin's config schema, if it has one
+fn validate_against_schema<S: State>(
+    plugin: &dyn NodePlugin<S>,
+    config: &Value,
+) -> Result<(), GraphError> {
+    let Some(schema) = plugin.config_schema() else {
+        return Ok(());
+    };
+    let compiled = JSONSchema::compile(&schema).map_err(|e| {
+        GraphError::CompilationError(format!(
+            "Invalid config schema for plugin '{}': {}",
+            plugin.plugin_type(),
+            e
+        ))
+    })?;
+    let errors: Vec<ConfigValidationError> = match compiled.validate(config) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors
+            .map(|error| {
+                let mut path = error.instance_path.to_string();
+                if let ValidationErrorKind::Required { property } = &error.kind {
+                    path.push('/');
+                    path.push_str(property.as_str().unwrap_or_default());
+                    return ConfigValidationError {
+                        path,
+                        expected: "required property".to_string(),
+                        got: Value::Null,
+                    };
+                }
+                // Name the failed keyword with its value in the schema, e.g. `minimum 0`
+                let schema_path = error.schema_path.to_string();
+                let keyword = schema_path.rsplit('/').next().unwrap_or_default();
+                let expected = match schema.pointer(&schema_path) {
+                    Some(constraint) => format!("{} {}", keyword, constraint),
+                    None => error.to_string(),
+                };
+                ConfigValidationError {
+                    path,
+                    expected,
+                    got: error.instance.into_owned(),
+                }
+            })
+            .collect(),
+    };
+    Err(GraphError::InvalidConfig {
+        plugin_type: plugin.plugin_type().to_string(),
+        errors,
+    })
 }
 
 impl<S: State> Default for NodePluginRegistry<S> {
@@ -117,6 +786,10 @@ where
     F: Fn(&str, C) -> Result<Arc<dyn Node<S>>, GraphError> + Send + Sync + 'static,
 {
     plugin_type: String,
+    display_name: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+    config_schema: Option<Value>,
     factory: F,
     _state: PhantomData<S>,
     _config: PhantomData<C>,
@@ -131,11 +804,39 @@ where
     pub fn new(plugin_type: impl Into<String>, factory: F) -> Self {
         Self {
             plugin_type: plugin_type.into(),
+            display_name: None,
+            description: None,
+            version: None,
+            config_schema: None,
             factory,
             _state: PhantomData,
             _config: PhantomData,
         }
     }
+
+    /// Set the human-readable name listed in the plugin's [PluginDescriptor].
+    pub fn with_display_name(mut self, name: impl Into<String>) -> Self {
+        self.display_name = Some(name.into());
+        self
+    }
+
+    /// Set the description listed in the plugin's [PluginDescriptor].
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the crate version listed in the plugin's [PluginDescriptor].
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Publish a JSON Schema for the config, validated before it is deserialized.
+    pub fn with_config_schema(mut self, schema: Value) -> Self {
+        self.config_schema = Some(schema);
+        self
+    }
 }
 
 impl<S: State, C, F> NodePlugin<S> for TypedNodePlugin<S, C, F>
@@ -147,6 +848,26 @@ where
         &self.plugin_type
     }
 
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.plugin_type)
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn config_schema(&self) -> Option<Value> {
+        self.config_schema.clone()
+    }
+
     fn create_node(&self, name: &str, config: &Value) -> Result<Arc<dyn Node<S>>, GraphError> {
         let typed_config: C = serde_json::from_value(config.clone()).map_err(|e| {
             GraphError::CompilationError(format!(
@@ -170,6 +891,66 @@ where
     TypedNodePlugin::new(plugin_type, factory)
 }
 
+/// Typed router adapter that deserializes config into a concrete Rust type before building the router.
+pub struct TypedRouterPlugin<S: State, C, F>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+    F: Fn(C) -> Result<PluginRouter<S>, GraphError> + Send + Sync + 'static,
+{
+    plugin_type: String,
+    factory: F,
+    _state: PhantomData<S>,
+    _config: PhantomData<C>,
+}
+
+impl<S: State, C, F> TypedRouterPlugin<S, C, F>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+    F: Fn(C) -> Result<PluginRouter<S>, GraphError> + Send + Sync + 'static,
+{
+    /// Create a new typed router adapter.
+    pub fn new(plugin_type: impl Into<String>, factory: F) -> Self {
+        Self {
+            plugin_type: plugin_type.into(),
+            factory,
+            _state: PhantomData,
+            _config: PhantomData,
+        }
+    }
+}
+
+impl<S: State, C, F> RouterPlugin<S> for TypedRouterPlugin<S, C, F>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+    F: Fn(C) -> Result<PluginRouter<S>, GraphError> + Send + Sync + 'static,
+{
+    fn plugin_type(&self) -> &str {
+        &self.plugin_type
+    }
+
+    fn create_router(&self, config: &Value) -> Result<PluginRouter<S>, GraphError> {
+        let typed_config: C = serde_json::from_value(config.clone()).map_err(|e| {
+            GraphError::CompilationError(format!(
+                "Invalid config for router '{}': {}",
+                self.plugin_type, e
+            ))
+        })?;
+        (self.factory)(typed_config)
+    }
+}
+
+/// Convenience constructor for [TypedRouterPlugin].
+pub fn typed_router_plugin<S: State, C, F>(
+    plugin_type: impl Into<String>,
+    factory: F,
+) -> TypedRouterPlugin<S, C, F>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+    F: Fn(C) -> Result<PluginRouter<S>, GraphError> + Send + Sync + 'static,
+{
+    TypedRouterPlugin::new(plugin_type, factory)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,20 +971,23 @@ mod tests {
     fn build_registry() -> NodePluginRegistry<MessagesState> {
         let mut registry = NodePluginRegistry::new();
         registry
-            .register_plugin(typed_node_plugin("echo", |name, config: EchoConfig| {
-                let prefix = config.prefix;
-                Ok(Arc::new(function_node(
-                    name.to_string(),
-                    move |_state: &MessagesState| {
-                        let content = format!("{} from plugin", prefix);
-                        async move {
-                            Ok(messages_state_update(vec![Message::new_ai_message(
-                                &content,
-                            )]))
-                        }
-                    },
-                )))
-            }))
+            .register_plugin(typed_node_plugin(
+                "test/echo",
+                |name, config: EchoConfig| {
+                    let prefix = config.prefix;
+                    Ok(Arc::new(function_node(
+                        name.to_string(),
+                        move |_state: &MessagesState| {
+                            let content = format!("{} from plugin", prefix);
+                            async move {
+                                Ok(messages_state_update(vec![Message::new_ai_message(
+                                    &content,
+                                )]))
+                            }
+                        },
+                    )))
+                },
+            ))
             .expect("register plugin");
         registry
     }
@@ -214,7 +998,7 @@ mod tests {
         graph
             .add_plugin_node(
                 "echo-node",
-                "echo",
+                "test/echo",
                 serde_json::json!({"prefix": "hello"}),
                 &registry,
             )
@@ -228,7 +1012,7 @@ mod tests {
     fn registry_rejects_duplicate_plugin_types() {
         let mut registry = NodePluginRegistry::<MessagesState>::new();
         registry
-            .register_plugin(typed_node_plugin("echo", |_name, _config: EchoConfig| {
+            .register_plugin(typed_node_plugin("test/echo", |_name, _config: EchoConfig| {
                 Ok(Arc::new(function_node(
                     "echo",
                     |_state: &MessagesState| async move { Ok(messages_state_update(Vec::new())) },
@@ -237,7 +1021,7 @@ mod tests {
             .expect("first register");
 
         let err = match registry.register_plugin(typed_node_plugin(
-            "echo",
+            "test/echo",
             |_name, _config: EchoConfig| {
                 Ok(Arc::new(function_node(
                     "echo-2",
@@ -249,32 +1033,347 @@ mod tests {
             Err(err) => err,
         };
 
-        assert!(matches!(err, GraphError::CompilationError(_)));
+        match err {
+            GraphError::PluginConflict {
+                plugin_type,
+                existing,
+                conflicting,
+            } => {
+                assert_eq!(plugin_type, "test/echo");
+                assert!(existing.starts_with(file!()), "{}", existing);
+                assert!(conflicting.starts_with(file!()), "{}", conflicting);
+                assert_ne!(existing, conflicting);
+            }
+            err => panic!("expected a conflict, got {}", err),
+        }
+
+        let replaced = registry.register_plugin_override(typed_node_plugin(
+            "test/echo",
+            |_name, _config: EchoConfig| {
+                Ok(Arc::new(function_node(
+                    "echo-3",
+                    |_state: &MessagesState| async move { Ok(messages_state_update(Vec::new())) },
+                )))
+            },
+        ));
+        assert!(replaced.expect("compatible plugin").is_some());
+        assert_eq!(registry.plugin_types(), vec!["test/echo".to_string()]);
+    }
+
+    fn noop_plugin(plugin_type: &str) -> impl NodePlugin<MessagesState> {
+        typed_node_plugin(plugin_type, |name, _config: Value| {
+            Ok(Arc::new(function_node(
+                name.to_string(),
+                |_state: &MessagesState| async move { Ok(messages_state_update(Vec::new())) },
+            )))
+        })
+    }
+
+    #[test]
+    fn registry_requires_namespaced_types_unless_legacy_types_are_allowed() {
+        assert_eq!(parse_plugin_type("acme/echo"), Ok(("acme", "echo")));
+        for plugin_type in ["echo", "/echo", "acme/", "acme/echo/v2", "acme corp/echo"] {
+            assert!(parse_plugin_type(plugin_type).is_err(), "{}", plugin_type);
+        }
+
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        let err = registry.register_plugin(noop_plugin("echo")).err().unwrap();
+        assert!(
+            err.to_string().contains("expected 'namespace/name'"),
+            "{}",
+            err
+        );
+        assert!(registry.register_plugin(noop_plugin("acme/")).is_err());
+
+        registry.allow_legacy_plugin_types();
+        registry.register_plugin(noop_plugin("echo")).unwrap();
+        assert_eq!(registry.plugin_types(), ["legacy/echo"]);
+        assert!(registry.contains("echo"));
+        assert_eq!(
+            registry.describe("echo").unwrap().plugin_type,
+            "legacy/echo"
+        );
+        assert!(registry
+            .create_node("node", "echo", &serde_json::json!({}))
+            .is_ok());
+        assert!(matches!(
+            registry.register_plugin(noop_plugin("legacy/echo")),
+            Err(GraphError::PluginConflict { .. })
+        ));
+    }
+
+    #[test]
+    fn reserved_namespaces_only_accept_guarded_registrations() {
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        registry.register_plugin(noop_plugin("acme/early")).unwrap();
+        registry
+            .register_namespace("acme", |source| source.library.is_some())
+            .err()
+            .unwrap();
+
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        registry
+            .register_namespace("acme", |source| source.library.is_some())
+            .unwrap();
+        let err = registry
+            .register_plugin(noop_plugin("acme/echo"))
+            .err()
+            .unwrap();
+        match &err {
+            GraphError::PluginNamespaceReserved {
+                namespace,
+                plugin_type,
+                reserved_at,
+                rejected,
+            } => {
+                assert_eq!(
+                    (namespace.as_str(), plugin_type.as_str()),
+                    ("acme", "acme/echo")
+                );
+                assert!(reserved_at.starts_with(file!()), "{}", reserved_at);
+                assert!(rejected.starts_with(file!()), "{}", rejected);
+            }
+            err => panic!("expected a reserved namespace, got {}", err),
+        }
+        assert!(registry
+            .register_router(typed_router_plugin("acme/route", |_config: Value| {
+                Ok(Arc::new(|_state: &MessagesState| "next".to_string()))
+            }))
+            .is_err());
+        assert!(registry.register_plugin(noop_plugin("other/echo")).is_ok());
+        assert!(registry.register_namespace("acme", |_| true).is_err());
+        assert_eq!(
+            registry.provenance("other/echo").unwrap().library,
+            None::<PathBuf>
+        );
+    }
+
+    /// A plugin built against plugin API version 0
+    struct LegacyPlugin;
+
+    impl NodePlugin<MessagesState> for LegacyPlugin {
+        fn plugin_type(&self) -> &str {
+            "test/legacy"
+        }
+
+        fn api_version(&self) -> u32 {
+            0
+        }
+
+        fn create_node(
+            &self,
+            name: &str,
+            _config: &Value,
+        ) -> Result<Arc<dyn Node<MessagesState>>, GraphError> {
+            Ok(Arc::new(function_node(
+                name.to_string(),
+                |_state: &MessagesState| async move { Ok(messages_state_update(Vec::new())) },
+            )))
+        }
+    }
+
+    #[test]
+    fn registry_rejects_plugins_for_another_api_version() {
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        let err = match registry.register_plugin(LegacyPlugin) {
+            Ok(_) => panic!("legacy plugin should be rejected"),
+            Err(err) => err,
+        };
+        assert!(matches!(
+            err,
+            GraphError::PluginIncompatible { ref plugin_type, expected: PLUGIN_API_VERSION, got: 0 }
+                if plugin_type == "test/legacy"
+        ));
+        assert!(registry.register_plugin_override(LegacyPlugin).is_err());
+        assert!(!registry.contains("test/legacy"));
+    }
+
+    #[test]
+    fn registry_lists_plugin_descriptors() {
+        let mut registry = build_registry();
+        registry
+            .register_plugin(
+                typed_node_plugin("test/shout", |name, _config: EchoConfig| {
+                    Ok(Arc::new(function_node(
+                        name.to_string(),
+                        |_state: &MessagesState| async move { Ok(messages_state_update(Vec::new())) },
+                    )))
+                })
+                .with_display_name("Shout")
+                .with_description("Appends the prefix in capitals")
+                .with_version("1.2.0"),
+            )
+            .expect("register plugin");
+
+        let descriptors = registry.list();
+        assert_eq!(
+            descriptors
+                .iter()
+                .map(|d| d.plugin_type.as_str())
+                .collect::<Vec<_>>(),
+            vec!["test/echo", "test/shout"]
+        );
+        assert_eq!(descriptors[0].name, "test/echo");
+        assert_eq!(descriptors[0].description, None);
+        assert_eq!(descriptors[1].name, "Shout");
+        assert_eq!(
+            descriptors[1].description.as_deref(),
+            Some("Appends the prefix in capitals")
+        );
+        assert_eq!(descriptors[1].version.as_deref(), Some("1.2.0"));
+        assert_eq!(descriptors[1].api_version, PLUGIN_API_VERSION);
+        assert_eq!(
+            registry.describe("test/shout").as_ref(),
+            Some(&descriptors[1])
+        );
     }
 
     #[test]
     fn registry_unregister_plugin_removes_and_prevents_create() {
         let mut registry = build_registry();
-        assert!(registry.contains("echo"));
-        assert!(registry.unregister_plugin("echo"));
-        assert!(!registry.contains("echo"));
-        assert!(!registry.unregister_plugin("echo"));
-        let result = registry.create_node("x", "echo", &serde_json::json!({"prefix": "hi"}));
+        assert!(registry.contains("test/echo"));
+        assert!(registry.unregister_plugin("test/echo"));
+        assert!(!registry.contains("test/echo"));
+        assert!(!registry.unregister_plugin("test/echo"));
+        let result = registry.create_node("x", "test/echo", &serde_json::json!({"prefix": "hi"}));
         assert!(matches!(result, Err(GraphError::CompilationError(_))));
     }
 
     #[test]
     fn registry_validates_typed_config_at_runtime() {
         let registry = build_registry();
-        let err =
-            match registry.create_node("broken", "echo", &serde_json::json!({"unknown": true})) {
-                Ok(_) => panic!("invalid config should fail"),
-                Err(err) => err,
-            };
+        let err = match registry.create_node(
+            "broken",
+            "test/echo",
+            &serde_json::json!({"unknown": true}),
+        ) {
+            Ok(_) => panic!("invalid config should fail"),
+            Err(err) => err,
+        };
 
         assert!(matches!(err, GraphError::CompilationError(_)));
     }
 
+    #[test]
+    fn registry_validates_config_against_plugin_schema() {
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "prefix": { "type": "string", "minLength": 1 } },
+            "required": ["prefix"]
+        });
+        registry
+            .register_plugin(
+                typed_node_plugin("test/echo", |name, config: EchoConfig| {
+                    let prefix = config.prefix;
+                    Ok(Arc::new(function_node(
+                        name.to_string(),
+                        move |_state: &MessagesState| {
+                            let content = prefix.clone();
+                            async move {
+                                Ok(messages_state_update(vec![Message::new_ai_message(
+                                    content,
+                                )]))
+                            }
+                        },
+                    )))
+                })
+                .with_config_schema(schema.clone()),
+            )
+            .expect("register plugin");
+
+        assert!(registry
+            .validate_config("test/echo", &serde_json::json!({"prefix": "hi"}))
+            .is_ok());
+        let errors = match registry.validate_config("test/echo", &serde_json::json!({"prefix": 3}))
+        {
+            Err(GraphError::InvalidConfig { errors, .. }) => errors,
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        };
+        assert_eq!(
+            errors,
+            vec![ConfigValidationError {
+                path: "/prefix".to_string(),
+                expected: "type \"string\"".to_string(),
+                got: serde_json::json!(3),
+            }]
+        );
+
+        // Validation runs before the constructor, so serde never sees the payload
+        let err = match registry.create_node("echo-node", "test/echo", &serde_json::json!({})) {
+            Ok(_) => panic!("invalid config should fail"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Invalid config for plugin 'test/echo': /prefix: expected required property, got null"
+        );
+
+        let description = registry.describe("test/echo").expect("registered");
+        assert_eq!(description.config_schema, Some(schema));
+        assert!(registry.describe("missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn registry_expands_secrets_without_leaking_them() {
+        let mut registry = build_registry();
+        registry.set_secret_resolver(HashMap::from([(
+            "PREFIX".to_string(),
+            "Hidden".to_string(),
+        )]));
+
+        let node = registry
+            .create_node(
+                "echo",
+                "test/echo",
+                &serde_json::json!({"prefix": "${secret:PREFIX}"}),
+            )
+            .unwrap();
+        let update = node.invoke(&MessagesState::new()).await.unwrap();
+        let state = MessagesState::new().apply_update(&update).unwrap();
+        assert_eq!(state.messages[0].content, "Hidden from plugin");
+
+        let err = match registry.create_node(
+            "echo",
+            "test/echo",
+            &serde_json::json!({"prefix": "${secret:NOPE}"}),
+        ) {
+            Ok(_) => panic!("missing secret should fail"),
+            Err(err) => err,
+        };
+        assert!(matches!(
+            err,
+            GraphError::UnresolvedPlaceholder { ref placeholder, .. } if placeholder == "${secret:NOPE}"
+        ));
+
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        registry.set_secret_resolver(HashMap::from([("TOKEN".to_string(), "sk-123".to_string())]));
+        registry
+            .register_plugin(
+                typed_node_plugin("test/echo", |name, _config: EchoConfig| {
+                    Ok(Arc::new(function_node(
+                        name.to_string(),
+                        |_state: &MessagesState| async move { Ok(messages_state_update(vec![])) },
+                    )))
+                })
+                .with_config_schema(serde_json::json!({
+                    "type": "object",
+                    "properties": { "prefix": { "type": "string", "maxLength": 3 } }
+                })),
+            )
+            .unwrap();
+        let err = registry
+            .validate_config(
+                "test/echo",
+                &serde_json::json!({"prefix": "${secret:TOKEN}"}),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid config for plugin 'test/echo': /prefix: expected maxLength 3, got \"${secret:TOKEN}\""
+        );
+    }
+
     #[tokio::test]
     async fn graph_can_execute_runtime_registered_plugin_nodes() {
         let graph = build_plugin_graph().await;
@@ -283,4 +1382,72 @@ mod tests {
         assert_eq!(result.messages.len(), 1);
         assert_eq!(result.messages[0].content, "hello from plugin");
     }
+
+    #[derive(Clone, Debug, Default, serde::Serialize, Deserialize)]
+    struct TodoState {
+        todos: Vec<String>,
+        remaining: u32,
+    }
+
+    impl State for TodoState {
+        fn reducer(field: &str) -> crate::graph::Reducer {
+            match field {
+                "todos" => crate::graph::Reducer::Append,
+                _ => crate::graph::Reducer::Overwrite,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn plugins_run_on_custom_state_types() {
+        let mut registry = NodePluginRegistry::<TodoState>::new();
+        registry
+            .register_plugin(typed_node_plugin(
+                "test/todo",
+                |name, config: EchoConfig| {
+                    Ok(Arc::new(function_node(
+                        name.to_string(),
+                        move |state: &TodoState| {
+                            let mut update = crate::graph::state_update(
+                                crate::state_field!(TodoState, todos),
+                                vec![config.prefix.clone()],
+                            );
+                            update.extend(crate::graph::state_update(
+                                crate::state_field!(TodoState, remaining),
+                                state.remaining - 1,
+                            ));
+                            async move { Ok(update) }
+                        },
+                    )))
+                },
+            ))
+            .expect("register plugin");
+
+        let mut graph = StateGraph::<TodoState>::new();
+        for (name, prefix) in [("first", "write"), ("second", "review")] {
+            graph
+                .add_plugin_node(
+                    name,
+                    "test/todo",
+                    serde_json::json!({ "prefix": prefix }),
+                    &registry,
+                )
+                .expect("plugin node");
+        }
+        graph.add_edge(START, "first");
+        graph.add_edge("first", "second");
+        graph.add_edge("second", END);
+
+        let state = graph
+            .compile()
+            .expect("compile")
+            .invoke(TodoState {
+                todos: Vec::new(),
+                remaining: 5,
+            })
+            .await
+            .expect("invoke");
+        assert_eq!(state.todos, ["write", "review"]);
+        assert_eq!(state.remaining, 3);
+    }
 }
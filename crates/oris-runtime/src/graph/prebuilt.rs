@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::json;
+
+use super::{
+    compiled::CompiledGraph,
+    edge::{END, START},
+    error::GraphError,
+    graph::StateGraph,
+    node::{function_node, tool_node},
+    persistence::checkpointer::CheckpointerBox,
+    state::{messages_state_update, MessagesState},
+};
+use crate::{
+    language_models::{llm::LLM, options::CallOptions},
+    schemas::{
+        messages::{Message, MessageType},
+        FunctionCallResponse, FunctionDefinition,
+    },
+    tools::Tool,
+};
+
+/// Name of the model node in a graph built by [`create_react_agent`]
+pub const REACT_AGENT_NODE: &str = "agent";
+
+/// Name of the tool node in a graph built by [`create_react_agent`]
+pub const REACT_TOOLS_NODE: &str = "tools";
+
+/// Builds the messages sent to the model from the agent state
+pub type StateModifier = Arc<dyn Fn(&MessagesState) -> Vec<Message> + Send + Sync>;
+
+/// Options for [`create_react_agent`]
+#[derive(Clone, Default)]
+pub struct ReactAgentOptions {
+    /// System message prepended to every model call
+    pub system_prompt: Option<String>,
+    /// Model calls allowed per user turn before the agent stops
+    pub max_iterations: Option<usize>,
+    /// Pause before running tools so the calls can be approved (needs a checkpointer)
+    pub interrupt_before_tools: bool,
+    /// Applied to the state before each model call; defaults to all messages
+    pub state_modifier: Option<StateModifier>,
+    /// Checkpointer the compiled graph saves its state to
+    pub checkpointer: Option<CheckpointerBox<MessagesState>>,
+}
+
+impl ReactAgentOptions {
+    /// Create options with no system prompt, iteration limit or checkpointer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the system prompt
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Set the number of model calls allowed per user turn
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Pause before every tool run
+    pub fn with_interrupt_before_tools(mut self, interrupt: bool) -> Self {
+        self.interrupt_before_tools = interrupt;
+        self
+    }
+
+    /// Set the closure that builds the model's input messages from the state
+    pub fn with_state_modifier<F>(mut self, modifier: F) -> Self
+    where
+        F: Fn(&MessagesState) -> Vec<Message> + Send + Sync + 'static,
+    {
+        self.state_modifier = Some(Arc::new(modifier));
+        self
+    }
+
+    /// Set the checkpointer
+    pub fn with_checkpointer(mut self, checkpointer: CheckpointerBox<MessagesState>) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+}
+
+/// Build a ReAct-style agent: the model is called in a loop, running the tools it
+/// asks for, until it answers without tool calls
+///
+/// The graph is `START -> agent`, `agent -> tools` when the last AI message has tool
+/// calls (else `END`), and `tools -> agent`. The tools are offered to the model as
+/// functions, and tool errors are reported back to it as tool messages. When
+/// `max_iterations` model calls have been made since the last human message, the
+/// agent answers with a stop message instead of calling the model again.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use oris_runtime::graph::{create_react_agent, MessagesState, ReactAgentOptions};
+/// use oris_runtime::schemas::messages::Message;
+///
+/// let agent = create_react_agent(
+///     llm,
+///     vec![calculator],
+///     ReactAgentOptions::new()
+///         .with_system_prompt("You are a careful assistant")
+///         .with_max_iterations(5),
+/// )?;
+/// let state = agent
+///     .invoke(MessagesState::with_messages(vec![Message::new_human_message("2 + 2?")]))
+///     .await?;
+/// ```
+pub fn create_react_agent<L: LLM + 'static>(
+    mut model: L,
+    tools: Vec<Arc<dyn Tool>>,
+    options: ReactAgentOptions,
+) -> Result<CompiledGraph<MessagesState>, GraphError> {
+    if !tools.is_empty() {
+        let functions = tools
+            .iter()
+            .map(FunctionDefinition::from_langchain_tool)
+            .collect();
+        model.add_options(CallOptions::new().with_functions(functions));
+    }
+    let model: Arc<dyn LLM> = Arc::new(model);
+    let ReactAgentOptions {
+        system_prompt,
+        max_iterations,
+        interrupt_before_tools,
+        state_modifier,
+        checkpointer,
+    } = options;
+
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph.add_node(
+        REACT_AGENT_NODE,
+        function_node(REACT_AGENT_NODE, move |state: &MessagesState| {
+            let model = model.clone();
+            let iterations = model_calls_this_turn(state);
+            let mut messages: Vec<Message> = system_prompt
+                .iter()
+                .map(Message::new_system_message)
+                .collect();
+            match &state_modifier {
+                Some(modifier) => messages.extend(modifier(state)),
+                None => messages.extend(state.messages.iter().cloned()),
+            }
+            async move {
+                if let Some(limit) = max_iterations.filter(|limit| iterations >= *limit) {
+                    return Ok(messages_state_update(vec![Message::new_ai_message(
+                        format!(
+                            "Sorry, I stopped after reaching the limit of {} iterations.",
+                            limit
+                        ),
+                    )]));
+                }
+                let generation = model.generate(&messages).await?.generation;
+                let message = match serde_json::from_str::<Vec<FunctionCallResponse>>(&generation) {
+                    Ok(calls) if !calls.is_empty() => {
+                        Message::new_ai_message("").with_tool_calls(json!(calls))
+                    }
+                    _ => Message::new_ai_message(generation),
+                };
+                Ok(messages_state_update(vec![message]))
+            }
+        }),
+    )?;
+    graph.add_node(REACT_TOOLS_NODE, tool_node(tools))?;
+
+    let mut mapping = HashMap::new();
+    mapping.insert(REACT_TOOLS_NODE.to_string(), REACT_TOOLS_NODE.to_string());
+    mapping.insert(END.to_string(), END.to_string());
+    graph.add_edge(START, REACT_AGENT_NODE);
+    graph.add_conditional_edges_sync(
+        REACT_AGENT_NODE,
+        |state: &MessagesState| {
+            let calls_tools = state.messages.last().is_some_and(|m| {
+                matches!(m.message_type, MessageType::AIMessage) && m.tool_calls.is_some()
+            });
+            if calls_tools { REACT_TOOLS_NODE } else { END }.to_string()
+        },
+        mapping,
+    );
+    graph.add_edge(REACT_TOOLS_NODE, REACT_AGENT_NODE);
+
+    match (checkpointer, interrupt_before_tools) {
+        (Some(checkpointer), true) => {
+            graph.compile_with_interrupts(checkpointer, &[REACT_TOOLS_NODE], &[])
+        }
+        (None, true) => Err(GraphError::CompilationError(
+            "interrupt_before_tools requires a checkpointer".to_string(),
+        )),
+        (checkpointer, false) => graph.compile_with_persistence(checkpointer, None),
+    }
+}
+
+/// Model calls made since the last human message
+fn model_calls_this_turn(state: &MessagesState) -> usize {
+    state
+        .messages
+        .iter()
+        .rev()
+        .take_while(|m| !matches!(m.message_type, MessageType::HumanMessage))
+        .filter(|m| matches!(m.message_type, MessageType::AIMessage))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use futures::Stream;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::error::ToolError;
+    use crate::graph::{InMemorySaver, RunnableConfig};
+    use crate::language_models::{GenerateResult, LLMError};
+    use crate::schemas::StreamData;
+
+    /// Model that replays scripted generations and records the messages it was sent
+    #[derive(Clone)]
+    struct ScriptedModel {
+        replies: Arc<Mutex<VecDeque<String>>>,
+        seen: Arc<Mutex<Vec<Vec<Message>>>>,
+    }
+
+    impl ScriptedModel {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Arc::new(Mutex::new(replies.iter().map(|r| r.to_string()).collect())),
+                seen: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLM for ScriptedModel {
+        async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
+            self.seen.lock().unwrap().push(messages.to_vec());
+            Ok(GenerateResult {
+                generation: self.replies.lock().unwrap().pop_front().unwrap_or_default(),
+                ..Default::default()
+            })
+        }
+
+        async fn stream(
+            &self,
+            _messages: &[Message],
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError>
+        {
+            Ok(Box::pin(futures::stream::empty()))
+        }
+    }
+
+    struct AddTool;
+
+    #[async_trait]
+    impl Tool for AddTool {
+        fn name(&self) -> String {
+            "add".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Adds two numbers".to_string()
+        }
+
+        async fn parse_input(&self, input: &str) -> Value {
+            serde_json::from_str(input).unwrap_or_default()
+        }
+
+        async fn run(&self, input: Value) -> Result<String, ToolError> {
+            let sum = input["a"].as_i64().unwrap_or(0) + input["b"].as_i64().unwrap_or(0);
+            Ok(sum.to_string())
+        }
+    }
+
+    const ADD_CALL: &str = r#"[{"id":"call_1","type":"function","function":{"name":"add","arguments":"{\"a\":2,\"b\":3}"}}]"#;
+
+    fn question() -> MessagesState {
+        MessagesState::with_messages(vec![Message::new_human_message("2 + 3?")])
+    }
+
+    fn contents(state: &MessagesState) -> Vec<String> {
+        state.messages.iter().map(|m| m.content.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn runs_model_tool_loop_until_answer() {
+        let model = ScriptedModel::new(&[ADD_CALL, "It is 5"]);
+        let agent = create_react_agent(
+            model.clone(),
+            vec![Arc::new(AddTool)],
+            ReactAgentOptions::new()
+                .with_system_prompt("Be brief")
+                .with_state_modifier(|s: &MessagesState| {
+                    s.messages.iter().rev().take(2).rev().cloned().collect()
+                }),
+        )
+        .unwrap();
+
+        let state = agent.invoke(question()).await.unwrap();
+        assert_eq!(contents(&state), vec!["2 + 3?", "", "5", "It is 5"]);
+
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1][0].content, "Be brief");
+        // The modifier keeps the last two messages: the tool call and its result
+        assert_eq!(seen[1].len(), 3);
+        assert_eq!(seen[1][2].content, "5");
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_iterations() {
+        let model = ScriptedModel::new(&[ADD_CALL, ADD_CALL, ADD_CALL]);
+        let agent = create_react_agent(
+            model.clone(),
+            vec![Arc::new(AddTool)],
+            ReactAgentOptions::new().with_max_iterations(2),
+        )
+        .unwrap();
+
+        let state = agent.invoke(question()).await.unwrap();
+        assert_eq!(model.seen.lock().unwrap().len(), 2);
+        assert_eq!(
+            state.messages.last().unwrap().content,
+            "Sorry, I stopped after reaching the limit of 2 iterations."
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupt_before_tools_pauses_for_approval() {
+        let agent = create_react_agent(
+            ScriptedModel::new(&[ADD_CALL, "It is 5"]),
+            vec![Arc::new(AddTool)],
+            ReactAgentOptions::new()
+                .with_interrupt_before_tools(true)
+                .with_checkpointer(Arc::new(InMemorySaver::new())),
+        )
+        .unwrap();
+        let config = RunnableConfig::with_thread_id("react-approval");
+
+        let paused = agent
+            .invoke_with_config(Some(question()), &config)
+            .await
+            .unwrap();
+        assert_eq!(paused.messages.len(), 2);
+        let snapshot = agent.get_state(&config).await.unwrap();
+        assert_eq!(snapshot.next, vec![REACT_TOOLS_NODE.to_string()]);
+
+        let done = agent.invoke_with_config(None, &config).await.unwrap();
+        assert_eq!(contents(&done), vec!["2 + 3?", "", "5", "It is 5"]);
+
+        assert!(create_react_agent(
+            ScriptedModel::new(&[]),
+            vec![],
+            ReactAgentOptions::new().with_interrupt_before_tools(true),
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "sqlite-persistence")]
+    #[tokio::test]
+    async fn resumes_from_sqlite_checkpoint() {
+        use crate::graph::SqliteSaver;
+
+        let checkpointer: CheckpointerBox<MessagesState> =
+            Arc::new(SqliteSaver::new_in_memory().unwrap());
+        let agent = |replies: &[&str]| {
+            create_react_agent(
+                ScriptedModel::new(replies),
+                vec![Arc::new(AddTool)],
+                ReactAgentOptions::new()
+                    .with_interrupt_before_tools(true)
+                    .with_checkpointer(checkpointer.clone()),
+            )
+            .unwrap()
+        };
+        let config = RunnableConfig::with_thread_id("react-sqlite");
+
+        // The first process pauses before running the model's tool call
+        let first = agent(&[ADD_CALL]);
+        first
+            .invoke_with_config(Some(question()), &config)
+            .await
+            .unwrap();
+
+        // A fresh agent on the same database picks up from the saved checkpoint
+        let done = agent(&["It is 5"])
+            .invoke_with_config(None, &config)
+            .await
+            .unwrap();
+        assert_eq!(contents(&done), vec!["2 + 3?", "", "5", "It is 5"]);
+    }
+
+    #[test]
+    fn runs_through_the_kernel_step_adapter() {
+        use crate::graph::{GraphStepFnAdapter, GraphStepReducer, GraphStepState};
+        use crate::kernel::driver::{Kernel, RunStatus};
+        use crate::kernel::event_store::InMemoryEventStore;
+        use crate::kernel::runner::KernelRunner;
+        use crate::kernel::stubs::{AllowAllPolicy, NoopActionExecutor};
+        use crate::kernel::SharedEventStore;
+
+        let agent = Arc::new(
+            create_react_agent(
+                ScriptedModel::new(&[ADD_CALL, "It is 5"]),
+                vec![Arc::new(AddTool)],
+                ReactAgentOptions::new(),
+            )
+            .unwrap(),
+        );
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel = || Kernel::<GraphStepState<MessagesState>> {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(agent.clone())),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "react-kernel".to_string();
+        let status = KernelRunner::new(kernel())
+            .run_until_blocked_sync(&run_id, GraphStepState::new(question()))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let replayed = kernel()
+            .replay(&run_id, GraphStepState::new(question()))
+            .unwrap();
+        assert_eq!(
+            contents(&replayed.graph_state),
+            vec!["2 + 3?", "", "5", "It is 5"]
+        );
+    }
+}
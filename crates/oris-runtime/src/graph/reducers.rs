@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use super::{
+    error::GraphError,
+    node::GOTO_UPDATE_KEY,
+    state::{Reducer, State, StateUpdate},
+};
+
+/// A reducer registered on a graph: combines a key's current value (`Null` when
+/// unset) with the value an update writes to it
+pub type ReducerFn = Arc<dyn Fn(&Value, &Value) -> Value + Send + Sync>;
+
+/// Built-in reducers for [`StateGraph::set_reducer`](super::StateGraph::set_reducer)
+pub struct Reducers;
+
+impl Reducers {
+    /// Append the written items (or the value itself, if it is not an array) to the array
+    pub fn append(current: &Value, update: &Value) -> Value {
+        Reducer::Append.reduce(current, update)
+    }
+
+    /// Keep the written value
+    pub fn overwrite(_current: &Value, update: &Value) -> Value {
+        update.clone()
+    }
+
+    /// Keep the larger number; a non-numeric side yields the written value
+    pub fn max(current: &Value, update: &Value) -> Value {
+        match (current.as_f64(), update.as_f64()) {
+            (Some(a), Some(b)) if a > b => current.clone(),
+            _ => update.clone(),
+        }
+    }
+
+    /// Keep the smaller number; a non-numeric side yields the written value
+    pub fn min(current: &Value, update: &Value) -> Value {
+        match (current.as_f64(), update.as_f64()) {
+            (Some(a), Some(b)) if a < b => current.clone(),
+            _ => update.clone(),
+        }
+    }
+
+    /// Add the written number to the current one (an unset key counts as 0)
+    pub fn sum(current: &Value, update: &Value) -> Value {
+        match (current, update) {
+            (Value::Null, _) => update.clone(),
+            _ => match (current.as_i64(), update.as_i64()) {
+                (Some(a), Some(b)) => Value::from(a + b),
+                _ => match (current.as_f64(), update.as_f64()) {
+                    (Some(a), Some(b)) => Value::from(a + b),
+                    _ => update.clone(),
+                },
+            },
+        }
+    }
+}
+
+/// Per-key reducers registered on a graph, applied on top of the state's own
+/// [`State::reducer`]s
+///
+/// Every executor path merges node updates through this, so checkpoints, kernel
+/// events and replays all see the same reduced state.
+#[derive(Clone, Default)]
+pub struct StateReducers {
+    by_key: HashMap<String, ReducerFn>,
+}
+
+impl fmt::Debug for StateReducers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<&String> = self.by_key.keys().collect();
+        keys.sort();
+        f.debug_struct("StateReducers")
+            .field("keys", &keys)
+            .finish()
+    }
+}
+
+impl StateReducers {
+    /// Create an empty set of reducers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the reducer for `key`, replacing any previous one
+    pub fn insert(&mut self, key: impl Into<String>, reducer: ReducerFn) {
+        self.by_key.insert(key.into(), reducer);
+    }
+
+    /// Whether writes to `key` from several nodes in one step can be combined
+    ///
+    /// True when the graph registers a reducer for the key or the state's own
+    /// reducer for it does more than overwrite.
+    pub fn reduces<S: State>(&self, key: &str) -> bool {
+        self.by_key.contains_key(key) || !matches!(S::reducer(key), Reducer::Overwrite)
+    }
+
+    /// Apply one node's update
+    ///
+    /// Keys with a registered reducer are combined with it; the rest go through
+    /// [`State::apply_update`].
+    pub fn apply<S: State>(&self, state: &S, update: &StateUpdate) -> Result<S, GraphError> {
+        if !update.keys().any(|key| self.by_key.contains_key(key)) {
+            return state.apply_update(update);
+        }
+        let (reduced, rest): (StateUpdate, StateUpdate) = update
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .partition(|(key, _)| self.by_key.contains_key(key));
+        let state = state.apply_update(&rest)?;
+
+        let mut value = serde_json::to_value(&state)?;
+        let Value::Object(fields) = &mut value else {
+            return Err(GraphError::StateMergeError(
+                "State must serialize to a JSON object".to_string(),
+            ));
+        };
+        // Sorted so custom reducers run in a stable order
+        let reduced: BTreeMap<String, Value> = reduced.into_iter().collect();
+        for (key, update) in reduced {
+            let current = fields.get(&key).unwrap_or(&Value::Null);
+            let merged = (self.by_key[&key])(current, &update);
+            fields.insert(key, merged);
+        }
+        serde_json::from_value(value).map_err(|e| {
+            GraphError::StateMergeError(format!("Cannot apply update to state: {}", e))
+        })
+    }
+
+    /// Apply the updates of nodes that ran in the same step, in order
+    ///
+    /// Fails with `GraphError::ConflictingUpdate` if two of them write a key that
+    /// has no reducer, since which write should win is undefined. Nodes run in the
+    /// same step cannot return a goto.
+    pub fn apply_all<S: State>(
+        &self,
+        state: &S,
+        updates: &[(String, StateUpdate)],
+    ) -> Result<S, GraphError> {
+        if let Some((node, _)) = updates
+            .iter()
+            .find(|(_, update)| update.contains_key(GOTO_UPDATE_KEY))
+        {
+            return Err(GraphError::ExecutionError(format!(
+                "Node '{}' returned a goto, which nodes run in parallel cannot use",
+                node
+            )));
+        }
+        let mut writers: HashMap<&str, &str> = HashMap::new();
+        for (node, update) in updates {
+            let mut keys: Vec<&String> = update.keys().collect();
+            keys.sort();
+            for key in keys {
+                match writers.get(key.as_str()) {
+                    Some(first) if !self.reduces::<S>(key) => {
+                        return Err(GraphError::ConflictingUpdate {
+                            key: key.clone(),
+                            first: first.to_string(),
+                            second: node.clone(),
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        writers.insert(key, node);
+                    }
+                }
+            }
+        }
+
+        let mut current = state.clone();
+        for (node, update) in updates {
+            log::debug!("Merging update from node: {}", node);
+            current = self.apply(&current, update)?;
+        }
+        Ok(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{messages_state_update, MessagesState};
+    use crate::schemas::messages::Message;
+
+    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct ScoreState {
+        score: f64,
+        label: String,
+    }
+
+    impl State for ScoreState {}
+
+    fn update(key: &str, value: Value) -> StateUpdate {
+        HashMap::from([(key.to_string(), value)])
+    }
+
+    #[test]
+    fn registered_reducers_combine_parallel_writes() {
+        let mut reducers = StateReducers::new();
+        reducers.insert("score", Arc::new(Reducers::max));
+        let state = ScoreState::default();
+
+        let merged = reducers
+            .apply_all(
+                &state,
+                &[
+                    ("a".to_string(), update("score", 0.9.into())),
+                    ("b".to_string(), update("score", 0.4.into())),
+                ],
+            )
+            .unwrap();
+        assert_eq!(merged.score, 0.9);
+
+        let err = reducers
+            .apply_all(
+                &state,
+                &[
+                    ("a".to_string(), update("label", "x".into())),
+                    ("b".to_string(), update("label", "y".into())),
+                ],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conflicting writes to state key 'label' from nodes 'a' and 'b'; \
+             register a reducer for it with StateGraph::set_reducer"
+        );
+    }
+
+    #[test]
+    fn state_reducers_count_as_reducers() {
+        let reducers = StateReducers::new();
+        let merged = reducers
+            .apply_all(
+                &MessagesState::new(),
+                &[
+                    (
+                        "a".to_string(),
+                        messages_state_update(vec![Message::new_ai_message("a")]),
+                    ),
+                    (
+                        "b".to_string(),
+                        messages_state_update(vec![Message::new_ai_message("b")]),
+                    ),
+                ],
+            )
+            .unwrap();
+        assert_eq!(merged.messages.len(), 2);
+    }
+
+    #[test]
+    fn builtin_reducers() {
+        assert_eq!(Reducers::sum(&Value::Null, &3.into()), Value::from(3));
+        assert_eq!(Reducers::sum(&2.into(), &3.into()), Value::from(5));
+        assert_eq!(Reducers::min(&2.into(), &3.into()), Value::from(2));
+        assert_eq!(
+            Reducers::append(&serde_json::json!([1]), &serde_json::json!([2, 3])),
+            serde_json::json!([1, 2, 3])
+        );
+        assert_eq!(Reducers::overwrite(&1.into(), &2.into()), Value::from(2));
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GraphError;
+
+/// Checkpoint metadata key under which per-node attempt counts are recorded.
+pub const NODE_ATTEMPTS_METADATA_KEY: &str = "node_attempts";
+
+/// Delay strategy between node retry attempts
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Backoff {
+    /// Retry immediately
+    #[default]
+    None,
+    /// Wait a fixed duration between attempts
+    Fixed(Duration),
+    /// Wait `initial * multiplier^(attempt - 1)`, capped at `max`
+    Exponential {
+        initial: Duration,
+        max: Duration,
+        multiplier: f64,
+    },
+}
+
+impl Backoff {
+    /// Delay to wait after the given failed attempt (1-based)
+    ///
+    /// Exponential delays are clamped to `0..=max`, so a negative or non-finite
+    /// `multiplier` never panics; a NaN delay waits `max`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential {
+                initial,
+                max,
+                multiplier,
+            } => {
+                let factor = multiplier.powi(attempt.saturating_sub(1) as i32);
+                let delay = initial.as_secs_f64() * factor;
+                if delay.is_nan() {
+                    return *max;
+                }
+                Duration::try_from_secs_f64(delay.clamp(0.0, max.as_secs_f64())).unwrap_or(*max)
+            }
+        }
+    }
+}
+
+/// Retry policy for a single graph node
+///
+/// Retries happen inside the node boundary: state is only merged (and checkpointed)
+/// once the node succeeds or the policy gives up. Interrupts and fatal retriever errors
+/// are never retried.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use oris_runtime::graph::{Backoff, GraphError, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(3)
+///     .with_backoff(Backoff::Fixed(Duration::from_millis(200)))
+///     .with_retry_on(|err| matches!(err, GraphError::ExecutionError(_)));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay between attempts
+    pub backoff: Backoff,
+    /// Predicate deciding whether an error is retryable
+    pub retry_on: Arc<dyn Fn(&GraphError) -> bool + Send + Sync>,
+}
+
+impl RetryPolicy {
+    /// Create a policy that retries every error up to `max_attempts` total attempts
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::None,
+            retry_on: Arc::new(|_| true),
+        }
+    }
+
+    /// Set the delay strategy between attempts
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the predicate deciding which errors are retried
+    pub fn with_retry_on(
+        mut self,
+        retry_on: impl Fn(&GraphError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_on = Arc::new(retry_on);
+        self
+    }
+
+    /// Whether another attempt should be made after `attempt` failed with `error`
+    ///
+    /// Interrupts, kernel action requests and fatal retriever errors are never retried.
+    pub fn should_retry(&self, error: &GraphError, attempt: u32) -> bool {
+        match error {
+            GraphError::InterruptError(_) | GraphError::ActionRequested(_) => return false,
+            GraphError::RetrieverError(e) if !e.is_retryable() => return false,
+            _ => {}
+        }
+        attempt < self.max_attempts && (self.retry_on)(error)
+    }
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("retry_on", &"<fn>")
+            .finish()
+    }
+}
+
+/// Attempt statistics for one node execution, recorded in checkpoint metadata
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeAttempts {
+    /// Number of attempts made
+    pub attempts: u32,
+    /// Error message of the last failed attempt, if any
+    pub last_error: Option<String>,
+    /// Whether the update was replayed from the node cache instead of running the node
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+}
+
+impl NodeAttempts {
+    /// Whether the node needed more than one attempt, failed, or was served from cache
+    pub fn is_notable(&self) -> bool {
+        self.attempts > 1 || self.last_error.is_some() || self.cache_hit
+    }
+}
+
+/// Serialize notable node attempts for checkpoint metadata
+pub(crate) fn node_attempts_value(attempts: &HashMap<String, NodeAttempts>) -> serde_json::Value {
+    serde_json::to_value(attempts).unwrap_or(serde_json::Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(250),
+            multiplier: 2.0,
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(200));
+        assert_eq!(backoff.delay(3), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_exponential_backoff_clamps_invalid_multipliers() {
+        let backoff = |multiplier| Backoff::Exponential {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            multiplier,
+        };
+        assert_eq!(backoff(-2.0).delay(2), Duration::ZERO);
+        assert_eq!(backoff(f64::NAN).delay(2), Duration::from_secs(1));
+        assert_eq!(backoff(f64::INFINITY).delay(2), Duration::from_secs(1));
+        assert_eq!(backoff(f64::NEG_INFINITY).delay(2), Duration::ZERO);
+        assert_eq!(backoff(0.5).delay(2), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_interrupts_are_never_retried() {
+        let policy = RetryPolicy::new(5);
+        let interrupt =
+            GraphError::InterruptError(crate::graph::interrupts::InterruptError::new("approve?"));
+        assert!(!policy.should_retry(&interrupt, 1));
+        assert!(policy.should_retry(&GraphError::ExecutionError("flaky".into()), 1));
+        assert!(!policy.should_retry(&GraphError::ExecutionError("flaky".into()), 5));
+    }
+}
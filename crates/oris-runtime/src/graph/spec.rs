@@ -0,0 +1,377 @@
+//! Declarative graph definitions built from registered plugins.
+//!
+//! A [GraphSpec] lists a graph's nodes (plugin type, name and config), static and
+//! conditional edges, and breakpoints as plain data, so graphs can be kept in JSON or
+//! YAML files and assembled at run time with [`NodePluginRegistry::build_graph`].
+//! The entry and exit of the graph are the reserved node names [`START`] and [`END`]
+//! (`"__start__"` and `"__end__"`).
+//!
+//! ```json
+//! {
+//!   "nodes": [{ "name": "draft", "plugin_type": "llm", "config": { "prompt": "..." } }],
+//!   "edges": [{ "from": "__start__", "to": "draft" }],
+//!   "conditional_edges": [{
+//!     "from": "draft",
+//!     "router": "has_tool_calls",
+//!     "mapping": { "yes": "draft", "no": "__end__" }
+//!   }],
+//!   "interrupt_before": ["draft"]
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    edge::{END, START},
+    error::GraphError,
+    graph::StateGraph,
+    plugin::NodePluginRegistry,
+    state::State,
+};
+
+/// Serializable description of a graph assembled from registered plugins
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphSpec {
+    /// Nodes, each built by a registered node plugin
+    pub nodes: Vec<NodeSpec>,
+    /// Static edges
+    #[serde(default)]
+    pub edges: Vec<EdgeSpec>,
+    /// Conditional edges, each routed by a registered router plugin
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditional_edges: Vec<ConditionalEdgeSpec>,
+    /// Nodes to pause before
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interrupt_before: Vec<String>,
+    /// Nodes to pause after
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interrupt_after: Vec<String>,
+}
+
+/// A node built by the node plugin `plugin_type` from `config`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeSpec {
+    pub name: String,
+    pub plugin_type: String,
+    #[serde(default)]
+    pub config: Value,
+}
+
+/// A static edge from `from` to `to`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EdgeSpec {
+    pub from: String,
+    pub to: String,
+}
+
+/// A conditional edge from `from`, routed by the router plugin `router` built from
+/// `config`; `mapping` translates the router's key into the next node
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalEdgeSpec {
+    pub from: String,
+    pub router: String,
+    #[serde(default)]
+    pub config: Value,
+    pub mapping: BTreeMap<String, String>,
+}
+
+impl GraphSpec {
+    /// Parse a spec from JSON
+    pub fn from_json(json: &str) -> Result<Self, GraphError> {
+        serde_json::from_str(json).map_err(|e| spec_error("$", e))
+    }
+
+    /// Serialize the spec as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, GraphError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a spec from YAML
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, GraphError> {
+        serde_yaml::from_str(yaml).map_err(|e| spec_error("$", e))
+    }
+
+    /// Serialize the spec as YAML
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, GraphError> {
+        serde_yaml::to_string(self).map_err(|e| spec_error("$", e))
+    }
+
+    /// Describe a graph whose nodes and conditional edges were all added from plugins
+    ///
+    /// Nodes must have been added with `StateGraph::add_plugin_node` and conditional
+    /// edges with `StateGraph::add_plugin_conditional_edges`; other nodes, conditional
+    /// edges and fan-out edges cannot be described and return an error.
+    pub fn from_state_graph<S: State + 'static>(graph: &StateGraph<S>) -> Result<Self, GraphError> {
+        graph.to_spec()
+    }
+}
+
+impl<S: State + 'static> NodePluginRegistry<S> {
+    /// Assemble the graph described by `spec` from the registered plugins
+    ///
+    /// Errors are `GraphError::InvalidSpec` citing the JSON path of the offending entry:
+    /// unknown node or router types, duplicate or reserved node names, configs the
+    /// plugin rejects, and edges or breakpoints naming unknown nodes. The returned graph
+    /// still has to be compiled, which validates its structure.
+    pub fn build_graph(&self, spec: &GraphSpec) -> Result<StateGraph<S>, GraphError> {
+        let mut graph = StateGraph::new();
+        let mut names = HashSet::new();
+        for (i, node) in spec.nodes.iter().enumerate() {
+            let path = format!("$.nodes[{}]", i);
+            if node.name == START || node.name == END {
+                return Err(spec_error(
+                    format!("{}.name", path),
+                    format!("'{}' is a reserved node name", node.name),
+                ));
+            }
+            if !names.insert(node.name.as_str()) {
+                return Err(spec_error(
+                    format!("{}.name", path),
+                    format!("duplicate node name '{}'", node.name),
+                ));
+            }
+            if !self.contains(&node.plugin_type) {
+                return Err(spec_error(
+                    format!("{}.plugin_type", path),
+                    format!("plugin type '{}' is not registered", node.plugin_type),
+                ));
+            }
+            graph
+                .add_plugin_node(
+                    node.name.clone(),
+                    &node.plugin_type,
+                    node.config.clone(),
+                    self,
+                )
+                .map_err(|e| spec_error(format!("{}.config", path), e))?;
+        }
+
+        // `reserved` is START or END where the entry or exit may be named
+        let check_node = |path: String, name: &str, reserved: Option<&str>| {
+            if names.contains(name) || Some(name) == reserved {
+                Ok(())
+            } else {
+                Err(spec_error(path, format!("unknown node '{}'", name)))
+            }
+        };
+        for (i, edge) in spec.edges.iter().enumerate() {
+            check_node(format!("$.edges[{}].from", i), &edge.from, Some(START))?;
+            check_node(format!("$.edges[{}].to", i), &edge.to, Some(END))?;
+            graph.add_edge(edge.from.clone(), edge.to.clone());
+        }
+        for (i, edge) in spec.conditional_edges.iter().enumerate() {
+            let path = format!("$.conditional_edges[{}]", i);
+            check_node(format!("{}.from", path), &edge.from, Some(START))?;
+            for (key, to) in &edge.mapping {
+                check_node(format!("{}.mapping.{}", path, key), to, Some(END))?;
+            }
+            if !self.contains_router(&edge.router) {
+                return Err(spec_error(
+                    format!("{}.router", path),
+                    format!("router type '{}' is not registered", edge.router),
+                ));
+            }
+            graph
+                .add_plugin_conditional_edges(
+                    edge.from.clone(),
+                    &edge.router,
+                    edge.config.clone(),
+                    edge.mapping.clone().into_iter().collect(),
+                    self,
+                )
+                .map_err(|e| spec_error(format!("{}.config", path), e))?;
+        }
+
+        for (field, nodes) in [
+            ("interrupt_before", &spec.interrupt_before),
+            ("interrupt_after", &spec.interrupt_after),
+        ] {
+            for (i, node) in nodes.iter().enumerate() {
+                check_node(format!("$.{}[{}]", field, i), node, None)?;
+            }
+        }
+        let before: Vec<&str> = spec.interrupt_before.iter().map(String::as_str).collect();
+        let after: Vec<&str> = spec.interrupt_after.iter().map(String::as_str).collect();
+        graph
+            .add_interrupt_before(&before)
+            .add_interrupt_after(&after);
+        Ok(graph)
+    }
+}
+
+fn spec_error(path: impl Into<String>, message: impl ToString) -> GraphError {
+    GraphError::InvalidSpec {
+        path: path.into(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        graph::{
+            function_node, messages_state_update, typed_node_plugin, typed_router_plugin,
+            InMemorySaver, MessagesState, RunnableConfig,
+        },
+        schemas::messages::Message,
+    };
+
+    #[derive(Deserialize)]
+    struct SayConfig {
+        text: String,
+    }
+
+    #[derive(Deserialize)]
+    struct LengthConfig {
+        max: usize,
+    }
+
+    fn registry() -> NodePluginRegistry<MessagesState> {
+        let mut registry = NodePluginRegistry::new();
+        registry
+            .register_plugin(typed_node_plugin("test/say", |name, config: SayConfig| {
+                Ok(Arc::new(function_node(
+                    name.to_string(),
+                    move |_state: &MessagesState| {
+                        let text = config.text.clone();
+                        async move { Ok(messages_state_update(vec![Message::new_ai_message(text)])) }
+                    },
+                )))
+            }))
+            .unwrap();
+        registry
+            .register_router(typed_router_plugin(
+                "test/length",
+                |config: LengthConfig| {
+                    Ok(Arc::new(move |state: &MessagesState| {
+                        if state.messages.len() < config.max {
+                            "again".to_string()
+                        } else {
+                            "done".to_string()
+                        }
+                    }))
+                },
+            ))
+            .unwrap();
+        registry
+    }
+
+    const SPEC: &str = r#"{
+        "nodes": [
+            { "name": "draft", "plugin_type": "test/say", "config": { "text": "draft" } },
+            { "name": "review", "plugin_type": "test/say", "config": { "text": "review" } }
+        ],
+        "edges": [
+            { "from": "__start__", "to": "draft" },
+            { "from": "draft", "to": "review" }
+        ],
+        "conditional_edges": [{
+            "from": "review",
+            "router": "test/length",
+            "config": { "max": 4 },
+            "mapping": { "again": "draft", "done": "__end__" }
+        }],
+        "interrupt_before": ["review"]
+    }"#;
+
+    #[tokio::test]
+    async fn builds_and_runs_graph_from_json_spec() {
+        let spec = GraphSpec::from_json(SPEC).unwrap();
+        let compiled = registry()
+            .build_graph(&spec)
+            .unwrap()
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+        let config = RunnableConfig::with_thread_id("spec");
+
+        compiled
+            .invoke_with_config(Some(MessagesState::new()), &config)
+            .await
+            .unwrap();
+        let paused = compiled.get_state(&config).await.unwrap();
+        assert_eq!(paused.next, vec!["review".to_string()]);
+
+        compiled.invoke_with_config(None, &config).await.unwrap();
+        let state = compiled.invoke_with_config(None, &config).await.unwrap();
+        let contents: Vec<_> = state.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["draft", "review", "draft", "review"]);
+    }
+
+    #[test]
+    fn exported_spec_round_trips() {
+        let spec = GraphSpec::from_json(SPEC).unwrap();
+        let graph = registry().build_graph(&spec).unwrap();
+        let exported = GraphSpec::from_state_graph(&graph).unwrap();
+        assert_eq!(exported, spec);
+        assert_eq!(
+            GraphSpec::from_json(&exported.to_json().unwrap()).unwrap(),
+            spec
+        );
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "inline",
+                function_node("inline", |_s: &MessagesState| async move {
+                    Ok(messages_state_update(Vec::new()))
+                }),
+            )
+            .unwrap();
+        assert!(matches!(
+            GraphSpec::from_state_graph(&graph),
+            Err(GraphError::CompilationError(_))
+        ));
+    }
+
+    #[test]
+    fn errors_cite_the_offending_entry() {
+        let path_of = |mutate: fn(&mut GraphSpec)| {
+            let mut spec = GraphSpec::from_json(SPEC).unwrap();
+            mutate(&mut spec);
+            match registry().build_graph(&spec) {
+                Err(GraphError::InvalidSpec { path, .. }) => path,
+                Err(e) => panic!("expected InvalidSpec, got {}", e),
+                Ok(_) => panic!("expected InvalidSpec"),
+            }
+        };
+
+        assert_eq!(
+            path_of(|s| s.nodes[1].plugin_type = "missing".into()),
+            "$.nodes[1].plugin_type"
+        );
+        assert_eq!(
+            path_of(|s| s.nodes[1].name = "draft".into()),
+            "$.nodes[1].name"
+        );
+        assert_eq!(
+            path_of(|s| s.nodes[0].config = serde_json::json!({ "txt": 1 })),
+            "$.nodes[0].config"
+        );
+        assert_eq!(
+            path_of(|s| s.edges[1].to = "publish".into()),
+            "$.edges[1].to"
+        );
+        assert_eq!(
+            path_of(|s| s.conditional_edges[0].router = "missing".into()),
+            "$.conditional_edges[0].router"
+        );
+        assert_eq!(
+            path_of(|s| s.conditional_edges[0].config = serde_json::json!({})),
+            "$.conditional_edges[0].config"
+        );
+        assert_eq!(
+            path_of(|s| s.interrupt_before[0] = "publish".into()),
+            "$.interrupt_before[0]"
+        );
+    }
+}
@@ -1,23 +1,122 @@
 use std::collections::HashMap;
+use std::marker::PhantomData;
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::kernel::canonical::canonical_state_hash;
 use crate::kernel::state::KernelState;
+use crate::kernel::KernelError;
+use crate::kernel::{merge_value, MergeReport, MergeStrategy};
 use crate::schemas::messages::Message;
 
+use super::error::GraphError;
+
 /// Trait for state types used in LangGraph
 ///
-/// States must be cloneable, thread-safe, serializable, and must implement
-/// a merge strategy for combining state updates.
+/// States must be cloneable, thread-safe, serializable to a JSON object, and define
+/// how node updates combine with their fields. Executors apply each node's
+/// [`StateUpdate`] with [`apply_update`](Self::apply_update), which runs every updated
+/// field through the field's [`Reducer`]; fields overwrite by default.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use oris_runtime::graph::{state_update, Reducer, State};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Default, Serialize, Deserialize)]
+/// struct PlanState {
+///     plan: Vec<String>,
+///     budget: f64,
+/// }
+///
+/// impl State for PlanState {
+///     fn reducer(field: &str) -> Reducer {
+///         match field {
+///             "plan" => Reducer::Append,
+///             _ => Reducer::Overwrite,
+///         }
+///     }
+/// }
+///
+/// // Appends one step and sets the remaining budget
+/// let mut update = state_update(oris_runtime::state_field!(PlanState, plan), vec!["search".to_string()]);
+/// update.extend(state_update(oris_runtime::state_field!(PlanState, budget), 4.5));
+/// ```
 pub trait State: Clone + Send + Sync + Serialize + DeserializeOwned {
     /// Merge another state into this state
     ///
-    /// This method defines how state updates are combined.
-    /// The default implementation should handle the most common case
-    /// of merging state updates.
-    fn merge(&self, other: &Self) -> Self;
+    /// The default applies every field of `other` through its reducer, falling back
+    /// to `other` if the states do not serialize to JSON objects.
+    fn merge(&self, other: &Self) -> Self {
+        match serde_json::to_value(other) {
+            Ok(Value::Object(fields)) => self
+                .apply_update(&fields.into_iter().collect())
+                .unwrap_or_else(|_| other.clone()),
+            _ => other.clone(),
+        }
+    }
+
+    /// How updates to `field` combine with its current value
+    fn reducer(_field: &str) -> Reducer {
+        Reducer::Overwrite
+    }
+
+    /// Apply a node's update, combining each updated field with its reducer
+    ///
+    /// Fields the update does not mention are left unchanged; fields the state does
+    /// not have are ignored unless the state's deserializer rejects them.
+    fn apply_update(&self, update: &StateUpdate) -> Result<Self, GraphError> {
+        let mut value = serde_json::to_value(self)?;
+        let Value::Object(fields) = &mut value else {
+            return Err(GraphError::StateMergeError(
+                "State must serialize to a JSON object".to_string(),
+            ));
+        };
+        for (field, update) in update {
+            let current = fields.get(field).unwrap_or(&Value::Null);
+            let merged = Self::reducer(field).reduce(current, update);
+            fields.insert(field.clone(), merged);
+        }
+        serde_json::from_value(value).map_err(|e| {
+            GraphError::StateMergeError(format!("Cannot apply update to state: {}", e))
+        })
+    }
+}
+
+/// How a state field combines with the value a node update carries for it
+///
+/// The built-in reducers are the kernel's [`MergeStrategy`] merges, so a field combines
+/// the same way whether a graph node or a kernel `StateUpdated` updates it.
+#[derive(Clone, Copy, Debug)]
+pub enum Reducer {
+    /// Replace the field with the update's value
+    Overwrite,
+    /// Append the update's items (or the update itself, if it is not an array)
+    /// to the field's array
+    Append,
+    /// Merge the update's object into the field's object, recursively; other values
+    /// are replaced
+    DeepMerge,
+    /// Combine the current value (`Null` when unset) with the update's value
+    Custom(fn(&Value, &Value) -> Value),
+}
+
+impl Reducer {
+    /// Combine `current` with `update`
+    pub fn reduce(&self, current: &Value, update: &Value) -> Value {
+        let strategy = match self {
+            Self::Overwrite => MergeStrategy::LastWriteWins,
+            Self::Append => MergeStrategy::Append,
+            Self::DeepMerge => MergeStrategy::DeepMerge,
+            Self::Custom(reduce) => return reduce(current, update),
+        };
+        // Only FailOnConflict can fail, and no reducer maps to it
+        merge_value(strategy, "", current, update, &mut MergeReport::default())
+            .unwrap_or_else(|_| update.clone())
+    }
 }
 
 /// State update type - a map of field names to values
@@ -26,6 +125,76 @@ pub trait State: Clone + Send + Sync + Serialize + DeserializeOwned {
 /// The graph executor will merge these updates into the current state.
 pub type StateUpdate = HashMap<String, Value>;
 
+/// A field of state type `S` holding values of type `T`
+///
+/// Build one with [`state_field!`](crate::state_field), which checks the field exists
+/// on `S` and infers `T` from it, then pass it to [`state_update`].
+pub struct StateField<S, T> {
+    name: &'static str,
+    _field: PhantomData<fn(&S) -> &T>,
+}
+
+impl<S, T> StateField<S, T> {
+    /// Name a field; `accessor` ties the name to the field's type
+    ///
+    /// `name` must match the field's serialized name.
+    pub fn new(name: &'static str, accessor: fn(&S) -> &T) -> Self {
+        let _ = accessor;
+        Self {
+            name,
+            _field: PhantomData,
+        }
+    }
+
+    /// Serialized name of the field
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<S, T> Clone for StateField<S, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, T> Copy for StateField<S, T> {}
+
+/// Build a [`StateField`] for a field of a state struct, checked at compile time
+///
+/// ```rust,ignore
+/// let budget = oris_runtime::state_field!(PlanState, budget); // StateField<PlanState, f64>
+/// ```
+#[macro_export]
+macro_rules! state_field {
+    ($state:ty, $field:ident) => {
+        $crate::graph::StateField::<$state, _>::new(stringify!($field), |state: &$state| {
+            &state.$field
+        })
+    };
+}
+
+/// Create a state update that sets (or, per the field's reducer, extends) one field
+///
+/// The generic sibling of [`messages_state_update`]: the value is type-checked against
+/// the field. Combine updates for several fields with `extend`.
+pub fn state_update<S, T: Serialize>(field: StateField<S, T>, value: T) -> StateUpdate {
+    let mut update = HashMap::new();
+    update.insert(
+        field.name().to_string(),
+        serde_json::to_value(value).unwrap_or(Value::Null),
+    );
+    update
+}
+
+/// A state update that replaces every field with its value in `state`
+pub(crate) fn full_state_update<S: State>(state: &S) -> Result<StateUpdate, GraphError> {
+    match serde_json::to_value(state)? {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        _ => Ok(HashMap::new()),
+    }
+}
+
 /// MessagesState - a state type containing only messages
 ///
 /// This is the most common state type for LangGraph workflows,
@@ -66,12 +235,27 @@ impl State for MessagesState {
         messages.extend(other.messages.clone());
         Self { messages }
     }
+
+    fn reducer(field: &str) -> Reducer {
+        match field {
+            "messages" => Reducer::Append,
+            _ => Reducer::Overwrite,
+        }
+    }
+
+    fn apply_update(&self, update: &StateUpdate) -> Result<Self, GraphError> {
+        Ok(apply_update_to_messages_state(self, update))
+    }
 }
 
 impl KernelState for MessagesState {
     fn version(&self) -> u32 {
         1
     }
+
+    fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+        canonical_state_hash(self).map(Some)
+    }
 }
 
 /// Helper function to create a state update from a MessagesState
@@ -160,4 +344,71 @@ mod tests {
         let new_state = apply_update_to_messages_state(&state, &update);
         assert_eq!(new_state.messages.len(), 2);
     }
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, serde::Deserialize)]
+    struct PlanState {
+        plan: Vec<String>,
+        budget: f64,
+        #[serde(default)]
+        calls: u32,
+    }
+
+    impl State for PlanState {
+        fn reducer(field: &str) -> Reducer {
+            match field {
+                "plan" => Reducer::Append,
+                "calls" => Reducer::Custom(|current, update| {
+                    (current.as_u64().unwrap_or(0) + update.as_u64().unwrap_or(0)).into()
+                }),
+                _ => Reducer::Overwrite,
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_state_applies_field_reducers() {
+        let state = PlanState {
+            plan: vec!["search".to_string()],
+            budget: 10.0,
+            calls: 1,
+        };
+        let mut update = state_update(crate::state_field!(PlanState, plan), vec!["answer".into()]);
+        update.extend(state_update(crate::state_field!(PlanState, budget), 7.5));
+        update.extend(state_update(crate::state_field!(PlanState, calls), 2));
+
+        let updated = state.apply_update(&update).unwrap();
+        assert_eq!(
+            updated,
+            PlanState {
+                plan: vec!["search".to_string(), "answer".to_string()],
+                budget: 7.5,
+                calls: 3,
+            }
+        );
+        assert_eq!(updated.merge(&PlanState::default()).plan.len(), 2);
+        assert!(state
+            .apply_update(&HashMap::from([("budget".to_string(), "lots".into())]))
+            .is_err());
+    }
+
+    #[test]
+    fn builtin_reducers_match_the_kernel_merge_strategies() {
+        use serde_json::json;
+
+        let rules = crate::kernel::MergeRules::new(MergeStrategy::DeepMerge)
+            .with_key("plan", MergeStrategy::Append);
+        let mut kernel_state = json!({"plan": "search", "limits": {"tokens": 10}});
+        let update = json!({"plan": ["answer"], "limits": {"calls": 2}});
+        rules.merge(&mut kernel_state, &update).unwrap();
+
+        assert_eq!(
+            Reducer::Append.reduce(&json!("search"), &update["plan"]),
+            kernel_state["plan"]
+        );
+        assert_eq!(
+            Reducer::DeepMerge.reduce(&json!({"tokens": 10}), &update["limits"]),
+            json!({"tokens": 10, "calls": 2})
+        );
+        assert_eq!(kernel_state["limits"], json!({"tokens": 10, "calls": 2}));
+    }
 }
@@ -5,23 +5,44 @@
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::kernel::canonical::canonical_state_hash;
 use crate::kernel::event::Event;
 use crate::kernel::state::KernelState;
 use crate::kernel::step::{InterruptInfo, Next, StepFn};
+use crate::kernel::{MergeReport, MergeRules};
 
+use super::action::with_action_outputs;
 use super::compiled::CompiledGraph;
+use super::error::GraphError;
+use super::interrupts::{set_interrupt_context, InterruptContext};
 use super::state::State;
 use super::step_result::GraphStepOnceResult;
-use crate::graph::persistence::config::RunnableConfig;
+use crate::graph::persistence::config::{RunnableConfig, DEFAULT_STEP_LIMIT};
 use crate::kernel::KernelError;
 
 /// State for the kernel when driving a graph: graph state + current node (for replay).
+///
+/// `resume_value` holds the value of a `Resumed` event until the interrupted node
+/// consumes it, so `interrupt()` returns it instead of interrupting again.
+/// `steps` counts the nodes executed since the run started or was last resumed, and
+/// `last_node` is the most recently executed node; both back the step limit.
+/// `action_outputs` holds the outputs of the kernel actions the current node has
+/// requested so far, which [`request_action`](super::request_action) hands back to it.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound = "S: State + serde::Serialize + serde::de::DeserializeOwned")]
 pub struct GraphStepState<S: State> {
     pub graph_state: S,
     pub current_node: String,
+    #[serde(default)]
+    pub resume_value: Option<Value>,
+    #[serde(default)]
+    pub steps: usize,
+    #[serde(default)]
+    pub last_node: Option<String>,
+    #[serde(default)]
+    pub action_outputs: Vec<Value>,
 }
 
 impl<S: State> GraphStepState<S> {
@@ -29,6 +50,10 @@ impl<S: State> GraphStepState<S> {
         Self {
             graph_state,
             current_node: super::edge::START.to_string(),
+            resume_value: None,
+            steps: 0,
+            last_node: None,
+            action_outputs: Vec::new(),
         }
     }
 }
@@ -37,6 +62,10 @@ impl<S: State + KernelState> KernelState for GraphStepState<S> {
     fn version(&self) -> u32 {
         self.graph_state.version()
     }
+
+    fn state_hash(&self) -> Result<Option<[u8; 32]>, KernelError> {
+        canonical_state_hash(self).map(Some)
+    }
 }
 
 /// Sync StepFn that runs one graph node per next() via block_on.
@@ -64,6 +93,11 @@ impl<S: State + KernelState + 'static> GraphStepFnAdapter<S> {
 impl<S: State + KernelState + 'static> StepFn<GraphStepState<S>> for GraphStepFnAdapter<S> {
     /// Requires a Tokio runtime on the current thread; use `Handle::try_current()` to check.
     /// From async, use `block_in_place` or a dedicated thread.
+    ///
+    /// Once the config's step limit is reached or its deadline has passed, returns
+    /// `Next::FailWithCode` with the `GraphError::StepLimitExceeded` or
+    /// `GraphError::DeadlineExceeded` message and code instead of running another node.
+    /// A node that requests a kernel action yields `Next::Do` and runs again afterwards.
     fn next(&self, state: &GraphStepState<S>) -> Result<Next, KernelError> {
         let handle = tokio::runtime::Handle::try_current().map_err(|_| {
             KernelError::Driver(
@@ -71,38 +105,101 @@ impl<S: State + KernelState + 'static> StepFn<GraphStepState<S>> for GraphStepFn
             )
         })?;
         let config = self.config.as_ref();
-        let result = handle.block_on(self.graph.step_once(
-            &state.graph_state,
-            &state.current_node,
-            config,
+        let step_limit = config.map_or(DEFAULT_STEP_LIMIT, |c| c.get_step_limit());
+        let limit_error = if state.current_node == super::edge::END {
+            None
+        } else if state.steps >= step_limit {
+            Some(GraphError::StepLimitExceeded {
+                limit: step_limit,
+                last_node: state
+                    .last_node
+                    .clone()
+                    .unwrap_or_else(|| super::edge::START.to_string()),
+            })
+        } else if config.is_some_and(RunnableConfig::is_past_deadline) {
+            Some(GraphError::DeadlineExceeded {
+                node: state.current_node.clone(),
+            })
+        } else {
+            None
+        };
+        if let Some(error) = limit_error {
+            return Ok(Next::FailWithCode {
+                code: error.code().unwrap_or_default().to_string(),
+                reason: error.to_string(),
+            });
+        }
+        let ctx = match &state.resume_value {
+            Some(value) => InterruptContext::with_resume_value(value.clone()),
+            None => InterruptContext::new(),
+        };
+        let result = handle.block_on(with_action_outputs(
+            state.action_outputs.clone(),
+            set_interrupt_context(
+                ctx,
+                self.graph
+                    .step_once(&state.graph_state, &state.current_node, config),
+            ),
         ));
         match result.map_err(|e| KernelError::Driver(e.to_string()))? {
             GraphStepOnceResult::Emit {
                 executed_node,
                 new_state,
                 next_node,
-            } => {
-                let graph_state = serde_json::to_value(&new_state)
-                    .map_err(|e| KernelError::Driver(e.to_string()))?;
-                let payload = serde_json::json!({
-                    "graph_state": graph_state,
-                    "next_node": next_node,
-                });
-                Ok(Next::Emit(vec![Event::StateUpdated {
-                    step_id: Some(executed_node),
-                    payload,
-                }]))
-            }
+            } => state_updated(executed_node, &new_state, &next_node),
             GraphStepOnceResult::Interrupt { value, .. } => {
                 Ok(Next::Interrupt(InterruptInfo { value }))
             }
-            GraphStepOnceResult::Complete { .. } => Ok(Next::Complete),
+            GraphStepOnceResult::Action { action, .. } => Ok(Next::Do(action)),
+            GraphStepOnceResult::Complete {
+                executed_node: Some(executed_node),
+                state: new_state,
+            } => {
+                // Record the last node's update; the next step sees END and completes
+                state_updated(executed_node, &new_state, super::edge::END)
+            }
+            GraphStepOnceResult::Complete {
+                executed_node: None,
+                ..
+            } => Ok(Next::Complete),
         }
     }
 }
 
+/// `StateUpdated` envelope for a node that ran and the node to run next.
+fn state_updated<S: State>(
+    executed_node: String,
+    new_state: &S,
+    next_node: &str,
+) -> Result<Next, KernelError> {
+    let graph_state =
+        serde_json::to_value(new_state).map_err(|e| KernelError::Driver(e.to_string()))?;
+    #[allow(unused_mut)]
+    let mut payload = serde_json::json!({
+        "graph_state": graph_state,
+        "next_node": next_node,
+    });
+    #[cfg(feature = "otel")]
+    if let Some(trace) = crate::kernel::otel::trace_context(&opentelemetry::Context::current()) {
+        payload[crate::kernel::otel::TRACE_CONTEXT_KEY] = trace;
+    }
+    Ok(Next::Emit(vec![Event::StateUpdated {
+        step_id: Some(executed_node),
+        payload,
+        state_hash: None,
+        merge_report: None,
+    }]))
+}
+
 /// Reducer that applies events to GraphStepState.
 /// Supports envelope payload (`graph_state` + `next_node`) or legacy (payload = state, step_id = cursor).
+/// `Resumed` sets the pending resume value and restarts the step count;
+/// `ActionSucceeded` records an action output for the current node; the next
+/// `StateUpdated` clears both and counts one step.
+///
+/// The event carries the graph state after the node's update went through the state's
+/// field [`Reducer`](super::Reducer)s, so the graph state is replaced; the merge report
+/// lists its fields, as the kernel's `StateUpdatedOnlyReducer` does.
 #[derive(Debug, Clone, Default)]
 pub struct GraphStepReducer;
 
@@ -115,7 +212,20 @@ where
         state: &mut GraphStepState<S>,
         event: &crate::kernel::event::SequencedEvent,
     ) -> Result<(), KernelError> {
-        if let Event::StateUpdated { step_id, payload } = &event.event {
+        if let Event::Resumed { value } = &event.event {
+            state.resume_value = Some(value.clone());
+            state.steps = 0;
+        }
+        if let Event::ActionSucceeded { output, .. } = &event.event {
+            state.action_outputs.push(output.clone());
+        }
+        if let Event::StateUpdated {
+            step_id, payload, ..
+        } = &event.event
+        {
+            state.resume_value = None;
+            state.action_outputs.clear();
+            state.steps += 1;
             if let (Some(gs), Some(nn)) = (
                 payload.get("graph_state"),
                 payload.get("next_node").and_then(|v| v.as_str()),
@@ -123,6 +233,7 @@ where
                 state.graph_state = serde_json::from_value(gs.clone())
                     .map_err(|e| KernelError::EventStore(e.to_string()))?;
                 state.current_node = nn.to_string();
+                state.last_node = step_id.clone();
             } else {
                 state.graph_state = serde_json::from_value(payload.clone())
                     .map_err(|e| KernelError::EventStore(e.to_string()))?;
@@ -133,6 +244,21 @@ where
         }
         Ok(())
     }
+    fn apply_with_report(
+        &self,
+        state: &mut GraphStepState<S>,
+        event: &crate::kernel::event::SequencedEvent,
+    ) -> Result<Option<MergeReport>, KernelError> {
+        let Event::StateUpdated { payload, .. } = &event.event else {
+            return self.apply(state, event).map(|()| None);
+        };
+        let update = payload.get("graph_state").unwrap_or(payload);
+        let mut current = serde_json::to_value(&state.graph_state)
+            .map_err(|e| KernelError::Reducer(e.to_string()))?;
+        let report = MergeRules::default().merge(&mut current, update)?;
+        self.apply(state, event)?;
+        Ok(Some(report))
+    }
 }
 
 #[cfg(test)]
@@ -167,11 +293,12 @@ mod tests {
             events: Box::new(InMemoryEventStore::new()),
             snaps: None,
             reducer: Box::new(GraphStepReducer),
-            exec: Box::new(NoopActionExecutor),
+            exec: Arc::new(NoopActionExecutor),
             step: Box::new(adapter),
             policy: Box::new(AllowAllPolicy),
             effect_sink: None,
             mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
         };
         let runner = KernelRunner::new(kernel);
         let run_id = "graph-step-test".to_string();
@@ -201,4 +328,385 @@ mod tests {
             "START -> node1 -> END: one step runs node1 and reaches END"
         );
     }
+
+    /// A node timeout routes to the fallback node when driven by the kernel.
+    #[test]
+    fn graph_step_adapter_routes_timeout_to_fallback() {
+        use crate::graph::{NodeOptions, OnTimeout};
+        use crate::kernel::EventStore;
+        use crate::kernel::SharedEventStore;
+        use std::time::Duration;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node_with_options(
+                "slow",
+                function_node("slow", |_s: &MessagesState| async move {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    Ok(std::collections::HashMap::new())
+                }),
+                NodeOptions::new()
+                    .with_timeout(Duration::from_millis(10))
+                    .with_on_timeout(OnTimeout::Fallback("fallback".to_string())),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "fallback",
+                function_node("fallback", |_s: &MessagesState| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "slow");
+        graph.add_edge("slow", END);
+        graph.add_edge("fallback", END);
+        let compiled = Arc::new(graph.compile().unwrap());
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(compiled)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "graph-step-timeout".to_string();
+        let status = KernelRunner::new(kernel)
+            .run_until_blocked_sync(&run_id, GraphStepState::new(MessagesState::new()))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let next_nodes: Vec<_> = events
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                Event::StateUpdated {
+                    step_id, payload, ..
+                } => Some((
+                    step_id.unwrap_or_default(),
+                    payload["next_node"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            next_nodes.first(),
+            Some(&("slow".to_string(), "fallback".to_string()))
+        );
+    }
+
+    /// A node interrupt blocks the kernel run; resuming delivers the value to `interrupt()`.
+    #[test]
+    fn graph_step_adapter_resumes_interrupted_node() {
+        use crate::graph::interrupt;
+        use crate::kernel::driver::Signal;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "approve",
+                function_node("approve", |_s: &MessagesState| async move {
+                    let answer = interrupt("approve?").await?;
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message(
+                            answer.as_str().unwrap_or_default(),
+                        ),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "approve");
+        graph.add_edge("approve", END);
+        let compiled = Arc::new(graph.compile().unwrap());
+        let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+            events: Box::new(InMemoryEventStore::new()),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(compiled)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let runner = KernelRunner::new(kernel);
+        let run_id = "graph-step-interrupt".to_string();
+        let initial = GraphStepState::new(MessagesState::new());
+
+        let status = runner
+            .run_until_blocked_sync(&run_id, initial.clone())
+            .unwrap();
+        match status {
+            RunStatus::Blocked(info) => {
+                assert_eq!(info.interrupt.unwrap().value, serde_json::json!("approve?"))
+            }
+            other => panic!("expected Blocked, got {:?}", other),
+        }
+
+        let status = runner
+            .resume_sync(&run_id, initial, Signal::Resume(serde_json::json!("yes")))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+    }
+
+    /// Resuming with the blocked run's token hands the value to the node that called
+    /// `interrupt()`; the same token cannot resume it twice.
+    #[test]
+    fn graph_step_adapter_resumes_with_value_by_token() {
+        use crate::graph::interrupt;
+        use crate::kernel::KernelError;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "approve",
+                function_node("approve", |_s: &MessagesState| async move {
+                    let answer = interrupt(serde_json::json!({ "amount": 120 })).await?;
+                    Ok(crate::graph::messages_state_update(vec![
+                        crate::schemas::messages::Message::new_ai_message(
+                            answer["decision"].as_str().unwrap_or_default(),
+                        ),
+                    ]))
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "approve");
+        graph.add_edge("approve", END);
+        let compiled = Arc::new(graph.compile().unwrap());
+        let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+            events: Box::new(InMemoryEventStore::new()),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(compiled)),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let runner = KernelRunner::new(kernel);
+        let run_id = "graph-step-resume-token".to_string();
+        let initial = GraphStepState::new(MessagesState::new());
+
+        let token = match runner
+            .run_until_blocked_sync(&run_id, initial.clone())
+            .unwrap()
+        {
+            RunStatus::Blocked(info) => {
+                assert_eq!(
+                    info.interrupt.unwrap().value,
+                    serde_json::json!({ "amount": 120 })
+                );
+                info.resume_token.unwrap()
+            }
+            other => panic!("expected Blocked, got {:?}", other),
+        };
+        let approval = serde_json::json!({ "decision": "approved" });
+        let status = runner
+            .resume_with_value(&run_id, initial.clone(), &token, approval.clone())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+        let state = runner.state_at(&run_id, u64::MAX, initial.clone()).unwrap();
+        assert_eq!(
+            state.graph_state.messages.last().unwrap().content,
+            "approved"
+        );
+
+        assert!(matches!(
+            runner.resume_with_value(&run_id, initial, &token, approval),
+            Err(KernelError::AlreadyResumed { .. })
+        ));
+    }
+
+    /// An endless loop stops at the config's step limit with a Failed event.
+    #[test]
+    fn graph_step_adapter_enforces_step_limit() {
+        use crate::kernel::EventStore;
+        use crate::kernel::SharedEventStore;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "work",
+                function_node("work", |_s: &MessagesState| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "work");
+        let mut mapping = std::collections::HashMap::new();
+        mapping.insert("again".to_string(), "work".to_string());
+        mapping.insert("done".to_string(), END.to_string());
+        graph.add_conditional_edges_sync("work", |_s: &MessagesState| "again".to_string(), mapping);
+        let compiled = Arc::new(graph.compile().unwrap());
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::with_config(
+                compiled,
+                RunnableConfig::new().with_step_limit(2),
+            )),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "graph-step-limit".to_string();
+        let status = KernelRunner::new(kernel)
+            .run_until_blocked_sync(&run_id, GraphStepState::new(MessagesState::new()))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Failed { recoverable: true }));
+
+        let log: Vec<_> = events
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(
+            log.iter()
+                .filter(|e| matches!(e, Event::StateUpdated { .. }))
+                .count(),
+            2
+        );
+        assert!(matches!(
+            log.last(),
+            Some(Event::Failed { reason, code: Some(code) })
+                if reason == "Step limit of 2 exceeded after node 'work'" && code == "step_limit_exceeded"
+        ));
+    }
+
+    /// A config whose deadline has passed fails before the next node with its code.
+    #[test]
+    fn graph_step_adapter_enforces_deadline() {
+        use crate::kernel::EventStore;
+        use crate::kernel::SharedEventStore;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "node1",
+                function_node("node1", |_s: &MessagesState| async move {
+                    Ok(std::collections::HashMap::new())
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "node1");
+        graph.add_edge("node1", END);
+        let compiled = Arc::new(graph.compile().unwrap());
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel: Kernel<GraphStepState<MessagesState>> = Kernel {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::with_config(
+                compiled,
+                RunnableConfig::new().with_deadline(std::time::Instant::now()),
+            )),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "graph-deadline".to_string();
+        let status = KernelRunner::new(kernel)
+            .run_until_blocked_sync(&run_id, GraphStepState::new(MessagesState::new()))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Failed { recoverable: true }));
+
+        let events = events.scan(&run_id, 1).unwrap();
+        assert!(matches!(
+            events.last().map(|record| &record.event),
+            Some(Event::Failed { code: Some(code), .. }) if code == "deadline_exceeded"
+        ));
+    }
+
+    /// Graph reducers shape the state the kernel records, so replaying its events
+    /// reproduces the state of a direct invoke byte for byte.
+    #[test]
+    fn graph_step_replay_matches_invoke_with_reducers() {
+        use crate::graph::messages_state_update;
+        use crate::kernel::SharedEventStore;
+        use crate::schemas::messages::Message;
+
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["a", "b", "c"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, move |_s: &MessagesState| async move {
+                        Ok(messages_state_update(vec![Message::new_ai_message(name)]))
+                    }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "a");
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "c");
+        graph.add_edge("c", END);
+        // Keep only the two most recent messages
+        graph.set_reducer("messages", |current: &Value, update: &Value| {
+            let mut messages = current.as_array().cloned().unwrap_or_default();
+            messages.extend(update.as_array().cloned().unwrap_or_default());
+            let skip = messages.len().saturating_sub(2);
+            Value::Array(messages.split_off(skip))
+        });
+        let compiled = Arc::new(graph.compile().unwrap());
+
+        let invoked = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(compiled.invoke(MessagesState::new()))
+            .unwrap();
+        assert_eq!(invoked.messages.len(), 2);
+
+        let events = Arc::new(InMemoryEventStore::new());
+        let kernel = || Kernel::<GraphStepState<MessagesState>> {
+            events: Box::new(SharedEventStore(events.clone())),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(NoopActionExecutor),
+            step: Box::new(GraphStepFnAdapter::new(compiled.clone())),
+            policy: Box::new(AllowAllPolicy),
+            effect_sink: None,
+            mode: crate::kernel::KernelMode::Normal,
+            snapshot_policy: None,
+        };
+        let run_id = "graph-step-reducers".to_string();
+        let status = KernelRunner::new(kernel())
+            .run_until_blocked_sync(&run_id, GraphStepState::new(MessagesState::new()))
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let replayed = kernel()
+            .replay(&run_id, GraphStepState::new(MessagesState::new()))
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&replayed.graph_state).unwrap(),
+            serde_json::to_string(&invoked).unwrap()
+        );
+
+        use crate::kernel::EventStore;
+        let merged: Vec<_> = events
+            .scan(&run_id, 1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|se| match se.event {
+                Event::StateUpdated { merge_report, .. } => merge_report.map(|r| r.merged),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(merged, vec![vec!["messages".to_string()]; 3]);
+    }
 }
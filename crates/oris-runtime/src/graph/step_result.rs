@@ -4,6 +4,7 @@
 use serde_json::Value;
 
 use crate::graph::state::State;
+use crate::kernel::action::Action;
 
 /// Result of executing one node in the graph.
 #[derive(Debug, Clone)]
@@ -17,6 +18,11 @@ pub enum GraphStepOnceResult<S: State> {
     },
     /// Interrupt reached (e.g. human-in-the-loop).
     Interrupt { state: S, value: Value },
-    /// Graph reached END.
-    Complete { state: S },
+    /// The node requested a kernel action; run it again once the action is done.
+    Action { state: S, action: Action },
+    /// Graph reached END; `executed_node` is the node run on the way there, if any.
+    Complete {
+        executed_node: Option<String>,
+        state: S,
+    },
 }
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::graph::{
+    error::GraphError,
+    state::{State, StateUpdate},
+};
+
+/// Granularity of node payloads yielded by `CompiledGraph::stream_with_config_and_payload`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StreamPayload {
+    /// Yield only the update (delta) returned by each node
+    #[default]
+    Updates,
+    /// Yield the full merged state after each node
+    Values,
+}
+
+/// Node output carried by [GraphStreamEvent::NodeFinished]
+#[derive(Clone, Debug)]
+pub enum NodePayload<S: State> {
+    /// The update (delta) returned by the node
+    Update(StateUpdate),
+    /// The full state after the node's update was merged
+    Values(S),
+}
+
+/// Event yielded by `CompiledGraph::stream_with_config`
+///
+/// Unlike [crate::graph::StreamEvent], these events follow a checkpointed run:
+/// every checkpoint written during the run is reported, so a consumer can resume
+/// from the last one if the stream is dropped.
+#[derive(Clone, Debug)]
+pub enum GraphStreamEvent<S: State> {
+    /// A node is about to be executed
+    NodeStarted { node: String },
+    /// A node completed and its output was merged into the state
+    NodeFinished {
+        node: String,
+        update: NodePayload<S>,
+    },
+    /// A checkpoint was persisted
+    CheckpointWritten { checkpoint_id: String },
+    /// A node called `interrupt()` or a static breakpoint was reached; the run is paused
+    /// at the last written checkpoint
+    Interrupted { node: String, value: Value },
+    /// The run reached END
+    RunCompleted { final_state: S },
+    /// The run failed
+    Error { error: Arc<GraphError> },
+}
+
+impl<S: State> GraphStreamEvent<S> {
+    /// Whether this event ends the stream
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Interrupted { .. } | Self::RunCompleted { .. } | Self::Error { .. }
+        )
+    }
+}
@@ -1,4 +1,5 @@
 pub mod chunk;
+pub mod event;
 pub mod metadata;
 pub mod mode;
 pub mod writer;
@@ -7,6 +8,7 @@ pub mod writer;
 mod tests;
 
 pub use chunk::*;
+pub use event::*;
 pub use metadata::*;
 pub use mode::*;
 pub use writer::*;
@@ -0,0 +1,302 @@
+//! Test utilities for node plugin authors (feature `testing`).
+//!
+//! [NodeTestHarness] builds a node from a plugin and a config the way a
+//! [NodePluginRegistry] does (placeholder expansion and schema validation included) and
+//! runs it directly against a state, with no graph or checkpointer. Its virtual clock
+//! lets nodes that sleep finish instantly, and it can hand the node a run deadline or
+//! cancellation token. [UpdateAssertions] checks the update a node returned.
+//!
+//! ```rust,ignore
+//! let harness = NodeTestHarness::new(delay_node_plugin(), json!({"message": "hi", "delay_ms": 5000}))?
+//!     .with_virtual_time();
+//! harness.run(&MessagesState::new()).await?.assert_appends_ai_message("hi");
+//! assert!(harness.last_run_duration() >= Duration::from_secs(5));
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::schemas::messages::{Message, MessageType};
+
+use super::{
+    deadline::{with_cancellation, with_deadline},
+    error::GraphError,
+    node::Node,
+    plugin::{NodePlugin, NodePluginRegistry},
+    state::{State, StateUpdate},
+    CancellationToken,
+};
+
+/// Name the harness gives the node when built with [`NodeTestHarness::new`]
+pub const HARNESS_NODE_NAME: &str = "node";
+
+/// Runs one node built by a plugin, for plugin unit tests
+pub struct NodeTestHarness<S: State> {
+    node: Arc<dyn Node<S>>,
+    virtual_time: bool,
+    deadline: Option<Instant>,
+    cancellation: Option<CancellationToken>,
+    last_run: Mutex<Duration>,
+}
+
+impl<S: State + 'static> NodeTestHarness<S> {
+    /// Build the node `plugin` creates from `config`, named [HARNESS_NODE_NAME]
+    ///
+    /// Fails like `NodePluginRegistry::create_node` does: on configs the plugin's schema
+    /// or factory rejects and on unresolvable placeholders.
+    pub fn new(plugin: impl NodePlugin<S> + 'static, config: Value) -> Result<Self, GraphError> {
+        let plugin_type = plugin.plugin_type().to_string();
+        let mut registry = NodePluginRegistry::new();
+        registry.register_plugin(plugin)?;
+        Self::from_registry(&registry, HARNESS_NODE_NAME, &plugin_type, config)
+    }
+
+    /// Build node `name` from a plugin of `registry`, e.g. one with a custom secret resolver
+    pub fn from_registry(
+        registry: &NodePluginRegistry<S>,
+        name: &str,
+        plugin_type: &str,
+        config: Value,
+    ) -> Result<Self, GraphError> {
+        Ok(Self::from_node(registry.create_node(
+            name,
+            plugin_type,
+            &config,
+        )?))
+    }
+
+    /// Wrap a node built by other means
+    pub fn from_node(node: Arc<dyn Node<S>>) -> Self {
+        Self {
+            node,
+            virtual_time: false,
+            deadline: None,
+            cancellation: None,
+            last_run: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Run the node on a paused tokio clock, so its sleeps and timeouts elapse instantly
+    ///
+    /// Needs a current-thread runtime, such as `#[tokio::test]`'s default one, whose
+    /// clock is not already paused.
+    pub fn with_virtual_time(mut self) -> Self {
+        self.virtual_time = true;
+        self
+    }
+
+    /// Run the node with `deadline` as its [`current_deadline`](super::current_deadline)
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Run the node with `token` as its [`current_cancellation`](super::current_cancellation)
+    ///
+    /// Unlike a graph, the harness does not stop the node when the token fires, so tests
+    /// see what the node itself returns.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Run the node once on `state`, returning its update
+    pub async fn run(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        if self.virtual_time {
+            if Handle::current().runtime_flavor() != RuntimeFlavor::CurrentThread {
+                return Err(GraphError::ExecutionError(
+                    "NodeTestHarness virtual time needs a current-thread runtime".to_string(),
+                ));
+            }
+            tokio::time::pause();
+        }
+        let start = tokio::time::Instant::now();
+        let result = with_cancellation(
+            self.cancellation.clone(),
+            with_deadline(self.deadline, self.node.invoke(state)),
+        )
+        .await;
+        *self.last_run.lock().unwrap_or_else(|e| e.into_inner()) = start.elapsed();
+        if self.virtual_time {
+            tokio::time::resume();
+        }
+        result
+    }
+
+    /// Run the node once on `state` and apply its update with the state's reducers
+    pub async fn run_and_apply(&self, state: &S) -> Result<S, GraphError> {
+        let update = self.run(state).await?;
+        state.apply_update(&update)
+    }
+
+    /// How long the last run took; virtual time under [`with_virtual_time`](Self::with_virtual_time)
+    pub fn last_run_duration(&self) -> Duration {
+        *self.last_run.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Assertions on the update returned by a node, panicking with the update on failure
+pub trait UpdateAssertions {
+    /// Messages the update appends to `messages`
+    fn appended_messages(&self) -> Vec<Message>;
+
+    /// Assert the update appends exactly one AI message containing `contains`; returns it
+    fn assert_appends_ai_message(&self, contains: &str) -> Message;
+
+    /// Assert the update appends exactly one message of `message_type` containing
+    /// `contains`; returns it
+    fn assert_appends_message(&self, message_type: MessageType, contains: &str) -> Message;
+
+    /// Assert the update sets `field` to `expected`
+    fn assert_sets(&self, field: &str, expected: impl Serialize);
+}
+
+impl UpdateAssertions for StateUpdate {
+    #[track_caller]
+    fn appended_messages(&self) -> Vec<Message> {
+        match self.get("messages") {
+            Some(messages) => serde_json::from_value(messages.clone())
+                .unwrap_or_else(|e| panic!("update has malformed messages ({}): {:?}", e, self)),
+            None => Vec::new(),
+        }
+    }
+
+    #[track_caller]
+    fn assert_appends_ai_message(&self, contains: &str) -> Message {
+        self.assert_appends_message(MessageType::AIMessage, contains)
+    }
+
+    #[track_caller]
+    fn assert_appends_message(&self, message_type: MessageType, contains: &str) -> Message {
+        let mut messages = self.appended_messages();
+        assert!(
+            messages.len() == 1,
+            "expected one appended message, got {}: {:?}",
+            messages.len(),
+            self
+        );
+        let message = messages.remove(0);
+        assert!(
+            std::mem::discriminant(&message.message_type) == std::mem::discriminant(&message_type),
+            "expected a {:?}, got {:?}",
+            message_type,
+            message
+        );
+        assert!(
+            message.content.contains(contains),
+            "expected the message to contain {:?}, got {:?}",
+            contains,
+            message.content
+        );
+        message
+    }
+
+    #[track_caller]
+    fn assert_sets(&self, field: &str, expected: impl Serialize) {
+        let expected = serde_json::to_value(expected).expect("expected value serializes");
+        assert_eq!(
+            self.get(field),
+            Some(&expected),
+            "field '{}' of update {:?}",
+            field,
+            self
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+    use crate::graph::{function_node, messages_state_update, typed_node_plugin, MessagesState};
+
+    #[derive(Deserialize)]
+    struct SleepConfig {
+        millis: u64,
+    }
+
+    fn sleep_plugin() -> impl NodePlugin<MessagesState> {
+        typed_node_plugin("test/sleep", |name, config: SleepConfig| {
+            Ok(Arc::new(function_node(
+                name,
+                move |_state: &MessagesState| {
+                    let millis = config.millis;
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(millis)).await;
+                        Ok(messages_state_update(vec![Message::new_ai_message(
+                            format!("slept {}ms", millis),
+                        )]))
+                    }
+                },
+            )))
+        })
+    }
+
+    #[tokio::test]
+    async fn virtual_time_skips_sleeps() {
+        let harness = NodeTestHarness::new(sleep_plugin(), json!({"millis": 3_600_000}))
+            .unwrap()
+            .with_virtual_time();
+        let started = std::time::Instant::now();
+
+        let state = harness.run_and_apply(&MessagesState::new()).await.unwrap();
+        assert_eq!(state.messages[0].content, "slept 3600000ms");
+        let slept = harness.last_run_duration();
+        assert!(slept >= Duration::from_secs(3600) && slept < Duration::from_secs(3601));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // The clock runs again afterwards, so a second run works too
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("slept");
+    }
+
+    #[tokio::test]
+    async fn nodes_see_the_harness_deadline_and_cancellation() {
+        let node = function_node("probe", |_state: &MessagesState| async move {
+            let seen = format!(
+                "deadline={} cancelled={:?}",
+                crate::graph::current_deadline().is_some(),
+                crate::graph::current_cancellation().map(|token| token.is_cancelled())
+            );
+            Ok(messages_state_update(vec![Message::new_ai_message(seen)]))
+        });
+        let harness = NodeTestHarness::from_node(Arc::new(node));
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("deadline=false cancelled=None");
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let harness = harness
+            .with_deadline(Instant::now() + Duration::from_secs(1))
+            .with_cancellation(token);
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("deadline=true cancelled=Some(true)");
+    }
+
+    #[test]
+    fn invalid_configs_fail_to_build() {
+        assert!(NodeTestHarness::new(sleep_plugin(), json!({"millis": "soon"})).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the message to contain")]
+    fn assertions_report_mismatches() {
+        messages_state_update(vec![Message::new_ai_message("hello")])
+            .assert_appends_ai_message("goodbye");
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    edge::{Edge, EdgeType, END, START},
+    node::Node,
+    node_options::{NodeOptions, OnTimeout},
+    state::State,
+};
+
+/// A structural problem found when compiling a graph
+///
+/// `StateGraph::compile()` collects every issue and reports them together in
+/// `GraphError::ValidationFailed`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationIssue {
+    /// An edge starts at a node that does not exist
+    UnknownEdgeSource { from: String },
+    /// A regular edge points to a node that does not exist
+    UnknownEdgeTarget { from: String, to: String },
+    /// A conditional edge maps a router key to a node that does not exist
+    UnknownConditionalTarget {
+        from: String,
+        key: String,
+        to: String,
+    },
+    /// A timeout fallback names a node that does not exist
+    UnknownTimeoutFallback { node: String, fallback: String },
+    /// No edge path leads from START to the node
+    UnreachableNode { node: String },
+    /// The node is reachable but no edge path leads from it to END
+    DeadEnd { node: String },
+    /// The node has no outgoing edges and cannot jump elsewhere with a goto
+    NoOutgoingEdges { node: String },
+    /// No edge path leads from START to END
+    NoPathToEnd,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownEdgeSource { from } => {
+                write!(f, "edge source node '{}' not found", from)
+            }
+            Self::UnknownEdgeTarget { from, to } => {
+                write!(f, "edge '{}' -> '{}': target node not found", from, to)
+            }
+            Self::UnknownConditionalTarget { from, key, to } => write!(
+                f,
+                "conditional edge from '{}' maps '{}' to unknown node '{}'",
+                from, key, to
+            ),
+            Self::UnknownTimeoutFallback { node, fallback } => write!(
+                f,
+                "timeout fallback '{}' for node '{}' not found",
+                fallback, node
+            ),
+            Self::UnreachableNode { node } => {
+                write!(f, "node '{}' is unreachable from START", node)
+            }
+            Self::DeadEnd { node } => write!(f, "node '{}' has no path to END", node),
+            Self::NoOutgoingEdges { node } => write!(
+                f,
+                "node '{}' has no outgoing edges and does not return a goto",
+                node
+            ),
+            Self::NoPathToEnd => write!(f, "no path from START to END"),
+        }
+    }
+}
+
+/// Check a graph's structure and return every issue found, in a stable order
+pub(crate) fn validate_graph<S: State>(
+    nodes: &HashMap<String, Arc<dyn Node<S>>>,
+    edges: &[Edge<S>],
+    node_options: &HashMap<String, NodeOptions>,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let is_target = |name: &str| name == END || nodes.contains_key(name);
+
+    for edge in edges {
+        if edge.from != START && !nodes.contains_key(&edge.from) {
+            issues.push(ValidationIssue::UnknownEdgeSource {
+                from: edge.from.clone(),
+            });
+        }
+        match &edge.edge_type {
+            EdgeType::Regular { to } => {
+                if !is_target(to) {
+                    issues.push(ValidationIssue::UnknownEdgeTarget {
+                        from: edge.from.clone(),
+                        to: to.clone(),
+                    });
+                }
+            }
+            EdgeType::Conditional { mapping, .. } => {
+                let mut mapping: Vec<_> = mapping.iter().collect();
+                mapping.sort();
+                for (key, to) in mapping {
+                    if !is_target(to) {
+                        issues.push(ValidationIssue::UnknownConditionalTarget {
+                            from: edge.from.clone(),
+                            key: key.clone(),
+                            to: to.clone(),
+                        });
+                    }
+                }
+            }
+            EdgeType::FanOut { workers, join, .. } => {
+                for to in workers {
+                    if !nodes.contains_key(to) {
+                        issues.push(ValidationIssue::UnknownEdgeTarget {
+                            from: edge.from.clone(),
+                            to: to.clone(),
+                        });
+                    }
+                }
+                if !is_target(join) {
+                    issues.push(ValidationIssue::UnknownEdgeTarget {
+                        from: edge.from.clone(),
+                        to: join.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Nodes returning a goto may jump to their declared destinations
+    let mut gotos: Vec<(&String, Vec<String>)> = nodes
+        .iter()
+        .filter_map(|(name, node)| node.goto_destinations().map(|dests| (name, dests)))
+        .collect();
+    gotos.sort();
+    for (node, destinations) in &gotos {
+        for to in destinations {
+            if !is_target(to) {
+                issues.push(ValidationIssue::UnknownEdgeTarget {
+                    from: node.to_string(),
+                    to: to.clone(),
+                });
+            }
+        }
+    }
+
+    let mut fallbacks: Vec<(&String, &String)> = node_options
+        .iter()
+        .filter_map(|(node, options)| match &options.on_timeout {
+            OnTimeout::Fallback(target) => Some((node, target)),
+            _ => None,
+        })
+        .collect();
+    fallbacks.sort();
+    for (node, fallback) in &fallbacks {
+        if !nodes.contains_key(*fallback) {
+            issues.push(ValidationIssue::UnknownTimeoutFallback {
+                node: node.to_string(),
+                fallback: fallback.to_string(),
+            });
+        }
+    }
+
+    // Successors include timeout fallbacks, which the executor can route to, goto
+    // destinations, and fan-out workers, which continue at the join node
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        let targets = successors.entry(edge.from.as_str()).or_default();
+        match &edge.edge_type {
+            EdgeType::Regular { to } => targets.push(to),
+            EdgeType::Conditional { mapping, .. } => {
+                targets.extend(mapping.values().map(String::as_str))
+            }
+            EdgeType::FanOut { workers, join, .. } => {
+                targets.extend(workers.iter().map(String::as_str));
+                targets.push(join);
+                for worker in workers {
+                    successors.entry(worker.as_str()).or_default().push(join);
+                }
+            }
+        }
+    }
+    for (node, fallback) in &fallbacks {
+        successors
+            .entry(node.as_str())
+            .or_default()
+            .push(fallback.as_str());
+    }
+    for (node, destinations) in &gotos {
+        successors
+            .entry(node.as_str())
+            .or_default()
+            .extend(destinations.iter().map(String::as_str));
+    }
+    let mut predecessors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (from, targets) in &successors {
+        for to in targets {
+            predecessors.entry(to).or_default().push(from);
+        }
+    }
+
+    let reachable = reach(START, &successors);
+    if !reachable.contains(END) {
+        issues.push(ValidationIssue::NoPathToEnd);
+    }
+    let reaches_end = reach(END, &predecessors);
+
+    let mut names: Vec<&String> = nodes.keys().collect();
+    names.sort();
+    for name in names {
+        if !reachable.contains(name.as_str()) {
+            issues.push(ValidationIssue::UnreachableNode { node: name.clone() });
+        } else if successors.get(name.as_str()).map_or(true, Vec::is_empty)
+            && nodes[name].goto_destinations().is_none()
+        {
+            issues.push(ValidationIssue::NoOutgoingEdges { node: name.clone() });
+        } else if reachable.contains(END) && !reaches_end.contains(name.as_str()) {
+            issues.push(ValidationIssue::DeadEnd { node: name.clone() });
+        }
+    }
+
+    issues
+}
+
+/// Names reachable from `from` (inclusive) following `adjacency`
+fn reach<'a>(from: &'a str, adjacency: &HashMap<&'a str, Vec<&'a str>>) -> HashSet<&'a str> {
+    let mut seen = HashSet::from([from]);
+    let mut stack = vec![from];
+    while let Some(name) = stack.pop() {
+        for next in adjacency.get(name).into_iter().flatten() {
+            if seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
@@ -0,0 +1,395 @@
+use std::fmt::Write;
+
+use super::{
+    compiled::CompiledGraph,
+    edge::{EdgeType, END, START},
+    state::State,
+};
+
+/// Sorted snapshot of a compiled graph's topology, shared by the DOT and Mermaid renderers
+struct GraphView {
+    nodes: Vec<NodeView>,
+    edges: Vec<EdgeView>,
+}
+
+struct NodeView {
+    name: String,
+    /// Static breakpoints on the node ("before", "after")
+    breakpoints: Vec<&'static str>,
+    /// Topology of a subgraph node, rendered as a cluster
+    subgraph: Option<GraphView>,
+}
+
+impl NodeView {
+    fn label(&self) -> String {
+        if self.breakpoints.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} (interrupt {})", self.name, self.breakpoints.join(", "))
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct EdgeView {
+    from: String,
+    to: String,
+    /// Mapping key of a conditional edge; `None` for static edges
+    key: Option<String>,
+}
+
+impl<S: State + 'static> CompiledGraph<S> {
+    /// Render the graph topology in Graphviz DOT
+    ///
+    /// Static edges are solid and conditional edges are dashed and labelled with their
+    /// mapping keys; fan-out edges are dashed `send` edges to each worker and `join`
+    /// edges from the workers to the join node. Nodes with static breakpoints are bold and say where they pause,
+    /// and subgraph nodes are drawn as clusters entered through their START and left
+    /// through their END. Nodes and edges are sorted, so the output is stable across runs.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        write_dot(&mut out, &self.view(), "", 1);
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph topology as a Mermaid flowchart
+    ///
+    /// Uses the same conventions as [`to_dot`](Self::to_dot): conditional edges are
+    /// dotted and labelled with their mapping keys, and subgraph nodes are Mermaid
+    /// subgraphs. The output is stable across runs.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        write_mermaid(&mut out, &self.view(), "", 1);
+        out
+    }
+
+    fn view(&self) -> GraphView {
+        let (interrupt_before, interrupt_after) = self.breakpoints();
+        let mut nodes: Vec<NodeView> = self
+            .nodes()
+            .iter()
+            .map(|(name, node)| {
+                let mut breakpoints = Vec::new();
+                if interrupt_before.contains(name) {
+                    breakpoints.push("before");
+                }
+                if interrupt_after.contains(name) {
+                    breakpoints.push("after");
+                }
+                NodeView {
+                    name: name.clone(),
+                    breakpoints,
+                    subgraph: node.get_subgraph().map(|subgraph| subgraph.view()),
+                }
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut edges = Vec::new();
+        for edge in self.adjacency().values().flatten() {
+            match &edge.edge_type {
+                EdgeType::Regular { to } => edges.push(EdgeView {
+                    from: edge.from.clone(),
+                    to: to.clone(),
+                    key: None,
+                }),
+                EdgeType::Conditional { mapping, .. } => {
+                    edges.extend(mapping.iter().map(|(key, to)| EdgeView {
+                        from: edge.from.clone(),
+                        to: to.clone(),
+                        key: Some(key.clone()),
+                    }))
+                }
+                EdgeType::FanOut { workers, join, .. } => {
+                    for worker in workers {
+                        edges.push(EdgeView {
+                            from: edge.from.clone(),
+                            to: worker.clone(),
+                            key: Some("send".to_string()),
+                        });
+                        edges.push(EdgeView {
+                            from: worker.clone(),
+                            to: join.clone(),
+                            key: Some("join".to_string()),
+                        });
+                    }
+                }
+            }
+        }
+        edges.sort();
+
+        GraphView { nodes, edges }
+    }
+}
+
+impl GraphView {
+    fn subgraph(&self, name: &str) -> Option<&GraphView> {
+        self.nodes
+            .iter()
+            .find(|node| node.name == name)
+            .and_then(|node| node.subgraph.as_ref())
+    }
+
+    /// Id of an edge endpoint; edges into and out of a subgraph attach to its START and END
+    fn endpoint(&self, prefix: &str, name: &str, incoming: bool) -> String {
+        match self.subgraph(name) {
+            Some(_) => format!("{}{}/{}", prefix, name, if incoming { START } else { END }),
+            None => format!("{}{}", prefix, name),
+        }
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_dot(out: &mut String, view: &GraphView, prefix: &str, depth: usize) {
+    let pad = indent(depth);
+    for (name, label) in [(START, "START"), (END, "END")] {
+        let _ = writeln!(
+            out,
+            "{}{} [label={}, shape=oval];",
+            pad,
+            dot_quote(&format!("{}{}", prefix, name)),
+            dot_quote(label)
+        );
+    }
+    for node in &view.nodes {
+        let id = format!("{}{}", prefix, node.name);
+        match &node.subgraph {
+            Some(subgraph) => {
+                let _ = writeln!(
+                    out,
+                    "{}subgraph {} {{",
+                    pad,
+                    dot_quote(&format!("cluster_{}", id))
+                );
+                let _ = writeln!(out, "{}    label={};", pad, dot_quote(&node.label()));
+                write_dot(out, subgraph, &format!("{}/", id), depth + 1);
+                let _ = writeln!(out, "{}}}", pad);
+            }
+            None => {
+                let style = if node.breakpoints.is_empty() {
+                    ""
+                } else {
+                    ", style=bold"
+                };
+                let _ = writeln!(
+                    out,
+                    "{}{} [label={}, shape=box{}];",
+                    pad,
+                    dot_quote(&id),
+                    dot_quote(&node.label()),
+                    style
+                );
+            }
+        }
+    }
+    for edge in &view.edges {
+        let from = view.endpoint(prefix, &edge.from, false);
+        let to = view.endpoint(prefix, &edge.to, true);
+        let attrs = match &edge.key {
+            Some(key) => format!(" [label={}, style=dashed]", dot_quote(key)),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            out,
+            "{}{} -> {}{};",
+            pad,
+            dot_quote(&from),
+            dot_quote(&to),
+            attrs
+        );
+    }
+}
+
+/// Mermaid ids only allow a restricted character set
+fn mermaid_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn write_mermaid(out: &mut String, view: &GraphView, prefix: &str, depth: usize) {
+    let pad = indent(depth);
+    for (name, label) in [(START, "START"), (END, "END")] {
+        let _ = writeln!(
+            out,
+            "{}{}([\"{}\"])",
+            pad,
+            mermaid_id(&format!("{}{}", prefix, name)),
+            label
+        );
+    }
+    for node in &view.nodes {
+        let id = format!("{}{}", prefix, node.name);
+        match &node.subgraph {
+            Some(subgraph) => {
+                let _ = writeln!(
+                    out,
+                    "{}subgraph {}[\"{}\"]",
+                    pad,
+                    mermaid_id(&id),
+                    mermaid_text(&node.label())
+                );
+                write_mermaid(out, subgraph, &format!("{}/", id), depth + 1);
+                let _ = writeln!(out, "{}end", pad);
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "{}{}[\"{}\"]",
+                    pad,
+                    mermaid_id(&id),
+                    mermaid_text(&node.label())
+                );
+            }
+        }
+    }
+    for edge in &view.edges {
+        let from = mermaid_id(&view.endpoint(prefix, &edge.from, false));
+        let to = mermaid_id(&view.endpoint(prefix, &edge.to, true));
+        let _ = match &edge.key {
+            Some(key) => writeln!(
+                out,
+                "{}{} -.->|\"{}\"| {}",
+                pad,
+                from,
+                mermaid_text(key),
+                to
+            ),
+            None => writeln!(out, "{}{} --> {}", pad, from, to),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::graph::{function_node, InMemorySaver, MessagesState, StateGraph, END, START};
+
+    fn review_graph() -> StateGraph<MessagesState> {
+        let mut graph = StateGraph::<MessagesState>::new();
+        for name in ["draft", "review", "publish"] {
+            graph
+                .add_node(
+                    name,
+                    function_node(name, |_s: &MessagesState| async move { Ok(HashMap::new()) }),
+                )
+                .unwrap();
+        }
+        graph.add_edge(START, "draft");
+        graph.add_edge("draft", "review");
+        let mut mapping = HashMap::new();
+        mapping.insert("approve".to_string(), "publish".to_string());
+        mapping.insert("revise".to_string(), "draft".to_string());
+        graph.add_conditional_edges_sync(
+            "review",
+            |_s: &MessagesState| "approve".to_string(),
+            mapping,
+        );
+        graph.add_edge("publish", END);
+        graph
+    }
+
+    #[test]
+    fn to_dot_renders_three_node_graph() {
+        let compiled = review_graph()
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["publish"], &[])
+            .unwrap();
+        assert_eq!(
+            compiled.to_dot(),
+            r#"digraph {
+    "__start__" [label="START", shape=oval];
+    "__end__" [label="END", shape=oval];
+    "draft" [label="draft", shape=box];
+    "publish" [label="publish (interrupt before)", shape=box, style=bold];
+    "review" [label="review", shape=box];
+    "__start__" -> "draft";
+    "draft" -> "review";
+    "publish" -> "__end__";
+    "review" -> "draft" [label="revise", style=dashed];
+    "review" -> "publish" [label="approve", style=dashed];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn to_mermaid_renders_three_node_graph() {
+        let compiled = review_graph()
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["publish"], &[])
+            .unwrap();
+        assert_eq!(
+            compiled.to_mermaid(),
+            r#"flowchart TD
+    __start__(["START"])
+    __end__(["END"])
+    draft["draft"]
+    publish["publish (interrupt before)"]
+    review["review"]
+    __start__ --> draft
+    draft --> review
+    publish --> __end__
+    review -.->|"revise"| draft
+    review -.->|"approve"| publish
+"#
+        );
+    }
+
+    #[test]
+    fn subgraphs_render_as_clusters() {
+        let mut child = StateGraph::<MessagesState>::new();
+        child
+            .add_node(
+                "lint",
+                function_node(
+                    "lint",
+                    |_s: &MessagesState| async move { Ok(HashMap::new()) },
+                ),
+            )
+            .unwrap();
+        child.add_edge(START, "lint");
+        child.add_edge("lint", END);
+
+        let mut parent = StateGraph::<MessagesState>::new();
+        parent
+            .add_subgraph("check", child.compile().unwrap())
+            .unwrap();
+        parent.add_edge(START, "check");
+        parent.add_edge("check", END);
+        let compiled = parent.compile().unwrap();
+
+        assert_eq!(
+            compiled.to_mermaid(),
+            r#"flowchart TD
+    __start__(["START"])
+    __end__(["END"])
+    subgraph check["check"]
+        check___start__(["START"])
+        check___end__(["END"])
+        check_lint["lint"]
+        check___start__ --> check_lint
+        check_lint --> check___end__
+    end
+    __start__ --> check___start__
+    check___end__ --> __end__
+"#
+        );
+        assert!(compiled
+            .to_dot()
+            .contains("    subgraph \"cluster_check\" {\n        label=\"check\";\n"));
+        assert_eq!(compiled.to_dot(), compiled.to_dot());
+    }
+}
@@ -0,0 +1,439 @@
+//! Sandboxed node plugins compiled to WebAssembly (feature `wasm-plugins`).
+//!
+//! [WasmNodePlugin] builds nodes from WASM modules named in the node config. A module
+//! runs in a fresh instance per invocation with a fuel budget, a wall-clock limit and a
+//! memory cap, and only gets a WASI context without preopened directories, sockets,
+//! environment or arguments, so it cannot touch the host's filesystem or network.
+//!
+//! Modules exchange JSON with the host through their linear memory and export:
+//!
+//! - `memory`: the linear memory
+//! - `alloc(len: i32) -> i32`: returns a buffer of `len` bytes for the input
+//! - `run(ptr: i32, len: i32) -> i64`: reads the serialized state from the buffer and
+//!   returns the output's pointer in the high 32 bits and its length in the low 32 bits
+//!
+//! The output is `{"update": {...}}` with the state update, or `{"error": "..."}`.
+//! `examples/plugin_reference_wasm` is a minimal module written in Rust.
+
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::WasiCtxBuilder;
+
+use crate::plugins::PluginMetadata;
+
+use super::{
+    error::GraphError,
+    node::Node,
+    plugin::{NodePlugin, PLUGIN_API_VERSION},
+    state::{State, StateUpdate},
+};
+
+/// Plugin type of [WasmNodePlugin]
+pub const WASM_NODE_PLUGIN_TYPE: &str = "oris/wasm";
+
+/// Interval between epoch ticks, the granularity of [WasmNodeConfig::timeout_ms]
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Config of a node built by [WasmNodePlugin]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct WasmNodeConfig {
+    /// Path of the module, as a `.wasm` binary or `.wat` text
+    pub module: PathBuf,
+    /// Fuel per invocation; roughly one unit per executed instruction
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+    /// Wall-clock limit per invocation in milliseconds
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Maximum size of the module's linear memory in bytes
+    #[serde(default = "default_max_memory_bytes")]
+    pub max_memory_bytes: usize,
+}
+
+fn default_fuel() -> u64 {
+    1_000_000_000
+}
+
+fn default_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_max_memory_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+/// JSON Schema of [WasmNodeConfig], published through [NodePlugin::config_schema]
+pub fn wasm_node_config_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "module": {
+                "type": "string",
+                "description": "Path of the module, as a .wasm binary or .wat text."
+            },
+            "fuel": {
+                "type": "integer",
+                "minimum": 1,
+                "default": default_fuel(),
+                "description": "Fuel per invocation; roughly one unit per executed instruction."
+            },
+            "timeout_ms": {
+                "type": "integer",
+                "minimum": 1,
+                "default": default_timeout_ms(),
+                "description": "Wall-clock limit per invocation in milliseconds."
+            },
+            "max_memory_bytes": {
+                "type": "integer",
+                "minimum": 65536,
+                "default": default_max_memory_bytes(),
+                "description": "Maximum size of the module's linear memory in bytes."
+            }
+        },
+        "required": ["module"]
+    })
+}
+
+/// Node plugin running sandboxed WASM modules, for any state type
+///
+/// ```rust,ignore
+/// registry.register_plugin(WasmNodePlugin)?;
+/// graph.add_plugin_node(
+///     "score",
+///     WASM_NODE_PLUGIN_TYPE,
+///     json!({"module": "plugins/score.wasm", "fuel": 10_000_000, "timeout_ms": 200}),
+///     &registry,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WasmNodePlugin;
+
+impl<S: State + 'static> NodePlugin<S> for WasmNodePlugin {
+    fn plugin_type(&self) -> &str {
+        WASM_NODE_PLUGIN_TYPE
+    }
+
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    fn plugin_metadata(&self) -> PluginMetadata {
+        // Modules can read clocks and randomness, but nothing outside the sandbox
+        PluginMetadata {
+            side_effects: false,
+            ..PluginMetadata::conservative()
+        }
+    }
+
+    fn display_name(&self) -> &str {
+        "WASM module"
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("Runs a WebAssembly module on the state JSON with fuel, time and memory limits.")
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn config_schema(&self) -> Option<Value> {
+        Some(wasm_node_config_schema())
+    }
+
+    fn create_node(&self, name: &str, config: &Value) -> Result<Arc<dyn Node<S>>, GraphError> {
+        let config: WasmNodeConfig = serde_json::from_value(config.clone()).map_err(|e| {
+            GraphError::CompilationError(format!(
+                "Invalid config for plugin '{}': {}",
+                WASM_NODE_PLUGIN_TYPE, e
+            ))
+        })?;
+        Ok(Arc::new(WasmNode::new(name, config)?))
+    }
+}
+
+/// Node running one WASM module, built by [WasmNodePlugin]
+pub struct WasmNode {
+    name: String,
+    config: WasmNodeConfig,
+    module: Module,
+    linker: Arc<Linker<HostState>>,
+}
+
+struct HostState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+impl WasmNode {
+    /// Compile the module and check that it exports the node ABI
+    pub fn new(name: impl Into<String>, config: WasmNodeConfig) -> Result<Self, GraphError> {
+        let name = name.into();
+        let compile_error = |message: String| {
+            GraphError::CompilationError(format!(
+                "WASM node '{}' module '{}': {}",
+                name,
+                config.module.display(),
+                message
+            ))
+        };
+
+        let module = Module::from_file(engine(), &config.module)
+            .map_err(|e| compile_error(format!("{:#}", e)))?;
+        for export in ["memory", "alloc", "run"] {
+            if module.get_export(export).is_none() {
+                return Err(compile_error(format!("missing export `{}`", export)));
+            }
+        }
+        let mut linker = Linker::new(engine());
+        preview1::add_to_linker_sync(&mut linker, |host: &mut HostState| &mut host.wasi)
+            .map_err(|e| compile_error(format!("{:#}", e)))?;
+
+        Ok(Self {
+            name,
+            config,
+            module,
+            linker: Arc::new(linker),
+        })
+    }
+
+    fn run_error(&self, error: wasmtime::Error) -> GraphError {
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::Interrupt) => GraphError::NodeTimeout {
+                node: self.name.clone(),
+                elapsed: Duration::from_millis(self.config.timeout_ms),
+            },
+            Some(Trap::OutOfFuel) => GraphError::ExecutionError(format!(
+                "WASM node '{}' ran out of fuel ({} units)",
+                self.name, self.config.fuel
+            )),
+            Some(trap) => {
+                GraphError::ExecutionError(format!("WASM node '{}' trapped: {}", self.name, trap))
+            }
+            None => {
+                GraphError::ExecutionError(format!("WASM node '{}' failed: {:#}", self.name, error))
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WasmOutput {
+    Update(StateUpdate),
+    Error(String),
+}
+
+#[async_trait]
+impl<S: State + 'static> Node<S> for WasmNode {
+    async fn invoke(&self, state: &S) -> Result<StateUpdate, GraphError> {
+        let input = serde_json::to_vec(state)?;
+        let module = self.module.clone();
+        let linker = Arc::clone(&self.linker);
+        let config = self.config.clone();
+        let output =
+            tokio::task::spawn_blocking(move || run_module(&linker, &module, &config, &input))
+                .await
+                .map_err(|e| {
+                    GraphError::ExecutionError(format!("WASM node '{}' failed: {}", self.name, e))
+                })?
+                .map_err(|e| self.run_error(e))?;
+
+        match serde_json::from_slice(&output) {
+            Ok(WasmOutput::Update(update)) => Ok(update),
+            Ok(WasmOutput::Error(message)) => Err(GraphError::ExecutionError(format!(
+                "WASM node '{}' returned an error: {}",
+                self.name, message
+            ))),
+            Err(e) => Err(GraphError::ExecutionError(format!(
+                "WASM node '{}' returned invalid output: {}",
+                self.name, e
+            ))),
+        }
+    }
+}
+
+/// Instantiate the module in a fresh store and call it with `input`
+fn run_module(
+    linker: &Linker<HostState>,
+    module: &Module,
+    config: &WasmNodeConfig,
+    input: &[u8],
+) -> wasmtime::Result<Vec<u8>> {
+    let host = HostState {
+        wasi: WasiCtxBuilder::new().build_p1(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size(config.max_memory_bytes)
+            .build(),
+    };
+    let mut store = Store::new(module.engine(), host);
+    store.limiter(|host| &mut host.limits);
+    store.set_fuel(config.fuel)?;
+    store.set_epoch_deadline(
+        config
+            .timeout_ms
+            .div_ceil(EPOCH_TICK.as_millis() as u64)
+            .max(1),
+    );
+
+    let instance = linker.instantiate(&mut store, module)?;
+    // Modules built as WASI reactors initialize their runtime here
+    if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+        initialize.call(&mut store, ())?;
+    }
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| wasmtime::Error::msg("export `memory` is not a memory"))?;
+    let alloc = instance.get_typed_func::<u32, u32>(&mut store, "alloc")?;
+    let run = instance.get_typed_func::<(u32, u32), u64>(&mut store, "run")?;
+
+    let len = u32::try_from(input.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, ptr as usize, input)?;
+    let packed = run.call(&mut store, (ptr, len))?;
+    let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+    memory.read(&store, (packed >> 32) as usize, &mut output)?;
+    Ok(output)
+}
+
+/// Engine shared by all WASM nodes, with a thread advancing its epoch every [EPOCH_TICK]
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        let engine = Engine::new(&config).expect("fuel and epoch interruption are supported");
+        let ticker = engine.clone();
+        std::thread::Builder::new()
+            .name("oris-wasm-epoch".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                ticker.increment_epoch();
+            })
+            .expect("failed to spawn the WASM epoch thread");
+        engine
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::MessagesState;
+    use crate::schemas::messages::Message;
+
+    /// Module whose `run` executes `body` and returns `output` from a data segment
+    fn module(body: &str, output: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{}")
+                (func (export "alloc") (param i32) (result i32) (i32.const 4096))
+                (func (export "run") (param i32 i32) (result i64)
+                    {}
+                    (i64.or (i64.shl (i64.const 1024) (i64.const 32)) (i64.const {}))))"#,
+            output.replace('\\', "\\\\").replace('"', "\\\""),
+            body,
+            output.len()
+        )
+    }
+
+    fn wasm_node(
+        name: &str,
+        wat: &str,
+        limits: Value,
+    ) -> Result<Arc<dyn Node<MessagesState>>, GraphError> {
+        let path =
+            std::env::temp_dir().join(format!("oris-wasm-{}-{}.wat", std::process::id(), name));
+        std::fs::write(&path, wat).unwrap();
+        let mut config = serde_json::json!({"module": path});
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(limits.as_object().unwrap().clone());
+        let node = NodePlugin::<MessagesState>::create_node(&WasmNodePlugin, name, &config);
+        std::fs::remove_file(&path).unwrap();
+        node
+    }
+
+    fn state() -> MessagesState {
+        MessagesState::with_messages(vec![Message::new_human_message("hi")])
+    }
+
+    #[tokio::test]
+    async fn wasm_node_returns_module_update() {
+        let output = r#"{"update":{"messages":[{"content":"from wasm","message_type":"ai"}]}}"#;
+        let node = wasm_node("update", &module("", output), serde_json::json!({})).unwrap();
+
+        let update = node.invoke(&state()).await.unwrap();
+        let merged = state().apply_update(&update).unwrap();
+        assert_eq!(merged.messages.len(), 2);
+        assert_eq!(merged.messages[1].content, "from wasm");
+    }
+
+    #[tokio::test]
+    async fn wasm_node_reports_module_errors_and_traps() {
+        let node = wasm_node(
+            "error",
+            &module("", r#"{"error":"bad state"}"#),
+            serde_json::json!({}),
+        )
+        .unwrap();
+        let err = node.invoke(&state()).await.unwrap_err();
+        assert!(
+            err.to_string().contains("returned an error: bad state"),
+            "{}",
+            err
+        );
+
+        let node = wasm_node(
+            "trap",
+            &module("(unreachable)", "{}"),
+            serde_json::json!({}),
+        )
+        .unwrap();
+        let err = node.invoke(&state()).await.unwrap_err();
+        assert!(
+            err.to_string().contains("WASM node 'trap' trapped"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn wasm_node_enforces_fuel_and_time_limits() {
+        let spin = module("(loop $spin (br $spin))", "{}");
+
+        let node = wasm_node("fuel", &spin, serde_json::json!({"fuel": 10_000})).unwrap();
+        let err = node.invoke(&state()).await.unwrap_err();
+        assert!(err.to_string().contains("ran out of fuel"), "{}", err);
+
+        let node = wasm_node(
+            "slow",
+            &spin,
+            serde_json::json!({"fuel": u64::MAX, "timeout_ms": 50}),
+        )
+        .unwrap();
+        let err = node.invoke(&state()).await.unwrap_err();
+        assert!(matches!(err, GraphError::NodeTimeout { ref node, .. } if node == "slow"));
+    }
+
+    #[test]
+    fn wasm_node_rejects_modules_without_the_abi() {
+        let err = wasm_node("bare", "(module)", serde_json::json!({}))
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("missing export `memory`"),
+            "{}",
+            err
+        );
+    }
+}
@@ -55,22 +55,26 @@ pub use oris_execution_runtime::{
     AttemptRetryHistoryItem, AttemptRetryHistoryResponse, AuditLogItem, AuditLogListResponse,
     CancelJobRequest, CancelJobResponse, CheckpointInspectResponse, DeadLetterItem,
     DeadLetterListResponse, DeadLetterReplayResponse, InterruptDetailResponse,
-    InterruptListResponse, JobDetailResponse, JobHistoryItem, JobHistoryResponse, JobStateResponse,
-    JobTimelineItem, JobTimelineResponse, ListAuditLogsQuery, ListDeadLettersQuery,
-    ListInterruptsQuery, ListJobsQuery, ListJobsResponse, RejectInterruptRequest, ReplayJobRequest,
+    InterruptListResponse, JobDetailResponse, JobHistoryItem, JobHistoryResponse, JobRunMode,
+    JobStateResponse, JobTimelineItem, JobTimelineResponse, ListAuditLogsQuery,
+    ListDeadLettersQuery, ListInterruptsQuery, ListJobsQuery, ListJobsResponse, PauseJobResponse,
+    RejectInterruptRequest, ReplayJobRequest, ResolveInterruptRequest, ResolveInterruptResponse,
     ResumeInterruptRequest, ResumeJobRequest, RetryPolicyRequest, RunJobRequest, RunJobResponse,
     TimelineExportResponse, TimeoutPolicyRequest, TraceContextResponse, WorkerAckRequest,
     WorkerAckResponse, WorkerExtendLeaseRequest, WorkerHeartbeatRequest, WorkerLeaseResponse,
     WorkerPollRequest, WorkerPollResponse, WorkerReportStepRequest, RUNTIME_API_CONTRACT_DOC_PATH,
 };
 pub use oris_execution_runtime::{
-    AttemptDispatchRecord, AttemptExecutionStatus, InterruptRecord, LeaseConfig, LeaseManager,
-    LeaseRecord, LeaseTickResult, RepositoryLeaseManager, RunRecord, RunRuntimeStatus,
-    RuntimeRepository, SchedulerDecision, SkeletonScheduler,
+    AsyncRuntimeRepository, AttemptDispatchRecord, AttemptExecutionStatus, InterruptDecision,
+    InterruptFilter, InterruptRecord, InterruptStatus, LeaseConfig, LeaseManager, LeaseRecord,
+    LeaseTickResult, RepositoryLeaseManager, RunRecord, RunRuntimeStatus, RuntimeRepository,
+    SchedulerDecision, SkeletonScheduler,
 };
 #[cfg(all(feature = "execution-server", feature = "sqlite-persistence"))]
 pub use oris_execution_runtime::{
     IdempotencyRecord, SqliteIdempotencyStore, SqliteRuntimeRepository,
 };
+#[cfg(feature = "kernel-redis")]
+pub use oris_execution_runtime::{RedisRepositoryConfig, RedisRuntimeRepository};
 #[cfg(feature = "sqlite-persistence")]
 pub use oris_execution_runtime::{RuntimeStorageBackend, RuntimeStorageConfig};
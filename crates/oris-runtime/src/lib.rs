@@ -94,6 +94,9 @@ pub mod language_models;
 pub mod llm;
 /// Memory: simple, conversational, and long-term (Deep Agent). Experimental API in 0.1.x.
 pub mod memory;
+/// Metric names for graph, kernel, scheduler and lease internals (feature-gated).
+#[cfg(feature = "metrics")]
+pub mod metrics;
 /// Output parsers for chains and agents. Experimental API in 0.1.x.
 pub mod output_parsers;
 /// Plugin categories and interfaces (Node, Tool, Memory, LLMAdapter, Scheduler). Experimental API in 0.1.x.
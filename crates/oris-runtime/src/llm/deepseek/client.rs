@@ -385,6 +385,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            metadata: None,
             tool_calls: None,
         }];
 
@@ -401,6 +402,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            metadata: None,
             tool_calls: None,
         }];
 
@@ -417,6 +419,7 @@ mod tests {
             message_type: MessageType::HumanMessage,
             id: Some("test_id".to_string()),
             images: None,
+            metadata: None,
             tool_calls: None,
         }];
 
@@ -0,0 +1,117 @@
+//! Metric names recorded with the `metrics` feature.
+//!
+//! Graph, kernel, scheduler and lease internals record through the
+//! [`metrics`](https://docs.rs/metrics) facade, so any recorder works; nothing is
+//! collected until the application installs one. With a Prometheus exporter the
+//! execution server also serves them on `/metrics` (see
+//! `ExecutionApiState::with_metrics_renderer`). The names below are stable so
+//! dashboards and alerts can rely on them; call [`describe`] after installing the
+//! recorder to register their help text.
+
+pub use oris_execution_runtime::metrics::{
+    DISPATCHABLE_ATTEMPTS, HEARTBEAT_FAILURES_TOTAL, LEASES_EXPIRED_TOTAL, LEASES_GRANTED_TOTAL,
+};
+pub use oris_kernel::kernel::metrics::KERNEL_EVENTS_APPENDED_TOTAL;
+
+/// Histogram (seconds, label `node`): duration of successful graph node runs
+pub const GRAPH_NODE_DURATION_SECONDS: &str = "oris_graph_node_duration_seconds";
+/// Counter (label `node`): graph node runs that failed (interrupts are not failures)
+pub const GRAPH_NODE_ERRORS_TOTAL: &str = "oris_graph_node_errors_total";
+/// Counter: graph checkpoints written
+pub const GRAPH_CHECKPOINTS_WRITTEN_TOTAL: &str = "oris_graph_checkpoints_written_total";
+
+/// Register the descriptions of all Oris metrics with the installed recorder
+pub fn describe() {
+    ::metrics::describe_histogram!(
+        GRAPH_NODE_DURATION_SECONDS,
+        ::metrics::Unit::Seconds,
+        "Duration of successful graph node runs."
+    );
+    ::metrics::describe_counter!(GRAPH_NODE_ERRORS_TOTAL, "Graph node runs that failed.");
+    ::metrics::describe_counter!(
+        GRAPH_CHECKPOINTS_WRITTEN_TOTAL,
+        "Graph checkpoints written."
+    );
+    oris_kernel::kernel::metrics::describe();
+    oris_execution_runtime::metrics::describe();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+
+    use super::*;
+    use crate::graph::{
+        function_node, GraphError, InMemorySaver, MessagesState, RunnableConfig, StateGraph, END,
+        START,
+    };
+
+    #[test]
+    fn records_graph_node_and_checkpoint_metrics() {
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_node(
+                "ok",
+                function_node("ok", |_s: &MessagesState| async move { Ok(HashMap::new()) }),
+            )
+            .unwrap();
+        graph
+            .add_node(
+                "fail",
+                function_node("fail", |_s: &MessagesState| async move {
+                    Err(GraphError::ExecutionError("boom".to_string()))
+                }),
+            )
+            .unwrap();
+        graph.add_edge(START, "ok");
+        graph.add_edge("ok", "fail");
+        graph.add_edge("fail", END);
+        let compiled = graph
+            .compile_with_interrupts(Arc::new(InMemorySaver::new()), &["fail"], &[])
+            .unwrap();
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        ::metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let config = RunnableConfig::with_thread_id("metrics-1");
+                compiled
+                    .invoke_with_config(Some(MessagesState::new()), &config)
+                    .await
+                    .unwrap();
+                assert!(compiled.invoke_with_config(None, &config).await.is_err());
+            })
+        });
+
+        let metrics: HashMap<_, _> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| (key, value))
+            .collect();
+        let find = |kind: MetricKind, name: &str| {
+            metrics
+                .iter()
+                .find(|(key, _)| key.kind() == kind && key.key().name() == name)
+                .map(|(key, value)| (key.key().labels().cloned().collect::<Vec<_>>(), value))
+                .unwrap_or_else(|| panic!("{} not recorded", name))
+        };
+
+        let (labels, value) = find(MetricKind::Histogram, GRAPH_NODE_DURATION_SECONDS);
+        assert_eq!(labels[0].value(), "ok");
+        assert!(matches!(value, DebugValue::Histogram(samples) if samples.len() == 1));
+        let (labels, value) = find(MetricKind::Counter, GRAPH_NODE_ERRORS_TOTAL);
+        assert_eq!(labels[0].value(), "fail");
+        assert_eq!(value, &DebugValue::Counter(1));
+        let (_, value) = find(MetricKind::Counter, GRAPH_CHECKPOINTS_WRITTEN_TOTAL);
+        assert_eq!(value, &DebugValue::Counter(1));
+    }
+}
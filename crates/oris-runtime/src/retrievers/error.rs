@@ -39,6 +39,28 @@ pub enum RetrieverError {
     Unknown(String),
 }
 
+impl RetrieverError {
+    /// Whether the same request may succeed if tried again
+    ///
+    /// Remote API, reranker, vector store and unknown errors are treated as transient;
+    /// configuration, document processing and local indexing errors as fatal.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RetrieverError::WikipediaError(_)
+            | RetrieverError::ArxivError(_)
+            | RetrieverError::TavilyError(_)
+            | RetrieverError::RerankerError(_)
+            | RetrieverError::VectorStoreError(_)
+            | RetrieverError::Unknown(_) => true,
+            RetrieverError::BM25Error(_)
+            | RetrieverError::TFIDFError(_)
+            | RetrieverError::SVMError(_)
+            | RetrieverError::ConfigurationError(_)
+            | RetrieverError::DocumentProcessingError(_) => false,
+        }
+    }
+}
+
 impl From<Box<dyn std::error::Error + Send + Sync>> for RetrieverError {
     fn from(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
         RetrieverError::Unknown(e.to_string())
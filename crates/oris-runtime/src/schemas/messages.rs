@@ -71,6 +71,10 @@ pub struct Message {
     pub id: Option<String>,
     pub tool_calls: Option<Value>,
     pub images: Option<Vec<ImageContent>>,
+    /// Structured data attached by the producer, e.g. a command's exit code; set it with
+    /// [`Message::with_metadata`] and read it with [`Message::metadata`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) metadata: Option<Value>,
 }
 
 impl Message {
@@ -82,6 +86,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            metadata: None,
         }
     }
 
@@ -92,6 +97,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: Some(images.into_iter().map(|i| i.into()).collect()),
+            metadata: None,
         }
     }
 
@@ -103,6 +109,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            metadata: None,
         }
     }
 
@@ -114,6 +121,7 @@ impl Message {
             id: None,
             tool_calls: None,
             images: None,
+            metadata: None,
         }
     }
 
@@ -125,6 +133,7 @@ impl Message {
             id: Some(id.into()),
             tool_calls: None,
             images: None,
+            metadata: None,
         }
     }
 
@@ -142,6 +151,17 @@ impl Message {
         self
     }
 
+    /// Attaches structured metadata to the message.
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Structured metadata attached with [`Message::with_metadata`], if any.
+    pub fn metadata(&self) -> Option<&Value> {
+        self.metadata.as_ref()
+    }
+
     pub fn messages_from_value(value: &Value) -> Result<Vec<Message>, serde_json::error::Error> {
         serde_json::from_value(value.clone())
     }
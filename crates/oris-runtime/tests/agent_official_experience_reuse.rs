@@ -489,11 +489,12 @@ fn build_evo(
         events: Box::new(InMemoryEventStore::new()),
         snaps: None,
         reducer: Box::new(StateUpdatedOnlyReducer),
-        exec: Box::new(NoopActionExecutor),
+        exec: Arc::new(NoopActionExecutor),
         step: Box::new(NoopStepFn),
         policy: Box::new(AllowAllPolicy),
         effect_sink: None,
         mode: KernelMode::Normal,
+        snapshot_policy: None,
     });
 
     let store = Arc::new(JsonlEvolutionStore::new(store_root.to_path_buf()));
@@ -0,0 +1,220 @@
+//! RuntimeWorker against a SQLite runtime repository: enqueued attempts are leased, their
+//! graphs run, and the attempts (and their runs) end up completed, retried, dead-lettered
+//! or cancelled.
+
+#![cfg(feature = "sqlite-persistence")]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use oris_runtime::execution_runtime::{
+    AttemptCancellation, AttemptExecutionStatus, AttemptOutcome, EventStoreFactory, GraphRegistry,
+    RetryPolicyConfig, RetryStrategy, RunRecord, RunRuntimeStatus, RuntimeRepository,
+    RuntimeWorker, RuntimeWorkerConfig, SqliteRuntimeRepository,
+};
+use oris_runtime::graph::{function_node, CompiledGraph, MessagesState, StateGraph, END, START};
+use oris_runtime::kernel::{EventStore, InMemoryEventStore, SharedEventStore};
+
+fn hello_graph() -> Arc<CompiledGraph<MessagesState>> {
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph
+        .add_node(
+            "hello",
+            function_node(
+                "hello",
+                |_s: &MessagesState| async move { Ok(HashMap::new()) },
+            ),
+        )
+        .unwrap();
+    graph.add_edge(START, "hello");
+    graph.add_edge("hello", END);
+    Arc::new(graph.compile().unwrap())
+}
+
+fn enqueue_run(repo: &SqliteRuntimeRepository, run_id: &str, attempt_id: &str) {
+    let now = Utc::now();
+    repo.create_run(&RunRecord {
+        run_id: run_id.to_string(),
+        workflow_name: "hello".to_string(),
+        status: RunRuntimeStatus::Queued,
+        status_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+    .unwrap();
+    repo.enqueue_attempt(attempt_id, run_id).unwrap();
+}
+
+#[tokio::test]
+async fn worker_completes_every_enqueued_attempt() {
+    let repo = Arc::new(SqliteRuntimeRepository::new(":memory:").unwrap());
+    enqueue_run(&repo, "run-a", "attempt-a");
+    enqueue_run(&repo, "run-b", "attempt-b");
+
+    let mut graphs = GraphRegistry::new();
+    graphs.register("hello", hello_graph(), MessagesState::new());
+    let log = Arc::new(InMemoryEventStore::new());
+    let shared = Arc::clone(&log);
+    let event_store: EventStoreFactory =
+        Arc::new(
+            move || Ok(Box::new(SharedEventStore(Arc::clone(&shared))) as Box<dyn EventStore>),
+        );
+    let worker = RuntimeWorker::new(
+        Arc::clone(&repo),
+        Arc::new(graphs),
+        event_store,
+        RuntimeWorkerConfig {
+            worker_id: "worker-test".to_string(),
+            ..RuntimeWorkerConfig::default()
+        },
+    );
+
+    let mut executed = worker.run_once().await.unwrap();
+    executed.sort_by(|a, b| a.attempt_id.cmp(&b.attempt_id));
+    assert_eq!(executed.len(), 2);
+    for (attempt, run_id) in executed.iter().zip(["run-a", "run-b"]) {
+        assert_eq!(attempt.run_id, run_id);
+        assert_eq!(
+            attempt.outcome,
+            AttemptOutcome::Finished(AttemptExecutionStatus::Completed)
+        );
+        let (_, status) = repo
+            .get_attempt_status(&attempt.attempt_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(status, AttemptExecutionStatus::Completed);
+        assert!(log.head(&run_id.to_string()).unwrap() > 0);
+        let run = repo.get_run(&run_id.to_string()).unwrap().unwrap();
+        assert_eq!(run.status, RunRuntimeStatus::Completed);
+    }
+
+    // Nothing is dispatchable any more
+    assert!(worker.run_once().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn worker_retries_then_dead_letters_attempts_of_unregistered_graphs() {
+    let repo = Arc::new(SqliteRuntimeRepository::new(":memory:").unwrap());
+    enqueue_run(&repo, "run-a", "attempt-a");
+
+    let event_store: EventStoreFactory =
+        Arc::new(|| Ok(Box::new(InMemoryEventStore::new()) as Box<dyn EventStore>));
+    let worker = RuntimeWorker::new(
+        Arc::clone(&repo),
+        Arc::new(GraphRegistry::<MessagesState>::new()),
+        event_store,
+        RuntimeWorkerConfig {
+            retry_policy: RetryPolicyConfig {
+                strategy: RetryStrategy::Fixed,
+                backoff_ms: 100,
+                max_backoff_ms: None,
+                multiplier: None,
+                max_retries: 1,
+            },
+            ..RuntimeWorkerConfig::default()
+        },
+    );
+
+    let executed = worker.run_once().await.unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(
+        executed[0].outcome,
+        AttemptOutcome::Finished(AttemptExecutionStatus::RetryBackoff)
+    );
+    assert!(repo.get_lease_for_attempt("attempt-a").unwrap().is_none());
+    let run = repo.get_run(&"run-a".to_string()).unwrap().unwrap();
+    assert_eq!(run.status, RunRuntimeStatus::RetryBackoff);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let executed = worker.run_once().await.unwrap();
+    assert_eq!(
+        executed[0].outcome,
+        AttemptOutcome::Finished(AttemptExecutionStatus::DeadLetter)
+    );
+    let run = repo.get_run(&"run-a".to_string()).unwrap().unwrap();
+    assert_eq!(run.status, RunRuntimeStatus::Failed);
+    assert!(run.status_reason.unwrap().contains("not registered"));
+    let dead_letters = repo.list_dead_letter_attempts(10).unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].attempt_no, 2);
+    assert!(dead_letters[0]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("not registered"));
+}
+
+#[tokio::test]
+async fn worker_cancels_attempt_at_next_step_boundary_when_heartbeat_says_so() {
+    let repo = Arc::new(SqliteRuntimeRepository::new(":memory:").unwrap());
+    enqueue_run(&repo, "run-a", "attempt-a");
+
+    let reached_second = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&reached_second);
+    let mut graph = StateGraph::<MessagesState>::new();
+    graph
+        .add_node(
+            "slow",
+            function_node("slow", |_s: &MessagesState| async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                Ok(HashMap::new())
+            }),
+        )
+        .unwrap();
+    graph
+        .add_node(
+            "after",
+            function_node("after", move |_s: &MessagesState| {
+                let flag = Arc::clone(&flag);
+                async move {
+                    flag.store(true, Ordering::SeqCst);
+                    Ok(HashMap::new())
+                }
+            }),
+        )
+        .unwrap();
+    graph.add_edge(START, "slow");
+    graph.add_edge("slow", "after");
+    graph.add_edge("after", END);
+    let mut graphs = GraphRegistry::new();
+    graphs.register(
+        "hello",
+        Arc::new(graph.compile().unwrap()),
+        MessagesState::new(),
+    );
+
+    let event_store: EventStoreFactory =
+        Arc::new(|| Ok(Box::new(InMemoryEventStore::new()) as Box<dyn EventStore>));
+    let worker = RuntimeWorker::new(
+        Arc::clone(&repo),
+        Arc::new(graphs),
+        event_store,
+        RuntimeWorkerConfig {
+            heartbeat_interval: Duration::from_millis(20),
+            ..RuntimeWorkerConfig::default()
+        },
+    );
+
+    let canceller = Arc::clone(&repo);
+    let (executed, cancellation) = tokio::join!(worker.run_once(), async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel_attempt("attempt-a").unwrap()
+    });
+    assert_eq!(cancellation, AttemptCancellation::Requested);
+    let executed = executed.unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(
+        executed[0].outcome,
+        AttemptOutcome::Finished(AttemptExecutionStatus::Cancelled)
+    );
+    assert!(!reached_second.load(Ordering::SeqCst));
+    let (_, status) = repo.get_attempt_status("attempt-a").unwrap().unwrap();
+    assert_eq!(status, AttemptExecutionStatus::Cancelled);
+    let run = repo.get_run(&"run-a".to_string()).unwrap().unwrap();
+    assert_eq!(run.status, RunRuntimeStatus::Cancelled);
+    assert!(worker.run_once().await.unwrap().is_empty());
+}
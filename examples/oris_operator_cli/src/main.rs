@@ -43,9 +43,19 @@ enum Command {
         #[arg(long)]
         priority: Option<i32>,
     },
+    /// List jobs from their run records, most recently updated first.
     List {
         #[arg(long)]
         status: Option<String>,
+        /// Only jobs whose run executes this graph.
+        #[arg(long)]
+        workflow: Option<String>,
+        /// Only jobs created at or after this time (Unix milliseconds).
+        #[arg(long)]
+        created_from_ms: Option<i64>,
+        /// Only jobs created before this time (Unix milliseconds).
+        #[arg(long)]
+        created_to_ms: Option<i64>,
         #[arg(long, default_value_t = 20)]
         limit: usize,
         #[arg(long, default_value_t = 0)]
@@ -121,6 +131,9 @@ async fn main() -> Result<()> {
         }
         Command::List {
             status,
+            workflow,
+            created_from_ms,
+            created_to_ms,
             limit,
             offset,
         } => {
@@ -129,6 +142,15 @@ async fn main() -> Result<()> {
             if let Some(status) = status {
                 query.push(("status", status));
             }
+            if let Some(workflow) = workflow {
+                query.push(("workflow", workflow));
+            }
+            if let Some(ms) = created_from_ms {
+                query.push(("created_from_ms", ms.to_string()));
+            }
+            if let Some(ms) = created_to_ms {
+                query.push(("created_to_ms", ms.to_string()));
+            }
             let req = auth(client.get(format!("{}/v1/jobs", base)).query(&query), token);
             send_and_decode(req).await?
         }
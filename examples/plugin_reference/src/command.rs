@@ -0,0 +1,482 @@
+//! Command node: runs an allowlisted program and appends its stdout as an AI message.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use oris_runtime::graph::{
+    function_node, messages_state_update, request_action, typed_node_plugin, GraphError,
+    NodePlugin, State,
+};
+use oris_runtime::kernel::{Action, ActionError, ActionExecutor, ActionResult, KernelError, RunId};
+use oris_runtime::schemas::messages::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::template::render;
+
+/// Plugin type string for the command node. Use this when adding the node via
+/// [NodePluginRegistry](oris_runtime::graph::NodePluginRegistry).
+pub const COMMAND_NODE_PLUGIN_TYPE: &str = "plugin_reference/command";
+
+/// Tool name of the `CallTool` action the command node requests under the kernel, for
+/// policies such as `AllowListPolicy`.
+pub const COMMAND_TOOL: &str = "plugin_reference/command";
+
+/// Config for the command node plugin.
+///
+/// `args` are templates: `{input}` expands to the content of the last human message and
+/// `{state.KEY}` to the state field `KEY`. The program is run directly, not through a
+/// shell, so expanded values cannot inject further commands.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CommandNodeConfig {
+    /// Program to run; must be listed in `allowed_programs`.
+    pub program: String,
+    /// Argument templates.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Working directory; the host's current directory without it.
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+    /// Time the program may run before it is killed, in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Bytes of stdout (and of stderr) kept; the rest is replaced by a marker.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Programs the node may run, exactly as given in `program`.
+    pub allowed_programs: Vec<String>,
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_max_output_bytes() -> usize {
+    64 * 1024
+}
+
+/// JSON Schema of [CommandNodeConfig], published through [NodePlugin::config_schema].
+pub fn command_node_config_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "program": { "type": "string", "description": "Program to run." },
+            "args": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Argument templates; {input} and {state.KEY} are expanded."
+            },
+            "working_dir": { "type": "string" },
+            "timeout_ms": { "type": "integer", "minimum": 1, "default": 30000 },
+            "max_output_bytes": { "type": "integer", "minimum": 0, "default": 65536 },
+            "allowed_programs": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Programs the node may run; must include program."
+            }
+        },
+        "required": ["program", "allowed_programs"]
+    })
+}
+
+/// Builds a [NodePlugin] for the command node, for any state with a `messages` field.
+/// Register with: `registry.register_plugin(plugin_reference::command_node_plugin())?`
+///
+/// Building the node fails unless `program` is in `allowed_programs`. The node appends
+/// the program's stdout as an AI message whose metadata holds `exit_code`, `success`,
+/// `stderr` and `truncated`; a non-zero exit does not fail the node, but failing to
+/// start the program or running past the timeout does.
+///
+/// Under the kernel (`GraphStepFnAdapter`) the node does not run the program itself: it
+/// requests a `CallTool` action for [COMMAND_TOOL], so the kernel's policy can refuse it
+/// and its executor, such as [CommandActionExecutor], runs it.
+pub fn command_node_plugin<S: State + 'static>() -> impl NodePlugin<S> + 'static {
+    typed_node_plugin(COMMAND_NODE_PLUGIN_TYPE, |name, config: CommandNodeConfig| {
+        if !config.allowed_programs.contains(&config.program) {
+            return Err(GraphError::CompilationError(format!(
+                "Command node '{}': program '{}' is not in allowed_programs",
+                name, config.program
+            )));
+        }
+        let config = Arc::new(config);
+        let node = name.to_string();
+        Ok(Arc::new(function_node(name.to_string(), move |state: &S| {
+            let config = Arc::clone(&config);
+            let node = node.clone();
+            let state = serde_json::to_value(state);
+            async move {
+                let state = state?;
+                let request = CommandRequest {
+                    program: config.program.clone(),
+                    args: config
+                        .args
+                        .iter()
+                        .map(|arg| render(arg, &state, false))
+                        .collect::<Result<_, _>>()?,
+                    working_dir: config.working_dir.clone(),
+                    timeout_ms: config.timeout_ms,
+                    max_output_bytes: config.max_output_bytes,
+                };
+                let error = |message: String| {
+                    GraphError::ExecutionError(format!("Command node '{}': {}", node, message))
+                };
+                let output = match request_action(request.action())? {
+                    Some(output) => serde_json::from_value(output)?,
+                    None => tokio::task::spawn_blocking(move || run_command(&request))
+                        .await
+                        .map_err(|e| error(e.to_string()))?
+                        .map_err(error)?,
+                };
+                Ok(messages_state_update(vec![output.into_message()]))
+            }
+        })))
+    })
+    .with_display_name("Command")
+    .with_description(
+        "Runs an allowlisted program with templated arguments and appends its stdout as an AI message.",
+    )
+    .with_version(env!("CARGO_PKG_VERSION"))
+    .with_config_schema(command_node_config_schema())
+}
+
+/// A program to run: the input of the [COMMAND_TOOL] action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRequest {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+}
+
+impl CommandRequest {
+    fn action(&self) -> Action {
+        Action::CallTool {
+            tool: COMMAND_TOOL.to_string(),
+            input: serde_json::to_value(self).unwrap_or_default(),
+            idempotency_key: None,
+        }
+    }
+}
+
+/// What a program printed and how it exited: the output of the [COMMAND_TOOL] action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Exit code; `None` if the program was ended by a signal.
+    pub exit_code: Option<i32>,
+    /// Whether stdout or stderr exceeded `max_output_bytes`.
+    pub truncated: bool,
+}
+
+impl CommandOutput {
+    fn into_message(self) -> Message {
+        let metadata = serde_json::json!({
+            "exit_code": self.exit_code,
+            "success": self.exit_code == Some(0),
+            "stderr": self.stderr,
+            "truncated": self.truncated,
+        });
+        Message::new_ai_message(self.stdout).with_metadata(metadata)
+    }
+}
+
+/// Run `request` to completion, capturing its output
+///
+/// Fails if the program cannot be started or outlives `timeout_ms`, in which case it
+/// is killed. A non-zero exit is not an error.
+pub fn run_command(request: &CommandRequest) -> Result<CommandOutput, String> {
+    let mut command = Command::new(&request.program);
+    command
+        .args(&request.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = &request.working_dir {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("cannot start '{}': {}", request.program, e))?;
+    let stdout = capture(child.stdout.take(), request.max_output_bytes);
+    let stderr = capture(child.stderr.take(), request.max_output_bytes);
+
+    let deadline = Instant::now() + Duration::from_millis(request.timeout_ms);
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "'{}' timed out after {}ms",
+                request.program, request.timeout_ms
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let (stdout, stdout_truncated) = stdout.join().unwrap_or_default();
+    let (stderr, stderr_truncated) = stderr.join().unwrap_or_default();
+    Ok(CommandOutput {
+        stdout,
+        stderr,
+        exit_code: status.code(),
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+/// Read `pipe` on its own thread, keeping at most `max` bytes and draining the rest so
+/// the program never blocks on a full pipe
+fn capture<R: Read + Send + 'static>(pipe: Option<R>, max: usize) -> JoinHandle<(String, bool)> {
+    std::thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return (String::new(), false);
+        };
+        let mut bytes = Vec::new();
+        let _ = pipe.by_ref().take(max as u64 + 1).read_to_end(&mut bytes);
+        let truncated = bytes.len() > max;
+        if truncated {
+            bytes.truncate(max);
+            let _ = std::io::copy(&mut pipe, &mut std::io::sink());
+        }
+        let mut text = String::from_utf8_lossy(&bytes).into_owned();
+        if truncated {
+            text.push_str(&format!("\n[output truncated at {} bytes]", max));
+        }
+        (text, truncated)
+    })
+}
+
+/// [ActionExecutor] running [COMMAND_TOOL] actions, for kernels that run graphs with
+/// command nodes
+///
+/// It checks programs against its own allowlist, so a tampered event log or spec cannot
+/// widen what the host runs. Other actions fail.
+#[derive(Debug, Clone)]
+pub struct CommandActionExecutor {
+    allowed_programs: Vec<String>,
+}
+
+impl CommandActionExecutor {
+    pub fn new(allowed_programs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            allowed_programs: allowed_programs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl ActionExecutor for CommandActionExecutor {
+    fn execute(&self, _run_id: &RunId, action: &Action) -> Result<ActionResult, KernelError> {
+        let Action::CallTool { tool, input, .. } = action else {
+            return Ok(ActionResult::Failure(format!(
+                "unsupported action: {:?}",
+                action
+            )));
+        };
+        if tool != COMMAND_TOOL {
+            return Ok(ActionResult::Failure(format!("unknown tool: {}", tool)));
+        }
+        let request: CommandRequest = serde_json::from_value(input.clone())
+            .map_err(|e| KernelError::Executor(ActionError::permanent(e.to_string())))?;
+        if !self.allowed_programs.contains(&request.program) {
+            return Ok(ActionResult::Failure(format!(
+                "program not allowed: {}",
+                request.program
+            )));
+        }
+        let output =
+            run_command(&request).map_err(|e| KernelError::Executor(ActionError::transient(e)))?;
+        serde_json::to_value(output)
+            .map(ActionResult::Success)
+            .map_err(|e| KernelError::Executor(ActionError::permanent(e.to_string())))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use oris_runtime::graph::testing::{NodeTestHarness, UpdateAssertions};
+    use oris_runtime::graph::{
+        GraphStepFnAdapter, GraphStepReducer, GraphStepState, MessagesState, NodePluginRegistry,
+        StateGraph, END, START,
+    };
+    use oris_runtime::kernel::{
+        AllowAllPolicy, AllowListPolicy, Event, EventStore, InMemoryEventStore, Kernel, KernelMode,
+        KernelRunner, Policy, RunStatus, SharedEventStore,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn registry() -> NodePluginRegistry<MessagesState> {
+        let mut registry = NodePluginRegistry::new();
+        registry.register_plugin(command_node_plugin()).unwrap();
+        registry
+    }
+
+    fn graph(config: Value) -> StateGraph<MessagesState> {
+        let mut graph = StateGraph::new();
+        graph
+            .add_plugin_node("run", COMMAND_NODE_PLUGIN_TYPE, config, &registry())
+            .unwrap();
+        graph.add_edge(START, "run");
+        graph.add_edge("run", END);
+        graph
+    }
+
+    async fn run(config: Value) -> Result<Message, GraphError> {
+        let update = NodeTestHarness::new(command_node_plugin(), config)?
+            .run(&MessagesState::with_messages(vec![
+                Message::new_human_message("hello world"),
+            ]))
+            .await?;
+        Ok(update.assert_appends_ai_message(""))
+    }
+
+    #[tokio::test]
+    async fn command_node_appends_stdout_with_exit_code() {
+        let message = run(json!({
+            "program": "echo",
+            "args": ["said: {input}"],
+            "allowed_programs": ["echo"]
+        }))
+        .await
+        .unwrap();
+        assert_eq!(message.content, "said: hello world\n");
+        assert_eq!(message.metadata().unwrap()["exit_code"], 0);
+
+        let message = run(json!({
+            "program": "sh",
+            "args": ["-c", "echo oops >&2; exit 3"],
+            "allowed_programs": ["sh"]
+        }))
+        .await
+        .unwrap();
+        let metadata = message.metadata().unwrap();
+        assert_eq!(metadata["exit_code"], 3);
+        assert_eq!(metadata["success"], false);
+        assert_eq!(metadata["stderr"], "oops\n");
+    }
+
+    #[tokio::test]
+    async fn command_node_truncates_output_and_times_out() {
+        let message = run(json!({
+            "program": "sh",
+            "args": ["-c", "yes | head -c 100000"],
+            "max_output_bytes": 4,
+            "allowed_programs": ["sh"]
+        }))
+        .await
+        .unwrap();
+        assert_eq!(message.content, "y\ny\n\n[output truncated at 4 bytes]");
+        assert_eq!(message.metadata().unwrap()["truncated"], true);
+
+        let err = run(json!({
+            "program": "sleep",
+            "args": ["5"],
+            "timeout_ms": 50,
+            "allowed_programs": ["sleep"]
+        }))
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("timed out after 50ms"), "{}", err);
+    }
+
+    #[test]
+    fn programs_outside_the_allowlist_are_rejected_at_construction() {
+        let err = NodeTestHarness::<MessagesState>::new(
+            command_node_plugin(),
+            json!({"program": "rm", "args": ["-rf", "/"], "allowed_programs": ["echo"]}),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, GraphError::CompilationError(_)), "{}", err);
+    }
+
+    fn kernel(
+        events: Arc<InMemoryEventStore>,
+        policy: Box<dyn Policy>,
+    ) -> Kernel<GraphStepState<MessagesState>> {
+        let compiled = graph(json!({
+            "program": "echo",
+            "args": ["{input}"],
+            "allowed_programs": ["echo"]
+        }))
+        .compile()
+        .unwrap();
+        Kernel {
+            events: Box::new(SharedEventStore(events)),
+            snaps: None,
+            reducer: Box::new(GraphStepReducer),
+            exec: Arc::new(CommandActionExecutor::new(["echo"])),
+            step: Box::new(GraphStepFnAdapter::new(Arc::new(compiled))),
+            policy,
+            effect_sink: None,
+            mode: KernelMode::Normal,
+            snapshot_policy: None,
+        }
+    }
+
+    fn initial() -> GraphStepState<MessagesState> {
+        GraphStepState::new(MessagesState::with_messages(vec![
+            Message::new_human_message("from the kernel"),
+        ]))
+    }
+
+    #[test]
+    fn command_node_runs_through_kernel_actions() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let run_id = "command-node".to_string();
+        let status = KernelRunner::new(kernel(events.clone(), Box::new(AllowAllPolicy)))
+            .run_until_blocked_sync(&run_id, initial())
+            .unwrap();
+        assert!(matches!(status, RunStatus::Completed));
+
+        let log: Vec<_> = events.scan(&run_id, 1).unwrap();
+        assert!(log.iter().any(|record| matches!(
+            &record.event,
+            Event::ActionRequested { payload, .. } if payload["CallTool"]["tool"] == COMMAND_TOOL
+        )));
+        let state = log
+            .iter()
+            .rev()
+            .find_map(|record| match &record.event {
+                Event::StateUpdated { payload, .. } => Some(payload["graph_state"].clone()),
+                _ => None,
+            })
+            .unwrap();
+        let state: MessagesState = serde_json::from_value(state).unwrap();
+        assert_eq!(state.messages[1].content, "from the kernel\n");
+    }
+
+    #[test]
+    fn kernel_policy_can_refuse_commands() {
+        let events = Arc::new(InMemoryEventStore::new());
+        let run_id = "command-node-denied".to_string();
+        let result = KernelRunner::new(kernel(
+            events.clone(),
+            Box::new(AllowListPolicy::tools_only([])),
+        ))
+        .run_until_blocked_sync(&run_id, initial());
+        assert!(
+            matches!(result, Err(KernelError::Policy(_))),
+            "{:?}",
+            result
+        );
+        assert!(!events
+            .scan(&run_id, 1)
+            .unwrap()
+            .iter()
+            .any(|record| matches!(record.event, Event::ActionRequested { .. })));
+    }
+}
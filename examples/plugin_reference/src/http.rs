@@ -0,0 +1,411 @@
+//! HTTP request node: calls an endpoint and appends the response as an AI message.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use oris_runtime::graph::{
+    function_node, messages_state_update, typed_node_plugin, GraphError, NodePlugin, State,
+    StateUpdate,
+};
+use oris_runtime::schemas::messages::Message;
+use reqwest::{Client, Method, RequestBuilder, Url};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::path::{lookup, parse_path, PathSegment};
+use crate::template::{render, render_json};
+
+/// Plugin type string for the HTTP node. Use this when adding the node via
+/// [NodePluginRegistry](oris_runtime::graph::NodePluginRegistry).
+pub const HTTP_NODE_PLUGIN_TYPE: &str = "plugin_reference/http";
+
+/// Config for the HTTP node plugin.
+///
+/// `url`, header values and string values of `body` are templates: `{input}` expands to
+/// the content of the last human message and `{state.KEY}` to the state field `KEY`
+/// (percent-encoded in `url`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct HttpNodeConfig {
+    /// Request method.
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// URL template.
+    pub url: String,
+    /// Header templates by header name.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// JSON body; its string values are templates.
+    #[serde(default)]
+    pub body: Option<Value>,
+    /// Request timeout in milliseconds.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// JSONPath-style path of the value to append, e.g. `$.choices[0].text`; the whole
+    /// response body is appended without it.
+    #[serde(default)]
+    pub extract: Option<String>,
+    /// What to do when the request fails or returns a non-2xx status.
+    #[serde(default)]
+    pub on_error: OnHttpError,
+    /// Hosts the node may call, exactly (`api.example.com`) or by subdomain
+    /// (`*.example.com`); empty allows any host.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+/// Behavior of the HTTP node when a request fails or returns a non-2xx status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnHttpError {
+    /// Fail the node, so its retry policy applies.
+    #[default]
+    Fail,
+    /// Append the error as an AI message and carry on.
+    AppendMessage,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_timeout_ms() -> u64 {
+    10_000
+}
+
+/// JSON Schema of [HttpNodeConfig], published through [NodePlugin::config_schema].
+pub fn http_node_config_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "method": {
+                "enum": ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"],
+                "default": "GET"
+            },
+            "url": {
+                "type": "string",
+                "description": "URL template; {input} and {state.KEY} are percent-encoded."
+            },
+            "headers": {
+                "type": "object",
+                "additionalProperties": { "type": "string" }
+            },
+            "body": { "description": "JSON body; its string values are templates." },
+            "timeout_ms": { "type": "integer", "minimum": 1, "default": 10000 },
+            "extract": {
+                "type": "string",
+                "description": "Path of the value to append, e.g. $.choices[0].text."
+            },
+            "on_error": { "enum": ["fail", "append_message"], "default": "fail" },
+            "allowed_hosts": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Hosts the node may call, e.g. api.example.com or *.example.com."
+            }
+        },
+        "required": ["url"]
+    })
+}
+
+/// Builds a [NodePlugin] for the HTTP node, for any state with a `messages` field.
+/// Register with: `registry.register_plugin(plugin_reference::http_node_plugin())?`
+///
+/// Failed requests are node errors unless `on_error` is `append_message`, so retries and
+/// timeouts set with `StateGraph::set_node_options` apply to them.
+pub fn http_node_plugin<S: State + 'static>() -> impl NodePlugin<S> + 'static {
+    typed_node_plugin(HTTP_NODE_PLUGIN_TYPE, |name, config: HttpNodeConfig| {
+        let request = Arc::new(HttpRequest::new(name, config)?);
+        Ok(Arc::new(function_node(name.to_string(), move |state: &S| {
+            let request = Arc::clone(&request);
+            let state = serde_json::to_value(state);
+            async move { request.run(&state?).await }
+        })))
+    })
+    .with_display_name("HTTP request")
+    .with_description(
+        "Sends a templated HTTP request and appends the response, or a value extracted from it, as an AI message.",
+    )
+    .with_version(env!("CARGO_PKG_VERSION"))
+    .with_config_schema(http_node_config_schema())
+}
+
+struct HttpRequest {
+    node: String,
+    config: HttpNodeConfig,
+    method: Method,
+    extract: Option<Vec<PathSegment>>,
+    client: Client,
+}
+
+impl HttpRequest {
+    fn new(node: &str, mut config: HttpNodeConfig) -> Result<Self, GraphError> {
+        let invalid = |message: String| {
+            GraphError::CompilationError(format!("HTTP node '{}': {}", node, message))
+        };
+        let method = Method::from_bytes(config.method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| invalid(format!("invalid method '{}'", config.method)))?;
+        let extract = config
+            .extract
+            .as_deref()
+            .map(|path| {
+                parse_path(path).ok_or_else(|| invalid(format!("invalid extract path '{}'", path)))
+            })
+            .transpose()?;
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| invalid(e.to_string()))?;
+        for pattern in &mut config.allowed_hosts {
+            pattern.make_ascii_lowercase();
+        }
+        Ok(Self {
+            node: node.to_string(),
+            config,
+            method,
+            extract,
+            client,
+        })
+    }
+
+    async fn run(&self, state: &Value) -> Result<StateUpdate, GraphError> {
+        let url = render(&self.config.url, state, true)?;
+        let url = Url::parse(&url).map_err(|e| self.error(format!("invalid URL: {}", e)))?;
+        let host = url.host_str().unwrap_or_default();
+        if !host_allowed(&self.config.allowed_hosts, host) {
+            return Err(self.error(format!("host '{}' is not in allowed_hosts", host)));
+        }
+
+        let mut request = self.client.request(self.method.clone(), url);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, render(value, state, false)?);
+        }
+        if let Some(body) = &self.config.body {
+            request = request.json(&render_json(body, state)?);
+        }
+        match self.send(request).await {
+            Ok(text) => Ok(messages_state_update(vec![Message::new_ai_message(text)])),
+            Err(message) => match self.config.on_error {
+                OnHttpError::Fail => Err(self.error(message)),
+                OnHttpError::AppendMessage => {
+                    Ok(messages_state_update(vec![Message::new_ai_message(
+                        format!("HTTP request failed: {}", message),
+                    )]))
+                }
+            },
+        }
+    }
+
+    /// Send the request and return the text to append, or why there is none
+    async fn send(&self, request: RequestBuilder) -> Result<String, String> {
+        // Leave the URL out of transport errors; it may carry credentials
+        let response = request
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("status {}", status));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+        let Some(path) = &self.extract else {
+            return Ok(body);
+        };
+        let json: Value =
+            serde_json::from_str(&body).map_err(|e| format!("response is not JSON: {}", e))?;
+        match lookup(&json, path) {
+            Some(Value::String(text)) => Ok(text.clone()),
+            Some(value) => Ok(value.to_string()),
+            None => Err(format!(
+                "'{}' matched nothing in the response",
+                self.config.extract.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+
+    fn error(&self, message: String) -> GraphError {
+        GraphError::ExecutionError(format!("HTTP node '{}': {}", self.node, message))
+    }
+}
+
+/// Whether `host` matches one of the (lowercase) `patterns`; an empty list allows any host
+fn host_allowed(patterns: &[String], host: &str) -> bool {
+    patterns.is_empty()
+        || patterns
+            .iter()
+            .any(|pattern| match pattern.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+                None => host == pattern,
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{extract::Query, http::StatusCode, routing::get, Json, Router};
+    use oris_runtime::graph::testing::NodeTestHarness;
+    use oris_runtime::graph::{
+        MessagesState, NodeOptions, NodePluginRegistry, RetryPolicy, StateGraph, END, START,
+    };
+
+    use super::*;
+
+    /// Serve a stub API on a random local port, returning its base URL
+    async fn stub_server() -> String {
+        let flaky_calls = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route(
+                "/search",
+                get(|Query(query): Query<BTreeMap<String, String>>| async move {
+                    Json(serde_json::json!({
+                        "results": [{ "title": format!("{} result", query["q"]) }]
+                    }))
+                }),
+            )
+            .route(
+                "/flaky",
+                get(move || async move {
+                    match flaky_calls.fetch_add(1, Ordering::SeqCst) {
+                        0 => Err(StatusCode::SERVICE_UNAVAILABLE),
+                        _ => Ok("recovered"),
+                    }
+                }),
+            )
+            .route("/fail", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn registry() -> NodePluginRegistry<MessagesState> {
+        let mut registry = NodePluginRegistry::new();
+        registry.register_plugin(http_node_plugin()).unwrap();
+        registry
+    }
+
+    async fn run(config: Value) -> Result<MessagesState, GraphError> {
+        let harness =
+            NodeTestHarness::from_registry(&registry(), "http", HTTP_NODE_PLUGIN_TYPE, config)?;
+        harness
+            .run_and_apply(&MessagesState::with_messages(vec![
+                Message::new_human_message("rust graphs"),
+            ]))
+            .await
+    }
+
+    #[tokio::test]
+    async fn http_node_appends_extracted_value() {
+        let base = stub_server().await;
+        let state = run(serde_json::json!({
+            "url": format!("{}/search?q={{input}}", base),
+            "extract": "$.results[0].title"
+        }))
+        .await
+        .unwrap();
+
+        assert_eq!(state.messages[1].content, "rust graphs result");
+    }
+
+    #[tokio::test]
+    async fn http_node_on_error_fails_or_appends() {
+        let base = stub_server().await;
+        let err = run(serde_json::json!({ "url": format!("{}/fail", base) }))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: HTTP node 'http': status 500 Internal Server Error"
+        );
+
+        let state = run(serde_json::json!({
+            "url": format!("{}/fail", base),
+            "on_error": "append_message"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            state.messages[1].content,
+            "HTTP request failed: status 500 Internal Server Error"
+        );
+    }
+
+    #[tokio::test]
+    async fn http_node_rejects_hosts_outside_allowlist() {
+        let base = stub_server().await;
+        let err = run(serde_json::json!({
+            "url": format!("{}/search?q=x", base),
+            "allowed_hosts": ["*.example.com"],
+            "on_error": "append_message"
+        }))
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("host '127.0.0.1' is not in allowed_hosts"));
+
+        let patterns = vec!["api.example.com".to_string(), "*.internal.dev".to_string()];
+        assert!(host_allowed(&patterns, "api.example.com"));
+        assert!(host_allowed(&patterns, "db.internal.dev"));
+        assert!(!host_allowed(&patterns, "internal.dev"));
+        assert!(!host_allowed(&patterns, "evilinternal.dev"));
+        assert!(!host_allowed(&patterns, "example.com"));
+    }
+
+    #[tokio::test]
+    async fn http_node_failures_use_the_node_retry_policy() {
+        let base = stub_server().await;
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_plugin_node(
+                "fetch",
+                HTTP_NODE_PLUGIN_TYPE,
+                serde_json::json!({ "url": format!("{}/flaky", base) }),
+                &registry(),
+            )
+            .unwrap()
+            .set_node_options("fetch", NodeOptions::new().with_retry(RetryPolicy::new(2)))
+            .unwrap();
+        graph.add_edge(START, "fetch");
+        graph.add_edge("fetch", END);
+
+        let state = graph
+            .compile()
+            .unwrap()
+            .invoke(MessagesState::new())
+            .await
+            .unwrap();
+        assert_eq!(state.messages[0].content, "recovered");
+    }
+
+    #[test]
+    fn templates_and_paths() {
+        let state = serde_json::json!({
+            "messages": [{ "content": "a b", "message_type": "human" }],
+            "topic": "graphs/rust",
+            "limit": 3
+        });
+        assert_eq!(
+            render(
+                "q={input}&t={state.topic}&n={state.limit}&{x}",
+                &state,
+                true
+            )
+            .unwrap(),
+            "q=a%20b&t=graphs%2Frust&n=3&{x}"
+        );
+        assert!(render("{state.missing}", &state, false).is_err());
+
+        let path = parse_path("$.choices[0].text").unwrap();
+        let response = serde_json::json!({ "choices": [{ "text": "hi" }] });
+        assert_eq!(lookup(&response, &path), Some(&serde_json::json!("hi")));
+        assert!(parse_path("choices").is_none());
+        assert!(parse_path("$.a[x]").is_none());
+    }
+}
@@ -1,17 +1,32 @@
 //! Reference implementation of an external Oris graph node plugin (0.1.x).
 //!
 //! This crate demonstrates the packaged plugin layout: implement [NodePlugin],
-//! expose a constructor, and document plugin type + config schema. See
+//! expose a constructor, and document plugin type + config schema. Plugins can
+//! target [MessagesState] or an application's own state type, like [PlanState]. See
 //! [plugin-authoring](https://github.com/Colin4k1024/Oris/blob/main/docs/plugin-authoring.md).
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use oris_runtime::graph::{
-    function_node, messages_state_update, typed_node_plugin, GraphError, MessagesState, NodePlugin,
-    NodePluginRegistry,
+    current_cancellation, current_deadline, function_node, messages_state_update, state_update,
+    typed_node_plugin, GraphError, MessagesState, NodePlugin, NodePluginRegistry, Reducer, State,
 };
 use oris_runtime::schemas::messages::Message;
-use serde::Deserialize;
+use oris_runtime::state_field;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+mod command;
+mod http;
+mod path;
+mod prompt;
+mod router;
+mod template;
+pub use command::*;
+pub use http::*;
+pub use prompt::*;
+pub use router::*;
 
 /// Plugin type string for the delay node. Use this when adding the node via [NodePluginRegistry].
 pub const DELAY_NODE_PLUGIN_TYPE: &str = "plugin_reference/delay";
@@ -22,37 +37,350 @@ pub const DELAY_NODE_PLUGIN_TYPE: &str = "plugin_reference/delay";
 pub struct DelayNodeConfig {
     /// Message to append after the delay.
     pub message: String,
-    /// Delay in milliseconds (simulated).
+    /// Delay in milliseconds.
     #[serde(default = "default_delay_ms")]
     pub delay_ms: u64,
+    /// Up to this many milliseconds, chosen at random per run, added to `delay_ms`.
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Stop waiting at the run's deadline, appending nothing, instead of overrunning it.
+    #[serde(default = "default_respect_deadline")]
+    pub respect_deadline: bool,
 }
 
 fn default_delay_ms() -> u64 {
     100
 }
 
+fn default_respect_deadline() -> bool {
+    true
+}
+
+/// JSON Schema of [DelayNodeConfig], published through [NodePlugin::config_schema].
+pub fn delay_node_config_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "message": {
+                "type": "string",
+                "description": "Message to append after the delay."
+            },
+            "delay_ms": {
+                "type": "integer",
+                "minimum": 0,
+                "default": 100,
+                "description": "Delay in milliseconds."
+            },
+            "jitter_ms": {
+                "type": "integer",
+                "minimum": 0,
+                "default": 0,
+                "description": "Random extra delay of up to this many milliseconds."
+            },
+            "respect_deadline": {
+                "type": "boolean",
+                "default": true,
+                "description": "Stop waiting at the run's deadline, appending nothing."
+            }
+        },
+        "required": ["message"]
+    })
+}
+
 /// Builds a [NodePlugin] for the delay node. Register with:
 /// `registry.register_plugin(plugin_reference::delay_node_plugin())?`
+///
+/// The plugin publishes [delay_node_config_schema], so the registry rejects invalid
+/// configs with structured errors before the node is built.
+///
+/// The node waits on the run's [cancellation token](current_cancellation) and, with
+/// `respect_deadline`, no longer than until the [run's deadline](current_deadline). Cut
+/// short either way, it returns an update appending no message rather than an error,
+/// and leaves it to the graph to stop the run.
 pub fn delay_node_plugin() -> impl NodePlugin<MessagesState> + 'static {
     typed_node_plugin(DELAY_NODE_PLUGIN_TYPE, |name, config: DelayNodeConfig| {
-        let message = config.message;
-        let delay_ms = config.delay_ms;
+        let config = Arc::new(config);
         Ok(Arc::new(function_node(
             name.to_string(),
             move |_state: &MessagesState| {
-                let msg = message.clone();
+                let config = Arc::clone(&config);
                 async move {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-                    Ok(messages_state_update(vec![Message::new_ai_message(&msg)]))
+                    let jitter = match config.jitter_ms {
+                        0 => 0,
+                        jitter_ms => rand::thread_rng().gen_range(0..=jitter_ms),
+                    };
+                    let mut delay = Duration::from_millis(config.delay_ms.saturating_add(jitter));
+                    let mut cut_short = false;
+                    if let Some(deadline) = current_deadline().filter(|_| config.respect_deadline)
+                    {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        if remaining < delay {
+                            delay = remaining;
+                            cut_short = true;
+                        }
+                    }
+                    let cancellation = current_cancellation().unwrap_or_default();
+                    tokio::select! {
+                        biased;
+                        _ = cancellation.cancelled() => cut_short = true,
+                        _ = tokio::time::sleep(delay) => {}
+                    }
+                    let messages = if cut_short {
+                        Vec::new()
+                    } else {
+                        vec![Message::new_ai_message(&config.message)]
+                    };
+                    Ok(messages_state_update(messages))
                 }
             },
         )))
     })
+    .with_display_name("Delay")
+    .with_description(
+        "Waits `delay_ms` (plus up to `jitter_ms`) milliseconds, then appends `message` as an AI message; stops early, appending nothing, when the run is cancelled or reaches its deadline.",
+    )
+    .with_version(env!("CARGO_PKG_VERSION"))
+    .with_config_schema(delay_node_config_schema())
+}
+
+/// Plugin type string for the plan step node, which runs on [PlanState].
+pub const PLAN_STEP_PLUGIN_TYPE: &str = "plugin_reference/plan_step";
+
+/// Example application state: a plan that grows step by step within a budget.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanState {
+    /// Steps taken so far; updates append to it.
+    pub plan: Vec<String>,
+    /// Remaining budget; updates overwrite it.
+    pub budget: f64,
+}
+
+impl State for PlanState {
+    fn reducer(field: &str) -> Reducer {
+        match field {
+            "plan" => Reducer::Append,
+            _ => Reducer::Overwrite,
+        }
+    }
+}
+
+/// Config for the plan step node plugin.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PlanStepConfig {
+    /// Step appended to the plan.
+    pub step: String,
+    /// Budget the step spends.
+    pub cost: f64,
+}
+
+/// Builds a [NodePlugin] for the plan step node. The node fails when the remaining
+/// budget cannot cover the step's cost.
+pub fn plan_step_plugin() -> impl NodePlugin<PlanState> + 'static {
+    typed_node_plugin(PLAN_STEP_PLUGIN_TYPE, |name, config: PlanStepConfig| {
+        Ok(Arc::new(function_node(
+            name.to_string(),
+            move |state: &PlanState| {
+                let remaining = state.budget - config.cost;
+                let step = config.step.clone();
+                async move {
+                    if remaining < 0.0 {
+                        return Err(GraphError::ExecutionError(format!(
+                            "budget exhausted before step '{}'",
+                            step
+                        )));
+                    }
+                    let mut update = state_update(state_field!(PlanState, plan), vec![step]);
+                    update.extend(state_update(state_field!(PlanState, budget), remaining));
+                    Ok(update)
+                }
+            },
+        )))
+    })
+    .with_display_name("Plan step")
+    .with_description("Appends `step` to the plan and spends `cost` from the budget.")
+    .with_version(env!("CARGO_PKG_VERSION"))
+}
+
+/// Registers the [PlanState] plugins into the given registry.
+pub fn register_plan_plugins(
+    registry: &mut NodePluginRegistry<PlanState>,
+) -> Result<(), GraphError> {
+    registry.register_plugin(plan_step_plugin())?;
+    Ok(())
 }
 
 /// Registers all plugin_reference plugins into the given registry.
 /// Use from the host app: `plugin_reference::register_all(&mut registry)?`
 pub fn register_all(registry: &mut NodePluginRegistry<MessagesState>) -> Result<(), GraphError> {
     registry.register_plugin(delay_node_plugin())?;
+    registry.register_plugin(http_node_plugin())?;
+    registry.register_plugin(command_node_plugin())?;
+    registry.register_plugin(template_node_plugin())?;
+    registry.register_router(router_node_plugin())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oris_runtime::graph::testing::{NodeTestHarness, UpdateAssertions};
+    use oris_runtime::graph::{
+        CancellationToken, InMemorySaver, RunnableConfig, StateGraph, END, START,
+    };
+
+    #[tokio::test]
+    async fn delay_node_message_expands_env_placeholders() {
+        std::env::set_var("GREETING", "Hello from the environment");
+        let harness = NodeTestHarness::new(
+            delay_node_plugin(),
+            serde_json::json!({"message": "${env:GREETING}!", "delay_ms": 0}),
+        )
+        .unwrap();
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("Hello from the environment!");
+    }
+
+    #[tokio::test]
+    async fn delay_node_waits_on_virtual_time() {
+        let harness = NodeTestHarness::new(
+            delay_node_plugin(),
+            serde_json::json!({"message": "done", "delay_ms": 60_000}),
+        )
+        .unwrap()
+        .with_virtual_time();
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("done");
+        assert!(harness.last_run_duration() >= Duration::from_secs(60));
+    }
+
+    fn delay(config: serde_json::Value) -> NodeTestHarness<MessagesState> {
+        NodeTestHarness::new(delay_node_plugin(), config)
+            .unwrap()
+            .with_virtual_time()
+    }
+
+    #[tokio::test]
+    async fn delay_node_adds_jitter() {
+        let harness =
+            delay(serde_json::json!({"message": "done", "delay_ms": 1000, "jitter_ms": 500}));
+        for _ in 0..5 {
+            harness
+                .run(&MessagesState::new())
+                .await
+                .unwrap()
+                .assert_appends_ai_message("done");
+            let waited = harness.last_run_duration();
+            assert!(
+                waited >= Duration::from_millis(1000) && waited <= Duration::from_millis(1501),
+                "{:?}",
+                waited
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn delay_node_stops_when_the_run_is_cancelled() {
+        let token = CancellationToken::new();
+        let harness = delay(serde_json::json!({"message": "done", "delay_ms": 60_000}))
+            .with_cancellation(token.clone());
+
+        let state = MessagesState::new();
+        let (update, ()) = tokio::join!(harness.run(&state), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            token.cancel();
+        });
+        assert!(update.unwrap().appended_messages().is_empty());
+        assert!(harness.last_run_duration() < Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn delay_node_respects_the_run_deadline_unless_told_not_to() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let harness = delay(serde_json::json!({"message": "done", "delay_ms": 60_000}))
+            .with_deadline(deadline);
+        let update = harness.run(&MessagesState::new()).await.unwrap();
+        assert!(update.appended_messages().is_empty());
+        assert!(harness.last_run_duration() <= Duration::from_millis(51));
+
+        let harness = delay(serde_json::json!({
+            "message": "done", "delay_ms": 60_000, "respect_deadline": false
+        }))
+        .with_deadline(deadline);
+        harness
+            .run(&MessagesState::new())
+            .await
+            .unwrap()
+            .assert_appends_ai_message("done");
+        assert!(harness.last_run_duration() >= Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn cancelled_graph_runs_stop_at_the_delay_node() {
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        register_all(&mut registry).unwrap();
+        let mut graph = StateGraph::<MessagesState>::new();
+        graph
+            .add_plugin_node(
+                "wait",
+                DELAY_NODE_PLUGIN_TYPE,
+                serde_json::json!({"message": "done", "delay_ms": 60_000}),
+                &registry,
+            )
+            .unwrap();
+        graph.add_edge(START, "wait");
+        graph.add_edge("wait", END);
+        let compiled = graph
+            .compile_with_persistence(Some(Arc::new(InMemorySaver::new())), None)
+            .unwrap();
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        let started = std::time::Instant::now();
+        let err = compiled
+            .invoke_with_config(
+                Some(MessagesState::new()),
+                &RunnableConfig::with_thread_id("delay").with_cancellation(token),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, GraphError::Cancelled { ref node } if node == "wait"),
+            "{}",
+            err
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn plan_step_spends_budget() {
+        let harness = NodeTestHarness::new(
+            plan_step_plugin(),
+            serde_json::json!({"step": "draft", "cost": 1.5}),
+        )
+        .unwrap();
+        let state = PlanState {
+            plan: vec!["research".to_string()],
+            budget: 2.0,
+        };
+
+        let update = harness.run(&state).await.unwrap();
+        update.assert_sets("plan", ["draft"]);
+        update.assert_sets("budget", 0.5);
+        let state = harness.run_and_apply(&state).await.unwrap();
+        assert_eq!(state.plan, ["research", "draft"]);
+
+        let err = harness.run(&state).await.unwrap_err();
+        assert!(err.to_string().contains("budget exhausted"), "{}", err);
+    }
+}
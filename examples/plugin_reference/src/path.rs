@@ -0,0 +1,59 @@
+//! Paths into JSON values used by node and router configs: `$.a[0].b` paths into
+//! responses and message contents, and `messages.last` / `state.PATH` fields of the state.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse `$`, `.key` and `[index]` segments, e.g. `$.choices[0].text`
+pub(crate) fn parse_path(path: &str) -> Option<Vec<PathSegment>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return None;
+            }
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            segments.push(PathSegment::Index(after[..end].parse().ok()?));
+            rest = &after[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some(segments)
+}
+
+pub(crate) fn lookup<'a>(value: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(index) => value.get(index),
+    })
+}
+
+/// Value of the field `path` (`messages.last`, `messages.last_human` or `state.PATH`),
+/// or `None` if it names nothing in `state`
+pub(crate) fn resolve_field<'a>(state: &'a Value, path: &str) -> Option<&'a Value> {
+    let messages = || state["messages"].as_array().into_iter().flatten().rev();
+    match path {
+        "messages.last" => messages().next().map(|message| &message["content"]),
+        "messages.last_human" => messages()
+            .find(|message| message["message_type"] == "human")
+            .map(|message| &message["content"]),
+        _ => path
+            .strip_prefix("state.")?
+            .split('.')
+            .try_fold(state, |value, key| match value {
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+                _ => value.get(key),
+            }),
+    }
+}
@@ -0,0 +1,303 @@
+//! Prompt template node: renders a `{{...}}` template from state and appends it as a message.
+
+use std::sync::Arc;
+
+use oris_runtime::graph::{
+    function_node, messages_state_update, typed_node_plugin, GraphError, NodePlugin, State,
+};
+use oris_runtime::schemas::messages::Message;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::path::resolve_field;
+
+/// Plugin type string for the prompt template node. Use this when adding the node via
+/// [NodePluginRegistry](oris_runtime::graph::NodePluginRegistry).
+pub const TEMPLATE_NODE_PLUGIN_TYPE: &str = "plugin_reference/template";
+
+/// Config for the prompt template node plugin.
+///
+/// Placeholders are `{{messages.last}}` (content of the last message),
+/// `{{messages.last_human}}` (content of the last human message) and `{{state.PATH}}`
+/// (a state field; `PATH` is dot-separated keys and array indices, e.g.
+/// `state.docs.0.title`). Strings, numbers and booleans render as they are; other values
+/// need the `json` formatter, as in `{{state.filters | json}}`. `\{{` renders a literal
+/// `{{`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TemplateNodeConfig {
+    /// Template to render.
+    pub template: String,
+    /// Role of the appended message.
+    #[serde(default)]
+    pub role: PromptRole,
+    /// Fail on placeholders that resolve to nothing; otherwise keep them literally.
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+}
+
+/// Role of the message the prompt template node appends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptRole {
+    #[default]
+    Ai,
+    Human,
+    System,
+}
+
+fn default_strict() -> bool {
+    true
+}
+
+/// JSON Schema of [TemplateNodeConfig], published through [NodePlugin::config_schema].
+pub fn template_node_config_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "template": {
+                "type": "string",
+                "description": "Template with {{messages.last}}, {{messages.last_human}} and {{state.PATH}} placeholders."
+            },
+            "role": { "enum": ["ai", "human", "system"], "default": "ai" },
+            "strict": {
+                "type": "boolean",
+                "default": true,
+                "description": "Fail on unresolved placeholders instead of keeping them literally."
+            }
+        },
+        "required": ["template"]
+    })
+}
+
+/// Builds a [NodePlugin] for the prompt template node, for any state with a `messages`
+/// field. Register with: `registry.register_plugin(plugin_reference::template_node_plugin())?`
+///
+/// The template is parsed when the node is built, so an unclosed `{{` or an unknown
+/// formatter fails then. At execution, a placeholder that resolves to nothing (unknown
+/// name, missing state field, no matching message) fails the node when `strict` is set
+/// and is kept literally otherwise; a value that needs a formatter always fails it.
+pub fn template_node_plugin<S: State + 'static>() -> impl NodePlugin<S> + 'static {
+    typed_node_plugin(TEMPLATE_NODE_PLUGIN_TYPE, |name, config: TemplateNodeConfig| {
+        let template = Arc::new(PromptTemplate::parse(&config.template).map_err(|e| {
+            GraphError::CompilationError(format!("Template node '{}': {}", name, e))
+        })?);
+        let node = name.to_string();
+        Ok(Arc::new(function_node(name.to_string(), move |state: &S| {
+            let template = Arc::clone(&template);
+            let node = node.clone();
+            let state = serde_json::to_value(state);
+            async move {
+                let text = template.render(&state?, config.strict).map_err(|e| {
+                    GraphError::ExecutionError(format!("Template node '{}': {}", node, e))
+                })?;
+                let message = match config.role {
+                    PromptRole::Ai => Message::new_ai_message(text),
+                    PromptRole::Human => Message::new_human_message(text),
+                    PromptRole::System => Message::new_system_message(text),
+                };
+                Ok(messages_state_update(vec![message]))
+            }
+        })))
+    })
+    .with_display_name("Prompt template")
+    .with_description(
+        "Renders a template from the last messages and state fields and appends it as an AI, human or system message.",
+    )
+    .with_version(env!("CARGO_PKG_VERSION"))
+    .with_config_schema(template_node_config_schema())
+}
+
+/// A parsed `{{...}}` template
+#[derive(Debug, Clone, PartialEq)]
+struct PromptTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder {
+        /// The placeholder as written, kept when it does not resolve and `strict` is off
+        raw: String,
+        path: String,
+        json: bool,
+    },
+}
+
+impl PromptTemplate {
+    fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            if rest[..start].ends_with('\\') {
+                text.push_str(&rest[..start - 1]);
+                text.push_str("{{");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            text.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find("}}") else {
+                return Err(format!("unclosed placeholder '{}'", &rest[start..]));
+            };
+            let raw = &rest[start..start + end + 2];
+            let (path, formatter) = match raw[2..raw.len() - 2].split_once('|') {
+                Some((path, formatter)) => (path, Some(formatter.trim())),
+                None => (&raw[2..raw.len() - 2], None),
+            };
+            let json = match formatter {
+                None => false,
+                Some("json") => true,
+                Some(other) => {
+                    return Err(format!(
+                        "unknown formatter '{}' in '{}'; expected 'json'",
+                        other, raw
+                    ))
+                }
+            };
+            if !text.is_empty() {
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+            }
+            segments.push(Segment::Placeholder {
+                raw: raw.to_string(),
+                path: path.trim().to_string(),
+                json,
+            });
+            rest = &rest[start + end + 2..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self { segments })
+    }
+
+    fn render(&self, state: &Value, strict: bool) -> Result<String, String> {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Placeholder { raw, path, json } => match resolve_field(state, path) {
+                    Some(value) if *json => out.push_str(&value.to_string()),
+                    Some(Value::String(text)) => out.push_str(text),
+                    Some(value @ (Value::Number(_) | Value::Bool(_))) => {
+                        out.push_str(&value.to_string())
+                    }
+                    Some(_) => {
+                        return Err(format!(
+                            "'{}' is not a string, number or boolean; use '{{{{{} | json}}}}'",
+                            raw, path
+                        ))
+                    }
+                    None if strict => return Err(format!("cannot resolve placeholder '{}'", raw)),
+                    None => out.push_str(raw),
+                },
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use oris_runtime::graph::testing::{NodeTestHarness, UpdateAssertions};
+    use oris_runtime::graph::{MessagesState, Reducer};
+    use oris_runtime::schemas::messages::MessageType;
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Default, Serialize, serde::Deserialize)]
+    struct DocsState {
+        messages: Vec<Message>,
+        topic: String,
+        filters: Value,
+    }
+
+    impl State for DocsState {
+        fn reducer(field: &str) -> Reducer {
+            match field {
+                "messages" => Reducer::Append,
+                _ => Reducer::Overwrite,
+            }
+        }
+    }
+
+    fn state() -> DocsState {
+        DocsState {
+            messages: vec![
+                Message::new_human_message("what changed?"),
+                Message::new_ai_message("searching"),
+            ],
+            topic: "releases".to_string(),
+            filters: serde_json::json!({"year": 2026}),
+        }
+    }
+
+    async fn run(config: Value) -> Result<Message, GraphError> {
+        let harness = NodeTestHarness::new(template_node_plugin(), config)?;
+        let state = harness.run_and_apply(&state()).await?;
+        Ok(state.messages.last().unwrap().clone())
+    }
+
+    #[tokio::test]
+    async fn renders_messages_and_state_fields() {
+        let harness = NodeTestHarness::new(
+            template_node_plugin(),
+            serde_json::json!({
+                "template": "Q: {{messages.last_human}} ({{ messages.last }}) about {{state.topic}} \
+                             in {{state.filters.year}} with {{state.filters | json}}",
+                "role": "system"
+            }),
+        )
+        .unwrap();
+        let update = harness.run(&state()).await.unwrap();
+        update.assert_appends_message(
+            MessageType::SystemMessage,
+            "Q: what changed? (searching) about releases in 2026 with {\"year\":2026}",
+        );
+    }
+
+    #[tokio::test]
+    async fn strict_controls_unresolved_placeholders() {
+        let template = "{{state.missing}} and \\{{state.topic}}";
+        let err = run(serde_json::json!({"template": template}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: Template node 'node': cannot resolve placeholder '{{state.missing}}'"
+        );
+
+        let message = run(serde_json::json!({"template": template, "strict": false}))
+            .await
+            .unwrap();
+        assert_eq!(message.content, "{{state.missing}} and {{state.topic}}");
+    }
+
+    #[tokio::test]
+    async fn objects_need_the_json_formatter() {
+        let err = run(serde_json::json!({"template": "{{state.filters}}", "strict": false}))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("{{state.filters | json}}"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn malformed_templates_fail_at_construction() {
+        for template in ["{{state.topic", "{{state.topic | upper}}"] {
+            let err = NodeTestHarness::<MessagesState>::new(
+                template_node_plugin(),
+                serde_json::json!({ "template": template }),
+            )
+            .err()
+            .unwrap();
+            assert!(matches!(err, GraphError::CompilationError(_)), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Rule router: routes conditional edges by matching regexes or paths against the state.
+
+use std::sync::Arc;
+
+use oris_runtime::graph::{GraphError, PluginRouter, RouterPlugin, State};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::path::{lookup, parse_path, resolve_field, PathSegment};
+
+/// Router type string for the rule router. Use this as the `router` of a conditional
+/// edge, in a `GraphSpec` or with `StateGraph::add_plugin_conditional_edges`.
+pub const ROUTER_PLUGIN_TYPE: &str = "plugin_reference/rules";
+
+/// Config for the rule router plugin.
+///
+/// Rules are tried in order and the first match routes to its `target`; when none
+/// matches the router returns `default`. Targets are keys of the edge's mapping.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RouterConfig {
+    pub rules: Vec<RouteRule>,
+    /// Target when no rule matches.
+    pub default: String,
+}
+
+/// One rule of the rule router: `regex` or `path` (optionally with `equals`) checked
+/// against `field`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RouteRule {
+    /// Field the rule checks: `messages.last`, `messages.last_human` or `state.PATH`.
+    #[serde(default = "default_field")]
+    pub field: String,
+    /// Regex searched for in the field; non-string values are matched as compact JSON.
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// JSONPath-style path into the field, e.g. `$.verdict`; a string field is parsed as
+    /// JSON first. Matches when the path leads to a value other than `null` or `false`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Value `path` must lead to instead.
+    #[serde(default)]
+    pub equals: Option<Value>,
+    /// Mapping key to route to.
+    pub target: String,
+}
+
+fn default_field() -> String {
+    "messages.last".to_string()
+}
+
+/// Builds the rule router [RouterPlugin], for any state. Register with:
+/// `registry.register_router(plugin_reference::router_node_plugin())?`
+///
+/// Rules are checked when the router is built: regexes must compile, paths parse and
+/// targets be non-empty. The router publishes its targets as
+/// [route keys](RouterPlugin::route_keys), so a conditional edge whose mapping misses
+/// one of them is rejected when the graph is built.
+pub fn router_node_plugin<S: State>() -> impl RouterPlugin<S> + 'static {
+    RuleRouterPlugin
+}
+
+struct RuleRouterPlugin;
+
+impl<S: State> RouterPlugin<S> for RuleRouterPlugin {
+    fn plugin_type(&self) -> &str {
+        ROUTER_PLUGIN_TYPE
+    }
+
+    fn create_router(&self, config: &Value) -> Result<PluginRouter<S>, GraphError> {
+        let invalid =
+            |message: String| GraphError::CompilationError(format!("Rule router: {}", message));
+        let config: RouterConfig =
+            serde_json::from_value(config.clone()).map_err(|e| invalid(e.to_string()))?;
+        let rules = Arc::new(RouteRules::compile(config).map_err(invalid)?);
+        Ok(Arc::new(move |state: &S| {
+            let state = serde_json::to_value(state).unwrap_or_default();
+            rules.route(&state).to_string()
+        }))
+    }
+
+    fn route_keys(&self, config: &Value) -> Option<Vec<String>> {
+        let config: RouterConfig = serde_json::from_value(config.clone()).ok()?;
+        let mut keys: Vec<String> = Vec::new();
+        for target in config.rules.into_iter().map(|rule| rule.target) {
+            if !keys.contains(&target) {
+                keys.push(target);
+            }
+        }
+        if !keys.contains(&config.default) {
+            keys.push(config.default);
+        }
+        Some(keys)
+    }
+}
+
+struct RouteRules {
+    rules: Vec<CompiledRule>,
+    default: String,
+}
+
+struct CompiledRule {
+    field: String,
+    matcher: Matcher,
+    target: String,
+}
+
+enum Matcher {
+    Regex(Regex),
+    Path {
+        path: Vec<PathSegment>,
+        equals: Option<Value>,
+    },
+}
+
+impl RouteRules {
+    fn compile(config: RouterConfig) -> Result<Self, String> {
+        if config.default.is_empty() {
+            return Err("default must not be empty".to_string());
+        }
+        let rules = config
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let at = |message: String| format!("rules[{}]: {}", i, message);
+                if rule.target.is_empty() {
+                    return Err(at("target must not be empty".to_string()));
+                }
+                let known_field =
+                    matches!(rule.field.as_str(), "messages.last" | "messages.last_human")
+                        || rule
+                            .field
+                            .strip_prefix("state.")
+                            .is_some_and(|key| !key.is_empty());
+                if !known_field {
+                    return Err(at(format!(
+                        "unknown field '{}'; expected messages.last, messages.last_human or state.PATH",
+                        rule.field
+                    )));
+                }
+                let matcher = match (rule.regex, rule.path, rule.equals) {
+                    (Some(regex), None, None) => Matcher::Regex(
+                        Regex::new(&regex).map_err(|e| at(format!("invalid regex: {}", e)))?,
+                    ),
+                    (None, Some(path), equals) => Matcher::Path {
+                        path: parse_path(&path)
+                            .ok_or_else(|| at(format!("invalid path '{}'", path)))?,
+                        equals,
+                    },
+                    (Some(_), _, _) => {
+                        return Err(at("set either regex or path, not both".to_string()))
+                    }
+                    (None, None, _) => return Err(at("set regex or path".to_string())),
+                };
+                Ok(CompiledRule {
+                    field: rule.field,
+                    matcher,
+                    target: rule.target,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rules,
+            default: config.default,
+        })
+    }
+
+    /// Target of the first rule matching `state`, or the default
+    fn route(&self, state: &Value) -> &str {
+        self.rules
+            .iter()
+            .find(|rule| {
+                resolve_field(state, &rule.field).is_some_and(|value| rule.matcher.matches(value))
+            })
+            .map_or(&self.default, |rule| &rule.target)
+    }
+}
+
+impl Matcher {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Matcher::Regex(regex) => match value {
+                Value::String(text) => regex.is_match(text),
+                value => regex.is_match(&value.to_string()),
+            },
+            Matcher::Path { path, equals } => {
+                let parsed;
+                let value = match value {
+                    Value::String(text) => match serde_json::from_str(text) {
+                        Ok(json) => {
+                            parsed = json;
+                            &parsed
+                        }
+                        Err(_) => return false,
+                    },
+                    value => value,
+                };
+                match (lookup(value, path), equals) {
+                    (Some(found), Some(equals)) => found == equals,
+                    (Some(found), None) => !matches!(found, Value::Null | Value::Bool(false)),
+                    (None, _) => false,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use oris_runtime::graph::{GraphSpec, MessagesState, NodePluginRegistry};
+    use oris_runtime::schemas::messages::Message;
+    use serde_json::json;
+
+    use super::*;
+    use crate::register_all;
+
+    fn router(config: Value) -> Result<PluginRouter<MessagesState>, GraphError> {
+        router_node_plugin().create_router(&config)
+    }
+
+    fn state(last: &str) -> MessagesState {
+        MessagesState::with_messages(vec![
+            Message::new_human_message("question"),
+            Message::new_ai_message(last),
+        ])
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_default_catches_the_rest() {
+        let route = router(json!({
+            "rules": [
+                { "regex": "(?i)weather", "target": "weather" },
+                { "regex": "(?i)weather|news", "target": "news" },
+                { "field": "messages.last_human", "regex": "^help$", "target": "help" }
+            ],
+            "default": "chat"
+        }))
+        .unwrap();
+
+        assert_eq!(route(&state("Weather and news")), "weather");
+        assert_eq!(route(&state("news only")), "news");
+        assert_eq!(route(&state("hello")), "chat");
+        assert_eq!(route(&MessagesState::new()), "chat");
+    }
+
+    #[test]
+    fn path_rules_match_json_in_messages() {
+        let route = router(json!({
+            "rules": [
+                { "path": "$.verdict", "equals": "approve", "target": "ship" },
+                { "path": "$.tool_calls[0]", "target": "tools" }
+            ],
+            "default": "review"
+        }))
+        .unwrap();
+
+        assert_eq!(route(&state(r#"{"verdict":"approve"}"#)), "ship");
+        assert_eq!(route(&state(r#"{"verdict":"reject"}"#)), "review");
+        assert_eq!(
+            route(&state(r#"{"tool_calls":[{"name":"search"}]}"#)),
+            "tools"
+        );
+        assert_eq!(route(&state("not json")), "review");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected_when_the_router_is_built() {
+        for rules in [
+            json!([{ "regex": "(", "target": "a" }]),
+            json!([{ "path": "verdict", "target": "a" }]),
+            json!([{ "regex": "a", "path": "$.a", "target": "a" }]),
+            json!([{ "target": "a" }]),
+            json!([{ "regex": "a", "target": "" }]),
+            json!([{ "field": "state.", "regex": "a", "target": "a" }]),
+        ] {
+            let err = router(json!({ "rules": rules, "default": "b" }))
+                .err()
+                .unwrap();
+            assert!(matches!(err, GraphError::CompilationError(_)), "{}", err);
+        }
+        assert!(router(json!({ "rules": [] })).is_err());
+    }
+
+    fn spec(mapping: Value) -> GraphSpec {
+        serde_json::from_value(json!({
+            "nodes": [
+                { "name": "forecast", "plugin_type": "plugin_reference/delay",
+                  "config": { "message": "sunny", "delay_ms": 0 } },
+                { "name": "chat", "plugin_type": "plugin_reference/delay",
+                  "config": { "message": "hi", "delay_ms": 0 } }
+            ],
+            "edges": [
+                { "from": "forecast", "to": "__end__" },
+                { "from": "chat", "to": "__end__" }
+            ],
+            "conditional_edges": [{
+                "from": "__start__",
+                "router": ROUTER_PLUGIN_TYPE,
+                "config": {
+                    "rules": [{ "regex": "(?i)weather", "target": "weather" }],
+                    "default": "chat"
+                },
+                "mapping": mapping
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn spec_edges_route_by_label() {
+        let mut registry = NodePluginRegistry::<MessagesState>::new();
+        register_all(&mut registry).unwrap();
+
+        let graph = registry
+            .build_graph(&spec(json!({ "weather": "forecast", "chat": "chat" })))
+            .unwrap()
+            .compile()
+            .unwrap();
+        let state = graph
+            .invoke(MessagesState::with_messages(vec![
+                Message::new_human_message("What's the weather in Oslo?"),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(state.messages.last().unwrap().content, "sunny");
+
+        let err = registry
+            .build_graph(&spec(json!({ "chat": "chat" })))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("'weather'"), "{}", err);
+    }
+}
@@ -0,0 +1,86 @@
+//! `{input}` and `{state.KEY}` templates in node configs.
+
+use oris_runtime::graph::GraphError;
+use serde_json::Value;
+
+/// Expand `{input}` and `{state.KEY}` in `template`, percent-encoding the values if
+/// `encode` is set; other braces are kept as they are
+pub(crate) fn render(template: &str, state: &Value, encode: bool) -> Result<String, GraphError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let token = tail.find('}').map(|end| &tail[1..end]);
+        let value = match token {
+            Some("input") => Some(last_human_message(state)?),
+            Some(token) => match token.strip_prefix("state.") {
+                Some(key) => Some(state_field(state, key)?),
+                None => None,
+            },
+            None => None,
+        };
+        match (token, value) {
+            (Some(token), Some(value)) => {
+                if encode {
+                    out.push_str(&urlencoding::encode(&value));
+                } else {
+                    out.push_str(&value);
+                }
+                rest = &tail[token.len() + 2..];
+            }
+            _ => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+pub(crate) fn render_json(template: &Value, state: &Value) -> Result<Value, GraphError> {
+    Ok(match template {
+        Value::String(text) => Value::String(render(text, state, false)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_json(item, state))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), render_json(value, state)?)))
+                .collect::<Result<_, GraphError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn last_human_message(state: &Value) -> Result<String, GraphError> {
+    state["messages"]
+        .as_array()
+        .and_then(|messages| {
+            messages
+                .iter()
+                .rev()
+                .find(|message| message["message_type"] == "human")
+        })
+        .and_then(|message| message["content"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            GraphError::ExecutionError("no human message to expand {input} from".to_string())
+        })
+}
+
+fn state_field(state: &Value, key: &str) -> Result<String, GraphError> {
+    match state.get(key) {
+        Some(Value::String(text)) => Ok(text.clone()),
+        Some(value) => Ok(value.to_string()),
+        None => Err(GraphError::ExecutionError(format!(
+            "state has no field '{}' to expand {{state.{}}} from",
+            key, key
+        ))),
+    }
+}
@@ -0,0 +1,23 @@
+//! [plugin_reference] packaged as a dynamic library.
+//!
+//! The crate only exports `plugin_reference::register_plan_plugins` as the library's
+//! `oris_plugin_entry_v1` entry point. It exports the [PlanState] plugins rather than
+//! the delay node because the library links its own `tokio`, whose timers only work
+//! under the host's runtime. Build it with
+//! `cargo build -p plugin_reference_dylib`, then load the resulting
+//! `libplugin_reference_dylib.so` (`.dylib` on macOS, `plugin_reference_dylib.dll` on
+//! Windows) from a host built with the same toolchain and `oris-runtime`:
+//!
+//! ```rust,ignore
+//! let mut registry = NodePluginRegistry::<PlanState>::new();
+//! for report in registry.load_dylib_dir("plugins")? {
+//!     match report.result {
+//!         Ok(plugin_types) => println!("{}: {:?}", report.path.display(), plugin_types),
+//!         Err(e) => eprintln!("{}", e),
+//!     }
+//! }
+//! ```
+
+use plugin_reference::PlanState;
+
+oris_runtime::export_node_plugins!(PlanState, plugin_reference::register_plan_plugins);
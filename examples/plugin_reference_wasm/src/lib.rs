@@ -0,0 +1,60 @@
+//! Minimal module for `WasmNodePlugin` (the `oris/wasm` plugin type of `oris-runtime`).
+//!
+//! The node reads a `MessagesState` and appends an AI message counting its messages.
+//! Build it with
+//! `cargo build --release --target wasm32-wasip1 --manifest-path examples/plugin_reference_wasm/Cargo.toml`
+//! and point a node at the module from a host built with the `wasm-plugins` feature:
+//!
+//! ```rust,ignore
+//! registry.register_plugin(WasmNodePlugin)?;
+//! graph.add_plugin_node(
+//!     "count",
+//!     WASM_NODE_PLUGIN_TYPE,
+//!     json!({
+//!         "module": "examples/plugin_reference_wasm/target/wasm32-wasip1/release/plugin_reference_wasm.wasm",
+//!         "fuel": 50_000_000,
+//!         "timeout_ms": 500
+//!     }),
+//!     &registry,
+//! )?;
+//! ```
+
+use serde_json::{json, Value};
+
+/// Returns a buffer of `len` bytes for the host to write the input into
+#[no_mangle]
+pub extern "C" fn alloc(len: u32) -> u32 {
+    let mut buffer = Vec::<u8>::with_capacity(len as usize);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr as u32
+}
+
+/// Reads the state JSON from the buffer returned by [alloc] and returns the output's
+/// pointer and length packed into the high and low 32 bits
+#[no_mangle]
+pub extern "C" fn run(ptr: u32, len: u32) -> u64 {
+    // SAFETY: the host wrote `len` bytes into the buffer `alloc(len)` returned
+    let input = unsafe { Vec::from_raw_parts(ptr as *mut u8, len as usize, len as usize) };
+    let output = match serde_json::from_slice::<Value>(&input) {
+        Ok(state) => json!({ "update": count_messages(&state) }),
+        Err(e) => json!({ "error": format!("invalid state: {}", e) }),
+    };
+    let output = serde_json::to_vec(&output)
+        .expect("JSON values serialize")
+        .into_boxed_slice();
+    let (out_ptr, out_len) = (output.as_ptr() as u64, output.len() as u64);
+    // The instance is dropped after the call, so the output is never freed
+    std::mem::forget(output);
+    (out_ptr << 32) | out_len
+}
+
+fn count_messages(state: &Value) -> Value {
+    let count = state["messages"].as_array().map_or(0, Vec::len);
+    json!({
+        "messages": [{
+            "content": format!("The conversation has {} messages.", count),
+            "message_type": "ai"
+        }]
+    })
+}